@@ -0,0 +1,156 @@
+// Speed and heap allocations of the greeting helpers in src/lib.rs
+//
+//   cargo bench                  # allocation table, then every group
+//   cargo bench -- greeting      # allocation table, then one group
+//
+// A counting global allocator wraps the system one. Before criterion runs,
+// each helper is called once under `count` and the allocations, reallocations
+// and bytes it asked for are printed. Time and allocations usually move
+// together, and the table explains the timings.
+
+use criterion::{criterion_group, BenchmarkId, Criterion};
+use function::{
+    create_greeting, greeting_concat, greeting_cow, greeting_push_str, normalize_name,
+    normalize_name_owned, write_greeting,
+};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct Counting;
+
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+static REALLOCS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+
+// SAFETY: every call is forwarded unchanged to the system allocator
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        REALLOCS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(new_size.saturating_sub(layout.size()), Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Allocations {
+    allocs: usize,
+    reallocs: usize,
+    bytes: usize,
+}
+
+// Allocations made while `f` runs, including ones it frees again
+fn count<T>(f: impl FnOnce() -> T) -> Allocations {
+    let before = (
+        ALLOCS.load(Ordering::Relaxed),
+        REALLOCS.load(Ordering::Relaxed),
+        BYTES.load(Ordering::Relaxed),
+    );
+    black_box(f());
+    Allocations {
+        allocs: ALLOCS.load(Ordering::Relaxed) - before.0,
+        reallocs: REALLOCS.load(Ordering::Relaxed) - before.1,
+        bytes: BYTES.load(Ordering::Relaxed) - before.2,
+    }
+}
+
+const NAMES: [&str; 2] = ["Bob", "Maximiliane Wolkenstein-Rodenegg"];
+
+fn allocation_table() {
+    let mut buffer = String::new();
+    write_greeting(&mut buffer, NAMES[1]);
+
+    println!("Allocations per call (allocs / reallocs / bytes requested):");
+    for name in NAMES {
+        println!("  name {:?}", name);
+        let rows: [(&str, Allocations); 5] = [
+            ("format!", count(|| create_greeting(name))),
+            ("String + &str", count(|| greeting_concat(name))),
+            (
+                "with_capacity + push_str",
+                count(|| greeting_push_str(name)),
+            ),
+            (
+                "write! into reused buffer",
+                count(|| write_greeting(&mut buffer, name)),
+            ),
+            ("Cow normalise + push_str", count(|| greeting_cow(name))),
+        ];
+        for (label, a) in rows {
+            println!(
+                "    {:<28} {:>2} / {:>2} / {:>4}",
+                label, a.allocs, a.reallocs, a.bytes
+            );
+        }
+    }
+    for name in ["Bob", "  bob "] {
+        let owned = count(|| normalize_name_owned(name));
+        let cow = count(|| normalize_name(name));
+        println!(
+            "  normalise {:<10} owned {} alloc(s), Cow {} alloc(s)",
+            format!("{:?}", name),
+            owned.allocs,
+            cow.allocs
+        );
+    }
+    println!();
+}
+
+fn greeting(c: &mut Criterion) {
+    let mut group = c.benchmark_group("greeting");
+    for name in NAMES {
+        let id = name.len();
+        group.bench_with_input(BenchmarkId::new("format", id), name, |b, n| {
+            b.iter(|| create_greeting(black_box(n)))
+        });
+        group.bench_with_input(BenchmarkId::new("concat", id), name, |b, n| {
+            b.iter(|| greeting_concat(black_box(n)))
+        });
+        group.bench_with_input(BenchmarkId::new("push_str", id), name, |b, n| {
+            b.iter(|| greeting_push_str(black_box(n)))
+        });
+        group.bench_with_input(BenchmarkId::new("reused_buffer", id), name, |b, n| {
+            let mut buffer = String::new();
+            b.iter(|| {
+                write_greeting(&mut buffer, black_box(n));
+                buffer.len()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn normalize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("normalize_name");
+    for (label, name) in [("tidy", "Bob"), ("untidy", "  bob ")] {
+        group.bench_with_input(BenchmarkId::new("owned", label), name, |b, n| {
+            b.iter(|| normalize_name_owned(black_box(n)))
+        });
+        group.bench_with_input(BenchmarkId::new("cow", label), name, |b, n| {
+            b.iter(|| normalize_name(black_box(n)).len())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, greeting, normalize);
+
+// criterion_main! plus the allocation table first
+fn main() {
+    allocation_table();
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+}
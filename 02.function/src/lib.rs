@@ -0,0 +1,64 @@
+// Greeting and formatting helpers
+//
+// Every function here builds the same kind of text in a different way, so
+// `benches/strings.rs` can compare their speed and how many heap
+// allocations each one makes.
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+const PREFIX: &str = "   Greeting: Hello, ";
+const SUFFIX: &str = "! Welcome to Rust!";
+
+// The version used by main.rs: format! builds a new String
+pub fn create_greeting(name: &str) -> String {
+    format!("   Greeting: Hello, {}! Welcome to Rust!", name)
+}
+
+// `+` appends to the left-hand String, growing it as needed
+pub fn greeting_concat(name: &str) -> String {
+    String::from(PREFIX) + name + SUFFIX
+}
+
+// Reserve the exact length first, then append: one allocation
+pub fn greeting_push_str(name: &str) -> String {
+    let mut greeting = String::with_capacity(PREFIX.len() + name.len() + SUFFIX.len());
+    greeting.push_str(PREFIX);
+    greeting.push_str(name);
+    greeting.push_str(SUFFIX);
+    greeting
+}
+
+// Write into a buffer the caller keeps; no allocation once it is big enough
+pub fn write_greeting(out: &mut String, name: &str) {
+    out.clear();
+    // Writing to a String cannot fail
+    let _ = write!(out, "{}{}{}", PREFIX, name, SUFFIX);
+}
+
+// Tidy a user-supplied name: trim it and capitalise the first letter.
+// Always returns a new String, even when nothing changes
+pub fn normalize_name_owned(name: &str) -> String {
+    let trimmed = name.trim();
+    let mut chars = trimmed.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::from("stranger"),
+    }
+}
+
+// The same result, but borrowed when the name is already tidy, so the
+// common case allocates nothing
+pub fn normalize_name(name: &str) -> Cow<'_, str> {
+    let trimmed = name.trim();
+    match trimmed.chars().next() {
+        None => Cow::Borrowed("stranger"),
+        Some(first) if first.is_uppercase() || !first.is_alphabetic() => Cow::Borrowed(trimmed),
+        Some(_) => Cow::Owned(normalize_name_owned(trimmed)),
+    }
+}
+
+// Normalise, then greet: allocates once for a tidy name, twice otherwise
+pub fn greeting_cow(name: &str) -> String {
+    greeting_push_str(&normalize_name(name))
+}
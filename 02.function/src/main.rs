@@ -1,3 +1,7 @@
+// 11. create_greeting lives in src/lib.rs, next to the variants the
+// benchmarks compare it with
+use function::create_greeting;
+
 fn main() {
     println!("=== Rust Functions Learning ===\n");
 
@@ -141,11 +145,6 @@ fn increment(x: &mut i32) {
     *x += 1;  // Dereference and modify
 }
 
-// 11. Function returning String
-fn create_greeting(name: &str) -> String {
-    format!("   Greeting: Hello, {}! Welcome to Rust!", name)
-}
-
 // 13. Function with conditional logic
 fn max(a: i32, b: i32) -> i32 {
     if a > b {
@@ -1,4 +1,8 @@
+use enum_derive::{EnumDisplay, EnumFromStr, EnumIter, StateMachine};
+
 // Basic enum (no data)
+// The derives write the Display and FromStr match arms and the variant list
+#[derive(Debug, EnumIter, EnumDisplay, EnumFromStr)]
 enum Direction {
     North,
     South,
@@ -27,9 +31,15 @@ enum IpAddrDetailed {
 }
 
 // Enum with methods
+// Display prints each variant's `name`, replacing a hand-written
+// `description()` match
+#[derive(EnumIter, EnumDisplay)]
 enum Status {
+    #[variant(name = "User is active")]
     Active,
+    #[variant(name = "User is inactive")]
     Inactive,
+    #[variant(name = "User status is pending")]
     Pending,
 }
 
@@ -37,14 +47,6 @@ impl Status {
     fn is_active(&self) -> bool {
         matches!(self, Status::Active)
     }
-
-    fn description(&self) -> &str {
-        match self {
-            Status::Active => "User is active",
-            Status::Inactive => "User is inactive",
-            Status::Pending => "User status is pending",
-        }
-    }
 }
 
 // Enum with associated function
@@ -95,21 +97,23 @@ enum MaybeValue<T> {
 }
 
 // Enum for state machine
-#[derive(Debug)]
+// Each rule is one arm of the generated `next(event)` match
+#[derive(Debug, Clone, Copy, PartialEq, StateMachine)]
+#[state_machine(event = LightEvent)]
+#[transitions(
+    (Red, TimerExpired) => Green,
+    (Yellow, TimerExpired) => Red,
+    (Green, TimerExpired) => Yellow,
+)]
 enum TrafficLight {
     Red,
     Yellow,
     Green,
 }
 
-impl TrafficLight {
-    fn next(&self) -> TrafficLight {
-        match self {
-            TrafficLight::Red => TrafficLight::Green,
-            TrafficLight::Yellow => TrafficLight::Red,
-            TrafficLight::Green => TrafficLight::Yellow,
-        }
-    }
+#[derive(Debug, Clone)]
+enum LightEvent {
+    TimerExpired,
 }
 
 fn main() {
@@ -118,12 +122,7 @@ fn main() {
     // 1. Basic enum usage
     println!("1. Basic enum:");
     let direction = Direction::North;
-    match direction {
-        Direction::North => println!("   Heading North"),
-        Direction::South => println!("   Heading South"),
-        Direction::East => println!("   Heading East"),
-        Direction::West => println!("   Heading West"),
-    }
+    println!("   Heading {}", direction);
 
     // 2. Enum with data - struct-like variant
     println!("\n2. Enum with struct-like variant:");
@@ -183,11 +182,11 @@ fn main() {
     println!("\n7. Enum methods:");
     let status = Status::Active;
     println!("   Is active? {}", status.is_active());
-    println!("   Description: {}", status.description());
+    println!("   Description: {}", status);
 
     let status2 = Status::Pending;
     println!("   Is active? {}", status2.is_active());
-    println!("   Description: {}", status2.description());
+    println!("   Description: {}", status2);
 
     // 8. Enum with associated function
     println!("\n8. Enum with associated function:");
@@ -290,10 +289,10 @@ fn main() {
     let mut light = TrafficLight::Red;
     println!("   Current: {:?}", light);
     
-    light = light.next();
+    light = light.next(&LightEvent::TimerExpired).unwrap();
     println!("   After next: {:?}", light);
     
-    light = light.next();
+    light = light.next(&LightEvent::TimerExpired).unwrap();
     println!("   After next: {:?}", light);
 
     // 16. Nested pattern matching
@@ -338,6 +337,24 @@ fn main() {
     let default = none_value.unwrap_or(0);
     println!("   Default value: {}", default);
 
+    // 19. Derived variant lists and parsing
+    println!("\n19. Derived iteration and parsing:");
+    for direction in Direction::iter() {
+        print!("   {}", direction);
+    }
+    println!();
+    for status in Status::iter() {
+        println!("   {} (active: {})", status, status.is_active());
+    }
+    match "East".parse::<Direction>() {
+        Ok(direction) => println!("   parsed \"East\" as {:?}", direction),
+        Err(e) => println!("   {}", e),
+    }
+    match "Up".parse::<Direction>() {
+        Ok(direction) => println!("   parsed \"Up\" as {:?}", direction),
+        Err(e) => println!("   {}", e),
+    }
+
     println!("\n=== End of Enums Examples ===");
 }
 
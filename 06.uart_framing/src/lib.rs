@@ -0,0 +1,152 @@
+// UART byte-stream framing
+//
+// Wire format of one frame:
+//
+//   +-------+--------+-----------------+----------+
+//   | START | LENGTH | PAYLOAD (LENGTH)| CHECKSUM |
+//   +-------+--------+-----------------+----------+
+//     0x7E    1..=64    raw bytes        XOR of LENGTH and PAYLOAD
+
+pub const START_BYTE: u8 = 0x7E;
+pub const MAX_PAYLOAD: usize = 64;
+
+// A complete, checksum-verified frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub payload: Vec<u8>,
+}
+
+// Where the parser is inside the current frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    WaitStart,
+    Length,
+    Payload { expected: usize },
+    Checksum,
+}
+
+// Counters that help diagnose a noisy line
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ParserStats {
+    pub frames: u32,
+    pub noise_bytes: u32,
+    pub bad_length: u32,
+    pub bad_checksum: u32,
+}
+
+#[derive(Debug)]
+pub struct FrameParser {
+    state: State,
+    buffer: Vec<u8>,
+    checksum: u8,
+    stats: ParserStats,
+}
+
+impl Default for FrameParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameParser {
+    pub fn new() -> FrameParser {
+        FrameParser {
+            state: State::WaitStart,
+            buffer: Vec::with_capacity(MAX_PAYLOAD),
+            checksum: 0,
+            stats: ParserStats::default(),
+        }
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn stats(&self) -> ParserStats {
+        self.stats
+    }
+
+    // Feed a single byte; returns a frame when this byte completes one
+    pub fn push(&mut self, byte: u8) -> Option<Frame> {
+        match self.state {
+            State::WaitStart => {
+                if byte == START_BYTE {
+                    self.begin_frame();
+                } else {
+                    self.stats.noise_bytes += 1;
+                }
+                None
+            }
+            State::Length => {
+                let len = byte as usize;
+                if len == 0 || len > MAX_PAYLOAD {
+                    self.stats.bad_length += 1;
+                    self.resync(byte);
+                } else {
+                    self.checksum ^= byte;
+                    self.state = State::Payload { expected: len };
+                }
+                None
+            }
+            State::Payload { expected } => {
+                self.buffer.push(byte);
+                self.checksum ^= byte;
+                if self.buffer.len() == expected {
+                    self.state = State::Checksum;
+                }
+                None
+            }
+            State::Checksum => {
+                if byte == self.checksum {
+                    self.stats.frames += 1;
+                    self.state = State::WaitStart;
+                    Some(Frame {
+                        payload: std::mem::take(&mut self.buffer),
+                    })
+                } else {
+                    self.stats.bad_checksum += 1;
+                    self.resync(byte);
+                    None
+                }
+            }
+        }
+    }
+
+    // Feed a whole chunk (e.g. one read() from the port) and collect frames
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<Frame> {
+        bytes.iter().filter_map(|&b| self.push(b)).collect()
+    }
+
+    fn begin_frame(&mut self) {
+        self.buffer.clear();
+        self.checksum = 0;
+        self.state = State::Length;
+    }
+
+    // Drop the partial frame; the offending byte may itself be a new start
+    fn resync(&mut self, byte: u8) {
+        if byte == START_BYTE {
+            self.begin_frame();
+        } else {
+            self.buffer.clear();
+            self.state = State::WaitStart;
+        }
+    }
+}
+
+// Build the on-the-wire bytes for a payload
+pub fn encode(payload: &[u8]) -> Option<Vec<u8>> {
+    if payload.is_empty() || payload.len() > MAX_PAYLOAD {
+        return None;
+    }
+
+    let len = payload.len() as u8;
+    let checksum = payload.iter().fold(len, |acc, b| acc ^ b);
+
+    let mut out = Vec::with_capacity(payload.len() + 3);
+    out.push(START_BYTE);
+    out.push(len);
+    out.extend_from_slice(payload);
+    out.push(checksum);
+    Some(out)
+}
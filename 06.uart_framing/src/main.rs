@@ -0,0 +1,77 @@
+use uart_framing::{encode, FrameParser, START_BYTE};
+
+fn main() {
+    println!("=== UART Frame Parsing ===\n");
+
+    // 1. Encoding a frame
+    println!("1. Encoding a frame:");
+    let bytes = encode(b"T=21.5").unwrap();
+    println!("   Payload \"T=21.5\" -> {:02X?}", bytes);
+
+    // 2. Parsing byte by byte and watching the state
+    println!("\n2. Parsing one byte at a time:");
+    let mut parser = FrameParser::new();
+    for &b in &bytes {
+        let before = parser.state();
+        let frame = parser.push(b);
+        println!("   {:02X}  {:?} -> {:?}", b, before, parser.state());
+        if let Some(frame) = frame {
+            println!("   Frame: {:?}", String::from_utf8_lossy(&frame.payload));
+        }
+    }
+
+    // 3. Noise before and between frames
+    println!("\n3. Noise before and between frames:");
+    let mut stream = vec![0x00, 0xFF, 0x13];
+    stream.extend(encode(b"A").unwrap());
+    stream.extend([0x55, 0x55]);
+    stream.extend(encode(b"BC").unwrap());
+    let mut parser = FrameParser::new();
+    for frame in parser.feed(&stream) {
+        println!("   Frame: {:?}", String::from_utf8_lossy(&frame.payload));
+    }
+    println!("   Stats: {:?}", parser.stats());
+
+    // 4. A corrupted frame is dropped, the next one still arrives
+    println!("\n4. Corrupted checksum:");
+    let mut broken = encode(b"LOST").unwrap();
+    let last = broken.len() - 1;
+    broken[last] ^= 0x01;
+    let mut stream = broken;
+    stream.extend(encode(b"OK").unwrap());
+    let mut parser = FrameParser::new();
+    for frame in parser.feed(&stream) {
+        println!("   Frame: {:?}", String::from_utf8_lossy(&frame.payload));
+    }
+    println!("   Stats: {:?}", parser.stats());
+
+    // 5. A bogus length byte triggers a resync
+    println!("\n5. Invalid length:");
+    let mut stream = vec![START_BYTE, 0xC8];
+    stream.extend(encode(b"after").unwrap());
+    let mut parser = FrameParser::new();
+    for frame in parser.feed(&stream) {
+        println!("   Frame: {:?}", String::from_utf8_lossy(&frame.payload));
+    }
+    println!("   Stats: {:?}", parser.stats());
+
+    // 6. A frame split across several reads
+    println!("\n6. Frame split across reads:");
+    let bytes = encode(b"chunked").unwrap();
+    let mut parser = FrameParser::new();
+    for chunk in bytes.chunks(3) {
+        let frames = parser.feed(chunk);
+        println!(
+            "   read {:02X?} -> {} frame(s), state {:?}",
+            chunk,
+            frames.len(),
+            parser.state()
+        );
+    }
+
+    // 7. Oversized payloads cannot be encoded
+    println!("\n7. Oversized payload:");
+    println!("   encode(&[0; 100]) = {:?}", encode(&[0; 100]));
+
+    println!("\n=== End of UART Framing Examples ===");
+}
@@ -0,0 +1,122 @@
+use uart_framing::{encode, Frame, FrameParser, ParserStats, State, MAX_PAYLOAD, START_BYTE};
+
+fn frame(payload: &[u8]) -> Frame {
+    Frame {
+        payload: payload.to_vec(),
+    }
+}
+
+#[test]
+fn encode_lays_out_start_length_payload_checksum() {
+    assert_eq!(
+        encode(b"T=21.5").unwrap(),
+        [
+            0x7E,
+            0x06,
+            b'T',
+            b'=',
+            b'2',
+            b'1',
+            b'.',
+            b'5',
+            0x06 ^ 0x54 ^ 0x3D ^ 0x32 ^ 0x31 ^ 0x2E ^ 0x35
+        ]
+    );
+    assert_eq!(
+        encode(&[START_BYTE]).unwrap(),
+        [START_BYTE, 0x01, START_BYTE, 0x7F]
+    );
+    assert_eq!(encode(&[]), None);
+    assert_eq!(encode(&[0; MAX_PAYLOAD + 1]), None);
+    assert_eq!(encode(&[0; MAX_PAYLOAD]).unwrap().len(), MAX_PAYLOAD + 3);
+}
+
+#[test]
+fn states_step_through_one_frame() {
+    let mut parser = FrameParser::new();
+    let bytes = encode(b"AB").unwrap();
+    let mut states = Vec::new();
+    for &b in &bytes[..bytes.len() - 1] {
+        assert_eq!(parser.push(b), None);
+        states.push(parser.state());
+    }
+    assert_eq!(
+        states,
+        [
+            State::Length,
+            State::Payload { expected: 2 },
+            State::Payload { expected: 2 },
+            State::Checksum
+        ]
+    );
+    assert_eq!(parser.push(bytes[bytes.len() - 1]), Some(frame(b"AB")));
+    assert_eq!(parser.state(), State::WaitStart);
+}
+
+#[test]
+fn every_payload_length_round_trips() {
+    let mut parser = FrameParser::new();
+    for len in 1..=MAX_PAYLOAD {
+        // Include the start byte inside the payload: it is only special
+        // while waiting for a frame
+        let payload: Vec<u8> = (0..len)
+            .map(|i| (i as u8).wrapping_mul(37) ^ 0x7E)
+            .collect();
+        assert_eq!(parser.feed(&encode(&payload).unwrap()), [frame(&payload)]);
+    }
+    assert_eq!(
+        parser.stats(),
+        ParserStats {
+            frames: MAX_PAYLOAD as u32,
+            ..ParserStats::default()
+        }
+    );
+}
+
+#[test]
+fn frames_split_at_every_chunk_size() {
+    let mut stream = Vec::new();
+    let payloads: [&[u8]; 3] = [b"first", b"\x7E\x7E", b"third frame"];
+    for payload in payloads {
+        stream.extend(encode(payload).unwrap());
+    }
+    for size in 1..=stream.len() {
+        let mut parser = FrameParser::new();
+        let frames: Vec<Frame> = stream.chunks(size).flat_map(|c| parser.feed(c)).collect();
+        assert_eq!(frames, payloads.map(frame), "chunks of {} bytes", size);
+    }
+}
+
+#[test]
+fn a_partial_frame_waits_for_the_rest() {
+    let bytes = encode(b"chunked").unwrap();
+    let mut parser = FrameParser::new();
+    assert!(parser.feed(&bytes[..5]).is_empty());
+    assert_eq!(parser.state(), State::Payload { expected: 7 });
+    assert!(parser.feed(&bytes[5..bytes.len() - 1]).is_empty());
+    assert_eq!(parser.state(), State::Checksum);
+    assert_eq!(parser.feed(&bytes[bytes.len() - 1..]), [frame(b"chunked")]);
+}
+
+#[test]
+fn back_to_back_frames() {
+    let mut stream = Vec::new();
+    for i in 0..100u8 {
+        stream.extend(encode(&[i, i.wrapping_add(1)]).unwrap());
+    }
+    let mut parser = FrameParser::new();
+    let frames = parser.feed(&stream);
+    assert_eq!(frames.len(), 100);
+    assert!(frames
+        .iter()
+        .enumerate()
+        .all(|(i, f)| f.payload == [i as u8, i as u8 + 1]));
+    assert_eq!(parser.stats().frames, 100);
+}
+
+#[test]
+fn default_is_a_fresh_parser() {
+    let parser = FrameParser::default();
+    assert_eq!(parser.state(), State::WaitStart);
+    assert_eq!(parser.stats(), ParserStats::default());
+}
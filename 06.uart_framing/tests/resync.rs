@@ -0,0 +1,176 @@
+// Recovery from a noisy line: garbage, cut-off frames, bad lengths and bad
+// checksums cost the frame they hit and nothing after it.
+
+use uart_framing::{encode, Frame, FrameParser, ParserStats, State, MAX_PAYLOAD, START_BYTE};
+
+struct Noise(u64);
+
+impl Noise {
+    fn next_byte(&mut self) -> u8 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 56) as u8
+    }
+}
+
+fn payloads(frames: &[Frame]) -> Vec<&[u8]> {
+    frames.iter().map(|f| f.payload.as_slice()).collect()
+}
+
+#[test]
+fn noise_before_and_between_frames_is_counted_and_skipped() {
+    let mut stream = vec![0x00, 0xFF, 0x13];
+    stream.extend(encode(b"A").unwrap());
+    stream.extend([0x55, 0x55]);
+    stream.extend(encode(b"BC").unwrap());
+    stream.push(0xAA);
+
+    let mut parser = FrameParser::new();
+    assert_eq!(payloads(&parser.feed(&stream)), [&b"A"[..], b"BC"]);
+    assert_eq!(
+        parser.stats(),
+        ParserStats {
+            frames: 2,
+            noise_bytes: 6,
+            ..ParserStats::default()
+        }
+    );
+    assert_eq!(parser.state(), State::WaitStart);
+}
+
+#[test]
+fn random_noise_without_start_bytes_loses_no_frame() {
+    let mut noise = Noise(0xC0FFEE);
+    let mut stream = Vec::new();
+    let mut sent = Vec::new();
+    let mut noise_bytes = 0;
+    for i in 0..500usize {
+        for _ in 0..noise.next_byte() % 8 {
+            let byte = match noise.next_byte() {
+                START_BYTE => 0x00,
+                b => b,
+            };
+            stream.push(byte);
+            noise_bytes += 1;
+        }
+        let payload: Vec<u8> = (0..1 + i % MAX_PAYLOAD)
+            .map(|_| noise.next_byte())
+            .collect();
+        stream.extend(encode(&payload).unwrap());
+        sent.push(payload);
+    }
+
+    let mut parser = FrameParser::new();
+    let frames = parser.feed(&stream);
+    assert_eq!(
+        frames.iter().map(|f| &f.payload).collect::<Vec<_>>(),
+        sent.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(parser.stats().noise_bytes, noise_bytes);
+}
+
+#[test]
+fn a_bad_checksum_drops_only_that_frame() {
+    let mut broken = encode(b"LOST").unwrap();
+    *broken.last_mut().unwrap() ^= 0x01;
+    let mut stream = encode(b"before").unwrap();
+    stream.extend(broken);
+    stream.extend(encode(b"after").unwrap());
+
+    let mut parser = FrameParser::new();
+    assert_eq!(payloads(&parser.feed(&stream)), [&b"before"[..], b"after"]);
+    assert_eq!(parser.stats().bad_checksum, 1);
+    assert_eq!(parser.stats().frames, 2);
+}
+
+#[test]
+fn a_corrupted_payload_byte_is_caught_by_the_checksum() {
+    let mut broken = encode(b"payload").unwrap();
+    broken[4] ^= 0x20;
+    let mut stream = broken;
+    stream.extend(encode(b"next").unwrap());
+
+    let mut parser = FrameParser::new();
+    assert_eq!(payloads(&parser.feed(&stream)), [b"next"]);
+    assert_eq!(parser.stats().bad_checksum, 1);
+}
+
+#[test]
+fn a_bad_checksum_byte_that_is_a_start_byte_begins_the_next_frame() {
+    // Two frames with the first one's checksum cut off: the start byte of
+    // the second lands in the checksum slot and is taken as a new start
+    let first = encode(b"cut").unwrap();
+    let mut stream = first[..first.len() - 1].to_vec();
+    stream.extend(encode(b"whole").unwrap());
+
+    let mut parser = FrameParser::new();
+    assert_eq!(payloads(&parser.feed(&stream)), [b"whole"]);
+    assert_eq!(parser.stats().bad_checksum, 1);
+}
+
+#[test]
+fn bad_lengths_resync() {
+    let mut stream = vec![START_BYTE, 0x00];
+    stream.extend([START_BYTE, MAX_PAYLOAD as u8 + 1]);
+    stream.extend([START_BYTE, 0xC8]);
+    // A lone start byte: the next frame's start lands in the length slot,
+    // is rejected as a length and taken as a new start
+    stream.push(START_BYTE);
+    stream.extend(encode(b"after").unwrap());
+
+    let mut parser = FrameParser::new();
+    assert_eq!(payloads(&parser.feed(&stream)), [b"after"]);
+    assert_eq!(parser.stats().bad_length, 4);
+    assert_eq!(parser.stats().noise_bytes, 0);
+}
+
+#[test]
+fn a_cut_off_frame_swallows_only_the_bytes_it_still_expects() {
+    // Power glitch mid-frame: "interrupted" declared 11 bytes and sent 4, so
+    // the parser takes the next 7 bytes as payload and the 8th as checksum.
+    // That is all of "one" and the start of "two"; the checksum fails and
+    // every frame after those 8 bytes arrives
+    let cut = &encode(b"interrupted").unwrap()[..6];
+    let mut stream = cut.to_vec();
+    for payload in [&b"one"[..], b"two", b"three", b"four"] {
+        stream.extend(encode(payload).unwrap());
+    }
+
+    let mut parser = FrameParser::new();
+    assert_eq!(payloads(&parser.feed(&stream)), [&b"three"[..], b"four"]);
+    assert_eq!(parser.stats().bad_checksum, 1);
+}
+
+#[test]
+fn idle_zeros_after_any_garbage_return_to_wait_start() {
+    // Whatever state garbage leaves the parser in, MAX_PAYLOAD + 2 zero
+    // bytes (an idle line) finish or reject the frame in progress
+    let mut noise = Noise(42);
+    for round in 0..2000 {
+        let mut parser = FrameParser::new();
+        let garbage: Vec<u8> = (0..1 + round % 100).map(|_| noise.next_byte()).collect();
+        parser.feed(&garbage);
+        parser.feed(&[0; MAX_PAYLOAD + 2]);
+        assert_eq!(parser.state(), State::WaitStart, "after {:02X?}", garbage);
+
+        let frames = parser.feed(&encode(b"ok").unwrap());
+        assert_eq!(payloads(&frames), [b"ok"]);
+    }
+}
+
+#[test]
+fn every_decoded_frame_has_a_valid_checksum() {
+    // On pure noise the parser may find frames by chance, but each one
+    // re-encodes to exactly the bytes it was parsed from
+    let mut noise = Noise(7);
+    let garbage: Vec<u8> = (0..200_000).map(|_| noise.next_byte()).collect();
+    let mut parser = FrameParser::new();
+    for frame in parser.feed(&garbage) {
+        let bytes = encode(&frame.payload).unwrap();
+        assert!(garbage.windows(bytes.len()).any(|w| w == bytes));
+    }
+    let stats = parser.stats();
+    assert!(stats.bad_length + stats.bad_checksum > 0);
+}
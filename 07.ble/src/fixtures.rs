@@ -0,0 +1,63 @@
+// Test vectors modelled on advertisements from common devices.
+// Addresses are anonymised; payload layouts follow the vendors' published formats.
+
+// Apple iBeacon, ADV_NONCONN_IND from a random address
+pub const IBEACON: &[u8] = &[
+    0x42, 0x24, // header (ADV_NONCONN_IND, TxAdd = random), length 36
+    0x11, 0x22, 0x33, 0x44, 0x55, 0xC6, // AdvA
+    0x02, 0x01, 0x06, // flags
+    0x1A, 0xFF, 0x4C, 0x00, 0x02, 0x15, // manufacturer data: Apple, iBeacon prefix
+    0xE2, 0xC5, 0x6D, 0xB5, 0xDF, 0xFB, 0x48, 0xD2, // proximity UUID
+    0xB0, 0x60, 0xD0, 0xF5, 0xA7, 0x10, 0x96, 0xE0,
+    0x00, 0x01, // major
+    0x00, 0x2A, // minor
+    0xC5, // measured power at 1 m (-59 dBm)
+];
+
+// RuuviTag environmental sensor, data format 5 (RAWv2)
+pub const RUUVI_TAG: &[u8] = &[
+    0x40, 0x25, // header (ADV_IND, TxAdd = random), length 37
+    0x9A, 0x3B, 0x6C, 0x2D, 0x1E, 0xF4, // AdvA
+    0x02, 0x01, 0x06, // flags
+    0x1B, 0xFF, 0x99, 0x04, // manufacturer data: Ruuvi Innovations
+    0x05, 0x12, 0xFC, 0x53, 0x94, 0xC3, 0x7C, 0x00, // format 5 payload
+    0x04, 0xFF, 0xFC, 0x04, 0x0C, 0xAC, 0x36, 0x42,
+    0x00, 0xCD, 0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F,
+];
+
+// Heart-rate chest strap, connectable, public address
+pub const HEART_RATE_STRAP: &[u8] = &[
+    0x00, 0x1B, // header (ADV_IND, TxAdd = public), length 27
+    0x01, 0x02, 0x03, 0x0A, 0x0B, 0x0C, // AdvA
+    0x02, 0x01, 0x06, // flags
+    0x03, 0x03, 0x0D, 0x18, // complete 16-bit UUIDs: Heart Rate (0x180D)
+    0x0A, 0x09, b'H', b'R', b'-', b'S', b't', b'r', b'a', b'p', b'1', // complete name
+    0x02, 0x0A, 0x04, // TX power +4 dBm
+];
+
+// Scan response from a Nordic UART Service peripheral
+pub const NUS_SCAN_RESPONSE: &[u8] = &[
+    0x44, 0x21, // header (SCAN_RSP, TxAdd = random), length 33
+    0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xD1, // AdvA
+    0x11, 0x07, // complete 128-bit UUIDs
+    0x9E, 0xCA, 0xDC, 0x24, 0x0E, 0xE5, 0xA9, 0xE0, // 6E400001-B5A3-F393-E0A9-E50E24DCCA9E
+    0x93, 0xF3, 0xA3, 0xB5, 0x01, 0x00, 0x40, 0x6E,
+    0x08, 0x09, b'N', b'U', b'S', b'-', b'D', b'e', b'v', // complete name
+];
+
+// A cheap beacon with a firmware bug: the last AD structure overruns the PDU
+pub const TRUNCATED_MANUFACTURER: &[u8] = &[
+    0x40, 0x15, // header (ADV_IND, TxAdd = random), length 21
+    0x10, 0x20, 0x30, 0x40, 0x50, 0xE0, // AdvA
+    0x02, 0x01, 0x06, // flags
+    0x05, 0x09, b'a', b'b', b'c', b'd', // complete name
+    0x09, 0xFF, 0x59, 0x00, 0x01, 0x02, // declares 9 bytes, only 5 follow
+];
+
+pub const ALL: &[(&str, &[u8])] = &[
+    ("iBeacon", IBEACON),
+    ("RuuviTag", RUUVI_TAG),
+    ("Heart-rate strap", HEART_RATE_STRAP),
+    ("NUS scan response", NUS_SCAN_RESPONSE),
+    ("Truncated beacon", TRUNCATED_MANUFACTURER),
+];
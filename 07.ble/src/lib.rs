@@ -0,0 +1,362 @@
+// BLE advertising PDU parser
+//
+// Layout of a legacy advertising PDU (as seen by a sniffer, without preamble,
+// access address and CRC):
+//
+//   +--------+--------+------------+-------------------------+
+//   | HEADER | LENGTH | AdvA (6 B) | AdvData (LENGTH - 6 B)  |
+//   +--------+--------+------------+-------------------------+
+//
+// AdvData is a sequence of AD structures: [len][type][len - 1 bytes of data]
+
+use std::fmt;
+
+#[rustfmt::skip]
+pub mod fixtures;
+
+// AD type codes from the Bluetooth Assigned Numbers document
+const AD_FLAGS: u8 = 0x01;
+const AD_UUID16_INCOMPLETE: u8 = 0x02;
+const AD_UUID16_COMPLETE: u8 = 0x03;
+const AD_UUID128_INCOMPLETE: u8 = 0x06;
+const AD_UUID128_COMPLETE: u8 = 0x07;
+const AD_NAME_SHORT: u8 = 0x08;
+const AD_NAME_COMPLETE: u8 = 0x09;
+const AD_TX_POWER: u8 = 0x0A;
+const AD_MANUFACTURER: u8 = 0xFF;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PduType {
+    AdvInd,
+    AdvDirectInd,
+    AdvNonconnInd,
+    ScanReq,
+    ScanRsp,
+    ConnectInd,
+    AdvScanInd,
+    Unknown(u8),
+}
+
+impl PduType {
+    fn from_bits(bits: u8) -> PduType {
+        match bits {
+            0x0 => PduType::AdvInd,
+            0x1 => PduType::AdvDirectInd,
+            0x2 => PduType::AdvNonconnInd,
+            0x3 => PduType::ScanReq,
+            0x4 => PduType::ScanRsp,
+            0x5 => PduType::ConnectInd,
+            0x6 => PduType::AdvScanInd,
+            other => PduType::Unknown(other),
+        }
+    }
+
+    // Only these PDU types carry an AdvA followed by AdvData
+    fn carries_adv_data(self) -> bool {
+        matches!(
+            self,
+            PduType::AdvInd | PduType::AdvNonconnInd | PduType::ScanRsp | PduType::AdvScanInd
+        )
+    }
+}
+
+// Device address; transmitted least-significant byte first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Address {
+    pub bytes: [u8; 6],
+    pub random: bool,
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let b = &self.bytes;
+        write!(
+            f,
+            "{:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}",
+            b[5], b[4], b[3], b[2], b[1], b[0]
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Flags(pub u8);
+
+impl Flags {
+    pub fn limited_discoverable(self) -> bool {
+        self.0 & 0x01 != 0
+    }
+
+    pub fn general_discoverable(self) -> bool {
+        self.0 & 0x02 != 0
+    }
+
+    pub fn br_edr_not_supported(self) -> bool {
+        self.0 & 0x04 != 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdStructure {
+    Flags(Flags),
+    ServiceUuids16 {
+        complete: bool,
+        uuids: Vec<u16>,
+    },
+    ServiceUuids128 {
+        complete: bool,
+        uuids: Vec<[u8; 16]>,
+    },
+    LocalName {
+        complete: bool,
+        name: String,
+    },
+    TxPower(i8),
+    ManufacturerData {
+        company_id: u16,
+        data: Vec<u8>,
+    },
+    Unknown {
+        ad_type: u8,
+        data: Vec<u8>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    TooShort {
+        needed: usize,
+        got: usize,
+    },
+    LengthMismatch {
+        header: usize,
+        actual: usize,
+    },
+    NotAdvertising(PduType),
+    Truncated {
+        offset: usize,
+        declared: usize,
+        remaining: usize,
+    },
+    InvalidField {
+        ad_type: u8,
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::TooShort { needed, got } => {
+                write!(f, "PDU too short: need {} bytes, got {}", needed, got)
+            }
+            ParseError::LengthMismatch { header, actual } => {
+                write!(f, "header says {} bytes but {} follow", header, actual)
+            }
+            ParseError::NotAdvertising(t) => write!(f, "{:?} does not carry AdvData", t),
+            ParseError::Truncated {
+                offset,
+                declared,
+                remaining,
+            } => write!(
+                f,
+                "AD structure at offset {} declares {} bytes, only {} remain",
+                offset, declared, remaining
+            ),
+            ParseError::InvalidField { ad_type, reason } => {
+                write!(f, "AD type 0x{:02X}: {}", ad_type, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// A parsed PDU; AD-level problems are collected instead of aborting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Advertisement {
+    pub pdu_type: PduType,
+    pub address: Address,
+    pub structures: Vec<AdStructure>,
+    pub errors: Vec<ParseError>,
+}
+
+impl Advertisement {
+    pub fn local_name(&self) -> Option<&str> {
+        self.structures.iter().find_map(|ad| match ad {
+            AdStructure::LocalName { name, .. } => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn manufacturer_data(&self) -> Option<(u16, &[u8])> {
+        self.structures.iter().find_map(|ad| match ad {
+            AdStructure::ManufacturerData { company_id, data } => {
+                Some((*company_id, data.as_slice()))
+            }
+            _ => None,
+        })
+    }
+
+    pub fn flags(&self) -> Option<Flags> {
+        self.structures.iter().find_map(|ad| match ad {
+            AdStructure::Flags(flags) => Some(*flags),
+            _ => None,
+        })
+    }
+}
+
+// Parse a complete PDU: header and address errors are fatal, AD errors are not
+pub fn parse_pdu(bytes: &[u8]) -> Result<Advertisement, ParseError> {
+    if bytes.len() < 8 {
+        return Err(ParseError::TooShort {
+            needed: 8,
+            got: bytes.len(),
+        });
+    }
+
+    let header = bytes[0];
+    let pdu_type = PduType::from_bits(header & 0x0F);
+    let random = header & 0x40 != 0;
+    let length = bytes[1] as usize;
+    let body = &bytes[2..];
+
+    if length != body.len() {
+        return Err(ParseError::LengthMismatch {
+            header: length,
+            actual: body.len(),
+        });
+    }
+    if !pdu_type.carries_adv_data() {
+        return Err(ParseError::NotAdvertising(pdu_type));
+    }
+
+    let mut addr = [0u8; 6];
+    addr.copy_from_slice(&body[..6]);
+    let (structures, errors) = parse_ad_structures(&body[6..]);
+
+    Ok(Advertisement {
+        pdu_type,
+        address: Address {
+            bytes: addr,
+            random,
+        },
+        structures,
+        errors,
+    })
+}
+
+// Walk the AD structures, keeping everything that parsed cleanly
+pub fn parse_ad_structures(data: &[u8]) -> (Vec<AdStructure>, Vec<ParseError>) {
+    let mut structures = Vec::new();
+    let mut errors = Vec::new();
+    let mut offset = 0;
+
+    while offset < data.len() {
+        let len = data[offset] as usize;
+
+        // A zero length marks the start of padding
+        if len == 0 {
+            break;
+        }
+
+        let remaining = data.len() - offset - 1;
+        if len > remaining {
+            errors.push(ParseError::Truncated {
+                offset,
+                declared: len,
+                remaining,
+            });
+            break;
+        }
+
+        let ad_type = data[offset + 1];
+        let payload = &data[offset + 2..offset + 1 + len];
+        match parse_ad(ad_type, payload) {
+            Ok(ad) => structures.push(ad),
+            Err(e) => errors.push(e),
+        }
+
+        offset += 1 + len;
+    }
+
+    (structures, errors)
+}
+
+fn parse_ad(ad_type: u8, data: &[u8]) -> Result<AdStructure, ParseError> {
+    let invalid = |reason| ParseError::InvalidField { ad_type, reason };
+
+    match ad_type {
+        AD_FLAGS => match data {
+            [flags] => Ok(AdStructure::Flags(Flags(*flags))),
+            _ => Err(invalid("flags must be exactly one byte")),
+        },
+        AD_UUID16_INCOMPLETE | AD_UUID16_COMPLETE => {
+            if !data.len().is_multiple_of(2) {
+                return Err(invalid("16-bit UUID list has odd length"));
+            }
+            let uuids = data
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .collect();
+            Ok(AdStructure::ServiceUuids16 {
+                complete: ad_type == AD_UUID16_COMPLETE,
+                uuids,
+            })
+        }
+        AD_UUID128_INCOMPLETE | AD_UUID128_COMPLETE => {
+            if !data.len().is_multiple_of(16) {
+                return Err(invalid("128-bit UUID list is not a multiple of 16"));
+            }
+            let uuids = data
+                .chunks_exact(16)
+                .map(|c| {
+                    let mut uuid = [0u8; 16];
+                    uuid.copy_from_slice(c);
+                    uuid.reverse();
+                    uuid
+                })
+                .collect();
+            Ok(AdStructure::ServiceUuids128 {
+                complete: ad_type == AD_UUID128_COMPLETE,
+                uuids,
+            })
+        }
+        AD_NAME_SHORT | AD_NAME_COMPLETE => match std::str::from_utf8(data) {
+            Ok(name) => Ok(AdStructure::LocalName {
+                complete: ad_type == AD_NAME_COMPLETE,
+                name: name.to_string(),
+            }),
+            Err(_) => Err(invalid("local name is not valid UTF-8")),
+        },
+        AD_TX_POWER => match data {
+            [power] => Ok(AdStructure::TxPower(*power as i8)),
+            _ => Err(invalid("TX power must be exactly one byte")),
+        },
+        AD_MANUFACTURER => {
+            if data.len() < 2 {
+                return Err(invalid("manufacturer data needs a company id"));
+            }
+            Ok(AdStructure::ManufacturerData {
+                company_id: u16::from_le_bytes([data[0], data[1]]),
+                data: data[2..].to_vec(),
+            })
+        }
+        _ => Ok(AdStructure::Unknown {
+            ad_type,
+            data: data.to_vec(),
+        }),
+    }
+}
+
+// Render a 128-bit UUID in the usual 8-4-4-4-12 form
+pub fn format_uuid128(uuid: &[u8; 16]) -> String {
+    let hex: String = uuid.iter().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
@@ -0,0 +1,105 @@
+use ble::{fixtures, format_uuid128, parse_ad_structures, parse_pdu, AdStructure};
+
+fn describe(ad: &AdStructure) -> String {
+    match ad {
+        AdStructure::Flags(flags) => format!(
+            "Flags(0x{:02X}) general={} br_edr_not_supported={}",
+            flags.0,
+            flags.general_discoverable(),
+            flags.br_edr_not_supported()
+        ),
+        AdStructure::ServiceUuids16 { complete, uuids } => {
+            let list: Vec<String> = uuids.iter().map(|u| format!("0x{:04X}", u)).collect();
+            format!("16-bit UUIDs (complete={}): {}", complete, list.join(", "))
+        }
+        AdStructure::ServiceUuids128 { complete, uuids } => {
+            let list: Vec<String> = uuids.iter().map(format_uuid128).collect();
+            format!("128-bit UUIDs (complete={}): {}", complete, list.join(", "))
+        }
+        AdStructure::LocalName { complete, name } => {
+            format!("Name (complete={}): {:?}", complete, name)
+        }
+        AdStructure::TxPower(dbm) => format!("TX power: {} dBm", dbm),
+        AdStructure::ManufacturerData { company_id, data } => {
+            format!("Manufacturer 0x{:04X}: {:02X?}", company_id, data)
+        }
+        AdStructure::Unknown { ad_type, data } => {
+            format!("Unknown type 0x{:02X}: {:02X?}", ad_type, data)
+        }
+    }
+}
+
+fn main() {
+    println!("=== BLE Advertisement Parsing ===\n");
+
+    // 1. Parsing every fixture
+    println!("1. Parsing device fixtures:");
+    for (label, bytes) in fixtures::ALL {
+        println!("\n   [{}]", label);
+        match parse_pdu(bytes) {
+            Ok(adv) => {
+                println!(
+                    "   {:?} from {} (random={})",
+                    adv.pdu_type, adv.address, adv.address.random
+                );
+                for ad in &adv.structures {
+                    println!("     - {}", describe(ad));
+                }
+                for err in &adv.errors {
+                    println!("     ! {}", err);
+                }
+            }
+            Err(e) => println!("   Error: {}", e),
+        }
+    }
+
+    // 2. Convenience accessors
+    println!("\n2. Convenience accessors:");
+    let strap = parse_pdu(fixtures::HEART_RATE_STRAP).unwrap();
+    println!("   Name: {:?}", strap.local_name());
+    println!("   Flags: {:?}", strap.flags());
+
+    // 3. Decoding vendor data: iBeacon major/minor
+    println!("\n3. Decoding iBeacon manufacturer data:");
+    let beacon = parse_pdu(fixtures::IBEACON).unwrap();
+    if let Some((0x004C, [0x02, 0x15, rest @ ..])) = beacon.manufacturer_data() {
+        let major = u16::from_be_bytes([rest[16], rest[17]]);
+        let minor = u16::from_be_bytes([rest[18], rest[19]]);
+        let power = rest[20] as i8;
+        println!(
+            "   major={} minor={} measured_power={} dBm",
+            major, minor, power
+        );
+    }
+
+    // 4. Decoding vendor data: RuuviTag temperature and humidity
+    println!("\n4. Decoding RuuviTag RAWv2 data:");
+    let ruuvi = parse_pdu(fixtures::RUUVI_TAG).unwrap();
+    if let Some((0x0499, [0x05, t_hi, t_lo, h_hi, h_lo, ..])) = ruuvi.manufacturer_data() {
+        let temp = i16::from_be_bytes([*t_hi, *t_lo]) as f32 * 0.005;
+        let humidity = u16::from_be_bytes([*h_hi, *h_lo]) as f32 * 0.0025;
+        println!("   temperature={:.2} C humidity={:.2} %", temp, humidity);
+    }
+
+    // 5. Fatal errors: the PDU itself is unusable
+    println!("\n5. Fatal PDU errors:");
+    let cases: [(&str, &[u8]); 3] = [
+        ("too short", &[0x40, 0x02, 0x01]),
+        ("length mismatch", &[0x40, 0x09, 1, 2, 3, 4, 5, 6, 0x02]),
+        ("scan request", &[0x03, 0x06, 1, 2, 3, 4, 5, 6]),
+    ];
+    for (label, bytes) in cases {
+        println!("   {}: {}", label, parse_pdu(bytes).unwrap_err());
+    }
+
+    // 6. Tolerant errors: bad structures are reported, good ones kept
+    println!("\n6. Tolerant AD parsing:");
+    let data = [
+        0x02, 0x01, 0x06, 0x02, 0x0A, 0x01, 0x02, 0x03, 0x0D, 0x04, 0x09, b'o', b'k',
+    ];
+    let (structures, errors) = parse_ad_structures(&data);
+    println!("   Parsed: {:?}", structures);
+    println!("   Errors: {:?}", errors);
+
+    println!("\n=== End of BLE Examples ===");
+}
@@ -0,0 +1,196 @@
+use ble::{
+    fixtures, format_uuid128, parse_ad_structures, parse_pdu, AdStructure, Flags, ParseError,
+    PduType,
+};
+
+#[test]
+fn every_fixture_parses() {
+    for (label, bytes) in fixtures::ALL {
+        assert!(parse_pdu(bytes).is_ok(), "{}", label);
+    }
+}
+
+#[test]
+fn ibeacon() {
+    let adv = parse_pdu(fixtures::IBEACON).unwrap();
+    assert_eq!(adv.pdu_type, PduType::AdvNonconnInd);
+    assert!(adv.address.random);
+    assert_eq!(adv.address.to_string(), "C6:55:44:33:22:11");
+    assert_eq!(adv.flags(), Some(Flags(0x06)));
+    assert!(adv.errors.is_empty());
+
+    let (company, data) = adv.manufacturer_data().unwrap();
+    assert_eq!(company, 0x004C);
+    assert_eq!(&data[..2], [0x02, 0x15]);
+    assert_eq!(u16::from_be_bytes([data[18], data[19]]), 1);
+    assert_eq!(u16::from_be_bytes([data[20], data[21]]), 42);
+    assert_eq!(data[22] as i8, -59);
+}
+
+#[test]
+fn ruuvi_tag() {
+    let adv = parse_pdu(fixtures::RUUVI_TAG).unwrap();
+    assert_eq!(adv.pdu_type, PduType::AdvInd);
+    let (company, data) = adv.manufacturer_data().unwrap();
+    assert_eq!(company, 0x0499);
+    assert_eq!(data[0], 5);
+    let temp = i16::from_be_bytes([data[1], data[2]]) as f32 * 0.005;
+    let humidity = u16::from_be_bytes([data[3], data[4]]) as f32 * 0.0025;
+    assert!((temp - 24.30).abs() < 0.001);
+    assert!((humidity - 53.49).abs() < 0.001);
+}
+
+#[test]
+fn heart_rate_strap() {
+    let adv = parse_pdu(fixtures::HEART_RATE_STRAP).unwrap();
+    assert!(!adv.address.random);
+    assert_eq!(adv.address.to_string(), "0C:0B:0A:03:02:01");
+    assert_eq!(
+        adv.structures,
+        [
+            AdStructure::Flags(Flags(0x06)),
+            AdStructure::ServiceUuids16 {
+                complete: true,
+                uuids: vec![0x180D]
+            },
+            AdStructure::LocalName {
+                complete: true,
+                name: "HR-Strap1".to_string()
+            },
+            AdStructure::TxPower(4),
+        ]
+    );
+    assert_eq!(adv.local_name(), Some("HR-Strap1"));
+    let flags = adv.flags().unwrap();
+    assert!(flags.general_discoverable());
+    assert!(flags.br_edr_not_supported());
+    assert!(!flags.limited_discoverable());
+}
+
+#[test]
+fn nus_scan_response() {
+    let adv = parse_pdu(fixtures::NUS_SCAN_RESPONSE).unwrap();
+    assert_eq!(adv.pdu_type, PduType::ScanRsp);
+    assert_eq!(adv.flags(), None);
+    assert_eq!(adv.local_name(), Some("NUS-Dev"));
+    match &adv.structures[0] {
+        AdStructure::ServiceUuids128 { complete, uuids } => {
+            assert!(complete);
+            assert_eq!(
+                uuids.iter().map(format_uuid128).collect::<Vec<_>>(),
+                ["6e400001-b5a3-f393-e0a9-e50e24dcca9e"]
+            );
+        }
+        other => panic!("expected 128-bit UUIDs, got {:?}", other),
+    }
+}
+
+#[test]
+fn truncated_structure_keeps_what_came_before() {
+    let adv = parse_pdu(fixtures::TRUNCATED_MANUFACTURER).unwrap();
+    assert_eq!(adv.flags(), Some(Flags(0x06)));
+    assert_eq!(adv.local_name(), Some("abcd"));
+    assert_eq!(adv.manufacturer_data(), None);
+    assert_eq!(
+        adv.errors,
+        [ParseError::Truncated {
+            offset: 9,
+            declared: 9,
+            remaining: 5
+        }]
+    );
+}
+
+#[test]
+fn fatal_pdu_errors() {
+    assert_eq!(
+        parse_pdu(&[0x40, 0x02, 0x01]),
+        Err(ParseError::TooShort { needed: 8, got: 3 })
+    );
+    assert_eq!(
+        parse_pdu(&[0x40, 0x09, 1, 2, 3, 4, 5, 6, 0x02]),
+        Err(ParseError::LengthMismatch {
+            header: 9,
+            actual: 7
+        })
+    );
+    for (header, pdu_type) in [
+        (0x01, PduType::AdvDirectInd),
+        (0x03, PduType::ScanReq),
+        (0x05, PduType::ConnectInd),
+        (0x0F, PduType::Unknown(0x0F)),
+    ] {
+        assert_eq!(
+            parse_pdu(&[header, 0x06, 1, 2, 3, 4, 5, 6]),
+            Err(ParseError::NotAdvertising(pdu_type))
+        );
+    }
+    // An address and no AdvData is a valid, empty advertisement
+    let empty = parse_pdu(&[0x06, 0x06, 1, 2, 3, 4, 5, 6]).unwrap();
+    assert_eq!(empty.pdu_type, PduType::AdvScanInd);
+    assert!(empty.structures.is_empty() && empty.errors.is_empty());
+}
+
+#[test]
+fn bad_fields_are_reported_and_parsing_continues() {
+    let data = [
+        0x02, 0x01, 0x06, // flags
+        0x02, 0x0A, 0x01, // TX power +1 dBm
+        0x02, 0x03, 0x0D, // odd-length 16-bit UUID list
+        0x03, 0x09, 0xFF, 0xFE, // name that isn't UTF-8
+        0x02, 0xFF, 0x59, // company id cut short
+        0x03, 0x01, 0x06, 0x00, // flags of two bytes
+        0x04, 0x16, 0x0D, 0x18, 0x55, // service data: kept as Unknown
+        0x03, 0x08, b'o', b'k', // short name
+        0x00, 0x00, 0x00, // padding
+    ];
+    let (structures, errors) = parse_ad_structures(&data);
+    assert_eq!(
+        structures,
+        [
+            AdStructure::Flags(Flags(0x06)),
+            AdStructure::TxPower(1),
+            AdStructure::Unknown {
+                ad_type: 0x16,
+                data: vec![0x0D, 0x18, 0x55]
+            },
+            AdStructure::LocalName {
+                complete: false,
+                name: "ok".to_string()
+            },
+        ]
+    );
+    let invalid: Vec<u8> = errors
+        .iter()
+        .map(|e| match e {
+            ParseError::InvalidField { ad_type, .. } => *ad_type,
+            other => panic!("unexpected {:?}", other),
+        })
+        .collect();
+    assert_eq!(invalid, [0x03, 0x09, 0xFF, 0x01]);
+}
+
+#[test]
+fn negative_tx_power_and_empty_input() {
+    let (structures, errors) = parse_ad_structures(&[0x02, 0x0A, 0xF4]);
+    assert_eq!(structures, [AdStructure::TxPower(-12)]);
+    assert!(errors.is_empty());
+    assert_eq!(parse_ad_structures(&[]), (Vec::new(), Vec::new()));
+}
+
+#[test]
+fn error_messages() {
+    let truncated = ParseError::Truncated {
+        offset: 9,
+        declared: 9,
+        remaining: 5,
+    };
+    assert_eq!(
+        truncated.to_string(),
+        "AD structure at offset 9 declares 9 bytes, only 5 remain"
+    );
+    assert_eq!(
+        ParseError::NotAdvertising(PduType::ScanReq).to_string(),
+        "ScanReq does not carry AdvData"
+    );
+}
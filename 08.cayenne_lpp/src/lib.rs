@@ -0,0 +1,355 @@
+// Cayenne Low Power Payload (LPP)
+//
+// Each reading is packed as:
+//
+//   +---------+------+------------------------------+
+//   | CHANNEL | TYPE | VALUE (size depends on TYPE) |
+//   +---------+------+------------------------------+
+//
+// Values are big-endian fixed-point integers with a per-type resolution.
+
+use std::fmt;
+
+pub const DIGITAL_INPUT: u8 = 0;
+pub const DIGITAL_OUTPUT: u8 = 1;
+pub const ANALOG_INPUT: u8 = 2;
+pub const ANALOG_OUTPUT: u8 = 3;
+pub const ILLUMINANCE: u8 = 101;
+pub const PRESENCE: u8 = 102;
+pub const TEMPERATURE: u8 = 103;
+pub const HUMIDITY: u8 = 104;
+pub const ACCELEROMETER: u8 = 113;
+pub const BAROMETER: u8 = 115;
+pub const GYROMETER: u8 = 134;
+pub const GPS: u8 = 136;
+
+// Maximum application payload for some common LoRaWAN data rates
+pub const US915_DR0_MAX: usize = 11;
+pub const EU868_DR0_MAX: usize = 51;
+pub const EU868_DR5_MAX: usize = 222;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    DigitalInput(u8),
+    DigitalOutput(u8),
+    AnalogInput(f32),
+    AnalogOutput(f32),
+    Illuminance(u16),
+    Presence(u8),
+    Temperature(f32),
+    Humidity(f32),
+    Accelerometer { x: f32, y: f32, z: f32 },
+    Barometer(f32),
+    Gyrometer { x: f32, y: f32, z: f32 },
+    Gps { lat: f64, lon: f64, alt: f64 },
+}
+
+impl Value {
+    pub fn type_id(&self) -> u8 {
+        match self {
+            Value::DigitalInput(_) => DIGITAL_INPUT,
+            Value::DigitalOutput(_) => DIGITAL_OUTPUT,
+            Value::AnalogInput(_) => ANALOG_INPUT,
+            Value::AnalogOutput(_) => ANALOG_OUTPUT,
+            Value::Illuminance(_) => ILLUMINANCE,
+            Value::Presence(_) => PRESENCE,
+            Value::Temperature(_) => TEMPERATURE,
+            Value::Humidity(_) => HUMIDITY,
+            Value::Accelerometer { .. } => ACCELEROMETER,
+            Value::Barometer(_) => BAROMETER,
+            Value::Gyrometer { .. } => GYROMETER,
+            Value::Gps { .. } => GPS,
+        }
+    }
+
+    // Bytes on the wire, including channel and type
+    pub fn encoded_len(&self) -> usize {
+        2 + data_size(self.type_id()).unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reading {
+    pub channel: u8,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodeError {
+    PayloadFull { needed: usize, available: usize },
+    OutOfRange { type_id: u8, value: f64 },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::PayloadFull { needed, available } => {
+                write!(
+                    f,
+                    "payload full: need {} bytes, {} available",
+                    needed, available
+                )
+            }
+            EncodeError::OutOfRange { type_id, value } => {
+                write!(f, "value {} does not fit LPP type {}", value, type_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    UnknownType {
+        offset: usize,
+        type_id: u8,
+    },
+    Truncated {
+        offset: usize,
+        needed: usize,
+        remaining: usize,
+    },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnknownType { offset, type_id } => {
+                write!(f, "unknown LPP type {} at offset {}", type_id, offset)
+            }
+            DecodeError::Truncated {
+                offset,
+                needed,
+                remaining,
+            } => write!(
+                f,
+                "reading at offset {} needs {} bytes, {} remain",
+                offset, needed, remaining
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+// Size of the value field for each type
+fn data_size(type_id: u8) -> Option<usize> {
+    match type_id {
+        DIGITAL_INPUT | DIGITAL_OUTPUT | PRESENCE | HUMIDITY => Some(1),
+        ANALOG_INPUT | ANALOG_OUTPUT | ILLUMINANCE | TEMPERATURE | BAROMETER => Some(2),
+        ACCELEROMETER | GYROMETER => Some(6),
+        GPS => Some(9),
+        _ => None,
+    }
+}
+
+// Builds a payload without ever exceeding the configured budget
+#[derive(Debug)]
+pub struct LppEncoder {
+    buffer: Vec<u8>,
+    max_size: usize,
+}
+
+impl LppEncoder {
+    pub fn new(max_size: usize) -> LppEncoder {
+        LppEncoder {
+            buffer: Vec::with_capacity(max_size),
+            max_size,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.max_size - self.buffer.len()
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+    }
+
+    // Append one reading; on error the buffer is left untouched
+    pub fn add(&mut self, channel: u8, value: Value) -> Result<(), EncodeError> {
+        let needed = value.encoded_len();
+        if needed > self.remaining() {
+            return Err(EncodeError::PayloadFull {
+                needed,
+                available: self.remaining(),
+            });
+        }
+
+        let mut out = Vec::with_capacity(needed);
+        out.push(channel);
+        out.push(value.type_id());
+        encode_value(&value, &mut out)?;
+        self.buffer.extend_from_slice(&out);
+        Ok(())
+    }
+}
+
+fn scaled(
+    type_id: u8,
+    value: f64,
+    resolution: f64,
+    min: f64,
+    max: f64,
+) -> Result<i64, EncodeError> {
+    let raw = (value / resolution).round();
+    if raw < min || raw > max || raw.is_nan() {
+        return Err(EncodeError::OutOfRange { type_id, value });
+    }
+    Ok(raw as i64)
+}
+
+fn push_i16(
+    type_id: u8,
+    value: f64,
+    resolution: f64,
+    out: &mut Vec<u8>,
+) -> Result<(), EncodeError> {
+    let raw = scaled(type_id, value, resolution, i16::MIN as f64, i16::MAX as f64)?;
+    out.extend_from_slice(&(raw as i16).to_be_bytes());
+    Ok(())
+}
+
+fn push_u16(
+    type_id: u8,
+    value: f64,
+    resolution: f64,
+    out: &mut Vec<u8>,
+) -> Result<(), EncodeError> {
+    let raw = scaled(type_id, value, resolution, 0.0, u16::MAX as f64)?;
+    out.extend_from_slice(&(raw as u16).to_be_bytes());
+    Ok(())
+}
+
+// 24-bit signed, used by the GPS type
+fn push_i24(
+    type_id: u8,
+    value: f64,
+    resolution: f64,
+    out: &mut Vec<u8>,
+) -> Result<(), EncodeError> {
+    let raw = scaled(type_id, value, resolution, -8_388_608.0, 8_388_607.0)?;
+    out.extend_from_slice(&(raw as i32).to_be_bytes()[1..]);
+    Ok(())
+}
+
+fn encode_value(value: &Value, out: &mut Vec<u8>) -> Result<(), EncodeError> {
+    let t = value.type_id();
+    match *value {
+        Value::DigitalInput(v) | Value::DigitalOutput(v) | Value::Presence(v) => out.push(v),
+        Value::AnalogInput(v) | Value::AnalogOutput(v) => push_i16(t, v as f64, 0.01, out)?,
+        Value::Illuminance(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Value::Temperature(v) => push_i16(t, v as f64, 0.1, out)?,
+        Value::Humidity(v) => {
+            let raw = scaled(t, v as f64, 0.5, 0.0, u8::MAX as f64)?;
+            out.push(raw as u8);
+        }
+        Value::Accelerometer { x, y, z } => {
+            for axis in [x, y, z] {
+                push_i16(t, axis as f64, 0.001, out)?;
+            }
+        }
+        Value::Barometer(v) => push_u16(t, v as f64, 0.1, out)?,
+        Value::Gyrometer { x, y, z } => {
+            for axis in [x, y, z] {
+                push_i16(t, axis as f64, 0.01, out)?;
+            }
+        }
+        Value::Gps { lat, lon, alt } => {
+            push_i24(t, lat, 0.0001, out)?;
+            push_i24(t, lon, 0.0001, out)?;
+            push_i24(t, alt, 0.01, out)?;
+        }
+    }
+    Ok(())
+}
+
+fn i16_at(data: &[u8], i: usize) -> f32 {
+    i16::from_be_bytes([data[i], data[i + 1]]) as f32
+}
+
+fn i24_at(data: &[u8], i: usize) -> f64 {
+    // Place the three bytes in the top of an i32, then shift to sign-extend
+    let raw = i32::from_be_bytes([data[i], data[i + 1], data[i + 2], 0]) >> 8;
+    raw as f64
+}
+
+fn decode_value(type_id: u8, data: &[u8]) -> Value {
+    match type_id {
+        DIGITAL_INPUT => Value::DigitalInput(data[0]),
+        DIGITAL_OUTPUT => Value::DigitalOutput(data[0]),
+        ANALOG_INPUT => Value::AnalogInput(i16_at(data, 0) * 0.01),
+        ANALOG_OUTPUT => Value::AnalogOutput(i16_at(data, 0) * 0.01),
+        ILLUMINANCE => Value::Illuminance(u16::from_be_bytes([data[0], data[1]])),
+        PRESENCE => Value::Presence(data[0]),
+        TEMPERATURE => Value::Temperature(i16_at(data, 0) * 0.1),
+        HUMIDITY => Value::Humidity(data[0] as f32 * 0.5),
+        ACCELEROMETER => Value::Accelerometer {
+            x: i16_at(data, 0) * 0.001,
+            y: i16_at(data, 2) * 0.001,
+            z: i16_at(data, 4) * 0.001,
+        },
+        BAROMETER => Value::Barometer(u16::from_be_bytes([data[0], data[1]]) as f32 * 0.1),
+        GYROMETER => Value::Gyrometer {
+            x: i16_at(data, 0) * 0.01,
+            y: i16_at(data, 2) * 0.01,
+            z: i16_at(data, 4) * 0.01,
+        },
+        GPS => Value::Gps {
+            lat: i24_at(data, 0) * 0.0001,
+            lon: i24_at(data, 3) * 0.0001,
+            alt: i24_at(data, 6) * 0.01,
+        },
+        _ => unreachable!("data_size() rejects unknown types"),
+    }
+}
+
+// Decode a whole uplink payload
+pub fn decode(payload: &[u8]) -> Result<Vec<Reading>, DecodeError> {
+    let mut readings = Vec::new();
+    let mut offset = 0;
+
+    while offset < payload.len() {
+        let remaining = payload.len() - offset;
+        if remaining < 2 {
+            return Err(DecodeError::Truncated {
+                offset,
+                needed: 2,
+                remaining,
+            });
+        }
+
+        let channel = payload[offset];
+        let type_id = payload[offset + 1];
+        let size = data_size(type_id).ok_or(DecodeError::UnknownType { offset, type_id })?;
+        if remaining < 2 + size {
+            return Err(DecodeError::Truncated {
+                offset,
+                needed: 2 + size,
+                remaining,
+            });
+        }
+
+        let data = &payload[offset + 2..offset + 2 + size];
+        readings.push(Reading {
+            channel,
+            value: decode_value(type_id, data),
+        });
+        offset += 2 + size;
+    }
+
+    Ok(readings)
+}
@@ -0,0 +1,128 @@
+use cayenne_lpp::{decode, LppEncoder, Value, EU868_DR0_MAX, US915_DR0_MAX};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn main() {
+    println!("=== Cayenne LPP Encoding ===\n");
+
+    // 1. Encoding two temperature readings
+    println!("1. Encoding readings:");
+    let mut encoder = LppEncoder::new(EU868_DR0_MAX);
+    encoder.add(3, Value::Temperature(27.2)).unwrap();
+    encoder.add(5, Value::Temperature(25.5)).unwrap();
+    println!(
+        "   Payload: {} ({} bytes)",
+        hex(encoder.as_bytes()),
+        encoder.len()
+    );
+
+    // 2. Decoding it back
+    println!("\n2. Decoding:");
+    for reading in decode(encoder.as_bytes()).unwrap() {
+        println!("   channel {} -> {:?}", reading.channel, reading.value);
+    }
+
+    // 3. Round trip of every supported type
+    println!("\n3. Round trip of every type:");
+    let values = [
+        Value::DigitalInput(1),
+        Value::DigitalOutput(0),
+        Value::AnalogInput(-3.3),
+        Value::AnalogOutput(12.5),
+        Value::Illuminance(850),
+        Value::Presence(1),
+        Value::Temperature(-12.3),
+        Value::Humidity(48.5),
+        Value::Accelerometer {
+            x: 0.012,
+            y: -0.981,
+            z: 0.05,
+        },
+        Value::Barometer(1013.2),
+        Value::Gyrometer {
+            x: 1.5,
+            y: -0.25,
+            z: 90.0,
+        },
+        Value::Gps {
+            lat: 52.3702,
+            lon: 4.8952,
+            alt: 12.5,
+        },
+    ];
+    let mut encoder = LppEncoder::new(EU868_DR0_MAX * 2);
+    for (channel, value) in values.iter().enumerate() {
+        encoder.add(channel as u8, *value).unwrap();
+    }
+    println!(
+        "   {} values packed into {} bytes",
+        values.len(),
+        encoder.len()
+    );
+    for reading in decode(encoder.as_bytes()).unwrap() {
+        println!("   ch{:<2} {:?}", reading.channel, reading.value);
+    }
+
+    // 4. Staying within the LoRa payload budget
+    println!("\n4. Fitting a US915 DR0 budget ({} bytes):", US915_DR0_MAX);
+    let mut encoder = LppEncoder::new(US915_DR0_MAX);
+    let wanted = [
+        (1, Value::Temperature(21.4)),
+        (2, Value::Humidity(55.0)),
+        (3, Value::Barometer(1009.8)),
+        (
+            4,
+            Value::Gps {
+                lat: 0.0,
+                lon: 0.0,
+                alt: 0.0,
+            },
+        ),
+    ];
+    for (channel, value) in wanted {
+        match encoder.add(channel, value) {
+            Ok(()) => println!(
+                "   + ch{} {} bytes, {} left",
+                channel,
+                value.encoded_len(),
+                encoder.remaining()
+            ),
+            Err(e) => println!("   - ch{} skipped: {}", channel, e),
+        }
+    }
+    println!("   Uplink: {}", hex(encoder.as_bytes()));
+
+    // 5. Fixed-point resolution loses precision
+    println!("\n5. Quantization error:");
+    for t in [21.37_f32, 21.34, -0.04] {
+        let mut encoder = LppEncoder::new(4);
+        encoder.add(0, Value::Temperature(t)).unwrap();
+        let back = decode(encoder.as_bytes()).unwrap()[0].value;
+        println!("   {:>6} -> {:?}", t, back);
+    }
+
+    // 6. Values that do not fit the type
+    println!("\n6. Out-of-range values:");
+    let mut encoder = LppEncoder::new(EU868_DR0_MAX);
+    for value in [
+        Value::Temperature(4000.0),
+        Value::Humidity(130.0),
+        Value::Barometer(-1.0),
+    ] {
+        println!("   {:?}: {}", value, encoder.add(0, value).unwrap_err());
+    }
+    println!("   Buffer still empty: {}", encoder.is_empty());
+
+    // 7. Decoding errors
+    println!("\n7. Malformed payloads:");
+    println!("   {}", decode(&[0x01, 0x67, 0x01]).unwrap_err());
+    println!("   {}", decode(&[0x01, 0x99, 0x00]).unwrap_err());
+
+    println!("\n=== End of Cayenne LPP Examples ===");
+}
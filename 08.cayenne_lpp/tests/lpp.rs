@@ -0,0 +1,298 @@
+use cayenne_lpp::{
+    decode, DecodeError, EncodeError, LppEncoder, Reading, Value, BAROMETER, EU868_DR0_MAX,
+    HUMIDITY, TEMPERATURE, US915_DR0_MAX,
+};
+
+fn encode(readings: &[(u8, Value)]) -> Vec<u8> {
+    let mut encoder = LppEncoder::new(256);
+    for &(channel, value) in readings {
+        encoder.add(channel, value).unwrap();
+    }
+    encoder.as_bytes().to_vec()
+}
+
+fn close(a: f64, b: f64, resolution: f64) -> bool {
+    (a - b).abs() <= resolution / 2.0 + 1e-6
+}
+
+// Equal within half a step of the type's resolution
+fn same(sent: &Value, got: &Value) -> bool {
+    match (*sent, *got) {
+        (Value::DigitalInput(a), Value::DigitalInput(b))
+        | (Value::DigitalOutput(a), Value::DigitalOutput(b))
+        | (Value::Presence(a), Value::Presence(b)) => a == b,
+        (Value::Illuminance(a), Value::Illuminance(b)) => a == b,
+        (Value::AnalogInput(a), Value::AnalogInput(b))
+        | (Value::AnalogOutput(a), Value::AnalogOutput(b)) => close(a as f64, b as f64, 0.01),
+        (Value::Temperature(a), Value::Temperature(b))
+        | (Value::Barometer(a), Value::Barometer(b)) => close(a as f64, b as f64, 0.1),
+        (Value::Humidity(a), Value::Humidity(b)) => close(a as f64, b as f64, 0.5),
+        (
+            Value::Accelerometer { x, y, z },
+            Value::Accelerometer {
+                x: bx,
+                y: by,
+                z: bz,
+            },
+        ) => [(x, bx), (y, by), (z, bz)]
+            .iter()
+            .all(|&(a, b)| close(a as f64, b as f64, 0.001)),
+        (
+            Value::Gyrometer { x, y, z },
+            Value::Gyrometer {
+                x: bx,
+                y: by,
+                z: bz,
+            },
+        ) => [(x, bx), (y, by), (z, bz)]
+            .iter()
+            .all(|&(a, b)| close(a as f64, b as f64, 0.01)),
+        (
+            Value::Gps { lat, lon, alt },
+            Value::Gps {
+                lat: blat,
+                lon: blon,
+                alt: balt,
+            },
+        ) => close(lat, blat, 0.0001) && close(lon, blon, 0.0001) && close(alt, balt, 0.01),
+        _ => false,
+    }
+}
+
+#[test]
+fn reference_vectors() {
+    // The examples of the Cayenne LPP documentation
+    assert_eq!(
+        encode(&[(3, Value::Temperature(27.2)), (5, Value::Temperature(25.5))]),
+        [0x03, 0x67, 0x01, 0x10, 0x05, 0x67, 0x00, 0xFF]
+    );
+    assert_eq!(
+        encode(&[(
+            6,
+            Value::Accelerometer {
+                x: 1.234,
+                y: -1.234,
+                z: 0.0
+            }
+        )]),
+        [0x06, 0x71, 0x04, 0xD2, 0xFB, 0x2E, 0x00, 0x00]
+    );
+    let gps = [
+        0x01, 0x88, 0x06, 0x76, 0x5F, 0xF2, 0x96, 0x0A, 0x00, 0x03, 0xE8,
+    ];
+    assert_eq!(
+        encode(&[(
+            1,
+            Value::Gps {
+                lat: 42.3519,
+                lon: -87.9094,
+                alt: 10.0
+            }
+        )]),
+        gps
+    );
+    let decoded = decode(&gps).unwrap();
+    assert_eq!(decoded.len(), 1);
+    assert_eq!(decoded[0].channel, 1);
+    assert!(same(
+        &Value::Gps {
+            lat: 42.3519,
+            lon: -87.9094,
+            alt: 10.0
+        },
+        &decoded[0].value
+    ));
+}
+
+#[test]
+fn every_type_round_trips() {
+    let values = [
+        Value::DigitalInput(1),
+        Value::DigitalOutput(0),
+        Value::AnalogInput(-3.3),
+        Value::AnalogOutput(12.5),
+        Value::Illuminance(850),
+        Value::Presence(1),
+        Value::Temperature(-12.3),
+        Value::Humidity(48.5),
+        Value::Accelerometer {
+            x: 0.012,
+            y: -0.981,
+            z: 0.05,
+        },
+        Value::Barometer(1013.2),
+        Value::Gyrometer {
+            x: 1.5,
+            y: -0.25,
+            z: 90.0,
+        },
+        Value::Gps {
+            lat: -33.8688,
+            lon: 151.2093,
+            alt: -12.5,
+        },
+    ];
+    let readings: Vec<(u8, Value)> = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (i as u8 * 10, *v))
+        .collect();
+    let bytes = encode(&readings);
+    assert_eq!(
+        bytes.len(),
+        values.iter().map(Value::encoded_len).sum::<usize>()
+    );
+
+    let decoded = decode(&bytes).unwrap();
+    assert_eq!(decoded.len(), values.len());
+    for (
+        (channel, sent),
+        Reading {
+            channel: got_channel,
+            value,
+        },
+    ) in readings.iter().zip(&decoded)
+    {
+        assert_eq!(channel, got_channel);
+        assert_eq!(sent.type_id(), value.type_id());
+        assert!(same(sent, value), "{:?} came back as {:?}", sent, value);
+    }
+}
+
+#[test]
+fn random_temperatures_round_trip_within_resolution() {
+    let mut state = 1u64;
+    for _ in 0..10_000 {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        // -3276.8 to 3276.7 °C, the whole i16 range at 0.1
+        let t = ((state >> 48) as i16) as f32 / 10.0;
+        let decoded = decode(&encode(&[(0, Value::Temperature(t))])).unwrap();
+        assert!(same(&Value::Temperature(t), &decoded[0].value), "{}", t);
+    }
+}
+
+#[test]
+fn the_budget_is_never_exceeded() {
+    let mut encoder = LppEncoder::new(US915_DR0_MAX);
+    assert_eq!(encoder.remaining(), 11);
+    encoder.add(1, Value::Temperature(21.4)).unwrap();
+    encoder.add(2, Value::Humidity(55.0)).unwrap();
+    encoder.add(3, Value::Barometer(1009.8)).unwrap();
+    assert_eq!(encoder.len(), 11);
+    assert_eq!(
+        encoder.add(4, Value::Presence(1)),
+        Err(EncodeError::PayloadFull {
+            needed: 3,
+            available: 0
+        })
+    );
+    assert_eq!(encoder.len(), 11);
+
+    // A GPS fix alone (11 bytes) fills the whole US915 DR0 budget
+    let mut encoder = LppEncoder::new(US915_DR0_MAX);
+    encoder
+        .add(
+            1,
+            Value::Gps {
+                lat: 0.0,
+                lon: 0.0,
+                alt: 0.0,
+            },
+        )
+        .unwrap();
+    assert_eq!(encoder.remaining(), 0);
+
+    // Temperatures only: 4 bytes each, 12 of them in EU868 DR0
+    let mut encoder = LppEncoder::new(EU868_DR0_MAX);
+    let mut added = 0;
+    while encoder.add(added, Value::Temperature(20.0)).is_ok() {
+        added += 1;
+    }
+    assert_eq!(added, 12);
+    assert!(encoder.len() <= EU868_DR0_MAX);
+
+    encoder.reset();
+    assert!(encoder.is_empty());
+    assert_eq!(encoder.remaining(), EU868_DR0_MAX);
+}
+
+#[test]
+fn out_of_range_values_are_rejected_and_nothing_is_written() {
+    let mut encoder = LppEncoder::new(EU868_DR0_MAX);
+    let cases = [
+        (Value::Temperature(4000.0), TEMPERATURE),
+        (Value::Temperature(f32::NAN), TEMPERATURE),
+        (Value::Humidity(130.0), HUMIDITY),
+        (Value::Humidity(-1.0), HUMIDITY),
+        (Value::Barometer(-1.0), BAROMETER),
+    ];
+    for (value, type_id) in cases {
+        match encoder.add(0, value) {
+            Err(EncodeError::OutOfRange { type_id: t, .. }) => assert_eq!(t, type_id),
+            other => panic!("{:?} gave {:?}", value, other),
+        }
+    }
+    // An accelerometer whose last axis is out of range leaves no partial bytes
+    assert!(encoder
+        .add(
+            0,
+            Value::Accelerometer {
+                x: 0.0,
+                y: 0.0,
+                z: 40.0
+            }
+        )
+        .is_err());
+    assert!(encoder.is_empty());
+    // The extremes of each range still fit
+    encoder.add(0, Value::Humidity(127.5)).unwrap();
+    encoder.add(0, Value::Temperature(-3276.8)).unwrap();
+    encoder.add(0, Value::Barometer(6553.5)).unwrap();
+}
+
+#[test]
+fn malformed_payloads() {
+    assert_eq!(decode(&[]), Ok(Vec::new()));
+    assert_eq!(
+        decode(&[0x01]),
+        Err(DecodeError::Truncated {
+            offset: 0,
+            needed: 2,
+            remaining: 1
+        })
+    );
+    assert_eq!(
+        decode(&[0x01, 0x67, 0x01]),
+        Err(DecodeError::Truncated {
+            offset: 0,
+            needed: 4,
+            remaining: 3
+        })
+    );
+    assert_eq!(
+        decode(&[0x01, 0x99, 0x00]),
+        Err(DecodeError::UnknownType {
+            offset: 0,
+            type_id: 0x99
+        })
+    );
+    // The offset points at the bad reading, after the good ones
+    assert_eq!(
+        decode(&[0x01, 0x67, 0x00, 0xFF, 0x02, 0x88, 0x00]),
+        Err(DecodeError::Truncated {
+            offset: 4,
+            needed: 11,
+            remaining: 3
+        })
+    );
+    assert_eq!(
+        DecodeError::UnknownType {
+            offset: 4,
+            type_id: 0x99
+        }
+        .to_string(),
+        "unknown LPP type 153 at offset 4"
+    );
+}
@@ -0,0 +1,283 @@
+// NMEA 0183 sentence parsing
+//
+//   $GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47
+//   ^ ^^   ^ comma-separated fields                             ^ XOR checksum
+//   | talker id + sentence type
+
+use std::fmt;
+
+mod stream;
+
+pub use stream::NmeaReader;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    pub day: u8,
+    pub month: u8,
+    pub year: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixQuality {
+    Invalid,
+    Gps,
+    Dgps,
+    Pps,
+    Rtk,
+    FloatRtk,
+    Estimated,
+    Manual,
+    Simulation,
+    Unknown(u8),
+}
+
+impl FixQuality {
+    pub fn from_digit(d: u8) -> FixQuality {
+        match d {
+            0 => FixQuality::Invalid,
+            1 => FixQuality::Gps,
+            2 => FixQuality::Dgps,
+            3 => FixQuality::Pps,
+            4 => FixQuality::Rtk,
+            5 => FixQuality::FloatRtk,
+            6 => FixQuality::Estimated,
+            7 => FixQuality::Manual,
+            8 => FixQuality::Simulation,
+            other => FixQuality::Unknown(other),
+        }
+    }
+
+    pub fn has_fix(self) -> bool {
+        !matches!(self, FixQuality::Invalid | FixQuality::Unknown(_))
+    }
+}
+
+// Global Positioning System Fix Data
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gga {
+    pub time: Option<Time>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub fix_quality: FixQuality,
+    pub satellites: u8,
+    pub hdop: Option<f32>,
+    pub altitude_m: Option<f32>,
+}
+
+// Recommended Minimum Specific GNSS Data
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rmc {
+    pub time: Option<Time>,
+    pub active: bool,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub speed_knots: Option<f32>,
+    pub course_deg: Option<f32>,
+    pub date: Option<Date>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sentence {
+    Gga(Gga),
+    Rmc(Rmc),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NmeaError {
+    MissingDollar,
+    MissingChecksum,
+    BadChecksum { expected: u8, computed: u8 },
+    Unsupported(String),
+    MissingField(&'static str),
+    InvalidField(&'static str),
+    LineTooLong(usize),
+}
+
+impl fmt::Display for NmeaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NmeaError::MissingDollar => write!(f, "sentence does not start with '$'"),
+            NmeaError::MissingChecksum => write!(f, "sentence has no '*hh' checksum"),
+            NmeaError::BadChecksum { expected, computed } => write!(
+                f,
+                "checksum mismatch: sentence says {:02X}, computed {:02X}",
+                expected, computed
+            ),
+            NmeaError::Unsupported(kind) => write!(f, "unsupported sentence type {}", kind),
+            NmeaError::MissingField(name) => write!(f, "missing field: {}", name),
+            NmeaError::InvalidField(name) => write!(f, "invalid field: {}", name),
+            NmeaError::LineTooLong(limit) => {
+                write!(f, "line exceeds the {}-byte NMEA limit", limit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NmeaError {}
+
+pub fn checksum(body: &str) -> u8 {
+    body.bytes().fold(0, |acc, b| acc ^ b)
+}
+
+// Split "$BODY*HH" into BODY after verifying the checksum
+pub fn verify(sentence: &str) -> Result<&str, NmeaError> {
+    let rest = sentence.strip_prefix('$').ok_or(NmeaError::MissingDollar)?;
+    let (body, sum) = rest.rsplit_once('*').ok_or(NmeaError::MissingChecksum)?;
+    let sum = sum.trim_end();
+    if sum.len() != 2 {
+        return Err(NmeaError::MissingChecksum);
+    }
+    let expected = u8::from_str_radix(sum, 16).map_err(|_| NmeaError::MissingChecksum)?;
+    let computed = checksum(body);
+    if expected != computed {
+        return Err(NmeaError::BadChecksum { expected, computed });
+    }
+    Ok(body)
+}
+
+pub fn parse_sentence(sentence: &str) -> Result<Sentence, NmeaError> {
+    let body = verify(sentence.trim())?;
+    let fields: Vec<&str> = body.split(',').collect();
+
+    // The first field is a two-letter talker id (GP, GN, GL, ...) plus the type
+    let kind = fields[0];
+    if kind.len() != 5 || !kind.is_ascii() {
+        return Err(NmeaError::Unsupported(kind.to_string()));
+    }
+    match &kind[2..] {
+        "GGA" => parse_gga(&fields).map(Sentence::Gga),
+        "RMC" => parse_rmc(&fields).map(Sentence::Rmc),
+        _ => Err(NmeaError::Unsupported(kind.to_string())),
+    }
+}
+
+fn field<'a>(fields: &[&'a str], index: usize, name: &'static str) -> Result<&'a str, NmeaError> {
+    fields
+        .get(index)
+        .copied()
+        .ok_or(NmeaError::MissingField(name))
+}
+
+// Empty fields are legal in NMEA and mean "no data"
+fn optional<T: std::str::FromStr>(value: &str, name: &'static str) -> Result<Option<T>, NmeaError> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value
+        .parse()
+        .map(Some)
+        .map_err(|_| NmeaError::InvalidField(name))
+}
+
+fn parse_time(value: &str) -> Result<Option<Time>, NmeaError> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let invalid = NmeaError::InvalidField("time");
+    if value.len() < 6 || !value.is_ascii() {
+        return Err(invalid);
+    }
+    let hour: u8 = value[0..2].parse().map_err(|_| invalid.clone())?;
+    let minute: u8 = value[2..4].parse().map_err(|_| invalid.clone())?;
+    let second: f32 = value[4..].parse().map_err(|_| invalid.clone())?;
+    if hour > 23 || minute > 59 || second >= 61.0 {
+        return Err(invalid);
+    }
+    Ok(Some(Time {
+        hour,
+        minute,
+        second,
+    }))
+}
+
+fn parse_date(value: &str) -> Result<Option<Date>, NmeaError> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let invalid = NmeaError::InvalidField("date");
+    if value.len() != 6 || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid);
+    }
+    let day: u8 = value[0..2].parse().map_err(|_| invalid.clone())?;
+    let month: u8 = value[2..4].parse().map_err(|_| invalid.clone())?;
+    let yy: u16 = value[4..6].parse().map_err(|_| invalid.clone())?;
+    if !(1..=31).contains(&day) || !(1..=12).contains(&month) {
+        return Err(invalid);
+    }
+    // Two-digit years: pivot at 80 so 1980-2079 round-trip
+    let year = if yy >= 80 { 1900 + yy } else { 2000 + yy };
+    Ok(Some(Date { day, month, year }))
+}
+
+// Convert (d)ddmm.mmmm plus hemisphere into signed decimal degrees
+pub fn to_decimal_degrees(
+    value: &str,
+    hemisphere: &str,
+    degree_digits: usize,
+) -> Result<Option<f64>, NmeaError> {
+    if value.is_empty() && hemisphere.is_empty() {
+        return Ok(None);
+    }
+    let invalid = NmeaError::InvalidField("coordinate");
+    if value.len() < degree_digits + 2 || !value.is_ascii() {
+        return Err(invalid);
+    }
+
+    let degrees: f64 = value[..degree_digits]
+        .parse()
+        .map_err(|_| invalid.clone())?;
+    let minutes: f64 = value[degree_digits..]
+        .parse()
+        .map_err(|_| invalid.clone())?;
+    if minutes >= 60.0 {
+        return Err(invalid);
+    }
+    let magnitude = degrees + minutes / 60.0;
+
+    match hemisphere {
+        "N" | "E" => Ok(Some(magnitude)),
+        "S" | "W" => Ok(Some(-magnitude)),
+        _ => Err(NmeaError::InvalidField("hemisphere")),
+    }
+}
+
+fn parse_gga(f: &[&str]) -> Result<Gga, NmeaError> {
+    let quality: u8 = field(f, 6, "fix quality")?
+        .parse()
+        .map_err(|_| NmeaError::InvalidField("fix quality"))?;
+
+    Ok(Gga {
+        time: parse_time(field(f, 1, "time")?)?,
+        latitude: to_decimal_degrees(field(f, 2, "latitude")?, field(f, 3, "N/S")?, 2)?,
+        longitude: to_decimal_degrees(field(f, 4, "longitude")?, field(f, 5, "E/W")?, 3)?,
+        fix_quality: FixQuality::from_digit(quality),
+        satellites: optional(field(f, 7, "satellites")?, "satellites")?.unwrap_or(0),
+        hdop: optional(field(f, 8, "hdop")?, "hdop")?,
+        altitude_m: optional(field(f, 9, "altitude")?, "altitude")?,
+    })
+}
+
+fn parse_rmc(f: &[&str]) -> Result<Rmc, NmeaError> {
+    let active = match field(f, 2, "status")? {
+        "A" => true,
+        "V" => false,
+        _ => return Err(NmeaError::InvalidField("status")),
+    };
+
+    Ok(Rmc {
+        time: parse_time(field(f, 1, "time")?)?,
+        active,
+        latitude: to_decimal_degrees(field(f, 3, "latitude")?, field(f, 4, "N/S")?, 2)?,
+        longitude: to_decimal_degrees(field(f, 5, "longitude")?, field(f, 6, "E/W")?, 3)?,
+        speed_knots: optional(field(f, 7, "speed")?, "speed")?,
+        course_deg: optional(field(f, 8, "course")?, "course")?,
+        date: parse_date(field(f, 9, "date")?)?,
+    })
+}
@@ -0,0 +1,124 @@
+use gps::{parse_sentence, to_decimal_degrees, NmeaReader, Sentence};
+
+fn main() {
+    println!("=== NMEA 0183 GPS Parsing ===\n");
+
+    // 1. Parsing a GGA sentence
+    println!("1. GGA (fix data):");
+    let gga = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+    match parse_sentence(gga) {
+        Ok(Sentence::Gga(fix)) => {
+            println!("   time: {:?}", fix.time);
+            println!("   position: {:?}, {:?}", fix.latitude, fix.longitude);
+            println!(
+                "   quality: {:?} (has fix: {})",
+                fix.fix_quality,
+                fix.fix_quality.has_fix()
+            );
+            println!(
+                "   satellites: {}, hdop: {:?}, altitude: {:?} m",
+                fix.satellites, fix.hdop, fix.altitude_m
+            );
+        }
+        other => println!("   unexpected: {:?}", other),
+    }
+
+    // 2. Parsing an RMC sentence
+    println!("\n2. RMC (recommended minimum):");
+    let rmc = "$GPRMC,225446,A,4916.45,N,12311.12,W,000.5,054.7,191194,020.3,E*68";
+    if let Ok(Sentence::Rmc(rec)) = parse_sentence(rmc) {
+        println!("   active: {}", rec.active);
+        println!(
+            "   position: {:.5}, {:.5}",
+            rec.latitude.unwrap(),
+            rec.longitude.unwrap()
+        );
+        println!(
+            "   speed: {:?} kn, course: {:?} deg",
+            rec.speed_knots, rec.course_deg
+        );
+        println!("   date: {:?}", rec.date);
+    }
+
+    // 3. Coordinate conversion
+    println!("\n3. ddmm.mmmm to decimal degrees:");
+    for (value, hemi, digits) in [
+        ("4807.038", "N", 2),
+        ("01131.000", "E", 3),
+        ("3751.65", "S", 2),
+    ] {
+        println!(
+            "   {} {} -> {:?}",
+            value,
+            hemi,
+            to_decimal_degrees(value, hemi, digits)
+        );
+    }
+
+    // 4. Sentences without a fix
+    println!("\n4. No fix yet:");
+    let empty = "$GPGGA,235959,,,,,0,00,,,M,,M,,*67";
+    if let Ok(Sentence::Gga(fix)) = parse_sentence(empty) {
+        println!(
+            "   quality: {:?}, position: {:?}",
+            fix.fix_quality, fix.latitude
+        );
+    }
+    let void = "$GPRMC,081836,V,3751.65,S,14507.36,E,000.0,360.0,130998,011.3,E*75";
+    if let Ok(Sentence::Rmc(rec)) = parse_sentence(void) {
+        println!("   RMC status active: {}", rec.active);
+    }
+
+    // 5. Other talkers (GNSS receivers combining GPS + GLONASS)
+    println!("\n5. GN talker id:");
+    let gn = "$GNGGA,001043.00,4404.14036,N,12118.85961,W,1,12,0.98,1113.0,M,-21.3,M,,*47";
+    if let Ok(Sentence::Gga(fix)) = parse_sentence(gn) {
+        println!(
+            "   {:.6}, {:.6} with {} satellites",
+            fix.latitude.unwrap(),
+            fix.longitude.unwrap(),
+            fix.satellites
+        );
+    }
+
+    // 6. Malformed input
+    println!("\n6. Malformed sentences:");
+    let bad = [
+        "GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47",
+        "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,",
+        "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*48",
+        "$GPGSV,3,1,11,03,03,111,00,04,15,270,00,06,01,010,00,13,06,292,00*74",
+        "$GPRMC,225446,X,4916.45,N,12311.12,W,000.5,054.7,191194,020.3,E*71",
+        "$GPGGA,123519*77",
+    ];
+    for s in bad {
+        println!("   {}", parse_sentence(s).unwrap_err());
+    }
+
+    // 7. Streaming: partial lines across reads
+    println!("\n7. Streaming reader:");
+    let stream = format!("noise{}\r\n{}\r\n\r\n", gga, rmc);
+    let mut reader = NmeaReader::new();
+    for chunk in stream.as_bytes().chunks(20) {
+        for result in reader.push(chunk) {
+            match result {
+                Ok(Sentence::Gga(_)) => println!("   got GGA"),
+                Ok(Sentence::Rmc(_)) => println!("   got RMC"),
+                Err(e) => println!("   error: {}", e),
+            }
+        }
+    }
+    println!("   bytes pending: {}", reader.pending());
+
+    // 8. Streaming: runaway line without a newline
+    println!("\n8. Overlong line:");
+    let mut reader = NmeaReader::new();
+    let mut junk = vec![b'$'];
+    junk.extend(std::iter::repeat_n(b'A', 200));
+    junk.push(b'\n');
+    for result in reader.push(&junk) {
+        println!("   {}", result.unwrap_err());
+    }
+
+    println!("\n=== End of GPS Examples ===");
+}
@@ -0,0 +1,64 @@
+use crate::{parse_sentence, NmeaError, Sentence};
+
+// NMEA 0183 limits a sentence to 82 characters including "$" and "\r\n"
+const MAX_LINE: usize = 82;
+
+// Assembles sentences from arbitrary chunks of serial data
+#[derive(Debug, Default)]
+pub struct NmeaReader {
+    line: Vec<u8>,
+    overflowed: bool,
+}
+
+impl NmeaReader {
+    pub fn new() -> NmeaReader {
+        NmeaReader::default()
+    }
+
+    // Bytes of the sentence currently being assembled
+    pub fn pending(&self) -> usize {
+        self.line.len()
+    }
+
+    // Feed a chunk; returns one result per completed line
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<Result<Sentence, NmeaError>> {
+        let mut results = Vec::new();
+
+        for &b in bytes {
+            match b {
+                b'\n' => {
+                    if let Some(result) = self.finish_line() {
+                        results.push(result);
+                    }
+                }
+                // A '$' always starts a new sentence, even mid-line
+                b'$' => {
+                    self.line.clear();
+                    self.overflowed = false;
+                    self.line.push(b);
+                }
+                _ if self.overflowed => {}
+                _ if self.line.len() >= MAX_LINE => {
+                    self.overflowed = true;
+                }
+                _ => self.line.push(b),
+            }
+        }
+
+        results
+    }
+
+    fn finish_line(&mut self) -> Option<Result<Sentence, NmeaError>> {
+        let line = std::mem::take(&mut self.line);
+        if std::mem::take(&mut self.overflowed) {
+            return Some(Err(NmeaError::LineTooLong(line.len())));
+        }
+
+        let text = String::from_utf8_lossy(&line);
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        Some(parse_sentence(text))
+    }
+}
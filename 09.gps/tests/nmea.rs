@@ -0,0 +1,282 @@
+use gps::{
+    checksum, parse_sentence, to_decimal_degrees, verify, Date, FixQuality, Gga, NmeaError,
+    NmeaReader, Rmc, Sentence, Time,
+};
+
+const GGA: &str = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+const RMC: &str = "$GPRMC,225446,A,4916.45,N,12311.12,W,000.5,054.7,191194,020.3,E*68";
+
+// "$BODY*HH" with a correct checksum
+fn sentence(body: &str) -> String {
+    format!("${}*{:02X}", body, checksum(body))
+}
+
+fn close(a: Option<f64>, b: f64) -> bool {
+    a.is_some_and(|a| (a - b).abs() < 1e-9)
+}
+
+#[test]
+fn gga_fields() {
+    let Sentence::Gga(fix) = parse_sentence(GGA).unwrap() else {
+        panic!("not a GGA");
+    };
+    assert_eq!(
+        fix.time,
+        Some(Time {
+            hour: 12,
+            minute: 35,
+            second: 19.0
+        })
+    );
+    assert!(close(fix.latitude, 48.0 + 7.038 / 60.0));
+    assert!(close(fix.longitude, 11.0 + 31.0 / 60.0));
+    assert_eq!(fix.fix_quality, FixQuality::Gps);
+    assert!(fix.fix_quality.has_fix());
+    assert_eq!(fix.satellites, 8);
+    assert_eq!(fix.hdop, Some(0.9));
+    assert_eq!(fix.altitude_m, Some(545.4));
+}
+
+#[test]
+fn rmc_fields() {
+    let Sentence::Rmc(rec) = parse_sentence(RMC).unwrap() else {
+        panic!("not an RMC");
+    };
+    assert!(rec.active);
+    assert!(close(rec.latitude, 49.0 + 16.45 / 60.0));
+    assert!(close(rec.longitude, -(123.0 + 11.12 / 60.0)));
+    assert_eq!(rec.speed_knots, Some(0.5));
+    assert_eq!(rec.course_deg, Some(54.7));
+    assert_eq!(
+        rec.date,
+        Some(Date {
+            day: 19,
+            month: 11,
+            year: 1994
+        })
+    );
+}
+
+#[test]
+fn empty_fields_mean_no_data() {
+    let no_fix = parse_sentence("$GPGGA,235959,,,,,0,00,,,M,,M,,*67").unwrap();
+    assert_eq!(
+        no_fix,
+        Sentence::Gga(Gga {
+            time: Some(Time {
+                hour: 23,
+                minute: 59,
+                second: 59.0
+            }),
+            latitude: None,
+            longitude: None,
+            fix_quality: FixQuality::Invalid,
+            satellites: 0,
+            hdop: None,
+            altitude_m: None,
+        })
+    );
+    let void = parse_sentence(&sentence("GPRMC,,V,,,,,,,,,")).unwrap();
+    assert_eq!(
+        void,
+        Sentence::Rmc(Rmc {
+            time: None,
+            active: false,
+            latitude: None,
+            longitude: None,
+            speed_knots: None,
+            course_deg: None,
+            date: None,
+        })
+    );
+}
+
+#[test]
+fn other_talkers_and_hemispheres() {
+    let Sentence::Gga(fix) = parse_sentence(
+        "$GNGGA,001043.00,4404.14036,N,12118.85961,W,1,12,0.98,1113.0,M,-21.3,M,,*47",
+    )
+    .unwrap() else {
+        panic!("not a GGA");
+    };
+    assert_eq!(fix.time.unwrap().second, 43.0);
+    assert!(close(fix.longitude, -(121.0 + 18.85961 / 60.0)));
+
+    assert!(close(
+        to_decimal_degrees("3751.65", "S", 2).unwrap(),
+        -(37.0 + 51.65 / 60.0)
+    ));
+    assert!(close(to_decimal_degrees("00000.000", "E", 3).unwrap(), 0.0));
+    assert_eq!(to_decimal_degrees("", "", 2), Ok(None));
+    assert_eq!(
+        to_decimal_degrees("4807.038", "X", 2),
+        Err(NmeaError::InvalidField("hemisphere"))
+    );
+    assert_eq!(
+        to_decimal_degrees("4875.000", "N", 2),
+        Err(NmeaError::InvalidField("coordinate"))
+    );
+    assert_eq!(
+        to_decimal_degrees("48", "N", 2),
+        Err(NmeaError::InvalidField("coordinate"))
+    );
+}
+
+#[test]
+fn malformed_sentences() {
+    let cases: Vec<(String, NmeaError)> = vec![
+        (GGA[1..].to_string(), NmeaError::MissingDollar),
+        (GGA[..GGA.len() - 3].to_string(), NmeaError::MissingChecksum),
+        (format!("{}7", GGA), NmeaError::MissingChecksum),
+        (GGA.replace("*47", "*G7"), NmeaError::MissingChecksum),
+        (
+            GGA.replace("*47", "*48"),
+            NmeaError::BadChecksum {
+                expected: 0x48,
+                computed: 0x47,
+            },
+        ),
+        // One flipped character in the body
+        (
+            GGA.replace("545.4", "545.5"),
+            NmeaError::BadChecksum {
+                expected: 0x47,
+                computed: 0x46,
+            },
+        ),
+        (
+            sentence("GPGSV,3,1,11"),
+            NmeaError::Unsupported("GPGSV".to_string()),
+        ),
+        (sentence("GGA,1"), NmeaError::Unsupported("GGA".to_string())),
+        (
+            sentence("GPGGA,123519"),
+            NmeaError::MissingField("fix quality"),
+        ),
+        (
+            sentence("GPGGA,123519,4807.038,N,01131.000,E,x,08,0.9,545.4,M"),
+            NmeaError::InvalidField("fix quality"),
+        ),
+        (
+            sentence("GPGGA,253519,4807.038,N,01131.000,E,1,08,0.9,545.4,M"),
+            NmeaError::InvalidField("time"),
+        ),
+        (
+            sentence("GPGGA,123519,4807.038,N,01131.000,E,1,eight,0.9,545.4,M"),
+            NmeaError::InvalidField("satellites"),
+        ),
+        (
+            sentence("GPRMC,225446,X,4916.45,N,12311.12,W,000.5,054.7,191194"),
+            NmeaError::InvalidField("status"),
+        ),
+        (
+            sentence("GPRMC,225446,A,4916.45,N,12311.12,W,000.5,054.7,321194"),
+            NmeaError::InvalidField("date"),
+        ),
+        (
+            sentence("GPRMC,225446,A,4916.45,N,12311.12,W,000.5,054.7"),
+            NmeaError::MissingField("date"),
+        ),
+    ];
+    for (line, error) in cases {
+        assert_eq!(parse_sentence(&line), Err(error), "{}", line);
+    }
+}
+
+#[test]
+fn checksum_and_verify() {
+    assert_eq!(checksum(""), 0);
+    let body = &GGA[1..GGA.len() - 3];
+    assert_eq!(checksum(body), 0x47);
+    assert_eq!(verify(GGA), Ok(body));
+    // Lower-case hex and trailing CR/LF are accepted
+    assert_eq!(
+        verify(&format!("{}\r\n", sentence("GPXXX,a"))),
+        Ok("GPXXX,a")
+    );
+    let lower = format!("$GPXXX,h*{:02x}", checksum("GPXXX,h"));
+    assert!(lower.ends_with("*0b"));
+    assert_eq!(verify(&lower), Ok("GPXXX,h"));
+}
+
+// Every chunking of the stream gives the same sentences
+#[test]
+fn reader_assembles_lines_split_at_any_point() {
+    let stream = format!("{}\r\n{}\r\n{}\r\n", GGA, RMC, GGA);
+    let expected = vec![
+        parse_sentence(GGA),
+        parse_sentence(RMC),
+        parse_sentence(GGA),
+    ];
+    for size in 1..=stream.len() {
+        let mut reader = NmeaReader::new();
+        let results: Vec<_> = stream
+            .as_bytes()
+            .chunks(size)
+            .flat_map(|c| reader.push(c))
+            .collect();
+        assert_eq!(results, expected, "chunks of {} bytes", size);
+        assert_eq!(reader.pending(), 0);
+    }
+}
+
+#[test]
+fn reader_keeps_a_partial_line_until_its_newline() {
+    let mut reader = NmeaReader::new();
+    assert!(reader.push(&GGA.as_bytes()[..20]).is_empty());
+    assert_eq!(reader.pending(), 20);
+    assert!(reader.push(&GGA.as_bytes()[20..]).is_empty());
+    assert_eq!(reader.pending(), GGA.len());
+    assert_eq!(reader.push(b"\r\n"), [parse_sentence(GGA)]);
+    assert_eq!(reader.pending(), 0);
+}
+
+#[test]
+fn reader_recovers_from_noise_and_cut_off_sentences() {
+    let mut stream = Vec::new();
+    // Garbage from a baud-rate mismatch, then a blank line
+    stream.extend_from_slice(b"\xFF\x00\x13noise\r\n\r\n");
+    // A sentence cut off by the next '$': the new one starts over
+    stream.extend_from_slice(&GGA.as_bytes()[..30]);
+    stream.extend_from_slice(RMC.as_bytes());
+    stream.extend_from_slice(b"\r\n");
+    // A corrupted sentence is reported, and the next one still parses
+    stream.extend_from_slice(GGA.replace("4807", "4817").as_bytes());
+    stream.extend_from_slice(b"\r\n");
+    stream.extend_from_slice(GGA.as_bytes());
+    stream.extend_from_slice(b"\n");
+
+    let mut reader = NmeaReader::new();
+    let results = reader.push(&stream);
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0], Err(NmeaError::MissingDollar));
+    assert_eq!(results[1], parse_sentence(RMC));
+    assert!(matches!(results[2], Err(NmeaError::BadChecksum { .. })));
+    assert_eq!(results[3], parse_sentence(GGA));
+}
+
+#[test]
+fn reader_rejects_an_overlong_line_and_resyncs_on_the_next() {
+    let mut reader = NmeaReader::new();
+    let long = format!("$GPGGA,{}", "9".repeat(200));
+    let results = reader.push(long.as_bytes());
+    assert!(results.is_empty());
+    // Memory stays bounded while the line runs on
+    assert!(reader.pending() <= 82);
+
+    let mut rest = b"\r\n".to_vec();
+    rest.extend_from_slice(GGA.as_bytes());
+    rest.extend_from_slice(b"\r\n");
+    let results = reader.push(&rest);
+    assert_eq!(
+        results,
+        [Err(NmeaError::LineTooLong(82)), parse_sentence(GGA)]
+    );
+
+    // A '$' mid-way through an overlong line starts a fresh sentence
+    let mut reader = NmeaReader::new();
+    let mut stream = long.into_bytes();
+    stream.extend_from_slice(RMC.as_bytes());
+    stream.extend_from_slice(b"\r\n");
+    assert_eq!(reader.push(&stream), [parse_sentence(RMC)]);
+}
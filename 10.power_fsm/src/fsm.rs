@@ -0,0 +1,71 @@
+// A tiny state-machine framework: states describe their own transitions,
+// `Fsm` drives them and keeps a history of what happened.
+//
+// The `StateMachine` trait and its derive come from 67.enum_derive: a
+// state enum lists its transitions as `(from, on) => to` rules in one
+// `#[transitions(...)]` attribute and the derive generates `next(event)`
+// from them.
+
+pub use enum_derive::{InvalidTransition, StateMachine};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition<S: StateMachine> {
+    pub from: S,
+    pub event: S::Event,
+    pub to: S,
+}
+
+#[derive(Debug)]
+pub struct Fsm<S: StateMachine> {
+    state: S,
+    history: Vec<Transition<S>>,
+}
+
+impl<S: StateMachine> Fsm<S> {
+    pub fn new(initial: S) -> Fsm<S> {
+        Fsm {
+            state: initial,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn state(&self) -> S {
+        self.state
+    }
+
+    pub fn history(&self) -> &[Transition<S>] {
+        &self.history
+    }
+
+    pub fn handle(&mut self, event: S::Event) -> Result<S, InvalidTransition<S>> {
+        let to = self.state.next(&event)?;
+        if to != self.state {
+            self.history.push(Transition {
+                from: self.state,
+                event,
+                to,
+            });
+        }
+        self.state = to;
+        Ok(to)
+    }
+}
+
+// The traffic light from the enum lesson, expressed with the framework
+#[derive(Debug, Clone, Copy, PartialEq, Eq, StateMachine)]
+#[state_machine(event = LightEvent)]
+#[transitions(
+    (Red, TimerExpired) => Green,
+    (Green, TimerExpired) => Yellow,
+    (Yellow, TimerExpired) => Red,
+)]
+pub enum TrafficLight {
+    Red,
+    Yellow,
+    Green,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LightEvent {
+    TimerExpired,
+}
@@ -0,0 +1,5 @@
+pub mod fsm;
+pub mod power;
+
+pub use fsm::{Fsm, InvalidTransition, LightEvent, StateMachine, TrafficLight, Transition};
+pub use power::{run_workload, CurrentProfile, PowerEvent, PowerManager, PowerState, Step};
@@ -0,0 +1,129 @@
+use power_fsm::{
+    run_workload, CurrentProfile, Fsm, LightEvent, PowerEvent, PowerManager, PowerState,
+    StateMachine, Step, TrafficLight,
+};
+
+// One sensing cycle: wake, measure and transmit, then step down to deep sleep
+fn duty_cycle(active: f64, idle: f64, light: f64, deep: f64) -> Vec<Step> {
+    vec![
+        Step::new(active, PowerEvent::TimerExpired),
+        Step::new(idle, PowerEvent::TimerExpired),
+        Step::new(light, PowerEvent::TimerExpired),
+        Step::new(deep, PowerEvent::Activity),
+    ]
+}
+
+fn report(label: &str, manager: &PowerManager, capacity_mah: f64) {
+    println!("   [{}]", label);
+    for state in PowerState::ALL {
+        let secs = manager.seconds_in(state);
+        let share = 100.0 * secs / manager.total_seconds();
+        println!(
+            "     {:<10} {:>8.0} s ({:>5.1} %)",
+            format!("{:?}", state),
+            secs,
+            share
+        );
+    }
+    println!(
+        "     average current: {:.3} mA",
+        manager.average_current_ma()
+    );
+    if let Some(hours) = manager.battery_life_hours(capacity_mah) {
+        println!(
+            "     {} mAh battery lasts {:.0} h ({:.1} days)",
+            capacity_mah,
+            hours,
+            hours / 24.0
+        );
+    }
+}
+
+fn main() {
+    println!("=== Power-Management State Machine ===\n");
+
+    // 1. The framework with a familiar example
+    println!("1. TrafficLight on the generic Fsm:");
+    let mut light = Fsm::new(TrafficLight::Red);
+    for _ in 0..3 {
+        let from = light.state();
+        let to = light.handle(LightEvent::TimerExpired).unwrap();
+        println!("   {:?} -> {:?}", from, to);
+    }
+
+    // 2. Power state transitions
+    println!("\n2. Power transitions:");
+    let mut fsm = Fsm::new(PowerState::Active);
+    let events = [
+        PowerEvent::TimerExpired,
+        PowerEvent::TimerExpired,
+        PowerEvent::Activity,
+        PowerEvent::TimerExpired,
+        PowerEvent::LowBattery,
+    ];
+    for event in events {
+        let from = fsm.state();
+        match fsm.handle(event) {
+            Ok(to) => println!("   {:?} --{:?}--> {:?}", from, event, to),
+            Err(e) => println!("   rejected: {:?}", e),
+        }
+    }
+
+    // 3. Invalid transitions are reported, not ignored
+    println!("\n3. Invalid transition:");
+    match fsm.handle(PowerEvent::TimerExpired) {
+        Ok(state) => println!("   unexpectedly moved to {:?}", state),
+        Err(e) => println!("   {:?} cannot handle {:?} ({})", e.state, e.event, e),
+    }
+
+    // 4. Transition history
+    println!("\n4. History:");
+    for t in fsm.history() {
+        println!("   {:?} -> {:?} on {:?}", t.from, t.to, t.event);
+    }
+
+    // 5. Battery life for a sensor that reports once a minute
+    println!("\n5. Battery life from a simulated day:");
+    let capacity = 2400.0;
+    let cycles_per_day = 24 * 60;
+
+    let mut sleepy = PowerManager::new(CurrentProfile::default());
+    let cycle = duty_cycle(2.0, 3.0, 5.0, 50.0);
+    for _ in 0..cycles_per_day {
+        run_workload(&mut sleepy, &cycle);
+    }
+    report("deep sleep between readings", &sleepy, capacity);
+
+    let mut awake = PowerManager::new(CurrentProfile::default());
+    let cycle = [
+        Step::new(2.0, PowerEvent::TimerExpired),
+        Step::new(58.0, PowerEvent::Activity),
+    ];
+    for _ in 0..cycles_per_day {
+        run_workload(&mut awake, &cycle);
+    }
+    report("idle between readings", &awake, capacity);
+
+    // 6. Low battery forces deep sleep mid-cycle
+    println!("\n6. Low-battery event:");
+    let mut manager = PowerManager::new(CurrentProfile::default());
+    let steps = [
+        Step::new(2.0, PowerEvent::LowBattery),
+        Step::new(600.0, PowerEvent::TimerExpired),
+    ];
+    let rejected = run_workload(&mut manager, &steps);
+    println!(
+        "   state: {:?}, rejected events: {}",
+        manager.state(),
+        rejected
+    );
+    println!("   consumed: {:.4} mAh", manager.consumed_mah());
+
+    // 7. The rules the derive generated `next` from, in the order tried
+    println!("\n7. Transition table:");
+    for rule in PowerState::TRANSITIONS {
+        println!("   {}", rule);
+    }
+
+    println!("\n=== End of Power-Management Examples ===");
+}
@@ -0,0 +1,173 @@
+use crate::fsm::{Fsm, InvalidTransition, StateMachine};
+
+// Rules are tried in order, so the wildcards come first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, StateMachine)]
+#[state_machine(event = PowerEvent)]
+// Low battery overrides everything
+#[transition(from = "_", on = "LowBattery", to = "DeepSleep")]
+#[transitions(
+    // Any activity wakes the device fully
+    (_, Activity) => Active,
+    // Inactivity timers step down one level at a time
+    (Active, TimerExpired) => Idle,
+    (Idle, TimerExpired) => LightSleep,
+    (LightSleep, TimerExpired) => DeepSleep,
+)]
+// Deep sleep has no timer running; only activity can wake it
+pub enum PowerState {
+    Active,
+    Idle,
+    LightSleep,
+    DeepSleep,
+}
+
+impl PowerState {
+    pub const ALL: [PowerState; 4] = [
+        PowerState::Active,
+        PowerState::Idle,
+        PowerState::LightSleep,
+        PowerState::DeepSleep,
+    ];
+
+    fn index(self) -> usize {
+        match self {
+            PowerState::Active => 0,
+            PowerState::Idle => 1,
+            PowerState::LightSleep => 2,
+            PowerState::DeepSleep => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerEvent {
+    Activity,
+    TimerExpired,
+    LowBattery,
+}
+
+// Typical current draw per state for a small MCU + radio, in milliamps
+#[derive(Debug, Clone, Copy)]
+pub struct CurrentProfile {
+    pub active_ma: f64,
+    pub idle_ma: f64,
+    pub light_sleep_ma: f64,
+    pub deep_sleep_ma: f64,
+}
+
+impl Default for CurrentProfile {
+    fn default() -> Self {
+        CurrentProfile {
+            active_ma: 80.0,
+            idle_ma: 15.0,
+            light_sleep_ma: 0.8,
+            deep_sleep_ma: 0.01,
+        }
+    }
+}
+
+impl CurrentProfile {
+    pub fn draw_ma(&self, state: PowerState) -> f64 {
+        match state {
+            PowerState::Active => self.active_ma,
+            PowerState::Idle => self.idle_ma,
+            PowerState::LightSleep => self.light_sleep_ma,
+            PowerState::DeepSleep => self.deep_sleep_ma,
+        }
+    }
+}
+
+// Drives the power FSM and integrates current over time
+#[derive(Debug)]
+pub struct PowerManager {
+    fsm: Fsm<PowerState>,
+    profile: CurrentProfile,
+    seconds_in: [f64; 4],
+}
+
+impl PowerManager {
+    pub fn new(profile: CurrentProfile) -> PowerManager {
+        PowerManager {
+            fsm: Fsm::new(PowerState::Active),
+            profile,
+            seconds_in: [0.0; 4],
+        }
+    }
+
+    pub fn state(&self) -> PowerState {
+        self.fsm.state()
+    }
+
+    pub fn fsm(&self) -> &Fsm<PowerState> {
+        &self.fsm
+    }
+
+    // Spend time in the current state
+    pub fn advance(&mut self, seconds: f64) {
+        self.seconds_in[self.state().index()] += seconds;
+    }
+
+    pub fn handle(
+        &mut self,
+        event: PowerEvent,
+    ) -> Result<PowerState, InvalidTransition<PowerState>> {
+        self.fsm.handle(event)
+    }
+
+    pub fn seconds_in(&self, state: PowerState) -> f64 {
+        self.seconds_in[state.index()]
+    }
+
+    pub fn total_seconds(&self) -> f64 {
+        self.seconds_in.iter().sum()
+    }
+
+    // Charge drawn so far, in milliamp-hours
+    pub fn consumed_mah(&self) -> f64 {
+        PowerState::ALL
+            .iter()
+            .map(|&s| self.profile.draw_ma(s) * self.seconds_in(s) / 3600.0)
+            .sum()
+    }
+
+    pub fn average_current_ma(&self) -> f64 {
+        let total = self.total_seconds();
+        if total == 0.0 {
+            return 0.0;
+        }
+        self.consumed_mah() * 3600.0 / total
+    }
+
+    // Extrapolate the workload seen so far to a full battery
+    pub fn battery_life_hours(&self, capacity_mah: f64) -> Option<f64> {
+        let avg = self.average_current_ma();
+        if avg == 0.0 {
+            return None;
+        }
+        Some(capacity_mah / avg)
+    }
+}
+
+// One step of a simulated workload: stay for `seconds`, then deliver `event`
+#[derive(Debug, Clone, Copy)]
+pub struct Step {
+    pub seconds: f64,
+    pub event: PowerEvent,
+}
+
+impl Step {
+    pub fn new(seconds: f64, event: PowerEvent) -> Step {
+        Step { seconds, event }
+    }
+}
+
+pub fn run_workload(manager: &mut PowerManager, steps: &[Step]) -> usize {
+    let mut rejected = 0;
+    for step in steps {
+        manager.advance(step.seconds);
+        if manager.handle(step.event).is_err() {
+            rejected += 1;
+        }
+    }
+    rejected
+}
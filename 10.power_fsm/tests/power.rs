@@ -0,0 +1,89 @@
+use power_fsm::{run_workload, CurrentProfile, PowerEvent, PowerManager, PowerState, Step};
+
+// Round numbers so the expected figures can be worked out by hand
+const PROFILE: CurrentProfile = CurrentProfile {
+    active_ma: 100.0,
+    idle_ma: 10.0,
+    light_sleep_ma: 1.0,
+    deep_sleep_ma: 0.1,
+};
+
+// One hour: 6 min active, 12 idle, 12 light sleep, 30 deep sleep
+const HOUR: [Step; 4] = [
+    Step {
+        seconds: 360.0,
+        event: PowerEvent::TimerExpired,
+    },
+    Step {
+        seconds: 720.0,
+        event: PowerEvent::TimerExpired,
+    },
+    Step {
+        seconds: 720.0,
+        event: PowerEvent::TimerExpired,
+    },
+    Step {
+        seconds: 1800.0,
+        event: PowerEvent::Activity,
+    },
+];
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "{} != {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn time_is_charged_to_the_state_it_was_spent_in() {
+    let mut manager = PowerManager::new(PROFILE);
+    assert_eq!(run_workload(&mut manager, &HOUR), 0);
+    assert_eq!(manager.state(), PowerState::Active);
+
+    assert_close(manager.seconds_in(PowerState::Active), 360.0);
+    assert_close(manager.seconds_in(PowerState::Idle), 720.0);
+    assert_close(manager.seconds_in(PowerState::LightSleep), 720.0);
+    assert_close(manager.seconds_in(PowerState::DeepSleep), 1800.0);
+    assert_close(manager.total_seconds(), 3600.0);
+}
+
+#[test]
+fn charge_and_battery_life_of_a_fixed_workload() {
+    let mut manager = PowerManager::new(PROFILE);
+    for _ in 0..24 {
+        run_workload(&mut manager, &HOUR);
+    }
+
+    // Per hour: 100 mA * 0.1 h + 10 * 0.2 + 1 * 0.2 + 0.1 * 0.5
+    //         = 10 + 2 + 0.2 + 0.05 = 12.25 mAh
+    assert_close(manager.consumed_mah(), 24.0 * 12.25);
+    assert_close(manager.average_current_ma(), 12.25);
+    // 2450 mAh / 12.25 mA
+    assert_close(manager.battery_life_hours(2450.0).unwrap(), 200.0);
+}
+
+#[test]
+fn low_battery_forces_deep_sleep() {
+    let mut manager = PowerManager::new(PROFILE);
+    let steps = [
+        Step::new(36.0, PowerEvent::LowBattery),
+        // No timer runs in deep sleep: this one is rejected
+        Step::new(3600.0, PowerEvent::TimerExpired),
+    ];
+    assert_eq!(run_workload(&mut manager, &steps), 1);
+    assert_eq!(manager.state(), PowerState::DeepSleep);
+
+    // 100 mA for 0.01 h + 0.1 mA for 1 h
+    assert_close(manager.consumed_mah(), 1.0 + 0.1);
+}
+
+#[test]
+fn no_time_means_no_estimate() {
+    let manager = PowerManager::new(CurrentProfile::default());
+    assert_eq!(manager.consumed_mah(), 0.0);
+    assert_eq!(manager.average_current_ma(), 0.0);
+    assert_eq!(manager.battery_life_hours(2400.0), None);
+}
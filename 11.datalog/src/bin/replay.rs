@@ -0,0 +1,87 @@
+// Decode a data-log directory and print or re-emit its records
+//
+//   cargo run --bin replay -- <dir>          human-readable listing
+//   cargo run --bin replay -- <dir> --csv    CSV on stdout
+//   cargo run --bin replay -- <dir> --raw    valid records re-encoded on stdout
+
+use datalog::{read_dir, Entry};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+enum Format {
+    Text,
+    Csv,
+    Raw,
+}
+
+fn usage() -> ExitCode {
+    eprintln!("usage: replay <dir> [--csv | --raw]");
+    ExitCode::from(2)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (dir, format) = match args.as_slice() {
+        [dir] => (PathBuf::from(dir), Format::Text),
+        [dir, flag] if flag == "--csv" => (PathBuf::from(dir), Format::Csv),
+        [dir, flag] if flag == "--raw" => (PathBuf::from(dir), Format::Raw),
+        _ => return usage(),
+    };
+
+    let entries = match read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("replay: cannot read {}: {}", dir.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut problems = 0;
+
+    if let Format::Csv = format {
+        let _ = writeln!(out, "timestamp_ms,sensor_id,flags,value");
+    }
+
+    for entry in &entries {
+        let result = match (entry, &format) {
+            (Entry::Record(r), Format::Text) => writeln!(
+                out,
+                "{:>12} ms  sensor {:>3}  flags {:04X}  value {}",
+                r.timestamp_ms, r.sensor_id, r.flags, r.value
+            ),
+            (Entry::Record(r), Format::Csv) => writeln!(
+                out,
+                "{},{},{},{}",
+                r.timestamp_ms, r.sensor_id, r.flags, r.value
+            ),
+            (Entry::Record(r), Format::Raw) => out.write_all(&r.encode()),
+            (Entry::Corrupt { file, index, error }, _) => {
+                problems += 1;
+                eprintln!("replay: {} record {}: {}", file.display(), index, error);
+                Ok(())
+            }
+            (Entry::Torn { file, bytes }, _) => {
+                problems += 1;
+                eprintln!("replay: {} ends with {} torn bytes", file.display(), bytes);
+                Ok(())
+            }
+        };
+
+        // A closed pipe (e.g. `| head`) is not an error worth reporting
+        if let Err(e) = result {
+            if e.kind() == io::ErrorKind::BrokenPipe {
+                return ExitCode::SUCCESS;
+            }
+            eprintln!("replay: {}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    if problems > 0 {
+        eprintln!("replay: {} problem(s) found", problems);
+    }
+    ExitCode::SUCCESS
+}
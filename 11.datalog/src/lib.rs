@@ -0,0 +1,310 @@
+// Rotating binary data logger
+//
+// Every record is exactly RECORD_SIZE bytes, little-endian:
+//
+//   +--------------+-----------+-------+-------+-------+
+//   | timestamp ms | sensor id | flags | value | CRC32 |
+//   |     u64      |    u16    |  u16  |  f32  |  u32  |
+//   +--------------+-----------+-------+-------+-------+
+//
+// Records are appended to `log-NNNNNN.bin` files in one directory. When the
+// active file is full a new one is started, and the oldest files are deleted
+// once more than `max_files` exist.
+
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+pub use crc::crc32;
+
+pub const RECORD_SIZE: usize = 20;
+const PAYLOAD_SIZE: usize = RECORD_SIZE - 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Record {
+    pub timestamp_ms: u64,
+    pub sensor_id: u16,
+    pub flags: u16,
+    pub value: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrcMismatch {
+    pub stored: u32,
+    pub computed: u32,
+}
+
+impl fmt::Display for CrcMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "record CRC {:08X} does not match computed {:08X}",
+            self.stored, self.computed
+        )
+    }
+}
+
+impl std::error::Error for CrcMismatch {}
+
+impl Record {
+    pub fn encode(&self) -> [u8; RECORD_SIZE] {
+        let mut buf = [0u8; RECORD_SIZE];
+        buf[0..8].copy_from_slice(&self.timestamp_ms.to_le_bytes());
+        buf[8..10].copy_from_slice(&self.sensor_id.to_le_bytes());
+        buf[10..12].copy_from_slice(&self.flags.to_le_bytes());
+        buf[12..16].copy_from_slice(&self.value.to_le_bytes());
+        let crc = crc32(&buf[..PAYLOAD_SIZE]);
+        buf[16..20].copy_from_slice(&crc.to_le_bytes());
+        buf
+    }
+
+    pub fn decode(buf: &[u8; RECORD_SIZE]) -> Result<Record, CrcMismatch> {
+        let stored = u32::from_le_bytes([buf[16], buf[17], buf[18], buf[19]]);
+        let computed = crc32(&buf[..PAYLOAD_SIZE]);
+        if stored != computed {
+            return Err(CrcMismatch { stored, computed });
+        }
+
+        Ok(Record {
+            timestamp_ms: u64::from_le_bytes(buf[0..8].try_into().unwrap()),
+            sensor_id: u16::from_le_bytes([buf[8], buf[9]]),
+            flags: u16::from_le_bytes([buf[10], buf[11]]),
+            value: f32::from_le_bytes(buf[12..16].try_into().unwrap()),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub dir: PathBuf,
+    pub max_file_bytes: u64,
+    pub max_files: usize,
+}
+
+impl LogConfig {
+    pub fn new(dir: impl Into<PathBuf>) -> LogConfig {
+        LogConfig {
+            dir: dir.into(),
+            max_file_bytes: 64 * 1024,
+            max_files: 8,
+        }
+    }
+
+    fn records_per_file(&self) -> u64 {
+        (self.max_file_bytes / RECORD_SIZE as u64).max(1)
+    }
+}
+
+// What `DataLogger::open` had to repair
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Recovery {
+    pub files_found: usize,
+    pub valid_records: u64,
+    // Bad CRCs before the last record: kept in place, skipped when read
+    pub corrupt_records: u64,
+    pub truncated_bytes: u64,
+}
+
+#[derive(Debug)]
+pub struct DataLogger {
+    config: LogConfig,
+    file: File,
+    seq: u64,
+    records_in_file: u64,
+}
+
+fn file_name(seq: u64) -> String {
+    format!("log-{:06}.bin", seq)
+}
+
+fn parse_seq(path: &Path) -> Option<u64> {
+    let name = path.file_name()?.to_str()?;
+    name.strip_prefix("log-")?
+        .strip_suffix(".bin")?
+        .parse()
+        .ok()
+}
+
+// Sequence numbers of existing log files, oldest first
+pub fn list_files(dir: &Path) -> io::Result<Vec<(u64, PathBuf)>> {
+    let mut files: Vec<(u64, PathBuf)> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter_map(|path| parse_seq(&path).map(|seq| (seq, path)))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+// What a scan of the active file found
+struct Scan {
+    valid: u64,
+    corrupt: u64,
+    // Bytes worth keeping: everything but a torn tail
+    keep: u64,
+    len: u64,
+}
+
+// Only the last write can have been interrupted, so only the bytes after
+// the last whole record and a final record with a bad CRC are torn. A bad
+// CRC earlier in the file is damage to that one record, and the records
+// after it are still good.
+fn scan(path: &Path) -> io::Result<Scan> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+
+    let mut scan = Scan {
+        valid: 0,
+        corrupt: 0,
+        keep: 0,
+        len: bytes.len() as u64,
+    };
+    let mut last_ok = true;
+    for chunk in bytes.chunks_exact(RECORD_SIZE) {
+        last_ok = Record::decode(chunk.try_into().unwrap()).is_ok();
+        if last_ok {
+            scan.valid += 1;
+        } else {
+            scan.corrupt += 1;
+        }
+        scan.keep += RECORD_SIZE as u64;
+    }
+    if !last_ok {
+        scan.corrupt -= 1;
+        scan.keep -= RECORD_SIZE as u64;
+    }
+    Ok(scan)
+}
+
+impl DataLogger {
+    // Open (or create) the log directory, repairing a torn tail if needed
+    pub fn open(config: LogConfig) -> io::Result<(DataLogger, Recovery)> {
+        // Zero would delete the file being written on every rotation
+        if config.max_files == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "max_files must be at least 1",
+            ));
+        }
+        fs::create_dir_all(&config.dir)?;
+        let files = list_files(&config.dir)?;
+        let mut recovery = Recovery {
+            files_found: files.len(),
+            ..Recovery::default()
+        };
+
+        let seq = files.last().map(|(seq, _)| *seq).unwrap_or(0);
+        let path = config.dir.join(file_name(seq));
+
+        let mut records_in_file = 0;
+        if path.exists() {
+            let scan = scan(&path)?;
+            if scan.keep < scan.len {
+                OpenOptions::new()
+                    .write(true)
+                    .open(&path)?
+                    .set_len(scan.keep)?;
+                recovery.truncated_bytes = scan.len - scan.keep;
+            }
+            // Corrupt records still take up their slots in the file
+            records_in_file = scan.valid + scan.corrupt;
+            recovery.valid_records = scan.valid;
+            recovery.corrupt_records = scan.corrupt;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let mut logger = DataLogger {
+            config,
+            file,
+            seq,
+            records_in_file,
+        };
+        if logger.records_in_file >= logger.config.records_per_file() {
+            logger.rotate()?;
+        }
+        Ok((logger, recovery))
+    }
+
+    pub fn current_file(&self) -> PathBuf {
+        self.config.dir.join(file_name(self.seq))
+    }
+
+    pub fn append(&mut self, record: &Record) -> io::Result<()> {
+        self.file.write_all(&record.encode())?;
+        self.records_in_file += 1;
+        if self.records_in_file >= self.config.records_per_file() {
+            self.rotate()?;
+        }
+        Ok(())
+    }
+
+    // Force data to stable storage (expensive on flash; batch your calls)
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.file.sync_data()
+    }
+
+    // Start a new file: when the current one is full, or on a schedule
+    pub fn rotate(&mut self) -> io::Result<()> {
+        self.file.sync_data()?;
+        self.seq += 1;
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.current_file())?;
+        self.records_in_file = 0;
+
+        let files = list_files(&self.config.dir)?;
+        let excess = files.len().saturating_sub(self.config.max_files);
+        for (seq, path) in files.iter().take(excess) {
+            if *seq != self.seq {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// One decoded entry, or the reason it could not be decoded
+#[derive(Debug)]
+pub enum Entry {
+    Record(Record),
+    Corrupt {
+        file: PathBuf,
+        index: u64,
+        error: CrcMismatch,
+    },
+    Torn {
+        file: PathBuf,
+        bytes: usize,
+    },
+}
+
+// Read every file in order; used by the replay tool
+pub fn read_dir(dir: &Path) -> io::Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    for (_, path) in list_files(dir)? {
+        let mut bytes = Vec::new();
+        File::open(&path)?.read_to_end(&mut bytes)?;
+
+        let chunks = bytes.chunks_exact(RECORD_SIZE);
+        let tail = chunks.remainder().len();
+        for (index, chunk) in chunks.enumerate() {
+            match Record::decode(chunk.try_into().unwrap()) {
+                Ok(record) => entries.push(Entry::Record(record)),
+                Err(error) => entries.push(Entry::Corrupt {
+                    file: path.clone(),
+                    index: index as u64,
+                    error,
+                }),
+            }
+        }
+        if tail > 0 {
+            entries.push(Entry::Torn {
+                file: path.clone(),
+                bytes: tail,
+            });
+        }
+    }
+    Ok(entries)
+}
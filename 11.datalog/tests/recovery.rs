@@ -0,0 +1,197 @@
+use datalog::{read_dir, DataLogger, Entry, LogConfig, Record, Recovery, RECORD_SIZE};
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+
+fn reading(i: u64) -> Record {
+    Record {
+        timestamp_ms: 1_700_000_000_000 + i * 1000,
+        sensor_id: (i % 4) as u16,
+        flags: 0,
+        value: 20.0 + i as f32 / 10.0,
+    }
+}
+
+// A fresh directory holding one file of `count` records
+fn logged(name: &str, count: u64) -> (LogConfig, PathBuf) {
+    let dir =
+        std::env::temp_dir().join(format!("rust-sys-datalog-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let config = LogConfig::new(&dir);
+    let (mut logger, _) = DataLogger::open(config.clone()).unwrap();
+    for i in 0..count {
+        logger.append(&reading(i)).unwrap();
+    }
+    logger.sync().unwrap();
+    let file = logger.current_file();
+    (config, file)
+}
+
+// Flip one byte of the value in record `index`
+fn damage(file: &PathBuf, index: u64) {
+    let mut f = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(file)
+        .unwrap();
+    let offset = index * RECORD_SIZE as u64 + 12;
+    f.seek(SeekFrom::Start(offset)).unwrap();
+    let mut byte = [0u8];
+    f.read_exact(&mut byte).unwrap();
+    f.seek(SeekFrom::Start(offset)).unwrap();
+    f.write_all(&[byte[0] ^ 0x5A]).unwrap();
+}
+
+fn records(config: &LogConfig) -> Vec<Record> {
+    read_dir(&config.dir)
+        .unwrap()
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Entry::Record(record) => Some(record),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn a_clean_log_reopens_unchanged() {
+    let (config, file) = logged("clean", 10);
+    let (_, recovery) = DataLogger::open(config.clone()).unwrap();
+    assert_eq!(
+        recovery,
+        Recovery {
+            files_found: 1,
+            valid_records: 10,
+            corrupt_records: 0,
+            truncated_bytes: 0,
+        }
+    );
+    assert_eq!(fs::metadata(&file).unwrap().len(), 10 * RECORD_SIZE as u64);
+    fs::remove_dir_all(&config.dir).unwrap();
+}
+
+#[test]
+fn a_partial_trailing_record_is_truncated() {
+    let (config, file) = logged("torn", 10);
+    let half = &reading(10).encode()[..RECORD_SIZE / 2];
+    OpenOptions::new()
+        .append(true)
+        .open(&file)
+        .unwrap()
+        .write_all(half)
+        .unwrap();
+
+    let (mut logger, recovery) = DataLogger::open(config.clone()).unwrap();
+    assert_eq!(recovery.valid_records, 10);
+    assert_eq!(recovery.truncated_bytes, (RECORD_SIZE / 2) as u64);
+    logger.append(&reading(10)).unwrap();
+    drop(logger);
+
+    assert_eq!(records(&config), (0..11).map(reading).collect::<Vec<_>>());
+    fs::remove_dir_all(&config.dir).unwrap();
+}
+
+#[test]
+fn a_bad_final_record_is_truncated() {
+    let (config, file) = logged("final", 10);
+    damage(&file, 9);
+
+    let (_, recovery) = DataLogger::open(config.clone()).unwrap();
+    assert_eq!(recovery.valid_records, 9);
+    assert_eq!(recovery.corrupt_records, 0);
+    assert_eq!(recovery.truncated_bytes, RECORD_SIZE as u64);
+    assert_eq!(records(&config), (0..9).map(reading).collect::<Vec<_>>());
+    fs::remove_dir_all(&config.dir).unwrap();
+}
+
+#[test]
+fn a_corrupt_middle_record_keeps_the_records_after_it() {
+    let (config, file) = logged("middle", 10);
+    damage(&file, 4);
+
+    let (mut logger, recovery) = DataLogger::open(config.clone()).unwrap();
+    assert_eq!(recovery.valid_records, 9);
+    assert_eq!(recovery.corrupt_records, 1);
+    assert_eq!(recovery.truncated_bytes, 0);
+    assert_eq!(fs::metadata(&file).unwrap().len(), 10 * RECORD_SIZE as u64);
+    logger.append(&reading(10)).unwrap();
+    drop(logger);
+
+    // Reopening again changes nothing, and only record 4 is missing
+    let (_, again) = DataLogger::open(config.clone()).unwrap();
+    assert_eq!(again.valid_records, 10);
+    assert_eq!(again.corrupt_records, 1);
+    assert_eq!(again.truncated_bytes, 0);
+    let expected: Vec<Record> = (0..11).filter(|&i| i != 4).map(reading).collect();
+    assert_eq!(records(&config), expected);
+
+    let corrupt: Vec<u64> = read_dir(&config.dir)
+        .unwrap()
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Entry::Corrupt { index, .. } => Some(index),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(corrupt, [4]);
+    fs::remove_dir_all(&config.dir).unwrap();
+}
+
+#[test]
+fn corrupt_records_count_towards_rotation() {
+    let dir =
+        std::env::temp_dir().join(format!("rust-sys-datalog-rotation-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let config = LogConfig {
+        dir: dir.clone(),
+        max_file_bytes: 5 * RECORD_SIZE as u64,
+        max_files: 4,
+    };
+    let (mut logger, _) = DataLogger::open(config.clone()).unwrap();
+    for i in 0..4 {
+        logger.append(&reading(i)).unwrap();
+    }
+    let file = logger.current_file();
+    drop(logger);
+    damage(&file, 1);
+
+    // Four slots used, one of them damaged: the fifth record fills the file
+    let (mut logger, recovery) = DataLogger::open(config).unwrap();
+    assert_eq!(recovery.corrupt_records, 1);
+    logger.append(&reading(4)).unwrap();
+    assert_ne!(logger.current_file(), file);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn zero_max_files_is_rejected() {
+    let dir = std::env::temp_dir().join(format!("rust-sys-datalog-zero-{}", std::process::id()));
+    let config = LogConfig {
+        max_files: 0,
+        ..LogConfig::new(&dir)
+    };
+    let err = DataLogger::open(config).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn a_single_file_log_keeps_the_active_file() {
+    let dir = std::env::temp_dir().join(format!("rust-sys-datalog-single-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let config = LogConfig {
+        dir: dir.clone(),
+        max_file_bytes: 2 * RECORD_SIZE as u64,
+        max_files: 1,
+    };
+    let (mut logger, _) = DataLogger::open(config.clone()).unwrap();
+    for i in 0..5 {
+        logger.append(&reading(i)).unwrap();
+    }
+    logger.sync().unwrap();
+
+    // Two rotations deleted the full files; the fifth record survives
+    assert!(logger.current_file().exists());
+    assert_eq!(datalog::list_files(&dir).unwrap().len(), 1);
+    assert_eq!(records(&config), [reading(4)]);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,149 @@
+// Time-series downsampling for telemetry uplinks
+//
+// A point is `(timestamp_ms, value)`. Input slices are expected to be sorted
+// by timestamp, which is what a sensor stream naturally produces.
+//
+// - `lttb` keeps the visual shape of a series with a fixed number of points
+// - `aggregate` summarises fixed time buckets as min/max/mean/count
+// - `resample` turns those buckets back into one point per bucket
+
+pub type Point = (u64, f64);
+
+// Largest-Triangle-Three-Buckets (Sveinn Steinarsson, 2013)
+//
+// The first and last points are always kept. The points in between are split
+// into `threshold - 2` buckets, and from each bucket the point forming the
+// largest triangle with the previously selected point and the average of the
+// next bucket is chosen.
+pub fn lttb(data: &[Point], threshold: usize) -> Vec<Point> {
+    if threshold >= data.len() || threshold < 3 {
+        return data.to_vec();
+    }
+
+    let mut sampled = Vec::with_capacity(threshold);
+    let every = (data.len() - 2) as f64 / (threshold - 2) as f64;
+    let mut a = 0;
+    sampled.push(data[0]);
+
+    for i in 0..threshold - 2 {
+        // Average of the next bucket (the last point for the final bucket)
+        let avg_start = ((i + 1) as f64 * every) as usize + 1;
+        let avg_end = (((i + 2) as f64 * every) as usize + 1).min(data.len());
+        let next = &data[avg_start..avg_end];
+        let avg_x = next.iter().map(|p| p.0 as f64).sum::<f64>() / next.len() as f64;
+        let avg_y = next.iter().map(|p| p.1).sum::<f64>() / next.len() as f64;
+
+        // Candidates in the current bucket
+        let start = (i as f64 * every) as usize + 1;
+        let end = ((i + 1) as f64 * every) as usize + 1;
+        let (ax, ay) = (data[a].0 as f64, data[a].1);
+
+        let mut best = start;
+        let mut best_area = -1.0;
+        for (j, p) in data.iter().enumerate().take(end).skip(start) {
+            let area = ((ax - avg_x) * (p.1 - ay) - (ax - p.0 as f64) * (avg_y - ay)).abs() * 0.5;
+            if area > best_area {
+                best_area = area;
+                best = j;
+            }
+        }
+
+        sampled.push(data[best]);
+        a = best;
+    }
+
+    sampled.push(data[data.len() - 1]);
+    sampled
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bucket {
+    pub start: u64,
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    Min,
+    Max,
+    Mean,
+}
+
+impl Bucket {
+    pub fn get(&self, aggregate: Aggregate) -> f64 {
+        match aggregate {
+            Aggregate::Min => self.min,
+            Aggregate::Max => self.max,
+            Aggregate::Mean => self.mean,
+        }
+    }
+}
+
+// Summarise points into buckets aligned to multiples of `width_ms`.
+// Empty buckets are skipped rather than reported with made-up values.
+pub fn aggregate(data: &[Point], width_ms: u64) -> Vec<Bucket> {
+    assert!(width_ms > 0, "bucket width must be non-zero");
+
+    let mut buckets: Vec<Bucket> = Vec::new();
+    let mut sum = 0.0;
+
+    for &(t, v) in data {
+        let start = t - t % width_ms;
+        match buckets.last_mut() {
+            Some(b) if b.start == start => {
+                b.count += 1;
+                b.min = b.min.min(v);
+                b.max = b.max.max(v);
+                sum += v;
+                b.mean = sum / b.count as f64;
+            }
+            _ => {
+                sum = v;
+                buckets.push(Bucket {
+                    start,
+                    count: 1,
+                    min: v,
+                    max: v,
+                    mean: v,
+                });
+            }
+        }
+    }
+    buckets
+}
+
+// One point per non-empty bucket, stamped with the bucket start
+pub fn resample(data: &[Point], width_ms: u64, aggregate_by: Aggregate) -> Vec<Point> {
+    aggregate(data, width_ms)
+        .iter()
+        .map(|b| (b.start, b.get(aggregate_by)))
+        .collect()
+}
+
+// Largest vertical distance between the original series and a reduced one,
+// linearly interpolating the reduced series between its points
+pub fn max_error(original: &[Point], reduced: &[Point]) -> f64 {
+    if original.is_empty() {
+        return 0.0;
+    }
+    assert!(!reduced.is_empty(), "reduced series must not be empty");
+    let mut worst: f64 = 0.0;
+    let mut k = 0;
+    for &(t, v) in original {
+        while k + 2 < reduced.len() && reduced[k + 1].0 <= t {
+            k += 1;
+        }
+        let (t0, v0) = reduced[k];
+        let (t1, v1) = reduced[(k + 1).min(reduced.len() - 1)];
+        let estimate = if t1 == t0 {
+            v0
+        } else {
+            v0 + (v1 - v0) * (t as f64 - t0 as f64) / (t1 as f64 - t0 as f64)
+        };
+        worst = worst.max((v - estimate).abs());
+    }
+    worst
+}
@@ -0,0 +1,118 @@
+use downsample::{aggregate, lttb, max_error, resample, Aggregate, Point};
+
+// Small deterministic series; tests/downsample.rs checks LTTB's result
+// against the original implementation's
+const VALUES: [f64; 20] = [
+    3.0, 5.0, 4.0, 8.0, 15.0, 9.0, 7.0, 6.0, 12.0, 20.0, 18.0, 10.0, 4.0, 2.0, 3.0, 9.0, 11.0,
+    10.0, 6.0, 5.0,
+];
+
+// 1000 temperature samples, one per second, with a short spike at t=612 s
+fn temperature_series() -> Vec<Point> {
+    (0..1000u64)
+        .map(|i| {
+            let t = i as f64;
+            let mut v = 21.0 + 3.0 * (t / 120.0).sin() + 0.3 * (t * 1.7).sin();
+            if (610..615).contains(&i) {
+                v += 8.0;
+            }
+            (i * 1000, v)
+        })
+        .collect()
+}
+
+// Naive decimation for comparison: keep every n-th point
+fn every_nth(data: &[Point], n: usize) -> Vec<Point> {
+    let mut out: Vec<Point> = data.iter().step_by(n).copied().collect();
+    if out.last() != data.last() {
+        out.push(*data.last().unwrap());
+    }
+    out
+}
+
+fn main() {
+    println!("=== Time-Series Downsampling ===\n");
+
+    let small: Vec<Point> = VALUES
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| (i as u64 * 1000, v))
+        .collect();
+
+    // 1. The input series
+    println!("1. Input series ({} points):", small.len());
+    println!("   {:?}", VALUES);
+
+    // 2. LTTB on the small series
+    println!("\n2. LTTB to 7 points:");
+    let reduced = lttb(&small, 7);
+    for (t, v) in &reduced {
+        println!("   t={:>5} ms  v={}", t, v);
+    }
+
+    // 3. LTTB keeps features that naive decimation misses
+    println!("\n3. 1000 points reduced to 50:");
+    let series = temperature_series();
+    let by_lttb = lttb(&series, 50);
+    let by_nth = every_nth(&series, 20);
+    let peak = |data: &[Point]| data.iter().map(|p| p.1).fold(f64::MIN, f64::max);
+    println!("   {:<20} peak {:.2}", "original:", peak(&series));
+    println!(
+        "   {:<20} peak {:.2}, max error {:.2}",
+        format!("LTTB ({} pts):", by_lttb.len()),
+        peak(&by_lttb),
+        max_error(&series, &by_lttb)
+    );
+    println!(
+        "   {:<20} peak {:.2}, max error {:.2}",
+        format!("every 20th ({} pts):", by_nth.len()),
+        peak(&by_nth),
+        max_error(&series, &by_nth)
+    );
+
+    // 4. Bucket aggregation
+    println!("\n4. One-minute buckets (first 5):");
+    println!(
+        "   {:>8}  {:>5}  {:>6}  {:>6}  {:>6}",
+        "start s", "count", "min", "max", "mean"
+    );
+    for b in aggregate(&series, 60_000).iter().take(5) {
+        println!(
+            "   {:>8}  {:>5}  {:>6.2}  {:>6.2}  {:>6.2}",
+            b.start / 1000,
+            b.count,
+            b.min,
+            b.max,
+            b.mean
+        );
+    }
+
+    // 5. Resampling with different aggregates around the spike
+    println!("\n5. Resampling the spike minute (600-660 s):");
+    let window: Vec<Point> = series
+        .iter()
+        .copied()
+        .filter(|p| (600_000..660_000).contains(&p.0))
+        .collect();
+    for aggregate_by in [Aggregate::Min, Aggregate::Max, Aggregate::Mean] {
+        let points = resample(&window, 60_000, aggregate_by);
+        println!(
+            "   {:<5} -> {:.2}",
+            format!("{:?}", aggregate_by),
+            points[0].1
+        );
+    }
+
+    // 6. Uplink savings
+    println!("\n6. Uplink size (12 bytes per point):");
+    let minutes = resample(&series, 60_000, Aggregate::Mean);
+    for (name, count) in [
+        ("raw", series.len()),
+        ("LTTB 50", by_lttb.len()),
+        ("1-min mean", minutes.len()),
+    ] {
+        println!("   {:<10} {:>5} bytes", name, count * 12);
+    }
+
+    println!("\n=== End of Downsampling Examples ===");
+}
@@ -0,0 +1,175 @@
+use downsample::{aggregate, lttb, max_error, resample, Aggregate, Bucket, Point};
+
+fn series(values: &[f64]) -> Vec<Point> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| (i as u64 * 1000, v))
+        .collect()
+}
+
+// 1000 samples, one per second, with a five-second spike at 610 s
+fn temperature_series() -> Vec<Point> {
+    (0..1000u64)
+        .map(|i| {
+            let t = i as f64;
+            let mut v = 21.0 + 3.0 * (t / 120.0).sin() + 0.3 * (t * 1.7).sin();
+            if (610..615).contains(&i) {
+                v += 8.0;
+            }
+            (i * 1000, v)
+        })
+        .collect()
+}
+
+#[test]
+fn lttb_matches_the_reference_implementation() {
+    // Output of the original LTTB implementation for threshold 7
+    let data = series(&[
+        3.0, 5.0, 4.0, 8.0, 15.0, 9.0, 7.0, 6.0, 12.0, 20.0, 18.0, 10.0, 4.0, 2.0, 3.0, 9.0, 11.0,
+        10.0, 6.0, 5.0,
+    ]);
+    assert_eq!(
+        lttb(&data, 7),
+        [
+            (0, 3.0),
+            (3000, 8.0),
+            (7000, 6.0),
+            (9000, 20.0),
+            (13000, 2.0),
+            (16000, 11.0),
+            (19000, 5.0),
+        ]
+    );
+}
+
+#[test]
+fn lttb_keeps_endpoints_and_returns_a_sorted_subset() {
+    let data = temperature_series();
+    for threshold in [3, 4, 10, 50, 333, 999] {
+        let reduced = lttb(&data, threshold);
+        assert_eq!(reduced.len(), threshold);
+        assert_eq!(reduced.first(), data.first());
+        assert_eq!(reduced.last(), data.last());
+        assert!(reduced.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(reduced.iter().all(|p| data.contains(p)));
+    }
+}
+
+#[test]
+fn lttb_passes_small_inputs_through() {
+    let data = series(&[1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(lttb(&data, 4), data);
+    assert_eq!(lttb(&data, 10), data);
+    assert_eq!(lttb(&data, 2), data);
+    assert_eq!(lttb(&data, 0), data);
+    assert_eq!(lttb(&[], 5), []);
+}
+
+#[test]
+fn lttb_keeps_a_spike_that_decimation_misses() {
+    let data = temperature_series();
+    let peak = |points: &[Point]| points.iter().map(|p| p.1).fold(f64::MIN, f64::max);
+    let by_lttb = lttb(&data, 50);
+    let every_20th: Vec<Point> = data.iter().step_by(20).copied().collect();
+
+    assert_eq!(peak(&by_lttb), peak(&data));
+    assert!(peak(&every_20th) < peak(&data) - 2.0);
+    assert!(max_error(&data, &by_lttb) < max_error(&data, &every_20th));
+}
+
+#[test]
+fn buckets_are_aligned_and_summarised() {
+    let data = [
+        (59_000, 1.0),
+        (60_000, 4.0),
+        (61_000, 2.0),
+        (119_999, 6.0),
+        (120_000, 5.0),
+    ];
+    assert_eq!(
+        aggregate(&data, 60_000),
+        [
+            Bucket {
+                start: 0,
+                count: 1,
+                min: 1.0,
+                max: 1.0,
+                mean: 1.0
+            },
+            Bucket {
+                start: 60_000,
+                count: 3,
+                min: 2.0,
+                max: 6.0,
+                mean: 4.0
+            },
+            Bucket {
+                start: 120_000,
+                count: 1,
+                min: 5.0,
+                max: 5.0,
+                mean: 5.0
+            },
+        ]
+    );
+}
+
+#[test]
+fn empty_buckets_are_skipped() {
+    let data = [(1_000, 1.0), (2_000, 3.0), (305_000, 7.0)];
+    let starts: Vec<u64> = aggregate(&data, 60_000).iter().map(|b| b.start).collect();
+    assert_eq!(starts, [0, 300_000]);
+    assert!(aggregate(&[], 60_000).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "bucket width must be non-zero")]
+fn zero_width_buckets_panic() {
+    aggregate(&[(0, 1.0)], 0);
+}
+
+#[test]
+fn resample_picks_the_aggregate() {
+    let data = temperature_series();
+    let spike_minute: Vec<Point> = data
+        .iter()
+        .copied()
+        .filter(|p| (600_000..660_000).contains(&p.0))
+        .collect();
+    let one = |by| resample(&spike_minute, 60_000, by)[0];
+    let (min, max, mean) = (
+        one(Aggregate::Min),
+        one(Aggregate::Max),
+        one(Aggregate::Mean),
+    );
+    assert_eq!((min.0, max.0, mean.0), (600_000, 600_000, 600_000));
+    assert!(min.1 < mean.1 && mean.1 < max.1);
+    let peak = data.iter().map(|p| p.1).fold(f64::MIN, f64::max);
+    assert_eq!(max.1, peak);
+
+    let minutes = resample(&data, 60_000, Aggregate::Mean);
+    assert_eq!(minutes.len(), 17);
+    let total: f64 = aggregate(&data, 60_000)
+        .iter()
+        .map(|b| b.mean * b.count as f64)
+        .sum();
+    let direct: f64 = data.iter().map(|p| p.1).sum();
+    assert!((total - direct).abs() < 1e-6);
+}
+
+#[test]
+fn max_error_interpolates_between_kept_points() {
+    let data = series(&[0.0, 1.0, 2.0, 3.0, 10.0]);
+    assert_eq!(max_error(&data, &data), 0.0);
+    // A straight line from 0 to 10 over 4 s misses 3.0 at t=3 s by 4.5
+    let ends = [data[0], data[4]];
+    assert!((max_error(&data, &ends) - 4.5).abs() < 1e-12);
+    assert_eq!(max_error(&[], &[]), 0.0);
+}
+
+#[test]
+#[should_panic(expected = "reduced series must not be empty")]
+fn max_error_against_nothing_panics() {
+    max_error(&series(&[1.0, 2.0]), &[]);
+}
@@ -0,0 +1,261 @@
+// Threshold and alerting rules over sensor streams
+//
+// Rules are plain data: a condition tree plus how long it must hold before
+// an alert is raised. The engine is fed one reading at a time and emits
+// `AlertEvent::Raised` / `AlertEvent::Cleared` only when a rule changes state.
+
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reading {
+    pub metric: String,
+    pub value: f64,
+    pub timestamp_ms: u64,
+}
+
+impl Reading {
+    pub fn new(metric: &str, value: f64, timestamp_ms: u64) -> Reading {
+        Reading {
+            metric: metric.to_string(),
+            value,
+            timestamp_ms,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparator {
+    Above,
+    Below,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    // True once the metric crosses `threshold`; false again only after it
+    // comes back past `threshold -/+ hysteresis`
+    Threshold {
+        metric: String,
+        comparator: Comparator,
+        threshold: f64,
+        hysteresis: f64,
+    },
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+}
+
+impl Condition {
+    pub fn above(metric: &str, threshold: f64) -> Condition {
+        Condition::Threshold {
+            metric: metric.to_string(),
+            comparator: Comparator::Above,
+            threshold,
+            hysteresis: 0.0,
+        }
+    }
+
+    pub fn below(metric: &str, threshold: f64) -> Condition {
+        Condition::Threshold {
+            metric: metric.to_string(),
+            comparator: Comparator::Below,
+            threshold,
+            hysteresis: 0.0,
+        }
+    }
+
+    // Builder-style: only meaningful on a `Threshold`
+    pub fn with_hysteresis(mut self, value: f64) -> Condition {
+        if let Condition::Threshold { hysteresis, .. } = &mut self {
+            *hysteresis = value;
+        }
+        self
+    }
+
+    fn leaf_count(&self) -> usize {
+        match self {
+            Condition::Threshold { .. } => 1,
+            Condition::All(cs) | Condition::Any(cs) => cs.iter().map(|c| c.leaf_count()).sum(),
+        }
+    }
+
+    // Every leaf is visited (no short-circuit) so each keeps its own
+    // hysteresis state at a stable index
+    fn evaluate(
+        &self,
+        values: &HashMap<String, f64>,
+        leaves: &mut [bool],
+        next: &mut usize,
+    ) -> bool {
+        match self {
+            Condition::Threshold {
+                metric,
+                comparator,
+                threshold,
+                hysteresis,
+            } => {
+                let index = *next;
+                *next += 1;
+                let was_active = leaves[index];
+                if let Some(&v) = values.get(metric) {
+                    leaves[index] = match (comparator, was_active) {
+                        (Comparator::Above, false) => v > *threshold,
+                        (Comparator::Above, true) => v > threshold - hysteresis,
+                        (Comparator::Below, false) => v < *threshold,
+                        (Comparator::Below, true) => v < threshold + hysteresis,
+                    };
+                }
+                leaves[index]
+            }
+            Condition::All(cs) => {
+                let results: Vec<bool> = cs
+                    .iter()
+                    .map(|c| c.evaluate(values, leaves, next))
+                    .collect();
+                results.iter().all(|&b| b)
+            }
+            Condition::Any(cs) => {
+                let results: Vec<bool> = cs
+                    .iter()
+                    .map(|c| c.evaluate(values, leaves, next))
+                    .collect();
+                results.iter().any(|&b| b)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub name: String,
+    pub condition: Condition,
+    // The condition must hold continuously this long before raising
+    pub duration_ms: u64,
+}
+
+impl Rule {
+    pub fn new(name: &str, condition: Condition) -> Rule {
+        Rule {
+            name: name.to_string(),
+            condition,
+            duration_ms: 0,
+        }
+    }
+
+    pub fn for_at_least(mut self, duration_ms: u64) -> Rule {
+        self.duration_ms = duration_ms;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlertEvent {
+    Raised { rule: String, at_ms: u64 },
+    Cleared { rule: String, at_ms: u64 },
+}
+
+impl fmt::Display for AlertEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlertEvent::Raised { rule, at_ms } => write!(f, "RAISED  {} at {} ms", rule, at_ms),
+            AlertEvent::Cleared { rule, at_ms } => write!(f, "CLEARED {} at {} ms", rule, at_ms),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RuleState {
+    leaves: Vec<bool>,
+    true_since: Option<u64>,
+    raised: bool,
+}
+
+#[derive(Debug)]
+pub struct RuleEngine {
+    rules: Vec<Rule>,
+    states: Vec<RuleState>,
+    values: HashMap<String, f64>,
+}
+
+impl RuleEngine {
+    pub fn new(rules: Vec<Rule>) -> RuleEngine {
+        let states = rules
+            .iter()
+            .map(|r| RuleState {
+                leaves: vec![false; r.condition.leaf_count()],
+                true_since: None,
+                raised: false,
+            })
+            .collect();
+        RuleEngine {
+            rules,
+            states,
+            values: HashMap::new(),
+        }
+    }
+
+    pub fn is_raised(&self, name: &str) -> bool {
+        self.rules
+            .iter()
+            .zip(&self.states)
+            .any(|(r, s)| r.name == name && s.raised)
+    }
+
+    pub fn active(&self) -> Vec<&str> {
+        self.rules
+            .iter()
+            .zip(&self.states)
+            .filter(|(_, s)| s.raised)
+            .map(|(r, _)| r.name.as_str())
+            .collect()
+    }
+
+    // Replaces the rule of the same name, e.g. with a new threshold. A raised
+    // alert stays raised until the new condition stops holding; the
+    // hysteresis state is kept if the condition has the same shape.
+    // Returns false if there is no rule of that name.
+    pub fn update(&mut self, rule: Rule) -> bool {
+        let Some(i) = self.rules.iter().position(|r| r.name == rule.name) else {
+            return false;
+        };
+        let leaves = rule.condition.leaf_count();
+        if self.states[i].leaves.len() != leaves {
+            self.states[i].leaves = vec![false; leaves];
+        }
+        self.rules[i] = rule;
+        true
+    }
+
+    pub fn process(&mut self, reading: &Reading) -> Vec<AlertEvent> {
+        self.values.insert(reading.metric.clone(), reading.value);
+        let now = reading.timestamp_ms;
+        let mut events = Vec::new();
+
+        for (rule, state) in self.rules.iter().zip(self.states.iter_mut()) {
+            let holds = rule
+                .condition
+                .evaluate(&self.values, &mut state.leaves, &mut 0);
+
+            if holds {
+                let since = *state.true_since.get_or_insert(now);
+                // A clock stepped back counts as no time elapsed
+                if !state.raised && now.saturating_sub(since) >= rule.duration_ms {
+                    state.raised = true;
+                    events.push(AlertEvent::Raised {
+                        rule: rule.name.clone(),
+                        at_ms: now,
+                    });
+                }
+            } else {
+                state.true_since = None;
+                if state.raised {
+                    state.raised = false;
+                    events.push(AlertEvent::Cleared {
+                        rule: rule.name.clone(),
+                        at_ms: now,
+                    });
+                }
+            }
+        }
+        events
+    }
+}
@@ -0,0 +1,113 @@
+use rules::{AlertEvent, Condition, Reading, Rule, RuleEngine};
+
+// Temperature hovering around 30 °C with measurement noise
+const NOISY: [f64; 12] = [
+    29.6, 30.2, 29.9, 30.4, 29.8, 30.1, 29.7, 30.3, 31.5, 32.0, 29.2, 28.4,
+];
+
+fn feed(engine: &mut RuleEngine, metric: &str, values: &[f64]) -> Vec<AlertEvent> {
+    values
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &v)| engine.process(&Reading::new(metric, v, i as u64 * 1000)))
+        .collect()
+}
+
+fn describe(events: &[AlertEvent]) -> String {
+    let parts: Vec<String> = events.iter().map(|e| e.to_string()).collect();
+    parts.join(", ")
+}
+
+fn main() {
+    println!("=== Threshold and Alerting Rules ===\n");
+
+    // 1. A plain threshold
+    println!("1. Plain threshold (temp > 30):");
+    let mut engine = RuleEngine::new(vec![Rule::new("hot", Condition::above("temp", 30.0))]);
+    let events = feed(&mut engine, "temp", &NOISY);
+    for event in events.iter().take(4) {
+        println!("   {}", event);
+    }
+    println!("   ... {} events in total", events.len());
+
+    // 2. Hysteresis suppresses flapping
+    println!("\n2. With 1.0 °C hysteresis:");
+    let mut engine = RuleEngine::new(vec![Rule::new(
+        "hot",
+        Condition::above("temp", 30.0).with_hysteresis(1.0),
+    )]);
+    for event in feed(&mut engine, "temp", &NOISY) {
+        println!("   {}", event);
+    }
+
+    // 3. Duration: ignore short excursions
+    println!("\n3. Must hold for 3 s:");
+    let mut engine = RuleEngine::new(vec![
+        Rule::new("overheat", Condition::above("temp", 31.0)).for_at_least(3000)
+    ]);
+    let spike = [25.0, 35.0, 26.0, 32.0, 33.0, 34.0, 34.5, 30.0];
+    for (i, &v) in spike.iter().enumerate() {
+        let events = engine.process(&Reading::new("temp", v, i as u64 * 1000));
+        println!("   t={}s temp={:<5} {}", i, v, describe(&events));
+    }
+
+    // 4. AND of conditions across metrics
+    println!("\n4. AND: hot while the fan is off:");
+    let mut engine = RuleEngine::new(vec![Rule::new(
+        "cooling-failure",
+        Condition::All(vec![
+            Condition::above("temp", 40.0),
+            Condition::below("fan_rpm", 100.0),
+        ]),
+    )]);
+    let readings = [
+        Reading::new("temp", 45.0, 0),
+        Reading::new("fan_rpm", 1200.0, 1000),
+        Reading::new("fan_rpm", 0.0, 2000),
+        Reading::new("fan_rpm", 900.0, 3000),
+    ];
+    for reading in &readings {
+        let events = engine.process(reading);
+        let label = format!("{}={}", reading.metric, reading.value);
+        println!("   {:<13} {}", label, describe(&events));
+    }
+
+    // 5. OR of conditions
+    println!("\n5. OR: battery low or voltage sagging:");
+    let mut engine = RuleEngine::new(vec![Rule::new(
+        "power",
+        Condition::Any(vec![
+            Condition::below("battery_pct", 15.0),
+            Condition::below("vbus", 4.5).with_hysteresis(0.1),
+        ]),
+    )]);
+    let readings = [
+        Reading::new("battery_pct", 80.0, 0),
+        Reading::new("vbus", 4.4, 1000),
+        Reading::new("vbus", 4.55, 2000),
+        Reading::new("vbus", 4.7, 3000),
+    ];
+    for reading in &readings {
+        let events = engine.process(reading);
+        let label = format!("{}={}", reading.metric, reading.value);
+        println!(
+            "   {:<15} raised={:<5} {}",
+            label,
+            engine.is_raised("power"),
+            describe(&events)
+        );
+    }
+
+    // 6. Several rules at once
+    println!("\n6. Several rules:");
+    let mut engine = RuleEngine::new(vec![
+        Rule::new("hot", Condition::above("temp", 30.0).with_hysteresis(1.0)),
+        Rule::new("cold", Condition::below("temp", 5.0).with_hysteresis(1.0)),
+        Rule::new("humid", Condition::above("humidity", 80.0)),
+    ]);
+    engine.process(&Reading::new("temp", 33.0, 0));
+    engine.process(&Reading::new("humidity", 85.0, 0));
+    println!("   active: {:?}", engine.active());
+
+    println!("\n=== End of Rules Examples ===");
+}
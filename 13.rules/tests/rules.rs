@@ -0,0 +1,240 @@
+use rules::{AlertEvent, Condition, Reading, Rule, RuleEngine};
+
+// Temperature hovering around 30 °C with measurement noise
+const NOISY: [f64; 12] = [
+    29.6, 30.2, 29.9, 30.4, 29.8, 30.1, 29.7, 30.3, 31.5, 32.0, 29.2, 28.4,
+];
+
+fn feed(engine: &mut RuleEngine, metric: &str, values: &[f64]) -> Vec<AlertEvent> {
+    values
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &v)| engine.process(&Reading::new(metric, v, i as u64 * 1000)))
+        .collect()
+}
+
+fn raised(rule: &str, at_ms: u64) -> AlertEvent {
+    AlertEvent::Raised {
+        rule: rule.to_string(),
+        at_ms,
+    }
+}
+
+fn cleared(rule: &str, at_ms: u64) -> AlertEvent {
+    AlertEvent::Cleared {
+        rule: rule.to_string(),
+        at_ms,
+    }
+}
+
+#[test]
+fn plain_threshold_flaps_on_noise() {
+    let mut engine = RuleEngine::new(vec![Rule::new("hot", Condition::above("temp", 30.0))]);
+    let events = feed(&mut engine, "temp", &NOISY);
+    assert_eq!(
+        events,
+        vec![
+            raised("hot", 1000),
+            cleared("hot", 2000),
+            raised("hot", 3000),
+            cleared("hot", 4000),
+            raised("hot", 5000),
+            cleared("hot", 6000),
+            raised("hot", 7000),
+            cleared("hot", 10000),
+        ]
+    );
+}
+
+#[test]
+fn hysteresis_gives_one_raise_and_one_clear() {
+    let mut engine = RuleEngine::new(vec![Rule::new(
+        "hot",
+        Condition::above("temp", 30.0).with_hysteresis(1.0),
+    )]);
+    let events = feed(&mut engine, "temp", &NOISY);
+    // 29.2 is still above 30 - 1, only 28.4 clears
+    assert_eq!(events, vec![raised("hot", 1000), cleared("hot", 11000)]);
+}
+
+#[test]
+fn below_hysteresis_clears_above_threshold_plus_band() {
+    let mut engine = RuleEngine::new(vec![Rule::new(
+        "cold",
+        Condition::below("temp", 5.0).with_hysteresis(1.0),
+    )]);
+    assert_eq!(
+        feed(&mut engine, "temp", &[6.0, 4.5, 5.5, 5.9, 6.1]),
+        vec![raised("cold", 1000), cleared("cold", 4000)]
+    );
+}
+
+#[test]
+fn equal_to_threshold_does_not_raise() {
+    let mut engine = RuleEngine::new(vec![
+        Rule::new("hot", Condition::above("temp", 30.0)),
+        Rule::new("cold", Condition::below("temp", 30.0)),
+    ]);
+    assert!(feed(&mut engine, "temp", &[30.0]).is_empty());
+    assert!(engine.active().is_empty());
+}
+
+#[test]
+fn duration_ignores_short_excursions() {
+    let mut engine = RuleEngine::new(vec![
+        Rule::new("overheat", Condition::above("temp", 31.0)).for_at_least(3000)
+    ]);
+    let spike = [25.0, 35.0, 26.0, 32.0, 33.0, 34.0, 34.5, 30.0];
+    let events = feed(&mut engine, "temp", &spike);
+    // The spike at 1 s resets at 2 s; the run from 3 s raises at 6 s
+    assert_eq!(
+        events,
+        vec![raised("overheat", 6000), cleared("overheat", 7000)]
+    );
+}
+
+#[test]
+fn short_excursion_under_duration_emits_nothing() {
+    let mut engine = RuleEngine::new(vec![
+        Rule::new("overheat", Condition::above("temp", 31.0)).for_at_least(3000)
+    ]);
+    assert!(feed(&mut engine, "temp", &[35.0, 35.0, 35.0, 20.0]).is_empty());
+    assert!(!engine.is_raised("overheat"));
+}
+
+#[test]
+fn timestamps_going_backwards_do_not_panic() {
+    let mut engine = RuleEngine::new(vec![
+        Rule::new("hot", Condition::above("temp", 30.0)).for_at_least(10)
+    ]);
+    // A clock step back (NTP) while the condition holds
+    assert!(engine.process(&Reading::new("temp", 35.0, 1000)).is_empty());
+    assert!(engine.process(&Reading::new("temp", 35.0, 500)).is_empty());
+    // The duration still counts from when the condition became true
+    assert!(engine.process(&Reading::new("temp", 35.0, 1005)).is_empty());
+    assert_eq!(
+        engine.process(&Reading::new("temp", 35.0, 1010)),
+        vec![raised("hot", 1010)]
+    );
+}
+
+#[test]
+fn all_needs_every_metric() {
+    let mut engine = RuleEngine::new(vec![Rule::new(
+        "cooling-failure",
+        Condition::All(vec![
+            Condition::above("temp", 40.0),
+            Condition::below("fan_rpm", 100.0),
+        ]),
+    )]);
+    // No fan reading yet: that leaf is false
+    assert!(engine.process(&Reading::new("temp", 45.0, 0)).is_empty());
+    assert!(engine
+        .process(&Reading::new("fan_rpm", 1200.0, 1000))
+        .is_empty());
+    assert_eq!(
+        engine.process(&Reading::new("fan_rpm", 0.0, 2000)),
+        vec![raised("cooling-failure", 2000)]
+    );
+    assert_eq!(
+        engine.process(&Reading::new("fan_rpm", 900.0, 3000)),
+        vec![cleared("cooling-failure", 3000)]
+    );
+}
+
+#[test]
+fn any_keeps_hysteresis_per_leaf() {
+    let mut engine = RuleEngine::new(vec![Rule::new(
+        "power",
+        Condition::Any(vec![
+            Condition::below("battery_pct", 15.0),
+            Condition::below("vbus", 4.5).with_hysteresis(0.1),
+        ]),
+    )]);
+    assert!(engine
+        .process(&Reading::new("battery_pct", 80.0, 0))
+        .is_empty());
+    assert_eq!(
+        engine.process(&Reading::new("vbus", 4.4, 1000)),
+        vec![raised("power", 1000)]
+    );
+    // Inside the band: stays raised
+    assert!(engine.process(&Reading::new("vbus", 4.55, 2000)).is_empty());
+    assert!(engine.is_raised("power"));
+    assert_eq!(
+        engine.process(&Reading::new("vbus", 4.7, 3000)),
+        vec![cleared("power", 3000)]
+    );
+}
+
+#[test]
+fn other_metrics_do_not_change_a_leaf() {
+    let mut engine = RuleEngine::new(vec![Rule::new("hot", Condition::above("temp", 30.0))]);
+    engine.process(&Reading::new("temp", 33.0, 0));
+    assert!(engine
+        .process(&Reading::new("humidity", 10.0, 1000))
+        .is_empty());
+    assert!(engine.is_raised("hot"));
+}
+
+#[test]
+fn several_rules_report_active_in_rule_order() {
+    let mut engine = RuleEngine::new(vec![
+        Rule::new("hot", Condition::above("temp", 30.0).with_hysteresis(1.0)),
+        Rule::new("cold", Condition::below("temp", 5.0).with_hysteresis(1.0)),
+        Rule::new("humid", Condition::above("humidity", 80.0)),
+    ]);
+    engine.process(&Reading::new("humidity", 85.0, 0));
+    engine.process(&Reading::new("temp", 33.0, 0));
+    assert_eq!(engine.active(), vec!["hot", "humid"]);
+    assert!(!engine.is_raised("cold"));
+    assert!(!engine.is_raised("missing"));
+}
+
+#[test]
+fn update_keeps_a_raised_alert_while_it_holds() {
+    let mut engine = RuleEngine::new(vec![Rule::new("hot", Condition::above("temp", 30.0))]);
+    engine.process(&Reading::new("temp", 33.0, 0));
+
+    assert!(engine.update(Rule::new("hot", Condition::above("temp", 32.0))));
+    assert!(engine.is_raised("hot"));
+    assert!(engine.process(&Reading::new("temp", 33.0, 1000)).is_empty());
+    assert_eq!(
+        engine.process(&Reading::new("temp", 31.0, 2000)),
+        vec![cleared("hot", 2000)]
+    );
+}
+
+#[test]
+fn update_with_a_new_shape_resets_leaf_state() {
+    let mut engine = RuleEngine::new(vec![Rule::new(
+        "hot",
+        Condition::above("temp", 30.0).with_hysteresis(5.0),
+    )]);
+    engine.process(&Reading::new("temp", 33.0, 0));
+
+    // Two leaves now: the old hysteresis state is gone, so 28 isn't held
+    assert!(engine.update(Rule::new(
+        "hot",
+        Condition::Any(vec![
+            Condition::above("temp", 30.0).with_hysteresis(5.0),
+            Condition::above("humidity", 90.0),
+        ]),
+    )));
+    assert_eq!(
+        engine.process(&Reading::new("temp", 28.0, 1000)),
+        vec![cleared("hot", 1000)]
+    );
+}
+
+#[test]
+fn update_of_unknown_rule_is_rejected() {
+    let mut engine = RuleEngine::new(vec![Rule::new("hot", Condition::above("temp", 30.0))]);
+    assert!(!engine.update(Rule::new("cold", Condition::below("temp", 5.0))));
+}
+
+#[test]
+fn events_display_as_lines() {
+    assert_eq!(raised("hot", 1000).to_string(), "RAISED  hot at 1000 ms");
+    assert_eq!(cleared("hot", 2000).to_string(), "CLEARED hot at 2000 ms");
+}
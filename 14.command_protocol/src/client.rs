@@ -0,0 +1,149 @@
+// Host side: sequence numbers, retransmission and outcomes
+//
+// `submit` queues a command; `poll` returns the frames that need to go out
+// now (first transmissions and retries) and gives up on commands whose
+// deadline passed or that ran out of attempts.
+
+use crate::message::{Command, CommandKind, ErrorCode, Message};
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub timeout_ms: u32,
+    pub max_attempts: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            timeout_ms: 200,
+            max_attempts: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Acked {
+        seq: u16,
+        value: u32,
+        attempts: u32,
+    },
+    Nacked {
+        seq: u16,
+        error: ErrorCode,
+        attempts: u32,
+    },
+    TimedOut {
+        seq: u16,
+        attempts: u32,
+    },
+}
+
+#[derive(Debug)]
+struct Pending {
+    command: Command,
+    attempts: u32,
+    last_sent_ms: Option<u32>,
+}
+
+#[derive(Debug)]
+pub struct Client {
+    policy: RetryPolicy,
+    next_seq: u16,
+    pending: Vec<Pending>,
+    outcomes: Vec<Outcome>,
+    pub transmissions: u32,
+    pub stale_responses: u32,
+}
+
+impl Client {
+    pub fn new(policy: RetryPolicy) -> Client {
+        Client {
+            policy,
+            next_seq: 1,
+            pending: Vec::new(),
+            outcomes: Vec::new(),
+            transmissions: 0,
+            stale_responses: 0,
+        }
+    }
+
+    pub fn submit(&mut self, kind: CommandKind, now_ms: u32, ttl_ms: u32) -> u16 {
+        let seq = self.next_seq;
+        self.next_seq = self.next_seq.wrapping_add(1);
+        self.pending.push(Pending {
+            command: Command {
+                seq,
+                deadline_ms: now_ms + ttl_ms,
+                kind,
+            },
+            attempts: 0,
+            last_sent_ms: None,
+        });
+        seq
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn poll(&mut self, now_ms: u32) -> Vec<Vec<u8>> {
+        let policy = self.policy;
+        let mut frames = Vec::new();
+        let mut expired = Vec::new();
+
+        for p in &mut self.pending {
+            let due = match p.last_sent_ms {
+                None => true,
+                Some(t) => now_ms >= t + policy.timeout_ms,
+            };
+            if !due {
+                continue;
+            }
+            if now_ms > p.command.deadline_ms || p.attempts >= policy.max_attempts {
+                expired.push(p.command.seq);
+                continue;
+            }
+            p.attempts += 1;
+            p.last_sent_ms = Some(now_ms);
+            frames.push(Message::Command(p.command).encode());
+        }
+
+        for seq in expired {
+            let attempts = self.remove(seq).map(|p| p.attempts).unwrap_or(0);
+            self.outcomes.push(Outcome::TimedOut { seq, attempts });
+        }
+        self.transmissions += frames.len() as u32;
+        frames
+    }
+
+    fn remove(&mut self, seq: u16) -> Option<Pending> {
+        let index = self.pending.iter().position(|p| p.command.seq == seq)?;
+        Some(self.pending.remove(index))
+    }
+
+    // Match a response to its pending command; late duplicates are counted
+    pub fn handle(&mut self, frame: &[u8]) {
+        let outcome = match Message::decode(frame) {
+            Ok(Message::Ack { seq, value }) => self.remove(seq).map(|p| Outcome::Acked {
+                seq,
+                value,
+                attempts: p.attempts,
+            }),
+            Ok(Message::Nack { seq, error }) => self.remove(seq).map(|p| Outcome::Nacked {
+                seq,
+                error,
+                attempts: p.attempts,
+            }),
+            _ => None,
+        };
+        match outcome {
+            Some(o) => self.outcomes.push(o),
+            None => self.stale_responses += 1,
+        }
+    }
+
+    pub fn take_outcomes(&mut self) -> Vec<Outcome> {
+        std::mem::take(&mut self.outcomes)
+    }
+}
@@ -0,0 +1,104 @@
+// Device side: decode, deduplicate, execute, respond
+//
+// Responses to the last few sequence numbers are cached. A retransmitted
+// command gets the cached response again instead of being executed twice,
+// which matters for anything that is not idempotent (like a reboot).
+
+use crate::message::{Command, CommandKind, ErrorCode, Message};
+use std::collections::VecDeque;
+
+const DEDUP_WINDOW: usize = 32;
+
+#[derive(Debug)]
+pub struct Device {
+    recent: VecDeque<(u16, Message)>,
+    pub interval_secs: u32,
+    pub reboots: u32,
+    pub executed: u32,
+    pub duplicates: u32,
+    pub malformed: u32,
+}
+
+impl Default for Device {
+    fn default() -> Self {
+        Device::new()
+    }
+}
+
+impl Device {
+    pub fn new() -> Device {
+        Device {
+            recent: VecDeque::with_capacity(DEDUP_WINDOW),
+            interval_secs: 60,
+            reboots: 0,
+            executed: 0,
+            duplicates: 0,
+            malformed: 0,
+        }
+    }
+
+    // Handle one received frame; returns the response frame, if any
+    pub fn handle(&mut self, frame: &[u8], now_ms: u32) -> Option<Vec<u8>> {
+        let command = match Message::decode(frame) {
+            Ok(Message::Command(c)) => c,
+            Ok(_) => return None,
+            Err(_) => {
+                self.malformed += 1;
+                // A command with a readable header can still be NACKed
+                if frame.len() >= 3 && frame[0] == 0x01 {
+                    let seq = u16::from_le_bytes([frame[1], frame[2]]);
+                    let nack = Message::Nack {
+                        seq,
+                        error: ErrorCode::UnknownCommand,
+                    };
+                    return Some(nack.encode());
+                }
+                return None;
+            }
+        };
+
+        if let Some((_, cached)) = self.recent.iter().find(|(seq, _)| *seq == command.seq) {
+            self.duplicates += 1;
+            return Some(cached.encode());
+        }
+
+        let response = self.execute(&command, now_ms);
+        if self.recent.len() == DEDUP_WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back((command.seq, response));
+        Some(response.encode())
+    }
+
+    fn execute(&mut self, command: &Command, now_ms: u32) -> Message {
+        let seq = command.seq;
+        if now_ms > command.deadline_ms {
+            return Message::Nack {
+                seq,
+                error: ErrorCode::Expired,
+            };
+        }
+
+        self.executed += 1;
+        let result = match command.kind {
+            CommandKind::Ping => Ok(0),
+            CommandKind::SetInterval(secs) if (1..=86_400).contains(&secs) => {
+                self.interval_secs = secs;
+                Ok(secs)
+            }
+            CommandKind::SetInterval(_) => Err(ErrorCode::InvalidArgument),
+            // Four sensors on this device, reporting tenths of a degree
+            CommandKind::ReadSensor(id) if id < 4 => Ok(215 + id as u32 * 3),
+            CommandKind::ReadSensor(_) => Err(ErrorCode::InvalidArgument),
+            CommandKind::Reboot => {
+                self.reboots += 1;
+                Ok(0)
+            }
+        };
+
+        match result {
+            Ok(value) => Message::Ack { seq, value },
+            Err(error) => Message::Nack { seq, error },
+        }
+    }
+}
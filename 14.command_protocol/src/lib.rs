@@ -0,0 +1,38 @@
+// Device command protocol with ACK/NACK, sequence numbers and deadlines
+//
+// The host (`Client`) sends numbered commands over a lossy link and retries
+// until it receives a response or the command expires. The device (`Device`)
+// executes each sequence number at most once and answers with an ACK
+// carrying a value or a NACK carrying an error code.
+
+mod client;
+mod device;
+mod link;
+mod message;
+
+pub use client::{Client, Outcome, RetryPolicy};
+pub use device::Device;
+pub use link::LossyLink;
+pub use message::{Command, CommandKind, DecodeError, ErrorCode, Message};
+
+// Advance one simulated millisecond step: client -> downlink -> device ->
+// uplink -> client
+pub fn step(
+    client: &mut Client,
+    device: &mut Device,
+    downlink: &mut LossyLink,
+    uplink: &mut LossyLink,
+    now_ms: u32,
+) {
+    for frame in client.poll(now_ms) {
+        downlink.send(now_ms, frame);
+    }
+    while let Some(frame) = downlink.recv(now_ms) {
+        if let Some(response) = device.handle(&frame, now_ms) {
+            uplink.send(now_ms, response);
+        }
+    }
+    while let Some(frame) = uplink.recv(now_ms) {
+        client.handle(&frame);
+    }
+}
@@ -0,0 +1,67 @@
+// Mock radio link: fixed latency, pseudo-random loss
+//
+// The random generator is a seeded xorshift so a demo run is reproducible.
+
+use std::collections::VecDeque;
+
+#[derive(Debug)]
+pub struct LossyLink {
+    latency_ms: u32,
+    loss_percent: u32,
+    forced_drops: u32,
+    rng: u32,
+    in_flight: VecDeque<(u32, Vec<u8>)>,
+    pub sent: u32,
+    pub dropped: u32,
+}
+
+impl LossyLink {
+    pub fn new(latency_ms: u32, loss_percent: u32, seed: u32) -> LossyLink {
+        LossyLink {
+            latency_ms,
+            loss_percent,
+            forced_drops: 0,
+            rng: seed.max(1),
+            in_flight: VecDeque::new(),
+            sent: 0,
+            dropped: 0,
+        }
+    }
+
+    pub fn perfect() -> LossyLink {
+        LossyLink::new(0, 0, 1)
+    }
+
+    // Drop the next `count` frames regardless of the loss rate
+    pub fn drop_next(&mut self, count: u32) {
+        self.forced_drops += count;
+    }
+
+    fn next_random(&mut self) -> u32 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        self.rng
+    }
+
+    pub fn send(&mut self, now_ms: u32, frame: Vec<u8>) {
+        self.sent += 1;
+        if self.forced_drops > 0 {
+            self.forced_drops -= 1;
+            self.dropped += 1;
+            return;
+        }
+        if self.next_random() % 100 < self.loss_percent {
+            self.dropped += 1;
+            return;
+        }
+        self.in_flight.push_back((now_ms + self.latency_ms, frame));
+    }
+
+    pub fn recv(&mut self, now_ms: u32) -> Option<Vec<u8>> {
+        match self.in_flight.front() {
+            Some((due, _)) if *due <= now_ms => self.in_flight.pop_front().map(|(_, f)| f),
+            _ => None,
+        }
+    }
+}
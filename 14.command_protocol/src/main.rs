@@ -0,0 +1,180 @@
+use command_protocol::{
+    step, Client, Command, CommandKind, Device, ErrorCode, LossyLink, Message, Outcome, RetryPolicy,
+};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Run the simulation until nothing is in flight (or a time limit is hit)
+fn run(
+    client: &mut Client,
+    device: &mut Device,
+    downlink: &mut LossyLink,
+    uplink: &mut LossyLink,
+    start_ms: u32,
+) -> u32 {
+    let mut now = start_ms;
+    while client.in_flight() > 0 && now < start_ms + 60_000 {
+        step(client, device, downlink, uplink, now);
+        now += 10;
+    }
+    // Let late responses arrive
+    for _ in 0..100 {
+        step(client, device, downlink, uplink, now);
+        now += 10;
+    }
+    now
+}
+
+fn describe(outcome: &Outcome) -> String {
+    match outcome {
+        Outcome::Acked {
+            seq,
+            value,
+            attempts,
+        } => format!("#{} ACK value={} ({} attempt(s))", seq, value, attempts),
+        Outcome::Nacked {
+            seq,
+            error,
+            attempts,
+        } => format!("#{} NACK {} ({} attempt(s))", seq, error, attempts),
+        Outcome::TimedOut { seq, attempts } => {
+            format!("#{} TIMED OUT ({} attempt(s))", seq, attempts)
+        }
+    }
+}
+
+fn main() {
+    println!("=== Device Command Protocol ===\n");
+
+    // 1. Wire encoding
+    println!("1. Wire encoding:");
+    let messages = [
+        Message::Command(Command {
+            seq: 7,
+            deadline_ms: 5000,
+            kind: CommandKind::SetInterval(30),
+        }),
+        Message::Ack { seq: 7, value: 30 },
+        Message::Nack {
+            seq: 8,
+            error: ErrorCode::InvalidArgument,
+        },
+    ];
+    for message in &messages {
+        let bytes = message.encode();
+        let round_trip = Message::decode(&bytes) == Ok(*message);
+        println!("   {:<38} round trip: {}", hex(&bytes), round_trip);
+    }
+    // The same messages as JSON, for logs and the backend
+    for message in &messages {
+        let json = serde_json::to_string(message).expect("JSON");
+        let round_trip = serde_json::from_str::<Message>(&json).ok() == Some(*message);
+        println!("   {:<84} round trip: {}", json, round_trip);
+    }
+
+    // 2. Perfect link: ACKs and NACKs
+    println!("\n2. Perfect link:");
+    let mut client = Client::new(RetryPolicy::default());
+    let mut device = Device::new();
+    let (mut down, mut up) = (LossyLink::perfect(), LossyLink::perfect());
+    client.submit(CommandKind::Ping, 0, 1000);
+    client.submit(CommandKind::SetInterval(30), 0, 1000);
+    client.submit(CommandKind::SetInterval(0), 0, 1000);
+    client.submit(CommandKind::ReadSensor(2), 0, 1000);
+    client.submit(CommandKind::ReadSensor(9), 0, 1000);
+    run(&mut client, &mut device, &mut down, &mut up, 0);
+    for outcome in client.take_outcomes() {
+        println!("   {}", describe(&outcome));
+    }
+    println!("   device interval now {} s", device.interval_secs);
+
+    // 3. Lossy link with retransmission
+    println!("\n3. 30% loss in both directions, 20 commands:");
+    let mut client = Client::new(RetryPolicy {
+        timeout_ms: 150,
+        max_attempts: 6,
+    });
+    let mut device = Device::new();
+    let mut down = LossyLink::new(40, 30, 0xC0FFEE);
+    let mut up = LossyLink::new(40, 30, 0xBEEF);
+    for i in 0..20 {
+        client.submit(CommandKind::ReadSensor(i % 4), 0, 5000);
+    }
+    run(&mut client, &mut device, &mut down, &mut up, 0);
+    let outcomes = client.take_outcomes();
+    let acked = outcomes
+        .iter()
+        .filter(|o| matches!(o, Outcome::Acked { .. }))
+        .count();
+    println!("   acked:            {}/{}", acked, outcomes.len());
+    println!("   transmissions:    {}", client.transmissions);
+    println!(
+        "   frames dropped:   {} down, {} up",
+        down.dropped, up.dropped
+    );
+    println!("   executed:         {}", device.executed);
+    println!("   duplicates seen:  {}", device.duplicates);
+    println!("   stale responses:  {}", client.stale_responses);
+
+    // 4. Duplicate detection keeps non-idempotent commands safe
+    println!("\n4. Reboot whose ACK is lost twice:");
+    let mut client = Client::new(RetryPolicy::default());
+    let mut device = Device::new();
+    let mut down = LossyLink::perfect();
+    let mut up = LossyLink::perfect();
+    up.drop_next(2);
+    client.submit(CommandKind::Reboot, 0, 2000);
+    run(&mut client, &mut device, &mut down, &mut up, 0);
+    for outcome in client.take_outcomes() {
+        println!("   {}", describe(&outcome));
+    }
+    println!(
+        "   device rebooted {} time(s), answered {} duplicate(s) from cache",
+        device.reboots, device.duplicates
+    );
+
+    // 5. Deadlines
+    println!("\n5. Deadline shorter than the link latency:");
+    let mut client = Client::new(RetryPolicy::default());
+    let mut device = Device::new();
+    let mut down = LossyLink::new(300, 0, 1);
+    let mut up = LossyLink::new(300, 0, 1);
+    client.submit(CommandKind::Ping, 0, 200);
+    run(&mut client, &mut device, &mut down, &mut up, 0);
+    for outcome in client.take_outcomes() {
+        println!("   client: {}", describe(&outcome));
+    }
+    println!(
+        "   device executed {}, late NACK ignored by client: {}",
+        device.executed,
+        client.stale_responses > 0
+    );
+
+    // 6. Malformed frames
+    println!("\n6. Malformed frames:");
+    let mut device = Device::new();
+    let frames: [&[u8]; 3] = [
+        &[],
+        &[0x01, 0x09, 0x00, 0, 0, 0, 0, 0x7F, 0, 0, 0, 0],
+        &[0x55, 0x01],
+    ];
+    for frame in frames {
+        let decoded = Message::decode(frame);
+        let response = device
+            .handle(frame, 0)
+            .map(|r| format!("{:?}", Message::decode(&r).unwrap()))
+            .unwrap_or_else(|| "no response".to_string());
+        match decoded {
+            Ok(m) => println!("   [{}] -> {:?} -> {}", hex(frame), m, response),
+            Err(e) => println!("   [{}] -> {} -> {}", hex(frame), e, response),
+        }
+    }
+
+    println!("\n=== End of Command Protocol Examples ===");
+}
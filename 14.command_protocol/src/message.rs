@@ -0,0 +1,212 @@
+// Wire messages and their binary encoding
+//
+//   Command: [0x01][seq u16][deadline u32][kind u8][arg u32]
+//   Ack:     [0x02][seq u16][value u32]
+//   Nack:    [0x03][seq u16][error u8]
+//
+// All integers are little-endian. Deadlines are milliseconds on the shared
+// clock of the simulation.
+//
+// The same messages also have a JSON form, for logs and for a backend
+// that talks to the gateway rather than to the radio. Every enum names
+// its representation explicitly instead of taking serde's default:
+//
+//   Message      {"type":"ack","seq":7,"value":30}            internally tagged
+//   CommandKind  {"op":"set_interval","arg":30}                adjacently tagged
+//   ErrorCode    "invalid_argument"                            a plain string
+//
+// Field and variant names are part of that format; tests/json.rs pins
+// the JSON of every variant.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Adjacently tagged, so every kind has the same shape whether or not it
+// carries an argument. Serde's default would write `"ping"` for one and
+// `{"set_interval":30}` for another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", content = "arg", rename_all = "snake_case")]
+pub enum CommandKind {
+    Ping,
+    SetInterval(u32),
+    ReadSensor(u8),
+    Reboot,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Command {
+    pub seq: u16,
+    pub deadline_ms: u32,
+    pub kind: CommandKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    UnknownCommand,
+    InvalidArgument,
+    Expired,
+    Busy,
+}
+
+impl ErrorCode {
+    fn from_u8(b: u8) -> Option<ErrorCode> {
+        match b {
+            1 => Some(ErrorCode::UnknownCommand),
+            2 => Some(ErrorCode::InvalidArgument),
+            3 => Some(ErrorCode::Expired),
+            4 => Some(ErrorCode::Busy),
+            _ => None,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            ErrorCode::UnknownCommand => 1,
+            ErrorCode::InvalidArgument => 2,
+            ErrorCode::Expired => 3,
+            ErrorCode::Busy => 4,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorCode::UnknownCommand => write!(f, "unknown command"),
+            ErrorCode::InvalidArgument => write!(f, "invalid argument"),
+            ErrorCode::Expired => write!(f, "deadline expired"),
+            ErrorCode::Busy => write!(f, "device busy"),
+        }
+    }
+}
+
+// Internally tagged: the type is a field next to the others, like the
+// first byte of the binary frame. A `Command`'s fields sit at the top
+// level with it rather than nested one level down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    Command(Command),
+    Ack { seq: u16, value: u32 },
+    Nack { seq: u16, error: ErrorCode },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    Empty,
+    UnknownType(u8),
+    WrongLength { expected: usize, actual: usize },
+    InvalidField,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Empty => write!(f, "empty message"),
+            DecodeError::UnknownType(t) => write!(f, "unknown message type 0x{:02X}", t),
+            DecodeError::WrongLength { expected, actual } => {
+                write!(f, "expected {} bytes, got {}", expected, actual)
+            }
+            DecodeError::InvalidField => write!(f, "invalid field value"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+fn kind_to_wire(kind: CommandKind) -> (u8, u32) {
+    match kind {
+        CommandKind::Ping => (0, 0),
+        CommandKind::SetInterval(secs) => (1, secs),
+        CommandKind::ReadSensor(id) => (2, id as u32),
+        CommandKind::Reboot => (3, 0),
+    }
+}
+
+fn kind_from_wire(code: u8, arg: u32) -> Result<CommandKind, DecodeError> {
+    match code {
+        0 => Ok(CommandKind::Ping),
+        1 => Ok(CommandKind::SetInterval(arg)),
+        2 => u8::try_from(arg)
+            .map(CommandKind::ReadSensor)
+            .map_err(|_| DecodeError::InvalidField),
+        3 => Ok(CommandKind::Reboot),
+        _ => Err(DecodeError::InvalidField),
+    }
+}
+
+fn u16_at(b: &[u8], i: usize) -> u16 {
+    u16::from_le_bytes([b[i], b[i + 1]])
+}
+
+fn u32_at(b: &[u8], i: usize) -> u32 {
+    u32::from_le_bytes([b[i], b[i + 1], b[i + 2], b[i + 3]])
+}
+
+impl Message {
+    pub fn seq(&self) -> u16 {
+        match self {
+            Message::Command(c) => c.seq,
+            Message::Ack { seq, .. } | Message::Nack { seq, .. } => *seq,
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(12);
+        match self {
+            Message::Command(c) => {
+                let (code, arg) = kind_to_wire(c.kind);
+                out.push(0x01);
+                out.extend_from_slice(&c.seq.to_le_bytes());
+                out.extend_from_slice(&c.deadline_ms.to_le_bytes());
+                out.push(code);
+                out.extend_from_slice(&arg.to_le_bytes());
+            }
+            Message::Ack { seq, value } => {
+                out.push(0x02);
+                out.extend_from_slice(&seq.to_le_bytes());
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+            Message::Nack { seq, error } => {
+                out.push(0x03);
+                out.extend_from_slice(&seq.to_le_bytes());
+                out.push(error.to_u8());
+            }
+        }
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Message, DecodeError> {
+        let kind = *bytes.first().ok_or(DecodeError::Empty)?;
+        let expected = match kind {
+            0x01 => 12,
+            0x02 => 7,
+            0x03 => 4,
+            other => return Err(DecodeError::UnknownType(other)),
+        };
+        if bytes.len() != expected {
+            return Err(DecodeError::WrongLength {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+
+        let seq = u16_at(bytes, 1);
+        match kind {
+            0x01 => Ok(Message::Command(Command {
+                seq,
+                deadline_ms: u32_at(bytes, 3),
+                kind: kind_from_wire(bytes[7], u32_at(bytes, 8))?,
+            })),
+            0x02 => Ok(Message::Ack {
+                seq,
+                value: u32_at(bytes, 3),
+            }),
+            _ => Ok(Message::Nack {
+                seq,
+                error: ErrorCode::from_u8(bytes[3]).ok_or(DecodeError::InvalidField)?,
+            }),
+        }
+    }
+}
@@ -0,0 +1,143 @@
+// The JSON form of every message, pinned. A renamed field or variant
+// still compiles and still round-trips through this crate, but a backend
+// built against the old names stops understanding the gateway, so each
+// variant's exact JSON is written out here.
+
+use command_protocol::{Command, CommandKind, ErrorCode, Message};
+use serde_json::json;
+
+fn command(kind: CommandKind) -> Message {
+    Message::Command(Command {
+        seq: 7,
+        deadline_ms: 5000,
+        kind,
+    })
+}
+
+fn nack(error: ErrorCode) -> Message {
+    Message::Nack { seq: 8, error }
+}
+
+// One message per variant of every enum inside `Message`, with its JSON
+fn every_variant() -> Vec<(Message, &'static str)> {
+    vec![
+        (
+            command(CommandKind::Ping),
+            r#"{"type":"command","seq":7,"deadline_ms":5000,"kind":{"op":"ping"}}"#,
+        ),
+        (
+            command(CommandKind::SetInterval(30)),
+            r#"{"type":"command","seq":7,"deadline_ms":5000,"kind":{"op":"set_interval","arg":30}}"#,
+        ),
+        (
+            command(CommandKind::ReadSensor(2)),
+            r#"{"type":"command","seq":7,"deadline_ms":5000,"kind":{"op":"read_sensor","arg":2}}"#,
+        ),
+        (
+            command(CommandKind::Reboot),
+            r#"{"type":"command","seq":7,"deadline_ms":5000,"kind":{"op":"reboot"}}"#,
+        ),
+        (
+            Message::Ack { seq: 7, value: 30 },
+            r#"{"type":"ack","seq":7,"value":30}"#,
+        ),
+        (
+            nack(ErrorCode::UnknownCommand),
+            r#"{"type":"nack","seq":8,"error":"unknown_command"}"#,
+        ),
+        (
+            nack(ErrorCode::InvalidArgument),
+            r#"{"type":"nack","seq":8,"error":"invalid_argument"}"#,
+        ),
+        (
+            nack(ErrorCode::Expired),
+            r#"{"type":"nack","seq":8,"error":"expired"}"#,
+        ),
+        (
+            nack(ErrorCode::Busy),
+            r#"{"type":"nack","seq":8,"error":"busy"}"#,
+        ),
+    ]
+}
+
+#[test]
+fn every_variant_has_its_exact_json() {
+    for (message, expected) in every_variant() {
+        assert_eq!(serde_json::to_string(&message).unwrap(), expected);
+    }
+}
+
+#[test]
+fn every_variant_round_trips() {
+    for (message, json) in every_variant() {
+        assert_eq!(serde_json::from_str::<Message>(json).unwrap(), message);
+        let value = serde_json::to_value(message).unwrap();
+        assert_eq!(serde_json::from_value::<Message>(value).unwrap(), message);
+    }
+}
+
+// `every_variant` is written by hand; these matches stop compiling when
+// a variant is added, which is the reminder to add it there too
+#[test]
+fn the_table_covers_every_variant() {
+    let mut kinds = [false; 4];
+    let mut errors = [false; 4];
+    let mut types = [false; 3];
+    for (message, _) in every_variant() {
+        match message {
+            Message::Command(c) => {
+                types[0] = true;
+                kinds[match c.kind {
+                    CommandKind::Ping => 0,
+                    CommandKind::SetInterval(_) => 1,
+                    CommandKind::ReadSensor(_) => 2,
+                    CommandKind::Reboot => 3,
+                }] = true;
+            }
+            Message::Ack { .. } => types[1] = true,
+            Message::Nack { error, .. } => {
+                types[2] = true;
+                errors[match error {
+                    ErrorCode::UnknownCommand => 0,
+                    ErrorCode::InvalidArgument => 1,
+                    ErrorCode::Expired => 2,
+                    ErrorCode::Busy => 3,
+                }] = true;
+            }
+        }
+    }
+    assert!(kinds.iter().chain(&errors).chain(&types).all(|&seen| seen));
+}
+
+#[test]
+fn field_order_does_not_matter_when_reading() {
+    let message: Message = serde_json::from_value(json!({
+        "kind": { "arg": 30, "op": "set_interval" },
+        "seq": 7,
+        "deadline_ms": 5000,
+        "type": "command",
+    }))
+    .unwrap();
+    assert_eq!(message, command(CommandKind::SetInterval(30)));
+}
+
+#[test]
+fn malformed_messages_are_rejected() {
+    let rejected = [
+        // no tag, or one that isn't a message type
+        r#"{"seq":7,"value":30}"#,
+        r#"{"type":"Ack","seq":7,"value":30}"#,
+        r#"{"type":"reset","seq":7}"#,
+        // a missing field, and a value out of range for its type
+        r#"{"type":"ack","seq":7}"#,
+        r#"{"type":"ack","seq":70000,"value":30}"#,
+        // an argument that doesn't fit, or is missing
+        r#"{"type":"command","seq":7,"deadline_ms":5000,"kind":{"op":"read_sensor","arg":300}}"#,
+        r#"{"type":"command","seq":7,"deadline_ms":5000,"kind":{"op":"set_interval"}}"#,
+        // an error code by number, as on the radio
+        r#"{"type":"nack","seq":8,"error":2}"#,
+    ];
+    for json in rejected {
+        assert!(serde_json::from_str::<Message>(json).is_err(), "{}", json);
+    }
+}
@@ -0,0 +1,357 @@
+// The binary protocol end to end: encoding, the device's dispatcher and
+// duplicate cache, and the client's retries over perfect and lossy links.
+
+use command_protocol::{
+    step, Client, Command, CommandKind, DecodeError, Device, ErrorCode, LossyLink, Message,
+    Outcome, RetryPolicy,
+};
+
+fn command(seq: u16, deadline_ms: u32, kind: CommandKind) -> Message {
+    Message::Command(Command {
+        seq,
+        deadline_ms,
+        kind,
+    })
+}
+
+fn decode(frame: Option<Vec<u8>>) -> Message {
+    Message::decode(&frame.expect("a response")).expect("a valid response")
+}
+
+// Step every 10 ms until nothing is in flight, then a second more for
+// late responses
+fn run(client: &mut Client, device: &mut Device, down: &mut LossyLink, up: &mut LossyLink) {
+    let mut now = 0;
+    while client.in_flight() > 0 && now < 60_000 {
+        step(client, device, down, up, now);
+        now += 10;
+    }
+    for _ in 0..100 {
+        step(client, device, down, up, now);
+        now += 10;
+    }
+}
+
+#[test]
+fn encoding_is_pinned_byte_for_byte() {
+    assert_eq!(
+        command(7, 5000, CommandKind::SetInterval(30)).encode(),
+        [0x01, 0x07, 0x00, 0x88, 0x13, 0x00, 0x00, 0x01, 0x1E, 0x00, 0x00, 0x00]
+    );
+    assert_eq!(
+        Message::Ack { seq: 7, value: 30 }.encode(),
+        [0x02, 0x07, 0x00, 0x1E, 0x00, 0x00, 0x00]
+    );
+    assert_eq!(
+        Message::Nack {
+            seq: 8,
+            error: ErrorCode::InvalidArgument
+        }
+        .encode(),
+        [0x03, 0x08, 0x00, 0x02]
+    );
+}
+
+#[test]
+fn every_message_round_trips() {
+    let kinds = [
+        CommandKind::Ping,
+        CommandKind::SetInterval(86_400),
+        CommandKind::ReadSensor(255),
+        CommandKind::Reboot,
+    ];
+    let errors = [
+        ErrorCode::UnknownCommand,
+        ErrorCode::InvalidArgument,
+        ErrorCode::Expired,
+        ErrorCode::Busy,
+    ];
+    let mut messages: Vec<Message> = kinds
+        .iter()
+        .map(|&k| command(u16::MAX, u32::MAX, k))
+        .collect();
+    messages.push(Message::Ack {
+        seq: 0,
+        value: u32::MAX,
+    });
+    messages.extend(errors.iter().map(|&error| Message::Nack { seq: 1, error }));
+
+    for message in messages {
+        assert_eq!(Message::decode(&message.encode()), Ok(message));
+    }
+}
+
+#[test]
+fn decode_rejects_malformed_frames() {
+    assert_eq!(Message::decode(&[]), Err(DecodeError::Empty));
+    assert_eq!(
+        Message::decode(&[0x55, 0x01]),
+        Err(DecodeError::UnknownType(0x55))
+    );
+    assert_eq!(
+        Message::decode(&[0x02, 0x07, 0x00, 0x1E, 0x00, 0x00]),
+        Err(DecodeError::WrongLength {
+            expected: 7,
+            actual: 6
+        })
+    );
+    // Error code 9 and a sensor id that doesn't fit in a u8
+    assert_eq!(
+        Message::decode(&[0x03, 0x08, 0x00, 0x09]),
+        Err(DecodeError::InvalidField)
+    );
+    assert_eq!(
+        Message::decode(&[0x01, 0x07, 0x00, 0, 0, 0, 0, 0x02, 0x2C, 0x01, 0, 0]),
+        Err(DecodeError::InvalidField)
+    );
+}
+
+#[test]
+fn device_answers_each_command() {
+    let mut device = Device::new();
+    let mut send = |seq, kind| decode(device.handle(&command(seq, 1000, kind).encode(), 0));
+
+    assert_eq!(
+        send(1, CommandKind::Ping),
+        Message::Ack { seq: 1, value: 0 }
+    );
+    assert_eq!(
+        send(2, CommandKind::SetInterval(30)),
+        Message::Ack { seq: 2, value: 30 }
+    );
+    assert_eq!(
+        send(3, CommandKind::SetInterval(0)),
+        Message::Nack {
+            seq: 3,
+            error: ErrorCode::InvalidArgument
+        }
+    );
+    assert_eq!(
+        send(4, CommandKind::ReadSensor(2)),
+        Message::Ack { seq: 4, value: 221 }
+    );
+    assert_eq!(
+        send(5, CommandKind::ReadSensor(4)),
+        Message::Nack {
+            seq: 5,
+            error: ErrorCode::InvalidArgument
+        }
+    );
+    assert_eq!(device.interval_secs, 30);
+    assert_eq!(device.executed, 5);
+}
+
+#[test]
+fn device_nacks_expired_commands_without_executing() {
+    let mut device = Device::new();
+    let response = device.handle(&command(1, 100, CommandKind::Reboot).encode(), 101);
+    assert_eq!(
+        decode(response),
+        Message::Nack {
+            seq: 1,
+            error: ErrorCode::Expired
+        }
+    );
+    assert_eq!(device.reboots, 0);
+    assert_eq!(device.executed, 0);
+}
+
+#[test]
+fn device_handles_malformed_frames() {
+    let mut device = Device::new();
+    assert_eq!(device.handle(&[], 0), None);
+    assert_eq!(device.handle(&[0x55, 0x01], 0), None);
+    // Unknown command kind, but the header names seq 9
+    let response = device.handle(&[0x01, 0x09, 0x00, 0, 0, 0, 0, 0x7F, 0, 0, 0, 0], 0);
+    assert_eq!(
+        decode(response),
+        Message::Nack {
+            seq: 9,
+            error: ErrorCode::UnknownCommand
+        }
+    );
+    assert_eq!(device.malformed, 3);
+
+    // A well-formed response sent to the device is ignored, not malformed
+    let ack = Message::Ack { seq: 1, value: 0 }.encode();
+    assert_eq!(device.handle(&ack, 0), None);
+    assert_eq!(device.malformed, 3);
+    assert_eq!(device.executed, 0);
+}
+
+#[test]
+fn retransmitted_command_is_answered_from_the_cache() {
+    let mut device = Device::new();
+    let frame = command(1, 1000, CommandKind::Reboot).encode();
+    let first = device.handle(&frame, 0);
+    let second = device.handle(&frame, 10);
+    assert_eq!(first, second);
+    assert_eq!(device.reboots, 1);
+    assert_eq!(device.duplicates, 1);
+}
+
+#[test]
+fn duplicate_cache_forgets_after_its_window() {
+    let mut device = Device::new();
+    for seq in 1..=33 {
+        device.handle(&command(seq, 1000, CommandKind::Ping).encode(), 0);
+    }
+    // 33 is still cached, 1 has been pushed out by the 32 after it
+    device.handle(&command(33, 1000, CommandKind::Ping).encode(), 0);
+    assert_eq!(device.duplicates, 1);
+    device.handle(&command(1, 1000, CommandKind::Ping).encode(), 0);
+    assert_eq!(device.duplicates, 1);
+    assert_eq!(device.executed, 34);
+}
+
+#[test]
+fn perfect_link_completes_in_one_attempt() {
+    let mut client = Client::new(RetryPolicy::default());
+    let mut device = Device::new();
+    let (mut down, mut up) = (LossyLink::perfect(), LossyLink::perfect());
+    let ping = client.submit(CommandKind::Ping, 0, 1000);
+    let bad = client.submit(CommandKind::SetInterval(0), 0, 1000);
+    let read = client.submit(CommandKind::ReadSensor(1), 0, 1000);
+    assert_eq!((ping, bad, read), (1, 2, 3));
+
+    run(&mut client, &mut device, &mut down, &mut up);
+    assert_eq!(
+        client.take_outcomes(),
+        vec![
+            Outcome::Acked {
+                seq: 1,
+                value: 0,
+                attempts: 1
+            },
+            Outcome::Nacked {
+                seq: 2,
+                error: ErrorCode::InvalidArgument,
+                attempts: 1
+            },
+            Outcome::Acked {
+                seq: 3,
+                value: 218,
+                attempts: 1
+            },
+        ]
+    );
+    assert_eq!(client.transmissions, 3);
+    assert_eq!(client.in_flight(), 0);
+}
+
+#[test]
+fn lost_acks_retry_without_executing_twice() {
+    let mut client = Client::new(RetryPolicy::default());
+    let mut device = Device::new();
+    let (mut down, mut up) = (LossyLink::perfect(), LossyLink::perfect());
+    up.drop_next(2);
+    client.submit(CommandKind::Reboot, 0, 2000);
+
+    run(&mut client, &mut device, &mut down, &mut up);
+    assert_eq!(
+        client.take_outcomes(),
+        vec![Outcome::Acked {
+            seq: 1,
+            value: 0,
+            attempts: 3
+        }]
+    );
+    assert_eq!(device.reboots, 1);
+    assert_eq!(device.duplicates, 2);
+}
+
+#[test]
+fn gives_up_after_max_attempts() {
+    let mut client = Client::new(RetryPolicy {
+        timeout_ms: 100,
+        max_attempts: 3,
+    });
+    let mut device = Device::new();
+    let mut down = LossyLink::new(0, 100, 7);
+    let mut up = LossyLink::perfect();
+    client.submit(CommandKind::Ping, 0, 10_000);
+
+    run(&mut client, &mut device, &mut down, &mut up);
+    assert_eq!(
+        client.take_outcomes(),
+        vec![Outcome::TimedOut {
+            seq: 1,
+            attempts: 3
+        }]
+    );
+    assert_eq!(client.transmissions, 3);
+    assert_eq!(down.dropped, 3);
+    assert_eq!(device.executed, 0);
+}
+
+#[test]
+fn deadline_shorter_than_latency_times_out() {
+    let mut client = Client::new(RetryPolicy::default());
+    let mut device = Device::new();
+    let mut down = LossyLink::new(300, 0, 1);
+    let mut up = LossyLink::new(300, 0, 1);
+    client.submit(CommandKind::Ping, 0, 200);
+
+    run(&mut client, &mut device, &mut down, &mut up);
+    // Sent at 0 and 200; at 400 the deadline has passed
+    assert_eq!(
+        client.take_outcomes(),
+        vec![Outcome::TimedOut {
+            seq: 1,
+            attempts: 2
+        }]
+    );
+    // Both copies arrive late and are NACKed; the client ignores the NACKs
+    assert_eq!(device.executed, 0);
+    assert_eq!(client.stale_responses, 2);
+}
+
+#[test]
+fn responses_for_unknown_sequence_numbers_are_stale() {
+    let mut client = Client::new(RetryPolicy::default());
+    client.handle(&Message::Ack { seq: 42, value: 1 }.encode());
+    client.handle(&[0xFF]);
+    assert_eq!(client.stale_responses, 2);
+    assert!(client.take_outcomes().is_empty());
+}
+
+#[test]
+fn lossy_link_delivers_every_command_exactly_once() {
+    let mut client = Client::new(RetryPolicy {
+        timeout_ms: 150,
+        // Half the round trips fail; ten attempts make a timeout unlikely
+        max_attempts: 10,
+    });
+    let mut device = Device::new();
+    let mut down = LossyLink::new(40, 30, 0xC0FFEE);
+    let mut up = LossyLink::new(40, 30, 0xBEEF);
+    for i in 0..20 {
+        client.submit(CommandKind::ReadSensor(i % 4), 0, 5000);
+    }
+
+    run(&mut client, &mut device, &mut down, &mut up);
+    let outcomes = client.take_outcomes();
+    assert_eq!(outcomes.len(), 20);
+    for outcome in &outcomes {
+        let Outcome::Acked { seq, value, .. } = *outcome else {
+            panic!("not acked: {:?}", outcome);
+        };
+        assert_eq!(value, 215 + ((seq - 1) % 4) as u32 * 3);
+    }
+    assert!(down.dropped > 0 && up.dropped > 0);
+    assert!(client.transmissions > 20);
+    // Retransmissions that got through were answered from the cache
+    assert_eq!(device.executed, 20);
+}
+
+#[test]
+fn link_holds_frames_for_its_latency() {
+    let mut link = LossyLink::new(50, 0, 1);
+    link.send(0, vec![1]);
+    link.send(10, vec![2]);
+    assert_eq!(link.recv(49), None);
+    assert_eq!(link.recv(50), Some(vec![1]));
+    assert_eq!(link.recv(50), None);
+    assert_eq!(link.recv(60), Some(vec![2]));
+    assert_eq!((link.sent, link.dropped), (2, 0));
+}
@@ -0,0 +1,174 @@
+// Interned topic names vs a String per message
+//
+//   cargo bench              # memory report, then every group
+//   cargo bench -- count     # one group
+//
+// The topic set is a large gateway's: 5,000 devices with 8 measurements
+// each, 40,000 distinct names. 400,000 messages cycle through them. Before
+// the timing groups run, a counting allocator measures what it takes to
+// keep each message's topic as a `String`, as an interned `Arc<str>`, and
+// as a `Symbol`.
+
+use broker::{Interner, Symbol};
+use criterion::{criterion_group, Criterion, Throughput};
+use memprofile::{human, stats, Profiler};
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::sync::Arc;
+
+#[global_allocator]
+static GLOBAL: Profiler = Profiler;
+
+const DEVICES: usize = 5_000;
+const MEASUREMENTS: [&str; 8] = [
+    "temperature",
+    "humidity",
+    "pressure",
+    "battery",
+    "rssi",
+    "co2",
+    "lux",
+    "motion",
+];
+const MESSAGES: usize = 400_000;
+
+fn topics() -> Vec<String> {
+    (0..DEVICES)
+        .flat_map(|d| {
+            MEASUREMENTS
+                .iter()
+                .map(move |m| format!("site-3/building-a/floor-2/dev-{:05}/{}", d, m))
+        })
+        .collect()
+}
+
+// Message i is on topic i * 7919 % n: every topic, in a scattered order
+fn stream(topics: &[String]) -> impl Iterator<Item = &str> {
+    (0..MESSAGES).map(|i| topics[i * 7919 % topics.len()].as_str())
+}
+
+fn live_after<T>(build: impl FnOnce() -> T) -> (T, usize, usize) {
+    let start = stats();
+    let value = build();
+    let end = stats();
+    (
+        value,
+        end.live_bytes - start.live_bytes,
+        end.allocs - start.allocs,
+    )
+}
+
+fn memory_report() {
+    let topics = topics();
+    println!(
+        "Keeping the topic of {} messages ({} distinct):",
+        MESSAGES,
+        topics.len()
+    );
+    let (strings, string_bytes, string_allocs) =
+        live_after(|| stream(&topics).map(String::from).collect::<Vec<_>>());
+    let (shared, shared_bytes, shared_allocs) = live_after(|| {
+        let mut interner = Interner::new();
+        let arcs: Vec<Arc<str>> = stream(&topics)
+            .map(|t| {
+                let symbol = interner.intern(t);
+                interner.resolve_arc(symbol)
+            })
+            .collect();
+        (interner, arcs)
+    });
+    let (symbols, symbol_bytes, symbol_allocs) = live_after(|| {
+        let mut interner = Interner::new();
+        let symbols: Vec<Symbol> = stream(&topics).map(|t| interner.intern(t)).collect();
+        (interner, symbols)
+    });
+    for (label, bytes, allocs) in [
+        ("Vec<String>", string_bytes, string_allocs),
+        ("Interner + Vec<Arc<str>>", shared_bytes, shared_allocs),
+        ("Interner + Vec<Symbol>", symbol_bytes, symbol_allocs),
+    ] {
+        println!(
+            "  {:<26} {:>10} live, {:>7} allocations",
+            label,
+            human(bytes),
+            allocs
+        );
+    }
+    println!();
+    drop((strings, shared, symbols));
+}
+
+fn intern(c: &mut Criterion) {
+    let topics = topics();
+    let mut group = c.benchmark_group("intern");
+    group.throughput(Throughput::Elements(MESSAGES as u64));
+    group.bench_function("string_per_message", |b| {
+        b.iter(|| {
+            for topic in stream(&topics) {
+                black_box(String::from(topic));
+            }
+        })
+    });
+    // Every topic already interned: the steady state of a running broker
+    let mut interner = Interner::new();
+    for topic in &topics {
+        interner.intern(topic);
+    }
+    group.bench_function("interned_arc", |b| {
+        b.iter(|| {
+            for topic in stream(&topics) {
+                let symbol = interner.intern(topic);
+                black_box(interner.resolve_arc(symbol));
+            }
+        })
+    });
+    group.finish();
+}
+
+// Per-topic message counts, keyed by name or by symbol
+fn count(c: &mut Criterion) {
+    let topics = topics();
+    let mut interner = Interner::new();
+    let symbols: Vec<Symbol> = stream(&topics).map(|t| interner.intern(t)).collect();
+    let names: Vec<String> = stream(&topics).map(String::from).collect();
+    let mut group = c.benchmark_group("count");
+    group.throughput(Throughput::Elements(MESSAGES as u64));
+    group.bench_function("string_keys", |b| {
+        b.iter(|| {
+            let mut counts: HashMap<&str, u32> = HashMap::new();
+            for name in &names {
+                *counts.entry(name.as_str()).or_default() += 1;
+            }
+            counts.len()
+        })
+    });
+    group.bench_function("symbol_keys", |b| {
+        b.iter(|| {
+            let mut counts: HashMap<Symbol, u32> = HashMap::new();
+            for &symbol in &symbols {
+                *counts.entry(symbol).or_default() += 1;
+            }
+            counts.len()
+        })
+    });
+    // Symbols are dense indices, so a Vec replaces the map entirely
+    group.bench_function("symbol_index", |b| {
+        b.iter(|| {
+            let mut counts = vec![0u32; interner.len()];
+            for &symbol in &symbols {
+                counts[symbol.index()] += 1;
+            }
+            counts.len()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, intern, count);
+
+// criterion_main!, with the memory report first
+fn main() {
+    memory_report();
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+}
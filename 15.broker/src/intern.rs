@@ -0,0 +1,87 @@
+// String interning for repeated topic and device names
+//
+//   intern("sensors/kitchen/temp") -> Symbol(0)   stored once
+//   intern("sensors/garage/temp")  -> Symbol(1)
+//   intern("sensors/kitchen/temp") -> Symbol(0)   found, nothing allocated
+//
+// A gateway sees the same few thousand topic names millions of times. An
+// `Interner` keeps one `Arc<str>` per distinct string and hands out a
+// 4-byte `Symbol`; comparing or hashing two symbols is an integer
+// operation, whatever the length of the strings. Code that needs the text
+// gets it back with `resolve`, or an `Arc<str>` clone with `resolve_arc`,
+// which bumps a reference count instead of copying.
+//
+// Strings are never removed, so an interner should only see names from a
+// bounded set: topics from known devices, model names, firmware versions.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// Only meaningful for the interner that returned it. Ordered by first
+// interning, not alphabetically
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Interner {
+    // Both hold the same Arc: one allocation per distinct string
+    map: HashMap<Arc<str>, Symbol>,
+    strings: Vec<Arc<str>>,
+}
+
+impl Interner {
+    pub fn new() -> Interner {
+        Interner::default()
+    }
+
+    pub fn intern(&mut self, s: &str) -> Symbol {
+        if let Some(&symbol) = self.map.get(s) {
+            return symbol;
+        }
+        let symbol = Symbol(u32::try_from(self.strings.len()).expect("more than u32::MAX strings"));
+        let shared: Arc<str> = Arc::from(s);
+        self.strings.push(Arc::clone(&shared));
+        self.map.insert(shared, symbol);
+        symbol
+    }
+
+    // Looks up without inserting
+    pub fn get(&self, s: &str) -> Option<Symbol> {
+        self.map.get(s).copied()
+    }
+
+    // Panics for a symbol from another interner that is out of range
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.index()]
+    }
+
+    pub fn resolve_arc(&self, symbol: Symbol) -> Arc<str> {
+        Arc::clone(&self.strings[symbol.index()])
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    // Text bytes stored, each distinct string counted once
+    pub fn string_bytes(&self) -> usize {
+        self.strings.iter().map(|s| s.len()).sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &str)> {
+        self.strings
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (Symbol(i as u32), &**s))
+    }
+}
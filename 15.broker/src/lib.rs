@@ -0,0 +1,174 @@
+// Minimal in-process publish/subscribe broker
+//
+// Publishers call `publish(topic, payload)`. Each subscriber owns a bounded
+// queue; when it is full the subscriber's `DropPolicy` decides whether the
+// new message or the oldest queued one is discarded. A slow subscriber can
+// therefore never block publishers or other subscribers.
+//
+// Subscription filters are interned when subscribing, so an exact filter
+// (no `+` or `#`) matches a topic by comparing two `Symbol`s, and messages
+// on that topic share the filter's `Arc<str>`. Published topics are only
+// looked up, never interned: a publisher inventing topics can't grow the
+// interner, which holds at most one name per distinct filter.
+
+mod intern;
+mod topic;
+
+pub use intern::{Interner, Symbol};
+pub use topic::{matches, validate_filter, validate_topic, TopicError};
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub topic: Arc<str>,
+    // Shared between all subscribers that receive it
+    pub payload: Arc<[u8]>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropPolicy {
+    // Keep what is queued, discard the incoming message
+    DropNewest,
+    // Make room by discarding the oldest queued message
+    DropOldest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SubscriberId(u32);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SubscriberStats {
+    pub delivered: u64,
+    pub dropped: u64,
+    pub queued: usize,
+}
+
+#[derive(Debug)]
+struct Subscriber {
+    id: SubscriberId,
+    filter: Symbol,
+    // No wildcards: matched by symbol, not by `matches`
+    exact: bool,
+    capacity: usize,
+    policy: DropPolicy,
+    queue: VecDeque<Message>,
+    delivered: u64,
+    dropped: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct Broker {
+    subscribers: Vec<Subscriber>,
+    next_id: u32,
+    published: u64,
+    filters: Interner,
+}
+
+impl Broker {
+    pub fn new() -> Broker {
+        Broker::default()
+    }
+
+    pub fn subscribe(
+        &mut self,
+        filter: &str,
+        capacity: usize,
+        policy: DropPolicy,
+    ) -> Result<SubscriberId, TopicError> {
+        validate_filter(filter)?;
+        let id = SubscriberId(self.next_id);
+        self.next_id += 1;
+        self.subscribers.push(Subscriber {
+            id,
+            filter: self.filters.intern(filter),
+            exact: !filter.contains(['+', '#']),
+            capacity: capacity.max(1),
+            policy,
+            queue: VecDeque::new(),
+            delivered: 0,
+            dropped: 0,
+        });
+        Ok(id)
+    }
+
+    pub fn unsubscribe(&mut self, id: SubscriberId) -> bool {
+        let before = self.subscribers.len();
+        self.subscribers.retain(|s| s.id != id);
+        self.subscribers.len() != before
+    }
+
+    // Returns the number of subscribers that accepted the message
+    pub fn publish(&mut self, topic: &str, payload: &[u8]) -> Result<usize, TopicError> {
+        validate_topic(topic)?;
+        self.published += 1;
+        // A topic some filter names exactly reuses that filter's name
+        let symbol = self.filters.get(topic);
+        let message = Message {
+            topic: match symbol {
+                Some(symbol) => self.filters.resolve_arc(symbol),
+                None => Arc::from(topic),
+            },
+            payload: Arc::from(payload),
+        };
+
+        let filters = &self.filters;
+        let mut accepted = 0;
+        for sub in self.subscribers.iter_mut().filter(|s| {
+            if s.exact {
+                symbol == Some(s.filter)
+            } else {
+                matches(filters.resolve(s.filter), topic)
+            }
+        }) {
+            if sub.queue.len() >= sub.capacity {
+                sub.dropped += 1;
+                match sub.policy {
+                    DropPolicy::DropNewest => continue,
+                    DropPolicy::DropOldest => {
+                        sub.queue.pop_front();
+                    }
+                }
+            }
+            sub.queue.push_back(message.clone());
+            accepted += 1;
+        }
+        Ok(accepted)
+    }
+
+    pub fn receive(&mut self, id: SubscriberId) -> Option<Message> {
+        let sub = self.subscribers.iter_mut().find(|s| s.id == id)?;
+        let message = sub.queue.pop_front()?;
+        sub.delivered += 1;
+        Some(message)
+    }
+
+    pub fn drain(&mut self, id: SubscriberId) -> Vec<Message> {
+        std::iter::from_fn(|| self.receive(id)).collect()
+    }
+
+    pub fn stats(&self, id: SubscriberId) -> Option<SubscriberStats> {
+        self.subscribers
+            .iter()
+            .find(|s| s.id == id)
+            .map(|s| SubscriberStats {
+                delivered: s.delivered,
+                dropped: s.dropped,
+                queued: s.queue.len(),
+            })
+    }
+
+    pub fn published(&self) -> u64 {
+        self.published
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.len()
+    }
+
+    // Distinct filters subscribed so far, including unsubscribed ones
+    pub fn filters(&self) -> &Interner {
+        &self.filters
+    }
+}
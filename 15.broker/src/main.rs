@@ -0,0 +1,214 @@
+use broker::{matches, validate_filter, Broker, DropPolicy, Interner};
+use histogram::Histogram;
+use std::sync::Arc;
+use std::time::Instant;
+
+// (filter, topic); tests/topic.rs checks the results
+const MATCH_CASES: [(&str, &str); 16] = [
+    ("sensors/kitchen/temp", "sensors/kitchen/temp"),
+    ("sensors/kitchen/temp", "sensors/kitchen/humidity"),
+    ("sensors/+/temp", "sensors/kitchen/temp"),
+    ("sensors/+/temp", "sensors/kitchen/fridge/temp"),
+    ("sensors/+", "sensors"),
+    ("sensors/#", "sensors"),
+    ("sensors/#", "sensors/kitchen/fridge/temp"),
+    ("#", "anything/at/all"),
+    ("+/+", "a/b"),
+    ("+/+", "a/b/c"),
+    ("+", "/"),
+    ("+/+", "/"),
+    ("sensors/+/#", "sensors/kitchen"),
+    ("#", "$SYS/uptime"),
+    ("+/uptime", "$SYS/uptime"),
+    ("$SYS/#", "$SYS/uptime"),
+];
+
+fn main() {
+    println!("=== Publish/Subscribe Broker ===\n");
+
+    // 1. Topic matching
+    println!("1. Topic matching:");
+    for (filter, topic) in MATCH_CASES {
+        println!("   {:<22} {:<28} {}", filter, topic, matches(filter, topic));
+    }
+
+    // 2. Filter validation
+    println!("\n2. Filter validation:");
+    for filter in ["sensors/#", "sensors/#/temp", "sensors/te+mp", "", "a/+/b"] {
+        match validate_filter(filter) {
+            Ok(()) => println!("   {:<16} valid", format!("{:?}", filter)),
+            Err(e) => println!("   {:<16} {}", format!("{:?}", filter), e),
+        }
+    }
+
+    // 3. Routing to several subscribers
+    println!("\n3. Routing:");
+    let mut broker = Broker::new();
+    let dashboard = broker
+        .subscribe("sensors/#", 16, DropPolicy::DropOldest)
+        .unwrap();
+    let temps = broker
+        .subscribe("sensors/+/temp", 16, DropPolicy::DropOldest)
+        .unwrap();
+    let kitchen = broker
+        .subscribe("sensors/kitchen/+", 16, DropPolicy::DropOldest)
+        .unwrap();
+    for (topic, payload) in [
+        ("sensors/kitchen/temp", "21.5"),
+        ("sensors/garage/temp", "9.0"),
+        ("sensors/kitchen/humidity", "48"),
+        ("alerts/door", "open"),
+    ] {
+        let n = broker.publish(topic, payload.as_bytes()).unwrap();
+        println!("   {:<26} -> {} subscriber(s)", topic, n);
+    }
+    for (name, id) in [
+        ("dashboard", dashboard),
+        ("temps", temps),
+        ("kitchen", kitchen),
+    ] {
+        let topics: Vec<String> = broker
+            .drain(id)
+            .iter()
+            .map(|m| m.topic.to_string())
+            .collect();
+        println!("   {:<9} got {:?}", name, topics);
+    }
+
+    // 4. Bounded queues and drop policies
+    println!("\n4. Slow subscribers (capacity 3, 6 messages):");
+    let mut broker = Broker::new();
+    let newest = broker
+        .subscribe("counter", 3, DropPolicy::DropNewest)
+        .unwrap();
+    let oldest = broker
+        .subscribe("counter", 3, DropPolicy::DropOldest)
+        .unwrap();
+    for i in 1..=6 {
+        broker.publish("counter", &[i]).unwrap();
+    }
+    for (name, id) in [("DropNewest", newest), ("DropOldest", oldest)] {
+        let stats = broker.stats(id).unwrap();
+        let values: Vec<u8> = broker.drain(id).iter().map(|m| m.payload[0]).collect();
+        println!(
+            "   {:<10} kept {:?}, dropped {}",
+            name, values, stats.dropped
+        );
+    }
+
+    // 5. Shared payloads
+    println!("\n5. Payload sharing:");
+    let mut broker = Broker::new();
+    let a = broker.subscribe("blob", 4, DropPolicy::DropNewest).unwrap();
+    let b = broker.subscribe("blob", 4, DropPolicy::DropNewest).unwrap();
+    broker.publish("blob", &[0u8; 1024]).unwrap();
+    let ma = broker.receive(a).unwrap();
+    let mb = broker.receive(b).unwrap();
+    println!(
+        "   1 KiB payload stored once: {}",
+        Arc::ptr_eq(&ma.payload, &mb.payload)
+    );
+
+    // 6. Unsubscribing and statistics
+    println!("\n6. Unsubscribe:");
+    let mut broker = Broker::new();
+    let id = broker
+        .subscribe("status", 8, DropPolicy::DropOldest)
+        .unwrap();
+    broker.publish("status", b"up").unwrap();
+    broker.receive(id);
+    println!("   stats: {:?}", broker.stats(id).unwrap());
+    println!("   unsubscribed: {}", broker.unsubscribe(id));
+    println!(
+        "   publish after: {} subscriber(s), {} published in total",
+        broker.publish("status", b"down").unwrap(),
+        broker.published()
+    );
+
+    // 7. Interned topic names
+    println!("\n7. Topic interning:");
+    let mut names = Interner::new();
+    let kitchen = names.intern("sensors/kitchen/temp");
+    let garage = names.intern("sensors/garage/temp");
+    println!(
+        "   {:?} {:?}, again {:?}, lookup of unseen: {:?}",
+        kitchen,
+        garage,
+        names.intern("sensors/kitchen/temp"),
+        names.get("sensors/attic/temp")
+    );
+    println!("   {:?} resolves to {:?}", garage, names.resolve(garage));
+    let mut broker = Broker::new();
+    let kitchen = broker
+        .subscribe("sensors/kitchen/temp", 1000, DropPolicy::DropOldest)
+        .unwrap();
+    broker
+        .subscribe("sensors/#", 1000, DropPolicy::DropOldest)
+        .unwrap();
+    for i in 0..1000 {
+        let topic = format!("sensors/node{}/temp", i);
+        broker.publish(&topic, b"20.0").unwrap();
+        broker.publish("sensors/kitchen/temp", b"21.0").unwrap();
+    }
+    let messages = broker.drain(kitchen);
+    let shared = messages
+        .iter()
+        .all(|m| Arc::ptr_eq(&m.topic, &messages[0].topic));
+    println!(
+        "   {} messages on 1,001 topics, {} names interned ({} bytes): the filters",
+        broker.published(),
+        broker.filters().len(),
+        broker.filters().string_bytes()
+    );
+    println!(
+        "   messages on an exact filter share its name: {} ({} of them)",
+        shared,
+        messages.len()
+    );
+
+    // 8. Publish latency
+    println!("\n8. Publish latency, 100,000 messages on 64 topics (use --release):");
+    let topics: Vec<String> = (0..64)
+        .map(|i| {
+            format!(
+                "sensors/node{}/{}",
+                i / 4,
+                ["temp", "rh", "co2", "lux"][i % 4]
+            )
+        })
+        .collect();
+    for subscribers in [1, 8, 32] {
+        let mut broker = Broker::new();
+        let ids: Vec<_> = (0..subscribers)
+            .map(|i| {
+                let filter = if i % 2 == 0 {
+                    "sensors/#"
+                } else {
+                    "sensors/+/temp"
+                };
+                broker
+                    .subscribe(filter, 1024, DropPolicy::DropOldest)
+                    .unwrap()
+            })
+            .collect();
+        let mut latency = Histogram::new();
+        for i in 0..100_000 {
+            let started = Instant::now();
+            broker.publish(&topics[i % topics.len()], b"21.5").unwrap();
+            latency.record_duration(started.elapsed());
+            // Subscribers catch up every 500 messages, as a consumer task would
+            if i % 500 == 499 {
+                for &id in &ids {
+                    broker.drain(id);
+                }
+            }
+        }
+        println!(
+            "   {:>2} subscriber(s): {}",
+            subscribers,
+            latency.percentiles()
+        );
+    }
+
+    println!("\n=== End of Broker Examples ===");
+}
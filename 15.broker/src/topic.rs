@@ -0,0 +1,76 @@
+// MQTT-style topic names and filters
+//
+//   sensors/kitchen/temp      topic: levels separated by '/'
+//   sensors/+/temp            '+' matches exactly one level
+//   sensors/#                 '#' matches the parent and any number of levels
+//
+// As in MQTT, filters starting with a wildcard do not match topics starting
+// with '$' (reserved for broker-internal topics like `$SYS/uptime`).
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TopicError {
+    Empty,
+    WildcardInTopic,
+    HashNotLast,
+    WildcardNotWholeLevel,
+}
+
+impl fmt::Display for TopicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TopicError::Empty => write!(f, "topic is empty"),
+            TopicError::WildcardInTopic => write!(f, "wildcards are not allowed in topic names"),
+            TopicError::HashNotLast => write!(f, "'#' must be the last level"),
+            TopicError::WildcardNotWholeLevel => write!(f, "wildcards must occupy a whole level"),
+        }
+    }
+}
+
+impl std::error::Error for TopicError {}
+
+pub fn validate_topic(topic: &str) -> Result<(), TopicError> {
+    if topic.is_empty() {
+        return Err(TopicError::Empty);
+    }
+    if topic.contains(['+', '#']) {
+        return Err(TopicError::WildcardInTopic);
+    }
+    Ok(())
+}
+
+pub fn validate_filter(filter: &str) -> Result<(), TopicError> {
+    if filter.is_empty() {
+        return Err(TopicError::Empty);
+    }
+    let levels: Vec<&str> = filter.split('/').collect();
+    for (i, level) in levels.iter().enumerate() {
+        if level.contains(['+', '#']) && level.len() > 1 {
+            return Err(TopicError::WildcardNotWholeLevel);
+        }
+        if *level == "#" && i != levels.len() - 1 {
+            return Err(TopicError::HashNotLast);
+        }
+    }
+    Ok(())
+}
+
+// Both arguments are assumed valid (see `validate_*`)
+pub fn matches(filter: &str, topic: &str) -> bool {
+    if topic.starts_with('$') && (filter.starts_with('+') || filter.starts_with('#')) {
+        return false;
+    }
+
+    let mut filter_levels = filter.split('/');
+    let mut topic_levels = topic.split('/');
+    loop {
+        match (filter_levels.next(), topic_levels.next()) {
+            (Some("#"), _) => return true,
+            (Some("+"), Some(_)) => {}
+            (Some(f), Some(t)) if f == t => {}
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use broker::{Broker, DropPolicy};
+use std::sync::Arc;
+
+#[test]
+fn exact_and_wildcard_filters_route_the_same_topics_as_matches() {
+    let mut broker = Broker::new();
+    let exact = broker
+        .subscribe("sensors/kitchen/temp", 16, DropPolicy::DropNewest)
+        .unwrap();
+    let plus = broker
+        .subscribe("sensors/+/temp", 16, DropPolicy::DropNewest)
+        .unwrap();
+    let hash = broker
+        .subscribe("sensors/#", 16, DropPolicy::DropNewest)
+        .unwrap();
+
+    assert_eq!(broker.publish("sensors/kitchen/temp", b"1").unwrap(), 3);
+    assert_eq!(broker.publish("sensors/garage/temp", b"2").unwrap(), 2);
+    assert_eq!(broker.publish("sensors/kitchen/rh", b"3").unwrap(), 1);
+    assert_eq!(broker.publish("sensors/kitchen", b"4").unwrap(), 1);
+    assert_eq!(broker.publish("actuators/fan", b"5").unwrap(), 0);
+
+    let topics = |broker: &mut Broker, id| -> Vec<String> {
+        broker
+            .drain(id)
+            .iter()
+            .map(|m| m.topic.to_string())
+            .collect()
+    };
+    assert_eq!(topics(&mut broker, exact), ["sensors/kitchen/temp"]);
+    assert_eq!(
+        topics(&mut broker, plus),
+        ["sensors/kitchen/temp", "sensors/garage/temp"]
+    );
+    assert_eq!(
+        topics(&mut broker, hash),
+        [
+            "sensors/kitchen/temp",
+            "sensors/garage/temp",
+            "sensors/kitchen/rh",
+            "sensors/kitchen"
+        ]
+    );
+}
+
+#[test]
+fn publishing_on_new_topics_does_not_grow_the_interner() {
+    let mut broker = Broker::new();
+    let all = broker
+        .subscribe("sensors/#", 10_000, DropPolicy::DropOldest)
+        .unwrap();
+    broker
+        .subscribe("sensors/kitchen/temp", 1, DropPolicy::DropOldest)
+        .unwrap();
+    assert_eq!(broker.filters().len(), 2);
+
+    for i in 0..5_000 {
+        broker
+            .publish(&format!("sensors/node{}/temp", i), b"20.0")
+            .unwrap();
+    }
+    assert_eq!(broker.filters().len(), 2);
+    assert_eq!(broker.drain(all).len(), 5_000);
+}
+
+#[test]
+fn messages_on_an_exact_filter_share_its_name() {
+    let mut broker = Broker::new();
+    let exact = broker
+        .subscribe("sensors/kitchen/temp", 16, DropPolicy::DropNewest)
+        .unwrap();
+    let wildcard = broker
+        .subscribe("sensors/+/temp", 16, DropPolicy::DropNewest)
+        .unwrap();
+    for _ in 0..3 {
+        broker.publish("sensors/kitchen/temp", b"21.0").unwrap();
+    }
+
+    let messages = broker.drain(exact);
+    let name = broker
+        .filters()
+        .resolve_arc(broker.filters().get("sensors/kitchen/temp").unwrap());
+    assert_eq!(messages.len(), 3);
+    assert!(messages.iter().all(|m| Arc::ptr_eq(&m.topic, &name)));
+    // A wildcard subscriber receives the same message, name included
+    assert!(broker
+        .drain(wildcard)
+        .iter()
+        .all(|m| Arc::ptr_eq(&m.topic, &name)));
+}
+
+#[test]
+fn unsubscribing_keeps_exact_matching_for_the_remaining_subscribers() {
+    let mut broker = Broker::new();
+    let first = broker
+        .subscribe("status", 4, DropPolicy::DropNewest)
+        .unwrap();
+    let second = broker
+        .subscribe("status", 4, DropPolicy::DropNewest)
+        .unwrap();
+    assert_eq!(broker.filters().len(), 1);
+    assert!(broker.unsubscribe(first));
+    assert_eq!(broker.publish("status", b"up").unwrap(), 1);
+    assert_eq!(broker.drain(second).len(), 1);
+    assert_eq!(broker.publish("status/extra", b"x").unwrap(), 0);
+}
@@ -0,0 +1,123 @@
+use broker::{Broker, DropPolicy, SubscriberStats, TopicError};
+use std::sync::Arc;
+
+fn payloads(broker: &mut Broker, id: broker::SubscriberId) -> Vec<Vec<u8>> {
+    broker
+        .drain(id)
+        .iter()
+        .map(|m| m.payload.to_vec())
+        .collect()
+}
+
+#[test]
+fn drop_newest_keeps_the_first_messages() {
+    let mut broker = Broker::new();
+    let id = broker.subscribe("t", 3, DropPolicy::DropNewest).unwrap();
+    let accepted: Vec<usize> = (0..5u8)
+        .map(|i| broker.publish("t", &[i]).unwrap())
+        .collect();
+    assert_eq!(accepted, [1, 1, 1, 0, 0]);
+    assert_eq!(payloads(&mut broker, id), [[0], [1], [2]]);
+}
+
+#[test]
+fn drop_oldest_keeps_the_last_messages() {
+    let mut broker = Broker::new();
+    let id = broker.subscribe("t", 3, DropPolicy::DropOldest).unwrap();
+    let accepted: Vec<usize> = (0..5u8)
+        .map(|i| broker.publish("t", &[i]).unwrap())
+        .collect();
+    assert_eq!(accepted, [1, 1, 1, 1, 1]);
+    assert_eq!(payloads(&mut broker, id), [[2], [3], [4]]);
+}
+
+#[test]
+fn stats_count_delivered_dropped_and_queued() {
+    let mut broker = Broker::new();
+    let newest = broker.subscribe("t", 2, DropPolicy::DropNewest).unwrap();
+    let oldest = broker.subscribe("t", 2, DropPolicy::DropOldest).unwrap();
+    for i in 0..5u8 {
+        broker.publish("t", &[i]).unwrap();
+    }
+    broker.receive(newest);
+
+    assert_eq!(
+        broker.stats(newest),
+        Some(SubscriberStats {
+            delivered: 1,
+            dropped: 3,
+            queued: 1
+        })
+    );
+    assert_eq!(
+        broker.stats(oldest),
+        Some(SubscriberStats {
+            delivered: 0,
+            dropped: 3,
+            queued: 2
+        })
+    );
+    assert_eq!(broker.published(), 5);
+}
+
+#[test]
+fn a_full_subscriber_does_not_affect_the_others() {
+    let mut broker = Broker::new();
+    let slow = broker
+        .subscribe("sensors/#", 1, DropPolicy::DropNewest)
+        .unwrap();
+    let fast = broker
+        .subscribe("sensors/#", 100, DropPolicy::DropNewest)
+        .unwrap();
+    for i in 0..50u8 {
+        broker.publish("sensors/a", &[i]).unwrap();
+    }
+    assert_eq!(broker.drain(fast).len(), 50);
+    assert_eq!(broker.drain(slow).len(), 1);
+    assert_eq!(broker.stats(slow).unwrap().dropped, 49);
+}
+
+#[test]
+fn zero_capacity_holds_one_message() {
+    let mut broker = Broker::new();
+    let id = broker.subscribe("t", 0, DropPolicy::DropOldest).unwrap();
+    broker.publish("t", b"a").unwrap();
+    broker.publish("t", b"b").unwrap();
+    assert_eq!(payloads(&mut broker, id), [b"b"]);
+}
+
+#[test]
+fn subscribers_share_one_payload() {
+    let mut broker = Broker::new();
+    let a = broker.subscribe("t", 4, DropPolicy::DropNewest).unwrap();
+    let b = broker.subscribe("+", 4, DropPolicy::DropNewest).unwrap();
+    broker.publish("t", &[0u8; 1024]).unwrap();
+    let first = broker.receive(a).unwrap();
+    let second = broker.receive(b).unwrap();
+    assert!(Arc::ptr_eq(&first.payload, &second.payload));
+}
+
+#[test]
+fn invalid_filters_and_topics_are_rejected() {
+    let mut broker = Broker::new();
+    assert_eq!(
+        broker.subscribe("a/#/b", 1, DropPolicy::DropNewest),
+        Err(TopicError::HashNotLast)
+    );
+    assert_eq!(broker.publish("a/+", b""), Err(TopicError::WildcardInTopic));
+    assert_eq!(broker.subscriber_count(), 0);
+    // A rejected publish isn't counted
+    assert_eq!(broker.published(), 0);
+}
+
+#[test]
+fn unsubscribe_removes_queue_and_stats() {
+    let mut broker = Broker::new();
+    let id = broker.subscribe("t", 4, DropPolicy::DropNewest).unwrap();
+    broker.publish("t", b"x").unwrap();
+    assert!(broker.unsubscribe(id));
+    assert!(!broker.unsubscribe(id));
+    assert_eq!(broker.stats(id), None);
+    assert_eq!(broker.receive(id), None);
+    assert_eq!(broker.publish("t", b"y").unwrap(), 0);
+}
@@ -0,0 +1,77 @@
+use broker::{matches, validate_filter, validate_topic, TopicError};
+
+// (filter, topic, expected)
+const MATCH_CASES: [(&str, &str, bool); 20] = [
+    ("sensors/kitchen/temp", "sensors/kitchen/temp", true),
+    ("sensors/kitchen/temp", "sensors/kitchen/humidity", false),
+    ("sensors/kitchen/temp", "sensors/kitchen", false),
+    ("sensors/kitchen", "sensors/kitchen/temp", false),
+    ("sensors/+/temp", "sensors/kitchen/temp", true),
+    ("sensors/+/temp", "sensors/kitchen/fridge/temp", false),
+    ("sensors/+", "sensors", false),
+    ("sensors/+", "sensors/", true),
+    ("sensors/#", "sensors", true),
+    ("sensors/#", "sensors/kitchen/fridge/temp", true),
+    ("sensors/#", "sensorsx/kitchen", false),
+    ("#", "anything/at/all", true),
+    ("+/+", "a/b", true),
+    ("+/+", "a/b/c", false),
+    ("+", "/", false),
+    ("+/+", "/", true),
+    ("sensors/+/#", "sensors/kitchen", true),
+    ("#", "$SYS/uptime", false),
+    ("+/uptime", "$SYS/uptime", false),
+    ("$SYS/#", "$SYS/uptime", true),
+];
+
+#[test]
+fn matches_the_mqtt_table() {
+    for (filter, topic, expected) in MATCH_CASES {
+        assert_eq!(
+            matches(filter, topic),
+            expected,
+            "{:?} against {:?}",
+            filter,
+            topic
+        );
+    }
+}
+
+#[test]
+fn every_filter_in_the_table_is_valid() {
+    for (filter, topic, _) in MATCH_CASES {
+        assert_eq!(validate_filter(filter), Ok(()));
+        assert_eq!(validate_topic(topic), Ok(()));
+    }
+}
+
+#[test]
+fn rejects_malformed_filters() {
+    assert_eq!(validate_filter(""), Err(TopicError::Empty));
+    assert_eq!(
+        validate_filter("sensors/#/temp"),
+        Err(TopicError::HashNotLast)
+    );
+    assert_eq!(
+        validate_filter("sensors/te+mp"),
+        Err(TopicError::WildcardNotWholeLevel)
+    );
+    assert_eq!(
+        validate_filter("sensors/#x"),
+        Err(TopicError::WildcardNotWholeLevel)
+    );
+    assert_eq!(validate_filter("a/+/b"), Ok(()));
+}
+
+#[test]
+fn rejects_wildcards_in_topic_names() {
+    assert_eq!(validate_topic(""), Err(TopicError::Empty));
+    assert_eq!(
+        validate_topic("sensors/+/temp"),
+        Err(TopicError::WildcardInTopic)
+    );
+    assert_eq!(
+        validate_topic("sensors/#"),
+        Err(TopicError::WildcardInTopic)
+    );
+}
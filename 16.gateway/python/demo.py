@@ -0,0 +1,70 @@
+"""Prototype against the gateway's own simulator and filters from Python.
+
+    cd python
+    cargo build --release
+    cp target/release/libgateway_python.so gateway.so   # .dylib on macOS
+    python3 demo.py
+"""
+
+import math
+import statistics
+
+import gateway
+
+print("=== Gateway from Python ===\n")
+
+# 1. Streaming readings
+print("1. Iterating readings:")
+hub = gateway.SensorHub(count=2, seed=7)
+for reading in hub.stream(2, interval_ms=500):
+    print("  ", reading)
+print("  ", hub)
+
+# 2. A whole series, as a plain list of floats (numpy.asarray works directly)
+print("\n2. Series for node 0 temperature (200 polls at 100 ms):")
+hub = gateway.SensorHub(count=3, seed=42)
+temps = hub.series(0, "temperature", 200)
+print("   %d samples, min %.2f, max %.2f" % (len(temps), min(temps), max(temps)))
+
+# 3. The filters the gateway uses
+print("\n3. Filters on a noisy constant:")
+truth = 21.0
+noisy = [truth + 0.8 * math.sin(i * 2.3) + 0.6 * math.cos(i * 5.1) for i in range(200)]
+ema = gateway.Ema(0.1).apply(noisy)
+avg = gateway.MovingAverage(20).apply(noisy)
+kalman = gateway.Kalman(process_noise=1e-4, measurement_noise=0.5)
+est = kalman.apply(noisy)
+
+
+def rms(values):
+    return math.sqrt(statistics.fmean((v - truth) ** 2 for v in values[50:]))
+
+
+print("   raw    rms error %.3f" % rms(noisy))
+print("   ema    rms error %.3f" % rms(ema))
+print("   avg20  rms error %.3f" % rms(avg))
+print("   kalman rms error %.3f (variance %.5f)" % (rms(est), kalman.variance))
+
+# 4. Known answers
+print("\n4. Checks:")
+
+
+def check(name, got, want):
+    ok = all(abs(a - b) < 1e-12 for a, b in zip(got, want)) and len(got) == len(want)
+    print("   %-40s %s" % (name, "ok" if ok else "MISMATCH"))
+
+
+# With no process noise the Kalman estimate is the running mean
+check("Kalman(q=0) == running mean", gateway.Kalman(0.0, 1.0).apply([1.0, 2.0, 3.0, 4.0]), [1.0, 1.5, 2.0, 2.5])
+check("MovingAverage(2) of [1, 3, 5]", gateway.MovingAverage(2).apply([1.0, 3.0, 5.0]), [1.0, 2.0, 4.0])
+check("Ema(0.5) of [0, 2, 2]", gateway.Ema(0.5).apply([0.0, 2.0, 2.0]), [0.0, 1.0, 1.5])
+a, b = gateway.SensorHub(seed=9), gateway.SensorHub(seed=9)
+check("same seed, same series", a.series(1, "humidity", 50), b.series(1, "humidity", 50))
+try:
+    hub.series(0, "pressure", 1)
+    print("   unknown metric accepted?!")
+except ValueError as e:
+    print("   ValueError:", e)
+print("   METRICS:", gateway.METRICS)
+
+print("\n=== End of Gateway from Python ===")
@@ -0,0 +1,265 @@
+// Python bindings for the gateway's simulator and filters
+//
+// Exposes the simulated `SensorHub` and the streaming filters to Python so
+// that analysis notebooks run against exactly the simulator and filter code
+// the gateway uses. Series come back as `list[float]`, which
+// `numpy.asarray` accepts without any numpy dependency on this side.
+//
+// A crate of its own, so the gateway library is never built as a cdylib
+// and never links Python. The module is still imported as `gateway`.
+//
+//   cd python
+//   cargo build --release
+//   cp target/release/libgateway_python.so gateway.so   # .dylib on macOS
+//   python3 demo.py
+
+use gateway::filter::{Ema, Filter, Kalman, MovingAverage};
+use gateway::sensors::{Reading, SensorHub, METRICS};
+use pyo3::exceptions::{PyStopIteration, PyValueError};
+use pyo3::prelude::*;
+
+#[pyclass(name = "Reading", module = "gateway", frozen, get_all)]
+#[derive(Clone)]
+struct PyReading {
+    sensor_id: u16,
+    metric: &'static str,
+    value: f64,
+    timestamp_ms: u64,
+}
+
+#[pymethods]
+impl PyReading {
+    fn __repr__(&self) -> String {
+        format!(
+            "Reading(sensor_id={}, metric='{}', value={:.3}, timestamp_ms={})",
+            self.sensor_id, self.metric, self.value, self.timestamp_ms
+        )
+    }
+}
+
+impl From<Reading> for PyReading {
+    fn from(r: Reading) -> PyReading {
+        PyReading {
+            sensor_id: r.sensor_id,
+            metric: r.metric,
+            value: r.value,
+            timestamp_ms: r.timestamp_ms,
+        }
+    }
+}
+
+#[pyclass(name = "SensorHub", module = "gateway")]
+struct PySensorHub {
+    hub: SensorHub,
+    elapsed_ms: u64,
+}
+
+#[pymethods]
+impl PySensorHub {
+    #[new]
+    #[pyo3(signature = (count = 3, seed = 42))]
+    fn new(count: u16, seed: u32) -> PySensorHub {
+        PySensorHub {
+            hub: SensorHub::new(count, seed),
+            elapsed_ms: 0,
+        }
+    }
+
+    #[getter]
+    fn sensor_count(&self) -> u16 {
+        self.hub.sensor_count()
+    }
+
+    #[getter]
+    fn elapsed_ms(&self) -> u64 {
+        self.elapsed_ms
+    }
+
+    // One poll `interval_ms` after the previous one
+    #[pyo3(signature = (interval_ms = 100))]
+    fn poll(&mut self, interval_ms: u64) -> Vec<PyReading> {
+        self.elapsed_ms += interval_ms;
+        self.hub
+            .poll(self.elapsed_ms, self.elapsed_ms)
+            .into_iter()
+            .map(PyReading::from)
+            .collect()
+    }
+
+    // `for r in hub.stream(50): ...` - advances this hub as it goes
+    #[pyo3(signature = (polls, interval_ms = 100))]
+    fn stream(slf: Py<Self>, polls: u64, interval_ms: u64) -> ReadingStream {
+        ReadingStream {
+            hub: slf,
+            polls_left: polls,
+            interval_ms,
+            pending: Vec::new(),
+        }
+    }
+
+    // Values of one channel over `polls` polls, ready for numpy
+    #[pyo3(signature = (sensor_id, metric, polls, interval_ms = 100))]
+    fn series(
+        &mut self,
+        sensor_id: u16,
+        metric: &str,
+        polls: usize,
+        interval_ms: u64,
+    ) -> PyResult<Vec<f64>> {
+        if sensor_id >= self.hub.sensor_count() {
+            return Err(PyValueError::new_err(format!(
+                "sensor_id {} out of range (hub has {})",
+                sensor_id,
+                self.hub.sensor_count()
+            )));
+        }
+        if !METRICS.contains(&metric) {
+            return Err(PyValueError::new_err(format!(
+                "unknown metric '{}', expected one of {:?}",
+                metric, METRICS
+            )));
+        }
+        Ok((0..polls)
+            .filter_map(|_| {
+                self.poll(interval_ms)
+                    .into_iter()
+                    .find(|r| r.sensor_id == sensor_id && r.metric == metric)
+                    .map(|r| r.value)
+            })
+            .collect())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "SensorHub(count={}, elapsed_ms={})",
+            self.hub.sensor_count(),
+            self.elapsed_ms
+        )
+    }
+}
+
+#[pyclass(module = "gateway")]
+struct ReadingStream {
+    hub: Py<PySensorHub>,
+    polls_left: u64,
+    interval_ms: u64,
+    // Readings of the current poll not yet handed out, in reverse order
+    pending: Vec<PyReading>,
+}
+
+#[pymethods]
+impl ReadingStream {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<PyReading> {
+        if self.pending.is_empty() && self.polls_left > 0 {
+            self.polls_left -= 1;
+            let mut hub = self.hub.bind(py).borrow_mut();
+            self.pending = hub.poll(self.interval_ms);
+            self.pending.reverse();
+        }
+        self.pending
+            .pop()
+            .ok_or_else(|| PyStopIteration::new_err(()))
+    }
+}
+
+// Every filter has the same Python surface: update, apply, reset
+fn apply(filter: &mut impl Filter, values: Vec<f64>) -> Vec<f64> {
+    values.into_iter().map(|v| filter.update(v)).collect()
+}
+
+#[pyclass(name = "Ema", module = "gateway")]
+struct PyEma(Ema);
+
+#[pymethods]
+impl PyEma {
+    #[new]
+    fn new(alpha: f64) -> PyResult<PyEma> {
+        if !(alpha > 0.0 && alpha <= 1.0) {
+            return Err(PyValueError::new_err("alpha must be in (0, 1]"));
+        }
+        Ok(PyEma(Ema::new(alpha)))
+    }
+
+    fn update(&mut self, value: f64) -> f64 {
+        self.0.update(value)
+    }
+
+    // Filter a whole series, continuing from the current state
+    fn apply(&mut self, values: Vec<f64>) -> Vec<f64> {
+        apply(&mut self.0, values)
+    }
+
+    fn reset(&mut self) {
+        self.0.reset()
+    }
+}
+
+#[pyclass(name = "MovingAverage", module = "gateway")]
+struct PyMovingAverage(MovingAverage);
+
+#[pymethods]
+impl PyMovingAverage {
+    #[new]
+    fn new(window: usize) -> PyMovingAverage {
+        PyMovingAverage(MovingAverage::new(window))
+    }
+
+    fn update(&mut self, value: f64) -> f64 {
+        self.0.update(value)
+    }
+
+    fn apply(&mut self, values: Vec<f64>) -> Vec<f64> {
+        apply(&mut self.0, values)
+    }
+
+    fn reset(&mut self) {
+        self.0.reset()
+    }
+}
+
+#[pyclass(name = "Kalman", module = "gateway")]
+struct PyKalman(Kalman);
+
+#[pymethods]
+impl PyKalman {
+    #[new]
+    fn new(process_noise: f64, measurement_noise: f64) -> PyResult<PyKalman> {
+        Kalman::new(process_noise, measurement_noise)
+            .map(PyKalman)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    #[getter]
+    fn variance(&self) -> f64 {
+        self.0.variance()
+    }
+
+    fn update(&mut self, value: f64) -> f64 {
+        self.0.update(value)
+    }
+
+    fn apply(&mut self, values: Vec<f64>) -> Vec<f64> {
+        apply(&mut self.0, values)
+    }
+
+    fn reset(&mut self) {
+        self.0.reset()
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "gateway")]
+fn gateway_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyReading>()?;
+    m.add_class::<PySensorHub>()?;
+    m.add_class::<ReadingStream>()?;
+    m.add_class::<PyEma>()?;
+    m.add_class::<PyMovingAverage>()?;
+    m.add_class::<PyKalman>()?;
+    m.add("METRICS", METRICS.to_vec())?;
+    Ok(())
+}
@@ -0,0 +1,140 @@
+// Upstream stand-in: accept gateway connections and print received batches
+//
+//   cargo run --bin collector -- [bind address, default 127.0.0.1:7878]
+//
+// Messages already received, over any connection, are dropped (`ingest`).
+//
+// On Ctrl-C the collector stops accepting, lets each connection finish
+// the line it is reading, and prints what it ingested. Connections read
+// with a timeout so they can notice the shutdown between lines; a second
+// Ctrl-C exits at once.
+
+use gateway::ingest::{Ingest, DEFAULT_FP_RATE, DEFAULT_WINDOW};
+use shutdown::{handle_signals, join_within, Shutdown, Token};
+use std::io::{self, BufRead, BufReader};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const POLL: Duration = Duration::from_millis(100);
+const GRACE: Duration = Duration::from_secs(5);
+
+fn ingest_line(line: &[u8], peer: SocketAddr, ingest: &Mutex<Ingest>) {
+    let line = String::from_utf8_lossy(line);
+    let mut ingest = ingest.lock().unwrap_or_else(|e| e.into_inner());
+    let before = ingest.stats().duplicates;
+    match ingest.accept_line(line.trim_end()) {
+        Ok(batch) => {
+            let alerts = batch
+                .messages
+                .iter()
+                .filter(|m| m.topic.starts_with("alerts/"))
+                .count();
+            println!(
+                "collector: {} batch #{}: {} message(s), {} alert(s), {} duplicate(s) dropped",
+                batch.gateway,
+                batch.seq,
+                batch.messages.len(),
+                alerts,
+                ingest.stats().duplicates - before
+            );
+        }
+        Err(e) => println!("collector: bad batch from {}: {}", peer, e),
+    }
+}
+
+// One gateway connection. `read_until` keeps what it read before a
+// timeout in `line`, so a batch split across reads is never lost; the
+// connection only gives up on shutdown between lines.
+fn serve(stream: TcpStream, peer: SocketAddr, ingest: &Mutex<Ingest>, token: &Token) {
+    println!("collector: {} connected", peer);
+    let _ = stream.set_read_timeout(Some(POLL));
+    let mut reader = BufReader::new(stream);
+    let mut line = Vec::new();
+    loop {
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => break,
+            Ok(_) => {
+                ingest_line(&line, peer, ingest);
+                line.clear();
+            }
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                if token.is_cancelled() && line.is_empty() {
+                    println!("collector: closing {}", peer);
+                    return;
+                }
+            }
+            Err(_) => break,
+        }
+    }
+    println!("collector: {} disconnected", peer);
+}
+
+fn main() -> io::Result<()> {
+    let bind = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:7878".to_string());
+    let listener = TcpListener::bind(&bind)?;
+    listener.set_nonblocking(true)?;
+    println!("collector: listening on {}", listener.local_addr()?);
+    let ingest = Ingest::new(DEFAULT_WINDOW, DEFAULT_FP_RATE).expect("valid defaults");
+    println!(
+        "collector: remembering the last {} message ids in {} KiB",
+        DEFAULT_WINDOW,
+        ingest.memory_bytes() / 1024
+    );
+    let ingest = Arc::new(Mutex::new(ingest));
+
+    let shutdown = Shutdown::new();
+    if let Err(e) = handle_signals(&shutdown) {
+        eprintln!("collector: cannot install Ctrl-C handler: {}", e);
+    }
+    let token = shutdown.token();
+    let mut connections = Vec::new();
+    while !token.is_cancelled() {
+        let (stream, peer) = match listener.accept() {
+            Ok(accepted) => accepted,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                token.wait_timeout(POLL);
+                continue;
+            }
+            Err(e) => {
+                eprintln!("collector: accept failed: {}", e);
+                continue;
+            }
+        };
+        stream.set_nonblocking(false)?;
+        let (ingest, token) = (Arc::clone(&ingest), shutdown.token());
+        connections.retain(|c: &thread::JoinHandle<()>| !c.is_finished());
+        connections.push(
+            thread::Builder::new()
+                .name(peer.to_string())
+                .spawn(move || serve(stream, peer, &ingest, &token))?,
+        );
+    }
+
+    drop(listener);
+    connections.retain(|c| !c.is_finished());
+    println!(
+        "collector: shutting down, {} connection(s) open",
+        connections.len()
+    );
+    for peer in join_within(connections, GRACE) {
+        println!(
+            "collector: {} still mid-line after {:?}, dropped",
+            peer, GRACE
+        );
+    }
+    let stats = ingest.lock().unwrap_or_else(|e| e.into_inner()).stats();
+    println!(
+        "collector: {} batch(es), {} message(s), {} duplicate(s), {} rejected",
+        stats.batches, stats.messages, stats.duplicates, stats.rejected
+    );
+    Ok(())
+}
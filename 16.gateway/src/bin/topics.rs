@@ -0,0 +1,156 @@
+// Topic router walkthrough: the wildcard match table and retained messages
+//
+//   cargo run --bin topics
+
+use gateway::TopicRouter;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// (filter, topic) - MQTT 3.1.1 section 4.7 plus the usual traps; the
+// expected answers are in `SPEC_EXAMPLES` in tests/topicrouter.rs
+const MATCH_TABLE: &[(&str, &str)] = &[
+    // Exact levels
+    ("sport/tennis/player", "sport/tennis/player"),
+    ("sport/tennis/player", "sport/tennis"),
+    ("sport/tennis", "Sport/tennis"),
+    // '#' matches the parent level too, and any depth below it
+    ("sport/#", "sport"),
+    ("sport/#", "sport/tennis/player/ranking"),
+    ("sport/#", "sports"),
+    ("sport/tennis/#", "sport/tennis"),
+    ("#", "sport/tennis/player"),
+    // '+' matches exactly one level, which may be empty
+    ("sport/+/player", "sport/tennis/player"),
+    ("sport/+/player", "sport/player"),
+    ("sport/+/player", "sport/tennis/player/ranking"),
+    ("sport/+/player", "sport//player"),
+    ("sport/+", "sport"),
+    ("sport/+", "sport/"),
+    ("+", "sport"),
+    ("+", "/sport"),
+    ("+/+", "/sport"),
+    ("/+", "/sport"),
+    ("+/tennis/#", "sport/tennis"),
+    // '$' topics are hidden from leading wildcards
+    ("#", "$SYS/uptime"),
+    ("+/uptime", "$SYS/uptime"),
+    ("$SYS/#", "$SYS/uptime"),
+    ("$SYS/+", "$SYS/uptime"),
+];
+
+fn main() {
+    println!("=== Topic Router ===\n");
+
+    // 1. Match table
+    println!("1. Wildcard matching ({} cases):", MATCH_TABLE.len());
+    for &(filter, topic) in MATCH_TABLE {
+        let mut router = TopicRouter::new();
+        router.subscribe(filter, |_| {}).unwrap();
+        let matched = router.publish(topic, b"x", false).unwrap() == 1;
+        println!(
+            "   {:<20} {:<30} {}",
+            filter,
+            format!("{:?}", topic),
+            if matched { "match" } else { "no match" }
+        );
+    }
+
+    // 2. The classic confusion: sport/# vs sport/+/player
+    println!("\n2. sport/# vs sport/+/player:");
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut router = TopicRouter::new();
+    for filter in ["sport/#", "sport/+/player"] {
+        let log = Rc::clone(&log);
+        router
+            .subscribe(filter, move |d| {
+                log.borrow_mut().push(format!("{} <- {}", filter, d.topic))
+            })
+            .unwrap();
+    }
+    for topic in [
+        "sport",
+        "sport/tennis/player",
+        "sport/tennis/player/ranking",
+    ] {
+        let called = router.publish(topic, b"", false).unwrap();
+        println!("   publish {:<28} -> {} handler(s)", topic, called);
+    }
+    for line in log.borrow().iter() {
+        println!("     {}", line);
+    }
+
+    // 3. Invalid filters
+    println!("\n3. Invalid filters are rejected:");
+    for filter in ["sport/tennis#", "sport/#/player", "sport+", ""] {
+        match router.subscribe(filter, |_| {}) {
+            Ok(_) => println!("   {:<16} accepted?!", format!("{:?}", filter)),
+            Err(e) => println!("   {:<16} {}", format!("{:?}", filter), e),
+        }
+    }
+    match router.publish("sport/+/player", b"", false) {
+        Ok(_) => println!("   publish to a wildcard topic accepted?!"),
+        Err(e) => println!("   publish \"sport/+/player\": {}", e),
+    }
+
+    // 4. Retained messages
+    println!("\n4. Retained messages:");
+    let mut router = TopicRouter::new();
+    router
+        .publish("alerts/hot/0", br#"{"state":"raised"}"#, true)
+        .unwrap();
+    router
+        .publish("alerts/hot/1", br#"{"state":"cleared"}"#, true)
+        .unwrap();
+    router
+        .publish("sensors/0/temperature", b"21.5", false)
+        .unwrap();
+    println!("   retained topics: {}", router.retained_count());
+
+    let late = router
+        .subscribe("alerts/#", |d| {
+            println!(
+                "   late subscriber got {} {} (retained: {})",
+                d.topic,
+                String::from_utf8_lossy(d.payload),
+                d.retained
+            )
+        })
+        .unwrap();
+    router
+        .subscribe("sensors/#", |d| {
+            println!(
+                "   sensors/# got {} {} (live only)",
+                d.topic,
+                String::from_utf8_lossy(d.payload)
+            )
+        })
+        .unwrap();
+    router
+        .publish("sensors/0/temperature", b"21.7", false)
+        .unwrap();
+    router
+        .publish("alerts/hot/0", br#"{"state":"cleared"}"#, true)
+        .unwrap();
+    println!(
+        "   alerts/hot/0 now retains {}",
+        String::from_utf8_lossy(router.retained("alerts/hot/0").unwrap_or_default())
+    );
+
+    // An empty retained payload clears the topic
+    router.publish("alerts/hot/1", b"", true).unwrap();
+    println!(
+        "   after clearing alerts/hot/1: {} retained, alerts/hot/1 = {:?}",
+        router.retained_count(),
+        router.retained("alerts/hot/1")
+    );
+    println!(
+        "   unsubscribe late subscriber: {}",
+        router.unsubscribe(late)
+    );
+    println!(
+        "   remaining routes: {:?}",
+        router.filters().collect::<Vec<_>>()
+    );
+
+    println!("\n=== End of Topic Router ===");
+}
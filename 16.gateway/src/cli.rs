@@ -0,0 +1,142 @@
+// Command line of the gateway binary
+//
+//   gateway [--config FILE] [--data-dir DIR] [COMMAND]
+//
+//   run              the gateway loop (also what no command means)
+//   devices list     sensor nodes and their latest logged values
+//   send-command     a command to one node over the command protocol
+//   dump-config      the effective configuration as TOML
+//   replay-log       data log records, decoded
+//   completions      a shell completion script
+//
+// `--config` and `--data-dir` are global, so they work before or after the
+// command name. The flags of `run` are also accepted without the command,
+// which keeps `gateway --config gateway.toml` working as before; `check`
+// rejects them in front of any other command.
+
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "gateway",
+    version,
+    about = "Edge gateway: poll, filter, log and forward sensor data"
+)]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+impl Cli {
+    // What clap can't express: run flags given with another command
+    pub fn check(&self) -> Result<(), clap::Error> {
+        if self.command.is_some() && (self.run.print_config || self.run.profile) {
+            return Err(Cli::command().error(
+                ErrorKind::ArgumentConflict,
+                "--print-config and --profile belong to `run`",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct GlobalArgs {
+    /// TOML config file; GATEWAY_* environment variables override it
+    #[arg(short, long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+    /// Data log directory, instead of `datalog.dir`
+    #[arg(long, global = true, value_name = "DIR")]
+    pub data_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Commands {
+    /// Run the gateway loop until Ctrl-C or `gateway.run_seconds`
+    Run(RunArgs),
+    /// Inspect the sensor nodes
+    #[command(subcommand)]
+    Devices(DevicesCommand),
+    /// Send a command to a sensor node and wait for its ACK or NACK
+    SendCommand(SendCommandArgs),
+    /// Print the effective configuration (defaults, file, environment)
+    DumpConfig {
+        /// Print the built-in defaults instead
+        #[arg(long)]
+        defaults: bool,
+    },
+    /// Print the records in the data log
+    ReplayLog(ReplayArgs),
+    /// Print a completion script, e.g. `gateway completions bash > gateway.bash`
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+#[derive(Debug, Default, Args)]
+pub struct RunArgs {
+    /// Print the effective configuration at startup
+    #[arg(long)]
+    pub print_config: bool,
+    /// Print allocation reports per phase (needs the `memprofile` feature)
+    #[arg(long)]
+    pub profile: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DevicesCommand {
+    /// Each configured node with the latest value of each metric in the data log
+    List,
+}
+
+#[derive(Debug, Args)]
+pub struct SendCommandArgs {
+    /// Node id, 0 to `sensors.count - 1`
+    pub device: u16,
+    #[command(subcommand)]
+    pub command: DeviceCommand,
+    /// Simulated loss on the link, in percent each way
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u32).range(0..=100))]
+    pub loss: u32,
+    /// Give up when no response arrived within this many milliseconds
+    #[arg(long, default_value_t = 2000)]
+    pub ttl_ms: u32,
+}
+
+#[derive(Debug, Clone, Copy, Subcommand)]
+pub enum DeviceCommand {
+    Ping,
+    /// Set the reporting interval
+    SetInterval {
+        seconds: u32,
+    },
+    /// Read one sensor channel
+    ReadSensor {
+        channel: u8,
+    },
+    Reboot,
+}
+
+#[derive(Debug, Args)]
+pub struct ReplayArgs {
+    /// Only this node
+    #[arg(long)]
+    pub device: Option<u16>,
+    /// Only this metric, e.g. temperature
+    #[arg(long)]
+    pub metric: Option<String>,
+    /// Only the last N matching records
+    #[arg(long, value_name = "N")]
+    pub tail: Option<usize>,
+    /// CSV instead of a table
+    #[arg(long)]
+    pub csv: bool,
+}
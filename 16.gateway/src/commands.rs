@@ -0,0 +1,185 @@
+// The subcommands other than `run`
+//
+// Each takes the loaded config and writes to stdout. Errors come back as a
+// message for `main` to print, since all of them end the process the same
+// way.
+
+use clap::CommandFactory;
+use command_protocol::{step, Client, CommandKind, Device, LossyLink, Outcome, RetryPolicy};
+use datalog::{read_dir, Entry, Record};
+use gateway::cli::{DeviceCommand, ReplayArgs, SendCommandArgs};
+use gateway::gateway::split_channel;
+use gateway::sensors::METRICS;
+use gateway::Config;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+// One-way latency of the simulated link to a node
+const LINK_LATENCY_MS: u32 = 20;
+
+fn records(config: &Config) -> Result<(Vec<Record>, usize), String> {
+    let dir = &config.datalog.dir;
+    let entries =
+        read_dir(dir).map_err(|e| format!("cannot read data log {}: {}", dir.display(), e))?;
+    let mut records = Vec::new();
+    let mut problems = 0;
+    for entry in entries {
+        match entry {
+            Entry::Record(record) => records.push(record),
+            Entry::Corrupt { .. } | Entry::Torn { .. } => problems += 1,
+        }
+    }
+    Ok((records, problems))
+}
+
+pub fn devices_list(config: &Config) -> Result<(), String> {
+    let (records, _) = records(config)?;
+    // (node, metric) -> (count, latest record)
+    let mut latest: BTreeMap<(u16, &str), (u64, Record)> = BTreeMap::new();
+    for record in records {
+        if let (node, Some(metric)) = split_channel(record.sensor_id) {
+            let slot = latest.entry((node, metric)).or_insert((0, record));
+            slot.0 += 1;
+            if record.timestamp_ms >= slot.1.timestamp_ms {
+                slot.1 = record;
+            }
+        }
+    }
+    println!(
+        "{:<6} {:<12} {:>8} {:>10}  at (unix ms)",
+        "node", "metric", "records", "latest"
+    );
+    for node in 0..config.sensors.count {
+        for metric in METRICS {
+            match latest.get(&(node, metric)) {
+                Some((count, record)) => println!(
+                    "{:<6} {:<12} {:>8} {:>10.2}  {}",
+                    node, metric, count, record.value, record.timestamp_ms
+                ),
+                None => println!("{:<6} {:<12} {:>8} {:>10}  -", node, metric, 0, "-"),
+            }
+        }
+    }
+    // Logged by an earlier run with more sensors configured
+    let extra = latest
+        .keys()
+        .filter(|(node, _)| *node >= config.sensors.count)
+        .count();
+    if extra > 0 {
+        println!("({} channel(s) from nodes no longer configured)", extra);
+    }
+    Ok(())
+}
+
+// The nodes are simulated, so the command runs the protocol from
+// 14.command_protocol against a simulated device over a lossy link
+pub fn send_command(config: &Config, args: &SendCommandArgs) -> Result<(), String> {
+    if args.device >= config.sensors.count {
+        return Err(format!(
+            "no node {}: sensors.count is {}",
+            args.device, config.sensors.count
+        ));
+    }
+    let kind = match args.command {
+        DeviceCommand::Ping => CommandKind::Ping,
+        DeviceCommand::SetInterval { seconds } => CommandKind::SetInterval(seconds),
+        DeviceCommand::ReadSensor { channel } => CommandKind::ReadSensor(channel),
+        DeviceCommand::Reboot => CommandKind::Reboot,
+    };
+    let mut client = Client::new(RetryPolicy::default());
+    let mut device = Device::new();
+    let seed = config.sensors.seed ^ ((args.device as u32 + 1) * 0x9E37);
+    let mut downlink = LossyLink::new(LINK_LATENCY_MS, args.loss, seed);
+    let mut uplink = LossyLink::new(LINK_LATENCY_MS, args.loss, seed.rotate_left(16));
+
+    let seq = client.submit(kind, 0, args.ttl_ms);
+    let mut now = 0;
+    let outcome = loop {
+        step(&mut client, &mut device, &mut downlink, &mut uplink, now);
+        if let Some(outcome) = client.take_outcomes().into_iter().next() {
+            break outcome;
+        }
+        now += 1;
+    };
+    match outcome {
+        Outcome::Acked {
+            value, attempts, ..
+        } => {
+            println!(
+                "node {}: #{} {:?} ACK value={} after {} ms, {} attempt(s)",
+                args.device, seq, kind, value, now, attempts
+            );
+            Ok(())
+        }
+        Outcome::Nacked {
+            error, attempts, ..
+        } => Err(format!(
+            "node {}: #{} {:?} NACK {} after {} attempt(s)",
+            args.device, seq, kind, error, attempts
+        )),
+        Outcome::TimedOut { attempts, .. } => Err(format!(
+            "node {}: #{} {:?} timed out after {} ms, {} attempt(s)",
+            args.device, seq, kind, now, attempts
+        )),
+    }
+}
+
+pub fn dump_config(config: &Config) {
+    print!("{}", config.to_toml());
+}
+
+pub fn replay_log(config: &Config, args: &ReplayArgs) -> Result<(), String> {
+    let (records, problems) = records(config)?;
+    let selected: Vec<(u16, &str, Record)> = records
+        .into_iter()
+        .filter_map(|record| match split_channel(record.sensor_id) {
+            (node, Some(metric)) => Some((node, metric, record)),
+            (_, None) => None,
+        })
+        .filter(|(node, metric, _)| {
+            args.device.is_none_or(|d| d == *node)
+                && args.metric.as_deref().is_none_or(|m| m == *metric)
+        })
+        .collect();
+    let skip = args.tail.map_or(0, |n| selected.len().saturating_sub(n));
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut result = if args.csv {
+        writeln!(out, "timestamp_ms,node,metric,value")
+    } else {
+        Ok(())
+    };
+    for (node, metric, record) in &selected[skip..] {
+        if result.is_err() {
+            break;
+        }
+        result = if args.csv {
+            writeln!(
+                out,
+                "{},{},{},{}",
+                record.timestamp_ms, node, metric, record.value
+            )
+        } else {
+            writeln!(
+                out,
+                "{:>14} ms  node {:>3}  {:<12} {:>8.2}",
+                record.timestamp_ms, node, metric, record.value
+            )
+        };
+    }
+    match result {
+        // A closed pipe (e.g. `| head`) is not an error worth reporting
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e.to_string()),
+        _ => {}
+    }
+    if problems > 0 {
+        eprintln!("gateway: {} damaged record(s) skipped", problems);
+    }
+    Ok(())
+}
+
+pub fn completions(shell: clap_complete::Shell) {
+    let mut command = gateway::cli::Cli::command();
+    clap_complete::generate(shell, &mut command, "gateway", &mut io::stdout());
+}
@@ -0,0 +1,390 @@
+// Layered configuration: built-in defaults < TOML file < environment
+//
+// Every section derives `Default` and uses `#[serde(default)]`, so a config
+// file only needs the keys it wants to change. Environment variables named
+// `GATEWAY_<SECTION>_<KEY>` override both.
+//
+// `[flags]` is the config layer of the feature flags (93.flags); their
+// environment layer, `GATEWAY_FLAGS_<NAME>`, is kept apart from it there.
+
+use crate::{gateway, toggles};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub gateway: GatewayConfig,
+    pub sensors: SensorConfig,
+    pub filter: FilterConfig,
+    pub datalog: DatalogConfig,
+    pub status: StatusConfig,
+    pub uplink: UplinkConfig,
+    pub storage: StorageConfig,
+    pub schedule: ScheduleConfig,
+    pub control: ControlConfig,
+    // Flag name to "on", "off" or "N%"; see `toggles`
+    pub flags: BTreeMap<String, String>,
+    pub rules: Vec<RuleConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GatewayConfig {
+    pub id: String,
+    pub poll_interval_ms: u64,
+    // 0 runs until Ctrl-C
+    pub run_seconds: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SensorConfig {
+    pub count: u16,
+    pub seed: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterConfig {
+    // Exponential moving average weight of the newest sample (0..=1)
+    pub alpha: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatalogConfig {
+    pub dir: PathBuf,
+    pub max_file_bytes: u64,
+    pub max_files: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusConfig {
+    // Empty disables the status endpoint
+    pub bind: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UplinkConfig {
+    // Empty disables forwarding
+    pub addr: String,
+    pub batch_size: usize,
+    pub max_pending_batches: usize,
+    // Token bucket for sending batches: the long-run rate and the largest
+    // burst, e.g. after a reconnect. 0 disables the limit
+    pub max_batches_per_sec: f64,
+    pub burst_batches: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    // "sqlite" or "redb" (needs the matching cargo feature); empty disables
+    pub backend: String,
+    pub path: PathBuf,
+    // Readings per write transaction
+    pub batch_size: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleConfig {
+    // Cron expressions (UTC, see 73.cron); empty disables the job
+    pub calibration: String,
+    pub log_rotation: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ControlConfig {
+    // Unix socket for flag commands; empty disables it
+    pub socket: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Comparison {
+    Above,
+    Below,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleConfig {
+    pub name: String,
+    pub metric: String,
+    pub when: Comparison,
+    pub threshold: f64,
+    #[serde(default)]
+    pub hysteresis: f64,
+    #[serde(default)]
+    pub duration_ms: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            gateway: GatewayConfig::default(),
+            sensors: SensorConfig::default(),
+            filter: FilterConfig::default(),
+            datalog: DatalogConfig::default(),
+            status: StatusConfig::default(),
+            uplink: UplinkConfig::default(),
+            storage: StorageConfig::default(),
+            schedule: ScheduleConfig::default(),
+            control: ControlConfig::default(),
+            flags: BTreeMap::new(),
+            rules: vec![
+                RuleConfig {
+                    name: "hot".to_string(),
+                    metric: "temperature".to_string(),
+                    when: Comparison::Above,
+                    threshold: 30.0,
+                    hysteresis: 1.0,
+                    duration_ms: 500,
+                },
+                RuleConfig {
+                    name: "battery-low".to_string(),
+                    metric: "battery".to_string(),
+                    when: Comparison::Below,
+                    threshold: 3.4,
+                    hysteresis: 0.05,
+                    duration_ms: 0,
+                },
+            ],
+        }
+    }
+}
+
+impl Default for GatewayConfig {
+    fn default() -> Self {
+        GatewayConfig {
+            id: "gw-01".to_string(),
+            poll_interval_ms: 100,
+            run_seconds: 5,
+        }
+    }
+}
+
+impl Default for SensorConfig {
+    fn default() -> Self {
+        SensorConfig { count: 3, seed: 42 }
+    }
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        FilterConfig { alpha: 0.3 }
+    }
+}
+
+impl Default for DatalogConfig {
+    fn default() -> Self {
+        DatalogConfig {
+            dir: std::env::temp_dir().join("rust-sys-gateway-log"),
+            max_file_bytes: 64 * 1024,
+            max_files: 4,
+        }
+    }
+}
+
+impl Default for StatusConfig {
+    fn default() -> Self {
+        StatusConfig {
+            bind: "127.0.0.1:8080".to_string(),
+        }
+    }
+}
+
+impl Default for UplinkConfig {
+    fn default() -> Self {
+        UplinkConfig {
+            addr: String::new(),
+            batch_size: 20,
+            max_pending_batches: 50,
+            max_batches_per_sec: 5.0,
+            burst_batches: 10,
+        }
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig {
+            backend: String::new(),
+            path: std::env::temp_dir().join("rust-sys-gateway-history.db"),
+            batch_size: 60,
+        }
+    }
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        ScheduleConfig {
+            calibration: "0 */6 * * *".to_string(),
+            log_rotation: "0 0 * * *".to_string(),
+        }
+    }
+}
+
+impl Default for ControlConfig {
+    fn default() -> Self {
+        ControlConfig {
+            socket: std::env::temp_dir().join("rust-sys-gateway.sock"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, std::io::Error),
+    Parse(toml::de::Error),
+    Env { key: String, value: String },
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, e) => write!(f, "cannot read {}: {}", path.display(), e),
+            ConfigError::Parse(e) => write!(f, "invalid config file: {}", e),
+            ConfigError::Env { key, value } => write!(f, "invalid value {:?} for {}", value, key),
+            ConfigError::Invalid(msg) => write!(f, "invalid configuration: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+fn parse_env<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, ConfigError> {
+    value.parse().map_err(|_| ConfigError::Env {
+        key: key.to_string(),
+        value: value.to_string(),
+    })
+}
+
+impl Config {
+    pub fn from_toml(text: &str) -> Result<Config, ConfigError> {
+        toml::from_str(text).map_err(ConfigError::Parse)
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).expect("config is always serializable")
+    }
+
+    // Apply `GATEWAY_*` overrides from any source of key/value pairs
+    pub fn apply_env<I>(&mut self, vars: I) -> Result<Vec<String>, ConfigError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut applied = Vec::new();
+        for (key, value) in vars {
+            let v = value.as_str();
+            match key.as_str() {
+                "GATEWAY_GATEWAY_ID" => self.gateway.id = value.clone(),
+                "GATEWAY_GATEWAY_POLL_INTERVAL_MS" => {
+                    self.gateway.poll_interval_ms = parse_env(&key, v)?
+                }
+                "GATEWAY_GATEWAY_RUN_SECONDS" => self.gateway.run_seconds = parse_env(&key, v)?,
+                "GATEWAY_SENSORS_COUNT" => self.sensors.count = parse_env(&key, v)?,
+                "GATEWAY_SENSORS_SEED" => self.sensors.seed = parse_env(&key, v)?,
+                "GATEWAY_FILTER_ALPHA" => self.filter.alpha = parse_env(&key, v)?,
+                "GATEWAY_DATALOG_DIR" => self.datalog.dir = PathBuf::from(v),
+                "GATEWAY_STATUS_BIND" => self.status.bind = value.clone(),
+                "GATEWAY_UPLINK_ADDR" => self.uplink.addr = value.clone(),
+                "GATEWAY_UPLINK_BATCH_SIZE" => self.uplink.batch_size = parse_env(&key, v)?,
+                "GATEWAY_UPLINK_MAX_BATCHES_PER_SEC" => {
+                    self.uplink.max_batches_per_sec = parse_env(&key, v)?
+                }
+                "GATEWAY_STORAGE_BACKEND" => self.storage.backend = value.clone(),
+                "GATEWAY_STORAGE_PATH" => self.storage.path = PathBuf::from(v),
+                "GATEWAY_SCHEDULE_CALIBRATION" => self.schedule.calibration = value.clone(),
+                "GATEWAY_SCHEDULE_LOG_ROTATION" => self.schedule.log_rotation = value.clone(),
+                "GATEWAY_CONTROL_SOCKET" => self.control.socket = PathBuf::from(v),
+                _ => continue,
+            }
+            applied.push(key);
+        }
+        Ok(applied)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |msg: &str| Err(ConfigError::Invalid(msg.to_string()));
+        if self.gateway.poll_interval_ms == 0 {
+            return invalid("gateway.poll_interval_ms must be positive");
+        }
+        if !(0.0..=1.0).contains(&self.filter.alpha) || self.filter.alpha == 0.0 {
+            return invalid("filter.alpha must be in (0, 1]");
+        }
+        if self.uplink.batch_size == 0 {
+            return invalid("uplink.batch_size must be positive");
+        }
+        let rate = self.uplink.max_batches_per_sec;
+        if !rate.is_finite() || rate < 0.0 {
+            return invalid("uplink.max_batches_per_sec must be 0 or positive");
+        }
+        if self.sensors.count == 0 {
+            return invalid("sensors.count must be positive");
+        }
+        if self.sensors.count > gateway::MAX_SENSORS {
+            return invalid(&format!(
+                "sensors.count must be at most {}",
+                gateway::MAX_SENSORS
+            ));
+        }
+        if self.datalog.max_files == 0 {
+            return invalid("datalog.max_files must be positive");
+        }
+        if self.datalog.max_file_bytes < datalog::RECORD_SIZE as u64 {
+            return invalid(&format!(
+                "datalog.max_file_bytes must hold one record ({} bytes)",
+                datalog::RECORD_SIZE
+            ));
+        }
+        if !self.storage.backend.is_empty() {
+            // Only the name is checked here; whether the backend is
+            // compiled in is reported when the gateway opens it
+            if let Err(e) = self.storage.backend.parse::<storage::Backend>() {
+                return invalid(&format!("storage.backend: {}", e));
+            }
+            if self.storage.batch_size == 0 {
+                return invalid("storage.batch_size must be positive");
+            }
+        }
+        for (key, expr) in [
+            ("schedule.calibration", &self.schedule.calibration),
+            ("schedule.log_rotation", &self.schedule.log_rotation),
+        ] {
+            if !expr.is_empty() {
+                if let Err(e) = expr.parse::<cron::Schedule>() {
+                    return invalid(&format!("{}: {}", key, e));
+                }
+            }
+        }
+        for (name, value) in &self.flags {
+            if let Err(e) = toggles::check(name, value) {
+                return invalid(&format!("flags: {}", e));
+            }
+        }
+        Ok(())
+    }
+
+    // Defaults, then the file (if given), then the process environment
+    pub fn load(path: Option<&Path>) -> Result<(Config, Vec<String>), ConfigError> {
+        let mut config = match path {
+            Some(path) => {
+                let text = std::fs::read_to_string(path)
+                    .map_err(|e| ConfigError::Io(path.to_path_buf(), e))?;
+                Config::from_toml(&text)?
+            }
+            None => Config::default(),
+        };
+        let applied = config.apply_env(std::env::vars())?;
+        config.validate()?;
+        Ok((config, applied))
+    }
+}
@@ -0,0 +1,158 @@
+// What the gateway needs from the outside world
+//
+// Everything that touches the clock, the network, the disk or the sensors
+// goes through a trait, and `Parts` carries one of each into
+// `Gateway::with_parts`:
+//
+//   Clock         wall time for timestamps and cron, monotonic time for
+//                 uptime, reconnect backoff, the breaker and the rate limit
+//   Transport     the connection to the collector, one line per batch
+//   Storage       the reading history (49.storage)
+//   SensorSource  where readings come from
+//
+// `Parts::from_config` is the composition root: the one place that picks
+// concrete types (the system clock, TCP, the configured storage backend,
+// the simulated hub) and opens the data log. Nothing else in the gateway
+// names them. `doubles` has a stand-in for each trait, so tests build the
+// same gateway without a network, a disk or a sleep.
+
+use crate::config::Config;
+use crate::sensors::{Reading, SensorHub};
+use datalog::{DataLogger, LogConfig, Recovery};
+use std::io::{self, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::rc::Rc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub use retry::SystemClock;
+pub use storage::Storage;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+
+// `retry::Clock` (monotonic `now`, `sleep`) plus wall time. One clock is
+// shared by the loop, the uplink, its breaker and its rate limiter, so a
+// test that advances it moves all of them together.
+pub trait Clock: retry::Clock {
+    fn unix_ms(&self) -> u64;
+}
+
+impl Clock for SystemClock {
+    fn unix_ms(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+// A line-oriented connection to the collector. The uplink decides when to
+// connect and what to send; an error from `send` means the connection is
+// gone and the uplink will reconnect after a backoff.
+pub trait Transport {
+    // Where batches go, for log messages
+    fn peer(&self) -> &str;
+    fn connect(&mut self) -> io::Result<()>;
+    fn send(&mut self, line: &[u8]) -> io::Result<()>;
+    fn close(&mut self);
+}
+
+#[derive(Debug)]
+pub struct TcpTransport {
+    addr: String,
+    stream: Option<TcpStream>,
+}
+
+impl TcpTransport {
+    pub fn new(addr: &str) -> TcpTransport {
+        TcpTransport {
+            addr: addr.to_string(),
+            stream: None,
+        }
+    }
+}
+
+impl Transport for TcpTransport {
+    fn peer(&self) -> &str {
+        &self.addr
+    }
+
+    fn connect(&mut self) -> io::Result<()> {
+        let addr = self
+            .addr
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address"))?;
+        let stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)?;
+        stream.set_write_timeout(Some(CONNECT_TIMEOUT))?;
+        stream.set_nodelay(true)?;
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    fn send(&mut self, line: &[u8]) -> io::Result<()> {
+        match &mut self.stream {
+            Some(stream) => stream.write_all(line),
+            None => Err(io::ErrorKind::NotConnected.into()),
+        }
+    }
+
+    fn close(&mut self) {
+        self.stream = None;
+    }
+}
+
+// One poll of every sensor; `elapsed_ms` is time since the gateway
+// started, `timestamp_ms` the Unix time to stamp readings with
+pub trait SensorSource {
+    fn poll(&mut self, elapsed_ms: u64, timestamp_ms: u64) -> Vec<Reading>;
+}
+
+impl SensorSource for SensorHub {
+    fn poll(&mut self, elapsed_ms: u64, timestamp_ms: u64) -> Vec<Reading> {
+        SensorHub::poll(self, elapsed_ms, timestamp_ms)
+    }
+}
+
+// The gateway's dependencies. `transport` None means no uplink, `history`
+// None no reading history. The data log is the gateway's own file format
+// rather than a dependency with alternatives, so it has no trait; a
+// gateway built without one keeps readings in history and the uplink only.
+pub struct Parts {
+    pub clock: Rc<dyn Clock>,
+    pub sensors: Box<dyn SensorSource>,
+    pub transport: Option<Box<dyn Transport>>,
+    pub history: Option<Box<dyn Storage>>,
+    pub log: Option<DataLogger>,
+}
+
+impl Parts {
+    // The production wiring, from configuration
+    pub fn from_config(config: &Config) -> io::Result<(Parts, Recovery)> {
+        let log_config = LogConfig {
+            dir: config.datalog.dir.clone(),
+            max_file_bytes: config.datalog.max_file_bytes,
+            max_files: config.datalog.max_files,
+        };
+        let (log, recovery) = DataLogger::open(log_config)?;
+        let history = if config.storage.backend.is_empty() {
+            None
+        } else {
+            let backend = config.storage.backend.parse().map_err(io::Error::other)?;
+            Some(storage::open(backend, &config.storage.path).map_err(io::Error::other)?)
+        };
+        let transport = if config.uplink.addr.is_empty() {
+            None
+        } else {
+            Some(Box::new(TcpTransport::new(&config.uplink.addr)) as Box<dyn Transport>)
+        };
+
+        let parts = Parts {
+            clock: Rc::new(SystemClock),
+            sensors: Box::new(SensorHub::new(config.sensors.count, config.sensors.seed)),
+            transport,
+            history,
+            log: Some(log),
+        };
+        Ok((parts, recovery))
+    }
+}
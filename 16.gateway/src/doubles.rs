@@ -0,0 +1,217 @@
+// Stand-ins for the traits in `deps`
+//
+// Each double is a cheap handle over shared state, so a test keeps one
+// clone to drive or inspect it while the gateway owns another:
+//
+//   ManualClock      time moves only when `advance` (or `sleep`) is called
+//   MemoryTransport  records every line sent; `set_reachable(false)` makes
+//                    the collector unreachable until it is set back
+//   MemoryStorage    the `memory` backend from 49.storage
+//   ScriptedSensors  returns a fixed list of readings per poll
+//
+//   let clock = Rc::new(ManualClock::new(START_MS));
+//   let wire = MemoryTransport::new("collector");
+//   let parts = Parts {
+//       clock: clock.clone(),
+//       sensors: Box::new(ScriptedSensors::new().then(&[(0, "temperature", 21.0)])),
+//       transport: Some(Box::new(wire.clone())),
+//       history: Some(Box::new(MemoryStorage::new())),
+//       log: None,
+//   };
+//   let mut gw = Gateway::with_parts(config, parts);
+//
+// They are ordinary public types rather than `#[cfg(test)]`, so the
+// integration tests in `tests/` and other crates embedding the gateway can
+// use them.
+
+use crate::deps::{Clock, SensorSource, Transport};
+use crate::sensors::Reading;
+use crate::uplink::Batch;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::io;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+pub use storage::MemoryStorage;
+
+#[derive(Debug)]
+pub struct ManualClock {
+    start: Instant,
+    unix_start_ms: u64,
+    elapsed: Cell<Duration>,
+}
+
+impl ManualClock {
+    // Starts at `unix_ms` wall time and stays there
+    pub fn new(unix_ms: u64) -> ManualClock {
+        ManualClock {
+            start: Instant::now(),
+            unix_start_ms: unix_ms,
+            elapsed: Cell::new(Duration::ZERO),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed.set(self.elapsed.get() + duration);
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed.get()
+    }
+}
+
+impl retry::Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed.get()
+    }
+
+    // Returns at once, having moved the time on
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration)
+    }
+}
+
+impl Clock for ManualClock {
+    fn unix_ms(&self) -> u64 {
+        self.unix_start_ms + self.elapsed.get().as_millis() as u64
+    }
+}
+
+#[derive(Debug)]
+struct Wire {
+    reachable: bool,
+    connected: bool,
+    connects: u64,
+    lines: Vec<Vec<u8>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MemoryTransport {
+    peer: String,
+    wire: Rc<RefCell<Wire>>,
+}
+
+impl MemoryTransport {
+    pub fn new(peer: &str) -> MemoryTransport {
+        MemoryTransport {
+            peer: peer.to_string(),
+            wire: Rc::new(RefCell::new(Wire {
+                reachable: true,
+                connected: false,
+                connects: 0,
+                lines: Vec::new(),
+            })),
+        }
+    }
+
+    // An unreachable collector refuses connects and drops the current one
+    pub fn set_reachable(&self, reachable: bool) {
+        let mut wire = self.wire.borrow_mut();
+        wire.reachable = reachable;
+        wire.connected &= reachable;
+    }
+
+    // Whether the uplink holds a connection right now
+    pub fn is_connected(&self) -> bool {
+        self.wire.borrow().connected
+    }
+
+    // Successful connects so far
+    pub fn connects(&self) -> u64 {
+        self.wire.borrow().connects
+    }
+
+    pub fn lines(&self) -> Vec<Vec<u8>> {
+        self.wire.borrow().lines.clone()
+    }
+
+    // Every line sent, decoded the way the collector would
+    pub fn batches(&self) -> Vec<Batch> {
+        self.wire
+            .borrow()
+            .lines
+            .iter()
+            .map(|line| serde_json::from_slice(line).expect("the uplink sends batches"))
+            .collect()
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn peer(&self) -> &str {
+        &self.peer
+    }
+
+    fn connect(&mut self) -> io::Result<()> {
+        let mut wire = self.wire.borrow_mut();
+        if !wire.reachable {
+            return Err(io::ErrorKind::ConnectionRefused.into());
+        }
+        wire.connected = true;
+        wire.connects += 1;
+        Ok(())
+    }
+
+    fn send(&mut self, line: &[u8]) -> io::Result<()> {
+        let mut wire = self.wire.borrow_mut();
+        if !wire.connected {
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+        wire.lines.push(line.to_vec());
+        Ok(())
+    }
+
+    fn close(&mut self) {
+        self.wire.borrow_mut().connected = false;
+    }
+}
+
+// Readings for each poll in turn, then nothing
+#[derive(Debug, Default)]
+pub struct ScriptedSensors {
+    polls: VecDeque<Vec<(u16, &'static str, f64)>>,
+}
+
+impl ScriptedSensors {
+    pub fn new() -> ScriptedSensors {
+        ScriptedSensors::default()
+    }
+
+    // Appends one poll's worth of `(sensor_id, metric, value)`
+    pub fn then(mut self, readings: &[(u16, &'static str, f64)]) -> ScriptedSensors {
+        self.polls.push_back(readings.to_vec());
+        self
+    }
+
+    // The same poll `times` times over
+    pub fn repeat(
+        mut self,
+        times: usize,
+        readings: &[(u16, &'static str, f64)],
+    ) -> ScriptedSensors {
+        for _ in 0..times {
+            self = self.then(readings);
+        }
+        self
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.polls.len()
+    }
+}
+
+impl SensorSource for ScriptedSensors {
+    fn poll(&mut self, _elapsed_ms: u64, timestamp_ms: u64) -> Vec<Reading> {
+        self.polls
+            .pop_front()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(sensor_id, metric, value)| Reading {
+                sensor_id,
+                metric,
+                value,
+                timestamp_ms,
+            })
+            .collect()
+    }
+}
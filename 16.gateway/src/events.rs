@@ -0,0 +1,75 @@
+// Events on the gateway's internal bus
+//
+// The poll loop publishes one `ReadingFiltered` per reading. The rule
+// engine subscribes to those and posts `AlertRaised` / `AlertCleared`.
+// Every other subsystem subscribes to the types it needs and knows nothing
+// about who publishes them:
+//
+//   poll loop ──ReadingFiltered──┬──▶ rule engine ──AlertRaised, AlertCleared──┐
+//                                ├──▶ data log, history                        │
+//                                ├──▶ uplink batcher ◀─────────────────────────┤
+//                                └──▶ topic router ◀───────────────────────────┘
+//
+// The uplink and the router still speak topics, so each event knows its
+// topic (`sensors/<id>/<metric>`, `alerts/<rule>`) and JSON payload.
+
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadingFiltered {
+    pub sensor_id: u16,
+    pub metric: &'static str,
+    pub raw: f64,
+    pub value: f64,
+    // Unix ms, and ms since the gateway started (what the rules run on)
+    pub t: u64,
+    pub elapsed_ms: u64,
+}
+
+impl ReadingFiltered {
+    pub fn topic(&self) -> String {
+        format!("sensors/{}/{}", self.sensor_id, self.metric)
+    }
+
+    pub fn payload(&self) -> Value {
+        json!({ "t": self.t, "raw": self.raw, "value": self.value })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertRaised {
+    pub rule: String,
+    pub at_ms: u64,
+    pub t: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertCleared {
+    pub rule: String,
+    pub at_ms: u64,
+    pub t: u64,
+}
+
+fn alert_payload(t: u64, at_ms: u64, state: &str) -> Value {
+    json!({ "t": t, "at_ms": at_ms, "state": state })
+}
+
+impl AlertRaised {
+    pub fn topic(&self) -> String {
+        format!("alerts/{}", self.rule)
+    }
+
+    pub fn payload(&self) -> Value {
+        alert_payload(self.t, self.at_ms, "raised")
+    }
+}
+
+impl AlertCleared {
+    pub fn topic(&self) -> String {
+        format!("alerts/{}", self.rule)
+    }
+
+    pub fn payload(&self) -> Value {
+        alert_payload(self.t, self.at_ms, "cleared")
+    }
+}
@@ -0,0 +1,155 @@
+// Streaming filters applied to each sensor channel
+
+use std::collections::VecDeque;
+use std::fmt;
+
+pub trait Filter {
+    fn update(&mut self, value: f64) -> f64;
+    fn reset(&mut self);
+}
+
+// Exponential moving average
+#[derive(Debug, Clone)]
+pub struct Ema {
+    alpha: f64,
+    state: Option<f64>,
+}
+
+impl Ema {
+    pub fn new(alpha: f64) -> Ema {
+        Ema { alpha, state: None }
+    }
+
+    // Takes effect from the next sample, without restarting the average
+    pub fn set_alpha(&mut self, alpha: f64) {
+        self.alpha = alpha;
+    }
+}
+
+impl Filter for Ema {
+    fn update(&mut self, value: f64) -> f64 {
+        let next = match self.state {
+            Some(prev) => prev + self.alpha * (value - prev),
+            None => value,
+        };
+        self.state = Some(next);
+        next
+    }
+
+    fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+// Simple moving average over the last `window` samples
+#[derive(Debug, Clone)]
+pub struct MovingAverage {
+    window: usize,
+    samples: VecDeque<f64>,
+    sum: f64,
+}
+
+impl MovingAverage {
+    pub fn new(window: usize) -> MovingAverage {
+        MovingAverage {
+            window: window.max(1),
+            samples: VecDeque::new(),
+            sum: 0.0,
+        }
+    }
+}
+
+impl Filter for MovingAverage {
+    fn update(&mut self, value: f64) -> f64 {
+        self.samples.push_back(value);
+        self.sum += value;
+        if self.samples.len() > self.window {
+            self.sum -= self.samples.pop_front().unwrap_or(0.0);
+        }
+        self.sum / self.samples.len() as f64
+    }
+
+    fn reset(&mut self) {
+        self.samples.clear();
+        self.sum = 0.0;
+    }
+}
+
+// One-dimensional Kalman filter for a slowly drifting value
+//
+// The state is an estimate plus its variance. `process_noise` is how much
+// the true value may wander between samples, `measurement_noise` is the
+// sensor's variance. The gain K = P / (P + R) decides how far each reading
+// moves the estimate: a noisy sensor (large R) is trusted less.
+//
+// R must be positive: with R = 0 the first gain is 0 / 0 and the estimate
+// becomes NaN for good.
+#[derive(Debug, Clone)]
+pub struct Kalman {
+    process_noise: f64,
+    measurement_noise: f64,
+    estimate: Option<f64>,
+    variance: f64,
+}
+
+impl Kalman {
+    pub fn new(process_noise: f64, measurement_noise: f64) -> Result<Kalman, NoiseError> {
+        // Written so that NaN fails too
+        if !(process_noise >= 0.0 && measurement_noise > 0.0) || process_noise.is_infinite() {
+            return Err(NoiseError {
+                process_noise,
+                measurement_noise,
+            });
+        }
+        Ok(Kalman {
+            process_noise,
+            measurement_noise,
+            estimate: None,
+            variance: measurement_noise,
+        })
+    }
+
+    // Uncertainty of the current estimate
+    pub fn variance(&self) -> f64 {
+        self.variance
+    }
+}
+
+impl Filter for Kalman {
+    fn update(&mut self, value: f64) -> f64 {
+        let Some(estimate) = self.estimate else {
+            self.estimate = Some(value);
+            self.variance = self.measurement_noise;
+            return value;
+        };
+        let predicted = self.variance + self.process_noise;
+        let gain = predicted / (predicted + self.measurement_noise);
+        let next = estimate + gain * (value - estimate);
+        self.estimate = Some(next);
+        self.variance = (1.0 - gain) * predicted;
+        next
+    }
+
+    fn reset(&mut self) {
+        self.estimate = None;
+        self.variance = self.measurement_noise;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoiseError {
+    pub process_noise: f64,
+    pub measurement_noise: f64,
+}
+
+impl fmt::Display for NoiseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "process_noise must be >= 0 and measurement_noise > 0 (got {} and {})",
+            self.process_noise, self.measurement_noise
+        )
+    }
+}
+
+impl std::error::Error for NoiseError {}
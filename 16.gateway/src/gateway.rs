@@ -0,0 +1,700 @@
+// The gateway loop: poll -> filter -> publish -> forward
+//
+// Subsystems talk through a typed event bus (81.eventbus). The loop only
+// filters readings and publishes each as a `ReadingFiltered`; the rule
+// engine, the data log, the history batch and the uplink batcher are
+// subscribers wired up in `wire`, and none of them refers to another. The
+// rule engine posts `AlertRaised` / `AlertCleared`, which the uplink and the
+// loop's own alert list subscribe to. The `TopicRouter` is one more
+// subscriber: it republishes events on `sensors/<id>/<metric>` and
+// `alerts/<rule>` for in-process handlers, with alert state retained so a
+// late subscriber learns the current state at once.
+//
+// Periodic maintenance (calibration, log rotation) runs at the end of a
+// cycle when its cron schedule is due, so it never races the loop.
+//
+// With the `alert-flush` flag on (see `toggles`), a cycle that raises an
+// alert forwards the partial batch at once, so the alert doesn't wait for
+// the batch to fill.
+//
+// Besides the `Status` snapshot, the loop keeps `gateway_*` metrics in the
+// global registry (94.metrics) for `GET /metrics`: counters where things
+// happen, gauges for the queue depths once per cycle.
+//
+// `register_checks` gives the status server's `/healthz` and `/readyz`
+// (see `health`) a check per subsystem. They run on the server's thread,
+// so they read the `Status` the loop shares, or probe on their own.
+//
+// The clock, the sensors, the uplink's transport and the history store
+// arrive as trait objects in `Parts` (see `deps`); `new` gets the
+// production ones from `Parts::from_config`, and tests pass doubles to
+// `with_parts`.
+
+use crate::config::{Comparison, Config};
+use crate::deps::{Clock, Parts, SensorSource};
+use crate::events::{AlertCleared, AlertRaised, ReadingFiltered};
+use crate::filter::{Ema, Filter};
+use crate::health::{self, Health, HealthRegistry, Probe};
+use crate::sensors::METRICS;
+use crate::status::{SharedStatus, Status};
+use crate::toggles;
+use crate::topicrouter::{Delivery, RouteId, TopicRouter};
+use crate::uplink::{Batch, Uplink, UplinkMessage};
+use broker::TopicError;
+use cron::{Missed, Scheduler};
+use datalog::{DataLogger, Record, Recovery};
+use eventbus::{EventBus, SubscriptionId};
+use flags::Flag;
+use metrics::{counter, gauge, histogram};
+use rules::{AlertEvent, Condition, Rule, RuleEngine};
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use storage::Storage;
+
+// Periodic maintenance run from `tick` on cron schedules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Job {
+    Calibrate,
+    RotateLog,
+}
+
+fn build_schedule(config: &Config, now: i64) -> Scheduler<Job> {
+    let mut scheduler = Scheduler::new();
+    let jobs = [
+        // A calibration that was missed is just done at the next slot
+        (
+            "calibration",
+            &config.schedule.calibration,
+            Missed::Skip,
+            Job::Calibrate,
+        ),
+        // A missed rotation still starts one new file when the loop resumes
+        (
+            "log-rotation",
+            &config.schedule.log_rotation,
+            Missed::RunOnce,
+            Job::RotateLog,
+        ),
+    ];
+    for (name, expr, missed, job) in jobs {
+        if !expr.is_empty() {
+            let schedule = expr.parse().expect("validated with the config");
+            scheduler.add(name, schedule, missed, job, now);
+        }
+    }
+    scheduler
+}
+
+// One rule per configured rule per sensor node, named `<rule>/<node>`
+fn build_rules(config: &Config) -> Vec<Rule> {
+    let mut rules = Vec::new();
+    for id in 0..config.sensors.count {
+        for rc in &config.rules {
+            let metric = format!("{}/{}", id, rc.metric);
+            let condition = match rc.when {
+                Comparison::Above => Condition::above(&metric, rc.threshold),
+                Comparison::Below => Condition::below(&metric, rc.threshold),
+            }
+            .with_hysteresis(rc.hysteresis);
+            let name = format!("{}/{}", rc.name, id);
+            rules.push(Rule::new(&name, condition).for_at_least(rc.duration_ms));
+        }
+    }
+    rules
+}
+
+// Health checks that only read `Status`, and the one that writes a file
+const CHECK_TIMEOUT: Duration = Duration::from_millis(100);
+const DISK_TIMEOUT: Duration = Duration::from_secs(1);
+// The loop is stuck after this long, or ten poll intervals if longer
+const STALL_AFTER: Duration = Duration::from_secs(5);
+
+// Writes, syncs and removes a small file: the data log's directory is
+// still there, writable, and the storage under it answers
+fn probe_dir(dir: &Path) -> io::Result<()> {
+    let path = dir.join(".health");
+    let mut file = File::create(&path)?;
+    file.write_all(b"ok\n")?;
+    file.sync_all()?;
+    fs::remove_file(&path)
+}
+
+// Node ids that fit in the upper 12 bits of a data-log channel
+pub const MAX_SENSORS: u16 = 1 << 12;
+
+// Data-log channel: node id in the upper bits, metric index in the low nibble
+pub fn channel(sensor_id: u16, metric: &str) -> u16 {
+    let index = METRICS.iter().position(|m| *m == metric).unwrap_or(0xF);
+    sensor_id << 4 | index as u16
+}
+
+// The node id and metric name back from a data-log channel
+pub fn split_channel(channel: u16) -> (u16, Option<&'static str>) {
+    (channel >> 4, METRICS.get((channel & 0xF) as usize).copied())
+}
+
+// State a subscriber shares with the loop sits in an `Rc<RefCell<..>>`:
+// the bus owns the handler, the gateway keeps a second handle for status,
+// scheduled jobs and shutdown
+pub struct Gateway {
+    config: Config,
+    clock: Rc<dyn Clock>,
+    sensors: Box<dyn SensorSource>,
+    filters: HashMap<(u16, &'static str), Ema>,
+    bus: EventBus,
+    engine: Rc<RefCell<RuleEngine>>,
+    // None only for a gateway built without a data log (tests)
+    logger: Option<Rc<RefCell<DataLogger>>>,
+    // A handler can't return an error, so the logger's first one waits here
+    // for `tick` to return it
+    log_error: Rc<RefCell<Option<io::Error>>>,
+    // Queryable reading history, when `storage.backend` is set
+    history: Option<Box<dyn Storage>>,
+    history_batch: Rc<RefCell<Vec<storage::Reading>>>,
+    router: Rc<RefCell<TopicRouter>>,
+    uplink: Option<Uplink>,
+    batch: Rc<RefCell<Vec<UplinkMessage>>>,
+    batch_seq: u64,
+    boot: u64,
+    alert_flush: &'static Flag,
+    // This cycle's alert transitions, for `tick` to return
+    alerts: Rc<RefCell<Vec<AlertEvent>>>,
+    schedule: Scheduler<Job>,
+    status: SharedStatus,
+    started: Instant,
+    polls: u64,
+    readings: u64,
+    reporting: usize,
+    stored: u64,
+    alerts_raised: u64,
+    calibrations: u64,
+    log_rotations: u64,
+}
+
+impl Gateway {
+    pub fn new(config: Config) -> io::Result<(Gateway, Recovery)> {
+        let (parts, recovery) = Parts::from_config(&config)?;
+        Ok((Gateway::with_parts(config, parts), recovery))
+    }
+
+    // The gateway on whatever `parts` holds; `config` still sets rules,
+    // filters, batching and schedules
+    pub fn with_parts(config: Config, parts: Parts) -> Gateway {
+        let clock = parts.clock;
+        let uplink = parts.transport.map(|transport| {
+            let uplink = Uplink::new(
+                transport,
+                Rc::clone(&clock),
+                config.uplink.max_pending_batches,
+            );
+            let rate = config.uplink.max_batches_per_sec;
+            if rate > 0.0 {
+                uplink.with_rate_limit(config.uplink.burst_batches, rate)
+            } else {
+                uplink
+            }
+        });
+
+        toggles::apply(&config).expect("flags validated with the config");
+
+        let status = Arc::new(Mutex::new(Status {
+            gateway_id: config.gateway.id.clone(),
+            ..Status::default()
+        }));
+
+        let mut gateway = Gateway {
+            sensors: parts.sensors,
+            filters: HashMap::new(),
+            bus: EventBus::new(),
+            engine: Rc::new(RefCell::new(RuleEngine::new(build_rules(&config)))),
+            logger: parts.log.map(|log| Rc::new(RefCell::new(log))),
+            log_error: Rc::new(RefCell::new(None)),
+            history: parts.history,
+            history_batch: Rc::new(RefCell::new(Vec::new())),
+            router: Rc::new(RefCell::new(TopicRouter::new())),
+            uplink,
+            batch: Rc::new(RefCell::new(Vec::new())),
+            batch_seq: 0,
+            boot: clock.unix_ms(),
+            alert_flush: toggles::flag(toggles::ALERT_FLUSH),
+            alerts: Rc::new(RefCell::new(Vec::new())),
+            schedule: build_schedule(&config, (clock.unix_ms() / 1000) as i64),
+            status,
+            started: clock.now(),
+            clock,
+            polls: 0,
+            readings: 0,
+            reporting: 0,
+            stored: 0,
+            alerts_raised: 0,
+            calibrations: 0,
+            log_rotations: 0,
+            config,
+        };
+        gateway.wire();
+        gateway
+    }
+
+    // Handlers for one event type run in the order subscribed here. Alerts
+    // the rules post are delivered once the reading has reached everyone,
+    // so on the uplink a reading always precedes the alert it caused.
+    fn wire(&mut self) {
+        let bus = &mut self.bus;
+
+        // Rule engine: readings in, alert transitions out
+        let engine = Rc::clone(&self.engine);
+        let poster = bus.poster();
+        bus.subscribe(move |r: &ReadingFiltered| {
+            let metric = format!("{}/{}", r.sensor_id, r.metric);
+            let reading = rules::Reading::new(&metric, r.value, r.elapsed_ms);
+            for event in engine.borrow_mut().process(&reading) {
+                match event {
+                    AlertEvent::Raised { rule, at_ms } => poster.post(AlertRaised {
+                        rule,
+                        at_ms,
+                        t: r.t,
+                    }),
+                    AlertEvent::Cleared { rule, at_ms } => poster.post(AlertCleared {
+                        rule,
+                        at_ms,
+                        t: r.t,
+                    }),
+                }
+            }
+        });
+
+        // Data log and history batch, for whichever of the two exist
+        if let Some(logger) = &self.logger {
+            let logger = Rc::clone(logger);
+            let log_error = Rc::clone(&self.log_error);
+            bus.subscribe(move |r: &ReadingFiltered| {
+                let record = Record {
+                    timestamp_ms: r.t,
+                    sensor_id: channel(r.sensor_id, r.metric),
+                    flags: 0,
+                    value: r.value as f32,
+                };
+                if let Err(e) = logger.borrow_mut().append(&record) {
+                    log_error.borrow_mut().get_or_insert(e);
+                }
+            });
+        }
+        if self.history.is_some() {
+            let batch = Rc::clone(&self.history_batch);
+            bus.subscribe(move |r: &ReadingFiltered| {
+                batch
+                    .borrow_mut()
+                    .push(storage::Reading::new(r.sensor_id, r.metric, r.value, r.t));
+            });
+        }
+
+        // Alert transitions for `tick` to return
+        let alerts = Rc::clone(&self.alerts);
+        bus.subscribe(move |a: &AlertRaised| {
+            alerts.borrow_mut().push(AlertEvent::Raised {
+                rule: a.rule.clone(),
+                at_ms: a.at_ms,
+            })
+        });
+        let alerts = Rc::clone(&self.alerts);
+        bus.subscribe(move |a: &AlertCleared| {
+            alerts.borrow_mut().push(AlertEvent::Cleared {
+                rule: a.rule.clone(),
+                at_ms: a.at_ms,
+            })
+        });
+
+        // Uplink batcher: every event becomes a numbered topic message
+        let seq = Rc::new(RefCell::new(0u64));
+        let enqueue = {
+            let batch = Rc::clone(&self.batch);
+            move |topic: String, payload: serde_json::Value| {
+                let mut seq = seq.borrow_mut();
+                *seq += 1;
+                batch.borrow_mut().push(UplinkMessage {
+                    id: *seq,
+                    topic,
+                    payload,
+                });
+            }
+        };
+        let to_uplink = enqueue.clone();
+        bus.subscribe(move |r: &ReadingFiltered| to_uplink(r.topic(), r.payload()));
+        let to_uplink = enqueue.clone();
+        bus.subscribe(move |a: &AlertRaised| to_uplink(a.topic(), a.payload()));
+        bus.subscribe(move |a: &AlertCleared| enqueue(a.topic(), a.payload()));
+
+        // Topic router for in-process handlers; alert state is retained
+        let router = Rc::clone(&self.router);
+        bus.subscribe(move |r: &ReadingFiltered| {
+            let payload = r.payload().to_string();
+            let _ = router
+                .borrow_mut()
+                .publish(&r.topic(), payload.as_bytes(), false);
+        });
+        let router = Rc::clone(&self.router);
+        bus.subscribe(move |a: &AlertRaised| {
+            let payload = a.payload().to_string();
+            let _ = router
+                .borrow_mut()
+                .publish(&a.topic(), payload.as_bytes(), true);
+        });
+        let router = Rc::clone(&self.router);
+        bus.subscribe(move |a: &AlertCleared| {
+            let payload = a.payload().to_string();
+            let _ = router
+                .borrow_mut()
+                .publish(&a.topic(), payload.as_bytes(), true);
+        });
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    // Switches to a config that differs from the running one only in the
+    // live settings (see `reload::plan`): the filters take the new weight,
+    // the rules the new thresholds without losing their state, and the
+    // flags the new config layer. The poll interval is the caller's, read
+    // from `config()` every cycle.
+    pub fn reconfigure(&mut self, config: Config) {
+        if config.filter.alpha != self.config.filter.alpha {
+            for filter in self.filters.values_mut() {
+                filter.set_alpha(config.filter.alpha);
+            }
+        }
+        if config.rules != self.config.rules {
+            let mut engine = self.engine.borrow_mut();
+            for rule in build_rules(&config) {
+                engine.update(rule);
+            }
+        }
+        if config.flags != self.config.flags {
+            toggles::apply(&config).expect("flags validated with the config");
+        }
+        self.config = config;
+    }
+
+    pub fn status(&self) -> SharedStatus {
+        Arc::clone(&self.status)
+    }
+
+    // Liveness: the loop keeps polling. Readiness: the sensors report, and
+    // each of the uplink, data log and history that this gateway has works.
+    pub fn register_checks(&self, health: &HealthRegistry) {
+        let poll = Duration::from_millis(self.config.gateway.poll_interval_ms);
+        health.register(
+            "loop",
+            Probe::Liveness,
+            CHECK_TIMEOUT,
+            health::progress(self.status(), (poll * 10).max(STALL_AFTER)),
+        );
+
+        let status = self.status();
+        let expected = usize::from(self.config.sensors.count);
+        health.register("sensors", Probe::Readiness, CHECK_TIMEOUT, move || {
+            let s = status.lock().unwrap_or_else(|e| e.into_inner());
+            match s.sensors_reporting {
+                _ if s.polls == 0 => Health::Failed("no poll yet".to_string()),
+                0 => Health::Failed("no node reported in the last poll".to_string()),
+                n if n < expected => {
+                    Health::Degraded(format!("{} of {} nodes reported", n, expected))
+                }
+                _ => Health::Ok,
+            }
+        });
+
+        if self.uplink.is_some() {
+            let status = self.status();
+            let max_pending = self.config.uplink.max_pending_batches;
+            health.register("uplink", Probe::Readiness, CHECK_TIMEOUT, move || {
+                let s = status.lock().unwrap_or_else(|e| e.into_inner());
+                if s.batches_pending >= max_pending {
+                    Health::Failed(format!(
+                        "{} batches pending, new ones are dropped",
+                        s.batches_pending
+                    ))
+                } else if matches!(s.uplink_circuit.as_str(), "open" | "half-open") {
+                    Health::Degraded(format!(
+                        "circuit {}, {} batches pending",
+                        s.uplink_circuit, s.batches_pending
+                    ))
+                } else {
+                    Health::Ok
+                }
+            });
+        }
+
+        if self.logger.is_some() {
+            let dir = self.config.datalog.dir.clone();
+            health.register(
+                "datalog",
+                Probe::Readiness,
+                DISK_TIMEOUT,
+                move || match probe_dir(&dir) {
+                    Ok(()) => Health::Ok,
+                    Err(e) => Health::Failed(format!("{}: {}", dir.display(), e)),
+                },
+            );
+        }
+
+        if let Some(history) = &self.history {
+            let status = self.status();
+            let path = history
+                .backend()
+                .persistent()
+                .then(|| self.config.storage.path.clone());
+            let batch_size = self.config.storage.batch_size;
+            health.register("history", Probe::Readiness, CHECK_TIMEOUT, move || {
+                if let Some(path) = path.as_ref().filter(|p| !p.exists()) {
+                    return Health::Failed(format!("{} is missing", path.display()));
+                }
+                let queued = status
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .history_queued;
+                if queued > batch_size {
+                    Health::Degraded(format!("{} readings waiting to be written", queued))
+                } else {
+                    Health::Ok
+                }
+            });
+        }
+    }
+
+    // Call `handler` for every reading or alert whose topic matches `filter`
+    pub fn route<F>(&mut self, filter: &str, handler: F) -> Result<RouteId, TopicError>
+    where
+        F: FnMut(&Delivery) + 'static,
+    {
+        self.router.borrow_mut().subscribe(filter, handler)
+    }
+
+    pub fn unroute(&mut self, id: RouteId) -> bool {
+        self.router.borrow_mut().unsubscribe(id)
+    }
+
+    // Call `handler` with every event of type `E` (see `events`), after the
+    // gateway's own subscribers
+    pub fn subscribe<E, F>(&mut self, handler: F) -> SubscriptionId
+    where
+        E: Any,
+        F: FnMut(&E) + 'static,
+    {
+        self.bus.subscribe(handler)
+    }
+
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        self.bus.unsubscribe(id)
+    }
+
+    pub fn history(&self) -> Option<&dyn Storage> {
+        self.history.as_deref()
+    }
+
+    pub fn elapsed_ms(&self) -> u64 {
+        (self.clock.now() - self.started).as_millis() as u64
+    }
+
+    // One poll cycle; returns the alert transitions it caused
+    pub fn tick(&mut self) -> io::Result<Vec<AlertEvent>> {
+        let elapsed = self.elapsed_ms();
+        let now = self.clock.unix_ms();
+        let readings = self.sensors.poll(elapsed, now);
+        let alpha = self.config.filter.alpha;
+
+        for reading in &readings {
+            let filter = self
+                .filters
+                .entry((reading.sensor_id, reading.metric))
+                .or_insert_with(|| Ema::new(alpha));
+            let value = filter.update(reading.value);
+            self.bus.publish(ReadingFiltered {
+                sensor_id: reading.sensor_id,
+                metric: reading.metric,
+                raw: reading.value,
+                value,
+                t: now,
+                elapsed_ms: elapsed,
+            });
+            if let Some(e) = self.log_error.borrow_mut().take() {
+                return Err(e);
+            }
+        }
+        let events: Vec<AlertEvent> = self.alerts.borrow_mut().drain(..).collect();
+
+        if self.history_batch.borrow().len() >= self.config.storage.batch_size {
+            self.flush_history()?;
+        }
+
+        let raised = events
+            .iter()
+            .filter(|e| matches!(e, AlertEvent::Raised { .. }))
+            .count() as u64;
+        self.polls += 1;
+        self.readings += readings.len() as u64;
+        self.reporting = readings
+            .iter()
+            .map(|r| r.sensor_id)
+            .collect::<HashSet<_>>()
+            .len();
+        self.alerts_raised += raised;
+        counter!("gateway_polls_total", "Poll cycles run").inc();
+        counter!("gateway_readings_total", "Sensor readings polled").add(readings.len() as u64);
+        counter!("gateway_alerts_raised_total", "Alerts raised by the rules").add(raised);
+        self.forward(raised > 0 && self.alert_flush.enabled(), false);
+        self.run_jobs((now / 1000) as i64)?;
+        self.update_status();
+        Ok(events)
+    }
+
+    // The scheduled jobs that are due, in the loop like everything else
+    fn run_jobs(&mut self, now: i64) -> io::Result<()> {
+        let mut due = Vec::new();
+        self.schedule.run_due(now, |_, job, _| due.push(*job));
+        for job in due {
+            match job {
+                // The simulated sensors have no calibration procedure, so
+                // this only restarts the filters from the next raw reading
+                Job::Calibrate => {
+                    self.filters.clear();
+                    self.calibrations += 1;
+                }
+                Job::RotateLog => {
+                    if let Some(logger) = &self.logger {
+                        logger.borrow_mut().rotate()?;
+                        self.log_rotations += 1;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Name, expression and next fire time (Unix seconds) of each job
+    pub fn scheduled_jobs(&self) -> Vec<(String, String, Option<i64>)> {
+        self.schedule
+            .jobs()
+            .into_iter()
+            .map(|job| (job.name, job.schedule, job.next))
+            .collect()
+    }
+
+    // One write transaction per batch instead of one per reading
+    fn flush_history(&mut self) -> io::Result<()> {
+        if let Some(history) = &mut self.history {
+            let mut batch = self.history_batch.borrow_mut();
+            if !batch.is_empty() {
+                let written = history.append(&batch).map_err(io::Error::other)?;
+                self.stored += written as u64;
+                batch.clear();
+            }
+        }
+        Ok(())
+    }
+
+    // Hand full batches of the queued messages to the uplink, and with
+    // `partial` the rest as a smaller one. `force` is for shutdown (see
+    // `Uplink::flush`).
+    fn forward(&mut self, partial: bool, force: bool) {
+        let batch_size = self.config.uplink.batch_size;
+        let mut queued = self.batch.borrow_mut();
+        while queued.len() >= batch_size || (partial && !queued.is_empty()) {
+            let take = queued.len().min(batch_size);
+            let messages: Vec<UplinkMessage> = queued.drain(..take).collect();
+            histogram!(
+                "gateway_batch_messages",
+                "Messages per uplink batch",
+                &[1.0, 5.0, 10.0, 20.0, 50.0]
+            )
+            .observe(messages.len() as f64);
+            self.batch_seq += 1;
+            let batch = Batch {
+                gateway: self.config.gateway.id.clone(),
+                boot: self.boot,
+                seq: self.batch_seq,
+                messages,
+            };
+            if let Some(uplink) = &mut self.uplink {
+                uplink.enqueue(batch);
+            }
+        }
+
+        if let Some(uplink) = &mut self.uplink {
+            uplink.flush(force);
+        }
+    }
+
+    fn update_status(&self) {
+        let mut status = self.status.lock().unwrap();
+        status.uptime_ms = self.elapsed_ms();
+        status.polls = self.polls;
+        status.readings = self.readings;
+        status.sensors_reporting = self.reporting;
+        status.logged_records = if self.logger.is_some() {
+            self.readings
+        } else {
+            0
+        };
+        status.stored_readings = self.stored;
+        status.alerts_raised = self.alerts_raised;
+        status.calibrations = self.calibrations;
+        status.log_rotations = self.log_rotations;
+        status.batch_queued = self.batch.borrow().len();
+        status.history_queued = self.history_batch.borrow().len();
+        status.active_alerts = self
+            .engine
+            .borrow()
+            .active()
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        gauge!("gateway_active_alerts", "Alerts raised and not yet cleared")
+            .set(status.active_alerts.len() as f64);
+        gauge!(
+            "gateway_batch_queued_messages",
+            "Messages waiting for a full uplink batch"
+        )
+        .set(status.batch_queued as f64);
+        let events = self.bus.stats();
+        status.events_published = events.published;
+        status.events_delivered = events.delivered;
+        if let Some(uplink) = &self.uplink {
+            let stats = uplink.stats();
+            status.batches_sent = stats.sent;
+            status.batches_pending = stats.pending;
+            gauge!(
+                "gateway_uplink_pending_batches",
+                "Batches waiting for the uplink"
+            )
+            .set(stats.pending as f64);
+            status.batches_dropped = stats.dropped;
+            status.uplink_errors = stats.errors;
+            status.uplink_circuit = stats.circuit.to_string();
+            status.uplink_circuit_trips = stats.circuit_trips;
+        }
+    }
+
+    // Flush partial batches and the data log and hang up on the collector;
+    // returns the final status
+    pub fn shutdown(mut self) -> io::Result<Status> {
+        self.forward(true, true);
+        if let Some(uplink) = &mut self.uplink {
+            uplink.close();
+        }
+        self.flush_history()?;
+        if let Some(logger) = &self.logger {
+            logger.borrow_mut().sync()?;
+        }
+        self.update_status();
+        let status = self.status.lock().unwrap().clone();
+        Ok(status)
+    }
+}
@@ -0,0 +1,249 @@
+// Health checks behind `GET /healthz` and `GET /readyz`
+//
+//   register("uplink", Readiness, 200 ms, || ...) ──▶ HealthRegistry
+//                                                        │ run(probe)
+//                      one thread per check ◀────────────┤
+//                      result, or "timed out" ───────────┴──▶ Report ──▶ JSON, 200 or 503
+//
+// Liveness asks "is the process stuck?": if it fails, restarting helps,
+// so it only covers the gateway's own loop. Readiness asks "is it doing
+// its job?", and covers the dependencies too: sensors, the uplink, the
+// data log, the history store. A collector being down makes the gateway
+// not ready, but restarting it wouldn't bring the collector back.
+//
+// A check returns `Ok`, `Degraded` (working, but something needs looking
+// at, e.g. batches queuing behind an open circuit) or `Failed`. The
+// overall state is the worst of them; only `Failed` makes the response a
+// 503, so a degraded gateway stays in service.
+//
+// Checks run on the status server's thread, not the loop's, so they read
+// what the loop shares (`Status`) or do their own small probe, such as
+// writing a file. Each runs on a thread of its own with a timeout, so a
+// probe stuck on a dead SD card can't hang the endpoint. A check that
+// timed out and is still running isn't started again: the next probe
+// reports it as failed at once instead of piling up threads.
+
+use crate::status::SharedStatus;
+use retry::{Clock, SystemClock};
+use serde::Serialize;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Health {
+    Ok,
+    Degraded(String),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum State {
+    Ok,
+    Degraded,
+    Failed,
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            State::Ok => "ok",
+            State::Degraded => "degraded",
+            State::Failed => "failed",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Which endpoint a check belongs to; readiness runs the liveness checks too
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Probe {
+    Liveness,
+    Readiness,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CheckReport {
+    pub name: String,
+    pub status: State,
+    pub latency_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Report {
+    pub status: State,
+    pub checks: Vec<CheckReport>,
+}
+
+impl Report {
+    pub fn http_status(&self) -> &'static str {
+        match self.status {
+            State::Ok | State::Degraded => "200 OK",
+            State::Failed => "503 Service Unavailable",
+        }
+    }
+}
+
+type CheckFn = Box<dyn Fn() -> Health + Send + Sync>;
+
+struct Check {
+    name: String,
+    probe: Probe,
+    timeout: Duration,
+    check: CheckFn,
+    running: AtomicBool,
+}
+
+#[derive(Default)]
+pub struct HealthRegistry {
+    checks: Mutex<Vec<Arc<Check>>>,
+}
+
+pub type SharedHealth = Arc<HealthRegistry>;
+
+impl HealthRegistry {
+    pub fn new() -> HealthRegistry {
+        HealthRegistry::default()
+    }
+
+    pub fn register<F>(&self, name: &str, probe: Probe, timeout: Duration, check: F)
+    where
+        F: Fn() -> Health + Send + Sync + 'static,
+    {
+        self.checks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Arc::new(Check {
+                name: name.to_string(),
+                probe,
+                timeout,
+                check: Box::new(check),
+                running: AtomicBool::new(false),
+            }));
+    }
+
+    pub fn names(&self) -> Vec<(String, Probe)> {
+        let checks = self.checks.lock().unwrap_or_else(|e| e.into_inner());
+        checks.iter().map(|c| (c.name.clone(), c.probe)).collect()
+    }
+
+    // Runs the checks for `probe` at the same time and waits for each
+    // until its own timeout; reports them in registration order
+    pub fn run(&self, probe: Probe) -> Report {
+        let checks: Vec<Arc<Check>> = self
+            .checks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|c| probe == Probe::Readiness || c.probe == Probe::Liveness)
+            .cloned()
+            .collect();
+        let started = Instant::now();
+        let pending: Vec<_> = checks.into_iter().map(start).collect();
+        let checks: Vec<CheckReport> = pending
+            .into_iter()
+            .map(|(check, rx)| {
+                let (health, latency) = match rx {
+                    None => (
+                        Health::Failed("still running after an earlier timeout".to_string()),
+                        Duration::ZERO,
+                    ),
+                    Some(rx) => {
+                        let left = check.timeout.saturating_sub(started.elapsed());
+                        match rx.recv_timeout(left) {
+                            Ok(done) => done,
+                            Err(RecvTimeoutError::Timeout) => (
+                                Health::Failed(format!(
+                                    "timed out after {} ms",
+                                    check.timeout.as_millis()
+                                )),
+                                check.timeout,
+                            ),
+                            Err(RecvTimeoutError::Disconnected) => (
+                                Health::Failed("check panicked".to_string()),
+                                started.elapsed(),
+                            ),
+                        }
+                    }
+                };
+                let (status, message) = match health {
+                    Health::Ok => (State::Ok, None),
+                    Health::Degraded(m) => (State::Degraded, Some(m)),
+                    Health::Failed(m) => (State::Failed, Some(m)),
+                };
+                CheckReport {
+                    name: check.name.clone(),
+                    status,
+                    latency_ms: latency.as_secs_f64() * 1000.0,
+                    message,
+                }
+            })
+            .collect();
+        Report {
+            status: checks.iter().map(|c| c.status).max().unwrap_or(State::Ok),
+            checks,
+        }
+    }
+}
+
+type Pending = (Arc<Check>, Option<mpsc::Receiver<(Health, Duration)>>);
+
+// Starts `check` on its own thread, unless it is still running
+fn start(check: Arc<Check>) -> Pending {
+    if check.running.swap(true, Ordering::Acquire) {
+        return (check, None);
+    }
+    let (tx, rx) = mpsc::channel();
+    let worker = Arc::clone(&check);
+    thread::spawn(move || {
+        let t0 = Instant::now();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| (worker.check)()));
+        // Before the result is sent, so the next probe may start it again
+        worker.running.store(false, Ordering::Release);
+        // A panic drops `tx`, which the caller reports
+        if let Ok(health) = result {
+            let _ = tx.send((health, t0.elapsed()));
+        }
+    });
+    (check, Some(rx))
+}
+
+// A liveness check: failed once the loop's poll count hasn't moved for
+// `stall_after`. The gateway's clock may be simulated, so the time since
+// the last change is measured here, in wall time.
+pub fn progress(status: SharedStatus, stall_after: Duration) -> impl Fn() -> Health {
+    progress_with_clock(status, stall_after, SystemClock)
+}
+
+// `progress` timed by `clock`, so a test can stall the loop without waiting
+pub fn progress_with_clock<C>(
+    status: SharedStatus,
+    stall_after: Duration,
+    clock: C,
+) -> impl Fn() -> Health
+where
+    C: Clock + Send + Sync + 'static,
+{
+    let last = Mutex::new((u64::MAX, clock.now()));
+    move || {
+        let polls = status.lock().unwrap_or_else(|e| e.into_inner()).polls;
+        let mut last = last.lock().unwrap_or_else(|e| e.into_inner());
+        let now = clock.now();
+        if polls != last.0 {
+            *last = (polls, now);
+        }
+        let idle = now.duration_since(last.1);
+        if idle > stall_after {
+            Health::Failed(format!("no poll cycle for {} ms", idle.as_millis()))
+        } else {
+            Health::Ok
+        }
+    }
+}
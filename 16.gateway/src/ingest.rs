@@ -0,0 +1,75 @@
+// Collector-side ingest: parse uplink batches, drop repeated messages
+//
+// The uplink delivers at least once, and a collector behind a load
+// balancer may hear from the same gateway over several connections, so
+// the same message can arrive more than once. Remembering every id in a
+// set grows without bound; a `bloom::Dedup` remembers the most recent
+// `window` ids in fixed memory.
+//
+// The price is that a new message is occasionally taken for a repeat and
+// dropped, at the configured false-positive rate. The default (one in a
+// million) costs about 60 bits per id in the window, 737 KiB in all.
+
+use crate::uplink::Batch;
+use bloom::{BloomError, Dedup};
+
+pub const DEFAULT_WINDOW: usize = 100_000;
+pub const DEFAULT_FP_RATE: f64 = 1e-6;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IngestStats {
+    pub batches: u64,
+    pub messages: u64,
+    pub duplicates: u64,
+    pub rejected: u64,
+}
+
+#[derive(Debug)]
+pub struct Ingest {
+    seen: Dedup,
+    stats: IngestStats,
+}
+
+impl Ingest {
+    pub fn new(window: usize, fp_rate: f64) -> Result<Ingest, BloomError> {
+        Ok(Ingest {
+            seen: Dedup::new(window, fp_rate)?,
+            stats: IngestStats::default(),
+        })
+    }
+
+    // The batch without the messages seen before. Messages with no id
+    // (from older gateways) can't be checked and are kept.
+    pub fn accept(&mut self, mut batch: Batch) -> Batch {
+        self.stats.batches += 1;
+        let before = batch.messages.len();
+        let (gateway, boot) = (batch.gateway.as_str(), batch.boot);
+        let seen = &mut self.seen;
+        batch
+            .messages
+            .retain(|m| m.id == 0 || seen.check(&(gateway, boot, m.id)));
+        let kept = batch.messages.len();
+        self.stats.messages += kept as u64;
+        self.stats.duplicates += (before - kept) as u64;
+        batch
+    }
+
+    // One line of the uplink protocol
+    pub fn accept_line(&mut self, line: &str) -> Result<Batch, serde_json::Error> {
+        match serde_json::from_str::<Batch>(line) {
+            Ok(batch) => Ok(self.accept(batch)),
+            Err(e) => {
+                self.stats.rejected += 1;
+                Err(e)
+            }
+        }
+    }
+
+    pub fn stats(&self) -> IngestStats {
+        self.stats
+    }
+
+    pub fn memory_bytes(&self) -> usize {
+        self.seen.memory_bytes()
+    }
+}
@@ -0,0 +1,509 @@
+mod commands;
+
+use clap::Parser;
+use flags::{ControlSocket, FLAGS};
+use gateway::cli::{Cli, Commands, DevicesCommand, RunArgs};
+use gateway::health::HealthRegistry;
+use gateway::reload::{self, ConfigWatcher};
+use gateway::status::StatusServer;
+use gateway::toggles;
+use gateway::{AlertCleared, AlertRaised, Config, ConfigError, Gateway};
+use shutdown::{handle_signals, Reason, Shutdown};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// How long the config file must be left alone after a change before it
+// is reloaded; an editor's save is several events in quick succession
+const RELOAD_SETTLE: Duration = Duration::from_millis(250);
+
+// Buckets for the time one poll cycle takes, 100 µs to 25 ms
+const CYCLE_BUCKETS: &[f64] = &[0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025];
+
+#[cfg(feature = "memprofile")]
+#[global_allocator]
+static GLOBAL: memprofile::Profiler = memprofile::Profiler;
+
+// Allocation reports per phase for --profile. Each `begin` closes the
+// previous phase's scope; the counters are process-wide, so the status
+// server and uplink threads count toward whichever phase is open
+#[cfg(feature = "memprofile")]
+struct Phases {
+    enabled: bool,
+    current: Option<memprofile::Scope>,
+    reports: Vec<memprofile::ScopeReport>,
+}
+
+#[cfg(feature = "memprofile")]
+impl Phases {
+    fn new(enabled: bool) -> Self {
+        Phases {
+            enabled,
+            current: None,
+            // Allocated up front so pushing a report never shows up in one
+            reports: Vec::with_capacity(4),
+        }
+    }
+
+    fn begin(&mut self, name: &'static str) {
+        self.end();
+        if self.enabled {
+            self.current = Some(memprofile::scope(name));
+        }
+    }
+
+    fn end(&mut self) {
+        if let Some(scope) = self.current.take() {
+            self.reports.push(scope.finish());
+        }
+    }
+
+    fn print(mut self) {
+        self.end();
+        if !self.enabled {
+            return;
+        }
+        println!("\n5. Allocation profile:");
+        for report in &self.reports {
+            println!("   {}", report);
+        }
+        let total = memprofile::stats();
+        println!(
+            "   process: {} allocs, {} live, peak {}",
+            total.allocs,
+            memprofile::human(total.live_bytes),
+            memprofile::human(total.peak_bytes)
+        );
+    }
+}
+
+// Without the feature `--profile` is rejected, so these never record
+#[cfg(not(feature = "memprofile"))]
+struct Phases;
+
+#[cfg(not(feature = "memprofile"))]
+impl Phases {
+    fn new(_enabled: bool) -> Self {
+        Phases
+    }
+
+    fn begin(&mut self, _name: &'static str) {}
+
+    fn print(self) {}
+}
+
+// The status listener from a systemd socket unit, if started by one
+#[cfg(feature = "systemd")]
+fn activated_listener() -> Option<TcpListener> {
+    match systemd::take_listen_fds() {
+        Ok(fds) => fds
+            .into_iter()
+            .find(systemd::ListenFd::is_tcp_listener)
+            .and_then(|fd| fd.into_tcp_listener().ok()),
+        Err(e) => {
+            eprintln!("gateway: ignoring socket activation: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "systemd"))]
+fn activated_listener() -> Option<TcpListener> {
+    None
+}
+
+// The file and environment, then the command line on top
+fn load_config(
+    path: Option<&Path>,
+    data_dir: Option<&Path>,
+) -> Result<(Config, Vec<String>), ConfigError> {
+    let (mut config, overrides) = Config::load(path)?;
+    if let Some(dir) = data_dir {
+        config.datalog.dir = dir.to_path_buf();
+    }
+    Ok((config, overrides))
+}
+
+// Loads the changed file and applies what can change without a restart
+fn reload_config(path: &Path, data_dir: Option<&Path>, gw: &mut Gateway) {
+    let candidate = match load_config(Some(path), data_dir) {
+        Ok((candidate, _)) => candidate,
+        Err(e) => {
+            println!(
+                "   config reload rejected, keeping the running config: {}",
+                e
+            );
+            return;
+        }
+    };
+    match reload::plan(gw.config(), &candidate) {
+        Ok(plan) if plan.is_empty() => {}
+        Ok(plan) => {
+            for change in &plan.applied {
+                println!("   config reload: {}", change);
+            }
+            for change in &plan.rejected {
+                println!("   config reload: {} (needs a restart)", change);
+            }
+            gw.reconfigure(plan.config);
+        }
+        Err(e) => println!(
+            "   config reload rejected, keeping the running config: {}",
+            e
+        ),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Err(e) = cli.check() {
+        e.exit();
+    }
+    // Needs no config, so it works even where the config doesn't load
+    if let Some(Commands::Completions { shell }) = cli.command {
+        commands::completions(shell);
+        return ExitCode::SUCCESS;
+    }
+    let config_path = cli.global.config.as_deref();
+    let data_dir = cli.global.data_dir.as_deref();
+    let (config, overrides) = match load_config(config_path, data_dir) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("gateway: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let result = match &cli.command {
+        None => return run(config_path, data_dir, config, overrides, &cli.run),
+        Some(Commands::Run(args)) => return run(config_path, data_dir, config, overrides, args),
+        Some(Commands::Devices(DevicesCommand::List)) => commands::devices_list(&config),
+        Some(Commands::SendCommand(args)) => commands::send_command(&config, args),
+        Some(Commands::DumpConfig { defaults: true }) => {
+            commands::dump_config(&Config::default());
+            Ok(())
+        }
+        Some(Commands::DumpConfig { defaults: false }) => {
+            commands::dump_config(&config);
+            Ok(())
+        }
+        Some(Commands::ReplayLog(args)) => commands::replay_log(&config, args),
+        Some(Commands::Completions { .. }) => unreachable!("handled above"),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("gateway: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(
+    config_path: Option<&Path>,
+    data_dir: Option<&Path>,
+    config: Config,
+    overrides: Vec<String>,
+    args: &RunArgs,
+) -> ExitCode {
+    if args.profile && cfg!(not(feature = "memprofile")) {
+        eprintln!("gateway: --profile needs a build with `--features memprofile`");
+        return ExitCode::from(2);
+    }
+    let mut phases = Phases::new(args.profile);
+
+    println!("=== Edge Gateway ===\n");
+
+    phases.begin("startup");
+
+    // 1. Layered configuration
+    println!("1. Configuration:");
+    match config_path {
+        Some(path) => println!("   file: {}", path.display()),
+        None => println!("   file: (none, using defaults)"),
+    }
+    println!("   env overrides: {:?}", overrides);
+    if args.print_config {
+        for line in config.to_toml().lines() {
+            println!("   {}", line);
+        }
+    }
+    println!(
+        "   {} sensors every {} ms, {} rule(s)",
+        config.sensors.count,
+        config.gateway.poll_interval_ms,
+        config.rules.len()
+    );
+
+    // The flags' environment layer; their config layer comes with the
+    // gateway below
+    toggles::define();
+    if let Err(e) = FLAGS.apply_env(toggles::ENV_PREFIX, std::env::vars()) {
+        eprintln!("gateway: {}*: {}", toggles::ENV_PREFIX, e);
+        return ExitCode::FAILURE;
+    }
+
+    // 2. Starting subsystems
+    println!("\n2. Starting subsystems:");
+    // Before any thread exists: this clears the LISTEN_* variables
+    let activated = activated_listener();
+    #[cfg(feature = "systemd")]
+    let mut notifier = systemd::Notifier::from_env().unwrap_or_else(|e| {
+        eprintln!("gateway: no sd_notify: {}", e);
+        systemd::Notifier::disabled()
+    });
+    // Ctrl-C or SIGTERM ends the main loop; a second one exits at once
+    let shutdown = Shutdown::new();
+    if let Err(e) = handle_signals(&shutdown) {
+        eprintln!("gateway: cannot install Ctrl-C handler: {}", e);
+    }
+
+    let (mut gw, recovery) = match Gateway::new(config.clone()) {
+        Ok(started) => started,
+        Err(e) => {
+            eprintln!("gateway: cannot start: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    println!(
+        "   data log: {} ({:?})",
+        config.datalog.dir.display(),
+        recovery
+    );
+
+    let health = Arc::new(HealthRegistry::new());
+    gw.register_checks(&health);
+    let server = match activated {
+        Some(listener) => {
+            match StatusServer::serve(listener, gw.status(), Arc::clone(&health), shutdown.token())
+            {
+                Ok(server) => {
+                    println!(
+                    "   status endpoint: http://{0}/status, /metrics, /healthz, /readyz (socket activation)",
+                    server.local_addr()
+                );
+                    Some(server)
+                }
+                Err(e) => {
+                    println!("   status endpoint: unavailable ({})", e);
+                    None
+                }
+            }
+        }
+        None if config.status.bind.is_empty() => {
+            println!("   status endpoint: disabled");
+            None
+        }
+        None => match StatusServer::start(
+            &config.status.bind,
+            gw.status(),
+            Arc::clone(&health),
+            shutdown.token(),
+        ) {
+            Ok(server) => {
+                println!(
+                    "   status endpoint: http://{0}/status, /metrics, /healthz, /readyz",
+                    server.local_addr()
+                );
+                Some(server)
+            }
+            Err(e) => {
+                println!("   status endpoint: unavailable ({})", e);
+                None
+            }
+        },
+    };
+    match gw.history() {
+        Some(history) if !history.backend().persistent() => {
+            println!("   history: in memory, lost at exit")
+        }
+        Some(history) => println!(
+            "   history: {} at {}, {} readings stored",
+            history.backend(),
+            config.storage.path.display(),
+            history.count().unwrap_or(0)
+        ),
+        None => println!("   history: disabled (set storage.backend)"),
+    }
+    // Alerts reach the console through typed subscriptions, like any other
+    // consumer; no topic parsing or JSON involved
+    gw.subscribe(|a: &AlertRaised| println!("   [{:>6} ms] ALERT   {}", a.at_ms, a.rule));
+    gw.subscribe(|a: &AlertCleared| println!("   [{:>6} ms] cleared {}", a.at_ms, a.rule));
+    println!("   events: AlertRaised, AlertCleared -> console");
+    for (name, expr, next) in gw.scheduled_jobs() {
+        let next = next.map(|t| cron::DateTime::from_unix(t).to_string());
+        println!(
+            "   job: {} \"{}\", next {} UTC",
+            name,
+            expr,
+            next.as_deref().unwrap_or("never")
+        );
+    }
+    for flag in FLAGS.list() {
+        println!(
+            "   flag: {} = {} ({}), {}",
+            flag.name,
+            flag.setting,
+            flag.source,
+            if flag.enabled { "enabled" } else { "disabled" }
+        );
+    }
+    let flag_changes = flags::subscribe();
+    let control = if config.control.socket.as_os_str().is_empty() {
+        println!("   control socket: disabled");
+        None
+    } else {
+        match ControlSocket::serve(&config.control.socket, &FLAGS, shutdown.token()) {
+            Ok(control) => {
+                println!("   control socket: {}", control.path().display());
+                Some(control)
+            }
+            Err(e) => {
+                println!("   control socket: unavailable ({})", e);
+                None
+            }
+        }
+    };
+    // Poll interval, filter weight, rule thresholds and flags change live
+    let mut watcher =
+        config_path.and_then(|path| match ConfigWatcher::start(path, RELOAD_SETTLE) {
+            Ok(watcher) => {
+                println!("   config reload: watching {}", path.display());
+                Some(watcher)
+            }
+            Err(e) => {
+                println!("   config reload: unavailable ({})", e);
+                None
+            }
+        });
+    if config.uplink.addr.is_empty() {
+        println!("   uplink: disabled (set uplink.addr or GATEWAY_UPLINK_ADDR)");
+    } else {
+        println!(
+            "   uplink: {} in batches of {}",
+            config.uplink.addr, config.uplink.batch_size
+        );
+    }
+
+    #[cfg(feature = "systemd")]
+    if notifier.is_enabled() {
+        let _ = notifier.notify(&[systemd::State::Ready]);
+        match notifier.watchdog_interval() {
+            Some(every) => println!("   systemd: READY=1, watchdog ping every {:?}", every),
+            None => println!("   systemd: READY=1"),
+        }
+    }
+
+    // 3. Main loop
+    match config.gateway.run_seconds {
+        0 => println!("\n3. Running until Ctrl-C:"),
+        n => println!("\n3. Running for {} s (Ctrl-C to stop early):", n),
+    }
+    phases.begin("main loop");
+    let limit_ms = config.gateway.run_seconds * 1000;
+    let mut next_report = 1000;
+    let token = shutdown.token();
+    while !token.is_cancelled() {
+        if limit_ms > 0 && gw.elapsed_ms() >= limit_ms {
+            shutdown.trigger(Reason::Requested("run time limit"));
+            break;
+        }
+        // Wall time of the cycle, measured out here: the gateway itself
+        // only reads its injected clock
+        let cycle = Instant::now();
+        let ticked = gw.tick();
+        metrics::histogram!(
+            "gateway_cycle_seconds",
+            "Time spent in one poll cycle",
+            CYCLE_BUCKETS
+        )
+        .observe_duration(cycle.elapsed());
+        if let Err(e) = ticked {
+            eprintln!("gateway: data log failed: {}", e);
+            shutdown.trigger(Reason::Requested("data log failed"));
+            break;
+        }
+        #[cfg(feature = "systemd")]
+        let _ = notifier.keepalive();
+        if gw.elapsed_ms() >= next_report {
+            let s = gw.status().lock().unwrap().clone();
+            #[cfg(feature = "systemd")]
+            let _ = notifier.notify(&[systemd::State::Status(&format!(
+                "{} readings, {} active alert(s)",
+                s.readings,
+                s.active_alerts.len()
+            ))]);
+            println!(
+                "   [{:>6} ms] {} readings, {} active alert(s), {} batch(es) sent",
+                s.uptime_ms,
+                s.readings,
+                s.active_alerts.len(),
+                s.batches_sent
+            );
+            next_report += 1000;
+        }
+        if let (Some(watcher), Some(path)) = (&mut watcher, config_path) {
+            if watcher.poll(Instant::now()) {
+                reload_config(path, data_dir, &mut gw);
+            }
+        }
+        // From a reload or the control socket
+        for change in flag_changes.try_iter() {
+            println!("   [{:>6} ms] flag {}", gw.elapsed_ms(), change);
+        }
+        // Sleeps until the next poll, or until Ctrl-C
+        let interval = Duration::from_millis(gw.config().gateway.poll_interval_ms);
+        token.wait_timeout(interval);
+    }
+
+    // 4. Graceful shutdown
+    println!("\n4. Shutting down:");
+    phases.begin("shutdown");
+    if let Some(reason) = token.reason() {
+        println!("   reason:         {}", reason);
+    }
+    #[cfg(feature = "systemd")]
+    let _ = notifier.notify(&[systemd::State::Stopping]);
+    let status = match gw.shutdown() {
+        Ok(status) => status,
+        Err(e) => {
+            eprintln!("gateway: flush failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(server) = server {
+        server.join();
+    }
+    if let Some(control) = control {
+        control.join();
+    }
+    println!("   polls:          {}", status.polls);
+    println!("   records logged: {}", status.logged_records);
+    println!("   history rows:   {}", status.stored_readings);
+    println!("   alerts raised:  {}", status.alerts_raised);
+    println!(
+        "   events:         {} published, {} handler calls",
+        status.events_published, status.events_delivered
+    );
+    println!(
+        "   jobs run:       {} calibration(s), {} log rotation(s)",
+        status.calibrations, status.log_rotations
+    );
+    println!(
+        "   batches:        {} sent, {} pending, {} dropped",
+        status.batches_sent, status.batches_pending, status.batches_dropped
+    );
+    if !config.uplink.addr.is_empty() {
+        println!(
+            "   uplink circuit: {}, {} trip(s)",
+            status.uplink_circuit, status.uplink_circuit_trips
+        );
+    }
+
+    phases.print();
+
+    println!("\n=== End of Edge Gateway ===");
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,302 @@
+// Hot configuration reload: watch the TOML file, apply what is safe live
+//
+//   file event ─▶ Debounce ─(quiet for `settle`)─▶ Config::load ─▶ plan
+//                                                                   │
+//          live keys ─▶ Gateway::reconfigure        everything else ┘─▶ rejected,
+//                                                                       needs a restart
+//
+// An editor saves a file as several events (truncate, write, rename), and
+// reading it after the first would see it empty or half written. The
+// debounce waits until the events have stopped for `settle`. The
+// directory is watched rather than the file, because a save that renames
+// a new file into place replaces the inode a file watch is attached to.
+//
+// Only the settings the loop reads on every cycle are live: the poll
+// interval, the filter weight, the rule thresholds and the `[flags]`
+// table, where a flag may also be added or removed. The rest (sensor
+// count, data log, uplink address, ...) was used to build a subsystem at
+// startup; a change there is reported and ignored until the next start.
+// A new file that fails to parse or validate changes nothing at all.
+
+use crate::config::{Config, ConfigError};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+use toml::Value;
+
+// Keys applied without a restart; `*` is a rule index or a flag name
+const LIVE: &[&str] = &[
+    "gateway.poll_interval_ms",
+    "filter.alpha",
+    "rules.*.threshold",
+    "rules.*.hysteresis",
+    "rules.*.duration_ms",
+    "flags.*",
+];
+
+// A rule is matched by its position, so a change to one of these makes it
+// a different rule, and none of its keys is live
+const RULE_IDENTITY: &[&str] = &["name", "metric", "when"];
+
+// One setting that differs; `None` for a key only one side has, such as
+// the keys of an added rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub key: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |v: &Option<String>| v.clone().unwrap_or_else(|| "(none)".to_string());
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.key,
+            show(&self.old),
+            show(&self.new)
+        )
+    }
+}
+
+// What a reload does with a validated config
+#[derive(Debug, Clone)]
+pub struct Plan {
+    // The running config with the live changes applied
+    pub config: Config,
+    pub applied: Vec<Change>,
+    // Changes that need a restart; the running values stay
+    pub rejected: Vec<Change>,
+}
+
+impl Plan {
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty() && self.rejected.is_empty()
+    }
+}
+
+// Every leaf setting under its dotted key, e.g. `rules.0.threshold`
+fn flatten(config: &Config) -> BTreeMap<String, Value> {
+    fn walk(prefix: String, value: Value, out: &mut BTreeMap<String, Value>) {
+        let join = |key: &str| {
+            if prefix.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", prefix, key)
+            }
+        };
+        match value {
+            Value::Table(table) => {
+                for (key, v) in table {
+                    walk(join(&key), v, out);
+                }
+            }
+            Value::Array(items) => {
+                for (i, v) in items.into_iter().enumerate() {
+                    walk(join(&i.to_string()), v, out);
+                }
+            }
+            leaf => {
+                out.insert(prefix, leaf);
+            }
+        }
+    }
+    let mut out = BTreeMap::new();
+    let root = Value::try_from(config).expect("config is always serializable");
+    walk(String::new(), root, &mut out);
+    out
+}
+
+// Sets a leaf by its dotted key, or removes it for `None`. Only a table
+// entry (a flag) can be added or removed; array items are only replaced.
+fn set(root: &mut Value, key: &str, value: Option<Value>) {
+    let (path, last) = key.rsplit_once('.').expect("a leaf is in a section");
+    let mut node = root;
+    for part in path.split('.') {
+        node = match node {
+            Value::Array(items) => &mut items[part.parse::<usize>().expect("array index")],
+            other => other.get_mut(part).expect("key from flatten"),
+        };
+    }
+    match (node, value) {
+        (Value::Array(items), Some(value)) => {
+            items[last.parse::<usize>().expect("array index")] = value
+        }
+        (Value::Table(table), Some(value)) => {
+            table.insert(last.to_string(), value);
+        }
+        (Value::Table(table), None) => {
+            table.remove(last);
+        }
+        _ => unreachable!("only flags are added or removed"),
+    }
+}
+
+// Every setting that differs between `old` and `new`, in key order
+pub fn diff(old: &Config, new: &Config) -> Vec<Change> {
+    let (old, new) = (flatten(old), flatten(new));
+    let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|key| old.get(*key) != new.get(*key))
+        .map(|key| Change {
+            key: key.clone(),
+            old: old.get(key).map(Value::to_string),
+            new: new.get(key).map(Value::to_string),
+        })
+        .collect()
+}
+
+// Whether `key` is one of the live settings, ignoring the rule index
+// or flag name
+pub fn is_live(key: &str) -> bool {
+    let wildcard = key.starts_with("rules.") || key.starts_with("flags.");
+    let pattern: Vec<&str> = key
+        .split('.')
+        .enumerate()
+        .map(|(i, part)| match (i, wildcard) {
+            (1, true) => "*",
+            _ => part,
+        })
+        .collect();
+    LIVE.contains(&pattern.join(".").as_str())
+}
+
+// The rule index in a `rules.N.*` key
+fn rule_index(key: &str) -> Option<&str> {
+    key.strip_prefix("rules.")?.split('.').next()
+}
+
+// Splits the differences between the running config and a newly loaded
+// one into the live ones, applied to a copy of `running`, and the rest
+pub fn plan(running: &Config, candidate: &Config) -> Result<Plan, ConfigError> {
+    candidate.validate()?;
+    let changes = diff(running, candidate);
+    // Rules that were added, removed or turned into another rule
+    let replaced: Vec<&str> = changes
+        .iter()
+        .filter(|c| {
+            let field = c.key.rsplit('.').next().unwrap_or_default();
+            c.old.is_none() || c.new.is_none() || RULE_IDENTITY.contains(&field)
+        })
+        .filter_map(|c| rule_index(&c.key))
+        .collect();
+
+    let mut new_values = flatten(candidate);
+    let mut root = Value::try_from(running).expect("config is always serializable");
+    let (mut applied, mut rejected) = (Vec::new(), Vec::new());
+    for change in changes.iter().cloned() {
+        let rule_replaced = rule_index(&change.key).is_some_and(|i| replaced.contains(&i));
+        if is_live(&change.key) && !rule_replaced {
+            set(&mut root, &change.key, new_values.remove(&change.key));
+            applied.push(change);
+        } else {
+            rejected.push(change);
+        }
+    }
+    let config: Config = root
+        .try_into()
+        .map_err(|e: toml::de::Error| ConfigError::Invalid(e.to_string()))?;
+    // Each live value is valid alone; this checks them against the rest
+    config.validate()?;
+    Ok(Plan {
+        config,
+        applied,
+        rejected,
+    })
+}
+
+// Turns the events for one save into one reload
+#[derive(Debug)]
+pub struct Debounce {
+    file: OsString,
+    settle: Duration,
+    last: Option<Instant>,
+}
+
+impl Debounce {
+    pub fn new(path: &Path, settle: Duration) -> Debounce {
+        Debounce {
+            file: path.file_name().unwrap_or_default().to_os_string(),
+            settle,
+            last: None,
+        }
+    }
+
+    // Notes an event from the watched directory; returns whether it
+    // concerns the config file. Reads (access events) don't count.
+    pub fn event(&mut self, event: &Event, now: Instant) -> bool {
+        let kind = matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) | EventKind::Any
+        );
+        let ours = event
+            .paths
+            .iter()
+            .any(|p| p.file_name() == Some(self.file.as_os_str()));
+        if kind && ours {
+            self.last = Some(now);
+        }
+        kind && ours
+    }
+
+    // True once, when the file changed and nothing has happened for `settle`
+    pub fn due(&mut self, now: Instant) -> bool {
+        match self.last {
+            Some(last) if now.duration_since(last) >= self.settle => {
+                self.last = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+// Watches the config file's directory from notify's own thread; the loop
+// asks `poll` once per cycle, so nothing here blocks it
+pub struct ConfigWatcher {
+    // Dropping the watcher stops the notifications
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    debounce: Debounce,
+}
+
+impl ConfigWatcher {
+    pub fn start(path: &Path, settle: Duration) -> notify::Result<ConfigWatcher> {
+        let (tx, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        Ok(ConfigWatcher {
+            _watcher: watcher,
+            events,
+            debounce: Debounce::new(path, settle),
+        })
+    }
+
+    // True when the file has changed and settled since the last true
+    pub fn poll(&mut self, now: Instant) -> bool {
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                Ok(event) => {
+                    self.debounce.event(&event, now);
+                }
+                // Events may have been lost (e.g. an inotify queue
+                // overflow); reloading is harmless, missing a change isn't
+                Err(_) => {
+                    self.debounce.last = Some(now);
+                }
+            }
+        }
+        self.debounce.due(now)
+    }
+}
@@ -0,0 +1,97 @@
+// Simulated sensor hub
+//
+// Each node reports temperature, humidity and battery voltage. Values follow
+// slow sine waves plus noise from a seeded generator, so runs are repeatable.
+// Node 0 warms up after a few seconds to give the rules something to do.
+//
+// The temperature model is written in `units` quantities (86.units), so a
+// heater rise can only be added to a temperature as a `TemperatureDelta`
+// and the waveform periods are `Time`s. A `Reading` still carries an `f64`
+// in the metric's display unit (°C for temperature), as it goes on the
+// wire and into the rules and history.
+
+use units::{Temperature, TemperatureDelta, Time};
+
+const BASELINE: Temperature = Temperature::celsius(22.0);
+const SWING: TemperatureDelta = TemperatureDelta::kelvin(2.0);
+const TEMPERATURE_PERIOD: Time = Time::seconds(3.0);
+const TEMPERATURE_NOISE: TemperatureDelta = TemperatureDelta::kelvin(0.4);
+// Node 0's heater comes on at 1.5 s and adds 10 K over the next 2 s
+const HEATER_ON: Time = Time::seconds(1.5);
+const HEATER_RAMP: Time = Time::seconds(2.0);
+const HEATER_RISE: TemperatureDelta = TemperatureDelta::kelvin(10.0);
+const HUMIDITY_PERIOD: Time = Time::seconds(5.0);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reading {
+    pub sensor_id: u16,
+    pub metric: &'static str,
+    pub value: f64,
+    pub timestamp_ms: u64,
+}
+
+pub const METRICS: [&str; 3] = ["temperature", "humidity", "battery"];
+
+#[derive(Debug)]
+pub struct SensorHub {
+    count: u16,
+    rng: u32,
+    polls: u64,
+}
+
+impl SensorHub {
+    pub fn new(count: u16, seed: u32) -> SensorHub {
+        SensorHub {
+            count,
+            rng: seed.max(1),
+            polls: 0,
+        }
+    }
+
+    pub fn sensor_count(&self) -> u16 {
+        self.count
+    }
+
+    // Uniform noise in [-1, 1]
+    fn noise(&mut self) -> f64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        (self.rng as f64 / u32::MAX as f64) * 2.0 - 1.0
+    }
+
+    // One reading per metric per node; `elapsed_ms` drives the waveforms
+    pub fn poll(&mut self, elapsed_ms: u64, timestamp_ms: u64) -> Vec<Reading> {
+        self.polls += 1;
+        let t = Time::millis(elapsed_ms as f64);
+        let mut readings = Vec::with_capacity(self.count as usize * METRICS.len());
+
+        for id in 0..self.count {
+            let phase = id as f64;
+            let heating = if id == 0 {
+                let on_for = (t - HEATER_ON).clamp(Time::seconds(0.0), HEATER_RAMP);
+                HEATER_RISE * (on_for / HEATER_RAMP).value()
+            } else {
+                TemperatureDelta::kelvin(0.0)
+            };
+            let temperature = BASELINE
+                + SWING * ((t / TEMPERATURE_PERIOD).value() + phase).sin()
+                + heating
+                + TEMPERATURE_NOISE * self.noise();
+            let values = [
+                temperature.as_celsius(),
+                45.0 + 5.0 * ((t / HUMIDITY_PERIOD).value() + phase).cos() + 1.0 * self.noise(),
+                3.7 - 0.003 * self.polls as f64 * (id as f64 + 1.0) + 0.01 * self.noise(),
+            ];
+            for (metric, value) in METRICS.iter().zip(values) {
+                readings.push(Reading {
+                    sensor_id: id,
+                    metric,
+                    value,
+                    timestamp_ms,
+                });
+            }
+        }
+        readings
+    }
+}
@@ -0,0 +1,146 @@
+// Local HTTP status endpoint
+//
+// A deliberately tiny HTTP/1.0 responder on std's TcpListener: it answers
+// `GET /status` with a JSON snapshot of the shared `Status`, and
+// `GET /metrics` with the global metrics registry (94.metrics) in the
+// Prometheus text format, and `GET /healthz` / `GET /readyz` with the
+// checks of a `HealthRegistry`, then closes the connection. The listener is
+// non-blocking so the thread can notice the shutdown token.
+
+use crate::health::{Probe, SharedHealth};
+use serde::Serialize;
+use shutdown::Token;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct Status {
+    pub gateway_id: String,
+    pub uptime_ms: u64,
+    pub polls: u64,
+    pub readings: u64,
+    // Sensor nodes that returned a reading in the last poll
+    pub sensors_reporting: usize,
+    pub logged_records: u64,
+    pub stored_readings: u64,
+    pub alerts_raised: u64,
+    pub active_alerts: Vec<String>,
+    // Typed events on the internal bus, and the handler calls they made
+    pub events_published: u64,
+    pub events_delivered: u64,
+    // Runs of the scheduled jobs
+    pub calibrations: u64,
+    pub log_rotations: u64,
+    // Queue depths along the pipeline: messages waiting for a full uplink
+    // batch, and readings waiting for the next history write
+    pub batch_queued: usize,
+    pub history_queued: usize,
+    pub batches_sent: u64,
+    pub batches_pending: usize,
+    pub batches_dropped: u64,
+    pub uplink_errors: u64,
+    // Circuit breaker in front of the uplink: "closed", "open" or "half-open"
+    pub uplink_circuit: String,
+    pub uplink_circuit_trips: u64,
+}
+
+pub type SharedStatus = Arc<Mutex<Status>>;
+
+#[derive(Debug)]
+pub struct StatusServer {
+    addr: SocketAddr,
+    handle: JoinHandle<()>,
+}
+
+fn respond(stream: TcpStream, status: &SharedStatus, health: &SharedHealth) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_millis(500)))?;
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut stream = reader.into_inner();
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or("");
+    let json = "application/json";
+    let (code, content_type, body) = match path {
+        "/" | "/status" => {
+            let snapshot = status.lock().unwrap().clone();
+            ("200 OK", json, serde_json::to_string_pretty(&snapshot)?)
+        }
+        "/metrics" => ("200 OK", metrics::CONTENT_TYPE, metrics::render()),
+        "/healthz" | "/readyz" => {
+            let probe = if path == "/healthz" {
+                Probe::Liveness
+            } else {
+                Probe::Readiness
+            };
+            let report = health.run(probe);
+            let body = serde_json::to_string_pretty(&report)?;
+            (report.http_status(), json, body)
+        }
+        _ => (
+            "404 Not Found",
+            json,
+            "{\"error\":\"not found\"}".to_string(),
+        ),
+    };
+    write!(
+        stream,
+        "HTTP/1.0 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n{}",
+        code,
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+impl StatusServer {
+    pub fn start(
+        bind: &str,
+        status: SharedStatus,
+        health: SharedHealth,
+        token: Token,
+    ) -> io::Result<Self> {
+        StatusServer::serve(TcpListener::bind(bind)?, status, health, token)
+    }
+
+    // Serve on a listener opened elsewhere, e.g. passed in by systemd
+    pub fn serve(
+        listener: TcpListener,
+        status: SharedStatus,
+        health: SharedHealth,
+        token: Token,
+    ) -> io::Result<Self> {
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+
+        let handle = thread::spawn(move || {
+            while !token.is_cancelled() {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let _ = stream.set_nonblocking(false);
+                        if let Err(e) = respond(stream, &status, &health) {
+                            eprintln!("status: {}", e);
+                        }
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                        token.wait_timeout(Duration::from_millis(20));
+                    }
+                    Err(e) => eprintln!("status: accept failed: {}", e),
+                }
+            }
+        });
+        Ok(StatusServer { addr, handle })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    // Waits for the thread; the caller triggers the shutdown first
+    pub fn join(self) {
+        let _ = self.handle.join();
+    }
+}
@@ -0,0 +1,59 @@
+// The gateway's feature flags, defined in the global registry (93.flags)
+//
+//   defaults (here) < [flags] in the config file < GATEWAY_FLAGS_<NAME> < control socket
+//
+// A flag guards a behaviour that is new or risky, so it can be rolled out
+// to a percentage of the fleet and turned off again on one device without
+// a new build. Percentages are decided per `gateway.id`.
+
+use crate::config::Config;
+use flags::{Change, Flag, FlagError, Setting, Source, FLAGS};
+
+// Forward the queued messages as soon as a cycle raises an alert, instead
+// of waiting for a full batch
+pub const ALERT_FLUSH: &str = "alert-flush";
+
+// Prefix of the environment layer: GATEWAY_FLAGS_ALERT_FLUSH=on
+pub const ENV_PREFIX: &str = "GATEWAY_FLAGS_";
+
+const DEFINED: &[(&str, Setting, &str)] = &[(
+    ALERT_FLUSH,
+    Setting::Off,
+    "forward queued messages as soon as an alert is raised",
+)];
+
+// Defines every flag above; safe to call more than once
+pub fn define() {
+    for &(name, default, description) in DEFINED {
+        FLAGS.define(name, default, description);
+    }
+}
+
+pub fn flag(name: &str) -> &'static Flag {
+    define();
+    FLAGS.flag(name).expect("defined above")
+}
+
+// Checks one `[flags]` entry without touching the registry, so
+// `Config::validate` stays free of side effects
+pub fn check(name: &str, value: &str) -> Result<(), FlagError> {
+    if !DEFINED.iter().any(|(defined, _, _)| *defined == name) {
+        return Err(FlagError::Unknown(name.to_string()));
+    }
+    match value.parse::<Setting>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(FlagError::Value {
+            name: name.to_string(),
+            value: value.to_string(),
+        }),
+    }
+}
+
+// Makes the config's `[flags]` table the config layer, and decides the
+// percentages for this gateway's id
+pub fn apply(config: &Config) -> Result<Vec<Change>, FlagError> {
+    define();
+    let mut changes = FLAGS.set_unit(&config.gateway.id);
+    changes.extend(FLAGS.replace_layer(Source::Config, &config.flags)?);
+    Ok(changes)
+}
@@ -0,0 +1,136 @@
+// Topic routing to handler callbacks
+//
+// The broker (15.broker) queues messages for subscribers to drain later;
+// the router instead calls a handler synchronously for every matching
+// subscription, in subscription order. Filters use the same MQTT rules
+// (`+` one level, `#` the rest, `$` topics hidden from leading wildcards).
+//
+// Retained messages are emulated as in MQTT: publishing with `retain` keeps
+// the last payload per topic, an empty retained payload clears it, and every
+// new subscription is immediately handed the retained messages it matches.
+// Retained topics are interned, and republishing on a topic reuses its
+// payload buffer, so a steady stream of retained alerts stops allocating.
+
+use broker::{matches, validate_filter, validate_topic, Interner, Symbol, TopicError};
+use std::collections::BTreeMap;
+
+// What a handler sees; `retained` is only set for the replay on subscribe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Delivery<'a> {
+    pub topic: &'a str,
+    pub payload: &'a [u8],
+    pub retained: bool,
+}
+
+pub type Handler = Box<dyn FnMut(&Delivery)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RouteId(u32);
+
+struct Route {
+    id: RouteId,
+    filter: String,
+    handler: Handler,
+}
+
+#[derive(Default)]
+pub struct TopicRouter {
+    routes: Vec<Route>,
+    // Keyed by symbol, so replayed in the order topics were first retained
+    retained: BTreeMap<Symbol, Vec<u8>>,
+    topics: Interner,
+    next_id: u32,
+}
+
+impl TopicRouter {
+    pub fn new() -> TopicRouter {
+        TopicRouter::default()
+    }
+
+    pub fn subscribe<F>(&mut self, filter: &str, handler: F) -> Result<RouteId, TopicError>
+    where
+        F: FnMut(&Delivery) + 'static,
+    {
+        validate_filter(filter)?;
+        let mut handler: Handler = Box::new(handler);
+        for (&symbol, payload) in &self.retained {
+            let topic = self.topics.resolve(symbol);
+            if matches(filter, topic) {
+                handler(&Delivery {
+                    topic,
+                    payload,
+                    retained: true,
+                });
+            }
+        }
+
+        let id = RouteId(self.next_id);
+        self.next_id += 1;
+        self.routes.push(Route {
+            id,
+            filter: filter.to_string(),
+            handler,
+        });
+        Ok(id)
+    }
+
+    pub fn unsubscribe(&mut self, id: RouteId) -> bool {
+        let before = self.routes.len();
+        self.routes.retain(|r| r.id != id);
+        self.routes.len() != before
+    }
+
+    // Returns the number of handlers called
+    pub fn publish(
+        &mut self,
+        topic: &str,
+        payload: &[u8],
+        retain: bool,
+    ) -> Result<usize, TopicError> {
+        validate_topic(topic)?;
+        if retain {
+            // Clearing only looks the topic up: a stream of clears for
+            // topics never retained must not grow the interner
+            if payload.is_empty() {
+                if let Some(symbol) = self.topics.get(topic) {
+                    self.retained.remove(&symbol);
+                }
+            } else {
+                let symbol = self.topics.intern(topic);
+                let slot = self.retained.entry(symbol).or_default();
+                slot.clear();
+                slot.extend_from_slice(payload);
+            }
+        }
+
+        let delivery = Delivery {
+            topic,
+            payload,
+            retained: false,
+        };
+        let mut called = 0;
+        for route in self.routes.iter_mut().filter(|r| matches(&r.filter, topic)) {
+            (route.handler)(&delivery);
+            called += 1;
+        }
+        Ok(called)
+    }
+
+    pub fn retained(&self, topic: &str) -> Option<&[u8]> {
+        let symbol = self.topics.get(topic)?;
+        self.retained.get(&symbol).map(|p| p.as_slice())
+    }
+
+    pub fn retained_count(&self) -> usize {
+        self.retained.len()
+    }
+
+    // Topics ever retained; a cleared topic keeps its symbol for reuse
+    pub fn interned_topics(&self) -> usize {
+        self.topics.len()
+    }
+
+    pub fn filters(&self) -> impl Iterator<Item = &str> {
+        self.routes.iter().map(|r| r.filter.as_str())
+    }
+}
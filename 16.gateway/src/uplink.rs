@@ -0,0 +1,270 @@
+// Batched forwarding to an upstream collector
+//
+// Batches are written as one JSON document per line. If the connection is
+// down, batches queue up (bounded, oldest dropped first) and are resent after
+// a reconnect. A write that fails may still have reached the collector, so
+// delivery is at least once and the collector drops repeats (`ingest`).
+// Reconnects back off exponentially from 1 s to 30 s with jitter, so gateways
+// that lost the collector together don't all come back in the same second. An
+// optional token bucket limits how fast batches go out, so the backlog after
+// a reconnect is sent as a steady stream rather than all at once.
+//
+// Connects and writes also go through a circuit breaker. A collector that
+// accepts connections and then drops them keeps the backoff reset, so
+// when most recent attempts fail the breaker opens and the uplink stops
+// trying for a cool-down, then probes with a single attempt.
+//
+// The connection is a `Transport` (TCP in production) and all timing comes
+// from the gateway's `Clock`, including the breaker's and the rate
+// limiter's, so the whole retry behaviour can be driven by a test.
+//
+// Each outcome also counts in a `gateway_uplink_*` metric (94.metrics),
+// and so does every change of circuit state.
+
+use crate::deps::{Clock, Transport};
+use circuitbreaker::{CircuitBreaker, State};
+use metrics::counter;
+use ratelimit::TokenBucket;
+use retry::{Backoff, Jitter, Policy};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+fn reconnect_policy() -> Policy {
+    Policy {
+        max_attempts: None,
+        base: Duration::from_secs(1),
+        max_delay: Duration::from_secs(30),
+        jitter: Jitter::Equal,
+        ..Policy::default()
+    }
+}
+
+fn breaker_config() -> circuitbreaker::Config {
+    circuitbreaker::Config {
+        failure_rate: 0.5,
+        window: 10,
+        min_calls: 4,
+        cool_down: Duration::from_secs(15),
+        probes: 1,
+    }
+}
+
+// `id` counts up from 1 per gateway boot; `(gateway, boot, id)` names a
+// message uniquely, which is what the collector deduplicates on. Both
+// default to 0 (no id) for batches from gateways that predate them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UplinkMessage {
+    #[serde(default)]
+    pub id: u64,
+    pub topic: String,
+    pub payload: serde_json::Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Batch {
+    pub gateway: String,
+    // Unix time in ms when the gateway started
+    #[serde(default)]
+    pub boot: u64,
+    pub seq: u64,
+    pub messages: Vec<UplinkMessage>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct UplinkStats {
+    pub sent: u64,
+    pub errors: u64,
+    pub dropped: u64,
+    pub pending: usize,
+    pub circuit: State,
+    pub circuit_trips: u64,
+}
+
+pub struct Uplink {
+    transport: Box<dyn Transport>,
+    clock: Rc<dyn Clock>,
+    max_pending: usize,
+    connected: bool,
+    pending: VecDeque<Batch>,
+    next_attempt: Option<Instant>,
+    backoff: Backoff,
+    limiter: Option<TokenBucket<Rc<dyn Clock>>>,
+    breaker: CircuitBreaker<Rc<dyn Clock>>,
+    stats: UplinkStats,
+}
+
+impl Uplink {
+    pub fn new(transport: Box<dyn Transport>, clock: Rc<dyn Clock>, max_pending: usize) -> Uplink {
+        Uplink {
+            breaker: Uplink::breaker(transport.peer(), Rc::clone(&clock)),
+            transport,
+            clock,
+            max_pending: max_pending.max(1),
+            connected: false,
+            pending: VecDeque::new(),
+            next_attempt: None,
+            backoff: Backoff::new(&reconnect_policy()),
+            limiter: None,
+            stats: UplinkStats::default(),
+        }
+    }
+
+    fn breaker(addr: &str, clock: Rc<dyn Clock>) -> CircuitBreaker<Rc<dyn Clock>> {
+        let mut breaker = CircuitBreaker::with_clock(breaker_config(), clock);
+        let addr = addr.to_string();
+        breaker.on_transition(move |t| {
+            // Labels are literals, so one arm per state
+            let counter = match t.to {
+                State::Closed => counter!(
+                    "gateway_uplink_circuit_transitions_total",
+                    "Uplink circuit breaker state changes, by new state",
+                    "to" => "closed"
+                ),
+                State::Open => counter!(
+                    "gateway_uplink_circuit_transitions_total",
+                    "Uplink circuit breaker state changes, by new state",
+                    "to" => "open"
+                ),
+                State::HalfOpen => counter!(
+                    "gateway_uplink_circuit_transitions_total",
+                    "Uplink circuit breaker state changes, by new state",
+                    "to" => "half-open"
+                ),
+            };
+            counter.inc();
+            match t.to {
+                State::Open => eprintln!(
+                    "gateway: uplink {} circuit open ({:.0}% of recent attempts failed)",
+                    addr,
+                    t.failure_rate * 100.0
+                ),
+                _ => eprintln!("gateway: uplink {} circuit {}", addr, t.to),
+            }
+        });
+        breaker
+    }
+
+    // At most `per_second` batches per second on average, `burst` at once
+    pub fn with_rate_limit(mut self, burst: u32, per_second: f64) -> Uplink {
+        self.limiter = Some(TokenBucket::with_clock(
+            burst,
+            per_second,
+            Rc::clone(&self.clock),
+        ));
+        self
+    }
+
+    pub fn stats(&self) -> UplinkStats {
+        UplinkStats {
+            pending: self.pending.len(),
+            circuit_trips: self.breaker.stats().trips,
+            ..self.stats
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    // Hang up; a later flush reconnects. Called once at shutdown, after
+    // the final forced flush, so the collector sees the connection end
+    // rather than time out on it.
+    pub fn close(&mut self) {
+        if self.connected {
+            self.transport.close();
+            self.connected = false;
+        }
+    }
+
+    pub fn enqueue(&mut self, batch: Batch) {
+        if self.pending.len() >= self.max_pending {
+            self.pending.pop_front();
+            self.stats.dropped += 1;
+            counter!(
+                "gateway_uplink_batches_total",
+                "Batches leaving the uplink queue, by outcome",
+                "result" => "dropped"
+            )
+            .inc();
+        }
+        self.pending.push_back(batch);
+    }
+
+    // Try to send everything that is queued. `force` ignores the backoff,
+    // the rate limit and an open circuit (used once during shutdown).
+    pub fn flush(&mut self, force: bool) {
+        self.stats.circuit = self.breaker.state();
+        if self.pending.is_empty() {
+            return;
+        }
+        if !self.connected {
+            let now = self.clock.now();
+            if !force && self.next_attempt.is_some_and(|t| now < t) {
+                return;
+            }
+            if !self.admit(force) {
+                return;
+            }
+            let connected = self.transport.connect();
+            self.record(connected.is_ok());
+            if connected.is_err() {
+                self.stats.errors += 1;
+                counter!("gateway_uplink_errors_total", "Failed connects and writes").inc();
+                self.next_attempt = Some(now + self.backoff.next_delay());
+                return;
+            }
+            self.connected = true;
+            self.backoff.reset();
+        }
+
+        while let Some(batch) = self.pending.front() {
+            // Out of tokens: the rest waits for a later flush
+            if !force && self.limiter.as_mut().is_some_and(|l| !l.try_acquire(1)) {
+                return;
+            }
+            let mut line = serde_json::to_vec(batch).expect("batch is serializable");
+            line.push(b'\n');
+            if !self.admit(force) {
+                return;
+            }
+            let written = self.transport.send(&line);
+            self.record(written.is_ok());
+            if written.is_err() {
+                self.stats.errors += 1;
+                counter!("gateway_uplink_errors_total", "Failed connects and writes").inc();
+                self.transport.close();
+                self.connected = false;
+                self.next_attempt = Some(self.clock.now() + self.backoff.next_delay());
+                return;
+            }
+            self.pending.pop_front();
+            self.stats.sent += 1;
+            counter!(
+                "gateway_uplink_batches_total",
+                "Batches leaving the uplink queue, by outcome",
+                "result" => "sent"
+            )
+            .inc();
+            counter!(
+                "gateway_uplink_bytes_total",
+                "Bytes written to the collector"
+            )
+            .add(line.len() as u64);
+        }
+    }
+
+    // Asks the breaker for one attempt. A forced flush goes ahead anyway;
+    // its outcome is still recorded.
+    fn admit(&mut self, force: bool) -> bool {
+        let admitted = self.breaker.allow().is_ok();
+        self.stats.circuit = self.breaker.state();
+        admitted || force
+    }
+
+    fn record(&mut self, success: bool) {
+        self.breaker.record(success);
+        self.stats.circuit = self.breaker.state();
+    }
+}
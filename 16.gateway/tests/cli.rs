@@ -0,0 +1,68 @@
+// The command line: clap's own consistency checks on the definitions, and
+// the compatibility rules around `run` and the global flags
+
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use gateway::cli::{Cli, Commands, DeviceCommand, DevicesCommand};
+use std::path::Path;
+
+fn parse(args: &[&str]) -> Cli {
+    let cli = Cli::try_parse_from(args).unwrap();
+    cli.check().unwrap();
+    cli
+}
+
+#[test]
+fn definitions_are_consistent() {
+    Cli::command().debug_assert();
+}
+
+#[test]
+fn no_command_runs_with_the_old_flags() {
+    let cli = parse(&["gateway", "--config", "x.toml", "--print-config"]);
+    assert!(cli.command.is_none());
+    assert_eq!(cli.global.config.as_deref(), Some(Path::new("x.toml")));
+    assert!(cli.run.print_config);
+}
+
+#[test]
+fn run_flags_before_another_command_are_rejected() {
+    for command in ["dump-config", "run"] {
+        let cli = Cli::try_parse_from(["gateway", "--profile", command]).unwrap();
+        assert_eq!(cli.check().unwrap_err().kind(), ErrorKind::ArgumentConflict);
+    }
+    // After `run` they are its own flags
+    let cli = parse(&["gateway", "run", "--profile"]);
+    assert!(matches!(cli.command, Some(Commands::Run(args)) if args.profile));
+}
+
+#[test]
+fn global_flags_work_after_the_command() {
+    let cli = parse(&["gateway", "devices", "list", "--config", "x.toml"]);
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Devices(DevicesCommand::List))
+    ));
+    assert_eq!(cli.global.config.as_deref(), Some(Path::new("x.toml")));
+
+    let cli = parse(&["gateway", "replay-log", "--data-dir", "/tmp/log", "--csv"]);
+    assert_eq!(cli.global.data_dir.as_deref(), Some(Path::new("/tmp/log")));
+}
+
+#[test]
+fn send_command_arguments() {
+    let cli = parse(&["gateway", "send-command", "2", "set-interval", "30"]);
+    let Some(Commands::SendCommand(args)) = cli.command else {
+        panic!("expected send-command");
+    };
+    assert_eq!(args.device, 2);
+    assert!(matches!(
+        args.command,
+        DeviceCommand::SetInterval { seconds: 30 }
+    ));
+    assert_eq!((args.loss, args.ttl_ms), (0, 2000));
+
+    let err =
+        Cli::try_parse_from(["gateway", "send-command", "--loss", "101", "0", "ping"]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ValueValidation);
+}
@@ -0,0 +1,150 @@
+// Config layering and validation: defaults, then the TOML file, then
+// `GATEWAY_*` variables, and the checks `validate` runs on the result
+
+use gateway::gateway::MAX_SENSORS;
+use gateway::{Config, ConfigError};
+use std::path::Path;
+
+fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn invalid(config: &Config) -> String {
+    match config.validate() {
+        Err(ConfigError::Invalid(msg)) => msg,
+        other => panic!("expected Invalid, got {:?}", other),
+    }
+}
+
+#[test]
+fn file_overrides_defaults_and_env_overrides_file() {
+    let mut config = Config::from_toml(
+        r#"
+        [gateway]
+        id = "gw-file"
+        poll_interval_ms = 250
+
+        [uplink]
+        batch_size = 5
+        "#,
+    )
+    .unwrap();
+    // Keys the file leaves out keep their defaults
+    assert_eq!(config.sensors, Config::default().sensors);
+
+    let applied = config
+        .apply_env(vars(&[
+            ("GATEWAY_GATEWAY_ID", "gw-env"),
+            ("GATEWAY_DATALOG_DIR", "/var/log/gw"),
+            ("GATEWAY_NOT_A_SETTING", "1"),
+            ("PATH", "/usr/bin"),
+        ]))
+        .unwrap();
+    assert_eq!(applied, ["GATEWAY_GATEWAY_ID", "GATEWAY_DATALOG_DIR"]);
+    assert_eq!(config.gateway.id, "gw-env");
+    assert_eq!(config.datalog.dir, Path::new("/var/log/gw"));
+    // Not in the environment: the file's values stand
+    assert_eq!(config.gateway.poll_interval_ms, 250);
+    assert_eq!(config.uplink.batch_size, 5);
+}
+
+#[test]
+fn later_env_pairs_win() {
+    let mut config = Config::default();
+    config
+        .apply_env(vars(&[
+            ("GATEWAY_SENSORS_COUNT", "4"),
+            ("GATEWAY_SENSORS_COUNT", "7"),
+        ]))
+        .unwrap();
+    assert_eq!(config.sensors.count, 7);
+}
+
+#[test]
+fn unparsable_env_value_is_an_error() {
+    let mut config = Config::default();
+    let err = config
+        .apply_env(vars(&[("GATEWAY_FILTER_ALPHA", "high")]))
+        .unwrap_err();
+    assert!(matches!(
+        &err,
+        ConfigError::Env { key, value } if key == "GATEWAY_FILTER_ALPHA" && value == "high"
+    ));
+    assert_eq!(
+        err.to_string(),
+        "invalid value \"high\" for GATEWAY_FILTER_ALPHA"
+    );
+}
+
+#[test]
+fn defaults_are_valid() {
+    assert!(Config::default().validate().is_ok());
+}
+
+#[test]
+fn validate_rejects_out_of_range_settings() {
+    let mut config = Config::default();
+    config.gateway.poll_interval_ms = 0;
+    assert_eq!(
+        invalid(&config),
+        "gateway.poll_interval_ms must be positive"
+    );
+
+    for alpha in [0.0, -0.1, 1.5] {
+        let mut config = Config::default();
+        config.filter.alpha = alpha;
+        assert_eq!(invalid(&config), "filter.alpha must be in (0, 1]");
+    }
+
+    let mut config = Config::default();
+    config.uplink.batch_size = 0;
+    assert_eq!(invalid(&config), "uplink.batch_size must be positive");
+
+    let mut config = Config::default();
+    config.uplink.max_batches_per_sec = f64::NAN;
+    assert_eq!(
+        invalid(&config),
+        "uplink.max_batches_per_sec must be 0 or positive"
+    );
+
+    let mut config = Config::default();
+    config.sensors.count = 0;
+    assert_eq!(invalid(&config), "sensors.count must be positive");
+
+    // Node ids share a u16 data-log channel with the metric index
+    let mut config = Config::default();
+    config.sensors.count = MAX_SENSORS + 1;
+    assert_eq!(invalid(&config), "sensors.count must be at most 4096");
+    config.sensors.count = MAX_SENSORS;
+    assert!(config.validate().is_ok());
+
+    let config = Config::from_toml("[datalog]\nmax_files = 0\n").unwrap();
+    assert_eq!(invalid(&config), "datalog.max_files must be positive");
+
+    let mut config = Config::default();
+    config.datalog.max_file_bytes = datalog::RECORD_SIZE as u64 - 1;
+    assert_eq!(
+        invalid(&config),
+        "datalog.max_file_bytes must hold one record (20 bytes)"
+    );
+    config.datalog.max_file_bytes = datalog::RECORD_SIZE as u64;
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn validate_checks_backend_names_and_cron_expressions() {
+    let mut config = Config::default();
+    config.storage.backend = "mongo".to_string();
+    assert!(invalid(&config).starts_with("storage.backend: "));
+
+    let mut config = Config::default();
+    config.schedule.calibration = "every six hours".to_string();
+    assert!(invalid(&config).starts_with("schedule.calibration: "));
+
+    // Empty disables the job rather than failing to parse
+    config.schedule.calibration = String::new();
+    assert!(config.validate().is_ok());
+}
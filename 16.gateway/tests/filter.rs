@@ -0,0 +1,132 @@
+// The per-channel smoothing filters, checked against hand-worked values
+
+use gateway::filter::{Ema, Filter, Kalman, MovingAverage};
+
+fn run(filter: &mut impl Filter, values: &[f64]) -> Vec<f64> {
+    values.iter().map(|&v| filter.update(v)).collect()
+}
+
+#[test]
+fn ema_starts_at_the_first_sample() {
+    let mut ema = Ema::new(0.5);
+    assert_eq!(
+        run(&mut ema, &[10.0, 20.0, 20.0, 0.0]),
+        [10.0, 15.0, 17.5, 8.75]
+    );
+}
+
+#[test]
+fn ema_alpha_one_follows_the_input() {
+    let mut ema = Ema::new(1.0);
+    assert_eq!(run(&mut ema, &[3.0, -1.0, 4.0]), [3.0, -1.0, 4.0]);
+}
+
+#[test]
+fn ema_set_alpha_keeps_the_average() {
+    let mut ema = Ema::new(0.5);
+    run(&mut ema, &[10.0, 20.0]);
+    ema.set_alpha(0.1);
+    assert_eq!(ema.update(25.0), 16.0);
+}
+
+#[test]
+fn ema_reset_forgets_the_history() {
+    let mut ema = Ema::new(0.2);
+    run(&mut ema, &[100.0, 100.0]);
+    ema.reset();
+    assert_eq!(ema.update(5.0), 5.0);
+}
+
+#[test]
+fn moving_average_over_a_sliding_window() {
+    let mut avg = MovingAverage::new(3);
+    assert_eq!(
+        run(&mut avg, &[3.0, 6.0, 9.0, 12.0, 0.0]),
+        [3.0, 4.5, 6.0, 9.0, 7.0]
+    );
+}
+
+#[test]
+fn moving_average_window_zero_is_one() {
+    let mut avg = MovingAverage::new(0);
+    assert_eq!(run(&mut avg, &[1.0, 2.0]), [1.0, 2.0]);
+}
+
+#[test]
+fn moving_average_reset_empties_the_window() {
+    let mut avg = MovingAverage::new(4);
+    run(&mut avg, &[50.0, 50.0, 50.0]);
+    avg.reset();
+    assert_eq!(avg.update(2.0), 2.0);
+}
+
+#[test]
+fn kalman_first_sample_passes_through() {
+    let mut kalman = Kalman::new(1e-4, 0.5).unwrap();
+    assert_eq!(kalman.update(21.5), 21.5);
+    assert_eq!(kalman.variance(), 0.5);
+}
+
+#[test]
+fn kalman_variance_shrinks_to_a_steady_state() {
+    let mut kalman = Kalman::new(1e-4, 0.5).unwrap();
+    let mut variances = Vec::new();
+    for _ in 0..2000 {
+        kalman.update(20.0);
+        variances.push(kalman.variance());
+    }
+    assert!(variances.windows(2).all(|w| w[1] <= w[0]));
+    // Process noise keeps it from reaching zero
+    let last = variances[variances.len() - 1];
+    assert!(last > 0.0 && last < 0.01, "{}", last);
+    assert!((variances[variances.len() - 2] - last).abs() < 1e-12);
+}
+
+#[test]
+fn kalman_trusts_a_noisy_sensor_less() {
+    // Settle on 0 first: the very first gain is about 0.5 whatever R is,
+    // since the variance starts at R
+    let step = |measurement_noise| {
+        let mut kalman = Kalman::new(0.01, measurement_noise).unwrap();
+        run(&mut kalman, &[0.0; 50]);
+        kalman.update(10.0)
+    };
+    let (quiet, noisy) = (step(0.1), step(10.0));
+    assert!(quiet > 2.0 && noisy < 1.0, "{} {}", quiet, noisy);
+}
+
+#[test]
+fn kalman_without_process_noise_is_the_running_mean() {
+    let mut kalman = Kalman::new(0.0, 1.0).unwrap();
+    let out = run(&mut kalman, &[1.0, 2.0, 3.0, 4.0]);
+    for (got, want) in out.iter().zip([1.0, 1.5, 2.0, 2.5]) {
+        assert!((got - want).abs() < 1e-12, "{:?}", out);
+    }
+}
+
+#[test]
+fn kalman_reset_restores_the_initial_state() {
+    let mut kalman = Kalman::new(1e-3, 2.0).unwrap();
+    run(&mut kalman, &[5.0, 6.0, 7.0]);
+    assert!(kalman.variance() < 2.0);
+    kalman.reset();
+    assert_eq!(kalman.variance(), 2.0);
+    assert_eq!(kalman.update(-3.0), -3.0);
+}
+
+#[test]
+fn kalman_rejects_noise_that_would_give_nan() {
+    for (q, r) in [
+        (0.0, 0.0),
+        (-1.0, 1.0),
+        (f64::NAN, 1.0),
+        (0.1, f64::NAN),
+        (f64::INFINITY, 1.0),
+    ] {
+        assert!(Kalman::new(q, r).is_err(), "q={} r={}", q, r);
+    }
+    assert_eq!(
+        Kalman::new(0.0, 0.0).unwrap_err().to_string(),
+        "process_noise must be >= 0 and measurement_noise > 0 (got 0 and 0)"
+    );
+}
@@ -0,0 +1,120 @@
+// Feature flags in the gateway: the `[flags]` table checked like any
+// other setting, reloaded live, and `alert-flush` changing when a partial
+// batch is forwarded. The flag registry is global to the process, so only
+// the last test here changes it, and this file is a test binary of its
+// own.
+
+use gateway::doubles::{ManualClock, MemoryTransport, ScriptedSensors};
+use gateway::reload;
+use gateway::toggles::{self, ALERT_FLUSH};
+use gateway::{Config, ConfigError, Gateway, Parts};
+use std::rc::Rc;
+use std::time::Duration;
+
+fn with_flag(value: &str) -> Config {
+    let mut config = Config::default();
+    config.sensors.count = 1;
+    config
+        .flags
+        .insert(ALERT_FLUSH.to_string(), value.to_string());
+    config
+}
+
+#[test]
+fn config_flags_are_checked_against_the_definitions() {
+    assert!(with_flag("25%").validate().is_ok());
+    let mut typo = Config::default();
+    typo.flags
+        .insert("alert-flsh".to_string(), "on".to_string());
+    for (config, message) in [
+        (typo, "flags: no flag named \"alert-flsh\""),
+        (
+            with_flag("sometimes"),
+            "flags: alert-flush: \"sometimes\" is not on, off or a percentage",
+        ),
+    ] {
+        match config.validate() {
+            Err(ConfigError::Invalid(msg)) => assert_eq!(msg, message),
+            other => panic!("{:?}", other),
+        }
+    }
+    let text = "[flags]\nalert-flush = \"10%\"\n";
+    assert_eq!(Config::from_toml(text).unwrap().flags[ALERT_FLUSH], "10%");
+}
+
+#[test]
+fn flags_are_added_changed_and_removed_live() {
+    let running = Config::default();
+    let on = with_flag("on");
+    let plan = reload::plan(&running, &on).unwrap();
+    // The sensor count differs too, and still needs a restart
+    assert_eq!(plan.applied.len(), 1);
+    assert_eq!(
+        plan.applied[0].to_string(),
+        "flags.alert-flush: (none) -> \"on\""
+    );
+    assert_eq!(plan.config.flags, on.flags);
+
+    let mut half = plan.config.clone();
+    half.flags
+        .insert(ALERT_FLUSH.to_string(), "50%".to_string());
+    let plan = reload::plan(&plan.config, &half).unwrap();
+    assert_eq!(plan.config.flags[ALERT_FLUSH], "50%");
+
+    let plan = reload::plan(&plan.config, &running).unwrap();
+    assert_eq!(
+        plan.applied[0].to_string(),
+        "flags.alert-flush: \"50%\" -> (none)"
+    );
+    assert!(plan.config.flags.is_empty());
+}
+
+// Polls 31 °C until the `hot` rule raises; returns the batches the
+// collector had by then
+fn until_alert(config: Config) -> (Gateway, MemoryTransport) {
+    let clock = Rc::new(ManualClock::new(1_700_000_000_000));
+    let wire = MemoryTransport::new("collector");
+    let parts = Parts {
+        clock: clock.clone(),
+        sensors: Box::new(ScriptedSensors::new().repeat(50, &[(0, "temperature", 31.0)])),
+        transport: Some(Box::new(wire.clone())),
+        history: None,
+        log: None,
+    };
+    let mut gw = Gateway::with_parts(config, parts);
+    for _ in 0..50 {
+        clock.advance(Duration::from_millis(100));
+        if !gw.tick().unwrap().is_empty() {
+            return (gw, wire);
+        }
+    }
+    panic!("no alert");
+}
+
+#[test]
+fn alert_flush_forwards_the_partial_batch_with_the_alert() {
+    // Off: the alert waits in the queue for 19 more messages
+    let (mut gw, wire) = until_alert(with_flag("off"));
+    assert!(wire.batches().is_empty());
+    assert!(!toggles::flag(ALERT_FLUSH).enabled());
+
+    // A reload turns it on for the running gateway
+    let on = with_flag("on");
+    gw.reconfigure(reload::plan(gw.config(), &on).unwrap().config);
+    assert!(flags::enabled(ALERT_FLUSH));
+
+    // On: the cycle that raises the alert sends what is queued at once
+    let (_gw, wire) = until_alert(on);
+    let batches = wire.batches();
+    assert_eq!(batches.len(), 1);
+    let topics: Vec<&str> = batches[0]
+        .messages
+        .iter()
+        .map(|m| m.topic.as_str())
+        .collect();
+    assert!(topics.len() < 20);
+    assert_eq!(topics.last(), Some(&"alerts/hot/0"));
+    assert!(topics[..topics.len() - 1]
+        .iter()
+        .all(|t| *t == "sensors/0/temperature"));
+}
@@ -0,0 +1,225 @@
+// The whole gateway loop on the doubles from `gateway::doubles`: scripted
+// readings, a clock that moves only when told, an in-memory collector and
+// history. Nothing here opens a socket or a file or sleeps, so rule
+// durations, reconnect backoff and cron schedules that span seconds or
+// hours run in microseconds.
+
+use gateway::doubles::{ManualClock, MemoryStorage, MemoryTransport, ScriptedSensors};
+use gateway::{AlertRaised, Config, Gateway, Parts, ReadingFiltered};
+use rules::AlertEvent;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+// 2023-11-14 05:59:50 UTC, ten seconds before a calibration slot
+const START_MS: u64 = 1_699_941_590_000;
+const POLL: Duration = Duration::from_millis(100);
+
+fn build(config: Config, sensors: ScriptedSensors) -> (Gateway, Rc<ManualClock>, MemoryTransport) {
+    let clock = Rc::new(ManualClock::new(START_MS));
+    let wire = MemoryTransport::new("collector");
+    let parts = Parts {
+        clock: clock.clone(),
+        sensors: Box::new(sensors),
+        transport: Some(Box::new(wire.clone())),
+        history: Some(Box::new(MemoryStorage::new())),
+        log: None,
+    };
+    (Gateway::with_parts(config, parts), clock, wire)
+}
+
+// One poll per call, with the clock moved on by `POLL` first
+fn run(gw: &mut Gateway, clock: &ManualClock, polls: usize) -> Vec<(usize, AlertEvent)> {
+    let mut events = Vec::new();
+    for i in 0..polls {
+        clock.advance(POLL);
+        events.extend(
+            gw.tick()
+                .expect("no I/O to fail")
+                .into_iter()
+                .map(|e| (i, e)),
+        );
+    }
+    events
+}
+
+#[test]
+fn readings_reach_history_and_uplink() {
+    let mut config = Config::default();
+    config.storage.batch_size = 3;
+    let sensors = ScriptedSensors::new().repeat(
+        10,
+        &[
+            (0, "temperature", 21.0),
+            (1, "temperature", 22.0),
+            (2, "temperature", 23.0),
+        ],
+    );
+    let (mut gw, clock, wire) = build(config, sensors);
+
+    run(&mut gw, &clock, 10);
+    let history = gw.history().expect("history configured");
+    assert_eq!(history.count().unwrap(), 30);
+    let node1 = history
+        .range("temperature", Some(1), START_MS, START_MS + 2_000)
+        .unwrap();
+    assert_eq!(node1.len(), 10);
+    assert_eq!(node1[0].timestamp_ms, START_MS + 100);
+    assert_eq!(node1[9].timestamp_ms, START_MS + 1_000);
+
+    // 20 messages per batch: one full batch now, the rest at shutdown
+    assert_eq!(wire.batches().len(), 1);
+    let status = gw.shutdown().unwrap();
+    let batches = wire.batches();
+    assert_eq!(batches.iter().map(|b| b.seq).collect::<Vec<_>>(), [1, 2]);
+    assert!(batches
+        .iter()
+        .all(|b| b.gateway == "gw-01" && b.boot == START_MS));
+    let ids: Vec<u64> = batches
+        .iter()
+        .flat_map(|b| b.messages.iter().map(|m| m.id))
+        .collect();
+    assert_eq!(ids, (1..=30).collect::<Vec<_>>());
+    assert_eq!(status.readings, 30);
+    assert_eq!(status.stored_readings, 30);
+    assert_eq!(status.logged_records, 0);
+    assert_eq!(status.uptime_ms, 1_000);
+}
+
+#[test]
+fn a_batch_goes_out_each_time_batch_size_fills() {
+    let mut config = Config::default();
+    config.uplink.batch_size = 4;
+    let sensors = ScriptedSensors::new().repeat(
+        4,
+        &[
+            (0, "temperature", 21.0),
+            (1, "temperature", 22.0),
+            (2, "temperature", 23.0),
+        ],
+    );
+    let (mut gw, clock, wire) = build(config, sensors);
+
+    // Three messages a poll: nothing until the fourth is queued
+    run(&mut gw, &clock, 1);
+    assert!(wire.batches().is_empty());
+    run(&mut gw, &clock, 1);
+    assert_eq!(wire.batches().len(), 1);
+    run(&mut gw, &clock, 2);
+    let sizes: Vec<usize> = wire.batches().iter().map(|b| b.messages.len()).collect();
+    assert_eq!(sizes, [4, 4, 4]);
+
+    // Shutdown sends nothing more: the twelve messages filled three batches
+    gw.shutdown().unwrap();
+    assert_eq!(wire.batches().len(), 3);
+}
+
+#[test]
+fn alert_waits_for_its_duration_then_clears() {
+    let mut config = Config::default();
+    config.sensors.count = 1;
+    let sensors = ScriptedSensors::new()
+        .repeat(5, &[(0, "temperature", 20.0)])
+        .repeat(20, &[(0, "temperature", 40.0)])
+        .repeat(20, &[(0, "temperature", 20.0)]);
+    let (mut gw, clock, wire) = build(config, sensors);
+    let filtered = Rc::new(RefCell::new(Vec::new()));
+    {
+        let filtered = Rc::clone(&filtered);
+        gw.subscribe(move |r: &ReadingFiltered| filtered.borrow_mut().push(r.value));
+    }
+    let raised = Rc::new(RefCell::new(Vec::new()));
+    {
+        let raised = Rc::clone(&raised);
+        gw.subscribe(move |a: &AlertRaised| raised.borrow_mut().push(a.rule.clone()));
+    }
+
+    let events = run(&mut gw, &clock, 45);
+    // `hot` fires once the smoothed value has stayed above 30 for 500 ms,
+    // that is five polls after it first crossed
+    let crossed = filtered.borrow().iter().position(|v| *v > 30.0).unwrap();
+    let [(at, AlertEvent::Raised { rule, .. }), (cleared_at, AlertEvent::Cleared { .. })] =
+        events.as_slice()
+    else {
+        panic!("expected one raise and one clear, got {:?}", events);
+    };
+    assert_eq!(rule, "hot/0");
+    assert_eq!(*at, crossed + 5);
+    assert!(*cleared_at >= 25);
+    assert_eq!(*raised.borrow(), ["hot/0"]);
+    assert!(gw.status().lock().unwrap().active_alerts.is_empty());
+
+    // On the uplink the alert follows the reading that raised it
+    gw.shutdown().unwrap();
+    let topics: Vec<String> = wire
+        .batches()
+        .into_iter()
+        .flat_map(|b| b.messages.into_iter().map(|m| m.topic))
+        .collect();
+    let alert = topics.iter().position(|t| t == "alerts/hot/0").unwrap();
+    assert_eq!(alert, at + 1);
+    assert_eq!(topics[alert - 1], "sensors/0/temperature");
+}
+
+#[test]
+fn uplink_outage_backs_off_and_resends_in_order() {
+    let mut config = Config::default();
+    config.uplink.batch_size = 3;
+    let sensors = ScriptedSensors::new().repeat(
+        30,
+        &[
+            (0, "humidity", 45.0),
+            (1, "humidity", 46.0),
+            (2, "humidity", 47.0),
+        ],
+    );
+    let (mut gw, clock, wire) = build(config, sensors);
+
+    wire.set_reachable(false);
+    run(&mut gw, &clock, 3);
+    {
+        let status = gw.status();
+        let status = status.lock().unwrap();
+        assert_eq!(status.uplink_errors, 1);
+        assert_eq!(status.batches_pending, 3);
+    }
+    assert!(wire.lines().is_empty());
+
+    // Reachable again, but the first retry waits at least half a second
+    wire.set_reachable(true);
+    run(&mut gw, &clock, 1);
+    assert!(wire.lines().is_empty());
+
+    clock.advance(Duration::from_secs(1));
+    run(&mut gw, &clock, 1);
+    let seqs: Vec<u64> = wire.batches().iter().map(|b| b.seq).collect();
+    assert_eq!(seqs, [1, 2, 3, 4, 5]);
+    assert_eq!(wire.connects(), 1);
+    let status = gw.status();
+    let status = status.lock().unwrap();
+    assert_eq!(status.batches_sent, 5);
+    assert_eq!(status.batches_pending, 0);
+    assert_eq!(status.uplink_circuit, "closed");
+}
+
+#[test]
+fn calibration_runs_on_the_gateway_clock() {
+    let sensors = ScriptedSensors::new().repeat(30, &[(0, "temperature", 21.0)]);
+    let (mut gw, clock, _) = build(Config::default(), sensors);
+    let next = gw
+        .scheduled_jobs()
+        .into_iter()
+        .find(|(name, _, _)| name == "calibration")
+        .and_then(|(_, _, next)| next);
+    assert_eq!(next, Some(START_MS as i64 / 1000 + 10));
+
+    // Nine seconds in, nothing; past 06:00:00, one calibration
+    run(&mut gw, &clock, 90);
+    assert_eq!(gw.status().lock().unwrap().calibrations, 0);
+    run(&mut gw, &clock, 20);
+    let status = gw.status();
+    let status = status.lock().unwrap();
+    assert_eq!(status.calibrations, 1);
+    // No data log was given, so the midnight rotation has nothing to do
+    assert_eq!(status.log_rotations, 0);
+}
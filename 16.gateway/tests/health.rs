@@ -0,0 +1,357 @@
+// `/healthz` and `/readyz`: the gateway's checks on the doubles, driven
+// into degraded and failed states, and the registry's timeouts
+
+use datalog::{DataLogger, LogConfig};
+use gateway::doubles::{ManualClock, MemoryTransport, ScriptedSensors};
+use gateway::health::{progress_with_clock, Health, HealthRegistry, Probe, SharedHealth, State};
+use gateway::status::{SharedStatus, StatusServer};
+use gateway::{Config, Gateway, Parts};
+use retry::FakeClock;
+use serde_json::Value;
+use shutdown::Shutdown;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const START_MS: u64 = 1_700_000_000_000;
+const ALL_NODES: &[(u16, &str, f64)] = &[
+    (0, "temperature", 21.0),
+    (1, "temperature", 22.0),
+    (2, "humidity", 40.0),
+];
+
+fn gateway(
+    config: Config,
+    sensors: ScriptedSensors,
+    wire: &MemoryTransport,
+) -> (Gateway, Rc<ManualClock>) {
+    let clock = Rc::new(ManualClock::new(START_MS));
+    let parts = Parts {
+        clock: clock.clone(),
+        sensors: Box::new(sensors),
+        transport: Some(Box::new(wire.clone())),
+        history: None,
+        log: None,
+    };
+    (Gateway::with_parts(config, parts), clock)
+}
+
+fn get(addr: SocketAddr, path: &str) -> (String, Value) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(stream, "GET {} HTTP/1.0\r\n\r\n", path).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    (head.to_string(), serde_json::from_str(body).unwrap())
+}
+
+fn check<'a>(report: &'a Value, name: &str) -> &'a Value {
+    report["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["name"] == name)
+        .unwrap_or_else(|| panic!("no check {} in {}", name, report))
+}
+
+#[test]
+fn a_running_gateway_is_live_and_ready() {
+    let wire = MemoryTransport::new("collector");
+    let (mut gw, clock) = gateway(
+        Config::default(),
+        ScriptedSensors::new().repeat(5, ALL_NODES),
+        &wire,
+    );
+    let health = SharedHealth::default();
+    gw.register_checks(&health);
+    let shutdown = Shutdown::new();
+    let server = StatusServer::start(
+        "127.0.0.1:0",
+        gw.status(),
+        Arc::clone(&health),
+        shutdown.token(),
+    )
+    .unwrap();
+
+    // Not ready before the first poll, but live
+    let (head, before) = get(server.local_addr(), "/readyz");
+    assert!(
+        head.starts_with("HTTP/1.0 503 Service Unavailable"),
+        "{}",
+        head
+    );
+    assert_eq!(check(&before, "sensors")["message"], "no poll yet");
+
+    for _ in 0..5 {
+        clock.advance(Duration::from_millis(100));
+        gw.tick().unwrap();
+    }
+    let (live_head, live) = get(server.local_addr(), "/healthz");
+    let (ready_head, ready) = get(server.local_addr(), "/readyz");
+    shutdown.trigger(shutdown::Reason::Requested("test done"));
+    server.join();
+
+    assert!(live_head.starts_with("HTTP/1.0 200 OK"));
+    assert!(live_head.contains("Content-Type: application/json"));
+    assert_eq!(live["status"], "ok");
+    let names = |report: &Value| -> Vec<String> {
+        report["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["name"].as_str().unwrap().to_string())
+            .collect()
+    };
+    // Liveness runs only the loop's check; readiness runs all of them
+    assert_eq!(names(&live), ["loop"]);
+    assert!(ready_head.starts_with("HTTP/1.0 200 OK"));
+    assert_eq!(ready["status"], "ok");
+    assert_eq!(names(&ready), ["loop", "sensors", "uplink"]);
+    for c in ready["checks"].as_array().unwrap() {
+        assert_eq!(c["status"], "ok");
+        assert!(c["latency_ms"].as_f64().unwrap() >= 0.0);
+        // Only a check that isn't ok explains itself
+        assert!(c.get("message").is_none(), "{}", c);
+    }
+}
+
+#[test]
+fn missing_sensor_nodes_degrade_then_fail_readiness() {
+    let wire = MemoryTransport::new("collector");
+    let sensors = ScriptedSensors::new()
+        .then(ALL_NODES)
+        .then(&ALL_NODES[..2])
+        .then(&[]);
+    let (mut gw, clock) = gateway(Config::default(), sensors, &wire);
+    let health = HealthRegistry::new();
+    gw.register_checks(&health);
+
+    let mut states = Vec::new();
+    for _ in 0..3 {
+        clock.advance(Duration::from_millis(100));
+        gw.tick().unwrap();
+        let report = health.run(Probe::Readiness);
+        let sensors = report.checks.iter().find(|c| c.name == "sensors").unwrap();
+        states.push((report.status, sensors.message.clone()));
+    }
+    assert_eq!(
+        states,
+        [
+            (State::Ok, None),
+            (State::Degraded, Some("2 of 3 nodes reported".to_string())),
+            (
+                State::Failed,
+                Some("no node reported in the last poll".to_string())
+            ),
+        ]
+    );
+    // A silent sensor bus is the sensors' problem, not the loop's
+    assert_eq!(health.run(Probe::Liveness).status, State::Ok);
+}
+
+#[test]
+fn an_unreachable_collector_degrades_then_fails_readiness() {
+    let mut config = Config::default();
+    // One batch per poll, and room for only a few of them
+    config.uplink.batch_size = 3;
+    config.uplink.max_pending_batches = 16;
+    let wire = MemoryTransport::new("collector");
+    wire.set_reachable(false);
+    let (mut gw, clock) = gateway(config, ScriptedSensors::new().repeat(20, ALL_NODES), &wire);
+    let health = HealthRegistry::new();
+    gw.register_checks(&health);
+
+    let uplink = |health: &HealthRegistry| {
+        let report = health.run(Probe::Readiness);
+        let check = report
+            .checks
+            .into_iter()
+            .find(|c| c.name == "uplink")
+            .unwrap();
+        (report.status, check)
+    };
+    let mut seen = Vec::new();
+    for _ in 0..20 {
+        clock.advance(Duration::from_secs(1));
+        gw.tick().unwrap();
+        let (overall, check) = uplink(&health);
+        assert_eq!(overall, check.status);
+        if seen.last() != Some(&check.status) {
+            seen.push(check.status);
+        }
+        if check.status == State::Degraded {
+            assert!(
+                check
+                    .message
+                    .as_deref()
+                    .unwrap()
+                    .starts_with("circuit open"),
+                "{:?}",
+                check
+            );
+        }
+    }
+    // Attempts fail until the circuit opens; the batches then pile up
+    // behind it until the queue is full
+    assert_eq!(seen, [State::Ok, State::Degraded, State::Failed]);
+    assert_eq!(gw.status().lock().unwrap().batches_pending, 16);
+
+    // Back to ok once the collector answers and the queue drains
+    wire.set_reachable(true);
+    for _ in 0..30 {
+        clock.advance(Duration::from_secs(1));
+        gw.tick().unwrap();
+    }
+    assert_eq!(uplink(&health).1.status, State::Ok);
+}
+
+#[test]
+fn a_failing_data_log_fails_readiness_only() {
+    let dir = std::env::temp_dir().join(format!("rust-sys-gateway-health-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let (log, _) = DataLogger::open(LogConfig::new(&dir)).unwrap();
+    let mut config = Config::default();
+    config.datalog.dir = dir.clone();
+    let clock = Rc::new(ManualClock::new(START_MS));
+    let parts = Parts {
+        clock: clock.clone(),
+        sensors: Box::new(ScriptedSensors::new().repeat(1, ALL_NODES)),
+        transport: None,
+        history: None,
+        log: Some(log),
+    };
+    let mut gw = Gateway::with_parts(config, parts);
+    clock.advance(Duration::from_millis(100));
+    gw.tick().unwrap();
+    let health = HealthRegistry::new();
+    gw.register_checks(&health);
+
+    let ready = health.run(Probe::Readiness);
+    assert_eq!(ready.status, State::Ok, "{:?}", ready);
+    // The probe file doesn't stay behind
+    assert!(!dir.join(".health").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    let ready = health.run(Probe::Readiness);
+    let datalog = ready.checks.iter().find(|c| c.name == "datalog").unwrap();
+    assert_eq!(ready.status, State::Failed);
+    assert_eq!(ready.http_status(), "503 Service Unavailable");
+    assert!(
+        datalog
+            .message
+            .as_deref()
+            .unwrap()
+            .starts_with(&dir.display().to_string()),
+        "{:?}",
+        datalog
+    );
+    assert_eq!(health.run(Probe::Liveness).status, State::Ok);
+}
+
+#[test]
+fn a_slow_check_times_out_and_is_not_stacked_up() {
+    let health = HealthRegistry::new();
+    let started = Arc::new(AtomicUsize::new(0));
+    let runs = Arc::clone(&started);
+    // The check blocks until the test lets it go, however slow the machine
+    let (release, gate) = mpsc::channel::<()>();
+    let (finished, done) = mpsc::channel();
+    let gate = Mutex::new(gate);
+    health.register(
+        "slow",
+        Probe::Readiness,
+        Duration::from_millis(50),
+        move || {
+            runs.fetch_add(1, Ordering::SeqCst);
+            let _ = gate.lock().unwrap().recv();
+            let _ = finished.send(());
+            Health::Ok
+        },
+    );
+    health.register("fast", Probe::Readiness, Duration::from_millis(50), || {
+        Health::Degraded("almost full".to_string())
+    });
+
+    let first = health.run(Probe::Readiness);
+    assert_eq!(first.status, State::Failed);
+    assert_eq!(
+        first.checks[0].message.as_deref(),
+        Some("timed out after 50 ms")
+    );
+    assert!((first.checks[0].latency_ms - 50.0).abs() < f64::EPSILON);
+    // The other check's result isn't lost to the slow one
+    assert_eq!(first.checks[1].status, State::Degraded);
+    assert_eq!(first.checks[1].message.as_deref(), Some("almost full"));
+
+    // Still running: failed at once, without another thread
+    let second = health.run(Probe::Readiness);
+    assert_eq!(second.checks[0].status, State::Failed);
+    assert_eq!(
+        second.checks[0].message.as_deref(),
+        Some("still running after an earlier timeout")
+    );
+    assert_eq!(second.checks[0].latency_ms, 0.0);
+    assert_eq!(started.load(Ordering::SeqCst), 1);
+
+    // Once it has finished it is started again. Closing the gate lets every
+    // later run through; between the check returning and its thread
+    // marking it idle, a run may still see it running.
+    drop(release);
+    done.recv().unwrap();
+    while health.run(Probe::Readiness).checks[0].message.as_deref()
+        == Some("still running after an earlier timeout")
+    {
+        thread::yield_now();
+    }
+    assert_eq!(started.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn a_panicking_check_fails_and_runs_again() {
+    let health = HealthRegistry::new();
+    health.register(
+        "broken",
+        Probe::Liveness,
+        Duration::from_millis(500),
+        || panic!("sensor bus gone"),
+    );
+    for _ in 0..2 {
+        let report = health.run(Probe::Liveness);
+        assert_eq!(report.status, State::Failed);
+        assert_eq!(report.checks[0].message.as_deref(), Some("check panicked"));
+    }
+}
+
+#[test]
+fn progress_fails_once_the_polls_stop() {
+    let status = SharedStatus::default();
+    let clock = Arc::new(FakeClock::new());
+    let check = progress_with_clock(
+        Arc::clone(&status),
+        Duration::from_millis(50),
+        Arc::clone(&clock),
+    );
+    assert_eq!(check(), Health::Ok);
+    clock.advance(Duration::from_millis(50));
+    assert_eq!(check(), Health::Ok);
+    clock.advance(Duration::from_millis(30));
+    assert_eq!(
+        check(),
+        Health::Failed("no poll cycle for 80 ms".to_string())
+    );
+
+    // A poll resets the stall; the time counts from when it was seen
+    status.lock().unwrap().polls += 1;
+    assert_eq!(check(), Health::Ok);
+    clock.advance(Duration::from_millis(50));
+    assert_eq!(check(), Health::Ok);
+    clock.advance(Duration::from_millis(1));
+    assert_eq!(
+        check(),
+        Health::Failed("no poll cycle for 51 ms".to_string())
+    );
+}
@@ -0,0 +1,104 @@
+// `GET /metrics` from the status server after a run on the doubles. The
+// metrics registry is global to the process, so one gateway runs in this
+// test binary and the counts are exact.
+
+use gateway::doubles::{ManualClock, MemoryTransport, ScriptedSensors};
+use gateway::health::SharedHealth;
+use gateway::status::StatusServer;
+use gateway::{Config, Gateway, Parts};
+use shutdown::Shutdown;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::rc::Rc;
+use std::time::Duration;
+
+fn get(addr: SocketAddr, path: &str) -> (String, String) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(stream, "GET {} HTTP/1.0\r\n\r\n", path).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    (head.to_string(), body.to_string())
+}
+
+#[test]
+fn the_status_server_serves_the_gateway_metrics() {
+    let clock = Rc::new(ManualClock::new(1_700_000_000_000));
+    let wire = MemoryTransport::new("collector");
+    let sensors = ScriptedSensors::new().repeat(
+        10,
+        &[
+            (0, "temperature", 21.0),
+            (1, "temperature", 22.0),
+            (2, "humidity", 40.0),
+        ],
+    );
+    let parts = Parts {
+        clock: clock.clone(),
+        sensors: Box::new(sensors),
+        transport: Some(Box::new(wire.clone())),
+        history: None,
+        log: None,
+    };
+    let mut gw = Gateway::with_parts(Config::default(), parts);
+    for _ in 0..10 {
+        clock.advance(Duration::from_millis(100));
+        gw.tick().unwrap();
+    }
+
+    let shutdown = Shutdown::new();
+    let server = StatusServer::start(
+        "127.0.0.1:0",
+        gw.status(),
+        SharedHealth::default(),
+        shutdown.token(),
+    )
+    .unwrap();
+    let (head, body) = get(server.local_addr(), "/metrics");
+    let (status_head, _) = get(server.local_addr(), "/status");
+    shutdown.trigger(shutdown::Reason::Requested("test done"));
+    server.join();
+
+    assert!(head.starts_with("HTTP/1.0 200 OK"));
+    assert!(head.contains("Content-Type: text/plain; version=0.0.4; charset=utf-8"));
+    assert!(head.contains(&format!("Content-Length: {}", body.len())));
+    assert!(status_head.contains("Content-Type: application/json"));
+
+    // 30 readings in 10 polls: one batch of 20 sent, 10 messages queued
+    for family in [
+        "# HELP gateway_polls_total Poll cycles run\n\
+         # TYPE gateway_polls_total counter\n\
+         gateway_polls_total 10\n",
+        "# TYPE gateway_readings_total counter\n\
+         gateway_readings_total 30\n",
+        "# TYPE gateway_batch_queued_messages gauge\n\
+         gateway_batch_queued_messages 10\n",
+        "# TYPE gateway_uplink_batches_total counter\n\
+         gateway_uplink_batches_total{result=\"sent\"} 1\n",
+        "# TYPE gateway_batch_messages histogram\n\
+         gateway_batch_messages_bucket{le=\"1\"} 0\n\
+         gateway_batch_messages_bucket{le=\"5\"} 0\n\
+         gateway_batch_messages_bucket{le=\"10\"} 0\n\
+         gateway_batch_messages_bucket{le=\"20\"} 1\n\
+         gateway_batch_messages_bucket{le=\"50\"} 1\n\
+         gateway_batch_messages_bucket{le=\"+Inf\"} 1\n\
+         gateway_batch_messages_sum 20\n\
+         gateway_batch_messages_count 1\n",
+        "gateway_alerts_raised_total 0\n",
+    ] {
+        assert!(body.contains(family), "missing:\n{}\nin:\n{}", family, body);
+    }
+    // Every sample comes after its family's TYPE line
+    let mut typed = Vec::new();
+    for line in body.lines() {
+        match line.strip_prefix("# TYPE ") {
+            Some(rest) => typed.push(rest.split(' ').next().unwrap().to_string()),
+            None if line.starts_with('#') => {}
+            None => assert!(
+                typed.iter().any(|name| line.starts_with(name.as_str())),
+                "{}",
+                line
+            ),
+        }
+    }
+}
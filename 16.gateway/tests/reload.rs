@@ -0,0 +1,198 @@
+// Hot reload: the diff and its split into live and restart-only changes,
+// the gateway taking live changes without losing state, the debounce on
+// synthetic file events, and the real watcher on a temp dir
+
+use gateway::doubles::{ManualClock, ScriptedSensors};
+use gateway::reload::{self, ConfigWatcher, Debounce};
+use gateway::{Config, ConfigError, Gateway, Parts};
+use notify::event::{AccessKind, CreateKind, DataChange, ModifyKind};
+use notify::{Event, EventKind};
+use rules::AlertEvent;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn keys(changes: &[reload::Change]) -> Vec<&str> {
+    changes.iter().map(|c| c.key.as_str()).collect()
+}
+
+#[test]
+fn diff_lists_each_changed_setting() {
+    let old = Config::default();
+    let mut new = old.clone();
+    new.gateway.poll_interval_ms = 250;
+    new.rules[1].threshold = 3.3;
+    let changes = reload::diff(&old, &new);
+    assert_eq!(
+        keys(&changes),
+        ["gateway.poll_interval_ms", "rules.1.threshold"]
+    );
+    assert_eq!(
+        changes[0].to_string(),
+        "gateway.poll_interval_ms: 100 -> 250"
+    );
+    assert!(reload::diff(&old, &old).is_empty());
+}
+
+#[test]
+fn live_changes_are_applied_and_the_rest_rejected() {
+    let running = Config::default();
+    let mut candidate = running.clone();
+    candidate.gateway.poll_interval_ms = 500;
+    candidate.filter.alpha = 0.5;
+    candidate.rules[0].threshold = 28.0;
+    candidate.sensors.count = 5;
+    candidate.status.bind = "0.0.0.0:9000".to_string();
+
+    let plan = reload::plan(&running, &candidate).unwrap();
+    assert_eq!(
+        keys(&plan.applied),
+        [
+            "filter.alpha",
+            "gateway.poll_interval_ms",
+            "rules.0.threshold"
+        ]
+    );
+    assert_eq!(keys(&plan.rejected), ["sensors.count", "status.bind"]);
+    assert_eq!(plan.config.gateway.poll_interval_ms, 500);
+    assert_eq!(plan.config.filter.alpha, 0.5);
+    assert_eq!(plan.config.rules[0].threshold, 28.0);
+    // What needs a restart keeps its running value
+    assert_eq!(plan.config.sensors.count, running.sensors.count);
+    assert_eq!(plan.config.status.bind, running.status.bind);
+}
+
+#[test]
+fn a_replaced_or_added_rule_needs_a_restart() {
+    let running = Config::default();
+    let mut candidate = running.clone();
+    // Rule 0 becomes a different rule; its new threshold must not be
+    // applied to the old one
+    candidate.rules[0].metric = "humidity".to_string();
+    candidate.rules[0].threshold = 80.0;
+    let mut extra = candidate.rules[1].clone();
+    extra.name = "battery-critical".to_string();
+    candidate.rules.push(extra);
+
+    let plan = reload::plan(&running, &candidate).unwrap();
+    assert!(plan.applied.is_empty());
+    assert!(keys(&plan.rejected).contains(&"rules.0.threshold"));
+    assert!(keys(&plan.rejected).contains(&"rules.2.name"));
+    assert!(plan.rejected.iter().any(|c| c.old.is_none()));
+    assert_eq!(plan.config, running);
+}
+
+#[test]
+fn an_invalid_file_changes_nothing() {
+    let running = Config::default();
+    let mut candidate = running.clone();
+    candidate.gateway.poll_interval_ms = 500;
+    candidate.filter.alpha = 0.0;
+    assert!(matches!(
+        reload::plan(&running, &candidate),
+        Err(ConfigError::Invalid(_))
+    ));
+}
+
+#[test]
+fn the_gateway_takes_a_new_threshold_without_losing_alert_state() {
+    let clock = Rc::new(ManualClock::new(1_700_000_000_000));
+    let sensors = ScriptedSensors::new().repeat(20, &[(0, "temperature", 29.0)]);
+    let parts = Parts {
+        clock: clock.clone(),
+        sensors: Box::new(sensors),
+        transport: None,
+        history: None,
+        log: None,
+    };
+    let mut config = Config::default();
+    config.sensors.count = 1;
+    let mut gw = Gateway::with_parts(config.clone(), parts);
+    let tick = |gw: &mut Gateway| {
+        clock.advance(ms(100));
+        gw.tick().unwrap()
+    };
+
+    // 29 °C is below the 30 °C threshold
+    for _ in 0..8 {
+        assert!(tick(&mut gw).is_empty());
+    }
+    let mut lower = config.clone();
+    lower.rules[0].threshold = 28.0;
+    let plan = reload::plan(gw.config(), &lower).unwrap();
+    gw.reconfigure(plan.config);
+    let mut raised = Vec::new();
+    for _ in 0..8 {
+        raised.extend(tick(&mut gw));
+    }
+    assert_eq!(raised.len(), 1);
+    assert!(matches!(&raised[0], AlertEvent::Raised { rule, .. } if rule == "hot/0"));
+
+    // Another reload that leaves the rule alone keeps the alert raised
+    let mut slower = lower.clone();
+    slower.gateway.poll_interval_ms = 200;
+    gw.reconfigure(reload::plan(gw.config(), &slower).unwrap().config);
+    assert!(tick(&mut gw).is_empty());
+    assert_eq!(gw.config().gateway.poll_interval_ms, 200);
+    assert_eq!(gw.status().lock().unwrap().active_alerts, ["hot/0"]);
+}
+
+fn event(kind: EventKind, path: &str) -> Event {
+    Event::new(kind).add_path(PathBuf::from(path))
+}
+
+#[test]
+fn debounce_waits_for_the_events_of_a_save_to_settle() {
+    let modify = EventKind::Modify(ModifyKind::Data(DataChange::Content));
+    let mut debounce = Debounce::new(Path::new("/etc/gw/gateway.toml"), ms(200));
+    let t0 = Instant::now();
+
+    // Other files in the directory, and reads of ours, don't count
+    assert!(!debounce.event(&event(modify, "/etc/gw/other.toml"), t0));
+    let read = EventKind::Access(AccessKind::Any);
+    assert!(!debounce.event(&event(read, "/etc/gw/gateway.toml"), t0));
+    assert!(!debounce.due(t0 + ms(500)));
+
+    // Truncate, write, write: one reload, 200 ms after the last
+    assert!(debounce.event(&event(modify, "/etc/gw/gateway.toml"), t0));
+    assert!(debounce.event(&event(modify, "/etc/gw/gateway.toml"), t0 + ms(50)));
+    assert!(debounce.event(&event(modify, "/etc/gw/gateway.toml"), t0 + ms(100)));
+    assert!(!debounce.due(t0 + ms(250)));
+    assert!(debounce.due(t0 + ms(300)));
+    assert!(!debounce.due(t0 + ms(400)));
+
+    // A save that renames a temp file into place ends with a create
+    let create = EventKind::Create(CreateKind::File);
+    assert!(debounce.event(&event(create, "/etc/gw/gateway.toml"), t0 + ms(500)));
+    assert!(debounce.due(t0 + ms(700)));
+}
+
+#[test]
+fn the_watcher_sees_a_write_to_the_config_file() {
+    let dir = std::env::temp_dir().join(format!("rust-sys-reload-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("gateway.toml");
+    fs::write(&path, "[gateway]\npoll_interval_ms = 100\n").unwrap();
+
+    let mut watcher = ConfigWatcher::start(&path, ms(50)).unwrap();
+    fs::write(dir.join("notes.txt"), "not the config").unwrap();
+    fs::write(&path, "[gateway]\npoll_interval_ms = 300\n").unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut changed = false;
+    while !changed && Instant::now() < deadline {
+        std::thread::sleep(ms(20));
+        changed = watcher.poll(Instant::now());
+    }
+    assert!(changed, "no change seen within 5 s");
+    let (reloaded, _) = Config::load(Some(&path)).unwrap();
+    let plan = reload::plan(&Config::default(), &reloaded).unwrap();
+    assert_eq!(keys(&plan.applied), ["gateway.poll_interval_ms"]);
+    fs::remove_dir_all(&dir).unwrap();
+}
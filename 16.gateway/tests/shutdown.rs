@@ -0,0 +1,143 @@
+// Ctrl-C, simulated with `Shutdown::signal`: the main loop stops between
+// polls, and what was polled before it reaches the data log and the
+// collector, and the uplink hangs up instead of leaving the socket open.
+
+use datalog::{DataLogger, Entry, LogConfig};
+use gateway::doubles::{ManualClock, MemoryTransport, ScriptedSensors};
+use gateway::health::SharedHealth;
+use gateway::status::{SharedStatus, StatusServer};
+use gateway::{Config, Gateway, Parts};
+use shutdown::{Reason, Shutdown};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const START_MS: u64 = 1_699_941_590_000;
+const POLL: Duration = Duration::from_millis(100);
+
+fn sensors(polls: usize) -> ScriptedSensors {
+    ScriptedSensors::new().repeat(
+        polls,
+        &[
+            (0, "temperature", 21.0),
+            (1, "temperature", 22.0),
+            (2, "humidity", 40.0),
+        ],
+    )
+}
+
+#[test]
+fn a_signal_mid_run_still_flushes_the_log_and_the_uplink() {
+    let dir =
+        std::env::temp_dir().join(format!("rust-sys-gateway-shutdown-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let (log, _) = DataLogger::open(LogConfig::new(&dir)).unwrap();
+    let clock = Rc::new(ManualClock::new(START_MS));
+    let wire = MemoryTransport::new("collector");
+    let parts = Parts {
+        clock: clock.clone(),
+        sensors: Box::new(sensors(100)),
+        transport: Some(Box::new(wire.clone())),
+        history: None,
+        log: Some(log),
+    };
+    let mut gw = Gateway::with_parts(Config::default(), parts);
+
+    // The main loop from `gateway run`, with the Ctrl-C arriving on
+    // another thread after the 15th poll
+    let shutdown = Shutdown::new();
+    let token = shutdown.token();
+    let mut polls = 0;
+    while !token.is_cancelled() {
+        clock.advance(POLL);
+        gw.tick().unwrap();
+        polls += 1;
+        if polls == 15 {
+            let handler = shutdown.clone();
+            thread::spawn(move || handler.signal()).join().unwrap();
+        }
+        // The sleep between polls, which a signal cuts short
+        token.wait_timeout(Duration::ZERO);
+    }
+    assert_eq!(polls, 15);
+    assert_eq!(token.reason(), Some(Reason::Signal));
+    // Two full batches of 20 went out while running; 5 messages wait
+    assert_eq!(wire.batches().len(), 2);
+    assert!(wire.is_connected());
+
+    let status = gw.shutdown().unwrap();
+    assert_eq!(status.readings, 45);
+    assert_eq!(status.batches_pending, 0);
+    let batches = wire.batches();
+    let delivered: usize = batches.iter().map(|b| b.messages.len()).sum();
+    assert_eq!(delivered, 45);
+    assert!(!wire.is_connected(), "the uplink hangs up at shutdown");
+
+    let logged = datalog::read_dir(&dir)
+        .unwrap()
+        .into_iter()
+        .filter(|e| matches!(e, Entry::Record(_)))
+        .count();
+    assert_eq!(logged, 45);
+    assert_eq!(status.logged_records, 45);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn shutdown_without_a_connection_connects_once_to_flush() {
+    let clock = Rc::new(ManualClock::new(START_MS));
+    let wire = MemoryTransport::new("collector");
+    let parts = Parts {
+        clock: clock.clone(),
+        sensors: Box::new(sensors(2)),
+        transport: Some(Box::new(wire.clone())),
+        history: None,
+        log: None,
+    };
+    let mut gw = Gateway::with_parts(Config::default(), parts);
+    for _ in 0..2 {
+        clock.advance(POLL);
+        gw.tick().unwrap();
+    }
+    // Six messages, short of a batch: nothing sent, never connected
+    assert_eq!(wire.connects(), 0);
+    gw.shutdown().unwrap();
+    assert_eq!(wire.connects(), 1);
+    assert_eq!(wire.batches().len(), 1);
+    assert!(!wire.is_connected());
+}
+
+fn get(addr: std::net::SocketAddr) -> std::io::Result<String> {
+    let mut stream = TcpStream::connect(addr)?;
+    stream.write_all(b"GET /status HTTP/1.0\r\n\r\n")?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    Ok(response)
+}
+
+#[test]
+fn the_status_server_stops_on_a_signal() {
+    let shutdown = Shutdown::new();
+    let status = SharedStatus::default();
+    status.lock().unwrap().gateway_id = "gw-test".into();
+    let server = StatusServer::start(
+        "127.0.0.1:0",
+        status,
+        SharedHealth::default(),
+        shutdown.token(),
+    )
+    .unwrap();
+    let addr = server.local_addr();
+    let response = get(addr).unwrap();
+    assert!(response.starts_with("HTTP/1.0 200 OK"));
+    assert!(response.contains("gw-test"));
+
+    let signalled = Instant::now();
+    shutdown.signal();
+    server.join();
+    assert!(signalled.elapsed() < Duration::from_secs(1));
+    // The listener went with the thread
+    assert!(get(addr).is_err());
+}
@@ -0,0 +1,234 @@
+// Wildcard matching through the router: each row subscribes one filter,
+// publishes one topic and checks whether the handler ran, both for a live
+// publish and for the retained replay on subscribe.
+
+use broker::TopicError;
+use gateway::TopicRouter;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const MATCHES: &[(&str, &str, bool)] = &[
+    // Exact
+    ("sensors/kitchen/temp", "sensors/kitchen/temp", true),
+    ("sensors/kitchen/temp", "sensors/kitchen/rh", false),
+    ("sensors/kitchen", "sensors/kitchen/temp", false),
+    ("sensors/kitchen/temp", "sensors/kitchen", false),
+    ("Sensors/kitchen", "sensors/kitchen", false),
+    // '+' is exactly one level
+    ("sensors/+/temp", "sensors/kitchen/temp", true),
+    ("sensors/+/temp", "sensors/kitchen/rh", false),
+    ("sensors/+/temp", "sensors/temp", false),
+    ("sensors/+/temp", "sensors/a/b/temp", false),
+    ("sensors/+", "sensors/kitchen", true),
+    ("sensors/+", "sensors", false),
+    ("+/+/temp", "sensors/kitchen/temp", true),
+    ("+", "sensors", true),
+    ("+", "sensors/kitchen", false),
+    // '#' is the parent level and everything below it
+    ("sensors/#", "sensors", true),
+    ("sensors/#", "sensors/kitchen", true),
+    ("sensors/#", "sensors/kitchen/temp", true),
+    ("sensors/#", "actuators/fan", false),
+    ("sensors/#", "sensorsx", false),
+    ("sensors/+/#", "sensors/kitchen", true),
+    ("sensors/+/#", "sensors/kitchen/temp/raw", true),
+    ("sensors/+/#", "sensors", false),
+    ("#", "sensors/kitchen/temp", true),
+    // Empty levels are levels like any other
+    ("sensors//temp", "sensors//temp", true),
+    ("sensors/+/temp", "sensors//temp", true),
+    ("sensors/+", "sensors/", true),
+    ("sensors/#", "sensors/", true),
+    ("sensors/temp", "sensors//temp", false),
+    ("+/sensors", "/sensors", true),
+    ("#", "/sensors", true),
+    ("/#", "/sensors", true),
+    ("/#", "sensors", false),
+    ("+", "/", false),
+    ("+/+", "/", true),
+    // '$' topics are hidden from a leading wildcard only
+    ("#", "$SYS/uptime", false),
+    ("+/uptime", "$SYS/uptime", false),
+    ("$SYS/#", "$SYS/uptime", true),
+    ("$SYS/+", "$SYS/uptime", true),
+    ("$SYS/uptime", "$SYS/uptime", true),
+    ("+", "$SYS", false),
+    ("sensors/#", "sensors/$internal", true),
+    ("sensors/+", "sensors/$internal", true),
+];
+
+fn counting(router: &mut TopicRouter, filter: &str) -> Rc<RefCell<Vec<(String, bool)>>> {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let sink = Rc::clone(&seen);
+    router
+        .subscribe(filter, move |d| {
+            sink.borrow_mut().push((d.topic.to_string(), d.retained))
+        })
+        .unwrap_or_else(|e| panic!("filter {:?} rejected: {}", filter, e));
+    seen
+}
+
+#[test]
+fn wildcard_match_table() {
+    for &(filter, topic, expected) in MATCHES {
+        let mut router = TopicRouter::new();
+        let seen = counting(&mut router, filter);
+        let called = router.publish(topic, b"1", false).unwrap();
+        assert_eq!(called == 1, expected, "{:?} against {:?}", filter, topic);
+        let expected_seen: Vec<(String, bool)> = if expected {
+            vec![(topic.to_string(), false)]
+        } else {
+            Vec::new()
+        };
+        assert_eq!(*seen.borrow(), expected_seen);
+    }
+}
+
+// The examples from MQTT 3.1.1 section 4.7, plus the usual traps, as
+// `cargo run --bin topics` prints them
+const SPEC_EXAMPLES: &[(&str, &str, bool)] = &[
+    // Exact levels
+    ("sport/tennis/player", "sport/tennis/player", true),
+    ("sport/tennis/player", "sport/tennis", false),
+    ("sport/tennis", "Sport/tennis", false),
+    // '#' matches the parent level too, and any depth below it
+    ("sport/#", "sport", true),
+    ("sport/#", "sport/tennis/player/ranking", true),
+    ("sport/#", "sports", false),
+    ("sport/tennis/#", "sport/tennis", true),
+    ("#", "sport/tennis/player", true),
+    // '+' matches exactly one level, which may be empty
+    ("sport/+/player", "sport/tennis/player", true),
+    ("sport/+/player", "sport/player", false),
+    ("sport/+/player", "sport/tennis/player/ranking", false),
+    ("sport/+/player", "sport//player", true),
+    ("sport/+", "sport", false),
+    ("sport/+", "sport/", true),
+    ("+", "sport", true),
+    ("+", "/sport", false),
+    ("+/+", "/sport", true),
+    ("/+", "/sport", true),
+    ("+/tennis/#", "sport/tennis", true),
+    // '$' topics are hidden from leading wildcards
+    ("#", "$SYS/uptime", false),
+    ("+/uptime", "$SYS/uptime", false),
+    ("$SYS/#", "$SYS/uptime", true),
+    ("$SYS/+", "$SYS/uptime", true),
+];
+
+#[test]
+fn the_spec_examples_match() {
+    for &(filter, topic, expected) in SPEC_EXAMPLES {
+        let mut router = TopicRouter::new();
+        router.subscribe(filter, |_| {}).unwrap();
+        let called = router.publish(topic, b"x", false).unwrap();
+        assert_eq!(called == 1, expected, "{:?} against {:?}", filter, topic);
+    }
+}
+
+#[test]
+fn retained_replay_follows_the_same_table() {
+    for &(filter, topic, expected) in MATCHES {
+        let mut router = TopicRouter::new();
+        router.publish(topic, b"1", true).unwrap();
+        let seen = counting(&mut router, filter);
+        let expected_seen: Vec<(String, bool)> = if expected {
+            vec![(topic.to_string(), true)]
+        } else {
+            Vec::new()
+        };
+        assert_eq!(
+            *seen.borrow(),
+            expected_seen,
+            "{:?} against retained {:?}",
+            filter,
+            topic
+        );
+    }
+}
+
+#[test]
+fn invalid_filters_are_rejected() {
+    let cases = [
+        ("", TopicError::Empty),
+        ("sensors/#/temp", TopicError::HashNotLast),
+        ("#/sensors", TopicError::HashNotLast),
+        ("sensors/#/#", TopicError::HashNotLast),
+        ("sensors/kitchen#", TopicError::WildcardNotWholeLevel),
+        ("sensors/+kitchen", TopicError::WildcardNotWholeLevel),
+        ("sensors/kit+chen/temp", TopicError::WildcardNotWholeLevel),
+        ("++", TopicError::WildcardNotWholeLevel),
+        ("sensors/##", TopicError::WildcardNotWholeLevel),
+    ];
+    let mut router = TopicRouter::new();
+    for (filter, error) in cases {
+        assert_eq!(
+            router.subscribe(filter, |_| {}).err(),
+            Some(error),
+            "filter {:?}",
+            filter
+        );
+    }
+    assert_eq!(router.filters().count(), 0);
+}
+
+#[test]
+fn invalid_topics_are_rejected_and_not_retained() {
+    let mut router = TopicRouter::new();
+    let seen = counting(&mut router, "#");
+    for (topic, error) in [
+        ("", TopicError::Empty),
+        ("sensors/+/temp", TopicError::WildcardInTopic),
+        ("sensors/#", TopicError::WildcardInTopic),
+        ("sensors/kitchen+", TopicError::WildcardInTopic),
+    ] {
+        assert_eq!(router.publish(topic, b"1", true), Err(error), "{:?}", topic);
+    }
+    assert_eq!(router.retained_count(), 0);
+    assert!(seen.borrow().is_empty());
+}
+
+#[test]
+fn clearing_a_topic_never_retained_interns_nothing() {
+    let mut router = TopicRouter::new();
+    let seen = counting(&mut router, "alerts/#");
+    for n in 0..100 {
+        let topic = format!("alerts/rule-{}", n);
+        assert_eq!(router.publish(&topic, b"", true), Ok(1));
+    }
+    assert_eq!(seen.borrow().len(), 100);
+    assert_eq!(router.retained_count(), 0);
+    assert_eq!(router.interned_topics(), 0);
+
+    // Storing interns; clearing and storing again reuses the symbol
+    router.publish("alerts/rule-1", b"raised", true).unwrap();
+    router.publish("alerts/rule-1", b"", true).unwrap();
+    assert_eq!(router.retained("alerts/rule-1"), None);
+    assert_eq!(router.retained_count(), 0);
+    router.publish("alerts/rule-1", b"raised", true).unwrap();
+    assert_eq!(router.retained("alerts/rule-1"), Some(&b"raised"[..]));
+    assert_eq!(router.interned_topics(), 1);
+}
+
+#[test]
+fn overlapping_filters_each_get_one_call_in_subscription_order() {
+    let mut router = TopicRouter::new();
+    let order = Rc::new(RefCell::new(Vec::new()));
+    for filter in [
+        "sensors/#",
+        "sensors/+/temp",
+        "sensors/kitchen/temp",
+        "#",
+        "+/+",
+    ] {
+        let order = Rc::clone(&order);
+        router
+            .subscribe(filter, move |_| order.borrow_mut().push(filter))
+            .unwrap();
+    }
+    assert_eq!(router.publish("sensors/kitchen/temp", b"21", false), Ok(4));
+    assert_eq!(
+        *order.borrow(),
+        ["sensors/#", "sensors/+/temp", "sensors/kitchen/temp", "#"]
+    );
+}
@@ -0,0 +1,75 @@
+// The uplink's circuit breaker as seen through the metrics registry. The
+// registry is global to the process, so this test binary drives one
+// uplink and the counts are exact.
+
+use circuitbreaker::State;
+use gateway::doubles::{ManualClock, MemoryTransport};
+use gateway::uplink::{Batch, Uplink};
+use std::rc::Rc;
+use std::time::Duration;
+
+fn transitions(to: &str) -> u64 {
+    metrics::REGISTRY
+        .counter_with(
+            "gateway_uplink_circuit_transitions_total",
+            "Uplink circuit breaker state changes, by new state",
+            &[("to", to)],
+        )
+        .get()
+}
+
+fn batch(seq: u64) -> Batch {
+    Batch {
+        gateway: "gw-test".to_string(),
+        boot: 0,
+        seq,
+        messages: Vec::new(),
+    }
+}
+
+#[test]
+fn an_open_close_cycle_counts_each_transition() {
+    let clock = Rc::new(ManualClock::new(1_700_000_000_000));
+    let wire = MemoryTransport::new("collector");
+    let mut uplink = Uplink::new(Box::new(wire.clone()), clock.clone(), 16);
+    uplink.enqueue(batch(1));
+
+    // Every connect fails; past the backoff each flush is one more attempt
+    wire.set_reachable(false);
+    let mut attempts = 0;
+    while uplink.stats().circuit != State::Open {
+        attempts += 1;
+        assert!(attempts <= 10, "the circuit never opened");
+        clock.advance(Duration::from_secs(31));
+        uplink.flush(false);
+    }
+    assert_eq!(attempts, 4, "opens once min_calls attempts have failed");
+    assert_eq!(transitions("open"), 1);
+    assert_eq!(transitions("half-open"), 0);
+    assert_eq!(transitions("closed"), 0);
+
+    // Inside the cool-down nothing is tried and nothing changes
+    clock.advance(Duration::from_secs(5));
+    uplink.flush(false);
+    assert_eq!(uplink.stats().circuit, State::Open);
+    assert_eq!(transitions("open"), 1);
+
+    // After it, one probe goes through and closes the circuit again
+    wire.set_reachable(true);
+    clock.advance(Duration::from_secs(31));
+    uplink.flush(false);
+    assert_eq!(uplink.stats().circuit, State::Closed);
+    assert_eq!(wire.batches().len(), 1);
+    assert_eq!(transitions("open"), 1);
+    assert_eq!(transitions("half-open"), 1);
+    assert_eq!(transitions("closed"), 1);
+
+    let text = metrics::render();
+    for line in [
+        "gateway_uplink_circuit_transitions_total{to=\"closed\"} 1\n",
+        "gateway_uplink_circuit_transitions_total{to=\"half-open\"} 1\n",
+        "gateway_uplink_circuit_transitions_total{to=\"open\"} 1\n",
+    ] {
+        assert!(text.contains(line), "{:?} missing from\n{}", line, text);
+    }
+}
@@ -0,0 +1,183 @@
+// Classic CAN 2.0 frames
+//
+// Standard identifiers are 11 bits, extended identifiers 29 bits. A classic
+// frame carries 0..=8 data bytes; the DLC is the data length.
+//
+// The byte layout used by `to_bytes`/`from_bytes` mirrors Linux SocketCAN's
+// `struct can_frame`: a 32-bit id word (bit 31 = extended, bit 30 = remote),
+// the DLC, three padding bytes and eight data bytes.
+
+use std::fmt;
+
+pub const MAX_STANDARD_ID: u32 = 0x7FF;
+pub const MAX_EXTENDED_ID: u32 = 0x1FFF_FFFF;
+pub const FRAME_BYTES: usize = 16;
+
+const EFF_FLAG: u32 = 0x8000_0000;
+const RTR_FLAG: u32 = 0x4000_0000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CanId {
+    Standard(u16),
+    Extended(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameError {
+    IdOutOfRange(u32),
+    TooMuchData(usize),
+    InvalidDlc(u8),
+    WrongLength(usize),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::IdOutOfRange(id) => write!(f, "identifier 0x{:X} out of range", id),
+            FrameError::TooMuchData(n) => write!(f, "{} data bytes (max 8)", n),
+            FrameError::InvalidDlc(dlc) => write!(f, "invalid DLC {}", dlc),
+            FrameError::WrongLength(n) => write!(f, "expected {} bytes, got {}", FRAME_BYTES, n),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+impl CanId {
+    pub fn standard(id: u16) -> Result<CanId, FrameError> {
+        if id as u32 > MAX_STANDARD_ID {
+            return Err(FrameError::IdOutOfRange(id as u32));
+        }
+        Ok(CanId::Standard(id))
+    }
+
+    pub fn extended(id: u32) -> Result<CanId, FrameError> {
+        if id > MAX_EXTENDED_ID {
+            return Err(FrameError::IdOutOfRange(id));
+        }
+        Ok(CanId::Extended(id))
+    }
+
+    pub fn raw(&self) -> u32 {
+        match self {
+            CanId::Standard(id) => *id as u32,
+            CanId::Extended(id) => *id,
+        }
+    }
+}
+
+impl fmt::Display for CanId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CanId::Standard(id) => write!(f, "{:03X}", id),
+            CanId::Extended(id) => write!(f, "{:08X}", id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanFrame {
+    id: CanId,
+    remote: bool,
+    dlc: u8,
+    data: [u8; 8],
+}
+
+impl CanFrame {
+    pub fn new(id: CanId, data: &[u8]) -> Result<CanFrame, FrameError> {
+        if data.len() > 8 {
+            return Err(FrameError::TooMuchData(data.len()));
+        }
+        let mut buf = [0u8; 8];
+        buf[..data.len()].copy_from_slice(data);
+        Ok(CanFrame {
+            id,
+            remote: false,
+            dlc: data.len() as u8,
+            data: buf,
+        })
+    }
+
+    // A remote frame requests data; it has a DLC but no payload
+    pub fn remote(id: CanId, dlc: u8) -> Result<CanFrame, FrameError> {
+        if dlc > 8 {
+            return Err(FrameError::InvalidDlc(dlc));
+        }
+        Ok(CanFrame {
+            id,
+            remote: true,
+            dlc,
+            data: [0; 8],
+        })
+    }
+
+    pub fn id(&self) -> CanId {
+        self.id
+    }
+
+    pub fn is_remote(&self) -> bool {
+        self.remote
+    }
+
+    pub fn dlc(&self) -> u8 {
+        self.dlc
+    }
+
+    pub fn data(&self) -> &[u8] {
+        if self.remote {
+            &[]
+        } else {
+            &self.data[..self.dlc as usize]
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; FRAME_BYTES] {
+        let mut word = self.id.raw();
+        if let CanId::Extended(_) = self.id {
+            word |= EFF_FLAG;
+        }
+        if self.remote {
+            word |= RTR_FLAG;
+        }
+
+        let mut out = [0u8; FRAME_BYTES];
+        out[0..4].copy_from_slice(&word.to_le_bytes());
+        out[4] = self.dlc;
+        out[8..16].copy_from_slice(&self.data);
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<CanFrame, FrameError> {
+        if bytes.len() != FRAME_BYTES {
+            return Err(FrameError::WrongLength(bytes.len()));
+        }
+        let word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let id = if word & EFF_FLAG != 0 {
+            CanId::extended(word & MAX_EXTENDED_ID)?
+        } else {
+            CanId::standard((word & MAX_STANDARD_ID) as u16)?
+        };
+        let dlc = bytes[4];
+        if dlc > 8 {
+            return Err(FrameError::InvalidDlc(dlc));
+        }
+        if word & RTR_FLAG != 0 {
+            return CanFrame::remote(id, dlc);
+        }
+        CanFrame::new(id, &bytes[8..8 + dlc as usize])
+    }
+}
+
+// candump-style: `123#DEADBEEF`, `18FEF100#...`, `123#R`
+impl fmt::Display for CanFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}#", self.id)?;
+        if self.remote {
+            return write!(f, "R");
+        }
+        for b in self.data() {
+            write!(f, "{:02X}", b)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,7 @@
+// CAN bus frames and DBC-lite signal decoding
+
+mod frame;
+mod signal;
+
+pub use frame::{CanFrame, CanId, FrameError, FRAME_BYTES, MAX_EXTENDED_ID, MAX_STANDARD_ID};
+pub use signal::{ByteOrder, MessageDef, Signal, SignalError};
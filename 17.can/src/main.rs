@@ -0,0 +1,170 @@
+use can::{ByteOrder, CanFrame, CanId, MessageDef, Signal};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn intel(
+    name: &'static str,
+    start_bit: u16,
+    length: u16,
+    scale: f64,
+    offset: f64,
+    unit: &'static str,
+) -> Signal {
+    Signal {
+        name,
+        start_bit,
+        length,
+        byte_order: ByteOrder::Intel,
+        signed: false,
+        scale,
+        offset,
+        unit,
+    }
+}
+
+// J1939 Electronic Engine Controller 1 (a subset of its signals)
+fn eec1() -> MessageDef {
+    MessageDef {
+        name: "EEC1",
+        id: 0x0CF0_0400,
+        dlc: 8,
+        signals: vec![
+            intel("ActualTorque", 16, 8, 1.0, -125.0, "%"),
+            intel("EngineSpeed", 24, 16, 0.125, 0.0, "rpm"),
+        ],
+    }
+}
+
+// A made-up steering sensor using Motorola byte order and a signed value
+fn steering() -> MessageDef {
+    MessageDef {
+        name: "Steering",
+        id: 0x1A0,
+        dlc: 4,
+        signals: vec![
+            Signal {
+                name: "Angle",
+                start_bit: 7,
+                length: 16,
+                byte_order: ByteOrder::Motorola,
+                signed: true,
+                scale: 0.1,
+                offset: 0.0,
+                unit: "deg",
+            },
+            Signal {
+                name: "Rate",
+                start_bit: 23,
+                length: 12,
+                byte_order: ByteOrder::Motorola,
+                signed: false,
+                scale: 1.0,
+                offset: 0.0,
+                unit: "deg/s",
+            },
+        ],
+    }
+}
+
+fn main() {
+    println!("=== CAN Bus Frames and Signals ===\n");
+
+    // 1. Identifiers and frames
+    println!("1. Identifiers and frames:");
+    let frames = [
+        CanFrame::new(CanId::standard(0x123).unwrap(), &[0xDE, 0xAD, 0xBE, 0xEF]).unwrap(),
+        CanFrame::new(CanId::extended(0x18FE_EE00).unwrap(), &[0x5A]).unwrap(),
+        CanFrame::remote(CanId::standard(0x7DF).unwrap(), 8).unwrap(),
+    ];
+    for frame in &frames {
+        println!(
+            "   {:<20} dlc={} remote={}",
+            frame.to_string(),
+            frame.dlc(),
+            frame.is_remote()
+        );
+    }
+    println!("   standard 0x800: {:?}", CanId::standard(0x800));
+    println!(
+        "   9 data bytes:   {:?}",
+        CanFrame::new(CanId::Standard(1), &[0; 9])
+    );
+
+    // 2. SocketCAN layout round trip
+    println!("\n2. SocketCAN byte layout:");
+    for frame in &frames {
+        let bytes = frame.to_bytes();
+        let back = CanFrame::from_bytes(&bytes).unwrap();
+        println!("   {}  round trip: {}", hex(&bytes), back == *frame);
+    }
+
+    // 3. Decoding a captured J1939 frame
+    println!("\n3. Decoding EEC1 (1500 rpm, 20% torque):");
+    let captured = [0xFF, 0xFF, 0x91, 0xE0, 0x2E, 0xFF, 0xFF, 0xFF];
+    let def = eec1();
+    let frame = CanFrame::new(CanId::extended(def.id).unwrap(), &captured).unwrap();
+    println!("   frame: {}", frame);
+    for (name, value, unit) in def.decode(frame.data()).unwrap() {
+        println!("   {:<13} {:>8.2} {}", name, value, unit);
+    }
+
+    // 4. Motorola byte order and signed values
+    println!("\n4. Motorola signals:");
+    let def = steering();
+    let data = def.encode(&[("Angle", -12.5), ("Rate", 300.0)]).unwrap();
+    println!("   encoded: {}", hex(&data));
+    for signal in &def.signals {
+        println!(
+            "   {:<6} raw=0x{:04X} -> {} {}",
+            signal.name,
+            signal.raw(&data).unwrap(),
+            signal.decode(&data).unwrap(),
+            signal.unit
+        );
+    }
+
+    // 5. Encode/decode round trips
+    println!("\n5. Round trips:");
+    let cases: [(&str, MessageDef, &str, f64); 6] = [
+        ("EEC1", eec1(), "EngineSpeed", 0.0),
+        ("EEC1", eec1(), "EngineSpeed", 8031.875),
+        ("EEC1", eec1(), "ActualTorque", -125.0),
+        ("EEC1", eec1(), "ActualTorque", 130.0),
+        ("Steering", steering(), "Angle", 3276.7),
+        ("Steering", steering(), "Angle", -3276.8),
+    ];
+    for (msg, def, name, value) in cases {
+        let data = def.encode(&[(name, value)]).unwrap();
+        let decoded = def.decode(&data).unwrap();
+        let back = decoded.iter().find(|(n, _, _)| *n == name).unwrap().1;
+        println!(
+            "   {:<8} {:<12} {:>9.3} -> {:<23} -> {:>9.3}",
+            msg,
+            name,
+            value,
+            hex(&data),
+            back
+        );
+    }
+
+    // 6. Errors
+    println!("\n6. Errors:");
+    let def = eec1();
+    match def.encode(&[("EngineSpeed", 9000.0)]) {
+        Ok(data) => println!("   unexpected: {}", hex(&data)),
+        Err(e) => println!("   {}", e),
+    }
+    let short = [0x00, 0x01];
+    match def.decode(&short) {
+        Ok(values) => println!("   unexpected: {:?}", values),
+        Err(e) => println!("   {}", e),
+    }
+
+    println!("\n=== End of CAN Examples ===");
+}
@@ -0,0 +1,182 @@
+// DBC-lite signal definitions
+//
+// A signal is a bit field inside a frame's data, converted to an engineering
+// value with `physical = raw * scale + offset`.
+//
+// Bit numbering follows DBC files: bit n is bit (n % 8) of byte (n / 8).
+// - Intel (little-endian): `start_bit` is the least significant bit and the
+//   field grows towards higher bit numbers.
+// - Motorola (big-endian): `start_bit` is the most significant bit; the field
+//   continues towards bit 0 of that byte, then into bit 7 of the next byte.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    Intel,
+    Motorola,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signal {
+    pub name: &'static str,
+    pub start_bit: u16,
+    pub length: u16,
+    pub byte_order: ByteOrder,
+    pub signed: bool,
+    pub scale: f64,
+    pub offset: f64,
+    pub unit: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignalError {
+    OutOfFrame { name: &'static str },
+    OutOfRange { name: &'static str, value: f64 },
+    // A name passed to `MessageDef::encode` that the message doesn't have
+    UnknownSignal { name: String },
+}
+
+impl fmt::Display for SignalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignalError::OutOfFrame { name } => write!(f, "signal {} does not fit the frame", name),
+            SignalError::OutOfRange { name, value } => {
+                write!(f, "value {} does not fit signal {}", value, name)
+            }
+            SignalError::UnknownSignal { name } => write!(f, "no signal named {}", name),
+        }
+    }
+}
+
+impl std::error::Error for SignalError {}
+
+// Bit positions occupied by the signal, most significant first
+fn bit_positions(signal: &Signal) -> Vec<u16> {
+    let len = signal.length;
+    match signal.byte_order {
+        ByteOrder::Intel => (0..len).rev().map(|i| signal.start_bit + i).collect(),
+        ByteOrder::Motorola => {
+            let mut positions = Vec::with_capacity(len as usize);
+            let mut bit = signal.start_bit;
+            for _ in 0..len {
+                positions.push(bit);
+                bit = if bit.is_multiple_of(8) {
+                    bit + 15
+                } else {
+                    bit - 1
+                };
+            }
+            positions
+        }
+    }
+}
+
+impl Signal {
+    fn check_fits(&self, data_len: usize) -> Result<Vec<u16>, SignalError> {
+        let positions = bit_positions(self);
+        if self.length == 0
+            || self.length > 64
+            || positions.iter().any(|&b| b as usize >= data_len * 8)
+        {
+            return Err(SignalError::OutOfFrame { name: self.name });
+        }
+        Ok(positions)
+    }
+
+    pub fn raw(&self, data: &[u8]) -> Result<u64, SignalError> {
+        let mut raw = 0u64;
+        for bit in self.check_fits(data.len())? {
+            let set = data[bit as usize / 8] >> (bit % 8) & 1;
+            raw = raw << 1 | set as u64;
+        }
+        Ok(raw)
+    }
+
+    pub fn decode(&self, data: &[u8]) -> Result<f64, SignalError> {
+        let raw = self.raw(data)?;
+        let value = if self.signed && self.length < 64 && raw >> (self.length - 1) & 1 == 1 {
+            // Sign-extend from `length` bits
+            (raw | !0u64 << self.length) as i64 as f64
+        } else if self.signed {
+            raw as i64 as f64
+        } else {
+            raw as f64
+        };
+        Ok(value * self.scale + self.offset)
+    }
+
+    pub fn encode(&self, value: f64, data: &mut [u8]) -> Result<(), SignalError> {
+        let positions = self.check_fits(data.len())?;
+        let scaled = ((value - self.offset) / self.scale).round();
+        let bits = self.length as u32;
+        let (min, max) = if self.signed {
+            (
+                -(2f64.powi(bits as i32 - 1)),
+                2f64.powi(bits as i32 - 1) - 1.0,
+            )
+        } else {
+            (0.0, 2f64.powi(bits as i32) - 1.0)
+        };
+        if !(min..=max).contains(&scaled) {
+            return Err(SignalError::OutOfRange {
+                name: self.name,
+                value,
+            });
+        }
+
+        // Through i64 for two's complement; an unsigned 64-bit field can
+        // hold values above i64::MAX, which that cast would saturate
+        let raw = if self.signed {
+            scaled as i64 as u64
+        } else {
+            scaled as u64
+        };
+        for (i, &bit) in positions.iter().rev().enumerate() {
+            let byte = &mut data[bit as usize / 8];
+            let mask = 1u8 << (bit % 8);
+            if raw >> i & 1 == 1 {
+                *byte |= mask;
+            } else {
+                *byte &= !mask;
+            }
+        }
+        Ok(())
+    }
+}
+
+// A message definition: which frame id carries which signals
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageDef {
+    pub name: &'static str,
+    pub id: u32,
+    pub dlc: u8,
+    pub signals: Vec<Signal>,
+}
+
+impl MessageDef {
+    pub fn decode(
+        &self,
+        data: &[u8],
+    ) -> Result<Vec<(&'static str, f64, &'static str)>, SignalError> {
+        self.signals
+            .iter()
+            .map(|s| s.decode(data).map(|v| (s.name, v, s.unit)))
+            .collect()
+    }
+
+    pub fn encode(&self, values: &[(&str, f64)]) -> Result<Vec<u8>, SignalError> {
+        let mut data = vec![0u8; self.dlc as usize];
+        for (name, value) in values {
+            let signal = self
+                .signals
+                .iter()
+                .find(|s| s.name == *name)
+                .ok_or_else(|| SignalError::UnknownSignal {
+                    name: name.to_string(),
+                })?;
+            signal.encode(*value, &mut data)?;
+        }
+        Ok(data)
+    }
+}
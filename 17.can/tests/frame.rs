@@ -0,0 +1,97 @@
+use can::{CanFrame, CanId, FrameError, FRAME_BYTES};
+
+#[test]
+fn identifiers_are_range_checked() {
+    assert_eq!(CanId::standard(0x7FF), Ok(CanId::Standard(0x7FF)));
+    assert_eq!(CanId::standard(0x800), Err(FrameError::IdOutOfRange(0x800)));
+    assert_eq!(
+        CanId::extended(0x1FFF_FFFF),
+        Ok(CanId::Extended(0x1FFF_FFFF))
+    );
+    assert_eq!(
+        CanId::extended(0x2000_0000),
+        Err(FrameError::IdOutOfRange(0x2000_0000))
+    );
+}
+
+#[test]
+fn frames_hold_at_most_eight_bytes() {
+    assert_eq!(
+        CanFrame::new(CanId::Standard(1), &[0; 9]),
+        Err(FrameError::TooMuchData(9))
+    );
+    assert_eq!(
+        CanFrame::remote(CanId::Standard(1), 9),
+        Err(FrameError::InvalidDlc(9))
+    );
+    let empty = CanFrame::new(CanId::Standard(1), &[]).unwrap();
+    assert_eq!(empty.dlc(), 0);
+    assert!(empty.data().is_empty());
+}
+
+#[test]
+fn remote_frames_have_a_dlc_but_no_data() {
+    let frame = CanFrame::remote(CanId::Standard(0x7DF), 8).unwrap();
+    assert!(frame.is_remote());
+    assert_eq!(frame.dlc(), 8);
+    assert!(frame.data().is_empty());
+}
+
+#[test]
+fn candump_formatting() {
+    let standard = CanFrame::new(CanId::Standard(0x123), &[0xDE, 0xAD, 0xBE, 0xEF]).unwrap();
+    let extended = CanFrame::new(CanId::Extended(0x18FE_EE00), &[0x5A]).unwrap();
+    let remote = CanFrame::remote(CanId::Standard(0x7DF), 8).unwrap();
+    assert_eq!(standard.to_string(), "123#DEADBEEF");
+    assert_eq!(extended.to_string(), "18FEEE00#5A");
+    assert_eq!(remote.to_string(), "7DF#R");
+}
+
+#[test]
+fn socketcan_layout_is_pinned() {
+    let standard = CanFrame::new(CanId::Standard(0x123), &[0xDE, 0xAD, 0xBE, 0xEF]).unwrap();
+    assert_eq!(
+        standard.to_bytes(),
+        [0x23, 0x01, 0, 0, 4, 0, 0, 0, 0xDE, 0xAD, 0xBE, 0xEF, 0, 0, 0, 0]
+    );
+    // Bit 31 marks an extended id, bit 30 a remote frame
+    let extended = CanFrame::new(CanId::Extended(0x18FE_EE00), &[0x5A]).unwrap();
+    assert_eq!(extended.to_bytes()[..5], [0x00, 0xEE, 0xFE, 0x98, 1]);
+    let remote = CanFrame::remote(CanId::Standard(0x7DF), 8).unwrap();
+    assert_eq!(remote.to_bytes()[..5], [0xDF, 0x07, 0x00, 0x40, 8]);
+}
+
+#[test]
+fn socketcan_layout_round_trips() {
+    let frames = [
+        CanFrame::new(CanId::Standard(0), &[]).unwrap(),
+        CanFrame::new(CanId::Standard(0x7FF), &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap(),
+        CanFrame::new(CanId::Extended(0x1FFF_FFFF), &[0xFF; 3]).unwrap(),
+        CanFrame::remote(CanId::Extended(0x100), 2).unwrap(),
+        CanFrame::remote(CanId::Standard(0x7DF), 0).unwrap(),
+    ];
+    for frame in frames {
+        assert_eq!(CanFrame::from_bytes(&frame.to_bytes()), Ok(frame));
+    }
+}
+
+#[test]
+fn from_bytes_rejects_bad_input() {
+    assert_eq!(
+        CanFrame::from_bytes(&[0; 15]),
+        Err(FrameError::WrongLength(15))
+    );
+    let mut bytes = [0u8; FRAME_BYTES];
+    bytes[4] = 9;
+    assert_eq!(CanFrame::from_bytes(&bytes), Err(FrameError::InvalidDlc(9)));
+}
+
+#[test]
+fn from_bytes_ignores_padding_and_unused_data() {
+    let mut bytes = [0xAAu8; FRAME_BYTES];
+    bytes[..4].copy_from_slice(&0x123u32.to_le_bytes());
+    bytes[4] = 2;
+    let frame = CanFrame::from_bytes(&bytes).unwrap();
+    assert_eq!(frame.id(), CanId::Standard(0x123));
+    assert_eq!(frame.data(), [0xAA, 0xAA]);
+}
@@ -0,0 +1,202 @@
+use can::{ByteOrder, MessageDef, Signal, SignalError};
+
+fn signal(start_bit: u16, length: u16, byte_order: ByteOrder, signed: bool) -> Signal {
+    Signal {
+        name: "S",
+        start_bit,
+        length,
+        byte_order,
+        signed,
+        scale: 1.0,
+        offset: 0.0,
+        unit: "",
+    }
+}
+
+fn eec1() -> MessageDef {
+    let field = |name, start_bit, length, scale, offset, unit| Signal {
+        name,
+        start_bit,
+        length,
+        byte_order: ByteOrder::Intel,
+        signed: false,
+        scale,
+        offset,
+        unit,
+    };
+    MessageDef {
+        name: "EEC1",
+        id: 0x0CF0_0400,
+        dlc: 8,
+        signals: vec![
+            field("ActualTorque", 16, 8, 1.0, -125.0, "%"),
+            field("EngineSpeed", 24, 16, 0.125, 0.0, "rpm"),
+        ],
+    }
+}
+
+fn steering() -> MessageDef {
+    MessageDef {
+        name: "Steering",
+        id: 0x1A0,
+        dlc: 4,
+        signals: vec![
+            Signal {
+                name: "Angle",
+                scale: 0.1,
+                unit: "deg",
+                ..signal(7, 16, ByteOrder::Motorola, true)
+            },
+            Signal {
+                name: "Rate",
+                unit: "deg/s",
+                ..signal(23, 12, ByteOrder::Motorola, false)
+            },
+        ],
+    }
+}
+
+#[test]
+fn decodes_a_captured_j1939_frame() {
+    let captured = [0xFF, 0xFF, 0x91, 0xE0, 0x2E, 0xFF, 0xFF, 0xFF];
+    assert_eq!(
+        eec1().decode(&captured).unwrap(),
+        [("ActualTorque", 20.0, "%"), ("EngineSpeed", 1500.0, "rpm")]
+    );
+}
+
+#[test]
+fn motorola_fields_run_down_then_into_the_next_byte() {
+    let def = steering();
+    let data = def.encode(&[("Angle", -12.5), ("Rate", 300.0)]).unwrap();
+    // Angle 0xFF83 in bytes 0-1; Rate 0x12C from bit 23 down, then bits 31-28
+    assert_eq!(data, [0xFF, 0x83, 0x12, 0xC0]);
+    assert_eq!(def.signals[0].raw(&data), Ok(0xFF83));
+    assert_eq!(def.signals[1].raw(&data), Ok(0x12C));
+    assert_eq!(
+        def.decode(&data).unwrap(),
+        [("Angle", -12.5, "deg"), ("Rate", 300.0, "deg/s")]
+    );
+}
+
+#[test]
+fn intel_fields_start_at_the_least_significant_bit() {
+    // 12 bits from bit 4: the high nibble of byte 0, then all of byte 1
+    let s = signal(4, 12, ByteOrder::Intel, false);
+    let mut data = [0u8; 2];
+    s.encode(0xABC as f64, &mut data).unwrap();
+    assert_eq!(data, [0xC0, 0xAB]);
+}
+
+#[test]
+fn range_limits_round_trip_and_one_past_is_rejected() {
+    let cases: [(MessageDef, &str, f64, f64); 3] = [
+        (eec1(), "EngineSpeed", 0.0, 8191.875),
+        (eec1(), "ActualTorque", -125.0, 130.0),
+        (steering(), "Angle", -3276.8, 3276.7),
+    ];
+    for (def, name, min, max) in cases {
+        let signal = def.signals.iter().find(|s| s.name == name).unwrap();
+        for value in [min, max] {
+            let data = def.encode(&[(name, value)]).unwrap();
+            assert!((signal.decode(&data).unwrap() - value).abs() < 1e-9);
+        }
+        for value in [min - signal.scale, max + signal.scale] {
+            assert_eq!(
+                def.encode(&[(name, value)]),
+                Err(SignalError::OutOfRange {
+                    name: signal.name,
+                    value
+                })
+            );
+        }
+    }
+}
+
+#[test]
+fn signals_outside_the_data_are_rejected() {
+    let def = eec1();
+    assert_eq!(
+        def.decode(&[0x00, 0x01]),
+        Err(SignalError::OutOfFrame {
+            name: "ActualTorque"
+        })
+    );
+    let mut data = [0u8; 8];
+    assert!(signal(60, 8, ByteOrder::Intel, false)
+        .encode(1.0, &mut data)
+        .is_err());
+    assert!(signal(0, 0, ByteOrder::Intel, false).raw(&data).is_err());
+    // Motorola from bit 0 of the last byte runs off the end at once
+    assert!(signal(56, 2, ByteOrder::Motorola, false)
+        .raw(&data)
+        .is_err());
+}
+
+#[test]
+fn unknown_signal_names_are_rejected_when_encoding() {
+    let err = eec1()
+        .encode(&[("EngineSpeed", 1500.0), ("Nope", 1.0)])
+        .unwrap_err();
+    assert_eq!(
+        err,
+        SignalError::UnknownSignal {
+            name: "Nope".to_string()
+        }
+    );
+    assert_eq!(err.to_string(), "no signal named Nope");
+}
+
+#[test]
+fn unsigned_64_bit_fields_keep_values_above_i64_max() {
+    let s = signal(0, 64, ByteOrder::Intel, false);
+    let value = 2f64.powi(63) + 2f64.powi(62);
+    let mut data = [0u8; 8];
+    s.encode(value, &mut data).unwrap();
+    assert_eq!(s.raw(&data), Ok(0xC000_0000_0000_0000));
+    assert_eq!(s.decode(&data), Ok(value));
+}
+
+#[test]
+fn random_values_round_trip_without_touching_other_bits() {
+    let mut state = 0x5EED_u64;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        state >> 11
+    };
+    let layouts = [
+        signal(0, 1, ByteOrder::Intel, false),
+        signal(3, 7, ByteOrder::Intel, true),
+        signal(13, 20, ByteOrder::Intel, false),
+        signal(0, 64, ByteOrder::Intel, false),
+        signal(7, 8, ByteOrder::Motorola, false),
+        signal(20, 11, ByteOrder::Motorola, true),
+        signal(39, 32, ByteOrder::Motorola, false),
+    ];
+    for s in &layouts {
+        // f64 carries 53 bits exactly
+        let bits = s.length.min(52);
+        for _ in 0..200 {
+            let raw = next() & ((1u64 << bits) - 1);
+            let value = if s.signed {
+                // Shift into the signed range
+                raw as f64 - (1u64 << (bits - 1)) as f64
+            } else {
+                raw as f64
+            };
+            let background = next().to_le_bytes();
+            let mut data = background;
+            s.encode(value, &mut data).unwrap();
+            assert_eq!(s.decode(&data), Ok(value), "{:?} {}", s, value);
+
+            // Zero in the field, ones everywhere else
+            let mut outside = [0xFFu8; 8];
+            s.encode(0.0, &mut outside).unwrap();
+            for i in 0..8 {
+                assert_eq!(data[i] & outside[i], background[i] & outside[i]);
+            }
+        }
+    }
+}
@@ -0,0 +1,166 @@
+// CRC-8, CRC-16/CCITT, CRC-16/MODBUS and CRC-32
+//
+// All lookup tables are `const` items built by `const fn`, so they live in
+// the binary's read-only data and cost nothing at startup. Each algorithm is
+// available two ways:
+// - a `const fn` one-shot (`crc32(data)`), usable in const contexts
+// - a streaming type implementing `Crc` (`update` as data arrives)
+//
+// Parameters follow the Rocksoft model used by the CRC RevEng catalogue.
+// "Check" is the CRC of the ASCII string "123456789".
+
+mod table;
+
+pub const CHECK_INPUT: &[u8] = b"123456789";
+
+pub trait Crc: Default {
+    type Output: Copy + PartialEq + std::fmt::Debug;
+    const NAME: &'static str;
+    const CHECK: Self::Output;
+
+    fn update(&mut self, data: &[u8]);
+    fn finalize(&self) -> Self::Output;
+    fn reset(&mut self);
+
+    fn checksum(data: &[u8]) -> Self::Output {
+        let mut crc = Self::default();
+        crc.update(data);
+        crc.finalize()
+    }
+}
+
+// CRC-8/SMBUS: poly 0x07, init 0x00, not reflected
+pub const CRC8_TABLE: [u8; 256] = table::msb_first_8(0x07);
+
+// CRC-16/CCITT-FALSE (IBM-3740): poly 0x1021, init 0xFFFF, not reflected
+pub const CRC16_CCITT_TABLE: [u16; 256] = table::msb_first_16(0x1021);
+
+// CRC-16/MODBUS: poly 0x8005 reflected (0xA001), init 0xFFFF
+pub const CRC16_MODBUS_TABLE: [u16; 256] = table::lsb_first_16(0xA001);
+
+// CRC-32/ISO-HDLC (zip, Ethernet): poly 0x04C11DB7 reflected (0xEDB88320)
+pub const CRC32_TABLE: [u32; 256] = table::lsb_first_32(0xEDB8_8320);
+
+const fn crc8_step(mut crc: u8, data: &[u8]) -> u8 {
+    let mut i = 0;
+    while i < data.len() {
+        crc = CRC8_TABLE[(crc ^ data[i]) as usize];
+        i += 1;
+    }
+    crc
+}
+
+const fn crc16_ccitt_step(mut crc: u16, data: &[u8]) -> u16 {
+    let mut i = 0;
+    while i < data.len() {
+        let index = ((crc >> 8) as u8 ^ data[i]) as usize;
+        crc = (crc << 8) ^ CRC16_CCITT_TABLE[index];
+        i += 1;
+    }
+    crc
+}
+
+const fn crc16_modbus_step(mut crc: u16, data: &[u8]) -> u16 {
+    let mut i = 0;
+    while i < data.len() {
+        let index = ((crc as u8) ^ data[i]) as usize;
+        crc = (crc >> 8) ^ CRC16_MODBUS_TABLE[index];
+        i += 1;
+    }
+    crc
+}
+
+const fn crc32_step(mut crc: u32, data: &[u8]) -> u32 {
+    let mut i = 0;
+    while i < data.len() {
+        let index = ((crc as u8) ^ data[i]) as usize;
+        crc = (crc >> 8) ^ CRC32_TABLE[index];
+        i += 1;
+    }
+    crc
+}
+
+pub const fn crc8(data: &[u8]) -> u8 {
+    crc8_step(0x00, data)
+}
+
+pub const fn crc16_ccitt(data: &[u8]) -> u16 {
+    crc16_ccitt_step(0xFFFF, data)
+}
+
+// Transmitted low byte first on the wire
+pub const fn crc16_modbus(data: &[u8]) -> u16 {
+    crc16_modbus_step(0xFFFF, data)
+}
+
+pub const fn crc32(data: &[u8]) -> u32 {
+    !crc32_step(0xFFFF_FFFF, data)
+}
+
+// The check values are verified by the compiler on every build
+const _: () = assert!(crc8(CHECK_INPUT) == 0xF4);
+const _: () = assert!(crc16_ccitt(CHECK_INPUT) == 0x29B1);
+const _: () = assert!(crc16_modbus(CHECK_INPUT) == 0x4B37);
+const _: () = assert!(crc32(CHECK_INPUT) == 0xCBF4_3926);
+
+macro_rules! streaming_crc {
+    ($name:ident, $out:ty, $label:expr, $check:expr, $init:expr, $step:ident, $fin:expr) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct $name {
+            state: $out,
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                $name { state: $init }
+            }
+        }
+
+        impl Crc for $name {
+            type Output = $out;
+            const NAME: &'static str = $label;
+            const CHECK: $out = $check;
+
+            fn update(&mut self, data: &[u8]) {
+                self.state = $step(self.state, data);
+            }
+
+            fn finalize(&self) -> $out {
+                $fin(self.state)
+            }
+
+            fn reset(&mut self) {
+                self.state = $init;
+            }
+        }
+    };
+}
+
+streaming_crc!(Crc8, u8, "CRC-8/SMBUS", 0xF4, 0x00, crc8_step, |s| s);
+streaming_crc!(
+    Crc16Ccitt,
+    u16,
+    "CRC-16/CCITT-FALSE",
+    0x29B1,
+    0xFFFF,
+    crc16_ccitt_step,
+    |s| s
+);
+streaming_crc!(
+    Crc16Modbus,
+    u16,
+    "CRC-16/MODBUS",
+    0x4B37,
+    0xFFFF,
+    crc16_modbus_step,
+    |s| s
+);
+streaming_crc!(
+    Crc32,
+    u32,
+    "CRC-32/ISO-HDLC",
+    0xCBF4_3926,
+    0xFFFF_FFFF,
+    crc32_step,
+    |s: u32| !s
+);
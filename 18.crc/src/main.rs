@@ -0,0 +1,103 @@
+use crc::{
+    crc16_modbus, crc32, Crc, Crc16Ccitt, Crc16Modbus, Crc32, Crc8, CHECK_INPUT, CRC32_TABLE,
+};
+use std::time::Instant;
+
+// CRC-32 computed one bit at a time, for comparison with the table version
+fn crc32_bitwise(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &b in data {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn check<C: Crc>() {
+    let value = C::checksum(CHECK_INPUT);
+    println!(
+        "   {:<20} {:>10} {}",
+        C::NAME,
+        format!("{:X?}", value),
+        if value == C::CHECK { "ok" } else { "FAIL" }
+    );
+}
+
+// Feed the data in uneven pieces; the result must match the one-shot CRC
+fn streaming<C: Crc>(data: &[u8]) -> bool {
+    let mut crc = C::default();
+    for chunk in data.chunks(7) {
+        crc.update(chunk);
+    }
+    crc.finalize() == C::checksum(data)
+}
+
+// Compile-time CRC of a firmware identification string
+const FIRMWARE_ID: &[u8] = b"edge-gateway v1.4.2";
+const FIRMWARE_CRC: u32 = crc32(FIRMWARE_ID);
+
+fn main() {
+    println!("=== CRC Library ===\n");
+
+    // 1. Standard check values
+    println!("1. Check values for \"123456789\":");
+    check::<Crc8>();
+    check::<Crc16Ccitt>();
+    check::<Crc16Modbus>();
+    check::<Crc32>();
+
+    // 2. Streaming updates
+    println!("\n2. Streaming in 7-byte chunks matches one-shot:");
+    let data: Vec<u8> = (0..1000u32).map(|i| (i * 31 % 251) as u8).collect();
+    println!("   Crc8:        {}", streaming::<Crc8>(&data));
+    println!("   Crc16Ccitt:  {}", streaming::<Crc16Ccitt>(&data));
+    println!("   Crc16Modbus: {}", streaming::<Crc16Modbus>(&data));
+    println!("   Crc32:       {}", streaming::<Crc32>(&data));
+
+    // 3. Tables generated at compile time
+    println!("\n3. Compile-time tables:");
+    println!("   CRC32_TABLE[0..4] = {:08X?}", &CRC32_TABLE[..4]);
+    println!(
+        "   const FIRMWARE_CRC = {:08X} (crc32 of {:?})",
+        FIRMWARE_CRC,
+        std::str::from_utf8(FIRMWARE_ID).unwrap()
+    );
+
+    // 4. Modbus RTU frame
+    println!("\n4. Modbus RTU frame (read 2 holding registers from slave 1):");
+    let mut frame = vec![0x01, 0x03, 0x00, 0x00, 0x00, 0x02];
+    let crc = crc16_modbus(&frame);
+    frame.extend_from_slice(&crc.to_le_bytes());
+    println!("   {:02X?}", frame);
+    println!(
+        "   CRC over the whole frame is 0: {}",
+        crc16_modbus(&frame) == 0
+    );
+
+    // 5. Error detection
+    println!("\n5. Detecting a single flipped bit:");
+    let mut damaged = data.clone();
+    damaged[500] ^= 0x10;
+    println!("   original {:08X}", crc32(&data));
+    println!("   damaged  {:08X}", crc32(&damaged));
+
+    // 6. Table vs bitwise speed
+    println!("\n6. Table vs bitwise (1 MiB):");
+    let big: Vec<u8> = (0..1 << 20).map(|i: u32| (i ^ (i >> 8)) as u8).collect();
+    let start = Instant::now();
+    let table_crc = crc32(&big);
+    let table_time = start.elapsed();
+    let start = Instant::now();
+    let bitwise_crc = crc32_bitwise(&big);
+    let bitwise_time = start.elapsed();
+    println!("   table:   {:08X} in {:?}", table_crc, table_time);
+    println!("   bitwise: {:08X} in {:?}", bitwise_crc, bitwise_time);
+
+    println!("\n=== End of CRC Examples ===");
+}
@@ -0,0 +1,82 @@
+use crc::{
+    crc16_ccitt, crc16_modbus, crc32, crc8, Crc, Crc16Ccitt, Crc16Modbus, Crc32, Crc8, CHECK_INPUT,
+};
+
+// Feeding the input in two pieces, split anywhere, must match the one-shot
+fn split_anywhere<C: Crc>(one_shot: fn(&[u8]) -> C::Output) {
+    let expected = one_shot(CHECK_INPUT);
+    assert_eq!(expected, C::CHECK, "{}", C::NAME);
+    assert_eq!(C::checksum(CHECK_INPUT), expected, "{}", C::NAME);
+
+    for at in 0..=CHECK_INPUT.len() {
+        let (head, tail) = CHECK_INPUT.split_at(at);
+        let mut crc = C::default();
+        crc.update(head);
+        crc.update(tail);
+        assert_eq!(crc.finalize(), expected, "{} split at {}", C::NAME, at);
+    }
+}
+
+// Byte by byte, with empty updates in between
+fn byte_by_byte<C: Crc>() {
+    let mut crc = C::default();
+    for &byte in CHECK_INPUT {
+        crc.update(&[]);
+        crc.update(&[byte]);
+    }
+    assert_eq!(crc.finalize(), C::CHECK, "{}", C::NAME);
+}
+
+// `finalize` does not consume the state, and `reset` starts over
+fn reset_starts_over<C: Crc>(one_shot: fn(&[u8]) -> C::Output) {
+    let mut crc = C::default();
+    crc.update(b"some earlier frame");
+    assert_eq!(crc.finalize(), one_shot(b"some earlier frame"));
+    assert_eq!(crc.finalize(), one_shot(b"some earlier frame"));
+
+    crc.reset();
+    assert_eq!(crc.finalize(), one_shot(b""), "{}", C::NAME);
+    crc.update(CHECK_INPUT);
+    assert_eq!(crc.finalize(), C::CHECK, "{}", C::NAME);
+}
+
+#[test]
+fn crc8_streaming() {
+    split_anywhere::<Crc8>(crc8);
+    byte_by_byte::<Crc8>();
+    reset_starts_over::<Crc8>(crc8);
+}
+
+#[test]
+fn crc16_ccitt_streaming() {
+    split_anywhere::<Crc16Ccitt>(crc16_ccitt);
+    byte_by_byte::<Crc16Ccitt>();
+    reset_starts_over::<Crc16Ccitt>(crc16_ccitt);
+}
+
+#[test]
+fn crc16_modbus_streaming() {
+    split_anywhere::<Crc16Modbus>(crc16_modbus);
+    byte_by_byte::<Crc16Modbus>();
+    reset_starts_over::<Crc16Modbus>(crc16_modbus);
+}
+
+#[test]
+fn crc32_streaming() {
+    split_anywhere::<Crc32>(crc32);
+    byte_by_byte::<Crc32>();
+    reset_starts_over::<Crc32>(crc32);
+}
+
+#[test]
+fn three_way_splits_match() {
+    for a in 0..=CHECK_INPUT.len() {
+        for b in a..=CHECK_INPUT.len() {
+            let mut crc = Crc32::default();
+            crc.update(&CHECK_INPUT[..a]);
+            crc.update(&CHECK_INPUT[a..b]);
+            crc.update(&CHECK_INPUT[b..]);
+            assert_eq!(crc.finalize(), Crc32::CHECK, "split at {} and {}", a, b);
+        }
+    }
+}
@@ -0,0 +1,240 @@
+// Consistent Overhead Byte Stuffing (COBS)
+//
+// COBS removes every zero byte from a message so that 0x00 can be used as an
+// unambiguous frame delimiter. The encoded data is a sequence of blocks: a
+// code byte N followed by N-1 non-zero data bytes, with an implicit zero
+// after every block whose code is below 0xFF (except the last).
+//
+//   11 22 00 33     ->  03 11 22 02 33
+//   00              ->  01 01
+//
+// Overhead is at most one byte per 254 bytes, plus one.
+//
+// The core functions work on caller-provided slices and compile without
+// `std`; `encode`/`decode` returning `Vec` need the default `std` feature.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::fmt;
+
+pub const DELIMITER: u8 = 0x00;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CobsError {
+    // Destination slice is too small
+    BufferTooSmall,
+    // A zero byte appeared inside encoded data
+    ZeroInData,
+    // A code byte points past the end of the input
+    Truncated,
+}
+
+impl fmt::Display for CobsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CobsError::BufferTooSmall => write!(f, "destination buffer too small"),
+            CobsError::ZeroInData => write!(f, "zero byte inside encoded data"),
+            CobsError::Truncated => write!(f, "encoded data ends mid-block"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CobsError {}
+
+// Worst-case encoded size for `len` input bytes (without the delimiter)
+pub const fn max_encoded_len(len: usize) -> usize {
+    len + len / 254 + 1
+}
+
+// Encode `src` into `dst`; returns the number of bytes written
+pub fn encode_into(src: &[u8], dst: &mut [u8]) -> Result<usize, CobsError> {
+    if dst.len() < max_encoded_len(src.len()) {
+        return Err(CobsError::BufferTooSmall);
+    }
+
+    let mut code_index = 0;
+    let mut out = 1;
+    let mut code = 1u8;
+
+    for &byte in src {
+        // A full block (254 data bytes) is closed only when more data follows
+        if code == 0xFF {
+            dst[code_index] = code;
+            code_index = out;
+            out += 1;
+            code = 1;
+        }
+        if byte == 0 {
+            dst[code_index] = code;
+            code_index = out;
+            out += 1;
+            code = 1;
+        } else {
+            dst[out] = byte;
+            out += 1;
+            code += 1;
+        }
+    }
+    dst[code_index] = code;
+    Ok(out)
+}
+
+// Decode `src` (without delimiter) into `dst`; returns the decoded length
+pub fn decode_into(src: &[u8], dst: &mut [u8]) -> Result<usize, CobsError> {
+    let mut i = 0;
+    let mut out = 0;
+    while i < src.len() {
+        let code = src[i];
+        if code == 0 {
+            return Err(CobsError::ZeroInData);
+        }
+        i += 1;
+
+        let end = i + code as usize - 1;
+        if end > src.len() {
+            return Err(CobsError::Truncated);
+        }
+        for &byte in &src[i..end] {
+            if byte == 0 {
+                return Err(CobsError::ZeroInData);
+            }
+            *dst.get_mut(out).ok_or(CobsError::BufferTooSmall)? = byte;
+            out += 1;
+        }
+        i = end;
+
+        if code != 0xFF && i < src.len() {
+            *dst.get_mut(out).ok_or(CobsError::BufferTooSmall)? = 0;
+            out += 1;
+        }
+    }
+    Ok(out)
+}
+
+// Decode in place: the decoded data is never longer than the encoded data
+// and never overtakes the read position, so one buffer is enough.
+pub fn decode_in_place(buf: &mut [u8]) -> Result<usize, CobsError> {
+    let mut i = 0;
+    let mut out = 0;
+    while i < buf.len() {
+        let code = buf[i];
+        if code == 0 {
+            return Err(CobsError::ZeroInData);
+        }
+        i += 1;
+
+        let end = i + code as usize - 1;
+        if end > buf.len() {
+            return Err(CobsError::Truncated);
+        }
+        while i < end {
+            if buf[i] == 0 {
+                return Err(CobsError::ZeroInData);
+            }
+            buf[out] = buf[i];
+            out += 1;
+            i += 1;
+        }
+
+        if code != 0xFF && i < buf.len() {
+            buf[out] = 0;
+            out += 1;
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(feature = "std")]
+pub fn encode(src: &[u8]) -> Vec<u8> {
+    let mut out = vec![0u8; max_encoded_len(src.len())];
+    let len = encode_into(src, &mut out).expect("buffer sized by max_encoded_len");
+    out.truncate(len);
+    out
+}
+
+#[cfg(feature = "std")]
+pub fn decode(src: &[u8]) -> Result<Vec<u8>, CobsError> {
+    let mut out = vec![0u8; src.len()];
+    let len = decode_into(src, &mut out)?;
+    out.truncate(len);
+    Ok(out)
+}
+
+// Encoded frame followed by the delimiter, ready to send
+#[cfg(feature = "std")]
+pub fn frame(src: &[u8]) -> Vec<u8> {
+    let mut out = encode(src);
+    out.push(DELIMITER);
+    out
+}
+
+// Splits a byte stream on zero delimiters and decodes each frame, using a
+// fixed buffer of N bytes (no allocation).
+#[derive(Debug)]
+pub struct FrameDecoder<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+    overflowed: bool,
+}
+
+impl<const N: usize> Default for FrameDecoder<N> {
+    fn default() -> Self {
+        FrameDecoder::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameError {
+    // The frame was longer than the decoder's buffer
+    Overflow,
+    Cobs(CobsError),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::Overflow => write!(f, "frame longer than buffer"),
+            FrameError::Cobs(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FrameError {}
+
+impl<const N: usize> FrameDecoder<N> {
+    pub const fn new() -> Self {
+        FrameDecoder {
+            buf: [0; N],
+            len: 0,
+            overflowed: false,
+        }
+    }
+
+    // Feed one byte. At a delimiter, returns the decoded frame (or why it
+    // was rejected). Empty frames (back-to-back delimiters) are skipped.
+    pub fn push(&mut self, byte: u8) -> Option<Result<&[u8], FrameError>> {
+        if byte != DELIMITER {
+            if self.len < N {
+                self.buf[self.len] = byte;
+                self.len += 1;
+            } else {
+                self.overflowed = true;
+            }
+            return None;
+        }
+
+        let len = core::mem::take(&mut self.len);
+        if core::mem::take(&mut self.overflowed) {
+            return Some(Err(FrameError::Overflow));
+        }
+        if len == 0 {
+            return None;
+        }
+        match decode_in_place(&mut self.buf[..len]) {
+            Ok(n) => Some(Ok(&self.buf[..n])),
+            Err(e) => Some(Err(FrameError::Cobs(e))),
+        }
+    }
+}
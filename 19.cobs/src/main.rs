@@ -0,0 +1,131 @@
+use cobs::{decode_in_place, encode, encode_into, frame, max_encoded_len, FrameDecoder};
+
+fn hex(bytes: &[u8]) -> String {
+    if bytes.len() > 12 {
+        let head: Vec<String> = bytes[..4].iter().map(|b| format!("{:02X}", b)).collect();
+        let tail: Vec<String> = bytes[bytes.len() - 3..]
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect();
+        return format!(
+            "{} .. {} ({} bytes)",
+            head.join(" "),
+            tail.join(" "),
+            bytes.len()
+        );
+    }
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Small xorshift generator for the randomized round-trip check
+struct Rng(u32);
+
+impl Rng {
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+}
+
+fn main() {
+    println!("=== COBS Framing ===\n");
+
+    // 1. Reference vectors (from the COBS paper / Wikipedia)
+    println!("1. Reference vectors:");
+    // tests/cobs.rs checks the encodings
+    let cases: Vec<Vec<u8>> = vec![
+        vec![0x00],
+        vec![0x00, 0x00],
+        vec![0x11, 0x22, 0x00, 0x33],
+        vec![0x11, 0x22, 0x33, 0x44],
+        vec![0x11, 0x00, 0x00, 0x00],
+        (1..=254).collect(),
+        (1..=255).collect(),
+        [vec![0x00], (1..=254).collect()].concat(),
+    ];
+    for input in &cases {
+        println!("   {:<36} -> {}", hex(input), hex(&encode(input)));
+    }
+
+    // 2. Overhead
+    println!("\n2. Worst-case overhead:");
+    for len in [0, 1, 100, 254, 255, 1000] {
+        println!("   {:>5} bytes -> at most {:>5}", len, max_encoded_len(len));
+    }
+
+    // 3. Allocation-free encode and in-place decode
+    println!("\n3. Fixed buffers (no_std style):");
+    let message = [0x01, 0x00, 0x02, 0x00, 0x00, 0x03];
+    let mut buf = [0u8; 16];
+    let n = encode_into(&message, &mut buf).unwrap();
+    println!("   encode_into:     {}", hex(&buf[..n]));
+    let m = decode_in_place(&mut buf[..n]).unwrap();
+    println!("   decode_in_place: {}", hex(&buf[..m]));
+    let mut tiny = [0u8; 4];
+    println!("   into 4 bytes:    {:?}", encode_into(&message, &mut tiny));
+
+    // 4. Extracting frames from a byte stream
+    println!("\n4. Stream of frames split at zero bytes:");
+    let mut stream = Vec::new();
+    stream.extend(frame(b"temp=21.5"));
+    stream.extend(frame(&[0x00, 0x10, 0x00]));
+    stream.push(0x00); // stray delimiter
+    stream.extend([0x05, 0x11, 0x22]); // frame cut short by a reset
+    stream.push(0x00);
+    stream.extend(frame(b"ok"));
+    println!("   wire: {}", hex(&stream));
+    let mut decoder = FrameDecoder::<64>::new();
+    for &byte in &stream {
+        match decoder.push(byte) {
+            Some(Ok(frame)) => println!("   frame: {}", hex(frame)),
+            Some(Err(e)) => println!("   error: {}", e),
+            None => {}
+        }
+    }
+
+    // 5. Oversized frames are rejected, then the decoder resynchronises
+    println!("\n5. Frame larger than the buffer:");
+    let mut decoder = FrameDecoder::<8>::new();
+    let mut stream = frame(&[0xAA; 20]);
+    stream.extend(frame(b"hi"));
+    for &byte in &stream {
+        match decoder.push(byte) {
+            Some(Ok(frame)) => println!("   frame: {}", hex(frame)),
+            Some(Err(e)) => println!("   error: {}", e),
+            None => {}
+        }
+    }
+
+    // 6. Overhead on random payloads
+    println!("\n6. Overhead on random payloads:");
+    let mut rng = Rng(0x1234_5678);
+    let mut total_in = 0;
+    let mut total_out = 0;
+    for _ in 0..10_000 {
+        let len = (rng.next() % 600) as usize;
+        // Bias towards zeros so both block kinds are exercised
+        let data: Vec<u8> = (0..len)
+            .map(|_| {
+                if rng.next().is_multiple_of(4) {
+                    0
+                } else {
+                    rng.next() as u8
+                }
+            })
+            .collect();
+        total_in += len;
+        total_out += encode(&data).len();
+    }
+    println!(
+        "   10000 payloads, overhead: {:.3}%",
+        (total_out - total_in) as f64 * 100.0 / total_in as f64
+    );
+
+    println!("\n=== End of COBS Examples ===");
+}
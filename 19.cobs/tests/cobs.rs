@@ -0,0 +1,203 @@
+use cobs::{
+    decode, decode_in_place, decode_into, encode, encode_into, frame, max_encoded_len, CobsError,
+    FrameDecoder, FrameError,
+};
+
+fn lcg(state: &mut u64) -> u8 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    (*state >> 56) as u8
+}
+
+// Encodes and decodes through every API, checking each step
+fn round_trip(data: &[u8]) {
+    let encoded = encode(data);
+    assert!(!encoded.contains(&0), "zero in {:?}", encoded);
+    assert!(encoded.len() <= max_encoded_len(data.len()));
+    assert_eq!(decode(&encoded).as_deref(), Ok(data));
+
+    let mut buf = vec![0u8; max_encoded_len(data.len())];
+    let n = encode_into(data, &mut buf).unwrap();
+    assert_eq!(buf[..n], encoded[..]);
+    let mut out = vec![0u8; data.len()];
+    assert_eq!(decode_into(&encoded, &mut out), Ok(data.len()));
+    assert_eq!(out, data);
+    let m = decode_in_place(&mut buf[..n]).unwrap();
+    assert_eq!(&buf[..m], data);
+}
+
+#[test]
+fn reference_vectors() {
+    let long_254: Vec<u8> = (1..=254).collect();
+    let long_255: Vec<u8> = (1..=255).collect();
+    let cases: Vec<(Vec<u8>, Vec<u8>)> = vec![
+        (vec![], vec![0x01]),
+        (vec![0x00], vec![0x01, 0x01]),
+        (vec![0x00, 0x00], vec![0x01, 0x01, 0x01]),
+        (
+            vec![0x11, 0x22, 0x00, 0x33],
+            vec![0x03, 0x11, 0x22, 0x02, 0x33],
+        ),
+        (
+            vec![0x11, 0x22, 0x33, 0x44],
+            vec![0x05, 0x11, 0x22, 0x33, 0x44],
+        ),
+        (
+            vec![0x11, 0x00, 0x00, 0x00],
+            vec![0x02, 0x11, 0x01, 0x01, 0x01],
+        ),
+        (long_254.clone(), [&[0xFF][..], &long_254].concat()),
+        (
+            long_255.clone(),
+            [&[0xFF][..], &long_254, &[0x02, 0xFF]].concat(),
+        ),
+        (
+            [&[0x00][..], &long_254].concat(),
+            [&[0x01, 0xFF][..], &long_254].concat(),
+        ),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(encode(&input), expected, "encoding {:?}", input);
+        round_trip(&input);
+    }
+}
+
+#[test]
+fn all_zero_input_becomes_all_ones() {
+    for len in 0..=600 {
+        let zeros = vec![0u8; len];
+        assert_eq!(encode(&zeros), vec![0x01; len + 1]);
+        round_trip(&zeros);
+    }
+}
+
+#[test]
+fn runs_around_the_block_boundary() {
+    for run in [1, 253, 254, 255, 256, 507, 508, 509, 1000] {
+        let data = vec![0xA5u8; run];
+        // Full blocks of 254 each cost one code byte, plus the final one
+        let blocks = run.div_ceil(254).max(1);
+        assert_eq!(encode(&data).len(), run + blocks, "run of {}", run);
+
+        for shape in [
+            data.clone(),
+            [&[0][..], &data].concat(),
+            [&data[..], &[0]].concat(),
+            [&data[..], &[0], &data].concat(),
+            [&[0, 0][..], &data, &[0, 0]].concat(),
+        ] {
+            round_trip(&shape);
+        }
+    }
+}
+
+#[test]
+fn exactly_254_bytes_needs_no_trailing_block() {
+    let data = vec![0x01u8; 254];
+    let encoded = encode(&data);
+    assert_eq!(encoded.len(), 255);
+    assert_eq!(encoded[0], 0xFF);
+    // With a zero after it, an empty block carries the zero
+    let encoded = encode(&[&data[..], &[0]].concat());
+    assert_eq!(encoded[255..], [0x01, 0x01]);
+}
+
+#[test]
+fn arbitrary_vectors_round_trip() {
+    let mut state = 0xC0B5_u64;
+    for i in 0..5_000 {
+        let len = lcg(&mut state) as usize * 3;
+        // Every fourth payload is mostly zeros, the rest uniform bytes
+        let data: Vec<u8> = (0..len)
+            .map(|_| {
+                let b = lcg(&mut state);
+                if i % 4 == 0 && b < 200 {
+                    0
+                } else {
+                    b
+                }
+            })
+            .collect();
+        round_trip(&data);
+    }
+}
+
+#[test]
+fn malformed_encodings_are_rejected() {
+    assert_eq!(decode(&[0x03, 0x11, 0x00]), Err(CobsError::ZeroInData));
+    assert_eq!(decode(&[0x00]), Err(CobsError::ZeroInData));
+    assert_eq!(decode(&[0x05, 0x11, 0x22]), Err(CobsError::Truncated));
+    let mut buf = [0x02, 0x11, 0x04, 0x22];
+    assert_eq!(decode_in_place(&mut buf), Err(CobsError::Truncated));
+}
+
+#[test]
+fn small_buffers_are_reported() {
+    let mut dst = [0u8; 4];
+    assert_eq!(
+        encode_into(&[1, 2, 3, 4], &mut dst),
+        Err(CobsError::BufferTooSmall)
+    );
+    let mut dst = [0u8; 2];
+    assert_eq!(
+        decode_into(&[0x03, 0x11, 0x22, 0x01], &mut dst),
+        Err(CobsError::BufferTooSmall)
+    );
+}
+
+fn frames_in<const N: usize>(stream: &[u8]) -> Vec<Result<Vec<u8>, FrameError>> {
+    let mut decoder = FrameDecoder::<N>::new();
+    stream
+        .iter()
+        .filter_map(|&b| decoder.push(b).map(|r| r.map(|f| f.to_vec())))
+        .collect()
+}
+
+#[test]
+fn stream_decoder_skips_empty_frames_and_reports_bad_ones() {
+    let mut stream = Vec::new();
+    stream.extend(frame(b"temp=21.5"));
+    stream.extend(frame(&[0x00, 0x10, 0x00]));
+    stream.push(0x00);
+    // Cut short by a reset
+    stream.extend([0x05, 0x11, 0x22, 0x00]);
+    stream.extend(frame(b"ok"));
+
+    assert_eq!(
+        frames_in::<64>(&stream),
+        [
+            Ok(b"temp=21.5".to_vec()),
+            Ok(vec![0x00, 0x10, 0x00]),
+            Err(FrameError::Cobs(CobsError::Truncated)),
+            Ok(b"ok".to_vec()),
+        ]
+    );
+}
+
+#[test]
+fn stream_decoder_recovers_after_an_oversized_frame() {
+    let mut stream = frame(&[0xAA; 20]);
+    stream.extend(frame(b"hi"));
+    assert_eq!(
+        frames_in::<8>(&stream),
+        [Err(FrameError::Overflow), Ok(b"hi".to_vec())]
+    );
+}
+
+#[test]
+fn stream_decoder_returns_random_frames_in_order() {
+    let mut state = 0xF4A3_u64;
+    let payloads: Vec<Vec<u8>> = (0..500)
+        .map(|_| {
+            let len = lcg(&mut state) as usize;
+            (0..len).map(|_| lcg(&mut state) & 0x0F).collect()
+        })
+        .collect();
+    let stream: Vec<u8> = payloads.iter().flat_map(|p| frame(p)).collect();
+
+    // An empty payload encodes as [0x01] and decodes back to nothing
+    let expected: Vec<Result<Vec<u8>, FrameError>> =
+        payloads.iter().map(|p| Ok(p.clone())).collect();
+    assert_eq!(frames_in::<300>(&stream), expected);
+}
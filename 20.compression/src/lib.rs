@@ -0,0 +1,64 @@
+// Lightweight compression for telemetry batches
+//
+// - `rle`: PackBits run-length encoding, great for long runs of equal bytes
+// - `lz`: LZSS window compressor, finds repeated sequences anywhere in the
+//   last 4 KiB
+// - `delta`: a reversible pre-transform that turns slowly changing samples
+//   into small numbers, which both compressors then handle much better
+
+pub mod lz;
+pub mod rle;
+
+// Replace each byte by its difference to the previous one (wrapping)
+pub fn delta_encode(data: &[u8]) -> Vec<u8> {
+    let mut prev = 0u8;
+    data.iter()
+        .map(|&b| {
+            let d = b.wrapping_sub(prev);
+            prev = b;
+            d
+        })
+        .collect()
+}
+
+pub fn delta_decode(data: &[u8]) -> Vec<u8> {
+    let mut prev = 0u8;
+    data.iter()
+        .map(|&d| {
+            prev = prev.wrapping_add(d);
+            prev
+        })
+        .collect()
+}
+
+// Reorder fixed-size records so byte k of every record is stored together
+// ("byte shuffle"). Slowly changing fields then form long similar runs.
+// A record size of 0 leaves the data as it is.
+pub fn shuffle(data: &[u8], record_size: usize) -> Vec<u8> {
+    if record_size == 0 {
+        return data.to_vec();
+    }
+    let records = data.len() / record_size;
+    let mut out = Vec::with_capacity(data.len());
+    for k in 0..record_size {
+        out.extend((0..records).map(|r| data[r * record_size + k]));
+    }
+    out.extend_from_slice(&data[records * record_size..]);
+    out
+}
+
+pub fn unshuffle(data: &[u8], record_size: usize) -> Vec<u8> {
+    if record_size == 0 {
+        return data.to_vec();
+    }
+    let records = data.len() / record_size;
+    let mut out = vec![0u8; data.len()];
+    for k in 0..record_size {
+        for r in 0..records {
+            out[r * record_size + k] = data[k * records + r];
+        }
+    }
+    let tail = records * record_size;
+    out[tail..].copy_from_slice(&data[tail..]);
+    out
+}
@@ -0,0 +1,172 @@
+// LZSS-style window compressor (a member of the LZ77 family)
+//
+// Input is replaced by a mix of literals and back-references
+// (offset, length) into the last WINDOW bytes of output. Items are grouped
+// in eights behind a flag byte; bit i set means item i is a match.
+//
+//   literal: 1 byte
+//   match:   2 bytes, big-endian: 12-bit offset-1 | 4-bit length-MIN_MATCH
+//
+// Matches are found through hash chains over 3-byte prefixes, limited to
+// MAX_CHAIN candidates so compression time stays predictable.
+
+use std::fmt;
+
+pub const WINDOW: usize = 4096;
+pub const MIN_MATCH: usize = 3;
+pub const MAX_MATCH: usize = MIN_MATCH + 15;
+const MAX_CHAIN: usize = 32;
+const HASH_BITS: u32 = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LzError {
+    Truncated,
+    BadOffset { position: usize, offset: usize },
+}
+
+impl fmt::Display for LzError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LzError::Truncated => write!(f, "compressed data ends inside an item"),
+            LzError::BadOffset { position, offset } => {
+                write!(
+                    f,
+                    "offset {} at output position {} points before start",
+                    offset, position
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for LzError {}
+
+fn hash(bytes: &[u8]) -> usize {
+    let v = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+    (v.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize
+}
+
+struct Matcher {
+    head: Vec<usize>,
+    prev: Vec<usize>,
+}
+
+const NONE: usize = usize::MAX;
+
+impl Matcher {
+    fn new(len: usize) -> Matcher {
+        Matcher {
+            head: vec![NONE; 1 << HASH_BITS],
+            prev: vec![NONE; len],
+        }
+    }
+
+    fn insert(&mut self, data: &[u8], pos: usize) {
+        if pos + MIN_MATCH <= data.len() {
+            let h = hash(&data[pos..]);
+            self.prev[pos] = self.head[h];
+            self.head[h] = pos;
+        }
+    }
+
+    // Longest match for `pos` within the window: (offset, length)
+    fn find(&self, data: &[u8], pos: usize) -> Option<(usize, usize)> {
+        if pos + MIN_MATCH > data.len() {
+            return None;
+        }
+        let max_len = MAX_MATCH.min(data.len() - pos);
+        let mut best: Option<(usize, usize)> = None;
+        let mut candidate = self.head[hash(&data[pos..])];
+
+        for _ in 0..MAX_CHAIN {
+            if candidate == NONE || pos - candidate > WINDOW {
+                break;
+            }
+            let len = data[candidate..]
+                .iter()
+                .zip(&data[pos..pos + max_len])
+                .take_while(|(a, b)| a == b)
+                .count();
+            if len >= MIN_MATCH && best.is_none_or(|(_, l)| len > l) {
+                best = Some((pos - candidate, len));
+                if len == max_len {
+                    break;
+                }
+            }
+            candidate = self.prev[candidate];
+        }
+        best
+    }
+}
+
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() / 2);
+    let mut matcher = Matcher::new(data.len());
+    let mut flag_index = 0;
+    let mut item = 8;
+    let mut pos = 0;
+
+    while pos < data.len() {
+        if item == 8 {
+            flag_index = out.len();
+            out.push(0);
+            item = 0;
+        }
+
+        match matcher.find(data, pos) {
+            Some((offset, len)) => {
+                out[flag_index] |= 1 << item;
+                let word = ((offset - 1) << 4 | (len - MIN_MATCH)) as u16;
+                out.extend_from_slice(&word.to_be_bytes());
+                for p in pos..pos + len {
+                    matcher.insert(data, p);
+                }
+                pos += len;
+            }
+            None => {
+                out.push(data[pos]);
+                matcher.insert(data, pos);
+                pos += 1;
+            }
+        }
+        item += 1;
+    }
+    out
+}
+
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, LzError> {
+    let mut out = Vec::with_capacity(data.len() * 2);
+    let mut i = 0;
+    while i < data.len() {
+        let flags = data[i];
+        i += 1;
+        for bit in 0..8 {
+            if i >= data.len() {
+                break;
+            }
+            if flags & (1 << bit) == 0 {
+                out.push(data[i]);
+                i += 1;
+                continue;
+            }
+
+            let bytes = data.get(i..i + 2).ok_or(LzError::Truncated)?;
+            let word = u16::from_be_bytes([bytes[0], bytes[1]]) as usize;
+            i += 2;
+            let offset = (word >> 4) + 1;
+            let len = (word & 0xF) + MIN_MATCH;
+            if offset > out.len() {
+                return Err(LzError::BadOffset {
+                    position: out.len(),
+                    offset,
+                });
+            }
+            // Byte by byte: a match may overlap the bytes it produces
+            let start = out.len() - offset;
+            for k in 0..len {
+                out.push(out[start + k]);
+            }
+        }
+    }
+    Ok(out)
+}
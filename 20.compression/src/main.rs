@@ -0,0 +1,191 @@
+use compression::{delta_decode, delta_encode, lz, rle, shuffle, unshuffle};
+use std::time::Instant;
+
+const RECORD_SIZE: usize = 10;
+
+// Small xorshift generator for sensor noise
+struct Rng(u32);
+
+impl Rng {
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+
+    fn noise(&mut self, amplitude: f64) -> f64 {
+        (self.next() as f64 / u32::MAX as f64 * 2.0 - 1.0) * amplitude
+    }
+}
+
+// 500 records: u32 timestamp, i16 temp (centi-°C), u16 humidity (centi-%),
+// u8 status, u8 battery
+fn binary_batch() -> Vec<u8> {
+    let mut rng = Rng(7);
+    let mut out = Vec::with_capacity(500 * RECORD_SIZE);
+    for i in 0..500u32 {
+        let t = i as f64;
+        let temp = (2150.0 + 150.0 * (t / 80.0).sin() + rng.noise(4.0)) as i16;
+        let humidity = (4500.0 + 300.0 * (t / 120.0).cos() + rng.noise(10.0)) as u16;
+        let status = if (300..305).contains(&i) { 1u8 } else { 0 };
+        out.extend_from_slice(&(1_700_000_000 + i * 10).to_le_bytes());
+        out.extend_from_slice(&temp.to_le_bytes());
+        out.extend_from_slice(&humidity.to_le_bytes());
+        out.push(status);
+        out.push(87 - (i / 100) as u8);
+    }
+    out
+}
+
+fn csv_batch(binary: &[u8]) -> Vec<u8> {
+    let mut out = String::from("ts,temp,humidity,status,battery\n");
+    for r in binary.chunks_exact(RECORD_SIZE) {
+        let ts = u32::from_le_bytes([r[0], r[1], r[2], r[3]]);
+        let temp = i16::from_le_bytes([r[4], r[5]]) as f64 / 100.0;
+        let humidity = u16::from_le_bytes([r[6], r[7]]) as f64 / 100.0;
+        out.push_str(&format!(
+            "{},{:.2},{:.2},{},{}\n",
+            ts, temp, humidity, r[8], r[9]
+        ));
+    }
+    out.into_bytes()
+}
+
+// A door sensor reporting its state every second: long runs of equal bytes
+fn door_log() -> Vec<u8> {
+    let mut out = Vec::new();
+    for (state, seconds) in [(0u8, 900), (1, 40), (0, 1500), (1, 5), (0, 600)] {
+        out.extend(std::iter::repeat_n(state, seconds));
+    }
+    out
+}
+
+// Shuffle bytes by field, then delta-encode: slowly changing values become
+// runs of small numbers
+fn preprocess(data: &[u8]) -> Vec<u8> {
+    delta_encode(&shuffle(data, RECORD_SIZE))
+}
+
+fn postprocess(data: &[u8]) -> Vec<u8> {
+    unshuffle(&delta_decode(data), RECORD_SIZE)
+}
+
+fn ratio(original: usize, compressed: usize) -> String {
+    format!(
+        "{:>6} B  {:>5.2}x",
+        compressed,
+        original as f64 / compressed as f64
+    )
+}
+
+fn main() {
+    println!("=== Lightweight Compression ===\n");
+
+    // 1. Run-length encoding
+    println!("1. PackBits RLE:");
+    let sample = b"AAAAAAAABCDEFFFFFFFFFFFFG";
+    let packed = rle::encode(sample);
+    println!(
+        "   input:   {:?} ({} bytes)",
+        std::str::from_utf8(sample).unwrap(),
+        sample.len()
+    );
+    println!("   encoded: {:02X?} ({} bytes)", packed, packed.len());
+    println!("   decoded: {}", rle::decode(&packed).unwrap() == sample);
+
+    // 2. LZ back-references
+    println!("\n2. LZSS back-references:");
+    let text = b"temp=21.5;temp=21.6;temp=21.5;temp=21.7;";
+    let packed = lz::compress(text);
+    println!("   input:   {} bytes", text.len());
+    println!("   encoded: {} bytes", packed.len());
+    println!("   decoded: {}", lz::decompress(&packed).unwrap() == text);
+
+    // 3. Realistic telemetry
+    println!("\n3. Compression ratios:");
+    let binary = binary_batch();
+    let csv = csv_batch(&binary);
+    let door = door_log();
+    println!(
+        "   {:<18} {:>7}  {:>15}  {:>15}  {:>15}",
+        "dataset", "raw", "RLE", "LZ", "shuffle+delta+LZ"
+    );
+    for (name, data, record_based) in [
+        ("binary records", &binary, true),
+        ("CSV text", &csv, false),
+        ("door sensor", &door, false),
+    ] {
+        let best = if record_based {
+            ratio(data.len(), lz::compress(&preprocess(data)).len())
+        } else {
+            "-".to_string()
+        };
+        println!(
+            "   {:<18} {:>5} B  {:>15}  {:>15}  {:>15}",
+            name,
+            data.len(),
+            ratio(data.len(), rle::encode(data).len()),
+            ratio(data.len(), lz::compress(data).len()),
+            best
+        );
+    }
+
+    // 4. The full pipeline round trip
+    println!("\n4. Pipeline round trip (shuffle -> delta -> LZ):");
+    let start = Instant::now();
+    let packed = lz::compress(&preprocess(&binary));
+    let compress_time = start.elapsed();
+    let start = Instant::now();
+    let restored = postprocess(&lz::decompress(&packed).unwrap());
+    let decompress_time = start.elapsed();
+    println!("   {} -> {} bytes", binary.len(), packed.len());
+    println!(
+        "   compress {:?}, decompress {:?}",
+        compress_time, decompress_time
+    );
+    println!("   identical: {}", restored == binary);
+
+    // 5. Baseline
+    println!("\n5. lz4_flex baseline:");
+    lz4_baseline(&binary, &csv);
+
+    // 6. Corrupted input
+    println!("\n6. Corrupted input:");
+    println!(
+        "   RLE cut short:    {:?}",
+        rle::decode(&[0x05, b'a', b'b'])
+    );
+    println!(
+        "   LZ bad offset:    {:?}",
+        lz::decompress(&[0x01, 0x10, 0x00])
+    );
+    println!("   LZ cut short:     {:?}", lz::decompress(&[0x01, 0x10]));
+
+    println!("\n=== End of Compression Examples ===");
+}
+
+#[cfg(feature = "lz4")]
+fn lz4_baseline(binary: &[u8], csv: &[u8]) {
+    for (name, data) in [("binary records", binary), ("CSV text", csv)] {
+        let packed = lz4_flex::compress_prepend_size(data);
+        let ok = lz4_flex::decompress_size_prepended(&packed).is_ok_and(|d| d == data);
+        println!(
+            "   {:<21} {}  round trip: {}",
+            name,
+            ratio(data.len(), packed.len()),
+            ok
+        );
+    }
+    let packed = lz4_flex::compress_prepend_size(&preprocess(binary));
+    println!(
+        "   {:<18} {}",
+        "binary, preprocessed",
+        ratio(binary.len(), packed.len())
+    );
+}
+
+#[cfg(not(feature = "lz4"))]
+fn lz4_baseline(_binary: &[u8], _csv: &[u8]) {
+    println!("   not built; run with: cargo run --features lz4");
+}
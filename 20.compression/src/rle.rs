@@ -0,0 +1,88 @@
+// Run-length encoding in the PackBits format (Apple, TIFF)
+//
+// The output is a sequence of packets, each starting with a signed header:
+//   0..=127     copy the next header+1 bytes literally
+//   -127..=-1   repeat the next byte 1-header times (2..=128)
+//   -128        no-op (never produced here)
+//
+// Unlike naive (count, byte) RLE, data without runs grows by at most one
+// byte per 128.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RleError {
+    Truncated,
+}
+
+impl fmt::Display for RleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RleError::Truncated => write!(f, "RLE data ends inside a packet"),
+        }
+    }
+}
+
+impl std::error::Error for RleError {}
+
+fn run_length(data: &[u8], start: usize) -> usize {
+    let byte = data[start];
+    data[start..]
+        .iter()
+        .take(128)
+        .take_while(|&&b| b == byte)
+        .count()
+}
+
+pub fn encode(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + data.len() / 128 + 1);
+    let mut i = 0;
+    let mut literal_start = 0;
+
+    let flush_literals = |out: &mut Vec<u8>, from: usize, to: usize| {
+        for chunk in data[from..to].chunks(128) {
+            out.push((chunk.len() - 1) as u8);
+            out.extend_from_slice(chunk);
+        }
+    };
+
+    while i < data.len() {
+        let run = run_length(data, i);
+        // Runs of 3+ pay off; a run of 2 costs the same as two literals
+        if run >= 3 {
+            flush_literals(&mut out, literal_start, i);
+            out.push((1i16 - run as i16) as i8 as u8);
+            out.push(data[i]);
+            i += run;
+            literal_start = i;
+        } else {
+            i += 1;
+        }
+    }
+    flush_literals(&mut out, literal_start, data.len());
+    out
+}
+
+pub fn decode(data: &[u8]) -> Result<Vec<u8>, RleError> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < data.len() {
+        let header = data[i] as i8;
+        i += 1;
+        match header {
+            0..=127 => {
+                let n = header as usize + 1;
+                let literal = data.get(i..i + n).ok_or(RleError::Truncated)?;
+                out.extend_from_slice(literal);
+                i += n;
+            }
+            -128 => {}
+            _ => {
+                let byte = *data.get(i).ok_or(RleError::Truncated)?;
+                out.extend(std::iter::repeat_n(byte, (1 - header as i16) as usize));
+                i += 1;
+            }
+        }
+    }
+    Ok(out)
+}
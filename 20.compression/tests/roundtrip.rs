@@ -0,0 +1,153 @@
+use compression::lz::{self, LzError, MAX_MATCH, WINDOW};
+use compression::rle::{self, RleError};
+use compression::{delta_decode, delta_encode, shuffle, unshuffle};
+
+// Deterministic bytes without long repeats (LCG, high byte)
+fn noise(len: usize, seed: u32) -> Vec<u8> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (state >> 16) as u8
+        })
+        .collect()
+}
+
+fn samples() -> Vec<Vec<u8>> {
+    vec![
+        Vec::new(),
+        vec![0x42],
+        vec![7; 128],
+        vec![7; 129],
+        vec![7; 300],
+        b"abcabcabcabcabc-temperature=21.5;temperature=21.6;".to_vec(),
+        noise(1000, 1),
+        [vec![1, 2, 3], vec![9; 200], noise(50, 2), vec![0; 3]].concat(),
+    ]
+}
+
+#[test]
+fn rle_round_trips() {
+    for data in samples() {
+        assert_eq!(rle::decode(&rle::encode(&data)).unwrap(), data);
+    }
+}
+
+#[test]
+fn lz_round_trips() {
+    for data in samples() {
+        assert_eq!(lz::decompress(&lz::compress(&data)).unwrap(), data);
+    }
+}
+
+#[test]
+fn empty_and_single_byte_encodings() {
+    assert!(rle::encode(&[]).is_empty());
+    assert!(lz::compress(&[]).is_empty());
+    assert_eq!(rle::encode(&[0x42]), [0, 0x42]);
+    assert_eq!(lz::compress(&[0x42]), [0, 0x42]);
+}
+
+#[test]
+fn rle_runs_split_at_128() {
+    // 128 fits one packet: header -127 repeats 128 times
+    assert_eq!(rle::encode(&[7; 128]), [0x81, 7]);
+    // 129 leaves one byte over, written as a literal
+    assert_eq!(rle::encode(&[7; 129]), [0x81, 7, 0, 7]);
+}
+
+#[test]
+fn lz_matches_at_the_window_edge() {
+    let head = noise(MAX_MATCH, 3);
+    let edge = [head.clone(), noise(WINDOW - head.len(), 4), head.clone()].concat();
+    let beyond = [
+        head.clone(),
+        noise(WINDOW + 1 - head.len(), 4),
+        head.clone(),
+    ]
+    .concat();
+
+    let edge_packed = lz::compress(&edge);
+    let beyond_packed = lz::compress(&beyond);
+    assert_eq!(lz::decompress(&edge_packed).unwrap(), edge);
+    assert_eq!(lz::decompress(&beyond_packed).unwrap(), beyond);
+
+    // At offset WINDOW the repeat becomes one match; one byte further it
+    // is out of reach and costs about a byte per input byte
+    assert!(edge_packed.len() + head.len() / 2 < beyond_packed.len());
+}
+
+#[test]
+fn truncated_rle_is_an_error() {
+    let packed = rle::encode(b"hello, world");
+    assert_eq!(
+        rle::decode(&packed[..packed.len() - 1]),
+        Err(RleError::Truncated)
+    );
+    // Repeat header with no byte to repeat
+    assert_eq!(rle::decode(&[0xF0]), Err(RleError::Truncated));
+}
+
+#[test]
+fn truncated_lz_is_an_error() {
+    // Flag says match, but only one of its two bytes follows
+    assert_eq!(lz::decompress(&[0x01, 0x00]), Err(LzError::Truncated));
+}
+
+#[test]
+fn corrupt_lz_offset_is_an_error() {
+    // A match before any literal points before the start of the output
+    assert_eq!(
+        lz::decompress(&[0x01, 0x00, 0x00]),
+        Err(LzError::BadOffset {
+            position: 0,
+            offset: 1
+        })
+    );
+}
+
+#[test]
+fn corrupt_input_never_panics() {
+    let packed = lz::compress(&[b"abcabcabc".repeat(20), noise(100, 5)].concat());
+    for cut in 0..packed.len() {
+        let _ = lz::decompress(&packed[..cut]);
+        let _ = rle::decode(&packed[..cut]);
+    }
+    for seed in 0..200 {
+        let junk = noise(64, seed);
+        let _ = lz::decompress(&junk);
+        let _ = rle::decode(&junk);
+    }
+}
+
+#[test]
+fn delta_round_trips() {
+    let data = noise(500, 6);
+    assert_eq!(delta_decode(&delta_encode(&data)), data);
+    assert_eq!(delta_encode(&[10, 12, 11]), [10, 2, 255]);
+}
+
+#[test]
+fn shuffle_round_trips_with_a_partial_tail() {
+    // 5 whole 6-byte records and a 4-byte tail
+    let data = noise(34, 7);
+    for record_size in [1, 2, 4, 6, 34, 40] {
+        let shuffled = shuffle(&data, record_size);
+        assert_eq!(shuffled.len(), data.len());
+        assert_eq!(unshuffle(&shuffled, record_size), data);
+    }
+    assert_eq!(shuffle(&data, 6)[30..], data[30..]);
+}
+
+#[test]
+fn shuffle_groups_bytes_by_field() {
+    let data = [1, 10, 2, 20, 3, 30, 99];
+    assert_eq!(shuffle(&data, 2), [1, 2, 3, 10, 20, 30, 99]);
+}
+
+#[test]
+fn zero_record_size_leaves_data_unchanged() {
+    let data = noise(10, 8);
+    assert_eq!(shuffle(&data, 0), data);
+    assert_eq!(unshuffle(&data, 0), data);
+}
@@ -0,0 +1,94 @@
+// Firmware signing tool
+//
+//   fwsign keygen <secret.key> <public.key>
+//   fwsign sign <secret.key> <version> <firmware.bin> <image.out>
+//   fwsign verify <public.key> <image> [min-version]
+//   fwsign inspect <image>
+//
+// Keys are stored as raw 32-byte files. Keep the secret key off devices.
+
+use secure_boot::ed25519_dalek::{SigningKey, VerifyingKey};
+use secure_boot::image::{parse, HEADER_LEN};
+use secure_boot::{sign_image, Bootloader};
+use std::error::Error;
+use std::fs;
+use std::process::ExitCode;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn read_key(path: &str) -> Result<[u8; 32], Box<dyn Error>> {
+    let bytes = fs::read(path)?;
+    let key: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| format!("{}: expected a 32-byte key", path))?;
+    Ok(key)
+}
+
+fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    match args {
+        [cmd, secret, public] if cmd == "keygen" => {
+            let mut seed = [0u8; 32];
+            getrandom::getrandom(&mut seed).map_err(|e| format!("no randomness: {}", e))?;
+            let key = SigningKey::from_bytes(&seed);
+            fs::write(secret, key.to_bytes())?;
+            fs::write(public, key.verifying_key().to_bytes())?;
+            println!("public key: {}", hex(key.verifying_key().as_bytes()));
+        }
+        [cmd, secret, version, input, output] if cmd == "sign" => {
+            let key = SigningKey::from_bytes(&read_key(secret)?);
+            let version: u32 = version.parse().map_err(|_| "version must be a number")?;
+            let payload = fs::read(input)?;
+            let image = sign_image(&key, version, &payload);
+            fs::write(output, &image)?;
+            println!(
+                "signed {} ({} bytes) as version {}",
+                output,
+                image.len(),
+                version
+            );
+        }
+        [cmd, public, image, rest @ ..] if cmd == "verify" && rest.len() <= 1 => {
+            let key = VerifyingKey::from_bytes(&read_key(public)?)?;
+            let min_version = match rest.first() {
+                Some(v) => v.parse().map_err(|_| "min-version must be a number")?,
+                None => 0,
+            };
+            let verified = Bootloader::new(key, min_version).verify(&fs::read(image)?)?;
+            println!(
+                "OK: version {}, {} payload bytes",
+                verified.version,
+                verified.payload.len()
+            );
+        }
+        [cmd, image] if cmd == "inspect" => {
+            let bytes = fs::read(image)?;
+            let (header, _) = parse(&bytes)?;
+            println!("version:      {}", header.version);
+            println!("header:       {} bytes", HEADER_LEN);
+            println!("payload:      {} bytes", header.payload_len);
+            println!("sha256:       {}", hex(&header.payload_hash));
+            println!("signature:    {}", hex(&header.signature));
+        }
+        _ => {
+            return Err("usage: fwsign keygen <secret.key> <public.key>\n       \
+                 fwsign sign <secret.key> <version> <firmware.bin> <image.out>\n       \
+                 fwsign verify <public.key> <image> [min-version]\n       \
+                 fwsign inspect <image>"
+                .into())
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("fwsign: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
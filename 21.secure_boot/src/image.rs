@@ -0,0 +1,122 @@
+// Signed firmware image format
+//
+//   offset  size  field
+//   0       4     magic "RSFW"
+//   4       2     format version (1)
+//   6       2     reserved, zero
+//   8       4     firmware version (monotonic security counter)
+//   12      4     payload length
+//   16      32    SHA-256 of the payload
+//   48      64    Ed25519 signature over bytes 0..48
+//   112     ...   payload
+//
+// All integers are little-endian. The signature covers the header, and the
+// header pins the payload through its hash, so one signature protects both.
+
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+pub const MAGIC: [u8; 4] = *b"RSFW";
+pub const FORMAT_VERSION: u16 = 1;
+pub const SIGNED_LEN: usize = 48;
+pub const HEADER_LEN: usize = SIGNED_LEN + 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageError {
+    TooShort(usize),
+    BadMagic([u8; 4]),
+    UnsupportedFormat(u16),
+    LengthMismatch { header: u32, actual: usize },
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::TooShort(n) => {
+                write!(f, "image is {} bytes, header needs {}", n, HEADER_LEN)
+            }
+            ImageError::BadMagic(m) => write!(f, "bad magic {:02X?}", m),
+            ImageError::UnsupportedFormat(v) => write!(f, "unsupported format version {}", v),
+            ImageError::LengthMismatch { header, actual } => {
+                write!(
+                    f,
+                    "header says {} payload bytes, file has {}",
+                    header, actual
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub version: u32,
+    pub payload_len: u32,
+    pub payload_hash: [u8; 32],
+    pub signature: [u8; 64],
+}
+
+impl Header {
+    pub fn new(version: u32, payload: &[u8]) -> Header {
+        Header {
+            version,
+            payload_len: payload.len() as u32,
+            payload_hash: sha256(payload),
+            signature: [0; 64],
+        }
+    }
+
+    // The bytes the signature is computed over
+    pub fn signed_bytes(&self) -> [u8; SIGNED_LEN] {
+        let mut out = [0u8; SIGNED_LEN];
+        out[0..4].copy_from_slice(&MAGIC);
+        out[4..6].copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+        out[8..12].copy_from_slice(&self.version.to_le_bytes());
+        out[12..16].copy_from_slice(&self.payload_len.to_le_bytes());
+        out[16..48].copy_from_slice(&self.payload_hash);
+        out
+    }
+
+    pub fn to_bytes(&self) -> [u8; HEADER_LEN] {
+        let mut out = [0u8; HEADER_LEN];
+        out[..SIGNED_LEN].copy_from_slice(&self.signed_bytes());
+        out[SIGNED_LEN..].copy_from_slice(&self.signature);
+        out
+    }
+}
+
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+// Split an image into its header and payload; checks structure only
+pub fn parse(image: &[u8]) -> Result<(Header, &[u8]), ImageError> {
+    if image.len() < HEADER_LEN {
+        return Err(ImageError::TooShort(image.len()));
+    }
+    let magic: [u8; 4] = image[0..4].try_into().unwrap();
+    if magic != MAGIC {
+        return Err(ImageError::BadMagic(magic));
+    }
+    let format = u16::from_le_bytes([image[4], image[5]]);
+    if format != FORMAT_VERSION {
+        return Err(ImageError::UnsupportedFormat(format));
+    }
+
+    let header = Header {
+        version: u32::from_le_bytes(image[8..12].try_into().unwrap()),
+        payload_len: u32::from_le_bytes(image[12..16].try_into().unwrap()),
+        payload_hash: image[16..48].try_into().unwrap(),
+        signature: image[48..112].try_into().unwrap(),
+    };
+    let payload = &image[HEADER_LEN..];
+    if payload.len() != header.payload_len as usize {
+        return Err(ImageError::LengthMismatch {
+            header: header.payload_len,
+            actual: payload.len(),
+        });
+    }
+    Ok((header, payload))
+}
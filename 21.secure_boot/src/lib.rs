@@ -0,0 +1,124 @@
+// Firmware signing and a simulated secure bootloader
+//
+// The build server holds an Ed25519 signing key and produces signed images
+// (`sign_image`). The device holds only the public verifying key and an
+// anti-rollback counter; `Bootloader::verify` accepts an image only if its
+// structure, payload hash, signature and version all check out.
+
+pub mod image;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use image::{parse, sha256, Header, ImageError};
+use std::fmt;
+
+pub use ed25519_dalek;
+
+pub fn sign_image(key: &SigningKey, version: u32, payload: &[u8]) -> Vec<u8> {
+    let mut header = Header::new(version, payload);
+    header.signature = key.sign(&header.signed_bytes()).to_bytes();
+
+    let mut out = header.to_bytes().to_vec();
+    out.extend_from_slice(payload);
+    out
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BootError {
+    Format(ImageError),
+    HashMismatch,
+    BadSignature,
+    Rollback { image: u32, minimum: u32 },
+}
+
+impl fmt::Display for BootError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BootError::Format(e) => write!(f, "malformed image: {}", e),
+            BootError::HashMismatch => write!(f, "payload does not match its hash"),
+            BootError::BadSignature => write!(f, "signature verification failed"),
+            BootError::Rollback { image, minimum } => write!(
+                f,
+                "image version {} is older than the minimum {}",
+                image, minimum
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BootError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BootError::Format(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ImageError> for BootError {
+    fn from(e: ImageError) -> Self {
+        BootError::Format(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedImage {
+    pub version: u32,
+    pub payload: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct Bootloader {
+    trusted_key: VerifyingKey,
+    // Anti-rollback counter; on real hardware this lives in OTP fuses or
+    // a monotonic counter that software cannot decrease
+    min_version: u32,
+}
+
+impl Bootloader {
+    pub fn new(trusted_key: VerifyingKey, min_version: u32) -> Bootloader {
+        Bootloader {
+            trusted_key,
+            min_version,
+        }
+    }
+
+    pub fn min_version(&self) -> u32 {
+        self.min_version
+    }
+
+    // Checks run cheapest-first; nothing from the image is trusted until the
+    // signature has been verified
+    pub fn verify(&self, image: &[u8]) -> Result<VerifiedImage, BootError> {
+        let (header, payload) = parse(image)?;
+
+        if sha256(payload) != header.payload_hash {
+            return Err(BootError::HashMismatch);
+        }
+
+        // Strict: no second encoding of the same signature, and no
+        // small-order key or R for which one signature fits many headers
+        let signature = Signature::from_bytes(&header.signature);
+        self.trusted_key
+            .verify_strict(&header.signed_bytes(), &signature)
+            .map_err(|_| BootError::BadSignature)?;
+
+        if header.version < self.min_version {
+            return Err(BootError::Rollback {
+                image: header.version,
+                minimum: self.min_version,
+            });
+        }
+
+        Ok(VerifiedImage {
+            version: header.version,
+            payload: payload.to_vec(),
+        })
+    }
+
+    // Verify and, on success, raise the rollback counter to this version
+    pub fn install(&mut self, image: &[u8]) -> Result<VerifiedImage, BootError> {
+        let verified = self.verify(image)?;
+        self.min_version = self.min_version.max(verified.version);
+        Ok(verified)
+    }
+}
@@ -0,0 +1,123 @@
+use secure_boot::ed25519_dalek::SigningKey;
+use secure_boot::image::{HEADER_LEN, SIGNED_LEN};
+use secure_boot::{sign_image, Bootloader};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn firmware(version: u32) -> Vec<u8> {
+    // Stand-in for a compiled application binary
+    let mut payload = format!("edge-app v{} ", version).into_bytes();
+    payload.extend((0..4096u32).map(|i| (i * 7 + version) as u8));
+    payload
+}
+
+fn main() {
+    println!("=== Secure Boot ===\n");
+
+    // Fixed seeds keep the demo reproducible; real keys come from `fwsign keygen`
+    let vendor = SigningKey::from_bytes(&[0x42; 32]);
+    let attacker = SigningKey::from_bytes(&[0x66; 32]);
+
+    // 1. Keys
+    println!("1. Keys:");
+    println!(
+        "   vendor public key: {}",
+        hex(vendor.verifying_key().as_bytes())
+    );
+    let mut bootloader = Bootloader::new(vendor.verifying_key(), 0);
+
+    // 2. Signing an image
+    println!("\n2. Signing firmware version 3:");
+    let image_v3 = sign_image(&vendor, 3, &firmware(3));
+    println!(
+        "   image: {} bytes ({} header + payload)",
+        image_v3.len(),
+        HEADER_LEN
+    );
+    println!(
+        "   signature: {}...",
+        hex(&image_v3[SIGNED_LEN..SIGNED_LEN + 16])
+    );
+    match bootloader.install(&image_v3) {
+        Ok(v) => println!(
+            "   accepted version {}, counter now {}",
+            v.version,
+            bootloader.min_version()
+        ),
+        Err(e) => println!("   rejected: {}", e),
+    }
+
+    // 3. Tampering
+    println!("\n3. Tampered images:");
+    let mut payload_patch = image_v3.clone();
+    payload_patch[HEADER_LEN + 100] ^= 0x01;
+
+    let mut version_patch = image_v3.clone();
+    version_patch[8] = 9;
+
+    let mut rehashed = sign_image(&vendor, 3, &firmware(3));
+    rehashed[HEADER_LEN + 100] ^= 0x01;
+    let new_hash = secure_boot::image::sha256(&rehashed[HEADER_LEN..]);
+    rehashed[16..48].copy_from_slice(&new_hash);
+
+    let foreign = sign_image(&attacker, 4, &firmware(4));
+
+    let mut truncated = image_v3.clone();
+    truncated.truncate(image_v3.len() - 10);
+
+    for (name, image) in [
+        ("payload byte flipped", &payload_patch),
+        ("version field edited", &version_patch),
+        ("payload + hash patched", &rehashed),
+        ("signed by another key", &foreign),
+        ("truncated download", &truncated),
+        ("random bytes", &vec![0xAB; 64]),
+    ] {
+        match bootloader.verify(image) {
+            Ok(v) => println!("   {:<24} ACCEPTED version {} (!)", name, v.version),
+            Err(e) => println!("   {:<24} rejected: {}", name, e),
+        }
+    }
+
+    // 4. Version monotonicity
+    println!("\n4. Anti-rollback:");
+    let image_v2 = sign_image(&vendor, 2, &firmware(2));
+    let image_v5 = sign_image(&vendor, 5, &firmware(5));
+    for (name, image) in [
+        ("v2 (old, validly signed)", &image_v2),
+        ("v5", &image_v5),
+        ("v3 again", &image_v3),
+    ] {
+        match bootloader.install(image) {
+            Ok(v) => println!(
+                "   {:<25} accepted, counter {} -> {}",
+                name,
+                v.version,
+                bootloader.min_version()
+            ),
+            Err(e) => println!("   {:<25} rejected: {}", name, e),
+        }
+    }
+
+    // 5. The boot decision
+    println!("\n5. Boot decision with two slots (A = v5, B = corrupted):");
+    let slots = [("A", &image_v5), ("B", &payload_patch)];
+    let chosen = slots
+        .iter()
+        .filter_map(|(slot, image)| bootloader.verify(image).ok().map(|v| (*slot, v)))
+        .max_by_key(|(_, v)| v.version);
+    match chosen {
+        Some((slot, v)) => println!("   booting slot {} (version {})", slot, v.version),
+        None => println!("   no valid image: staying in recovery mode"),
+    }
+
+    // 6. The tooling
+    println!("\n6. Signing tool:");
+    println!("   cargo run --bin fwsign -- keygen vendor.key vendor.pub");
+    println!("   cargo run --bin fwsign -- sign vendor.key 6 app.bin app.img");
+    println!("   cargo run --bin fwsign -- verify vendor.pub app.img 5");
+
+    println!("\n=== End of Secure Boot Examples ===");
+}
@@ -0,0 +1,214 @@
+use secure_boot::ed25519_dalek::{SigningKey, VerifyingKey};
+use secure_boot::image::{parse, sha256, Header, ImageError, HEADER_LEN, MAGIC, SIGNED_LEN};
+use secure_boot::{sign_image, BootError, Bootloader};
+
+fn vendor() -> SigningKey {
+    SigningKey::from_bytes(&[0x42; 32])
+}
+
+fn firmware(version: u32) -> Vec<u8> {
+    let mut payload = format!("edge-app v{} ", version).into_bytes();
+    payload.extend((0..4096u32).map(|i| (i * 7 + version) as u8));
+    payload
+}
+
+fn bootloader(min_version: u32) -> Bootloader {
+    Bootloader::new(vendor().verifying_key(), min_version)
+}
+
+#[test]
+fn header_layout_is_pinned() {
+    let payload = firmware(3);
+    let image = sign_image(&vendor(), 3, &payload);
+    assert_eq!(image.len(), HEADER_LEN + payload.len());
+    assert_eq!(image[0..4], MAGIC);
+    assert_eq!(image[4..8], [1, 0, 0, 0]);
+    assert_eq!(image[8..12], 3u32.to_le_bytes());
+    assert_eq!(image[12..16], (payload.len() as u32).to_le_bytes());
+    assert_eq!(image[16..48], sha256(&payload));
+    assert_eq!(image[HEADER_LEN..], payload[..]);
+
+    let (header, body) = parse(&image).unwrap();
+    assert_eq!(header.version, 3);
+    assert_eq!(header.to_bytes()[..], image[..HEADER_LEN]);
+    assert_eq!(body, &payload[..]);
+}
+
+#[test]
+fn signed_image_is_accepted_and_raises_the_counter() {
+    let mut boot = bootloader(0);
+    let verified = boot
+        .install(&sign_image(&vendor(), 3, &firmware(3)))
+        .unwrap();
+    assert_eq!(verified.version, 3);
+    assert_eq!(verified.payload, firmware(3));
+    assert_eq!(boot.min_version(), 3);
+}
+
+#[test]
+fn empty_payload_is_valid() {
+    let verified = bootloader(0)
+        .verify(&sign_image(&vendor(), 1, &[]))
+        .unwrap();
+    assert!(verified.payload.is_empty());
+}
+
+#[test]
+fn tampered_images_are_rejected() {
+    let image = sign_image(&vendor(), 3, &firmware(3));
+    let boot = bootloader(0);
+
+    let mut payload_flip = image.clone();
+    payload_flip[HEADER_LEN + 100] ^= 0x01;
+    assert_eq!(boot.verify(&payload_flip), Err(BootError::HashMismatch));
+
+    let mut version_edit = image.clone();
+    version_edit[8] = 9;
+    assert_eq!(boot.verify(&version_edit), Err(BootError::BadSignature));
+
+    // A consistent payload and hash still need the vendor's signature
+    let mut rehashed = payload_flip.clone();
+    let hash = sha256(&rehashed[HEADER_LEN..]);
+    rehashed[16..48].copy_from_slice(&hash);
+    assert_eq!(boot.verify(&rehashed), Err(BootError::BadSignature));
+
+    let mut signature = image.clone();
+    signature[SIGNED_LEN] ^= 0x80;
+    assert_eq!(boot.verify(&signature), Err(BootError::BadSignature));
+}
+
+// The group order L, little-endian
+const L: [u8; 32] = [
+    0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10,
+];
+
+#[test]
+fn a_malleated_signature_is_rejected() {
+    // S + L is the same scalar mod L, so it satisfies the verification
+    // equation; only a canonical S is accepted
+    let image = sign_image(&vendor(), 3, &firmware(3));
+    let mut malleated = image.clone();
+    let s = &mut malleated[SIGNED_LEN + 32..HEADER_LEN];
+    let mut carry = 0u16;
+    for (byte, l) in s.iter_mut().zip(L) {
+        let sum = *byte as u16 + l as u16 + carry;
+        *byte = sum as u8;
+        carry = sum >> 8;
+    }
+    assert_eq!(carry, 0);
+    assert_ne!(malleated, image);
+    assert_eq!(
+        bootloader(0).verify(&malleated),
+        Err(BootError::BadSignature)
+    );
+    assert!(bootloader(0).verify(&image).is_ok());
+}
+
+#[test]
+fn a_small_order_key_verifies_nothing() {
+    // With the identity as the key, R = identity and S = 0 satisfy the
+    // plain equation for every message. Strict verification refuses the key.
+    let mut identity = [0u8; 32];
+    identity[0] = 1;
+    let weak = VerifyingKey::from_bytes(&identity).unwrap();
+    let payload = firmware(9);
+    let mut header = Header::new(9, &payload);
+    header.signature[..32].copy_from_slice(&identity);
+    let mut image = header.to_bytes().to_vec();
+    image.extend_from_slice(&payload);
+    assert_eq!(
+        Bootloader::new(weak, 0).verify(&image),
+        Err(BootError::BadSignature)
+    );
+}
+
+#[test]
+fn another_key_is_not_trusted() {
+    let attacker = SigningKey::from_bytes(&[0x66; 32]);
+    let image = sign_image(&attacker, 4, &firmware(4));
+    assert_eq!(bootloader(0).verify(&image), Err(BootError::BadSignature));
+}
+
+#[test]
+fn malformed_images_fail_before_any_crypto() {
+    let image = sign_image(&vendor(), 3, &firmware(3));
+    let boot = bootloader(0);
+
+    let truncated = &image[..image.len() - 10];
+    assert_eq!(
+        boot.verify(truncated),
+        Err(BootError::Format(ImageError::LengthMismatch {
+            header: firmware(3).len() as u32,
+            actual: firmware(3).len() - 10,
+        }))
+    );
+    assert_eq!(
+        boot.verify(&[0xAB; 64]),
+        Err(BootError::Format(ImageError::TooShort(64)))
+    );
+
+    let mut magic = image.clone();
+    magic[0] = b'X';
+    assert_eq!(
+        boot.verify(&magic),
+        Err(BootError::Format(ImageError::BadMagic(*b"XSFW")))
+    );
+
+    let mut format = image.clone();
+    format[4] = 2;
+    assert_eq!(
+        boot.verify(&format),
+        Err(BootError::Format(ImageError::UnsupportedFormat(2)))
+    );
+}
+
+#[test]
+fn older_versions_are_refused_after_an_install() {
+    let mut boot = bootloader(0);
+    let v2 = sign_image(&vendor(), 2, &firmware(2));
+    let v3 = sign_image(&vendor(), 3, &firmware(3));
+    let v5 = sign_image(&vendor(), 5, &firmware(5));
+
+    boot.install(&v3).unwrap();
+    assert_eq!(
+        boot.install(&v2),
+        Err(BootError::Rollback {
+            image: 2,
+            minimum: 3
+        })
+    );
+    boot.install(&v5).unwrap();
+    assert_eq!(
+        boot.verify(&v3),
+        Err(BootError::Rollback {
+            image: 3,
+            minimum: 5
+        })
+    );
+    // Reinstalling the current version is allowed
+    assert_eq!(boot.install(&v5).unwrap().version, 5);
+    assert_eq!(boot.min_version(), 5);
+}
+
+#[test]
+fn verify_alone_does_not_move_the_counter() {
+    let boot = bootloader(1);
+    boot.verify(&sign_image(&vendor(), 7, &firmware(7)))
+        .unwrap();
+    assert_eq!(boot.min_version(), 1);
+}
+
+#[test]
+fn boot_picks_the_newest_valid_slot() {
+    let boot = bootloader(0);
+    let v4 = sign_image(&vendor(), 4, &firmware(4));
+    let mut corrupted_v6 = sign_image(&vendor(), 6, &firmware(6));
+    corrupted_v6[HEADER_LEN] ^= 0xFF;
+
+    let chosen = [("A", &v4), ("B", &corrupted_v6)]
+        .iter()
+        .filter_map(|(slot, image)| boot.verify(image).ok().map(|v| (*slot, v.version)))
+        .max_by_key(|(_, version)| *version);
+    assert_eq!(chosen, Some(("A", 4)));
+}
@@ -0,0 +1,44 @@
+// The signing tool end to end, through the built binary
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn fwsign(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_fwsign"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .expect("run fwsign")
+}
+
+#[test]
+fn keygen_sign_verify_inspect() {
+    let dir = std::env::temp_dir().join(format!("rust-sys-fwsign-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("app.bin"), b"firmware payload").unwrap();
+
+    assert!(fwsign(&dir, &["keygen", "vendor.key", "vendor.pub"])
+        .status
+        .success());
+    assert_eq!(fs::read(dir.join("vendor.key")).unwrap().len(), 32);
+
+    let sign = fwsign(&dir, &["sign", "vendor.key", "6", "app.bin", "app.img"]);
+    assert!(sign.status.success());
+
+    let verify = fwsign(&dir, &["verify", "vendor.pub", "app.img", "5"]);
+    assert!(verify.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&verify.stdout).trim(),
+        "OK: version 6, 16 payload bytes"
+    );
+
+    // Below the minimum version the tool fails
+    let rollback = fwsign(&dir, &["verify", "vendor.pub", "app.img", "7"]);
+    assert!(!rollback.status.success());
+
+    let inspect = fwsign(&dir, &["inspect", "app.img"]);
+    assert!(String::from_utf8_lossy(&inspect.stdout).contains("version:      6"));
+
+    assert!(!fwsign(&dir, &["bogus"]).status.success());
+    fs::remove_dir_all(&dir).unwrap();
+}
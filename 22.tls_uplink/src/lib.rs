@@ -0,0 +1,222 @@
+// Telemetry over TLS with rustls
+//
+// The device trusts exactly one root CA, loaded from a PEM file, instead of
+// the system store. Failures are reported by category so the caller can
+// react sensibly: an IO error is worth retrying, a certificate error means
+// the configuration (or the network) is wrong and retrying will not help.
+
+pub mod pki;
+pub mod server;
+
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use rustls::{
+    AlertDescription, CertificateError, ClientConfig, ClientConnection, RootCertStore, StreamOwned,
+};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum UplinkError {
+    // Network trouble: refused, reset, timed out; usually worth a retry
+    Io(io::Error),
+    // A PEM file could not be read or contained nothing usable
+    Pem { path: PathBuf, reason: String },
+    // We rejected the server's certificate
+    Certificate(CertificateError),
+    // The server rejected us, e.g. a missing or untrusted client certificate
+    PeerRejected(AlertDescription),
+    // Any other TLS protocol failure
+    Tls(rustls::Error),
+    // The server answered something we did not expect
+    Protocol(String),
+}
+
+impl UplinkError {
+    // Only network errors are transient; everything else needs a human
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, UplinkError::Io(_))
+    }
+}
+
+impl fmt::Display for UplinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UplinkError::Io(e) => write!(f, "network error: {}", e),
+            UplinkError::Pem { path, reason } => write!(f, "{}: {}", path.display(), reason),
+            UplinkError::Certificate(e) => write!(f, "server certificate rejected: {}", e),
+            UplinkError::PeerRejected(alert) => write!(f, "server rejected us: {:?}", alert),
+            UplinkError::Tls(e) => write!(f, "TLS error: {}", e),
+            UplinkError::Protocol(msg) => write!(f, "protocol error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for UplinkError {}
+
+impl From<rustls::Error> for UplinkError {
+    fn from(e: rustls::Error) -> Self {
+        match e {
+            rustls::Error::InvalidCertificate(cert) => UplinkError::Certificate(cert),
+            rustls::Error::AlertReceived(alert) => UplinkError::PeerRejected(alert),
+            other => UplinkError::Tls(other),
+        }
+    }
+}
+
+// rustls reports handshake failures through `io::Error`; dig the TLS error
+// back out so it is not mistaken for a network problem
+impl From<io::Error> for UplinkError {
+    fn from(e: io::Error) -> Self {
+        let is_tls = e
+            .get_ref()
+            .is_some_and(|inner| inner.downcast_ref::<rustls::Error>().is_some());
+        if is_tls {
+            let inner = e.into_inner().unwrap();
+            return (*inner.downcast::<rustls::Error>().unwrap()).into();
+        }
+        UplinkError::Io(e)
+    }
+}
+
+fn pem_error(path: &Path, e: impl fmt::Display) -> UplinkError {
+    UplinkError::Pem {
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    }
+}
+
+pub fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, UplinkError> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .map_err(|e| pem_error(path, e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| pem_error(path, e))?;
+    if certs.is_empty() {
+        return Err(pem_error(path, "no certificates found"));
+    }
+    Ok(certs)
+}
+
+pub fn load_key(path: &Path) -> Result<PrivateKeyDer<'static>, UplinkError> {
+    PrivateKeyDer::from_pem_file(path).map_err(|e| pem_error(path, e))
+}
+
+// A root store holding only the CAs in `path`
+pub fn load_roots(path: &Path) -> Result<RootCertStore, UplinkError> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(path)? {
+        roots.add(cert)?;
+    }
+    Ok(roots)
+}
+
+// Certificate and key presented to the server for mutual TLS
+pub struct ClientIdentity {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+pub fn client_config(
+    ca: &Path,
+    identity: Option<&ClientIdentity>,
+) -> Result<Arc<ClientConfig>, UplinkError> {
+    let builder = ClientConfig::builder().with_root_certificates(load_roots(ca)?);
+    let config = match identity {
+        Some(id) => builder.with_client_auth_cert(load_certs(&id.cert)?, load_key(&id.key)?)?,
+        None => builder.with_no_client_auth(),
+    };
+    Ok(Arc::new(config))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Telemetry<'a> {
+    pub sensor: &'a str,
+    pub metric: &'a str,
+    pub value: f64,
+    pub timestamp_ms: u64,
+}
+
+impl Telemetry<'_> {
+    // One JSON object per line
+    pub fn to_line(&self) -> String {
+        format!(
+            "{{\"sensor\":\"{}\",\"metric\":\"{}\",\"value\":{},\"ts\":{}}}\n",
+            self.sensor, self.metric, self.value, self.timestamp_ms
+        )
+    }
+}
+
+pub struct TelemetryClient {
+    stream: BufReader<StreamOwned<ClientConnection, TcpStream>>,
+    sent: u64,
+}
+
+impl TelemetryClient {
+    // Connect and finish the handshake up front so certificate problems are
+    // reported here rather than on the first send
+    pub fn connect(
+        addr: SocketAddr,
+        server_name: &str,
+        config: Arc<ClientConfig>,
+    ) -> Result<TelemetryClient, UplinkError> {
+        let name = ServerName::try_from(server_name.to_string())
+            .map_err(|e| UplinkError::Protocol(format!("bad server name: {}", e)))?;
+        let tcp = TcpStream::connect_timeout(&addr, Duration::from_secs(2))?;
+        tcp.set_read_timeout(Some(Duration::from_secs(2)))?;
+
+        let mut tls = StreamOwned::new(ClientConnection::new(config, name)?, tcp);
+        while tls.conn.is_handshaking() {
+            tls.conn.complete_io(&mut tls.sock)?;
+        }
+        Ok(TelemetryClient {
+            stream: BufReader::new(tls),
+            sent: 0,
+        })
+    }
+
+    // Negotiated protocol and cipher suite, e.g. for a startup log line
+    pub fn session(&self) -> (String, String) {
+        let conn = &self.stream.get_ref().conn;
+        let version = conn
+            .protocol_version()
+            .map(|v| format!("{:?}", v))
+            .unwrap_or_default();
+        let suite = conn
+            .negotiated_cipher_suite()
+            .map(|s| format!("{:?}", s.suite()))
+            .unwrap_or_default();
+        (version, suite)
+    }
+
+    // Send one reading and wait for the server's "ack <n>"
+    pub fn send(&mut self, reading: &Telemetry) -> Result<u64, UplinkError> {
+        self.stream
+            .get_mut()
+            .write_all(reading.to_line().as_bytes())?;
+        self.stream.get_mut().flush()?;
+        self.sent += 1;
+
+        let mut line = String::new();
+        if self.stream.read_line(&mut line)? == 0 {
+            return Err(UplinkError::Protocol("connection closed".into()));
+        }
+        match line.trim().strip_prefix("ack ").map(str::parse::<u64>) {
+            Some(Ok(n)) if n == self.sent => Ok(n),
+            _ => Err(UplinkError::Protocol(format!(
+                "unexpected reply {:?}",
+                line.trim()
+            ))),
+        }
+    }
+
+    pub fn close(mut self) -> Result<(), UplinkError> {
+        let tls = self.stream.get_mut();
+        tls.conn.send_close_notify();
+        tls.flush()?;
+        Ok(())
+    }
+}
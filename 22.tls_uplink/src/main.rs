@@ -0,0 +1,181 @@
+use retry::{retry_with, Jitter, Policy, SystemClock};
+use std::net::{SocketAddr, TcpListener};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use tls_uplink::server::{server_config, TelemetryServer};
+use tls_uplink::{client_config, pki, ClientIdentity, Telemetry, TelemetryClient, UplinkError};
+
+const SERVER_NAME: &str = "telemetry.example.local";
+
+fn reading(i: u64) -> Telemetry<'static> {
+    Telemetry {
+        sensor: "gw-01/0",
+        metric: "temperature",
+        value: 21.5 + i as f64 * 0.25,
+        timestamp_ms: 1_700_000_000_000 + i * 1000,
+    }
+}
+
+fn report(label: &str, result: Result<TelemetryClient, UplinkError>) {
+    match result {
+        Ok(_) => println!("   {:<22} connected (!)", label),
+        Err(e) => println!(
+            "   {:<22} {} [retry: {}]",
+            label,
+            e,
+            if e.is_retryable() { "yes" } else { "no" }
+        ),
+    }
+}
+
+// Let the server thread record what it saw before printing it
+fn server_events(server: &TelemetryServer) {
+    thread::sleep(Duration::from_millis(50));
+    for event in server.take_events() {
+        println!("   server: {}", event);
+    }
+}
+
+fn main() -> Result<(), UplinkError> {
+    println!("=== TLS Uplink ===\n");
+
+    // 1. Certificates on disk
+    println!("1. Generating a demo PKI:");
+    let dir = std::env::temp_dir().join("rust-sys-tls-demo");
+    let files = pki::generate(&dir, SERVER_NAME, "gw-01")?;
+    for path in [&files.ca_cert, &files.server_cert, &files.device_cert] {
+        println!("   wrote {}", path.display());
+    }
+
+    // 2. A normal session: the device trusts only our CA file
+    println!("\n2. Sending telemetry over TLS:");
+    let server =
+        TelemetryServer::start(server_config(&files.server_cert, &files.server_key, None)?)?;
+    let addr = server.local_addr();
+    let config = client_config(&files.ca_cert, None)?;
+    let mut client = TelemetryClient::connect(addr, SERVER_NAME, config.clone())?;
+    let (version, suite) = client.session();
+    println!("   negotiated {} with {}", version, suite);
+    for i in 0..3 {
+        let r = reading(i);
+        let ack = client.send(&r)?;
+        println!("   sent {} = {:.2} -> ack {}", r.metric, r.value, ack);
+    }
+    client.close()?;
+    server_events(&server);
+
+    // 3. Certificate errors are not IO errors
+    println!("\n3. Certificate failures:");
+    let rogue = client_config(&files.rogue_ca_cert, None)?;
+    report(
+        "untrusted root",
+        TelemetryClient::connect(addr, SERVER_NAME, rogue),
+    );
+    report(
+        "wrong host name",
+        TelemetryClient::connect(addr, "evil.example.com", config.clone()),
+    );
+    server_events(&server);
+
+    // 4. IO and configuration errors
+    println!("\n4. Network and file failures:");
+    let closed: SocketAddr = {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        listener.local_addr()?
+    };
+    report(
+        "nothing listening",
+        TelemetryClient::connect(closed, SERVER_NAME, config),
+    );
+    match client_config(Path::new("/nonexistent/ca.pem"), None) {
+        Ok(_) => println!("   missing CA file       loaded (!)"),
+        Err(e) => println!("   {:<22} {}", "missing CA file", e),
+    }
+    match client_config(&files.device_key, None) {
+        Ok(_) => println!("   key as CA file        loaded (!)"),
+        Err(e) => println!("   {:<22} {}", "key as CA file", e),
+    }
+
+    // 5. Mutual TLS: the server also checks the device
+    println!("\n5. Mutual TLS:");
+    let mtls = TelemetryServer::start(server_config(
+        &files.server_cert,
+        &files.server_key,
+        Some(&files.ca_cert),
+    )?)?;
+    let identity = ClientIdentity {
+        cert: files.device_cert.clone(),
+        key: files.device_key.clone(),
+    };
+    let with_cert = client_config(&files.ca_cert, Some(&identity))?;
+    let mut client = TelemetryClient::connect(mtls.local_addr(), SERVER_NAME, with_cert)?;
+    println!("   with device cert:    ack {}", client.send(&reading(10))?);
+    client.close()?;
+    server_events(&mtls);
+
+    // In TLS 1.3 the client finishes its side of the handshake first, so the
+    // server's rejection arrives with the first reply
+    let without_cert = client_config(&files.ca_cert, None)?;
+    let result = TelemetryClient::connect(mtls.local_addr(), SERVER_NAME, without_cert)
+        .and_then(|mut client| client.send(&reading(11)));
+    match result {
+        Ok(ack) => println!("   without device cert: ack {} (!)", ack),
+        Err(e) => println!("   without device cert: {}", e),
+    }
+    server_events(&mtls);
+
+    // 6. Reconnecting, but only while the error is transient
+    println!("\n6. Reconnecting with backoff:");
+    let policy = Policy {
+        max_attempts: Some(8),
+        base: Duration::from_millis(50),
+        max_delay: Duration::from_millis(400),
+        jitter: Jitter::Equal,
+        ..Policy::default()
+    };
+    // The collector comes up 300 ms after the device starts connecting
+    let late: SocketAddr = {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        listener.local_addr()?
+    };
+    let late_config = server_config(&files.server_cert, &files.server_key, None)?;
+    let starter = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(300));
+        TelemetryServer::start_on(late, late_config)
+    });
+    let config = client_config(&files.ca_cert, None)?;
+    let result = retry_with(
+        &policy,
+        &SystemClock,
+        UplinkError::is_retryable,
+        |attempt| {
+            let result = TelemetryClient::connect(late, SERVER_NAME, config.clone());
+            if let Err(e) = &result {
+                println!("   attempt {}: {}", attempt, e);
+            }
+            result
+        },
+    );
+    let late_server = starter.join().expect("server thread panicked")?;
+    match result {
+        Ok(mut client) => {
+            println!("   connected, ack {}", client.send(&reading(20))?);
+            client.close()?;
+        }
+        Err(e) => println!("   {}: {}", e, e.last),
+    }
+    server_events(&late_server);
+
+    // A certificate problem gives up at once instead of looping
+    let rogue = client_config(&files.rogue_ca_cert, None)?;
+    let result = retry_with(&policy, &SystemClock, UplinkError::is_retryable, |_| {
+        TelemetryClient::connect(addr, SERVER_NAME, rogue.clone())
+    });
+    if let Err(e) = result {
+        println!("   untrusted root: {}", e);
+    }
+
+    println!("\n=== End of TLS Uplink Examples ===");
+    Ok(())
+}
@@ -0,0 +1,73 @@
+// A throwaway certificate authority for the demo
+//
+// Real deployments get their CA and device certificates from a provisioning
+// system; here rcgen writes the same PEM files so the rest of the lesson can
+// load them from disk exactly as a device would.
+
+use rcgen::{BasicConstraints, CertificateParams, DnType, IsCa, KeyPair};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub struct PkiFiles {
+    pub ca_cert: PathBuf,
+    pub server_cert: PathBuf,
+    pub server_key: PathBuf,
+    pub device_cert: PathBuf,
+    pub device_key: PathBuf,
+    // A CA nobody trusts, for the "wrong root" case
+    pub rogue_ca_cert: PathBuf,
+}
+
+fn to_io(e: rcgen::Error) -> io::Error {
+    io::Error::other(e)
+}
+
+fn ca(name: &str) -> Result<(rcgen::Certificate, KeyPair), rcgen::Error> {
+    let key = KeyPair::generate()?;
+    let mut params = CertificateParams::new(Vec::new())?;
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params.distinguished_name.push(DnType::CommonName, name);
+    Ok((params.self_signed(&key)?, key))
+}
+
+fn leaf(
+    name: &str,
+    sans: &[&str],
+    issuer: &rcgen::Certificate,
+    issuer_key: &KeyPair,
+) -> Result<(rcgen::Certificate, KeyPair), rcgen::Error> {
+    let key = KeyPair::generate()?;
+    let mut params =
+        CertificateParams::new(sans.iter().map(|s| s.to_string()).collect::<Vec<_>>())?;
+    params.distinguished_name.push(DnType::CommonName, name);
+    Ok((params.signed_by(&key, issuer, issuer_key)?, key))
+}
+
+// Create a CA, a server certificate for `server_name` and a device
+// certificate, and write them as PEM files into `dir`
+pub fn generate(dir: &Path, server_name: &str, device_id: &str) -> io::Result<PkiFiles> {
+    fs::create_dir_all(dir)?;
+
+    let (ca_cert, ca_key) = ca("rust-sys demo root CA").map_err(to_io)?;
+    let (server, server_key) =
+        leaf(server_name, &[server_name], &ca_cert, &ca_key).map_err(to_io)?;
+    let (device, device_key) = leaf(device_id, &[], &ca_cert, &ca_key).map_err(to_io)?;
+    let (rogue, _) = ca("somebody else's CA").map_err(to_io)?;
+
+    let files = PkiFiles {
+        ca_cert: dir.join("ca.pem"),
+        server_cert: dir.join("server.pem"),
+        server_key: dir.join("server.key"),
+        device_cert: dir.join("device.pem"),
+        device_key: dir.join("device.key"),
+        rogue_ca_cert: dir.join("rogue-ca.pem"),
+    };
+    fs::write(&files.ca_cert, ca_cert.pem())?;
+    fs::write(&files.server_cert, server.pem())?;
+    fs::write(&files.server_key, server_key.serialize_pem())?;
+    fs::write(&files.device_cert, device.pem())?;
+    fs::write(&files.device_key, device_key.serialize_pem())?;
+    fs::write(&files.rogue_ca_cert, rogue.pem())?;
+    Ok(files)
+}
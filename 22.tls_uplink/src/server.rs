@@ -0,0 +1,107 @@
+// A minimal TLS telemetry sink: acknowledges every line it receives
+//
+// It stands in for the cloud endpoint so the lesson runs without a network.
+// Each connection is served on its own thread, and what happened is recorded
+// in a shared event list the demo prints.
+
+use crate::{load_certs, load_key, load_roots, UplinkError};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{ServerConfig, ServerConnection, StreamOwned};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// With `client_ca` set the server demands a client certificate signed by it
+pub fn server_config(
+    cert: &Path,
+    key: &Path,
+    client_ca: Option<&Path>,
+) -> Result<Arc<ServerConfig>, UplinkError> {
+    let builder = match client_ca {
+        Some(ca) => {
+            let verifier = WebPkiClientVerifier::builder(Arc::new(load_roots(ca)?))
+                .build()
+                .map_err(|e| UplinkError::Tls(rustls::Error::General(e.to_string())))?;
+            ServerConfig::builder().with_client_cert_verifier(verifier)
+        }
+        None => ServerConfig::builder().with_no_client_auth(),
+    };
+    Ok(Arc::new(
+        builder.with_single_cert(load_certs(cert)?, load_key(key)?)?,
+    ))
+}
+
+pub struct TelemetryServer {
+    addr: SocketAddr,
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl TelemetryServer {
+    // On a free local port
+    pub fn start(config: Arc<ServerConfig>) -> std::io::Result<TelemetryServer> {
+        TelemetryServer::start_on("127.0.0.1:0", config)
+    }
+
+    pub fn start_on(
+        addr: impl ToSocketAddrs,
+        config: Arc<ServerConfig>,
+    ) -> std::io::Result<TelemetryServer> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let events = Arc::new(Mutex::new(Vec::new()));
+
+        let log = Arc::clone(&events);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let config = Arc::clone(&config);
+                let log = Arc::clone(&log);
+                thread::spawn(move || {
+                    let event = serve(stream, config);
+                    log.lock().unwrap().push(event);
+                });
+            }
+        });
+        Ok(TelemetryServer { addr, events })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    // Take the events recorded since the last call
+    pub fn take_events(&self) -> Vec<String> {
+        std::mem::take(&mut *self.events.lock().unwrap())
+    }
+}
+
+fn serve(tcp: TcpStream, config: Arc<ServerConfig>) -> String {
+    let conn = match ServerConnection::new(config) {
+        Ok(conn) => conn,
+        Err(e) => return format!("setup failed: {}", e),
+    };
+    let mut tls = BufReader::new(StreamOwned::new(conn, tcp));
+    let mut received = 0u64;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match tls.read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => {
+                received += 1;
+                let reply = format!("ack {}\n", received);
+                if tls.get_mut().write_all(reply.as_bytes()).is_err() {
+                    break;
+                }
+            }
+            Err(e) => return format!("connection failed after {} lines: {}", received, e),
+        }
+    }
+
+    let client_cert = match tls.get_ref().conn.peer_certificates() {
+        Some(chain) => format!("client cert {} bytes", chain[0].len()),
+        None => "no client cert".to_string(),
+    };
+    format!("received {} lines ({})", received, client_cert)
+}
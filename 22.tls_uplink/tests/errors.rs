@@ -0,0 +1,108 @@
+use rustls::CertificateError;
+use std::net::{SocketAddr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::thread;
+use tls_uplink::pki::{self, PkiFiles};
+use tls_uplink::server::{server_config, TelemetryServer};
+use tls_uplink::{client_config, Telemetry, TelemetryClient, UplinkError};
+
+const SERVER_NAME: &str = "telemetry.example.local";
+
+// A fresh PKI per test, so tests can run in parallel
+fn pki(name: &str) -> (PathBuf, PkiFiles) {
+    let dir = std::env::temp_dir().join(format!("rust-sys-tls-{}-{}", name, std::process::id()));
+    let files = pki::generate(&dir, SERVER_NAME, "gw-01").unwrap();
+    (dir, files)
+}
+
+fn start_server(files: &PkiFiles) -> TelemetryServer {
+    let config = server_config(&files.server_cert, &files.server_key, None).unwrap();
+    TelemetryServer::start(config).unwrap()
+}
+
+// Connect trusting only the CA in `roots`; the attempt must fail
+fn connect_error(addr: SocketAddr, name: &str, roots: &Path) -> UplinkError {
+    let config = client_config(roots, None).unwrap();
+    match TelemetryClient::connect(addr, name, config) {
+        Ok(_) => panic!("connection to {} should have failed", addr),
+        Err(e) => e,
+    }
+}
+
+#[test]
+fn trusted_server_acknowledges_readings() {
+    let (dir, files) = pki("ok");
+    let server = start_server(&files);
+    let config = client_config(&files.ca_cert, None).unwrap();
+    let mut client = TelemetryClient::connect(server.local_addr(), SERVER_NAME, config).unwrap();
+
+    let reading = Telemetry {
+        sensor: "gw-01/0",
+        metric: "temperature",
+        value: 21.5,
+        timestamp_ms: 1_700_000_000_000,
+    };
+    assert_eq!(client.send(&reading).unwrap(), 1);
+    assert_eq!(client.send(&reading).unwrap(), 2);
+    client.close().unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn certificate_from_another_ca_is_a_certificate_error() {
+    let (dir, files) = pki("rogue");
+    let server = start_server(&files);
+
+    let error = connect_error(server.local_addr(), SERVER_NAME, &files.rogue_ca_cert);
+    assert!(
+        matches!(
+            error,
+            UplinkError::Certificate(CertificateError::UnknownIssuer)
+        ),
+        "{:?}",
+        error
+    );
+    assert!(!error.is_retryable());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn wrong_host_name_is_a_certificate_error() {
+    let (dir, files) = pki("hostname");
+    let server = start_server(&files);
+
+    let error = connect_error(server.local_addr(), "evil.example.com", &files.ca_cert);
+    assert!(matches!(error, UplinkError::Certificate(_)), "{:?}", error);
+    assert!(!error.is_retryable());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn refused_connection_is_an_io_error() {
+    let (dir, files) = pki("refused");
+    // Bind and release a port so nothing is listening on it
+    let closed = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let error = connect_error(closed, SERVER_NAME, &files.ca_cert);
+    assert!(matches!(error, UplinkError::Io(_)), "{:?}", error);
+    assert!(error.is_retryable());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn dropped_connection_is_an_io_error() {
+    let (dir, files) = pki("dropped");
+    // Accepts the TCP connection, then hangs up before any TLS
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = thread::spawn(move || drop(listener.accept().unwrap()));
+
+    let error = connect_error(addr, SERVER_NAME, &files.ca_cert);
+    server.join().unwrap();
+    assert!(matches!(error, UplinkError::Io(_)), "{:?}", error);
+    assert!(error.is_retryable());
+    std::fs::remove_dir_all(dir).unwrap();
+}
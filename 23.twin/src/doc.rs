@@ -0,0 +1,80 @@
+// JSON merge patches (RFC 7386)
+//
+// A patch is a document of the fields that changed; `null` deletes a field
+// and nested objects are merged recursively. Arrays and scalars are replaced
+// whole. It is what AWS and Azure device shadows send as "delta" documents.
+
+use serde_json::{Map, Value};
+
+// The patch that turns `from` into `to`
+pub fn diff(from: &Value, to: &Value) -> Value {
+    match (from, to) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut patch = Map::new();
+            for (key, old) in a {
+                match b.get(key) {
+                    None => {
+                        patch.insert(key.clone(), Value::Null);
+                    }
+                    Some(new) if new != old => {
+                        patch.insert(key.clone(), diff(old, new));
+                    }
+                    Some(_) => {}
+                }
+            }
+            for (key, new) in b {
+                if !a.contains_key(key) {
+                    patch.insert(key.clone(), new.clone());
+                }
+            }
+            Value::Object(patch)
+        }
+        _ => to.clone(),
+    }
+}
+
+pub fn merge(target: &mut Value, patch: &Value) {
+    let Value::Object(fields) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let object = target.as_object_mut().unwrap();
+    for (key, value) in fields {
+        if value.is_null() {
+            object.remove(key);
+        } else {
+            merge(object.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+// True if the patch changes nothing
+pub fn is_empty(patch: &Value) -> bool {
+    patch.as_object().is_some_and(|fields| fields.is_empty())
+}
+
+// Fields of `desired` that `reported` does not (yet) match; nested objects
+// are compared field by field, and fields only in `reported` are ignored
+pub fn delta(desired: &Value, reported: &Value) -> Value {
+    let mut out = Map::new();
+    if let Value::Object(fields) = desired {
+        for (key, want) in fields {
+            match (want, reported.get(key)) {
+                (Value::Object(_), Some(have @ Value::Object(_))) => {
+                    let inner = delta(want, have);
+                    if !is_empty(&inner) {
+                        out.insert(key.clone(), inner);
+                    }
+                }
+                (want, Some(have)) if want == have => {}
+                (want, _) => {
+                    out.insert(key.clone(), want.clone());
+                }
+            }
+        }
+    }
+    Value::Object(out)
+}
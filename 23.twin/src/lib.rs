@@ -0,0 +1,14 @@
+// Device twin: desired vs reported state
+//
+// The cloud keeps two documents per device. Operators write `desired`
+// ("sample every 5 s"), the device writes `reported` ("I am sampling every
+// 5 s"), and the difference between them is work still to be done. This is
+// the model behind AWS IoT device shadows and Azure IoT Hub device twins.
+
+pub mod doc;
+pub mod shadow;
+pub mod twin;
+
+pub use doc::{delta, diff, merge};
+pub use shadow::{DesiredUpdate, Shadow, VersionConflict};
+pub use twin::{Handler, Report, Twin, TwinError};
@@ -0,0 +1,136 @@
+use serde_json::{json, Value};
+use std::cell::Cell;
+use std::rc::Rc;
+use twin::{delta, diff, DesiredUpdate, Shadow, Twin, TwinError};
+
+// Send the twin's report to the shadow, rebasing once if another writer
+// updated the reported document in the meantime
+fn publish(twin: &mut Twin, shadow: &mut Shadow, patch: &Value) {
+    match shadow.update_reported(twin.reported_version(), patch) {
+        Ok(version) => {
+            twin.acknowledge(version);
+            println!("   reported {} -> version {}", patch, version);
+        }
+        Err(conflict) => {
+            println!("   conflict: {}", conflict);
+            let (remote, version) = shadow.reported();
+            let retry = twin.rebase(&remote.clone(), version);
+            let version = shadow
+                .update_reported(version, &retry)
+                .expect("rebased write");
+            twin.acknowledge(version);
+            println!("   rebased and re-sent {} -> version {}", retry, version);
+        }
+    }
+}
+
+fn apply(twin: &mut Twin, shadow: &mut Shadow, update: &DesiredUpdate) {
+    print!("   desired v{} {}: ", update.version, update.patch);
+    match twin.apply(update) {
+        Ok(report) => {
+            println!("applied");
+            for (field, reason) in &report.rejected {
+                println!("   rejected {}: {}", field, reason);
+            }
+            publish(twin, shadow, &report.patch);
+        }
+        Err(e @ TwinError::Stale { .. }) => println!("ignored ({})", e),
+        Err(e @ TwinError::Gap { .. }) => {
+            println!("{}; resyncing", e);
+            let (desired, version) = shadow.desired();
+            let report = twin.resync(&desired.clone(), version);
+            publish(twin, shadow, &report.patch);
+        }
+    }
+}
+
+fn main() {
+    println!("=== Device Twin ===\n");
+
+    // 1. Merge patches
+    println!("1. Diff and merge:");
+    let before = json!({"interval_s": 10, "led": "off", "net": {"apn": "iot", "roaming": false}});
+    let after = json!({"interval_s": 5, "net": {"apn": "iot", "roaming": true}});
+    let patch = diff(&before, &after);
+    println!("   before: {}", before);
+    println!("   after:  {}", after);
+    println!("   patch:  {}", patch);
+    let mut rebuilt = before.clone();
+    twin::merge(&mut rebuilt, &patch);
+    println!("   merge(before, patch) == after: {}", rebuilt == after);
+
+    // 2. Handlers
+    println!("\n2. Registering handlers:");
+    let interval = Rc::new(Cell::new(10u64));
+    let mut device = Twin::new();
+    let applied = Rc::clone(&interval);
+    device.on("interval_s", move |v| {
+        // The hardware cannot sample faster than once a second
+        let want = v.as_u64().ok_or("expected a number of seconds")?;
+        let actual = want.clamp(1, 3600);
+        applied.set(actual);
+        Ok(json!(actual))
+    });
+    device.on("led", |v| match v.as_str() {
+        Some("on") | Some("off") | Some("blink") => Ok(v.clone()),
+        _ => Err(format!("unsupported led mode {}", v)),
+    });
+    device.on("net", |v| Ok(v.clone()));
+    println!("   handlers: interval_s, led, net");
+
+    // 3. Normal flow
+    println!("\n3. In-order updates:");
+    let mut shadow = Shadow::new();
+    let u1 = shadow
+        .set_desired(&json!({"interval_s": 5, "led": "on"}))
+        .unwrap();
+    apply(&mut device, &mut shadow, &u1);
+    let u2 = shadow
+        .set_desired(&json!({"interval_s": 0, "led": "rainbow"}))
+        .unwrap();
+    apply(&mut device, &mut shadow, &u2);
+    println!("   sampling interval now {} s", interval.get());
+    println!("   outstanding delta: {}", shadow.delta());
+    println!(
+        "   no-op desired change: {:?}",
+        shadow.set_desired(&json!({"interval_s": 0}))
+    );
+
+    // 4. Out-of-order and duplicate delivery
+    println!("\n4. Out-of-order delivery:");
+    let u3 = shadow.set_desired(&json!({"led": "blink"})).unwrap();
+    let u4 = shadow
+        .set_desired(&json!({"net": {"apn": "iot", "roaming": false}}))
+        .unwrap();
+    let u5 = shadow.set_desired(&json!({"interval_s": 30})).unwrap();
+    apply(&mut device, &mut shadow, &u3);
+    apply(&mut device, &mut shadow, &u5); // u4 was delayed
+    apply(&mut device, &mut shadow, &u4); // and arrives late
+    apply(&mut device, &mut shadow, &u5); // duplicate
+    println!("   device desired version {}", device.desired_version());
+
+    // 5. Concurrent reported writes
+    println!("\n5. Reported-state conflict:");
+    let (_, seen) = shadow.reported();
+    shadow
+        .update_reported(seen, &json!({"location": "rack 4"}))
+        .unwrap();
+    println!("   another writer set location at version {}", seen + 1);
+    let local = device.report_local(&json!({"led": "off"}));
+    println!("   button press on the device: {}", local);
+    publish(&mut device, &mut shadow, &local);
+
+    // 6. Final state
+    println!("\n6. Final documents:");
+    let (desired, dv) = shadow.desired();
+    let (reported, rv) = shadow.reported();
+    println!("   desired  v{}: {}", dv, desired);
+    println!("   reported v{}: {}", rv, reported);
+    println!("   delta: {}", delta(desired, reported));
+    println!(
+        "   device and shadow agree: {}",
+        device.reported() == reported
+    );
+
+    println!("\n=== End of Device Twin Examples ===");
+}
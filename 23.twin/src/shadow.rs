@@ -0,0 +1,105 @@
+// The cloud side of the twin
+//
+// Both documents carry a version that increases on every change. Desired
+// updates are pushed to the device as numbered patches; reported updates use
+// optimistic concurrency: the writer names the version it last saw and the
+// write is refused if somebody else got there first.
+
+use crate::doc::{diff, is_empty, merge};
+use serde_json::{json, Value};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesiredUpdate {
+    pub version: u64,
+    pub patch: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionConflict {
+    pub expected: u64,
+    pub actual: u64,
+}
+
+impl fmt::Display for VersionConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected reported version {}, shadow is at {}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for VersionConflict {}
+
+#[derive(Debug)]
+pub struct Shadow {
+    desired: Value,
+    desired_version: u64,
+    reported: Value,
+    reported_version: u64,
+}
+
+impl Default for Shadow {
+    fn default() -> Self {
+        Shadow::new()
+    }
+}
+
+impl Shadow {
+    pub fn new() -> Shadow {
+        Shadow {
+            desired: json!({}),
+            desired_version: 0,
+            reported: json!({}),
+            reported_version: 0,
+        }
+    }
+
+    pub fn desired(&self) -> (&Value, u64) {
+        (&self.desired, self.desired_version)
+    }
+
+    pub fn reported(&self) -> (&Value, u64) {
+        (&self.reported, self.reported_version)
+    }
+
+    // An operator changes the desired state; returns the patch to push, or
+    // None if nothing actually changed
+    pub fn set_desired(&mut self, patch: &Value) -> Option<DesiredUpdate> {
+        let mut next = self.desired.clone();
+        merge(&mut next, patch);
+        let effective = diff(&self.desired, &next);
+        if is_empty(&effective) {
+            return None;
+        }
+        self.desired = next;
+        self.desired_version += 1;
+        Some(DesiredUpdate {
+            version: self.desired_version,
+            patch: effective,
+        })
+    }
+
+    pub fn update_reported(
+        &mut self,
+        expected: u64,
+        patch: &Value,
+    ) -> Result<u64, VersionConflict> {
+        if expected != self.reported_version {
+            return Err(VersionConflict {
+                expected,
+                actual: self.reported_version,
+            });
+        }
+        merge(&mut self.reported, patch);
+        self.reported_version += 1;
+        Ok(self.reported_version)
+    }
+
+    // What the device still has to do
+    pub fn delta(&self) -> Value {
+        crate::doc::delta(&self.desired, &self.reported)
+    }
+}
@@ -0,0 +1,184 @@
+// The device side of the twin
+//
+// The device keeps a copy of the desired document and its version, applies
+// each incoming patch through the handler registered for that field, and
+// builds the reported patch from what the handlers actually achieved.
+
+use crate::doc::{diff, merge};
+use crate::shadow::DesiredUpdate;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+
+// Receives the new desired value (Null if removed) and returns the value to
+// report, which may differ from the request (e.g. clamped to a valid range)
+pub type Handler = Box<dyn FnMut(&Value) -> Result<Value, String>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TwinError {
+    // Already applied; a duplicate or late delivery
+    Stale { version: u64, current: u64 },
+    // Updates were missed; fetch the full desired document and `resync`
+    Gap { expected: u64, got: u64 },
+}
+
+impl fmt::Display for TwinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TwinError::Stale { version, current } => {
+                write!(
+                    f,
+                    "desired version {} is not newer than {}",
+                    version, current
+                )
+            }
+            TwinError::Gap { expected, got } => {
+                write!(f, "expected desired version {}, got {}", expected, got)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TwinError {}
+
+// The result of applying desired changes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub patch: Value,
+    pub rejected: Vec<(String, String)>,
+}
+
+pub struct Twin {
+    desired: Value,
+    desired_version: u64,
+    reported: Value,
+    // Shadow's reported version as of our last successful write
+    reported_version: u64,
+    handlers: BTreeMap<String, Handler>,
+}
+
+impl Default for Twin {
+    fn default() -> Self {
+        Twin::new()
+    }
+}
+
+impl Twin {
+    pub fn new() -> Twin {
+        Twin {
+            desired: json!({}),
+            desired_version: 0,
+            reported: json!({}),
+            reported_version: 0,
+            handlers: BTreeMap::new(),
+        }
+    }
+
+    pub fn on<F>(&mut self, field: &str, handler: F)
+    where
+        F: FnMut(&Value) -> Result<Value, String> + 'static,
+    {
+        self.handlers.insert(field.to_string(), Box::new(handler));
+    }
+
+    pub fn desired_version(&self) -> u64 {
+        self.desired_version
+    }
+
+    pub fn reported_version(&self) -> u64 {
+        self.reported_version
+    }
+
+    pub fn reported(&self) -> &Value {
+        &self.reported
+    }
+
+    // Apply the next desired patch; patches must arrive in version order
+    pub fn apply(&mut self, update: &DesiredUpdate) -> Result<Report, TwinError> {
+        if update.version <= self.desired_version {
+            return Err(TwinError::Stale {
+                version: update.version,
+                current: self.desired_version,
+            });
+        }
+        if update.version != self.desired_version + 1 {
+            return Err(TwinError::Gap {
+                expected: self.desired_version + 1,
+                got: update.version,
+            });
+        }
+        merge(&mut self.desired, &update.patch);
+        self.desired_version = update.version;
+        Ok(self.dispatch(&update.patch))
+    }
+
+    // Replace the desired document wholesale after a gap; only the fields
+    // that really changed reach the handlers
+    pub fn resync(&mut self, desired: &Value, version: u64) -> Report {
+        let patch = diff(&self.desired, desired);
+        self.desired = desired.clone();
+        self.desired_version = version;
+        self.dispatch(&patch)
+    }
+
+    // A change made on the device itself, e.g. from a local button
+    pub fn report_local(&mut self, patch: &Value) -> Value {
+        merge(&mut self.reported, patch);
+        patch.clone()
+    }
+
+    // The shadow accepted our write and is now at `version`
+    pub fn acknowledge(&mut self, version: u64) {
+        self.reported_version = version;
+    }
+
+    // Our write lost a race: adopt the shadow's document, then return the
+    // patch that re-applies our own fields on top of it
+    pub fn rebase(&mut self, remote: &Value, version: u64) -> Value {
+        let mut patch = Map::new();
+        if let Value::Object(local) = &self.reported {
+            for (key, value) in local {
+                if remote.get(key) != Some(value) {
+                    patch.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        let patch = Value::Object(patch);
+        let mut merged = remote.clone();
+        merge(&mut merged, &patch);
+        self.reported = merged;
+        self.reported_version = version;
+        patch
+    }
+
+    fn dispatch(&mut self, patch: &Value) -> Report {
+        let mut report = Map::new();
+        let mut rejected = Vec::new();
+        let Value::Object(fields) = patch else {
+            return Report {
+                patch: Value::Object(report),
+                rejected,
+            };
+        };
+
+        // Handlers see the complete new value of their field, even when the
+        // patch only touched part of a nested object
+        for field in fields.keys() {
+            let target = self.desired.get(field).unwrap_or(&Value::Null);
+            let Some(handler) = self.handlers.get_mut(field) else {
+                rejected.push((field.clone(), "no handler".to_string()));
+                continue;
+            };
+            match handler(target) {
+                Ok(actual) => {
+                    report.insert(field.clone(), actual);
+                }
+                Err(reason) => rejected.push((field.clone(), reason)),
+            }
+        }
+
+        let patch = Value::Object(report);
+        merge(&mut self.reported, &patch);
+        Report { patch, rejected }
+    }
+}
@@ -0,0 +1,134 @@
+use serde_json::{json, Map, Value};
+use twin::{delta, diff, merge};
+
+#[test]
+fn diff_deletes_changes_and_adds() {
+    let before = json!({"interval_s": 10, "led": "off", "net": {"apn": "iot", "roaming": false}});
+    let after = json!({"interval_s": 5, "net": {"apn": "iot", "roaming": true}, "fw": "1.2"});
+    let patch = diff(&before, &after);
+    assert_eq!(
+        patch,
+        json!({"interval_s": 5, "led": null, "net": {"roaming": true}, "fw": "1.2"})
+    );
+    let mut rebuilt = before.clone();
+    merge(&mut rebuilt, &patch);
+    assert_eq!(rebuilt, after);
+}
+
+#[test]
+fn equal_documents_diff_to_an_empty_patch() {
+    let doc = json!({"a": 1, "b": {"c": [1, 2]}});
+    assert_eq!(diff(&doc, &doc), json!({}));
+}
+
+#[test]
+fn arrays_and_scalars_are_replaced_whole() {
+    assert_eq!(
+        diff(&json!({"list": [1, 2, 3]}), &json!({"list": [1, 2]})),
+        json!({"list": [1, 2]})
+    );
+    assert_eq!(diff(&json!(1), &json!({"a": 1})), json!({"a": 1}));
+
+    let mut target = json!({"a": {"b": 1}});
+    merge(&mut target, &json!({"a": 7}));
+    assert_eq!(target, json!({"a": 7}));
+    // A non-object patch replaces the whole document
+    merge(&mut target, &json!([1]));
+    assert_eq!(target, json!([1]));
+}
+
+#[test]
+fn merge_follows_rfc_7386_examples() {
+    let cases = [
+        (json!({"a": "b"}), json!({"a": "c"}), json!({"a": "c"})),
+        (
+            json!({"a": "b"}),
+            json!({"b": "c"}),
+            json!({"a": "b", "b": "c"}),
+        ),
+        (json!({"a": "b"}), json!({"a": null}), json!({})),
+        (
+            json!({"a": "b", "b": "c"}),
+            json!({"a": null}),
+            json!({"b": "c"}),
+        ),
+        (json!({"a": ["b"]}), json!({"a": "c"}), json!({"a": "c"})),
+        (
+            json!({"a": {"b": "c"}}),
+            json!({"a": {"b": "d", "c": null}}),
+            json!({"a": {"b": "d"}}),
+        ),
+        (
+            json!({"e": null}),
+            json!({"a": 1}),
+            json!({"e": null, "a": 1}),
+        ),
+        (
+            json!([1, 2]),
+            json!({"a": "b", "c": null}),
+            json!({"a": "b"}),
+        ),
+        (
+            json!({}),
+            json!({"a": {"bb": {"ccc": null}}}),
+            json!({"a": {"bb": {}}}),
+        ),
+    ];
+    for (target, patch, expected) in cases {
+        let mut doc = target.clone();
+        merge(&mut doc, &patch);
+        assert_eq!(doc, expected, "{} + {}", target, patch);
+    }
+}
+
+#[test]
+fn delta_lists_only_unmet_desired_fields() {
+    let desired = json!({"a": 1, "b": 2, "net": {"apn": "iot", "roaming": true}});
+    let reported = json!({"a": 1, "b": 3, "net": {"apn": "iot", "roaming": false}, "extra": 5});
+    assert_eq!(
+        delta(&desired, &reported),
+        json!({"b": 2, "net": {"roaming": true}})
+    );
+    // A nested object the device hasn't reported is wanted whole
+    assert_eq!(
+        delta(&desired, &json!({"a": 1, "b": 2})),
+        json!({"net": {"apn": "iot", "roaming": true}})
+    );
+    assert_eq!(delta(&desired, &desired), json!({}));
+}
+
+fn next(state: &mut u64) -> u8 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    (*state >> 56) as u8
+}
+
+// A small document of nested objects, numbers, strings and arrays
+fn random_doc(state: &mut u64, depth: u32) -> Value {
+    let mut fields = Map::new();
+    for _ in 0..next(state) % 5 {
+        let key = ["a", "b", "c", "d", "e"][next(state) as usize % 5].to_string();
+        let value = match next(state) % 4 {
+            0 if depth < 3 => random_doc(state, depth + 1),
+            1 => json!(["x", "y"][..next(state) as usize % 3]),
+            2 => json!(format!("s{}", next(state) % 3)),
+            _ => json!(next(state) % 3),
+        };
+        fields.insert(key, value);
+    }
+    Value::Object(fields)
+}
+
+#[test]
+fn merging_a_diff_reproduces_the_target() {
+    let mut state = 0x7A11_u64;
+    for _ in 0..2_000 {
+        let from = random_doc(&mut state, 0);
+        let to = random_doc(&mut state, 0);
+        let mut doc = from.clone();
+        merge(&mut doc, &diff(&from, &to));
+        assert_eq!(doc, to, "from {} to {}", from, to);
+        assert_eq!(delta(&to, &doc), json!({}));
+    }
+}
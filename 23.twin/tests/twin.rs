@@ -0,0 +1,229 @@
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::rc::Rc;
+use twin::{DesiredUpdate, Shadow, Twin, TwinError, VersionConflict};
+
+// Interval clamped to 1..=3600 s, three LED modes, network settings as given
+fn device() -> Twin {
+    let mut twin = Twin::new();
+    twin.on("interval_s", |v| {
+        let want = v.as_u64().ok_or("expected a number of seconds")?;
+        Ok(json!(want.clamp(1, 3600)))
+    });
+    twin.on("led", |v| match v.as_str() {
+        Some("on") | Some("off") | Some("blink") => Ok(v.clone()),
+        _ => Err(format!("unsupported led mode {}", v)),
+    });
+    twin.on("net", |v| Ok(v.clone()));
+    twin
+}
+
+// Each value the handler was called with
+fn recording(twin: &mut Twin, field: &str) -> Rc<RefCell<Vec<Value>>> {
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let seen = Rc::clone(&calls);
+    twin.on(field, move |v| {
+        seen.borrow_mut().push(v.clone());
+        Ok(v.clone())
+    });
+    calls
+}
+
+fn update(version: u64, patch: Value) -> DesiredUpdate {
+    DesiredUpdate { version, patch }
+}
+
+#[test]
+fn handlers_report_what_they_achieved() {
+    let mut twin = device();
+    let report = twin
+        .apply(&update(1, json!({"interval_s": 5, "led": "on"})))
+        .unwrap();
+    assert_eq!(report.patch, json!({"interval_s": 5, "led": "on"}));
+    assert!(report.rejected.is_empty());
+
+    let report = twin
+        .apply(&update(
+            2,
+            json!({"interval_s": 0, "led": "rainbow", "fan": 1}),
+        ))
+        .unwrap();
+    // Clamped, not rejected; unknown fields and bad values are rejected
+    assert_eq!(report.patch, json!({"interval_s": 1}));
+    assert_eq!(
+        report.rejected,
+        [
+            ("fan".to_string(), "no handler".to_string()),
+            (
+                "led".to_string(),
+                "unsupported led mode \"rainbow\"".to_string()
+            ),
+        ]
+    );
+    assert_eq!(twin.reported(), &json!({"interval_s": 1, "led": "on"}));
+    assert_eq!(twin.desired_version(), 2);
+}
+
+#[test]
+fn duplicates_are_stale_and_gaps_are_reported() {
+    let mut twin = device();
+    twin.apply(&update(1, json!({"led": "on"}))).unwrap();
+    assert_eq!(
+        twin.apply(&update(1, json!({"led": "off"}))),
+        Err(TwinError::Stale {
+            version: 1,
+            current: 1
+        })
+    );
+    assert_eq!(
+        twin.apply(&update(3, json!({"led": "off"}))),
+        Err(TwinError::Gap {
+            expected: 2,
+            got: 3
+        })
+    );
+    // Neither changed anything
+    assert_eq!(twin.desired_version(), 1);
+    assert_eq!(twin.reported(), &json!({"led": "on"}));
+}
+
+#[test]
+fn handlers_see_the_whole_nested_value_and_removals() {
+    let mut twin = Twin::new();
+    let calls = recording(&mut twin, "net");
+    twin.apply(&update(1, json!({"net": {"apn": "iot", "roaming": false}})))
+        .unwrap();
+    twin.apply(&update(2, json!({"net": {"roaming": true}})))
+        .unwrap();
+    twin.apply(&update(3, json!({"net": null}))).unwrap();
+    assert_eq!(
+        *calls.borrow(),
+        [
+            json!({"apn": "iot", "roaming": false}),
+            json!({"apn": "iot", "roaming": true}),
+            Value::Null,
+        ]
+    );
+}
+
+#[test]
+fn resync_only_dispatches_changed_fields() {
+    let mut twin = Twin::new();
+    let led = recording(&mut twin, "led");
+    let interval = recording(&mut twin, "interval_s");
+    twin.apply(&update(1, json!({"led": "on", "interval_s": 5})))
+        .unwrap();
+
+    let report = twin.resync(&json!({"led": "on", "interval_s": 30}), 4);
+    assert_eq!(report.patch, json!({"interval_s": 30}));
+    assert_eq!(led.borrow().len(), 1);
+    assert_eq!(*interval.borrow(), [json!(5), json!(30)]);
+    assert_eq!(twin.desired_version(), 4);
+    // In order again from the resynced version
+    assert!(twin.apply(&update(5, json!({"led": "off"}))).is_ok());
+}
+
+#[test]
+fn shadow_versions_and_no_op_updates() {
+    let mut shadow = Shadow::new();
+    let first = shadow.set_desired(&json!({"interval_s": 5})).unwrap();
+    assert_eq!(first, update(1, json!({"interval_s": 5})));
+    assert_eq!(shadow.set_desired(&json!({"interval_s": 5})), None);
+    // The pushed patch holds only what changed
+    let second = shadow
+        .set_desired(&json!({"interval_s": 5, "led": "on"}))
+        .unwrap();
+    assert_eq!(second, update(2, json!({"led": "on"})));
+    assert_eq!(shadow.desired().1, 2);
+    assert_eq!(shadow.delta(), json!({"interval_s": 5, "led": "on"}));
+}
+
+#[test]
+fn reported_writes_need_the_current_version() {
+    let mut shadow = Shadow::new();
+    assert_eq!(shadow.update_reported(0, &json!({"a": 1})), Ok(1));
+    assert_eq!(
+        shadow.update_reported(0, &json!({"a": 2})),
+        Err(VersionConflict {
+            expected: 0,
+            actual: 1
+        })
+    );
+    assert_eq!(shadow.reported(), (&json!({"a": 1}), 1));
+}
+
+#[test]
+fn rebase_reapplies_local_fields_over_the_remote_document() {
+    let mut shadow = Shadow::new();
+    let mut twin = device();
+    let report = twin
+        .apply(
+            &shadow
+                .set_desired(&json!({"interval_s": 5, "led": "on"}))
+                .unwrap(),
+        )
+        .unwrap();
+    twin.acknowledge(shadow.update_reported(0, &report.patch).unwrap());
+
+    // Another writer gets in first, then the device changes the LED itself
+    shadow
+        .update_reported(1, &json!({"location": "rack 4"}))
+        .unwrap();
+    let local = twin.report_local(&json!({"led": "off"}));
+    let conflict = shadow
+        .update_reported(twin.reported_version(), &local)
+        .unwrap_err();
+    assert_eq!(
+        conflict,
+        VersionConflict {
+            expected: 1,
+            actual: 2
+        }
+    );
+
+    let (remote, version) = shadow.reported();
+    let retry = twin.rebase(&remote.clone(), version);
+    assert_eq!(retry, json!({"led": "off"}));
+    let version = shadow.update_reported(version, &retry).unwrap();
+    twin.acknowledge(version);
+
+    assert_eq!(
+        shadow.reported(),
+        (
+            &json!({"interval_s": 5, "led": "off", "location": "rack 4"}),
+            3
+        )
+    );
+    assert_eq!(twin.reported(), shadow.reported().0);
+    assert_eq!(twin.reported_version(), 3);
+    // The local change now differs from what the operator wants
+    assert_eq!(shadow.delta(), json!({"led": "on"}));
+}
+
+#[test]
+fn out_of_order_delivery_converges_after_resync() {
+    let mut shadow = Shadow::new();
+    let mut twin = device();
+    let u1 = shadow.set_desired(&json!({"led": "blink"})).unwrap();
+    let u2 = shadow
+        .set_desired(&json!({"net": {"apn": "iot", "roaming": false}}))
+        .unwrap();
+    let u3 = shadow.set_desired(&json!({"interval_s": 30})).unwrap();
+
+    let mut reported = Vec::new();
+    reported.push(twin.apply(&u1).unwrap().patch);
+    // u2 is delayed: u3 shows a gap, so fetch the whole document
+    assert!(matches!(twin.apply(&u3), Err(TwinError::Gap { .. })));
+    let (desired, version) = shadow.desired();
+    reported.push(twin.resync(&desired.clone(), version).patch);
+    // The late u2 and a duplicate u3 are both stale now
+    assert!(matches!(twin.apply(&u2), Err(TwinError::Stale { .. })));
+    assert!(matches!(twin.apply(&u3), Err(TwinError::Stale { .. })));
+
+    for patch in reported {
+        let version = shadow.update_reported(twin.reported_version(), &patch);
+        twin.acknowledge(version.unwrap());
+    }
+    assert_eq!(shadow.delta(), json!({}));
+    assert_eq!(twin.reported(), shadow.reported().0);
+}
@@ -0,0 +1,128 @@
+// Geofencing over GPS positions
+//
+// A `GeofenceMonitor` turns a stream of positions into enter/exit events,
+// with a hysteresis band so a receiver jittering along a boundary does not
+// flap. It can also publish each fence as rule-engine readings
+// (`geo/<fence>/inside`, `geo/<fence>/distance_m`) so distance and dwell
+// alerts are written as ordinary rules.
+
+pub mod shape;
+
+pub use shape::{haversine_m, Coord, Shape, EARTH_RADIUS_M};
+
+use rules::Reading;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Geofence {
+    pub name: String,
+    pub shape: Shape,
+    // Once inside, the position must be this far outside before an exit
+    pub hysteresis_m: f64,
+}
+
+impl Geofence {
+    pub fn new(name: &str, shape: Shape) -> Geofence {
+        Geofence {
+            name: name.to_string(),
+            shape,
+            hysteresis_m: 0.0,
+        }
+    }
+
+    pub fn with_hysteresis(mut self, metres: f64) -> Geofence {
+        self.hysteresis_m = metres;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeoEvent {
+    Entered { fence: String, at_ms: u64 },
+    Exited { fence: String, at_ms: u64 },
+}
+
+impl fmt::Display for GeoEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeoEvent::Entered { fence, at_ms } => write!(f, "[{} ms] ENTERED {}", at_ms, fence),
+            GeoEvent::Exited { fence, at_ms } => write!(f, "[{} ms] EXITED  {}", at_ms, fence),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GeofenceMonitor {
+    fences: Vec<Geofence>,
+    // None until the first position, so startup does not emit events
+    inside: Vec<Option<bool>>,
+    distances: Vec<f64>,
+}
+
+impl GeofenceMonitor {
+    pub fn new(fences: Vec<Geofence>) -> GeofenceMonitor {
+        let n = fences.len();
+        GeofenceMonitor {
+            fences,
+            inside: vec![None; n],
+            distances: vec![f64::NAN; n],
+        }
+    }
+
+    pub fn is_inside(&self, name: &str) -> bool {
+        self.fences
+            .iter()
+            .position(|f| f.name == name)
+            .and_then(|i| self.inside[i])
+            .unwrap_or(false)
+    }
+
+    pub fn update(&mut self, position: Coord, timestamp_ms: u64) -> Vec<GeoEvent> {
+        let mut events = Vec::new();
+        for (i, fence) in self.fences.iter().enumerate() {
+            let distance = fence.shape.signed_distance_m(position);
+            self.distances[i] = distance;
+
+            let now = match self.inside[i] {
+                // Enter on crossing the boundary, leave only past the band
+                Some(true) => distance <= fence.hysteresis_m,
+                Some(false) | None => distance <= 0.0,
+            };
+            match (self.inside[i], now) {
+                (Some(false), true) => events.push(GeoEvent::Entered {
+                    fence: fence.name.clone(),
+                    at_ms: timestamp_ms,
+                }),
+                (Some(true), false) => events.push(GeoEvent::Exited {
+                    fence: fence.name.clone(),
+                    at_ms: timestamp_ms,
+                }),
+                _ => {}
+            }
+            self.inside[i] = Some(now);
+        }
+        events
+    }
+
+    // Current state as readings for the rule engine
+    pub fn readings(&self, timestamp_ms: u64) -> Vec<Reading> {
+        let mut out = Vec::new();
+        for (i, fence) in self.fences.iter().enumerate() {
+            let Some(inside) = self.inside[i] else {
+                continue;
+            };
+            let inside = if inside { 1.0 } else { 0.0 };
+            out.push(Reading::new(
+                &format!("geo/{}/inside", fence.name),
+                inside,
+                timestamp_ms,
+            ));
+            out.push(Reading::new(
+                &format!("geo/{}/distance_m", fence.name),
+                self.distances[i],
+                timestamp_ms,
+            ));
+        }
+        out
+    }
+}
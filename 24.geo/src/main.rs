@@ -0,0 +1,184 @@
+use geo::{haversine_m, Coord, GeoEvent, Geofence, GeofenceMonitor, Shape};
+use gps::{parse_sentence, Sentence};
+use rules::{Condition, Rule, RuleEngine};
+
+fn check(shape: &Shape, name: &str, p: Coord) {
+    println!(
+        "   {:<22} inside={:<5} distance={:>9.2} m",
+        name,
+        shape.contains(p),
+        shape.signed_distance_m(p)
+    );
+}
+
+// Offset a coordinate by metres north/east (good enough for a demo track)
+fn offset(origin: Coord, north_m: f64, east_m: f64) -> Coord {
+    let k = geo::EARTH_RADIUS_M.to_radians();
+    Coord::new(
+        origin.lat + north_m / k,
+        origin.lon + east_m / (k * origin.lat.to_radians().cos()),
+    )
+}
+
+// Format a position as an RMC sentence, as a GPS receiver would send it
+fn rmc(p: Coord, second: u32) -> String {
+    let dm = |v: f64, width: usize| {
+        let deg = v.abs().trunc();
+        format!(
+            "{:0w$}{:07.4}",
+            deg as u32,
+            (v.abs() - deg) * 60.0,
+            w = width
+        )
+    };
+    let body = format!(
+        "GPRMC,1200{:02}.00,A,{},{},{},{},0.5,90.0,150326,,",
+        second,
+        dm(p.lat, 2),
+        if p.lat >= 0.0 { "N" } else { "S" },
+        dm(p.lon, 3),
+        if p.lon >= 0.0 { "E" } else { "W" },
+    );
+    format!("${}*{:02X}", body, gps::checksum(&body))
+}
+
+fn main() {
+    println!("=== Geofencing ===\n");
+
+    // 1. Distances
+    println!("1. Haversine distance:");
+    let paris = Coord::new(48.8566, 2.3522);
+    let london = Coord::new(51.5074, -0.1278);
+    println!(
+        "   Paris -> London:           {:>10.1} km",
+        haversine_m(paris, london) / 1000.0
+    );
+    let west = Coord::new(0.0, 179.9);
+    let east = Coord::new(0.0, -179.9);
+    println!(
+        "   179.9E -> 179.9W (equator): {:>9.1} km",
+        haversine_m(west, east) / 1000.0
+    );
+    println!(
+        "   pole to pole:              {:>10.1} km",
+        haversine_m(Coord::new(90.0, 0.0), Coord::new(-90.0, 0.0)) / 1000.0
+    );
+
+    // 2. Circular fences
+    println!("\n2. Circle (depot, 200 m radius):");
+    let depot_center = Coord::new(52.3700, 4.9000);
+    let depot = Shape::circle(depot_center, 200.0);
+    check(&depot, "centre", depot_center);
+    check(&depot, "150 m north", offset(depot_center, 150.0, 0.0));
+    check(&depot, "250 m east", offset(depot_center, 0.0, 250.0));
+
+    // 3. Polygon edge cases
+    println!("\n3. Polygons:");
+    let square = Shape::polygon(vec![
+        Coord::new(0.0, 0.0),
+        Coord::new(0.0, 0.01),
+        Coord::new(0.01, 0.01),
+        Coord::new(0.01, 0.0),
+    ]);
+    check(&square, "square: centre", Coord::new(0.005, 0.005));
+    check(&square, "square: on edge", Coord::new(0.0, 0.005));
+    check(&square, "square: on vertex", Coord::new(0.01, 0.01));
+    check(&square, "square: outside", Coord::new(0.02, 0.005));
+
+    // A ray cast from this point passes exactly through two vertices
+    let diamond = Shape::polygon(vec![
+        Coord::new(0.0, 0.01),
+        Coord::new(0.01, 0.02),
+        Coord::new(0.02, 0.01),
+        Coord::new(0.01, 0.0),
+    ]);
+    check(&diamond, "diamond: centre", Coord::new(0.01, 0.01));
+    check(&diamond, "diamond: ray via tips", Coord::new(0.01, -0.01));
+
+    // An L shape: the notch is inside the bounding box but outside the fence
+    let l_shape = Shape::polygon(vec![
+        Coord::new(0.0, 0.0),
+        Coord::new(0.0, 0.02),
+        Coord::new(0.01, 0.02),
+        Coord::new(0.01, 0.01),
+        Coord::new(0.02, 0.01),
+        Coord::new(0.02, 0.0),
+    ]);
+    check(&l_shape, "L: in the foot", Coord::new(0.005, 0.015));
+    check(&l_shape, "L: in the notch", Coord::new(0.015, 0.015));
+
+    // A fence straddling the antimeridian near Fiji
+    let dateline = Shape::polygon(vec![
+        Coord::new(-16.0, 179.5),
+        Coord::new(-16.0, -179.5),
+        Coord::new(-17.0, -179.5),
+        Coord::new(-17.0, 179.5),
+    ]);
+    check(&dateline, "dateline: 179.9E", Coord::new(-16.5, 179.9));
+    check(&dateline, "dateline: 179.9W", Coord::new(-16.5, -179.9));
+    check(&dateline, "dateline: 178.0E", Coord::new(-16.5, 178.0));
+
+    // 4. Hysteresis
+    println!("\n4. Jitter on the depot boundary:");
+    // Drive out of the depot, then park just outside its edge where the
+    // GPS error wobbles the fix back and forth across it
+    let track: Vec<f64> = vec![
+        0.0, 80.0, 160.0, 195.0, 203.0, 198.0, 206.0, 199.0, 204.0, 230.0, 260.0, 400.0,
+    ];
+    for hysteresis in [0.0, 15.0] {
+        let mut monitor = GeofenceMonitor::new(vec![
+            Geofence::new("depot", depot.clone()).with_hysteresis(hysteresis)
+        ]);
+        let events: Vec<GeoEvent> = track
+            .iter()
+            .enumerate()
+            .flat_map(|(i, m)| monitor.update(offset(depot_center, *m, 0.0), i as u64 * 1000))
+            .collect();
+        println!("   hysteresis {:>4} m: {} events", hysteresis, events.len());
+        for event in &events {
+            println!("      {}", event);
+        }
+    }
+
+    // 5. Positions from NMEA, alerts from the rule engine
+    println!("\n5. NMEA positions through the rule engine:");
+    let mut monitor = GeofenceMonitor::new(vec![
+        Geofence::new("depot", depot.clone()).with_hysteresis(15.0)
+    ]);
+    let mut engine = RuleEngine::new(vec![
+        Rule::new("left-depot", Condition::below("geo/depot/inside", 0.5)).for_at_least(3000),
+        Rule::new(
+            "far-from-depot",
+            Condition::above("geo/depot/distance_m", 500.0).with_hysteresis(100.0),
+        ),
+    ]);
+    for (i, north) in [
+        0.0, 150.0, 300.0, 450.0, 600.0, 750.0, 900.0, 600.0, 350.0, 100.0,
+    ]
+    .iter()
+    .enumerate()
+    {
+        let sentence = rmc(offset(depot_center, *north, 0.0), i as u32);
+        let Ok(Sentence::Rmc(fix)) = parse_sentence(&sentence) else {
+            continue;
+        };
+        let Some(position) = Coord::from_fix(fix.latitude, fix.longitude) else {
+            continue;
+        };
+        let at_ms = i as u64 * 1000;
+        for event in monitor.update(position, at_ms) {
+            println!("   {}", event);
+        }
+        for reading in monitor.readings(at_ms) {
+            for alert in engine.process(&reading) {
+                println!("   {}", alert);
+            }
+        }
+        if i == 0 {
+            println!("   first fix: {}", sentence);
+        }
+    }
+    println!("   active alerts: {:?}", engine.active());
+
+    println!("\n=== End of Geofencing Examples ===");
+}
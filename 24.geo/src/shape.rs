@@ -0,0 +1,129 @@
+// Coordinates, distances and fence shapes
+//
+// Distances use the haversine formula on a spherical Earth (error < 0.5%).
+// Polygon tests project the vertices into a flat local frame around the
+// point being tested, which is accurate for fences up to tens of kilometres
+// and handles polygons that straddle the antimeridian.
+
+pub const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+// Points closer than this to a polygon edge count as inside
+const EDGE_TOLERANCE_M: f64 = 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coord {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl Coord {
+    pub fn new(lat: f64, lon: f64) -> Coord {
+        Coord { lat, lon }
+    }
+
+    // From a parsed GGA/RMC fix, which may lack a position
+    pub fn from_fix(lat: Option<f64>, lon: Option<f64>) -> Option<Coord> {
+        Some(Coord::new(lat?, lon?))
+    }
+}
+
+pub fn haversine_m(a: Coord, b: Coord) -> f64 {
+    let (lat1, lat2) = (a.lat.to_radians(), b.lat.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (b.lon - a.lon).to_radians();
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * h.sqrt().min(1.0).asin()
+}
+
+// Longitude difference folded into -180..180, so 179.9 -> -179.9 is 0.2
+fn wrap_lon(delta: f64) -> f64 {
+    let d = (delta + 180.0).rem_euclid(360.0) - 180.0;
+    if d == -180.0 {
+        180.0
+    } else {
+        d
+    }
+}
+
+// Metres east/north of `origin`
+fn project(origin: Coord, p: Coord) -> (f64, f64) {
+    let k = EARTH_RADIUS_M * std::f64::consts::PI / 180.0;
+    let x = wrap_lon(p.lon - origin.lon) * k * origin.lat.to_radians().cos();
+    let y = (p.lat - origin.lat) * k;
+    (x, y)
+}
+
+fn segment_distance((ax, ay): (f64, f64), (bx, by): (f64, f64)) -> f64 {
+    // Distance from the origin to segment a-b
+    let (dx, dy) = (bx - ax, by - ay);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 == 0.0 {
+        0.0
+    } else {
+        (-(ax * dx + ay * dy) / len2).clamp(0.0, 1.0)
+    };
+    (ax + t * dx).hypot(ay + t * dy)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Circle { center: Coord, radius_m: f64 },
+    // Vertices in order; the closing edge back to the first is implied
+    Polygon(Vec<Coord>),
+}
+
+impl Shape {
+    pub fn circle(center: Coord, radius_m: f64) -> Shape {
+        Shape::Circle { center, radius_m }
+    }
+
+    pub fn polygon(vertices: Vec<Coord>) -> Shape {
+        Shape::Polygon(vertices)
+    }
+
+    pub fn contains(&self, p: Coord) -> bool {
+        self.signed_distance_m(p) <= 0.0
+    }
+
+    // Distance to the boundary: negative inside, positive outside
+    pub fn signed_distance_m(&self, p: Coord) -> f64 {
+        match self {
+            Shape::Circle { center, radius_m } => haversine_m(*center, p) - radius_m,
+            Shape::Polygon(vertices) => {
+                if vertices.len() < 3 {
+                    return f64::INFINITY;
+                }
+                let pts: Vec<(f64, f64)> = vertices.iter().map(|v| project(p, *v)).collect();
+                let edge = pts
+                    .iter()
+                    .zip(pts.iter().cycle().skip(1))
+                    .map(|(a, b)| segment_distance(*a, *b))
+                    .fold(f64::INFINITY, f64::min);
+                if edge <= EDGE_TOLERANCE_M {
+                    return 0.0;
+                }
+                if ray_cast(&pts) {
+                    -edge
+                } else {
+                    edge
+                }
+            }
+        }
+    }
+}
+
+// Even-odd rule: cast a ray from the origin towards +x and count crossings
+fn ray_cast(pts: &[(f64, f64)]) -> bool {
+    let mut inside = false;
+    for (a, b) in pts.iter().zip(pts.iter().cycle().skip(1)) {
+        let ((ax, ay), (bx, by)) = (*a, *b);
+        // Half-open test so a ray through a vertex is counted once
+        if (ay > 0.0) != (by > 0.0) {
+            let x = ax + (0.0 - ay) * (bx - ax) / (by - ay);
+            if x > 0.0 {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
@@ -0,0 +1,169 @@
+use geo::{Coord, GeoEvent, Geofence, GeofenceMonitor, Shape, EARTH_RADIUS_M};
+use gps::{parse_sentence, Sentence};
+use rules::{AlertEvent, Condition, Rule, RuleEngine};
+
+const DEPOT: Coord = Coord {
+    lat: 52.37,
+    lon: 4.9,
+};
+
+fn north_of_depot(metres: f64) -> Coord {
+    Coord::new(DEPOT.lat + metres / EARTH_RADIUS_M.to_radians(), DEPOT.lon)
+}
+
+fn monitor(hysteresis_m: f64) -> GeofenceMonitor {
+    GeofenceMonitor::new(vec![
+        Geofence::new("depot", Shape::circle(DEPOT, 200.0)).with_hysteresis(hysteresis_m)
+    ])
+}
+
+fn entered(at_ms: u64) -> GeoEvent {
+    GeoEvent::Entered {
+        fence: "depot".to_string(),
+        at_ms,
+    }
+}
+
+fn exited(at_ms: u64) -> GeoEvent {
+    GeoEvent::Exited {
+        fence: "depot".to_string(),
+        at_ms,
+    }
+}
+
+// Out of the depot, then parked where GPS jitter crosses the 200 m edge
+const TRACK: [f64; 12] = [
+    0.0, 80.0, 160.0, 195.0, 203.0, 198.0, 206.0, 199.0, 204.0, 230.0, 260.0, 400.0,
+];
+
+fn drive(monitor: &mut GeofenceMonitor, track: &[f64]) -> Vec<GeoEvent> {
+    track
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &m)| monitor.update(north_of_depot(m), i as u64 * 1000))
+        .collect()
+}
+
+#[test]
+fn jitter_flaps_without_hysteresis() {
+    assert_eq!(
+        drive(&mut monitor(0.0), &TRACK),
+        [
+            exited(4000),
+            entered(5000),
+            exited(6000),
+            entered(7000),
+            exited(8000)
+        ]
+    );
+}
+
+#[test]
+fn hysteresis_gives_a_single_exit() {
+    let mut monitor = monitor(15.0);
+    assert_eq!(drive(&mut monitor, &TRACK), [exited(9000)]);
+    assert!(!monitor.is_inside("depot"));
+}
+
+#[test]
+fn entering_needs_the_boundary_itself() {
+    // With a band, re-entering still happens at 200 m, not 215 m
+    let mut monitor = monitor(15.0);
+    assert_eq!(drive(&mut monitor, &[400.0, 210.0, 199.0]), [entered(2000)]);
+}
+
+#[test]
+fn first_position_sets_state_without_events() {
+    let mut inside = monitor(0.0);
+    assert!(inside.update(DEPOT, 0).is_empty());
+    assert!(inside.is_inside("depot"));
+    let mut outside = monitor(0.0);
+    assert!(outside.update(north_of_depot(1000.0), 0).is_empty());
+    assert!(!outside.is_inside("depot"));
+    assert!(!outside.is_inside("unknown"));
+}
+
+#[test]
+fn readings_appear_after_the_first_position() {
+    let mut monitor = monitor(0.0);
+    assert!(monitor.readings(0).is_empty());
+    monitor.update(north_of_depot(150.0), 0);
+    let readings = monitor.readings(1000);
+    assert_eq!(readings.len(), 2);
+    assert_eq!(readings[0].metric, "geo/depot/inside");
+    assert_eq!(readings[0].value, 1.0);
+    assert_eq!(readings[1].metric, "geo/depot/distance_m");
+    assert!((readings[1].value + 50.0).abs() < 1e-6);
+    assert_eq!(readings[1].timestamp_ms, 1000);
+}
+
+// An RMC sentence for `p`, as a receiver would send it
+fn rmc(p: Coord, second: u32) -> String {
+    let dm = |v: f64, width: usize| {
+        let deg = v.abs().trunc();
+        format!(
+            "{:0w$}{:07.4}",
+            deg as u32,
+            (v.abs() - deg) * 60.0,
+            w = width
+        )
+    };
+    let body = format!(
+        "GPRMC,1200{:02}.00,A,{},N,{},E,0.5,90.0,150326,,",
+        second,
+        dm(p.lat, 2),
+        dm(p.lon, 3),
+    );
+    format!("${}*{:02X}", body, gps::checksum(&body))
+}
+
+#[test]
+fn nmea_positions_drive_rules() {
+    let mut monitor = monitor(15.0);
+    let mut engine = RuleEngine::new(vec![
+        Rule::new("left-depot", Condition::below("geo/depot/inside", 0.5)).for_at_least(3000),
+        Rule::new(
+            "far-from-depot",
+            Condition::above("geo/depot/distance_m", 500.0),
+        ),
+    ]);
+
+    let track = [
+        0.0, 150.0, 300.0, 450.0, 600.0, 750.0, 900.0, 600.0, 350.0, 100.0,
+    ];
+    let mut geo_events = Vec::new();
+    let mut alerts = Vec::new();
+    for (i, &north) in track.iter().enumerate() {
+        let Ok(Sentence::Rmc(fix)) = parse_sentence(&rmc(north_of_depot(north), i as u32)) else {
+            panic!("RMC did not parse");
+        };
+        let position = Coord::from_fix(fix.latitude, fix.longitude).unwrap();
+        let at_ms = i as u64 * 1000;
+        geo_events.extend(monitor.update(position, at_ms));
+        for reading in monitor.readings(at_ms) {
+            alerts.extend(engine.process(&reading));
+        }
+    }
+
+    assert_eq!(geo_events, [exited(2000), entered(9000)]);
+    let alert = |raised: bool, rule: &str, at_ms| {
+        let rule = rule.to_string();
+        if raised {
+            AlertEvent::Raised { rule, at_ms }
+        } else {
+            AlertEvent::Cleared { rule, at_ms }
+        }
+    };
+    assert_eq!(
+        alerts,
+        [
+            // Outside since 2 s, held for 3 s
+            alert(true, "left-depot", 5000),
+            // 550 m from the edge at 750 m north, back to 400 m at 7 s
+            alert(true, "far-from-depot", 5000),
+            alert(false, "far-from-depot", 7000),
+            alert(false, "left-depot", 9000),
+        ]
+    );
+    assert!(engine.active().is_empty());
+}
@@ -0,0 +1,131 @@
+use geo::{haversine_m, Coord, Shape, EARTH_RADIUS_M};
+
+// Metres north/east of `origin` on the same sphere as `haversine_m`
+fn offset(origin: Coord, north_m: f64, east_m: f64) -> Coord {
+    let k = EARTH_RADIUS_M.to_radians();
+    Coord::new(
+        origin.lat + north_m / k,
+        origin.lon + east_m / (k * origin.lat.to_radians().cos()),
+    )
+}
+
+fn square() -> Shape {
+    Shape::polygon(vec![
+        Coord::new(0.0, 0.0),
+        Coord::new(0.0, 0.01),
+        Coord::new(0.01, 0.01),
+        Coord::new(0.01, 0.0),
+    ])
+}
+
+#[test]
+fn haversine_distances() {
+    let paris = Coord::new(48.8566, 2.3522);
+    let london = Coord::new(51.5074, -0.1278);
+    assert!((haversine_m(paris, london) - 343_500.0).abs() < 1_000.0);
+    assert_eq!(haversine_m(paris, paris), 0.0);
+
+    // Across the antimeridian: 0.2 degrees, not 359.8
+    let across = haversine_m(Coord::new(0.0, 179.9), Coord::new(0.0, -179.9));
+    assert!((across - EARTH_RADIUS_M * 0.2f64.to_radians()).abs() < 1e-3);
+
+    let poles = haversine_m(Coord::new(90.0, 0.0), Coord::new(-90.0, 0.0));
+    assert!((poles - EARTH_RADIUS_M * std::f64::consts::PI).abs() < 1e-3);
+}
+
+#[test]
+fn circle_signed_distance() {
+    let center = Coord::new(52.37, 4.9);
+    let depot = Shape::circle(center, 200.0);
+    assert_eq!(depot.signed_distance_m(center), -200.0);
+    let north = depot.signed_distance_m(offset(center, 150.0, 0.0));
+    assert!((north + 50.0).abs() < 1e-6, "{}", north);
+    let east = depot.signed_distance_m(offset(center, 0.0, 250.0));
+    assert!((east - 50.0).abs() < 0.1, "{}", east);
+    assert!(depot.contains(offset(center, 200.0 - 1e-3, 0.0)));
+    assert!(!depot.contains(offset(center, 200.0 + 1e-3, 0.0)));
+}
+
+#[test]
+fn square_inside_edge_vertex_outside() {
+    let square = square();
+    let centre = square.signed_distance_m(Coord::new(0.005, 0.005));
+    // Half the side, about 556 m, from every edge
+    assert!((centre + 556.0).abs() < 1.0, "{}", centre);
+    assert_eq!(square.signed_distance_m(Coord::new(0.0, 0.005)), 0.0);
+    assert_eq!(square.signed_distance_m(Coord::new(0.01, 0.01)), 0.0);
+    let outside = square.signed_distance_m(Coord::new(0.02, 0.005));
+    assert!((outside - 1112.0).abs() < 1.0, "{}", outside);
+}
+
+#[test]
+fn ray_through_vertices_counts_once() {
+    let diamond = Shape::polygon(vec![
+        Coord::new(0.0, 0.01),
+        Coord::new(0.01, 0.02),
+        Coord::new(0.02, 0.01),
+        Coord::new(0.01, 0.0),
+    ]);
+    assert!(diamond.contains(Coord::new(0.01, 0.01)));
+    assert!(!diamond.contains(Coord::new(0.01, -0.01)));
+    assert!(!diamond.contains(Coord::new(0.01, 0.03)));
+}
+
+#[test]
+fn concave_notch_is_outside() {
+    let l_shape = Shape::polygon(vec![
+        Coord::new(0.0, 0.0),
+        Coord::new(0.0, 0.02),
+        Coord::new(0.01, 0.02),
+        Coord::new(0.01, 0.01),
+        Coord::new(0.02, 0.01),
+        Coord::new(0.02, 0.0),
+    ]);
+    assert!(l_shape.contains(Coord::new(0.005, 0.015)));
+    assert!(l_shape.contains(Coord::new(0.015, 0.005)));
+    assert!(!l_shape.contains(Coord::new(0.015, 0.015)));
+}
+
+#[test]
+fn polygon_across_the_antimeridian() {
+    let fiji = Shape::polygon(vec![
+        Coord::new(-16.0, 179.5),
+        Coord::new(-16.0, -179.5),
+        Coord::new(-17.0, -179.5),
+        Coord::new(-17.0, 179.5),
+    ]);
+    assert!(fiji.contains(Coord::new(-16.5, 179.9)));
+    assert!(fiji.contains(Coord::new(-16.5, -179.9)));
+    assert!(fiji.contains(Coord::new(-16.5, 180.0)));
+    assert!(!fiji.contains(Coord::new(-16.5, 178.0)));
+    assert!(!fiji.contains(Coord::new(-16.5, -178.0)));
+}
+
+#[test]
+fn vertex_order_does_not_matter() {
+    let Shape::Polygon(mut vertices) = square() else {
+        unreachable!()
+    };
+    vertices.reverse();
+    let reversed = Shape::polygon(vertices);
+    for p in [Coord::new(0.005, 0.005), Coord::new(0.02, 0.005)] {
+        assert_eq!(reversed.signed_distance_m(p), square().signed_distance_m(p));
+    }
+}
+
+#[test]
+fn degenerate_polygons_contain_nothing() {
+    let line = Shape::polygon(vec![Coord::new(0.0, 0.0), Coord::new(0.0, 1.0)]);
+    assert_eq!(line.signed_distance_m(Coord::new(0.0, 0.5)), f64::INFINITY);
+    assert!(!Shape::polygon(vec![]).contains(Coord::new(0.0, 0.0)));
+}
+
+#[test]
+fn from_fix_needs_both_coordinates() {
+    assert_eq!(
+        Coord::from_fix(Some(1.0), Some(2.0)),
+        Some(Coord::new(1.0, 2.0))
+    );
+    assert_eq!(Coord::from_fix(Some(1.0), None), None);
+    assert_eq!(Coord::from_fix(None, Some(2.0)), None);
+}
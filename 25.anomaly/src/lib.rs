@@ -0,0 +1,270 @@
+// Streaming anomaly detection
+//
+// Detectors follow the same shape as the gateway's `Filter` trait: one
+// sample in, one result out, constant memory, and `reset` to start over.
+// Each needs a warm-up period to learn what "normal" looks like and says so
+// instead of guessing.
+
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    WarmingUp,
+    Normal { score: f64 },
+    Anomaly { score: f64 },
+}
+
+impl Verdict {
+    pub fn is_anomaly(&self) -> bool {
+        matches!(self, Verdict::Anomaly { .. })
+    }
+
+    pub fn score(&self) -> Option<f64> {
+        match self {
+            Verdict::WarmingUp => None,
+            Verdict::Normal { score } | Verdict::Anomaly { score } => Some(*score),
+        }
+    }
+}
+
+pub trait Detector {
+    fn update(&mut self, value: f64) -> Verdict;
+    fn reset(&mut self);
+}
+
+fn judge(score: f64, threshold: f64) -> Verdict {
+    if score.abs() > threshold {
+        Verdict::Anomaly { score }
+    } else {
+        Verdict::Normal { score }
+    }
+}
+
+// Rolling z-score: (x - mean) / stddev over the last `window` normal samples.
+// Anomalies are not added to the window, so a spike does not inflate the
+// variance and hide the next one.
+#[derive(Debug, Clone)]
+pub struct ZScore {
+    window: usize,
+    threshold: f64,
+    samples: VecDeque<f64>,
+    sum: f64,
+    sum_sq: f64,
+}
+
+impl ZScore {
+    pub fn new(window: usize, threshold: f64) -> ZScore {
+        ZScore {
+            window: window.max(2),
+            threshold,
+            samples: VecDeque::new(),
+            sum: 0.0,
+            sum_sq: 0.0,
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        self.samples.push_back(value);
+        self.sum += value;
+        self.sum_sq += value * value;
+        if self.samples.len() > self.window {
+            let old = self.samples.pop_front().unwrap_or(0.0);
+            self.sum -= old;
+            self.sum_sq -= old * old;
+        }
+    }
+}
+
+impl Detector for ZScore {
+    fn update(&mut self, value: f64) -> Verdict {
+        if self.samples.len() < self.window {
+            self.push(value);
+            return Verdict::WarmingUp;
+        }
+        let n = self.samples.len() as f64;
+        let mean = self.sum / n;
+        // Clamp: the running sums can go slightly negative through rounding
+        let std = ((self.sum_sq / n - mean * mean).max(0.0)).sqrt();
+        let score = if std > 0.0 {
+            (value - mean) / std
+        } else if value == mean {
+            0.0
+        } else {
+            f64::INFINITY.copysign(value - mean)
+        };
+
+        let verdict = judge(score, self.threshold);
+        if !verdict.is_anomaly() {
+            self.push(value);
+        }
+        verdict
+    }
+
+    fn reset(&mut self) {
+        self.samples.clear();
+        self.sum = 0.0;
+        self.sum_sq = 0.0;
+    }
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+// Median absolute deviation: a robust z-score. The median and MAD ignore
+// up to half the window being outliers, where mean and stddev break down
+// after a single extreme value.
+#[derive(Debug, Clone)]
+pub struct Mad {
+    window: usize,
+    threshold: f64,
+    samples: VecDeque<f64>,
+    scratch: Vec<f64>,
+}
+
+// Scales MAD to match the standard deviation of normally distributed data
+const MAD_SCALE: f64 = 1.4826;
+
+impl Mad {
+    pub fn new(window: usize, threshold: f64) -> Mad {
+        Mad {
+            window: window.max(3),
+            threshold,
+            samples: VecDeque::new(),
+            scratch: Vec::with_capacity(window),
+        }
+    }
+}
+
+impl Detector for Mad {
+    fn update(&mut self, value: f64) -> Verdict {
+        let warm = self.samples.len() >= self.window;
+        let verdict = if warm {
+            self.scratch.clear();
+            self.scratch.extend(self.samples.iter());
+            self.scratch.sort_by(f64::total_cmp);
+            let med = median(&self.scratch);
+            for x in self.scratch.iter_mut() {
+                *x = (*x - med).abs();
+            }
+            self.scratch.sort_by(f64::total_cmp);
+            let mad = median(&self.scratch) * MAD_SCALE;
+            let score = if mad > 0.0 {
+                (value - med) / mad
+            } else if value == med {
+                0.0
+            } else {
+                f64::INFINITY.copysign(value - med)
+            };
+            judge(score, self.threshold)
+        } else {
+            Verdict::WarmingUp
+        };
+
+        // Unlike ZScore every sample goes into the window: the median is
+        // robust enough to absorb them
+        self.samples.push_back(value);
+        if self.samples.len() > self.window {
+            self.samples.pop_front();
+        }
+        verdict
+    }
+
+    fn reset(&mut self) {
+        self.samples.clear();
+    }
+}
+
+// Two-sided CUSUM change-point detector
+//
+// Learns a baseline mean and stddev during warm-up, then accumulates
+// standardised deviations beyond a `drift` allowance. A brief spike barely
+// moves the sums; a sustained shift grows them until they cross
+// `threshold`. Each sample's contribution is clipped to +/-Z_CLIP so that
+// a single huge outlier cannot trigger an alarm on its own. After an alarm
+// the detector re-learns the new level.
+// The score is positive for an upward shift and negative for a downward one.
+const Z_CLIP: f64 = 3.0;
+
+#[derive(Debug, Clone)]
+pub struct Cusum {
+    warmup: usize,
+    drift: f64,
+    threshold: f64,
+    baseline: Vec<f64>,
+    mean: f64,
+    std: f64,
+    high: f64,
+    low: f64,
+}
+
+impl Cusum {
+    pub fn new(warmup: usize, drift: f64, threshold: f64) -> Cusum {
+        Cusum {
+            warmup: warmup.max(2),
+            drift,
+            threshold,
+            baseline: Vec::with_capacity(warmup),
+            mean: 0.0,
+            std: 0.0,
+            high: 0.0,
+            low: 0.0,
+        }
+    }
+
+    // Mean of the learned baseline, once warm
+    pub fn baseline_mean(&self) -> Option<f64> {
+        (self.baseline.len() >= self.warmup).then_some(self.mean)
+    }
+}
+
+impl Detector for Cusum {
+    fn update(&mut self, value: f64) -> Verdict {
+        if self.baseline.len() < self.warmup {
+            self.baseline.push(value);
+            if self.baseline.len() == self.warmup {
+                let n = self.warmup as f64;
+                self.mean = self.baseline.iter().sum::<f64>() / n;
+                let var = self
+                    .baseline
+                    .iter()
+                    .map(|x| (x - self.mean).powi(2))
+                    .sum::<f64>()
+                    / (n - 1.0);
+                self.std = var.sqrt().max(f64::EPSILON);
+            }
+            return Verdict::WarmingUp;
+        }
+
+        let z = ((value - self.mean) / self.std).clamp(-Z_CLIP, Z_CLIP);
+        self.high = (self.high + z - self.drift).max(0.0);
+        self.low = (self.low - z - self.drift).max(0.0);
+
+        if self.high > self.threshold || self.low > self.threshold {
+            let score = if self.high > self.low {
+                self.high
+            } else {
+                -self.low
+            };
+            self.reset();
+            return Verdict::Anomaly { score };
+        }
+        let score = if self.high >= self.low {
+            self.high
+        } else {
+            -self.low
+        };
+        Verdict::Normal { score }
+    }
+
+    fn reset(&mut self) {
+        self.baseline.clear();
+        self.high = 0.0;
+        self.low = 0.0;
+    }
+}
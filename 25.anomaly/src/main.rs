@@ -0,0 +1,164 @@
+use anomaly::{Cusum, Detector, Mad, Verdict, ZScore};
+
+const LEN: usize = 600;
+const SHIFT_AT: usize = 300;
+const SPIKES: [(usize, f64); 6] = [
+    (80, 3.0),
+    (150, -2.5),
+    (151, 2.8),
+    (230, 2.0),
+    (420, -3.0),
+    (500, 2.5),
+];
+
+// Deterministic pseudo-noise, roughly normal with stddev 0.2
+struct Noise(u64);
+
+impl Noise {
+    fn next(&mut self) -> f64 {
+        let mut sum = 0.0;
+        for _ in 0..4 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            sum += (self.0 >> 11) as f64 / (1u64 << 53) as f64;
+        }
+        (sum - 2.0) * 0.35
+    }
+}
+
+fn signal() -> Vec<f64> {
+    let mut noise = Noise(0x9E37_79B9_7F4A_7C15);
+    let mut data: Vec<f64> = (0..LEN)
+        .map(|i| {
+            let level = if i < SHIFT_AT { 20.0 } else { 21.5 };
+            level + noise.next()
+        })
+        .collect();
+    for (i, delta) in SPIKES {
+        data[i] += delta;
+    }
+    data
+}
+
+fn is_spike(i: usize) -> bool {
+    SPIKES.iter().any(|(at, _)| *at == i)
+}
+
+// Run a detector over the signal and score it against the injected spikes
+fn evaluate(name: &str, detector: &mut dyn Detector, data: &[f64]) -> Vec<usize> {
+    let flagged: Vec<usize> = data
+        .iter()
+        .enumerate()
+        .filter(|(_, x)| detector.update(**x).is_anomaly())
+        .map(|(i, _)| i)
+        .collect();
+    let hits = flagged.iter().filter(|i| is_spike(**i)).count();
+    let false_alarms = flagged.len() - hits;
+    let after_shift = flagged
+        .iter()
+        .filter(|i| **i >= SHIFT_AT && !is_spike(**i))
+        .count();
+    println!(
+        "   {:<8} spikes found {}/{}, false alarms {:>3} ({} after the level shift)",
+        name,
+        hits,
+        SPIKES.len(),
+        false_alarms,
+        after_shift
+    );
+    flagged
+}
+
+fn main() {
+    println!("=== Streaming Anomaly Detection ===\n");
+
+    let data = signal();
+
+    // 1. Test signal
+    println!("1. Synthetic signal:");
+    println!(
+        "   {} samples at 20.0 +/- 0.2, level shift to 21.5 at {}",
+        LEN, SHIFT_AT
+    );
+    println!(
+        "   spikes injected at {:?}",
+        SPIKES.iter().map(|(i, _)| *i).collect::<Vec<_>>()
+    );
+
+    // 2. Warm-up
+    println!("\n2. Warm-up:");
+    let mut z = ZScore::new(30, 4.0);
+    let verdicts: Vec<Verdict> = data.iter().take(32).map(|x| z.update(*x)).collect();
+    let warming = verdicts
+        .iter()
+        .filter(|v| **v == Verdict::WarmingUp)
+        .count();
+    println!("   first {} verdicts are WarmingUp", warming);
+    println!("   then: {:?}", verdicts[warming]);
+
+    // 3. Outlier detectors
+    println!("\n3. Outlier detectors (window 30, threshold 4):");
+    let z_flags = evaluate("z-score", &mut ZScore::new(30, 4.0), &data);
+    let mad_flags = evaluate("MAD", &mut Mad::new(30, 4.0), &data);
+    println!(
+        "   z-score flagged {:?}...",
+        &z_flags[..z_flags.len().min(10)]
+    );
+    println!("   MAD     flagged {:?}", mad_flags);
+    println!("   (z-score never admits the new level into its window, so it flags it forever)");
+
+    // 4. Back-to-back spikes
+    println!("\n4. Contamination (spikes at 150 and 151):");
+    for (name, flags) in [("z-score", &z_flags), ("MAD", &mad_flags)] {
+        let found: Vec<usize> = flags
+            .iter()
+            .copied()
+            .filter(|i| (150..=151).contains(i))
+            .collect();
+        println!("   {:<8} caught {:?}", name, found);
+    }
+
+    // 5. Change points
+    println!("\n5. CUSUM change-point detector:");
+    let mut cusum = Cusum::new(50, 1.0, 8.0);
+    for (i, x) in data.iter().enumerate() {
+        if let Verdict::Anomaly { score } = cusum.update(*x) {
+            println!(
+                "   change at sample {} (score {:+.1}, {} samples after the shift)",
+                i,
+                score,
+                i as i64 - SHIFT_AT as i64
+            );
+        }
+    }
+    println!(
+        "   baseline re-learned at {:.2}",
+        cusum.baseline_mean().unwrap_or(f64::NAN)
+    );
+
+    // 6. Combining them
+    println!("\n6. Z-score reset on change points:");
+    let mut z = ZScore::new(30, 4.0);
+    let mut cusum = Cusum::new(50, 1.0, 8.0);
+    let mut flagged = Vec::new();
+    for (i, x) in data.iter().enumerate() {
+        if cusum.update(*x).is_anomaly() {
+            println!("   sample {}: level changed, resetting z-score", i);
+            z.reset();
+        }
+        if z.update(*x).is_anomaly() {
+            flagged.push(i);
+        }
+    }
+    let hits = flagged.iter().filter(|i| is_spike(**i)).count();
+    println!(
+        "   spikes found {}/{}, false alarms {}: {:?}",
+        hits,
+        SPIKES.len(),
+        flagged.len() - hits,
+        flagged
+    );
+
+    println!("\n=== End of Streaming Anomaly Detection Examples ===");
+}
@@ -0,0 +1,210 @@
+use anomaly::{Cusum, Detector, Mad, Verdict, ZScore};
+
+const SHIFT_AT: usize = 300;
+const SPIKES: [(usize, f64); 6] = [
+    (80, 3.0),
+    (150, -2.5),
+    (151, 2.8),
+    (230, 2.0),
+    (420, -3.0),
+    (500, 2.5),
+];
+
+// The demo's signal: 20.0 +/- 0.2, a step to 21.5 at 300, six spikes
+fn signal() -> Vec<f64> {
+    let mut state = 0x9E37_79B9_7F4A_7C15_u64;
+    let mut noise = || {
+        let mut sum = 0.0;
+        for _ in 0..4 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            sum += (state >> 11) as f64 / (1u64 << 53) as f64;
+        }
+        (sum - 2.0) * 0.35
+    };
+    let mut data: Vec<f64> = (0..600)
+        .map(|i| if i < SHIFT_AT { 20.0 } else { 21.5 } + noise())
+        .collect();
+    for (i, delta) in SPIKES {
+        data[i] += delta;
+    }
+    data
+}
+
+fn flagged(detector: &mut dyn Detector, data: &[f64]) -> Vec<usize> {
+    (0..data.len())
+        .filter(|&i| detector.update(data[i]).is_anomaly())
+        .collect()
+}
+
+fn warming_up(detector: &mut dyn Detector, data: &[f64]) -> usize {
+    data.iter()
+        .take_while(|&&x| detector.update(x) == Verdict::WarmingUp)
+        .count()
+}
+
+#[test]
+fn verdict_accessors() {
+    assert_eq!(Verdict::WarmingUp.score(), None);
+    assert!(!Verdict::WarmingUp.is_anomaly());
+    assert_eq!(Verdict::Normal { score: 1.5 }.score(), Some(1.5));
+    assert!(Verdict::Anomaly { score: -5.0 }.is_anomaly());
+}
+
+#[test]
+fn each_detector_warms_up_for_its_window() {
+    let data = signal();
+    assert_eq!(warming_up(&mut ZScore::new(30, 4.0), &data), 30);
+    assert_eq!(warming_up(&mut Mad::new(30, 4.0), &data), 30);
+    assert_eq!(warming_up(&mut Cusum::new(50, 1.0, 8.0), &data), 50);
+    // Windows too small to have a spread are raised to a minimum
+    assert_eq!(warming_up(&mut ZScore::new(0, 4.0), &data), 2);
+    assert_eq!(warming_up(&mut Mad::new(1, 4.0), &data), 3);
+}
+
+#[test]
+fn reset_starts_the_warm_up_again() {
+    let data = signal();
+    let mut detectors: Vec<Box<dyn Detector>> = vec![
+        Box::new(ZScore::new(10, 4.0)),
+        Box::new(Mad::new(10, 4.0)),
+        Box::new(Cusum::new(10, 1.0, 8.0)),
+    ];
+    for detector in &mut detectors {
+        for &x in &data[..20] {
+            detector.update(x);
+        }
+        detector.reset();
+        assert_eq!(warming_up(detector.as_mut(), &data), 10);
+    }
+}
+
+#[test]
+fn zscore_of_a_known_window() {
+    // Alternating 9 and 11: mean 10, population stddev 1
+    let mut z = ZScore::new(4, 3.0);
+    for x in [9.0, 11.0, 9.0, 11.0] {
+        z.update(x);
+    }
+    assert_eq!(z.update(6.0), Verdict::Anomaly { score: -4.0 });
+    // The anomaly was not added, so the window is unchanged
+    assert_eq!(z.update(12.0), Verdict::Normal { score: 2.0 });
+}
+
+#[test]
+fn constant_input_has_zero_spread() {
+    let mut z = ZScore::new(5, 4.0);
+    let mut mad = Mad::new(5, 4.0);
+    for _ in 0..5 {
+        z.update(7.0);
+        mad.update(7.0);
+    }
+    assert_eq!(z.update(7.0), Verdict::Normal { score: 0.0 });
+    assert_eq!(mad.update(7.0), Verdict::Normal { score: 0.0 });
+    assert_eq!(
+        z.update(7.1),
+        Verdict::Anomaly {
+            score: f64::INFINITY
+        }
+    );
+    assert_eq!(
+        mad.update(6.9),
+        Verdict::Anomaly {
+            score: f64::NEG_INFINITY
+        }
+    );
+}
+
+#[test]
+fn zscore_keeps_spikes_out_of_its_window() {
+    let data = signal();
+    let mut z = ZScore::new(30, 4.0);
+    let flags = flagged(&mut z, &data[..SHIFT_AT]);
+    // Back-to-back spikes at 150 and 151 are both caught
+    assert_eq!(flags, [80, 150, 151, 230]);
+}
+
+#[test]
+fn zscore_never_learns_a_level_shift() {
+    let flags = flagged(&mut ZScore::new(30, 4.0), &signal());
+    assert_eq!(flags.len(), 4 + 300);
+    assert!((SHIFT_AT..600).all(|i| flags.contains(&i)));
+}
+
+#[test]
+fn mad_finds_every_spike_and_absorbs_the_shift() {
+    assert_eq!(
+        flagged(&mut Mad::new(30, 4.0), &signal()),
+        [80, 150, 151, 230, 300, 301, 302, 303, 304, 305, 306, 420, 500]
+    );
+}
+
+#[test]
+fn mad_tolerates_outliers_inside_its_window() {
+    // A third of the window is wild, yet a normal value still scores low
+    let mut mad = Mad::new(9, 4.0);
+    for x in [10.0, 1000.0, 10.1, 9.9, -500.0, 10.0, 10.2, 2000.0, 9.8] {
+        mad.update(x);
+    }
+    assert!(!mad.update(10.05).is_anomaly());
+    assert!(mad.update(11.0).is_anomaly());
+}
+
+#[test]
+fn cusum_ignores_a_single_outlier() {
+    let mut cusum = Cusum::new(10, 1.0, 8.0);
+    for i in 0..10 {
+        cusum.update(if i % 2 == 0 { 9.0 } else { 11.0 });
+    }
+    // Clipped to 3 standard deviations, less the drift, stays under 8
+    assert!(!cusum.update(1e9).is_anomaly());
+    for _ in 0..20 {
+        assert!(!cusum.update(10.0).is_anomaly());
+    }
+}
+
+#[test]
+fn cusum_signs_the_direction_of_a_shift() {
+    for (level, upward) in [(14.0, true), (6.0, false)] {
+        let mut cusum = Cusum::new(10, 1.0, 8.0);
+        for i in 0..10 {
+            cusum.update(if i % 2 == 0 { 9.0 } else { 11.0 });
+        }
+        let verdict = (0..20)
+            .map(|_| cusum.update(level))
+            .find(|v| v.is_anomaly())
+            .expect("shift detected");
+        assert_eq!(verdict.score().unwrap() > 0.0, upward);
+        // It re-learns the new level from scratch
+        assert_eq!(cusum.baseline_mean(), None);
+    }
+}
+
+#[test]
+fn cusum_finds_the_level_shift_and_relearns() {
+    let data = signal();
+    let mut cusum = Cusum::new(50, 1.0, 8.0);
+    let changes = flagged(&mut cusum, &data);
+    assert_eq!(changes, [303]);
+    let baseline = cusum.baseline_mean().unwrap();
+    assert!((baseline - 21.5).abs() < 0.1, "{}", baseline);
+}
+
+#[test]
+fn zscore_reset_on_change_points() {
+    let data = signal();
+    let mut z = ZScore::new(30, 4.0);
+    let mut cusum = Cusum::new(50, 1.0, 8.0);
+    let mut flags = Vec::new();
+    for (i, &x) in data.iter().enumerate() {
+        if cusum.update(x).is_anomaly() {
+            z.reset();
+        }
+        if z.update(x).is_anomaly() {
+            flags.push(i);
+        }
+    }
+    // Every spike, and only the three samples before the shift was noticed
+    assert_eq!(flags, [80, 150, 151, 230, 300, 301, 302, 420, 500]);
+}
@@ -0,0 +1,331 @@
+// Bitcask-style append-only key-value store
+//
+// Every put or delete appends a record to the active segment file
+// (`data-NNNNNN.log`); nothing is ever modified in place. An in-memory
+// index maps each key to the file and offset of its latest value, so a get
+// is one hash lookup and one read. The index is rebuilt by scanning the
+// segments on open, and `compact` rewrites only the live records to reclaim
+// the space taken by overwritten and deleted keys.
+
+pub mod record;
+
+use record::{decode, encode, ScanError, MAX_KEY_LEN, MAX_VALUE_LEN};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::fs::FileExt;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+pub struct StoreConfig {
+    pub dir: PathBuf,
+    pub max_segment_bytes: u64,
+}
+
+impl StoreConfig {
+    pub fn new(dir: impl Into<PathBuf>) -> StoreConfig {
+        StoreConfig {
+            dir: dir.into(),
+            max_segment_bytes: 1024 * 1024,
+        }
+    }
+}
+
+// What `Store::open` found and repaired
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Recovery {
+    pub segments: usize,
+    pub records: u64,
+    pub truncated_bytes: u64,
+    // Older segments with a bad record; everything after it is skipped
+    pub damaged_segments: usize,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub keys: usize,
+    pub segments: usize,
+    pub live_bytes: u64,
+    pub dead_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compaction {
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub segments_removed: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Location {
+    segment: u64,
+    value_offset: u64,
+    value_len: u32,
+    record_len: u32,
+}
+
+#[derive(Debug)]
+pub struct Store {
+    config: StoreConfig,
+    index: HashMap<Vec<u8>, Location>,
+    segments: BTreeMap<u64, File>,
+    writer: Option<File>,
+    active: u64,
+    active_len: u64,
+    live_bytes: u64,
+    dead_bytes: u64,
+}
+
+fn segment_name(id: u64) -> String {
+    format!("data-{:06}.log", id)
+}
+
+fn parse_segment(path: &Path) -> Option<u64> {
+    let name = path.file_name()?.to_str()?;
+    name.strip_prefix("data-")?
+        .strip_suffix(".log")?
+        .parse()
+        .ok()
+}
+
+// Segment ids present in `dir`, oldest first
+pub fn list_segments(dir: &Path) -> io::Result<Vec<u64>> {
+    let mut ids: Vec<u64> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| parse_segment(&entry.path()))
+        .collect();
+    ids.sort();
+    Ok(ids)
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+
+impl Store {
+    pub fn open(config: StoreConfig) -> io::Result<(Store, Recovery)> {
+        fs::create_dir_all(&config.dir)?;
+        let ids = list_segments(&config.dir)?;
+        let mut store = Store {
+            config,
+            index: HashMap::new(),
+            segments: BTreeMap::new(),
+            writer: None,
+            active: ids.last().copied().unwrap_or(0),
+            active_len: 0,
+            live_bytes: 0,
+            dead_bytes: 0,
+        };
+        let mut recovery = Recovery {
+            segments: ids.len(),
+            ..Recovery::default()
+        };
+
+        for &id in &ids {
+            let path = store.path(id);
+            let bytes = fs::read(&path)?;
+            let mut offset = 0usize;
+            let mut stopped: Option<ScanError> = None;
+            while offset < bytes.len() {
+                match decode(&bytes[offset..]) {
+                    Ok(scanned) => {
+                        let location = Location {
+                            segment: id,
+                            value_offset: (offset + scanned.value_offset) as u64,
+                            value_len: scanned.value.map_or(0, |v| v.len() as u32),
+                            record_len: scanned.len as u32,
+                        };
+                        store.apply(
+                            scanned.key,
+                            scanned.value.map(|_| location),
+                            scanned.len as u64,
+                        );
+                        recovery.records += 1;
+                        offset += scanned.len;
+                    }
+                    Err(e) => {
+                        stopped = Some(e);
+                        break;
+                    }
+                }
+            }
+
+            if stopped.is_some() {
+                if id == store.active {
+                    // A torn or garbled tail from a crash: cut it off
+                    OpenOptions::new()
+                        .write(true)
+                        .open(&path)?
+                        .set_len(offset as u64)?;
+                    recovery.truncated_bytes += (bytes.len() - offset) as u64;
+                } else {
+                    recovery.damaged_segments += 1;
+                }
+            }
+            if id == store.active {
+                store.active_len = offset as u64;
+            }
+            store.segments.insert(id, File::open(&path)?);
+        }
+
+        store.open_active()?;
+        Ok((store, recovery))
+    }
+
+    fn path(&self, id: u64) -> PathBuf {
+        self.config.dir.join(segment_name(id))
+    }
+
+    fn open_active(&mut self) -> io::Result<()> {
+        let path = self.path(self.active);
+        self.writer = Some(OpenOptions::new().create(true).append(true).open(&path)?);
+        self.segments.insert(self.active, File::open(&path)?);
+        Ok(())
+    }
+
+    // Update the index and space accounting for one record
+    fn apply(&mut self, key: &[u8], location: Option<Location>, record_len: u64) {
+        let previous = match location {
+            Some(loc) => self.index.insert(key.to_vec(), loc),
+            None => self.index.remove(key),
+        };
+        if let Some(old) = previous {
+            self.live_bytes -= old.record_len as u64;
+            self.dead_bytes += old.record_len as u64;
+        }
+        match location {
+            Some(_) => self.live_bytes += record_len,
+            // A tombstone is dead the moment it is written
+            None => self.dead_bytes += record_len,
+        }
+    }
+
+    fn append(&mut self, bytes: &[u8]) -> io::Result<(u64, u64)> {
+        if self.active_len > 0
+            && self.active_len + bytes.len() as u64 > self.config.max_segment_bytes
+        {
+            self.sync()?;
+            self.active += 1;
+            self.active_len = 0;
+            self.open_active()?;
+        }
+        let offset = self.active_len;
+        self.writer_mut().write_all(bytes)?;
+        self.active_len += bytes.len() as u64;
+        Ok((self.active, offset))
+    }
+
+    fn writer_mut(&mut self) -> &mut File {
+        self.writer.as_mut().expect("active segment is open")
+    }
+
+    pub fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        if key.len() > MAX_KEY_LEN {
+            return Err(invalid("key too large"));
+        }
+        if value.len() > MAX_VALUE_LEN {
+            return Err(invalid("value too large"));
+        }
+        let bytes = encode(key, Some(value));
+        let (segment, offset) = self.append(&bytes)?;
+        let location = Location {
+            segment,
+            value_offset: offset + (record::HEADER_LEN + key.len()) as u64,
+            value_len: value.len() as u32,
+            record_len: bytes.len() as u32,
+        };
+        self.apply(key, Some(location), bytes.len() as u64);
+        Ok(())
+    }
+
+    // Returns whether the key existed
+    pub fn delete(&mut self, key: &[u8]) -> io::Result<bool> {
+        if !self.index.contains_key(key) {
+            return Ok(false);
+        }
+        let bytes = encode(key, None);
+        self.append(&bytes)?;
+        self.apply(key, None, bytes.len() as u64);
+        Ok(true)
+    }
+
+    pub fn get(&self, key: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        let Some(loc) = self.index.get(key) else {
+            return Ok(None);
+        };
+        let mut value = vec![0u8; loc.value_len as usize];
+        self.segments[&loc.segment].read_exact_at(&mut value, loc.value_offset)?;
+        Ok(Some(value))
+    }
+
+    pub fn contains_key(&self, key: &[u8]) -> bool {
+        self.index.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    // All live keys, sorted
+    pub fn keys(&self) -> Vec<&[u8]> {
+        let mut keys: Vec<&[u8]> = self.index.keys().map(Vec::as_slice).collect();
+        keys.sort();
+        keys
+    }
+
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.writer_mut().sync_data()
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            keys: self.index.len(),
+            segments: self.segments.len(),
+            live_bytes: self.live_bytes,
+            dead_bytes: self.dead_bytes,
+        }
+    }
+
+    // Copy live records into fresh segments, then delete the old ones.
+    // Old segments are removed oldest first, so a crash part-way leaves a
+    // directory that still replays to the same contents.
+    pub fn compact(&mut self) -> io::Result<Compaction> {
+        let old: Vec<u64> = self.segments.keys().copied().collect();
+        let bytes_before = self.live_bytes + self.dead_bytes;
+
+        let mut keys: Vec<Vec<u8>> = self.index.keys().cloned().collect();
+        keys.sort();
+        let values: Vec<(Vec<u8>, Vec<u8>)> = keys
+            .into_iter()
+            .map(|key| {
+                let value = self.get(&key)?.unwrap_or_default();
+                Ok((key, value))
+            })
+            .collect::<io::Result<_>>()?;
+
+        self.active += 1;
+        self.active_len = 0;
+        self.open_active()?;
+        self.index.clear();
+        self.live_bytes = 0;
+        self.dead_bytes = 0;
+        for (key, value) in &values {
+            self.put(key, value)?;
+        }
+        self.sync()?;
+
+        for id in &old {
+            self.segments.remove(id);
+            fs::remove_file(self.path(*id))?;
+        }
+        Ok(Compaction {
+            bytes_before,
+            bytes_after: self.live_bytes,
+            segments_removed: old.len(),
+        })
+    }
+}
@@ -0,0 +1,131 @@
+use kvstore::{list_segments, Store, StoreConfig};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+fn show(store: &Store, key: &str) {
+    match store.get(key.as_bytes()) {
+        Ok(Some(value)) => println!("   {} = {}", key, String::from_utf8_lossy(&value)),
+        Ok(None) => println!("   {} = (missing)", key),
+        Err(e) => println!("   {} -> error: {}", key, e),
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    println!("=== Append-Only Key-Value Store ===\n");
+
+    let dir = std::env::temp_dir().join("rust-sys-kvstore-demo");
+    let _ = fs::remove_dir_all(&dir);
+    let config = StoreConfig {
+        dir: dir.clone(),
+        max_segment_bytes: 4096,
+    };
+
+    // 1. Basic operations
+    println!("1. Put, get, overwrite, delete:");
+    let (mut store, _) = Store::open(config.clone())?;
+    store.put(b"device/id", b"gw-01")?;
+    store.put(b"wifi/ssid", b"plant-floor")?;
+    store.put(b"calibration/offset", b"0.00")?;
+    store.put(b"calibration/offset", b"-0.35")?;
+    println!("   deleted wifi/ssid: {}", store.delete(b"wifi/ssid")?);
+    println!("   deleted it again:  {}", store.delete(b"wifi/ssid")?);
+    for key in ["device/id", "calibration/offset", "wifi/ssid"] {
+        show(&store, key);
+    }
+    println!("   {:?}", store.stats());
+
+    // 2. Churn: the same few keys rewritten many times
+    println!("\n2. Writing 500 counter updates:");
+    for i in 0..500u32 {
+        let key = format!("counter/{}", i % 5);
+        store.put(key.as_bytes(), i.to_string().as_bytes())?;
+    }
+    store.sync()?;
+    let stats = store.stats();
+    println!("   {:?}", stats);
+    println!(
+        "   {:.0}% of the disk space holds dead records",
+        100.0 * stats.dead_bytes as f64 / (stats.live_bytes + stats.dead_bytes) as f64
+    );
+    drop(store);
+
+    // 3. Restart: the index is rebuilt from the segments
+    println!("\n3. Reopen:");
+    let (store, recovery) = Store::open(config.clone())?;
+    println!("   {:?}", recovery);
+    println!(
+        "   {} keys: {:?}",
+        store.len(),
+        store
+            .keys()
+            .iter()
+            .map(|k| String::from_utf8_lossy(k))
+            .collect::<Vec<_>>()
+    );
+    show(&store, "counter/4");
+    drop(store);
+
+    // 4. Crash in the middle of a write
+    println!("\n4. Torn write:");
+    let active = *list_segments(&dir)?.last().unwrap();
+    let path = dir.join(format!("data-{:06}.log", active));
+    let record = kvstore::record::encode(b"counter/0", Some(b"999999"));
+    OpenOptions::new()
+        .append(true)
+        .open(&path)?
+        .write_all(&record[..record.len() - 3])?;
+    println!(
+        "   appended {} of {} bytes to segment {}",
+        record.len() - 3,
+        record.len(),
+        active
+    );
+    let (mut store, recovery) = Store::open(config.clone())?;
+    println!("   {:?}", recovery);
+    show(&store, "counter/0");
+    store.put(b"counter/0", b"500")?;
+    show(&store, "counter/0");
+
+    // 5. Compaction
+    println!("\n5. Compaction:");
+    let before = list_segments(&dir)?;
+    let report = store.compact()?;
+    println!("   {:?}", report);
+    println!("   segments {:?} -> {:?}", before, list_segments(&dir)?);
+    println!("   {:?}", store.stats());
+    drop(store);
+
+    let (store, recovery) = Store::open(config.clone())?;
+    println!("   after reopen: {:?}", recovery);
+    for key in [
+        "device/id",
+        "calibration/offset",
+        "counter/0",
+        "counter/4",
+        "wifi/ssid",
+    ] {
+        show(&store, key);
+    }
+    drop(store);
+
+    // 6. Bit rot in an older segment
+    println!("\n6. Corruption in a sealed segment:");
+    let (mut store, _) = Store::open(config.clone())?;
+    for i in 0..300u32 {
+        store.put(format!("log/{:03}", i).as_bytes(), &[b'x'; 32])?;
+    }
+    drop(store);
+    let first = list_segments(&dir)?[0];
+    let path = dir.join(format!("data-{:06}.log", first));
+    let mut bytes = fs::read(&path)?;
+    let mid = bytes.len() / 2;
+    bytes[mid] ^= 0x10;
+    fs::write(&path, bytes)?;
+    let (store, recovery) = Store::open(config)?;
+    println!("   flipped one bit in segment {}", first);
+    println!("   {:?}", recovery);
+    println!("   {} keys still readable", store.len());
+
+    println!("\n=== End of Key-Value Store Examples ===");
+    Ok(())
+}
@@ -0,0 +1,82 @@
+// On-disk record layout, little-endian:
+//
+//   +-------+---------+-----------+-----+-------+
+//   | CRC32 | key len | value len | key | value |
+//   |  u32  |   u32   |    u32    | ... |  ...  |
+//   +-------+---------+-----------+-----+-------+
+//
+// The CRC covers everything after itself. A value length of u32::MAX marks
+// a tombstone (a deleted key) and is followed by no value bytes.
+
+use crc::crc32;
+
+pub const HEADER_LEN: usize = 12;
+pub const TOMBSTONE: u32 = u32::MAX;
+pub const MAX_KEY_LEN: usize = 64 * 1024;
+pub const MAX_VALUE_LEN: usize = 16 * 1024 * 1024;
+
+pub fn encode(key: &[u8], value: Option<&[u8]>) -> Vec<u8> {
+    let value_len = value.map_or(TOMBSTONE, |v| v.len() as u32);
+    let mut buf = Vec::with_capacity(HEADER_LEN + key.len() + value.map_or(0, |v| v.len()));
+    buf.extend_from_slice(&[0; 4]);
+    buf.extend_from_slice(&(key.len() as u32).to_le_bytes());
+    buf.extend_from_slice(&value_len.to_le_bytes());
+    buf.extend_from_slice(key);
+    if let Some(v) = value {
+        buf.extend_from_slice(v);
+    }
+    let crc = crc32(&buf[4..]);
+    buf[0..4].copy_from_slice(&crc.to_le_bytes());
+    buf
+}
+
+// A record found while scanning a segment
+#[derive(Debug, PartialEq, Eq)]
+pub struct Scanned<'a> {
+    pub key: &'a [u8],
+    pub value: Option<&'a [u8]>,
+    // Offset of the value within the record, and total record length
+    pub value_offset: usize,
+    pub len: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanError {
+    // Not enough bytes left for the record: a torn write at the tail
+    Truncated,
+    // Lengths out of range or checksum mismatch
+    Corrupt,
+}
+
+pub fn decode(buf: &[u8]) -> Result<Scanned<'_>, ScanError> {
+    if buf.len() < HEADER_LEN {
+        return Err(ScanError::Truncated);
+    }
+    let stored = u32::from_le_bytes(buf[0..4].try_into().unwrap());
+    let key_len = u32::from_le_bytes(buf[4..8].try_into().unwrap()) as usize;
+    let raw_value_len = u32::from_le_bytes(buf[8..12].try_into().unwrap());
+    let value_len = if raw_value_len == TOMBSTONE {
+        0
+    } else {
+        raw_value_len as usize
+    };
+    if key_len > MAX_KEY_LEN || value_len > MAX_VALUE_LEN {
+        return Err(ScanError::Corrupt);
+    }
+
+    let len = HEADER_LEN + key_len + value_len;
+    if buf.len() < len {
+        return Err(ScanError::Truncated);
+    }
+    if crc32(&buf[4..len]) != stored {
+        return Err(ScanError::Corrupt);
+    }
+
+    let value_offset = HEADER_LEN + key_len;
+    Ok(Scanned {
+        key: &buf[HEADER_LEN..value_offset],
+        value: (raw_value_len != TOMBSTONE).then(|| &buf[value_offset..len]),
+        value_offset,
+        len,
+    })
+}
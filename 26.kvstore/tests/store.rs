@@ -0,0 +1,258 @@
+use kvstore::record::{decode, encode, ScanError, HEADER_LEN};
+use kvstore::{list_segments, Recovery, Store, StoreConfig};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+// A fresh directory per test, small segments so they roll over
+fn config(name: &str) -> StoreConfig {
+    let dir =
+        std::env::temp_dir().join(format!("rust-sys-kvstore-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    StoreConfig {
+        dir,
+        max_segment_bytes: 4096,
+    }
+}
+
+fn segment_path(config: &StoreConfig, id: u64) -> PathBuf {
+    config.dir.join(format!("data-{:06}.log", id))
+}
+
+#[test]
+fn records_round_trip_and_detect_damage() {
+    let bytes = encode(b"key", Some(b"value"));
+    assert_eq!(bytes.len(), HEADER_LEN + 8);
+    let scanned = decode(&bytes).unwrap();
+    assert_eq!(
+        (scanned.key, scanned.value),
+        (&b"key"[..], Some(&b"value"[..]))
+    );
+    assert_eq!(scanned.len, bytes.len());
+
+    let tombstone = encode(b"key", None);
+    assert_eq!(decode(&tombstone).unwrap().value, None);
+
+    assert_eq!(decode(&bytes[..5]), Err(ScanError::Truncated));
+    assert_eq!(decode(&bytes[..bytes.len() - 1]), Err(ScanError::Truncated));
+    let mut flipped = bytes.clone();
+    flipped[HEADER_LEN] ^= 0x01;
+    assert_eq!(decode(&flipped), Err(ScanError::Corrupt));
+    // A garbage length is corrupt, not a request for 4 GB
+    let mut huge = bytes;
+    huge[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(decode(&huge), Err(ScanError::Corrupt));
+}
+
+#[test]
+fn put_get_overwrite_delete() {
+    let config = config("basic");
+    let (mut store, recovery) = Store::open(config.clone()).unwrap();
+    assert_eq!(recovery, Recovery::default());
+
+    store.put(b"device/id", b"gw-01").unwrap();
+    store.put(b"offset", b"0.00").unwrap();
+    store.put(b"offset", b"-0.35").unwrap();
+    store.put(b"empty", b"").unwrap();
+    assert_eq!(
+        store.get(b"offset").unwrap().as_deref(),
+        Some(&b"-0.35"[..])
+    );
+    assert_eq!(store.get(b"empty").unwrap().as_deref(), Some(&b""[..]));
+    assert!(store.delete(b"device/id").unwrap());
+    assert!(!store.delete(b"device/id").unwrap());
+    assert_eq!(store.get(b"device/id").unwrap(), None);
+    assert_eq!(store.keys(), [&b"empty"[..], &b"offset"[..]]);
+    fs::remove_dir_all(&config.dir).unwrap();
+}
+
+#[test]
+fn stats_count_overwritten_and_deleted_records_as_dead() {
+    let config = config("stats");
+    let (mut store, _) = Store::open(config.clone()).unwrap();
+    let record = encode(b"k", Some(b"v")).len() as u64;
+    let tombstone = encode(b"k", None).len() as u64;
+
+    store.put(b"k", b"v").unwrap();
+    store.put(b"k", b"v").unwrap();
+    let stats = store.stats();
+    assert_eq!((stats.live_bytes, stats.dead_bytes), (record, record));
+
+    store.delete(b"k").unwrap();
+    let stats = store.stats();
+    assert_eq!(stats.keys, 0);
+    assert_eq!(
+        (stats.live_bytes, stats.dead_bytes),
+        (0, 2 * record + tombstone)
+    );
+    fs::remove_dir_all(&config.dir).unwrap();
+}
+
+#[test]
+fn reopen_rebuilds_the_index_including_deletes() {
+    let config = config("reopen");
+    let (mut store, _) = Store::open(config.clone()).unwrap();
+    for i in 0..500u32 {
+        let key = format!("counter/{}", i % 5);
+        store.put(key.as_bytes(), i.to_string().as_bytes()).unwrap();
+    }
+    store.delete(b"counter/2").unwrap();
+    let stats = store.stats();
+    drop(store);
+
+    let (store, recovery) = Store::open(config.clone()).unwrap();
+    assert_eq!(recovery.records, 501);
+    assert_eq!(recovery.segments, stats.segments);
+    assert!(recovery.segments > 1, "segments should have rolled over");
+    assert_eq!(recovery.truncated_bytes, 0);
+    assert_eq!(store.stats(), stats);
+    assert_eq!(
+        store.get(b"counter/4").unwrap().as_deref(),
+        Some(&b"499"[..])
+    );
+    assert_eq!(store.get(b"counter/2").unwrap(), None);
+    fs::remove_dir_all(&config.dir).unwrap();
+}
+
+#[test]
+fn torn_write_is_cut_off_and_writing_continues() {
+    let config = config("torn");
+    let (mut store, _) = Store::open(config.clone()).unwrap();
+    store.put(b"counter", b"1").unwrap();
+    drop(store);
+
+    let active = *list_segments(&config.dir).unwrap().last().unwrap();
+    let record = encode(b"counter", Some(b"999999"));
+    OpenOptions::new()
+        .append(true)
+        .open(segment_path(&config, active))
+        .unwrap()
+        .write_all(&record[..record.len() - 3])
+        .unwrap();
+
+    let (mut store, recovery) = Store::open(config.clone()).unwrap();
+    assert_eq!(recovery.records, 1);
+    assert_eq!(recovery.truncated_bytes, record.len() as u64 - 3);
+    assert_eq!(store.get(b"counter").unwrap().as_deref(), Some(&b"1"[..]));
+
+    // The next record lands where the torn one began
+    store.put(b"counter", b"2").unwrap();
+    drop(store);
+    let (store, recovery) = Store::open(config.clone()).unwrap();
+    assert_eq!((recovery.records, recovery.truncated_bytes), (2, 0));
+    assert_eq!(store.get(b"counter").unwrap().as_deref(), Some(&b"2"[..]));
+    fs::remove_dir_all(&config.dir).unwrap();
+}
+
+#[test]
+fn damage_in_a_sealed_segment_is_reported_not_truncated() {
+    let config = config("bitrot");
+    let (mut store, _) = Store::open(config.clone()).unwrap();
+    for i in 0..300u32 {
+        store
+            .put(format!("log/{:03}", i).as_bytes(), &[b'x'; 32])
+            .unwrap();
+    }
+    drop(store);
+
+    let first = list_segments(&config.dir).unwrap()[0];
+    let path = segment_path(&config, first);
+    let mut bytes = fs::read(&path).unwrap();
+    let len = bytes.len();
+    bytes[len / 2] ^= 0x10;
+    fs::write(&path, &bytes).unwrap();
+
+    let (store, recovery) = Store::open(config.clone()).unwrap();
+    assert_eq!(recovery.damaged_segments, 1);
+    assert_eq!(recovery.truncated_bytes, 0);
+    assert_eq!(fs::metadata(&path).unwrap().len(), len as u64);
+    // Records before the damage and in later segments are still there
+    assert!(store.contains_key(b"log/000"));
+    assert!(store.contains_key(b"log/299"));
+    assert!(store.len() < 300);
+    fs::remove_dir_all(&config.dir).unwrap();
+}
+
+#[test]
+fn compaction_keeps_contents_and_drops_dead_space() {
+    let config = config("compact");
+    let (mut store, _) = Store::open(config.clone()).unwrap();
+    for i in 0..400u32 {
+        let key = format!("key/{}", i % 7);
+        store
+            .put(key.as_bytes(), format!("v{}", i).as_bytes())
+            .unwrap();
+    }
+    store.delete(b"key/3").unwrap();
+    let before = list_segments(&config.dir).unwrap();
+    let live = store.stats().live_bytes;
+
+    let report = store.compact().unwrap();
+    assert_eq!(report.segments_removed, before.len());
+    assert_eq!(report.bytes_after, live);
+    assert!(report.bytes_before > report.bytes_after);
+    assert_eq!(store.stats().dead_bytes, 0);
+    let after = list_segments(&config.dir).unwrap();
+    assert!(after.iter().all(|id| id > before.last().unwrap()));
+    drop(store);
+
+    let (store, recovery) = Store::open(config.clone()).unwrap();
+    assert_eq!(recovery.records, 6);
+    for i in 0..7u32 {
+        let expected = (i != 3).then(|| format!("v{}", 399 - (399 - i) % 7).into_bytes());
+        assert_eq!(
+            store.get(format!("key/{}", i).as_bytes()).unwrap(),
+            expected
+        );
+    }
+    fs::remove_dir_all(&config.dir).unwrap();
+}
+
+#[test]
+fn oversized_keys_are_rejected() {
+    let config = config("limits");
+    let (mut store, _) = Store::open(config.clone()).unwrap();
+    let key = vec![b'k'; 64 * 1024 + 1];
+    assert!(store.put(&key, b"v").is_err());
+    assert!(store.is_empty());
+    fs::remove_dir_all(&config.dir).unwrap();
+}
+
+#[test]
+fn random_operations_match_a_hashmap_across_reopens() {
+    let config = config("oracle");
+    let mut oracle: HashMap<Vec<u8>, Vec<u8>> = HashMap::new();
+    let mut state = 0x4B56_u64;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 56) as u8
+    };
+
+    for round in 0..5 {
+        let (mut store, _) = Store::open(config.clone()).unwrap();
+        for _ in 0..400 {
+            let key = format!("k{}", next() % 40).into_bytes();
+            if next() < 64 {
+                assert_eq!(store.delete(&key).unwrap(), oracle.remove(&key).is_some());
+            } else {
+                let value = vec![next(); next() as usize];
+                store.put(&key, &value).unwrap();
+                oracle.insert(key, value);
+            }
+        }
+        if round == 2 {
+            store.compact().unwrap();
+        }
+        drop(store);
+
+        let (store, _) = Store::open(config.clone()).unwrap();
+        assert_eq!(store.len(), oracle.len());
+        for (key, value) in &oracle {
+            assert_eq!(store.get(key).unwrap().as_ref(), Some(value));
+        }
+    }
+    fs::remove_dir_all(&config.dir).unwrap();
+}
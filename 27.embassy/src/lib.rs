@@ -0,0 +1,69 @@
+// Hardware-facing types shared by the async tasks
+//
+// This part is `no_std` and knows nothing about the host: the tasks in
+// main.rs are written against these traits, so moving to a microcontroller
+// means swapping the mock drivers for HAL ones and the std executor for the
+// Cortex-M one, not rewriting the tasks.
+
+#![no_std]
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SensorError {
+    NotResponding,
+    Crc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reading {
+    pub sensor: u8,
+    pub celsius: f32,
+    pub at_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedMode {
+    // Short blink every second: alive and well
+    Heartbeat,
+    // Fast blinking: an alert is active
+    Alert,
+    Off,
+}
+
+impl LedMode {
+    // (on, off) durations in milliseconds
+    pub fn pattern(self) -> (u64, u64) {
+        match self {
+            LedMode::Heartbeat => (50, 450),
+            LedMode::Alert => (100, 100),
+            LedMode::Off => (0, 500),
+        }
+    }
+}
+
+pub trait Led {
+    fn set(&mut self, on: bool);
+}
+
+// Reading a sensor takes time (an ADC conversion, a bus transaction); an
+// async read lets other tasks run meanwhile instead of busy-waiting
+#[allow(async_fn_in_trait)]
+pub trait TemperatureSensor {
+    async fn read(&mut self) -> Result<f32, SensorError>;
+}
+
+// Hysteresis so a reading hovering at the limit does not flicker the LED
+#[derive(Debug, Clone, Copy)]
+pub struct AlertLimits {
+    pub raise_above: f32,
+    pub clear_below: f32,
+}
+
+impl AlertLimits {
+    pub fn next(&self, active: bool, celsius: f32) -> bool {
+        if active {
+            celsius >= self.clear_below
+        } else {
+            celsius > self.raise_above
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+use embassy_executor::Spawner;
+use embassy_futures::join::join;
+use embassy_futures::select::{select, Either};
+use embassy_lesson::{AlertLimits, Led, LedMode, Reading, SensorError, TemperatureSensor};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
+use embassy_sync::signal::Signal;
+use embassy_time::{with_timeout, Duration, Instant, Ticker, Timer};
+
+// Statics are how embassy tasks share state: they must outlive every task
+static READINGS: Channel<CriticalSectionRawMutex, Reading, 8> = Channel::new();
+static LED_MODE: Signal<CriticalSectionRawMutex, LedMode> = Signal::new();
+static TOGGLES: AtomicU32 = AtomicU32::new(0);
+static DROPPED: AtomicU32 = AtomicU32::new(0);
+
+fn now_ms() -> u64 {
+    Instant::now().as_millis()
+}
+
+// A mock sensor: each read takes `conversion_ms`, like a DS18B20 taking
+// 750 ms for a 12-bit conversion (scaled down here)
+struct MockSensor {
+    id: u8,
+    conversion_ms: u64,
+    base: f32,
+    slope_per_s: f32,
+    hung: bool,
+}
+
+impl TemperatureSensor for MockSensor {
+    async fn read(&mut self) -> Result<f32, SensorError> {
+        if self.hung {
+            // Never answers; the caller must time out
+            core::future::pending::<()>().await;
+        }
+        Timer::after_millis(self.conversion_ms).await;
+        Ok(self.base + self.slope_per_s * now_ms() as f32 / 1000.0)
+    }
+}
+
+struct MockLed {
+    on: bool,
+}
+
+impl Led for MockLed {
+    fn set(&mut self, on: bool) {
+        if on != self.on {
+            TOGGLES.fetch_add(1, Ordering::Relaxed);
+        }
+        self.on = on;
+    }
+}
+
+#[embassy_executor::task(pool_size = 2)]
+async fn sensor_task(mut sensor: MockSensor, period_ms: u64) {
+    // A Ticker schedules from the previous deadline, so the read time does
+    // not make the period drift the way `Timer::after` in a loop would
+    let mut ticker = Ticker::every(Duration::from_millis(period_ms));
+    loop {
+        match with_timeout(Duration::from_millis(100), sensor.read()).await {
+            Ok(Ok(celsius)) => {
+                let reading = Reading {
+                    sensor: sensor.id,
+                    celsius,
+                    at_ms: now_ms(),
+                };
+                // Never block a sensor loop on a slow consumer
+                if READINGS.try_send(reading).is_err() {
+                    DROPPED.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            Ok(Err(e)) => println!("   [{:>4} ms] sensor {} error {:?}", now_ms(), sensor.id, e),
+            Err(_) => println!("   [{:>4} ms] sensor {} timed out", now_ms(), sensor.id),
+        }
+        ticker.next().await;
+    }
+}
+
+#[embassy_executor::task]
+async fn monitor_task(limits: AlertLimits) {
+    // Alert state per sensor; the LED shows whether any is active
+    let mut alerts = [false; 4];
+    loop {
+        let reading = READINGS.receive().await;
+        let Some(active) = alerts.get_mut(reading.sensor as usize) else {
+            continue;
+        };
+        let next = limits.next(*active, reading.celsius);
+        if next == *active {
+            continue;
+        }
+        *active = next;
+        println!(
+            "   [{:>4} ms] sensor {} at {:.1} C -> alert {}",
+            reading.at_ms,
+            reading.sensor,
+            reading.celsius,
+            if next { "RAISED" } else { "cleared" }
+        );
+        let any = alerts.iter().any(|a| *a);
+        LED_MODE.signal(if any {
+            LedMode::Alert
+        } else {
+            LedMode::Heartbeat
+        });
+    }
+}
+
+#[embassy_executor::task]
+async fn blink_task(mut led: MockLed) {
+    let mut mode = LedMode::Heartbeat;
+    loop {
+        let (on_ms, off_ms) = mode.pattern();
+        led.set(on_ms > 0);
+        // Wake early if the mode changes, instead of finishing the pattern
+        let wait = Timer::after_millis(on_ms.max(1));
+        if let Either::Second(m) = select(wait, LED_MODE.wait()).await {
+            mode = m;
+            continue;
+        }
+        led.set(false);
+        if let Either::Second(m) = select(Timer::after_millis(off_ms), LED_MODE.wait()).await {
+            mode = m;
+        }
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    println!("=== Embassy Async Embedded ===\n");
+
+    // 1. Concurrency without threads
+    println!("1. Two sensor reads with join:");
+    let mut a = MockSensor {
+        id: 0,
+        conversion_ms: 75,
+        base: 21.0,
+        slope_per_s: 0.0,
+        hung: false,
+    };
+    let mut b = MockSensor {
+        id: 1,
+        conversion_ms: 60,
+        base: 22.0,
+        slope_per_s: 0.0,
+        hung: false,
+    };
+    let start = now_ms();
+    let (ra, rb) = join(a.read(), b.read()).await;
+    println!("   results {:?} / {:?}", ra, rb);
+    println!(
+        "   took {} ms, not 135: both conversions overlapped",
+        now_ms() - start
+    );
+
+    // 2. Timeouts
+    println!("\n2. A hung sensor with with_timeout:");
+    let mut hung = MockSensor {
+        id: 9,
+        conversion_ms: 75,
+        base: 0.0,
+        slope_per_s: 0.0,
+        hung: true,
+    };
+    let start = now_ms();
+    match with_timeout(Duration::from_millis(100), hung.read()).await {
+        Ok(r) => println!("   got {:?}", r),
+        Err(_) => println!("   gave up after {} ms", now_ms() - start),
+    }
+
+    // 3. Spawned tasks
+    println!("\n3. Tasks: two sensors, a monitor and a status LED for 1.5 s:");
+    let t0 = now_ms();
+    println!("   [{:>4} ms] spawning", t0);
+    spawner.spawn(sensor_task(a, 100)).unwrap();
+    spawner
+        .spawn(sensor_task(
+            MockSensor {
+                id: 1,
+                conversion_ms: 60,
+                base: 22.0,
+                // Heats up 6 C per second, crosses the limit, then keeps going
+                slope_per_s: 6.0,
+                hung: false,
+            },
+            100,
+        ))
+        .unwrap();
+    spawner
+        .spawn(monitor_task(AlertLimits {
+            raise_above: 28.0,
+            clear_below: 27.0,
+        }))
+        .unwrap();
+    spawner.spawn(blink_task(MockLed { on: false })).unwrap();
+    Timer::after_millis(1500).await;
+
+    // 4. Summary
+    println!("\n4. Summary:");
+    println!("   LED toggles:      {}", TOGGLES.load(Ordering::Relaxed));
+    println!("   dropped readings: {}", DROPPED.load(Ordering::Relaxed));
+    println!("   one OS thread ran every task; on an MCU the same code runs with no OS");
+
+    println!("\n=== End of Embassy Async Embedded Examples ===");
+    // The executor never returns; end the host process explicitly
+    std::process::exit(0);
+}
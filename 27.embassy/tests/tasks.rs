@@ -0,0 +1,129 @@
+use embassy_executor::{Executor, Spawner};
+use embassy_lesson::{AlertLimits, Led, LedMode, SensorError, TemperatureSensor};
+use embassy_time::{Duration, Instant, Ticker, Timer};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::thread;
+
+// Which sensor delivered a reading, in the order they arrived
+static ORDER: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+static TOGGLES: AtomicU32 = AtomicU32::new(0);
+
+// Same shape as the demo's mocks: a read costs `conversion_ms`
+struct MockSensor {
+    id: u8,
+    conversion_ms: u64,
+}
+
+impl TemperatureSensor for MockSensor {
+    async fn read(&mut self) -> Result<f32, SensorError> {
+        Timer::after_millis(self.conversion_ms).await;
+        Ok(20.0 + self.id as f32)
+    }
+}
+
+struct MockLed {
+    on: bool,
+}
+
+impl Led for MockLed {
+    fn set(&mut self, on: bool) {
+        if on != self.on {
+            TOGGLES.fetch_add(1, Ordering::Relaxed);
+        }
+        self.on = on;
+    }
+}
+
+#[embassy_executor::task(pool_size = 2)]
+async fn sensor_task(mut sensor: MockSensor, period_ms: u64) {
+    let mut ticker = Ticker::every(Duration::from_millis(period_ms));
+    loop {
+        if sensor.read().await.is_ok() {
+            ORDER.lock().unwrap().push(sensor.id);
+        }
+        ticker.next().await;
+    }
+}
+
+#[embassy_executor::task]
+async fn blink_task(mut led: MockLed, mode: LedMode) {
+    let (on_ms, off_ms) = mode.pattern();
+    loop {
+        led.set(true);
+        Timer::after_millis(on_ms).await;
+        led.set(false);
+        Timer::after_millis(off_ms).await;
+    }
+}
+
+// Reports back to the test thread once `run_ms` have passed
+#[embassy_executor::task]
+async fn stop_after(run_ms: u64, done: Sender<u64>) {
+    let start = Instant::now();
+    Timer::after_millis(run_ms).await;
+    done.send(start.elapsed().as_millis()).unwrap();
+}
+
+#[test]
+fn tasks_interleave_on_one_executor() {
+    let (done, finished) = mpsc::channel();
+
+    // The executor never returns, so it gets its own thread; the test
+    // thread only waits for `stop_after`
+    thread::spawn(move || {
+        let executor: &'static mut Executor = Box::leak(Box::new(Executor::new()));
+        executor.run(|spawner: Spawner| {
+            // Sensor 1 converts faster, so it reports first every period
+            spawner
+                .spawn(sensor_task(
+                    MockSensor {
+                        id: 0,
+                        conversion_ms: 20,
+                    },
+                    100,
+                ))
+                .unwrap();
+            spawner
+                .spawn(sensor_task(
+                    MockSensor {
+                        id: 1,
+                        conversion_ms: 10,
+                    },
+                    100,
+                ))
+                .unwrap();
+            spawner
+                .spawn(blink_task(MockLed { on: false }, LedMode::Alert))
+                .unwrap();
+            spawner.spawn(stop_after(260, done)).unwrap();
+        });
+    });
+
+    let elapsed = finished
+        .recv_timeout(std::time::Duration::from_secs(5))
+        .unwrap();
+    let order = ORDER.lock().unwrap().clone();
+    let toggles = TOGGLES.load(Ordering::Relaxed);
+    assert!(elapsed >= 260, "stopped after {} ms", elapsed);
+
+    // Readings at 10, 20, 110, 120, 210 and 220 ms
+    assert_eq!(order, [1, 0, 1, 0, 1, 0]);
+    // Alert blinks 100 ms on / 100 ms off: on at 0 and 200, off at 100
+    assert_eq!(toggles, 3);
+}
+
+#[test]
+fn alert_limits_have_hysteresis() {
+    let limits = AlertLimits {
+        raise_above: 28.0,
+        clear_below: 27.0,
+    };
+    assert!(!limits.next(false, 28.0));
+    assert!(limits.next(false, 28.1));
+    // Between the limits the state is kept either way
+    assert!(limits.next(true, 27.5));
+    assert!(!limits.next(false, 27.5));
+    assert!(!limits.next(true, 26.9));
+}
@@ -0,0 +1,106 @@
+// Fixed-block memory pools
+//
+// `Pool` is a single block size; `SizeClasses` combines three pools into an
+// allocator that can serve a whole program, and can be installed with
+// `#[global_allocator]`. Everything lives in statically sized arrays: the
+// worst-case memory use is known at link time, and running out is a
+// reported event rather than the heap silently growing.
+
+#![no_std]
+
+pub mod pool;
+
+pub use pool::{Pool, Stats};
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::ptr;
+
+pub struct SizeClasses {
+    pub small: Pool<32, 1024>,
+    pub medium: Pool<256, 256>,
+    pub large: Pool<4096, 32>,
+}
+
+impl Default for SizeClasses {
+    fn default() -> Self {
+        SizeClasses::new()
+    }
+}
+
+impl SizeClasses {
+    pub const fn new() -> SizeClasses {
+        SizeClasses {
+            small: Pool::new(),
+            medium: Pool::new(),
+            large: Pool::new(),
+        }
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        [
+            self.small.block_size() * self.small.capacity(),
+            self.medium.block_size() * self.medium.capacity(),
+            self.large.block_size() * self.large.capacity(),
+        ]
+        .iter()
+        .sum()
+    }
+
+    // Block size that currently backs `ptr`
+    fn block_of(&self, ptr: *const u8) -> usize {
+        if self.small.contains(ptr) {
+            self.small.block_size()
+        } else if self.medium.contains(ptr) {
+            self.medium.block_size()
+        } else {
+            self.large.block_size()
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for SizeClasses {
+    // Smallest class that fits; if it is exhausted, spill into the next one
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if self.small.fits(layout) {
+            let p = self.small.take();
+            if !p.is_null() {
+                return p;
+            }
+        }
+        if self.medium.fits(layout) {
+            let p = self.medium.take();
+            if !p.is_null() {
+                return p;
+            }
+        }
+        if self.large.fits(layout) {
+            return self.large.take();
+        }
+        self.large.record_failure();
+        ptr::null_mut()
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
+        if self.small.contains(ptr) {
+            self.small.give(ptr);
+        } else if self.medium.contains(ptr) {
+            self.medium.give(ptr);
+        } else {
+            self.large.give(ptr);
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let block = self.block_of(ptr);
+        if new_size <= block {
+            return ptr;
+        }
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        let new = self.alloc(new_layout);
+        if !new.is_null() {
+            ptr::copy_nonoverlapping(ptr, new, layout.size());
+            self.dealloc(ptr, layout);
+        }
+        new
+    }
+}
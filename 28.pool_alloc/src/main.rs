@@ -0,0 +1,199 @@
+use pool_alloc::{Pool, SizeClasses};
+use std::alloc::{GlobalAlloc, Layout};
+use std::sync::Arc;
+use std::thread;
+
+// Not under `cargo test`: libtest allocates more than the pools can serve
+#[cfg(feature = "global")]
+#[cfg_attr(not(test), global_allocator)]
+static GLOBAL: SizeClasses = SizeClasses::new();
+
+static SMALL: Pool<64, 8> = Pool::new();
+static SHARED: Pool<32, 64> = Pool::new();
+
+fn main() {
+    println!("=== Fixed-Block Memory Pool ===\n");
+
+    // 1. Allocating and freeing
+    println!("1. Pool<64, 8>:");
+    println!(
+        "   {} blocks of {} bytes, max alignment {}",
+        SMALL.capacity(),
+        SMALL.block_size(),
+        SMALL.max_align()
+    );
+    let layout = Layout::new::<[u32; 12]>();
+    unsafe {
+        let a = SMALL.alloc(layout);
+        let b = SMALL.alloc(layout);
+        a.cast::<[u32; 12]>().write([7; 12]);
+        println!(
+            "   a = {:p}, b = {:p} ({} bytes apart)",
+            a,
+            b,
+            b as usize - a as usize
+        );
+        println!("   a[0] = {}", (*a.cast::<[u32; 12]>())[0]);
+        SMALL.dealloc(a, layout);
+        let c = SMALL.alloc(layout);
+        println!(
+            "   after freeing a, the next block is {:p} (reused: {})",
+            c,
+            c == a
+        );
+        SMALL.dealloc(b, layout);
+        SMALL.dealloc(c, layout);
+    }
+    println!("   {:?}", SMALL.stats());
+
+    // 2. Requests the pool cannot serve
+    println!("\n2. Refused requests:");
+    for (name, layout) in [
+        ("65 bytes", Layout::from_size_align(65, 1).unwrap()),
+        ("align 128", Layout::from_size_align(16, 128).unwrap()),
+    ] {
+        let p = unsafe { SMALL.alloc(layout) };
+        println!(
+            "   {:<10} -> {}",
+            name,
+            if p.is_null() { "null" } else { "allocated (!)" }
+        );
+    }
+
+    // 3. Exhaustion is a null pointer, not a crash
+    println!("\n3. Exhaustion:");
+    let layout = Layout::new::<u64>();
+    let mut held = Vec::new();
+    loop {
+        let p = unsafe { SMALL.alloc(layout) };
+        if p.is_null() {
+            break;
+        }
+        held.push(p);
+    }
+    println!("   got {} blocks, then null", held.len());
+    for p in held.drain(..) {
+        unsafe { SMALL.dealloc(p, layout) };
+    }
+    println!("   {:?}", SMALL.stats());
+
+    // 4. Concurrent use
+    println!("\n4. Four threads, 20k alloc/free pairs each:");
+    // Keep the run short under Miri, which is far slower than native code
+    let rounds = if cfg!(miri) { 200 } else { 20_000 };
+    let corrupted = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let handles: Vec<_> = (0..4u8)
+        .map(|id| {
+            let corrupted = Arc::clone(&corrupted);
+            thread::spawn(move || {
+                let layout = Layout::new::<[u8; 32]>();
+                for _ in 0..rounds {
+                    unsafe {
+                        let p = SHARED.alloc(layout);
+                        if p.is_null() {
+                            continue;
+                        }
+                        // If two threads ever got the same block, the
+                        // pattern would be overwritten before we check it
+                        p.write_bytes(id, 32);
+                        thread::yield_now();
+                        if (0..32).any(|i| *p.add(i) != id) {
+                            corrupted.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        }
+                        SHARED.dealloc(p, layout);
+                    }
+                }
+            })
+        })
+        .collect();
+    for h in handles {
+        h.join().unwrap();
+    }
+    let stats = SHARED.stats();
+    println!("   {:?}", stats);
+    println!(
+        "   corrupted blocks: {}, leaked: {}",
+        corrupted.load(std::sync::atomic::Ordering::Relaxed),
+        stats.in_use
+    );
+
+    // 5. Size classes
+    println!("\n5. Size classes:");
+    let classes = SizeClasses::new();
+    println!(
+        "   {} KiB total in three pools",
+        classes.total_bytes() / 1024
+    );
+    unsafe {
+        let tiny = classes.alloc(Layout::from_size_align(12, 4).unwrap());
+        let mid = classes.alloc(Layout::from_size_align(200, 8).unwrap());
+        let big = classes.alloc(Layout::from_size_align(3000, 8).unwrap());
+        let huge = classes.alloc(Layout::from_size_align(5000, 8).unwrap());
+        println!(
+            "   12 B -> small: {}, 200 B -> medium: {}, 3000 B -> large: {}, 5000 B -> {}",
+            classes.small.contains(tiny),
+            classes.medium.contains(mid),
+            classes.large.contains(big),
+            if huge.is_null() {
+                "null"
+            } else {
+                "allocated (!)"
+            }
+        );
+        // Growing past a block moves the data to the next class
+        let grown = classes.realloc(tiny, Layout::from_size_align(12, 4).unwrap(), 100);
+        println!(
+            "   realloc 12 -> 100 B moved to medium: {}",
+            classes.medium.contains(grown)
+        );
+        classes.dealloc(grown, Layout::from_size_align(100, 4).unwrap());
+        classes.dealloc(mid, Layout::from_size_align(200, 8).unwrap());
+        classes.dealloc(big, Layout::from_size_align(3000, 8).unwrap());
+    }
+    println!("   small  {:?}", classes.small.stats());
+    println!("   medium {:?}", classes.medium.stats());
+    println!("   large  {:?}", classes.large.stats());
+
+    // 6. As the global allocator
+    println!("\n6. Global allocator:");
+    global_demo();
+
+    println!("\n=== End of Fixed-Block Memory Pool Examples ===");
+}
+
+#[cfg(feature = "global")]
+fn global_demo() {
+    let before = (
+        GLOBAL.small.stats(),
+        GLOBAL.medium.stats(),
+        GLOBAL.large.stats(),
+    );
+    let names: Vec<String> = (0..50).map(|i| format!("sensor-{:02}", i)).collect();
+    let joined = names.join(",");
+    println!(
+        "   built {} strings, {} bytes joined",
+        names.len(),
+        joined.len()
+    );
+    for (name, (b, a)) in [
+        ("small ", (before.0, GLOBAL.small.stats())),
+        ("medium", (before.1, GLOBAL.medium.stats())),
+        ("large ", (before.2, GLOBAL.large.stats())),
+    ] {
+        println!(
+            "   {} +{} allocations, in use {}, peak {}",
+            name,
+            a.allocations - b.allocations,
+            a.in_use,
+            a.peak
+        );
+    }
+    // A single allocation larger than 4 KiB would now abort the process via
+    // `handle_alloc_error`: exactly the hard limit a small MCU heap has
+}
+
+#[cfg(not(feature = "global"))]
+fn global_demo() {
+    println!("   run with `cargo run --features global` to serve every");
+    println!("   allocation in this program from SizeClasses");
+}
@@ -0,0 +1,231 @@
+// A fixed-block pool over a static byte array
+//
+// BLOCKS blocks of BLOCK bytes each. Free blocks form an intrusive singly
+// linked list: the first word of a free block holds the index of the next
+// one, so the free list costs no memory of its own. Blocks never handed out
+// yet are taken from a high-water mark, which lets `new` be a `const fn`
+// with nothing to initialise at startup.
+//
+// The free-list head stores index + 1 so that 0 means "empty": a fresh pool
+// is all zero bytes and a `static` one lands in .bss, costing no flash.
+//
+// A spinlock guards the list. Allocation and free are O(1) and there is no
+// fragmentation: any free block satisfies any request that fits.
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::UnsafeCell;
+use core::hint;
+use core::mem::size_of;
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+#[repr(C, align(64))]
+struct Storage<const BLOCK: usize, const BLOCKS: usize>([[u8; BLOCK]; BLOCKS]);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub allocations: usize,
+    pub frees: usize,
+    // Requests refused because the pool was full or the layout did not fit
+    pub failures: usize,
+    pub in_use: usize,
+    pub peak: usize,
+}
+
+#[derive(Default)]
+struct Counters {
+    allocations: AtomicUsize,
+    frees: AtomicUsize,
+    failures: AtomicUsize,
+    in_use: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl Counters {
+    const fn new() -> Counters {
+        Counters {
+            allocations: AtomicUsize::new(0),
+            frees: AtomicUsize::new(0),
+            failures: AtomicUsize::new(0),
+            in_use: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        }
+    }
+}
+
+pub struct Pool<const BLOCK: usize, const BLOCKS: usize> {
+    storage: UnsafeCell<Storage<BLOCK, BLOCKS>>,
+    lock: AtomicBool,
+    // Both only touched while `lock` is held
+    free_head: UnsafeCell<usize>,
+    untouched: UnsafeCell<usize>,
+    counters: Counters,
+}
+
+// SAFETY: all interior mutation happens under `lock` or through atomics,
+// and a block is owned by exactly one caller between alloc and dealloc
+unsafe impl<const BLOCK: usize, const BLOCKS: usize> Sync for Pool<BLOCK, BLOCKS> {}
+
+impl<const BLOCK: usize, const BLOCKS: usize> Default for Pool<BLOCK, BLOCKS> {
+    fn default() -> Self {
+        Pool::new()
+    }
+}
+
+impl<const BLOCK: usize, const BLOCKS: usize> Pool<BLOCK, BLOCKS> {
+    pub const fn new() -> Pool<BLOCK, BLOCKS> {
+        // Evaluated at compile time when used in a `static`
+        assert!(
+            BLOCK >= size_of::<usize>(),
+            "blocks must hold a free-list link"
+        );
+        Pool {
+            storage: UnsafeCell::new(Storage([[0; BLOCK]; BLOCKS])),
+            lock: AtomicBool::new(false),
+            free_head: UnsafeCell::new(0),
+            untouched: UnsafeCell::new(0),
+            counters: Counters::new(),
+        }
+    }
+
+    pub const fn block_size(&self) -> usize {
+        BLOCK
+    }
+
+    pub const fn capacity(&self) -> usize {
+        BLOCKS
+    }
+
+    // Largest alignment every block satisfies: the storage is 64-aligned
+    // and blocks sit at multiples of BLOCK
+    pub const fn max_align(&self) -> usize {
+        let lowest_bit = BLOCK & BLOCK.wrapping_neg();
+        if lowest_bit < 64 {
+            lowest_bit
+        } else {
+            64
+        }
+    }
+
+    pub fn fits(&self, layout: Layout) -> bool {
+        layout.size() <= BLOCK && layout.align() <= self.max_align()
+    }
+
+    pub fn contains(&self, ptr: *const u8) -> bool {
+        let base = self.base() as usize;
+        (ptr as usize).wrapping_sub(base) < BLOCK * BLOCKS
+    }
+
+    pub fn stats(&self) -> Stats {
+        let c = &self.counters;
+        Stats {
+            allocations: c.allocations.load(Ordering::Relaxed),
+            frees: c.frees.load(Ordering::Relaxed),
+            failures: c.failures.load(Ordering::Relaxed),
+            in_use: c.in_use.load(Ordering::Relaxed),
+            peak: c.peak.load(Ordering::Relaxed),
+        }
+    }
+
+    fn base(&self) -> *mut u8 {
+        self.storage.get().cast::<u8>()
+    }
+
+    fn block(&self, index: usize) -> *mut u8 {
+        // SAFETY: callers pass index < BLOCKS, so this stays in `storage`
+        unsafe { self.base().add(index * BLOCK) }
+    }
+
+    fn with_lock<R>(&self, f: impl FnOnce() -> R) -> R {
+        while self
+            .lock
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            hint::spin_loop();
+        }
+        let result = f();
+        self.lock.store(false, Ordering::Release);
+        result
+    }
+
+    // Take one block, or null if the pool is exhausted
+    pub fn take(&self) -> *mut u8 {
+        let index = self.with_lock(|| {
+            // SAFETY: the lock is held, so we are the only user of these cells
+            unsafe {
+                let head = *self.free_head.get();
+                if head != 0 {
+                    let index = head - 1;
+                    *self.free_head.get() = self.block(index).cast::<usize>().read_unaligned();
+                    return Some(index);
+                }
+                let next = *self.untouched.get();
+                if next < BLOCKS {
+                    *self.untouched.get() = next + 1;
+                    return Some(next);
+                }
+                None
+            }
+        });
+
+        let c = &self.counters;
+        match index {
+            Some(index) => {
+                c.allocations.fetch_add(1, Ordering::Relaxed);
+                let in_use = c.in_use.fetch_add(1, Ordering::Relaxed) + 1;
+                c.peak.fetch_max(in_use, Ordering::Relaxed);
+                self.block(index)
+            }
+            None => {
+                c.failures.fetch_add(1, Ordering::Relaxed);
+                ptr::null_mut()
+            }
+        }
+    }
+
+    /// Return a block obtained from `take`
+    ///
+    /// # Safety
+    /// `ptr` must come from `take` on this pool and not have been given back
+    pub unsafe fn give(&self, ptr: *mut u8) {
+        debug_assert!(self.contains(ptr));
+        let index = (ptr as usize - self.base() as usize) / BLOCK;
+        self.with_lock(|| {
+            // SAFETY: the lock is held and the caller owns the block
+            unsafe {
+                ptr.cast::<usize>().write_unaligned(*self.free_head.get());
+                *self.free_head.get() = index + 1;
+            }
+        });
+        self.counters.frees.fetch_add(1, Ordering::Relaxed);
+        self.counters.in_use.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_failure(&self) {
+        self.counters.failures.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+unsafe impl<const BLOCK: usize, const BLOCKS: usize> GlobalAlloc for Pool<BLOCK, BLOCKS> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if !self.fits(layout) {
+            self.record_failure();
+            return ptr::null_mut();
+        }
+        self.take()
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
+        self.give(ptr);
+    }
+
+    // Growing or shrinking within one block needs no copy
+    unsafe fn realloc(&self, ptr: *mut u8, _layout: Layout, new_size: usize) -> *mut u8 {
+        if new_size <= BLOCK {
+            return ptr;
+        }
+        self.record_failure();
+        ptr::null_mut()
+    }
+}
@@ -0,0 +1,89 @@
+// Run with `cargo test --features global`. SizeClasses serves every
+// allocation in this binary, so it has its own `main` instead of libtest
+// and checks with plain asserts.
+
+use pool_alloc::{SizeClasses, Stats};
+use std::collections::HashMap;
+
+#[global_allocator]
+static GLOBAL: SizeClasses = SizeClasses::new();
+
+fn stats() -> [Stats; 3] {
+    [
+        GLOBAL.small.stats(),
+        GLOBAL.medium.stats(),
+        GLOBAL.large.stats(),
+    ]
+}
+
+fn allocations(before: &[Stats; 3], after: &[Stats; 3]) -> [usize; 3] {
+    [0, 1, 2].map(|i| after[i].allocations - before[i].allocations)
+}
+
+fn in_use() -> usize {
+    stats().iter().map(|s| s.in_use).sum()
+}
+
+fn each_size_goes_to_the_smallest_class_that_fits() {
+    let before = stats();
+    let small = Box::new([1u8; 24]);
+    let medium = Box::new([2u8; 200]);
+    let large = Box::new([3u8; 3000]);
+    let after = stats();
+    assert_eq!(allocations(&before, &after), [1, 1, 1]);
+    assert!(GLOBAL.small.contains(small.as_ptr()));
+    assert!(GLOBAL.medium.contains(medium.as_ptr()));
+    assert!(GLOBAL.large.contains(large.as_ptr()));
+    assert_eq!((small[23], medium[199], large[2999]), (1, 2, 3));
+}
+
+fn collections_are_served_and_freed_by_the_pools() {
+    let live = in_use();
+    {
+        let names: Vec<String> = (0..50).map(|i| format!("sensor-{:02}", i)).collect();
+        let joined = names.join(",");
+        assert_eq!(joined.len(), 50 * 9 + 49);
+        let mut readings: HashMap<&str, Vec<f32>> = HashMap::new();
+        for (n, name) in names.iter().enumerate() {
+            readings.entry(name).or_default().push(n as f32);
+        }
+        assert_eq!(readings["sensor-07"], [7.0]);
+        assert!(in_use() > live + 50);
+    }
+    assert_eq!(in_use(), live);
+}
+
+fn growing_a_vec_moves_it_up_the_classes() {
+    let mut v: Vec<u8> = Vec::with_capacity(8);
+    v.extend(0..8);
+    assert!(GLOBAL.small.contains(v.as_ptr()));
+    v.extend(8..=255);
+    assert!(GLOBAL.medium.contains(v.as_ptr()));
+    v.extend((0..3000).map(|i| i as u8));
+    assert!(GLOBAL.large.contains(v.as_ptr()));
+    // The copies kept the contents
+    assert!(v[..256].iter().enumerate().all(|(i, &b)| b == i as u8));
+}
+
+fn main() {
+    let checks: [(&str, fn()); 3] = [
+        (
+            "each_size_goes_to_the_smallest_class_that_fits",
+            each_size_goes_to_the_smallest_class_that_fits,
+        ),
+        (
+            "collections_are_served_and_freed_by_the_pools",
+            collections_are_served_and_freed_by_the_pools,
+        ),
+        (
+            "growing_a_vec_moves_it_up_the_classes",
+            growing_a_vec_moves_it_up_the_classes,
+        ),
+    ];
+    for (name, check) in checks {
+        check();
+        println!("test {} ... ok", name);
+    }
+    let failures: usize = stats().iter().map(|s| s.failures).sum();
+    assert_eq!(failures, 0);
+}
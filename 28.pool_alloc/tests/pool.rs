@@ -0,0 +1,209 @@
+use pool_alloc::{Pool, SizeClasses, Stats};
+use std::alloc::{GlobalAlloc, Layout};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+fn layout(size: usize, align: usize) -> Layout {
+    Layout::from_size_align(size, align).unwrap()
+}
+
+#[test]
+fn blocks_are_distinct_aligned_and_reused_last_freed_first() {
+    let pool: Pool<64, 8> = Pool::new();
+    let l = layout(48, 8);
+    unsafe {
+        let a = pool.alloc(l);
+        let b = pool.alloc(l);
+        assert_eq!(b as usize - a as usize, 64);
+        assert_eq!(a as usize % 64, 0);
+        assert!(pool.contains(a) && pool.contains(b));
+
+        pool.dealloc(a, l);
+        pool.dealloc(b, l);
+        assert_eq!(pool.alloc(l), b);
+        assert_eq!(pool.alloc(l), a);
+        pool.dealloc(a, l);
+        pool.dealloc(b, l);
+    }
+}
+
+#[test]
+fn data_survives_until_freed() {
+    let pool: Pool<64, 4> = Pool::new();
+    let l = Layout::new::<[u32; 12]>();
+    unsafe {
+        let ptrs: Vec<*mut u8> = (0..4).map(|_| pool.alloc(l)).collect();
+        for (i, p) in ptrs.iter().enumerate() {
+            p.cast::<[u32; 12]>().write([i as u32; 12]);
+        }
+        // Freeing one writes the free-list link into it, not into the others
+        pool.dealloc(ptrs[1], l);
+        for i in [0, 2, 3] {
+            assert_eq!(*ptrs[i].cast::<[u32; 12]>(), [i as u32; 12]);
+        }
+        for i in [0, 2, 3] {
+            pool.dealloc(ptrs[i], l);
+        }
+    }
+}
+
+#[test]
+fn alignment_follows_the_block_size() {
+    assert_eq!(Pool::<8, 1>::new().max_align(), 8);
+    assert_eq!(Pool::<24, 1>::new().max_align(), 8);
+    assert_eq!(Pool::<96, 1>::new().max_align(), 32);
+    assert_eq!(Pool::<128, 1>::new().max_align(), 64);
+
+    let pool: Pool<96, 4> = Pool::new();
+    assert!(pool.fits(layout(96, 32)));
+    assert!(!pool.fits(layout(96, 64)));
+    assert!(!pool.fits(layout(97, 1)));
+}
+
+#[test]
+fn refused_layouts_and_exhaustion_count_as_failures() {
+    let pool: Pool<64, 8> = Pool::new();
+    unsafe {
+        assert!(pool.alloc(layout(65, 1)).is_null());
+        assert!(pool.alloc(layout(16, 128)).is_null());
+
+        let l = Layout::new::<u64>();
+        let held: Vec<*mut u8> = (0..8).map(|_| pool.alloc(l)).collect();
+        assert!(held.iter().all(|p| !p.is_null()));
+        assert_eq!(held.iter().collect::<HashSet<_>>().len(), 8);
+        assert!(pool.alloc(l).is_null());
+
+        assert_eq!(
+            pool.stats(),
+            Stats {
+                allocations: 8,
+                frees: 0,
+                failures: 3,
+                in_use: 8,
+                peak: 8
+            }
+        );
+        for p in held {
+            pool.dealloc(p, l);
+        }
+        // Usable again after everything was returned
+        let p = pool.alloc(l);
+        assert!(!p.is_null());
+        pool.dealloc(p, l);
+    }
+    let stats = pool.stats();
+    assert_eq!((stats.in_use, stats.peak, stats.frees), (0, 8, 9));
+}
+
+#[test]
+fn realloc_within_a_block_keeps_the_pointer() {
+    let pool: Pool<64, 2> = Pool::new();
+    let l = layout(8, 8);
+    unsafe {
+        let p = pool.alloc(l);
+        assert_eq!(pool.realloc(p, l, 64), p);
+        assert!(pool.realloc(p, l, 65).is_null());
+        pool.dealloc(p, layout(64, 8));
+    }
+    assert_eq!(pool.stats().failures, 1);
+}
+
+static SHARED: Pool<32, 64> = Pool::new();
+
+#[test]
+fn threads_never_share_a_block() {
+    let rounds = if cfg!(miri) { 50 } else { 20_000 };
+    let corrupted = AtomicUsize::new(0);
+    thread::scope(|s| {
+        for id in 1..=4u8 {
+            let corrupted = &corrupted;
+            s.spawn(move || {
+                let l = layout(32, 1);
+                for _ in 0..rounds {
+                    unsafe {
+                        let p = SHARED.alloc(l);
+                        assert!(!p.is_null());
+                        p.write_bytes(id, 32);
+                        thread::yield_now();
+                        if (0..32).any(|i| *p.add(i) != id) {
+                            corrupted.fetch_add(1, Ordering::Relaxed);
+                        }
+                        SHARED.dealloc(p, l);
+                    }
+                }
+            });
+        }
+    });
+    assert_eq!(corrupted.load(Ordering::Relaxed), 0);
+    let stats = SHARED.stats();
+    assert_eq!(stats.allocations, 4 * rounds);
+    assert_eq!(stats.frees, 4 * rounds);
+    assert_eq!(stats.in_use, 0);
+    assert!(stats.peak <= 4);
+}
+
+#[test]
+fn size_classes_pick_the_smallest_pool_that_fits() {
+    let classes = SizeClasses::new();
+    assert_eq!(classes.total_bytes(), 32 * 1024 + 256 * 256 + 4096 * 32);
+    unsafe {
+        let tiny = classes.alloc(layout(12, 4));
+        let mid = classes.alloc(layout(200, 8));
+        let big = classes.alloc(layout(3000, 8));
+        assert!(classes.small.contains(tiny));
+        assert!(classes.medium.contains(mid));
+        assert!(classes.large.contains(big));
+        assert!(classes.alloc(layout(5000, 8)).is_null());
+        assert_eq!(classes.large.stats().failures, 1);
+
+        classes.dealloc(tiny, layout(12, 4));
+        classes.dealloc(mid, layout(200, 8));
+        classes.dealloc(big, layout(3000, 8));
+    }
+    for stats in [
+        classes.small.stats(),
+        classes.medium.stats(),
+        classes.large.stats(),
+    ] {
+        assert_eq!(stats.in_use, 0);
+    }
+}
+
+#[test]
+fn a_full_class_spills_into_the_next() {
+    let classes = SizeClasses::new();
+    let l = layout(16, 8);
+    unsafe {
+        let small: Vec<*mut u8> = (0..classes.small.capacity())
+            .map(|_| classes.alloc(l))
+            .collect();
+        assert!(small.iter().all(|&p| classes.small.contains(p)));
+        let spilled = classes.alloc(l);
+        assert!(classes.medium.contains(spilled));
+        // Freed memory goes back to the pool it came from
+        classes.dealloc(spilled, l);
+        assert_eq!(classes.medium.stats().in_use, 0);
+        for p in small {
+            classes.dealloc(p, l);
+        }
+    }
+    assert_eq!(classes.small.stats().in_use, 0);
+}
+
+#[test]
+fn realloc_moves_data_to_a_larger_class() {
+    let classes = SizeClasses::new();
+    unsafe {
+        let p = classes.alloc(layout(12, 4));
+        p.copy_from_nonoverlapping(b"hello, pools".as_ptr(), 12);
+        // Still fits its 32-byte block: no move
+        assert_eq!(classes.realloc(p, layout(12, 4), 32), p);
+
+        let grown = classes.realloc(p, layout(32, 4), 100);
+        assert!(classes.medium.contains(grown));
+        assert_eq!(std::slice::from_raw_parts(grown, 12), b"hello, pools");
+        assert_eq!(classes.small.stats().in_use, 0);
+        classes.dealloc(grown, layout(100, 4));
+    }
+}
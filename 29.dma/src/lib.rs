@@ -0,0 +1,186 @@
+// DMA-style double buffering, simulated with a thread
+//
+// A "peripheral" thread plays the DMA controller: it fills one buffer with
+// ADC samples at a fixed rate while the application processes another.
+// Ownership of each buffer moves explicitly between the two sides through
+// channels, so at any moment a buffer belongs to exactly one of them:
+//
+//   peripheral --(filled)--> application --(free)--> peripheral
+//
+// When the peripheral finishes a buffer and no free one is waiting, that is
+// an overrun: the next block of samples has nowhere to go and is lost.
+
+use std::ops::Deref;
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TryRecvError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy)]
+pub struct DmaConfig {
+    pub block_len: usize,
+    // 2 for double buffering, 3 for triple, ...
+    pub buffers: usize,
+    // Time the peripheral takes to fill one block
+    pub block_period: Duration,
+}
+
+#[derive(Debug)]
+pub struct Block {
+    // Sequence number of the first sample, for loss detection
+    pub first_seq: u64,
+    pub samples: Vec<u16>,
+}
+
+// What the peripheral saw
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PeripheralStats {
+    pub blocks_filled: u64,
+    pub overruns: u64,
+    pub samples_dropped: u64,
+}
+
+// What the application saw
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ConsumerStats {
+    pub blocks: u64,
+    pub samples: u64,
+    // Samples missing from the sequence, found independently of the
+    // peripheral's own counters
+    pub samples_missing: u64,
+}
+
+// Simulated 12-bit ADC reading for sample `seq`
+pub fn adc_sample(seq: u64) -> u16 {
+    let phase = seq as f64 * 0.05;
+    (2048.0 + 1500.0 * phase.sin()) as u16
+}
+
+fn peripheral(
+    config: DmaConfig,
+    blocks: u64,
+    filled: SyncSender<Block>,
+    free: Receiver<Block>,
+) -> PeripheralStats {
+    let mut stats = PeripheralStats::default();
+    let mut seq = 0u64;
+    let mut deadline = Instant::now();
+    let Ok(mut current) = free.recv() else {
+        return stats;
+    };
+
+    while stats.blocks_filled + stats.overruns < blocks {
+        // Filling takes a fixed time no matter what the application does
+        deadline += config.block_period;
+        current.first_seq = seq;
+        for (i, slot) in current.samples.iter_mut().enumerate() {
+            *slot = adc_sample(seq + i as u64);
+        }
+        seq += config.block_len as u64;
+        thread::sleep(deadline.saturating_duration_since(Instant::now()));
+
+        // Swap: hand the full buffer over and take a free one. Only one
+        // buffer is ever in transit, so this send never blocks.
+        loop {
+            match free.try_recv() {
+                Ok(next) => {
+                    stats.blocks_filled += 1;
+                    if filled.send(std::mem::replace(&mut current, next)).is_err() {
+                        return stats;
+                    }
+                    break;
+                }
+                Err(TryRecvError::Empty) => {
+                    // Overrun: keep the unread buffer and lose the samples
+                    // that arrive during the next period
+                    stats.overruns += 1;
+                    stats.samples_dropped += config.block_len as u64;
+                    seq += config.block_len as u64;
+                    deadline += config.block_period;
+                    thread::sleep(deadline.saturating_duration_since(Instant::now()));
+                    if stats.blocks_filled + stats.overruns >= blocks {
+                        return stats;
+                    }
+                }
+                Err(TryRecvError::Disconnected) => return stats,
+            }
+        }
+    }
+    stats
+}
+
+pub struct Consumer {
+    filled: Receiver<Block>,
+    free: SyncSender<Block>,
+    expected_seq: u64,
+    stats: ConsumerStats,
+}
+
+// A filled buffer on loan to the application; dropping it hands the buffer
+// back to the peripheral, so it cannot be forgotten
+pub struct Filled<'a> {
+    block: Option<Block>,
+    free: &'a SyncSender<Block>,
+}
+
+impl Deref for Filled<'_> {
+    type Target = Block;
+
+    fn deref(&self) -> &Block {
+        self.block.as_ref().unwrap()
+    }
+}
+
+impl Drop for Filled<'_> {
+    fn drop(&mut self) {
+        if let Some(block) = self.block.take() {
+            // The peripheral may already have finished
+            let _ = self.free.send(block);
+        }
+    }
+}
+
+impl Consumer {
+    // Wait for the next filled buffer; None once the peripheral has stopped
+    pub fn acquire(&mut self) -> Option<Filled<'_>> {
+        let block = self.filled.recv().ok()?;
+        if block.first_seq > self.expected_seq {
+            self.stats.samples_missing += block.first_seq - self.expected_seq;
+        }
+        self.expected_seq = block.first_seq + block.samples.len() as u64;
+        self.stats.blocks += 1;
+        self.stats.samples += block.samples.len() as u64;
+        Some(Filled {
+            block: Some(block),
+            free: &self.free,
+        })
+    }
+
+    pub fn stats(&self) -> ConsumerStats {
+        self.stats
+    }
+}
+
+// Start a peripheral producing `blocks` block periods' worth of samples
+pub fn start(config: DmaConfig, blocks: u64) -> (Consumer, JoinHandle<PeripheralStats>) {
+    let buffers = config.buffers.max(2);
+    let (filled_tx, filled_rx) = sync_channel(buffers);
+    let (free_tx, free_rx) = sync_channel(buffers);
+    for _ in 0..buffers {
+        let block = Block {
+            first_seq: 0,
+            samples: vec![0; config.block_len],
+        };
+        free_tx
+            .send(block)
+            .expect("channel has room for every buffer");
+    }
+
+    let handle = thread::spawn(move || peripheral(config, blocks, filled_tx, free_rx));
+    let consumer = Consumer {
+        filled: filled_rx,
+        free: free_tx,
+        expected_seq: 0,
+        stats: ConsumerStats::default(),
+    };
+    (consumer, handle)
+}
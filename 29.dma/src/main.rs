@@ -0,0 +1,99 @@
+use dma::{start, DmaConfig};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const PERIOD: Duration = Duration::from_millis(20);
+const BLOCKS: u64 = 40;
+
+// Run one scenario; `latency(i)` is how long processing block i takes
+fn scenario(name: &str, buffers: usize, latency: impl Fn(u64) -> Duration) {
+    let config = DmaConfig {
+        block_len: 64,
+        buffers,
+        block_period: PERIOD,
+    };
+    let (mut consumer, peripheral) = start(config, BLOCKS);
+    let mut i = 0;
+    let mut checksum = 0u64;
+    while let Some(block) = consumer.acquire() {
+        checksum += block.samples.iter().map(|s| *s as u64).sum::<u64>();
+        thread::sleep(latency(i));
+        i += 1;
+        // `block` is dropped here, returning the buffer to the peripheral
+    }
+    std::hint::black_box(checksum);
+    let p = peripheral.join().unwrap();
+    let c = consumer.stats();
+    println!(
+        "   {:<24} {:>2} bufs  blocks {:>3}  overruns {:>3}  missing {:>5}  {}",
+        name,
+        buffers,
+        c.blocks,
+        p.overruns,
+        c.samples_missing,
+        if c.samples_missing == 0 && p.overruns == 0 {
+            "no loss"
+        } else {
+            "LOSS"
+        }
+    );
+}
+
+fn ms(fraction: f64) -> Duration {
+    PERIOD.mul_f64(fraction)
+}
+
+fn main() {
+    println!("=== DMA Double Buffering ===\n");
+
+    // 1. One swap, step by step
+    println!("1. The swap protocol:");
+    let (mut consumer, peripheral) = start(
+        DmaConfig {
+            block_len: 8,
+            buffers: 2,
+            block_period: Duration::from_millis(5),
+        },
+        3,
+    );
+    let t0 = Instant::now();
+    while let Some(block) = consumer.acquire() {
+        println!(
+            "   [{:>2} ms] got block at seq {:>2}: {:?}",
+            t0.elapsed().as_millis(),
+            block.first_seq,
+            block.samples
+        );
+        // While we hold `block`, the peripheral is filling the other buffer
+    }
+    println!("   peripheral: {:?}", peripheral.join().unwrap());
+    println!("   application: {:?}", consumer.stats());
+
+    // 2. Steady processing time
+    println!(
+        "\n2. Constant processing time ({} ms period, {} blocks):",
+        PERIOD.as_millis(),
+        BLOCKS
+    );
+    scenario("50% of the period", 2, |_| ms(0.5));
+    scenario("70% of the period", 2, |_| ms(0.7));
+    scenario("150% of the period", 2, |_| ms(1.5));
+    scenario("150% of the period", 4, |_| ms(1.5));
+
+    // 3. Jitter: fast on average, occasionally slow
+    println!("\n3. Jittery processing (40% usually, 180% every 10th block):");
+    let jitter = |i: u64| if i % 10 == 9 { ms(1.8) } else { ms(0.4) };
+    scenario("double buffering", 2, jitter);
+    scenario("triple buffering", 3, jitter);
+
+    // 4. Burst: several slow blocks in a row
+    println!("\n4. Bursts (three blocks at 150% every 20 blocks):");
+    let burst = |i: u64| if i % 20 >= 17 { ms(1.5) } else { ms(0.3) };
+    scenario("triple buffering", 3, burst);
+    scenario("four buffers", 4, burst);
+
+    println!("\n   More buffers absorb latency spikes; only faster processing");
+    println!("   fixes an average that exceeds the period.");
+
+    println!("\n=== End of DMA Double Buffering Examples ===");
+}
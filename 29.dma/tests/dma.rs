@@ -0,0 +1,139 @@
+use dma::{adc_sample, start, DmaConfig, PeripheralStats};
+use std::thread;
+use std::time::Duration;
+
+fn config(buffers: usize) -> DmaConfig {
+    DmaConfig {
+        block_len: 16,
+        buffers,
+        block_period: Duration::from_millis(10),
+    }
+}
+
+// Consume every block, sleeping `hold` while holding each one; returns the
+// first sequence number of each block seen
+fn run(
+    buffers: usize,
+    blocks: u64,
+    hold: Duration,
+) -> (Vec<u64>, PeripheralStats, dma::ConsumerStats) {
+    let (mut consumer, peripheral) = start(config(buffers), blocks);
+    let mut seqs = Vec::new();
+    while let Some(block) = consumer.acquire() {
+        for (i, sample) in block.samples.iter().enumerate() {
+            assert_eq!(*sample, adc_sample(block.first_seq + i as u64));
+        }
+        seqs.push(block.first_seq);
+        thread::sleep(hold);
+    }
+    (seqs, peripheral.join().unwrap(), consumer.stats())
+}
+
+#[test]
+fn adc_samples_are_twelve_bit() {
+    for seq in 0..10_000 {
+        assert!(adc_sample(seq) < 4096);
+    }
+}
+
+#[test]
+fn a_fast_consumer_sees_every_sample_in_order() {
+    let (seqs, p, c) = run(2, 12, Duration::ZERO);
+    assert_eq!(seqs, (0..12).map(|i| i * 16).collect::<Vec<_>>());
+    assert_eq!(
+        p,
+        PeripheralStats {
+            blocks_filled: 12,
+            overruns: 0,
+            samples_dropped: 0,
+        }
+    );
+    assert_eq!(c.blocks, 12);
+    assert_eq!(c.samples, 12 * 16);
+    assert_eq!(c.samples_missing, 0);
+}
+
+#[test]
+fn a_slow_consumer_causes_overruns_both_sides_agree_on() {
+    let (seqs, p, c) = run(2, 20, Duration::from_millis(25));
+    assert!(p.overruns > 0);
+    assert_eq!(p.blocks_filled + p.overruns, 20);
+    assert_eq!(p.samples_dropped, p.overruns * 16);
+    assert_eq!(c.blocks, p.blocks_filled);
+    // The gaps the consumer finds in the sequence are lost blocks; overruns
+    // after the last delivered block leave no gap to find
+    assert!(c.samples_missing > 0);
+    assert!(c.samples_missing <= p.samples_dropped);
+    assert_eq!(c.samples_missing % 16, 0);
+    let last = *seqs.last().unwrap();
+    assert_eq!(last + 16 - 16 * c.blocks, c.samples_missing);
+    assert!(seqs
+        .windows(2)
+        .all(|w| w[1] > w[0] && (w[1] - w[0]) % 16 == 0));
+}
+
+#[test]
+fn a_held_block_is_not_refilled() {
+    let (mut consumer, peripheral) = start(config(2), 6);
+    let first = consumer.acquire().unwrap();
+    let snapshot = first.samples.clone();
+    // Two periods pass; with only one other buffer the peripheral overruns
+    // rather than writing into the block on loan
+    thread::sleep(Duration::from_millis(35));
+    assert_eq!(first.first_seq, 0);
+    assert_eq!(first.samples, snapshot);
+    drop(first);
+    while consumer.acquire().is_some() {}
+    let p = peripheral.join().unwrap();
+    assert!(p.overruns > 0);
+    assert!(consumer.stats().samples_missing <= p.samples_dropped);
+}
+
+#[test]
+fn dropping_the_guard_returns_the_buffer() {
+    // Each block is released before the next acquire; with a fast consumer
+    // two buffers keep circulating for many more blocks than exist
+    let (mut consumer, peripheral) = start(config(2), 10);
+    let mut blocks = 0;
+    while let Some(block) = consumer.acquire() {
+        assert_eq!(block.samples.len(), 16);
+        blocks += 1;
+    }
+    assert_eq!(blocks, 10);
+    assert_eq!(peripheral.join().unwrap().overruns, 0);
+}
+
+#[test]
+fn fewer_than_two_buffers_means_double_buffering() {
+    for buffers in [0, 1] {
+        let (seqs, p, _) = run(buffers, 5, Duration::ZERO);
+        assert_eq!(seqs.len(), 5);
+        assert_eq!(p.overruns, 0);
+    }
+}
+
+#[test]
+fn more_buffers_absorb_a_latency_spike() {
+    // One block takes 2.5 periods; the others are instant
+    let spike = |buffers| {
+        let (mut consumer, peripheral) = start(config(buffers), 12);
+        let mut i = 0;
+        while let Some(_block) = consumer.acquire() {
+            if i == 3 {
+                thread::sleep(Duration::from_millis(25));
+            }
+            i += 1;
+        }
+        peripheral.join().unwrap()
+    };
+    assert!(spike(2).overruns > 0);
+    assert_eq!(spike(6).overruns, 0);
+}
+
+#[test]
+fn dropping_the_consumer_stops_the_peripheral() {
+    let (consumer, peripheral) = start(config(2), 1_000_000);
+    drop(consumer);
+    let p = peripheral.join().unwrap();
+    assert!(p.blocks_filled + p.overruns < 1_000_000);
+}
@@ -0,0 +1,148 @@
+// Critical sections, shaped like the `critical-section` crate
+//
+// Library code calls `with(|cs| ...)` and never knows how the section is
+// implemented. The implementation is chosen once per program with
+// `set_impl!`, which defines two linker symbols:
+//
+//   single-core MCU:  save PRIMASK and disable interrupts / restore it
+//   host (std):       a global lock, re-entrant per thread
+//
+// A `CriticalSection` token proves the section is active, so data guarded
+// by `Mutex` can only be reached while it is.
+
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+
+// Whatever `acquire` needs to hand back to `release`; on Cortex-M it is the
+// previous interrupt-enable state
+pub type RawRestoreState = bool;
+
+/// # Safety
+/// `acquire` must prevent every other context (threads, interrupts) from
+/// entering a critical section until the matching `release`, and nested
+/// acquire/release pairs must restore the state they were given.
+pub unsafe trait Impl {
+    /// # Safety
+    /// Must be paired with exactly one `release` of the returned state.
+    unsafe fn acquire() -> RawRestoreState;
+    /// # Safety
+    /// `restore_state` must come from the matching `acquire`, released in
+    /// reverse order of acquisition.
+    unsafe fn release(restore_state: RawRestoreState);
+}
+
+extern "Rust" {
+    fn _isr_queue_cs_acquire() -> RawRestoreState;
+    fn _isr_queue_cs_release(restore_state: RawRestoreState);
+}
+
+// Install `$t: Impl` as the program's critical-section implementation
+#[macro_export]
+macro_rules! set_impl {
+    ($t:ty) => {
+        #[no_mangle]
+        fn _isr_queue_cs_acquire() -> $crate::cs::RawRestoreState {
+            // SAFETY: forwarded from the `Impl` contract
+            unsafe { <$t as $crate::cs::Impl>::acquire() }
+        }
+        #[no_mangle]
+        fn _isr_queue_cs_release(restore_state: $crate::cs::RawRestoreState) {
+            // SAFETY: forwarded from the `Impl` contract
+            unsafe { <$t as $crate::cs::Impl>::release(restore_state) }
+        }
+    };
+}
+
+// Proof that a critical section is active for lifetime 'cs
+#[derive(Clone, Copy, Debug)]
+pub struct CriticalSection<'cs> {
+    _private: PhantomData<&'cs ()>,
+}
+
+pub fn with<R>(f: impl FnOnce(CriticalSection<'_>) -> R) -> R {
+    // Release even if `f` panics, so a failed section cannot wedge others
+    struct Guard(RawRestoreState);
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            // SAFETY: pairs with the acquire below
+            unsafe { _isr_queue_cs_release(self.0) }
+        }
+    }
+
+    // SAFETY: the symbols come from `set_impl!` and uphold `Impl`
+    let _guard = Guard(unsafe { _isr_queue_cs_acquire() });
+    f(CriticalSection {
+        _private: PhantomData,
+    })
+}
+
+// Data that may only be touched inside a critical section. Combine with
+// `Cell` or `RefCell` for mutation, as on bare metal.
+pub struct Mutex<T> {
+    inner: UnsafeCell<T>,
+}
+
+// SAFETY: access requires a CriticalSection token, which excludes every
+// other context for as long as the borrow lives
+unsafe impl<T: Send> Sync for Mutex<T> {}
+
+impl<T> Mutex<T> {
+    pub const fn new(value: T) -> Mutex<T> {
+        Mutex {
+            inner: UnsafeCell::new(value),
+        }
+    }
+
+    pub fn borrow<'cs>(&'cs self, _cs: CriticalSection<'cs>) -> &'cs T {
+        // SAFETY: only shared references are handed out, and only while a
+        // critical section is active
+        unsafe { &*self.inner.get() }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+}
+
+#[cfg(feature = "std")]
+pub mod host {
+    use super::{Impl, RawRestoreState};
+    use core::hint;
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use std::cell::Cell;
+
+    static LOCKED: AtomicBool = AtomicBool::new(false);
+
+    std::thread_local! {
+        static HELD: Cell<bool> = const { Cell::new(false) };
+    }
+
+    // A global spinlock standing in for "interrupts disabled". Nesting on
+    // the same thread is allowed, just as disabling interrupts twice is.
+    pub struct HostCriticalSection;
+
+    unsafe impl Impl for HostCriticalSection {
+        unsafe fn acquire() -> RawRestoreState {
+            if HELD.with(|held| held.get()) {
+                return true;
+            }
+            while LOCKED
+                .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_err()
+            {
+                hint::spin_loop();
+            }
+            HELD.with(|held| held.set(true));
+            false
+        }
+
+        unsafe fn release(was_held: RawRestoreState) {
+            if !was_held {
+                HELD.with(|held| held.set(false));
+                LOCKED.store(false, Ordering::Release);
+            }
+        }
+    }
+
+    crate::set_impl!(HostCriticalSection);
+}
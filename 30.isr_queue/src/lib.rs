@@ -0,0 +1,108 @@
+// Sharing data with interrupt handlers
+//
+// An interrupt can fire between any two instructions of the main loop, so
+// anything both touch must be accessed with interrupts masked. `cs`
+// provides that as a safe abstraction and `Queue` builds a bounded FIFO on
+// it that an ISR can push into and the main loop can drain.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod cs;
+
+use core::cell::RefCell;
+use cs::Mutex;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct QueueStats {
+    pub pushed: u64,
+    // Rejected because the queue was full
+    pub dropped: u64,
+    pub popped: u64,
+    pub high_water: usize,
+}
+
+struct Ring<T, const N: usize> {
+    slots: [Option<T>; N],
+    head: usize,
+    len: usize,
+    stats: QueueStats,
+}
+
+// Bounded FIFO; every operation is one short critical section, so the
+// worst-case interrupt latency it adds is a few instructions
+pub struct Queue<T, const N: usize> {
+    ring: Mutex<RefCell<Ring<T, N>>>,
+}
+
+impl<T, const N: usize> Default for Queue<T, N> {
+    fn default() -> Self {
+        Queue::new()
+    }
+}
+
+impl<T, const N: usize> Queue<T, N> {
+    pub const fn new() -> Queue<T, N> {
+        Queue {
+            ring: Mutex::new(RefCell::new(Ring {
+                slots: [const { None }; N],
+                head: 0,
+                len: 0,
+                stats: QueueStats {
+                    pushed: 0,
+                    dropped: 0,
+                    popped: 0,
+                    high_water: 0,
+                },
+            })),
+        }
+    }
+
+    // Safe to call from interrupt context; never blocks. Gives the item
+    // back if the queue is full.
+    pub fn push(&self, item: T) -> Result<(), T> {
+        cs::with(|cs| {
+            let mut ring = self.ring.borrow(cs).borrow_mut();
+            if ring.len == N {
+                ring.stats.dropped += 1;
+                return Err(item);
+            }
+            let tail = (ring.head + ring.len) % N;
+            ring.slots[tail] = Some(item);
+            ring.len += 1;
+            ring.stats.pushed += 1;
+            ring.stats.high_water = ring.stats.high_water.max(ring.len);
+            Ok(())
+        })
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        cs::with(|cs| {
+            let mut ring = self.ring.borrow(cs).borrow_mut();
+            if ring.len == 0 {
+                return None;
+            }
+            let head = ring.head;
+            let item = ring.slots[head].take();
+            ring.head = (head + 1) % N;
+            ring.len -= 1;
+            ring.stats.popped += 1;
+            item
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        cs::with(|cs| self.ring.borrow(cs).borrow().len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn stats(&self) -> QueueStats {
+        cs::with(|cs| self.ring.borrow(cs).borrow().stats)
+    }
+}
@@ -0,0 +1,156 @@
+use isr_queue::cs::{self, Mutex};
+use isr_queue::Queue;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Shared with the "ISR" the way firmware does it: a static behind a Mutex
+static TICKS: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+
+const PER_PRODUCER: u32 = 200_000;
+
+fn main() {
+    println!("=== Interrupt-Safe Queue ===\n");
+
+    // 1. Guarded data needs a CriticalSection token
+    println!("1. Mutex + CriticalSection token:");
+    cs::with(|cs| {
+        let ticks = TICKS.borrow(cs);
+        ticks.set(ticks.get() + 1);
+    });
+    println!("   ticks = {}", cs::with(|cs| TICKS.borrow(cs).get()));
+
+    // 2. Nested sections are allowed, like masking interrupts twice
+    println!("\n2. Nesting:");
+    let depth = cs::with(|outer| {
+        let inner_value = cs::with(|inner| TICKS.borrow(inner).get());
+        TICKS.borrow(outer).set(inner_value + 1);
+        inner_value + 1
+    });
+    println!("   inner section saw the outer's data, ticks = {}", depth);
+
+    // 3. Read-modify-write with an interrupt in the middle
+    println!("\n3. Lost updates without a critical section:");
+    let racy = AtomicU32::new(0);
+    let guarded = Mutex::new(Cell::new(0u32));
+    let rounds = 20_000;
+    thread::scope(|s| {
+        for _ in 0..2 {
+            s.spawn(|| {
+                for _ in 0..rounds {
+                    // load ... (interrupt fires here) ... store
+                    let v = racy.load(Ordering::Relaxed);
+                    thread::yield_now();
+                    racy.store(v + 1, Ordering::Relaxed);
+
+                    cs::with(|cs| {
+                        let c = guarded.borrow(cs);
+                        c.set(c.get() + 1);
+                    });
+                }
+            });
+        }
+    });
+    println!("   expected:                {}", 2 * rounds);
+    println!(
+        "   load/store:              {}",
+        racy.load(Ordering::Relaxed)
+    );
+    println!("   inside critical section: {}", guarded.into_inner().get());
+
+    // 4. Bounded queue semantics
+    println!("\n4. Queue basics (capacity 4):");
+    let q: Queue<u8, 4> = Queue::new();
+    for i in 0..6 {
+        match q.push(i) {
+            Ok(()) => println!("   push {} -> ok (len {})", i, q.len()),
+            Err(v) => println!("   push {} -> full, item {} handed back", i, v),
+        }
+    }
+    let drained: Vec<u8> = std::iter::from_fn(|| q.pop()).collect();
+    println!("   drained {:?}", drained);
+    println!("   {:?}", q.stats());
+
+    // 5. Hammer: two producers and a consumer on separate threads
+    println!("\n5. Hammer test, 2 producers x {} items:", PER_PRODUCER);
+    static HAMMER: Queue<(u8, u32), 64> = Queue::new();
+    let start = Instant::now();
+    // tests/queue.rs checks that each producer's items arrive in order
+    let received = thread::scope(|s| {
+        for id in 0..2u8 {
+            s.spawn(move || {
+                for seq in 0..PER_PRODUCER {
+                    let mut item = (id, seq);
+                    // Retry on full so nothing is lost; an ISR would drop
+                    while let Err(back) = HAMMER.push(item) {
+                        item = back;
+                        thread::yield_now();
+                    }
+                }
+            });
+        }
+
+        let consumer = s.spawn(|| {
+            let mut received = 0u32;
+            while received < 2 * PER_PRODUCER {
+                match HAMMER.pop() {
+                    Some(_) => received += 1,
+                    None => thread::yield_now(),
+                }
+            }
+            received
+        });
+        consumer.join().unwrap()
+    });
+    let stats = HAMMER.stats();
+    println!("   received {} in {:?}", received, start.elapsed());
+    println!(
+        "   pushed {} popped {} rejected-while-full {} high water {}/{}",
+        stats.pushed,
+        stats.popped,
+        stats.dropped,
+        stats.high_water,
+        HAMMER.capacity()
+    );
+    println!("   empty at end: {}", HAMMER.is_empty());
+
+    // 6. Timer "ISR" feeding a slow main loop
+    println!("\n6. Pseudo-ISR at 1 kHz, main loop drains every 5-20 ms, 8 slots:");
+    static SAMPLES: Queue<u16, 8> = Queue::new();
+    let running = AtomicBool::new(true);
+    let mut consumed = 0u32;
+    thread::scope(|s| {
+        s.spawn(|| {
+            let mut adc = 0u16;
+            while running.load(Ordering::Relaxed) {
+                // An ISR never blocks: if the queue is full the sample is lost
+                let _ = SAMPLES.push(adc);
+                adc = adc.wrapping_add(1);
+                thread::sleep(Duration::from_millis(1));
+            }
+        });
+
+        for round in 0..10 {
+            // Every third pass the main loop is busy for longer
+            let busy = if round % 3 == 2 { 20 } else { 5 };
+            thread::sleep(Duration::from_millis(busy));
+            while SAMPLES.pop().is_some() {
+                consumed += 1;
+            }
+        }
+        running.store(false, Ordering::Relaxed);
+    });
+    let stats = SAMPLES.stats();
+    println!(
+        "   pushed {} consumed {} dropped {} high water {}/{}",
+        stats.pushed,
+        consumed,
+        stats.dropped,
+        stats.high_water,
+        SAMPLES.capacity()
+    );
+    println!("   (size the queue for the longest gap between drains)");
+
+    println!("\n=== End of Interrupt-Safe Queue Examples ===");
+}
@@ -0,0 +1,62 @@
+use isr_queue::cs::{self, Mutex};
+use std::cell::Cell;
+use std::panic;
+use std::thread;
+
+#[test]
+fn guarded_data_is_reached_through_the_token() {
+    static COUNT: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+    cs::with(|cs| COUNT.borrow(cs).set(41));
+    let value = cs::with(|cs| {
+        let count = COUNT.borrow(cs);
+        count.set(count.get() + 1);
+        count.get()
+    });
+    assert_eq!(value, 42);
+}
+
+#[test]
+fn sections_nest_on_one_thread() {
+    let data = Mutex::new(Cell::new(1u32));
+    let result = cs::with(|outer| {
+        let inner = cs::with(|inner| {
+            data.borrow(inner).set(2);
+            cs::with(|innermost| data.borrow(innermost).get() * 10)
+        });
+        data.borrow(outer).get() + inner
+    });
+    assert_eq!(result, 22);
+    // Leaving the nest released the section: another thread can enter
+    let other = thread::spawn(|| cs::with(|_| 7)).join().unwrap();
+    assert_eq!(other, 7);
+}
+
+#[test]
+fn read_modify_write_inside_a_section_loses_nothing() {
+    let counter = Mutex::new(Cell::new(0u32));
+    let rounds = 2_000;
+    thread::scope(|s| {
+        for _ in 0..2 {
+            s.spawn(|| {
+                for _ in 0..rounds {
+                    cs::with(|cs| {
+                        let c = counter.borrow(cs);
+                        let v = c.get();
+                        thread::yield_now();
+                        c.set(v + 1);
+                    });
+                }
+            });
+        }
+    });
+    assert_eq!(counter.into_inner().get(), 2 * rounds);
+}
+
+#[test]
+fn a_panic_inside_a_section_releases_it() {
+    let result = panic::catch_unwind(|| cs::with(|_| panic!("handler failed")));
+    assert!(result.is_err());
+    // Both this thread and others can still enter
+    assert_eq!(cs::with(|_| 1), 1);
+    assert_eq!(thread::spawn(|| cs::with(|_| 2)).join().unwrap(), 2);
+}
@@ -0,0 +1,140 @@
+use isr_queue::{Queue, QueueStats};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+#[test]
+fn fifo_order_and_full_hands_the_item_back() {
+    let q: Queue<u8, 4> = Queue::new();
+    assert!(q.is_empty());
+    assert_eq!(q.capacity(), 4);
+    for i in 0..4 {
+        assert_eq!(q.push(i), Ok(()));
+    }
+    assert_eq!(q.len(), 4);
+    assert_eq!(q.push(4), Err(4));
+    assert_eq!(q.push(5), Err(5));
+    let drained: Vec<u8> = std::iter::from_fn(|| q.pop()).collect();
+    assert_eq!(drained, [0, 1, 2, 3]);
+    assert_eq!(q.pop(), None);
+    assert_eq!(
+        q.stats(),
+        QueueStats {
+            pushed: 4,
+            dropped: 2,
+            popped: 4,
+            high_water: 4,
+        }
+    );
+}
+
+#[test]
+fn wraps_around_the_ring() {
+    let q: Queue<u32, 3> = Queue::new();
+    let mut next_out = 0;
+    let mut next_in = 0;
+    for round in 0..100 {
+        // Alternate between pushing two and one so head moves unevenly
+        for _ in 0..(1 + round % 2) {
+            if q.push(next_in).is_ok() {
+                next_in += 1;
+            }
+        }
+        if let Some(v) = q.pop() {
+            assert_eq!(v, next_out);
+            next_out += 1;
+        }
+        assert!(q.len() <= 3);
+    }
+    while let Some(v) = q.pop() {
+        assert_eq!(v, next_out);
+        next_out += 1;
+    }
+    assert_eq!(next_out, next_in);
+    let stats = q.stats();
+    assert_eq!(stats.pushed, next_in as u64);
+    assert_eq!(stats.popped, next_in as u64);
+    assert_eq!(stats.high_water, 3);
+}
+
+#[test]
+fn high_water_tracks_the_deepest_fill() {
+    let q: Queue<u8, 8> = Queue::default();
+    for i in 0..5 {
+        q.push(i).unwrap();
+    }
+    for _ in 0..5 {
+        q.pop();
+    }
+    q.push(9).unwrap();
+    assert_eq!(q.stats().high_water, 5);
+}
+
+#[test]
+fn single_slot_queue() {
+    let q: Queue<&str, 1> = Queue::new();
+    assert_eq!(q.push("a"), Ok(()));
+    assert_eq!(q.push("b"), Err("b"));
+    assert_eq!(q.pop(), Some("a"));
+    assert_eq!(q.push("c"), Ok(()));
+    assert_eq!(q.pop(), Some("c"));
+}
+
+#[test]
+fn queued_items_are_dropped_with_the_queue() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    struct Counted;
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let q: Queue<Counted, 4> = Queue::new();
+    for _ in 0..3 {
+        assert!(q.push(Counted).is_ok());
+    }
+    drop(q.pop());
+    assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+    drop(q);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn concurrent_producers_keep_their_own_order() {
+    const PER_PRODUCER: u32 = 50_000;
+    static QUEUE: Queue<(u8, u32), 16> = Queue::new();
+    let next = thread::scope(|s| {
+        for id in 0..3u8 {
+            s.spawn(move || {
+                for seq in 0..PER_PRODUCER {
+                    let mut item = (id, seq);
+                    while let Err(back) = QUEUE.push(item) {
+                        item = back;
+                        thread::yield_now();
+                    }
+                }
+            });
+        }
+
+        let mut next = [0u32; 3];
+        let mut received = 0;
+        while received < 3 * PER_PRODUCER {
+            match QUEUE.pop() {
+                Some((id, seq)) => {
+                    // No loss or duplicates, FIFO per producer
+                    assert_eq!(seq, next[id as usize]);
+                    next[id as usize] += 1;
+                    received += 1;
+                }
+                None => thread::yield_now(),
+            }
+        }
+        next
+    });
+    assert_eq!(next, [PER_PRODUCER; 3]);
+    assert!(QUEUE.is_empty());
+    let stats = QUEUE.stats();
+    assert_eq!(stats.pushed, 3 * PER_PRODUCER as u64);
+    assert_eq!(stats.popped, 3 * PER_PRODUCER as u64);
+    assert!(stats.high_water <= 16);
+}
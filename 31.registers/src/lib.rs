@@ -0,0 +1,222 @@
+// Type-safe peripheral registers, the way svd2rust generates them
+//
+// A peripheral is a `#[repr(C)]` block of `Reg<SPEC>` fields placed over its
+// base address. Each register's SPEC type says what it may do:
+//
+//   ReadOnly   read()
+//   WriteOnly  write(|w| ..)
+//   ReadWrite  read(), write(|w| ..), modify(|r, w| ..)
+//
+// Field accessors are inherent methods on `R<SPEC>` / `W<SPEC>`, so
+// `cr1.modify(|_, w| w.te().set_bit())` only compiles for registers that
+// have a TE bit and allow both reading and writing.
+
+pub mod mock;
+pub mod uart;
+
+use std::cell::UnsafeCell;
+use std::marker::PhantomData;
+use std::ptr;
+
+pub struct ReadOnly;
+pub struct WriteOnly;
+pub struct ReadWrite;
+
+pub trait Readable {}
+pub trait Writable {}
+
+impl Readable for ReadOnly {}
+impl Readable for ReadWrite {}
+impl Writable for WriteOnly {}
+impl Writable for ReadWrite {}
+
+// Describes one register: who may access it and its value after reset
+pub trait RegisterSpec {
+    type Access;
+    const RESET: u32;
+}
+
+// One 32-bit register. Every access is volatile so the compiler never
+// caches, merges or drops it.
+#[repr(transparent)]
+pub struct Reg<S: RegisterSpec> {
+    value: UnsafeCell<u32>,
+    _spec: PhantomData<S>,
+}
+
+impl<S: RegisterSpec> Reg<S> {
+    // Only mocks create registers; on hardware they already exist
+    pub const fn at_reset() -> Reg<S> {
+        Reg {
+            value: UnsafeCell::new(S::RESET),
+            _spec: PhantomData,
+        }
+    }
+
+    pub fn as_ptr(&self) -> *mut u32 {
+        self.value.get()
+    }
+}
+
+impl<S: RegisterSpec> Reg<S>
+where
+    S::Access: Readable,
+{
+    pub fn read(&self) -> R<S> {
+        R {
+            // SAFETY: the pointer comes from a live register
+            bits: unsafe { ptr::read_volatile(self.value.get()) },
+            _spec: PhantomData,
+        }
+    }
+}
+
+impl<S: RegisterSpec> Reg<S>
+where
+    S::Access: Writable,
+{
+    // Start from the reset value; fields not mentioned get their defaults
+    pub fn write(&self, f: impl FnOnce(&mut W<S>) -> &mut W<S>) {
+        let mut w = W {
+            bits: S::RESET,
+            _spec: PhantomData,
+        };
+        f(&mut w);
+        // SAFETY: the pointer comes from a live register
+        unsafe { ptr::write_volatile(self.value.get(), w.bits) }
+    }
+
+    pub fn reset(&self) {
+        self.write(|w| w);
+    }
+}
+
+impl<S: RegisterSpec> Reg<S>
+where
+    S::Access: Readable + Writable,
+{
+    // Read once, change some fields, write once
+    pub fn modify<F>(&self, f: F)
+    where
+        F: for<'w> FnOnce(&R<S>, &'w mut W<S>) -> &'w mut W<S>,
+    {
+        let r = self.read();
+        let mut w = W {
+            bits: r.bits,
+            _spec: PhantomData,
+        };
+        f(&r, &mut w);
+        // SAFETY: the pointer comes from a live register
+        unsafe { ptr::write_volatile(self.value.get(), w.bits) }
+    }
+}
+
+// A value read from a register
+pub struct R<S> {
+    bits: u32,
+    _spec: PhantomData<S>,
+}
+
+impl<S> R<S> {
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    pub fn bit(&self, offset: u8) -> bool {
+        self.bits & (1 << offset) != 0
+    }
+
+    pub fn field(&self, offset: u8, width: u8) -> u32 {
+        (self.bits >> offset) & mask(width)
+    }
+}
+
+// A value being built for a register write
+pub struct W<S> {
+    bits: u32,
+    _spec: PhantomData<S>,
+}
+
+impl<S> W<S> {
+    /// # Safety
+    /// Writes every field at once, including reserved bits and values the
+    /// hardware does not define. Prefer the field writers.
+    pub unsafe fn bits(&mut self, bits: u32) -> &mut W<S> {
+        self.bits = bits;
+        self
+    }
+
+    pub fn bit_writer<const O: u8>(&mut self) -> BitWriter<'_, S, O> {
+        BitWriter { w: self }
+    }
+
+    pub fn field_writer<FI, const O: u8, const WIDTH: u8>(
+        &mut self,
+    ) -> FieldWriter<'_, S, FI, O, WIDTH> {
+        FieldWriter {
+            w: self,
+            _value: PhantomData,
+        }
+    }
+}
+
+const fn mask(width: u8) -> u32 {
+    if width >= 32 {
+        u32::MAX
+    } else {
+        (1 << width) - 1
+    }
+}
+
+// Writes one bit at offset O and hands the writer back for chaining
+pub struct BitWriter<'a, S, const O: u8> {
+    w: &'a mut W<S>,
+}
+
+impl<'a, S, const O: u8> BitWriter<'a, S, O> {
+    pub fn bit(self, value: bool) -> &'a mut W<S> {
+        self.w.bits = (self.w.bits & !(1 << O)) | ((value as u32) << O);
+        self.w
+    }
+
+    pub fn set_bit(self) -> &'a mut W<S> {
+        self.bit(true)
+    }
+
+    pub fn clear_bit(self) -> &'a mut W<S> {
+        self.bit(false)
+    }
+}
+
+// Writes a WIDTH-bit field at offset O whose values are of type FI
+pub struct FieldWriter<'a, S, FI, const O: u8, const WIDTH: u8> {
+    w: &'a mut W<S>,
+    _value: PhantomData<FI>,
+}
+
+impl<'a, S, FI: Into<u32>, const O: u8, const WIDTH: u8> FieldWriter<'a, S, FI, O, WIDTH> {
+    // Enumerated fields: only the listed values can be written
+    pub fn variant(self, value: FI) -> &'a mut W<S> {
+        self.set(value.into())
+    }
+
+    fn set(self, value: u32) -> &'a mut W<S> {
+        let m = mask(WIDTH) << O;
+        self.w.bits = (self.w.bits & !m) | ((value << O) & m);
+        self.w
+    }
+}
+
+impl<'a, S, const O: u8, const WIDTH: u8> FieldWriter<'a, S, u32, O, WIDTH> {
+    // Numeric fields: values that do not fit are a bug, not something to
+    // silently truncate
+    pub fn bits(self, value: u32) -> &'a mut W<S> {
+        assert!(
+            value <= mask(WIDTH),
+            "value {} does not fit in {} bits",
+            value,
+            WIDTH
+        );
+        self.set(value)
+    }
+}
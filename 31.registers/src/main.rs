@@ -0,0 +1,132 @@
+use registers::mock::Mock;
+use registers::uart::{self, baud_divider, Config, Parity, RegisterBlock, Serial};
+
+// Register offsets, as a test would spell them out from the datasheet
+const SR: usize = 0x00;
+const DR: usize = 0x04;
+const BRR: usize = 0x08;
+const CR1: usize = 0x0C;
+const RQR: usize = 0x10;
+
+const PCLK_HZ: u32 = 8_000_000;
+
+fn show(mock: &Mock<RegisterBlock>) {
+    for (offset, value) in mock.dump() {
+        let name = ["SR", "DR", "BRR", "CR1", "RQR"][offset / 4];
+        println!("   {:#04x} {:<3} = {:#010x}", offset, name, value);
+    }
+}
+
+fn main() {
+    println!("=== Type-Safe Peripheral Registers ===\n");
+
+    // 1. A register block in ordinary memory, at reset values
+    println!("1. Mock USART at reset:");
+    let mock = Mock::new(RegisterBlock::at_reset());
+    show(&mock);
+    println!(
+        "   on hardware: unsafe {{ RegisterBlock::steal({:#x}) }}",
+        uart::USART1_BASE
+    );
+
+    // 2. Driver initialisation; tests/uart.rs checks the bits against the
+    //    datasheet
+    println!("\n2. Serial::new(115200 baud, even parity) at 8 MHz:");
+    let mut serial = Serial::new(
+        mock.regs(),
+        PCLK_HZ,
+        Config {
+            baud: 115_200,
+            parity: Some(Parity::Even),
+        },
+    );
+    let (mantissa, fraction) = baud_divider(PCLK_HZ, 115_200);
+    let actual = PCLK_HZ / (mantissa * 16 + fraction);
+    println!(
+        "   BRR mantissa {} fraction {}/16 -> {} baud",
+        mantissa, fraction, actual
+    );
+    println!("   BRR = {:#06x}", mock.peek(BRR));
+    let cr1 = mock.regs().cr1.read();
+    println!(
+        "   CR1 ue={} m={:?} pce={} ps={:?} te={} re={}",
+        cr1.ue(),
+        cr1.m(),
+        cr1.pce(),
+        cr1.ps(),
+        cr1.te(),
+        cr1.re()
+    );
+
+    // 3. modify() changes one field and preserves the rest
+    println!("\n3. modify() keeps other fields:");
+    let before = mock.peek(CR1);
+    serial.listen();
+    let after = mock.peek(CR1);
+    println!("   CR1 {:#06x} -> {:#06x}", before, after);
+
+    // 4. Transmitting: the test sees every byte written to DR
+    println!("\n4. Transmit:");
+    let mut sent = Vec::new();
+    for &byte in b"OK\n" {
+        serial.write_byte(byte);
+        sent.push(mock.peek(DR) as u8);
+    }
+    println!("   DR received {:?}", String::from_utf8_lossy(&sent));
+
+    // TXE clear means "busy"; the driver would spin until hardware sets it
+    mock.clear_bits(SR, 1 << 7);
+    println!(
+        "   TXE cleared by 'hardware': txe={}",
+        mock.regs().sr.read().txe()
+    );
+    mock.set_bits(SR, 1 << 7);
+
+    // 5. Receiving: the test plays the hardware side
+    println!("\n5. Receive:");
+    println!("   nothing pending: {:?}", serial.read_byte());
+    mock.poke(DR, b'x' as u32);
+    mock.set_bits(SR, 1 << 5);
+    println!(
+        "   RXNE set, DR='x': {:?}",
+        serial.read_byte().map(char::from)
+    );
+    mock.clear_bits(SR, 1 << 5);
+
+    // 6. Write-only register: the driver cannot read it back, the mock can
+    println!("\n6. Write-only RQR:");
+    serial.send_break();
+    println!("   RQR = {:#x} (SBKRQ)", mock.peek(RQR));
+
+    // 7. What the types rule out
+    println!("\n7. Rejected at compile time:");
+    println!("   mock.regs().sr.write(..)        // SR is ReadOnly: no write()");
+    println!("   mock.regs().rqr.read()          // RQR is WriteOnly: no read()");
+    println!("   mock.regs().rqr.modify(..)      // modify() needs both");
+    println!("   cr1.write(|w| w.txe()..)        // TXE is not a CR1 field");
+    println!("   w.ps().variant(3)               // PS only takes a Parity");
+
+    println!("   and at run time:");
+    std::panic::set_hook(Box::new(|_| {}));
+    let overflow = std::panic::catch_unwind(|| {
+        let regs = RegisterBlock::at_reset();
+        regs.dr.write(|w| w.dr().bits(0x200));
+    });
+    let _ = std::panic::take_hook();
+    println!(
+        "   w.dr().bits(0x200) on a 9-bit field -> {}",
+        if overflow.is_err() {
+            "panic"
+        } else {
+            "accepted?!"
+        }
+    );
+
+    // 8. Releasing the peripheral
+    println!("\n8. Release:");
+    serial.release();
+    println!("   ue={}", mock.regs().cr1.read().ue());
+    show(&mock);
+
+    println!("\n=== End of Peripheral Register Examples ===");
+}
@@ -0,0 +1,62 @@
+// A heap-backed register block for testing drivers off-target
+//
+// The driver gets an ordinary `&RegisterBlock`; the test keeps the `Mock`
+// and plays the hardware's part with `poke`, then checks what the driver
+// wrote with `peek`. Hardware side effects (e.g. reading DR clearing RXNE)
+// are not modelled - the test performs them explicitly.
+
+use std::mem::size_of;
+
+pub struct Mock<B> {
+    block: Box<B>,
+}
+
+impl<B> Mock<B> {
+    pub fn new(block: B) -> Mock<B> {
+        Mock {
+            block: Box::new(block),
+        }
+    }
+
+    pub fn regs(&self) -> &B {
+        &self.block
+    }
+
+    fn word(&self, offset: usize) -> *mut u32 {
+        assert!(
+            offset.is_multiple_of(4) && offset + 4 <= size_of::<B>(),
+            "bad register offset {:#x}",
+            offset
+        );
+        let base = &*self.block as *const B as *mut u8;
+        // SAFETY: in bounds and aligned (checked above)
+        unsafe { base.add(offset) as *mut u32 }
+    }
+
+    // Raw register contents, including write-only registers
+    pub fn peek(&self, offset: usize) -> u32 {
+        // SAFETY: registers are UnsafeCells, so shared reads are fine
+        unsafe { self.word(offset).read_volatile() }
+    }
+
+    // Set a register as the hardware would, e.g. a status flag
+    pub fn poke(&self, offset: usize, value: u32) {
+        // SAFETY: registers are UnsafeCells, so writing through a shared
+        // reference is allowed
+        unsafe { self.word(offset).write_volatile(value) }
+    }
+
+    pub fn set_bits(&self, offset: usize, mask: u32) {
+        self.poke(offset, self.peek(offset) | mask);
+    }
+
+    pub fn clear_bits(&self, offset: usize, mask: u32) {
+        self.poke(offset, self.peek(offset) & !mask);
+    }
+
+    pub fn dump(&self) -> Vec<(usize, u32)> {
+        (0..size_of::<B>() / 4)
+            .map(|i| (i * 4, self.peek(i * 4)))
+            .collect()
+    }
+}
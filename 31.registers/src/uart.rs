@@ -0,0 +1,329 @@
+// A USART peripheral in svd2rust's layout, modelled on the STM32F1 USART
+//
+//   0x00 SR   status (read-only)
+//   0x04 DR   data
+//   0x08 BRR  baud rate
+//   0x0C CR1  control
+//   0x10 RQR  requests (write-only)
+//
+// svd2rust would generate everything above `Serial` from the vendor's SVD
+// file; here it is written by hand to show what that code looks like.
+
+use crate::{BitWriter, FieldWriter, ReadOnly, ReadWrite, Reg, RegisterSpec, WriteOnly};
+use std::mem::offset_of;
+
+pub const USART1_BASE: usize = 0x4001_3800;
+
+#[repr(C)]
+pub struct RegisterBlock {
+    pub sr: Reg<sr::SrSpec>,
+    pub dr: Reg<dr::DrSpec>,
+    pub brr: Reg<brr::BrrSpec>,
+    pub cr1: Reg<cr1::Cr1Spec>,
+    pub rqr: Reg<rqr::RqrSpec>,
+}
+
+// The layout must match the datasheet exactly
+const _: () = {
+    assert!(offset_of!(RegisterBlock, sr) == 0x00);
+    assert!(offset_of!(RegisterBlock, dr) == 0x04);
+    assert!(offset_of!(RegisterBlock, brr) == 0x08);
+    assert!(offset_of!(RegisterBlock, cr1) == 0x0C);
+    assert!(offset_of!(RegisterBlock, rqr) == 0x10);
+};
+
+impl RegisterBlock {
+    // Every register at its reset value, for mocks
+    pub const fn at_reset() -> RegisterBlock {
+        RegisterBlock {
+            sr: Reg::at_reset(),
+            dr: Reg::at_reset(),
+            brr: Reg::at_reset(),
+            cr1: Reg::at_reset(),
+            rqr: Reg::at_reset(),
+        }
+    }
+
+    /// # Safety
+    /// `base` must be the address of this peripheral, and nothing else may
+    /// assume exclusive access to it.
+    pub unsafe fn steal<'a>(base: usize) -> &'a RegisterBlock {
+        &*(base as *const RegisterBlock)
+    }
+}
+
+pub mod sr {
+    use super::*;
+
+    pub struct SrSpec;
+    impl RegisterSpec for SrSpec {
+        type Access = ReadOnly;
+        // Transmitter idle after reset
+        const RESET: u32 = 0x0000_00C0;
+    }
+
+    pub type R = crate::R<SrSpec>;
+
+    impl R {
+        // Transmit data register empty
+        pub fn txe(&self) -> bool {
+            self.bit(7)
+        }
+        // Transmission complete
+        pub fn tc(&self) -> bool {
+            self.bit(6)
+        }
+        // Read data register not empty
+        pub fn rxne(&self) -> bool {
+            self.bit(5)
+        }
+        // Overrun: a byte arrived before the previous one was read
+        pub fn ore(&self) -> bool {
+            self.bit(3)
+        }
+    }
+}
+
+pub mod dr {
+    use super::*;
+
+    pub struct DrSpec;
+    impl RegisterSpec for DrSpec {
+        type Access = ReadWrite;
+        const RESET: u32 = 0;
+    }
+
+    pub type R = crate::R<DrSpec>;
+    pub type W = crate::W<DrSpec>;
+
+    impl R {
+        pub fn dr(&self) -> u16 {
+            self.field(0, 9) as u16
+        }
+    }
+
+    impl W {
+        pub fn dr(&mut self) -> FieldWriter<'_, DrSpec, u32, 0, 9> {
+            self.field_writer()
+        }
+    }
+}
+
+pub mod brr {
+    use super::*;
+
+    pub struct BrrSpec;
+    impl RegisterSpec for BrrSpec {
+        type Access = ReadWrite;
+        const RESET: u32 = 0;
+    }
+
+    pub type R = crate::R<BrrSpec>;
+    pub type W = crate::W<BrrSpec>;
+
+    impl R {
+        pub fn mantissa(&self) -> u16 {
+            self.field(4, 12) as u16
+        }
+        pub fn fraction(&self) -> u8 {
+            self.field(0, 4) as u8
+        }
+    }
+
+    impl W {
+        pub fn mantissa(&mut self) -> FieldWriter<'_, BrrSpec, u32, 4, 12> {
+            self.field_writer()
+        }
+        pub fn fraction(&mut self) -> FieldWriter<'_, BrrSpec, u32, 0, 4> {
+            self.field_writer()
+        }
+    }
+}
+
+pub mod cr1 {
+    use super::*;
+
+    pub struct Cr1Spec;
+    impl RegisterSpec for Cr1Spec {
+        type Access = ReadWrite;
+        const RESET: u32 = 0;
+    }
+
+    pub type R = crate::R<Cr1Spec>;
+    pub type W = crate::W<Cr1Spec>;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum WordLength {
+        Bits8 = 0,
+        Bits9 = 1,
+    }
+
+    impl From<WordLength> for u32 {
+        fn from(value: WordLength) -> u32 {
+            value as u32
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Parity {
+        Even = 0,
+        Odd = 1,
+    }
+
+    impl From<Parity> for u32 {
+        fn from(value: Parity) -> u32 {
+            value as u32
+        }
+    }
+
+    impl R {
+        pub fn ue(&self) -> bool {
+            self.bit(13)
+        }
+        pub fn m(&self) -> WordLength {
+            if self.bit(12) {
+                WordLength::Bits9
+            } else {
+                WordLength::Bits8
+            }
+        }
+        pub fn pce(&self) -> bool {
+            self.bit(10)
+        }
+        pub fn ps(&self) -> Parity {
+            if self.bit(9) {
+                Parity::Odd
+            } else {
+                Parity::Even
+            }
+        }
+        pub fn rxneie(&self) -> bool {
+            self.bit(5)
+        }
+        pub fn te(&self) -> bool {
+            self.bit(3)
+        }
+        pub fn re(&self) -> bool {
+            self.bit(2)
+        }
+    }
+
+    impl W {
+        // USART enable
+        pub fn ue(&mut self) -> BitWriter<'_, Cr1Spec, 13> {
+            self.bit_writer()
+        }
+        pub fn m(&mut self) -> FieldWriter<'_, Cr1Spec, WordLength, 12, 1> {
+            self.field_writer()
+        }
+        // Parity control enable
+        pub fn pce(&mut self) -> BitWriter<'_, Cr1Spec, 10> {
+            self.bit_writer()
+        }
+        pub fn ps(&mut self) -> FieldWriter<'_, Cr1Spec, Parity, 9, 1> {
+            self.field_writer()
+        }
+        // Interrupt when a byte is received
+        pub fn rxneie(&mut self) -> BitWriter<'_, Cr1Spec, 5> {
+            self.bit_writer()
+        }
+        pub fn te(&mut self) -> BitWriter<'_, Cr1Spec, 3> {
+            self.bit_writer()
+        }
+        pub fn re(&mut self) -> BitWriter<'_, Cr1Spec, 2> {
+            self.bit_writer()
+        }
+    }
+}
+
+pub mod rqr {
+    use super::*;
+
+    pub struct RqrSpec;
+    impl RegisterSpec for RqrSpec {
+        type Access = WriteOnly;
+        const RESET: u32 = 0;
+    }
+
+    pub type W = crate::W<RqrSpec>;
+
+    impl W {
+        // Flush the receive data register
+        pub fn rxfrq(&mut self) -> BitWriter<'_, RqrSpec, 3> {
+            self.bit_writer()
+        }
+        // Send a break character
+        pub fn sbkrq(&mut self) -> BitWriter<'_, RqrSpec, 1> {
+            self.bit_writer()
+        }
+    }
+}
+
+// ---- a small driver on top of the register API ----
+
+pub use cr1::Parity;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    pub baud: u32,
+    pub parity: Option<Parity>,
+}
+
+pub struct Serial<'a> {
+    regs: &'a RegisterBlock,
+}
+
+// BRR holds USARTDIV = pclk / (16 * baud) as 12.4 fixed point
+pub fn baud_divider(pclk_hz: u32, baud: u32) -> (u32, u32) {
+    let div16 = (pclk_hz + baud / 2) / baud;
+    (div16 >> 4, div16 & 0xF)
+}
+
+impl<'a> Serial<'a> {
+    pub fn new(regs: &'a RegisterBlock, pclk_hz: u32, config: Config) -> Serial<'a> {
+        let (mantissa, fraction) = baud_divider(pclk_hz, config.baud);
+        regs.brr
+            .write(|w| w.mantissa().bits(mantissa).fraction().bits(fraction));
+
+        // The parity bit takes a data bit, so 8 data bits + parity needs M=9
+        regs.cr1.write(|w| {
+            let w = match config.parity {
+                Some(parity) => w
+                    .m()
+                    .variant(cr1::WordLength::Bits9)
+                    .pce()
+                    .set_bit()
+                    .ps()
+                    .variant(parity),
+                None => w,
+            };
+            w.te().set_bit().re().set_bit().ue().set_bit()
+        });
+        Serial { regs }
+    }
+
+    pub fn write_byte(&mut self, byte: u8) {
+        while !self.regs.sr.read().txe() {}
+        self.regs.dr.write(|w| w.dr().bits(byte as u32));
+    }
+
+    pub fn read_byte(&mut self) -> Option<u8> {
+        if self.regs.sr.read().rxne() {
+            Some(self.regs.dr.read().dr() as u8)
+        } else {
+            None
+        }
+    }
+
+    pub fn listen(&mut self) {
+        self.regs.cr1.modify(|_, w| w.rxneie().set_bit());
+    }
+
+    pub fn send_break(&mut self) {
+        self.regs.rqr.write(|w| w.sbkrq().set_bit());
+    }
+
+    pub fn release(self) {
+        self.regs.cr1.modify(|_, w| w.ue().clear_bit());
+    }
+}
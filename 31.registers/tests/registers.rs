@@ -0,0 +1,111 @@
+use registers::{ReadWrite, Reg, RegisterSpec, WriteOnly, W};
+
+struct CtrlSpec;
+impl RegisterSpec for CtrlSpec {
+    type Access = ReadWrite;
+    const RESET: u32 = 0x0000_0F00;
+}
+
+#[derive(Clone, Copy)]
+enum Mode {
+    Off = 0,
+    Fast = 5,
+}
+
+impl From<Mode> for u32 {
+    fn from(value: Mode) -> u32 {
+        value as u32
+    }
+}
+
+// Field accessors as a generated register would define them
+trait CtrlW {
+    fn en(&mut self) -> registers::BitWriter<'_, CtrlSpec, 0>;
+    fn mode(&mut self) -> registers::FieldWriter<'_, CtrlSpec, Mode, 4, 3>;
+    fn count(&mut self) -> registers::FieldWriter<'_, CtrlSpec, u32, 8, 8>;
+}
+
+impl CtrlW for W<CtrlSpec> {
+    fn en(&mut self) -> registers::BitWriter<'_, CtrlSpec, 0> {
+        self.bit_writer()
+    }
+    fn mode(&mut self) -> registers::FieldWriter<'_, CtrlSpec, Mode, 4, 3> {
+        self.field_writer()
+    }
+    fn count(&mut self) -> registers::FieldWriter<'_, CtrlSpec, u32, 8, 8> {
+        self.field_writer()
+    }
+}
+
+struct CmdSpec;
+impl RegisterSpec for CmdSpec {
+    type Access = WriteOnly;
+    const RESET: u32 = 0x8000_0000;
+}
+
+#[test]
+fn registers_start_at_their_reset_value() {
+    let reg: Reg<CtrlSpec> = Reg::at_reset();
+    assert_eq!(reg.read().bits(), 0x0F00);
+    assert_eq!(reg.read().field(8, 8), 0x0F);
+    assert!(!reg.read().bit(0));
+}
+
+#[test]
+fn write_starts_from_reset_and_sets_fields() {
+    let reg: Reg<CtrlSpec> = Reg::at_reset();
+    reg.write(|w| w.en().set_bit().mode().variant(Mode::Fast));
+    // The count field keeps its reset value because write() starts there
+    assert_eq!(reg.read().bits(), 0x0F00 | (5 << 4) | 1);
+
+    reg.write(|w| w.count().bits(0xAB));
+    assert_eq!(reg.read().bits(), 0xAB00);
+}
+
+#[test]
+fn modify_changes_only_the_named_fields() {
+    let reg: Reg<CtrlSpec> = Reg::at_reset();
+    reg.write(|w| w.en().set_bit().mode().variant(Mode::Fast).count().bits(3));
+    reg.modify(|r, w| {
+        assert_eq!(r.field(8, 8), 3);
+        w.count().bits(r.field(8, 8) + 1)
+    });
+    assert_eq!(reg.read().bits(), (4 << 8) | (5 << 4) | 1);
+
+    reg.modify(|_, w| w.en().clear_bit().mode().variant(Mode::Off));
+    assert_eq!(reg.read().bits(), 4 << 8);
+}
+
+#[test]
+fn reset_restores_the_reset_value() {
+    let reg: Reg<CtrlSpec> = Reg::at_reset();
+    reg.write(|w| w.count().bits(0));
+    reg.reset();
+    assert_eq!(reg.read().bits(), 0x0F00);
+}
+
+#[test]
+fn field_writes_do_not_touch_neighbours() {
+    let reg: Reg<CtrlSpec> = Reg::at_reset();
+    // SAFETY: all ones is just a test pattern for a register in memory
+    reg.write(|w| unsafe { w.bits(u32::MAX) });
+    reg.modify(|_, w| w.mode().variant(Mode::Off));
+    assert_eq!(reg.read().bits(), !(0b111 << 4));
+    reg.modify(|_, w| w.count().bits(0));
+    assert_eq!(reg.read().bits(), !(0b111 << 4) & !(0xFF << 8));
+}
+
+#[test]
+fn write_only_registers_are_written_through_the_pointer() {
+    let reg: Reg<CmdSpec> = Reg::at_reset();
+    reg.write(|w| w);
+    // SAFETY: the register lives on this stack frame
+    assert_eq!(unsafe { reg.as_ptr().read_volatile() }, 0x8000_0000);
+}
+
+#[test]
+#[should_panic(expected = "does not fit in 8 bits")]
+fn numeric_fields_reject_values_that_do_not_fit() {
+    let reg: Reg<CtrlSpec> = Reg::at_reset();
+    reg.write(|w| w.count().bits(0x100));
+}
@@ -0,0 +1,129 @@
+use registers::mock::Mock;
+use registers::uart::{baud_divider, Config, Parity, RegisterBlock, Serial};
+
+const SR: usize = 0x00;
+const DR: usize = 0x04;
+const BRR: usize = 0x08;
+const CR1: usize = 0x0C;
+const RQR: usize = 0x10;
+
+const PCLK_HZ: u32 = 8_000_000;
+
+fn serial(mock: &Mock<RegisterBlock>, parity: Option<Parity>) -> Serial<'_> {
+    Serial::new(
+        mock.regs(),
+        PCLK_HZ,
+        Config {
+            baud: 115_200,
+            parity,
+        },
+    )
+}
+
+#[test]
+fn reset_values() {
+    let mock = Mock::new(RegisterBlock::at_reset());
+    assert_eq!(
+        mock.dump(),
+        [(SR, 0xC0), (DR, 0), (BRR, 0), (CR1, 0), (RQR, 0)]
+    );
+    let sr = mock.regs().sr.read();
+    assert!(sr.txe() && sr.tc() && !sr.rxne() && !sr.ore());
+}
+
+#[test]
+fn baud_dividers_match_the_reference_manual() {
+    // USARTDIV values from the STM32F1 reference manual's baud rate table
+    assert_eq!(baud_divider(8_000_000, 115_200), (4, 5));
+    assert_eq!(baud_divider(8_000_000, 9_600), (52, 1));
+    assert_eq!(baud_divider(72_000_000, 115_200), (39, 1));
+    assert_eq!(baud_divider(36_000_000, 9_600), (234, 6));
+}
+
+#[test]
+fn init_with_even_parity() {
+    let mock = Mock::new(RegisterBlock::at_reset());
+    let _serial = serial(&mock, Some(Parity::Even));
+    assert_eq!(mock.peek(BRR), (4 << 4) | 5);
+    // UE | M | PCE | TE | RE, PS clear for even parity
+    assert_eq!(
+        mock.peek(CR1),
+        (1 << 13) | (1 << 12) | (1 << 10) | (1 << 3) | (1 << 2)
+    );
+    let cr1 = mock.regs().cr1.read();
+    assert_eq!(cr1.ps(), Parity::Even);
+    assert_eq!(cr1.m(), registers::uart::cr1::WordLength::Bits9);
+}
+
+#[test]
+fn init_with_odd_and_no_parity() {
+    let mock = Mock::new(RegisterBlock::at_reset());
+    let _serial = serial(&mock, Some(Parity::Odd));
+    assert_eq!(mock.peek(CR1) & (1 << 9), 1 << 9);
+    assert_eq!(mock.regs().cr1.read().ps(), Parity::Odd);
+
+    let mock = Mock::new(RegisterBlock::at_reset());
+    let _serial = serial(&mock, None);
+    assert_eq!(mock.peek(CR1), (1 << 13) | (1 << 3) | (1 << 2));
+}
+
+#[test]
+fn listen_preserves_the_other_bits() {
+    let mock = Mock::new(RegisterBlock::at_reset());
+    let mut serial = serial(&mock, Some(Parity::Even));
+    let before = mock.peek(CR1);
+    serial.listen();
+    assert_eq!(mock.peek(CR1), before | (1 << 5));
+    assert!(mock.regs().cr1.read().rxneie());
+}
+
+#[test]
+fn transmit_writes_each_byte_to_dr() {
+    let mock = Mock::new(RegisterBlock::at_reset());
+    let mut serial = serial(&mock, None);
+    let mut sent = Vec::new();
+    for &byte in b"OK\n" {
+        serial.write_byte(byte);
+        sent.push(mock.peek(DR) as u8);
+    }
+    assert_eq!(sent, b"OK\n");
+}
+
+#[test]
+fn receive_only_when_rxne_is_set() {
+    let mock = Mock::new(RegisterBlock::at_reset());
+    let mut serial = serial(&mock, None);
+    mock.poke(DR, b'x' as u32);
+    assert_eq!(serial.read_byte(), None);
+    mock.set_bits(SR, 1 << 5);
+    assert_eq!(serial.read_byte(), Some(b'x'));
+    mock.clear_bits(SR, 1 << 5);
+    assert_eq!(serial.read_byte(), None);
+}
+
+#[test]
+fn break_request_and_release() {
+    let mock = Mock::new(RegisterBlock::at_reset());
+    let mut serial = serial(&mock, None);
+    serial.send_break();
+    assert_eq!(mock.peek(RQR), 1 << 1);
+    serial.release();
+    let cr1 = mock.regs().cr1.read();
+    assert!(!cr1.ue());
+    // Releasing only disables the USART
+    assert!(cr1.te() && cr1.re());
+}
+
+#[test]
+#[should_panic(expected = "bad register offset")]
+fn mock_rejects_offsets_outside_the_block() {
+    let mock = Mock::new(RegisterBlock::at_reset());
+    mock.peek(0x14);
+}
+
+#[test]
+#[should_panic(expected = "bad register offset")]
+fn mock_rejects_unaligned_offsets() {
+    let mock = Mock::new(RegisterBlock::at_reset());
+    mock.poke(0x02, 0);
+}
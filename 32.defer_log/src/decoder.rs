@@ -0,0 +1,295 @@
+// Host-side decoder: frames + interned table -> text
+//
+// The table normally comes from the firmware's ELF file; here it can be
+// taken from this binary (`Table::from_binary`) or saved to and loaded
+// from a text file, which is what a separate host tool would do.
+
+use crate::encode::{unzigzag, Level, Tag, MAX_FRAME};
+use cobs::{FrameDecoder, FrameError};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableEntry {
+    pub level: Level,
+    pub location: String,
+    pub format: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    entries: Vec<TableEntry>,
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+impl Table {
+    pub fn from_binary() -> Table {
+        Table {
+            entries: crate::table()
+                .iter()
+                .map(|e| TableEntry {
+                    level: e.level,
+                    location: format!("{}:{}", e.file, e.line),
+                    format: e.format.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    // One line per id: level <TAB> location <TAB> format
+    pub fn to_text(&self) -> String {
+        self.entries
+            .iter()
+            .map(|e| {
+                format!(
+                    "{}\t{}\t{}\n",
+                    e.level as u8,
+                    escape(&e.location),
+                    escape(&e.format)
+                )
+            })
+            .collect()
+    }
+
+    pub fn parse(text: &str) -> Result<Table, DecodeError> {
+        let mut entries = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let bad = || DecodeError::BadTable { line: index + 1 };
+            let mut parts = line.splitn(3, '\t');
+            let level = parts
+                .next()
+                .and_then(|l| l.parse().ok())
+                .and_then(Level::from_u8)
+                .ok_or_else(bad)?;
+            let location = parts.next().ok_or_else(bad)?;
+            let format = parts.next().ok_or_else(bad)?;
+            entries.push(TableEntry {
+                level,
+                location: unescape(location),
+                format: unescape(format),
+            });
+        }
+        Ok(Table { entries })
+    }
+
+    pub fn get(&self, id: usize) -> Option<&TableEntry> {
+        self.entries.get(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TableEntry> {
+        self.entries.iter()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Arg {
+    Unsigned(u64),
+    Signed(i64),
+    F32(f32),
+    Bool(bool),
+    Str(String),
+}
+
+impl fmt::Display for Arg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Arg::Unsigned(v) => write!(f, "{}", v),
+            Arg::Signed(v) => write!(f, "{}", v),
+            Arg::F32(v) => write!(f, "{}", v),
+            Arg::Bool(v) => write!(f, "{}", v),
+            Arg::Str(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub id: usize,
+    pub timestamp_ms: u64,
+    pub level: Level,
+    pub location: String,
+    pub text: String,
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>4}.{:03} {:<5} {}  ({})",
+            self.timestamp_ms / 1000,
+            self.timestamp_ms % 1000,
+            self.level,
+            self.text,
+            self.location
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    Frame(FrameError),
+    UnknownId(u64),
+    Malformed(&'static str),
+    BadTable { line: usize },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Frame(e) => write!(f, "bad frame: {}", e),
+            DecodeError::UnknownId(id) => write!(f, "id {} is not in the table", id),
+            DecodeError::Malformed(what) => write!(f, "malformed frame: {}", what),
+            DecodeError::BadTable { line } => write!(f, "table line {} is invalid", line),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Result<u8, DecodeError> {
+        let (&first, rest) = self
+            .bytes
+            .split_first()
+            .ok_or(DecodeError::Malformed("unexpected end"))?;
+        self.bytes = rest;
+        Ok(first)
+    }
+
+    fn take(&mut self, n: usize) -> Result<&[u8], DecodeError> {
+        if self.bytes.len() < n {
+            return Err(DecodeError::Malformed("unexpected end"));
+        }
+        let (head, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn varint(&mut self) -> Result<u64, DecodeError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(DecodeError::Malformed("varint too long"))
+    }
+
+    fn arg(&mut self) -> Result<Arg, DecodeError> {
+        let tag = Tag::from_u8(self.byte()?).ok_or(DecodeError::Malformed("unknown tag"))?;
+        Ok(match tag {
+            Tag::Unsigned => Arg::Unsigned(self.varint()?),
+            Tag::Signed => Arg::Signed(unzigzag(self.varint()?)),
+            Tag::F32 => Arg::F32(f32::from_le_bytes(self.take(4)?.try_into().unwrap())),
+            Tag::Bool => Arg::Bool(self.byte()? != 0),
+            Tag::Str => {
+                let len = self.varint()? as usize;
+                Arg::Str(String::from_utf8_lossy(self.take(len)?).into_owned())
+            }
+        })
+    }
+}
+
+// Fill `{}` placeholders in order; arguments dropped on the target for
+// lack of frame space show as <?>
+pub fn render(format: &str, args: &[Arg]) -> String {
+    let mut out = String::with_capacity(format.len() + args.len() * 4);
+    let mut args = args.iter();
+    let mut rest = format;
+    while let Some(pos) = rest.find("{}") {
+        out.push_str(&rest[..pos]);
+        match args.next() {
+            Some(arg) => out.push_str(&arg.to_string()),
+            None => out.push_str("<?>"),
+        }
+        rest = &rest[pos + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+pub struct Decoder {
+    table: Table,
+    frames: FrameDecoder<{ cobs::max_encoded_len(MAX_FRAME) }>,
+}
+
+impl Decoder {
+    pub fn new(table: Table) -> Decoder {
+        Decoder {
+            table,
+            frames: FrameDecoder::new(),
+        }
+    }
+
+    // Feed bytes as they arrive; returns every frame completed by them
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<Result<Message, DecodeError>> {
+        let mut out = Vec::new();
+        for &byte in bytes {
+            let result = match self.frames.push(byte) {
+                None => continue,
+                Some(Err(e)) => Err(DecodeError::Frame(e)),
+                Some(Ok(frame)) => Ok(frame.to_vec()),
+            };
+            out.push(result.and_then(|frame| self.parse(&frame)));
+        }
+        out
+    }
+
+    pub fn parse(&self, frame: &[u8]) -> Result<Message, DecodeError> {
+        let mut reader = Reader { bytes: frame };
+        let id = reader.varint()?;
+        let timestamp_ms = reader.varint()?;
+        let entry = self
+            .table
+            .get(id as usize)
+            .ok_or(DecodeError::UnknownId(id))?;
+
+        let mut args = Vec::new();
+        while !reader.bytes.is_empty() {
+            args.push(reader.arg()?);
+        }
+        Ok(Message {
+            id: id as usize,
+            timestamp_ms,
+            level: entry.level,
+            location: entry.location.clone(),
+            text: render(&entry.format, &args),
+        })
+    }
+}
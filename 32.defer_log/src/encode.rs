@@ -0,0 +1,211 @@
+// Wire encoding for log arguments
+//
+// Each argument is one tag byte followed by its value. Integers are LEB128
+// varints (signed ones zigzag-encoded first), so small numbers cost one
+// byte whatever their type.
+
+use core::fmt;
+
+pub const MAX_FRAME: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Tag {
+    Unsigned = 0,
+    Signed = 1,
+    F32 = 2,
+    Bool = 3,
+    Str = 4,
+}
+
+impl Tag {
+    pub fn from_u8(byte: u8) -> Option<Tag> {
+        match byte {
+            0 => Some(Tag::Unsigned),
+            1 => Some(Tag::Signed),
+            2 => Some(Tag::F32),
+            3 => Some(Tag::Bool),
+            4 => Some(Tag::Str),
+            _ => None,
+        }
+    }
+}
+
+// A frame being built on the stack; arguments that do not fit are dropped
+// whole and the decoder shows them as missing
+pub struct Frame {
+    buf: [u8; MAX_FRAME],
+    len: usize,
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Frame::new()
+    }
+}
+
+impl Frame {
+    pub const fn new() -> Frame {
+        Frame {
+            buf: [0; MAX_FRAME],
+            len: 0,
+        }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    // Run `f`, rolling back if it overflowed the frame
+    fn atomic(&mut self, f: impl FnOnce(&mut Writer) -> Option<()>) {
+        let mut writer = Writer {
+            buf: &mut self.buf,
+            len: self.len,
+        };
+        if f(&mut writer).is_some() {
+            self.len = writer.len;
+        }
+    }
+
+    pub fn varint(&mut self, value: u64) {
+        self.atomic(|w| w.varint(value));
+    }
+
+    pub fn tagged(&mut self, tag: Tag, f: impl FnOnce(&mut Writer) -> Option<()>) {
+        self.atomic(|w| {
+            w.byte(tag as u8)?;
+            f(w)
+        });
+    }
+}
+
+pub struct Writer<'a> {
+    buf: &'a mut [u8; MAX_FRAME],
+    len: usize,
+}
+
+impl Writer<'_> {
+    pub fn byte(&mut self, byte: u8) -> Option<()> {
+        *self.buf.get_mut(self.len)? = byte;
+        self.len += 1;
+        Some(())
+    }
+
+    pub fn bytes(&mut self, bytes: &[u8]) -> Option<()> {
+        let end = self.len + bytes.len();
+        self.buf.get_mut(self.len..end)?.copy_from_slice(bytes);
+        self.len = end;
+        Some(())
+    }
+
+    pub fn varint(&mut self, mut value: u64) -> Option<()> {
+        loop {
+            let low = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                return self.byte(low);
+            }
+            self.byte(low | 0x80)?;
+        }
+    }
+}
+
+pub fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+pub fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+// Anything that can be a log argument
+pub trait Encode {
+    fn encode(&self, frame: &mut Frame);
+}
+
+impl<T: Encode + ?Sized> Encode for &T {
+    fn encode(&self, frame: &mut Frame) {
+        (**self).encode(frame)
+    }
+}
+
+macro_rules! encode_unsigned {
+    ($($t:ty),*) => {$(
+        impl Encode for $t {
+            fn encode(&self, frame: &mut Frame) {
+                frame.tagged(Tag::Unsigned, |w| w.varint(*self as u64));
+            }
+        }
+    )*};
+}
+
+macro_rules! encode_signed {
+    ($($t:ty),*) => {$(
+        impl Encode for $t {
+            fn encode(&self, frame: &mut Frame) {
+                frame.tagged(Tag::Signed, |w| w.varint(zigzag(*self as i64)));
+            }
+        }
+    )*};
+}
+
+encode_unsigned!(u8, u16, u32, u64, usize);
+encode_signed!(i8, i16, i32, i64, isize);
+
+impl Encode for f32 {
+    fn encode(&self, frame: &mut Frame) {
+        frame.tagged(Tag::F32, |w| w.bytes(&self.to_le_bytes()));
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, frame: &mut Frame) {
+        frame.tagged(Tag::Bool, |w| w.byte(*self as u8));
+    }
+}
+
+// Strings are the one thing sent as text; keep them short
+impl Encode for str {
+    fn encode(&self, frame: &mut Frame) {
+        frame.tagged(Tag::Str, |w| {
+            w.varint(self.len() as u64)?;
+            w.bytes(self.as_bytes())
+        });
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Level {
+    Trace = 0,
+    Debug = 1,
+    Info = 2,
+    Warn = 3,
+    Error = 4,
+}
+
+impl Level {
+    pub fn from_u8(byte: u8) -> Option<Level> {
+        match byte {
+            0 => Some(Level::Trace),
+            1 => Some(Level::Debug),
+            2 => Some(Level::Info),
+            3 => Some(Level::Warn),
+            4 => Some(Level::Error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Trace => "TRACE",
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        };
+        f.pad(name)
+    }
+}
@@ -0,0 +1,251 @@
+// Deferred-formatting logger in the style of `defmt`
+//
+// Formatting text on a microcontroller costs flash (the format strings and
+// `core::fmt` itself), RAM and CPU time. This logger does none of it on the
+// target:
+//
+//   info!("temp {} on sensor {}", t, id)
+//     -> the format string goes into a table in the binary, not the log
+//     -> the frame holds only [table index][timestamp][t][id]
+//     -> a decoder on the host looks the string up and does the formatting
+//
+// Frames are COBS-encoded with a 0x00 delimiter and written into a ring
+// buffer under a critical section, so interrupt handlers can log too. The
+// application drains the buffer to UART/RTT/USB whenever it likes.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod encode;
+
+#[cfg(feature = "std")]
+pub mod decoder;
+
+pub use encode::{Encode, Frame, Level, MAX_FRAME};
+
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+use isr_queue::cs::{self, Mutex};
+
+// One call site: what the decoder needs to turn a frame back into text.
+// The statics live in their own linker section so they form an array.
+#[repr(C)]
+pub struct Entry {
+    pub level: Level,
+    pub format: &'static str,
+    pub file: &'static str,
+    pub line: u32,
+}
+
+extern "C" {
+    // Defined by the ELF linker for any section whose name is an identifier
+    static __start_defer_log_strings: u8;
+    static __stop_defer_log_strings: u8;
+}
+
+// Every interned call site in the program; a frame's id indexes this
+pub fn table() -> &'static [Entry] {
+    // SAFETY: the section contains only `Entry` statics placed by `log!`
+    unsafe {
+        let start = &raw const __start_defer_log_strings as *const Entry;
+        let stop = &raw const __stop_defer_log_strings as *const Entry;
+        core::slice::from_raw_parts(start, stop.offset_from(start) as usize)
+    }
+}
+
+pub fn id_of(entry: &'static Entry) -> usize {
+    let start = table().as_ptr() as usize;
+    (entry as *const Entry as usize - start) / core::mem::size_of::<Entry>()
+}
+
+// Number of `{}` in a format string, checked against the argument count at
+// compile time
+pub const fn placeholders(format: &str) -> usize {
+    let bytes = format.as_bytes();
+    let mut count = 0;
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        if bytes[i] == b'{' && bytes[i + 1] == b'}' {
+            count += 1;
+            i += 1;
+        }
+        i += 1;
+    }
+    count
+}
+
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $format:literal $(, $arg:expr)* $(,)?) => {{
+        const _: () = assert!(
+            $crate::placeholders($format) == <[&str]>::len(&[$(stringify!($arg)),*]),
+            "number of {{}} placeholders does not match the arguments"
+        );
+        #[link_section = "defer_log_strings"]
+        #[used]
+        static ENTRY: $crate::Entry = $crate::Entry {
+            level: $level,
+            format: $format,
+            file: file!(),
+            line: line!(),
+        };
+        $crate::write_entry(&ENTRY, &[$(&$arg as &dyn $crate::Encode),*]);
+    }};
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($t:tt)*) => { $crate::log!($crate::Level::Trace, $($t)*) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($t:tt)*) => { $crate::log!($crate::Level::Debug, $($t)*) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($t:tt)*) => { $crate::log!($crate::Level::Info, $($t)*) };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($t:tt)*) => { $crate::log!($crate::Level::Warn, $($t)*) };
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($t:tt)*) => { $crate::log!($crate::Level::Error, $($t)*) };
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LogStats {
+    pub frames: u32,
+    pub bytes: u32,
+    // Frames discarded because the buffer was full
+    pub dropped: u32,
+}
+
+struct ByteRing<const N: usize> {
+    buf: [u8; N],
+    head: usize,
+    len: usize,
+    stats: LogStats,
+}
+
+// Byte ring that only ever holds whole frames
+pub struct LogBuffer<const N: usize> {
+    ring: Mutex<RefCell<ByteRing<N>>>,
+}
+
+impl<const N: usize> Default for LogBuffer<N> {
+    fn default() -> Self {
+        LogBuffer::new()
+    }
+}
+
+impl<const N: usize> LogBuffer<N> {
+    pub const fn new() -> LogBuffer<N> {
+        LogBuffer {
+            ring: Mutex::new(RefCell::new(ByteRing {
+                buf: [0; N],
+                head: 0,
+                len: 0,
+                stats: LogStats {
+                    frames: 0,
+                    bytes: 0,
+                    dropped: 0,
+                },
+            })),
+        }
+    }
+
+    // All or nothing, so a reader never sees half a frame
+    pub fn write_frame(&self, frame: &[u8]) -> bool {
+        cs::with(|cs| {
+            let mut ring = self.ring.borrow(cs).borrow_mut();
+            if N - ring.len < frame.len() {
+                ring.stats.dropped += 1;
+                return false;
+            }
+            // At most two copies: up to the end of the array, then wrapped
+            let tail = (ring.head + ring.len) % N;
+            let first = frame.len().min(N - tail);
+            ring.buf[tail..tail + first].copy_from_slice(&frame[..first]);
+            ring.buf[..frame.len() - first].copy_from_slice(&frame[first..]);
+            ring.len += frame.len();
+            ring.stats.frames += 1;
+            ring.stats.bytes += frame.len() as u32;
+            true
+        })
+    }
+
+    // Move up to `out.len()` bytes to the transport
+    pub fn read(&self, out: &mut [u8]) -> usize {
+        cs::with(|cs| {
+            let mut ring = self.ring.borrow(cs).borrow_mut();
+            let n = out.len().min(ring.len);
+            let head = ring.head;
+            let first = n.min(N - head);
+            out[..first].copy_from_slice(&ring.buf[head..head + first]);
+            out[first..n].copy_from_slice(&ring.buf[..n - first]);
+            ring.head = (head + n) % N;
+            ring.len -= n;
+            n
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        cs::with(|cs| self.ring.borrow(cs).borrow().len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> LogStats {
+        cs::with(|cs| self.ring.borrow(cs).borrow().stats)
+    }
+}
+
+pub const BUFFER_SIZE: usize = 1024;
+
+static BUFFER: LogBuffer<BUFFER_SIZE> = LogBuffer::new();
+static MIN_LEVEL: AtomicU8 = AtomicU8::new(Level::Trace as u8);
+static NOW_MS: AtomicU32 = AtomicU32::new(0);
+
+pub fn set_level(level: Level) {
+    MIN_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+// Called from the tick interrupt; stamped on every frame
+pub fn set_time(ms: u32) {
+    NOW_MS.store(ms, Ordering::Relaxed);
+}
+
+pub fn buffer() -> &'static LogBuffer<BUFFER_SIZE> {
+    &BUFFER
+}
+
+// Encode one call: [id][timestamp][args...], COBS-framed
+pub fn encode_entry(entry: &'static Entry, args: &[&dyn Encode]) -> Frame {
+    let mut frame = Frame::new();
+    frame.varint(id_of(entry) as u64);
+    frame.varint(NOW_MS.load(Ordering::Relaxed) as u64);
+    for arg in args {
+        arg.encode(&mut frame);
+    }
+    frame
+}
+
+pub fn write_entry(entry: &'static Entry, args: &[&dyn Encode]) {
+    if (entry.level as u8) < MIN_LEVEL.load(Ordering::Relaxed) {
+        return;
+    }
+    let frame = encode_entry(entry, args);
+
+    let mut framed = [0u8; cobs::max_encoded_len(MAX_FRAME) + 1];
+    // The buffer is sized for the largest frame, so this cannot fail
+    let n = cobs::encode_into(frame.bytes(), &mut framed).unwrap_or(0);
+    framed[n] = cobs::DELIMITER;
+    BUFFER.write_frame(&framed[..=n]);
+}
@@ -0,0 +1,234 @@
+use defer_log::decoder::{Decoder, Table};
+use defer_log::{buffer, debug, error, info, set_level, set_time, warn, Level, BUFFER_SIZE};
+use std::fmt::Write;
+use std::fs;
+use std::thread;
+use std::time::Instant;
+
+// Drain everything the target has logged so far, as a transport would
+fn drain() -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut chunk = [0u8; 32];
+    loop {
+        let n = buffer().read(&mut chunk);
+        if n == 0 {
+            return out;
+        }
+        out.extend_from_slice(&chunk[..n]);
+    }
+}
+
+fn frames(bytes: &[u8]) -> Vec<&[u8]> {
+    bytes
+        .split_inclusive(|&b| b == 0)
+        .filter(|f| !f.is_empty())
+        .collect()
+}
+
+fn boot_sequence() {
+    set_time(12);
+    info!("boot: firmware {} build {}", "1.4.2", 0x2f1au32);
+    set_time(15);
+    debug!("adc calibrated, offset {} gain {}", -3i16, 1.0021f32);
+    set_time(1_250);
+    info!("sensor {} temp {} C", 2u8, 23.75f32);
+    set_time(1_251);
+    warn!("battery low: {} mV", 3_310u16);
+    set_time(2_000);
+    error!("radio timeout after {} retries, link up: {}", 3u8, false);
+}
+
+fn main() {
+    println!("=== Deferred Logging ===\n");
+
+    // 1. What actually leaves the device
+    println!("1. Frames in the log buffer:");
+    boot_sequence();
+    let bytes = drain();
+    for frame in frames(&bytes) {
+        println!("   {:02x?}", frame);
+    }
+    println!("   (no format strings - only ids, timestamps and arguments)");
+
+    // 2. The interned table: every log call site in the program
+    println!("\n2. Interned format strings:");
+    let table = Table::from_binary();
+    for (id, entry) in table.iter().enumerate() {
+        println!(
+            "   {:>2} {:<5} {:<50} {}",
+            id, entry.level, entry.format, entry.location
+        );
+    }
+
+    // 3. A host tool decodes with the table saved from the build
+    println!("\n3. Host decoder (table loaded from file, bytes fed 7 at a time):");
+    let path = std::env::temp_dir().join("rust-sys-defer-log.table");
+    fs::write(&path, table.to_text()).expect("write table");
+    let loaded = Table::parse(&fs::read_to_string(&path).expect("read table")).expect("parse");
+    let mut decoder = Decoder::new(loaded);
+    let mut messages = Vec::new();
+    for chunk in bytes.chunks(7) {
+        messages.extend(decoder.feed(chunk));
+    }
+    for message in &messages {
+        match message {
+            Ok(m) => println!("   {}", m),
+            Err(e) => println!("   <{}>", e),
+        }
+    }
+
+    // 4. Bytes on the wire compared to formatting on the target
+    println!("\n4. Size on the wire:");
+    let mut wire = 0;
+    let mut text = 0;
+    for (frame, message) in frames(&bytes).iter().zip(&messages) {
+        let m = message.as_ref().unwrap();
+        let line = format!("[{}] {}\n", m.level, m.text);
+        wire += frame.len();
+        text += line.len();
+        println!(
+            "   {:>2} bytes vs {:>2} bytes  {}",
+            frame.len(),
+            line.len(),
+            m.text
+        );
+    }
+    println!(
+        "   total {} vs {} bytes ({:.0}% of the text size)",
+        wire,
+        text,
+        100.0 * wire as f64 / text as f64
+    );
+
+    // 5. CPU time per message on the logging side; both paths store into
+    // the same ring buffer and are drained into the same sink
+    println!("\n5. Cost per call (host CPU, 100k calls):");
+    const CALLS: u32 = 100_000;
+    let mut sink = [0u8; 256];
+    let before = buffer().stats().bytes;
+    let start = Instant::now();
+    for i in 0..CALLS {
+        info!(
+            "sensor {} temp {} C",
+            (i % 8) as u8,
+            20.0f32 + (i % 50) as f32 * 0.1
+        );
+        if i % 8 == 0 {
+            while buffer().read(&mut sink) > 0 {}
+        }
+    }
+    let deferred = start.elapsed();
+    let stored = buffer().stats().bytes - before;
+
+    let mut line = String::with_capacity(64);
+    let start = Instant::now();
+    for i in 0..CALLS {
+        line.clear();
+        let _ = writeln!(
+            line,
+            "[INFO] sensor {} temp {} C",
+            (i % 8) as u8,
+            20.0f32 + (i % 50) as f32 * 0.1
+        );
+        buffer().write_frame(line.as_bytes());
+        if i % 8 == 0 {
+            while buffer().read(&mut sink) > 0 {}
+        }
+    }
+    while buffer().read(&mut sink) > 0 {}
+    let formatted = start.elapsed();
+    let stored_text = buffer().stats().bytes - before - stored;
+    println!(
+        "   deferred:  {:>4} ns/call, {:>7} bytes stored",
+        deferred.as_nanos() / CALLS as u128,
+        stored
+    );
+    println!(
+        "   formatted: {:>4} ns/call, {:>7} bytes stored",
+        formatted.as_nanos() / CALLS as u128,
+        stored_text
+    );
+    println!(
+        "   (desktop CPU, try --release; a Cortex-M without an FPU formats floats far more slowly)"
+    );
+
+    // 6. Logging from an interrupt handler while main logs too
+    println!("\n6. Two contexts logging at once:");
+    let mut decoder = Decoder::new(Table::from_binary());
+    let mut decoded = 0;
+    let mut errors = 0;
+    thread::scope(|s| {
+        let isr = s.spawn(|| {
+            for i in 0..300u32 {
+                warn!("isr: overrun #{}", i);
+                thread::yield_now();
+            }
+        });
+        let mut i = 0u32;
+        while i < 300 || !isr.is_finished() {
+            if i < 300 {
+                info!("main: loop {}", i);
+                i += 1;
+            }
+            for result in decoder.feed(&drain()) {
+                match result {
+                    Ok(_) => decoded += 1,
+                    Err(_) => errors += 1,
+                }
+            }
+            thread::yield_now();
+        }
+    });
+    for result in decoder.feed(&drain()) {
+        match result {
+            Ok(_) => decoded += 1,
+            Err(_) => errors += 1,
+        }
+    }
+    let stats = buffer().stats();
+    println!(
+        "   decoded {} frames, {} errors, {} dropped (frames never interleave)",
+        decoded, errors, stats.dropped
+    );
+
+    // 7. A full buffer drops whole frames; the level filter skips encoding
+    println!(
+        "\n7. Overflow and level filtering ({}-byte buffer):",
+        BUFFER_SIZE
+    );
+    let before = buffer().stats().dropped;
+    for i in 0..200u32 {
+        info!("flood {}", i);
+    }
+    println!(
+        "   200 frames without draining -> {} dropped, {} bytes buffered",
+        buffer().stats().dropped - before,
+        buffer().len()
+    );
+    drain();
+    set_level(Level::Warn);
+    debug!("not encoded");
+    info!("not encoded either");
+    warn!("still logged");
+    let kept = Decoder::new(Table::from_binary()).feed(&drain());
+    println!("   level Warn: {} of 3 messages encoded", kept.len());
+    set_level(Level::Trace);
+
+    // 8. Transport damage is confined to one frame
+    println!("\n8. Corrupted stream:");
+    info!("first {}", 1u8);
+    info!("second {}", 2u8);
+    let mut damaged = drain();
+    damaged[1] ^= 0x7f;
+    for result in Decoder::new(Table::from_binary()).feed(&damaged) {
+        match result {
+            Ok(m) => println!("   ok   {}", m.text),
+            Err(e) => println!("   err  {}", e),
+        }
+    }
+
+    // Mismatched arguments do not compile:
+    //   info!("{} and {}", 1u8);  -> number of {} placeholders does not match
+
+    println!("\n=== End of Deferred Logging Examples ===");
+}
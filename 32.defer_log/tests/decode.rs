@@ -0,0 +1,243 @@
+use defer_log::decoder::{Arg, DecodeError, Decoder, Table};
+use defer_log::{buffer, error, info, set_level, set_time, warn, Level, LogBuffer};
+use std::sync::Mutex;
+
+// The log buffer, level and clock are globals; tests that log take turns
+static GLOBALS: Mutex<()> = Mutex::new(());
+
+fn drain() -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut chunk = [0u8; 32];
+    loop {
+        let n = buffer().read(&mut chunk);
+        if n == 0 {
+            return out;
+        }
+        out.extend_from_slice(&chunk[..n]);
+    }
+}
+
+fn frames(bytes: &[u8]) -> Vec<&[u8]> {
+    bytes.split_inclusive(|&b| b == 0).collect()
+}
+
+fn texts(decoder: &mut Decoder, bytes: &[u8]) -> Vec<String> {
+    decoder
+        .feed(bytes)
+        .into_iter()
+        .map(|m| m.unwrap().text)
+        .collect()
+}
+
+#[test]
+fn host_rebuilds_messages_from_ids_and_arguments() {
+    let _guard = GLOBALS.lock().unwrap();
+    set_level(Level::Trace);
+    drain();
+
+    set_time(1_250);
+    info!("sensor {} temp {} C", 2u8, 23.75f32);
+    set_time(1_300);
+    warn!("battery {} mV, charging {}", 3_312u16, false);
+    error!("offset {} on {}", -40i32, "imu");
+    info!("no arguments");
+
+    let mut decoder = Decoder::new(Table::from_binary());
+    let messages: Vec<_> = decoder
+        .feed(&drain())
+        .into_iter()
+        .map(Result::unwrap)
+        .collect();
+
+    let texts: Vec<_> = messages.iter().map(|m| m.text.as_str()).collect();
+    assert_eq!(
+        texts,
+        [
+            "sensor 2 temp 23.75 C",
+            "battery 3312 mV, charging false",
+            "offset -40 on imu",
+            "no arguments",
+        ]
+    );
+    assert_eq!(messages[0].timestamp_ms, 1_250);
+    assert_eq!(messages[1].timestamp_ms, 1_300);
+    assert_eq!(
+        messages.iter().map(|m| m.level).collect::<Vec<_>>(),
+        [Level::Info, Level::Warn, Level::Error, Level::Info]
+    );
+    assert!(messages[0].location.starts_with("tests/decode.rs:"));
+}
+
+#[test]
+fn frames_hold_ids_not_strings() {
+    let _guard = GLOBALS.lock().unwrap();
+    set_level(Level::Trace);
+    drain();
+
+    info!(
+        "a rather long format string that never leaves the target {}",
+        1u8
+    );
+    let bytes = drain();
+    // COBS overhead + id + timestamp + tag + value: far shorter than the text
+    assert!(bytes.len() < 12, "{} bytes", bytes.len());
+    assert!(!bytes.windows(6).any(|w| w == b"rather"));
+}
+
+#[test]
+fn table_survives_a_text_round_trip() {
+    let _guard = GLOBALS.lock().unwrap();
+    set_level(Level::Trace);
+    drain();
+
+    info!("tab\there, newline\nthere: {}", 7u32);
+    let table = Table::parse(&Table::from_binary().to_text()).unwrap();
+    assert_eq!(table.len(), Table::from_binary().len());
+    let mut decoder = Decoder::new(table);
+    assert_eq!(
+        texts(&mut decoder, &drain()),
+        ["tab\there, newline\nthere: 7"]
+    );
+}
+
+#[test]
+fn messages_below_the_level_are_not_written() {
+    let _guard = GLOBALS.lock().unwrap();
+    drain();
+
+    set_level(Level::Warn);
+    info!("filtered {}", 1u8);
+    warn!("kept {}", 2u8);
+    set_level(Level::Trace);
+
+    let mut decoder = Decoder::new(Table::from_binary());
+    assert_eq!(texts(&mut decoder, &drain()), ["kept 2"]);
+}
+
+#[test]
+fn unknown_format_id_is_an_error() {
+    let _guard = GLOBALS.lock().unwrap();
+    set_level(Level::Trace);
+    drain();
+
+    set_time(5);
+    info!("known to this binary only {}", 1u8);
+    let bytes = drain();
+
+    // A table from another firmware build, without this call site
+    let mut stale = Decoder::new(Table::default());
+    let results = stale.feed(&bytes);
+    assert!(matches!(results[..], [Err(DecodeError::UnknownId(_))]));
+
+    // Hand-made frame: id 200, timestamp 0
+    let decoder = Decoder::new(Table::from_binary());
+    assert_eq!(
+        decoder.parse(&[200, 1, 0]),
+        Err(DecodeError::UnknownId(200))
+    );
+}
+
+#[test]
+fn malformed_arguments_are_errors() {
+    let decoder = Decoder::new(Table::from_binary());
+    // Id 0, timestamp 0, then an unknown tag / a string cut short
+    assert_eq!(
+        decoder.parse(&[0, 0, 9]),
+        Err(DecodeError::Malformed("unknown tag"))
+    );
+    assert_eq!(
+        decoder.parse(&[0, 0, 4, 5, b'a']),
+        Err(DecodeError::Malformed("unexpected end"))
+    );
+    assert_eq!(
+        decoder.parse(&[0x80]),
+        Err(DecodeError::Malformed("unexpected end"))
+    );
+}
+
+#[test]
+fn missing_arguments_render_as_placeholders() {
+    use defer_log::decoder::render;
+    assert_eq!(render("{} of {}", &[Arg::Unsigned(3)]), "3 of <?>");
+    assert_eq!(render("{}", &[Arg::Signed(-1), Arg::Bool(true)]), "-1");
+}
+
+// Log three messages and return them framed, one slice per frame
+fn three_frames() -> Vec<Vec<u8>> {
+    let _guard = GLOBALS.lock().unwrap();
+    set_level(Level::Trace);
+    drain();
+
+    set_time(100);
+    info!("first {}", 1u8);
+    info!("second {}", "frame");
+    info!("third {}", 3u8);
+    frames(&drain()).into_iter().map(<[u8]>::to_vec).collect()
+}
+
+#[test]
+fn frame_straddling_the_ring_wrap_decodes() {
+    let frames = three_frames();
+    let ring = LogBuffer::<24>::new();
+    let mut decoder = Decoder::new(Table::from_binary());
+    let mut out = Vec::new();
+
+    // Push the head forward so the second frame wraps round the end
+    assert!(ring.write_frame(&frames[0]));
+    let mut sink = [0u8; 24];
+    ring.read(&mut sink[..frames[0].len()]);
+    let pad = 24 - frames[0].len() - frames[1].len() / 2;
+    assert!(ring.write_frame(&vec![0u8; pad]));
+    ring.read(&mut sink[..pad]);
+    assert!(ring.write_frame(&frames[1]));
+
+    // Drained in small chunks, one of which crosses the end of the array
+    let mut chunk = [0u8; 24];
+    while !ring.is_empty() {
+        let n = ring.read(&mut chunk[..5]);
+        out.extend(texts(&mut decoder, &chunk[..n]));
+    }
+    assert_eq!(out, ["second frame"]);
+}
+
+#[test]
+fn record_cut_off_at_the_ring_wrap_is_skipped() {
+    let frames = three_frames();
+    let mut decoder = Decoder::new(Table::from_binary());
+
+    // The tail of the second frame is lost (the reader was reset at the
+    // wrap); the link layer resynchronises on the next delimiter
+    let cut = &frames[1][..frames[1].len() / 2];
+    let mut stream = frames[0].clone();
+    stream.extend_from_slice(cut);
+    stream.push(0);
+    stream.extend_from_slice(&frames[2]);
+
+    let results = decoder.feed(&stream);
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap().text, "first 1");
+    assert!(results[1].is_err(), "{:?}", results[1]);
+    assert_eq!(results[2].as_ref().unwrap().text, "third 3");
+}
+
+#[test]
+fn full_ring_drops_whole_frames() {
+    let frames = three_frames();
+    let ring = LogBuffer::<16>::new();
+    let mut written = 0;
+    for frame in frames.iter().cycle().take(10) {
+        if ring.write_frame(frame) {
+            written += 1;
+        }
+    }
+    assert_eq!(ring.stats().frames, written);
+    assert_eq!(ring.stats().dropped, 10 - written);
+
+    // What is there decodes cleanly: never half a frame
+    let mut decoder = Decoder::new(Table::from_binary());
+    let mut bytes = [0u8; 16];
+    let n = ring.read(&mut bytes);
+    let results = decoder.feed(&bytes[..n]);
+    assert_eq!(results.len() as u32, written);
+    assert!(results.iter().all(Result::is_ok));
+}
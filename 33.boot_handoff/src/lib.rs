@@ -0,0 +1,308 @@
+// Bootloader / application handoff, simulated on the host
+//
+// The "flash" is a directory: two application slots and a boot-state
+// record. On every power-on the bootloader:
+//
+//   1. loads the persistent boot state
+//   2. if an update is pending, counts one more trial boot, or rolls back
+//      once the image has had `max_attempts` chances without confirming
+//   3. validates the chosen slot: slot CRC, then magic/format, payload
+//      hash, signature and anti-rollback version (the secure-boot lesson)
+//   4. saves the state *before* jumping, so a hang still counts
+//   5. "jumps": reads the entry address from the payload and calls the
+//      function registered at that address in the vector table
+//
+// The application calls `BootControl::confirm` once it is healthy, which
+// makes a trial image permanent.
+
+pub mod flash;
+pub mod state;
+
+pub use flash::{Slot, SlotError};
+pub use state::BootState;
+
+use secure_boot::ed25519_dalek::VerifyingKey;
+use secure_boot::BootError;
+use std::fmt;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+// What the application sees at startup, like a shared RAM block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Handoff {
+    pub slot: Slot,
+    pub version: u32,
+    pub trial: bool,
+    pub attempt: u8,
+    pub boot_number: u32,
+}
+
+// Why the application stopped running (HardFault, watchdog, ...)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fault(pub String);
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub type AppEntry = fn(&Handoff, &mut BootControl) -> Result<(), Fault>;
+
+// The application's view of the boot state
+pub struct BootControl<'a> {
+    dir: &'a Path,
+    handoff: Handoff,
+    confirmed: bool,
+}
+
+impl BootControl<'_> {
+    // Mark the running image good: no more rollback, and older versions
+    // are refused from now on
+    pub fn confirm(&mut self) -> io::Result<()> {
+        let mut state = BootState::load(self.dir);
+        state.active = self.handoff.slot;
+        state.pending = None;
+        state.attempts = 0;
+        state.min_version = state.min_version.max(self.handoff.version);
+        state.save(self.dir)?;
+        self.confirmed = true;
+        Ok(())
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed
+    }
+}
+
+// Payload layout: entry address (u32 LE) followed by the application
+pub fn build_payload(entry: u32, body: &[u8]) -> Vec<u8> {
+    let mut payload = entry.to_le_bytes().to_vec();
+    payload.extend_from_slice(body);
+    payload
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    Slot(SlotError),
+    Image(BootError),
+    NoEntryPoint,
+    UnknownEntry(u32),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::Slot(e) => write!(f, "{}", e),
+            ValidationError::Image(e) => write!(f, "{}", e),
+            ValidationError::NoEntryPoint => write!(f, "payload has no entry address"),
+            ValidationError::UnknownEntry(addr) => {
+                write!(f, "entry address {:#010x} is not executable", addr)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Rejected { slot: Slot, error: ValidationError },
+    RolledBack { slot: Slot, attempts: u8 },
+    FellBack { from: Slot, to: Slot },
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Rejected { slot, error } => write!(f, "slot {} rejected: {}", slot, error),
+            Event::RolledBack { slot, attempts } => write!(
+                f,
+                "slot {} unconfirmed after {} boots, rolled back",
+                slot, attempts
+            ),
+            Event::FellBack { from, to } => {
+                write!(f, "active slot {} unusable, switched to {}", from, to)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    // The application returned, i.e. requested a reset
+    Reset { confirmed: bool },
+    Faulted { fault: Fault, confirmed: bool },
+    // Nothing bootable: stay in the bootloader (recovery / DFU mode)
+    NoImage,
+}
+
+#[derive(Debug, Clone)]
+pub struct BootRecord {
+    pub events: Vec<Event>,
+    pub handoff: Option<Handoff>,
+    pub outcome: Outcome,
+    pub state: BootState,
+}
+
+pub struct Bootloader {
+    dir: PathBuf,
+    key: VerifyingKey,
+    max_attempts: u8,
+    // Stand-in for code in flash: address -> function
+    vectors: Vec<(u32, AppEntry)>,
+}
+
+impl Bootloader {
+    pub fn new(dir: impl Into<PathBuf>, key: VerifyingKey, max_attempts: u8) -> Bootloader {
+        Bootloader {
+            dir: dir.into(),
+            key,
+            max_attempts,
+            vectors: Vec::new(),
+        }
+    }
+
+    pub fn with_entry(mut self, address: u32, entry: AppEntry) -> Bootloader {
+        self.vectors.push((address, entry));
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn validate(
+        &self,
+        slot: Slot,
+        min_version: u32,
+    ) -> Result<(u32, AppEntry), ValidationError> {
+        let image = flash::read_slot(&self.dir, slot).map_err(ValidationError::Slot)?;
+        let verified = secure_boot::Bootloader::new(self.key, min_version)
+            .verify(&image)
+            .map_err(ValidationError::Image)?;
+
+        let address = verified
+            .payload
+            .get(0..4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+            .ok_or(ValidationError::NoEntryPoint)?;
+        let entry = self
+            .vectors
+            .iter()
+            .find(|(a, _)| *a == address)
+            .map(|(_, entry)| *entry)
+            .ok_or(ValidationError::UnknownEntry(address))?;
+        Ok((verified.version, entry))
+    }
+
+    pub fn power_on(&self) -> io::Result<BootRecord> {
+        let mut state = BootState::load(&self.dir);
+        state.boots += 1;
+        let mut events = Vec::new();
+        let mut chosen = None;
+
+        if let Some(pending) = state.pending {
+            if state.attempts >= self.max_attempts {
+                events.push(Event::RolledBack {
+                    slot: pending,
+                    attempts: state.attempts,
+                });
+                state.pending = None;
+                state.attempts = 0;
+                state.rollbacks += 1;
+            } else {
+                match self.validate(pending, state.min_version) {
+                    Ok((version, entry)) => {
+                        state.attempts += 1;
+                        chosen = Some((pending, version, entry, true));
+                    }
+                    Err(error) => {
+                        events.push(Event::Rejected {
+                            slot: pending,
+                            error,
+                        });
+                        state.pending = None;
+                        state.attempts = 0;
+                    }
+                }
+            }
+        }
+
+        if chosen.is_none() {
+            for slot in [state.active, state.active.other()] {
+                match self.validate(slot, state.min_version) {
+                    Ok((version, entry)) => {
+                        if slot != state.active {
+                            events.push(Event::FellBack {
+                                from: state.active,
+                                to: slot,
+                            });
+                            state.active = slot;
+                        }
+                        chosen = Some((slot, version, entry, false));
+                        break;
+                    }
+                    Err(error) => events.push(Event::Rejected { slot, error }),
+                }
+            }
+        }
+
+        // Persist before jumping: if the app hangs, this attempt still counts
+        state.save(&self.dir)?;
+
+        let Some((slot, version, entry, trial)) = chosen else {
+            return Ok(BootRecord {
+                events,
+                handoff: None,
+                outcome: Outcome::NoImage,
+                state,
+            });
+        };
+
+        let handoff = Handoff {
+            slot,
+            version,
+            trial,
+            attempt: if trial { state.attempts } else { 0 },
+            boot_number: state.boots,
+        };
+        let mut control = BootControl {
+            dir: &self.dir,
+            handoff,
+            confirmed: false,
+        };
+
+        // The jump. A panic in the app plays the part of a HardFault.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| entry(&handoff, &mut control)));
+        let confirmed = control.confirmed;
+        let outcome = match result {
+            Ok(Ok(())) => Outcome::Reset { confirmed },
+            Ok(Err(fault)) => Outcome::Faulted { fault, confirmed },
+            Err(_) => Outcome::Faulted {
+                fault: Fault("HardFault (panic)".to_string()),
+                confirmed,
+            },
+        };
+
+        Ok(BootRecord {
+            events,
+            handoff: Some(handoff),
+            outcome,
+            state: BootState::load(&self.dir),
+        })
+    }
+}
+
+// What the running application does to install an update: write the
+// inactive slot, then mark it pending for a trial boot
+pub fn stage_update(dir: &Path, image: &[u8]) -> io::Result<Slot> {
+    let mut state = BootState::load(dir);
+    let target = state.active.other();
+    flash::write_slot(dir, target, image)?;
+    state.pending = Some(target);
+    state.attempts = 0;
+    state.save(dir)?;
+    Ok(target)
+}
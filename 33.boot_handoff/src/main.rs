@@ -0,0 +1,204 @@
+use boot_handoff::flash::{self, Slot};
+use boot_handoff::{
+    build_payload, stage_update, state, BootControl, BootRecord, BootState, Bootloader, Fault,
+    Handoff, Outcome,
+};
+use secure_boot::ed25519_dalek::SigningKey;
+use secure_boot::sign_image;
+use std::fs;
+
+// Entry addresses, as they would appear in each image's vector table
+const STABLE: u32 = 0x0800_4000;
+const CRASHES: u32 = 0x0800_8000;
+const HEALTHY: u32 = 0x0800_C000;
+const HANGS: u32 = 0x0801_0000;
+
+fn stable_app(handoff: &Handoff, control: &mut BootControl) -> Result<(), Fault> {
+    if handoff.trial {
+        control.confirm().map_err(|e| Fault(e.to_string()))?;
+    }
+    Ok(())
+}
+
+// Dies during start-up before it gets to confirm
+fn crashing_app(_: &Handoff, _: &mut BootControl) -> Result<(), Fault> {
+    let sensors: Vec<u8> = Vec::new();
+    let _first = sensors[0];
+    Ok(())
+}
+
+// Runs a self-test, then confirms
+fn healthy_app(handoff: &Handoff, control: &mut BootControl) -> Result<(), Fault> {
+    let self_test_passed = true;
+    if handoff.trial && self_test_passed {
+        control.confirm().map_err(|e| Fault(e.to_string()))?;
+    }
+    Ok(())
+}
+
+fn hanging_app(_: &Handoff, _: &mut BootControl) -> Result<(), Fault> {
+    Err(Fault("watchdog reset".to_string()))
+}
+
+fn show(record: &BootRecord) {
+    for event in &record.events {
+        println!("   ! {}", event);
+    }
+    match (&record.handoff, &record.outcome) {
+        (_, Outcome::NoImage) => println!("   no bootable image - staying in recovery mode"),
+        (Some(h), outcome) => {
+            let how = match outcome {
+                Outcome::Reset { confirmed: true } => "confirmed, reset".to_string(),
+                Outcome::Reset { confirmed: false } => "reset".to_string(),
+                Outcome::Faulted { fault, .. } => format!("FAULT: {}", fault),
+                Outcome::NoImage => unreachable!(),
+            };
+            println!(
+                "   boot #{}: slot {} v{}{} -> {}",
+                h.boot_number,
+                h.slot,
+                h.version,
+                if h.trial {
+                    format!(" (trial {})", h.attempt)
+                } else {
+                    String::new()
+                },
+                how
+            );
+        }
+        (None, _) => unreachable!(),
+    }
+}
+
+fn state_line(state: &BootState) -> String {
+    format!(
+        "active {} pending {:?} attempts {} min_version {} rollbacks {}",
+        state.active, state.pending, state.attempts, state.min_version, state.rollbacks
+    )
+}
+
+fn main() -> std::io::Result<()> {
+    println!("=== Bootloader Handoff ===\n");
+
+    let dir = std::env::temp_dir().join("rust-sys-boot-demo");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir)?;
+
+    let vendor = SigningKey::from_bytes(&[0x42; 32]);
+    let attacker = SigningKey::from_bytes(&[0x66; 32]);
+    let image = |key: &SigningKey, version: u32, entry: u32| {
+        sign_image(key, version, &build_payload(entry, b"application code"))
+    };
+
+    let boot = Bootloader::new(&dir, vendor.verifying_key(), 3)
+        .with_entry(STABLE, stable_app)
+        .with_entry(CRASHES, crashing_app)
+        .with_entry(HEALTHY, healthy_app)
+        .with_entry(HANGS, hanging_app);
+
+    // Panics are the simulated HardFaults; keep their messages quiet
+    std::panic::set_hook(Box::new(|_| {}));
+
+    // 1. Factory state: v1 in slot A
+    println!("1. Factory image:");
+    flash::write_slot(&dir, Slot::A, &image(&vendor, 1, STABLE))?;
+    show(&boot.power_on()?);
+    show(&boot.power_on()?);
+
+    // 2. Every check the bootloader makes
+    println!("\n2. Validation checks (slot B):");
+    let good = image(&vendor, 2, HEALTHY);
+    let mut bad_magic = good.clone();
+    bad_magic[0] = b'X';
+    // (name, image, bytes written before a power cut)
+    let cases = [
+        ("valid v2", good.clone(), None),
+        ("power cut mid-copy", good.clone(), Some(100)),
+        ("bad magic", bad_magic, None),
+        ("attacker-signed", image(&attacker, 2, HEALTHY), None),
+        (
+            "unknown entry address",
+            image(&vendor, 2, 0xDEAD_BEEF),
+            None,
+        ),
+    ];
+    for (name, bytes, torn) in &cases {
+        match torn {
+            Some(written) => flash::write_slot_torn(&dir, Slot::B, bytes, *written)?,
+            None => flash::write_slot(&dir, Slot::B, bytes)?,
+        }
+        match boot.validate(Slot::B, 1) {
+            Ok((version, _)) => println!("   {:<22} ok, v{}", name, version),
+            Err(e) => println!("   {:<22} {}", name, e),
+        }
+    }
+    flash::write_slot(&dir, Slot::B, &good)?;
+    let mut stored = fs::read(Slot::B.path(&dir))?;
+    stored[120] ^= 0x01;
+    fs::write(Slot::B.path(&dir), stored)?;
+    match boot.validate(Slot::B, 1) {
+        Ok(_) => println!("   {:<22} accepted?!", "bit flip in flash"),
+        Err(e) => println!("   {:<22} {}", "bit flip in flash", e),
+    }
+    flash::write_slot(&dir, Slot::B, &image(&vendor, 0, STABLE))?;
+    match boot.validate(Slot::B, 1) {
+        Ok(_) => println!("   {:<22} accepted?!", "v0 with minimum v1"),
+        Err(e) => println!("   {:<22} {}", "v0 with minimum v1", e),
+    }
+    flash::erase_slot(&dir, Slot::B)?;
+
+    // 3. An update that crashes on start-up is rolled back
+    println!("\n3. Update to v2 that crashes before confirming:");
+    let slot = stage_update(&dir, &image(&vendor, 2, CRASHES))?;
+    println!("   staged in slot {}", slot);
+    for _ in 0..4 {
+        show(&boot.power_on()?);
+    }
+    println!("   state: {}", state_line(&BootState::load(&dir)));
+
+    // 4. An update that hangs is treated the same way
+    println!("\n4. Update to v2 that hangs (watchdog):");
+    stage_update(&dir, &image(&vendor, 2, HANGS))?;
+    for _ in 0..4 {
+        show(&boot.power_on()?);
+    }
+
+    // 5. A healthy update confirms itself and becomes permanent
+    println!("\n5. Update to v3 that confirms:");
+    stage_update(&dir, &image(&vendor, 3, HEALTHY))?;
+    show(&boot.power_on()?);
+    show(&boot.power_on()?);
+    println!("   state: {}", state_line(&BootState::load(&dir)));
+
+    // 6. Downgrades are refused once v3 is confirmed
+    println!("\n6. Attempted downgrade to v1:");
+    stage_update(&dir, &image(&vendor, 1, STABLE))?;
+    show(&boot.power_on()?);
+
+    // 7. Power cut while copying an update
+    println!("\n7. Power cut during an update:");
+    let mut state = BootState::load(&dir);
+    let target = state.active.other();
+    flash::write_slot_torn(&dir, target, &image(&vendor, 4, HEALTHY), 60)?;
+    state.pending = Some(target);
+    state.save(&dir)?;
+    show(&boot.power_on()?);
+
+    // 8. A damaged boot-state record falls back to defaults
+    println!("\n8. Corrupted boot state:");
+    fs::write(state::path(&dir), b"garbage")?;
+    let record = boot.power_on()?;
+    show(&record);
+    println!("   state: {}", state_line(&record.state));
+    println!("   (min_version was lost: real devices keep it in OTP or a monotonic counter)");
+
+    // 9. Nothing bootable at all
+    println!("\n9. Both slots erased:");
+    flash::erase_slot(&dir, Slot::A)?;
+    flash::erase_slot(&dir, Slot::B)?;
+    show(&boot.power_on()?);
+
+    let _ = std::panic::take_hook();
+    println!("\n=== End of Bootloader Handoff Examples ===");
+    Ok(())
+}
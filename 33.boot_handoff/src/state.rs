@@ -0,0 +1,116 @@
+// Persistent boot state, kept in its own flash sector
+//
+//   0   4  magic "BOOT"
+//   4   1  active slot (0 = A, 1 = B)
+//   5   1  pending slot (0 = none, 1 = A, 2 = B)
+//   6   1  boot attempts of the pending image
+//   7   1  reserved
+//   8   4  minimum firmware version (anti-rollback)
+//   12  4  total boots
+//   16  4  rollbacks performed
+//   20  4  CRC32 of bytes 0..20
+//
+// A missing or corrupt record falls back to the defaults (boot slot A),
+// and saves go through a temporary file and a rename, so a power cut
+// during a save leaves either the old or the new record.
+
+use crate::flash::Slot;
+use crc::crc32;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const MAGIC: [u8; 4] = *b"BOOT";
+const LEN: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BootState {
+    pub active: Slot,
+    // Image on trial: booted but not yet confirmed by the application
+    pub pending: Option<Slot>,
+    pub attempts: u8,
+    pub min_version: u32,
+    pub boots: u32,
+    pub rollbacks: u32,
+}
+
+impl Default for BootState {
+    fn default() -> Self {
+        BootState {
+            active: Slot::A,
+            pending: None,
+            attempts: 0,
+            min_version: 0,
+            boots: 0,
+            rollbacks: 0,
+        }
+    }
+}
+
+pub fn path(dir: &Path) -> PathBuf {
+    dir.join("boot-state.bin")
+}
+
+impl BootState {
+    pub fn encode(&self) -> [u8; LEN] {
+        let mut buf = [0u8; LEN];
+        buf[0..4].copy_from_slice(&MAGIC);
+        buf[4] = match self.active {
+            Slot::A => 0,
+            Slot::B => 1,
+        };
+        buf[5] = match self.pending {
+            None => 0,
+            Some(Slot::A) => 1,
+            Some(Slot::B) => 2,
+        };
+        buf[6] = self.attempts;
+        buf[8..12].copy_from_slice(&self.min_version.to_le_bytes());
+        buf[12..16].copy_from_slice(&self.boots.to_le_bytes());
+        buf[16..20].copy_from_slice(&self.rollbacks.to_le_bytes());
+        let crc = crc32(&buf[..20]);
+        buf[20..24].copy_from_slice(&crc.to_le_bytes());
+        buf
+    }
+
+    pub fn decode(buf: &[u8]) -> Option<BootState> {
+        if buf.len() != LEN || buf[0..4] != MAGIC {
+            return None;
+        }
+        if crc32(&buf[..20]) != u32::from_le_bytes(buf[20..24].try_into().unwrap()) {
+            return None;
+        }
+        let word = |at: usize| u32::from_le_bytes(buf[at..at + 4].try_into().unwrap());
+        Some(BootState {
+            active: match buf[4] {
+                0 => Slot::A,
+                1 => Slot::B,
+                _ => return None,
+            },
+            pending: match buf[5] {
+                0 => None,
+                1 => Some(Slot::A),
+                2 => Some(Slot::B),
+                _ => return None,
+            },
+            attempts: buf[6],
+            min_version: word(8),
+            boots: word(12),
+            rollbacks: word(16),
+        })
+    }
+
+    // Defaults if the record is missing or damaged
+    pub fn load(dir: &Path) -> BootState {
+        fs::read(path(dir))
+            .ok()
+            .and_then(|bytes| BootState::decode(&bytes))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, dir: &Path) -> io::Result<()> {
+        let tmp = dir.join("boot-state.tmp");
+        fs::write(&tmp, self.encode())?;
+        fs::rename(tmp, path(dir))
+    }
+}
@@ -0,0 +1,266 @@
+use boot_handoff::flash::{self, Slot, SlotError};
+use boot_handoff::{
+    build_payload, stage_update, state, BootControl, BootState, Bootloader, Event, Fault, Handoff,
+    Outcome, ValidationError,
+};
+use secure_boot::ed25519_dalek::SigningKey;
+use secure_boot::{sign_image, BootError};
+use std::fs;
+use std::path::PathBuf;
+
+const CONFIRMS: u32 = 0x0800_4000;
+const NEVER_CONFIRMS: u32 = 0x0800_8000;
+
+fn confirming_app(handoff: &Handoff, control: &mut BootControl) -> Result<(), Fault> {
+    if handoff.trial {
+        control.confirm().map_err(|e| Fault(e.to_string()))?;
+    }
+    Ok(())
+}
+
+fn watchdog_app(_: &Handoff, _: &mut BootControl) -> Result<(), Fault> {
+    Err(Fault("watchdog reset".to_string()))
+}
+
+fn vendor() -> SigningKey {
+    SigningKey::from_bytes(&[0x42; 32])
+}
+
+fn image(key: &SigningKey, version: u32, entry: u32) -> Vec<u8> {
+    sign_image(key, version, &build_payload(entry, b"application code"))
+}
+
+// A fresh flash directory per test, with v1 in slot A
+fn setup(name: &str) -> (PathBuf, Bootloader) {
+    let dir = std::env::temp_dir().join(format!("rust-sys-boot-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    flash::write_slot(&dir, Slot::A, &image(&vendor(), 1, CONFIRMS)).unwrap();
+    let boot = Bootloader::new(&dir, vendor().verifying_key(), 3)
+        .with_entry(CONFIRMS, confirming_app)
+        .with_entry(NEVER_CONFIRMS, watchdog_app);
+    (dir, boot)
+}
+
+#[test]
+fn factory_image_boots_from_slot_a() {
+    let (dir, boot) = setup("factory");
+    let record = boot.power_on().unwrap();
+    let handoff = record.handoff.unwrap();
+    assert_eq!(
+        (handoff.slot, handoff.version, handoff.trial),
+        (Slot::A, 1, false)
+    );
+    assert_eq!(record.outcome, Outcome::Reset { confirmed: false });
+    assert!(record.events.is_empty());
+    assert_eq!(boot.power_on().unwrap().state.boots, 2);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn unconfirmed_update_counts_trials_then_rolls_back() {
+    let (dir, boot) = setup("rollback");
+    assert_eq!(
+        stage_update(&dir, &image(&vendor(), 2, NEVER_CONFIRMS)).unwrap(),
+        Slot::B
+    );
+
+    for attempt in 1..=3 {
+        let record = boot.power_on().unwrap();
+        let handoff = record.handoff.unwrap();
+        assert_eq!((handoff.slot, handoff.trial), (Slot::B, true));
+        assert_eq!(handoff.attempt, attempt);
+        assert_eq!(record.state.attempts, attempt);
+        assert_eq!(record.state.pending, Some(Slot::B));
+        assert!(matches!(
+            record.outcome,
+            Outcome::Faulted {
+                confirmed: false,
+                ..
+            }
+        ));
+    }
+
+    // Fourth power-on: three chances used up, back to the old image
+    let record = boot.power_on().unwrap();
+    assert_eq!(
+        record.events,
+        [Event::RolledBack {
+            slot: Slot::B,
+            attempts: 3
+        }]
+    );
+    let handoff = record.handoff.unwrap();
+    assert_eq!(
+        (handoff.slot, handoff.version, handoff.trial),
+        (Slot::A, 1, false)
+    );
+    assert_eq!(record.state.pending, None);
+    assert_eq!(record.state.attempts, 0);
+    assert_eq!(record.state.rollbacks, 1);
+    assert_eq!(record.state.active, Slot::A);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn confirm_makes_the_update_permanent_and_raises_min_version() {
+    let (dir, boot) = setup("confirm");
+    stage_update(&dir, &image(&vendor(), 5, CONFIRMS)).unwrap();
+
+    let record = boot.power_on().unwrap();
+    assert_eq!(record.outcome, Outcome::Reset { confirmed: true });
+    assert_eq!(record.state.active, Slot::B);
+    assert_eq!(record.state.pending, None);
+    assert_eq!(record.state.min_version, 5);
+
+    // Later boots use slot B as a normal (non-trial) image
+    let handoff = boot.power_on().unwrap().handoff.unwrap();
+    assert_eq!(
+        (handoff.slot, handoff.version, handoff.trial),
+        (Slot::B, 5, false)
+    );
+
+    // An older image staged afterwards is refused by anti-rollback
+    assert_eq!(
+        stage_update(&dir, &image(&vendor(), 3, CONFIRMS)).unwrap(),
+        Slot::A
+    );
+    let record = boot.power_on().unwrap();
+    assert_eq!(
+        record.events,
+        [Event::Rejected {
+            slot: Slot::A,
+            error: ValidationError::Image(BootError::Rollback {
+                image: 3,
+                minimum: 5
+            }),
+        }]
+    );
+    assert_eq!(record.handoff.unwrap().slot, Slot::B);
+    assert_eq!(record.state.pending, None);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn bad_crc_falls_back_to_the_other_slot() {
+    let (dir, boot) = setup("crc");
+    flash::write_slot(&dir, Slot::B, &image(&vendor(), 1, CONFIRMS)).unwrap();
+
+    // Flip one bit of slot A's image; the trailer CRC no longer matches
+    let path = Slot::A.path(&dir);
+    let mut bytes = fs::read(&path).unwrap();
+    bytes[10] ^= 0x01;
+    fs::write(&path, bytes).unwrap();
+
+    let record = boot.power_on().unwrap();
+    assert!(matches!(
+        record.events[0],
+        Event::Rejected {
+            slot: Slot::A,
+            error: ValidationError::Slot(SlotError::CrcMismatch { .. }),
+        }
+    ));
+    assert_eq!(
+        record.events[1],
+        Event::FellBack {
+            from: Slot::A,
+            to: Slot::B
+        }
+    );
+    assert_eq!(record.handoff.unwrap().slot, Slot::B);
+    assert_eq!(record.state.active, Slot::B);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn bad_signature_falls_back_to_the_other_slot() {
+    let (dir, boot) = setup("signature");
+    let attacker = SigningKey::from_bytes(&[0x66; 32]);
+    flash::write_slot(&dir, Slot::A, &image(&attacker, 9, CONFIRMS)).unwrap();
+    flash::write_slot(&dir, Slot::B, &image(&vendor(), 1, CONFIRMS)).unwrap();
+
+    let record = boot.power_on().unwrap();
+    assert_eq!(
+        record.events,
+        [
+            Event::Rejected {
+                slot: Slot::A,
+                error: ValidationError::Image(BootError::BadSignature),
+            },
+            Event::FellBack {
+                from: Slot::A,
+                to: Slot::B
+            },
+        ]
+    );
+    assert_eq!(record.handoff.unwrap().slot, Slot::B);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn nothing_bootable_stays_in_recovery() {
+    let (dir, boot) = setup("recovery");
+    flash::write_slot_torn(&dir, Slot::A, &image(&vendor(), 1, CONFIRMS), 100).unwrap();
+
+    let record = boot.power_on().unwrap();
+    assert_eq!(record.outcome, Outcome::NoImage);
+    assert_eq!(record.handoff, None);
+    assert_eq!(
+        record.events,
+        [
+            Event::Rejected {
+                slot: Slot::A,
+                error: ValidationError::Slot(SlotError::Truncated),
+            },
+            Event::Rejected {
+                slot: Slot::B,
+                error: ValidationError::Slot(SlotError::Empty),
+            },
+        ]
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn boot_state_round_trips() {
+    let state = BootState {
+        active: Slot::B,
+        pending: Some(Slot::A),
+        attempts: 2,
+        min_version: 7,
+        boots: 41,
+        rollbacks: 3,
+    };
+    assert_eq!(BootState::decode(&state.encode()), Some(state));
+}
+
+#[test]
+fn corrupt_boot_state_is_rejected() {
+    let bytes = BootState::default().encode();
+
+    for i in 0..bytes.len() {
+        let mut flipped = bytes;
+        flipped[i] ^= 0x01;
+        assert_eq!(BootState::decode(&flipped), None, "bit flip in byte {}", i);
+    }
+    assert_eq!(BootState::decode(&bytes[..bytes.len() - 1]), None);
+    assert_eq!(BootState::decode(&[]), None);
+}
+
+#[test]
+fn corrupt_boot_state_file_loads_defaults() {
+    let (dir, _) = setup("state");
+    let saved = BootState {
+        active: Slot::B,
+        min_version: 4,
+        ..BootState::default()
+    };
+    saved.save(&dir).unwrap();
+    assert_eq!(BootState::load(&dir), saved);
+
+    let mut bytes = fs::read(state::path(&dir)).unwrap();
+    bytes[8] ^= 0xFF;
+    fs::write(state::path(&dir), bytes).unwrap();
+    assert_eq!(BootState::load(&dir), BootState::default());
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,319 @@
+// Sensor calibration by least-squares curve fitting
+//
+// A calibration run records pairs of (measured, reference) values: what the
+// sensor reported and what a trusted instrument said at the same moment.
+// Fitting a curve reference = f(measured) gives a correction to apply to
+// every future raw reading.
+//
+//   linear     f(x) = gain * x + offset
+//   quadratic  f(x) = a * x^2 + b * x + c
+//
+// Residuals (reference - f(measured)) show how well the curve explains the
+// data; a quadratic is only worth storing if it explains clearly more.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use tensor::Matrix;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub measured: f64,
+    pub reference: f64,
+}
+
+impl Point {
+    pub fn new(measured: f64, reference: f64) -> Point {
+        Point {
+            measured,
+            reference,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve {
+    Linear { gain: f64, offset: f64 },
+    Quadratic { a: f64, b: f64, c: f64 },
+}
+
+impl Curve {
+    pub const IDENTITY: Curve = Curve::Linear {
+        gain: 1.0,
+        offset: 0.0,
+    };
+
+    pub fn apply(&self, raw: f64) -> f64 {
+        match *self {
+            Curve::Linear { gain, offset } => gain * raw + offset,
+            Curve::Quadratic { a, b, c } => (a * raw + b) * raw + c,
+        }
+    }
+
+    pub fn coefficients(&self) -> Vec<f64> {
+        match *self {
+            Curve::Linear { gain, offset } => vec![gain, offset],
+            Curve::Quadratic { a, b, c } => vec![a, b, c],
+        }
+    }
+}
+
+impl fmt::Display for Curve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Curve::Linear { gain, offset } => write!(f, "{:.6} * x {:+.6}", gain, offset),
+            Curve::Quadratic { a, b, c } => {
+                write!(f, "{:.6e} * x^2 {:+.6} * x {:+.6}", a, b, c)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalibrationError {
+    TooFewPoints { needed: usize, got: usize },
+    NonFinite { index: usize },
+    // All measured values (nearly) equal: the slope is undetermined
+    Degenerate,
+    Parse { line: usize, reason: String },
+    UnknownSensor(String),
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalibrationError::TooFewPoints { needed, got } => {
+                write!(f, "need at least {} points, got {}", needed, got)
+            }
+            CalibrationError::NonFinite { index } => {
+                write!(f, "point {} is not a finite number", index)
+            }
+            CalibrationError::Degenerate => {
+                write!(f, "measured values do not vary enough to fit a curve")
+            }
+            CalibrationError::Parse { line, reason } => write!(f, "line {}: {}", line, reason),
+            CalibrationError::UnknownSensor(name) => write!(f, "no calibration for {}", name),
+        }
+    }
+}
+
+impl std::error::Error for CalibrationError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fit {
+    pub curve: Curve,
+    // reference - curve(measured), in input order
+    pub residuals: Vec<f64>,
+    pub rms: f64,
+    pub max_abs: f64,
+    // Fraction of the reference variance explained (1.0 = perfect)
+    pub r_squared: f64,
+}
+
+impl Fit {
+    fn new(curve: Curve, points: &[Point]) -> Fit {
+        let residuals: Vec<f64> = points
+            .iter()
+            .map(|p| p.reference - curve.apply(p.measured))
+            .collect();
+        let n = points.len() as f64;
+        let ss_res: f64 = residuals.iter().map(|r| r * r).sum();
+        let mean = points.iter().map(|p| p.reference).sum::<f64>() / n;
+        let ss_tot: f64 = points.iter().map(|p| (p.reference - mean).powi(2)).sum();
+
+        Fit {
+            curve,
+            rms: (ss_res / n).sqrt(),
+            max_abs: residuals.iter().fold(0.0, |m, r| m.max(r.abs())),
+            r_squared: if ss_tot > 0.0 {
+                1.0 - ss_res / ss_tot
+            } else {
+                1.0
+            },
+            residuals,
+        }
+    }
+}
+
+fn check(points: &[Point], needed: usize) -> Result<(), CalibrationError> {
+    if points.len() < needed {
+        return Err(CalibrationError::TooFewPoints {
+            needed,
+            got: points.len(),
+        });
+    }
+    match points
+        .iter()
+        .position(|p| !p.measured.is_finite() || !p.reference.is_finite())
+    {
+        Some(index) => Err(CalibrationError::NonFinite { index }),
+        None => Ok(()),
+    }
+}
+
+// Mean and spread of the measured values; x is centred on the mean before
+// fitting, which keeps the sums well conditioned for raw ADC counts
+fn centre(points: &[Point]) -> Result<(f64, f64), CalibrationError> {
+    let n = points.len() as f64;
+    let mean = points.iter().map(|p| p.measured).sum::<f64>() / n;
+    let spread = points
+        .iter()
+        .map(|p| (p.measured - mean).abs())
+        .fold(0.0, f64::max);
+    if spread <= 1e-12 * mean.abs().max(1.0) {
+        return Err(CalibrationError::Degenerate);
+    }
+    Ok((mean, spread))
+}
+
+pub fn fit_linear(points: &[Point]) -> Result<Fit, CalibrationError> {
+    check(points, 2)?;
+    let (mx, _) = centre(points)?;
+    let n = points.len() as f64;
+    let my = points.iter().map(|p| p.reference).sum::<f64>() / n;
+
+    let sxy: f64 = points
+        .iter()
+        .map(|p| (p.measured - mx) * (p.reference - my))
+        .sum();
+    let sxx: f64 = points.iter().map(|p| (p.measured - mx).powi(2)).sum();
+    let gain = sxy / sxx;
+
+    Ok(Fit::new(
+        Curve::Linear {
+            gain,
+            offset: my - gain * mx,
+        },
+        points,
+    ))
+}
+
+pub fn fit_quadratic(points: &[Point]) -> Result<Fit, CalibrationError> {
+    check(points, 3)?;
+    let (mx, scale) = centre(points)?;
+
+    // Normal equations (VᵀV) q = Vᵀy for the design matrix V with rows
+    // [1, u, u²], u = (x - mx) / scale
+    let design = Matrix::from_fn(points.len(), 3, |r, k| {
+        ((points[r].measured - mx) / scale).powi(k as i32)
+    });
+    let reference: Vec<f64> = points.iter().map(|p| p.reference).collect();
+    let vt = design.transpose();
+    let rhs = vt.matvec(&reference).expect("design has one row per point");
+    let q = (&vt * &design)
+        .solve(&rhs)
+        .ok_or(CalibrationError::Degenerate)?;
+    let (q0, q1, q2) = (q[0], q[1], q[2]);
+
+    // Expand q0 + q1*u + q2*u^2 back into powers of x
+    let a = q2 / (scale * scale);
+    let b = q1 / scale - 2.0 * a * mx;
+    let c = q0 - q1 * mx / scale + a * mx * mx;
+    Ok(Fit::new(Curve::Quadratic { a, b, c }, points))
+}
+
+// Linear unless a quadratic cuts the RMS residual by at least
+// `min_improvement` (e.g. 0.5 = halves it); extra terms must earn their place
+pub fn fit_auto(points: &[Point], min_improvement: f64) -> Result<Fit, CalibrationError> {
+    let linear = fit_linear(points)?;
+    if points.len() < 4 {
+        return Ok(linear);
+    }
+    match fit_quadratic(points) {
+        Ok(quadratic) if quadratic.rms <= linear.rms * (1.0 - min_improvement) => Ok(quadratic),
+        _ => Ok(linear),
+    }
+}
+
+// Coefficients for every sensor, stored as one line each:
+//
+//   <sensor> linear <gain> <offset>
+//   <sensor> quadratic <a> <b> <c>
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CalibrationStore {
+    curves: BTreeMap<String, Curve>,
+}
+
+impl CalibrationStore {
+    pub fn new() -> CalibrationStore {
+        CalibrationStore::default()
+    }
+
+    pub fn set(&mut self, sensor: &str, curve: Curve) {
+        self.curves.insert(sensor.to_string(), curve);
+    }
+
+    pub fn get(&self, sensor: &str) -> Option<&Curve> {
+        self.curves.get(sensor)
+    }
+
+    pub fn apply(&self, sensor: &str, raw: f64) -> Result<f64, CalibrationError> {
+        self.get(sensor)
+            .map(|curve| curve.apply(raw))
+            .ok_or_else(|| CalibrationError::UnknownSensor(sensor.to_string()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.curves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.curves.is_empty()
+    }
+
+    // `f64::to_string` prints the shortest text that parses back exactly
+    pub fn to_text(&self) -> String {
+        self.curves
+            .iter()
+            .map(|(sensor, curve)| {
+                let kind = match curve {
+                    Curve::Linear { .. } => "linear",
+                    Curve::Quadratic { .. } => "quadratic",
+                };
+                let values: Vec<String> =
+                    curve.coefficients().iter().map(|c| c.to_string()).collect();
+                format!("{} {} {}\n", sensor, kind, values.join(" "))
+            })
+            .collect()
+    }
+
+    pub fn parse(text: &str) -> Result<CalibrationStore, CalibrationError> {
+        let mut store = CalibrationStore::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let bad = |reason: &str| CalibrationError::Parse {
+                line: index + 1,
+                reason: reason.to_string(),
+            };
+            let mut fields = line.split_whitespace();
+            let sensor = fields.next().ok_or_else(|| bad("missing sensor"))?;
+            let kind = fields.next().ok_or_else(|| bad("missing curve type"))?;
+            let values = fields
+                .map(|v| v.parse::<f64>())
+                .collect::<Result<Vec<f64>, _>>()
+                .map_err(|_| bad("bad coefficient"))?;
+            let curve = match (kind, values.as_slice()) {
+                ("linear", &[gain, offset]) => Curve::Linear { gain, offset },
+                ("quadratic", &[a, b, c]) => Curve::Quadratic { a, b, c },
+                ("linear" | "quadratic", _) => return Err(bad("wrong number of coefficients")),
+                _ => return Err(bad("unknown curve type")),
+            };
+            store.set(sensor, curve);
+        }
+        Ok(store)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+    pub fn load(path: &Path) -> io::Result<CalibrationStore> {
+        let text = fs::read_to_string(path)?;
+        CalibrationStore::parse(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
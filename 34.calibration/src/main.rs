@@ -0,0 +1,157 @@
+use calibration::{fit_auto, fit_linear, fit_quadratic, CalibrationStore, Curve, Fit, Point};
+
+fn points(pairs: &[(f64, f64)]) -> Vec<Point> {
+    pairs.iter().map(|&(m, r)| Point::new(m, r)).collect()
+}
+
+fn summary(fit: &Fit) {
+    println!("   curve: {}", fit.curve);
+    println!(
+        "   rms {:.4}  max {:.4}  r^2 {:.4}",
+        fit.rms, fit.max_abs, fit.r_squared
+    );
+}
+
+// Small deterministic "noise" so the demo output never changes
+fn noise(i: usize) -> f64 {
+    ((i as f64 * 12.9898).sin() * 43758.5453).fract() - 0.5
+}
+
+fn main() {
+    println!("=== Sensor Calibration ===\n");
+
+    // 1. Exact line; tests/fit.rs checks these fits against known answers
+    println!("1. Exact line, reference = 2x + 1:");
+    let line = points(&[(0.0, 1.0), (1.0, 3.0), (2.0, 5.0), (5.0, 11.0)]);
+    let fit = fit_linear(&line).unwrap();
+    summary(&fit);
+
+    // 2. Anscombe's quartet, set I: published fit y = 3.00 + 0.500x, r^2 0.67
+    println!("\n2. Anscombe's quartet, set I:");
+    let anscombe = points(&[
+        (10.0, 8.04),
+        (8.0, 6.95),
+        (13.0, 7.58),
+        (9.0, 8.81),
+        (11.0, 8.33),
+        (14.0, 9.96),
+        (6.0, 7.24),
+        (4.0, 4.26),
+        (12.0, 10.84),
+        (7.0, 4.82),
+        (5.0, 5.68),
+    ]);
+    let fit = fit_linear(&anscombe).unwrap();
+    summary(&fit);
+
+    // 3. Exact quadratic (a thermistor-like bend)
+    println!("\n3. Exact quadratic, reference = 0.002x^2 - 0.5x + 40:");
+    let truth = Curve::Quadratic {
+        a: 0.002,
+        b: -0.5,
+        c: 40.0,
+    };
+    let bent: Vec<Point> = (0..12)
+        .map(|i| {
+            let raw = 100.0 + i as f64 * 25.0;
+            Point::new(raw, truth.apply(raw))
+        })
+        .collect();
+    let fit = fit_quadratic(&bent).unwrap();
+    summary(&fit);
+
+    // 4. Raw ADC counts: large x values that break naive normal equations
+    println!("\n4. 16-bit ADC counts (x ~ 60000), noisy linear:");
+    let adc: Vec<Point> = (0..20)
+        .map(|i| {
+            let counts = 60_000.0 + i as f64 * 100.0;
+            Point::new(counts, 0.00125 * counts - 50.0 + 0.02 * noise(i))
+        })
+        .collect();
+    let fit = fit_linear(&adc).unwrap();
+    summary(&fit);
+
+    // 5. Choosing the model: only use the quadratic when it earns its place
+    println!("\n5. Linear or quadratic?");
+    let noisy_bent: Vec<Point> = bent
+        .iter()
+        .enumerate()
+        .map(|(i, p)| Point::new(p.measured, p.reference + 0.05 * noise(i)))
+        .collect();
+    for (name, data) in [
+        ("ADC data (linear)", &adc),
+        ("thermistor (bent)", &noisy_bent),
+    ] {
+        let linear = fit_linear(data).unwrap();
+        let quadratic = fit_quadratic(data).unwrap();
+        let chosen = fit_auto(data, 0.5).unwrap();
+        println!(
+            "   {:<18} rms linear {:.4} quadratic {:.4} -> {}",
+            name,
+            linear.rms,
+            quadratic.rms,
+            match chosen.curve {
+                Curve::Linear { .. } => "linear",
+                Curve::Quadratic { .. } => "quadratic",
+            }
+        );
+    }
+
+    // 6. Residuals point at a bad reference reading
+    println!("\n6. Residuals expose an outlier:");
+    let mut with_outlier = line.clone();
+    with_outlier.push(Point::new(3.0, 9.0));
+    let fit = fit_linear(&with_outlier).unwrap();
+    for (p, r) in with_outlier.iter().zip(&fit.residuals) {
+        println!(
+            "   x={:<4} ref={:<5} residual {:+.3}",
+            p.measured, p.reference, r
+        );
+    }
+
+    // 7. Storing and applying coefficients
+    println!("\n7. Calibration store:");
+    let mut store = CalibrationStore::new();
+    store.set("temp0", fit_auto(&noisy_bent, 0.5).unwrap().curve);
+    store.set("supply_v", fit_linear(&adc).unwrap().curve);
+    let path = std::env::temp_dir().join("rust-sys-calibration.txt");
+    store.save(&path).unwrap();
+    print!(
+        "{}",
+        store
+            .to_text()
+            .lines()
+            .map(|l| format!("   {}\n", l))
+            .collect::<String>()
+    );
+    let loaded = CalibrationStore::load(&path).unwrap();
+    for (sensor, raw) in [("temp0", 250.0), ("supply_v", 61_000.0), ("humidity", 40.0)] {
+        match loaded.apply(sensor, raw) {
+            Ok(value) => println!("   {:<9} raw {:>8} -> {:.3}", sensor, raw, value),
+            Err(e) => println!("   {:<9} raw {:>8} -> {}", sensor, raw, e),
+        }
+    }
+
+    // 8. Inputs that cannot be fitted
+    println!("\n8. Rejected inputs:");
+    let cases: [(&str, Vec<Point>); 3] = [
+        ("one point", points(&[(1.0, 2.0)])),
+        (
+            "all measured equal",
+            points(&[(5.0, 1.0), (5.0, 2.0), (5.0, 3.0)]),
+        ),
+        ("NaN reading", points(&[(1.0, 2.0), (f64::NAN, 3.0)])),
+    ];
+    for (name, data) in &cases {
+        match fit_linear(data) {
+            Ok(fit) => println!("   {:<20} {}", name, fit.curve),
+            Err(e) => println!("   {:<20} {}", name, e),
+        }
+    }
+    match CalibrationStore::parse("temp0 cubic 1 2 3 4") {
+        Ok(store) => println!("   {:<20} {} curves", "unknown curve type", store.len()),
+        Err(e) => println!("   {:<20} {}", "unknown curve type", e),
+    }
+
+    println!("\n=== End of Sensor Calibration Examples ===");
+}
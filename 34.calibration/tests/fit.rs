@@ -0,0 +1,201 @@
+use calibration::{fit_auto, fit_linear, fit_quadratic, CalibrationError, Curve, Point};
+
+fn points(pairs: &[(f64, f64)]) -> Vec<Point> {
+    pairs.iter().map(|&(m, r)| Point::new(m, r)).collect()
+}
+
+fn close(a: f64, b: f64, tolerance: f64) -> bool {
+    (a - b).abs() <= tolerance
+}
+
+// Deterministic noise in [-0.5, 0.5)
+fn noise(i: usize) -> f64 {
+    ((i as f64 * 12.9898).sin() * 43758.5453).fract() - 0.5
+}
+
+fn thermistor() -> (Curve, Vec<Point>) {
+    let truth = Curve::Quadratic {
+        a: 0.002,
+        b: -0.5,
+        c: 40.0,
+    };
+    let bent = (0..12)
+        .map(|i| {
+            let raw = 100.0 + i as f64 * 25.0;
+            Point::new(raw, truth.apply(raw))
+        })
+        .collect();
+    (truth, bent)
+}
+
+fn adc() -> Vec<Point> {
+    (0..20)
+        .map(|i| {
+            let counts = 60_000.0 + i as f64 * 100.0;
+            Point::new(counts, 0.00125 * counts - 50.0 + 0.02 * noise(i))
+        })
+        .collect()
+}
+
+#[test]
+fn exact_line_is_recovered() {
+    let fit = fit_linear(&points(&[(0.0, 1.0), (1.0, 3.0), (2.0, 5.0), (5.0, 11.0)])).unwrap();
+    let Curve::Linear { gain, offset } = fit.curve else {
+        panic!("linear fit returned {:?}", fit.curve)
+    };
+    assert!(close(gain, 2.0, 1e-12));
+    assert!(close(offset, 1.0, 1e-12));
+    assert!(fit.max_abs < 1e-12);
+    assert!(fit.rms < 1e-12);
+    assert!(close(fit.r_squared, 1.0, 1e-12));
+}
+
+#[test]
+fn anscombe_set_one_matches_the_published_fit() {
+    // Published: y = 3.00 + 0.500x, r^2 = 0.67
+    let fit = fit_linear(&points(&[
+        (10.0, 8.04),
+        (8.0, 6.95),
+        (13.0, 7.58),
+        (9.0, 8.81),
+        (11.0, 8.33),
+        (14.0, 9.96),
+        (6.0, 7.24),
+        (4.0, 4.26),
+        (12.0, 10.84),
+        (7.0, 4.82),
+        (5.0, 5.68),
+    ]))
+    .unwrap();
+    let Curve::Linear { gain, offset } = fit.curve else {
+        panic!("linear fit returned {:?}", fit.curve)
+    };
+    assert!(close(gain, 0.5001, 1e-4));
+    assert!(close(offset, 3.0001, 1e-4));
+    assert!(close(fit.r_squared, 0.6665, 1e-4));
+}
+
+#[test]
+fn exact_quadratic_is_recovered() {
+    let (_, bent) = thermistor();
+    let fit = fit_quadratic(&bent).unwrap();
+    let Curve::Quadratic { a, b, c } = fit.curve else {
+        panic!("quadratic fit returned {:?}", fit.curve)
+    };
+    assert!(close(a, 0.002, 1e-9));
+    assert!(close(b, -0.5, 1e-9));
+    assert!(close(c, 40.0, 1e-9));
+    assert!(fit.max_abs < 1e-9);
+}
+
+#[test]
+fn large_adc_counts_stay_well_conditioned() {
+    let data = adc();
+    let fit = fit_linear(&data).unwrap();
+    let Curve::Linear { gain, offset } = fit.curve else {
+        panic!("linear fit returned {:?}", fit.curve)
+    };
+    assert!(close(gain, 0.00125, 2e-6));
+    assert!(close(offset, -50.0, 0.15));
+    // Noise is at most 0.01 either way
+    assert!(fit.max_abs <= 0.02);
+
+    let quad = fit_quadratic(&data).unwrap();
+    assert!(quad.curve.coefficients().iter().all(|c| c.is_finite()));
+    // Least squares with one more term can only do as well or better
+    assert!(quad.rms <= fit.rms + 1e-9);
+}
+
+#[test]
+fn residuals_are_in_input_order_and_expose_an_outlier() {
+    let data = points(&[(0.0, 1.0), (1.0, 3.0), (2.0, 5.0), (5.0, 11.0), (3.0, 9.0)]);
+    let fit = fit_linear(&data).unwrap();
+    assert_eq!(fit.residuals.len(), data.len());
+    for (p, r) in data.iter().zip(&fit.residuals) {
+        assert!(close(*r, p.reference - fit.curve.apply(p.measured), 1e-12));
+    }
+    let worst = fit
+        .residuals
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+        .unwrap()
+        .0;
+    assert_eq!(worst, 4);
+    assert_eq!(fit.max_abs, fit.residuals[4].abs());
+}
+
+#[test]
+fn auto_keeps_linear_unless_the_quadratic_earns_its_place() {
+    assert!(matches!(
+        fit_auto(&adc(), 0.5).unwrap().curve,
+        Curve::Linear { .. }
+    ));
+
+    let (_, bent) = thermistor();
+    let noisy: Vec<Point> = bent
+        .iter()
+        .enumerate()
+        .map(|(i, p)| Point::new(p.measured, p.reference + 0.05 * noise(i)))
+        .collect();
+    assert!(matches!(
+        fit_auto(&noisy, 0.5).unwrap().curve,
+        Curve::Quadratic { .. }
+    ));
+    // Demanding more improvement than is possible falls back to linear
+    assert!(matches!(
+        fit_auto(&noisy, 1.0).unwrap().curve,
+        Curve::Linear { .. }
+    ));
+    // Three points always fit a quadratic exactly, so auto stays linear
+    let three = &bent[..3];
+    assert!(matches!(
+        fit_auto(three, 0.5).unwrap().curve,
+        Curve::Linear { .. }
+    ));
+}
+
+#[test]
+fn rejected_inputs() {
+    assert_eq!(
+        fit_linear(&points(&[(1.0, 2.0)])),
+        Err(CalibrationError::TooFewPoints { needed: 2, got: 1 })
+    );
+    assert_eq!(
+        fit_quadratic(&points(&[(1.0, 2.0), (2.0, 3.0)])),
+        Err(CalibrationError::TooFewPoints { needed: 3, got: 2 })
+    );
+    assert_eq!(
+        fit_linear(&points(&[(5.0, 1.0), (5.0, 2.0), (5.0, 3.0)])),
+        Err(CalibrationError::Degenerate)
+    );
+    assert_eq!(
+        fit_quadratic(&points(&[(5.0, 1.0), (5.0, 2.0), (5.0, 3.0)])),
+        Err(CalibrationError::Degenerate)
+    );
+    assert_eq!(
+        fit_linear(&points(&[(1.0, 2.0), (f64::NAN, 3.0)])),
+        Err(CalibrationError::NonFinite { index: 1 })
+    );
+    assert_eq!(
+        fit_linear(&points(&[(1.0, 2.0), (2.0, f64::INFINITY)])),
+        Err(CalibrationError::NonFinite { index: 1 })
+    );
+}
+
+#[test]
+fn curves_apply_and_display() {
+    assert_eq!(Curve::IDENTITY.apply(12.5), 12.5);
+    let quad = Curve::Quadratic {
+        a: 1.0,
+        b: -2.0,
+        c: 3.0,
+    };
+    assert_eq!(quad.apply(4.0), 11.0);
+    assert_eq!(quad.coefficients(), [1.0, -2.0, 3.0]);
+    let line = Curve::Linear {
+        gain: 2.0,
+        offset: -1.0,
+    };
+    assert_eq!(line.to_string(), "2.000000 * x -1.000000");
+}
@@ -0,0 +1,107 @@
+use calibration::{CalibrationError, CalibrationStore, Curve};
+use std::io;
+
+fn store() -> CalibrationStore {
+    let mut store = CalibrationStore::new();
+    store.set(
+        "temp0",
+        Curve::Quadratic {
+            a: 0.0020000000000001,
+            b: -0.1 - 0.2,
+            c: 40.0,
+        },
+    );
+    store.set(
+        "supply_v",
+        Curve::Linear {
+            gain: 1.0 / 3.0,
+            offset: -50.0,
+        },
+    );
+    store
+}
+
+#[test]
+fn text_round_trip_is_exact() {
+    let store = store();
+    let text = store.to_text();
+    assert_eq!(text.lines().count(), 2);
+    // Sorted by sensor name
+    assert!(text.starts_with("supply_v linear "));
+    assert_eq!(CalibrationStore::parse(&text).unwrap(), store);
+}
+
+#[test]
+fn file_round_trip() {
+    let path = std::env::temp_dir().join(format!(
+        "rust-sys-calibration-test-{}.txt",
+        std::process::id()
+    ));
+    let store = store();
+    store.save(&path).unwrap();
+    let loaded = CalibrationStore::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.unwrap(), store);
+}
+
+#[test]
+fn apply_by_sensor() {
+    let store = store();
+    assert_eq!(store.len(), 2);
+    assert_eq!(store.apply("supply_v", 300.0).unwrap(), 300.0 / 3.0 - 50.0);
+    assert_eq!(
+        store.apply("humidity", 40.0),
+        Err(CalibrationError::UnknownSensor("humidity".to_string()))
+    );
+}
+
+#[test]
+fn set_replaces_a_sensor_curve() {
+    let mut store = store();
+    store.set("temp0", Curve::IDENTITY);
+    assert_eq!(store.len(), 2);
+    assert_eq!(store.get("temp0"), Some(&Curve::IDENTITY));
+}
+
+#[test]
+fn comments_and_blank_lines_are_skipped() {
+    let store = CalibrationStore::parse("# bench run 3\n\n  temp0 linear 2 1  \n").unwrap();
+    assert_eq!(store.apply("temp0", 3.0).unwrap(), 7.0);
+    assert!(CalibrationStore::parse("").unwrap().is_empty());
+}
+
+#[test]
+fn parse_errors_name_the_line() {
+    let cases = [
+        ("temp0 cubic 1 2 3 4", "unknown curve type"),
+        ("temp0 linear 1", "wrong number of coefficients"),
+        ("temp0 quadratic 1 2", "wrong number of coefficients"),
+        ("temp0 linear 1 x", "bad coefficient"),
+        ("temp0", "missing curve type"),
+    ];
+    for (line, reason) in cases {
+        let text = format!("# header\n{}\n", line);
+        assert_eq!(
+            CalibrationStore::parse(&text),
+            Err(CalibrationError::Parse {
+                line: 2,
+                reason: reason.to_string()
+            }),
+            "{:?}",
+            line
+        );
+    }
+}
+
+#[test]
+fn a_corrupt_file_is_invalid_data() {
+    let path = std::env::temp_dir().join(format!(
+        "rust-sys-calibration-bad-{}.txt",
+        std::process::id()
+    ));
+    std::fs::write(&path, "temp0 linear one two\n").unwrap();
+    let err = CalibrationStore::load(&path).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("line 1"));
+}
@@ -0,0 +1,199 @@
+// Radix-2 FFT, Hann window and magnitude spectrum
+//
+// The transform is the classic iterative Cooley-Tukey algorithm:
+//
+//   1. reorder the input by bit-reversed index
+//   2. log2(n) stages of butterflies, each combining pairs of half-size
+//      transforms:  a' = a + w*b,  b' = a - w*b
+//
+// It works in place on `Complex<f32>` and needs only the n/2 twiddle
+// factors w = e^(-2*pi*i*k/n), which `Fft::new` computes once (in f64 for
+// accuracy) so repeated transforms of the same size do no trigonometry.
+
+use num_complex::Complex;
+use std::f64::consts::PI;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FftError {
+    NotPowerOfTwo(usize),
+    LengthMismatch { expected: usize, actual: usize },
+}
+
+impl fmt::Display for FftError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FftError::NotPowerOfTwo(n) => write!(f, "length {} is not a power of two", n),
+            FftError::LengthMismatch { expected, actual } => {
+                write!(f, "expected {} samples, got {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FftError {}
+
+#[derive(Debug, Clone)]
+pub struct Fft {
+    n: usize,
+    twiddles: Vec<Complex<f32>>,
+}
+
+impl Fft {
+    pub fn new(n: usize) -> Result<Fft, FftError> {
+        if !n.is_power_of_two() {
+            return Err(FftError::NotPowerOfTwo(n));
+        }
+        let twiddles = (0..n / 2)
+            .map(|k| {
+                let angle = -2.0 * PI * k as f64 / n as f64;
+                Complex::new(angle.cos() as f32, angle.sin() as f32)
+            })
+            .collect();
+        Ok(Fft { n, twiddles })
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    pub fn forward(&self, buf: &mut [Complex<f32>]) -> Result<(), FftError> {
+        self.process(buf, false)
+    }
+
+    // Inverse transform, scaled by 1/n so inverse(forward(x)) == x
+    pub fn inverse(&self, buf: &mut [Complex<f32>]) -> Result<(), FftError> {
+        self.process(buf, true)?;
+        let scale = 1.0 / self.n as f32;
+        for x in buf.iter_mut() {
+            *x *= scale;
+        }
+        Ok(())
+    }
+
+    fn process(&self, buf: &mut [Complex<f32>], inverse: bool) -> Result<(), FftError> {
+        let n = self.n;
+        if buf.len() != n {
+            return Err(FftError::LengthMismatch {
+                expected: n,
+                actual: buf.len(),
+            });
+        }
+        if n <= 1 {
+            return Ok(());
+        }
+
+        // Bit-reversal permutation
+        let bits = n.trailing_zeros();
+        for i in 0..n {
+            let j = i.reverse_bits() >> (usize::BITS - bits);
+            if i < j {
+                buf.swap(i, j);
+            }
+        }
+
+        // Butterflies: stage with blocks of `size`, twiddle stride n/size
+        let mut size = 2;
+        while size <= n {
+            let half = size / 2;
+            let stride = n / size;
+            for block in buf.chunks_exact_mut(size) {
+                let (lo, hi) = block.split_at_mut(half);
+                for (k, (a, b)) in lo.iter_mut().zip(hi.iter_mut()).enumerate() {
+                    let mut w = self.twiddles[k * stride];
+                    if inverse {
+                        w = w.conj();
+                    }
+                    let t = w * *b;
+                    *b = *a - t;
+                    *a += t;
+                }
+            }
+            size *= 2;
+        }
+        Ok(())
+    }
+}
+
+// One-off transform; build an `Fft` instead when transforming repeatedly
+pub fn fft(buf: &mut [Complex<f32>]) -> Result<(), FftError> {
+    Fft::new(buf.len())?.forward(buf)
+}
+
+// Periodic Hann window: w[i] = 0.5 - 0.5*cos(2*pi*i/n). Periodic (not
+// symmetric) is the right variant for spectral analysis.
+pub fn hann(n: usize) -> Vec<f32> {
+    (0..n)
+        .map(|i| (0.5 - 0.5 * (2.0 * PI * i as f64 / n as f64).cos()) as f32)
+        .collect()
+}
+
+// Multiply real samples by a window into a complex buffer ready for `fft`
+pub fn windowed(samples: &[f32], window: &[f32]) -> Vec<Complex<f32>> {
+    samples
+        .iter()
+        .zip(window)
+        .map(|(&x, &w)| Complex::new(x * w, 0.0))
+        .collect()
+}
+
+// Single-sided amplitude spectrum (bins 0..=n/2) of a real signal's FFT,
+// corrected for the window's coherent gain so a sine of amplitude A that
+// falls on a bin reads as A
+pub fn magnitude_spectrum(spectrum: &[Complex<f32>], window: &[f32]) -> Vec<f32> {
+    let n = spectrum.len();
+    let gain: f32 = window.iter().sum::<f32>() / n as f32;
+    let scale = 1.0 / (n as f32 * gain);
+    (0..=n / 2)
+        .map(|k| {
+            let both_sides = if k == 0 || k == n / 2 { 1.0 } else { 2.0 };
+            spectrum[k].norm() * scale * both_sides
+        })
+        .collect()
+}
+
+pub fn bin_frequency(bin: usize, n: usize, sample_rate: f32) -> f32 {
+    bin as f32 * sample_rate / n as f32
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Peak {
+    pub bin: usize,
+    // Interpolated between bins
+    pub frequency: f32,
+    pub amplitude: f32,
+}
+
+// Local maxima of a magnitude spectrum, strongest first, ignoring DC.
+// Frequencies are refined by fitting a parabola through the peak bin and
+// its neighbours, which recovers much of the resolution lost to binning.
+pub fn peaks(magnitudes: &[f32], n: usize, sample_rate: f32, count: usize) -> Vec<Peak> {
+    let mut found: Vec<Peak> = (1..magnitudes.len().saturating_sub(1))
+        .filter(|&k| magnitudes[k] > magnitudes[k - 1] && magnitudes[k] >= magnitudes[k + 1])
+        .map(|k| {
+            let (a, b, c) = (magnitudes[k - 1], magnitudes[k], magnitudes[k + 1]);
+            let denom = a - 2.0 * b + c;
+            let offset = if denom.abs() > f32::EPSILON {
+                0.5 * (a - c) / denom
+            } else {
+                0.0
+            };
+            Peak {
+                bin: k,
+                frequency: (k as f32 + offset) * sample_rate / n as f32,
+                amplitude: b,
+            }
+        })
+        .collect();
+    found.sort_by(|x, y| y.amplitude.total_cmp(&x.amplitude));
+    found.truncate(count);
+    found
+}
+
+pub fn dominant_frequency(magnitudes: &[f32], n: usize, sample_rate: f32) -> Option<Peak> {
+    peaks(magnitudes, n, sample_rate, 1).into_iter().next()
+}
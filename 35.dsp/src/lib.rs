@@ -0,0 +1,5 @@
+// Digital signal processing building blocks for sensor data
+
+pub mod fft;
+
+pub use num_complex::Complex;
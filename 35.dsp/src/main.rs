@@ -0,0 +1,198 @@
+use dsp::fft::{
+    bin_frequency, dominant_frequency, fft, hann, magnitude_spectrum, peaks, windowed, Fft,
+};
+use dsp::Complex;
+use std::f64::consts::PI;
+use std::time::Instant;
+
+// Deterministic pseudo-random noise in [-0.5, 0.5)
+struct Noise(u64);
+
+impl Noise {
+    fn next(&mut self) -> f32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32 - 0.5
+    }
+}
+
+fn naive_dft(x: &[Complex<f32>]) -> Vec<Complex<f64>> {
+    let n = x.len();
+    (0..n)
+        .map(|k| {
+            x.iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    let angle = -2.0 * PI * (k * i % n) as f64 / n as f64;
+                    Complex::new(v.re as f64, v.im as f64) * Complex::from_polar(1.0, angle)
+                })
+                .sum()
+        })
+        .collect()
+}
+
+fn to_complex(samples: &[f32]) -> Vec<Complex<f32>> {
+    samples.iter().map(|&x| Complex::new(x, 0.0)).collect()
+}
+
+fn db(ratio: f32) -> f32 {
+    20.0 * ratio.max(1e-12).log10()
+}
+
+// One second of velocity readings from a motor-driven pump, mm/s
+fn machine(sample_rate: f32, n: usize, shaft_hz: f32, bearing_defect: f32) -> Vec<f32> {
+    let mut noise = Noise(7);
+    (0..n)
+        .map(|i| {
+            let t = i as f32 / sample_rate;
+            let w = 2.0 * std::f32::consts::PI * t;
+            1.2 * (w * shaft_hz).sin()                 // 1x: unbalance
+                + 0.35 * (w * 2.0 * shaft_hz + 0.7).sin() // 2x: misalignment
+                + bearing_defect * (w * 3.57 * shaft_hz).sin() // outer race defect
+                + 0.2 * noise.next()
+        })
+        .collect()
+}
+
+fn main() {
+    println!("=== FFT Vibration Analysis ===\n");
+
+    // 1. Transform of a short sequence
+    let short = [1.0, 2.0, 3.0, 4.0, 0.0, 0.0, 0.0, 0.0];
+    println!("1. FFT of {:?}:", short);
+    let mut buf = to_complex(&short);
+    fft(&mut buf).unwrap();
+    for (k, x) in buf.iter().enumerate() {
+        println!("   X[{}] = {:>9.5} {:+9.5}i", k, x.re, x.im);
+    }
+
+    // 2. Windowed magnitude spectrum
+    println!("\n2. Hann magnitude spectrum (n = 16):");
+    let n = 16;
+    let signal: Vec<f32> = (0..n)
+        .map(|i| {
+            let t = i as f64 / n as f64;
+            (0.25 + (2.0 * PI * 4.0 * t).cos() + 0.5 * (2.0 * PI * 6.5 * t).sin()) as f32
+        })
+        .collect();
+    let window = hann(n);
+    let mut buf = windowed(&signal, &window);
+    fft(&mut buf).unwrap();
+    let spectrum = magnitude_spectrum(&buf, &window);
+    println!("   {:.4?}", spectrum);
+
+    // 3. A larger transform: energy is the same in both domains, and the
+    // inverse brings the samples back
+    println!("\n3. n = 1024:");
+    let mut noise = Noise(1);
+    let input: Vec<Complex<f32>> = (0..1024)
+        .map(|_| Complex::new(noise.next(), noise.next()))
+        .collect();
+    let plan = Fft::new(1024).unwrap();
+    let mut buf = input.clone();
+    plan.forward(&mut buf).unwrap();
+    let energy: f32 = input.iter().map(|x| x.norm_sqr()).sum();
+    let spectral: f32 = buf.iter().map(|x| x.norm_sqr()).sum::<f32>() / 1024.0;
+    println!(
+        "   energy {:.4} in time, {:.4} in frequency / n",
+        energy, spectral
+    );
+    plan.inverse(&mut buf).unwrap();
+    for (x, back) in input.iter().zip(&buf).take(2) {
+        println!(
+            "   x {:>9.6} {:+9.6}i   inverse(forward(x)) {:>9.6} {:+9.6}i",
+            x.re, x.im, back.re, back.im
+        );
+    }
+
+    // 4. Why window: a tone between two bins leaks across the spectrum
+    println!("\n4. Spectral leakage, 24.5 Hz tone, 1 Hz bins:");
+    let (rate, n) = (1024.0f32, 1024usize);
+    let tone: Vec<f32> = (0..n)
+        .map(|i| (2.0 * std::f32::consts::PI * 24.5 * i as f32 / rate).sin())
+        .collect();
+    for (name, window) in [("rectangular", vec![1.0; n]), ("hann", hann(n))] {
+        let mut buf = windowed(&tone, &window);
+        plan.forward(&mut buf).unwrap();
+        let mags = magnitude_spectrum(&buf, &window);
+        let top = dominant_frequency(&mags, n, rate).unwrap();
+        println!(
+            "   {:<12} peak {:.3} Hz amp {:.3}   at 50 Hz {:>6.1} dB   at 200 Hz {:>6.1} dB",
+            name,
+            top.frequency,
+            top.amplitude,
+            db(mags[50] / top.amplitude),
+            db(mags[200] / top.amplitude)
+        );
+    }
+
+    // 5. The machine: find running speed and watch for a bearing defect
+    println!("\n5. Pump vibration at 2048 Hz, 4096-point FFT:");
+    let (rate, n) = (2048.0f32, 4096usize);
+    let plan = Fft::new(n).unwrap();
+    let window = hann(n);
+    println!("   resolution {:.2} Hz/bin", bin_frequency(1, n, rate));
+    for (label, defect) in [("healthy", 0.0f32), ("worn bearing", 0.6)] {
+        let samples = machine(rate, n, 24.5, defect);
+        let mut buf = windowed(&samples, &window);
+        plan.forward(&mut buf).unwrap();
+        let mags = magnitude_spectrum(&buf, &window);
+
+        let running = dominant_frequency(&mags, n, rate).unwrap();
+        println!(
+            "   {:<13} dominant {:.2} Hz = {:.0} rpm",
+            label,
+            running.frequency,
+            running.frequency * 60.0
+        );
+        for peak in peaks(&mags, n, rate, 3) {
+            let order = peak.frequency / running.frequency;
+            println!(
+                "      {:>7.2} Hz  {:.3} mm/s  ({:.2}x shaft)",
+                peak.frequency, peak.amplitude, order
+            );
+        }
+        // Outer-race defect frequency for this bearing is 3.57x shaft speed
+        let defect_bin = (3.57 * running.frequency * n as f32 / rate).round() as usize;
+        let level = mags[defect_bin - 1..=defect_bin + 1]
+            .iter()
+            .fold(0.0f32, |m, &a| m.max(a));
+        println!(
+            "      bearing band {:.1} Hz: {:.3} mm/s -> {}",
+            bin_frequency(defect_bin, n, rate),
+            level,
+            if level > 0.2 { "ALERT" } else { "ok" }
+        );
+    }
+
+    // 6. Cost
+    println!("\n6. Timing, n = 1024:");
+    let plan = Fft::new(1024).unwrap();
+    let mut buf = input.clone();
+    let runs = 200;
+    let start = Instant::now();
+    for _ in 0..runs {
+        plan.forward(&mut buf).unwrap();
+    }
+    let fast = start.elapsed() / runs;
+    let start = Instant::now();
+    let _ = naive_dft(&input);
+    let slow = start.elapsed();
+    println!("   radix-2 FFT {:>10.1?}   (n log n = {})", fast, 1024 * 10);
+    println!(
+        "   naive DFT   {:>10.1?}   (n^2     = {})",
+        slow,
+        1024 * 1024
+    );
+
+    // 7. Only powers of two
+    println!("\n7. Invalid length:");
+    let mut odd = vec![Complex::new(0.0f32, 0.0); 1000];
+    if let Err(e) = fft(&mut odd) {
+        println!("   {}", e);
+    }
+
+    println!("\n=== End of FFT Vibration Analysis Examples ===");
+}
@@ -0,0 +1,264 @@
+use dsp::fft::{
+    bin_frequency, dominant_frequency, fft, hann, magnitude_spectrum, peaks, windowed, Fft,
+    FftError,
+};
+use dsp::Complex;
+use std::f64::consts::PI;
+
+// Precomputed with an independent O(n^2) DFT in double precision
+const REFERENCE_INPUT: [f32; 8] = [1.0, 2.0, 3.0, 4.0, 0.0, 0.0, 0.0, 0.0];
+const REFERENCE_FFT: [(f32, f32); 8] = [
+    (10.0, 0.0),
+    (-0.414214, -7.242641),
+    (-2.0, 2.0),
+    (2.414214, -1.242641),
+    (-2.0, 0.0),
+    (2.414214, 1.242641),
+    (-2.0, -2.0),
+    (-0.414214, 7.242641),
+];
+
+// 0.25 + cos(2*pi*4n/16) + 0.5*sin(2*pi*6.5n/16), Hann window, n = 16
+const REFERENCE_HANN_SPECTRUM: [f32; 9] = [
+    0.249589, 0.250982, 0.001622, 0.503566, 0.988776, 0.583114, 0.428398, 0.412338, 0.084858,
+];
+
+// Deterministic pseudo-random samples in [-0.5, 0.5)
+struct Noise(u64);
+
+impl Noise {
+    fn next(&mut self) -> f32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32 - 0.5
+    }
+
+    fn complex(&mut self, n: usize) -> Vec<Complex<f32>> {
+        (0..n)
+            .map(|_| Complex::new(self.next(), self.next()))
+            .collect()
+    }
+}
+
+fn naive_dft(x: &[Complex<f32>]) -> Vec<Complex<f64>> {
+    let n = x.len();
+    (0..n)
+        .map(|k| {
+            x.iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    let angle = -2.0 * PI * (k * i % n) as f64 / n as f64;
+                    Complex::new(v.re as f64, v.im as f64) * Complex::from_polar(1.0, angle)
+                })
+                .sum()
+        })
+        .collect()
+}
+
+fn tone(frequency: f32, amplitude: f32, n: usize, rate: f32) -> Vec<f32> {
+    (0..n)
+        .map(|i| amplitude * (2.0 * std::f32::consts::PI * frequency * i as f32 / rate).sin())
+        .collect()
+}
+
+fn spectrum(samples: &[f32], window: &[f32]) -> Vec<f32> {
+    let mut buf = windowed(samples, window);
+    fft(&mut buf).unwrap();
+    magnitude_spectrum(&buf, window)
+}
+
+fn db(ratio: f32) -> f32 {
+    20.0 * ratio.max(1e-12).log10()
+}
+
+#[test]
+fn eight_points_match_the_reference() {
+    let mut buf: Vec<Complex<f32>> = REFERENCE_INPUT
+        .iter()
+        .map(|&x| Complex::new(x, 0.0))
+        .collect();
+    fft(&mut buf).unwrap();
+    for (k, (x, &(re, im))) in buf.iter().zip(&REFERENCE_FFT).enumerate() {
+        assert!((x - Complex::new(re, im)).norm() < 1e-5, "X[{}] = {}", k, x);
+    }
+}
+
+#[test]
+fn hann_spectrum_matches_the_reference() {
+    let n = 16;
+    let signal: Vec<f32> = (0..n)
+        .map(|i| {
+            let t = i as f64 / n as f64;
+            (0.25 + (2.0 * PI * 4.0 * t).cos() + 0.5 * (2.0 * PI * 6.5 * t).sin()) as f32
+        })
+        .collect();
+    let mags = spectrum(&signal, &hann(n));
+    assert_eq!(mags.len(), n / 2 + 1);
+    for (k, (a, b)) in mags.iter().zip(&REFERENCE_HANN_SPECTRUM).enumerate() {
+        assert!((a - b).abs() < 1e-5, "bin {}: {} vs {}", k, a, b);
+    }
+}
+
+#[test]
+fn every_size_matches_a_naive_f64_dft() {
+    let mut noise = Noise(1);
+    for bits in 0..=10 {
+        let n = 1 << bits;
+        let input = noise.complex(n);
+        let reference = naive_dft(&input);
+        let mut buf = input.clone();
+        Fft::new(n).unwrap().forward(&mut buf).unwrap();
+        let peak = reference.iter().map(|x| x.norm()).fold(0.0, f64::max);
+        let worst = buf
+            .iter()
+            .zip(&reference)
+            .map(|(a, b)| (Complex::new(a.re as f64, a.im as f64) - b).norm())
+            .fold(0.0, f64::max);
+        assert!(worst <= 1e-5 * peak, "n = {}: error {:e}", n, worst / peak);
+    }
+}
+
+#[test]
+fn inverse_undoes_forward() {
+    let mut noise = Noise(2);
+    for bits in 0..=12 {
+        let n = 1 << bits;
+        let plan = Fft::new(n).unwrap();
+        let input = noise.complex(n);
+        let mut buf = input.clone();
+        plan.forward(&mut buf).unwrap();
+        plan.inverse(&mut buf).unwrap();
+        let worst = buf
+            .iter()
+            .zip(&input)
+            .map(|(a, b)| (a - b).norm())
+            .fold(0.0f32, f32::max);
+        assert!(worst < 1e-5, "n = {}: error {:e}", n, worst);
+    }
+}
+
+// Parseval: the energy of a block is the same in both domains, up to the
+// 1/n the forward transform leaves unscaled
+#[test]
+fn energy_is_preserved() {
+    let mut noise = Noise(3);
+    for n in [8, 64, 1024, 4096] {
+        let input = noise.complex(n);
+        let mut buf = input.clone();
+        fft(&mut buf).unwrap();
+        let time: f64 = input.iter().map(|x| x.norm_sqr() as f64).sum();
+        let frequency: f64 = buf.iter().map(|x| x.norm_sqr() as f64).sum::<f64>() / n as f64;
+        assert!(
+            (time - frequency).abs() <= 1e-5 * time,
+            "n = {}: {} vs {}",
+            n,
+            time,
+            frequency
+        );
+    }
+}
+
+#[test]
+fn the_transform_is_linear() {
+    let mut noise = Noise(4);
+    let (a, b) = (noise.complex(256), noise.complex(256));
+    let mixed: Vec<Complex<f32>> = a.iter().zip(&b).map(|(x, y)| x * 2.0 + y * 3.0).collect();
+    let plan = Fft::new(256).unwrap();
+    let (mut fa, mut fb, mut fm) = (a, b, mixed);
+    for buf in [&mut fa, &mut fb, &mut fm] {
+        plan.forward(buf).unwrap();
+    }
+    for k in 0..256 {
+        assert!(
+            (fm[k] - (fa[k] * 2.0 + fb[k] * 3.0)).norm() < 1e-3,
+            "bin {}",
+            k
+        );
+    }
+}
+
+#[test]
+fn a_tone_on_a_bin_reads_its_amplitude() {
+    let (rate, n) = (1024.0, 1024);
+    let samples: Vec<f32> = tone(50.0, 0.8, n, rate).iter().map(|x| x + 0.3).collect();
+    for window in [vec![1.0; n], hann(n)] {
+        let mags = spectrum(&samples, &window);
+        assert!((mags[0] - 0.3).abs() < 1e-3, "DC {}", mags[0]);
+        assert!((mags[50] - 0.8).abs() < 1e-3, "tone {}", mags[50]);
+    }
+}
+
+#[test]
+fn hann_is_periodic_and_tapers_to_zero() {
+    let w = hann(16);
+    assert_eq!(w[0], 0.0);
+    assert!((w[8] - 1.0).abs() < 1e-6);
+    for i in 1..16 {
+        assert!((w[i] - w[16 - i]).abs() < 1e-6);
+    }
+    // Coherent gain 0.5
+    assert!((w.iter().sum::<f32>() / 16.0 - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn hann_keeps_leakage_far_below_a_rectangular_window() {
+    let (rate, n) = (1024.0, 1024);
+    let samples = tone(24.5, 1.0, n, rate);
+    let rect = spectrum(&samples, &vec![1.0; n]);
+    let hann = spectrum(&samples, &hann(n));
+    let rect_top = dominant_frequency(&rect, n, rate).unwrap();
+    let hann_top = dominant_frequency(&hann, n, rate).unwrap();
+    // Halfway between bins: the scalloping loss
+    assert!((rect_top.amplitude - 0.64).abs() < 0.01);
+    assert!((hann_top.amplitude - 0.85).abs() < 0.01);
+    let leak = |mags: &[f32], top: f32| db(mags[50] / top);
+    assert!(leak(&rect, rect_top.amplitude) > -45.0);
+    assert!(leak(&hann, hann_top.amplitude) < -85.0);
+}
+
+#[test]
+fn peaks_interpolate_between_bins_strongest_first() {
+    let (rate, n) = (1024.0, 1024);
+    let a = tone(24.5, 1.0, n, rate);
+    let b = tone(87.25, 0.4, n, rate);
+    let samples: Vec<f32> = a.iter().zip(&b).map(|(x, y)| x + y).collect();
+    let found = peaks(&spectrum(&samples, &hann(n)), n, rate, 2);
+    assert_eq!(found.len(), 2);
+    assert!((found[0].frequency - 24.5).abs() < 0.01, "{:?}", found[0]);
+    assert!((found[1].frequency - 87.25).abs() < 0.05, "{:?}", found[1]);
+    assert!(found[0].amplitude > found[1].amplitude);
+    // Either neighbour of 24.5 Hz may hold the maximum
+    assert!([24, 25].contains(&found[0].bin));
+    assert_eq!(bin_frequency(24, n, rate), 24.0);
+}
+
+#[test]
+fn a_flat_spectrum_has_no_peaks() {
+    assert!(peaks(&[1.0; 9], 16, 16.0, 3).is_empty());
+    assert!(dominant_frequency(&[], 0, 16.0).is_none());
+}
+
+#[test]
+fn lengths_must_be_powers_of_two_and_match_the_plan() {
+    let mut odd = vec![Complex::new(0.0f32, 0.0); 1000];
+    assert_eq!(fft(&mut odd), Err(FftError::NotPowerOfTwo(1000)));
+    assert_eq!(
+        FftError::NotPowerOfTwo(1000).to_string(),
+        "length 1000 is not a power of two"
+    );
+    let plan = Fft::new(64).unwrap();
+    assert_eq!(plan.len(), 64);
+    let mut short = vec![Complex::new(0.0f32, 0.0); 32];
+    let err = plan.forward(&mut short).unwrap_err();
+    assert_eq!(
+        err,
+        FftError::LengthMismatch {
+            expected: 64,
+            actual: 32
+        }
+    );
+    assert_eq!(err.to_string(), "expected 64 samples, got 32");
+    assert!(plan.inverse(&mut short).is_err());
+}
@@ -0,0 +1,186 @@
+// Per-frame level features and a silence/activity detector
+//
+//   RMS    average power, the best single "loudness" number
+//   peak   largest absolute sample, for clipping checks
+//   ZCR    zero-crossing rate: about 2 * frequency / sample_rate for a pure
+//          tone, high for hiss and fricatives, low for hum and vowels
+//
+// Levels are reported in dBFS: 0 dB is a full-scale sine's peak, and every
+// -6 dB halves the amplitude.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameStats {
+    pub rms: f32,
+    pub peak: f32,
+    // Sign changes per sample, 0.0..=1.0
+    pub zcr: f32,
+}
+
+pub const SILENCE_DB: f32 = -120.0;
+
+pub fn dbfs(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        SILENCE_DB
+    } else {
+        (20.0 * amplitude.log10()).max(SILENCE_DB)
+    }
+}
+
+impl FrameStats {
+    pub fn rms_db(&self) -> f32 {
+        dbfs(self.rms)
+    }
+
+    pub fn peak_db(&self) -> f32 {
+        dbfs(self.peak)
+    }
+
+    // Frequency a pure tone with this ZCR would have
+    pub fn zcr_hz(&self, sample_rate: u32) -> f32 {
+        self.zcr * sample_rate as f32 / 2.0
+    }
+}
+
+pub fn analyze(frame: &[f32]) -> FrameStats {
+    if frame.is_empty() {
+        return FrameStats {
+            rms: 0.0,
+            peak: 0.0,
+            zcr: 0.0,
+        };
+    }
+    let mut sum_sq = 0.0f64;
+    let mut peak = 0.0f32;
+    let mut crossings = 0u32;
+    for (i, &x) in frame.iter().enumerate() {
+        sum_sq += (x as f64) * (x as f64);
+        peak = peak.max(x.abs());
+        if i > 0 && (frame[i - 1] >= 0.0) != (x >= 0.0) {
+            crossings += 1;
+        }
+    }
+    FrameStats {
+        rms: (sum_sq / frame.len() as f64).sqrt() as f32,
+        peak,
+        zcr: crossings as f32 / (frame.len() - 1).max(1) as f32,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActivityConfig {
+    // Start when RMS rises above on_db, stop when it stays below off_db
+    pub on_db: f32,
+    pub off_db: f32,
+    // Quiet frames tolerated before an active segment ends; bridges the
+    // gaps between words or machine strokes
+    pub hangover_frames: u32,
+    // Segments shorter than this are discarded as clicks
+    pub min_frames: u32,
+}
+
+impl Default for ActivityConfig {
+    fn default() -> Self {
+        ActivityConfig {
+            on_db: -40.0,
+            off_db: -46.0,
+            hangover_frames: 8,
+            min_frames: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    pub start_frame: u64,
+    // Exclusive; excludes the hangover tail
+    pub end_frame: u64,
+    pub peak_db: f32,
+    pub mean_zcr: f32,
+}
+
+impl Segment {
+    pub fn frames(&self) -> u64 {
+        self.end_frame - self.start_frame
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ActivityDetector {
+    config: ActivityConfig,
+    frame: u64,
+    current: Option<Open>,
+}
+
+#[derive(Debug, Clone)]
+struct Open {
+    start: u64,
+    last_loud: u64,
+    peak_db: f32,
+    zcr_sum: f32,
+    loud_frames: u32,
+}
+
+impl ActivityDetector {
+    pub fn new(config: ActivityConfig) -> ActivityDetector {
+        ActivityDetector {
+            config,
+            frame: 0,
+            current: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.current.is_some()
+    }
+
+    // Feed one frame; returns a segment when one has just finished
+    pub fn update(&mut self, stats: &FrameStats) -> Option<Segment> {
+        let index = self.frame;
+        self.frame += 1;
+        let level = stats.rms_db();
+
+        match &mut self.current {
+            None => {
+                if level >= self.config.on_db {
+                    self.current = Some(Open {
+                        start: index,
+                        last_loud: index,
+                        peak_db: stats.peak_db(),
+                        zcr_sum: stats.zcr,
+                        loud_frames: 1,
+                    });
+                }
+                None
+            }
+            Some(open) => {
+                if level >= self.config.off_db {
+                    open.last_loud = index;
+                    open.peak_db = open.peak_db.max(stats.peak_db());
+                    open.zcr_sum += stats.zcr;
+                    open.loud_frames += 1;
+                    None
+                } else if index - open.last_loud >= self.config.hangover_frames as u64 {
+                    self.close()
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    // End of stream: close any open segment
+    pub fn finish(&mut self) -> Option<Segment> {
+        self.close()
+    }
+
+    fn close(&mut self) -> Option<Segment> {
+        let open = self.current.take()?;
+        let segment = Segment {
+            start_frame: open.start,
+            end_frame: open.last_loud + 1,
+            peak_db: open.peak_db,
+            mean_zcr: open.zcr_sum / open.loud_frames as f32,
+        };
+        (segment.frames() >= self.config.min_frames as u64).then_some(segment)
+    }
+}
@@ -0,0 +1,4 @@
+// Edge audio: streaming WAV input and per-frame level analysis
+
+pub mod analysis;
+pub mod wav;
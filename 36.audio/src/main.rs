@@ -0,0 +1,238 @@
+use audio::analysis::{analyze, ActivityConfig, ActivityDetector};
+use audio::wav::{write_wav, SampleFormat, WavError, WavFormat, WavReader};
+use std::f32::consts::PI;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Cursor};
+use std::time::Instant;
+
+const RATE: u32 = 16_000;
+const FRAME_MS: u32 = 20;
+
+// Deterministic white noise in [-1, 1)
+struct Noise(u32);
+
+impl Noise {
+    fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+}
+
+// Three seconds of a "doorbell" (two-tone chime with short gaps), silence,
+// a hiss (air leak) and a single click, over a faint noise floor
+fn scene() -> Vec<f32> {
+    let mut noise = Noise(0x1234_5678);
+    (0..RATE * 3)
+        .map(|i| {
+            let t = i as f32 / RATE as f32;
+            let mut x = 0.001 * noise.next();
+            let chime = (0.5..0.75).contains(&t) || (0.8..1.2).contains(&t);
+            if chime {
+                let f = if t < 0.75 { 659.0 } else { 523.0 };
+                x += 0.25 * (2.0 * PI * f * t).sin();
+            }
+            if (1.8..2.3).contains(&t) {
+                x += 0.1 * noise.next();
+            }
+            if (2.6..2.61).contains(&t) {
+                x += 0.8 * noise.next();
+            }
+            x
+        })
+        .collect()
+}
+
+fn bar(db: f32) -> String {
+    let width = ((db + 70.0) / 3.0).clamp(0.0, 24.0) as usize;
+    "#".repeat(width)
+}
+
+fn tone(format: WavFormat, seconds: f32) -> Vec<f32> {
+    let frames = (format.sample_rate as f32 * seconds) as usize;
+    let mut out = Vec::with_capacity(frames * format.channels as usize);
+    for i in 0..frames {
+        let x = 0.5 * (2.0 * PI * 440.0 * i as f32 / format.sample_rate as f32).sin();
+        for _ in 0..format.channels {
+            out.push(x);
+        }
+    }
+    out
+}
+
+fn main() -> Result<(), WavError> {
+    println!("=== Audio Level Analysis ===\n");
+
+    let dir = std::env::temp_dir().join("rust-sys-audio-demo");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join("scene.wav");
+
+    // 1. Write a test recording
+    println!("1. Writing {}:", path.display());
+    let format = WavFormat {
+        channels: 1,
+        sample_rate: RATE,
+        sample: SampleFormat::Int(16),
+    };
+    let samples = scene();
+    write_wav(
+        BufWriter::new(File::create(&path)?),
+        format,
+        &samples,
+        Some("door sensor microphone"),
+    )?;
+    println!(
+        "   {} samples, {} bytes",
+        samples.len(),
+        fs::metadata(&path)?.len()
+    );
+
+    // 2. Parsing the header
+    println!("\n2. Header:");
+    let mut reader = WavReader::new(BufReader::new(File::open(&path)?))?;
+    let format = reader.format();
+    println!("   {:?}", format);
+    let skipped: Vec<String> = reader
+        .skipped_chunks()
+        .iter()
+        .map(|id| String::from_utf8_lossy(id).into_owned())
+        .collect();
+    println!("   skipped chunks: {:?}", skipped);
+    println!(
+        "   {} frames = {:.2} s",
+        reader.remaining_frames(),
+        reader.remaining_frames() as f32 / format.sample_rate as f32
+    );
+
+    // 3. Streaming 20 ms frames through the analysis
+    println!("\n3. Level meter (every 100 ms, {} ms frames):", FRAME_MS);
+    let frame_len = (format.sample_rate * FRAME_MS / 1000) as usize;
+    let mut detector = ActivityDetector::new(ActivityConfig::default());
+    let mut segments = Vec::new();
+    let mut frame = Vec::with_capacity(frame_len);
+    let mut index = 0u64;
+    while reader.read_mono(frame_len, &mut frame)? > 0 {
+        let stats = analyze(&frame);
+        if index.is_multiple_of(5) {
+            println!(
+                "   {:>4.1}s rms {:>6.1} dB peak {:>6.1} dB zcr {:>5.0} Hz {}",
+                index as f32 * FRAME_MS as f32 / 1000.0,
+                stats.rms_db(),
+                stats.peak_db(),
+                stats.zcr_hz(format.sample_rate),
+                bar(stats.rms_db())
+            );
+        }
+        segments.extend(detector.update(&stats));
+        index += 1;
+    }
+    segments.extend(detector.finish());
+
+    // 4. Activity segments
+    println!("\n4. Detected activity:");
+    let seconds = |frame: u64| frame as f32 * FRAME_MS as f32 / 1000.0;
+    for s in &segments {
+        let zcr_hz = s.mean_zcr * format.sample_rate as f32 / 2.0;
+        println!(
+            "   {:.2}s - {:.2}s  peak {:>5.1} dBFS  zcr {:>5.0} Hz  {}",
+            seconds(s.start_frame),
+            seconds(s.end_frame),
+            s.peak_db,
+            zcr_hz,
+            if zcr_hz > 2000.0 {
+                "noise-like"
+            } else {
+                "tonal"
+            }
+        );
+    }
+    println!("   (the 50 ms gap in the chime is bridged; the 10 ms click is dropped)");
+
+    // 5. Other encodings decode to the same levels
+    println!("\n5. Encodings (440 Hz at -6 dBFS peak):");
+    for (name, channels, sample) in [
+        ("8-bit unsigned mono", 1, SampleFormat::Int(8)),
+        ("16-bit mono", 1, SampleFormat::Int(16)),
+        ("24-bit stereo", 2, SampleFormat::Int(24)),
+        ("32-bit int stereo", 2, SampleFormat::Int(32)),
+        ("32-bit float mono", 1, SampleFormat::Float32),
+    ] {
+        let format = WavFormat {
+            channels,
+            sample_rate: 8_000,
+            sample,
+        };
+        let mut bytes = Vec::new();
+        write_wav(&mut bytes, format, &tone(format, 0.5), None)?;
+        let mut reader = WavReader::new(Cursor::new(&bytes))?;
+        let mut all = Vec::new();
+        let mut block = Vec::new();
+        while reader.read_mono(1000, &mut block)? > 0 {
+            all.extend_from_slice(&block);
+        }
+        let stats = analyze(&all);
+        println!(
+            "   {:<20} {:>6} bytes  rms {:>6.2} dB  peak {:>6.2} dB",
+            name,
+            bytes.len(),
+            stats.rms_db(),
+            stats.peak_db()
+        );
+    }
+
+    // 6. Broken files
+    println!("\n6. Malformed input:");
+    let mut good = Vec::new();
+    write_wav(&mut good, format, &samples[..1600], None)?;
+    let mut adpcm = good.clone();
+    adpcm[20] = 2; // format tag 2 = MS ADPCM
+    let mut no_data = good.clone();
+    no_data.truncate(36);
+    let mut huge_fmt = good.clone();
+    huge_fmt[16..20].copy_from_slice(&u32::MAX.to_le_bytes());
+    let cases: [(&str, Vec<u8>); 5] = [
+        (
+            "MP3 renamed to .wav",
+            b"ID3\x04\x00\x00\x00\x00\x00\x00\x00\x00".to_vec(),
+        ),
+        ("ADPCM encoded", adpcm),
+        ("header only", no_data),
+        ("fmt size 4 GB", huge_fmt),
+        ("cut off mid-recording", good[..good.len() - 1001].to_vec()),
+    ];
+    for (name, bytes) in cases {
+        match WavReader::new(Cursor::new(bytes)) {
+            Ok(mut reader) => {
+                let expected = reader.remaining_frames();
+                let mut block = Vec::new();
+                let got = reader.read_mono(10_000, &mut block)?;
+                println!("   {:<22} read {} of {} frames", name, got, expected);
+            }
+            Err(e) => println!("   {:<22} {}", name, e),
+        }
+    }
+
+    // 7. Throughput
+    println!("\n7. Throughput:");
+    let start = Instant::now();
+    let mut reader = WavReader::new(BufReader::new(File::open(&path)?))?;
+    let mut frames = 0;
+    while reader.read_mono(frame_len, &mut frame)? > 0 {
+        std::hint::black_box(analyze(&frame));
+        frames += 1;
+    }
+    let elapsed = start.elapsed();
+    let audio_seconds = frames as f32 * FRAME_MS as f32 / 1000.0;
+    println!(
+        "   {} frames ({:.1} s of audio) in {:?} = {:.0}x real time",
+        frames,
+        audio_seconds,
+        elapsed,
+        audio_seconds / elapsed.as_secs_f32()
+    );
+
+    println!("\n=== End of Audio Level Analysis Examples ===");
+    Ok(())
+}
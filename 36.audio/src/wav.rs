@@ -0,0 +1,348 @@
+// Minimal WAV (RIFF) reader and writer
+//
+//   "RIFF" size "WAVE"
+//     "fmt " size  format tag, channels, sample rate, byte rate,
+//                  block align, bits per sample [, extension]
+//     "LIST" size  metadata (skipped)
+//     "data" size  interleaved samples, little-endian
+//
+// Every chunk is a 4-byte id, a u32 size and the body, padded to an even
+// length. The reader walks chunks until it reaches "data" and then streams
+// samples in caller-sized blocks, so a long recording never has to fit in
+// memory.
+
+use std::fmt;
+use std::io::{self, Read, Write};
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+// 16 bytes of PCM fields, a 2-byte extension size and the 22-byte
+// WAVE_FORMAT_EXTENSIBLE extension: the largest fmt chunk we can parse
+const MAX_FORMAT_LEN: u32 = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleFormat {
+    // 8-bit is unsigned, 16/24/32-bit are signed
+    Int(u16),
+    Float32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WavFormat {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub sample: SampleFormat,
+}
+
+impl WavFormat {
+    pub fn bytes_per_sample(&self) -> usize {
+        match self.sample {
+            SampleFormat::Int(bits) => bits as usize / 8,
+            SampleFormat::Float32 => 4,
+        }
+    }
+
+    // One sample for every channel
+    pub fn block_align(&self) -> usize {
+        self.bytes_per_sample() * self.channels as usize
+    }
+}
+
+#[derive(Debug)]
+pub enum WavError {
+    Io(io::Error),
+    NotRiff,
+    NotWave,
+    MissingFormat,
+    MissingData,
+    Unsupported { format_tag: u16, bits: u16 },
+    BadFormat(&'static str),
+}
+
+impl fmt::Display for WavError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WavError::Io(e) => write!(f, "I/O error: {}", e),
+            WavError::NotRiff => write!(f, "not a RIFF file"),
+            WavError::NotWave => write!(f, "RIFF file is not WAVE"),
+            WavError::MissingFormat => write!(f, "data chunk before fmt chunk"),
+            WavError::MissingData => write!(f, "no data chunk"),
+            WavError::Unsupported { format_tag, bits } => write!(
+                f,
+                "unsupported encoding: format tag {:#06x}, {} bits",
+                format_tag, bits
+            ),
+            WavError::BadFormat(what) => write!(f, "bad fmt chunk: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for WavError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WavError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for WavError {
+    fn from(e: io::Error) -> Self {
+        WavError::Io(e)
+    }
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn chunk_header<R: Read>(reader: &mut R) -> Result<Option<([u8; 4], u32)>, WavError> {
+    let mut header = [0u8; 8];
+    match reader.read_exact(&mut header) {
+        Ok(()) => Ok(Some((
+            header[0..4].try_into().unwrap(),
+            read_u32(&header, 4),
+        ))),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn parse_format(body: &[u8]) -> Result<WavFormat, WavError> {
+    if body.len() < 16 {
+        return Err(WavError::BadFormat("shorter than 16 bytes"));
+    }
+    let mut tag = read_u16(body, 0);
+    let channels = read_u16(body, 2);
+    let sample_rate = read_u32(body, 4);
+    let bits = read_u16(body, 14);
+
+    // WAVE_FORMAT_EXTENSIBLE keeps the real tag in its sub-format GUID
+    if tag == FORMAT_EXTENSIBLE {
+        if body.len() < 26 {
+            return Err(WavError::BadFormat("extensible format too short"));
+        }
+        tag = read_u16(body, 24);
+    }
+    if channels == 0 || sample_rate == 0 {
+        return Err(WavError::BadFormat("zero channels or sample rate"));
+    }
+
+    let sample = match (tag, bits) {
+        (FORMAT_PCM, 8 | 16 | 24 | 32) => SampleFormat::Int(bits),
+        (FORMAT_FLOAT, 32) => SampleFormat::Float32,
+        _ => {
+            return Err(WavError::Unsupported {
+                format_tag: tag,
+                bits,
+            })
+        }
+    };
+    let format = WavFormat {
+        channels,
+        sample_rate,
+        sample,
+    };
+    if read_u16(body, 12) as usize != format.block_align() {
+        return Err(WavError::BadFormat("block align does not match"));
+    }
+    Ok(format)
+}
+
+pub struct WavReader<R> {
+    reader: R,
+    format: WavFormat,
+    // Bytes of sample data not yet read
+    remaining: u64,
+    raw: Vec<u8>,
+    skipped_chunks: Vec<[u8; 4]>,
+}
+
+impl<R: Read> WavReader<R> {
+    pub fn new(mut reader: R) -> Result<WavReader<R>, WavError> {
+        let mut riff = [0u8; 12];
+        reader.read_exact(&mut riff)?;
+        if &riff[0..4] != b"RIFF" {
+            return Err(WavError::NotRiff);
+        }
+        if &riff[8..12] != b"WAVE" {
+            return Err(WavError::NotWave);
+        }
+
+        let mut format = None;
+        let mut skipped_chunks = Vec::new();
+        while let Some((id, size)) = chunk_header(&mut reader)? {
+            match &id {
+                b"fmt " => {
+                    // The size comes from the file: don't allocate whatever it says
+                    if size > MAX_FORMAT_LEN {
+                        return Err(WavError::BadFormat("longer than 40 bytes"));
+                    }
+                    let mut body = vec![0u8; size as usize];
+                    reader.read_exact(&mut body)?;
+                    format = Some(parse_format(&body)?);
+                }
+                b"data" => {
+                    let format = format.ok_or(WavError::MissingFormat)?;
+                    return Ok(WavReader {
+                        reader,
+                        format,
+                        remaining: size as u64,
+                        raw: Vec::new(),
+                        skipped_chunks,
+                    });
+                }
+                _ => {
+                    // Unknown chunks are skipped without buffering them
+                    skipped_chunks.push(id);
+                    io::copy(&mut (&mut reader).take(size as u64), &mut io::sink())?;
+                }
+            }
+            if size % 2 == 1 {
+                reader.read_exact(&mut [0u8; 1])?;
+            }
+        }
+        Err(WavError::MissingData)
+    }
+
+    pub fn format(&self) -> WavFormat {
+        self.format
+    }
+
+    pub fn skipped_chunks(&self) -> &[[u8; 4]] {
+        &self.skipped_chunks
+    }
+
+    // Duration of the data that has not been read yet
+    pub fn remaining_frames(&self) -> u64 {
+        self.remaining / self.format.block_align() as u64
+    }
+
+    // Read up to `frames` sample frames, mixed down to mono in [-1, 1].
+    // Returns the number read; 0 means the end of the data. A file cut off
+    // mid-recording ends early rather than failing.
+    pub fn read_mono(&mut self, frames: usize, out: &mut Vec<f32>) -> Result<usize, WavError> {
+        out.clear();
+        let align = self.format.block_align();
+        let wanted = (frames as u64 * align as u64).min(self.remaining) as usize;
+        self.raw.resize(wanted, 0);
+
+        let mut filled = 0;
+        while filled < wanted {
+            match self.reader.read(&mut self.raw[filled..])? {
+                0 => break,
+                n => filled += n,
+            }
+        }
+        let whole = filled / align;
+        self.remaining = if filled < wanted {
+            0
+        } else {
+            self.remaining - filled as u64
+        };
+
+        let width = self.format.bytes_per_sample();
+        let channels = self.format.channels as usize;
+        for frame in self.raw[..whole * align].chunks_exact(align) {
+            let sum: f32 = frame
+                .chunks_exact(width)
+                .map(|s| decode_sample(s, self.format.sample))
+                .sum();
+            out.push(sum / channels as f32);
+        }
+        Ok(whole)
+    }
+}
+
+fn decode_sample(bytes: &[u8], format: SampleFormat) -> f32 {
+    match format {
+        SampleFormat::Int(8) => (bytes[0] as f32 - 128.0) / 128.0,
+        SampleFormat::Int(16) => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0,
+        // Sign-extend by placing the 3 bytes at the top of an i32
+        SampleFormat::Int(24) => {
+            i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) as f32 / 2_147_483_648.0
+        }
+        SampleFormat::Int(_) => {
+            i32::from_le_bytes(bytes.try_into().unwrap()) as f32 / 2_147_483_648.0
+        }
+        SampleFormat::Float32 => f32::from_le_bytes(bytes.try_into().unwrap()),
+    }
+}
+
+fn encode_sample(value: f32, format: SampleFormat, out: &mut Vec<u8>) {
+    let v = value.clamp(-1.0, 1.0);
+    match format {
+        SampleFormat::Int(8) => out.push((v * 127.0 + 128.0).round() as u8),
+        SampleFormat::Int(16) => {
+            out.extend_from_slice(&((v * 32767.0).round() as i16).to_le_bytes())
+        }
+        SampleFormat::Int(24) => {
+            let s = (v * 8_388_607.0).round() as i32;
+            out.extend_from_slice(&s.to_le_bytes()[..3]);
+        }
+        SampleFormat::Int(_) => {
+            out.extend_from_slice(&((v as f64 * 2_147_483_647.0).round() as i32).to_le_bytes())
+        }
+        SampleFormat::Float32 => out.extend_from_slice(&v.to_le_bytes()),
+    }
+}
+
+// Write interleaved samples (one value per channel per frame), with an
+// optional metadata chunk before the data to exercise chunk skipping
+pub fn write_wav<W: Write>(
+    mut writer: W,
+    format: WavFormat,
+    interleaved: &[f32],
+    comment: Option<&str>,
+) -> io::Result<()> {
+    let mut data = Vec::with_capacity(interleaved.len() * format.bytes_per_sample());
+    for &sample in interleaved {
+        encode_sample(sample, format.sample, &mut data);
+    }
+
+    let (tag, bits) = match format.sample {
+        SampleFormat::Int(bits) => (FORMAT_PCM, bits),
+        SampleFormat::Float32 => (FORMAT_FLOAT, 32),
+    };
+    let align = format.block_align() as u32;
+    let mut fmt = Vec::with_capacity(16);
+    fmt.extend_from_slice(&tag.to_le_bytes());
+    fmt.extend_from_slice(&format.channels.to_le_bytes());
+    fmt.extend_from_slice(&format.sample_rate.to_le_bytes());
+    fmt.extend_from_slice(&(format.sample_rate * align).to_le_bytes());
+    fmt.extend_from_slice(&(align as u16).to_le_bytes());
+    fmt.extend_from_slice(&bits.to_le_bytes());
+
+    let mut chunks: Vec<(&[u8; 4], Vec<u8>)> = vec![(b"fmt ", fmt)];
+    if let Some(text) = comment {
+        let mut list = b"INFOICMT".to_vec();
+        let mut value = text.as_bytes().to_vec();
+        value.push(0);
+        list.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        list.extend_from_slice(&value);
+        if value.len() % 2 == 1 {
+            list.push(0);
+        }
+        chunks.push((b"LIST", list));
+    }
+    chunks.push((b"data", data));
+
+    let body: usize = chunks.iter().map(|(_, b)| 8 + b.len() + b.len() % 2).sum();
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&((4 + body) as u32).to_le_bytes())?;
+    writer.write_all(b"WAVE")?;
+    for (id, bytes) in chunks {
+        writer.write_all(id)?;
+        writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        writer.write_all(&bytes)?;
+        if bytes.len() % 2 == 1 {
+            writer.write_all(&[0])?;
+        }
+    }
+    writer.flush()
+}
@@ -0,0 +1,149 @@
+// Frame features on signals with known answers, and the detector's
+// hangover, minimum length and end-of-stream rules
+
+use audio::analysis::{analyze, dbfs, ActivityConfig, ActivityDetector, FrameStats, SILENCE_DB};
+use std::f32::consts::PI;
+
+fn close(a: f32, b: f32, eps: f32) -> bool {
+    (a - b).abs() <= eps
+}
+
+fn frame(level: f32) -> FrameStats {
+    FrameStats {
+        rms: level,
+        peak: level,
+        zcr: 0.1,
+    }
+}
+
+const LOUD: f32 = 0.5;
+const QUIET: f32 = 0.0;
+
+fn config(hangover_frames: u32, min_frames: u32) -> ActivityConfig {
+    ActivityConfig {
+        hangover_frames,
+        min_frames,
+        ..ActivityConfig::default()
+    }
+}
+
+// Feed every level, collecting finished segments, then finish
+fn run(detector: &mut ActivityDetector, levels: &[f32]) -> Vec<(u64, u64)> {
+    let mut segments: Vec<(u64, u64)> = levels
+        .iter()
+        .filter_map(|&level| detector.update(&frame(level)))
+        .map(|s| (s.start_frame, s.end_frame))
+        .collect();
+    segments.extend(detector.finish().map(|s| (s.start_frame, s.end_frame)));
+    segments
+}
+
+#[test]
+fn a_dc_frame_has_equal_rms_and_peak_and_no_crossings() {
+    let stats = analyze(&[-0.25; 64]);
+    assert!(close(stats.rms, 0.25, 1e-6));
+    assert!(close(stats.peak, 0.25, 1e-6));
+    assert_eq!(stats.zcr, 0.0);
+}
+
+#[test]
+fn a_sine_has_rms_peak_over_root_two_and_zcr_at_its_frequency() {
+    let rate = 16_000;
+    let tone = 1000.0;
+    // Phase offset keeps samples off exact zeros
+    let samples: Vec<f32> = (0..1600)
+        .map(|n| (2.0 * PI * tone * n as f32 / rate as f32 + 0.1).sin())
+        .collect();
+    let stats = analyze(&samples);
+    assert!(close(stats.rms, 1.0 / 2f32.sqrt(), 1e-3));
+    assert!(close(stats.peak, 1.0, 1e-2));
+    assert!(close(stats.zcr, 2.0 * tone / rate as f32, 2e-3));
+    assert!(close(stats.zcr_hz(rate), tone, 20.0));
+    assert!(close(stats.peak_db(), 0.0, 0.1));
+    assert!(close(stats.rms_db(), -3.01, 0.05));
+}
+
+#[test]
+fn an_alternating_frame_crosses_on_every_sample() {
+    let samples: Vec<f32> = (0..32)
+        .map(|n| if n % 2 == 0 { 0.5 } else { -0.5 })
+        .collect();
+    let stats = analyze(&samples);
+    assert_eq!(stats.zcr, 1.0);
+    assert_eq!(stats.zcr_hz(8000), 4000.0);
+}
+
+#[test]
+fn an_empty_frame_is_all_zero() {
+    let stats = analyze(&[]);
+    assert_eq!(
+        stats,
+        FrameStats {
+            rms: 0.0,
+            peak: 0.0,
+            zcr: 0.0
+        }
+    );
+    assert_eq!(stats.zcr_hz(16_000), 0.0);
+}
+
+#[test]
+fn silence_reports_the_dbfs_floor() {
+    assert_eq!(analyze(&[0.0; 128]).rms_db(), SILENCE_DB);
+    assert_eq!(dbfs(0.0), SILENCE_DB);
+    assert_eq!(dbfs(-1.0), SILENCE_DB);
+    assert_eq!(dbfs(1e-12), SILENCE_DB);
+    assert!(close(dbfs(1.0), 0.0, 1e-6));
+    assert!(close(dbfs(0.5), -6.02, 0.01));
+}
+
+#[test]
+fn hangover_bridges_a_gap_shorter_than_itself() {
+    let mut detector = ActivityDetector::new(config(3, 1));
+    let mut levels = vec![LOUD; 4];
+    levels.extend([QUIET; 2]);
+    levels.extend([LOUD; 4]);
+    levels.extend([QUIET; 5]);
+    // One segment over both bursts, ending after the last loud frame
+    assert_eq!(run(&mut detector, &levels), vec![(0, 10)]);
+}
+
+#[test]
+fn a_gap_as_long_as_the_hangover_splits_segments() {
+    let mut detector = ActivityDetector::new(config(3, 1));
+    let mut levels = vec![LOUD; 4];
+    levels.extend([QUIET; 3]);
+    levels.extend([LOUD; 4]);
+    assert_eq!(run(&mut detector, &levels), vec![(0, 4), (7, 11)]);
+}
+
+#[test]
+fn bursts_shorter_than_min_frames_are_dropped() {
+    let mut detector = ActivityDetector::new(config(2, 3));
+    let mut levels = vec![QUIET, LOUD, LOUD];
+    levels.extend([QUIET; 4]);
+    levels.extend([LOUD; 3]);
+    levels.extend([QUIET; 4]);
+    assert_eq!(run(&mut detector, &levels), vec![(7, 10)]);
+    assert!(!detector.is_active());
+}
+
+#[test]
+fn finish_closes_an_open_segment() {
+    let mut detector = ActivityDetector::new(config(8, 3));
+    for _ in 0..2 {
+        assert_eq!(detector.update(&frame(QUIET)), None);
+    }
+    for _ in 0..5 {
+        assert_eq!(detector.update(&frame(LOUD)), None);
+    }
+    assert!(detector.is_active());
+
+    let segment = detector.finish().unwrap();
+    assert_eq!((segment.start_frame, segment.end_frame), (2, 7));
+    assert_eq!(segment.frames(), 5);
+    assert!(close(segment.peak_db, dbfs(LOUD), 1e-6));
+    assert!(close(segment.mean_zcr, 0.1, 1e-6));
+    assert!(!detector.is_active());
+    assert_eq!(detector.finish(), None);
+}
@@ -0,0 +1,139 @@
+// Headers the reader must reject before trusting any size in them, and
+// the fmt chunk sizes real files use
+
+use audio::wav::{write_wav, SampleFormat, WavError, WavFormat, WavReader};
+use std::io::Cursor;
+
+const MONO_16: WavFormat = WavFormat {
+    channels: 1,
+    sample_rate: 16_000,
+    sample: SampleFormat::Int(16),
+};
+
+// RIFF/WAVE around the given chunks, each padded to an even length
+fn riff(chunks: &[(&[u8; 4], u32, &[u8])]) -> Vec<u8> {
+    let mut body = b"WAVE".to_vec();
+    for (id, size, bytes) in chunks {
+        body.extend_from_slice(*id);
+        body.extend_from_slice(&size.to_le_bytes());
+        body.extend_from_slice(bytes);
+        if bytes.len() % 2 == 1 {
+            body.push(0);
+        }
+    }
+    let mut file = b"RIFF".to_vec();
+    file.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    file.extend_from_slice(&body);
+    file
+}
+
+// The 16 PCM fields of a mono 16-bit 16 kHz file
+fn pcm_fields(tag: u16) -> Vec<u8> {
+    let mut fmt = Vec::new();
+    fmt.extend_from_slice(&tag.to_le_bytes());
+    fmt.extend_from_slice(&1u16.to_le_bytes());
+    fmt.extend_from_slice(&16_000u32.to_le_bytes());
+    fmt.extend_from_slice(&32_000u32.to_le_bytes());
+    fmt.extend_from_slice(&2u16.to_le_bytes());
+    fmt.extend_from_slice(&16u16.to_le_bytes());
+    fmt
+}
+
+fn samples() -> Vec<u8> {
+    [0i16, 16_384, -16_384, 0]
+        .iter()
+        .flat_map(|s| s.to_le_bytes())
+        .collect()
+}
+
+fn read_all(bytes: Vec<u8>) -> Result<Vec<f32>, WavError> {
+    let mut reader = WavReader::new(Cursor::new(bytes))?;
+    let mut out = Vec::new();
+    reader.read_mono(100, &mut out)?;
+    Ok(out)
+}
+
+#[test]
+fn a_huge_fmt_size_is_rejected_before_allocating() {
+    for size in [u32::MAX, 1 << 30, 41] {
+        let file = riff(&[(b"fmt ", size, &pcm_fields(1))]);
+        match WavReader::new(Cursor::new(file)) {
+            Err(WavError::BadFormat(what)) => assert_eq!(what, "longer than 40 bytes"),
+            Err(e) => panic!("size {}: {}", size, e),
+            Ok(_) => panic!("size {}: accepted", size),
+        }
+    }
+}
+
+#[test]
+fn fmt_chunks_of_16_18_and_40_bytes_are_read() {
+    let data = samples();
+    let plain = pcm_fields(1);
+
+    // cbSize = 0, as many writers add to plain PCM
+    let mut with_cb = pcm_fields(1);
+    with_cb.extend_from_slice(&0u16.to_le_bytes());
+
+    // WAVE_FORMAT_EXTENSIBLE: cbSize 22, valid bits, channel mask, then
+    // the sub-format GUID, whose first two bytes are the real tag
+    let mut extensible = pcm_fields(0xFFFE);
+    extensible.extend_from_slice(&22u16.to_le_bytes());
+    extensible.extend_from_slice(&16u16.to_le_bytes());
+    extensible.extend_from_slice(&4u32.to_le_bytes());
+    extensible.extend_from_slice(&1u16.to_le_bytes());
+    extensible.extend_from_slice(&[
+        0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+    ]);
+
+    for fmt in [plain, with_cb, extensible] {
+        let file = riff(&[
+            (b"fmt ", fmt.len() as u32, &fmt),
+            (b"data", data.len() as u32, &data),
+        ]);
+        assert_eq!(
+            read_all(file).unwrap(),
+            [0.0, 0.5, -0.5, 0.0],
+            "{} bytes",
+            fmt.len()
+        );
+    }
+}
+
+#[test]
+fn malformed_files_give_their_error() {
+    let mut good = Vec::new();
+    write_wav(&mut good, MONO_16, &[0.0, 0.5, -0.5, 0.0], None).unwrap();
+    let mut adpcm = good.clone();
+    adpcm[20] = 2;
+    let mut bad_align = good.clone();
+    bad_align[32] = 4;
+
+    assert!(matches!(
+        read_all(b"ID3\x04\x00\x00\x00\x00\x00\x00\x00\x00".to_vec()),
+        Err(WavError::NotRiff)
+    ));
+    assert!(matches!(
+        read_all(adpcm),
+        Err(WavError::Unsupported {
+            format_tag: 2,
+            bits: 16
+        })
+    ));
+    assert!(matches!(
+        read_all(bad_align),
+        Err(WavError::BadFormat("block align does not match"))
+    ));
+    assert!(matches!(
+        read_all(good[..36].to_vec()),
+        Err(WavError::MissingData)
+    ));
+    let data = samples();
+    assert!(matches!(
+        read_all(riff(&[(b"data", data.len() as u32, &data)])),
+        Err(WavError::MissingFormat)
+    ));
+    assert!(matches!(
+        read_all(riff(&[(b"fmt ", 14, &pcm_fields(1)[..14])])),
+        Err(WavError::BadFormat("shorter than 16 bytes"))
+    ));
+}
@@ -0,0 +1,77 @@
+// Each pipeline stage at the three frame sizes of the demo
+//
+//   cargo bench                # every stage
+//   cargo bench -- sobel       # one stage
+//
+// Every stage reads the grayscale conveyor scene (the binary image for
+// labelling), so the numbers add up to one pass of the detection pipeline.
+// Throughput is reported in pixels per second, which stays roughly flat
+// across sizes while the frames fit in cache.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use imgproc::filter::{convolve3x3, sobel, Kernel3};
+use imgproc::label::{label_components, Connectivity};
+use imgproc::ops::{histogram, otsu_level, threshold, to_grayscale};
+use imgproc::scene::conveyor_scene;
+use imgproc::{Gray, Image, Rgb};
+use std::hint::black_box;
+
+const SIZES: [(&str, usize, usize); 3] =
+    [("QVGA", 320, 240), ("VGA", 640, 480), ("720p", 1280, 720)];
+
+struct Frame {
+    rgb: Image<Rgb>,
+    gray: Image<Gray>,
+    binary: Image<Gray>,
+}
+
+fn frame(width: usize, height: usize) -> Frame {
+    let rgb = conveyor_scene(width, height);
+    let mut gray = Image::<Gray>::new(width, height).unwrap();
+    to_grayscale(&rgb.view(), &mut gray.view_mut()).unwrap();
+    let mut binary = Image::<Gray>::new(width, height).unwrap();
+    let level = otsu_level(&histogram(&gray.view()));
+    threshold(&gray.view(), &mut binary.view_mut(), level).unwrap();
+    Frame { rgb, gray, binary }
+}
+
+// One group per stage with a benchmark per frame size
+macro_rules! stage {
+    ($c:expr, $name:literal, |$f:ident, $out:ident| $body:expr) => {{
+        let mut group = $c.benchmark_group($name);
+        for (size, width, height) in SIZES {
+            let $f = frame(width, height);
+            let mut $out = Image::<Gray>::new(width, height).unwrap();
+            group.throughput(Throughput::Elements((width * height) as u64));
+            group.bench_function(BenchmarkId::from_parameter(size), |b| b.iter(|| $body));
+        }
+        group.finish();
+    }};
+}
+
+fn stages(c: &mut Criterion) {
+    stage!(c, "grayscale", |f, out| to_grayscale(
+        &black_box(&f.rgb).view(),
+        &mut out.view_mut()
+    ));
+    stage!(c, "gaussian", |f, out| convolve3x3(
+        &black_box(&f.gray).view(),
+        &mut out.view_mut(),
+        &Kernel3::GAUSSIAN
+    ));
+    stage!(c, "sobel", |f, out| sobel(
+        &black_box(&f.gray).view(),
+        &mut out.view_mut()
+    ));
+    stage!(c, "threshold", |f, out| {
+        let level = otsu_level(&histogram(&black_box(&f.gray).view()));
+        threshold(&f.gray.view(), &mut out.view_mut(), level)
+    });
+    stage!(c, "label", |f, _out| label_components(
+        &black_box(&f.binary).view(),
+        Connectivity::Eight
+    ));
+}
+
+criterion_group!(benches, stages);
+criterion_main!(benches);
@@ -0,0 +1,106 @@
+// 3x3 neighbourhood filters: blur, sharpen and Sobel edges
+//
+// Each output pixel is a weighted sum of the 3x3 block around the input
+// pixel. Pixels outside the frame repeat the nearest edge pixel, so the
+// output has the same size as the input and borders do not darken.
+//
+// Like most image libraries, this computes correlation: the kernel is
+// not flipped. That makes no difference for the symmetric blur kernels and
+// only flips the sign of a Sobel response.
+
+use crate::image::{check_same_size, Gray, ImageError, ImageView, ImageViewMut};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Kernel3 {
+    pub weights: [[i32; 3]; 3],
+    pub divisor: i32,
+}
+
+impl Kernel3 {
+    pub const BOX_BLUR: Kernel3 = Kernel3 {
+        weights: [[1, 1, 1], [1, 1, 1], [1, 1, 1]],
+        divisor: 9,
+    };
+
+    pub const GAUSSIAN: Kernel3 = Kernel3 {
+        weights: [[1, 2, 1], [2, 4, 2], [1, 2, 1]],
+        divisor: 16,
+    };
+
+    pub const SHARPEN: Kernel3 = Kernel3 {
+        weights: [[0, -1, 0], [-1, 5, -1], [0, -1, 0]],
+        divisor: 1,
+    };
+
+    // Horizontal intensity change: responds to vertical edges
+    pub const SOBEL_X: Kernel3 = Kernel3 {
+        weights: [[-1, 0, 1], [-2, 0, 2], [-1, 0, 1]],
+        divisor: 1,
+    };
+
+    // Vertical intensity change: responds to horizontal edges
+    pub const SOBEL_Y: Kernel3 = Kernel3 {
+        weights: [[-1, -2, -1], [0, 0, 0], [1, 2, 1]],
+        divisor: 1,
+    };
+
+    pub fn apply(&self, window: &[[i32; 3]; 3]) -> i32 {
+        let mut sum = 0;
+        for (w_row, p_row) in self.weights.iter().zip(window) {
+            for (w, p) in w_row.iter().zip(p_row) {
+                sum += w * p;
+            }
+        }
+        sum / self.divisor
+    }
+}
+
+// Calls `f` with the 3x3 window around every pixel (border replicated)
+// and stores the result
+fn map3x3<F>(
+    src: &ImageView<'_, Gray>,
+    dst: &mut ImageViewMut<'_, Gray>,
+    mut f: F,
+) -> Result<(), ImageError>
+where
+    F: FnMut(&[[i32; 3]; 3]) -> u8,
+{
+    check_same_size(src.dimensions(), dst.dimensions())?;
+    let (width, height) = src.dimensions();
+    for y in 0..height {
+        let rows = [
+            src.row(y.saturating_sub(1)),
+            src.row(y),
+            src.row((y + 1).min(height - 1)),
+        ];
+        let out = dst.row_mut(y);
+        for (x, o) in out.iter_mut().enumerate() {
+            let cols = [x.saturating_sub(1), x, (x + 1).min(width - 1)];
+            let window = rows.map(|row| cols.map(|c| row[c] as i32));
+            *o = f(&window);
+        }
+    }
+    Ok(())
+}
+
+// Applies `kernel`, clamping the result to 0..=255
+pub fn convolve3x3(
+    src: &ImageView<'_, Gray>,
+    dst: &mut ImageViewMut<'_, Gray>,
+    kernel: &Kernel3,
+) -> Result<(), ImageError> {
+    map3x3(src, dst, |window| kernel.apply(window).clamp(0, 255) as u8)
+}
+
+// Edge strength |Gx| + |Gy|, a cheap stand-in for sqrt(Gx² + Gy²),
+// saturated at 255
+pub fn sobel(
+    src: &ImageView<'_, Gray>,
+    dst: &mut ImageViewMut<'_, Gray>,
+) -> Result<(), ImageError> {
+    map3x3(src, dst, |window| {
+        let gx = Kernel3::SOBEL_X.apply(window);
+        let gy = Kernel3::SOBEL_Y.apply(window);
+        (gx.abs() + gy.abs()).min(255) as u8
+    })
+}
@@ -0,0 +1,360 @@
+// Typed views over raw pixel buffers
+//
+// Camera drivers hand over frames as plain byte slices: row after row of
+// interleaved pixels, each row `stride` bytes apart. The stride is often
+// larger than width * channels because hardware pads rows for alignment:
+//
+//   width 2, Rgb, stride 8:   R G B R G B . .   <- row 0
+//                             R G B R G B . .   <- row 1
+//
+// `ImageView` checks the dimensions against the buffer once, when it is
+// created, and carries the pixel format in its type. A function taking
+// `ImageView<Rgb>` cannot be handed a grayscale frame by mistake, and no
+// processing code has to trust loose width/height arguments.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::marker::PhantomData;
+
+pub trait Pixel: Copy + fmt::Debug {
+    const CHANNELS: usize;
+    const NAME: &'static str;
+}
+
+// 8-bit luminance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gray;
+
+// 8-bit red, green, blue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb;
+
+impl Pixel for Gray {
+    const CHANNELS: usize = 1;
+    const NAME: &'static str = "gray";
+}
+
+impl Pixel for Rgb {
+    const CHANNELS: usize = 3;
+    const NAME: &'static str = "rgb";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageError {
+    Empty,
+    StrideTooSmall {
+        stride: usize,
+        min: usize,
+    },
+    BufferTooSmall {
+        needed: usize,
+        actual: usize,
+    },
+    SizeMismatch {
+        expected: (usize, usize),
+        actual: (usize, usize),
+    },
+    OutOfBounds,
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::Empty => write!(f, "image has zero width or height"),
+            ImageError::StrideTooSmall { stride, min } => {
+                write!(f, "stride {} is shorter than a row ({} bytes)", stride, min)
+            }
+            ImageError::BufferTooSmall { needed, actual } => {
+                write!(f, "buffer has {} bytes, need {}", actual, needed)
+            }
+            ImageError::SizeMismatch { expected, actual } => write!(
+                f,
+                "expected a {}x{} image, got {}x{}",
+                expected.0, expected.1, actual.0, actual.1
+            ),
+            ImageError::OutOfBounds => write!(f, "region lies outside the image"),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+// Bytes a buffer must hold; the last row need not be padded
+fn check_layout<P: Pixel>(
+    len: usize,
+    width: usize,
+    height: usize,
+    stride: usize,
+) -> Result<(), ImageError> {
+    if width == 0 || height == 0 {
+        return Err(ImageError::Empty);
+    }
+    let row = width * P::CHANNELS;
+    if stride < row {
+        return Err(ImageError::StrideTooSmall { stride, min: row });
+    }
+    let needed = stride * (height - 1) + row;
+    if len < needed {
+        return Err(ImageError::BufferTooSmall {
+            needed,
+            actual: len,
+        });
+    }
+    Ok(())
+}
+
+// Fails unless both images have the same width and height
+pub fn check_same_size(expected: (usize, usize), actual: (usize, usize)) -> Result<(), ImageError> {
+    if expected != actual {
+        return Err(ImageError::SizeMismatch { expected, actual });
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ImageView<'a, P: Pixel> {
+    data: &'a [u8],
+    width: usize,
+    height: usize,
+    stride: usize,
+    _pixel: PhantomData<P>,
+}
+
+impl<'a, P: Pixel> ImageView<'a, P> {
+    // Tightly packed rows
+    pub fn new(data: &'a [u8], width: usize, height: usize) -> Result<Self, ImageError> {
+        ImageView::with_stride(data, width, height, width * P::CHANNELS)
+    }
+
+    pub fn with_stride(
+        data: &'a [u8],
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) -> Result<Self, ImageError> {
+        check_layout::<P>(data.len(), width, height, stride)?;
+        Ok(ImageView {
+            data,
+            width,
+            height,
+            stride,
+            _pixel: PhantomData,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    // The pixels of row `y`, without padding
+    pub fn row(&self, y: usize) -> &'a [u8] {
+        let start = y * self.stride;
+        &self.data[start..start + self.width * P::CHANNELS]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [u8]> + '_ {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    // A region of interest sharing the same buffer; nothing is copied
+    pub fn sub_view(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<ImageView<'a, P>, ImageError> {
+        if x + width > self.width || y + height > self.height {
+            return Err(ImageError::OutOfBounds);
+        }
+        // Checked before slicing: an empty region may start past the end
+        if width == 0 || height == 0 {
+            return Err(ImageError::Empty);
+        }
+        let start = y * self.stride + x * P::CHANNELS;
+        ImageView::with_stride(&self.data[start..], width, height, self.stride)
+    }
+}
+
+impl ImageView<'_, Gray> {
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        self.row(y)[x]
+    }
+}
+
+impl ImageView<'_, Rgb> {
+    pub fn get(&self, x: usize, y: usize) -> [u8; 3] {
+        let px = &self.row(y)[x * 3..x * 3 + 3];
+        [px[0], px[1], px[2]]
+    }
+}
+
+#[derive(Debug)]
+pub struct ImageViewMut<'a, P: Pixel> {
+    data: &'a mut [u8],
+    width: usize,
+    height: usize,
+    stride: usize,
+    _pixel: PhantomData<P>,
+}
+
+impl<'a, P: Pixel> ImageViewMut<'a, P> {
+    pub fn new(data: &'a mut [u8], width: usize, height: usize) -> Result<Self, ImageError> {
+        ImageViewMut::with_stride(data, width, height, width * P::CHANNELS)
+    }
+
+    pub fn with_stride(
+        data: &'a mut [u8],
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) -> Result<Self, ImageError> {
+        check_layout::<P>(data.len(), width, height, stride)?;
+        Ok(ImageViewMut {
+            data,
+            width,
+            height,
+            stride,
+            _pixel: PhantomData,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [u8] {
+        let start = y * self.stride;
+        &mut self.data[start..start + self.width * P::CHANNELS]
+    }
+
+    pub fn as_view(&self) -> ImageView<'_, P> {
+        ImageView {
+            data: self.data,
+            width: self.width,
+            height: self.height,
+            stride: self.stride,
+            _pixel: PhantomData,
+        }
+    }
+
+    pub fn fill(&mut self, value: u8) {
+        for y in 0..self.height {
+            self.row_mut(y).fill(value);
+        }
+    }
+}
+
+impl ImageViewMut<'_, Gray> {
+    pub fn set(&mut self, x: usize, y: usize, value: u8) {
+        self.row_mut(y)[x] = value;
+    }
+}
+
+impl ImageViewMut<'_, Rgb> {
+    pub fn set(&mut self, x: usize, y: usize, value: [u8; 3]) {
+        self.row_mut(y)[x * 3..x * 3 + 3].copy_from_slice(&value);
+    }
+}
+
+// An owned, tightly packed frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image<P: Pixel> {
+    data: Vec<u8>,
+    width: usize,
+    height: usize,
+    _pixel: PhantomData<P>,
+}
+
+impl<P: Pixel> Image<P> {
+    // A black frame
+    pub fn new(width: usize, height: usize) -> Result<Self, ImageError> {
+        Image::from_vec(vec![0; width * height * P::CHANNELS], width, height)
+    }
+
+    pub fn from_vec(data: Vec<u8>, width: usize, height: usize) -> Result<Self, ImageError> {
+        check_layout::<P>(data.len(), width, height, width * P::CHANNELS)?;
+        Ok(Image {
+            data,
+            width,
+            height,
+            _pixel: PhantomData,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        self.data
+    }
+
+    pub fn view(&self) -> ImageView<'_, P> {
+        ImageView {
+            data: &self.data,
+            width: self.width,
+            height: self.height,
+            stride: self.width * P::CHANNELS,
+            _pixel: PhantomData,
+        }
+    }
+
+    pub fn view_mut(&mut self) -> ImageViewMut<'_, P> {
+        ImageViewMut {
+            data: &mut self.data,
+            width: self.width,
+            height: self.height,
+            stride: self.width * P::CHANNELS,
+            _pixel: PhantomData,
+        }
+    }
+}
+
+impl Image<Gray> {
+    // Binary PGM (P5), viewable in most image viewers
+    pub fn write_pgm<W: Write>(&self, mut out: W) -> io::Result<()> {
+        write!(out, "P5\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.data)
+    }
+}
+
+impl Image<Rgb> {
+    // Binary PPM (P6)
+    pub fn write_ppm<W: Write>(&self, mut out: W) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.data)
+    }
+}
@@ -0,0 +1,202 @@
+// Connected-component labelling of binary images
+//
+// Every group of touching foreground (non-zero) pixels gets its own label,
+// so a thresholded frame turns into a list of objects with an area,
+// bounding box and centroid.
+//
+// The classic two-pass algorithm:
+//
+//   1. scan in raster order; give each foreground pixel the smallest label
+//      among its already-visited neighbours (or a new one), and record in a
+//      union-find table that all those neighbour labels are the same object
+//   2. scan again, replacing each label by its union-find root, renumbered
+//      1, 2, 3 ... in order of first appearance, and accumulate statistics
+//
+// Memory is one u32 per pixel plus one table entry per provisional label.
+
+use crate::image::{Gray, ImageView};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    // Left, right, up, down
+    Four,
+    // Diagonals too
+    Eight,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Component {
+    pub label: u32,
+    pub area: usize,
+    pub bbox: Rect,
+    pub centroid: (f32, f32),
+}
+
+#[derive(Debug, Clone)]
+pub struct Labels {
+    width: usize,
+    labels: Vec<u32>,
+    components: Vec<Component>,
+}
+
+impl Labels {
+    // 0 is background
+    pub fn label_at(&self, x: usize, y: usize) -> u32 {
+        self.labels[y * self.width + x]
+    }
+
+    pub fn components(&self) -> &[Component] {
+        &self.components
+    }
+
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+// Union-find over provisional labels; index 0 is unused
+struct Equivalences {
+    parent: Vec<u32>,
+}
+
+impl Equivalences {
+    fn new_label(&mut self) -> u32 {
+        let label = self.parent.len() as u32;
+        self.parent.push(label);
+        label
+    }
+
+    // Path halving keeps the trees flat without recursion
+    fn find(&mut self, mut label: u32) -> u32 {
+        while self.parent[label as usize] != label {
+            let grandparent = self.parent[self.parent[label as usize] as usize];
+            self.parent[label as usize] = grandparent;
+            label = grandparent;
+        }
+        label
+    }
+
+    // The smaller root wins, so roots follow raster order
+    fn union(&mut self, a: u32, b: u32) {
+        let (a, b) = (self.find(a), self.find(b));
+        let (low, high) = if a < b { (a, b) } else { (b, a) };
+        self.parent[high as usize] = low;
+    }
+}
+
+struct Accumulator {
+    area: usize,
+    sum_x: u64,
+    sum_y: u64,
+    min: (usize, usize),
+    max: (usize, usize),
+}
+
+pub fn label_components(binary: &ImageView<'_, Gray>, connectivity: Connectivity) -> Labels {
+    let (width, height) = binary.dimensions();
+    let mut labels = vec![0u32; width * height];
+    let mut eq = Equivalences { parent: vec![0] };
+
+    // Pass 1: provisional labels
+    for y in 0..height {
+        for (x, &value) in binary.row(y).iter().enumerate() {
+            if value == 0 {
+                continue;
+            }
+            let mut neighbours = [0u32; 4];
+            neighbours[0] = if x > 0 { labels[y * width + x - 1] } else { 0 };
+            if y > 0 {
+                let above = (y - 1) * width;
+                neighbours[1] = labels[above + x];
+                if connectivity == Connectivity::Eight {
+                    if x > 0 {
+                        neighbours[2] = labels[above + x - 1];
+                    }
+                    if x + 1 < width {
+                        neighbours[3] = labels[above + x + 1];
+                    }
+                }
+            }
+
+            let label = match neighbours.iter().filter(|&&n| n != 0).min() {
+                None => eq.new_label(),
+                Some(&smallest) => {
+                    for &n in neighbours.iter().filter(|&&n| n != 0 && n != smallest) {
+                        eq.union(smallest, n);
+                    }
+                    smallest
+                }
+            };
+            labels[y * width + x] = label;
+        }
+    }
+
+    // Pass 2: resolve to roots, renumber, gather statistics
+    let mut renumber = vec![0u32; eq.parent.len()];
+    let mut stats: Vec<Accumulator> = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            let provisional = labels[y * width + x];
+            if provisional == 0 {
+                continue;
+            }
+            let root = eq.find(provisional) as usize;
+            if renumber[root] == 0 {
+                stats.push(Accumulator {
+                    area: 0,
+                    sum_x: 0,
+                    sum_y: 0,
+                    min: (x, y),
+                    max: (x, y),
+                });
+                renumber[root] = stats.len() as u32;
+            }
+            let label = renumber[root];
+            labels[y * width + x] = label;
+
+            let s = &mut stats[label as usize - 1];
+            s.area += 1;
+            s.sum_x += x as u64;
+            s.sum_y += y as u64;
+            s.min = (s.min.0.min(x), s.min.1.min(y));
+            s.max = (s.max.0.max(x), s.max.1.max(y));
+        }
+    }
+
+    let components = stats
+        .iter()
+        .enumerate()
+        .map(|(i, s)| Component {
+            label: i as u32 + 1,
+            area: s.area,
+            bbox: Rect {
+                x: s.min.0,
+                y: s.min.1,
+                width: s.max.0 - s.min.0 + 1,
+                height: s.max.1 - s.min.1 + 1,
+            },
+            centroid: (
+                s.sum_x as f32 / s.area as f32,
+                s.sum_y as f32 / s.area as f32,
+            ),
+        })
+        .collect();
+
+    Labels {
+        width,
+        labels,
+        components,
+    }
+}
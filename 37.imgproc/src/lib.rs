@@ -0,0 +1,9 @@
+// Image processing on raw camera frame buffers
+
+pub mod filter;
+pub mod image;
+pub mod label;
+pub mod ops;
+pub mod scene;
+
+pub use image::{Gray, Image, ImageError, ImageView, ImageViewMut, Pixel, Rgb};
@@ -0,0 +1,219 @@
+use imgproc::filter::{convolve3x3, sobel, Kernel3};
+use imgproc::label::{label_components, Connectivity};
+use imgproc::ops::{histogram, luma, otsu_level, threshold, to_grayscale};
+use imgproc::scene::conveyor_scene;
+use imgproc::{Gray, Image, ImageView, Rgb};
+use std::fs::File;
+use std::io::BufWriter;
+use std::time::Instant;
+
+fn ascii(view: &ImageView<'_, Gray>) {
+    const RAMP: &[u8] = b" .:-=+*#%@";
+    for row in view.rows() {
+        let line: String = row
+            .iter()
+            .map(|&v| RAMP[v as usize * RAMP.len() / 256] as char)
+            .collect();
+        println!("   |{}|", line);
+    }
+}
+
+fn print_row(name: &str, row: &[u8]) {
+    let values: Vec<String> = row.iter().map(|v| format!("{:>3}", v)).collect();
+    println!("   {:<10} {}", name, values.join(" "));
+}
+
+fn main() {
+    println!("=== Image Processing Examples ===\n");
+
+    // 1. Views over raw buffers
+    println!("1. Views over raw buffers:");
+    // 2x2 RGB frame whose rows are padded to 8 bytes, as a DMA engine might
+    // deliver it
+    let raw = [
+        255, 0, 0, 0, 255, 0, 0xEE, 0xEE, //
+        0, 0, 255, 255, 255, 255, 0xEE, 0xEE,
+    ];
+    let view = ImageView::<Rgb>::with_stride(&raw, 2, 2, 8).unwrap();
+    println!(
+        "   {}x{} rgb, stride {}: (1,0) = {:?}, (0,1) = {:?}",
+        view.width(),
+        view.height(),
+        view.stride(),
+        view.get(1, 0),
+        view.get(0, 1)
+    );
+    let roi = view.sub_view(1, 1, 1, 1).unwrap();
+    println!("   sub view at (1,1): {:?}", roi.get(0, 0));
+    let attempts: [(&str, usize, usize, usize); 3] = [
+        ("rows longer than stride", 3, 2, 8),
+        ("one row too many", 2, 3, 8),
+        ("zero width", 0, 2, 8),
+    ];
+    for (name, w, h, stride) in attempts {
+        match ImageView::<Rgb>::with_stride(&raw, w, h, stride) {
+            Ok(_) => println!("   {:<24} accepted?!", name),
+            Err(e) => println!("   {:<24} rejected: {}", name, e),
+        }
+    }
+    println!("   and to_grayscale(&gray_view, ..) does not compile: the format is in the type");
+
+    // 2. Grayscale conversion
+    println!("\n2. BT.601 luma, fixed point:");
+    for (name, rgb) in [
+        ("red", [255, 0, 0]),
+        ("green", [0, 255, 0]),
+        ("blue", [0, 0, 255]),
+        ("white", [255, 255, 255]),
+        ("yellow", [220, 200, 40]),
+    ] {
+        println!("   {:<7} {:?} -> {}", name, rgb, luma(rgb));
+    }
+
+    // 3. Thresholding a small frame
+    println!("\n3. Grayscale and Otsu threshold, 60x20 frame:");
+    let scene = conveyor_scene(60, 20);
+    let mut gray = Image::<Gray>::new(60, 20).unwrap();
+    to_grayscale(&scene.view(), &mut gray.view_mut()).unwrap();
+    ascii(&gray.view());
+
+    let hist = histogram(&gray.view());
+    let level = otsu_level(&hist);
+    let bright: u32 = hist[level as usize + 1..].iter().sum();
+    println!(
+        "   Otsu level {} -> {} of {} pixels are foreground",
+        level,
+        bright,
+        60 * 20
+    );
+    let mut binary = Image::<Gray>::new(60, 20).unwrap();
+    threshold(&gray.view(), &mut binary.view_mut(), level).unwrap();
+    ascii(&binary.view());
+
+    // 4. 3x3 filters on a step edge
+    println!("\n4. 3x3 filters across a step edge:");
+    let step: Vec<u8> = (0..3)
+        .flat_map(|_| [20, 20, 20, 20, 220, 220, 220, 220])
+        .collect();
+    let step = ImageView::<Gray>::new(&step, 8, 3).unwrap();
+    print_row("input", step.row(1));
+    let mut out = Image::<Gray>::new(8, 3).unwrap();
+    for (name, kernel) in [
+        ("box blur", Kernel3::BOX_BLUR),
+        ("gaussian", Kernel3::GAUSSIAN),
+        ("sharpen", Kernel3::SHARPEN),
+    ] {
+        convolve3x3(&step, &mut out.view_mut(), &kernel).unwrap();
+        print_row(name, out.view().row(1));
+    }
+    sobel(&step, &mut out.view_mut()).unwrap();
+    print_row("sobel", out.view().row(1));
+    println!("   (sharpen overshoots and is clamped; sobel saturates at 255)");
+
+    // 5. Connected components
+    println!("\n5. Connected components of the thresholded frame:");
+    let labels = label_components(&binary.view(), Connectivity::Eight);
+    println!("   {} components", labels.len());
+    for c in labels.components() {
+        let note = if c.area < 4 { "  <- dust" } else { "" };
+        println!(
+            "   #{} area {:>3}  box {:>2}x{:<2} at ({:>2},{:>2})  centre ({:>4.1},{:>4.1}){}",
+            c.label,
+            c.area,
+            c.bbox.width,
+            c.bbox.height,
+            c.bbox.x,
+            c.bbox.y,
+            c.centroid.0,
+            c.centroid.1,
+            note
+        );
+    }
+    let parts = labels.components().iter().filter(|c| c.area >= 4).count();
+    println!(
+        "   {} parts after dropping components under 4 pixels",
+        parts
+    );
+
+    let diagonal: Vec<u8> = (0..25).map(|i| if i % 6 == 0 { 255 } else { 0 }).collect();
+    let diagonal = ImageView::<Gray>::new(&diagonal, 5, 5).unwrap();
+    println!(
+        "   5-pixel diagonal: {} components with 4-connectivity, {} with 8",
+        label_components(&diagonal, Connectivity::Four).len(),
+        label_components(&diagonal, Connectivity::Eight).len()
+    );
+
+    // 6. Saving results for inspection
+    println!("\n6. Writing PGM images:");
+    let dir = std::env::temp_dir().join("rust-sys-imgproc-demo");
+    std::fs::create_dir_all(&dir).unwrap();
+    let frame = conveyor_scene(320, 240);
+    let mut gray = Image::<Gray>::new(320, 240).unwrap();
+    to_grayscale(&frame.view(), &mut gray.view_mut()).unwrap();
+    let mut edges = Image::<Gray>::new(320, 240).unwrap();
+    sobel(&gray.view(), &mut edges.view_mut()).unwrap();
+    for (name, image) in [("gray.pgm", &gray), ("edges.pgm", &edges)] {
+        let path = dir.join(name);
+        image
+            .write_pgm(BufWriter::new(File::create(&path).unwrap()))
+            .unwrap();
+        println!("   {}", path.display());
+    }
+    let path = dir.join("scene.ppm");
+    frame
+        .write_ppm(BufWriter::new(File::create(&path).unwrap()))
+        .unwrap();
+    println!("   {}", path.display());
+
+    // 7. Benchmarks on realistic frame sizes
+    println!("\n7. Time per frame:");
+    println!(
+        "   {:<14} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "frame", "grayscale", "gaussian", "sobel", "threshold", "label"
+    );
+    for (name, width, height) in [("QVGA", 320, 240), ("VGA", 640, 480), ("720p", 1280, 720)] {
+        let frame = conveyor_scene(width, height);
+        let mut gray = Image::<Gray>::new(width, height).unwrap();
+        let mut scratch = Image::<Gray>::new(width, height).unwrap();
+        let mut binary = Image::<Gray>::new(width, height).unwrap();
+        let runs = 3;
+        let mut times = [0.0f64; 5];
+
+        for _ in 0..runs {
+            let start = Instant::now();
+            to_grayscale(&frame.view(), &mut gray.view_mut()).unwrap();
+            times[0] += start.elapsed().as_secs_f64();
+
+            let start = Instant::now();
+            convolve3x3(&gray.view(), &mut scratch.view_mut(), &Kernel3::GAUSSIAN).unwrap();
+            times[1] += start.elapsed().as_secs_f64();
+
+            let start = Instant::now();
+            sobel(&gray.view(), &mut scratch.view_mut()).unwrap();
+            times[2] += start.elapsed().as_secs_f64();
+
+            let start = Instant::now();
+            let level = otsu_level(&histogram(&gray.view()));
+            threshold(&gray.view(), &mut binary.view_mut(), level).unwrap();
+            times[3] += start.elapsed().as_secs_f64();
+
+            let start = Instant::now();
+            std::hint::black_box(label_components(&binary.view(), Connectivity::Eight));
+            times[4] += start.elapsed().as_secs_f64();
+        }
+
+        let cells: Vec<String> = times
+            .iter()
+            .map(|t| format!("{:>8.2}ms", t * 1000.0 / runs as f64))
+            .collect();
+        println!(
+            "   {:<14} {}",
+            format!("{} {}x{}", name, width, height),
+            cells.join(" ")
+        );
+    }
+    println!("   (build with --release for numbers comparable to a deployed binary;");
+    println!("    cargo bench measures each stage with criterion)");
+
+    println!("\n=== End of Image Processing Examples ===");
+}
@@ -0,0 +1,84 @@
+// Per-pixel operations: colour conversion, histogram and thresholding
+
+use crate::image::{check_same_size, Gray, ImageError, ImageView, ImageViewMut, Rgb};
+
+// ITU-R BT.601 luma in 8.8 fixed point: 0.299 R + 0.587 G + 0.114 B.
+// The weights sum to 256, so white stays 255 and no float is needed.
+const LUMA_R: u32 = 77;
+const LUMA_G: u32 = 150;
+const LUMA_B: u32 = 29;
+
+pub fn luma([r, g, b]: [u8; 3]) -> u8 {
+    ((LUMA_R * r as u32 + LUMA_G * g as u32 + LUMA_B * b as u32 + 128) >> 8) as u8
+}
+
+pub fn to_grayscale(
+    src: &ImageView<'_, Rgb>,
+    dst: &mut ImageViewMut<'_, Gray>,
+) -> Result<(), ImageError> {
+    check_same_size(src.dimensions(), dst.dimensions())?;
+    for y in 0..src.height() {
+        let out = dst.row_mut(y);
+        for (px, o) in src.row(y).chunks_exact(3).zip(out) {
+            *o = luma([px[0], px[1], px[2]]);
+        }
+    }
+    Ok(())
+}
+
+pub fn histogram(src: &ImageView<'_, Gray>) -> [u32; 256] {
+    let mut hist = [0u32; 256];
+    for row in src.rows() {
+        for &v in row {
+            hist[v as usize] += 1;
+        }
+    }
+    hist
+}
+
+// Pixels brighter than `level` become 255, the rest 0
+pub fn threshold(
+    src: &ImageView<'_, Gray>,
+    dst: &mut ImageViewMut<'_, Gray>,
+    level: u8,
+) -> Result<(), ImageError> {
+    check_same_size(src.dimensions(), dst.dimensions())?;
+    for y in 0..src.height() {
+        for (&s, d) in src.row(y).iter().zip(dst.row_mut(y)) {
+            *d = if s > level { 255 } else { 0 };
+        }
+    }
+    Ok(())
+}
+
+// Otsu's method: the level that maximises the variance between the two
+// classes it creates, i.e. separates a two-peaked histogram best.
+// Returns the last level of the dark class, ready for `threshold`.
+pub fn otsu_level(hist: &[u32; 256]) -> u8 {
+    let total: u64 = hist.iter().map(|&n| n as u64).sum();
+    let sum_all: u64 = hist
+        .iter()
+        .enumerate()
+        .map(|(v, &n)| v as u64 * n as u64)
+        .sum();
+
+    let (mut weight_dark, mut sum_dark) = (0u64, 0u64);
+    let (mut best_level, mut best_variance) = (0u8, 0.0f64);
+    for (level, &count) in hist.iter().enumerate() {
+        weight_dark += count as u64;
+        sum_dark += level as u64 * count as u64;
+        let weight_bright = total - weight_dark;
+        if weight_dark == 0 || weight_bright == 0 {
+            continue;
+        }
+        let mean_dark = sum_dark as f64 / weight_dark as f64;
+        let mean_bright = (sum_all - sum_dark) as f64 / weight_bright as f64;
+        let diff = mean_dark - mean_bright;
+        let variance = weight_dark as f64 * weight_bright as f64 * diff * diff;
+        if variance > best_variance {
+            best_variance = variance;
+            best_level = level as u8;
+        }
+    }
+    best_level
+}
@@ -0,0 +1,64 @@
+// A synthetic camera frame for the demo and the benchmarks: the same
+// parts at the same relative positions at every resolution, with noise
+// from a fixed seed, so every run sees the same pixels
+
+use crate::{Image, Rgb};
+
+// Deterministic pseudo-random noise in [-amplitude, amplitude]
+struct Noise(u64);
+
+impl Noise {
+    fn next(&mut self, amplitude: i32) -> i32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) as i32 % (2 * amplitude + 1) - amplitude
+    }
+}
+
+// A dark conveyor belt carrying three coloured parts and a few specks of
+// dust, scaled to any frame size
+pub fn conveyor_scene(width: usize, height: usize) -> Image<Rgb> {
+    let mut image = Image::<Rgb>::new(width, height).unwrap();
+    let mut view = image.view_mut();
+    let mut noise = Noise(7);
+    let (w, h) = (width as f32, height as f32);
+    let (disc_x, disc_y, disc_r) = (0.5 * w, 0.5 * h, 0.2 * h);
+
+    for y in 0..height {
+        for x in 0..width {
+            let (fx, fy) = (x as f32, y as f32);
+            let n = noise.next(6);
+            let shade = |c: i32| (c + n).clamp(0, 255) as u8;
+
+            // Orange bracket
+            let pixel = if fx > 0.08 * w && fx < 0.30 * w && fy > 0.2 * h && fy < 0.75 * h {
+                [shade(235), shade(130), shade(50)]
+            // Yellow washer
+            } else if (fx - disc_x).powi(2) + ((fy - disc_y) * 2.0).powi(2) < disc_r * disc_r * 4.0
+            {
+                [shade(220), shade(200), shade(40)]
+            // Green L-shaped plate
+            } else if fx > 0.72 * w
+                && fx < 0.92 * w
+                && fy > 0.15 * h
+                && fy < 0.85 * h
+                && (fx < 0.80 * w || fy > 0.65 * h)
+            {
+                [shade(60), shade(180), shade(70)]
+            } else {
+                // Belt, slightly brighter towards the bottom
+                let belt = 45 + (12.0 * fy / h) as i32;
+                [shade(belt - 5), shade(belt), shade(belt + 5)]
+            };
+            view.set(x, y, pixel);
+        }
+    }
+
+    // Dust: isolated bright pixels
+    for &(fx, fy) in &[(0.4, 0.1), (0.62, 0.9), (0.05, 0.9)] {
+        view.set((fx * w) as usize, (fy * h) as usize, [230, 230, 230]);
+    }
+    image
+}
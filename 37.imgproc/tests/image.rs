@@ -0,0 +1,96 @@
+use imgproc::{Gray, Image, ImageError, ImageView, ImageViewMut, Rgb};
+
+#[test]
+fn stride_skips_row_padding() {
+    // width 2, Rgb, stride 8: two padding bytes per row, none after the last
+    let data = [
+        1, 2, 3, 4, 5, 6, 0xEE, 0xEE, //
+        7, 8, 9, 10, 11, 12,
+    ];
+    let view = ImageView::<Rgb>::with_stride(&data, 2, 2, 8).unwrap();
+    assert_eq!(view.row(0), [1, 2, 3, 4, 5, 6]);
+    assert_eq!(view.row(1), [7, 8, 9, 10, 11, 12]);
+    assert_eq!(view.get(1, 1), [10, 11, 12]);
+    assert_eq!(view.rows().count(), 2);
+}
+
+#[test]
+fn layout_errors() {
+    let data = [0u8; 13];
+    assert_eq!(
+        ImageView::<Rgb>::with_stride(&data, 2, 2, 8).unwrap_err(),
+        ImageError::BufferTooSmall {
+            needed: 14,
+            actual: 13
+        }
+    );
+    assert_eq!(
+        ImageView::<Rgb>::with_stride(&data, 2, 2, 5).unwrap_err(),
+        ImageError::StrideTooSmall { stride: 5, min: 6 }
+    );
+    assert_eq!(
+        ImageView::<Gray>::new(&data, 0, 2).unwrap_err(),
+        ImageError::Empty
+    );
+    assert_eq!(
+        ImageView::<Gray>::new(&data, 4, 0).unwrap_err(),
+        ImageError::Empty
+    );
+    assert!(ImageView::<Gray>::new(&data, 13, 1).is_ok());
+    assert!(Image::<Rgb>::from_vec(vec![0; 11], 2, 2).is_err());
+}
+
+#[test]
+fn sub_view_shares_the_buffer() {
+    let data: Vec<u8> = (0..20).collect();
+    let view = ImageView::<Gray>::new(&data, 5, 4).unwrap();
+    let roi = view.sub_view(1, 2, 3, 2).unwrap();
+    assert_eq!(roi.dimensions(), (3, 2));
+    assert_eq!(roi.stride(), 5);
+    assert_eq!(roi.row(0), [11, 12, 13]);
+    assert_eq!(roi.row(1), [16, 17, 18]);
+    assert_eq!(roi.get(2, 1), 18);
+}
+
+#[test]
+fn sub_view_outside_the_image_is_an_error() {
+    let data = [0u8; 20];
+    let view = ImageView::<Gray>::new(&data, 5, 4).unwrap();
+    assert_eq!(
+        view.sub_view(3, 0, 3, 1).unwrap_err(),
+        ImageError::OutOfBounds
+    );
+    assert_eq!(
+        view.sub_view(0, 4, 1, 1).unwrap_err(),
+        ImageError::OutOfBounds
+    );
+}
+
+#[test]
+fn zero_size_sub_view_is_empty_not_a_panic() {
+    let data = [0u8; 20];
+    let view = ImageView::<Gray>::new(&data, 5, 4).unwrap();
+    assert_eq!(view.sub_view(0, 4, 5, 0).unwrap_err(), ImageError::Empty);
+    assert_eq!(view.sub_view(5, 0, 0, 4).unwrap_err(), ImageError::Empty);
+    assert_eq!(view.sub_view(5, 4, 0, 0).unwrap_err(), ImageError::Empty);
+}
+
+#[test]
+fn mutable_view_writes_through_padding() {
+    let mut data = [0xEEu8; 7];
+    {
+        let mut view = ImageViewMut::<Gray>::with_stride(&mut data, 3, 2, 4).unwrap();
+        view.fill(1);
+        view.set(2, 1, 9);
+        assert_eq!(view.as_view().get(2, 1), 9);
+    }
+    assert_eq!(data, [1, 1, 1, 0xEE, 1, 1, 9]);
+}
+
+#[test]
+fn pgm_header() {
+    let image = Image::<Gray>::from_vec(vec![0, 255], 2, 1).unwrap();
+    let mut out = Vec::new();
+    image.write_pgm(&mut out).unwrap();
+    assert_eq!(out, b"P5\n2 1\n255\n\x00\xff");
+}
@@ -0,0 +1,105 @@
+use imgproc::label::{label_components, Connectivity, Rect};
+use imgproc::{Gray, Image};
+
+// '#' is foreground, anything else background
+fn binary(rows: &[&str]) -> Image<Gray> {
+    let data = rows
+        .iter()
+        .flat_map(|r| r.bytes().map(|b| if b == b'#' { 255 } else { 0 }))
+        .collect();
+    Image::from_vec(data, rows[0].len(), rows.len()).unwrap()
+}
+
+#[test]
+fn diagonal_neighbours_depend_on_connectivity() {
+    let image = binary(&["#..", ".#.", "..#"]);
+    let four = label_components(&image.view(), Connectivity::Four);
+    let eight = label_components(&image.view(), Connectivity::Eight);
+
+    assert_eq!(four.len(), 3);
+    assert_eq!(
+        (
+            four.label_at(0, 0),
+            four.label_at(1, 1),
+            four.label_at(2, 2)
+        ),
+        (1, 2, 3)
+    );
+    assert_eq!(eight.len(), 1);
+    assert_eq!(eight.components()[0].area, 3);
+}
+
+#[test]
+fn anti_diagonal_joins_only_with_eight() {
+    let image = binary(&[".#", "#."]);
+    assert_eq!(label_components(&image.view(), Connectivity::Four).len(), 2);
+    assert_eq!(
+        label_components(&image.view(), Connectivity::Eight).len(),
+        1
+    );
+}
+
+#[test]
+fn u_shape_merges_provisional_labels() {
+    // The two arms get different labels in the first pass and only meet
+    // in the bottom row
+    let image = binary(&["#.#", "#.#", "###"]);
+    let labels = label_components(&image.view(), Connectivity::Four);
+
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels.label_at(0, 0), labels.label_at(2, 0));
+    assert_eq!(labels.label_at(1, 0), 0);
+    let c = labels.components()[0];
+    assert_eq!(c.label, 1);
+    assert_eq!(c.area, 7);
+    assert_eq!(
+        c.bbox,
+        Rect {
+            x: 0,
+            y: 0,
+            width: 3,
+            height: 3
+        }
+    );
+    // x: 0+2+0+2+0+1+2 = 7, y: 0+0+1+1+2+2+2 = 8
+    assert_eq!(c.centroid, (1.0, 8.0 / 7.0));
+}
+
+#[test]
+fn components_are_numbered_in_raster_order() {
+    let image = binary(&[
+        "......##", //
+        "##....##", //
+        "##......", //
+        "....#...",
+    ]);
+    let labels = label_components(&image.view(), Connectivity::Eight);
+    assert_eq!(labels.len(), 3);
+
+    let [top_right, left, speck] = labels.components() else {
+        panic!("expected three components");
+    };
+    assert_eq!((top_right.label, top_right.area), (1, 4));
+    assert_eq!(
+        top_right.bbox,
+        Rect {
+            x: 6,
+            y: 0,
+            width: 2,
+            height: 2
+        }
+    );
+    assert_eq!(top_right.centroid, (6.5, 0.5));
+    assert_eq!((left.label, left.area), (2, 4));
+    assert_eq!(left.centroid, (0.5, 1.5));
+    assert_eq!((speck.label, speck.area), (3, 1));
+    assert_eq!(speck.centroid, (4.0, 3.0));
+    assert_eq!(labels.label_at(4, 3), 3);
+}
+
+#[test]
+fn blank_image_has_no_components() {
+    let image = binary(&["....", "...."]);
+    let labels = label_components(&image.view(), Connectivity::Eight);
+    assert!(labels.is_empty());
+}
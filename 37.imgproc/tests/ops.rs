@@ -0,0 +1,132 @@
+use imgproc::filter::{convolve3x3, sobel, Kernel3};
+use imgproc::ops::{histogram, luma, otsu_level, threshold, to_grayscale};
+use imgproc::{Gray, Image, ImageError, Rgb};
+
+fn gray(width: usize, height: usize, pixels: &[u8]) -> Image<Gray> {
+    Image::from_vec(pixels.to_vec(), width, height).unwrap()
+}
+
+fn filtered(src: &Image<Gray>, f: impl Fn(&Image<Gray>, &mut Image<Gray>)) -> Vec<u8> {
+    let mut dst = Image::<Gray>::new(src.width(), src.height()).unwrap();
+    f(src, &mut dst);
+    dst.into_vec()
+}
+
+#[test]
+fn threshold_splits_at_level() {
+    let src = gray(4, 1, &[0, 100, 101, 255]);
+    let out = filtered(&src, |s, d| {
+        threshold(&s.view(), &mut d.view_mut(), 100).unwrap()
+    });
+    assert_eq!(out, [0, 0, 255, 255]);
+}
+
+#[test]
+fn otsu_separates_two_peaks() {
+    let mut pixels = vec![40; 60];
+    pixels.extend([41; 20]);
+    pixels.extend([200; 50]);
+    pixels.extend([210; 30]);
+    let src = gray(16, 10, &pixels);
+
+    let hist = histogram(&src.view());
+    assert_eq!((hist[40], hist[41], hist[200], hist[210]), (60, 20, 50, 30));
+    // Every level from 41 to 199 splits the classes the same way; the
+    // first one is the last level of the dark class
+    assert_eq!(otsu_level(&hist), 41);
+}
+
+#[test]
+fn otsu_of_a_flat_image_is_zero() {
+    let hist = histogram(&gray(4, 4, &[77; 16]).view());
+    assert_eq!(otsu_level(&hist), 0);
+}
+
+#[test]
+fn grayscale_uses_bt601_weights() {
+    assert_eq!(luma([255, 255, 255]), 255);
+    assert_eq!(luma([0, 0, 0]), 0);
+    assert_eq!(luma([255, 0, 0]), 77);
+    assert_eq!(luma([0, 255, 0]), 149);
+    assert_eq!(luma([0, 0, 255]), 29);
+
+    let src = Image::<Rgb>::from_vec(vec![255, 0, 0, 0, 0, 255], 2, 1).unwrap();
+    let mut dst = Image::<Gray>::new(2, 1).unwrap();
+    to_grayscale(&src.view(), &mut dst.view_mut()).unwrap();
+    assert_eq!(dst.as_bytes(), [77, 29]);
+}
+
+#[test]
+fn box_blur_spreads_a_point() {
+    // A 90 in the middle of a 5x5 frame becomes 10 in its 3x3 neighbourhood
+    let mut pixels = [0u8; 25];
+    pixels[12] = 90;
+    let out = filtered(&gray(5, 5, &pixels), |s, d| {
+        convolve3x3(&s.view(), &mut d.view_mut(), &Kernel3::BOX_BLUR).unwrap()
+    });
+    #[rustfmt::skip]
+    let expected = [
+        0, 0, 0, 0, 0,
+        0, 10, 10, 10, 0,
+        0, 10, 10, 10, 0,
+        0, 10, 10, 10, 0,
+        0, 0, 0, 0, 0,
+    ];
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn uniform_image_is_unchanged_by_blur_and_sharpen() {
+    let src = gray(3, 3, &[60; 9]);
+    for kernel in [Kernel3::BOX_BLUR, Kernel3::GAUSSIAN, Kernel3::SHARPEN] {
+        let out = filtered(&src, |s, d| {
+            convolve3x3(&s.view(), &mut d.view_mut(), &kernel).unwrap()
+        });
+        assert_eq!(out, [60; 9]);
+    }
+}
+
+#[test]
+fn sobel_finds_a_vertical_edge() {
+    // Step from 0 to 10 between columns 1 and 2
+    let src = gray(4, 3, &[0, 0, 10, 10, 0, 0, 10, 10, 0, 0, 10, 10]);
+
+    // Gx = (1 + 2 + 1) * 10 on both sides of the step, Gy = 0
+    let edges = filtered(&src, |s, d| sobel(&s.view(), &mut d.view_mut()).unwrap());
+    assert_eq!(edges, [0, 40, 40, 0, 0, 40, 40, 0, 0, 40, 40, 0]);
+
+    let gx = filtered(&src, |s, d| {
+        convolve3x3(&s.view(), &mut d.view_mut(), &Kernel3::SOBEL_X).unwrap()
+    });
+    assert_eq!(gx, edges);
+    let gy = filtered(&src, |s, d| {
+        convolve3x3(&s.view(), &mut d.view_mut(), &Kernel3::SOBEL_Y).unwrap()
+    });
+    assert_eq!(gy, [0; 12]);
+}
+
+#[test]
+fn sobel_saturates_and_convolution_clamps() {
+    let src = gray(2, 2, &[0, 255, 0, 255]);
+    let edges = filtered(&src, |s, d| sobel(&s.view(), &mut d.view_mut()).unwrap());
+    assert_eq!(edges, [255; 4]);
+
+    // A falling edge gives a negative Gx, clamped to 0
+    let falling = gray(2, 2, &[255, 0, 255, 0]);
+    let gx = filtered(&falling, |s, d| {
+        convolve3x3(&s.view(), &mut d.view_mut(), &Kernel3::SOBEL_X).unwrap()
+    });
+    assert_eq!(gx, [0; 4]);
+}
+
+#[test]
+fn size_mismatch_is_an_error() {
+    let src = gray(4, 2, &[0; 8]);
+    let mut dst = Image::<Gray>::new(2, 4).unwrap();
+    let expected = Err(ImageError::SizeMismatch {
+        expected: (4, 2),
+        actual: (2, 4),
+    });
+    assert_eq!(threshold(&src.view(), &mut dst.view_mut(), 1), expected);
+    assert_eq!(sobel(&src.view(), &mut dst.view_mut()), expected);
+}
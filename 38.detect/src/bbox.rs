@@ -0,0 +1,130 @@
+// Axis-aligned bounding boxes and intersection over union
+//
+// Boxes are stored as corners (x_min, y_min, x_max, y_max) in whatever unit
+// the caller uses: pixels, or 0..1 relative to the frame as many models
+// output. Detectors describe boxes in different ways, so there are
+// constructors for the common layouts:
+//
+//   corners   x_min, y_min, x_max, y_max      (Pascal VOC)
+//   xywh      top-left corner, width, height  (COCO)
+//   centre    centre x, centre y, w, h        (YOLO)
+
+use imgproc::label::Rect;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BBox {
+    pub x_min: f32,
+    pub y_min: f32,
+    pub x_max: f32,
+    pub y_max: f32,
+}
+
+impl BBox {
+    // Corners may be given in either order
+    pub fn new(x0: f32, y0: f32, x1: f32, y1: f32) -> BBox {
+        BBox {
+            x_min: x0.min(x1),
+            y_min: y0.min(y1),
+            x_max: x0.max(x1),
+            y_max: y0.max(y1),
+        }
+    }
+
+    pub fn from_xywh(x: f32, y: f32, width: f32, height: f32) -> BBox {
+        BBox::new(x, y, x + width, y + height)
+    }
+
+    pub fn from_center(cx: f32, cy: f32, width: f32, height: f32) -> BBox {
+        BBox::new(
+            cx - width / 2.0,
+            cy - height / 2.0,
+            cx + width / 2.0,
+            cy + height / 2.0,
+        )
+    }
+
+    pub fn width(&self) -> f32 {
+        self.x_max - self.x_min
+    }
+
+    pub fn height(&self) -> f32 {
+        self.y_max - self.y_min
+    }
+
+    pub fn area(&self) -> f32 {
+        self.width() * self.height()
+    }
+
+    pub fn center(&self) -> (f32, f32) {
+        (
+            (self.x_min + self.x_max) / 2.0,
+            (self.y_min + self.y_max) / 2.0,
+        )
+    }
+
+    // The overlapping region, if the boxes overlap with positive area
+    pub fn intersection(&self, other: &BBox) -> Option<BBox> {
+        let x_min = self.x_min.max(other.x_min);
+        let y_min = self.y_min.max(other.y_min);
+        let x_max = self.x_max.min(other.x_max);
+        let y_max = self.y_max.min(other.y_max);
+        if x_max <= x_min || y_max <= y_min {
+            return None;
+        }
+        Some(BBox {
+            x_min,
+            y_min,
+            x_max,
+            y_max,
+        })
+    }
+
+    // Intersection over union: 0 for disjoint boxes, 1 for identical ones
+    pub fn iou(&self, other: &BBox) -> f32 {
+        let inter = match self.intersection(other) {
+            Some(b) => b.area(),
+            None => return 0.0,
+        };
+        let union = self.area() + other.area() - inter;
+        if union <= 0.0 {
+            return 0.0;
+        }
+        inter / union
+    }
+
+    // Scale relative coordinates to pixels (or back, with 1/w and 1/h)
+    pub fn scale(&self, sx: f32, sy: f32) -> BBox {
+        BBox::new(
+            self.x_min * sx,
+            self.y_min * sy,
+            self.x_max * sx,
+            self.y_max * sy,
+        )
+    }
+
+    // Clip to a width x height frame; models happily predict boxes that
+    // stick out of the image
+    pub fn clamp(&self, width: f32, height: f32) -> BBox {
+        BBox {
+            x_min: self.x_min.clamp(0.0, width),
+            y_min: self.y_min.clamp(0.0, height),
+            x_max: self.x_max.clamp(0.0, width),
+            y_max: self.y_max.clamp(0.0, height),
+        }
+    }
+}
+
+// A labelled pixel region covers whole pixels, so its box ends one past
+// the last row and column
+impl From<Rect> for BBox {
+    fn from(r: Rect) -> BBox {
+        BBox::from_xywh(r.x as f32, r.y as f32, r.width as f32, r.height as f32)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Detection {
+    pub bbox: BBox,
+    pub score: f32,
+    pub class_id: usize,
+}
@@ -0,0 +1,7 @@
+// Post-processing for object detectors: boxes, IoU and non-maximum suppression
+
+pub mod bbox;
+pub mod nms;
+
+pub use bbox::{BBox, Detection};
+pub use nms::{decode_rows, filter_by_confidence, nms, postprocess, DecodeError, NmsConfig};
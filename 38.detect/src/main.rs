@@ -0,0 +1,242 @@
+use detect::{decode_rows, filter_by_confidence, nms, postprocess, BBox, Detection, NmsConfig};
+use imgproc::label::{label_components, Connectivity};
+use imgproc::{Gray, ImageView};
+use std::time::Instant;
+
+const CLASSES: [&str; 3] = ["person", "forklift", "pallet"];
+
+// Candidate boxes as a detector might emit them for one 640x400 frame:
+// x_min, y_min, x_max, y_max, score, class
+const CANDIDATES: [(f32, f32, f32, f32, f32, usize); 14] = [
+    (100.0, 80.0, 180.0, 300.0, 0.92, 0),
+    (104.0, 84.0, 186.0, 296.0, 0.88, 0),
+    (96.0, 70.0, 176.0, 290.0, 0.61, 0),
+    (160.0, 90.0, 240.0, 310.0, 0.85, 0),
+    (165.0, 95.0, 238.0, 305.0, 0.40, 0),
+    (300.0, 150.0, 520.0, 330.0, 0.95, 1),
+    (310.0, 160.0, 515.0, 340.0, 0.90, 1),
+    (295.0, 145.0, 530.0, 325.0, 0.30, 1),
+    (305.0, 155.0, 518.0, 335.0, 0.72, 2),
+    (420.0, 260.0, 600.0, 360.0, 0.66, 2),
+    (425.0, 262.0, 598.0, 362.0, 0.64, 2),
+    (20.0, 20.0, 60.0, 60.0, 0.12, 0),
+    (500.0, 40.0, 560.0, 120.0, 0.20, 1),
+    (102.0, 82.0, 182.0, 298.0, 0.92, 0),
+];
+
+fn candidates() -> Vec<Detection> {
+    CANDIDATES
+        .iter()
+        .map(|&(x0, y0, x1, y1, score, class_id)| Detection {
+            bbox: BBox::new(x0, y0, x1, y1),
+            score,
+            class_id,
+        })
+        .collect()
+}
+
+fn print_detection(d: &Detection) {
+    println!(
+        "   {:<8} {:.2}  ({:>5.1}, {:>5.1}) - ({:>5.1}, {:>5.1})",
+        CLASSES[d.class_id], d.score, d.bbox.x_min, d.bbox.y_min, d.bbox.x_max, d.bbox.y_max
+    );
+}
+
+// Deterministic pseudo-random numbers in [0, 1)
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> f32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+fn main() {
+    println!("=== Detection Post-processing Examples ===\n");
+
+    // 1. Box formats
+    println!("1. One box, three layouts:");
+    let corners = BBox::new(40.0, 30.0, 120.0, 90.0);
+    let xywh = BBox::from_xywh(40.0, 30.0, 80.0, 60.0);
+    let center = BBox::from_center(80.0, 60.0, 80.0, 60.0);
+    println!("   corners {:?}", corners);
+    println!(
+        "   xywh == corners: {}, centre == corners: {}",
+        xywh == corners,
+        center == corners
+    );
+    let relative = BBox::from_center(0.5, 0.25, 0.2, 0.1);
+    println!(
+        "   relative {:?}\n   -> pixels in 640x480 {:?}",
+        relative,
+        relative.scale(640.0, 480.0)
+    );
+    let outside = BBox::from_center(630.0, 10.0, 40.0, 40.0);
+    println!("   clamped to the frame: {:?}", outside.clamp(640.0, 480.0));
+
+    // 2. IoU between candidates; tests/bbox.rs compares these with values
+    //    computed independently
+    println!("\n2. IoU between candidates:");
+    let cands = candidates();
+    for (a, b) in [(0, 1), (0, 3), (5, 6), (5, 8), (0, 5), (9, 10)] {
+        println!(
+            "   box {:>2} vs {:>2}: {:.6}",
+            a,
+            b,
+            cands[a].bbox.iou(&cands[b].bbox)
+        );
+    }
+
+    // 3. Confidence filtering
+    println!("\n3. Confidence filtering:");
+    for threshold in [0.1, 0.25, 0.5, 0.9] {
+        let kept = filter_by_confidence(&cands, threshold);
+        println!(
+            "   score >= {:.2}: {:>2} of {} candidates",
+            threshold,
+            kept.len(),
+            cands.len()
+        );
+    }
+
+    // 4. NMS at different thresholds; tests/nms.rs holds the reference
+    //    outputs
+    println!("\n4. NMS:");
+    for (threshold, agnostic) in [(0.45, false), (0.45, true), (0.1, false), (0.9, false)] {
+        println!(
+            "   IoU > {:.2} {:<14} kept {:?}",
+            threshold,
+            if agnostic { "any class" } else { "same class" },
+            nms(&cands, threshold, agnostic)
+        );
+    }
+    println!("   (boxes 0 and 13 tie at 0.92; the earlier one wins every time)");
+
+    // 5. The full pipeline
+    println!("\n5. postprocess with the default config:");
+    let config = NmsConfig::default();
+    println!(
+        "   score >= {}, IoU > {} suppressed, at most {}",
+        config.score_threshold, config.iou_threshold, config.max_detections
+    );
+    for d in postprocess(&cands, &config) {
+        print_detection(&d);
+    }
+    let agnostic = NmsConfig {
+        class_agnostic: true,
+        ..config
+    };
+    println!("   class agnostic: the pallet under the forklift disappears");
+    for d in postprocess(&cands, &agnostic) {
+        print_detection(&d);
+    }
+
+    // 6. Decoding raw model output
+    println!("\n6. Decoding YOLO-style rows (cx, cy, w, h, objectness, 3 classes):");
+    #[rustfmt::skip]
+    let output: [f32; 32] = [
+        0.22, 0.47, 0.13, 0.55, 0.95, 0.97, 0.02, 0.01,
+        0.23, 0.48, 0.12, 0.53, 0.90, 0.95, 0.03, 0.02,
+        0.98, 0.10, 0.10, 0.12, 0.80, 0.10, 0.85, 0.05,
+        0.60, 0.70, 0.30, 0.20, 0.05, 0.30, 0.30, 0.40,
+    ];
+    let decoded = decode_rows(&output, 3).unwrap();
+    for d in postprocess(&decoded, &config) {
+        let pixels = Detection {
+            bbox: d.bbox.scale(640.0, 400.0).clamp(640.0, 400.0),
+            ..d
+        };
+        print_detection(&pixels);
+    }
+    match decode_rows(&output[..30], 3) {
+        Ok(rows) => println!("   truncated output: {} rows", rows.len()),
+        Err(e) => println!("   truncated output: {}", e),
+    }
+
+    // 7. Scoring detections against labelled ground truth
+    println!("\n7. Matching detections to a labelled mask (IoU >= 0.5):");
+    // 16x8 mask with two objects, as the image processing lesson produces
+    #[rustfmt::skip]
+    let mask: [u8; 128] = [
+        0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,
+        0,1,1,1,1,0,0,0,0,0,0,0,0,0,0,0,
+        0,1,1,1,1,0,0,0,0,0,1,1,1,1,1,0,
+        0,1,1,1,1,0,0,0,0,0,1,1,1,1,1,0,
+        0,1,1,1,1,0,0,0,0,0,1,1,1,1,1,0,
+        0,0,0,0,0,0,0,0,0,0,1,1,1,1,1,0,
+        0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,
+        0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,
+    ];
+    let mask = ImageView::<Gray>::new(&mask, 16, 8).unwrap();
+    let truth: Vec<BBox> = label_components(&mask, Connectivity::Eight)
+        .components()
+        .iter()
+        .map(|c| BBox::from(c.bbox))
+        .collect();
+    let predicted = [
+        BBox::new(1.0, 1.0, 5.0, 5.0),
+        BBox::new(11.0, 3.0, 15.0, 7.0),
+        BBox::new(6.0, 5.0, 9.0, 8.0),
+    ];
+    let mut matched = vec![false; truth.len()];
+    let mut true_positives = 0;
+    for p in &predicted {
+        let best = truth
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !matched[*i])
+            .map(|(i, t)| (i, p.iou(t)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        match best {
+            Some((i, iou)) if iou >= 0.5 => {
+                matched[i] = true;
+                true_positives += 1;
+                println!("   {:?} matches object {} (IoU {:.2})", p, i + 1, iou);
+            }
+            _ => println!("   {:?} is a false positive", p),
+        }
+    }
+    println!(
+        "   precision {:.2}, recall {:.2}",
+        true_positives as f32 / predicted.len() as f32,
+        true_positives as f32 / truth.len() as f32
+    );
+
+    // 8. Cost of NMS
+    println!("\n8. NMS time for random candidates:");
+    let mut rng = Rng(3);
+    for n in [100, 1000, 5000] {
+        let random: Vec<Detection> = (0..n)
+            .map(|_| Detection {
+                bbox: BBox::from_center(
+                    rng.next() * 640.0,
+                    rng.next() * 480.0,
+                    20.0 + rng.next() * 100.0,
+                    20.0 + rng.next() * 100.0,
+                ),
+                score: rng.next(),
+                class_id: (rng.next() * 3.0) as usize,
+            })
+            .collect();
+        let start = Instant::now();
+        let kept = nms(&random, 0.45, false);
+        let nms_time = start.elapsed();
+        let start = Instant::now();
+        let filtered = postprocess(&random, &NmsConfig::default());
+        println!(
+            "   {:>5} candidates: nms {:>10.2?} keeps {:>3}; filter first {:>10.2?} keeps {:>3}",
+            n,
+            nms_time,
+            kept.len(),
+            start.elapsed(),
+            filtered.len()
+        );
+    }
+    println!("   (NMS is O(n²) in the candidates: filter by confidence first)");
+
+    println!("\n=== End of Detection Post-processing Examples ===");
+}
@@ -0,0 +1,148 @@
+// From raw model output to a short list of detections
+//
+// A detector does not output "one box per object". It scores thousands of
+// candidate boxes, and a real object typically lights up several
+// neighbouring candidates. Post-processing:
+//
+//   1. decode   raw tensor rows -> boxes with a class and a score
+//   2. filter   drop candidates below a confidence threshold
+//   3. NMS      greedy non-maximum suppression: take the best remaining box,
+//               discard every other box of the same class that overlaps it
+//               by more than the IoU threshold, repeat
+
+use crate::bbox::{BBox, Detection};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NmsConfig {
+    pub score_threshold: f32,
+    pub iou_threshold: f32,
+    pub max_detections: usize,
+    // Suppress across classes, e.g. when one object gets two labels
+    pub class_agnostic: bool,
+}
+
+impl Default for NmsConfig {
+    fn default() -> NmsConfig {
+        NmsConfig {
+            score_threshold: 0.25,
+            iou_threshold: 0.45,
+            max_detections: 100,
+            class_agnostic: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    // The output length is not a whole number of rows
+    BadLength { len: usize, row: usize },
+    NoClasses,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::BadLength { len, row } => {
+                write!(
+                    f,
+                    "{} values is not a multiple of the row size {}",
+                    len, row
+                )
+            }
+            DecodeError::NoClasses => write!(f, "model must have at least one class"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+// YOLO-style rows: cx, cy, w, h, objectness, then one score per class.
+// The candidate's score is objectness x its best class score.
+pub fn decode_rows(output: &[f32], num_classes: usize) -> Result<Vec<Detection>, DecodeError> {
+    if num_classes == 0 {
+        return Err(DecodeError::NoClasses);
+    }
+    let row = 5 + num_classes;
+    if !output.len().is_multiple_of(row) {
+        return Err(DecodeError::BadLength {
+            len: output.len(),
+            row,
+        });
+    }
+
+    Ok(output
+        .chunks_exact(row)
+        .map(|r| {
+            let (class_id, class_score) =
+                r[5..]
+                    .iter()
+                    .enumerate()
+                    .fold(
+                        (0, f32::MIN),
+                        |best, (i, &s)| {
+                            if s > best.1 {
+                                (i, s)
+                            } else {
+                                best
+                            }
+                        },
+                    );
+            Detection {
+                bbox: BBox::from_center(r[0], r[1], r[2], r[3]),
+                score: r[4] * class_score,
+                class_id,
+            }
+        })
+        .collect())
+}
+
+pub fn filter_by_confidence(candidates: &[Detection], min_score: f32) -> Vec<Detection> {
+    candidates
+        .iter()
+        .filter(|d| d.score >= min_score)
+        .copied()
+        .collect()
+}
+
+// Greedy NMS; returns indices into `candidates` of the kept boxes, best
+// first. Equal scores keep input order so results are reproducible.
+pub fn nms(candidates: &[Detection], iou_threshold: f32, class_agnostic: bool) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    order.sort_by(|&a, &b| {
+        candidates[b]
+            .score
+            .total_cmp(&candidates[a].score)
+            .then(a.cmp(&b))
+    });
+
+    let mut suppressed = vec![false; candidates.len()];
+    let mut keep = Vec::new();
+    for (pos, &i) in order.iter().enumerate() {
+        if suppressed[i] {
+            continue;
+        }
+        keep.push(i);
+        let best = &candidates[i];
+        for &j in &order[pos + 1..] {
+            let other = &candidates[j];
+            if suppressed[j] || (!class_agnostic && other.class_id != best.class_id) {
+                continue;
+            }
+            if best.bbox.iou(&other.bbox) > iou_threshold {
+                suppressed[j] = true;
+            }
+        }
+    }
+    keep
+}
+
+// Filter, suppress and cap; the result is sorted by descending score
+pub fn postprocess(candidates: &[Detection], config: &NmsConfig) -> Vec<Detection> {
+    let confident = filter_by_confidence(candidates, config.score_threshold);
+    nms(&confident, config.iou_threshold, config.class_agnostic)
+        .into_iter()
+        .take(config.max_detections)
+        .map(|i| confident[i])
+        .collect()
+}
@@ -0,0 +1,99 @@
+use detect::BBox;
+use imgproc::label::Rect;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-5
+}
+
+#[test]
+fn the_three_layouts_describe_the_same_box() {
+    let corners = BBox::new(40.0, 30.0, 120.0, 90.0);
+    assert_eq!(BBox::from_xywh(40.0, 30.0, 80.0, 60.0), corners);
+    assert_eq!(BBox::from_center(80.0, 60.0, 80.0, 60.0), corners);
+    // Corners in either order
+    assert_eq!(BBox::new(120.0, 90.0, 40.0, 30.0), corners);
+    assert_eq!(corners.width(), 80.0);
+    assert_eq!(corners.height(), 60.0);
+    assert_eq!(corners.area(), 4800.0);
+    assert_eq!(corners.center(), (80.0, 60.0));
+}
+
+#[test]
+fn iou_of_simple_layouts() {
+    let a = BBox::new(0.0, 0.0, 2.0, 2.0);
+    assert_eq!(a.iou(&a), 1.0);
+    // Half overlap: 2 / (4 + 4 - 2)
+    assert!(close(a.iou(&BBox::new(1.0, 0.0, 3.0, 2.0)), 1.0 / 3.0));
+    // One inside the other: 1 / 4
+    assert!(close(a.iou(&BBox::new(0.5, 0.5, 1.5, 1.5)), 0.25));
+    // Touching edges and disjoint boxes do not overlap
+    assert_eq!(a.iou(&BBox::new(2.0, 0.0, 4.0, 2.0)), 0.0);
+    assert_eq!(a.iou(&BBox::new(5.0, 5.0, 6.0, 6.0)), 0.0);
+    assert_eq!(a.intersection(&BBox::new(2.0, 0.0, 4.0, 2.0)), None);
+    // Degenerate boxes have no area to share
+    let line = BBox::new(1.0, 0.0, 1.0, 2.0);
+    assert_eq!(line.iou(&line), 0.0);
+}
+
+#[test]
+fn iou_matches_reference_values() {
+    // Computed with an independent Python implementation
+    let boxes = [
+        BBox::new(100.0, 80.0, 180.0, 300.0),
+        BBox::new(104.0, 84.0, 186.0, 296.0),
+        BBox::new(160.0, 90.0, 240.0, 310.0),
+        BBox::new(300.0, 150.0, 520.0, 330.0),
+        BBox::new(310.0, 160.0, 515.0, 340.0),
+        BBox::new(305.0, 155.0, 518.0, 335.0),
+        BBox::new(420.0, 260.0, 600.0, 360.0),
+        BBox::new(425.0, 262.0, 598.0, 362.0),
+    ];
+    let cases = [
+        (0, 1, 0.853752),
+        (0, 2, 0.135484),
+        (3, 4, 0.836735),
+        (3, 5, 0.916636),
+        (0, 3, 0.0),
+        (6, 7, 0.924125),
+    ];
+    for (a, b, expected) in cases {
+        let iou = boxes[a].iou(&boxes[b]);
+        assert!(close(iou, expected), "{} vs {}: {}", a, b, iou);
+        // Symmetric
+        assert_eq!(iou, boxes[b].iou(&boxes[a]));
+    }
+}
+
+#[test]
+fn intersection_is_the_overlap() {
+    let a = BBox::new(0.0, 0.0, 10.0, 10.0);
+    let b = BBox::new(5.0, -5.0, 15.0, 5.0);
+    assert_eq!(a.intersection(&b), Some(BBox::new(5.0, 0.0, 10.0, 5.0)));
+}
+
+#[test]
+fn scale_and_clamp() {
+    let relative = BBox::from_center(0.5, 0.25, 0.2, 0.1);
+    let pixels = relative.scale(640.0, 480.0);
+    assert!(close(pixels.x_min, 256.0) && close(pixels.x_max, 384.0));
+    assert!(close(pixels.y_min, 96.0) && close(pixels.y_max, 144.0));
+    let back = pixels.scale(1.0 / 640.0, 1.0 / 480.0);
+    assert!(close(back.x_min, relative.x_min) && close(back.y_max, relative.y_max));
+
+    let outside = BBox::from_center(630.0, 10.0, 40.0, 40.0);
+    assert_eq!(
+        outside.clamp(640.0, 480.0),
+        BBox::new(610.0, 0.0, 640.0, 30.0)
+    );
+}
+
+#[test]
+fn labelled_rects_cover_whole_pixels() {
+    let rect = Rect {
+        x: 1,
+        y: 2,
+        width: 4,
+        height: 3,
+    };
+    assert_eq!(BBox::from(rect), BBox::new(1.0, 2.0, 5.0, 5.0));
+}
@@ -0,0 +1,200 @@
+use detect::{
+    decode_rows, filter_by_confidence, nms, postprocess, BBox, DecodeError, Detection, NmsConfig,
+};
+
+// The demo's frame: x_min, y_min, x_max, y_max, score, class
+const CANDIDATES: [(f32, f32, f32, f32, f32, usize); 14] = [
+    (100.0, 80.0, 180.0, 300.0, 0.92, 0),
+    (104.0, 84.0, 186.0, 296.0, 0.88, 0),
+    (96.0, 70.0, 176.0, 290.0, 0.61, 0),
+    (160.0, 90.0, 240.0, 310.0, 0.85, 0),
+    (165.0, 95.0, 238.0, 305.0, 0.40, 0),
+    (300.0, 150.0, 520.0, 330.0, 0.95, 1),
+    (310.0, 160.0, 515.0, 340.0, 0.90, 1),
+    (295.0, 145.0, 530.0, 325.0, 0.30, 1),
+    (305.0, 155.0, 518.0, 335.0, 0.72, 2),
+    (420.0, 260.0, 600.0, 360.0, 0.66, 2),
+    (425.0, 262.0, 598.0, 362.0, 0.64, 2),
+    (20.0, 20.0, 60.0, 60.0, 0.12, 0),
+    (500.0, 40.0, 560.0, 120.0, 0.20, 1),
+    (102.0, 82.0, 182.0, 298.0, 0.92, 0),
+];
+
+fn candidates() -> Vec<Detection> {
+    CANDIDATES
+        .iter()
+        .map(|&(x0, y0, x1, y1, score, class_id)| Detection {
+            bbox: BBox::new(x0, y0, x1, y1),
+            score,
+            class_id,
+        })
+        .collect()
+}
+
+#[test]
+fn nms_matches_reference_outputs() {
+    // Computed with an independent Python implementation
+    let cases: [(f32, bool, &[usize]); 4] = [
+        (0.45, false, &[5, 0, 3, 8, 9, 12, 11]),
+        (0.45, true, &[5, 0, 3, 9, 12, 11]),
+        (0.1, false, &[5, 0, 8, 12, 11]),
+        (0.9, false, &[5, 0, 6, 1, 3, 8, 9, 2, 4, 7, 12, 11]),
+    ];
+    let cands = candidates();
+    for (threshold, agnostic, expected) in cases {
+        assert_eq!(
+            nms(&cands, threshold, agnostic),
+            expected,
+            "IoU > {} agnostic {}",
+            threshold,
+            agnostic
+        );
+    }
+}
+
+#[test]
+fn equal_scores_keep_input_order() {
+    // Boxes 0 and 13 tie at 0.92 and overlap; the earlier one wins
+    let cands = candidates();
+    let kept = nms(&cands, 0.45, false);
+    assert!(kept.contains(&0) && !kept.contains(&13));
+
+    let mut swapped = cands.clone();
+    swapped.swap(0, 13);
+    let kept = nms(&swapped, 0.45, false);
+    assert!(kept.contains(&0) && !kept.contains(&13));
+    assert_eq!(swapped[0].bbox, BBox::new(102.0, 82.0, 182.0, 298.0));
+}
+
+#[test]
+fn nms_of_nothing_and_of_one() {
+    assert!(nms(&[], 0.5, false).is_empty());
+    assert_eq!(nms(&candidates()[..1], 0.5, false), [0]);
+}
+
+#[test]
+fn confidence_filter_keeps_order_and_inclusive_threshold() {
+    let cands = candidates();
+    let counts: Vec<usize> = [0.1, 0.25, 0.5, 0.92, 0.96]
+        .iter()
+        .map(|&t| filter_by_confidence(&cands, t).len())
+        .collect();
+    assert_eq!(counts, [14, 12, 10, 3, 0]);
+    let kept = filter_by_confidence(&cands, 0.92);
+    assert_eq!(kept, [cands[0], cands[5], cands[13]]);
+}
+
+#[test]
+fn postprocess_filters_suppresses_and_caps() {
+    let cands = candidates();
+    let config = NmsConfig::default();
+    let out = postprocess(&cands, &config);
+    let expected: Vec<Detection> = [5, 0, 3, 8, 9].iter().map(|&i| cands[i]).collect();
+    assert_eq!(out, expected);
+    assert!(out.windows(2).all(|w| w[0].score >= w[1].score));
+
+    // The pallet under the forklift goes when classes are ignored
+    let agnostic = postprocess(
+        &cands,
+        &NmsConfig {
+            class_agnostic: true,
+            ..config
+        },
+    );
+    assert_eq!(agnostic.len(), 4);
+    assert!(!agnostic.contains(&cands[8]));
+
+    let capped = postprocess(
+        &cands,
+        &NmsConfig {
+            max_detections: 2,
+            ..config
+        },
+    );
+    assert_eq!(capped, [cands[5], cands[0]]);
+}
+
+#[test]
+fn decode_picks_the_best_class_and_scales_by_objectness() {
+    #[rustfmt::skip]
+    let output = [
+        0.5, 0.5, 0.2, 0.4, 0.9, 0.1, 0.8, 0.1,
+        0.1, 0.2, 0.1, 0.1, 0.5, 0.6, 0.2, 0.7,
+    ];
+    let decoded = decode_rows(&output, 3).unwrap();
+    assert_eq!(decoded.len(), 2);
+    assert_eq!(decoded[0].class_id, 1);
+    assert!((decoded[0].score - 0.72).abs() < 1e-6);
+    assert_eq!(decoded[0].bbox, BBox::from_center(0.5, 0.5, 0.2, 0.4));
+    assert_eq!(decoded[1].class_id, 2);
+    assert!((decoded[1].score - 0.35).abs() < 1e-6);
+}
+
+#[test]
+fn decode_rejects_bad_shapes() {
+    assert_eq!(
+        decode_rows(&[0.0; 30], 3),
+        Err(DecodeError::BadLength { len: 30, row: 8 })
+    );
+    assert_eq!(decode_rows(&[0.0; 10], 0), Err(DecodeError::NoClasses));
+    assert_eq!(decode_rows(&[], 3), Ok(vec![]));
+}
+
+// Deterministic pseudo-random numbers in [0, 1)
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> f32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+#[test]
+fn random_candidates_satisfy_the_nms_invariants() {
+    let mut rng = Rng(7);
+    for round in 0..20 {
+        let threshold = 0.2 + 0.05 * round as f32;
+        let agnostic = round % 2 == 1;
+        let cands: Vec<Detection> = (0..200)
+            .map(|_| Detection {
+                bbox: BBox::from_center(
+                    rng.next() * 320.0,
+                    rng.next() * 240.0,
+                    10.0 + rng.next() * 60.0,
+                    10.0 + rng.next() * 60.0,
+                ),
+                score: rng.next(),
+                class_id: (rng.next() * 3.0) as usize,
+            })
+            .collect();
+        let same_class = |a: &Detection, b: &Detection| agnostic || a.class_id == b.class_id;
+        let kept = nms(&cands, threshold, agnostic);
+
+        // Best first, and no two kept boxes of a class overlap too much
+        assert!(kept
+            .windows(2)
+            .all(|w| cands[w[0]].score >= cands[w[1]].score));
+        for (n, &i) in kept.iter().enumerate() {
+            for &j in &kept[n + 1..] {
+                if same_class(&cands[i], &cands[j]) {
+                    assert!(cands[i].bbox.iou(&cands[j].bbox) <= threshold);
+                }
+            }
+        }
+        // Every dropped box was suppressed by a kept box at least as good
+        for (j, other) in cands.iter().enumerate() {
+            if kept.contains(&j) {
+                continue;
+            }
+            assert!(kept.iter().any(|&i| {
+                same_class(&cands[i], other)
+                    && cands[i].score >= other.score
+                    && cands[i].bbox.iou(&other.bbox) > threshold
+            }));
+        }
+    }
+}
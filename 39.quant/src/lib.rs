@@ -0,0 +1,267 @@
+// Affine int8 quantization for edge models
+//
+// A real value x is stored as an 8-bit integer q with a scale and a zero
+// point:
+//
+//   q = clamp(round(x / scale) + zero_point, -128, 127)
+//   x ≈ (q - zero_point) * scale
+//
+// The scale spreads the observed range [min, max] over the 256 codes; the
+// zero point is the code that represents 0.0 exactly, so zero padding and
+// ReLU outputs carry no rounding error.
+//
+//   asymmetric   range [min, max], any zero point    (activations)
+//   symmetric    range [-m, m], zero point 0         (weights)
+//
+// Weights are often quantized per channel: every output row of a layer gets
+// its own scale, so one row with large weights does not wash out the
+// resolution of all the others.
+
+use std::fmt;
+
+pub const Q_MIN: i32 = i8::MIN as i32;
+pub const Q_MAX: i32 = i8::MAX as i32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuantParams {
+    pub scale: f32,
+    pub zero_point: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    Asymmetric,
+    Symmetric,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity {
+    PerTensor,
+    // One set of parameters per row of a row-major [channels x n] tensor
+    PerChannel { channels: usize },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuantError {
+    Empty,
+    NotFinite(f32),
+    // The tensor does not split into whole channels
+    ShapeMismatch { len: usize, channels: usize },
+    LengthMismatch { expected: usize, actual: usize },
+}
+
+impl fmt::Display for QuantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuantError::Empty => write!(f, "cannot quantize an empty tensor"),
+            QuantError::NotFinite(x) => write!(f, "value {} is not finite", x),
+            QuantError::ShapeMismatch { len, channels } => {
+                write!(f, "{} values do not split into {} channels", len, channels)
+            }
+            QuantError::LengthMismatch { expected, actual } => {
+                write!(f, "expected {} values, got {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl std::error::Error for QuantError {}
+
+impl QuantParams {
+    // Asymmetric parameters covering [min, max]. The range is widened to
+    // include 0 so that zero stays exact.
+    pub fn from_min_max(min: f32, max: f32) -> QuantParams {
+        let (min, max) = (min.min(0.0), max.max(0.0));
+        if max == min {
+            return QuantParams {
+                scale: 1.0,
+                zero_point: 0,
+            };
+        }
+        let scale = (max - min) / (Q_MAX - Q_MIN) as f32;
+        let zero_point = (Q_MIN as f32 - min / scale).round() as i32;
+        QuantParams {
+            scale,
+            zero_point: zero_point.clamp(Q_MIN, Q_MAX),
+        }
+    }
+
+    // Symmetric parameters covering [-max_abs, max_abs]; -128 is unused so
+    // the range is balanced
+    pub fn symmetric(max_abs: f32) -> QuantParams {
+        let scale = if max_abs > 0.0 {
+            max_abs / Q_MAX as f32
+        } else {
+            1.0
+        };
+        QuantParams {
+            scale,
+            zero_point: 0,
+        }
+    }
+
+    pub fn for_values(values: &[f32], scheme: Scheme) -> Result<QuantParams, QuantError> {
+        if values.is_empty() {
+            return Err(QuantError::Empty);
+        }
+        let (mut min, mut max) = (f32::INFINITY, f32::NEG_INFINITY);
+        for &x in values {
+            if !x.is_finite() {
+                return Err(QuantError::NotFinite(x));
+            }
+            min = min.min(x);
+            max = max.max(x);
+        }
+        Ok(match scheme {
+            Scheme::Asymmetric => QuantParams::from_min_max(min, max),
+            Scheme::Symmetric => QuantParams::symmetric(min.abs().max(max.abs())),
+        })
+    }
+
+    pub fn quantize(&self, x: f32) -> i8 {
+        ((x / self.scale).round() as i32 + self.zero_point).clamp(Q_MIN, Q_MAX) as i8
+    }
+
+    pub fn dequantize(&self, q: i8) -> f32 {
+        (q as i32 - self.zero_point) as f32 * self.scale
+    }
+
+    // The real-valued range the 256 codes cover
+    pub fn range(&self) -> (f32, f32) {
+        (self.dequantize(i8::MIN), self.dequantize(i8::MAX))
+    }
+}
+
+pub fn quantize(values: &[f32], params: &QuantParams) -> Vec<i8> {
+    values.iter().map(|&x| params.quantize(x)).collect()
+}
+
+pub fn dequantize(values: &[i8], params: &QuantParams) -> Vec<f32> {
+    values.iter().map(|&q| params.dequantize(q)).collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantizedTensor {
+    pub data: Vec<i8>,
+    // One entry per tensor, or one per channel
+    pub params: Vec<QuantParams>,
+}
+
+impl QuantizedTensor {
+    pub fn quantize(
+        values: &[f32],
+        scheme: Scheme,
+        granularity: Granularity,
+    ) -> Result<QuantizedTensor, QuantError> {
+        if values.is_empty() {
+            return Err(QuantError::Empty);
+        }
+        let channels = match granularity {
+            Granularity::PerTensor => 1,
+            Granularity::PerChannel { channels } => channels,
+        };
+        if channels == 0 || !values.len().is_multiple_of(channels) {
+            return Err(QuantError::ShapeMismatch {
+                len: values.len(),
+                channels,
+            });
+        }
+
+        let mut data = Vec::with_capacity(values.len());
+        let mut params = Vec::with_capacity(channels);
+        for chunk in values.chunks_exact(values.len() / channels) {
+            let p = QuantParams::for_values(chunk, scheme)?;
+            data.extend(chunk.iter().map(|&x| p.quantize(x)));
+            params.push(p);
+        }
+        Ok(QuantizedTensor { data, params })
+    }
+
+    pub fn channels(&self) -> usize {
+        self.params.len()
+    }
+
+    pub fn channel_len(&self) -> usize {
+        self.data.len() / self.params.len()
+    }
+
+    pub fn dequantize(&self) -> Vec<f32> {
+        self.data
+            .chunks_exact(self.channel_len())
+            .zip(&self.params)
+            .flat_map(|(chunk, p)| chunk.iter().map(move |&q| p.dequantize(q)))
+            .collect()
+    }
+
+    // Bytes for the data plus one (f32, i32) pair per channel
+    pub fn size_bytes(&self) -> usize {
+        self.data.len() + self.params.len() * 8
+    }
+
+    // y = W x for weights of shape [outputs x inputs], quantized per tensor
+    // or per output channel, and a quantized input vector. The products are
+    // accumulated in i32, as an int8 accelerator would, and scaled back to
+    // real values once per output.
+    pub fn matvec(&self, input: &[i8], input_params: &QuantParams) -> Result<Vec<f32>, QuantError> {
+        let row_len = input.len();
+        if row_len == 0 || !self.data.len().is_multiple_of(row_len) {
+            return Err(QuantError::LengthMismatch {
+                expected: self.channel_len(),
+                actual: row_len,
+            });
+        }
+        let outputs = self.data.len() / row_len;
+        if self.params.len() != 1 && self.params.len() != outputs {
+            return Err(QuantError::ShapeMismatch {
+                len: self.data.len(),
+                channels: self.params.len(),
+            });
+        }
+
+        Ok(self
+            .data
+            .chunks_exact(row_len)
+            .enumerate()
+            .map(|(row, weights)| {
+                let p = self.params[row.min(self.params.len() - 1)];
+                let acc: i32 = weights
+                    .iter()
+                    .zip(input)
+                    .map(|(&w, &x)| {
+                        (w as i32 - p.zero_point) * (x as i32 - input_params.zero_point)
+                    })
+                    .sum();
+                acc as f32 * p.scale * input_params.scale
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ErrorMetrics {
+    pub mse: f32,
+    pub max_abs: f32,
+    // Signal-to-quantization-noise ratio; every extra bit adds about 6 dB
+    pub sqnr_db: f32,
+}
+
+pub fn error_metrics(original: &[f32], reconstructed: &[f32]) -> ErrorMetrics {
+    let (mut signal, mut noise, mut max_abs) = (0.0f64, 0.0f64, 0.0f32);
+    for (&x, &y) in original.iter().zip(reconstructed) {
+        let e = x - y;
+        signal += (x as f64).powi(2);
+        noise += (e as f64).powi(2);
+        max_abs = max_abs.max(e.abs());
+    }
+    let n = original.len().max(1) as f64;
+    let sqnr_db = if noise > 0.0 {
+        10.0 * (signal / noise).log10()
+    } else {
+        f64::INFINITY
+    };
+    ErrorMetrics {
+        mse: (noise / n) as f32,
+        max_abs,
+        sqnr_db: sqnr_db as f32,
+    }
+}
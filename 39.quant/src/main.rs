@@ -0,0 +1,306 @@
+use quant::{
+    dequantize, error_metrics, quantize, Granularity, QuantParams, QuantizedTensor, Scheme,
+};
+use tensor::Matrix;
+
+// Deterministic pseudo-random numbers in [0, 1)
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> f32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    // Roughly normal, mean 0, standard deviation 1
+    fn normal(&mut self) -> f32 {
+        (0..12).map(|_| self.next()).sum::<f32>() - 6.0
+    }
+}
+
+const INPUTS: usize = 6;
+const HIDDEN: usize = 16;
+const CLASSES: usize = 4;
+
+// A small two-layer classifier (vibration features -> machine state).
+// Rows of the first layer differ in magnitude by 40x, as trained layers
+// often do, which is what per-channel quantization is for.
+struct Mlp {
+    w1: Matrix<f32>,
+    b1: Vec<f32>,
+    w2: Matrix<f32>,
+    b2: Vec<f32>,
+}
+
+impl Mlp {
+    fn new(rng: &mut Rng) -> Mlp {
+        let w1 = Matrix::from_fn(HIDDEN, INPUTS, |row, _| {
+            rng.normal() * 0.05 * 40f32.powf(row as f32 / (HIDDEN - 1) as f32)
+        });
+        let b1 = (0..HIDDEN).map(|_| rng.normal() * 0.1).collect();
+        let w2 = Matrix::from_fn(CLASSES, HIDDEN, |_, _| rng.normal() * 0.5);
+        let b2 = (0..CLASSES).map(|_| rng.normal() * 0.1).collect();
+        Mlp { w1, b1, w2, b2 }
+    }
+
+    fn hidden(&self, x: &[f32]) -> Vec<f32> {
+        let y = self.w1.matvec(x).unwrap();
+        y.iter()
+            .zip(&self.b1)
+            .map(|(y, b)| (y + b).max(0.0))
+            .collect()
+    }
+
+    fn forward(&self, x: &[f32]) -> Vec<f32> {
+        let y = self.w2.matvec(&self.hidden(x)).unwrap();
+        y.iter().zip(&self.b2).map(|(y, b)| y + b).collect()
+    }
+}
+
+// The same network with int8 weights and activations
+struct QuantizedMlp<'a> {
+    float: &'a Mlp,
+    w1: QuantizedTensor,
+    w2: QuantizedTensor,
+    input: QuantParams,
+    hidden: QuantParams,
+}
+
+impl QuantizedMlp<'_> {
+    fn forward(&self, x: &[f32]) -> Vec<f32> {
+        let xq = quantize(x, &self.input);
+        let h: Vec<f32> = self
+            .w1
+            .matvec(&xq, &self.input)
+            .unwrap()
+            .iter()
+            .zip(&self.float.b1)
+            .map(|(y, b)| (y + b).max(0.0))
+            .collect();
+        let hq = quantize(&h, &self.hidden);
+        self.w2
+            .matvec(&hq, &self.hidden)
+            .unwrap()
+            .iter()
+            .zip(&self.float.b2)
+            .map(|(y, b)| y + b)
+            .collect()
+    }
+}
+
+fn argmax(v: &[f32]) -> usize {
+    v.iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(i, _)| i)
+        .unwrap()
+}
+
+fn sample(rng: &mut Rng) -> Vec<f32> {
+    // RMS, peak, crest factor, kurtosis, band energies: differently scaled
+    let scales = [2.0, 6.0, 3.0, 8.0, 1.0, 0.5];
+    scales.iter().map(|s| rng.next() * s).collect()
+}
+
+fn main() {
+    println!("=== Int8 Quantization Examples ===\n");
+
+    // 1. Parameters from an observed range
+    println!("1. Asymmetric parameters for the range [-1.0, 3.0]:");
+    let p = QuantParams::from_min_max(-1.0, 3.0);
+    println!("   scale {:.6}, zero point {}", p.scale, p.zero_point);
+    let (lo, hi) = p.range();
+    println!("   codes -128..127 cover [{:.4}, {:.4}]", lo, hi);
+    println!("   {:>8} {:>6} {:>10} {:>10}", "x", "q", "x'", "error");
+    for x in [-1.0, -0.3, 0.0, 0.01, 1.234, 3.0, 5.0] {
+        let q = p.quantize(x);
+        let back = p.dequantize(q);
+        println!(
+            "   {:>8.3} {:>6} {:>10.5} {:>10.5}{}",
+            x,
+            q,
+            back,
+            x - back,
+            if (x - back).abs() > p.scale {
+                "  <- saturated"
+            } else {
+                ""
+            }
+        );
+    }
+    println!(
+        "   0.0 -> {} -> {}",
+        p.quantize(0.0),
+        p.dequantize(p.quantize(0.0))
+    );
+
+    // 2. Symmetric vs asymmetric on ReLU activations
+    println!("\n2. ReLU activations in [0, 6], 10000 values:");
+    let mut rng = Rng(11);
+    let activations: Vec<f32> = (0..10_000).map(|_| rng.next() * 6.0).collect();
+    for scheme in [Scheme::Asymmetric, Scheme::Symmetric] {
+        let p = QuantParams::for_values(&activations, scheme).unwrap();
+        let back = dequantize(&quantize(&activations, &p), &p);
+        let m = error_metrics(&activations, &back);
+        println!(
+            "   {:<10} scale {:.5} zero point {:>4}  SQNR {:>5.1} dB  max error {:.4}",
+            format!("{:?}", scheme),
+            p.scale,
+            p.zero_point,
+            m.sqnr_db,
+            m.max_abs
+        );
+    }
+    println!("   symmetric wastes the negative half of the codes: one bit, ~6 dB");
+
+    // 3. Per-tensor vs per-channel weights
+    println!(
+        "\n3. Weights {}x{} with row gains from 0.05 to 2.0:",
+        HIDDEN, INPUTS
+    );
+    let model = Mlp::new(&mut Rng(5));
+    let smallest_row = model.w1.row(0);
+    for granularity in [
+        Granularity::PerTensor,
+        Granularity::PerChannel { channels: HIDDEN },
+    ] {
+        let q =
+            QuantizedTensor::quantize(model.w1.as_slice(), Scheme::Symmetric, granularity).unwrap();
+        let back = q.dequantize();
+        let all = error_metrics(model.w1.as_slice(), &back);
+        let small = error_metrics(smallest_row, &back[..INPUTS]);
+        println!(
+            "   {:<12} SQNR {:>5.1} dB overall, {:>5.1} dB on the smallest row",
+            if q.channels() == 1 {
+                "per tensor"
+            } else {
+                "per channel"
+            },
+            all.sqnr_db,
+            small.sqnr_db
+        );
+    }
+
+    // 4. Effect on a model's predictions
+    println!(
+        "\n4. Two-layer classifier ({} -> {} -> {}), 2000 test inputs:",
+        INPUTS, HIDDEN, CLASSES
+    );
+    // Calibrate activation ranges on data the model will see
+    let mut rng = Rng(21);
+    let calibration: Vec<Vec<f32>> = (0..200).map(|_| sample(&mut rng)).collect();
+    let inputs_flat: Vec<f32> = calibration.iter().flatten().copied().collect();
+    let hidden_flat: Vec<f32> = calibration.iter().flat_map(|x| model.hidden(x)).collect();
+    let input_params = QuantParams::for_values(&inputs_flat, Scheme::Asymmetric).unwrap();
+    let hidden_params = QuantParams::for_values(&hidden_flat, Scheme::Asymmetric).unwrap();
+
+    let tests: Vec<Vec<f32>> = (0..2000).map(|_| sample(&mut rng)).collect();
+    let reference: Vec<Vec<f32>> = tests.iter().map(|x| model.forward(x)).collect();
+    let reference_flat: Vec<f32> = reference.iter().flatten().copied().collect();
+
+    println!(
+        "   {:<22} {:>9} {:>11} {:>9}",
+        "weights", "agreement", "logit SQNR", "max error"
+    );
+    for (name, per_channel) in [("int8 per tensor", false), ("int8 per channel", true)] {
+        let granularity = |channels| {
+            if per_channel {
+                Granularity::PerChannel { channels }
+            } else {
+                Granularity::PerTensor
+            }
+        };
+        let quantized = QuantizedMlp {
+            float: &model,
+            w1: QuantizedTensor::quantize(
+                model.w1.as_slice(),
+                Scheme::Symmetric,
+                granularity(HIDDEN),
+            )
+            .unwrap(),
+            w2: QuantizedTensor::quantize(
+                model.w2.as_slice(),
+                Scheme::Symmetric,
+                granularity(CLASSES),
+            )
+            .unwrap(),
+            input: input_params,
+            hidden: hidden_params,
+        };
+        let outputs: Vec<Vec<f32>> = tests.iter().map(|x| quantized.forward(x)).collect();
+        let agree = outputs
+            .iter()
+            .zip(&reference)
+            .filter(|(q, f)| argmax(q) == argmax(f))
+            .count();
+        let flat: Vec<f32> = outputs.iter().flatten().copied().collect();
+        let m = error_metrics(&reference_flat, &flat);
+        println!(
+            "   {:<22} {:>8.1}% {:>8.1} dB {:>9.4}",
+            name,
+            100.0 * agree as f32 / tests.len() as f32,
+            m.sqnr_db,
+            m.max_abs
+        );
+    }
+    println!("   (agreement = same predicted class as the f32 model)");
+
+    // 5. Memory
+    println!("\n5. Model size:");
+    let f32_bytes = (model.w1.as_slice().len() + model.w2.as_slice().len()) * 4;
+    let q1 = QuantizedTensor::quantize(
+        model.w1.as_slice(),
+        Scheme::Symmetric,
+        Granularity::PerChannel { channels: HIDDEN },
+    )
+    .unwrap();
+    let q2 = QuantizedTensor::quantize(
+        model.w2.as_slice(),
+        Scheme::Symmetric,
+        Granularity::PerChannel { channels: CLASSES },
+    )
+    .unwrap();
+    let q_bytes = q1.size_bytes() + q2.size_bytes();
+    println!(
+        "   f32 weights {} bytes, int8 per channel {} bytes ({:.1}x smaller)",
+        f32_bytes,
+        q_bytes,
+        f32_bytes as f32 / q_bytes as f32
+    );
+    let layer = vec![0.1f32; 256 * 256];
+    let q = QuantizedTensor::quantize(
+        &layer,
+        Scheme::Symmetric,
+        Granularity::PerChannel { channels: 256 },
+    )
+    .unwrap();
+    println!(
+        "   a 256x256 layer: {} bytes -> {} bytes ({:.2}x); parameters only matter when tiny",
+        layer.len() * 4,
+        q.size_bytes(),
+        (layer.len() * 4) as f32 / q.size_bytes() as f32
+    );
+
+    // 6. Rejected inputs
+    println!("\n6. Rejected inputs:");
+    let cases: [(&str, &[f32], Granularity); 3] = [
+        ("empty tensor", &[], Granularity::PerTensor),
+        ("NaN weight", &[0.5, f32::NAN], Granularity::PerTensor),
+        (
+            "7 values, 2 channels",
+            &[1.0; 7],
+            Granularity::PerChannel { channels: 2 },
+        ),
+    ];
+    for (name, values, granularity) in cases {
+        match QuantizedTensor::quantize(values, Scheme::Symmetric, granularity) {
+            Ok(q) => println!("   {:<22} {} channels", name, q.channels()),
+            Err(e) => println!("   {:<22} {}", name, e),
+        }
+    }
+
+    println!("\n=== End of Int8 Quantization Examples ===");
+}
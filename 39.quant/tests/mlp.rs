@@ -0,0 +1,178 @@
+// What int8 quantization does to a small classifier's predictions: the
+// fixed-seed two-layer network of the demo, run in f32 and in int8
+
+use quant::{error_metrics, quantize, Granularity, QuantParams, QuantizedTensor, Scheme};
+use tensor::Matrix;
+
+const INPUTS: usize = 6;
+const HIDDEN: usize = 16;
+const CLASSES: usize = 4;
+
+// Deterministic pseudo-random numbers in [0, 1)
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> f32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    // Roughly normal, mean 0, standard deviation 1
+    fn normal(&mut self) -> f32 {
+        (0..12).map(|_| self.next()).sum::<f32>() - 6.0
+    }
+}
+
+// First-layer rows differ in magnitude by 40x, as trained layers often do
+struct Mlp {
+    w1: Matrix<f32>,
+    b1: Vec<f32>,
+    w2: Matrix<f32>,
+    b2: Vec<f32>,
+}
+
+impl Mlp {
+    fn new(rng: &mut Rng) -> Mlp {
+        let w1 = Matrix::from_fn(HIDDEN, INPUTS, |row, _| {
+            rng.normal() * 0.05 * 40f32.powf(row as f32 / (HIDDEN - 1) as f32)
+        });
+        let b1 = (0..HIDDEN).map(|_| rng.normal() * 0.1).collect();
+        let w2 = Matrix::from_fn(CLASSES, HIDDEN, |_, _| rng.normal() * 0.5);
+        let b2 = (0..CLASSES).map(|_| rng.normal() * 0.1).collect();
+        Mlp { w1, b1, w2, b2 }
+    }
+
+    fn hidden(&self, x: &[f32]) -> Vec<f32> {
+        let y = self.w1.matvec(x).unwrap();
+        y.iter()
+            .zip(&self.b1)
+            .map(|(y, b)| (y + b).max(0.0))
+            .collect()
+    }
+
+    fn forward(&self, x: &[f32]) -> Vec<f32> {
+        let y = self.w2.matvec(&self.hidden(x)).unwrap();
+        y.iter().zip(&self.b2).map(|(y, b)| y + b).collect()
+    }
+}
+
+// The same network with int8 weights and activations
+struct QuantizedMlp<'a> {
+    float: &'a Mlp,
+    w1: QuantizedTensor,
+    w2: QuantizedTensor,
+    input: QuantParams,
+    hidden: QuantParams,
+}
+
+impl QuantizedMlp<'_> {
+    fn new(
+        float: &Mlp,
+        per_channel: bool,
+        input: QuantParams,
+        hidden: QuantParams,
+    ) -> QuantizedMlp<'_> {
+        let weights = |m: &Matrix<f32>, channels| {
+            let granularity = if per_channel {
+                Granularity::PerChannel { channels }
+            } else {
+                Granularity::PerTensor
+            };
+            QuantizedTensor::quantize(m.as_slice(), Scheme::Symmetric, granularity).unwrap()
+        };
+        QuantizedMlp {
+            float,
+            w1: weights(&float.w1, HIDDEN),
+            w2: weights(&float.w2, CLASSES),
+            input,
+            hidden,
+        }
+    }
+
+    fn forward(&self, x: &[f32]) -> Vec<f32> {
+        let xq = quantize(x, &self.input);
+        let h: Vec<f32> = self
+            .w1
+            .matvec(&xq, &self.input)
+            .unwrap()
+            .iter()
+            .zip(&self.float.b1)
+            .map(|(y, b)| (y + b).max(0.0))
+            .collect();
+        let hq = quantize(&h, &self.hidden);
+        self.w2
+            .matvec(&hq, &self.hidden)
+            .unwrap()
+            .iter()
+            .zip(&self.float.b2)
+            .map(|(y, b)| y + b)
+            .collect()
+    }
+}
+
+fn argmax(v: &[f32]) -> usize {
+    v.iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(i, _)| i)
+        .unwrap()
+}
+
+// Differently scaled features, like RMS, peak and band energies
+fn sample(rng: &mut Rng) -> Vec<f32> {
+    let scales = [2.0, 6.0, 3.0, 8.0, 1.0, 0.5];
+    scales.iter().map(|s| rng.next() * s).collect()
+}
+
+// Class agreement with the f32 network and logit SQNR in dB
+fn evaluate(per_channel: bool) -> (f32, f32) {
+    let model = Mlp::new(&mut Rng(5));
+    let mut rng = Rng(21);
+    let calibration: Vec<Vec<f32>> = (0..200).map(|_| sample(&mut rng)).collect();
+    let inputs: Vec<f32> = calibration.iter().flatten().copied().collect();
+    let hidden: Vec<f32> = calibration.iter().flat_map(|x| model.hidden(x)).collect();
+    let quantized = QuantizedMlp::new(
+        &model,
+        per_channel,
+        QuantParams::for_values(&inputs, Scheme::Asymmetric).unwrap(),
+        QuantParams::for_values(&hidden, Scheme::Asymmetric).unwrap(),
+    );
+
+    let tests: Vec<Vec<f32>> = (0..2000).map(|_| sample(&mut rng)).collect();
+    let mut agree = 0;
+    let mut reference = Vec::new();
+    let mut outputs = Vec::new();
+    for x in &tests {
+        let f = model.forward(x);
+        let q = quantized.forward(x);
+        if argmax(&f) == argmax(&q) {
+            agree += 1;
+        }
+        reference.extend(f);
+        outputs.extend(q);
+    }
+    let sqnr = error_metrics(&reference, &outputs).sqnr_db;
+    (agree as f32 / tests.len() as f32, sqnr)
+}
+
+#[test]
+fn per_channel_int8_keeps_the_f32_predictions() {
+    let (agreement, sqnr) = evaluate(true);
+    assert!(agreement >= 0.98, "agreement {}", agreement);
+    assert!(sqnr > 35.0, "logit SQNR {} dB", sqnr);
+}
+
+#[test]
+fn per_channel_logits_beat_per_tensor() {
+    let (_, per_tensor) = evaluate(false);
+    let (_, per_channel) = evaluate(true);
+    assert!(
+        per_channel > per_tensor,
+        "per channel {} dB, per tensor {} dB",
+        per_channel,
+        per_tensor
+    );
+}
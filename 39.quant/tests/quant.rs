@@ -0,0 +1,247 @@
+use quant::{
+    dequantize, error_metrics, quantize, Granularity, QuantError, QuantParams, QuantizedTensor,
+    Scheme,
+};
+
+// Deterministic pseudo-random numbers in [0, 1)
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> f32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+#[test]
+fn asymmetric_parameters_for_a_known_range() {
+    let p = QuantParams::from_min_max(-1.0, 3.0);
+    assert!((p.scale - 4.0 / 255.0).abs() < 1e-7);
+    // -128 - (-1.0 / scale) = -64.25, rounded
+    assert_eq!(p.zero_point, -64);
+    assert_eq!(p.quantize(0.0), -64);
+    assert_eq!(p.dequantize(p.quantize(0.0)), 0.0);
+    let (lo, hi) = p.range();
+    assert!(lo <= -1.0 + p.scale && hi >= 3.0 - p.scale);
+}
+
+#[test]
+fn range_is_widened_to_include_zero() {
+    let p = QuantParams::from_min_max(2.0, 4.0);
+    assert_eq!(p.zero_point, -128);
+    assert_eq!(p.dequantize(p.quantize(0.0)), 0.0);
+    let p = QuantParams::from_min_max(-4.0, -2.0);
+    assert_eq!(p.zero_point, 127);
+    assert_eq!(p.dequantize(p.quantize(0.0)), 0.0);
+    // An all-zero range still gives usable parameters
+    assert_eq!(
+        QuantParams::from_min_max(0.0, 0.0),
+        QuantParams {
+            scale: 1.0,
+            zero_point: 0
+        }
+    );
+}
+
+#[test]
+fn rounding_error_is_at_most_half_a_step_inside_the_range() {
+    let p = QuantParams::from_min_max(-1.0, 3.0);
+    let mut rng = Rng(1);
+    for _ in 0..10_000 {
+        let x = -1.0 + rng.next() * 4.0;
+        let back = p.dequantize(p.quantize(x));
+        assert!(
+            (x - back).abs() <= p.scale / 2.0 + 1e-6,
+            "{} -> {}",
+            x,
+            back
+        );
+    }
+}
+
+#[test]
+fn values_outside_the_range_saturate() {
+    let p = QuantParams::from_min_max(-1.0, 3.0);
+    assert_eq!(p.quantize(5.0), 127);
+    assert_eq!(p.quantize(-7.0), -128);
+    assert_eq!(p.quantize(f32::INFINITY), 127);
+}
+
+#[test]
+fn symmetric_parameters_leave_minus_128_unused() {
+    let p = QuantParams::symmetric(2.54);
+    assert_eq!(p.zero_point, 0);
+    assert!((p.scale - 0.02).abs() < 1e-7);
+    assert_eq!(p.quantize(2.54), 127);
+    assert_eq!(p.quantize(-2.54), -127);
+    assert_eq!(QuantParams::symmetric(0.0).scale, 1.0);
+}
+
+#[test]
+fn for_values_picks_the_range_and_rejects_bad_input() {
+    let values = [-0.5, 2.0, 1.0];
+    assert_eq!(
+        QuantParams::for_values(&values, Scheme::Asymmetric).unwrap(),
+        QuantParams::from_min_max(-0.5, 2.0)
+    );
+    assert_eq!(
+        QuantParams::for_values(&values, Scheme::Symmetric).unwrap(),
+        QuantParams::symmetric(2.0)
+    );
+    assert_eq!(
+        QuantParams::for_values(&[], Scheme::Symmetric),
+        Err(QuantError::Empty)
+    );
+    assert!(matches!(
+        QuantParams::for_values(&[1.0, f32::NAN], Scheme::Symmetric),
+        Err(QuantError::NotFinite(x)) if x.is_nan()
+    ));
+}
+
+#[test]
+fn asymmetric_gains_about_one_bit_on_relu_activations() {
+    let mut rng = Rng(11);
+    let activations: Vec<f32> = (0..10_000).map(|_| rng.next() * 6.0).collect();
+    let sqnr = |scheme| {
+        let p = QuantParams::for_values(&activations, scheme).unwrap();
+        error_metrics(&activations, &dequantize(&quantize(&activations, &p), &p)).sqnr_db
+    };
+    let gain = sqnr(Scheme::Asymmetric) - sqnr(Scheme::Symmetric);
+    assert!((5.0..7.0).contains(&gain), "gain {} dB", gain);
+}
+
+#[test]
+fn per_channel_keeps_small_rows_precise() {
+    // Row 0 is 40x smaller than row 1
+    let mut rng = Rng(5);
+    let weights: Vec<f32> = (0..64)
+        .map(|i| (rng.next() - 0.5) * if i < 32 { 0.05 } else { 2.0 })
+        .collect();
+    let small_row_sqnr = |granularity| {
+        let q = QuantizedTensor::quantize(&weights, Scheme::Symmetric, granularity).unwrap();
+        error_metrics(&weights[..32], &q.dequantize()[..32]).sqnr_db
+    };
+    let per_tensor = small_row_sqnr(Granularity::PerTensor);
+    let per_channel = small_row_sqnr(Granularity::PerChannel { channels: 2 });
+    // 40x more resolution is about 32 dB
+    assert!(per_channel - per_tensor > 25.0);
+}
+
+#[test]
+fn tensor_shape_and_size() {
+    let values: Vec<f32> = (0..12).map(|i| i as f32 - 6.0).collect();
+    let q = QuantizedTensor::quantize(
+        &values,
+        Scheme::Symmetric,
+        Granularity::PerChannel { channels: 3 },
+    )
+    .unwrap();
+    assert_eq!(q.channels(), 3);
+    assert_eq!(q.channel_len(), 4);
+    assert_eq!(q.size_bytes(), 12 + 3 * 8);
+    assert_eq!(q.dequantize().len(), 12);
+    // Each channel's largest magnitude maps to +-127
+    assert_eq!(q.data[0], -127);
+    assert_eq!(q.data[11], 127);
+
+    assert_eq!(
+        QuantizedTensor::quantize(&[], Scheme::Symmetric, Granularity::PerTensor),
+        Err(QuantError::Empty)
+    );
+    assert_eq!(
+        QuantizedTensor::quantize(
+            &[1.0; 7],
+            Scheme::Symmetric,
+            Granularity::PerChannel { channels: 2 }
+        ),
+        Err(QuantError::ShapeMismatch {
+            len: 7,
+            channels: 2
+        })
+    );
+    assert_eq!(
+        QuantizedTensor::quantize(
+            &[1.0; 4],
+            Scheme::Symmetric,
+            Granularity::PerChannel { channels: 0 }
+        ),
+        Err(QuantError::ShapeMismatch {
+            len: 4,
+            channels: 0
+        })
+    );
+}
+
+#[test]
+fn matvec_accumulates_exactly_in_integers() {
+    let weights = [0.5, -1.0, 0.25, 2.0, 0.0, -0.75];
+    let w = QuantizedTensor::quantize(
+        &weights,
+        Scheme::Symmetric,
+        Granularity::PerChannel { channels: 2 },
+    )
+    .unwrap();
+    let input = [1.0, 2.0, -0.5];
+    let ip = QuantParams::for_values(&input, Scheme::Asymmetric).unwrap();
+    let xq = quantize(&input, &ip);
+    let y = w.matvec(&xq, &ip).unwrap();
+
+    // Same sum computed from the dequantized values
+    let wd = w.dequantize();
+    let xd = dequantize(&xq, &ip);
+    for (row, &out) in y.iter().enumerate() {
+        let expected: f32 = (0..3).map(|i| wd[row * 3 + i] * xd[i]).sum();
+        assert!((out - expected).abs() < 1e-5);
+    }
+    // And close to the float result
+    let exact = [0.5 - 2.0 - 0.125, 2.0 + 0.375];
+    for (out, e) in y.iter().zip(exact) {
+        assert!((out - e).abs() < 0.05, "{} vs {}", out, e);
+    }
+}
+
+#[test]
+fn matvec_rejects_mismatched_shapes() {
+    let w = QuantizedTensor::quantize(
+        &[1.0; 6],
+        Scheme::Symmetric,
+        Granularity::PerChannel { channels: 2 },
+    )
+    .unwrap();
+    let p = QuantParams::symmetric(1.0);
+    assert_eq!(
+        w.matvec(&[1; 4], &p),
+        Err(QuantError::LengthMismatch {
+            expected: 3,
+            actual: 4
+        })
+    );
+    assert!(matches!(
+        w.matvec(&[], &p),
+        Err(QuantError::LengthMismatch { .. })
+    ));
+    // 6 values as 3 rows of 2 do not match 2 channels
+    assert_eq!(
+        w.matvec(&[1; 2], &p),
+        Err(QuantError::ShapeMismatch {
+            len: 6,
+            channels: 2
+        })
+    );
+}
+
+#[test]
+fn error_metrics_of_known_signals() {
+    let m = error_metrics(&[1.0, -1.0, 2.0], &[1.0, -1.0, 2.0]);
+    assert_eq!(m.mse, 0.0);
+    assert_eq!(m.sqnr_db, f32::INFINITY);
+
+    // Signal power 4, noise power 0.04: 20 dB
+    let m = error_metrics(&[2.0, -2.0], &[2.2, -1.8]);
+    assert!((m.mse - 0.04).abs() < 1e-6);
+    assert!((m.max_abs - 0.2).abs() < 1e-6);
+    assert!((m.sqnr_db - 20.0).abs() < 1e-3);
+}
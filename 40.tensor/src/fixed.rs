@@ -0,0 +1,131 @@
+// Stack-allocated matrix with compile-time shape
+//
+// `MatrixN<T, R, C>` is a plain `[[T; C]; R]`: no heap, `Copy`, and the
+// shape is part of the type. Multiplying a 2x3 by a 2x2 is a compile
+// error rather than a run-time one, which suits the small fixed-size
+// matrices of a Kalman filter or a rotation on a microcontroller.
+
+use crate::matrix::Matrix;
+use crate::scalar::Scalar;
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatrixN<T, const R: usize, const C: usize> {
+    data: [[T; C]; R],
+}
+
+impl<T: Scalar, const R: usize, const C: usize> MatrixN<T, R, C> {
+    pub const fn from_rows(data: [[T; C]; R]) -> Self {
+        MatrixN { data }
+    }
+
+    pub fn zeros() -> Self {
+        MatrixN {
+            data: [[T::ZERO; C]; R],
+        }
+    }
+
+    pub fn rows(&self) -> &[[T; C]; R] {
+        &self.data
+    }
+
+    pub fn transpose(&self) -> MatrixN<T, C, R> {
+        let mut out = MatrixN::<T, C, R>::zeros();
+        for (r, row) in self.data.iter().enumerate() {
+            for (c, &value) in row.iter().enumerate() {
+                out.data[c][r] = value;
+            }
+        }
+        out
+    }
+
+    pub fn mul_vec(&self, v: &[T; C]) -> [T; R] {
+        self.data
+            .map(|row| row.iter().zip(v).fold(T::ZERO, |acc, (&a, &b)| acc + a * b))
+    }
+
+    pub fn scale(&self, k: T) -> Self {
+        MatrixN {
+            data: self.data.map(|row| row.map(|a| a * k)),
+        }
+    }
+
+    pub fn to_matrix(&self) -> Matrix<T> {
+        Matrix::from_fn(R, C, |r, c| self.data[r][c])
+    }
+}
+
+impl<T: Scalar, const N: usize> MatrixN<T, N, N> {
+    pub fn identity() -> Self {
+        let mut out = Self::zeros();
+        for (i, row) in out.data.iter_mut().enumerate() {
+            row[i] = T::ONE;
+        }
+        out
+    }
+}
+
+impl<T, const R: usize, const C: usize> Index<(usize, usize)> for MatrixN<T, R, C> {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &T {
+        &self.data[r][c]
+    }
+}
+
+impl<T, const R: usize, const C: usize> IndexMut<(usize, usize)> for MatrixN<T, R, C> {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut T {
+        &mut self.data[r][c]
+    }
+}
+
+impl<T: Scalar, const R: usize, const C: usize> Add for MatrixN<T, R, C> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        for (row, other_row) in self.data.iter_mut().zip(&other.data) {
+            for (a, &b) in row.iter_mut().zip(other_row) {
+                *a += b;
+            }
+        }
+        self
+    }
+}
+
+impl<T: Scalar, const R: usize, const C: usize> Sub for MatrixN<T, R, C> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        for (row, other_row) in self.data.iter_mut().zip(&other.data) {
+            for (a, &b) in row.iter_mut().zip(other_row) {
+                *a = *a - b;
+            }
+        }
+        self
+    }
+}
+
+// (R x K) * (K x C) = (R x C); the inner dimensions must agree to compile
+impl<T: Scalar, const R: usize, const K: usize, const C: usize> Mul<MatrixN<T, K, C>>
+    for MatrixN<T, R, K>
+{
+    type Output = MatrixN<T, R, C>;
+
+    fn mul(self, other: MatrixN<T, K, C>) -> MatrixN<T, R, C> {
+        let mut out = MatrixN::<T, R, C>::zeros();
+        for (out_row, row) in out.data.iter_mut().zip(&self.data) {
+            for (&a, other_row) in row.iter().zip(&other.data) {
+                for (o, &b) in out_row.iter_mut().zip(other_row) {
+                    *o += a * b;
+                }
+            }
+        }
+        out
+    }
+}
+
+impl<T: Scalar, const R: usize, const C: usize> From<MatrixN<T, R, C>> for Matrix<T> {
+    fn from(m: MatrixN<T, R, C>) -> Matrix<T> {
+        m.to_matrix()
+    }
+}
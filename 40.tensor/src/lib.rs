@@ -0,0 +1,9 @@
+// Small dense matrices for sensor fusion, fitting and inference
+
+pub mod fixed;
+pub mod matrix;
+pub mod scalar;
+
+pub use fixed::MatrixN;
+pub use matrix::{Matrix, MatrixView, ShapeError};
+pub use scalar::{Real, Scalar};
@@ -0,0 +1,196 @@
+use std::time::Instant;
+use tensor::{Matrix, MatrixN};
+
+// Deterministic pseudo-random noise in [-0.5, 0.5)
+struct Noise(u64);
+
+impl Noise {
+    fn next(&mut self) -> f64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+    }
+}
+
+// Continue multi-line matrix output at the section's indentation
+fn indent(text: String) -> String {
+    text.replace('\n', "\n   ")
+}
+
+// Textbook i-j-k product, for comparison with the cache-friendly order
+fn matmul_ijk(a: &Matrix<f32>, b: &Matrix<f32>) -> Matrix<f32> {
+    Matrix::from_fn(a.rows(), b.cols(), |i, j| {
+        (0..a.cols()).map(|k| a[(i, k)] * b[(k, j)]).sum()
+    })
+}
+
+fn main() {
+    println!("=== Matrix and Tensor Examples ===\n");
+
+    // 1. Construction and layout
+    println!("1. Row-major storage:");
+    let a = Matrix::from_rows(&[&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]]).unwrap();
+    println!(
+        "   A ({}x{}):\n   {}",
+        a.rows(),
+        a.cols(),
+        indent(format!("{:4.1}", a))
+    );
+    println!("   storage {:?}", a.as_slice());
+    println!("   A[(1, 2)] = {}, row 1 = {:?}", a[(1, 2)], a.row(1));
+    match Matrix::from_rows(&[&[1.0, 2.0], &[3.0]]) {
+        Ok(_) => println!("   ragged rows accepted?!"),
+        Err(e) => println!("   ragged rows: {}", e),
+    }
+
+    // 2. Products and transposes
+    println!("\n2. Matmul and transpose:");
+    let b = Matrix::from_rows(&[&[1.0, 0.0], &[0.0, 1.0], &[2.0, -1.0]]).unwrap();
+    let ab = &a * &b;
+    println!(
+        "   A * B ({}x{}):\n   {}",
+        ab.rows(),
+        ab.cols(),
+        indent(format!("{:5.1}", ab))
+    );
+    let lhs = ab.transpose();
+    let rhs = &b.transpose() * &a.transpose();
+    println!("   (AB)^T == B^T A^T: {}", lhs == rhs);
+    match a.matmul(&a) {
+        Ok(_) => println!("   A * A accepted?!"),
+        Err(e) => println!("   A * A: {}", e),
+    }
+    println!(
+        "   A x with x = [1, 1, 1]: {:?}",
+        a.matvec(&[1.0, 1.0, 1.0]).unwrap()
+    );
+
+    // 3. Elementwise operations
+    println!("\n3. Elementwise:");
+    let ones = Matrix::from_fn(2, 3, |_, _| 1.0);
+    for (name, m) in [
+        ("A + 1", &a + &ones),
+        ("A ∘ A", a.hadamard(&a).unwrap()),
+        ("A * 0.5", a.scale(0.5)),
+    ] {
+        println!(
+            "   {:<8} {}",
+            name,
+            format!("{:4.1}", m).replace('\n', "\n            ")
+        );
+    }
+    println!(
+        "   {:<8} {}",
+        "A as i32",
+        format!("{:3}", a.map(|x| x as i32 * 10)).replace('\n', "\n            ")
+    );
+    println!("   {:<8} {:.4}", "|A|", a.norm());
+
+    // 4. Views
+    println!("\n4. Views share storage:");
+    let big = Matrix::from_fn(4, 4, |r, c| (r * 4 + c) as f64);
+    println!("   M:\n   {}", indent(format!("{:5.1}", big)));
+    let block = big.view(1, 1, 2, 2).unwrap();
+    println!(
+        "   2x2 block at (1,1): rows {:?} and {:?}",
+        block.row(0),
+        block.row(1)
+    );
+    println!(
+        "   copied out:\n   {}",
+        indent(format!("{:5.1}", block.to_matrix()))
+    );
+    match big.view(3, 3, 2, 2) {
+        Ok(_) => println!("   view past the edge accepted?!"),
+        Err(e) => println!("   view past the edge: {}", e),
+    }
+
+    // 5. Linear systems
+    println!("\n5. Solving and inverting:");
+    let m = Matrix::from_rows(&[&[2.0, 1.0, -1.0], &[-3.0, -1.0, 2.0], &[-2.0, 1.0, 2.0]]).unwrap();
+    println!("   2x + y - z = 8, -3x - y + 2z = -11, -2x + y + 2z = -3");
+    let x = m.solve(&[8.0, -11.0, -3.0]).unwrap();
+    println!(
+        "   x = {:.6}, y = {:.6}, z = {:.6}  (expected 2, 3, -1)",
+        x[0], x[1], x[2]
+    );
+    let inv = m.inverse().unwrap();
+    println!("   M^-1:\n   {}", indent(format!("{:8.4}", inv)));
+    let residual = (&(&m * &inv) - &Matrix::identity(3)).norm();
+    println!("   |M M^-1 - I| = {:.2e}", residual);
+    let singular = Matrix::from_rows(&[&[1.0, 2.0], &[2.0, 4.0]]).unwrap();
+    println!("   singular matrix inverse: {:?}", singular.inverse());
+
+    // 6. Least squares with normal equations
+    println!("\n6. Least-squares line through noisy points:");
+    let mut noise = Noise(9);
+    let xs: Vec<f64> = (0..20).map(|i| i as f64 * 0.5).collect();
+    let ys: Vec<f64> = xs.iter().map(|x| 1.5 * x - 2.0 + noise.next()).collect();
+    let design = Matrix::from_fn(xs.len(), 2, |r, c| if c == 0 { 1.0 } else { xs[r] });
+    let dt = design.transpose();
+    let coeffs = (&dt * &design).solve(&dt.matvec(&ys).unwrap()).unwrap();
+    println!(
+        "   y = {:.3} x {} {:.3}   (true 1.5 x - 2.0)",
+        coeffs[1],
+        if coeffs[0] < 0.0 { '-' } else { '+' },
+        coeffs[0].abs()
+    );
+
+    // 7. Fixed-size matrices: a Kalman filter step
+    println!("\n7. MatrixN: constant-velocity Kalman filter, dt = 0.1 s:");
+    let dt = 0.1;
+    let f = MatrixN::from_rows([[1.0, dt], [0.0, 1.0]]);
+    let h = MatrixN::from_rows([[1.0, 0.0]]);
+    let q = MatrixN::from_rows([[1e-4, 0.0], [0.0, 1e-3]]);
+    let r = 0.25;
+    let mut state = [0.0, 0.0];
+    let mut p = MatrixN::<f64, 2, 2>::identity().scale(10.0);
+    let mut noise = Noise(4);
+    for step in 1..=50 {
+        // Predict: x = F x, P = F P F^T + Q
+        state = f.mul_vec(&state);
+        p = f * p * f.transpose() + q;
+
+        // Update with a noisy position (true motion: 2 m/s from 0)
+        let z = 2.0 * step as f64 * dt + noise.next() * 1.0;
+        let innovation = z - h.mul_vec(&state)[0];
+        let s = (h * p * h.transpose())[(0, 0)] + r;
+        let gain = (p * h.transpose()).scale(1.0 / s);
+        state = [
+            state[0] + gain[(0, 0)] * innovation,
+            state[1] + gain[(1, 0)] * innovation,
+        ];
+        p = (MatrixN::identity() - gain * h) * p;
+
+        if step % 10 == 0 {
+            println!(
+                "   t = {:.1} s  position {:>6.3} m  velocity {:>5.3} m/s  var {:.4}",
+                step as f64 * dt,
+                state[0],
+                state[1],
+                p[(1, 1)]
+            );
+        }
+    }
+    println!("   (`f * h` would not compile: 2x2 times 1x2)");
+
+    // 8. Loop order matters
+    println!("\n8. 128x128 matmul:");
+    let mut noise = Noise(2);
+    let x = Matrix::from_fn(128, 128, |_, _| noise.next() as f32);
+    let y = Matrix::from_fn(128, 128, |_, _| noise.next() as f32);
+    let start = Instant::now();
+    let fast = x.matmul(&y).unwrap();
+    let fast_time = start.elapsed();
+    let start = Instant::now();
+    let slow = matmul_ijk(&x, &y);
+    let slow_time = start.elapsed();
+    let diff = (&fast - &slow).norm();
+    println!("   i-k-j (row-contiguous) {:>10.2?}", fast_time);
+    println!("   i-j-k (column walk)    {:>10.2?}", slow_time);
+    println!("   results agree to {:.1e}", diff);
+
+    println!("\n=== End of Matrix and Tensor Examples ===");
+}
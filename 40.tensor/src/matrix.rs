@@ -0,0 +1,445 @@
+// Heap-allocated matrix with run-time shape
+//
+// Elements are stored row-major in one Vec: element (r, c) lives at
+// `r * cols + c`. One allocation per matrix, rows are contiguous slices,
+// and the layout matches what C libraries and model files expect, unlike
+// `Vec<Vec<T>>` with its allocation per row and no guarantee that all rows
+// have the same length.
+//
+// Shape mismatches are errors in the `try_*`/`matmul` methods and panics
+// in the operator impls (`&a * &b`), just as slice indexing panics.
+
+use crate::scalar::{Real, Scalar};
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShapeError {
+    pub op: &'static str,
+    pub left: (usize, usize),
+    pub right: (usize, usize),
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: shapes {}x{} and {}x{} are incompatible",
+            self.op, self.left.0, self.left.1, self.right.0, self.right.1
+        )
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T: Scalar> Matrix<T> {
+    pub fn zeros(rows: usize, cols: usize) -> Matrix<T> {
+        Matrix {
+            rows,
+            cols,
+            data: vec![T::ZERO; rows * cols],
+        }
+    }
+
+    pub fn identity(n: usize) -> Matrix<T> {
+        Matrix::from_fn(n, n, |r, c| if r == c { T::ONE } else { T::ZERO })
+    }
+
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(rows: usize, cols: usize, mut f: F) -> Matrix<T> {
+        let mut data = Vec::with_capacity(rows * cols);
+        for r in 0..rows {
+            for c in 0..cols {
+                data.push(f(r, c));
+            }
+        }
+        Matrix { rows, cols, data }
+    }
+
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<T>) -> Result<Matrix<T>, ShapeError> {
+        if data.len() != rows * cols {
+            return Err(ShapeError {
+                op: "from_vec",
+                left: (rows, cols),
+                right: (data.len(), 1),
+            });
+        }
+        Ok(Matrix { rows, cols, data })
+    }
+
+    // Every row must have the same length
+    pub fn from_rows(rows: &[&[T]]) -> Result<Matrix<T>, ShapeError> {
+        let cols = rows.first().map_or(0, |r| r.len());
+        let mut data = Vec::with_capacity(rows.len() * cols);
+        for row in rows {
+            if row.len() != cols {
+                return Err(ShapeError {
+                    op: "from_rows",
+                    left: (rows.len(), cols),
+                    right: (1, row.len()),
+                });
+            }
+            data.extend_from_slice(row);
+        }
+        Ok(Matrix {
+            rows: rows.len(),
+            cols,
+            data,
+        })
+    }
+
+    // An n x 1 column vector
+    pub fn column(values: &[T]) -> Matrix<T> {
+        Matrix {
+            rows: values.len(),
+            cols: 1,
+            data: values.to_vec(),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<T> {
+        if row < self.rows && col < self.cols {
+            Some(self.data[row * self.cols + col])
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, r: usize) -> &[T] {
+        &self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    pub fn row_mut(&mut self, r: usize) -> &mut [T] {
+        &mut self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    pub fn transpose(&self) -> Matrix<T> {
+        Matrix::from_fn(self.cols, self.rows, |r, c| self[(c, r)])
+    }
+
+    pub fn matmul(&self, other: &Matrix<T>) -> Result<Matrix<T>, ShapeError> {
+        if self.cols != other.rows {
+            return Err(ShapeError {
+                op: "matmul",
+                left: self.shape(),
+                right: other.shape(),
+            });
+        }
+        // i-k-j order: the inner loop walks both `other` and the output row
+        // contiguously, which is several times faster than the textbook
+        // i-j-k order on anything with a cache
+        let mut out = Matrix::zeros(self.rows, other.cols);
+        for i in 0..self.rows {
+            let out_row = &mut out.data[i * other.cols..(i + 1) * other.cols];
+            for (k, &a) in self.row(i).iter().enumerate() {
+                for (o, &b) in out_row.iter_mut().zip(other.row(k)) {
+                    *o += a * b;
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn matvec(&self, v: &[T]) -> Result<Vec<T>, ShapeError> {
+        if self.cols != v.len() {
+            return Err(ShapeError {
+                op: "matvec",
+                left: self.shape(),
+                right: (v.len(), 1),
+            });
+        }
+        Ok((0..self.rows)
+            .map(|r| {
+                self.row(r)
+                    .iter()
+                    .zip(v)
+                    .fold(T::ZERO, |acc, (&a, &b)| acc + a * b)
+            })
+            .collect())
+    }
+
+    pub fn map<U: Scalar, F: FnMut(T) -> U>(&self, f: F) -> Matrix<U> {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().copied().map(f).collect(),
+        }
+    }
+
+    // Elementwise combination of two matrices of the same shape
+    pub fn zip_with<F: FnMut(T, T) -> T>(
+        &self,
+        other: &Matrix<T>,
+        op: &'static str,
+        mut f: F,
+    ) -> Result<Matrix<T>, ShapeError> {
+        if self.shape() != other.shape() {
+            return Err(ShapeError {
+                op,
+                left: self.shape(),
+                right: other.shape(),
+            });
+        }
+        Ok(Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .iter()
+                .zip(&other.data)
+                .map(|(&a, &b)| f(a, b))
+                .collect(),
+        })
+    }
+
+    pub fn try_add(&self, other: &Matrix<T>) -> Result<Matrix<T>, ShapeError> {
+        self.zip_with(other, "add", |a, b| a + b)
+    }
+
+    pub fn try_sub(&self, other: &Matrix<T>) -> Result<Matrix<T>, ShapeError> {
+        self.zip_with(other, "sub", |a, b| a - b)
+    }
+
+    // Elementwise product
+    pub fn hadamard(&self, other: &Matrix<T>) -> Result<Matrix<T>, ShapeError> {
+        self.zip_with(other, "hadamard", |a, b| a * b)
+    }
+
+    pub fn scale(&self, k: T) -> Matrix<T> {
+        self.map(|a| a * k)
+    }
+
+    // A rectangular block sharing this matrix's storage
+    pub fn view(
+        &self,
+        row: usize,
+        col: usize,
+        rows: usize,
+        cols: usize,
+    ) -> Result<MatrixView<'_, T>, ShapeError> {
+        if row + rows > self.rows || col + cols > self.cols {
+            return Err(ShapeError {
+                op: "view",
+                left: self.shape(),
+                right: (row + rows, col + cols),
+            });
+        }
+        // An empty block may start past the end of the storage
+        let data = if rows == 0 || cols == 0 {
+            &[]
+        } else {
+            &self.data[row * self.cols + col..]
+        };
+        Ok(MatrixView {
+            data,
+            rows,
+            cols,
+            stride: self.cols,
+        })
+    }
+}
+
+impl<T: Real> Matrix<T> {
+    // Solve A x = b for square A; None if A is singular
+    pub fn solve(&self, b: &[T]) -> Option<Vec<T>> {
+        if self.rows != self.cols || b.len() != self.rows {
+            return None;
+        }
+        let n = self.rows;
+        let mut aug = Matrix::from_fn(n, n + 1, |r, c| if c < n { self[(r, c)] } else { b[r] });
+        aug.gauss_jordan(n)?;
+        Some((0..n).map(|r| aug[(r, n)]).collect())
+    }
+
+    pub fn inverse(&self) -> Option<Matrix<T>> {
+        if self.rows != self.cols {
+            return None;
+        }
+        let n = self.rows;
+        let mut aug = Matrix::from_fn(n, 2 * n, |r, c| {
+            if c < n {
+                self[(r, c)]
+            } else if c - n == r {
+                T::ONE
+            } else {
+                T::ZERO
+            }
+        });
+        aug.gauss_jordan(n)?;
+        Some(Matrix::from_fn(n, n, |r, c| aug[(r, n + c)]))
+    }
+
+    // Frobenius norm: sqrt of the sum of squares
+    pub fn norm(&self) -> T {
+        self.data.iter().fold(T::ZERO, |acc, &x| acc + x * x).sqrt()
+    }
+
+    // Reduce the left n x n block of an augmented matrix to the identity
+    // (partial pivoting); the right-hand columns end up holding the solution
+    fn gauss_jordan(&mut self, n: usize) -> Option<()> {
+        let w = self.cols;
+        for col in 0..n {
+            let mut pivot = col;
+            for r in col + 1..n {
+                if self.data[r * w + col].abs() > self.data[pivot * w + col].abs() {
+                    pivot = r;
+                }
+            }
+            if self.data[pivot * w + col].abs() < T::EPSILON {
+                return None;
+            }
+            for c in 0..w {
+                self.data.swap(pivot * w + c, col * w + c);
+            }
+
+            let p = self.data[col * w + col];
+            for c in 0..w {
+                self.data[col * w + c] = self.data[col * w + c] / p;
+            }
+            for r in (0..n).filter(|&r| r != col) {
+                let factor = self.data[r * w + col];
+                for c in 0..w {
+                    self.data[r * w + c] = self.data[r * w + c] - factor * self.data[col * w + c];
+                }
+            }
+        }
+        Some(())
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &T {
+        assert!(
+            r < self.rows && c < self.cols,
+            "index ({}, {}) out of bounds",
+            r,
+            c
+        );
+        &self.data[r * self.cols + c]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut T {
+        assert!(
+            r < self.rows && c < self.cols,
+            "index ({}, {}) out of bounds",
+            r,
+            c
+        );
+        &mut self.data[r * self.cols + c]
+    }
+}
+
+impl<T: Scalar> Add for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn add(self, other: &Matrix<T>) -> Matrix<T> {
+        self.try_add(other).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl<T: Scalar> Sub for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn sub(self, other: &Matrix<T>) -> Matrix<T> {
+        self.try_sub(other).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl<T: Scalar> Mul for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, other: &Matrix<T>) -> Matrix<T> {
+        self.matmul(other).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+// One row per line; precision and width apply to every element, so
+// `{:8.3}` prints an aligned table
+impl<T: Scalar> fmt::Display for Matrix<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for r in 0..self.rows {
+            if r > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "[")?;
+            for (c, value) in self.row(r).iter().enumerate() {
+                if c > 0 {
+                    write!(f, " ")?;
+                }
+                fmt::Display::fmt(value, f)?;
+            }
+            write!(f, "]")?;
+        }
+        Ok(())
+    }
+}
+
+// A borrowed block of a matrix; rows are `stride` elements apart
+#[derive(Debug, Clone, Copy)]
+pub struct MatrixView<'a, T> {
+    data: &'a [T],
+    rows: usize,
+    cols: usize,
+    stride: usize,
+}
+
+impl<'a, T: Scalar> MatrixView<'a, T> {
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn row(&self, r: usize) -> &'a [T] {
+        assert!(r < self.rows, "row {} out of bounds", r);
+        &self.data[r * self.stride..r * self.stride + self.cols]
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<T> {
+        if row < self.rows && col < self.cols {
+            Some(self.data[row * self.stride + col])
+        } else {
+            None
+        }
+    }
+
+    pub fn to_matrix(&self) -> Matrix<T> {
+        Matrix::from_fn(self.rows, self.cols, |r, c| self.row(r)[c])
+    }
+}
@@ -0,0 +1,65 @@
+// Element types a matrix can hold
+//
+// `Scalar` is what matmul and elementwise arithmetic need; `Real` adds what
+// solving a linear system needs (division and an absolute value for
+// choosing pivots). Integers are scalars, floats are both.
+
+use std::fmt::{Debug, Display};
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub};
+
+pub trait Scalar:
+    Copy
+    + PartialEq
+    + Debug
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + AddAssign
+{
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+pub trait Real: Scalar + PartialOrd + Div<Output = Self> + Neg<Output = Self> {
+    // Pivots smaller than this are treated as zero
+    const EPSILON: Self;
+
+    fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+}
+
+macro_rules! impl_scalar {
+    ($($t:ty => $zero:expr, $one:expr);*) => {
+        $(impl Scalar for $t {
+            const ZERO: Self = $zero;
+            const ONE: Self = $one;
+        })*
+    };
+}
+
+impl_scalar!(i32 => 0, 1; i64 => 0, 1; f32 => 0.0, 1.0; f64 => 0.0, 1.0);
+
+impl Real for f32 {
+    const EPSILON: Self = 1e-6;
+
+    fn abs(self) -> Self {
+        f32::abs(self)
+    }
+
+    fn sqrt(self) -> Self {
+        f32::sqrt(self)
+    }
+}
+
+impl Real for f64 {
+    const EPSILON: Self = 1e-12;
+
+    fn abs(self) -> Self {
+        f64::abs(self)
+    }
+
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+}
@@ -0,0 +1,159 @@
+use tensor::{Matrix, MatrixN, ShapeError};
+
+fn m(rows: &[&[f64]]) -> Matrix<f64> {
+    Matrix::from_rows(rows).unwrap()
+}
+
+fn assert_close(a: &Matrix<f64>, b: &Matrix<f64>) {
+    assert_eq!(a.shape(), b.shape());
+    for (x, y) in a.as_slice().iter().zip(b.as_slice()) {
+        assert!((x - y).abs() < 1e-9, "{}\n!=\n{}", a, b);
+    }
+}
+
+#[test]
+fn matmul_known_product() {
+    let a = Matrix::from_rows(&[&[1, 2, 3], &[4, 5, 6]]).unwrap();
+    let b = Matrix::from_rows(&[&[7, 8], &[9, 10], &[11, 12]]).unwrap();
+    let expected = Matrix::from_rows(&[&[58, 64], &[139, 154]]).unwrap();
+    assert_eq!(a.matmul(&b).unwrap(), expected);
+    assert_eq!(&a * &b, expected);
+    assert_eq!(a.matvec(&[1, 0, -1]).unwrap(), [-2, -2]);
+}
+
+#[test]
+fn identity_is_neutral() {
+    let a = m(&[&[2.0, -1.0], &[0.5, 3.0]]);
+    assert_eq!(a.matmul(&Matrix::identity(2)).unwrap(), a);
+    assert_eq!(Matrix::identity(2).matmul(&a).unwrap(), a);
+}
+
+#[test]
+fn transpose_swaps_rows_and_columns() {
+    let a = Matrix::from_rows(&[&[1, 2, 3], &[4, 5, 6]]).unwrap();
+    let t = a.transpose();
+    assert_eq!(t.shape(), (3, 2));
+    assert_eq!(t.as_slice(), [1, 4, 2, 5, 3, 6]);
+    assert_eq!(t.transpose(), a);
+}
+
+#[test]
+fn elementwise_ops() {
+    let a = Matrix::from_rows(&[&[1, 2], &[3, 4]]).unwrap();
+    let b = Matrix::from_rows(&[&[10, 20], &[30, 40]]).unwrap();
+    assert_eq!((&a + &b).as_slice(), [11, 22, 33, 44]);
+    assert_eq!((&b - &a).as_slice(), [9, 18, 27, 36]);
+    assert_eq!(a.hadamard(&b).unwrap().as_slice(), [10, 40, 90, 160]);
+    assert_eq!(a.scale(3).as_slice(), [3, 6, 9, 12]);
+    assert_eq!(a.map(|x| x as f64 / 2.0).as_slice(), [0.5, 1.0, 1.5, 2.0]);
+}
+
+#[test]
+fn inverse_of_known_matrix() {
+    let a = m(&[&[4.0, 7.0], &[2.0, 6.0]]);
+    let expected = m(&[&[0.6, -0.7], &[-0.2, 0.4]]);
+    let inv = a.inverse().unwrap();
+    assert_close(&inv, &expected);
+    assert_close(&a.matmul(&inv).unwrap(), &Matrix::identity(2));
+}
+
+#[test]
+fn inverse_needs_pivoting() {
+    // Zero in the top-left corner: fails without a row swap
+    let a = m(&[&[0.0, 1.0, 2.0], &[1.0, 0.0, 3.0], &[4.0, -3.0, 8.0]]);
+    let inv = a.inverse().unwrap();
+    assert_close(&a.matmul(&inv).unwrap(), &Matrix::identity(3));
+}
+
+#[test]
+fn solve_known_system() {
+    // 2x + y = 5, x - y = 1  =>  x = 2, y = 1
+    let a = m(&[&[2.0, 1.0], &[1.0, -1.0]]);
+    let x = a.solve(&[5.0, 1.0]).unwrap();
+    assert!((x[0] - 2.0).abs() < 1e-12 && (x[1] - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn singular_matrix_has_no_inverse() {
+    // Second row is twice the first
+    let a = m(&[&[1.0, 2.0], &[2.0, 4.0]]);
+    assert!(a.inverse().is_none());
+    assert!(a.solve(&[1.0, 2.0]).is_none());
+    assert!(m(&[&[1.0, 2.0, 3.0]]).inverse().is_none());
+}
+
+#[test]
+fn shape_mismatches_are_errors() {
+    let a = Matrix::<i32>::zeros(2, 3);
+    let b = Matrix::<i32>::zeros(2, 2);
+    assert_eq!(
+        a.matmul(&b),
+        Err(ShapeError {
+            op: "matmul",
+            left: (2, 3),
+            right: (2, 2)
+        })
+    );
+    assert_eq!(a.try_add(&b).unwrap_err().op, "add");
+    assert_eq!(a.try_sub(&b).unwrap_err().op, "sub");
+    assert_eq!(a.hadamard(&b).unwrap_err().op, "hadamard");
+    assert_eq!(a.matvec(&[1, 2]).unwrap_err().right, (2, 1));
+    assert!(Matrix::from_vec(2, 2, vec![1, 2, 3]).is_err());
+    assert!(Matrix::from_rows(&[&[1, 2], &[3][..]]).is_err());
+}
+
+#[test]
+#[should_panic(expected = "incompatible")]
+fn operator_panics_on_shape_mismatch() {
+    let _ = &Matrix::<i32>::zeros(2, 3) * &Matrix::<i32>::zeros(2, 3);
+}
+
+#[test]
+fn view_shares_a_block() {
+    let a = Matrix::from_fn(3, 4, |r, c| (r * 10 + c) as i32);
+    let v = a.view(1, 1, 2, 2).unwrap();
+    assert_eq!(v.row(0), [11, 12]);
+    assert_eq!(v.row(1), [21, 22]);
+    assert_eq!(v.get(1, 2), None);
+    assert_eq!(v.to_matrix().as_slice(), [11, 12, 21, 22]);
+    assert!(a.view(2, 0, 2, 1).is_err());
+}
+
+#[test]
+fn empty_view_at_the_edge() {
+    let a = Matrix::<i32>::zeros(3, 4);
+    assert_eq!(a.view(3, 1, 0, 0).unwrap().shape(), (0, 0));
+    assert_eq!(a.view(0, 4, 3, 0).unwrap().shape(), (3, 0));
+    assert!(a
+        .view(3, 1, 0, 0)
+        .unwrap()
+        .to_matrix()
+        .as_slice()
+        .is_empty());
+}
+
+#[test]
+fn fixed_size_matches_heap_matrix() {
+    let a = MatrixN::from_rows([[1, 2, 3], [4, 5, 6]]);
+    let b = MatrixN::from_rows([[7, 8], [9, 10], [11, 12]]);
+    let product = a * b;
+    assert_eq!(product, MatrixN::from_rows([[58, 64], [139, 154]]));
+    assert_eq!(
+        Matrix::from(product),
+        a.to_matrix().matmul(&b.to_matrix()).unwrap()
+    );
+    assert_eq!(a.transpose().rows(), &[[1, 4], [2, 5], [3, 6]]);
+    assert_eq!(a.mul_vec(&[1, 0, -1]), [-2, -2]);
+}
+
+#[test]
+fn fixed_size_elementwise_and_identity() {
+    let a = MatrixN::from_rows([[1.0, 2.0], [3.0, 4.0]]);
+    assert_eq!(a * MatrixN::identity(), a);
+    assert_eq!(a + a, a.scale(2.0));
+    assert_eq!(a - a, MatrixN::zeros());
+    let mut b = a;
+    b[(1, 0)] = 9.0;
+    assert_eq!(b[(1, 0)], 9.0);
+    assert_eq!(a[(1, 0)], 3.0);
+}
@@ -0,0 +1,14 @@
+// JSON-RPC 2.0 over a line-delimited transport
+//
+// Each line is one request (or a batch array) and each answer is one line,
+// so the same server works over a UART, a TCP socket or stdin/stdout. The
+// server parses and validates envelopes, dispatches to registered method
+// handlers and always answers with the standard error codes when it cannot.
+
+pub mod message;
+pub mod server;
+pub mod transport;
+
+pub use message::{Id, Reply, Request, Response, RpcError};
+pub use server::{parse_params, Params, Server};
+pub use transport::{serve, ServeStats};
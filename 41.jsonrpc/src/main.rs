@@ -0,0 +1,323 @@
+use jsonrpc::{parse_params, serve, Params, RpcError, Server};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Cursor};
+
+// Application error codes live in the -32000..-32099 server range
+const UNKNOWN_SENSOR: i64 = -32001;
+const UNKNOWN_KEY: i64 = -32002;
+
+// Deterministic pseudo-random noise in [-0.5, 0.5)
+struct Noise(u64);
+
+impl Noise {
+    fn next(&mut self) -> f64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+    }
+}
+
+struct Sensor {
+    unit: &'static str,
+    base: f64,
+    spread: f64,
+}
+
+struct Device {
+    name: String,
+    uptime_ms: u64,
+    sample_interval_ms: u64,
+    led: bool,
+    sensors: BTreeMap<&'static str, Sensor>,
+    noise: Noise,
+}
+
+impl Device {
+    fn new() -> Device {
+        let mut sensors = BTreeMap::new();
+        sensors.insert(
+            "temperature",
+            Sensor {
+                unit: "C",
+                base: 21.5,
+                spread: 0.4,
+            },
+        );
+        sensors.insert(
+            "humidity",
+            Sensor {
+                unit: "%",
+                base: 48.0,
+                spread: 2.0,
+            },
+        );
+        sensors.insert(
+            "battery",
+            Sensor {
+                unit: "V",
+                base: 3.7,
+                spread: 0.02,
+            },
+        );
+        Device {
+            name: "node-7".to_string(),
+            uptime_ms: 0,
+            sample_interval_ms: 1000,
+            led: false,
+            sensors,
+            noise: Noise(11),
+        }
+    }
+}
+
+// Accepts both {"sensor": "temperature"} and ["temperature"]
+#[derive(Deserialize)]
+struct ReadParams {
+    sensor: String,
+}
+
+#[derive(Deserialize)]
+struct SetParams {
+    key: String,
+    value: Value,
+}
+
+fn device_info(device: &mut Device, _: Params) -> Result<Value, RpcError> {
+    Ok(json!({
+        "name": device.name,
+        "firmware": env!("CARGO_PKG_VERSION"),
+        "uptime_ms": device.uptime_ms,
+        "sensors": device.sensors.keys().collect::<Vec<_>>(),
+    }))
+}
+
+fn sensor_read(device: &mut Device, params: Params) -> Result<Value, RpcError> {
+    let p: ReadParams = parse_params(params)?;
+    let noise = device.noise.next();
+    let sensor = device.sensors.get(p.sensor.as_str()).ok_or_else(|| {
+        RpcError::new(UNKNOWN_SENSOR, "Unknown sensor").with_data(Value::from(p.sensor.clone()))
+    })?;
+    let value = sensor.base + noise * sensor.spread;
+    Ok(json!({
+        "sensor": p.sensor,
+        "value": (value * 100.0).round() / 100.0,
+        "unit": sensor.unit,
+    }))
+}
+
+fn config_set(device: &mut Device, params: Params) -> Result<Value, RpcError> {
+    let p: SetParams = parse_params(params)?;
+    let old = match p.key.as_str() {
+        "sample_interval_ms" => {
+            let ms = p
+                .value
+                .as_u64()
+                .filter(|ms| (100..=60_000).contains(ms))
+                .ok_or_else(|| {
+                    RpcError::invalid_params("sample_interval_ms must be 100..=60000")
+                })?;
+            Value::from(std::mem::replace(&mut device.sample_interval_ms, ms))
+        }
+        "led" => {
+            let on = p
+                .value
+                .as_bool()
+                .ok_or_else(|| RpcError::invalid_params("led must be true or false"))?;
+            Value::from(std::mem::replace(&mut device.led, on))
+        }
+        "name" => {
+            let name = p
+                .value
+                .as_str()
+                .filter(|n| !n.is_empty() && n.len() <= 32)
+                .ok_or_else(|| RpcError::invalid_params("name must be 1-32 characters"))?;
+            Value::from(std::mem::replace(&mut device.name, name.to_string()))
+        }
+        _ => {
+            return Err(
+                RpcError::new(UNKNOWN_KEY, "Unknown config key").with_data(Value::from(p.key))
+            )
+        }
+    };
+    Ok(json!({ "key": p.key, "old": old, "new": p.value }))
+}
+
+fn device_server() -> Server<Device> {
+    let mut server = Server::new(Device::new());
+    server.register("device.info", device_info);
+    server.register("sensor.read", sensor_read);
+    server.register("config.set", config_set);
+    server
+}
+
+// Send one line and show the exchange
+fn call(server: &mut Server<Device>, line: &str) -> Option<String> {
+    server.state_mut().uptime_ms += 250;
+    println!("   --> {}", line);
+    let response = server.handle_line(line);
+    match &response {
+        Some(r) => println!("   <-- {}", r),
+        None => println!("   <-- (no response)"),
+    }
+    response
+}
+
+fn main() {
+    // `cargo run -- --stdio` serves requests from stdin until end of input
+    if std::env::args().nth(1).as_deref() == Some("--stdio") {
+        let mut server = device_server();
+        eprintln!("jsonrpc: serving on stdin/stdout, one request per line");
+        match serve(&mut server, io::stdin().lock(), io::stdout().lock()) {
+            Ok(stats) => eprintln!("jsonrpc: {:?}", stats),
+            Err(e) => eprintln!("jsonrpc: {}", e),
+        }
+        return;
+    }
+
+    println!("=== JSON-RPC 2.0 Examples ===\n");
+    let mut server = device_server();
+    println!(
+        "   methods: {}",
+        server.methods().collect::<Vec<_>>().join(", ")
+    );
+
+    // 1. Method calls
+    println!("\n1. Calls:");
+    call(
+        &mut server,
+        r#"{"jsonrpc":"2.0","method":"device.info","id":1}"#,
+    );
+    call(
+        &mut server,
+        r#"{"jsonrpc":"2.0","method":"sensor.read","params":{"sensor":"temperature"},"id":2}"#,
+    );
+    call(
+        &mut server,
+        r#"{"jsonrpc":"2.0","method":"sensor.read","params":["humidity"],"id":"read-hum"}"#,
+    );
+    call(
+        &mut server,
+        r#"{"jsonrpc":"2.0","method":"config.set","params":{"key":"sample_interval_ms","value":250},"id":3}"#,
+    );
+
+    // 2. Standard and application error codes
+    println!("\n2. Errors:");
+    for (expected, line) in [
+        (-32700, r#"{"jsonrpc":"2.0","method":"device.info","id":4"#),
+        (-32600, r#"{"jsonrpc":"1.0","method":"device.info","id":5}"#),
+        (-32600, r#"{"jsonrpc":"2.0","method":42,"id":6}"#),
+        (
+            -32600,
+            r#"{"jsonrpc":"2.0","method":"sensor.read","params":"temperature","id":7}"#,
+        ),
+        (
+            -32601,
+            r#"{"jsonrpc":"2.0","method":"device.reboot","id":8}"#,
+        ),
+        (
+            -32602,
+            r#"{"jsonrpc":"2.0","method":"sensor.read","params":{"name":"temperature"},"id":9}"#,
+        ),
+        (
+            -32602,
+            r#"{"jsonrpc":"2.0","method":"config.set","params":{"key":"led","value":"on"},"id":10}"#,
+        ),
+        (
+            -32602,
+            r#"{"jsonrpc":"2.0","method":"config.set","params":{"key":"sample_interval_ms","value":5},"id":11}"#,
+        ),
+        (
+            UNKNOWN_SENSOR,
+            r#"{"jsonrpc":"2.0","method":"sensor.read","params":["pressure"],"id":12}"#,
+        ),
+        (
+            UNKNOWN_KEY,
+            r#"{"jsonrpc":"2.0","method":"config.set","params":{"key":"wifi","value":1},"id":13}"#,
+        ),
+    ] {
+        let response = call(&mut server, line).unwrap_or_default();
+        let code =
+            serde_json::from_str::<Value>(&response).unwrap_or_default()["error"]["code"].as_i64();
+        println!("       code {:?}, expected {}", code, expected);
+    }
+
+    // 3. Notifications run but are never answered, even on error
+    println!("\n3. Notifications:");
+    call(
+        &mut server,
+        r#"{"jsonrpc":"2.0","method":"config.set","params":{"key":"led","value":true}}"#,
+    );
+    call(&mut server, r#"{"jsonrpc":"2.0","method":"device.reboot"}"#);
+    println!("   led is now {}", server.state().led);
+
+    // 4. Batches
+    println!("\n4. Batches:");
+    let batch = json!([
+        {"jsonrpc": "2.0", "method": "sensor.read", "params": ["temperature"], "id": 20},
+        {"jsonrpc": "2.0", "method": "config.set", "params": {"key": "name", "value": "node-7b"}},
+        {"jsonrpc": "2.0", "method": "sensor.read", "params": ["battery"], "id": "b"},
+        1,
+        {"jsonrpc": "2.0", "method": "device.info", "id": 21},
+    ]);
+    let response = call(&mut server, &batch.to_string()).unwrap_or_default();
+
+    // The client correlates by id: responses may come back in any order
+    let mut pending: HashMap<String, &str> = HashMap::new();
+    pending.insert("20".into(), "sensor.read temperature");
+    pending.insert("\"b\"".into(), "sensor.read battery");
+    pending.insert("21".into(), "device.info");
+    let responses: Vec<Value> = serde_json::from_str(&response).unwrap_or_default();
+    println!(
+        "   {} requests -> {} responses",
+        batch.as_array().map_or(0, |b| b.len()),
+        responses.len()
+    );
+    for r in responses.iter().rev() {
+        let id = r["id"].to_string();
+        match pending.remove(&id) {
+            Some(what) => println!("   id {:<4} -> {}: {}", id, what, r["result"]),
+            None => println!("   id {:<4} -> uncorrelated: {}", id, r["error"]["message"]),
+        }
+    }
+    println!("   still pending: {}", pending.len());
+    call(&mut server, "[]");
+    call(
+        &mut server,
+        r#"[{"jsonrpc":"2.0","method":"config.set","params":{"key":"led","value":false}}]"#,
+    );
+
+    // 5. Line transport
+    println!("\n5. Line transport (in-memory UART):");
+    let mut input = String::new();
+    input.push_str("{\"jsonrpc\":\"2.0\",\"method\":\"device.info\",\"id\":1}\r\n");
+    input.push_str("\r\n");
+    input.push_str(
+        "{\"jsonrpc\":\"2.0\",\"method\":\"sensor.read\",\"params\":[\"battery\"],\"id\":2}\n",
+    );
+    input.push_str(&format!("\"{}\"\n", "x".repeat(70_000)));
+    input.push_str("{\"jsonrpc\":\"2.0\",\"method\":\"config.set\",\"params\":{\"key\":\"led\",\"value\":true}}\n");
+    input.push_str(
+        "{\"jsonrpc\":\"2.0\",\"method\":\"sensor.read\",\"params\":[\"humidity\"],\"id\":3}",
+    );
+    let mut output = Vec::new();
+    match serve(&mut server, Cursor::new(input.as_bytes()), &mut output) {
+        Ok(stats) => {
+            println!("   rx {} bytes, tx {} bytes", input.len(), output.len());
+            for line in String::from_utf8_lossy(&output).lines() {
+                println!("   <-- {}", line);
+            }
+            println!(
+                "   {} line(s), {} response(s), {} oversized",
+                stats.lines, stats.responses, stats.oversized
+            );
+        }
+        Err(e) => println!("   transport error: {}", e),
+    }
+    println!("   try it: echo '{{\"jsonrpc\":\"2.0\",\"method\":\"device.info\",\"id\":1}}' | cargo run -q -- --stdio");
+
+    println!("\n=== End of JSON-RPC 2.0 Examples ===");
+}
@@ -0,0 +1,180 @@
+// Request, response and error envelopes
+//
+//   --> {"jsonrpc":"2.0","method":"sensor.read","params":{"sensor":"temp"},"id":7}
+//   <-- {"jsonrpc":"2.0","result":{"value":21.5},"id":7}
+//
+// A request without an "id" member is a notification and gets no response.
+// Requests are validated by hand from a `serde_json::Value` rather than
+// derived, so that each malformed envelope maps to the right error code.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+// Request ids may be numbers, strings or (discouraged) null
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Id {
+    Number(i64),
+    String(String),
+    Null,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+    // -32000 to -32099 are reserved for implementation-defined server errors
+
+    pub fn new(code: i64, message: &str) -> RpcError {
+        RpcError {
+            code,
+            message: message.to_string(),
+            data: None,
+        }
+    }
+
+    pub fn with_data(mut self, data: Value) -> RpcError {
+        self.data = Some(data);
+        self
+    }
+
+    pub fn parse_error(detail: &str) -> RpcError {
+        RpcError::new(Self::PARSE_ERROR, "Parse error").with_data(Value::from(detail))
+    }
+
+    pub fn invalid_request(detail: &str) -> RpcError {
+        RpcError::new(Self::INVALID_REQUEST, "Invalid Request").with_data(Value::from(detail))
+    }
+
+    pub fn method_not_found(method: &str) -> RpcError {
+        RpcError::new(Self::METHOD_NOT_FOUND, "Method not found").with_data(Value::from(method))
+    }
+
+    pub fn invalid_params(detail: &str) -> RpcError {
+        RpcError::new(Self::INVALID_PARAMS, "Invalid params").with_data(Value::from(detail))
+    }
+
+    pub fn internal(detail: &str) -> RpcError {
+        RpcError::new(Self::INTERNAL_ERROR, "Internal error").with_data(Value::from(detail))
+    }
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)?;
+        if let Some(data) = &self.data {
+            write!(f, ": {}", data)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    // Always an array or an object when present
+    pub params: Option<Value>,
+    // `None` for notifications
+    pub id: Option<Id>,
+}
+
+impl Request {
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
+    }
+
+    // Validate one envelope. On failure the error comes with the id to answer
+    // with, which is null when the id itself could not be read.
+    pub fn from_value(value: Value) -> Result<Request, (Id, RpcError)> {
+        let Value::Object(mut obj) = value else {
+            return Err((Id::Null, RpcError::invalid_request("not an object")));
+        };
+
+        let id = match obj.remove("id") {
+            None => None,
+            Some(Value::Null) => Some(Id::Null),
+            Some(Value::String(s)) => Some(Id::String(s)),
+            Some(Value::Number(n)) => match n.as_i64() {
+                Some(n) => Some(Id::Number(n)),
+                None => return Err((Id::Null, RpcError::invalid_request("id must be an integer"))),
+            },
+            Some(_) => {
+                return Err((
+                    Id::Null,
+                    RpcError::invalid_request("id must be a string or number"),
+                ))
+            }
+        };
+        let fail = |detail: &str| {
+            (
+                id.clone().unwrap_or(Id::Null),
+                RpcError::invalid_request(detail),
+            )
+        };
+
+        if obj.get("jsonrpc") != Some(&Value::from("2.0")) {
+            return Err(fail("jsonrpc must be \"2.0\""));
+        }
+        let method = match obj.remove("method") {
+            Some(Value::String(m)) => m,
+            _ => return Err(fail("method must be a string")),
+        };
+        let params = match obj.remove("params") {
+            None => None,
+            Some(p @ (Value::Array(_) | Value::Object(_))) => Some(p),
+            Some(_) => return Err(fail("params must be an array or object")),
+        };
+        Ok(Request { method, params, id })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Response {
+    pub jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+    pub id: Id,
+}
+
+impl Response {
+    pub fn success(id: Id, result: Value) -> Response {
+        Response {
+            jsonrpc: "2.0",
+            result: Some(result),
+            error: None,
+            id,
+        }
+    }
+
+    pub fn failure(id: Id, error: RpcError) -> Response {
+        Response {
+            jsonrpc: "2.0",
+            result: None,
+            error: Some(error),
+            id,
+        }
+    }
+}
+
+// What goes back for one line: a single response or a batch array
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Reply {
+    Single(Response),
+    Batch(Vec<Response>),
+}
@@ -0,0 +1,106 @@
+// Method registry and dispatch
+//
+// `Server<S>` owns the device state `S` and a table of handlers, each of
+// which gets `&mut S` and the request params. Handlers return a plain
+// `Result`; the server wraps it in a response envelope with the request id,
+// or drops it if the request was a notification.
+
+use crate::message::{Id, Reply, Request, Response, RpcError};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+pub type Params<'a> = Option<&'a Value>;
+
+type Handler<S> = Box<dyn Fn(&mut S, Params) -> Result<Value, RpcError>>;
+
+// Deserialize params into `T`, reporting failures as -32602
+pub fn parse_params<T: DeserializeOwned>(params: Params) -> Result<T, RpcError> {
+    let value = params.cloned().unwrap_or(Value::Null);
+    serde_json::from_value(value).map_err(|e| RpcError::invalid_params(&e.to_string()))
+}
+
+pub struct Server<S> {
+    state: S,
+    methods: BTreeMap<String, Handler<S>>,
+}
+
+impl<S> Server<S> {
+    pub fn new(state: S) -> Server<S> {
+        Server {
+            state,
+            methods: BTreeMap::new(),
+        }
+    }
+
+    pub fn register<F>(&mut self, method: &str, handler: F)
+    where
+        F: Fn(&mut S, Params) -> Result<Value, RpcError> + 'static,
+    {
+        self.methods.insert(method.to_string(), Box::new(handler));
+    }
+
+    pub fn methods(&self) -> impl Iterator<Item = &str> {
+        self.methods.keys().map(|m| m.as_str())
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut S {
+        &mut self.state
+    }
+
+    // Handle one request or batch; `None` means nothing is sent back
+    pub fn handle_value(&mut self, value: Value) -> Option<Reply> {
+        match value {
+            Value::Array(items) if items.is_empty() => Some(Reply::Single(Response::failure(
+                Id::Null,
+                RpcError::invalid_request("empty batch"),
+            ))),
+            Value::Array(items) => {
+                let responses: Vec<Response> = items
+                    .into_iter()
+                    .filter_map(|item| self.handle_single(item))
+                    .collect();
+                // A batch of notifications gets no response at all, not `[]`
+                if responses.is_empty() {
+                    None
+                } else {
+                    Some(Reply::Batch(responses))
+                }
+            }
+            single => self.handle_single(single).map(Reply::Single),
+        }
+    }
+
+    // Handle one line of input and produce one line of output (no newline)
+    pub fn handle_line(&mut self, line: &str) -> Option<String> {
+        let reply = match serde_json::from_str::<Value>(line) {
+            Ok(value) => self.handle_value(value)?,
+            Err(e) => Reply::Single(Response::failure(
+                Id::Null,
+                RpcError::parse_error(&e.to_string()),
+            )),
+        };
+        serde_json::to_string(&reply).ok()
+    }
+
+    fn handle_single(&mut self, value: Value) -> Option<Response> {
+        let request = match Request::from_value(value) {
+            Ok(request) => request,
+            Err((id, error)) => return Some(Response::failure(id, error)),
+        };
+        let result = match self.methods.get(&request.method) {
+            Some(handler) => handler(&mut self.state, request.params.as_ref()),
+            None => Err(RpcError::method_not_found(&request.method)),
+        };
+        // Notifications ran the handler but are never answered, even on error
+        let id = request.id?;
+        Some(match result {
+            Ok(value) => Response::success(id, value),
+            Err(error) => Response::failure(id, error),
+        })
+    }
+}
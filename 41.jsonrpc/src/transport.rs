@@ -0,0 +1,63 @@
+// Line-delimited framing over any byte stream
+//
+// Compact JSON never contains a raw newline (newlines inside strings are
+// escaped), so '\n' is a safe frame delimiter. The loop is generic over
+// `BufRead` and `Write`, which covers stdin/stdout, a TcpStream, a serial
+// port handle or an in-memory buffer in a demo.
+
+use crate::message::{Id, Response, RpcError};
+use crate::server::Server;
+use std::io::{self, BufRead, Read, Write};
+
+// Longer lines are answered with a parse error and skipped without ever
+// being held in memory whole
+pub const MAX_LINE: usize = 64 * 1024;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ServeStats {
+    pub lines: u64,
+    pub responses: u64,
+    pub oversized: u64,
+}
+
+// Serve until the reader reaches end of input
+pub fn serve<S, R: BufRead, W: Write>(
+    server: &mut Server<S>,
+    mut reader: R,
+    mut writer: W,
+) -> io::Result<ServeStats> {
+    let mut stats = ServeStats::default();
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        let limit = MAX_LINE as u64 + 1;
+        if reader.by_ref().take(limit).read_until(b'\n', &mut buf)? == 0 {
+            return Ok(stats);
+        }
+        stats.lines += 1;
+
+        let response = if buf.len() > MAX_LINE {
+            if buf.last() != Some(&b'\n') {
+                reader.skip_until(b'\n')?;
+            }
+            stats.oversized += 1;
+            let error = RpcError::parse_error(&format!("line longer than {} bytes", MAX_LINE));
+            serde_json::to_string(&Response::failure(Id::Null, error)).ok()
+        } else {
+            let line = String::from_utf8_lossy(&buf);
+            let line = line.trim();
+            // Blank lines (a bare "\r\n" from a terminal) are ignored
+            if line.is_empty() {
+                continue;
+            }
+            server.handle_line(line)
+        };
+
+        if let Some(response) = response {
+            writer.write_all(response.as_bytes())?;
+            writer.write_all(b"\n")?;
+            writer.flush()?;
+            stats.responses += 1;
+        }
+    }
+}
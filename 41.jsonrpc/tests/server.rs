@@ -0,0 +1,179 @@
+// The server against a small device of its own: every error code the
+// demo's table shows, notifications and batches
+
+use jsonrpc::{parse_params, RpcError, Server};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+const UNKNOWN_KEY: i64 = -32002;
+
+#[derive(Default)]
+struct Device {
+    led: bool,
+    calls: u32,
+}
+
+#[derive(Deserialize)]
+struct SetParams {
+    key: String,
+    value: Value,
+}
+
+fn server() -> Server<Device> {
+    let mut server = Server::new(Device::default());
+    server.register("device.ping", |device: &mut Device, _| {
+        device.calls += 1;
+        Ok(json!("pong"))
+    });
+    server.register("config.set", |device: &mut Device, params| {
+        device.calls += 1;
+        let p: SetParams = parse_params(params)?;
+        if p.key != "led" {
+            return Err(RpcError::new(UNKNOWN_KEY, "Unknown config key").with_data(p.key.into()));
+        }
+        let on = p
+            .value
+            .as_bool()
+            .ok_or_else(|| RpcError::invalid_params("led must be true or false"))?;
+        Ok(Value::from(std::mem::replace(&mut device.led, on)))
+    });
+    server
+}
+
+fn reply(server: &mut Server<Device>, line: &str) -> Value {
+    serde_json::from_str(&server.handle_line(line).unwrap()).unwrap()
+}
+
+#[test]
+fn a_call_echoes_its_id() {
+    let mut server = server();
+    assert_eq!(
+        reply(
+            &mut server,
+            r#"{"jsonrpc":"2.0","method":"device.ping","id":1}"#
+        ),
+        json!({"jsonrpc": "2.0", "result": "pong", "id": 1})
+    );
+    assert_eq!(
+        reply(
+            &mut server,
+            r#"{"jsonrpc":"2.0","method":"config.set","params":{"key":"led","value":true},"id":"set"}"#
+        ),
+        json!({"jsonrpc": "2.0", "result": false, "id": "set"})
+    );
+    assert!(server.state().led);
+}
+
+#[test]
+fn each_failure_has_its_own_code() {
+    let mut server = server();
+    for (expected, id, line) in [
+        (
+            -32700,
+            Value::Null,
+            r#"{"jsonrpc":"2.0","method":"device.ping","id":4"#,
+        ),
+        (
+            -32600,
+            json!(5),
+            r#"{"jsonrpc":"1.0","method":"device.ping","id":5}"#,
+        ),
+        (-32600, json!(6), r#"{"jsonrpc":"2.0","method":42,"id":6}"#),
+        (
+            -32600,
+            json!(7),
+            r#"{"jsonrpc":"2.0","method":"device.ping","params":"x","id":7}"#,
+        ),
+        (
+            -32600,
+            Value::Null,
+            r#"{"jsonrpc":"2.0","method":"device.ping","id":1.5}"#,
+        ),
+        (
+            -32601,
+            json!(8),
+            r#"{"jsonrpc":"2.0","method":"device.reboot","id":8}"#,
+        ),
+        (
+            -32602,
+            json!(9),
+            r#"{"jsonrpc":"2.0","method":"config.set","params":{"name":"led"},"id":9}"#,
+        ),
+        (
+            -32602,
+            json!(10),
+            r#"{"jsonrpc":"2.0","method":"config.set","params":{"key":"led","value":"on"},"id":10}"#,
+        ),
+        (
+            UNKNOWN_KEY,
+            json!(11),
+            r#"{"jsonrpc":"2.0","method":"config.set","params":{"key":"wifi","value":1},"id":11}"#,
+        ),
+    ] {
+        let response = reply(&mut server, line);
+        assert_eq!(response["error"]["code"], expected, "{}", line);
+        assert_eq!(response["id"], id, "{}", line);
+        assert!(response.get("result").is_none(), "{}", line);
+    }
+    // Envelope failures never reach a handler
+    assert_eq!(server.state().calls, 3);
+}
+
+#[test]
+fn notifications_run_but_are_never_answered() {
+    let mut server = server();
+    let line = r#"{"jsonrpc":"2.0","method":"config.set","params":{"key":"led","value":true}}"#;
+    assert_eq!(server.handle_line(line), None);
+    assert!(server.state().led);
+    // Not even when they fail
+    assert_eq!(
+        server.handle_line(r#"{"jsonrpc":"2.0","method":"device.reboot"}"#),
+        None
+    );
+    // An id of null is still a call
+    let response = reply(
+        &mut server,
+        r#"{"jsonrpc":"2.0","method":"device.ping","id":null}"#,
+    );
+    assert_eq!(response["result"], "pong");
+    assert_eq!(response["id"], Value::Null);
+}
+
+#[test]
+fn a_batch_answers_every_call_and_no_notification() {
+    let mut server = server();
+    let batch = json!([
+        {"jsonrpc": "2.0", "method": "device.ping", "id": 20},
+        {"jsonrpc": "2.0", "method": "config.set", "params": {"key": "led", "value": true}},
+        {"jsonrpc": "2.0", "method": "device.ping", "id": "b"},
+        1,
+        {"jsonrpc": "2.0", "method": "device.reboot", "id": 21},
+    ]);
+    let responses = reply(&mut server, &batch.to_string());
+    let ids: Vec<&Value> = responses
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| &r["id"])
+        .collect();
+    assert_eq!(ids, [&json!(20), &json!("b"), &Value::Null, &json!(21)]);
+    assert_eq!(responses[2]["error"]["code"], -32600);
+    assert_eq!(responses[3]["error"]["code"], -32601);
+    assert_eq!(server.state().calls, 3);
+    assert!(server.state().led);
+}
+
+#[test]
+fn an_empty_batch_is_invalid_and_a_batch_of_notifications_is_silent() {
+    let mut server = server();
+    let response = reply(&mut server, "[]");
+    assert_eq!(response["error"]["code"], -32600);
+    assert_eq!(response["id"], Value::Null);
+    assert_eq!(
+        server.handle_line(
+            r#"[{"jsonrpc":"2.0","method":"config.set","params":{"key":"led","value":true}}]"#
+        ),
+        None
+    );
+    assert!(server.state().led);
+}
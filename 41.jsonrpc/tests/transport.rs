@@ -0,0 +1,86 @@
+// `serve` over in-memory buffers standing in for a UART
+
+use jsonrpc::transport::MAX_LINE;
+use jsonrpc::{serve, ServeStats, Server};
+use serde_json::{json, Value};
+use std::io::Cursor;
+
+fn server() -> Server<u32> {
+    let mut server = Server::new(0);
+    server.register("counter.bump", |count: &mut u32, _| {
+        *count += 1;
+        Ok(Value::from(*count))
+    });
+    server
+}
+
+fn run(input: &[u8]) -> (ServeStats, Vec<Value>) {
+    let mut server = server();
+    let mut output = Vec::new();
+    let stats = serve(&mut server, Cursor::new(input), &mut output).unwrap();
+    let text = String::from_utf8(output).unwrap();
+    assert!(text.is_empty() || text.ends_with('\n'));
+    let responses = text
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    (stats, responses)
+}
+
+#[test]
+fn one_line_in_one_line_out() {
+    let mut input = String::new();
+    input.push_str("{\"jsonrpc\":\"2.0\",\"method\":\"counter.bump\",\"id\":1}\r\n");
+    input.push_str("\r\n");
+    input.push_str("{\"jsonrpc\":\"2.0\",\"method\":\"counter.bump\"}\n");
+    // The last line may end without a newline
+    input.push_str("{\"jsonrpc\":\"2.0\",\"method\":\"counter.bump\",\"id\":2}");
+    let (stats, responses) = run(input.as_bytes());
+    assert_eq!(
+        stats,
+        ServeStats {
+            lines: 4,
+            responses: 2,
+            oversized: 0,
+        }
+    );
+    assert_eq!(
+        responses,
+        [
+            json!({"jsonrpc": "2.0", "result": 1, "id": 1}),
+            json!({"jsonrpc": "2.0", "result": 3, "id": 2}),
+        ]
+    );
+}
+
+#[test]
+fn an_oversized_line_is_answered_and_skipped() {
+    let mut input = format!("\"{}\"\n", "x".repeat(MAX_LINE + 10)).into_bytes();
+    input.extend_from_slice(b"{\"jsonrpc\":\"2.0\",\"method\":\"counter.bump\",\"id\":1}\n");
+    let (stats, responses) = run(&input);
+    assert_eq!(stats.lines, 2);
+    assert_eq!(stats.oversized, 1);
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["error"]["code"], -32700);
+    assert_eq!(responses[0]["id"], Value::Null);
+    // The rest of the long line is not read as a request of its own
+    assert_eq!(responses[1]["result"], 1);
+}
+
+#[test]
+fn a_line_of_exactly_max_line_bytes_is_served() {
+    let call = "{\"jsonrpc\":\"2.0\",\"method\":\"counter.bump\",\"id\":1}";
+    let mut line = format!("{}{}", call, " ".repeat(MAX_LINE - call.len() - 1));
+    line.push('\n');
+    assert_eq!(line.len(), MAX_LINE);
+    let (stats, responses) = run(line.as_bytes());
+    assert_eq!(stats.oversized, 0);
+    assert_eq!(responses[0]["result"], 1);
+}
+
+#[test]
+fn empty_input_serves_nothing() {
+    let (stats, responses) = run(b"");
+    assert_eq!(stats, ServeStats::default());
+    assert!(responses.is_empty());
+}
@@ -0,0 +1,32 @@
+// Build steps that run before the crate compiles:
+// 1. cbindgen reads src/ and writes the C header, include/ffi_export.h
+// 2. cc compiles the C demo program against that header; the demo binary
+//    links it so `cargo run` can show C calling the exported functions
+
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let header = crate_dir.join("include").join("ffi_export.h");
+
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("cbindgen.toml is readable");
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("header generation failed")
+        .write_to_file(&header);
+
+    cc::Build::new()
+        .file(crate_dir.join("c").join("demo.c"))
+        .include(crate_dir.join("include"))
+        .warnings(true)
+        .extra_warnings(true)
+        .compile("ffi_c_demo");
+
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=c/demo.c");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+}
@@ -0,0 +1,83 @@
+/* C side of the FFI lesson: uses only what include/ffi_export.h declares.
+ * Returns the number of failed checks. */
+
+#include <math.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "ffi_export.h"
+
+static int failures = 0;
+
+static void check(const char *what, int ok) {
+    printf("   C: %-44s %s\n", what, ok ? "ok" : "MISMATCH");
+    if (!ok) {
+        failures++;
+    }
+}
+
+int ffi_c_demo(void) {
+    /* Values by value: Rectangle is a plain C struct */
+    Rectangle rect = shapes_rectangle_new(3.0, 4.0);
+    check("rectangle 3x4 area == 12", shapes_rectangle_area(rect) == 12.0);
+    check("rectangle 3x4 perimeter == 14", shapes_rectangle_perimeter(rect) == 14.0);
+    check("square(5) is square", shapes_rectangle_is_square(shapes_rectangle_square(5.0)));
+    check("3x4 can fit 2x4", shapes_rectangle_can_fit(rect, 2.0, 4.0));
+
+    /* Out-pointer with a status code */
+    check("resize(&rect, 6, 6) == OK", shapes_rectangle_resize(&rect, 6.0, 6.0) == FFI_STATUS_OK);
+    check("resize(NULL, ...) == NULL_POINTER",
+          shapes_rectangle_resize(NULL, 1.0, 1.0) == FFI_STATUS_NULL_POINTER);
+    check("resize(&rect, -1, 2) == INVALID_ARGUMENT",
+          shapes_rectangle_resize(&rect, -1.0, 2.0) == FFI_STATUS_INVALID_ARGUMENT);
+
+    /* Owned object: create, use, destroy */
+    Polygon *poly = shapes_polygon_new();
+    Point corners[] = {{0.0, 0.0}, {4.0, 0.0}, {4.0, 3.0}, {0.0, 3.0}};
+    for (size_t i = 0; i < sizeof corners / sizeof corners[0]; i++) {
+        shapes_polygon_push(poly, corners[i]);
+    }
+    check("polygon has 4 points", shapes_polygon_len(poly) == 4);
+    check("polygon area == 12", shapes_polygon_area(poly) == 12.0);
+    const Point *points = shapes_polygon_points(poly);
+    check("borrowed points[2] == (4, 3)", points[2].x == 4.0 && points[2].y == 3.0);
+    Rectangle bounds;
+    check("bounds == 4x3", shapes_polygon_bounds(poly, &bounds) == FFI_STATUS_OK &&
+                               bounds.width == 4.0 && bounds.height == 3.0);
+    check("push(NaN) rejected",
+          shapes_polygon_push(poly, (Point){NAN, 0.0}) == FFI_STATUS_INVALID_ARGUMENT);
+    shapes_polygon_free(poly);
+    shapes_polygon_free(NULL); /* like free(NULL): allowed */
+    check("area(NULL) is NaN", isnan(shapes_polygon_area(NULL)));
+
+    /* Tagged union: build a Color variant in C */
+    Color hsv = {.tag = COLOR_HSV, .HSV = {.h = 200, .s = 80, .v = 90}};
+    Rgb rgb = color_to_rgb(hsv);
+    check("HSV(200, 80, 90) -> RGB(46, 168, 230)", rgb.r == 46 && rgb.g == 168 && rgb.b == 230);
+    Color red = {.tag = COLOR_RED};
+    check("Red -> RGB(255, 0, 0)", color_to_rgb(red).r == 255);
+
+    /* Caller-owned buffer, snprintf style */
+    char small[6];
+    size_t needed = color_name(hsv, small, sizeof small);
+    check("name truncated to \"HSV(2\", needs 16", strcmp(small, "HSV(2") == 0 && needed == 16);
+    char big[32];
+    color_name(hsv, big, sizeof big);
+    check("name == \"HSV(200, 80, 90)\"", strcmp(big, "HSV(200, 80, 90)") == 0);
+
+    /* Rust-owned string: must go back to Rust to be freed */
+    char *text = color_describe((Color){.tag = COLOR_RGB, .RGB = {._0 = 255, ._1 = 128, ._2 = 0}});
+    check("describe == \"RGB(255, 128, 0) = #FF8000\"",
+          text != NULL && strcmp(text, "RGB(255, 128, 0) = #FF8000") == 0);
+    color_string_free(text);
+
+    Rgb parsed;
+    check("parse \"#1E90FF\"", color_parse_hex("#1E90FF", &parsed) == FFI_STATUS_OK &&
+                                  parsed.r == 0x1E && parsed.g == 0x90 && parsed.b == 0xFF);
+    check("parse \"#12345\" rejected",
+          color_parse_hex("#12345", &parsed) == FFI_STATUS_INVALID_ARGUMENT);
+
+    printf("   C: library version %s, %d failure(s)\n", ffi_export_version(), failures);
+    fflush(stdout);
+    return failures;
+}
@@ -0,0 +1,12 @@
+/* Standalone entry point for running the C demo against the shared library:
+ *
+ *   cargo build
+ *   cc c/main.c c/demo.c -Iinclude -Ltarget/debug -lffi_export -lm -o demo
+ *   LD_LIBRARY_PATH=target/debug ./demo
+ */
+
+int ffi_c_demo(void);
+
+int main(void) {
+    return ffi_c_demo() == 0 ? 0 : 1;
+}
@@ -0,0 +1,167 @@
+#ifndef FFI_EXPORT_H
+#define FFI_EXPORT_H
+
+/* Generated by cbindgen from src/ffi.rs - do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum FfiStatus {
+  FFI_STATUS_OK = 0,
+  FFI_STATUS_NULL_POINTER = 1,
+  FFI_STATUS_INVALID_ARGUMENT = 2,
+} FfiStatus;
+
+typedef struct Polygon Polygon;
+
+typedef struct Rectangle {
+  double width;
+  double height;
+} Rectangle;
+
+typedef struct Point {
+  double x;
+  double y;
+} Point;
+
+typedef struct Rgb {
+  uint8_t r;
+  uint8_t g;
+  uint8_t b;
+} Rgb;
+
+typedef enum Color_Tag {
+  COLOR_RED,
+  COLOR_GREEN,
+  COLOR_BLUE,
+  COLOR_RGB,
+  COLOR_HSV,
+} Color_Tag;
+
+typedef struct Color_Rgb_Body {
+  uint8_t _0;
+  uint8_t _1;
+  uint8_t _2;
+} Color_Rgb_Body;
+
+typedef struct Color_Hsv_Body {
+  uint16_t h;
+  uint8_t s;
+  uint8_t v;
+} Color_Hsv_Body;
+
+typedef struct Color {
+  Color_Tag tag;
+  union {
+    Color_Rgb_Body RGB;
+    Color_Hsv_Body HSV;
+  };
+} Color;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+const char *ffi_export_version(void);
+
+struct Rectangle shapes_rectangle_new(double width, double height);
+
+struct Rectangle shapes_rectangle_square(double size);
+
+double shapes_rectangle_area(struct Rectangle rect);
+
+double shapes_rectangle_perimeter(struct Rectangle rect);
+
+bool shapes_rectangle_is_square(struct Rectangle rect);
+
+bool shapes_rectangle_can_fit(struct Rectangle rect, double width, double height);
+
+/**
+ * # Safety
+ *
+ * `rect` must be null or point to a valid, writable `Rectangle`.
+ */
+enum FfiStatus shapes_rectangle_resize(struct Rectangle *rect, double width, double height);
+
+struct Polygon *shapes_polygon_new(void);
+
+/**
+ * # Safety
+ *
+ * `polygon` must be null or a pointer from `shapes_polygon_new` that has
+ * not been freed yet. It must not be used afterwards.
+ */
+void shapes_polygon_free(struct Polygon *polygon);
+
+/**
+ * # Safety
+ *
+ * `polygon` must be null or a live pointer from `shapes_polygon_new`.
+ */
+enum FfiStatus shapes_polygon_push(struct Polygon *polygon, struct Point point);
+
+/**
+ * # Safety
+ *
+ * `polygon` must be null or a live pointer from `shapes_polygon_new`.
+ */
+size_t shapes_polygon_len(const struct Polygon *polygon);
+
+/**
+ * # Safety
+ *
+ * `polygon` must be null or a live pointer from `shapes_polygon_new`.
+ */
+const struct Point *shapes_polygon_points(const struct Polygon *polygon);
+
+/**
+ * # Safety
+ *
+ * `polygon` must be null or a live pointer from `shapes_polygon_new`.
+ */
+double shapes_polygon_area(const struct Polygon *polygon);
+
+/**
+ * # Safety
+ *
+ * `polygon` must be null or a live pointer from `shapes_polygon_new`;
+ * `out` must be null or point to a writable `Rectangle`.
+ */
+enum FfiStatus shapes_polygon_bounds(const struct Polygon *polygon, struct Rectangle *out);
+
+struct Rgb color_to_rgb(struct Color color);
+
+/**
+ * # Safety
+ *
+ * `buf` must point to `capacity` writable bytes, or be null if `capacity`
+ * is 0.
+ */
+size_t color_name(struct Color color, char *buf, size_t capacity);
+
+char *color_describe(struct Color color);
+
+/**
+ * # Safety
+ *
+ * `text` must be null or a pointer from `color_describe` that has not
+ * been freed yet.
+ */
+void color_string_free(char *text);
+
+/**
+ * # Safety
+ *
+ * `text` must be null or a NUL-terminated string; `out` must be null or
+ * point to a writable `Rgb`.
+ */
+enum FfiStatus color_parse_hex(const char *text, struct Rgb *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* FFI_EXPORT_H */
@@ -0,0 +1,90 @@
+// Colors from the enum lesson (05.enum), made C-compatible
+//
+// A `#[repr(C)]` enum with data is laid out as a C tagged union: an integer
+// tag followed by a union of one struct per variant. cbindgen writes that
+// out as `Color_Tag` plus `Color`, so C can build and match on the same
+// values Rust does.
+
+use std::fmt;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Blue,
+    Rgb(u8, u8, u8),
+    // Hue in degrees (0-359), saturation and value in percent
+    Hsv { h: u16, s: u8, v: u8 },
+}
+
+impl Color {
+    pub fn to_rgb(&self) -> Rgb {
+        match *self {
+            Color::Red => Rgb { r: 255, g: 0, b: 0 },
+            Color::Green => Rgb { r: 0, g: 255, b: 0 },
+            Color::Blue => Rgb { r: 0, g: 0, b: 255 },
+            Color::Rgb(r, g, b) => Rgb { r, g, b },
+            Color::Hsv { h, s, v } => hsv_to_rgb(h, s, v),
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Color::Red => write!(f, "Red"),
+            Color::Green => write!(f, "Green"),
+            Color::Blue => write!(f, "Blue"),
+            Color::Rgb(r, g, b) => write!(f, "RGB({}, {}, {})", r, g, b),
+            Color::Hsv { h, s, v } => write!(f, "HSV({}, {}, {})", h, s, v),
+        }
+    }
+}
+
+impl Rgb {
+    // "#RRGGBB" or "RRGGBB"
+    pub fn parse_hex(text: &str) -> Option<Rgb> {
+        let hex = text.strip_prefix('#').unwrap_or(text);
+        if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Rgb {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        })
+    }
+}
+
+fn hsv_to_rgb(h: u16, s: u8, v: u8) -> Rgb {
+    let h = (h % 360) as f64;
+    let s = s.min(100) as f64 / 100.0;
+    let v = v.min(100) as f64 / 100.0;
+    let c = v * s;
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let m = v - c;
+    let (r, g, b) = match (h / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let to_u8 = |channel: f64| ((channel + m) * 255.0).round() as u8;
+    Rgb {
+        r: to_u8(r),
+        g: to_u8(g),
+        b: to_u8(b),
+    }
+}
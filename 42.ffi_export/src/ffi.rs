@@ -0,0 +1,227 @@
+// The C ABI surface
+//
+// Rules followed by every function here:
+// - `#[no_mangle] extern "C"` so the symbol name and calling convention are
+//   exactly what the generated header declares.
+// - Small `#[repr(C)]` values (`Rectangle`, `Point`, `Color`, `Rgb`) travel
+//   by value; anything owning heap memory is an opaque pointer with an
+//   explicit `_new` / `_free` pair, and memory is freed by the side that
+//   allocated it.
+// - Null pointers are checked, never trusted; failures are reported with
+//   `FfiStatus` or a documented sentinel value, never with a panic.
+
+use crate::color::{Color, Rgb};
+use crate::shapes::{Point, Polygon, Rectangle};
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FfiStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidArgument = 2,
+}
+
+// NUL-terminated, lives as long as the library is loaded
+#[no_mangle]
+pub extern "C" fn ffi_export_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+// ---- Rectangle: plain values ----
+
+#[no_mangle]
+pub extern "C" fn shapes_rectangle_new(width: f64, height: f64) -> Rectangle {
+    Rectangle::new(width, height)
+}
+
+#[no_mangle]
+pub extern "C" fn shapes_rectangle_square(size: f64) -> Rectangle {
+    Rectangle::square(size)
+}
+
+#[no_mangle]
+pub extern "C" fn shapes_rectangle_area(rect: Rectangle) -> f64 {
+    rect.area()
+}
+
+#[no_mangle]
+pub extern "C" fn shapes_rectangle_perimeter(rect: Rectangle) -> f64 {
+    rect.perimeter()
+}
+
+#[no_mangle]
+pub extern "C" fn shapes_rectangle_is_square(rect: Rectangle) -> bool {
+    rect.is_square()
+}
+
+#[no_mangle]
+pub extern "C" fn shapes_rectangle_can_fit(rect: Rectangle, width: f64, height: f64) -> bool {
+    rect.can_fit(width, height)
+}
+
+/// # Safety
+///
+/// `rect` must be null or point to a valid, writable `Rectangle`.
+#[no_mangle]
+pub unsafe extern "C" fn shapes_rectangle_resize(
+    rect: *mut Rectangle,
+    width: f64,
+    height: f64,
+) -> FfiStatus {
+    let Some(rect) = rect.as_mut() else {
+        return FfiStatus::NullPointer;
+    };
+    if !(width >= 0.0 && height >= 0.0) {
+        return FfiStatus::InvalidArgument;
+    }
+    rect.resize(width, height);
+    FfiStatus::Ok
+}
+
+// ---- Polygon: an owned, opaque object ----
+
+// Never returns null; release with `shapes_polygon_free`
+#[no_mangle]
+pub extern "C" fn shapes_polygon_new() -> *mut Polygon {
+    Box::into_raw(Box::new(Polygon::new()))
+}
+
+/// # Safety
+///
+/// `polygon` must be null or a pointer from `shapes_polygon_new` that has
+/// not been freed yet. It must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn shapes_polygon_free(polygon: *mut Polygon) {
+    if !polygon.is_null() {
+        drop(Box::from_raw(polygon));
+    }
+}
+
+/// # Safety
+///
+/// `polygon` must be null or a live pointer from `shapes_polygon_new`.
+#[no_mangle]
+pub unsafe extern "C" fn shapes_polygon_push(polygon: *mut Polygon, point: Point) -> FfiStatus {
+    let Some(polygon) = polygon.as_mut() else {
+        return FfiStatus::NullPointer;
+    };
+    if !(point.x.is_finite() && point.y.is_finite()) {
+        return FfiStatus::InvalidArgument;
+    }
+    polygon.push(point);
+    FfiStatus::Ok
+}
+
+/// # Safety
+///
+/// `polygon` must be null or a live pointer from `shapes_polygon_new`.
+#[no_mangle]
+pub unsafe extern "C" fn shapes_polygon_len(polygon: *const Polygon) -> usize {
+    polygon.as_ref().map_or(0, |p| p.points().len())
+}
+
+// Borrowed view of the vertices: valid until the next push or free
+/// # Safety
+///
+/// `polygon` must be null or a live pointer from `shapes_polygon_new`.
+#[no_mangle]
+pub unsafe extern "C" fn shapes_polygon_points(polygon: *const Polygon) -> *const Point {
+    polygon
+        .as_ref()
+        .map_or(ptr::null(), |p| p.points().as_ptr())
+}
+
+// NaN for a null polygon
+/// # Safety
+///
+/// `polygon` must be null or a live pointer from `shapes_polygon_new`.
+#[no_mangle]
+pub unsafe extern "C" fn shapes_polygon_area(polygon: *const Polygon) -> f64 {
+    polygon.as_ref().map_or(f64::NAN, |p| p.area())
+}
+
+/// # Safety
+///
+/// `polygon` must be null or a live pointer from `shapes_polygon_new`;
+/// `out` must be null or point to a writable `Rectangle`.
+#[no_mangle]
+pub unsafe extern "C" fn shapes_polygon_bounds(
+    polygon: *const Polygon,
+    out: *mut Rectangle,
+) -> FfiStatus {
+    let (Some(polygon), false) = (polygon.as_ref(), out.is_null()) else {
+        return FfiStatus::NullPointer;
+    };
+    match polygon.bounds() {
+        Some(bounds) => {
+            out.write(bounds);
+            FfiStatus::Ok
+        }
+        None => FfiStatus::InvalidArgument,
+    }
+}
+
+// ---- Color: a tagged union and two string ownership styles ----
+
+#[no_mangle]
+pub extern "C" fn color_to_rgb(color: Color) -> Rgb {
+    color.to_rgb()
+}
+
+// snprintf-style: writes at most `capacity - 1` bytes plus a NUL and
+// returns the full length, so a caller can retry with a bigger buffer
+/// # Safety
+///
+/// `buf` must point to `capacity` writable bytes, or be null if `capacity`
+/// is 0.
+#[no_mangle]
+pub unsafe extern "C" fn color_name(color: Color, buf: *mut c_char, capacity: usize) -> usize {
+    let name = color.to_string();
+    if !buf.is_null() && capacity > 0 {
+        let n = name.len().min(capacity - 1);
+        ptr::copy_nonoverlapping(name.as_ptr().cast(), buf, n);
+        buf.add(n).write(0);
+    }
+    name.len()
+}
+
+// Allocated by Rust: release with `color_string_free`, never with `free()`
+#[no_mangle]
+pub extern "C" fn color_describe(color: Color) -> *mut c_char {
+    let Rgb { r, g, b } = color.to_rgb();
+    let text = format!("{} = #{:02X}{:02X}{:02X}", color, r, g, b);
+    // `text` has no interior NUL, so this cannot fail
+    CString::new(text).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// # Safety
+///
+/// `text` must be null or a pointer from `color_describe` that has not
+/// been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn color_string_free(text: *mut c_char) {
+    if !text.is_null() {
+        drop(CString::from_raw(text));
+    }
+}
+
+/// # Safety
+///
+/// `text` must be null or a NUL-terminated string; `out` must be null or
+/// point to a writable `Rgb`.
+#[no_mangle]
+pub unsafe extern "C" fn color_parse_hex(text: *const c_char, out: *mut Rgb) -> FfiStatus {
+    if text.is_null() || out.is_null() {
+        return FfiStatus::NullPointer;
+    }
+    let parsed = CStr::from_ptr(text).to_str().ok().and_then(Rgb::parse_hex);
+    match parsed {
+        Some(rgb) => {
+            out.write(rgb);
+            FfiStatus::Ok
+        }
+        None => FfiStatus::InvalidArgument,
+    }
+}
@@ -0,0 +1,14 @@
+// Exporting Rust to C
+//
+// The shapes from 04.struct and the colors from 05.enum, rebuilt as a
+// library whose `ffi` module is compiled into a `cdylib` (libffi_export.so /
+// .dylib / .dll) with a generated C header. `shapes` and `color` stay plain,
+// safe Rust; everything unsafe is concentrated in `ffi`.
+
+pub mod color;
+pub mod ffi;
+pub mod shapes;
+
+pub use color::{Color, Rgb};
+pub use ffi::FfiStatus;
+pub use shapes::{Point, Polygon, Rectangle};
@@ -0,0 +1,134 @@
+use ffi_export::ffi::{
+    color_describe, color_name, color_string_free, shapes_polygon_area, shapes_polygon_free,
+    shapes_polygon_new, shapes_polygon_push,
+};
+use ffi_export::{Color, Point, Polygon, Rectangle};
+use std::ffi::{c_int, CStr};
+use std::io::Write;
+use std::mem::{align_of, size_of};
+
+// Compiled from c/demo.c by build.rs
+extern "C" {
+    fn ffi_c_demo() -> c_int;
+}
+
+const HEADER: &str = include_str!("../include/ffi_export.h");
+
+fn main() {
+    println!("=== C FFI Export Examples ===\n");
+
+    // 1. The safe Rust API
+    println!("1. Safe Rust API:");
+    let mut rect = Rectangle::new(3.0, 4.0);
+    println!(
+        "   {:?}: area {}, perimeter {}",
+        rect,
+        rect.area(),
+        rect.perimeter()
+    );
+    rect.resize(6.0, 6.0);
+    println!("   resized: square? {}", rect.is_square());
+    let mut triangle = Polygon::new();
+    for (x, y) in [(0.0, 0.0), (4.0, 0.0), (0.0, 3.0)] {
+        triangle.push(Point { x, y });
+    }
+    println!(
+        "   triangle area {}, bounds {:?}",
+        triangle.area(),
+        triangle.bounds()
+    );
+    for color in [
+        Color::Red,
+        Color::Rgb(255, 128, 0),
+        Color::Hsv {
+            h: 200,
+            s: 80,
+            v: 90,
+        },
+    ] {
+        println!("   {:<18} -> {:?}", color.to_string(), color.to_rgb());
+    }
+
+    // 2. Layouts C will see
+    println!("\n2. #[repr(C)] layouts:");
+    println!(
+        "   Rectangle: {} bytes, align {}",
+        size_of::<Rectangle>(),
+        align_of::<Rectangle>()
+    );
+    println!(
+        "   Point:     {} bytes, align {}",
+        size_of::<Point>(),
+        align_of::<Point>()
+    );
+    println!(
+        "   Color:     {} bytes (tag + largest variant, padded)",
+        size_of::<Color>()
+    );
+    println!(
+        "   Rgb:       {} bytes, align {}",
+        size_of::<ffi_export::Rgb>(),
+        align_of::<ffi_export::Rgb>()
+    );
+
+    // 3. Calling the exported functions the way C would
+    println!("\n3. The C ABI, called from Rust:");
+    // SAFETY: every pointer below comes from the matching constructor and
+    // is freed exactly once
+    unsafe {
+        let poly = shapes_polygon_new();
+        for (x, y) in [(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)] {
+            shapes_polygon_push(poly, Point { x, y });
+        }
+        println!("   polygon via pointer: area {}", shapes_polygon_area(poly));
+        shapes_polygon_free(poly);
+
+        let mut buf = [0 as std::ffi::c_char; 8];
+        let color = Color::Hsv {
+            h: 200,
+            s: 80,
+            v: 90,
+        };
+        let needed = color_name(color, buf.as_mut_ptr(), buf.len());
+        let name = CStr::from_ptr(buf.as_ptr());
+        println!(
+            "   color_name into 8 bytes: {:?} (needs {} + NUL)",
+            name, needed
+        );
+
+        let text = color_describe(color);
+        println!("   color_describe: {:?}", CStr::from_ptr(text));
+        color_string_free(text);
+    }
+
+    // 4. The generated header
+    println!("\n4. include/ffi_export.h (generated by cbindgen):");
+    let functions: Vec<&str> = HEADER
+        .lines()
+        .filter(|l| l.contains('(') && !l.starts_with(' ') && !l.starts_with('#'))
+        .collect();
+    println!(
+        "   {} lines, {} function declarations",
+        HEADER.lines().count(),
+        functions.len()
+    );
+    let start = HEADER.find("typedef enum Color_Tag").unwrap_or(0);
+    let end = HEADER[start..]
+        .find("} Color;")
+        .map_or(start, |i| start + i + "} Color;".len());
+    for line in HEADER[start..end].lines() {
+        println!("   | {}", line);
+    }
+
+    // 5. A C program using the library
+    println!("\n5. c/demo.c:");
+    std::io::stdout().flush().unwrap();
+    // SAFETY: ffi_c_demo takes no arguments and only calls the exported API
+    let failures = unsafe { ffi_c_demo() };
+    println!("   C demo returned {}", failures);
+
+    println!("\n   standalone, against the shared library:");
+    println!("   cc c/main.c c/demo.c -Iinclude -Ltarget/debug -lffi_export -lm -o demo");
+
+    println!("\n=== End of C FFI Export Examples ===");
+}
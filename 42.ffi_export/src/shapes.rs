@@ -0,0 +1,94 @@
+// Shapes from the struct lesson (04.struct), made C-compatible
+//
+// `#[repr(C)]` fixes the field order and padding to what a C compiler
+// would produce, so `Rectangle` and `Point` can cross the boundary by value.
+// `Polygon` owns a `Vec` and has no C layout; C only ever sees a pointer.
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rectangle {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rectangle {
+    pub fn new(width: f64, height: f64) -> Rectangle {
+        Rectangle { width, height }
+    }
+
+    pub fn square(size: f64) -> Rectangle {
+        Rectangle::new(size, size)
+    }
+
+    pub fn area(&self) -> f64 {
+        self.width * self.height
+    }
+
+    pub fn perimeter(&self) -> f64 {
+        2.0 * (self.width + self.height)
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.width == self.height
+    }
+
+    pub fn resize(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+
+    pub fn can_fit(&self, width: f64, height: f64) -> bool {
+        self.width >= width && self.height >= height
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Polygon {
+    points: Vec<Point>,
+}
+
+impl Polygon {
+    pub fn new() -> Polygon {
+        Polygon::default()
+    }
+
+    pub fn push(&mut self, point: Point) {
+        self.points.push(point);
+    }
+
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    // Shoelace formula; positive regardless of winding order
+    pub fn area(&self) -> f64 {
+        let n = self.points.len();
+        let twice: f64 = (0..n)
+            .map(|i| {
+                let (a, b) = (self.points[i], self.points[(i + 1) % n]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum();
+        twice.abs() / 2.0
+    }
+
+    // Axis-aligned bounding box size; `None` for an empty polygon
+    pub fn bounds(&self) -> Option<Rectangle> {
+        let first = self.points.first()?;
+        let (mut min, mut max) = (*first, *first);
+        for p in &self.points {
+            min.x = min.x.min(p.x);
+            min.y = min.y.min(p.y);
+            max.x = max.x.max(p.x);
+            max.y = max.y.max(p.y);
+        }
+        Some(Rectangle::new(max.x - min.x, max.y - min.y))
+    }
+}
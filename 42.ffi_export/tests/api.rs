@@ -0,0 +1,111 @@
+use ffi_export::{Color, Point, Polygon, Rectangle, Rgb};
+
+fn polygon(points: &[(f64, f64)]) -> Polygon {
+    let mut polygon = Polygon::new();
+    for &(x, y) in points {
+        polygon.push(Point { x, y });
+    }
+    polygon
+}
+
+#[test]
+fn rectangle_measurements() {
+    let mut rect = Rectangle::new(3.0, 4.0);
+    assert_eq!(rect.area(), 12.0);
+    assert_eq!(rect.perimeter(), 14.0);
+    assert!(!rect.is_square());
+    assert!(rect.can_fit(3.0, 4.0));
+    assert!(!rect.can_fit(3.5, 1.0));
+    rect.resize(6.0, 6.0);
+    assert!(rect.is_square());
+    assert_eq!(Rectangle::square(2.5), Rectangle::new(2.5, 2.5));
+}
+
+#[test]
+fn polygon_area_ignores_winding_order() {
+    let clockwise = polygon(&[(0.0, 0.0), (0.0, 3.0), (4.0, 3.0), (4.0, 0.0)]);
+    let anticlockwise = polygon(&[(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)]);
+    assert_eq!(clockwise.area(), 12.0);
+    assert_eq!(anticlockwise.area(), 12.0);
+    assert_eq!(polygon(&[(0.0, 0.0), (4.0, 0.0), (0.0, 3.0)]).area(), 6.0);
+    assert_eq!(Polygon::new().area(), 0.0);
+}
+
+#[test]
+fn polygon_bounds() {
+    let triangle = polygon(&[(-1.0, 2.0), (4.0, 0.0), (0.0, 3.0)]);
+    assert_eq!(triangle.bounds(), Some(Rectangle::new(5.0, 3.0)));
+    assert_eq!(Polygon::new().bounds(), None);
+    assert_eq!(
+        polygon(&[(7.0, 7.0)]).bounds(),
+        Some(Rectangle::new(0.0, 0.0))
+    );
+}
+
+#[test]
+fn named_colors_and_display() {
+    assert_eq!(Color::Red.to_rgb(), Rgb { r: 255, g: 0, b: 0 });
+    assert_eq!(Color::Green.to_rgb(), Rgb { r: 0, g: 255, b: 0 });
+    assert_eq!(Color::Blue.to_rgb(), Rgb { r: 0, g: 0, b: 255 });
+    assert_eq!(Color::Rgb(1, 2, 3).to_rgb(), Rgb { r: 1, g: 2, b: 3 });
+    assert_eq!(Color::Rgb(255, 128, 0).to_string(), "RGB(255, 128, 0)");
+    assert_eq!(
+        Color::Hsv {
+            h: 200,
+            s: 80,
+            v: 90
+        }
+        .to_string(),
+        "HSV(200, 80, 90)"
+    );
+}
+
+#[test]
+fn hsv_conversion() {
+    let rgb = |h, s, v| Color::Hsv { h, s, v }.to_rgb();
+    assert_eq!(rgb(0, 100, 100), Rgb { r: 255, g: 0, b: 0 });
+    assert_eq!(rgb(120, 100, 100), Rgb { r: 0, g: 255, b: 0 });
+    assert_eq!(rgb(240, 100, 100), Rgb { r: 0, g: 0, b: 255 });
+    assert_eq!(
+        rgb(200, 80, 90),
+        Rgb {
+            r: 46,
+            g: 168,
+            b: 230
+        }
+    );
+    // No saturation is grey, hue wraps, out-of-range percentages clamp
+    assert_eq!(
+        rgb(45, 0, 50),
+        Rgb {
+            r: 128,
+            g: 128,
+            b: 128
+        }
+    );
+    assert_eq!(rgb(360, 100, 100), rgb(0, 100, 100));
+    assert_eq!(rgb(60, 200, 200), rgb(60, 100, 100));
+}
+
+#[test]
+fn hex_parsing() {
+    assert_eq!(
+        Rgb::parse_hex("#1E90FF"),
+        Some(Rgb {
+            r: 0x1E,
+            g: 0x90,
+            b: 0xFF
+        })
+    );
+    assert_eq!(
+        Rgb::parse_hex("00ff7f"),
+        Some(Rgb {
+            r: 0,
+            g: 255,
+            b: 127
+        })
+    );
+    for bad in ["", "#12345", "#1234567", "#12345G", "+12345", "#+1234"] {
+        assert_eq!(Rgb::parse_hex(bad), None, "{:?}", bad);
+    }
+}
@@ -0,0 +1,18 @@
+// Runs c/demo.c, which build.rs compiles against the generated header, and
+// fails if any of its checks did
+use std::ffi::c_int;
+
+// The compiled C object is bundled into the library's rlib, which is only
+// linked when something names the crate
+extern crate ffi_export;
+
+extern "C" {
+    fn ffi_c_demo() -> c_int;
+}
+
+#[test]
+fn the_c_program_sees_what_the_header_promises() {
+    // SAFETY: ffi_c_demo takes no arguments and only calls the exported API
+    let failures = unsafe { ffi_c_demo() };
+    assert_eq!(failures, 0, "c/demo.c reported failed checks");
+}
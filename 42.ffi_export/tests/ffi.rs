@@ -0,0 +1,256 @@
+// The exported functions called the way C calls them, through raw pointers
+// and status codes
+
+use ffi_export::ffi::*;
+use ffi_export::{Color, FfiStatus, Point, Rectangle, Rgb};
+use std::ffi::{c_char, CStr};
+use std::mem::{align_of, offset_of, size_of};
+use std::ptr;
+
+const HEADER: &str = include_str!("../include/ffi_export.h");
+const FFI_SOURCE: &str = include_str!("../src/ffi.rs");
+
+#[test]
+fn layouts_match_c() {
+    assert_eq!((size_of::<Point>(), align_of::<Point>()), (16, 8));
+    assert_eq!((size_of::<Rectangle>(), align_of::<Rectangle>()), (16, 8));
+    assert_eq!((size_of::<Rgb>(), align_of::<Rgb>()), (3, 1));
+    assert_eq!(offset_of!(Rectangle, height), 8);
+    // A 4-byte tag, then a union whose largest member is { u16, u8, u8 }
+    assert_eq!(size_of::<Color>(), 8);
+    assert_eq!(size_of::<FfiStatus>(), 4);
+}
+
+#[test]
+fn every_export_is_declared_in_the_header() {
+    // The line after each `#[no_mangle]` holds the signature
+    let lines: Vec<&str> = FFI_SOURCE.lines().collect();
+    let exports: Vec<&str> = lines
+        .windows(2)
+        .filter(|w| w[0] == "#[no_mangle]")
+        .map(|w| {
+            let after = &w[1][w[1].find("fn ").unwrap() + 3..];
+            &after[..after.find('(').unwrap()]
+        })
+        .collect();
+    assert_eq!(exports.len(), 20);
+    for name in exports {
+        assert!(
+            HEADER.contains(&format!("{}(", name)),
+            "{} is missing from include/ffi_export.h",
+            name
+        );
+    }
+}
+
+#[test]
+fn version_is_the_crate_version() {
+    // SAFETY: the version string is static and NUL-terminated
+    let version = unsafe { CStr::from_ptr(ffi_export_version()) };
+    assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn rectangles_by_value() {
+    let rect = shapes_rectangle_new(3.0, 4.0);
+    assert_eq!(shapes_rectangle_area(rect), 12.0);
+    assert_eq!(shapes_rectangle_perimeter(rect), 14.0);
+    assert!(shapes_rectangle_is_square(shapes_rectangle_square(5.0)));
+    assert!(shapes_rectangle_can_fit(rect, 2.0, 4.0));
+    assert!(!shapes_rectangle_can_fit(rect, 2.0, 4.5));
+}
+
+#[test]
+fn resize_reports_status() {
+    let mut rect = shapes_rectangle_new(3.0, 4.0);
+    // SAFETY: `rect` is a live local, the others are null on purpose
+    unsafe {
+        assert_eq!(shapes_rectangle_resize(&mut rect, 6.0, 6.0), FfiStatus::Ok);
+        assert_eq!(rect, Rectangle::square(6.0));
+        assert_eq!(
+            shapes_rectangle_resize(ptr::null_mut(), 1.0, 1.0),
+            FfiStatus::NullPointer
+        );
+        for (w, h) in [(-1.0, 2.0), (2.0, -0.5), (f64::NAN, 1.0)] {
+            assert_eq!(
+                shapes_rectangle_resize(&mut rect, w, h),
+                FfiStatus::InvalidArgument
+            );
+        }
+    }
+    // A rejected resize leaves the rectangle alone
+    assert_eq!(rect, Rectangle::square(6.0));
+}
+
+#[test]
+fn polygon_lifecycle() {
+    // SAFETY: `poly` comes from shapes_polygon_new and is freed once, at
+    // the end; `points` is read before the next push
+    unsafe {
+        let poly = shapes_polygon_new();
+        assert!(!poly.is_null());
+        assert_eq!(shapes_polygon_len(poly), 0);
+        let mut bounds = Rectangle::new(0.0, 0.0);
+        assert_eq!(
+            shapes_polygon_bounds(poly, &mut bounds),
+            FfiStatus::InvalidArgument
+        );
+
+        for (x, y) in [(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)] {
+            assert_eq!(shapes_polygon_push(poly, Point { x, y }), FfiStatus::Ok);
+        }
+        assert_eq!(
+            shapes_polygon_push(
+                poly,
+                Point {
+                    x: f64::NAN,
+                    y: 0.0
+                }
+            ),
+            FfiStatus::InvalidArgument
+        );
+        assert_eq!(
+            shapes_polygon_push(
+                poly,
+                Point {
+                    x: 0.0,
+                    y: f64::INFINITY
+                }
+            ),
+            FfiStatus::InvalidArgument
+        );
+        assert_eq!(shapes_polygon_len(poly), 4);
+        assert_eq!(shapes_polygon_area(poly), 12.0);
+        let points = std::slice::from_raw_parts(shapes_polygon_points(poly), 4);
+        assert_eq!(points[2], Point { x: 4.0, y: 3.0 });
+        assert_eq!(shapes_polygon_bounds(poly, &mut bounds), FfiStatus::Ok);
+        assert_eq!(bounds, Rectangle::new(4.0, 3.0));
+        assert_eq!(
+            shapes_polygon_bounds(poly, ptr::null_mut()),
+            FfiStatus::NullPointer
+        );
+        shapes_polygon_free(poly);
+    }
+}
+
+#[test]
+fn null_polygons_are_tolerated() {
+    // SAFETY: every function below accepts null
+    unsafe {
+        shapes_polygon_free(ptr::null_mut());
+        assert_eq!(shapes_polygon_len(ptr::null()), 0);
+        assert!(shapes_polygon_points(ptr::null()).is_null());
+        assert!(shapes_polygon_area(ptr::null()).is_nan());
+        assert_eq!(
+            shapes_polygon_push(ptr::null_mut(), Point { x: 0.0, y: 0.0 }),
+            FfiStatus::NullPointer
+        );
+        let mut out = Rectangle::new(0.0, 0.0);
+        assert_eq!(
+            shapes_polygon_bounds(ptr::null(), &mut out),
+            FfiStatus::NullPointer
+        );
+    }
+}
+
+#[test]
+fn color_name_truncates_like_snprintf() {
+    let hsv = Color::Hsv {
+        h: 200,
+        s: 80,
+        v: 90,
+    };
+    // SAFETY: each buffer is a live local of the stated capacity
+    unsafe {
+        let mut small = [0x7f as c_char; 6];
+        assert_eq!(color_name(hsv, small.as_mut_ptr(), small.len()), 16);
+        assert_eq!(CStr::from_ptr(small.as_ptr()).to_str(), Ok("HSV(2"));
+
+        let mut exact = [0x7f as c_char; 17];
+        assert_eq!(color_name(hsv, exact.as_mut_ptr(), exact.len()), 16);
+        assert_eq!(
+            CStr::from_ptr(exact.as_ptr()).to_str(),
+            Ok("HSV(200, 80, 90)")
+        );
+
+        // Capacity 1 writes only the NUL; capacity 0 and null write nothing
+        let mut one = [0x7f as c_char; 1];
+        assert_eq!(color_name(Color::Red, one.as_mut_ptr(), 1), 3);
+        assert_eq!(one[0], 0);
+        let mut untouched = [0x7f as c_char; 1];
+        assert_eq!(color_name(Color::Red, untouched.as_mut_ptr(), 0), 3);
+        assert_eq!(untouched[0], 0x7f);
+        assert_eq!(color_name(Color::Blue, ptr::null_mut(), 0), 4);
+    }
+}
+
+#[test]
+fn described_strings_are_freed_by_rust() {
+    let text = color_describe(Color::Rgb(255, 128, 0));
+    assert!(!text.is_null());
+    // SAFETY: `text` comes from color_describe and is freed once
+    unsafe {
+        assert_eq!(
+            CStr::from_ptr(text).to_str(),
+            Ok("RGB(255, 128, 0) = #FF8000")
+        );
+        color_string_free(text);
+        color_string_free(ptr::null_mut());
+    }
+}
+
+#[test]
+fn parse_hex_reports_status() {
+    let mut out = Rgb { r: 0, g: 0, b: 0 };
+    // SAFETY: the strings are NUL-terminated literals and `out` is a local
+    unsafe {
+        assert_eq!(
+            color_parse_hex(c"#1E90FF".as_ptr(), &mut out),
+            FfiStatus::Ok
+        );
+        assert_eq!(
+            out,
+            Rgb {
+                r: 0x1E,
+                g: 0x90,
+                b: 0xFF
+            }
+        );
+        assert_eq!(
+            color_parse_hex(c"#12345".as_ptr(), &mut out),
+            FfiStatus::InvalidArgument
+        );
+        // Not UTF-8
+        assert_eq!(
+            color_parse_hex(c"\xff\xfe".as_ptr(), &mut out),
+            FfiStatus::InvalidArgument
+        );
+        assert_eq!(
+            color_parse_hex(ptr::null(), &mut out),
+            FfiStatus::NullPointer
+        );
+        assert_eq!(
+            color_parse_hex(c"#000000".as_ptr(), ptr::null_mut()),
+            FfiStatus::NullPointer
+        );
+    }
+    // Failed parses leave the output alone
+    assert_eq!(out.r, 0x1E);
+}
+
+#[test]
+fn colors_cross_by_value() {
+    assert_eq!(color_to_rgb(Color::Green), Rgb { r: 0, g: 255, b: 0 });
+    assert_eq!(
+        color_to_rgb(Color::Hsv {
+            h: 200,
+            s: 80,
+            v: 90
+        }),
+        Rgb {
+            r: 46,
+            g: 168,
+            b: 230
+        }
+    );
+}
@@ -0,0 +1,183 @@
+// The rule engine from `13.rules`
+//
+// `RuleEngine` takes all of its rules up front, so JS first fills a `RuleSet`
+// and then builds an `AlertEngine` from it. Timestamps are `f64`
+// milliseconds (what `Date.now()` and `performance.now()` return) and are
+// checked before they are narrowed to the engine's `u64`.
+
+use crate::listeners::Listeners;
+use js_sys::Function;
+use rules::{AlertEvent, Condition, Reading, Rule, RuleEngine};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub rule: String,
+    // false when the alert cleared
+    pub raised: bool,
+    #[wasm_bindgen(js_name = atMs)]
+    pub at_ms: f64,
+}
+
+impl From<AlertEvent> for Alert {
+    fn from(event: AlertEvent) -> Alert {
+        match event {
+            AlertEvent::Raised { rule, at_ms } => Alert {
+                rule,
+                raised: true,
+                at_ms: at_ms as f64,
+            },
+            AlertEvent::Cleared { rule, at_ms } => Alert {
+                rule,
+                raised: false,
+                at_ms: at_ms as f64,
+            },
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+}
+
+#[wasm_bindgen]
+impl RuleSet {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> RuleSet {
+        RuleSet::default()
+    }
+
+    // `rules.above("overheat", "temperature", 30, 1.5, 2000)`
+    pub fn above(
+        &mut self,
+        name: &str,
+        metric: &str,
+        threshold: f64,
+        hysteresis: f64,
+        for_ms: f64,
+    ) -> Result<(), JsError> {
+        let condition = Condition::above(metric, threshold);
+        self.add_checked(name, condition, hysteresis, for_ms)
+    }
+
+    pub fn below(
+        &mut self,
+        name: &str,
+        metric: &str,
+        threshold: f64,
+        hysteresis: f64,
+        for_ms: f64,
+    ) -> Result<(), JsError> {
+        let condition = Condition::below(metric, threshold);
+        self.add_checked(name, condition, hysteresis, for_ms)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.rules.len()
+    }
+}
+
+impl RuleSet {
+    pub fn push(&mut self, rule: Rule) {
+        self.rules.push(rule);
+    }
+
+    fn add_checked(
+        &mut self,
+        name: &str,
+        condition: Condition,
+        hysteresis: f64,
+        for_ms: f64,
+    ) -> Result<(), JsError> {
+        if self.rules.iter().any(|r| r.name == name) {
+            return Err(JsError::new(&format!("duplicate rule name '{}'", name)));
+        }
+        if !(hysteresis >= 0.0 && hysteresis.is_finite()) {
+            return Err(JsError::new("hysteresis must be a finite number >= 0"));
+        }
+        let duration_ms = millis(for_ms).ok_or_else(|| JsError::new("forMs must be >= 0"))?;
+        let rule = Rule::new(name, condition.with_hysteresis(hysteresis)).for_at_least(duration_ms);
+        self.push(rule);
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+pub struct AlertEngine {
+    engine: RuleEngine,
+    listeners: Listeners<Alert>,
+}
+
+#[wasm_bindgen]
+impl AlertEngine {
+    // The set is copied: changing it later does not affect this engine
+    #[wasm_bindgen(constructor)]
+    pub fn new(rules: &RuleSet) -> AlertEngine {
+        AlertEngine {
+            engine: RuleEngine::new(rules.rules.clone()),
+            listeners: Listeners::default(),
+        }
+    }
+
+    // Feed one reading; returns the alerts it raised or cleared
+    pub fn process(
+        &mut self,
+        metric: &str,
+        value: f64,
+        timestamp_ms: f64,
+    ) -> Result<Vec<Alert>, JsError> {
+        let timestamp_ms =
+            millis(timestamp_ms).ok_or_else(|| JsError::new("timestampMs must be >= 0"))?;
+        Ok(self.process_reading(&Reading::new(metric, value, timestamp_ms)))
+    }
+
+    #[wasm_bindgen(js_name = isRaised)]
+    pub fn is_raised(&self, name: &str) -> bool {
+        self.engine.is_raised(name)
+    }
+
+    pub fn active(&self) -> Vec<String> {
+        self.engine.active().into_iter().map(String::from).collect()
+    }
+
+    // Called once per alert, before `process` returns
+    #[wasm_bindgen(js_name = onAlert)]
+    pub fn on_alert(&mut self, callback: Function) {
+        self.listeners.add_js(callback);
+    }
+}
+
+impl AlertEngine {
+    pub fn from_rules(rules: Vec<Rule>) -> AlertEngine {
+        AlertEngine {
+            engine: RuleEngine::new(rules),
+            listeners: Listeners::default(),
+        }
+    }
+
+    pub fn process_reading(&mut self, reading: &Reading) -> Vec<Alert> {
+        let alerts: Vec<Alert> = self
+            .engine
+            .process(reading)
+            .into_iter()
+            .map(Alert::from)
+            .collect();
+        for alert in &alerts {
+            self.listeners.emit(alert);
+        }
+        alerts
+    }
+
+    pub fn on_alert_with(&mut self, listener: impl FnMut(&Alert) + 'static) {
+        self.listeners.add(listener);
+    }
+}
+
+// JS numbers to whole milliseconds; None for negative, NaN or infinite
+fn millis(value: f64) -> Option<u64> {
+    (value >= 0.0 && value.is_finite()).then_some(value as u64)
+}
@@ -0,0 +1,23 @@
+// The power-management state machines and the rule engine, compiled to
+// WebAssembly
+//
+// Nothing here re-implements logic: every class wraps the type from
+// `10.power_fsm` or `13.rules`, so a browser demo steps exactly the machine
+// the firmware runs. Each class has two faces:
+// - a `#[wasm_bindgen]` surface for JavaScript (JS enums, `f64` times,
+//   `JsError` on failure, `js_sys::Function` listeners)
+// - a plain Rust surface (`try_*`, `on_*_with`) that works on any target,
+//   which is what `src/main.rs` uses natively
+//
+//   wasm-pack build --target web
+//   python3 -m http.server    # then open http://localhost:8000/www/
+
+pub mod alerts;
+pub mod light;
+pub mod listeners;
+pub mod power;
+
+pub use alerts::{Alert, AlertEngine, RuleSet};
+pub use light::{Light, LightInput, TrafficLightMachine};
+pub use listeners::{Listeners, StateChange};
+pub use power::{PowerInput, PowerMachine, PowerMode};
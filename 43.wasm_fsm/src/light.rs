@@ -0,0 +1,108 @@
+// The traffic light from `10.power_fsm`
+//
+// wasm-bindgen can only export C-style enums, so JS gets its own `Light` and
+// `LightInput` mirrors with `From` conversions to the framework types.
+
+use crate::listeners::{Listeners, StateChange};
+use js_sys::Function;
+use power_fsm::{Fsm, LightEvent, TrafficLight};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Light {
+    Red,
+    Yellow,
+    Green,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightInput {
+    TimerExpired,
+}
+
+impl From<TrafficLight> for Light {
+    fn from(state: TrafficLight) -> Light {
+        match state {
+            TrafficLight::Red => Light::Red,
+            TrafficLight::Yellow => Light::Yellow,
+            TrafficLight::Green => Light::Green,
+        }
+    }
+}
+
+impl From<Light> for TrafficLight {
+    fn from(light: Light) -> TrafficLight {
+        match light {
+            Light::Red => TrafficLight::Red,
+            Light::Yellow => TrafficLight::Yellow,
+            Light::Green => TrafficLight::Green,
+        }
+    }
+}
+
+impl From<LightInput> for LightEvent {
+    fn from(input: LightInput) -> LightEvent {
+        match input {
+            LightInput::TimerExpired => LightEvent::TimerExpired,
+        }
+    }
+}
+
+#[wasm_bindgen(js_name = TrafficLight)]
+pub struct TrafficLightMachine {
+    fsm: Fsm<TrafficLight>,
+    listeners: Listeners<StateChange>,
+}
+
+#[wasm_bindgen(js_class = TrafficLight)]
+impl TrafficLightMachine {
+    #[wasm_bindgen(constructor)]
+    pub fn new(initial: Light) -> TrafficLightMachine {
+        TrafficLightMachine {
+            fsm: Fsm::new(initial.into()),
+            listeners: Listeners::default(),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn state(&self) -> Light {
+        self.fsm.state().into()
+    }
+
+    // Every light has a timer transition, so this cannot fail
+    pub fn handle(&mut self, input: LightInput) -> Light {
+        let before = self.fsm.history().len();
+        let state = self.fsm.handle(input.into()).unwrap_or_else(|e| e.state);
+        if let Some(t) = self.fsm.history().get(before) {
+            self.listeners.emit(&StateChange::from(t));
+        }
+        state.into()
+    }
+
+    pub fn tick(&mut self) -> Light {
+        self.handle(LightInput::TimerExpired)
+    }
+
+    #[wasm_bindgen(getter, js_name = transitionCount)]
+    pub fn transition_count(&self) -> usize {
+        self.fsm.history().len()
+    }
+
+    // `light.onChange(change => console.log(change.from, "->", change.to))`
+    #[wasm_bindgen(js_name = onChange)]
+    pub fn on_change(&mut self, callback: Function) {
+        self.listeners.add_js(callback);
+    }
+}
+
+impl TrafficLightMachine {
+    pub fn on_change_with(&mut self, listener: impl FnMut(&StateChange) + 'static) {
+        self.listeners.add(listener);
+    }
+
+    pub fn fsm(&self) -> &Fsm<TrafficLight> {
+        &self.fsm
+    }
+}
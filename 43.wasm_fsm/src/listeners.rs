@@ -0,0 +1,75 @@
+// Change notifications
+//
+// A machine keeps a list of boxed Rust closures. A JavaScript function is
+// just one more closure that converts the value to a `JsValue` and calls
+// back into JS, so Rust and JS listeners are notified in registration order
+// by the same code.
+
+use js_sys::Function;
+use power_fsm::{StateMachine, Transition};
+use wasm_bindgen::prelude::*;
+
+// One transition as JavaScript sees it: state and event names, not numbers
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateChange {
+    pub from: String,
+    pub event: String,
+    pub to: String,
+}
+
+impl<S: StateMachine> From<&Transition<S>> for StateChange {
+    fn from(t: &Transition<S>) -> StateChange {
+        StateChange {
+            from: format!("{:?}", t.from),
+            event: format!("{:?}", t.event),
+            to: format!("{:?}", t.to),
+        }
+    }
+}
+
+type Listener<T> = Box<dyn FnMut(&T)>;
+
+pub struct Listeners<T> {
+    list: Vec<Listener<T>>,
+}
+
+impl<T> Default for Listeners<T> {
+    fn default() -> Self {
+        Listeners { list: Vec::new() }
+    }
+}
+
+impl<T> Listeners<T> {
+    pub fn add(&mut self, listener: impl FnMut(&T) + 'static) {
+        self.list.push(Box::new(listener));
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+
+    pub fn emit(&mut self, value: &T) {
+        for listener in &mut self.list {
+            listener(value);
+        }
+    }
+}
+
+impl<T: Clone + Into<JsValue>> Listeners<T> {
+    // An exception thrown by the callback is dropped: a broken UI handler
+    // must not leave the machine half-way through a transition
+    pub fn add_js(&mut self, callback: Function) {
+        self.add(move |value: &T| {
+            let _ = callback.call1(&JsValue::NULL, &value.clone().into());
+        });
+    }
+}
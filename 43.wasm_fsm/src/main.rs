@@ -0,0 +1,131 @@
+use power_fsm::{run_workload, CurrentProfile, PowerEvent, PowerManager, Step};
+use rules::{Condition, Reading, Rule, RuleEngine};
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_fsm::{
+    Alert, AlertEngine, Light, PowerInput, PowerMachine, PowerMode, StateChange,
+    TrafficLightMachine,
+};
+
+// The same workload as a list of wrapper inputs and of framework events
+const WORKLOAD: [(f64, PowerInput); 7] = [
+    (5.0, PowerInput::TimerExpired),
+    (30.0, PowerInput::TimerExpired),
+    (120.0, PowerInput::TimerExpired),
+    (600.0, PowerInput::Activity),
+    (2.0, PowerInput::TimerExpired),
+    (58.0, PowerInput::LowBattery),
+    (900.0, PowerInput::TimerExpired),
+];
+
+const TEMPERATURES: [f64; 10] = [28.0, 31.0, 32.5, 33.0, 31.2, 29.9, 29.0, 31.5, 32.0, 27.0];
+
+fn main() {
+    println!("=== WebAssembly FSM Bindings Examples ===\n");
+
+    // 1. Traffic light with a change listener
+    println!("1. TrafficLight + onChange:");
+    let mut light = TrafficLightMachine::new(Light::Red);
+    light.on_change_with(|c: &StateChange| {
+        println!("   change: {} --{}--> {}", c.from, c.event, c.to)
+    });
+    for _ in 0..4 {
+        light.tick();
+    }
+    println!(
+        "   state {:?} after {} transitions",
+        light.state(),
+        light.transition_count()
+    );
+
+    // 2. Power manager: wrapper and framework stay in lockstep
+    println!("\n2. PowerManager workload:");
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let mut power = PowerMachine::new();
+    let log = Rc::clone(&changes);
+    power.on_change_with(move |c: &StateChange| log.borrow_mut().push(c.to.clone()));
+    let mut rejected = 0;
+    for (seconds, input) in WORKLOAD {
+        power.advance(seconds);
+        if let Err(e) = power.try_handle(input) {
+            println!("   rejected {:?} in {:?}", e.event, e.state);
+            rejected += 1;
+        }
+    }
+    println!("   states seen: {}", changes.borrow().join(" -> "));
+    println!(
+        "   {:.3} mAh, average {:.2} mA, {:.0} h on 1000 mAh",
+        power.consumed_mah(),
+        power.average_current_ma(),
+        power.battery_life_hours(1000.0).unwrap_or(f64::INFINITY)
+    );
+
+    let mut native = PowerManager::new(CurrentProfile::default());
+    let steps: Vec<Step> = WORKLOAD
+        .iter()
+        .map(|&(seconds, input)| Step::new(seconds, PowerEvent::from(input)))
+        .collect();
+    let native_rejected = run_workload(&mut native, &steps);
+    println!(
+        "   power_fsm: {:?}, {:.3} mAh, {} rejected ({} here)",
+        native.state(),
+        native.consumed_mah(),
+        native_rejected,
+        rejected
+    );
+
+    // 3. Time per state
+    println!("\n3. Seconds per state:");
+    for mode in [
+        PowerMode::Active,
+        PowerMode::Idle,
+        PowerMode::LightSleep,
+        PowerMode::DeepSleep,
+    ] {
+        println!(
+            "   {:<11} {:>6.0} s",
+            format!("{:?}", mode),
+            power.seconds_in(mode)
+        );
+    }
+
+    // 4. Alert engine
+    println!("\n4. AlertEngine + onAlert:");
+    let overheat = Rule::new(
+        "overheat",
+        Condition::above("temperature", 31.0).with_hysteresis(1.5),
+    )
+    .for_at_least(2000);
+    let mut alerts = AlertEngine::from_rules(vec![overheat.clone()]);
+    alerts.on_alert_with(|a: &Alert| {
+        let what = if a.raised { "raised " } else { "cleared" };
+        println!("   {} {} at {} ms", what, a.rule, a.at_ms);
+    });
+    let mut engine = RuleEngine::new(vec![overheat]);
+    let mut matched = 0;
+    for (i, &t) in TEMPERATURES.iter().enumerate() {
+        let reading = Reading::new("temperature", t, i as u64 * 1000);
+        let wrapped = alerts.process_reading(&reading);
+        let expected: Vec<Alert> = engine
+            .process(&reading)
+            .into_iter()
+            .map(Alert::from)
+            .collect();
+        matched += usize::from(wrapped == expected);
+    }
+    println!(
+        "   {} of {} readings alert like rules::RuleEngine",
+        matched,
+        TEMPERATURES.len()
+    );
+    println!("   active now: {:?}", alerts.active());
+
+    // 5. The JavaScript side
+    println!("\n5. From JavaScript:");
+    println!("   wasm-pack build --target web");
+    println!("   python3 -m http.server, then open http://localhost:8000/www/");
+    println!("   exports: TrafficLight, PowerManager, RuleSet, AlertEngine,");
+    println!("            Light, LightInput, PowerMode, PowerInput, StateChange, Alert");
+
+    println!("\n=== End of WebAssembly FSM Bindings Examples ===");
+}
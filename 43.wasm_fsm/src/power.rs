@@ -0,0 +1,165 @@
+// The power manager from `10.power_fsm`
+//
+// JS passes time as plain seconds (`f64`) and reads the same energy figures
+// the native lesson prints. A rejected event (a timer in deep sleep) is a
+// thrown `Error` in JS and an `InvalidTransition` in Rust.
+
+use crate::listeners::{Listeners, StateChange};
+use js_sys::Function;
+use power_fsm::{CurrentProfile, InvalidTransition, PowerEvent, PowerManager, PowerState};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerMode {
+    Active,
+    Idle,
+    LightSleep,
+    DeepSleep,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerInput {
+    Activity,
+    TimerExpired,
+    LowBattery,
+}
+
+impl From<PowerState> for PowerMode {
+    fn from(state: PowerState) -> PowerMode {
+        match state {
+            PowerState::Active => PowerMode::Active,
+            PowerState::Idle => PowerMode::Idle,
+            PowerState::LightSleep => PowerMode::LightSleep,
+            PowerState::DeepSleep => PowerMode::DeepSleep,
+        }
+    }
+}
+
+impl From<PowerMode> for PowerState {
+    fn from(mode: PowerMode) -> PowerState {
+        match mode {
+            PowerMode::Active => PowerState::Active,
+            PowerMode::Idle => PowerState::Idle,
+            PowerMode::LightSleep => PowerState::LightSleep,
+            PowerMode::DeepSleep => PowerState::DeepSleep,
+        }
+    }
+}
+
+impl From<PowerInput> for PowerEvent {
+    fn from(input: PowerInput) -> PowerEvent {
+        match input {
+            PowerInput::Activity => PowerEvent::Activity,
+            PowerInput::TimerExpired => PowerEvent::TimerExpired,
+            PowerInput::LowBattery => PowerEvent::LowBattery,
+        }
+    }
+}
+
+#[wasm_bindgen(js_name = PowerManager)]
+pub struct PowerMachine {
+    manager: PowerManager,
+    listeners: Listeners<StateChange>,
+}
+
+impl Default for PowerMachine {
+    fn default() -> Self {
+        PowerMachine::new()
+    }
+}
+
+#[wasm_bindgen(js_class = PowerManager)]
+impl PowerMachine {
+    // The default current profile from the lesson
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> PowerMachine {
+        PowerMachine::with_profile(CurrentProfile::default())
+    }
+
+    // Draw per state in milliamps, for comparing hardware in the browser
+    #[wasm_bindgen(js_name = withProfile)]
+    pub fn with_profile_ma(
+        active_ma: f64,
+        idle_ma: f64,
+        light_sleep_ma: f64,
+        deep_sleep_ma: f64,
+    ) -> PowerMachine {
+        PowerMachine::with_profile(CurrentProfile {
+            active_ma,
+            idle_ma,
+            light_sleep_ma,
+            deep_sleep_ma,
+        })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn state(&self) -> PowerMode {
+        self.manager.state().into()
+    }
+
+    pub fn advance(&mut self, seconds: f64) {
+        self.manager.advance(seconds);
+    }
+
+    pub fn handle(&mut self, input: PowerInput) -> Result<PowerMode, JsError> {
+        self.try_handle(input)
+            .map_err(|e| JsError::new(&format!("{:?} is not allowed in {:?}", e.event, e.state)))
+    }
+
+    #[wasm_bindgen(js_name = secondsIn)]
+    pub fn seconds_in(&self, mode: PowerMode) -> f64 {
+        self.manager.seconds_in(mode.into())
+    }
+
+    #[wasm_bindgen(getter, js_name = consumedMah)]
+    pub fn consumed_mah(&self) -> f64 {
+        self.manager.consumed_mah()
+    }
+
+    #[wasm_bindgen(getter, js_name = averageCurrentMa)]
+    pub fn average_current_ma(&self) -> f64 {
+        self.manager.average_current_ma()
+    }
+
+    // `undefined` until some time has been spent
+    #[wasm_bindgen(js_name = batteryLifeHours)]
+    pub fn battery_life_hours(&self, capacity_mah: f64) -> Option<f64> {
+        self.manager.battery_life_hours(capacity_mah)
+    }
+
+    #[wasm_bindgen(js_name = onChange)]
+    pub fn on_change(&mut self, callback: Function) {
+        self.listeners.add_js(callback);
+    }
+}
+
+impl PowerMachine {
+    pub fn with_profile(profile: CurrentProfile) -> PowerMachine {
+        PowerMachine {
+            manager: PowerManager::new(profile),
+            listeners: Listeners::default(),
+        }
+    }
+
+    pub fn try_handle(
+        &mut self,
+        input: PowerInput,
+    ) -> Result<PowerMode, InvalidTransition<PowerState>> {
+        let before = self.manager.fsm().history().len();
+        let state = self.manager.handle(input.into())?;
+        if let Some(t) = self.manager.fsm().history().get(before) {
+            self.listeners.emit(&StateChange::from(t));
+        }
+        Ok(state.into())
+    }
+
+    pub fn on_change_with(&mut self, listener: impl FnMut(&StateChange) + 'static) {
+        self.listeners.add(listener);
+    }
+
+    pub fn manager(&self) -> &PowerManager {
+        &self.manager
+    }
+}
@@ -0,0 +1,105 @@
+// The wrappers run natively too, so they can be checked against the crates
+// they wrap without a browser
+use power_fsm::{run_workload, CurrentProfile, PowerEvent, PowerManager, Step};
+use rules::{Condition, Reading, Rule, RuleEngine};
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_fsm::{
+    Alert, AlertEngine, Light, PowerInput, PowerMachine, PowerMode, StateChange,
+    TrafficLightMachine,
+};
+
+const WORKLOAD: [(f64, PowerInput); 7] = [
+    (5.0, PowerInput::TimerExpired),
+    (30.0, PowerInput::TimerExpired),
+    (120.0, PowerInput::TimerExpired),
+    (600.0, PowerInput::Activity),
+    (2.0, PowerInput::TimerExpired),
+    (58.0, PowerInput::LowBattery),
+    (900.0, PowerInput::TimerExpired),
+];
+
+#[test]
+fn the_light_notifies_every_change() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut light = TrafficLightMachine::new(Light::Red);
+    let log = Rc::clone(&seen);
+    light.on_change_with(move |c: &StateChange| {
+        log.borrow_mut().push(format!("{}->{}", c.from, c.to))
+    });
+    for _ in 0..4 {
+        light.tick();
+    }
+    assert_eq!(light.state(), Light::Green);
+    assert_eq!(light.transition_count(), 4);
+    assert_eq!(
+        *seen.borrow(),
+        ["Red->Green", "Green->Yellow", "Yellow->Red", "Red->Green"]
+    );
+}
+
+#[test]
+fn the_power_wrapper_keeps_step_with_power_fsm() {
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let mut power = PowerMachine::new();
+    let log = Rc::clone(&changes);
+    power.on_change_with(move |c: &StateChange| log.borrow_mut().push(c.to.clone()));
+    let mut rejected = 0;
+    for (seconds, input) in WORKLOAD {
+        power.advance(seconds);
+        if power.try_handle(input).is_err() {
+            rejected += 1;
+        }
+    }
+
+    let mut native = PowerManager::new(CurrentProfile::default());
+    let steps: Vec<Step> = WORKLOAD
+        .iter()
+        .map(|&(seconds, input)| Step::new(seconds, PowerEvent::from(input)))
+        .collect();
+    let native_rejected = run_workload(&mut native, &steps);
+
+    assert_eq!(power.state(), PowerMode::from(native.state()));
+    assert_eq!(power.consumed_mah(), native.consumed_mah());
+    assert_eq!(rejected, native_rejected);
+    assert_eq!(rejected, 1);
+    // One notification per recorded transition, none for rejected events
+    assert_eq!(changes.borrow().len(), native.fsm().history().len());
+    assert_eq!(
+        changes.borrow().join(" -> "),
+        "Idle -> LightSleep -> DeepSleep -> Active -> Idle -> DeepSleep"
+    );
+}
+
+#[test]
+fn alerts_match_the_rule_engine() {
+    let overheat = Rule::new(
+        "overheat",
+        Condition::above("temperature", 31.0).with_hysteresis(1.5),
+    )
+    .for_at_least(2000);
+    let mut alerts = AlertEngine::from_rules(vec![overheat.clone()]);
+    let heard = Rc::new(RefCell::new(Vec::new()));
+    let log = Rc::clone(&heard);
+    alerts.on_alert_with(move |a: &Alert| log.borrow_mut().push(a.clone()));
+    let mut engine = RuleEngine::new(vec![overheat]);
+
+    let temperatures = [28.0, 31.0, 32.5, 33.0, 31.2, 29.9, 29.0, 31.5, 32.0, 27.0];
+    let mut returned = Vec::new();
+    for (i, &t) in temperatures.iter().enumerate() {
+        let reading = Reading::new("temperature", t, i as u64 * 1000);
+        let wrapped = alerts.process_reading(&reading);
+        let expected: Vec<Alert> = engine
+            .process(&reading)
+            .into_iter()
+            .map(Alert::from)
+            .collect();
+        assert_eq!(wrapped, expected, "at {} ms", i * 1000);
+        returned.extend(wrapped);
+    }
+    // Raised after 2 s above 31, cleared once below 29.5
+    let events: Vec<(bool, f64)> = returned.iter().map(|a| (a.raised, a.at_ms)).collect();
+    assert_eq!(events, [(true, 4000.0), (false, 6000.0)]);
+    assert_eq!(*heard.borrow(), returned);
+    assert!(alerts.active().is_empty());
+}
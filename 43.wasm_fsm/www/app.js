@@ -0,0 +1,78 @@
+// Build first: `wasm-pack build --target web` writes ../pkg/
+import init, {
+  AlertEngine,
+  Light,
+  PowerInput,
+  PowerManager,
+  PowerMode,
+  RuleSet,
+  TrafficLight,
+} from "../pkg/wasm_fsm.js";
+
+const $ = (id) => document.getElementById(id);
+
+function log(line) {
+  const out = $("log");
+  out.textContent += line + "\n";
+  out.scrollTop = out.scrollHeight;
+}
+
+await init();
+
+// Traffic light: the lamps are redrawn from the change notification only
+const light = new TrafficLight(Light.Red);
+function drawLight() {
+  for (const name of ["Red", "Yellow", "Green"]) {
+    $(`lamp-${name.toLowerCase()}`).classList.toggle("on", light.state === Light[name]);
+  }
+}
+light.onChange((change) => {
+  log(`light: ${change.from} --${change.event}--> ${change.to}`);
+  drawLight();
+});
+$("tick").onclick = () => light.tick();
+drawLight();
+
+// Power manager: a rejected event throws, like the Rust version returns Err
+const power = new PowerManager();
+function drawPower() {
+  $("power-state").textContent = PowerMode[power.state];
+  const hours = power.batteryLifeHours(1000);
+  $("power-stats").textContent =
+    `${power.consumedMah.toFixed(3)} mAh used, ` +
+    (hours === undefined ? "no estimate yet" : `${hours.toFixed(0)} h on 1000 mAh`);
+}
+power.onChange((change) => log(`power: ${change.from} --${change.event}--> ${change.to}`));
+for (const button of document.querySelectorAll("button[data-input]")) {
+  button.onclick = () => {
+    try {
+      power.handle(PowerInput[button.dataset.input]);
+    } catch (e) {
+      log(`power: rejected, ${e.message}`);
+    }
+    drawPower();
+  };
+}
+$("wait").onclick = () => {
+  power.advance(60);
+  drawPower();
+};
+drawPower();
+
+// Alerts: one reading per slider change, timestamped with the page clock
+const rules = new RuleSet();
+rules.above("overheat", "temperature", 31, 1.5, 2000);
+const alerts = new AlertEngine(rules);
+alerts.onAlert((alert) => {
+  log(`alert: ${alert.rule} ${alert.raised ? "RAISED" : "cleared"} at ${alert.atMs} ms`);
+});
+function feed() {
+  const value = Number($("temperature").value);
+  $("temperature-value").textContent = value.toFixed(1);
+  alerts.process("temperature", value, performance.now());
+  $("active").textContent = alerts.active().join(", ") || "none";
+}
+$("temperature").oninput = feed;
+// `for_at_least` needs readings over time, not only on slider moves
+setInterval(feed, 500);
+feed();
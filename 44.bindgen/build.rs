@@ -0,0 +1,49 @@
+// Build steps that run before the crate compiles:
+// 1. cc compiles the vendored C library into a static archive and tells
+//    cargo to link it
+// 2. with `--features bindgen`, bindgen parses vendor/checksum/checksum.h
+//    and writes bindings.rs to OUT_DIR, which src/sys.rs includes instead
+//    of the committed src/bindings.rs; without the feature a build needs no
+//    libclang. A build script never writes into the source tree
+
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let vendor = crate_dir.join("vendor").join("checksum");
+
+    cc::Build::new()
+        .file(vendor.join("checksum.c"))
+        .include(&vendor)
+        .warnings(true)
+        .extra_warnings(true)
+        .compile("checksum");
+
+    #[cfg(feature = "bindgen")]
+    generate_bindings(
+        &vendor.join("checksum.h"),
+        &PathBuf::from(env::var("OUT_DIR").unwrap()),
+    );
+
+    println!("cargo:rerun-if-changed=vendor/checksum");
+}
+
+#[cfg(feature = "bindgen")]
+fn generate_bindings(header: &std::path::Path, out_dir: &std::path::Path) {
+    bindgen::Builder::default()
+        .header(header.to_string_lossy())
+        // Only the library's own names, not everything <stdint.h> pulls in
+        .allowlist_function("cks_.*")
+        .allowlist_type("cks_.*")
+        .allowlist_var("CKS_.*")
+        // C enums as integer constants: C may hand back any value, and an
+        // out-of-range Rust enum would be undefined behaviour
+        .default_enum_style(bindgen::EnumVariation::Consts)
+        .prepend_enum_name(false)
+        .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
+        .generate()
+        .expect("bindgen could not parse checksum.h")
+        .write_to_file(out_dir.join("bindings.rs"))
+        .expect("OUT_DIR is writable");
+}
@@ -0,0 +1,57 @@
+/* automatically generated by rust-bindgen 0.72.1 */
+
+pub const CKS_VERSION_MAJOR: u32 = 1;
+pub const CKS_VERSION_MINOR: u32 = 2;
+pub const CKS_CRC8: cks_algorithm = 0;
+pub const CKS_CRC16_CCITT: cks_algorithm = 1;
+pub const CKS_CRC16_MODBUS: cks_algorithm = 2;
+pub const CKS_CRC32: cks_algorithm = 3;
+pub type cks_algorithm = ::std::os::raw::c_uint;
+pub const CKS_OK: cks_status = 0;
+pub const CKS_ERR_NULL: cks_status = -1;
+pub const CKS_ERR_ALGORITHM: cks_status = -2;
+pub const CKS_ERR_LENGTH: cks_status = -3;
+pub type cks_status = ::std::os::raw::c_int;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct cks_ctx {
+    pub algorithm: cks_algorithm,
+    pub crc: u32,
+    pub length: u64,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of cks_ctx"][::std::mem::size_of::<cks_ctx>() - 16usize];
+    ["Alignment of cks_ctx"][::std::mem::align_of::<cks_ctx>() - 8usize];
+    ["Offset of field: cks_ctx::algorithm"][::std::mem::offset_of!(cks_ctx, algorithm) - 0usize];
+    ["Offset of field: cks_ctx::crc"][::std::mem::offset_of!(cks_ctx, crc) - 4usize];
+    ["Offset of field: cks_ctx::length"][::std::mem::offset_of!(cks_ctx, length) - 8usize];
+};
+unsafe extern "C" {
+    pub fn cks_init(ctx: *mut cks_ctx, algorithm: cks_algorithm) -> cks_status;
+}
+unsafe extern "C" {
+    pub fn cks_update(ctx: *mut cks_ctx, data: *const u8, len: usize) -> cks_status;
+}
+unsafe extern "C" {
+    pub fn cks_final(ctx: *const cks_ctx, out: *mut u32) -> cks_status;
+}
+unsafe extern "C" {
+    pub fn cks_find(
+        name: *const ::std::os::raw::c_char,
+        out: *mut cks_algorithm,
+    ) -> cks_status;
+}
+unsafe extern "C" {
+    pub fn cks_name(algorithm: cks_algorithm) -> *const ::std::os::raw::c_char;
+}
+unsafe extern "C" {
+    pub fn cks_width(algorithm: cks_algorithm) -> ::std::os::raw::c_int;
+}
+unsafe extern "C" {
+    pub fn cks_format(
+        ctx: *const cks_ctx,
+        buf: *mut ::std::os::raw::c_char,
+        cap: usize,
+    ) -> ::std::os::raw::c_int;
+}
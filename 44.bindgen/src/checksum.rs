@@ -0,0 +1,216 @@
+// The safe wrapper
+//
+// The C rules this layer enforces:
+// - a `cks_ctx` is only used after `cks_init` succeeded (`Checksum::new`)
+// - algorithm values are always in range (`Algorithm` is a Rust enum, and
+//   raw values are converted with `from_raw`, never transmuted)
+// - `data` and `len` come from the same slice
+// - every `cks_status` is turned into a `Result`
+
+use crate::sys;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    NullPointer,
+    UnknownAlgorithm(String),
+    InvalidLength,
+    // The name passed to `from_name` contained a NUL byte
+    InteriorNul,
+    BufferTooSmall { needed: usize },
+    // A status this wrapper does not know, e.g. from a newer library
+    Unknown(c_int),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NullPointer => write!(f, "null pointer passed to the C library"),
+            Error::UnknownAlgorithm(name) => write!(f, "unknown algorithm '{}'", name),
+            Error::InvalidLength => write!(f, "no data pointer for a non-zero length"),
+            Error::InteriorNul => write!(f, "name contains a NUL byte"),
+            Error::BufferTooSmall { needed } => {
+                write!(f, "buffer too small, {} bytes needed", needed)
+            }
+            Error::Unknown(code) => write!(f, "unknown status code {}", code),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Error {
+    // `context` names what the caller asked for, for `UnknownAlgorithm`
+    fn check(status: sys::cks_status, context: &str) -> Result<(), Error> {
+        match status {
+            sys::CKS_OK => Ok(()),
+            sys::CKS_ERR_NULL => Err(Error::NullPointer),
+            sys::CKS_ERR_ALGORITHM => Err(Error::UnknownAlgorithm(context.to_string())),
+            sys::CKS_ERR_LENGTH => Err(Error::InvalidLength),
+            other => Err(Error::Unknown(other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Crc8,
+    Crc16Ccitt,
+    Crc16Modbus,
+    Crc32,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 4] = [
+        Algorithm::Crc8,
+        Algorithm::Crc16Ccitt,
+        Algorithm::Crc16Modbus,
+        Algorithm::Crc32,
+    ];
+
+    pub fn raw(self) -> sys::cks_algorithm {
+        match self {
+            Algorithm::Crc8 => sys::CKS_CRC8,
+            Algorithm::Crc16Ccitt => sys::CKS_CRC16_CCITT,
+            Algorithm::Crc16Modbus => sys::CKS_CRC16_MODBUS,
+            Algorithm::Crc32 => sys::CKS_CRC32,
+        }
+    }
+
+    pub fn from_raw(raw: sys::cks_algorithm) -> Option<Algorithm> {
+        Algorithm::ALL.into_iter().find(|a| a.raw() == raw)
+    }
+
+    // Looked up by the C library, so names always agree with it
+    pub fn from_name(name: &str) -> Result<Algorithm, Error> {
+        let c_name = CString::new(name).map_err(|_| Error::InteriorNul)?;
+        let mut raw = 0;
+        // SAFETY: `c_name` is NUL-terminated and `raw` is a valid out-pointer
+        let status = unsafe { sys::cks_find(c_name.as_ptr(), &mut raw) };
+        Error::check(status, name)?;
+        Algorithm::from_raw(raw).ok_or(Error::Unknown(raw as c_int))
+    }
+
+    pub fn name(self) -> &'static str {
+        // SAFETY: for a known algorithm `cks_name` returns a pointer to a
+        // static, NUL-terminated string literal
+        let name = unsafe { CStr::from_ptr(sys::cks_name(self.raw())) };
+        name.to_str().unwrap_or("?")
+    }
+
+    pub fn width(self) -> u32 {
+        // SAFETY: no pointers involved
+        unsafe { sys::cks_width(self.raw()) as u32 }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Algorithm, Error> {
+        Algorithm::from_name(name)
+    }
+}
+
+// Owns its `cks_ctx` by value: no allocation and no `Drop`, as in C
+#[derive(Debug, Clone, Copy)]
+pub struct Checksum {
+    ctx: sys::cks_ctx,
+}
+
+impl Checksum {
+    pub fn new(algorithm: Algorithm) -> Checksum {
+        let mut ctx = sys::cks_ctx {
+            algorithm: 0,
+            crc: 0,
+            length: 0,
+        };
+        // SAFETY: `ctx` is a valid, writable context
+        let status = unsafe { sys::cks_init(&mut ctx, algorithm.raw()) };
+        // Cannot fail: the pointer is valid and the algorithm is in range
+        debug_assert_eq!(status, sys::CKS_OK);
+        Checksum { ctx }
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        // Only `cks_init` writes the field, with a value from `Algorithm::raw`
+        Algorithm::from_raw(self.ctx.algorithm).unwrap_or(Algorithm::Crc32)
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        // SAFETY: pointer and length describe one live slice (an empty
+        // slice has a dangling but non-null pointer, and len 0)
+        let status = unsafe { sys::cks_update(&mut self.ctx, data.as_ptr(), data.len()) };
+        debug_assert_eq!(status, sys::CKS_OK);
+    }
+
+    pub fn value(&self) -> u32 {
+        let mut out = 0;
+        // SAFETY: both pointers refer to live values
+        let status = unsafe { sys::cks_final(&self.ctx, &mut out) };
+        debug_assert_eq!(status, sys::CKS_OK);
+        out
+    }
+
+    pub fn bytes_processed(&self) -> u64 {
+        self.ctx.length
+    }
+
+    pub fn reset(&mut self) {
+        *self = Checksum::new(self.algorithm());
+    }
+
+    // Formats into a caller buffer, the way C callers use `cks_format`
+    pub fn write_hex<'b>(&self, buf: &'b mut [u8]) -> Result<&'b str, Error> {
+        // SAFETY: `buf` is writable for `buf.len()` bytes
+        let written =
+            unsafe { sys::cks_format(&self.ctx, buf.as_mut_ptr().cast::<c_char>(), buf.len()) };
+        // Negative values are a `cks_status`, anything else is a length
+        Error::check(written.min(sys::CKS_OK), self.algorithm().name())?;
+        let len = written as usize;
+        if len >= buf.len() {
+            return Err(Error::BufferTooSmall { needed: len + 1 });
+        }
+        // The library only writes the digits 0-9 and A-F
+        std::str::from_utf8(&buf[..len]).map_err(|_| Error::Unknown(written))
+    }
+
+    // snprintf's two-call pattern: ask for the length, then fill
+    pub fn to_hex(&self) -> String {
+        let needed = match self.write_hex(&mut []) {
+            Err(Error::BufferTooSmall { needed }) => needed,
+            _ => 1,
+        };
+        let mut buf = vec![0; needed];
+        self.write_hex(&mut buf)
+            .map(String::from)
+            .unwrap_or_default()
+    }
+}
+
+// Lets `io::copy` checksum a file or a socket directly
+impl io::Write for Checksum {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub fn checksum(algorithm: Algorithm, data: &[u8]) -> u32 {
+    let mut crc = Checksum::new(algorithm);
+    crc.update(data);
+    crc.value()
+}
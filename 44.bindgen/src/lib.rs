@@ -0,0 +1,11 @@
+// Binding an existing C library with bindgen
+//
+// Two layers over vendor/checksum:
+// - `sys`: the raw declarations bindgen generated from checksum.h
+// - `checksum`: a safe API (`Algorithm`, `Checksum`, `Error`) that upholds
+//   the C library's rules so callers never write `unsafe`
+
+mod checksum;
+pub mod sys;
+
+pub use checksum::{checksum, Algorithm, Checksum, Error};
@@ -0,0 +1,161 @@
+use bindgen_lesson::{checksum, sys, Algorithm, Checksum, Error};
+use crc::{Crc, Crc16Ccitt, Crc16Modbus, Crc32, Crc8, CHECK_INPUT};
+use std::ffi::CStr;
+use std::io;
+use std::mem::{align_of, size_of};
+use std::ptr;
+
+struct Noise(u64);
+
+impl Noise {
+    fn next_byte(&mut self) -> u8 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 56) as u8
+    }
+}
+
+// The pure-Rust implementation from 18.crc, as the reference
+fn reference(algorithm: Algorithm, data: &[u8]) -> u32 {
+    match algorithm {
+        Algorithm::Crc8 => Crc8::checksum(data) as u32,
+        Algorithm::Crc16Ccitt => Crc16Ccitt::checksum(data) as u32,
+        Algorithm::Crc16Modbus => Crc16Modbus::checksum(data) as u32,
+        Algorithm::Crc32 => Crc32::checksum(data),
+    }
+}
+
+fn main() {
+    println!("=== bindgen FFI Examples ===\n");
+
+    // 1. The raw layer: bindgen's declarations, called directly
+    println!("1. Raw bindings (unsafe):");
+    println!(
+        "   libchecksum {}.{}, cks_ctx is {} bytes, align {}",
+        sys::CKS_VERSION_MAJOR,
+        sys::CKS_VERSION_MINOR,
+        size_of::<sys::cks_ctx>(),
+        align_of::<sys::cks_ctx>()
+    );
+    // SAFETY: `ctx` and `out` are live locals; the data pointer and length
+    // come from one slice; null is passed only where the test expects it
+    unsafe {
+        let mut ctx = sys::cks_ctx {
+            algorithm: 0,
+            crc: 0,
+            length: 0,
+        };
+        let mut out = 0u32;
+        sys::cks_init(&mut ctx, sys::CKS_CRC32);
+        sys::cks_update(&mut ctx, CHECK_INPUT.as_ptr(), CHECK_INPUT.len());
+        sys::cks_final(&ctx, &mut out);
+        println!("   cks_final -> {:#010X} after {} bytes", out, ctx.length);
+        println!("   CRC-32 check value is {:#010X}", Crc32::CHECK);
+
+        let name = CStr::from_ptr(sys::cks_name(sys::CKS_CRC16_MODBUS));
+        println!("   cks_name(CKS_CRC16_MODBUS) -> {:?}", name);
+        println!("   cks_name(99) -> {:?}", sys::cks_name(99));
+        println!(
+            "   cks_init(NULL) -> {} (CKS_ERR_NULL is {})",
+            sys::cks_init(ptr::null_mut(), sys::CKS_CRC8),
+            sys::CKS_ERR_NULL
+        );
+        println!(
+            "   cks_init(ctx, 99) -> {} (CKS_ERR_ALGORITHM is {})",
+            sys::cks_init(&mut ctx, 99),
+            sys::CKS_ERR_ALGORITHM
+        );
+        println!(
+            "   cks_update(ctx, NULL, 4) -> {} (CKS_ERR_LENGTH is {})",
+            sys::cks_update(&mut ctx, ptr::null(), 4),
+            sys::CKS_ERR_LENGTH
+        );
+    }
+
+    // 2. The safe layer
+    println!("\n2. Safe API:");
+    for algorithm in Algorithm::ALL {
+        let value = checksum(algorithm, CHECK_INPUT);
+        let mut crc = Checksum::new(algorithm);
+        for chunk in CHECK_INPUT.chunks(4) {
+            crc.update(chunk);
+        }
+        println!(
+            "   {:<13} {:>2} bits  one-shot {:>8X}  streamed {:>8}",
+            algorithm,
+            algorithm.width(),
+            value,
+            crc.to_hex()
+        );
+    }
+
+    // 3. C status codes as Rust errors
+    println!("\n3. Errors:");
+    let mut crc = Checksum::new(Algorithm::Crc32);
+    crc.update(CHECK_INPUT);
+    let mut small = [0u8; 4];
+    let mut exact = [0u8; 9];
+    let cases: [(&str, Result<String, Error>); 5] = [
+        (
+            "from_name(\"crc16-ccitt\")",
+            "crc16-ccitt".parse().map(|a: Algorithm| format!("{:?}", a)),
+        ),
+        (
+            "from_name(\"crc64\")",
+            Algorithm::from_name("crc64").map(|a| a.to_string()),
+        ),
+        (
+            "from_name(\"crc\\0 8\")",
+            Algorithm::from_name("crc\0 8").map(|a| a.to_string()),
+        ),
+        (
+            "write_hex into 4 bytes",
+            crc.write_hex(&mut small).map(String::from),
+        ),
+        (
+            "write_hex into 9 bytes",
+            crc.write_hex(&mut exact).map(String::from),
+        ),
+    ];
+    for (label, result) in cases {
+        match result {
+            Ok(value) => println!("   {:<28} Ok({})", label, value),
+            Err(e) => println!("   {:<28} Err: {}", label, e),
+        }
+    }
+
+    // 4. C against the Rust implementation from 18.crc
+    println!("\n4. Cross-check against 18.crc:");
+    let mut noise = Noise(7);
+    let data: Vec<u8> = (0..4096).map(|_| noise.next_byte()).collect();
+    for algorithm in Algorithm::ALL {
+        println!(
+            "   {:<13} C {:>8X}, Rust {:>8X} over 4 KiB",
+            algorithm,
+            checksum(algorithm, &data),
+            reference(algorithm, &data)
+        );
+    }
+
+    // 5. Checksum as an io::Write sink
+    println!("\n5. io::copy into a Checksum:");
+    let mut sink = Checksum::new(Algorithm::Crc32);
+    let copied = io::copy(&mut io::Cursor::new(&data), &mut sink).unwrap_or(0);
+    println!(
+        "   {} bytes copied, {} processed, crc {}",
+        copied,
+        sink.bytes_processed(),
+        sink.to_hex()
+    );
+    println!("   one-shot crc {:08X}", checksum(Algorithm::Crc32, &data));
+    sink.reset();
+    println!(
+        "   after reset: {} bytes, crc {}",
+        sink.bytes_processed(),
+        sink.to_hex()
+    );
+
+    println!("\n=== End of bindgen FFI Examples ===");
+}
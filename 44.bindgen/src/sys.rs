@@ -0,0 +1,13 @@
+// The raw C API, exactly as bindgen generated it from checksum.h
+//
+// Everything here is `unsafe` to call and uses C's types and naming.
+// `cargo build --features bindgen` generates the file into OUT_DIR and uses
+// that; otherwise the committed copy is used.
+
+#![allow(non_camel_case_types, non_upper_case_globals, dead_code)]
+
+#[cfg(feature = "bindgen")]
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+#[cfg(not(feature = "bindgen"))]
+include!("bindings.rs");
@@ -0,0 +1,138 @@
+// The safe layer, and the C library against the pure-Rust CRCs of 18.crc
+use bindgen_lesson::{checksum, Algorithm, Checksum, Error};
+use crc::{Crc, Crc16Ccitt, Crc16Modbus, Crc32, Crc8, CHECK_INPUT};
+use std::io;
+
+fn reference(algorithm: Algorithm, data: &[u8]) -> u32 {
+    match algorithm {
+        Algorithm::Crc8 => Crc8::checksum(data) as u32,
+        Algorithm::Crc16Ccitt => Crc16Ccitt::checksum(data) as u32,
+        Algorithm::Crc16Modbus => Crc16Modbus::checksum(data) as u32,
+        Algorithm::Crc32 => Crc32::checksum(data),
+    }
+}
+
+fn noise(len: usize) -> Vec<u8> {
+    let mut state = 7u64;
+    (0..len)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 56) as u8
+        })
+        .collect()
+}
+
+#[test]
+fn check_values_and_hex() {
+    let cases = [
+        (Algorithm::Crc8, 8, "F4"),
+        (Algorithm::Crc16Ccitt, 16, "29B1"),
+        (Algorithm::Crc16Modbus, 16, "4B37"),
+        (Algorithm::Crc32, 32, "CBF43926"),
+    ];
+    for (algorithm, width, hex) in cases {
+        assert_eq!(algorithm.width(), width);
+        let mut crc = Checksum::new(algorithm);
+        crc.update(CHECK_INPUT);
+        assert_eq!(crc.to_hex(), hex, "{}", algorithm);
+        assert_eq!(crc.value(), u32::from_str_radix(hex, 16).unwrap());
+        assert_eq!(crc.value(), checksum(algorithm, CHECK_INPUT));
+        assert_eq!(crc.algorithm(), algorithm);
+    }
+}
+
+#[test]
+fn agrees_with_18_crc_on_random_data() {
+    let data = noise(4096);
+    for algorithm in Algorithm::ALL {
+        for n in [0, 1, 7, 64, 1000, data.len()] {
+            assert_eq!(
+                checksum(algorithm, &data[..n]),
+                reference(algorithm, &data[..n]),
+                "{} over {} bytes",
+                algorithm,
+                n
+            );
+        }
+    }
+}
+
+#[test]
+fn streaming_reset_and_empty_updates() {
+    let data = noise(1000);
+    let mut crc = Checksum::new(Algorithm::Crc16Modbus);
+    crc.update(&[]);
+    for chunk in data.chunks(33) {
+        crc.update(chunk);
+    }
+    assert_eq!(crc.value(), checksum(Algorithm::Crc16Modbus, &data));
+    assert_eq!(crc.bytes_processed(), 1000);
+
+    crc.reset();
+    assert_eq!(crc.bytes_processed(), 0);
+    assert_eq!(crc.value(), checksum(Algorithm::Crc16Modbus, &[]));
+    assert_eq!(crc.algorithm(), Algorithm::Crc16Modbus);
+}
+
+#[test]
+fn names_parse_and_display() {
+    for algorithm in Algorithm::ALL {
+        let name = algorithm.to_string();
+        assert_eq!(name.parse::<Algorithm>(), Ok(algorithm));
+        assert_eq!(Algorithm::from_raw(algorithm.raw()), Some(algorithm));
+    }
+    assert_eq!(Algorithm::Crc16Ccitt.name(), "crc16-ccitt");
+    assert_eq!(format!("[{:>6}]", Algorithm::Crc8), "[  crc8]");
+    assert_eq!(Algorithm::from_raw(99), None);
+}
+
+#[test]
+fn c_status_codes_become_errors() {
+    assert_eq!(
+        Algorithm::from_name("crc64"),
+        Err(Error::UnknownAlgorithm("crc64".to_string()))
+    );
+    assert_eq!(
+        Algorithm::from_name("CRC32"),
+        Err(Error::UnknownAlgorithm("CRC32".to_string()))
+    );
+    assert_eq!(Algorithm::from_name("crc\0 8"), Err(Error::InteriorNul));
+    assert_eq!(
+        Error::UnknownAlgorithm("crc64".to_string()).to_string(),
+        "unknown algorithm 'crc64'"
+    );
+}
+
+#[test]
+fn write_hex_reports_the_size_it_needs() {
+    let mut crc = Checksum::new(Algorithm::Crc32);
+    crc.update(CHECK_INPUT);
+    assert_eq!(
+        crc.write_hex(&mut [0u8; 4]),
+        Err(Error::BufferTooSmall { needed: 9 })
+    );
+    assert_eq!(
+        crc.write_hex(&mut [0u8; 8]),
+        Err(Error::BufferTooSmall { needed: 9 })
+    );
+    assert_eq!(
+        crc.write_hex(&mut []),
+        Err(Error::BufferTooSmall { needed: 9 })
+    );
+    let mut exact = [0u8; 9];
+    assert_eq!(crc.write_hex(&mut exact), Ok("CBF43926"));
+    let mut roomy = [0u8; 32];
+    assert_eq!(crc.write_hex(&mut roomy), Ok("CBF43926"));
+}
+
+#[test]
+fn io_copy_into_a_checksum() {
+    let data = noise(10_000);
+    let mut sink = Checksum::new(Algorithm::Crc32);
+    let copied = io::copy(&mut io::Cursor::new(&data), &mut sink).unwrap();
+    assert_eq!(copied, 10_000);
+    assert_eq!(sink.bytes_processed(), 10_000);
+    assert_eq!(sink.value(), checksum(Algorithm::Crc32, &data));
+}
@@ -0,0 +1,155 @@
+// The raw layer, called the way C code would call it
+use bindgen_lesson::sys;
+use crc::{Crc, Crc16Ccitt, Crc16Modbus, Crc32, Crc8, CHECK_INPUT};
+use std::ffi::CStr;
+use std::mem::{align_of, offset_of, size_of};
+use std::ptr;
+
+fn zeroed() -> sys::cks_ctx {
+    sys::cks_ctx {
+        algorithm: 0,
+        crc: 0,
+        length: 0,
+    }
+}
+
+// SAFETY (for callers): `data` is one live slice
+unsafe fn raw_checksum(algorithm: sys::cks_algorithm, data: &[u8]) -> (sys::cks_status, u32) {
+    let mut ctx = zeroed();
+    let mut out = 0;
+    let status = sys::cks_init(&mut ctx, algorithm);
+    if status != sys::CKS_OK {
+        return (status, 0);
+    }
+    assert_eq!(
+        sys::cks_update(&mut ctx, data.as_ptr(), data.len()),
+        sys::CKS_OK
+    );
+    assert_eq!(sys::cks_final(&ctx, &mut out), sys::CKS_OK);
+    (sys::CKS_OK, out)
+}
+
+#[test]
+fn layout_matches_the_c_struct() {
+    assert_eq!(size_of::<sys::cks_ctx>(), 16);
+    assert_eq!(align_of::<sys::cks_ctx>(), 8);
+    assert_eq!(offset_of!(sys::cks_ctx, algorithm), 0);
+    assert_eq!(offset_of!(sys::cks_ctx, crc), 4);
+    assert_eq!(offset_of!(sys::cks_ctx, length), 8);
+    assert_eq!((sys::CKS_VERSION_MAJOR, sys::CKS_VERSION_MINOR), (1, 2));
+}
+
+#[test]
+fn check_values_of_every_algorithm() {
+    let cases = [
+        (sys::CKS_CRC8, Crc8::CHECK as u32),
+        (sys::CKS_CRC16_CCITT, Crc16Ccitt::CHECK as u32),
+        (sys::CKS_CRC16_MODBUS, Crc16Modbus::CHECK as u32),
+        (sys::CKS_CRC32, Crc32::CHECK),
+    ];
+    for (algorithm, check) in cases {
+        // SAFETY: CHECK_INPUT is a live slice
+        let (status, value) = unsafe { raw_checksum(algorithm, CHECK_INPUT) };
+        assert_eq!(status, sys::CKS_OK);
+        assert_eq!(value, check, "algorithm {}", algorithm);
+    }
+}
+
+#[test]
+fn streaming_in_pieces_equals_one_call() {
+    let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+    // SAFETY: `ctx` and `out` are live locals, every slice is live
+    unsafe {
+        let (_, whole) = raw_checksum(sys::CKS_CRC32, &data);
+        let mut ctx = zeroed();
+        let mut out = 0;
+        assert_eq!(sys::cks_init(&mut ctx, sys::CKS_CRC32), sys::CKS_OK);
+        for chunk in data.chunks(7) {
+            assert_eq!(
+                sys::cks_update(&mut ctx, chunk.as_ptr(), chunk.len()),
+                sys::CKS_OK
+            );
+            // cks_final doesn't change the context, so it can be read midway
+            assert_eq!(sys::cks_final(&ctx, &mut out), sys::CKS_OK);
+        }
+        assert_eq!(out, whole);
+        assert_eq!(ctx.length, 1000);
+    }
+}
+
+#[test]
+fn status_codes_for_bad_arguments() {
+    // SAFETY: null is passed only where the library checks for it
+    unsafe {
+        let mut ctx = zeroed();
+        let mut out = 0;
+        assert_eq!(
+            sys::cks_init(ptr::null_mut(), sys::CKS_CRC8),
+            sys::CKS_ERR_NULL
+        );
+        assert_eq!(sys::cks_init(&mut ctx, 99), sys::CKS_ERR_ALGORITHM);
+        assert_eq!(sys::cks_init(&mut ctx, sys::CKS_CRC8), sys::CKS_OK);
+        assert_eq!(
+            sys::cks_update(&mut ctx, ptr::null(), 4),
+            sys::CKS_ERR_LENGTH
+        );
+        // NULL with a length of 0 is an empty update, not an error
+        assert_eq!(sys::cks_update(&mut ctx, ptr::null(), 0), sys::CKS_OK);
+        assert_eq!(sys::cks_final(ptr::null(), &mut out), sys::CKS_ERR_NULL);
+        assert_eq!(sys::cks_final(&ctx, ptr::null_mut()), sys::CKS_ERR_NULL);
+    }
+}
+
+#[test]
+fn names_widths_and_lookup() {
+    let cases = [
+        (sys::CKS_CRC8, c"crc8", 8),
+        (sys::CKS_CRC16_CCITT, c"crc16-ccitt", 16),
+        (sys::CKS_CRC16_MODBUS, c"crc16-modbus", 16),
+        (sys::CKS_CRC32, c"crc32", 32),
+    ];
+    // SAFETY: names are NUL-terminated literals, `found` is a live local
+    unsafe {
+        for (algorithm, name, width) in cases {
+            assert_eq!(CStr::from_ptr(sys::cks_name(algorithm)), name);
+            assert_eq!(sys::cks_width(algorithm), width);
+            let mut found = 99;
+            assert_eq!(sys::cks_find(name.as_ptr(), &mut found), sys::CKS_OK);
+            assert_eq!(found, algorithm);
+        }
+        assert!(sys::cks_name(99).is_null());
+        assert_eq!(sys::cks_width(99), 0);
+        let mut found = 0;
+        assert_eq!(
+            sys::cks_find(c"CRC32".as_ptr(), &mut found),
+            sys::CKS_ERR_ALGORITHM
+        );
+        assert_eq!(sys::cks_find(ptr::null(), &mut found), sys::CKS_ERR_NULL);
+    }
+}
+
+#[test]
+fn format_is_snprintf_style() {
+    // SAFETY: every buffer pointer is valid for the capacity passed with it
+    unsafe {
+        let mut ctx = zeroed();
+        sys::cks_init(&mut ctx, sys::CKS_CRC32);
+        sys::cks_update(&mut ctx, CHECK_INPUT.as_ptr(), CHECK_INPUT.len());
+
+        // Capacity 0 may pass NULL and still reports the full length
+        assert_eq!(sys::cks_format(&ctx, ptr::null_mut(), 0), 8);
+
+        let mut small = [0x7F_u8 as std::ffi::c_char; 4];
+        assert_eq!(sys::cks_format(&ctx, small.as_mut_ptr(), small.len()), 8);
+        assert_eq!(CStr::from_ptr(small.as_ptr()), c"CBF");
+
+        let mut exact = [0 as std::ffi::c_char; 9];
+        assert_eq!(sys::cks_format(&ctx, exact.as_mut_ptr(), exact.len()), 8);
+        assert_eq!(CStr::from_ptr(exact.as_ptr()), c"CBF43926");
+
+        assert_eq!(
+            sys::cks_format(ptr::null(), exact.as_mut_ptr(), exact.len()),
+            sys::CKS_ERR_NULL
+        );
+    }
+}
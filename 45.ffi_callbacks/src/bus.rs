@@ -0,0 +1,177 @@
+// Safe wrapper: Rust closures in, C function pointers out
+//
+// C cannot call a closure, only a function pointer, so each closure type `F`
+// gets its own `trampoline::<F>`. The closure itself travels as the
+// `void *user_data` and the trampoline casts it back to `F`. What differs
+// between the two APIs is who owns that pointer and for how long:
+// - `for_each`: C uses it only during the call, so a `&mut F` on the stack
+//   is enough and `F` may borrow local variables
+// - `subscribe`: C keeps it, so it must be a `Box<F>` with `F: 'static`,
+//   and C hands it back through `drop_box::<F>` when the subscription ends
+
+use crate::sys::{self, Sample};
+use std::any::Any;
+use std::cell::Cell;
+use std::ffi::{c_int, c_void};
+use std::fmt;
+use std::ops::ControlFlow;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr::NonNull;
+
+// A panic must not unwind through C frames (it would abort the process), so
+// the trampoline catches it, parks the payload here and returns BUS_STOP.
+// Once C has returned, the wrapper resumes the panic on the Rust side.
+thread_local! {
+    static PANIC: Cell<Option<Box<dyn Any + Send>>> = const { Cell::new(None) };
+}
+
+fn resume_pending_panic() {
+    if let Some(payload) = PANIC.take() {
+        panic::resume_unwind(payload);
+    }
+}
+
+unsafe extern "C" fn trampoline<F>(user_data: *mut c_void, sample: *const Sample) -> c_int
+where
+    F: FnMut(&Sample) -> ControlFlow<()>,
+{
+    // SAFETY: `user_data` is the `F` registered together with this exact
+    // trampoline, and `sample` is valid for the duration of the call
+    let (callback, sample) = (&mut *user_data.cast::<F>(), &*sample);
+    match panic::catch_unwind(AssertUnwindSafe(|| callback(sample))) {
+        Ok(ControlFlow::Continue(())) => sys::BUS_CONTINUE,
+        Ok(ControlFlow::Break(())) => sys::BUS_STOP,
+        Err(payload) => {
+            PANIC.set(Some(payload));
+            sys::BUS_STOP
+        }
+    }
+}
+
+unsafe extern "C" fn drop_box<F>(user_data: *mut c_void) {
+    // SAFETY: `user_data` came from `Box::<F>::into_raw` in `subscribe`,
+    // and the bus calls this exactly once per subscription
+    let callback = Box::from_raw(user_data.cast::<F>());
+    // Dropping a closure runs the destructors of its captures; one of those
+    // panicking must not reach C either
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(callback)));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusError {
+    NullPointer,
+    NotFound,
+    Unknown(c_int),
+}
+
+impl fmt::Display for BusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BusError::NullPointer => write!(f, "null pointer"),
+            BusError::NotFound => write!(f, "no such subscription"),
+            BusError::Unknown(code) => write!(f, "unknown status {}", code),
+        }
+    }
+}
+
+impl std::error::Error for BusError {}
+
+fn check(status: c_int) -> Result<c_int, BusError> {
+    match status {
+        s if s >= 0 => Ok(s),
+        sys::BUS_ERR_NULL => Err(BusError::NullPointer),
+        sys::BUS_ERR_NOT_FOUND => Err(BusError::NotFound),
+        other => Err(BusError::Unknown(other)),
+    }
+}
+
+// Only the id: ending a subscription goes through the bus that owns it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Subscription(c_int);
+
+// `*mut` keeps `Bus` !Send and !Sync: the C bus is not thread-safe
+pub struct Bus {
+    raw: NonNull<sys::Bus>,
+}
+
+impl Default for Bus {
+    fn default() -> Self {
+        Bus::new()
+    }
+}
+
+impl Bus {
+    pub fn new() -> Bus {
+        // SAFETY: no arguments; the result is checked for null
+        let raw = unsafe { sys::bus_new() };
+        Bus {
+            raw: NonNull::new(raw).expect("bus_new returned null"),
+        }
+    }
+
+    // `sensor_id` of `None` receives every sensor. Return
+    // `ControlFlow::Break(())` from the callback to unsubscribe.
+    pub fn subscribe<F>(&mut self, sensor_id: Option<u16>, callback: F) -> Subscription
+    where
+        F: FnMut(&Sample) -> ControlFlow<()> + 'static,
+    {
+        let user_data = Box::into_raw(Box::new(callback)).cast::<c_void>();
+        // SAFETY: the bus pointer is live; `user_data` stays valid until the
+        // bus passes it to `drop_box::<F>`, which matches its type
+        let status = unsafe {
+            sys::bus_subscribe(
+                self.raw.as_ptr(),
+                sensor_id.unwrap_or(sys::BUS_ALL_SENSORS),
+                Some(trampoline::<F>),
+                user_data,
+                Some(drop_box::<F>),
+            )
+        };
+        // Both pointers are non-null, so the bus accepted the subscription
+        // and now owns `user_data`
+        Subscription(status)
+    }
+
+    pub fn unsubscribe(&mut self, subscription: Subscription) -> Result<(), BusError> {
+        // SAFETY: the bus pointer is live
+        check(unsafe { sys::bus_unsubscribe(self.raw.as_ptr(), subscription.0) }).map(|_| ())
+    }
+
+    // Number of callbacks that saw the sample. If a callback panicked, the
+    // panic continues here, after the C code has returned.
+    pub fn publish(&mut self, sample: Sample) -> usize {
+        // SAFETY: the bus pointer is live and `sample` outlives the call
+        let status = unsafe { sys::bus_publish(self.raw.as_ptr(), &sample) };
+        resume_pending_panic();
+        check(status).map_or(0, |n| n as usize)
+    }
+}
+
+impl Drop for Bus {
+    fn drop(&mut self) {
+        // SAFETY: `raw` came from `bus_new` and is freed only here; the bus
+        // drops every remaining closure through `drop_box`
+        unsafe { sys::bus_free(self.raw.as_ptr()) };
+    }
+}
+
+// Visit samples with a closure that may borrow local state. Returns how
+// many samples were visited before the closure broke out.
+pub fn for_each<F>(samples: &[Sample], mut callback: F) -> usize
+where
+    F: FnMut(&Sample) -> ControlFlow<()>,
+{
+    let user_data = (&mut callback as *mut F).cast::<c_void>();
+    // SAFETY: `samples` is a live slice; `user_data` points to `callback`,
+    // which outlives the call, and C does not keep it afterwards
+    let visited = unsafe {
+        sys::bus_for_each(
+            samples.as_ptr(),
+            samples.len(),
+            Some(trampoline::<F>),
+            user_data,
+        )
+    };
+    resume_pending_panic();
+    visited
+}
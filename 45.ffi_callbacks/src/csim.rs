@@ -0,0 +1,135 @@
+// The "C library": a sensor bus that stores callbacks
+//
+// Written in Rust so the lesson needs no C compiler, but only in C terms:
+// `#[no_mangle] extern "C"` functions, raw pointers, integer status codes
+// and no knowledge of closures. The rest of the crate reaches it only
+// through the `extern "C"` declarations in `sys.rs`, exactly as it would
+// reach a real C library.
+
+use crate::sys::{
+    DestroyNotify, Sample, SampleCallback, BUS_ALL_SENSORS, BUS_ERR_NOT_FOUND, BUS_ERR_NULL,
+    BUS_STOP,
+};
+use std::ffi::{c_int, c_void};
+
+struct Subscription {
+    id: c_int,
+    sensor_id: u16,
+    callback: unsafe extern "C" fn(*mut c_void, *const Sample) -> c_int,
+    user_data: *mut c_void,
+    destroy: DestroyNotify,
+}
+
+impl Subscription {
+    // Ending a subscription hands `user_data` back to its owner
+    unsafe fn end(self) {
+        if let Some(destroy) = self.destroy {
+            destroy(self.user_data);
+        }
+    }
+}
+
+struct BusState {
+    subscriptions: Vec<Subscription>,
+    next_id: c_int,
+}
+
+#[no_mangle]
+extern "C" fn bus_new() -> *mut BusState {
+    Box::into_raw(Box::new(BusState {
+        subscriptions: Vec::new(),
+        next_id: 1,
+    }))
+}
+
+#[no_mangle]
+unsafe extern "C" fn bus_free(bus: *mut BusState) {
+    if bus.is_null() {
+        return;
+    }
+    let bus = Box::from_raw(bus);
+    for sub in bus.subscriptions {
+        sub.end();
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn bus_subscribe(
+    bus: *mut BusState,
+    sensor_id: u16,
+    callback: SampleCallback,
+    user_data: *mut c_void,
+    destroy: DestroyNotify,
+) -> c_int {
+    let (Some(bus), Some(callback)) = (bus.as_mut(), callback) else {
+        return BUS_ERR_NULL;
+    };
+    let id = bus.next_id;
+    bus.next_id += 1;
+    bus.subscriptions.push(Subscription {
+        id,
+        sensor_id,
+        callback,
+        user_data,
+        destroy,
+    });
+    id
+}
+
+#[no_mangle]
+unsafe extern "C" fn bus_unsubscribe(bus: *mut BusState, id: c_int) -> c_int {
+    let Some(bus) = bus.as_mut() else {
+        return BUS_ERR_NULL;
+    };
+    match bus.subscriptions.iter().position(|s| s.id == id) {
+        Some(index) => {
+            bus.subscriptions.remove(index).end();
+            0
+        }
+        None => BUS_ERR_NOT_FOUND,
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn bus_publish(bus: *mut BusState, sample: *const Sample) -> c_int {
+    let (Some(bus), false) = (bus.as_mut(), sample.is_null()) else {
+        return BUS_ERR_NULL;
+    };
+    let mut delivered = 0;
+    let mut index = 0;
+    while index < bus.subscriptions.len() {
+        let sub = &bus.subscriptions[index];
+        if sub.sensor_id != BUS_ALL_SENSORS && sub.sensor_id != (*sample).sensor_id {
+            index += 1;
+            continue;
+        }
+        delivered += 1;
+        if (sub.callback)(sub.user_data, sample) == BUS_STOP {
+            bus.subscriptions.remove(index).end();
+        } else {
+            index += 1;
+        }
+    }
+    delivered
+}
+
+#[no_mangle]
+unsafe extern "C" fn bus_for_each(
+    samples: *const Sample,
+    len: usize,
+    callback: SampleCallback,
+    user_data: *mut c_void,
+) -> usize {
+    let Some(callback) = callback else {
+        return 0;
+    };
+    if samples.is_null() {
+        return 0;
+    }
+    for i in 0..len {
+        if callback(user_data, samples.add(i)) == BUS_STOP {
+            return i + 1;
+        }
+    }
+    len
+}
@@ -0,0 +1,13 @@
+// Passing Rust closures to a C API that takes a function pointer plus
+// `void *user_data`
+//
+// - `sys`: the C declarations (what bindgen would generate from a header)
+// - `csim`: the simulated C library behind those declarations
+// - `bus`: the safe layer - closures, ownership and panics
+
+mod bus;
+mod csim;
+pub mod sys;
+
+pub use bus::{for_each, Bus, BusError, Subscription};
+pub use sys::Sample;
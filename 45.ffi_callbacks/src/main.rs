@@ -0,0 +1,152 @@
+use ffi_callbacks::{for_each, sys, Bus, Sample};
+use std::cell::{Cell, RefCell};
+use std::ffi::{c_int, c_void};
+use std::mem::size_of;
+use std::ops::ControlFlow;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+fn sample(sensor_id: u16, value: f64, timestamp_ms: u64) -> Sample {
+    Sample {
+        sensor_id,
+        value,
+        timestamp_ms,
+    }
+}
+
+// Announces its own drop, to show when the bus releases a closure
+struct DropLog(&'static str);
+
+impl Drop for DropLog {
+    fn drop(&mut self) {
+        println!("   dropped: {}", self.0);
+    }
+}
+
+// A callback written the C way: a plain function, state through user_data
+unsafe extern "C" fn count_samples(user_data: *mut c_void, _sample: *const Sample) -> c_int {
+    *user_data.cast::<u32>() += 1;
+    sys::BUS_CONTINUE
+}
+
+fn main() {
+    println!("=== FFI Callbacks Examples ===\n");
+
+    let samples: Vec<Sample> = (0..8)
+        .map(|i| sample(i % 2, 20.0 + i as f64 * 1.5, i as u64 * 100))
+        .collect();
+
+    // 1. The C view: a function pointer and a void pointer
+    println!("1. Plain C callback:");
+    let mut count: u32 = 0;
+    // SAFETY: `samples` is live and `count` outlives the call
+    let visited = unsafe {
+        sys::bus_for_each(
+            samples.as_ptr(),
+            samples.len(),
+            Some(count_samples),
+            (&mut count as *mut u32).cast(),
+        )
+    };
+    println!("   visited {}, counter {}", visited, count);
+    println!(
+        "   size of fn pointer {}, of Option<fn pointer> {}",
+        size_of::<unsafe extern "C" fn(*mut c_void)>(),
+        size_of::<sys::DestroyNotify>()
+    );
+
+    // 2. A borrowing closure through the synchronous API
+    println!("\n2. Borrowed closure (for_each):");
+    let limit = 26.0;
+    let mut total = 0.0;
+    let visited = for_each(&samples, |s| {
+        total += s.value;
+        if s.value > limit {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    println!(
+        "   stopped after {} samples (first above {}), sum {:.1}",
+        visited, limit, total
+    );
+
+    // 3. Boxed closures the bus keeps
+    println!("\n3. Stored closures (subscribe/publish):");
+    let mut bus = Bus::new();
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let log = Rc::clone(&seen);
+    let all = bus.subscribe(None, move |s| {
+        log.borrow_mut().push(s.sensor_id);
+        ControlFlow::Continue(())
+    });
+    let hot = Rc::new(Cell::new(0));
+    let counter = Rc::clone(&hot);
+    bus.subscribe(Some(1), move |s| {
+        if s.value > 25.0 {
+            counter.set(counter.get() + 1);
+        }
+        ControlFlow::Continue(())
+    });
+    let delivered: usize = samples.iter().map(|&s| bus.publish(s)).sum();
+    println!(
+        "   {} deliveries, catch-all saw {:?}, sensor 1 hot {}",
+        delivered,
+        seen.borrow(),
+        hot.get()
+    );
+
+    // 4. Who owns user_data, and when it is freed
+    println!("\n4. Ownership of user_data:");
+    println!("   Rc count while subscribed: {}", Rc::strong_count(&seen));
+    bus.unsubscribe(all).expect("subscribed above");
+    println!("   Rc count after unsubscribe: {}", Rc::strong_count(&seen));
+    println!("   unsubscribing again: {:?}", bus.unsubscribe(all));
+
+    let guard = DropLog("one-shot closure");
+    bus.subscribe(None, move |s| {
+        let _keep = &guard;
+        println!("   one-shot got sensor {}, unsubscribing", s.sensor_id);
+        ControlFlow::Break(())
+    });
+    bus.publish(sample(0, 1.0, 900));
+    println!(
+        "   second publish reaches {} callback(s)",
+        bus.publish(sample(0, 1.0, 1000))
+    );
+
+    let guard = DropLog("closure still subscribed when the bus is dropped");
+    bus.subscribe(None, move |_| {
+        let _keep = &guard;
+        ControlFlow::Continue(())
+    });
+    drop(bus);
+
+    // 5. A panicking closure
+    println!("\n5. Panics at the boundary:");
+    let mut bus = Bus::new();
+    bus.subscribe(Some(7), |s| {
+        if s.value.is_nan() {
+            panic!("sensor 7 sent NaN");
+        }
+        ControlFlow::Continue(())
+    });
+    let quiet = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| bus.publish(sample(7, f64::NAN, 0))));
+    panic::set_hook(quiet);
+    match &result {
+        Ok(n) => println!("   no panic, {} deliveries", n),
+        Err(payload) => println!(
+            "   caught after C returned: {:?}",
+            payload.downcast_ref::<&str>().unwrap_or(&"?")
+        ),
+    }
+    println!(
+        "   next sample from sensor 7 reaches {} callback(s)",
+        bus.publish(sample(7, 1.0, 1))
+    );
+
+    println!("\n=== End of FFI Callbacks Examples ===");
+}
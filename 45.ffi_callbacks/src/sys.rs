@@ -0,0 +1,69 @@
+// What a C header for the sensor bus would declare
+//
+//   typedef int (*sample_cb)(void *user_data, const Sample *sample);
+//   typedef void (*destroy_notify)(void *user_data);
+//
+// `Option<extern "C" fn>` is the Rust spelling of a nullable C function
+// pointer: the `None` niche is the null pointer, so the size is unchanged.
+
+use std::ffi::{c_int, c_void};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub sensor_id: u16,
+    pub value: f64,
+    pub timestamp_ms: u64,
+}
+
+// Opaque: C code only ever holds a pointer
+#[repr(C)]
+pub struct Bus {
+    _private: [u8; 0],
+}
+
+// Callback return values
+pub const BUS_CONTINUE: c_int = 0;
+pub const BUS_STOP: c_int = 1;
+
+// Subscribe to every sensor
+pub const BUS_ALL_SENSORS: u16 = 0xFFFF;
+
+pub const BUS_ERR_NULL: c_int = -1;
+pub const BUS_ERR_NOT_FOUND: c_int = -2;
+
+pub type SampleCallback =
+    Option<unsafe extern "C" fn(user_data: *mut c_void, sample: *const Sample) -> c_int>;
+pub type DestroyNotify = Option<unsafe extern "C" fn(user_data: *mut c_void)>;
+
+extern "C" {
+    pub fn bus_new() -> *mut Bus;
+
+    // Calls `destroy` for every remaining subscription
+    pub fn bus_free(bus: *mut Bus);
+
+    // Returns a subscription id > 0, or a negative error. The bus keeps
+    // `user_data` until the subscription ends, then passes it to `destroy`.
+    pub fn bus_subscribe(
+        bus: *mut Bus,
+        sensor_id: u16,
+        callback: SampleCallback,
+        user_data: *mut c_void,
+        destroy: DestroyNotify,
+    ) -> c_int;
+
+    pub fn bus_unsubscribe(bus: *mut Bus, id: c_int) -> c_int;
+
+    // Delivers to matching subscribers in subscription order. A callback
+    // returning BUS_STOP is unsubscribed. Returns the number of deliveries.
+    pub fn bus_publish(bus: *mut Bus, sample: *const Sample) -> c_int;
+
+    // Synchronous: `user_data` is only used until this returns. Stops at
+    // the first BUS_STOP and returns how many samples were visited.
+    pub fn bus_for_each(
+        samples: *const Sample,
+        len: usize,
+        callback: SampleCallback,
+        user_data: *mut c_void,
+    ) -> usize;
+}
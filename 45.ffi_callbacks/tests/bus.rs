@@ -0,0 +1,193 @@
+use ffi_callbacks::{for_each, sys, Bus, BusError, Sample};
+use std::cell::{Cell, RefCell};
+use std::ffi::{c_int, c_void};
+use std::ops::ControlFlow;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::rc::Rc;
+
+fn sample(sensor_id: u16, value: f64, timestamp_ms: u64) -> Sample {
+    Sample {
+        sensor_id,
+        value,
+        timestamp_ms,
+    }
+}
+
+// Sensors 0 and 1 in turn, 20.0 rising by 1.5
+fn samples() -> Vec<Sample> {
+    (0..8)
+        .map(|i| sample(i % 2, 20.0 + i as f64 * 1.5, i as u64 * 100))
+        .collect()
+}
+
+// Sets its flag when dropped, to see when the bus releases a closure
+struct DropFlag(Rc<Cell<bool>>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
+unsafe extern "C" fn count_samples(user_data: *mut c_void, _sample: *const Sample) -> c_int {
+    *user_data.cast::<u32>() += 1;
+    sys::BUS_CONTINUE
+}
+
+#[test]
+fn a_plain_c_callback_gets_its_state_through_user_data() {
+    let samples = samples();
+    let mut count: u32 = 0;
+    // SAFETY: `samples` is live and `count` outlives the call
+    let visited = unsafe {
+        sys::bus_for_each(
+            samples.as_ptr(),
+            samples.len(),
+            Some(count_samples),
+            (&mut count as *mut u32).cast(),
+        )
+    };
+    assert_eq!((visited, count), (8, 8));
+    // SAFETY: C checks both pointers before using them
+    unsafe {
+        assert_eq!(
+            sys::bus_for_each(ptr::null(), 8, Some(count_samples), ptr::null_mut()),
+            0
+        );
+        assert_eq!(
+            sys::bus_for_each(samples.as_ptr(), 8, None, ptr::null_mut()),
+            0
+        );
+        assert_eq!(sys::bus_unsubscribe(ptr::null_mut(), 0), sys::BUS_ERR_NULL);
+    }
+}
+
+#[test]
+fn for_each_borrows_locals_and_stops_on_break() {
+    let samples = samples();
+    let limit = 26.0;
+    let mut total = 0.0;
+    let visited = for_each(&samples, |s| {
+        total += s.value;
+        if s.value > limit {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    // 20.0 + 21.5 + ... + 27.5, stopping on the first above 26
+    assert_eq!(visited, 6);
+    assert_eq!(total, 142.5);
+
+    assert_eq!(for_each(&samples, |_| ControlFlow::Continue(())), 8);
+    assert_eq!(for_each(&[], |_| ControlFlow::Break(())), 0);
+}
+
+#[test]
+fn subscribers_see_their_sensor_or_every_sensor() {
+    let mut bus = Bus::new();
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let log = Rc::clone(&seen);
+    bus.subscribe(None, move |s| {
+        log.borrow_mut().push(s.sensor_id);
+        ControlFlow::Continue(())
+    });
+    let hot = Rc::new(Cell::new(0));
+    let counter = Rc::clone(&hot);
+    bus.subscribe(Some(1), move |s| {
+        if s.value > 25.0 {
+            counter.set(counter.get() + 1);
+        }
+        ControlFlow::Continue(())
+    });
+    let delivered: Vec<usize> = samples().into_iter().map(|s| bus.publish(s)).collect();
+    // Sensor 1 samples reach both
+    assert_eq!(delivered, [1, 2, 1, 2, 1, 2, 1, 2]);
+    assert_eq!(*seen.borrow(), [0, 1, 0, 1, 0, 1, 0, 1]);
+    // 26.5 and 29.5
+    assert_eq!(hot.get(), 2);
+    assert_eq!(bus.publish(sample(9, 0.0, 0)), 1);
+}
+
+#[test]
+fn unsubscribe_frees_the_closure_once() {
+    let mut bus = Bus::new();
+    let state = Rc::new(Cell::new(0));
+    let dropped = Rc::new(Cell::new(false));
+    let (count, flag) = (Rc::clone(&state), DropFlag(Rc::clone(&dropped)));
+    let subscription = bus.subscribe(None, move |_| {
+        let _keep = &flag;
+        count.set(count.get() + 1);
+        ControlFlow::Continue(())
+    });
+    assert_eq!(Rc::strong_count(&state), 2);
+    bus.publish(sample(0, 1.0, 0));
+
+    assert_eq!(bus.unsubscribe(subscription), Ok(()));
+    assert!(dropped.get());
+    assert_eq!(Rc::strong_count(&state), 1);
+    assert_eq!(bus.unsubscribe(subscription), Err(BusError::NotFound));
+    assert_eq!(bus.publish(sample(0, 1.0, 100)), 0);
+    assert_eq!(state.get(), 1);
+}
+
+#[test]
+fn break_unsubscribes_and_frees_the_closure() {
+    let mut bus = Bus::new();
+    let dropped = Rc::new(Cell::new(false));
+    let flag = DropFlag(Rc::clone(&dropped));
+    bus.subscribe(None, move |_| {
+        let _keep = &flag;
+        ControlFlow::Break(())
+    });
+    assert_eq!(bus.publish(sample(0, 1.0, 900)), 1);
+    assert!(dropped.get());
+    assert_eq!(bus.publish(sample(0, 1.0, 1000)), 0);
+}
+
+#[test]
+fn dropping_the_bus_frees_what_is_still_subscribed() {
+    let mut bus = Bus::new();
+    let flags: Vec<Rc<Cell<bool>>> = (0..3).map(|_| Rc::new(Cell::new(false))).collect();
+    for (sensor, dropped) in flags.iter().enumerate() {
+        let flag = DropFlag(Rc::clone(dropped));
+        bus.subscribe(Some(sensor as u16), move |_| {
+            let _keep = &flag;
+            ControlFlow::Continue(())
+        });
+    }
+    assert!(flags.iter().all(|f| !f.get()));
+    drop(bus);
+    assert!(flags.iter().all(|f| f.get()));
+}
+
+#[test]
+fn a_panic_resumes_in_rust_after_c_returns() {
+    let mut bus = Bus::new();
+    bus.subscribe(Some(7), |s| {
+        if s.value.is_nan() {
+            panic!("sensor 7 sent NaN");
+        }
+        ControlFlow::Continue(())
+    });
+    let after = Rc::new(Cell::new(0));
+    let count = Rc::clone(&after);
+    bus.subscribe(None, move |_| {
+        count.set(count.get() + 1);
+        ControlFlow::Continue(())
+    });
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| bus.publish(sample(7, f64::NAN, 0))));
+    let payload = result.expect_err("the panic reaches the caller");
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"sensor 7 sent NaN"));
+    // The C loop went on to the next subscriber before returning
+    assert_eq!(after.get(), 1);
+    // The panicking subscriber was removed, the other one stays
+    assert_eq!(bus.publish(sample(7, 1.0, 1)), 1);
+
+    let result = panic::catch_unwind(|| for_each(&samples(), |_| panic!("in for_each")));
+    assert!(result.is_err());
+    // Nothing is left pending for the next call
+    assert_eq!(bus.publish(sample(7, 1.0, 2)), 1);
+}
@@ -0,0 +1,14 @@
+// Generates the message types and the client/server stubs from
+// proto/device.proto into OUT_DIR. protoc comes from protoc-bin-vendored,
+// so no system install is needed.
+
+fn main() {
+    let protoc = protoc_bin_vendored::protoc_bin_path().expect("no vendored protoc for this host");
+    std::env::set_var("PROTOC", protoc);
+
+    tonic_prost_build::configure()
+        .compile_protos(&["proto/device.proto"], &["proto"])
+        .expect("proto/device.proto failed to compile");
+
+    println!("cargo:rerun-if-changed=proto/device.proto");
+}
@@ -0,0 +1,65 @@
+// Device-side client: register, heartbeat, then print some telemetry
+//
+//   cargo run --bin client -- http://127.0.0.1:50051 node-7
+
+use grpc_device::pb::device_service_client::DeviceServiceClient;
+use grpc_device::pb::{HeartbeatRequest, RegisterRequest, TelemetryRequest};
+use grpc_device::{authorized, BearerAuth};
+use std::time::{Duration, Instant};
+use tonic::transport::Channel;
+use tonic::Request;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let url = args
+        .next()
+        .unwrap_or_else(|| "http://127.0.0.1:50051".to_string());
+    let device_id = args.next().unwrap_or_else(|| "node-1".to_string());
+    let started = Instant::now();
+
+    let channel = Channel::from_shared(url.clone())?
+        .connect_timeout(Duration::from_secs(3))
+        .connect()
+        .await?;
+    let session = DeviceServiceClient::new(channel.clone())
+        .register(RegisterRequest {
+            device_id: device_id.clone(),
+            model: "th-sensor".to_string(),
+            firmware: env!("CARGO_PKG_VERSION").to_string(),
+        })
+        .await?
+        .into_inner();
+    println!(
+        "registered {} at {} (existing: {})",
+        device_id, url, session.already_registered
+    );
+
+    let auth = BearerAuth::new(&session.session_token).ok_or("bad token")?;
+    let mut client = authorized(channel, auth);
+
+    // Every unary call gets a deadline: a hung server must not hang the device
+    let mut request = Request::new(HeartbeatRequest {
+        device_id: device_id.clone(),
+        uptime_s: started.elapsed().as_secs(),
+    });
+    request.set_timeout(Duration::from_secs(2));
+    let beat = client.heartbeat(request).await?.into_inner();
+    println!(
+        "heartbeat #{} at server time {}",
+        beat.heartbeats, beat.server_time_ms
+    );
+
+    let mut request = Request::new(TelemetryRequest {
+        device_id,
+        interval_ms: 200,
+        max_readings: 9,
+        metrics: Vec::new(),
+    });
+    request.set_timeout(Duration::from_secs(5));
+    let mut stream = client.stream_telemetry(request).await?.into_inner();
+    while let Some(r) = stream.message().await? {
+        println!("#{:<3} {:<12} {:>7.3}", r.sequence, r.metric, r.value);
+    }
+    Ok(())
+}
@@ -0,0 +1,40 @@
+// Standalone DeviceService server
+//
+//   cargo run --bin server                  # listens on 127.0.0.1:50051
+//   cargo run --bin server -- 0.0.0.0:50051
+
+use grpc_device::service::{now_ms, HEARTBEAT_INTERVAL_MS};
+use grpc_device::{serve, DeviceManager, Registry};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let addr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:50051".to_string());
+    let registry = Arc::new(Mutex::new(Registry::new(now_ms())));
+    let listener = TcpListener::bind(&addr).await?;
+    println!("DeviceService listening on {}", listener.local_addr()?);
+
+    // Report devices that missed three heartbeats
+    let watched = Arc::clone(&registry);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(10));
+        loop {
+            ticker.tick().await;
+            let registry = watched.lock().unwrap_or_else(|e| e.into_inner());
+            for device in registry.stale(now_ms(), 3 * HEARTBEAT_INTERVAL_MS as u64) {
+                println!(
+                    "stale: {} (last seen {} ms ago)",
+                    device.id,
+                    now_ms() - device.last_seen_ms
+                );
+            }
+        }
+    });
+
+    serve(listener, DeviceManager::new(registry)).await?;
+    Ok(())
+}
@@ -0,0 +1,57 @@
+// Device metadata from the model catalog
+//
+// What a device can report depends on its model and firmware, not on the
+// device, so the catalog is keyed by that pair. In a deployment the catalog
+// is a product database behind another service and every fetch is a round
+// trip; here it is a table, and it counts fetches so the cache in front of
+// it (see `Registry::metadata`) can be checked.
+
+use gateway::sensors::METRICS;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceMetadata {
+    pub model: Arc<str>,
+    pub firmware: Arc<str>,
+    // The metrics this model reports, a subset of `METRICS`
+    pub metrics: Vec<&'static str>,
+}
+
+impl DeviceMetadata {
+    pub fn reports(&self, metric: &str) -> bool {
+        self.metrics.contains(&metric)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Catalog {
+    fetches: AtomicU64,
+}
+
+impl Catalog {
+    pub fn new() -> Catalog {
+        Catalog::default()
+    }
+
+    // Models the catalog doesn't know are assumed to report everything
+    pub fn fetch(&self, model: &Arc<str>, firmware: &Arc<str>) -> DeviceMetadata {
+        self.fetches.fetch_add(1, Ordering::Relaxed);
+        let metrics = match (&**model, &**firmware) {
+            // Battery reporting arrived in firmware 1.2
+            ("th-sensor", "1.0.0" | "1.1.0") => vec!["temperature", "humidity"],
+            ("th-sensor", _) => METRICS.to_vec(),
+            ("t-probe", _) => vec!["temperature", "battery"],
+            _ => METRICS.to_vec(),
+        };
+        DeviceMetadata {
+            model: Arc::clone(model),
+            firmware: Arc::clone(firmware),
+            metrics,
+        }
+    }
+
+    pub fn fetches(&self) -> u64 {
+        self.fetches.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,39 @@
+// Client helpers
+//
+// `BearerAuth` is a client interceptor: it runs on every outgoing request
+// and adds the session token, so call sites never handle metadata for auth.
+
+use crate::service::pb::device_service_client::DeviceServiceClient;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
+use tonic::transport::Channel;
+use tonic::{Request, Status};
+
+#[derive(Debug, Clone)]
+pub struct BearerAuth {
+    header: MetadataValue<Ascii>,
+}
+
+impl BearerAuth {
+    // `None` if the token cannot be sent as a header value
+    pub fn new(token: &str) -> Option<BearerAuth> {
+        let header = format!("Bearer {}", token).parse().ok()?;
+        Some(BearerAuth { header })
+    }
+}
+
+impl Interceptor for BearerAuth {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        request
+            .metadata_mut()
+            .insert("authorization", self.header.clone());
+        Ok(request)
+    }
+}
+
+pub type AuthorizedClient = DeviceServiceClient<InterceptedService<Channel, BearerAuth>>;
+
+pub fn authorized(channel: Channel, auth: BearerAuth) -> AuthorizedClient {
+    DeviceServiceClient::with_interceptor(channel, auth)
+}
@@ -0,0 +1,17 @@
+// gRPC device management with tonic
+//
+// - `registry`: the device registry (plain Rust, no gRPC)
+// - `catalog`: per-model device metadata, cached by the registry
+// - `service`: the `DeviceService` implementation generated from
+//   proto/device.proto, plus metadata and deadline handling
+// - `client`: an interceptor that attaches the session token
+
+pub mod catalog;
+pub mod client;
+pub mod registry;
+pub mod service;
+
+pub use catalog::{Catalog, DeviceMetadata};
+pub use client::{authorized, AuthorizedClient, BearerAuth};
+pub use registry::{validate_id, DeviceRecord, Registry, RegistryError, MAX_ID_LEN};
+pub use service::{pb, serve, DeviceManager};
@@ -0,0 +1,265 @@
+use grpc_device::pb::device_service_client::DeviceServiceClient;
+use grpc_device::pb::{HeartbeatRequest, RegisterRequest, TelemetryRequest};
+use grpc_device::service::{parse_grpc_timeout, REQUEST_ID};
+use grpc_device::{authorized, serve, BearerAuth, DeviceManager, Registry};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tonic::transport::Channel;
+use tonic::Request;
+
+fn register(id: &str, model: &str) -> RegisterRequest {
+    RegisterRequest {
+        device_id: id.to_string(),
+        model: model.to_string(),
+        firmware: "1.4.2".to_string(),
+    }
+}
+
+fn heartbeat(id: &str, uptime_s: u64) -> HeartbeatRequest {
+    HeartbeatRequest {
+        device_id: id.to_string(),
+        uptime_s,
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== gRPC Device Service Examples ===\n");
+
+    // The server runs in this process on a free port; src/bin/server.rs is
+    // the standalone version
+    let registry = Arc::new(Mutex::new(Registry::new(42)));
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(serve(listener, DeviceManager::new(Arc::clone(&registry))));
+    let channel = Channel::from_shared(format!("http://{}", addr))?
+        .connect()
+        .await?;
+    let mut client = DeviceServiceClient::new(channel.clone());
+
+    // 1. Register (unary)
+    println!("1. Register:");
+    let first = client
+        .register(register("node-7", "th-sensor"))
+        .await?
+        .into_inner();
+    println!(
+        "   node-7: token {}, heartbeat every {} ms",
+        first.session_token, first.heartbeat_interval_ms
+    );
+    let again = client
+        .register(register("node-7", "th-sensor"))
+        .await?
+        .into_inner();
+    println!(
+        "   again: already registered {}, token {}",
+        again.already_registered, again.session_token
+    );
+    let other = client
+        .register(register("node-9", "th-sensor"))
+        .await?
+        .into_inner();
+    println!("   node-9: token {}", other.session_token);
+    let status = client.register(register("Node 7!", "x")).await.unwrap_err();
+    println!("   invalid id -> {:?}: {}", status.code(), status.message());
+
+    // 2. Metadata: the token rides in `authorization`, ids are echoed
+    println!("\n2. Heartbeat and metadata:");
+    let auth = BearerAuth::new(&first.session_token).ok_or("token is not a header value")?;
+    let mut node7 = authorized(channel.clone(), auth);
+    let mut request = Request::new(heartbeat("node-7", 120));
+    request
+        .metadata_mut()
+        .insert(REQUEST_ID, "demo-0001".parse()?);
+    let response = node7.heartbeat(request).await?;
+    println!(
+        "   {} heartbeat(s), {} = {:?}",
+        response.get_ref().heartbeats,
+        REQUEST_ID,
+        response.metadata().get(REQUEST_ID)
+    );
+    let failures = [
+        ("no token", client.heartbeat(heartbeat("node-7", 1)).await),
+        (
+            "token of node-7 for node-9",
+            node7.heartbeat(heartbeat("node-9", 1)).await,
+        ),
+        (
+            "unknown device",
+            node7.heartbeat(heartbeat("node-404", 1)).await,
+        ),
+    ];
+    for (label, result) in failures {
+        match result {
+            Ok(_) => println!("   {:<28} unexpectedly ok", label),
+            Err(s) => println!("   {:<28} {:?}: {}", label, s.code(), s.message()),
+        }
+    }
+
+    // 3. Server streaming
+    println!("\n3. StreamTelemetry:");
+    let mut stream = node7
+        .stream_telemetry(TelemetryRequest {
+            device_id: "node-7".to_string(),
+            interval_ms: 50,
+            max_readings: 6,
+            metrics: vec!["temperature".to_string(), "humidity".to_string()],
+        })
+        .await?
+        .into_inner();
+    let mut received = 0;
+    while let Some(reading) = stream.message().await? {
+        println!(
+            "   #{} {:<12} {:>6.2}",
+            reading.sequence, reading.metric, reading.value
+        );
+        received += 1;
+    }
+    println!("   end of stream after {} readings", received);
+    let status = node7
+        .stream_telemetry(TelemetryRequest {
+            device_id: "node-7".to_string(),
+            interval_ms: 50,
+            max_readings: 5,
+            metrics: vec!["pressure".to_string()],
+        })
+        .await
+        .unwrap_err();
+    println!("   bad metric -> {:?}: {}", status.code(), status.message());
+    let t_probe = client
+        .register(RegisterRequest {
+            device_id: "probe-3".to_string(),
+            model: "t-probe".to_string(),
+            firmware: "2.0.1".to_string(),
+        })
+        .await?
+        .into_inner();
+    let auth = BearerAuth::new(&t_probe.session_token).ok_or("token is not a header value")?;
+    let mut probe3 = authorized(channel.clone(), auth);
+    let status = probe3
+        .stream_telemetry(TelemetryRequest {
+            device_id: "probe-3".to_string(),
+            interval_ms: 50,
+            max_readings: 5,
+            metrics: vec!["humidity".to_string()],
+        })
+        .await
+        .unwrap_err();
+    println!(
+        "   not on model -> {:?}: {}",
+        status.code(),
+        status.message()
+    );
+    // No metrics: whatever the model reports
+    let mut stream = probe3
+        .stream_telemetry(TelemetryRequest {
+            device_id: "probe-3".to_string(),
+            interval_ms: 20,
+            max_readings: 4,
+            metrics: vec![],
+        })
+        .await?
+        .into_inner();
+    let mut metrics = Vec::new();
+    while let Some(reading) = stream.message().await? {
+        if !metrics.contains(&reading.metric) {
+            metrics.push(reading.metric);
+        }
+    }
+    println!("   probe-3, no metrics asked for: {:?}", metrics);
+
+    // 4. Deadlines
+    println!("\n4. Deadlines:");
+    println!(
+        "   grpc-timeout \"300m\" = {:?}, \"2S\" = {:?}",
+        parse_grpc_timeout("300m"),
+        parse_grpc_timeout("2S")
+    );
+    // 40 readings at 50 ms would need 2 s; the client allows 300 ms
+    let mut request = Request::new(TelemetryRequest {
+        device_id: "node-7".to_string(),
+        interval_ms: 50,
+        max_readings: 40,
+        metrics: vec!["temperature".to_string()],
+    });
+    request.set_timeout(Duration::from_millis(300));
+    let mut stream = node7.stream_telemetry(request).await?.into_inner();
+    let mut received = 0;
+    let ended = loop {
+        match stream.message().await {
+            Ok(Some(_)) => received += 1,
+            Ok(None) => break None,
+            Err(status) => break Some(status),
+        }
+    };
+    match &ended {
+        Some(s) => println!(
+            "   {} readings, then {:?}: {}",
+            received,
+            s.code(),
+            s.message()
+        ),
+        None => println!("   {} readings, then end of stream", received),
+    }
+
+    // 5. What the registry saw
+    println!("\n5. Registry:");
+    for id in [
+        "plant-3/floor-2/th-2",
+        "plant-3/floor-1/th-1",
+        "plant-3/floor-20/th-1",
+        "plant-3/floor-2/th-1",
+    ] {
+        client.register(register(id, "th-sensor")).await?;
+    }
+    let status = client
+        .register(register("plant-3//th-1", "th-sensor"))
+        .await
+        .unwrap_err();
+    println!(
+        "   plant-3//th-1 -> {:?}: {}",
+        status.code(),
+        status.message()
+    );
+    let registry = registry.lock().unwrap_or_else(|e| e.into_inner());
+    let floor: Vec<&str> = registry
+        .under("plant-3/floor-2/")
+        .map(|d| d.id.as_str())
+        .collect();
+    println!("   under plant-3/floor-2/: {:?}", floor);
+    for id in ["node-7", "node-9"] {
+        if let Some(d) = registry.get(id) {
+            println!(
+                "   {} {} fw {}: {} heartbeat(s), uptime {} s",
+                d.id, d.model, d.firmware, d.heartbeats, d.uptime_s
+            );
+        }
+    }
+    println!("   {} devices registered", registry.len());
+    let shared = match (registry.get("node-7"), registry.get("node-9")) {
+        (Some(a), Some(b)) => Arc::ptr_eq(&a.firmware, &b.firmware),
+        _ => false,
+    };
+    println!(
+        "   {} distinct model/firmware names interned",
+        registry.interned_names()
+    );
+    println!("   node-7 and node-9 share one firmware string: {}", shared);
+    for id in ["node-7", "node-9", "probe-3", "node-7"] {
+        if let Ok(m) = registry.metadata(id) {
+            println!("   {} metadata: {:?}", id, m.metrics);
+        }
+    }
+    // Two model/firmware pairs: th-sensor 1.4.2 and t-probe 2.0.1
+    let stats = registry.metadata_stats();
+    println!(
+        "   metadata cache: {} hits, {} misses, catalog fetched {} times",
+        stats.hits,
+        stats.misses,
+        registry.catalog_fetches()
+    );
+
+    println!("\n=== End of gRPC Device Service Examples ===");
+    Ok(())
+}
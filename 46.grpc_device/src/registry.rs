@@ -0,0 +1,243 @@
+// The device registry behind the service
+//
+// Plain synchronous Rust with no gRPC types in it: the service layer locks
+// it, calls one method and maps `RegistryError` to a `tonic::Status`.
+//
+// A fleet has thousands of devices but a handful of models and firmware
+// versions, so those names are interned: every record on "th-sensor" /
+// "1.4.2" shares one allocation of each, and re-registering a device with
+// unchanged names allocates nothing.
+//
+// The same few pairs make metadata lookups cacheable: `metadata` asks the
+// catalog once per model/firmware pair and serves every later lookup from
+// an LRU cache. The cache is keyed by the pair, so a device that updates
+// its firmware simply looks up a different entry; nothing goes stale.
+//
+// Ids name where a device sits, "plant-3/floor-2/th-1", so the records live
+// in a radix trie keyed by id rather than a HashMap. A lookup by id costs
+// about the same, and `under("plant-3/floor-2/")` walks just that subtree,
+// in id order, instead of scanning the whole fleet.
+
+use crate::catalog::{Catalog, DeviceMetadata};
+use broker::Interner;
+use cache::{Stats, SyncLruCache};
+use std::fmt;
+use std::sync::Arc;
+use trie::RadixTrie;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceRecord {
+    pub id: String,
+    pub model: Arc<str>,
+    pub firmware: Arc<str>,
+    pub token: String,
+    // Seed for this device's simulated sensors, stable across registrations
+    pub seed: u32,
+    pub registered_ms: u64,
+    pub last_seen_ms: u64,
+    pub uptime_s: u64,
+    pub heartbeats: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    InvalidId(String),
+    UnknownDevice(String),
+    BadToken(String),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::InvalidId(id) => {
+                write!(
+                    f,
+                    "invalid device id '{}': use 1-{} of [a-z0-9-], with '/' between segments",
+                    id, MAX_ID_LEN
+                )
+            }
+            RegistryError::UnknownDevice(id) => write!(f, "device '{}' is not registered", id),
+            RegistryError::BadToken(id) => write!(f, "wrong session token for '{}'", id),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+pub const MAX_ID_LEN: usize = 64;
+
+// Room for far more model/firmware pairs than a fleet runs at once
+pub const METADATA_CACHE_CAPACITY: usize = 64;
+
+type MetadataKey = (Arc<str>, Arc<str>);
+
+#[derive(Debug)]
+pub struct Registry {
+    devices: RadixTrie<DeviceRecord>,
+    names: Interner,
+    rng: u64,
+    catalog: Catalog,
+    metadata: SyncLruCache<MetadataKey, Arc<DeviceMetadata>>,
+}
+
+impl Registry {
+    pub fn new(seed: u64) -> Registry {
+        Registry {
+            devices: RadixTrie::new(),
+            names: Interner::new(),
+            rng: seed,
+            catalog: Catalog::new(),
+            metadata: SyncLruCache::new(METADATA_CACHE_CAPACITY),
+        }
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.rng = self
+            .rng
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.rng
+    }
+
+    // Returns the record and whether it already existed. Re-registering
+    // keeps the session token, so a device that rebooted can carry on.
+    pub fn register(
+        &mut self,
+        id: &str,
+        model: &str,
+        firmware: &str,
+        now_ms: u64,
+    ) -> Result<(&DeviceRecord, bool), RegistryError> {
+        validate_id(id)?;
+        let model = self.names.intern(model);
+        let firmware = self.names.intern(firmware);
+        let model = self.names.resolve_arc(model);
+        let firmware = self.names.resolve_arc(firmware);
+        let existed = self.devices.contains_key(id);
+        if !existed {
+            let token = format!("{:016x}", self.next_random());
+            let seed = (self.next_random() >> 32) as u32;
+            self.devices.insert(
+                id,
+                DeviceRecord {
+                    id: id.to_string(),
+                    model: Arc::clone(&model),
+                    firmware: Arc::clone(&firmware),
+                    token,
+                    seed,
+                    registered_ms: now_ms,
+                    last_seen_ms: now_ms,
+                    uptime_s: 0,
+                    heartbeats: 0,
+                },
+            );
+        }
+        let record = self.devices.get_mut(id).expect("inserted above");
+        record.model = model;
+        record.firmware = firmware;
+        record.last_seen_ms = now_ms;
+        Ok((record, existed))
+    }
+
+    pub fn authenticate(&self, id: &str, token: &str) -> Result<&DeviceRecord, RegistryError> {
+        let record = self
+            .devices
+            .get(id)
+            .ok_or_else(|| RegistryError::UnknownDevice(id.to_string()))?;
+        if record.token != token {
+            return Err(RegistryError::BadToken(id.to_string()));
+        }
+        Ok(record)
+    }
+
+    pub fn heartbeat(
+        &mut self,
+        id: &str,
+        token: &str,
+        uptime_s: u64,
+        now_ms: u64,
+    ) -> Result<&DeviceRecord, RegistryError> {
+        self.authenticate(id, token)?;
+        let record = self.devices.get_mut(id).expect("authenticated above");
+        record.uptime_s = uptime_s;
+        record.last_seen_ms = now_ms;
+        record.heartbeats += 1;
+        Ok(record)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&DeviceRecord> {
+        self.devices.get(id)
+    }
+
+    // Takes `&self`, like `authenticate`: the cache locks internally
+    pub fn metadata(&self, id: &str) -> Result<Arc<DeviceMetadata>, RegistryError> {
+        let record = self
+            .devices
+            .get(id)
+            .ok_or_else(|| RegistryError::UnknownDevice(id.to_string()))?;
+        let key = (Arc::clone(&record.model), Arc::clone(&record.firmware));
+        Ok(self.metadata.get_or_insert_with(key, || {
+            Arc::new(self.catalog.fetch(&record.model, &record.firmware))
+        }))
+    }
+
+    pub fn metadata_stats(&self) -> Stats {
+        self.metadata.stats()
+    }
+
+    // How many lookups missed the cache and went to the catalog
+    pub fn catalog_fetches(&self) -> u64 {
+        self.catalog.fetches()
+    }
+
+    // Distinct model and firmware names seen so far
+    pub fn interned_names(&self) -> usize {
+        self.names.len()
+    }
+
+    // In id order
+    pub fn iter(&self) -> impl Iterator<Item = &DeviceRecord> {
+        self.devices.iter().map(|(_, record)| record)
+    }
+
+    // Devices whose id starts with `prefix`, in id order. Pass a whole
+    // segment with its '/', "plant-3/floor-2/", or "plant-3/floor-2" will
+    // also match "plant-3/floor-20/...".
+    pub fn under(&self, prefix: &str) -> impl Iterator<Item = &DeviceRecord> {
+        self.devices.prefix(prefix).map(|(_, record)| record)
+    }
+
+    pub fn len(&self) -> usize {
+        self.devices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+
+    // Devices not heard from within `timeout_ms`, oldest first
+    pub fn stale(&self, now_ms: u64, timeout_ms: u64) -> Vec<&DeviceRecord> {
+        let mut stale: Vec<&DeviceRecord> = self
+            .iter()
+            .filter(|d| now_ms.saturating_sub(d.last_seen_ms) > timeout_ms)
+            .collect();
+        stale.sort_by_key(|d| d.last_seen_ms);
+        stale
+    }
+}
+
+// One or more segments of [a-z0-9-], joined by single slashes
+pub fn validate_id(id: &str) -> Result<(), RegistryError> {
+    let valid = (1..=MAX_ID_LEN).contains(&id.len())
+        && id.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(RegistryError::InvalidId(id.to_string()))
+    }
+}
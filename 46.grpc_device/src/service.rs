@@ -0,0 +1,268 @@
+// The tonic service: `DeviceService` from proto/device.proto
+//
+// Each handler reads metadata, locks the registry for one call and maps the
+// result to a response or a `Status`. The telemetry stream runs in its own
+// task and feeds a channel, so a slow client only blocks its own stream.
+
+use crate::registry::{Registry, RegistryError};
+use gateway::sensors::{SensorHub, METRICS};
+use pb::device_service_server::{DeviceService, DeviceServiceServer};
+use pb::{
+    HeartbeatRequest, HeartbeatResponse, RegisterRequest, RegisterResponse, TelemetryReading,
+    TelemetryRequest,
+};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+use tokio_stream::Stream;
+use tonic::metadata::{Ascii, MetadataMap, MetadataValue};
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+
+pub mod pb {
+    tonic::include_proto!("device.v1");
+}
+
+pub const HEARTBEAT_INTERVAL_MS: u32 = 5_000;
+pub const MIN_INTERVAL_MS: u32 = 10;
+pub const MAX_READINGS: u32 = 10_000;
+
+// Echoed back so a client can match responses to its logs
+pub const REQUEST_ID: &str = "x-request-id";
+
+impl From<RegistryError> for Status {
+    fn from(e: RegistryError) -> Status {
+        match e {
+            RegistryError::InvalidId(_) => Status::invalid_argument(e.to_string()),
+            RegistryError::UnknownDevice(_) => Status::not_found(e.to_string()),
+            RegistryError::BadToken(_) => Status::unauthenticated(e.to_string()),
+        }
+    }
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+// `authorization: Bearer <token>`
+fn bearer_token(metadata: &MetadataMap) -> Result<String, Status> {
+    let value = metadata
+        .get("authorization")
+        .ok_or_else(|| Status::unauthenticated("missing authorization metadata"))?;
+    value
+        .to_str()
+        .ok()
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::to_string)
+        .ok_or_else(|| Status::unauthenticated("authorization must be 'Bearer <token>'"))
+}
+
+// The client's deadline travels as a `grpc-timeout` header: up to 8 digits
+// and a unit (H, M, S, m, u, n)
+pub fn parse_grpc_timeout(value: &str) -> Option<Duration> {
+    if value.len() < 2 || value.len() > 9 {
+        return None;
+    }
+    let (digits, unit) = value.split_at(value.len() - 1);
+    let amount: u64 = digits.parse().ok()?;
+    match unit {
+        "H" => Some(Duration::from_secs(amount * 3600)),
+        "M" => Some(Duration::from_secs(amount * 60)),
+        "S" => Some(Duration::from_secs(amount)),
+        "m" => Some(Duration::from_millis(amount)),
+        "u" => Some(Duration::from_micros(amount)),
+        "n" => Some(Duration::from_nanos(amount)),
+        _ => None,
+    }
+}
+
+fn deadline(metadata: &MetadataMap) -> Option<Instant> {
+    let timeout = metadata.get("grpc-timeout")?.to_str().ok()?;
+    parse_grpc_timeout(timeout).map(|t| Instant::now() + t)
+}
+
+fn respond<T>(message: T, request_id: Option<MetadataValue<Ascii>>) -> Response<T> {
+    let mut response = Response::new(message);
+    if let Some(id) = request_id {
+        response.metadata_mut().insert(REQUEST_ID, id);
+    }
+    response
+}
+
+#[derive(Debug, Clone)]
+pub struct DeviceManager {
+    registry: Arc<Mutex<Registry>>,
+}
+
+impl DeviceManager {
+    pub fn new(registry: Arc<Mutex<Registry>>) -> DeviceManager {
+        DeviceManager { registry }
+    }
+
+    // A panic while holding the lock must not take every later call down
+    fn registry(&self) -> MutexGuard<'_, Registry> {
+        self.registry.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn into_server(self) -> DeviceServiceServer<DeviceManager> {
+        DeviceServiceServer::new(self)
+    }
+}
+
+type TelemetryStream = Pin<Box<dyn Stream<Item = Result<TelemetryReading, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl DeviceService for DeviceManager {
+    async fn register(
+        &self,
+        request: Request<RegisterRequest>,
+    ) -> Result<Response<RegisterResponse>, Status> {
+        let request_id = request.metadata().get(REQUEST_ID).cloned();
+        let req = request.into_inner();
+        let mut registry = self.registry();
+        let (record, existed) =
+            registry.register(&req.device_id, &req.model, &req.firmware, now_ms())?;
+        let reply = RegisterResponse {
+            session_token: record.token.clone(),
+            heartbeat_interval_ms: HEARTBEAT_INTERVAL_MS,
+            already_registered: existed,
+        };
+        Ok(respond(reply, request_id))
+    }
+
+    async fn heartbeat(
+        &self,
+        request: Request<HeartbeatRequest>,
+    ) -> Result<Response<HeartbeatResponse>, Status> {
+        let request_id = request.metadata().get(REQUEST_ID).cloned();
+        let token = bearer_token(request.metadata())?;
+        let req = request.into_inner();
+        let now = now_ms();
+        let mut registry = self.registry();
+        let record = registry.heartbeat(&req.device_id, &token, req.uptime_s, now)?;
+        let reply = HeartbeatResponse {
+            server_time_ms: now,
+            heartbeats: record.heartbeats,
+        };
+        Ok(respond(reply, request_id))
+    }
+
+    type StreamTelemetryStream = TelemetryStream;
+
+    async fn stream_telemetry(
+        &self,
+        request: Request<TelemetryRequest>,
+    ) -> Result<Response<TelemetryStream>, Status> {
+        let request_id = request.metadata().get(REQUEST_ID).cloned();
+        let token = bearer_token(request.metadata())?;
+        let deadline = deadline(request.metadata());
+        let mut req = request.into_inner();
+
+        if req.interval_ms < MIN_INTERVAL_MS {
+            return Err(Status::invalid_argument(format!(
+                "interval_ms must be at least {}",
+                MIN_INTERVAL_MS
+            )));
+        }
+        if !(1..=MAX_READINGS).contains(&req.max_readings) {
+            return Err(Status::invalid_argument(format!(
+                "max_readings must be 1-{}",
+                MAX_READINGS
+            )));
+        }
+        if let Some(m) = req.metrics.iter().find(|m| !METRICS.contains(&m.as_str())) {
+            return Err(Status::invalid_argument(format!(
+                "unknown metric '{}', expected one of {:?}",
+                m, METRICS
+            )));
+        }
+        let (seed, metadata) = {
+            let registry = self.registry();
+            let seed = registry.authenticate(&req.device_id, &token)?.seed;
+            (seed, registry.metadata(&req.device_id)?)
+        };
+        if let Some(m) = req.metrics.iter().find(|m| !metadata.reports(m)) {
+            return Err(Status::failed_precondition(format!(
+                "{} fw {} does not report '{}'",
+                metadata.model, metadata.firmware, m
+            )));
+        }
+        // No metrics asked for means everything this model reports
+        if req.metrics.is_empty() {
+            req.metrics = metadata.metrics.iter().map(|m| m.to_string()).collect();
+        }
+
+        let (tx, rx) = mpsc::channel(16);
+        tokio::spawn(produce_telemetry(req, seed, deadline, tx));
+        let stream: TelemetryStream = Box::pin(ReceiverStream::new(rx));
+        Ok(respond(stream, request_id))
+    }
+}
+
+// One simulated sensor node per stream, seeded per device so a device's
+// readings are the same every time it is asked
+async fn produce_telemetry(
+    req: TelemetryRequest,
+    seed: u32,
+    deadline: Option<Instant>,
+    tx: mpsc::Sender<Result<TelemetryReading, Status>>,
+) {
+    let interval = Duration::from_millis(req.interval_ms as u64);
+    let mut hub = SensorHub::new(1, seed);
+    let mut next_poll = Instant::now();
+    let mut sequence = 0u64;
+    let mut elapsed_ms = 0u64;
+
+    loop {
+        // Ending with DEADLINE_EXCEEDED at the deadline beats sending a
+        // reading the client has already given up on
+        if let Some(deadline) = deadline.filter(|&d| d <= next_poll) {
+            tokio::time::sleep_until(deadline).await;
+            let status = Status::deadline_exceeded(format!(
+                "deadline reached after {} of {} readings",
+                sequence, req.max_readings
+            ));
+            let _ = tx.send(Err(status)).await;
+            return;
+        }
+        tokio::time::sleep_until(next_poll).await;
+
+        let timestamp_ms = now_ms();
+        for reading in hub.poll(elapsed_ms, timestamp_ms) {
+            if !req.metrics.iter().any(|m| m == reading.metric) {
+                continue;
+            }
+            sequence += 1;
+            let message = TelemetryReading {
+                device_id: req.device_id.clone(),
+                metric: reading.metric.to_string(),
+                value: reading.value,
+                timestamp_ms,
+                sequence,
+            };
+            // The receiver is dropped when the client cancels or disconnects
+            if tx.send(Ok(message)).await.is_err() || sequence == req.max_readings as u64 {
+                return;
+            }
+        }
+        elapsed_ms += req.interval_ms as u64;
+        next_poll += interval;
+    }
+}
+
+// Serve on an already bound listener, so callers can use port 0
+pub async fn serve(
+    listener: TcpListener,
+    manager: DeviceManager,
+) -> Result<(), tonic::transport::Error> {
+    Server::builder()
+        .add_service(manager.into_server())
+        .serve_with_incoming(TcpListenerStream::new(listener))
+        .await
+}
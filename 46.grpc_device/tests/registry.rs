@@ -0,0 +1,183 @@
+use grpc_device::{validate_id, Registry, RegistryError};
+use std::sync::Arc;
+
+#[test]
+fn registering_again_keeps_the_token() {
+    let mut registry = Registry::new(42);
+    let (first, existed) = registry
+        .register("node-7", "th-sensor", "1.4.2", 1_000)
+        .unwrap();
+    let token = first.token.clone();
+    assert!(!existed);
+    let (again, existed) = registry
+        .register("node-7", "th-sensor", "1.5.0", 2_000)
+        .unwrap();
+    assert!(existed);
+    assert_eq!(again.token, token);
+    assert_eq!(&*again.firmware, "1.5.0");
+    assert_eq!((again.registered_ms, again.last_seen_ms), (1_000, 2_000));
+
+    let (other, _) = registry
+        .register("node-9", "th-sensor", "1.4.2", 3_000)
+        .unwrap();
+    assert_ne!(other.token, token);
+    assert_eq!(registry.len(), 2);
+}
+
+#[test]
+fn ids_are_lowercase_segments() {
+    for id in ["node-7", "plant-3/floor-2/th-1", "a", &"x".repeat(64)] {
+        assert_eq!(validate_id(id), Ok(()), "{:?}", id);
+    }
+    for id in [
+        "",
+        "Node 7!",
+        "plant-3//th-1",
+        "/node",
+        "node/",
+        "nöde",
+        &"x".repeat(65),
+    ] {
+        assert_eq!(
+            validate_id(id),
+            Err(RegistryError::InvalidId(id.to_string())),
+            "{:?}",
+            id
+        );
+    }
+    let mut registry = Registry::new(1);
+    assert!(registry
+        .register("plant-3//th-1", "th-sensor", "1.4.2", 0)
+        .is_err());
+    assert!(registry.is_empty());
+}
+
+#[test]
+fn heartbeats_need_the_device_token() {
+    let mut registry = Registry::new(42);
+    let token = registry
+        .register("node-7", "th-sensor", "1.4.2", 0)
+        .unwrap()
+        .0
+        .token
+        .clone();
+    registry
+        .register("node-9", "th-sensor", "1.4.2", 0)
+        .unwrap();
+
+    let record = registry.heartbeat("node-7", &token, 120, 5_000).unwrap();
+    assert_eq!(
+        (record.heartbeats, record.uptime_s, record.last_seen_ms),
+        (1, 120, 5_000)
+    );
+    assert_eq!(
+        registry.heartbeat("node-9", &token, 1, 6_000),
+        Err(RegistryError::BadToken("node-9".to_string()))
+    );
+    assert_eq!(
+        registry.heartbeat("node-404", &token, 1, 6_000),
+        Err(RegistryError::UnknownDevice("node-404".to_string()))
+    );
+    assert_eq!(registry.get("node-9").map(|d| d.heartbeats), Some(0));
+}
+
+#[test]
+fn prefix_lookups_stop_at_the_segment() {
+    let mut registry = Registry::new(42);
+    for id in [
+        "plant-3/floor-2/th-2",
+        "plant-3/floor-1/th-1",
+        "plant-3/floor-20/th-1",
+        "plant-3/floor-2/th-1",
+    ] {
+        registry.register(id, "th-sensor", "1.4.2", 0).unwrap();
+    }
+    let ids = |prefix| -> Vec<String> { registry.under(prefix).map(|d| d.id.clone()).collect() };
+    assert_eq!(
+        ids("plant-3/floor-2/"),
+        ["plant-3/floor-2/th-1", "plant-3/floor-2/th-2"]
+    );
+    // Without the '/', floor-20 matches too
+    assert_eq!(ids("plant-3/floor-2").len(), 3);
+    let all: Vec<&str> = registry.iter().map(|d| d.id.as_str()).collect();
+    assert_eq!(
+        all,
+        [
+            "plant-3/floor-1/th-1",
+            "plant-3/floor-2/th-1",
+            "plant-3/floor-2/th-2",
+            "plant-3/floor-20/th-1",
+        ]
+    );
+}
+
+#[test]
+fn devices_share_interned_names() {
+    let mut registry = Registry::new(42);
+    for id in ["node-7", "node-9", "node-11"] {
+        registry.register(id, "th-sensor", "1.4.2", 0).unwrap();
+    }
+    let (a, b) = (
+        registry.get("node-7").unwrap(),
+        registry.get("node-9").unwrap(),
+    );
+    assert!(Arc::ptr_eq(&a.firmware, &b.firmware));
+    assert!(Arc::ptr_eq(&a.model, &b.model));
+    assert_eq!(registry.interned_names(), 2);
+}
+
+#[test]
+fn metadata_is_fetched_once_per_model_and_firmware() {
+    let mut registry = Registry::new(42);
+    registry
+        .register("node-7", "th-sensor", "1.4.2", 0)
+        .unwrap();
+    registry
+        .register("node-9", "th-sensor", "1.4.2", 0)
+        .unwrap();
+    registry.register("probe-3", "t-probe", "2.0.1", 0).unwrap();
+    registry
+        .register("node-1", "th-sensor", "1.1.0", 0)
+        .unwrap();
+
+    for id in ["node-7", "node-9", "probe-3", "node-7", "probe-3"] {
+        registry.metadata(id).unwrap();
+    }
+    assert_eq!(registry.catalog_fetches(), 2);
+    let stats = registry.metadata_stats();
+    assert_eq!((stats.hits, stats.misses), (3, 2));
+
+    assert_eq!(
+        registry.metadata("probe-3").unwrap().metrics,
+        ["temperature", "battery"]
+    );
+    // Battery reporting came with firmware 1.2
+    assert!(!registry.metadata("node-1").unwrap().reports("battery"));
+    assert!(registry.metadata("node-7").unwrap().reports("battery"));
+    assert_eq!(
+        registry.metadata("node-404"),
+        Err(RegistryError::UnknownDevice("node-404".to_string()))
+    );
+
+    // A firmware update is a new key, nothing to invalidate
+    registry
+        .register("node-1", "th-sensor", "1.4.2", 0)
+        .unwrap();
+    assert!(registry.metadata("node-1").unwrap().reports("battery"));
+    assert_eq!(registry.catalog_fetches(), 3);
+}
+
+#[test]
+fn stale_devices_oldest_first() {
+    let mut registry = Registry::new(42);
+    registry.register("a", "th-sensor", "1.4.2", 1_000).unwrap();
+    registry.register("b", "th-sensor", "1.4.2", 500).unwrap();
+    registry.register("c", "th-sensor", "1.4.2", 9_000).unwrap();
+    let stale: Vec<&str> = registry
+        .stale(10_000, 5_000)
+        .iter()
+        .map(|d| d.id.as_str())
+        .collect();
+    assert_eq!(stale, ["b", "a"]);
+    assert!(registry.stale(10_000, 10_000).is_empty());
+}
@@ -0,0 +1,231 @@
+// The service over a real connection, one server per test on a free port
+use grpc_device::pb::device_service_client::DeviceServiceClient;
+use grpc_device::pb::{HeartbeatRequest, RegisterRequest, TelemetryRequest};
+use grpc_device::service::{parse_grpc_timeout, REQUEST_ID};
+use grpc_device::{authorized, serve, AuthorizedClient, BearerAuth, DeviceManager, Registry};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tonic::transport::Channel;
+use tonic::{Code, Request};
+
+fn register(id: &str, model: &str, firmware: &str) -> RegisterRequest {
+    RegisterRequest {
+        device_id: id.to_string(),
+        model: model.to_string(),
+        firmware: firmware.to_string(),
+    }
+}
+
+fn heartbeat(id: &str, uptime_s: u64) -> HeartbeatRequest {
+    HeartbeatRequest {
+        device_id: id.to_string(),
+        uptime_s,
+    }
+}
+
+fn telemetry(id: &str, max_readings: u32, metrics: &[&str]) -> TelemetryRequest {
+    TelemetryRequest {
+        device_id: id.to_string(),
+        interval_ms: 10,
+        max_readings,
+        metrics: metrics.iter().map(|m| m.to_string()).collect(),
+    }
+}
+
+async fn start() -> (Channel, Arc<Mutex<Registry>>) {
+    let registry = Arc::new(Mutex::new(Registry::new(42)));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve(listener, DeviceManager::new(Arc::clone(&registry))));
+    let channel = Channel::from_shared(format!("http://{}", addr))
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+    (channel, registry)
+}
+
+// Registers `id` and returns a client that sends its token
+async fn device(channel: &Channel, id: &str, model: &str, firmware: &str) -> AuthorizedClient {
+    let mut client = DeviceServiceClient::new(channel.clone());
+    let reply = client
+        .register(register(id, model, firmware))
+        .await
+        .unwrap()
+        .into_inner();
+    authorized(
+        channel.clone(),
+        BearerAuth::new(&reply.session_token).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn register_is_idempotent_and_validates_ids() {
+    let (channel, _) = start().await;
+    let mut client = DeviceServiceClient::new(channel);
+    let first = client
+        .register(register("node-7", "th-sensor", "1.4.2"))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!first.already_registered);
+    assert_eq!(first.heartbeat_interval_ms, 5_000);
+    let again = client
+        .register(register("node-7", "th-sensor", "1.4.2"))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(again.already_registered);
+    assert_eq!(again.session_token, first.session_token);
+    let other = client
+        .register(register("node-9", "th-sensor", "1.4.2"))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_ne!(other.session_token, first.session_token);
+
+    for id in ["Node 7!", "plant-3//th-1"] {
+        let status = client.register(register(id, "x", "1")).await.unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument, "{:?}", id);
+    }
+}
+
+#[tokio::test]
+async fn heartbeats_are_authenticated_and_echo_the_request_id() {
+    let (channel, registry) = start().await;
+    let mut node7 = device(&channel, "node-7", "th-sensor", "1.4.2").await;
+    device(&channel, "node-9", "th-sensor", "1.4.2").await;
+
+    let mut request = Request::new(heartbeat("node-7", 120));
+    request
+        .metadata_mut()
+        .insert(REQUEST_ID, "test-0001".parse().unwrap());
+    let response = node7.heartbeat(request).await.unwrap();
+    assert_eq!(response.get_ref().heartbeats, 1);
+    assert_eq!(
+        response
+            .metadata()
+            .get(REQUEST_ID)
+            .map(|v| v.to_str().unwrap()),
+        Some("test-0001")
+    );
+
+    let mut anonymous = DeviceServiceClient::new(channel.clone());
+    let failures = [
+        (
+            anonymous.heartbeat(heartbeat("node-7", 1)).await,
+            Code::Unauthenticated,
+        ),
+        (
+            node7.heartbeat(heartbeat("node-9", 1)).await,
+            Code::Unauthenticated,
+        ),
+        (
+            node7.heartbeat(heartbeat("node-404", 1)).await,
+            Code::NotFound,
+        ),
+    ];
+    for (result, code) in failures {
+        assert_eq!(result.unwrap_err().code(), code);
+    }
+    let registry = registry.lock().unwrap();
+    assert_eq!(registry.get("node-7").map(|d| d.uptime_s), Some(120));
+    assert_eq!(registry.get("node-9").map(|d| d.heartbeats), Some(0));
+}
+
+#[tokio::test]
+async fn a_stream_ends_after_max_readings() {
+    let (channel, _) = start().await;
+    let mut node7 = device(&channel, "node-7", "th-sensor", "1.4.2").await;
+    let mut stream = node7
+        .stream_telemetry(telemetry("node-7", 6, &["temperature", "humidity"]))
+        .await
+        .unwrap()
+        .into_inner();
+    let mut sequences = Vec::new();
+    while let Some(reading) = stream.message().await.unwrap() {
+        assert!(["temperature", "humidity"].contains(&reading.metric.as_str()));
+        assert_eq!(reading.device_id, "node-7");
+        sequences.push(reading.sequence);
+    }
+    assert_eq!(sequences, [1, 2, 3, 4, 5, 6]);
+}
+
+#[tokio::test]
+async fn streams_check_metrics_against_the_model() {
+    let (channel, _) = start().await;
+    let mut node7 = device(&channel, "node-7", "th-sensor", "1.4.2").await;
+    let mut probe3 = device(&channel, "probe-3", "t-probe", "2.0.1").await;
+
+    let status = node7
+        .stream_telemetry(telemetry("node-7", 5, &["pressure"]))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    let status = probe3
+        .stream_telemetry(telemetry("probe-3", 5, &["humidity"]))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::FailedPrecondition);
+
+    // No metrics: whatever the model reports
+    let mut stream = probe3
+        .stream_telemetry(telemetry("probe-3", 4, &[]))
+        .await
+        .unwrap()
+        .into_inner();
+    let mut metrics = Vec::new();
+    while let Some(reading) = stream.message().await.unwrap() {
+        if !metrics.contains(&reading.metric) {
+            metrics.push(reading.metric);
+        }
+    }
+    assert_eq!(metrics, ["temperature", "battery"]);
+
+    for bad in [
+        TelemetryRequest {
+            interval_ms: 1,
+            ..telemetry("node-7", 5, &[])
+        },
+        telemetry("node-7", 0, &[]),
+        telemetry("node-7", 10_001, &[]),
+    ] {
+        let status = node7.stream_telemetry(bad).await.unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+    }
+}
+
+#[tokio::test]
+async fn a_stream_stops_at_the_client_deadline() {
+    let (channel, _) = start().await;
+    let mut node7 = device(&channel, "node-7", "th-sensor", "1.4.2").await;
+    // 40 readings at 50 ms would need 2 s; the client allows 300 ms
+    let mut request = Request::new(TelemetryRequest {
+        interval_ms: 50,
+        ..telemetry("node-7", 40, &["temperature"])
+    });
+    request.set_timeout(Duration::from_millis(300));
+    let mut stream = node7.stream_telemetry(request).await.unwrap().into_inner();
+    let mut received = 0;
+    let ended = loop {
+        match stream.message().await {
+            Ok(Some(_)) => received += 1,
+            Ok(None) => break None,
+            Err(status) => break Some(status),
+        }
+    };
+    assert_eq!(ended.map(|s| s.code()), Some(Code::DeadlineExceeded));
+    assert!(received < 40, "{} readings", received);
+}
+
+#[test]
+fn grpc_timeouts_parse() {
+    assert_eq!(parse_grpc_timeout("300m"), Some(Duration::from_millis(300)));
+    assert_eq!(parse_grpc_timeout("2S"), Some(Duration::from_secs(2)));
+    assert_eq!(parse_grpc_timeout("1H"), Some(Duration::from_secs(3600)));
+    assert_eq!(parse_grpc_timeout("5n"), Some(Duration::from_nanos(5)));
+    for bad in ["", "m", "300", "3x", "123456789S", "-1S"] {
+        assert_eq!(parse_grpc_timeout(bad), None, "{:?}", bad);
+    }
+}
@@ -0,0 +1,190 @@
+// Routes and handlers
+//
+//   GET  /devices                  list, optionally ?model=...
+//   GET  /devices/{id}             one device
+//   POST /devices/{id}/commands    queue a command, 202 Accepted
+//
+// Handlers are plain async functions; extractors in their arguments pull
+// state, path segments, query strings and JSON bodies out of the request,
+// and anything that implements `IntoResponse` can be returned.
+
+use crate::commands::{CommandQueue, CommandRequest, DEFAULT_TTL_MS, QUEUE_CAPACITY};
+use crate::error::ApiError;
+use axum::extract::rejection::{JsonRejection, QueryRejection};
+use axum::extract::{Path, Query, State};
+use axum::http::{StatusCode, Uri};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use grpc_device::service::now_ms;
+use grpc_device::{validate_id, DeviceRecord, Registry};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+pub const MAX_TTL_MS: u32 = 3_600_000;
+
+// Cloned into every request, so it only holds handles
+#[derive(Debug, Clone)]
+pub struct AppState {
+    registry: Arc<Mutex<Registry>>,
+    commands: Arc<Mutex<CommandQueue>>,
+}
+
+impl AppState {
+    pub fn new(registry: Arc<Mutex<Registry>>, commands: Arc<Mutex<CommandQueue>>) -> AppState {
+        AppState { registry, commands }
+    }
+
+    fn registry(&self) -> MutexGuard<'_, Registry> {
+        self.registry.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn commands(&self) -> MutexGuard<'_, CommandQueue> {
+        self.commands.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/devices", get(list_devices))
+        .route("/devices/{id}", get(get_device))
+        .route("/devices/{id}/commands", post(post_command))
+        .fallback(no_route)
+        .with_state(state)
+}
+
+// What the API shows of a device: never the session token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceView {
+    pub id: String,
+    pub model: String,
+    pub firmware: String,
+    pub registered_ms: u64,
+    pub last_seen_ms: u64,
+    pub uptime_s: u64,
+    pub heartbeats: u64,
+    pub pending_commands: usize,
+}
+
+impl DeviceView {
+    fn new(record: &DeviceRecord, pending_commands: usize) -> DeviceView {
+        DeviceView {
+            id: record.id.clone(),
+            model: record.model.to_string(),
+            firmware: record.firmware.to_string(),
+            registered_ms: record.registered_ms,
+            last_seen_ms: record.last_seen_ms,
+            uptime_s: record.uptime_s,
+            heartbeats: record.heartbeats,
+            pending_commands,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeviceList {
+    pub count: usize,
+    pub devices: Vec<DeviceView>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListParams {
+    model: Option<String>,
+    // "plant-3/floor-2/": every device on that floor
+    prefix: Option<String>,
+}
+
+// {"command": "read_sensor", "channel": 2, "ttl_ms": 5000}
+#[derive(Debug, Deserialize)]
+pub struct CommandBody {
+    #[serde(flatten)]
+    command: CommandRequest,
+    #[serde(default = "default_ttl")]
+    ttl_ms: u32,
+}
+
+fn default_ttl() -> u32 {
+    DEFAULT_TTL_MS
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommandAccepted {
+    pub command_id: u64,
+    pub device_id: String,
+    pub command: CommandRequest,
+    pub ttl_ms: u32,
+    pub pending: usize,
+}
+
+async fn list_devices(
+    State(state): State<AppState>,
+    params: Result<Query<ListParams>, QueryRejection>,
+) -> Result<Json<DeviceList>, ApiError> {
+    let Query(params) = params?;
+    let registry = state.registry();
+    let commands = state.commands();
+    // The registry walks only the devices under the prefix, in id order,
+    // so the listing is stable without sorting
+    let devices: Vec<DeviceView> = registry
+        .under(params.prefix.as_deref().unwrap_or(""))
+        .filter(|d| params.model.as_ref().is_none_or(|m| *d.model == **m))
+        .map(|d| DeviceView::new(d, commands.pending(&d.id)))
+        .collect();
+    Ok(Json(DeviceList {
+        count: devices.len(),
+        devices,
+    }))
+}
+
+fn lookup(state: &AppState, id: &str) -> Result<DeviceView, ApiError> {
+    validate_id(id)?;
+    let registry = state.registry();
+    let record = registry
+        .get(id)
+        .ok_or_else(|| ApiError::DeviceNotFound(id.to_string()))?;
+    Ok(DeviceView::new(record, state.commands().pending(id)))
+}
+
+async fn get_device(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<DeviceView>, ApiError> {
+    lookup(&state, &id).map(Json)
+}
+
+// The body extractor must come last: it consumes the request
+async fn post_command(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    body: Result<Json<CommandBody>, JsonRejection>,
+) -> Result<(StatusCode, Json<CommandAccepted>), ApiError> {
+    // 404 for an unknown device wins over complaints about the body
+    lookup(&state, &id)?;
+    let Json(body) = body?;
+    let kind = body.command.validate().map_err(ApiError::InvalidCommand)?;
+    if !(1..=MAX_TTL_MS).contains(&body.ttl_ms) {
+        return Err(ApiError::InvalidCommand(format!(
+            "ttl_ms must be 1-{}",
+            MAX_TTL_MS
+        )));
+    }
+
+    let mut commands = state.commands();
+    let queued = commands
+        .push(&id, kind, body.ttl_ms, now_ms())
+        .ok_or_else(|| ApiError::QueueFull {
+            device: id.clone(),
+            capacity: QUEUE_CAPACITY,
+        })?;
+    let accepted = CommandAccepted {
+        command_id: queued.id,
+        device_id: id.clone(),
+        command: queued.kind.into(),
+        ttl_ms: queued.ttl_ms,
+        pending: commands.pending(&id),
+    };
+    Ok((StatusCode::ACCEPTED, Json(accepted)))
+}
+
+async fn no_route(uri: Uri) -> ApiError {
+    ApiError::NoRoute(uri.path().to_string())
+}
@@ -0,0 +1,84 @@
+// Standalone REST server
+//
+//   cargo run --bin server                  # listens on 127.0.0.1:8080
+//   cargo run --bin server -- 0.0.0.0:8080
+//
+//   curl localhost:8080/devices
+//   curl -X POST localhost:8080/devices/node-1/commands \
+//        -H 'content-type: application/json' -d '{"command":"reboot"}'
+//
+// With `--features systemd` a socket unit can own the port instead: the
+// server then takes the listener it is given and ignores the address.
+//
+// Ctrl-C or SIGTERM stops accepting connections and lets requests already
+// in flight finish before the process exits; a second one exits at once.
+
+use grpc_device::service::now_ms;
+use grpc_device::Registry;
+use rest_api::{router, AppState, CommandQueue};
+use shutdown::{handle_signals, Shutdown, Token};
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+
+// The socket unit's listener, or `addr` bound here
+#[cfg(feature = "systemd")]
+fn std_listener(addr: &str) -> Result<std::net::TcpListener, Box<dyn std::error::Error>> {
+    let (listener, source) = systemd::tcp_listener_or_bind(addr)?;
+    if let systemd::ListenerSource::Activated(name) = source {
+        println!("socket activation: using fd '{}'", name);
+    }
+    Ok(listener)
+}
+
+#[cfg(not(feature = "systemd"))]
+fn std_listener(addr: &str) -> Result<std::net::TcpListener, Box<dyn std::error::Error>> {
+    Ok(std::net::TcpListener::bind(addr)?)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Taken before the runtime starts its threads: this clears LISTEN_*
+    let addr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    let listener = std_listener(&addr)?;
+    // tokio wants it non-blocking
+    listener.set_nonblocking(true)?;
+    let shutdown = Shutdown::new();
+    handle_signals(&shutdown)?;
+    serve(listener, shutdown.token())
+}
+
+#[tokio::main]
+async fn serve(
+    listener: std::net::TcpListener,
+    token: Token,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // A few devices to look at; a real gateway would share this registry
+    // with the gRPC service from 46.grpc_device
+    let mut registry = Registry::new(now_ms());
+    for (id, model) in [("node-1", "th-sensor"), ("node-2", "th-sensor")] {
+        registry.register(id, model, "1.4.2", now_ms())?;
+    }
+    let state = AppState::new(
+        Arc::new(Mutex::new(registry)),
+        Arc::new(Mutex::new(CommandQueue::new())),
+    );
+
+    let listener = TcpListener::from_std(listener)?;
+    println!("REST API listening on http://{}", listener.local_addr()?);
+    // Type=notify units start dependents only after this
+    #[cfg(feature = "systemd")]
+    systemd::Notifier::from_env()?.notify(&[systemd::State::Ready])?;
+    let stop = token.clone();
+    axum::serve(listener, router(state))
+        .with_graceful_shutdown(async move {
+            stop.cancelled().await;
+        })
+        .await?;
+    #[cfg(feature = "systemd")]
+    systemd::Notifier::from_env()?.notify(&[systemd::State::Stopping])?;
+    if let Some(reason) = token.reason() {
+        println!("REST API stopped ({}), in-flight requests finished", reason);
+    }
+    Ok(())
+}
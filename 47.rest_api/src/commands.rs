@@ -0,0 +1,124 @@
+// Commands waiting to be delivered to devices
+//
+// The REST side only queues; a device (or the command protocol link from
+// 14.command_protocol) drains its queue on its next contact. The JSON body
+// is a tagged enum so every command carries exactly the fields it needs.
+
+use command_protocol::CommandKind;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+pub const QUEUE_CAPACITY: usize = 8;
+pub const DEFAULT_TTL_MS: u32 = 60_000;
+pub const MAX_INTERVAL_SECS: u32 = 86_400;
+pub const SENSOR_CHANNELS: u8 = 8;
+
+// {"command": "set_interval", "seconds": 30}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum CommandRequest {
+    Ping,
+    SetInterval { seconds: u32 },
+    ReadSensor { channel: u8 },
+    Reboot,
+}
+
+impl CommandRequest {
+    // Range checks serde cannot express
+    pub fn validate(self) -> Result<CommandKind, String> {
+        match self {
+            CommandRequest::Ping => Ok(CommandKind::Ping),
+            CommandRequest::SetInterval { seconds } => {
+                if (1..=MAX_INTERVAL_SECS).contains(&seconds) {
+                    Ok(CommandKind::SetInterval(seconds))
+                } else {
+                    Err(format!("seconds must be 1-{}", MAX_INTERVAL_SECS))
+                }
+            }
+            CommandRequest::ReadSensor { channel } => {
+                if channel < SENSOR_CHANNELS {
+                    Ok(CommandKind::ReadSensor(channel))
+                } else {
+                    Err(format!("channel must be 0-{}", SENSOR_CHANNELS - 1))
+                }
+            }
+            CommandRequest::Reboot => Ok(CommandKind::Reboot),
+        }
+    }
+}
+
+impl From<CommandKind> for CommandRequest {
+    fn from(kind: CommandKind) -> CommandRequest {
+        match kind {
+            CommandKind::Ping => CommandRequest::Ping,
+            CommandKind::SetInterval(seconds) => CommandRequest::SetInterval { seconds },
+            CommandKind::ReadSensor(channel) => CommandRequest::ReadSensor { channel },
+            CommandKind::Reboot => CommandRequest::Reboot,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueuedCommand {
+    pub id: u64,
+    pub kind: CommandKind,
+    pub queued_ms: u64,
+    pub ttl_ms: u32,
+}
+
+impl QueuedCommand {
+    pub fn expired(&self, now_ms: u64) -> bool {
+        now_ms >= self.queued_ms + self.ttl_ms as u64
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CommandQueue {
+    pending: HashMap<String, VecDeque<QueuedCommand>>,
+    next_id: u64,
+}
+
+impl CommandQueue {
+    pub fn new() -> CommandQueue {
+        CommandQueue::default()
+    }
+
+    // Expired commands are dropped first so they never block new ones.
+    // Returns None when the device already has QUEUE_CAPACITY pending.
+    pub fn push(
+        &mut self,
+        device_id: &str,
+        kind: CommandKind,
+        ttl_ms: u32,
+        now_ms: u64,
+    ) -> Option<QueuedCommand> {
+        let queue = self.pending.entry(device_id.to_string()).or_default();
+        queue.retain(|c| !c.expired(now_ms));
+        if queue.len() >= QUEUE_CAPACITY {
+            return None;
+        }
+        self.next_id += 1;
+        let command = QueuedCommand {
+            id: self.next_id,
+            kind,
+            queued_ms: now_ms,
+            ttl_ms,
+        };
+        queue.push_back(command);
+        Some(command)
+    }
+
+    pub fn pending(&self, device_id: &str) -> usize {
+        self.pending.get(device_id).map_or(0, VecDeque::len)
+    }
+
+    // Everything still deliverable, oldest first
+    pub fn drain(&mut self, device_id: &str, now_ms: u64) -> Vec<QueuedCommand> {
+        self.pending
+            .remove(device_id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|c| !c.expired(now_ms))
+            .collect()
+    }
+}
@@ -0,0 +1,138 @@
+// Typed API errors
+//
+// Every failure leaves the API as the same JSON shape:
+//
+//   {"error": {"code": "device_not_found", "message": "..."}}
+//
+// Handlers return `Result<_, ApiError>` and use `?`; `IntoResponse` picks
+// the HTTP status in one place.
+
+use axum::extract::rejection::{JsonRejection, QueryRejection};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use grpc_device::{RegistryError, MAX_ID_LEN};
+use serde::Serialize;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ApiError {
+    InvalidId(String),
+    DeviceNotFound(String),
+    InvalidCommand(String),
+    QueueFull { device: String, capacity: usize },
+    BadRequest(String),
+    UnsupportedMediaType(String),
+    NoRoute(String),
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::InvalidId(_) | ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::DeviceNotFound(_) | ApiError::NoRoute(_) => StatusCode::NOT_FOUND,
+            ApiError::InvalidCommand(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::QueueFull { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        }
+    }
+
+    // Stable, machine-readable; the message is for humans and may change
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::InvalidId(_) => "invalid_id",
+            ApiError::DeviceNotFound(_) => "device_not_found",
+            ApiError::InvalidCommand(_) => "invalid_command",
+            ApiError::QueueFull { .. } => "queue_full",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::UnsupportedMediaType(_) => "unsupported_media_type",
+            ApiError::NoRoute(_) => "no_route",
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::InvalidId(id) => {
+                write!(
+                    f,
+                    "invalid device id '{}': use 1-{} of [a-z0-9-], with '/' between segments",
+                    id, MAX_ID_LEN
+                )
+            }
+            ApiError::DeviceNotFound(id) => write!(f, "device '{}' is not registered", id),
+            ApiError::InvalidCommand(why) => write!(f, "invalid command: {}", why),
+            ApiError::QueueFull { device, capacity } => write!(
+                f,
+                "device '{}' already has {} commands pending",
+                device, capacity
+            ),
+            ApiError::BadRequest(why) | ApiError::UnsupportedMediaType(why) => {
+                write!(f, "{}", why)
+            }
+            ApiError::NoRoute(path) => write!(f, "no route for {}", path),
+        }
+    }
+}
+
+impl Error for ApiError {}
+
+impl From<RegistryError> for ApiError {
+    fn from(e: RegistryError) -> ApiError {
+        match e {
+            RegistryError::InvalidId(id) => ApiError::InvalidId(id),
+            RegistryError::UnknownDevice(id) | RegistryError::BadToken(id) => {
+                ApiError::DeviceNotFound(id)
+            }
+        }
+    }
+}
+
+// axum's own rejections would answer in plain text; keep the JSON shape
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> ApiError {
+        match rejection {
+            // Well-formed JSON that does not fit the type is a semantic
+            // error; serde's message is the useful part
+            JsonRejection::JsonDataError(e) => match e.source() {
+                Some(serde) => ApiError::InvalidCommand(serde.to_string()),
+                None => ApiError::InvalidCommand(e.body_text()),
+            },
+            JsonRejection::MissingJsonContentType(e) => {
+                ApiError::UnsupportedMediaType(e.body_text())
+            }
+            other => ApiError::BadRequest(other.body_text()),
+        }
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> ApiError {
+        ApiError::BadRequest(rejection.body_text())
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: ErrorDetail,
+}
+
+#[derive(Serialize)]
+struct ErrorDetail {
+    code: &'static str,
+    message: String,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorBody {
+            error: ErrorDetail {
+                code: self.code(),
+                message: self.to_string(),
+            },
+        };
+        (self.status(), Json(body)).into_response()
+    }
+}
@@ -0,0 +1,16 @@
+// REST API over the device registry with axum
+//
+// - `api`: the router, handlers and JSON views
+// - `error`: `ApiError`, one JSON error shape for every failure
+// - `commands`: the per-device queue behind POST /devices/{id}/commands
+//
+// The registry is the one from 46.grpc_device, so the same devices can be
+// served over gRPC and REST at once.
+
+pub mod api;
+pub mod commands;
+pub mod error;
+
+pub use api::{router, AppState, CommandAccepted, DeviceList, DeviceView};
+pub use commands::{CommandQueue, CommandRequest, QueuedCommand};
+pub use error::ApiError;
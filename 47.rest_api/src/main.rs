@@ -0,0 +1,213 @@
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use grpc_device::Registry;
+use http_body_util::BodyExt;
+use rest_api::{router, AppState, CommandQueue};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+
+// Drive the router like a server would, without a socket: `oneshot` sends
+// one request through the service and returns its response
+async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(request)
+        .await
+        .expect("Router never fails");
+    let status = response.status();
+    let bytes = response
+        .into_body()
+        .collect()
+        .await
+        .map(|b| b.to_bytes())
+        .unwrap_or_default();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+async fn get(app: &Router, uri: &str) -> (StatusCode, Value) {
+    let request = Request::get(uri)
+        .body(Body::empty())
+        .expect("valid request");
+    send(app, request).await
+}
+
+async fn post_json(app: &Router, uri: &str, body: &str) -> (StatusCode, Value) {
+    let request = Request::post(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("valid request");
+    send(app, request).await
+}
+
+fn show(label: &str, (status, body): &(StatusCode, Value)) {
+    let text = match body.pointer("/error") {
+        Some(error) => format!("{} {}", error["code"], error["message"]),
+        None => body.to_string(),
+    };
+    println!("   {:<34} {} {}", label, status.as_u16(), text);
+}
+
+#[tokio::main]
+async fn main() {
+    println!("=== REST API with axum Examples ===\n");
+
+    // The registry is filled the way the gRPC service would fill it
+    let registry = Arc::new(Mutex::new(Registry::new(42)));
+    {
+        let mut r = registry.lock().unwrap();
+        for (id, model, at) in [
+            ("node-7", "th-sensor", 1_000),
+            ("node-3", "th-sensor", 2_000),
+            ("pump-1", "flow-meter", 3_000),
+            ("plant-3/floor-2/th-1", "th-sensor", 4_000),
+            ("plant-3/floor-2/flow-1", "flow-meter", 4_000),
+            ("plant-3/floor-1/th-1", "th-sensor", 4_000),
+        ] {
+            r.register(id, model, "1.4.2", at).expect("valid id");
+        }
+        let token = r.get("node-7").unwrap().token.clone();
+        r.heartbeat("node-7", &token, 3600, 5_000).unwrap();
+    }
+    let commands = Arc::new(Mutex::new(CommandQueue::new()));
+    let app = router(AppState::new(Arc::clone(&registry), Arc::clone(&commands)));
+
+    // 1. Listing
+    println!("1. GET /devices:");
+    let (status, list) = get(&app, "/devices").await;
+    let ids: Vec<&str> = list["devices"]
+        .as_array()
+        .map(|d| d.iter().filter_map(|d| d["id"].as_str()).collect())
+        .unwrap_or_default();
+    println!("   {} {:?}", status.as_u16(), ids);
+    for uri in [
+        "/devices?model=flow-meter",
+        "/devices?prefix=plant-3/floor-2/",
+        "/devices?prefix=plant-3/&model=th-sensor",
+    ] {
+        let (_, filtered) = get(&app, uri).await;
+        println!("   {:<41} {} device(s)", uri, filtered["count"]);
+    }
+
+    // 2. One device
+    println!("\n2. GET /devices/{{id}}:");
+    let found = get(&app, "/devices/node-7").await;
+    println!(
+        "   {}",
+        serde_json::to_string_pretty(&found.1)
+            .unwrap()
+            .replace('\n', "\n   ")
+    );
+    let missing = get(&app, "/devices/node-404").await;
+    show("/devices/node-404", &missing);
+    let invalid = get(&app, "/devices/Node%207").await;
+    show("/devices/Node%207", &invalid);
+    // A '/' inside an id is sent as %2F; routing sees one segment and the
+    // Path extractor decodes it
+    let (status, nested) = get(&app, "/devices/plant-3%2Ffloor-2%2Fth-1").await;
+    println!(
+        "   {:<34} {} {}",
+        "/devices/plant-3%2Ffloor-2%2Fth-1",
+        status.as_u16(),
+        nested["id"]
+    );
+
+    // 3. Queueing commands
+    println!("\n3. POST /devices/{{id}}/commands:");
+    let bodies = [
+        json!({"command": "set_interval", "seconds": 30}),
+        json!({"command": "read_sensor", "channel": 2, "ttl_ms": 5000}),
+        json!({"command": "ping"}),
+    ];
+    for body in &bodies {
+        let (status, reply) = post_json(&app, "/devices/node-7/commands", &body.to_string()).await;
+        println!("   {} {}", status.as_u16(), reply);
+    }
+    let (_, node7) = get(&app, "/devices/node-7").await;
+    println!("   node-7 pending_commands: {}", node7["pending_commands"]);
+
+    // 4. Every failure has the same JSON shape
+    println!("\n4. Typed error responses:");
+    let cases = [
+        (
+            "unknown device",
+            post_json(&app, "/devices/node-404/commands", r#"{"command":"ping"}"#).await,
+        ),
+        (
+            "malformed JSON",
+            post_json(&app, "/devices/node-7/commands", r#"{"command":"#).await,
+        ),
+        (
+            "unknown command",
+            post_json(&app, "/devices/node-7/commands", r#"{"command":"format"}"#).await,
+        ),
+        (
+            "seconds out of range",
+            post_json(
+                &app,
+                "/devices/node-7/commands",
+                r#"{"command":"set_interval","seconds":0}"#,
+            )
+            .await,
+        ),
+        (
+            "missing content-type",
+            send(
+                &app,
+                Request::post("/devices/node-7/commands")
+                    .body(Body::from(r#"{"command":"ping"}"#))
+                    .unwrap(),
+            )
+            .await,
+        ),
+        ("unknown route", get(&app, "/sensors").await),
+    ];
+    for (label, response) in &cases {
+        show(label, response);
+    }
+
+    let mut last = StatusCode::ACCEPTED;
+    let mut posted = 0;
+    while last == StatusCode::ACCEPTED {
+        last = post_json(&app, "/devices/pump-1/commands", r#"{"command":"ping"}"#)
+            .await
+            .0;
+        posted += 1;
+    }
+    println!(
+        "   pump-1 queue full after {} posts, then {}",
+        posted - 1,
+        last.as_u16()
+    );
+    let wrong_method = send(
+        &app,
+        Request::builder()
+            .method(Method::DELETE)
+            .uri("/devices/node-7")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    println!(
+        "   DELETE /devices/node-7 -> {} (axum's own answer)",
+        wrong_method.0.as_u16()
+    );
+
+    // 5. The device side drains its queue on next contact
+    println!("\n5. Delivering queued commands:");
+    let delivered = commands
+        .lock()
+        .unwrap()
+        .drain("node-7", grpc_device::service::now_ms());
+    for command in &delivered {
+        println!("   #{} {:?}", command.id, command.kind);
+    }
+    let (_, node7) = get(&app, "/devices/node-7").await;
+    println!("   node-7 pending_commands: {}", node7["pending_commands"]);
+
+    println!("\n=== End of REST API with axum Examples ===");
+}
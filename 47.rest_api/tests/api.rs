@@ -0,0 +1,310 @@
+// Every route and error case, sent through the router with `oneshot`
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use command_protocol::CommandKind;
+use grpc_device::Registry;
+use http_body_util::BodyExt;
+use rest_api::{router, AppState, CommandQueue};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+
+struct Api {
+    app: Router,
+    commands: Arc<Mutex<CommandQueue>>,
+}
+
+fn api() -> Api {
+    let registry = Arc::new(Mutex::new(Registry::new(42)));
+    {
+        let mut r = registry.lock().unwrap();
+        for (id, model, at) in [
+            ("node-7", "th-sensor", 1_000),
+            ("node-3", "th-sensor", 2_000),
+            ("pump-1", "flow-meter", 3_000),
+            ("plant-3/floor-2/th-1", "th-sensor", 4_000),
+            ("plant-3/floor-2/flow-1", "flow-meter", 4_000),
+            ("plant-3/floor-1/th-1", "th-sensor", 4_000),
+        ] {
+            r.register(id, model, "1.4.2", at).unwrap();
+        }
+        let token = r.get("node-7").unwrap().token.clone();
+        r.heartbeat("node-7", &token, 3600, 5_000).unwrap();
+    }
+    let commands = Arc::new(Mutex::new(CommandQueue::new()));
+    Api {
+        app: router(AppState::new(registry, Arc::clone(&commands))),
+        commands,
+    }
+}
+
+async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Value) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+async fn get(app: &Router, uri: &str) -> (StatusCode, Value) {
+    send(app, Request::get(uri).body(Body::empty()).unwrap()).await
+}
+
+async fn post_json(app: &Router, uri: &str, body: &str) -> (StatusCode, Value) {
+    let request = Request::post(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    send(app, request).await
+}
+
+fn ids(list: &Value) -> Vec<&str> {
+    list["devices"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["id"].as_str().unwrap())
+        .collect()
+}
+
+// The error shape every failure shares, as (status, code)
+fn error(response: &(StatusCode, Value)) -> (StatusCode, &str) {
+    let code = response.1["error"]["code"].as_str().unwrap_or("");
+    assert!(response.1["error"]["message"].is_string());
+    (response.0, code)
+}
+
+#[tokio::test]
+async fn list_is_sorted_by_id_and_hides_tokens() {
+    let api = api();
+    let (status, list) = get(&api.app, "/devices").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(list["count"], 6);
+    assert_eq!(
+        ids(&list),
+        [
+            "node-3",
+            "node-7",
+            "plant-3/floor-1/th-1",
+            "plant-3/floor-2/flow-1",
+            "plant-3/floor-2/th-1",
+            "pump-1",
+        ]
+    );
+    assert!(!list.to_string().contains("token"));
+}
+
+#[tokio::test]
+async fn list_filters_by_model_and_prefix() {
+    let api = api();
+    let (_, by_model) = get(&api.app, "/devices?model=flow-meter").await;
+    assert_eq!(ids(&by_model), ["plant-3/floor-2/flow-1", "pump-1"]);
+    let (_, by_prefix) = get(&api.app, "/devices?prefix=plant-3/floor-2/").await;
+    assert_eq!(
+        ids(&by_prefix),
+        ["plant-3/floor-2/flow-1", "plant-3/floor-2/th-1"]
+    );
+    let (_, both) = get(&api.app, "/devices?prefix=plant-3/&model=th-sensor").await;
+    assert_eq!(both["count"], 2);
+    assert_eq!(ids(&both), ["plant-3/floor-1/th-1", "plant-3/floor-2/th-1"]);
+    let (status, none) = get(&api.app, "/devices?model=camera").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(none, json!({"count": 0, "devices": []}));
+}
+
+#[tokio::test]
+async fn one_device_as_json() {
+    let api = api();
+    let (status, device) = get(&api.app, "/devices/node-7").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        device,
+        json!({
+            "id": "node-7",
+            "model": "th-sensor",
+            "firmware": "1.4.2",
+            "registered_ms": 1000,
+            "last_seen_ms": 5000,
+            "uptime_s": 3600,
+            "heartbeats": 1,
+            "pending_commands": 0,
+        })
+    );
+
+    // A '/' inside an id is sent as %2F and decoded by the Path extractor
+    let (status, nested) = get(&api.app, "/devices/plant-3%2Ffloor-2%2Fth-1").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(nested["id"], "plant-3/floor-2/th-1");
+}
+
+#[tokio::test]
+async fn unknown_and_invalid_ids() {
+    let api = api();
+    let missing = get(&api.app, "/devices/node-404").await;
+    assert_eq!(error(&missing), (StatusCode::NOT_FOUND, "device_not_found"));
+    assert_eq!(
+        missing.1["error"]["message"],
+        "device 'node-404' is not registered"
+    );
+    let invalid = get(&api.app, "/devices/Node%207").await;
+    assert_eq!(error(&invalid), (StatusCode::BAD_REQUEST, "invalid_id"));
+}
+
+#[tokio::test]
+async fn commands_are_accepted_queued_and_delivered_in_order() {
+    let api = api();
+    let bodies = [
+        json!({"command": "set_interval", "seconds": 30}),
+        json!({"command": "read_sensor", "channel": 2, "ttl_ms": 5000}),
+        json!({"command": "ping"}),
+    ];
+    for (i, body) in bodies.iter().enumerate() {
+        let (status, reply) =
+            post_json(&api.app, "/devices/node-7/commands", &body.to_string()).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert_eq!(reply["device_id"], "node-7");
+        assert_eq!(reply["pending"], i + 1);
+        assert_eq!(reply["command"]["command"], body["command"]);
+    }
+    let (_, accepted) = post_json(
+        &api.app,
+        "/devices/pump-1/commands",
+        r#"{"command":"reboot"}"#,
+    )
+    .await;
+    assert_eq!(accepted["ttl_ms"], 60_000);
+    assert_eq!(accepted["command"], json!({"command": "reboot"}));
+
+    let (_, node7) = get(&api.app, "/devices/node-7").await;
+    assert_eq!(node7["pending_commands"], 3);
+
+    let delivered = api
+        .commands
+        .lock()
+        .unwrap()
+        .drain("node-7", grpc_device::service::now_ms());
+    let kinds: Vec<CommandKind> = delivered.iter().map(|c| c.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            CommandKind::SetInterval(30),
+            CommandKind::ReadSensor(2),
+            CommandKind::Ping
+        ]
+    );
+    assert!(delivered.windows(2).all(|w| w[0].id < w[1].id));
+    let (_, node7) = get(&api.app, "/devices/node-7").await;
+    assert_eq!(node7["pending_commands"], 0);
+}
+
+#[tokio::test]
+async fn bad_commands_have_typed_errors() {
+    let api = api();
+    let uri = "/devices/node-7/commands";
+    let cases = [
+        (
+            post_json(
+                &api.app,
+                "/devices/node-404/commands",
+                r#"{"command":"ping"}"#,
+            )
+            .await,
+            StatusCode::NOT_FOUND,
+            "device_not_found",
+        ),
+        (
+            post_json(&api.app, uri, r#"{"command":"#).await,
+            StatusCode::BAD_REQUEST,
+            "bad_request",
+        ),
+        (
+            post_json(&api.app, uri, r#"{"command":"format"}"#).await,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "invalid_command",
+        ),
+        (
+            post_json(&api.app, uri, r#"{"command":"set_interval","seconds":0}"#).await,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "invalid_command",
+        ),
+        (
+            post_json(&api.app, uri, r#"{"command":"read_sensor","channel":8}"#).await,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "invalid_command",
+        ),
+        (
+            post_json(&api.app, uri, r#"{"command":"ping","ttl_ms":0}"#).await,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "invalid_command",
+        ),
+        (
+            send(
+                &api.app,
+                Request::post(uri)
+                    .body(Body::from(r#"{"command":"ping"}"#))
+                    .unwrap(),
+            )
+            .await,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "unsupported_media_type",
+        ),
+        (
+            get(&api.app, "/sensors").await,
+            StatusCode::NOT_FOUND,
+            "no_route",
+        ),
+    ];
+    for (i, (response, status, code)) in cases.iter().enumerate() {
+        assert_eq!(error(response), (*status, *code), "case {}", i);
+    }
+    assert_eq!(
+        get(&api.app, "/sensors").await.1["error"]["message"],
+        "no route for /sensors"
+    );
+    // Nothing was queued by the rejected requests
+    assert_eq!(api.commands.lock().unwrap().pending("node-7"), 0);
+}
+
+#[tokio::test]
+async fn a_full_queue_answers_429() {
+    let api = api();
+    for _ in 0..8 {
+        let (status, _) = post_json(
+            &api.app,
+            "/devices/pump-1/commands",
+            r#"{"command":"ping"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+    }
+    let full = post_json(
+        &api.app,
+        "/devices/pump-1/commands",
+        r#"{"command":"ping"}"#,
+    )
+    .await;
+    assert_eq!(error(&full), (StatusCode::TOO_MANY_REQUESTS, "queue_full"));
+    // Other devices have their own queues
+    let (status, _) = post_json(
+        &api.app,
+        "/devices/node-3/commands",
+        r#"{"command":"ping"}"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+}
+
+#[tokio::test]
+async fn wrong_method_is_405() {
+    let api = api();
+    let request = Request::builder()
+        .method(Method::DELETE)
+        .uri("/devices/node-7")
+        .body(Body::empty())
+        .unwrap();
+    let (status, _) = send(&api.app, request).await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+}
@@ -0,0 +1,11 @@
+// SQLite persistence for telemetry history
+//
+// - `migrations`: versioned schema changes tracked in `PRAGMA user_version`
+// - `store`: `TelemetryStore` with batch inserts, range queries and
+//   time-bucket aggregation
+
+pub mod migrations;
+pub mod store;
+
+pub use migrations::{migrate, Migration, MigrationError, MIGRATIONS};
+pub use store::{Bucket, Reading, StoreError, StoredReading, TelemetryStore};
@@ -0,0 +1,205 @@
+use rusqlite::Connection;
+use sqlite_store::migrations::{schema_version, FLAG_OUT_OF_RANGE};
+use sqlite_store::{migrate, Reading, StoreError, TelemetryStore, MIGRATIONS};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+struct Noise(u64);
+
+impl Noise {
+    // Uniform in [-1, 1]
+    fn next(&mut self) -> f64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+    }
+}
+
+// A database file and its WAL side files
+fn remove_db(path: &Path) {
+    for suffix in ["", "-wal", "-shm"] {
+        let mut name = path.as_os_str().to_owned();
+        name.push(suffix);
+        let _ = fs::remove_file(PathBuf::from(name));
+    }
+}
+
+// Ten minutes of 1 Hz temperature and humidity from three nodes
+fn simulate(start_ms: u64) -> Vec<Reading> {
+    let mut noise = Noise(7);
+    let mut readings = Vec::new();
+    for s in 0..600u64 {
+        let t = start_ms + s * 1000;
+        for id in 0..3u16 {
+            let temp = 21.0 + id as f64 + (s as f64 / 120.0).sin() + 0.3 * noise.next();
+            let humidity = 48.0 - 2.0 * id as f64 + 1.5 * noise.next();
+            readings.push(Reading::new(id, "temperature", temp, t));
+            readings.push(Reading::new(id, "humidity", humidity, t));
+        }
+    }
+    readings
+}
+
+fn main() -> Result<(), StoreError> {
+    println!("=== SQLite Telemetry Store Examples ===\n");
+
+    let path = std::env::temp_dir().join("sqlite_store_demo.db");
+    remove_db(&path);
+    // A whole minute, so buckets line up with the simulated minutes
+    let start_ms = 1_700_000_040_000;
+    let data = simulate(start_ms);
+
+    // 1. Migrations
+    println!("1. Schema migrations:");
+    for m in MIGRATIONS {
+        println!("   v{}: {}", m.version, m.name);
+    }
+    let store = TelemetryStore::open(&path)?;
+    println!("   fresh file: applied {:?}", store.migrated());
+    drop(store);
+    let store = TelemetryStore::open(&path)?;
+    println!("   reopened: applied {:?}", store.migrated());
+    drop(store);
+
+    // A gateway still on v1, with a bad humidity value already stored
+    let mut old = Connection::open_in_memory()?;
+    migrate(&mut old, &MIGRATIONS[..1])?;
+    old.execute_batch(
+        "INSERT INTO readings (sensor_id, metric, value, timestamp_ms)
+         VALUES (0, 'humidity', 140.0, 1), (0, 'humidity', 45.0, 2);",
+    )?;
+    let upgraded = migrate(&mut old, MIGRATIONS)?;
+    let flagged: i64 = old.query_row(
+        "SELECT COUNT(*) FROM readings WHERE flags & ?1 != 0",
+        [FLAG_OUT_OF_RANGE],
+        |row| row.get(0),
+    )?;
+    println!(
+        "   v1 database: applied {:?}, now v{}",
+        upgraded,
+        schema_version(&old)?
+    );
+    println!("   {} reading(s) flagged out of range", flagged);
+    old.pragma_update(None, "user_version", 99)?;
+    match migrate(&mut old, MIGRATIONS) {
+        Ok(applied) => println!("   v99 accepted?! {:?}", applied),
+        Err(e) => println!("   v99 database: {}", e),
+    }
+
+    // 2. Inserts
+    println!("\n2. Inserts (file with WAL):");
+    let mut store = TelemetryStore::open(&path)?;
+    let (single, rest) = data.split_at(600);
+    let started = Instant::now();
+    for reading in single {
+        store.insert(reading)?;
+    }
+    let per_row = started.elapsed();
+    let started = Instant::now();
+    store.insert_batch(rest)?;
+    let batched = started.elapsed();
+    println!(
+        "   {:>5} rows one by one    {:>10.1?}  ({:.1?}/row)",
+        single.len(),
+        per_row,
+        per_row / single.len() as u32
+    );
+    println!(
+        "   {:>5} rows in one batch  {:>10.1?}  ({:.1?}/row)",
+        rest.len(),
+        batched,
+        batched / rest.len() as u32
+    );
+    println!("   {} rows stored", store.count()?);
+
+    // 3. A batch is one transaction
+    println!("\n3. Atomic batches:");
+    let before = store.count()?;
+    let mut bad: Vec<Reading> = (0..10)
+        .map(|i| Reading::new(9, "temperature", 20.0, start_ms + i))
+        .collect();
+    bad[6].value = f64::NAN;
+    match store.insert_batch(&bad) {
+        Ok(n) => println!("   {} rows accepted?!", n),
+        Err(e) => println!("   rejected: {}", e),
+    }
+    println!(
+        "   rows before {}, after {}; node 9 latest {:?}",
+        before,
+        store.count()?,
+        store.latest(9, "temperature")?.map(|r| r.id)
+    );
+
+    // 4. Range queries
+    println!("\n4. Range queries:");
+    let window = store.range("temperature", Some(1), start_ms + 60_000, start_ms + 65_000)?;
+    for r in &window {
+        println!(
+            "   #{:<5} node {} {} {:.2} at +{} s",
+            r.id,
+            r.reading.sensor_id,
+            r.reading.metric,
+            r.reading.value,
+            (r.reading.timestamp_ms - start_ms) / 1000
+        );
+    }
+    if let Some(r) = store.latest(2, "humidity")? {
+        println!(
+            "   latest humidity of node 2: {:.2} at +{} s",
+            r.reading.value,
+            (r.reading.timestamp_ms - start_ms) / 1000
+        );
+    }
+
+    // 5. Aggregation in SQL
+    println!("\n5. Per-minute temperature, all nodes:");
+    let buckets = store.aggregate("temperature", None, start_ms, start_ms + 300_000, 60_000)?;
+    println!("   minute  count    min    max   mean");
+    for b in &buckets {
+        println!(
+            "   {:>6} {:>6} {:>6.2} {:>6.2} {:>6.2}",
+            (b.start_ms - start_ms) / 60_000,
+            b.count,
+            b.min,
+            b.max,
+            b.mean
+        );
+    }
+    // The same numbers computed in Rust from the generated data
+    let first: Vec<f64> = data
+        .iter()
+        .filter(|r| r.metric == "temperature" && r.timestamp_ms < start_ms + 60_000)
+        .map(|r| r.value)
+        .collect();
+    let mean = first.iter().sum::<f64>() / first.len() as f64;
+    println!(
+        "   minute 0 in Rust: {} readings, mean {:.2}",
+        first.len(),
+        mean
+    );
+
+    // 6. Durability and retention
+    println!("\n6. Reopen and prune:");
+    let stored = store.count()?;
+    drop(store);
+    let store = TelemetryStore::open(&path)?;
+    println!(
+        "   {} rows before closing, {} after reopening",
+        stored,
+        store.count()?
+    );
+    let pruned = store.prune(start_ms + 300_000)?;
+    println!(
+        "   pruned {} rows older than +300 s, {} left",
+        pruned,
+        store.count()?
+    );
+    drop(store);
+    remove_db(&path);
+
+    println!("\n=== End of SQLite Telemetry Store Examples ===");
+    Ok(())
+}
@@ -0,0 +1,154 @@
+// Schema migrations, written in Rust
+//
+// The schema version lives in SQLite's `PRAGMA user_version`. Each
+// migration has a version, a name and a function that runs inside one
+// transaction together with the version bump: it either applies completely
+// or not at all. Migrations are only ever appended, never edited, because
+// deployed gateways have already run the old ones.
+
+use rusqlite::{Connection, Transaction};
+use std::fmt;
+
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub up: fn(&Transaction) -> rusqlite::Result<()>,
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create readings",
+        up: create_readings,
+    },
+    Migration {
+        version: 2,
+        name: "index by metric and time",
+        up: index_by_time,
+    },
+    Migration {
+        version: 3,
+        name: "add flags, mark impossible values",
+        up: add_flags,
+    },
+];
+
+pub const FLAG_OUT_OF_RANGE: u32 = 1;
+
+fn create_readings(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE readings (
+             id           INTEGER PRIMARY KEY,
+             sensor_id    INTEGER NOT NULL,
+             metric       TEXT    NOT NULL,
+             value        REAL    NOT NULL,
+             timestamp_ms INTEGER NOT NULL
+         );",
+    )
+}
+
+fn index_by_time(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch("CREATE INDEX readings_by_time ON readings (metric, timestamp_ms);")
+}
+
+// Schema change plus a data fix in the same transaction: something plain
+// .sql files cannot express as easily
+fn add_flags(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch("ALTER TABLE readings ADD COLUMN flags INTEGER NOT NULL DEFAULT 0;")?;
+    tx.execute(
+        "UPDATE readings SET flags = flags | ?1
+         WHERE (metric = 'humidity' AND (value < 0 OR value > 100))
+            OR (metric = 'temperature' AND (value < -60 OR value > 125))",
+        [FLAG_OUT_OF_RANGE],
+    )?;
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum MigrationError {
+    // The database was written by a newer build; refuse rather than guess
+    TooNew {
+        found: u32,
+        known: u32,
+    },
+    Failed {
+        version: u32,
+        name: &'static str,
+        source: rusqlite::Error,
+    },
+    Sqlite(rusqlite::Error),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::TooNew { found, known } => write!(
+                f,
+                "database schema v{} is newer than this build (v{})",
+                found, known
+            ),
+            MigrationError::Failed {
+                version,
+                name,
+                source,
+            } => write!(f, "migration {} ({}) failed: {}", version, name, source),
+            MigrationError::Sqlite(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MigrationError::Failed { source, .. } => Some(source),
+            MigrationError::Sqlite(e) => Some(e),
+            MigrationError::TooNew { .. } => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for MigrationError {
+    fn from(e: rusqlite::Error) -> MigrationError {
+        MigrationError::Sqlite(e)
+    }
+}
+
+pub fn schema_version(conn: &Connection) -> rusqlite::Result<u32> {
+    conn.pragma_query_value(None, "user_version", |row| row.get(0))
+}
+
+pub fn latest_version(migrations: &[Migration]) -> u32 {
+    migrations.last().map_or(0, |m| m.version)
+}
+
+// Bring the database up to the last of `migrations`; returns the versions
+// applied, empty when it was already current
+pub fn migrate(
+    conn: &mut Connection,
+    migrations: &[Migration],
+) -> Result<Vec<u32>, MigrationError> {
+    let current = schema_version(conn)?;
+    let known = latest_version(migrations);
+    if current > known {
+        return Err(MigrationError::TooNew {
+            found: current,
+            known,
+        });
+    }
+
+    let mut applied = Vec::new();
+    for m in migrations.iter().filter(|m| m.version > current) {
+        let failed = |source| MigrationError::Failed {
+            version: m.version,
+            name: m.name,
+            source,
+        };
+        let tx = conn.transaction()?;
+        (m.up)(&tx).map_err(failed)?;
+        tx.pragma_update(None, "user_version", m.version)
+            .map_err(failed)?;
+        tx.commit().map_err(failed)?;
+        applied.push(m.version);
+    }
+    Ok(applied)
+}
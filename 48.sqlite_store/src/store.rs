@@ -0,0 +1,286 @@
+// Telemetry history in one SQLite file
+//
+// Writes go through cached prepared statements; batches share one
+// transaction, which is what makes them fast (one fsync instead of one per
+// row) and atomic (a bad row rolls the whole batch back). Queries return
+// owned rows so no statement or borrow outlives the call.
+
+use crate::migrations::{self, MigrationError, MIGRATIONS};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::fmt;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reading {
+    pub sensor_id: u16,
+    pub metric: String,
+    pub value: f64,
+    pub timestamp_ms: u64,
+}
+
+impl Reading {
+    pub fn new(sensor_id: u16, metric: &str, value: f64, timestamp_ms: u64) -> Reading {
+        Reading {
+            sensor_id,
+            metric: metric.to_string(),
+            value,
+            timestamp_ms,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredReading {
+    pub id: i64,
+    pub reading: Reading,
+    pub flags: u32,
+}
+
+// One time bucket of an aggregation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bucket {
+    pub start_ms: u64,
+    pub count: u64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+#[derive(Debug)]
+pub enum StoreError {
+    Migration(MigrationError),
+    Sqlite(rusqlite::Error),
+    // SQLite would store NaN as NULL; reject it with a clear message instead
+    NotFinite { index: usize, value: f64 },
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Migration(e) => write!(f, "{}", e),
+            StoreError::Sqlite(e) => write!(f, "sqlite: {}", e),
+            StoreError::NotFinite { index, value } => {
+                write!(f, "reading {} has non-finite value {}", index, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StoreError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StoreError::Migration(e) => Some(e),
+            StoreError::Sqlite(e) => Some(e),
+            StoreError::NotFinite { .. } => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for StoreError {
+    fn from(e: rusqlite::Error) -> StoreError {
+        StoreError::Sqlite(e)
+    }
+}
+
+impl From<MigrationError> for StoreError {
+    fn from(e: MigrationError) -> StoreError {
+        StoreError::Migration(e)
+    }
+}
+
+const INSERT: &str =
+    "INSERT INTO readings (sensor_id, metric, value, timestamp_ms) VALUES (?1, ?2, ?3, ?4)";
+
+fn check(index: usize, reading: &Reading) -> Result<(), StoreError> {
+    if reading.value.is_finite() {
+        Ok(())
+    } else {
+        Err(StoreError::NotFinite {
+            index,
+            value: reading.value,
+        })
+    }
+}
+
+fn stored(row: &Row) -> rusqlite::Result<StoredReading> {
+    Ok(StoredReading {
+        id: row.get(0)?,
+        reading: Reading {
+            sensor_id: row.get(1)?,
+            metric: row.get(2)?,
+            value: row.get(3)?,
+            timestamp_ms: row.get::<_, i64>(4)? as u64,
+        },
+        flags: row.get(5)?,
+    })
+}
+
+pub struct TelemetryStore {
+    conn: Connection,
+    migrated: Vec<u32>,
+}
+
+impl TelemetryStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<TelemetryStore, StoreError> {
+        TelemetryStore::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<TelemetryStore, StoreError> {
+        TelemetryStore::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(mut conn: Connection) -> Result<TelemetryStore, StoreError> {
+        // WAL lets readers run while a batch is being written; NORMAL
+        // syncs at checkpoints, which is durable enough for telemetry
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        let migrated = migrations::migrate(&mut conn, MIGRATIONS)?;
+        Ok(TelemetryStore { conn, migrated })
+    }
+
+    // Versions applied while opening
+    pub fn migrated(&self) -> &[u32] {
+        &self.migrated
+    }
+
+    pub fn schema_version(&self) -> Result<u32, StoreError> {
+        Ok(migrations::schema_version(&self.conn)?)
+    }
+
+    // One row in its own implicit transaction
+    pub fn insert(&self, reading: &Reading) -> Result<i64, StoreError> {
+        check(0, reading)?;
+        let mut stmt = self.conn.prepare_cached(INSERT)?;
+        stmt.execute(params![
+            reading.sensor_id,
+            reading.metric,
+            reading.value,
+            reading.timestamp_ms as i64
+        ])?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    // All rows or none. The statement is prepared once and re-bound per row.
+    pub fn insert_batch(&mut self, readings: &[Reading]) -> Result<usize, StoreError> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(INSERT)?;
+            for (index, r) in readings.iter().enumerate() {
+                check(index, r)?;
+                stmt.execute(params![
+                    r.sensor_id,
+                    r.metric,
+                    r.value,
+                    r.timestamp_ms as i64
+                ])?;
+            }
+        }
+        // Returning early above drops `tx`, which rolls back
+        tx.commit()?;
+        Ok(readings.len())
+    }
+
+    pub fn count(&self) -> Result<u64, StoreError> {
+        let n: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM readings", [], |row| row.get(0))?;
+        Ok(n as u64)
+    }
+
+    // Readings of one metric in [from_ms, to_ms), oldest first;
+    // `sensor_id` None means every sensor
+    pub fn range(
+        &self,
+        metric: &str,
+        sensor_id: Option<u16>,
+        from_ms: u64,
+        to_ms: u64,
+    ) -> Result<Vec<StoredReading>, StoreError> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, sensor_id, metric, value, timestamp_ms, flags FROM readings
+             WHERE metric = ?1 AND timestamp_ms >= ?2 AND timestamp_ms < ?3
+               AND (?4 IS NULL OR sensor_id = ?4)
+             ORDER BY timestamp_ms, id",
+        )?;
+        let rows = stmt.query_map(
+            params![metric, from_ms as i64, to_ms as i64, sensor_id],
+            stored,
+        )?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    // Count, min, max and mean per `bucket_ms` window, computed by SQLite
+    pub fn aggregate(
+        &self,
+        metric: &str,
+        sensor_id: Option<u16>,
+        from_ms: u64,
+        to_ms: u64,
+        bucket_ms: u64,
+    ) -> Result<Vec<Bucket>, StoreError> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT (timestamp_ms / ?5) * ?5 AS bucket,
+                    COUNT(*), MIN(value), MAX(value), AVG(value)
+             FROM readings
+             WHERE metric = ?1 AND timestamp_ms >= ?2 AND timestamp_ms < ?3
+               AND (?4 IS NULL OR sensor_id = ?4)
+             GROUP BY bucket ORDER BY bucket",
+        )?;
+        let rows = stmt.query_map(
+            params![
+                metric,
+                from_ms as i64,
+                to_ms as i64,
+                sensor_id,
+                bucket_ms.max(1) as i64
+            ],
+            |row| {
+                Ok(Bucket {
+                    start_ms: row.get::<_, i64>(0)? as u64,
+                    count: row.get::<_, i64>(1)? as u64,
+                    min: row.get(2)?,
+                    max: row.get(3)?,
+                    mean: row.get(4)?,
+                })
+            },
+        )?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn latest(
+        &self,
+        sensor_id: u16,
+        metric: &str,
+    ) -> Result<Option<StoredReading>, StoreError> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, sensor_id, metric, value, timestamp_ms, flags FROM readings
+             WHERE sensor_id = ?1 AND metric = ?2
+             ORDER BY timestamp_ms DESC, id DESC LIMIT 1",
+        )?;
+        Ok(stmt
+            .query_row(params![sensor_id, metric], stored)
+            .optional()?)
+    }
+
+    pub fn flagged(&self, flag: u32) -> Result<u64, StoreError> {
+        let n: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM readings WHERE flags & ?1 != 0",
+            [flag],
+            |row| row.get(0),
+        )?;
+        Ok(n as u64)
+    }
+
+    // Retention: drop everything older than `before_ms`
+    pub fn prune(&self, before_ms: u64) -> Result<usize, StoreError> {
+        Ok(self.conn.execute(
+            "DELETE FROM readings WHERE timestamp_ms < ?1",
+            [before_ms as i64],
+        )?)
+    }
+
+    // The raw connection, for anything the helpers do not cover
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+}
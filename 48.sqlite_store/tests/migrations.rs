@@ -0,0 +1,109 @@
+use rusqlite::{Connection, Transaction};
+use sqlite_store::migrations::{latest_version, schema_version, FLAG_OUT_OF_RANGE};
+use sqlite_store::{migrate, Migration, MigrationError, MIGRATIONS};
+
+fn create_t(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch("CREATE TABLE t (x INTEGER);")
+}
+
+// Creates a table, then fails, so the table must not survive
+fn half_done(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch("CREATE TABLE u (y INTEGER); SELECT * FROM missing;")
+}
+
+fn tables(conn: &Connection) -> Vec<String> {
+    let mut stmt = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table' ORDER BY name")
+        .unwrap();
+    let names = stmt.query_map([], |row| row.get(0)).unwrap();
+    names.collect::<rusqlite::Result<_>>().unwrap()
+}
+
+#[test]
+fn a_fresh_database_gets_every_migration_once() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    assert_eq!(schema_version(&conn).unwrap(), 0);
+    assert_eq!(migrate(&mut conn, MIGRATIONS).unwrap(), [1, 2, 3]);
+    assert_eq!(schema_version(&conn).unwrap(), latest_version(MIGRATIONS));
+    assert!(migrate(&mut conn, MIGRATIONS).unwrap().is_empty());
+    assert_eq!(tables(&conn), ["readings"]);
+}
+
+#[test]
+fn versions_only_ever_increase() {
+    let versions: Vec<u32> = MIGRATIONS.iter().map(|m| m.version).collect();
+    assert!(versions.windows(2).all(|w| w[0] < w[1]), "{:?}", versions);
+    assert_eq!(latest_version(&[]), 0);
+}
+
+#[test]
+fn the_v3_data_migration_flags_impossible_values() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    assert_eq!(migrate(&mut conn, &MIGRATIONS[..1]).unwrap(), [1]);
+    conn.execute_batch(
+        "INSERT INTO readings (sensor_id, metric, value, timestamp_ms) VALUES
+             (0, 'humidity', 140.0, 1), (0, 'humidity', 45.0, 2),
+             (0, 'humidity', -1.0, 3), (0, 'temperature', 130.0, 4),
+             (0, 'temperature', -61.0, 5), (0, 'temperature', 125.0, 6);",
+    )
+    .unwrap();
+    assert_eq!(migrate(&mut conn, MIGRATIONS).unwrap(), [2, 3]);
+    let flagged: Vec<i64> = {
+        let mut stmt = conn
+            .prepare("SELECT timestamp_ms FROM readings WHERE flags & ?1 != 0 ORDER BY 1")
+            .unwrap();
+        let rows = stmt
+            .query_map([FLAG_OUT_OF_RANGE], |row| row.get(0))
+            .unwrap();
+        rows.collect::<rusqlite::Result<_>>().unwrap()
+    };
+    assert_eq!(flagged, [1, 3, 4, 5]);
+}
+
+#[test]
+fn a_newer_database_is_refused() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    migrate(&mut conn, MIGRATIONS).unwrap();
+    conn.pragma_update(None, "user_version", 99).unwrap();
+    match migrate(&mut conn, MIGRATIONS) {
+        Err(MigrationError::TooNew {
+            found: 99,
+            known: 3,
+        }) => {}
+        other => panic!("expected TooNew, got {:?}", other),
+    }
+    assert_eq!(schema_version(&conn).unwrap(), 99);
+}
+
+#[test]
+fn a_failed_migration_rolls_back_and_stops() {
+    let steps = [
+        Migration {
+            version: 1,
+            name: "create t",
+            up: create_t,
+        },
+        Migration {
+            version: 2,
+            name: "half done",
+            up: half_done,
+        },
+        Migration {
+            version: 3,
+            name: "never reached",
+            up: create_t,
+        },
+    ];
+    let mut conn = Connection::open_in_memory().unwrap();
+    match migrate(&mut conn, &steps) {
+        Err(MigrationError::Failed {
+            version: 2,
+            name: "half done",
+            ..
+        }) => {}
+        other => panic!("expected version 2 to fail, got {:?}", other),
+    }
+    // Version 1 committed on its own; nothing of version 2 is left
+    assert_eq!(schema_version(&conn).unwrap(), 1);
+    assert_eq!(tables(&conn), ["t"]);
+}
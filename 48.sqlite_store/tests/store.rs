@@ -0,0 +1,216 @@
+use sqlite_store::{Reading, StoreError, TelemetryStore};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// A whole minute, so buckets line up with the simulated minutes
+const START_MS: u64 = 1_700_000_040_000;
+
+// The database file and its WAL side files, removed when dropped
+struct TempDb(PathBuf);
+
+impl TempDb {
+    fn new(name: &str) -> TempDb {
+        let path =
+            std::env::temp_dir().join(format!("sqlite_store_{}_{}.db", name, std::process::id()));
+        remove_db(&path);
+        TempDb(path)
+    }
+}
+
+impl Drop for TempDb {
+    fn drop(&mut self) {
+        remove_db(&self.0);
+    }
+}
+
+fn remove_db(path: &Path) {
+    for suffix in ["", "-wal", "-shm"] {
+        let mut name = path.as_os_str().to_owned();
+        name.push(suffix);
+        let _ = fs::remove_file(PathBuf::from(name));
+    }
+}
+
+// Ten minutes of 1 Hz temperature and humidity from three nodes
+fn simulate() -> Vec<Reading> {
+    let mut state = 7u64;
+    let mut noise = move || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+    };
+    let mut readings = Vec::new();
+    for s in 0..600u64 {
+        let t = START_MS + s * 1000;
+        for id in 0..3u16 {
+            let temp = 21.0 + id as f64 + (s as f64 / 120.0).sin() + 0.3 * noise();
+            let humidity = 48.0 - 2.0 * id as f64 + 1.5 * noise();
+            readings.push(Reading::new(id, "temperature", temp, t));
+            readings.push(Reading::new(id, "humidity", humidity, t));
+        }
+    }
+    readings
+}
+
+fn filled() -> (TelemetryStore, Vec<Reading>) {
+    let mut store = TelemetryStore::open_in_memory().unwrap();
+    let data = simulate();
+    assert_eq!(store.insert_batch(&data).unwrap(), data.len());
+    (store, data)
+}
+
+#[test]
+fn single_and_batched_inserts_store_everything() {
+    let mut store = TelemetryStore::open_in_memory().unwrap();
+    assert_eq!(store.migrated(), [1, 2, 3]);
+    let data = simulate();
+    let (single, rest) = data.split_at(600);
+    let ids: Vec<i64> = single.iter().map(|r| store.insert(r).unwrap()).collect();
+    assert_eq!(ids, (1..=600).collect::<Vec<i64>>());
+    store.insert_batch(rest).unwrap();
+    assert_eq!(store.count().unwrap(), data.len() as u64);
+    assert_eq!(store.insert_batch(&[]).unwrap(), 0);
+}
+
+#[test]
+fn a_bad_row_rolls_the_whole_batch_back() {
+    let (mut store, _) = filled();
+    let before = store.count().unwrap();
+    let mut bad: Vec<Reading> = (0..10)
+        .map(|i| Reading::new(9, "temperature", 20.0, START_MS + i))
+        .collect();
+    bad[6].value = f64::NAN;
+    match store.insert_batch(&bad) {
+        Err(StoreError::NotFinite { index: 6, value }) => assert!(value.is_nan()),
+        other => panic!("expected NotFinite at 6, got {:?}", other),
+    }
+    assert_eq!(store.count().unwrap(), before);
+    assert!(store.latest(9, "temperature").unwrap().is_none());
+
+    assert!(matches!(
+        store.insert(&Reading::new(9, "temperature", f64::INFINITY, START_MS)),
+        Err(StoreError::NotFinite { index: 0, .. })
+    ));
+    assert_eq!(store.count().unwrap(), before);
+}
+
+#[test]
+fn range_is_half_open_and_filters_by_sensor() {
+    let (store, data) = filled();
+    let window = store
+        .range("temperature", Some(1), START_MS + 60_000, START_MS + 65_000)
+        .unwrap();
+    let times: Vec<u64> = window
+        .iter()
+        .map(|r| r.reading.timestamp_ms - START_MS)
+        .collect();
+    assert_eq!(times, [60_000, 61_000, 62_000, 63_000, 64_000]);
+    assert!(window
+        .iter()
+        .all(|r| r.reading.sensor_id == 1 && r.flags == 0));
+
+    let all = store
+        .range("temperature", None, START_MS + 60_000, START_MS + 65_000)
+        .unwrap();
+    assert_eq!(all.len(), 15);
+    let expected: Vec<&Reading> = data
+        .iter()
+        .filter(|r| {
+            r.metric == "temperature"
+                && (START_MS + 60_000..START_MS + 65_000).contains(&r.timestamp_ms)
+        })
+        .collect();
+    let got: Vec<&Reading> = all.iter().map(|r| &r.reading).collect();
+    assert_eq!(got, expected);
+    assert!(store
+        .range("pressure", None, 0, u64::MAX >> 1)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn latest_is_the_newest_reading() {
+    let (store, data) = filled();
+    let expected = data
+        .iter()
+        .rev()
+        .find(|r| r.sensor_id == 2 && r.metric == "humidity")
+        .cloned();
+    let latest = store.latest(2, "humidity").unwrap();
+    assert_eq!(latest.map(|r| r.reading), expected);
+    assert!(store.latest(7, "humidity").unwrap().is_none());
+}
+
+#[test]
+fn aggregates_match_a_computation_in_rust() {
+    let (store, data) = filled();
+    let buckets = store
+        .aggregate("temperature", None, START_MS, START_MS + 300_000, 60_000)
+        .unwrap();
+    assert_eq!(buckets.len(), 5);
+    for (minute, bucket) in buckets.iter().enumerate() {
+        let from = START_MS + minute as u64 * 60_000;
+        let values: Vec<f64> = data
+            .iter()
+            .filter(|r| r.metric == "temperature")
+            .filter(|r| (from..from + 60_000).contains(&r.timestamp_ms))
+            .map(|r| r.value)
+            .collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        assert_eq!(bucket.start_ms, from);
+        assert_eq!(bucket.count, 180);
+        assert_eq!((bucket.min, bucket.max), (min, max));
+        assert!((bucket.mean - mean).abs() < 1e-9, "minute {}", minute);
+    }
+    // Buckets are aligned to multiples of `bucket_ms`, not to `from_ms`
+    let node0 = store
+        .aggregate("humidity", Some(0), START_MS, START_MS + 600_000, 600_000)
+        .unwrap();
+    let starts: Vec<u64> = node0.iter().map(|b| b.start_ms).collect();
+    assert_eq!(
+        starts,
+        [
+            START_MS / 600_000 * 600_000,
+            (START_MS / 600_000 + 1) * 600_000
+        ]
+    );
+    assert_eq!(node0.iter().map(|b| b.count).sum::<u64>(), 600);
+}
+
+#[test]
+fn rows_survive_a_reopen_and_prune_drops_old_ones() {
+    let db = TempDb::new("reopen");
+    let data = simulate();
+    {
+        let mut store = TelemetryStore::open(&db.0).unwrap();
+        assert_eq!(store.migrated(), [1, 2, 3]);
+        store.insert_batch(&data).unwrap();
+    }
+    let store = TelemetryStore::open(&db.0).unwrap();
+    assert!(store.migrated().is_empty());
+    assert_eq!(store.schema_version().unwrap(), 3);
+    assert_eq!(store.count().unwrap(), data.len() as u64);
+
+    let pruned = store.prune(START_MS + 300_000).unwrap();
+    assert_eq!(pruned * 2, data.len());
+    assert!(store
+        .range("temperature", None, 0, START_MS + 300_000)
+        .unwrap()
+        .is_empty());
+    assert_eq!(store.prune(START_MS + 300_000).unwrap(), 0);
+}
+
+#[test]
+fn flagged_counts_rows_with_a_flag_set() {
+    let (store, _) = filled();
+    assert_eq!(store.flagged(1).unwrap(), 0);
+    store
+        .connection()
+        .execute("UPDATE readings SET flags = 1 WHERE sensor_id = 2", [])
+        .unwrap();
+    assert_eq!(store.flagged(1).unwrap(), 1200);
+    assert_eq!(store.flagged(2).unwrap(), 0);
+}
@@ -0,0 +1,120 @@
+// Side-by-side benchmarks of the storage backends
+//
+//   cargo bench                         # both backends
+//   cargo bench -- append               # one group
+//   cargo bench --no-default-features --features redb
+//
+// Every backend runs the same code through `Box<dyn Storage>`, on a file in
+// the temp directory, so the numbers include real fsyncs.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use std::fs;
+use std::hint::black_box;
+use std::path::PathBuf;
+use storage::{open, Backend, Reading, Storage};
+
+const START_MS: u64 = 1_700_000_040_000;
+
+// Three nodes, two metrics, 1 Hz
+fn simulate(seconds: u64) -> Vec<Reading> {
+    let mut readings = Vec::new();
+    for s in 0..seconds {
+        let t = START_MS + s * 1000;
+        for id in 0..3u16 {
+            let phase = s as f64 / 60.0 + id as f64;
+            readings.push(Reading::new(id, "temperature", 21.0 + phase.sin(), t));
+            readings.push(Reading::new(id, "humidity", 48.0 + phase.cos(), t));
+        }
+    }
+    readings
+}
+
+fn fresh(backend: Backend) -> Box<dyn Storage> {
+    let path = std::env::temp_dir().join(format!("storage_bench.{}", backend));
+    for suffix in ["", "-wal", "-shm"] {
+        let mut name = path.as_os_str().to_owned();
+        name.push(suffix);
+        let _ = fs::remove_file(PathBuf::from(name));
+    }
+    open(backend, &path).expect("backend opens")
+}
+
+fn backends() -> impl Iterator<Item = Backend> {
+    Backend::ALL.into_iter().filter(|b| b.available())
+}
+
+// One transaction of 100 readings into a store that already holds an hour
+fn append(c: &mut Criterion) {
+    let history = simulate(3600);
+    let batch = simulate(17);
+    let mut group = c.benchmark_group("append_100");
+    for backend in backends() {
+        let mut store = fresh(backend);
+        store.append(&history).unwrap();
+        group.bench_function(BenchmarkId::from_parameter(backend), |b| {
+            b.iter(|| store.append(black_box(&batch[..100])).unwrap())
+        });
+    }
+    group.finish();
+}
+
+// Ten minutes of one sensor out of an hour of data
+fn range(c: &mut Criterion) {
+    let history = simulate(3600);
+    let mut group = c.benchmark_group("range_10min");
+    for backend in backends() {
+        let mut store = fresh(backend);
+        store.append(&history).unwrap();
+        group.bench_function(BenchmarkId::from_parameter(backend), |b| {
+            b.iter(|| {
+                store
+                    .range("temperature", Some(1), START_MS, START_MS + 600_000)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+// Per-minute buckets over the whole hour: SQL GROUP BY against the trait's
+// default implementation on top of `range`
+fn aggregate(c: &mut Criterion) {
+    let history = simulate(3600);
+    let mut group = c.benchmark_group("aggregate_1h");
+    for backend in backends() {
+        let mut store = fresh(backend);
+        store.append(&history).unwrap();
+        group.bench_function(BenchmarkId::from_parameter(backend), |b| {
+            b.iter(|| {
+                store
+                    .aggregate("temperature", None, START_MS, START_MS + 3_600_000, 60_000)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+// Dropping the older half; every iteration needs a freshly filled store
+fn prune(c: &mut Criterion) {
+    let history = simulate(600);
+    let mut group = c.benchmark_group("prune_half");
+    group.sample_size(10);
+    for backend in backends() {
+        group.bench_function(BenchmarkId::from_parameter(backend), |b| {
+            b.iter_batched(
+                || {
+                    let mut store = fresh(backend);
+                    store.append(&history).unwrap();
+                    store
+                },
+                |mut store| store.prune(START_MS + 300_000).unwrap(),
+                BatchSize::PerIteration,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, append, range, aggregate, prune);
+criterion_main!(benches);
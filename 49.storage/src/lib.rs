@@ -0,0 +1,240 @@
+// Pluggable telemetry storage
+//
+// The gateway only needs a handful of operations on its history: append a
+// batch, read a time range, aggregate, count and prune. `Storage` names
+// exactly those, and each backend lives behind its own cargo feature:
+//
+// - `sqlite`: the rusqlite store from 48.sqlite_store (C library, SQL)
+// - `redb`: a pure-Rust embedded B-tree database, no C toolchain needed
+// - `memory`: a Vec, always compiled in; for tests and trial runs, since
+//   nothing survives a restart
+//
+// `open` picks a backend at run time from configuration, so one binary
+// can carry several and the choice is a config value, not a rebuild.
+
+pub mod memory_backend;
+#[cfg(feature = "redb")]
+pub mod redb_backend;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_backend;
+
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+pub use memory_backend::MemoryStorage;
+#[cfg(feature = "redb")]
+pub use redb_backend::RedbStorage;
+#[cfg(feature = "sqlite")]
+pub use sqlite_backend::SqliteStorage;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reading {
+    pub sensor_id: u16,
+    pub metric: String,
+    pub value: f64,
+    pub timestamp_ms: u64,
+}
+
+impl Reading {
+    pub fn new(sensor_id: u16, metric: &str, value: f64, timestamp_ms: u64) -> Reading {
+        Reading {
+            sensor_id,
+            metric: metric.to_string(),
+            value,
+            timestamp_ms,
+        }
+    }
+}
+
+// One `bucket_ms` window of an aggregation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bucket {
+    pub start_ms: u64,
+    pub count: u64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+#[derive(Debug)]
+pub enum StorageError {
+    UnknownBackend(String),
+    // Known, but this binary was built without its feature
+    Disabled(Backend),
+    NotFinite {
+        index: usize,
+        value: f64,
+    },
+    #[cfg(feature = "sqlite")]
+    Sqlite(sqlite_store::StoreError),
+    // Boxed: redb's error is large and would bloat every Result
+    #[cfg(feature = "redb")]
+    Redb(Box<redb::Error>),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::UnknownBackend(name) => write!(
+                f,
+                "unknown storage backend '{}', expected one of: {}",
+                name,
+                Backend::ALL.map(Backend::name).join(", ")
+            ),
+            StorageError::Disabled(backend) => write!(
+                f,
+                "storage backend '{}' is not compiled in (enable the '{}' feature)",
+                backend, backend
+            ),
+            StorageError::NotFinite { index, value } => {
+                write!(f, "reading {} has non-finite value {}", index, value)
+            }
+            #[cfg(feature = "sqlite")]
+            StorageError::Sqlite(e) => write!(f, "sqlite: {}", e),
+            #[cfg(feature = "redb")]
+            StorageError::Redb(e) => write!(f, "redb: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Sqlite,
+    Redb,
+    Memory,
+}
+
+impl Backend {
+    pub const ALL: [Backend; 3] = [Backend::Sqlite, Backend::Redb, Backend::Memory];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Sqlite => "sqlite",
+            Backend::Redb => "redb",
+            Backend::Memory => "memory",
+        }
+    }
+
+    // Whether this binary was built with the backend's feature
+    pub fn available(self) -> bool {
+        match self {
+            Backend::Sqlite => cfg!(feature = "sqlite"),
+            Backend::Redb => cfg!(feature = "redb"),
+            Backend::Memory => true,
+        }
+    }
+
+    // Whether readings outlive the process
+    pub fn persistent(self) -> bool {
+        self != Backend::Memory
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl FromStr for Backend {
+    type Err = StorageError;
+
+    fn from_str(s: &str) -> Result<Backend, StorageError> {
+        Backend::ALL
+            .into_iter()
+            .find(|b| b.name() == s)
+            .ok_or_else(|| StorageError::UnknownBackend(s.to_string()))
+    }
+}
+
+// What the gateway needs from a history store. `Send` so a store can be
+// moved to a writer thread.
+pub trait Storage: Send {
+    fn backend(&self) -> Backend;
+
+    // All or nothing; returns the number of readings written
+    fn append(&mut self, readings: &[Reading]) -> Result<usize, StorageError>;
+
+    // One metric in [from_ms, to_ms), oldest first; `sensor_id` None means
+    // every sensor
+    fn range(
+        &self,
+        metric: &str,
+        sensor_id: Option<u16>,
+        from_ms: u64,
+        to_ms: u64,
+    ) -> Result<Vec<Reading>, StorageError>;
+
+    fn count(&self) -> Result<u64, StorageError>;
+
+    // Drop everything older than `before_ms`; returns how many went
+    fn prune(&mut self, before_ms: u64) -> Result<u64, StorageError>;
+
+    // Works for any backend on top of `range`; backends that can do better
+    // (SQLite's GROUP BY) override it
+    fn aggregate(
+        &self,
+        metric: &str,
+        sensor_id: Option<u16>,
+        from_ms: u64,
+        to_ms: u64,
+        bucket_ms: u64,
+    ) -> Result<Vec<Bucket>, StorageError> {
+        let bucket_ms = bucket_ms.max(1);
+        let mut buckets: Vec<Bucket> = Vec::new();
+        for r in self.range(metric, sensor_id, from_ms, to_ms)? {
+            let start_ms = r.timestamp_ms / bucket_ms * bucket_ms;
+            match buckets.last_mut() {
+                Some(b) if b.start_ms == start_ms => {
+                    b.count += 1;
+                    b.min = b.min.min(r.value);
+                    b.max = b.max.max(r.value);
+                    // Running sum for now, divided below
+                    b.mean += r.value;
+                }
+                _ => buckets.push(Bucket {
+                    start_ms,
+                    count: 1,
+                    min: r.value,
+                    max: r.value,
+                    mean: r.value,
+                }),
+            }
+        }
+        for b in &mut buckets {
+            b.mean /= b.count as f64;
+        }
+        Ok(buckets)
+    }
+}
+
+// Shared by the backends: NaN and infinities are rejected before writing
+pub(crate) fn check_finite(readings: &[Reading]) -> Result<(), StorageError> {
+    match readings.iter().position(|r| !r.value.is_finite()) {
+        Some(index) => Err(StorageError::NotFinite {
+            index,
+            value: readings[index].value,
+        }),
+        None => Ok(()),
+    }
+}
+
+// Open (or create) a store at `path` with the chosen backend; `memory`
+// ignores the path
+pub fn open(backend: Backend, path: &Path) -> Result<Box<dyn Storage>, StorageError> {
+    match backend {
+        Backend::Memory => Ok(Box::new(MemoryStorage::new())),
+        #[cfg(feature = "sqlite")]
+        Backend::Sqlite => Ok(Box::new(SqliteStorage::open(path)?)),
+        #[cfg(feature = "redb")]
+        Backend::Redb => Ok(Box::new(RedbStorage::open(path)?)),
+        #[allow(unreachable_patterns)]
+        disabled => {
+            let _ = path;
+            Err(StorageError::Disabled(disabled))
+        }
+    }
+}
@@ -0,0 +1,65 @@
+// `Storage` in a Vec
+//
+// Nothing is written anywhere and nothing survives a restart. It needs no
+// feature and no file, which makes it the stand-in for tests that drive
+// the gateway without a disk, and a reference the real backends can be
+// checked against. Readings are kept in arrival order; `range` sorts its
+// answer by timestamp.
+
+use crate::{check_finite, Backend, Reading, Storage, StorageError};
+
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    readings: Vec<Reading>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> MemoryStorage {
+        MemoryStorage::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn backend(&self) -> Backend {
+        Backend::Memory
+    }
+
+    // Checked before anything is pushed, so a bad batch changes nothing
+    fn append(&mut self, readings: &[Reading]) -> Result<usize, StorageError> {
+        check_finite(readings)?;
+        self.readings.extend_from_slice(readings);
+        Ok(readings.len())
+    }
+
+    fn range(
+        &self,
+        metric: &str,
+        sensor_id: Option<u16>,
+        from_ms: u64,
+        to_ms: u64,
+    ) -> Result<Vec<Reading>, StorageError> {
+        let mut found: Vec<Reading> = self
+            .readings
+            .iter()
+            .filter(|r| {
+                r.metric == metric
+                    && sensor_id.is_none_or(|id| r.sensor_id == id)
+                    && (from_ms..to_ms).contains(&r.timestamp_ms)
+            })
+            .cloned()
+            .collect();
+        // Stable, so equal timestamps stay in arrival order
+        found.sort_by_key(|r| r.timestamp_ms);
+        Ok(found)
+    }
+
+    fn count(&self) -> Result<u64, StorageError> {
+        Ok(self.readings.len() as u64)
+    }
+
+    fn prune(&mut self, before_ms: u64) -> Result<u64, StorageError> {
+        let before = self.readings.len();
+        self.readings.retain(|r| r.timestamp_ms >= before_ms);
+        Ok((before - self.readings.len()) as u64)
+    }
+}
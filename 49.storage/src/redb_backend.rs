@@ -0,0 +1,126 @@
+// `Storage` on redb, a pure-Rust embedded key-value store
+//
+// redb has no SQL, so the layout is the query plan. Readings are keyed by
+// (metric, timestamp, sequence): one metric's history is a contiguous,
+// time-ordered run of keys, and a time range is a single B-tree range
+// scan. The sequence number keeps two readings with the same metric and
+// timestamp (from different sensors) from overwriting each other.
+//
+//   readings: (metric, timestamp_ms, seq) -> (sensor_id, value)
+//   meta:     "next_seq" -> u64
+
+use crate::{check_finite, Backend, Reading, Storage, StorageError};
+use redb::{Database, ReadableTable, ReadableTableMetadata, TableDefinition};
+use std::path::Path;
+
+const READINGS: TableDefinition<(&str, u64, u64), (u16, f64)> = TableDefinition::new("readings");
+const META: TableDefinition<&str, u64> = TableDefinition::new("meta");
+const NEXT_SEQ: &str = "next_seq";
+
+// redb has a separate error type per operation; all of them convert into
+// the umbrella `redb::Error`
+fn redb<E: Into<redb::Error>>(e: E) -> StorageError {
+    StorageError::Redb(Box::new(e.into()))
+}
+
+pub struct RedbStorage {
+    db: Database,
+}
+
+impl RedbStorage {
+    pub fn open(path: &Path) -> Result<RedbStorage, StorageError> {
+        RedbStorage::with_database(Database::create(path).map_err(redb)?)
+    }
+
+    pub fn in_memory() -> Result<RedbStorage, StorageError> {
+        let db = Database::builder()
+            .create_with_backend(redb::backends::InMemoryBackend::new())
+            .map_err(redb)?;
+        RedbStorage::with_database(db)
+    }
+
+    // Read transactions fail on tables that were never created, so create
+    // both up front
+    fn with_database(db: Database) -> Result<RedbStorage, StorageError> {
+        let tx = db.begin_write().map_err(redb)?;
+        tx.open_table(READINGS).map_err(redb)?;
+        tx.open_table(META).map_err(redb)?;
+        tx.commit().map_err(redb)?;
+        Ok(RedbStorage { db })
+    }
+}
+
+impl Storage for RedbStorage {
+    fn backend(&self) -> Backend {
+        Backend::Redb
+    }
+
+    // One write transaction: committed together or not at all
+    fn append(&mut self, readings: &[Reading]) -> Result<usize, StorageError> {
+        check_finite(readings)?;
+        let tx = self.db.begin_write().map_err(redb)?;
+        {
+            let mut meta = tx.open_table(META).map_err(redb)?;
+            let mut seq = meta.get(NEXT_SEQ).map_err(redb)?.map_or(0, |v| v.value());
+            let mut table = tx.open_table(READINGS).map_err(redb)?;
+            for r in readings {
+                table
+                    .insert(
+                        (r.metric.as_str(), r.timestamp_ms, seq),
+                        (r.sensor_id, r.value),
+                    )
+                    .map_err(redb)?;
+                seq += 1;
+            }
+            meta.insert(NEXT_SEQ, seq).map_err(redb)?;
+        }
+        tx.commit().map_err(redb)?;
+        Ok(readings.len())
+    }
+
+    fn range(
+        &self,
+        metric: &str,
+        sensor_id: Option<u16>,
+        from_ms: u64,
+        to_ms: u64,
+    ) -> Result<Vec<Reading>, StorageError> {
+        let tx = self.db.begin_read().map_err(redb)?;
+        let table = tx.open_table(READINGS).map_err(redb)?;
+        let mut out = Vec::new();
+        for entry in table
+            .range((metric, from_ms, 0)..(metric, to_ms, 0))
+            .map_err(redb)?
+        {
+            let (key, value) = entry.map_err(redb)?;
+            let (_, timestamp_ms, _) = key.value();
+            let (sensor, value) = value.value();
+            if sensor_id.is_none_or(|id| id == sensor) {
+                out.push(Reading::new(sensor, metric, value, timestamp_ms));
+            }
+        }
+        Ok(out)
+    }
+
+    fn count(&self) -> Result<u64, StorageError> {
+        let tx = self.db.begin_read().map_err(redb)?;
+        let table = tx.open_table(READINGS).map_err(redb)?;
+        table.len().map_err(redb)
+    }
+
+    // Keys are ordered by metric first, so old readings are spread over
+    // the whole table; `retain` walks it once
+    fn prune(&mut self, before_ms: u64) -> Result<u64, StorageError> {
+        let tx = self.db.begin_write().map_err(redb)?;
+        let removed = {
+            let mut table = tx.open_table(READINGS).map_err(redb)?;
+            let before = table.len().map_err(redb)?;
+            table
+                .retain(|(_, timestamp_ms, _), _| timestamp_ms >= before_ms)
+                .map_err(redb)?;
+            before - table.len().map_err(redb)?
+        };
+        tx.commit().map_err(redb)?;
+        Ok(removed)
+    }
+}
@@ -0,0 +1,98 @@
+// `Storage` on top of the rusqlite store from 48.sqlite_store
+//
+// A thin adapter: convert readings, forward calls, and let SQLite do the
+// aggregation instead of the trait's default.
+
+use crate::{check_finite, Backend, Bucket, Reading, Storage, StorageError};
+use sqlite_store::{StoreError, TelemetryStore};
+use std::path::Path;
+
+impl From<StoreError> for StorageError {
+    fn from(e: StoreError) -> StorageError {
+        StorageError::Sqlite(e)
+    }
+}
+
+pub struct SqliteStorage {
+    store: TelemetryStore,
+}
+
+impl SqliteStorage {
+    pub fn open(path: &Path) -> Result<SqliteStorage, StorageError> {
+        Ok(SqliteStorage {
+            store: TelemetryStore::open(path)?,
+        })
+    }
+
+    pub fn in_memory() -> Result<SqliteStorage, StorageError> {
+        Ok(SqliteStorage {
+            store: TelemetryStore::open_in_memory()?,
+        })
+    }
+}
+
+fn to_sqlite(r: &Reading) -> sqlite_store::Reading {
+    sqlite_store::Reading::new(r.sensor_id, &r.metric, r.value, r.timestamp_ms)
+}
+
+impl Storage for SqliteStorage {
+    fn backend(&self) -> Backend {
+        Backend::Sqlite
+    }
+
+    fn append(&mut self, readings: &[Reading]) -> Result<usize, StorageError> {
+        check_finite(readings)?;
+        let rows: Vec<sqlite_store::Reading> = readings.iter().map(to_sqlite).collect();
+        Ok(self.store.insert_batch(&rows)?)
+    }
+
+    fn range(
+        &self,
+        metric: &str,
+        sensor_id: Option<u16>,
+        from_ms: u64,
+        to_ms: u64,
+    ) -> Result<Vec<Reading>, StorageError> {
+        let rows = self.store.range(metric, sensor_id, from_ms, to_ms)?;
+        Ok(rows
+            .into_iter()
+            .map(|s| Reading {
+                sensor_id: s.reading.sensor_id,
+                metric: s.reading.metric,
+                value: s.reading.value,
+                timestamp_ms: s.reading.timestamp_ms,
+            })
+            .collect())
+    }
+
+    fn count(&self) -> Result<u64, StorageError> {
+        Ok(self.store.count()?)
+    }
+
+    fn prune(&mut self, before_ms: u64) -> Result<u64, StorageError> {
+        Ok(self.store.prune(before_ms)? as u64)
+    }
+
+    fn aggregate(
+        &self,
+        metric: &str,
+        sensor_id: Option<u16>,
+        from_ms: u64,
+        to_ms: u64,
+        bucket_ms: u64,
+    ) -> Result<Vec<Bucket>, StorageError> {
+        let buckets = self
+            .store
+            .aggregate(metric, sensor_id, from_ms, to_ms, bucket_ms)?;
+        Ok(buckets
+            .into_iter()
+            .map(|b| Bucket {
+                start_ms: b.start_ms,
+                count: b.count,
+                min: b.min,
+                max: b.max,
+                mean: b.mean,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,199 @@
+// The same checks against every backend compiled in; run with
+// `--no-default-features` (plus a feature) to cover other builds
+use std::fs;
+use std::path::PathBuf;
+use storage::{open, Backend, Bucket, Reading, StorageError};
+
+const START_MS: u64 = 1_700_000_040_000;
+
+// `seconds` of 1 Hz temperature and humidity from three nodes
+fn simulate(seconds: u64) -> Vec<Reading> {
+    let mut state = 7u64;
+    let mut noise = move || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+    };
+    let mut readings = Vec::new();
+    for s in 0..seconds {
+        let t = START_MS + s * 1000;
+        for id in 0..3u16 {
+            let temp = 21.0 + id as f64 + (s as f64 / 120.0).sin() + 0.3 * noise();
+            let humidity = 48.0 - 2.0 * id as f64 + 1.5 * noise();
+            readings.push(Reading::new(id, "temperature", temp, t));
+            readings.push(Reading::new(id, "humidity", humidity, t));
+        }
+    }
+    readings
+}
+
+// A file per backend and test, with SQLite's WAL files, removed when dropped
+struct TempDb(PathBuf);
+
+impl TempDb {
+    fn new(backend: Backend, test: &str) -> TempDb {
+        let path = std::env::temp_dir().join(format!(
+            "storage_{}_{}_{}",
+            test,
+            std::process::id(),
+            backend
+        ));
+        let db = TempDb(path);
+        db.remove();
+        db
+    }
+
+    fn remove(&self) {
+        for suffix in ["", "-wal", "-shm"] {
+            let mut name = self.0.as_os_str().to_owned();
+            name.push(suffix);
+            let _ = fs::remove_file(PathBuf::from(name));
+        }
+    }
+}
+
+impl Drop for TempDb {
+    fn drop(&mut self) {
+        self.remove();
+    }
+}
+
+fn available() -> impl Iterator<Item = Backend> {
+    Backend::ALL.into_iter().filter(|b| b.available())
+}
+
+fn assert_same_buckets(a: &[Bucket], b: &[Bucket], what: &str) {
+    assert_eq!(a.len(), b.len(), "{}", what);
+    for (x, y) in a.iter().zip(b) {
+        assert_eq!(
+            (x.start_ms, x.count, x.min, x.max),
+            (y.start_ms, y.count, y.min, y.max),
+            "{}",
+            what
+        );
+        assert!((x.mean - y.mean).abs() < 1e-9, "{}", what);
+    }
+}
+
+#[test]
+fn every_backend_answers_like_memory() {
+    let data = simulate(600);
+    let mut reference = open(Backend::Memory, &PathBuf::new()).unwrap();
+    reference.append(&data).unwrap();
+    for backend in available() {
+        let db = TempDb::new(backend, "answers");
+        let mut store = open(backend, &db.0).unwrap();
+        assert_eq!(store.backend(), backend);
+        assert_eq!(store.append(&data).unwrap(), data.len());
+        assert_eq!(store.count().unwrap(), data.len() as u64, "{}", backend);
+        for (metric, sensor, from, to) in [
+            ("temperature", Some(1), START_MS, START_MS + 60_000),
+            ("humidity", None, START_MS + 59_000, START_MS + 61_000),
+            ("humidity", Some(2), START_MS + 599_000, u64::MAX >> 1),
+            ("pressure", None, 0, u64::MAX >> 1),
+        ] {
+            assert_eq!(
+                store.range(metric, sensor, from, to).unwrap(),
+                reference.range(metric, sensor, from, to).unwrap(),
+                "{} {} {:?}",
+                backend,
+                metric,
+                sensor
+            );
+        }
+        // SQLite overrides `aggregate` with GROUP BY; the others use the
+        // trait's default, which the memory store stands for
+        let buckets = store
+            .aggregate("humidity", None, START_MS, START_MS + 300_000, 60_000)
+            .unwrap();
+        assert_eq!(buckets.len(), 5);
+        assert!(buckets.iter().all(|b| b.count == 180));
+        let expected = reference
+            .aggregate("humidity", None, START_MS, START_MS + 300_000, 60_000)
+            .unwrap();
+        assert_same_buckets(&buckets, &expected, backend.name());
+    }
+}
+
+#[test]
+fn a_bad_batch_changes_nothing() {
+    let mut bad = simulate(5);
+    bad[7].value = f64::INFINITY;
+    for backend in available() {
+        let db = TempDb::new(backend, "atomic");
+        let mut store = open(backend, &db.0).unwrap();
+        store.append(&simulate(2)).unwrap();
+        match store.append(&bad) {
+            Err(StorageError::NotFinite { index: 7, .. }) => {}
+            other => panic!("{}: expected NotFinite at 7, got {:?}", backend, other),
+        }
+        assert_eq!(store.count().unwrap(), 12, "{}", backend);
+    }
+}
+
+#[test]
+fn prune_drops_the_older_half() {
+    let data = simulate(600);
+    for backend in available() {
+        let db = TempDb::new(backend, "prune");
+        let mut store = open(backend, &db.0).unwrap();
+        store.append(&data).unwrap();
+        assert_eq!(
+            store.prune(START_MS + 300_000).unwrap(),
+            1800,
+            "{}",
+            backend
+        );
+        assert_eq!(store.count().unwrap(), 1800);
+        assert!(store
+            .range("temperature", None, 0, START_MS + 300_000)
+            .unwrap()
+            .is_empty());
+        assert_eq!(store.prune(START_MS + 300_000).unwrap(), 0);
+    }
+}
+
+#[test]
+fn persistent_backends_keep_readings_across_a_reopen() {
+    let data = simulate(60);
+    for backend in available() {
+        let db = TempDb::new(backend, "reopen");
+        open(backend, &db.0).unwrap().append(&data).unwrap();
+        let store = open(backend, &db.0).unwrap();
+        let expected = if backend.persistent() { data.len() } else { 0 };
+        assert_eq!(store.count().unwrap(), expected as u64, "{}", backend);
+    }
+}
+
+#[test]
+fn backends_are_chosen_by_name() {
+    for backend in Backend::ALL {
+        assert_eq!(backend.name().parse::<Backend>().unwrap(), backend);
+        assert_eq!(
+            format!("[{:<7}]", backend),
+            format!("[{:<7}]", backend.name())
+        );
+    }
+    match "rocksdb".parse::<Backend>() {
+        Err(e @ StorageError::UnknownBackend(_)) => assert_eq!(
+            e.to_string(),
+            "unknown storage backend 'rocksdb', expected one of: sqlite, redb, memory"
+        ),
+        other => panic!("expected UnknownBackend, got {:?}", other),
+    }
+    assert!(Backend::Memory.available());
+    assert_eq!(Backend::Sqlite.available(), cfg!(feature = "sqlite"));
+    assert_eq!(Backend::Redb.available(), cfg!(feature = "redb"));
+}
+
+#[cfg(not(feature = "redb"))]
+#[test]
+fn a_backend_left_out_of_the_build_is_an_error() {
+    let db = TempDb::new(Backend::Redb, "disabled");
+    match open(Backend::Redb, &db.0) {
+        Err(StorageError::Disabled(Backend::Redb)) => {}
+        Err(e) => panic!("expected Disabled, got {}", e),
+        Ok(_) => panic!("redb opened without its feature"),
+    }
+}
@@ -0,0 +1,177 @@
+// The gateway as a long-running D-Bus service
+//
+//   cargo run --bin service
+//   busctl --user call org.rustsys.Gateway /org/rustsys/Gateway org.rustsys.Gateway StatusJson
+//   busctl --user get-property org.rustsys.Gateway /org/rustsys/Gateway org.rustsys.Gateway ActiveAlerts
+//   busctl --user monitor org.rustsys.Gateway
+//
+// `--system` owns the name on the system bus instead, which needs the
+// policy in org.rustsys.Gateway.conf installed in /etc/dbus-1/system.d/.
+// logind and NetworkManager are read from the system bus whenever there is
+// one: a delay lock holds shutdown until the data log is flushed.
+
+use dbus::system::{
+    network_state_name, HostState, Login1ManagerProxy, NetworkManagerProxy, ShutdownDelay,
+};
+use dbus::{AlertPublisher, GatewayService, OBJECT_PATH, SERVICE_NAME};
+use gateway::{Config, Gateway};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use zbus::blocking::connection::Builder;
+use zbus::blocking::Connection;
+
+fn usage() -> ExitCode {
+    eprintln!("usage: service [--system] [--config <file.toml>]");
+    ExitCode::from(2)
+}
+
+// Stop the main loop when logind announces a shutdown
+fn watch_shutdown(system: Connection, stop: Arc<AtomicBool>) {
+    thread::spawn(move || {
+        let Ok(logind) = Login1ManagerProxy::new(&system) else {
+            return;
+        };
+        let Ok(signals) = logind.receive_prepare_for_shutdown() else {
+            return;
+        };
+        for signal in signals {
+            if signal.args().is_ok_and(|a| a.start) {
+                println!("service: system is shutting down");
+                stop.store(true, Ordering::Relaxed);
+                break;
+            }
+        }
+    });
+}
+
+// Log network changes; a real uplink would pause while offline
+fn watch_network(system: Connection) {
+    thread::spawn(move || {
+        let Ok(nm) = NetworkManagerProxy::new(&system) else {
+            return;
+        };
+        for change in nm.receive_state_changed() {
+            if let Ok(state) = change.get() {
+                println!("service: network {}", network_state_name(state));
+            }
+        }
+    });
+}
+
+fn main() -> ExitCode {
+    let mut system_bus = false;
+    let mut config_path: Option<PathBuf> = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--system" => system_bus = true,
+            "--config" => match args.next() {
+                Some(path) => config_path = Some(PathBuf::from(path)),
+                None => return usage(),
+            },
+            _ => return usage(),
+        }
+    }
+
+    let config = match Config::load(config_path.as_deref()) {
+        Ok((config, _)) => config,
+        Err(e) => {
+            eprintln!("service: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let (mut gw, _) = match Gateway::new(config.clone()) {
+        Ok(started) => started,
+        Err(e) => {
+            eprintln!("service: cannot start gateway: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let builder = if system_bus {
+        Builder::system()
+    } else {
+        Builder::session()
+    };
+    let conn = match builder
+        .and_then(|b| b.name(SERVICE_NAME))
+        .and_then(|b| b.serve_at(OBJECT_PATH, GatewayService::new(gw.status())))
+        .and_then(|b| b.build())
+    {
+        Ok(conn) => conn,
+        Err(e) => {
+            eprintln!("service: cannot own {}: {}", SERVICE_NAME, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let publisher = AlertPublisher::new(&conn).expect("interface was just served");
+    println!(
+        "service: {} on the {} bus",
+        SERVICE_NAME,
+        if system_bus { "system" } else { "session" }
+    );
+
+    let stop = Arc::new(AtomicBool::new(false));
+    {
+        let stop = Arc::clone(&stop);
+        if let Err(e) = ctrlc::set_handler(move || stop.store(true, Ordering::Relaxed)) {
+            eprintln!("service: cannot install Ctrl-C handler: {}", e);
+        }
+    }
+
+    // The host's services, if this machine has them
+    let mut delay = None;
+    match Connection::system() {
+        Ok(system) => {
+            println!("service: host {}", HostState::read(&system));
+            delay = Login1ManagerProxy::new(&system)
+                .and_then(|logind| ShutdownDelay::take(&logind, "rust-sys gateway"))
+                .map_err(|e| println!("service: no shutdown delay lock ({})", e))
+                .ok();
+            watch_shutdown(system.clone(), Arc::clone(&stop));
+            watch_network(system);
+        }
+        Err(e) => println!("service: no system bus ({})", e),
+    }
+
+    let interval = Duration::from_millis(config.gateway.poll_interval_ms);
+    let limit_ms = config.gateway.run_seconds * 1000;
+    while !stop.load(Ordering::Relaxed) && (limit_ms == 0 || gw.elapsed_ms() < limit_ms) {
+        match gw.tick() {
+            Ok(events) => {
+                for event in &events {
+                    println!("service: {}", event);
+                }
+                if let Err(e) = publisher.publish(&events) {
+                    eprintln!("service: cannot emit signals: {}", e);
+                }
+            }
+            Err(e) => {
+                eprintln!("service: data log failed: {}", e);
+                break;
+            }
+        }
+        thread::sleep(interval);
+    }
+
+    // Flush first, then let shutdown proceed
+    let status = match gw.shutdown() {
+        Ok(status) => status,
+        Err(e) => {
+            eprintln!("service: flush failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(delay) = delay {
+        delay.release();
+    }
+    println!(
+        "service: stopped after {} polls, {} alerts raised",
+        status.polls, status.alerts_raised
+    );
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,72 @@
+// A throwaway message bus
+//
+// `dbus-daemon --session` on a socket of our own: the same daemon and
+// protocol as the real buses, but nothing else is connected and any name
+// may be owned, so stand-ins for system services can claim their real
+// names. The daemon is killed when the `PrivateBus` is dropped.
+
+use std::fs;
+use std::io::{self, BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Tests start several buses in one process, each needs its own socket
+static SPAWNED: AtomicUsize = AtomicUsize::new(0);
+
+pub struct PrivateBus {
+    child: Child,
+    socket: PathBuf,
+    address: String,
+}
+
+impl PrivateBus {
+    pub fn spawn() -> io::Result<PrivateBus> {
+        let socket = std::env::temp_dir().join(format!(
+            "rust-sys-dbus-{}-{}.sock",
+            std::process::id(),
+            SPAWNED.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_file(&socket);
+        let mut child = Command::new("dbus-daemon")
+            .arg("--session")
+            .arg("--nofork")
+            .arg("--print-address=1")
+            .arg(format!("--address=unix:path={}", socket.display()))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            // Warnings about fd limits and activatable services are noise here
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        // The daemon prints its address once it is listening
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let mut address = String::new();
+        BufReader::new(stdout).read_line(&mut address)?;
+        let address = address.trim().to_string();
+        if address.is_empty() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::Error::other("dbus-daemon exited without an address"));
+        }
+        Ok(PrivateBus {
+            child,
+            socket,
+            address,
+        })
+    }
+
+    // `unix:path=...,guid=...`, for `connection::Builder::address` or
+    // `busctl --address`
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+}
+
+impl Drop for PrivateBus {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = fs::remove_file(&self.socket);
+    }
+}
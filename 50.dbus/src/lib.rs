@@ -0,0 +1,24 @@
+// D-Bus integration for the gateway
+//
+// On a Linux edge box, D-Bus is how services find and talk to each other:
+// systemd, logind, NetworkManager, BlueZ and ModemManager all live there.
+// This crate puts the gateway on the bus and reads the host's state from it:
+//
+// - `service`: the `org.rustsys.Gateway` interface (methods, properties and
+//   alert signals) served from the gateway's status, plus its client proxy
+// - `system`: proxies for logind and NetworkManager, written by hand from
+//   their published interfaces
+// - `bus`: a private `dbus-daemon` for demos and tests, so nothing touches
+//   the real session or system bus
+// - `standins`: fake logind and NetworkManager to put on that bus
+
+pub mod bus;
+pub mod service;
+pub mod standins;
+pub mod system;
+
+pub use bus::PrivateBus;
+pub use service::{AlertPublisher, GatewayClientProxy, GatewayError, GatewayService};
+
+pub const SERVICE_NAME: &str = "org.rustsys.Gateway";
+pub const OBJECT_PATH: &str = "/org/rustsys/Gateway";
@@ -0,0 +1,221 @@
+use dbus::standins::StandIns;
+use dbus::system::{HostState, Login1ManagerProxy, ShutdownDelay};
+use dbus::{
+    AlertPublisher, GatewayClientProxy, GatewayError, GatewayService, PrivateBus, OBJECT_PATH,
+    SERVICE_NAME,
+};
+use gateway::config::{Comparison, RuleConfig};
+use gateway::{Config, Gateway};
+use rules::AlertEvent;
+use std::error::Error;
+use std::thread;
+use std::time::Duration;
+use zbus::blocking::connection::Builder;
+use zbus::blocking::fdo::IntrospectableProxy;
+use zbus::proxy::CacheProperties;
+
+// No HTTP endpoint or uplink; a rule sensitive enough to fire in a few
+// seconds of simulated data
+fn demo_config() -> Config {
+    let mut config = Config::default();
+    config.gateway.id = "gw-dbus".to_string();
+    config.status.bind = String::new();
+    config.datalog.dir = std::env::temp_dir().join("rust-sys-dbus-log");
+    config.rules.push(RuleConfig {
+        name: "warm".to_string(),
+        metric: "temperature".to_string(),
+        when: Comparison::Above,
+        threshold: 23.0,
+        hysteresis: 0.2,
+        duration_ms: 0,
+    });
+    config
+}
+
+// The `<method>`, `<property>`, `<signal>` and `<arg>` lines of one
+// interface in introspection XML
+fn members(xml: &str, interface: &str) -> Vec<String> {
+    xml.lines()
+        .map(str::trim)
+        .skip_while(|l| !l.contains(&format!("<interface name=\"{}\"", interface)))
+        .skip(1)
+        .take_while(|l| !l.starts_with("</interface>"))
+        .filter(|l| !l.starts_with("<!--") && !l.starts_with("</") && !l.starts_with("<annotation"))
+        // Arguments indented under their member
+        .map(|l| match l.starts_with("<arg") {
+            true => format!("  {}", l),
+            false => l.to_string(),
+        })
+        .collect()
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    println!("=== D-Bus Integration Examples ===\n");
+
+    // 1. A bus of our own
+    println!("1. Private message bus:");
+    let bus = match PrivateBus::spawn() {
+        Ok(bus) => bus,
+        Err(e) => {
+            println!("   cannot start dbus-daemon: {}", e);
+            println!("   (install the dbus package to run this demo)");
+            return Ok(());
+        }
+    };
+    let address = bus.address().split(",guid=").next().unwrap_or_default();
+    println!("   dbus-daemon listening on {}", address);
+
+    // 2. The gateway on the bus
+    println!("\n2. Serving {}:", SERVICE_NAME);
+    let config = demo_config();
+    let (mut gw, _) = Gateway::new(config.clone())?;
+    let service = Builder::address(bus.address())?
+        .name(SERVICE_NAME)?
+        .serve_at(OBJECT_PATH, GatewayService::new(gw.status()))?
+        .build()?;
+    let publisher = AlertPublisher::new(&service)?;
+    println!(
+        "   {} owns {} at {}",
+        service
+            .unique_name()
+            .map(|n| n.to_string())
+            .unwrap_or_default(),
+        SERVICE_NAME,
+        OBJECT_PATH
+    );
+
+    // A second connection plays the client, as another process would
+    let client = Builder::address(bus.address())?.build()?;
+    let introspect = IntrospectableProxy::builder(&client)
+        .destination(SERVICE_NAME)?
+        .path(OBJECT_PATH)?
+        .build()?;
+    for line in members(&introspect.introspect()?, SERVICE_NAME) {
+        println!("   {}", line);
+    }
+
+    // 3. Alert transitions become signals
+    println!("\n3. Alerts as signals:");
+    // Uncached: every property read is a Get call, so the values printed
+    // below come straight from the service
+    let proxy = GatewayClientProxy::builder(&client)
+        .cache_properties(CacheProperties::No)
+        .build()?;
+    // Subscribe before anything happens; matching signals queue up
+    let mut raised_signals = proxy.receive_alert_raised()?;
+    let mut cleared_signals = proxy.receive_alert_cleared()?;
+
+    let (mut raised, mut cleared) = (0, 0);
+    while gw.elapsed_ms() < 3000 {
+        let events = gw.tick()?;
+        publisher.publish(&events)?;
+        for event in &events {
+            match event {
+                AlertEvent::Raised { .. } => raised += 1,
+                AlertEvent::Cleared { .. } => cleared += 1,
+            }
+        }
+        thread::sleep(Duration::from_millis(config.gateway.poll_interval_ms));
+    }
+    let status = gw.status().lock().unwrap().clone();
+    println!(
+        "   ran {} polls: {} raised, {} cleared",
+        status.polls, raised, cleared
+    );
+    let got_raised: Vec<String> = raised_signals
+        .by_ref()
+        .take(raised)
+        .filter_map(|s| {
+            s.args()
+                .ok()
+                .map(|a| format!("AlertRaised({:?}, {})", a.rule, a.at_ms))
+        })
+        .collect();
+    let got_cleared: Vec<String> = cleared_signals
+        .by_ref()
+        .take(cleared)
+        .filter_map(|s| {
+            s.args()
+                .ok()
+                .map(|a| format!("AlertCleared({:?}, {})", a.rule, a.at_ms))
+        })
+        .collect();
+    for line in got_raised.iter().chain(&got_cleared) {
+        println!("   {}", line);
+    }
+
+    // 4. Methods, properties and errors
+    println!("\n4. Calling the gateway:");
+    let reply = proxy.status()?;
+    println!(
+        "   Status(): {} polls, {} readings, active {:?}",
+        reply.polls, reply.readings, reply.active_alerts
+    );
+    let json: serde_json::Value = serde_json::from_str(&proxy.status_json()?)?;
+    println!("   StatusJson(): polls {}", json["polls"]);
+    println!("   GatewayId = {:?}", proxy.gateway_id()?);
+
+    let before = proxy.unacknowledged()?;
+    match reply.active_alerts.first() {
+        Some(rule) => {
+            let first = proxy.acknowledge(rule)?;
+            let again = proxy.acknowledge(rule)?;
+            println!(
+                "   Acknowledge({:?}) -> {}, again -> {}",
+                rule, first, again
+            );
+            println!(
+                "   Unacknowledged: {:?} -> {:?}",
+                before,
+                proxy.unacknowledged()?
+            );
+        }
+        None => println!("   no alert active to acknowledge"),
+    }
+    match proxy.acknowledge("flood/9") {
+        Err(GatewayError::NotActive(message)) => {
+            println!("   Acknowledge(\"flood/9\") -> NotActive: {}", message)
+        }
+        other => println!("   Acknowledge(\"flood/9\") -> {:?}", other),
+    }
+
+    // 5. Consuming system services
+    println!("\n5. Reading the host:");
+    match zbus::blocking::Connection::system() {
+        Ok(system) => println!("   system bus: {}", HostState::read(&system)),
+        Err(e) => println!("   system bus: unavailable ({})", e),
+    }
+    let standins = StandIns::start(bus.address())?;
+    println!("   stand-ins for logind and NetworkManager on the private bus");
+    let host = HostState::read(&client);
+    println!("   {} (online: {})", host, host.online());
+    standins.set_network_state(20)?;
+    let host = HostState::read(&client);
+    println!("   modem drops: {} (online: {})", host, host.online());
+    standins.set_network_state(70)?;
+
+    // 6. Shutting down without losing data
+    println!("\n6. Shutdown with a delay lock:");
+    let logind = Login1ManagerProxy::new(&client)?;
+    let delay = ShutdownDelay::take(&logind, "rust-sys gateway")?;
+    println!(
+        "   delay lock taken, logind holds {}",
+        standins.locks_held()
+    );
+    let mut announcements = logind.receive_prepare_for_shutdown()?;
+    standins.begin_shutdown()?;
+    if let Some(signal) = announcements.next() {
+        println!("   PrepareForShutdown({})", signal.args()?.start);
+    }
+    let last = gw.shutdown()?;
+    println!(
+        "   flushed: {} records logged, {} alerts raised",
+        last.logged_records, last.alerts_raised
+    );
+    delay.release();
+    println!("   released, logind holds {}", standins.locks_held());
+
+    let _ = std::fs::remove_dir_all(&config.datalog.dir);
+    println!("\n=== End of D-Bus Integration Examples ===");
+    Ok(())
+}
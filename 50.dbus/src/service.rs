@@ -0,0 +1,208 @@
+// The `org.rustsys.Gateway` interface
+//
+//   methods:    Status() -> (stttttastt)
+//               StatusJson() -> s
+//               Acknowledge(s rule) -> b
+//   properties: GatewayId s (const), ActiveAlerts as, Unacknowledged as
+//   signals:    AlertRaised(s rule, t at_ms), AlertCleared(s rule, t at_ms)
+//
+// Everything is read from the gateway's `SharedStatus`, the same snapshot
+// the HTTP endpoint serves, so the gateway loop does not know D-Bus exists.
+// Only the alert transitions are pushed, through `AlertPublisher`.
+
+use crate::OBJECT_PATH;
+use gateway::status::{SharedStatus, Status};
+use rules::AlertEvent;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use zbus::blocking::object_server::InterfaceRef;
+use zbus::blocking::Connection;
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::Type;
+use zbus::{interface, proxy, DBusError};
+
+// `Status()`'s reply: a D-Bus struct, marshalled field by field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct StatusReply {
+    pub gateway_id: String,
+    pub uptime_ms: u64,
+    pub polls: u64,
+    pub readings: u64,
+    pub stored_readings: u64,
+    pub alerts_raised: u64,
+    pub active_alerts: Vec<String>,
+    pub batches_sent: u64,
+    pub uplink_errors: u64,
+}
+
+impl From<&Status> for StatusReply {
+    fn from(s: &Status) -> StatusReply {
+        StatusReply {
+            gateway_id: s.gateway_id.clone(),
+            uptime_ms: s.uptime_ms,
+            polls: s.polls,
+            readings: s.readings,
+            stored_readings: s.stored_readings,
+            alerts_raised: s.alerts_raised,
+            active_alerts: s.active_alerts.clone(),
+            batches_sent: s.batches_sent,
+            uplink_errors: s.uplink_errors,
+        }
+    }
+}
+
+// Replies as `org.rustsys.Gateway.Error.<Variant>`; the client proxy turns
+// the name back into the variant
+#[derive(Debug, DBusError)]
+#[zbus(prefix = "org.rustsys.Gateway.Error")]
+pub enum GatewayError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    // Acknowledge() for a rule that is not raised
+    NotActive(String),
+}
+
+pub struct GatewayService {
+    status: SharedStatus,
+    acknowledged: HashSet<String>,
+}
+
+impl GatewayService {
+    pub fn new(status: SharedStatus) -> GatewayService {
+        GatewayService {
+            status,
+            acknowledged: HashSet::new(),
+        }
+    }
+
+    fn active(&self) -> Vec<String> {
+        self.status.lock().unwrap().active_alerts.clone()
+    }
+}
+
+#[interface(name = "org.rustsys.Gateway")]
+impl GatewayService {
+    // The whole snapshot in one round trip
+    fn status(&self) -> StatusReply {
+        StatusReply::from(&*self.status.lock().unwrap())
+    }
+
+    // The HTTP endpoint's JSON, for shell scripts (`busctl call ...`)
+    fn status_json(&self) -> String {
+        serde_json::to_string(&*self.status.lock().unwrap()).unwrap_or_default()
+    }
+
+    // Mark a raised alert as seen; false if it already was
+    async fn acknowledge(
+        &mut self,
+        rule: &str,
+        #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+    ) -> Result<bool, GatewayError> {
+        if !self.active().iter().any(|r| r == rule) {
+            return Err(GatewayError::NotActive(format!(
+                "alert '{}' is not raised",
+                rule
+            )));
+        }
+        let added = self.acknowledged.insert(rule.to_string());
+        if added {
+            self.unacknowledged_changed(&emitter).await?;
+        }
+        Ok(added)
+    }
+
+    #[zbus(property(emits_changed_signal = "const"))]
+    fn gateway_id(&self) -> String {
+        self.status.lock().unwrap().gateway_id.clone()
+    }
+
+    #[zbus(property)]
+    fn active_alerts(&self) -> Vec<String> {
+        self.active()
+    }
+
+    // Raised and not yet acknowledged
+    #[zbus(property)]
+    fn unacknowledged(&self) -> Vec<String> {
+        self.active()
+            .into_iter()
+            .filter(|r| !self.acknowledged.contains(r))
+            .collect()
+    }
+
+    #[zbus(signal)]
+    async fn alert_raised(emitter: &SignalEmitter<'_>, rule: &str, at_ms: u64) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn alert_cleared(emitter: &SignalEmitter<'_>, rule: &str, at_ms: u64)
+        -> zbus::Result<()>;
+}
+
+// Pushes the gateway's alert transitions onto the bus. The gateway loop is
+// synchronous, so the async signal functions are driven with `block_on`.
+pub struct AlertPublisher {
+    iface: InterfaceRef<GatewayService>,
+}
+
+impl AlertPublisher {
+    // `conn` must already serve a `GatewayService` at OBJECT_PATH
+    pub fn new(conn: &Connection) -> zbus::Result<AlertPublisher> {
+        let iface = conn.object_server().interface(OBJECT_PATH)?;
+        Ok(AlertPublisher { iface })
+    }
+
+    // One signal per transition, then one PropertiesChanged per list.
+    // Call after `Gateway::tick`, which has already updated the status.
+    pub fn publish(&self, events: &[AlertEvent]) -> zbus::Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let emitter = self.iface.signal_emitter();
+        for event in events {
+            match event {
+                AlertEvent::Raised { rule, at_ms } => {
+                    zbus::block_on(GatewayService::alert_raised(emitter, rule, *at_ms))?
+                }
+                AlertEvent::Cleared { rule, at_ms } => {
+                    // A later raise needs a fresh acknowledgement
+                    self.iface.get_mut().acknowledged.remove(rule);
+                    zbus::block_on(GatewayService::alert_cleared(emitter, rule, *at_ms))?
+                }
+            }
+        }
+        let service = self.iface.get();
+        zbus::block_on(service.active_alerts_changed(emitter))?;
+        zbus::block_on(service.unacknowledged_changed(emitter))
+    }
+}
+
+// The client side, as another program would declare it. Property reads
+// are cached and kept fresh by PropertiesChanged.
+#[proxy(
+    interface = "org.rustsys.Gateway",
+    default_service = "org.rustsys.Gateway",
+    default_path = "/org/rustsys/Gateway",
+    gen_async = false
+)]
+pub trait GatewayClient {
+    fn status(&self) -> zbus::Result<StatusReply>;
+
+    fn status_json(&self) -> zbus::Result<String>;
+
+    fn acknowledge(&self, rule: &str) -> Result<bool, GatewayError>;
+
+    #[zbus(property)]
+    fn gateway_id(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn active_alerts(&self) -> zbus::Result<Vec<String>>;
+
+    #[zbus(property)]
+    fn unacknowledged(&self) -> zbus::Result<Vec<String>>;
+
+    #[zbus(signal)]
+    fn alert_raised(&self, rule: String, at_ms: u64) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn alert_cleared(&self, rule: String, at_ms: u64) -> zbus::Result<()>;
+}
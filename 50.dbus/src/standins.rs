@@ -0,0 +1,138 @@
+// Stand-ins for logind and NetworkManager
+//
+// A private bus has no system services, so these claim the real
+// well-known names and implement just the members `system.rs` uses. The
+// proxies cannot tell the difference, which is how the demo and the tests
+// run D-Bus clients without root or a full system.
+
+use std::io::{ErrorKind, Read};
+use std::os::unix::net::UnixStream;
+use std::sync::{Arc, Mutex};
+use zbus::blocking::connection::Builder;
+use zbus::blocking::Connection;
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::OwnedFd;
+use zbus::{fdo, interface};
+
+const LOGIND_PATH: &str = "/org/freedesktop/login1";
+const NM_PATH: &str = "/org/freedesktop/NetworkManager";
+
+struct FakeLogind {
+    preparing: bool,
+    // Our end of every inhibitor lock handed out
+    locks: Arc<Mutex<Vec<UnixStream>>>,
+}
+
+#[interface(name = "org.freedesktop.login1.Manager")]
+impl FakeLogind {
+    // Real logind hands out one end of a FIFO; a socket pair behaves the
+    // same: the lock is released when the client closes its end
+    fn inhibit(&mut self, what: &str, who: &str, why: &str, mode: &str) -> fdo::Result<OwnedFd> {
+        let _ = (what, who, why, mode);
+        let (ours, theirs) = UnixStream::pair().map_err(|e| fdo::Error::Failed(e.to_string()))?;
+        self.locks.lock().unwrap().push(ours);
+        Ok(std::os::fd::OwnedFd::from(theirs).into())
+    }
+
+    #[zbus(property)]
+    fn idle_hint(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn preparing_for_shutdown(&self) -> bool {
+        self.preparing
+    }
+
+    #[zbus(signal)]
+    async fn prepare_for_shutdown(emitter: &SignalEmitter<'_>, start: bool) -> zbus::Result<()>;
+}
+
+struct FakeNetworkManager {
+    state: u32,
+}
+
+#[interface(name = "org.freedesktop.NetworkManager")]
+impl FakeNetworkManager {
+    #[zbus(property)]
+    fn state(&self) -> u32 {
+        self.state
+    }
+
+    #[zbus(property)]
+    fn connectivity(&self) -> u32 {
+        if self.state == 70 {
+            4
+        } else {
+            1
+        }
+    }
+
+    #[zbus(property)]
+    fn networking_enabled(&self) -> bool {
+        true
+    }
+}
+
+pub struct StandIns {
+    conn: Connection,
+    locks: Arc<Mutex<Vec<UnixStream>>>,
+}
+
+impl StandIns {
+    pub fn start(address: &str) -> zbus::Result<StandIns> {
+        let locks = Arc::new(Mutex::new(Vec::new()));
+        let logind = FakeLogind {
+            preparing: false,
+            locks: Arc::clone(&locks),
+        };
+        let nm = FakeNetworkManager { state: 70 };
+        let conn = Builder::address(address)?
+            .name("org.freedesktop.login1")?
+            .name("org.freedesktop.NetworkManager")?
+            .serve_at(LOGIND_PATH, logind)?
+            .serve_at(NM_PATH, nm)?
+            .build()?;
+        Ok(StandIns { conn, locks })
+    }
+
+    // The modem drops or comes back
+    pub fn set_network_state(&self, state: u32) -> zbus::Result<()> {
+        let iface = self
+            .conn
+            .object_server()
+            .interface::<_, FakeNetworkManager>(NM_PATH)?;
+        iface.get_mut().state = state;
+        let nm = iface.get();
+        zbus::block_on(nm.state_changed(iface.signal_emitter()))?;
+        zbus::block_on(nm.connectivity_changed(iface.signal_emitter()))
+    }
+
+    // `systemctl poweroff`: logind announces it and waits for delay locks
+    pub fn begin_shutdown(&self) -> zbus::Result<()> {
+        let iface = self
+            .conn
+            .object_server()
+            .interface::<_, FakeLogind>(LOGIND_PATH)?;
+        iface.get_mut().preparing = true;
+        let emitter = iface.signal_emitter();
+        zbus::block_on(FakeLogind::prepare_for_shutdown(emitter, true))?;
+        let logind = iface.get();
+        zbus::block_on(logind.preparing_for_shutdown_changed(emitter))
+    }
+
+    // Inhibitor locks whose holder has not closed its end yet
+    pub fn locks_held(&self) -> usize {
+        let mut locks = self.locks.lock().unwrap();
+        locks.retain(|stream| {
+            let _ = stream.set_nonblocking(true);
+            let mut byte = [0u8; 1];
+            match (&*stream).read(&mut byte) {
+                Ok(0) => false,
+                Err(e) => e.kind() == ErrorKind::WouldBlock,
+                Ok(_) => true,
+            }
+        });
+        locks.len()
+    }
+}
@@ -0,0 +1,151 @@
+// Reading the host from the system bus
+//
+// Proxies for the two services an edge gateway cares about most, declared
+// from their documented interfaces (`busctl introspect` shows the same):
+//
+// - logind: is the machine about to shut down or suspend? A *delay*
+//   inhibitor lock gives the gateway time to flush before it does.
+// - NetworkManager: is there a route to the outside world? No point in
+//   retrying the uplink while the modem is down.
+//
+// Only the members used here are declared; a proxy does not have to cover
+// the whole interface.
+
+use std::fmt;
+use zbus::blocking::Connection;
+use zbus::proxy;
+use zbus::zvariant::OwnedFd;
+
+#[proxy(
+    interface = "org.freedesktop.login1.Manager",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1",
+    gen_async = false
+)]
+pub trait Login1Manager {
+    // what: "shutdown:sleep", mode: "delay" or "block". The lock is held
+    // for as long as the returned descriptor stays open.
+    fn inhibit(&self, what: &str, who: &str, why: &str, mode: &str) -> zbus::Result<OwnedFd>;
+
+    #[zbus(property)]
+    fn idle_hint(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn preparing_for_shutdown(&self) -> zbus::Result<bool>;
+
+    // `start` is true before shutting down, false if it was cancelled
+    #[zbus(signal)]
+    fn prepare_for_shutdown(&self, start: bool) -> zbus::Result<()>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager",
+    gen_async = false
+)]
+pub trait NetworkManager {
+    // NetworkManager also has a StateChanged signal; PropertiesChanged
+    // carries the same news, and `receive_state_changed` (generated for the
+    // property) subscribes to that
+    #[zbus(property)]
+    fn state(&self) -> zbus::Result<u32>;
+
+    #[zbus(property)]
+    fn connectivity(&self) -> zbus::Result<u32>;
+
+    #[zbus(property)]
+    fn networking_enabled(&self) -> zbus::Result<bool>;
+}
+
+// NMState values from NetworkManager's D-Bus API
+pub const NM_STATE_CONNECTED_SITE: u32 = 60;
+pub const NM_STATE_CONNECTED_GLOBAL: u32 = 70;
+
+pub fn network_state_name(state: u32) -> &'static str {
+    match state {
+        10 => "asleep",
+        20 => "disconnected",
+        30 => "disconnecting",
+        40 => "connecting",
+        50 => "connected (local only)",
+        NM_STATE_CONNECTED_SITE => "connected (site only)",
+        NM_STATE_CONNECTED_GLOBAL => "connected (global)",
+        _ => "unknown",
+    }
+}
+
+// NMConnectivityState: what NetworkManager's connectivity check found
+pub fn connectivity_name(connectivity: u32) -> &'static str {
+    match connectivity {
+        1 => "none",
+        2 => "portal",
+        3 => "limited",
+        4 => "full",
+        _ => "unknown",
+    }
+}
+
+// The gateway's view of the host; a service that is not on the bus leaves
+// its fields as None rather than failing the whole read
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HostState {
+    pub network_state: Option<u32>,
+    pub connectivity: Option<u32>,
+    pub idle: Option<bool>,
+    pub preparing_for_shutdown: Option<bool>,
+}
+
+impl HostState {
+    pub fn read(conn: &Connection) -> HostState {
+        let mut host = HostState::default();
+        if let Ok(nm) = NetworkManagerProxy::new(conn) {
+            host.network_state = nm.state().ok();
+            host.connectivity = nm.connectivity().ok();
+        }
+        if let Ok(logind) = Login1ManagerProxy::new(conn) {
+            host.idle = logind.idle_hint().ok();
+            host.preparing_for_shutdown = logind.preparing_for_shutdown().ok();
+        }
+        host
+    }
+
+    // Worth trying the uplink? Site-only may still reach an on-premises
+    // collector.
+    pub fn online(&self) -> bool {
+        self.network_state
+            .is_some_and(|s| s >= NM_STATE_CONNECTED_SITE)
+    }
+}
+
+impl fmt::Display for HostState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.network_state {
+            Some(state) => write!(f, "network {}", network_state_name(state))?,
+            None => write!(f, "network ?")?,
+        }
+        if let Some(c) = self.connectivity {
+            write!(f, ", connectivity {}", connectivity_name(c))?;
+        }
+        match self.preparing_for_shutdown {
+            Some(true) => write!(f, ", shutting down"),
+            Some(false) => write!(f, ", running"),
+            None => write!(f, ", logind ?"),
+        }
+    }
+}
+
+// A logind delay lock: shutdown waits (up to InhibitDelayMaxSec) until
+// this is dropped, so take it at start-up and drop it after flushing
+pub struct ShutdownDelay {
+    _fd: OwnedFd,
+}
+
+impl ShutdownDelay {
+    pub fn take(logind: &Login1ManagerProxy<'_>, who: &str) -> zbus::Result<ShutdownDelay> {
+        let fd = logind.inhibit("shutdown:sleep", who, "flush telemetry", "delay")?;
+        Ok(ShutdownDelay { _fd: fd })
+    }
+
+    pub fn release(self) {}
+}
@@ -0,0 +1,282 @@
+// The gateway's interface and the system proxies, end to end on a private
+// `dbus-daemon`: one service connection, one client connection, and the
+// gateway driven by scripted readings and a manual clock so every alert
+// lands on a known poll. Without `dbus-daemon` installed each test says
+// so and passes, as the demo does.
+
+use dbus::service::StatusReply;
+use dbus::standins::StandIns;
+use dbus::system::{HostState, Login1ManagerProxy, ShutdownDelay};
+use dbus::{
+    AlertPublisher, GatewayClientProxy, GatewayError, GatewayService, PrivateBus, OBJECT_PATH,
+    SERVICE_NAME,
+};
+use gateway::config::{Comparison, RuleConfig};
+use gateway::doubles::{ManualClock, ScriptedSensors};
+use gateway::{Config, Gateway, Parts};
+use rules::AlertEvent;
+use std::rc::Rc;
+use std::time::Duration;
+use zbus::blocking::connection::Builder;
+use zbus::blocking::fdo::IntrospectableProxy;
+use zbus::blocking::Connection;
+use zbus::proxy::CacheProperties;
+
+const WARM: &str = "warm/0";
+
+fn config() -> Config {
+    let mut config = Config::default();
+    config.gateway.id = "gw-test".to_string();
+    config.sensors.count = 1;
+    config.status.bind = String::new();
+    config.rules.push(RuleConfig {
+        name: "warm".to_string(),
+        metric: "temperature".to_string(),
+        when: Comparison::Above,
+        threshold: 23.0,
+        hysteresis: 0.2,
+        duration_ms: 0,
+    });
+    config
+}
+
+// Sensor 0 warms, cools and warms again, 25 polls each time; readings
+// are smoothed, so `warm` follows a few polls behind. The default `hot`
+// rule never fires at 28.
+fn gateway() -> (Gateway, Rc<ManualClock>) {
+    let clock = Rc::new(ManualClock::new(1_700_000_000_000));
+    let sensors = ScriptedSensors::new()
+        .repeat(5, &[(0, "temperature", 20.0)])
+        .repeat(25, &[(0, "temperature", 28.0)])
+        .repeat(25, &[(0, "temperature", 18.0)])
+        .repeat(25, &[(0, "temperature", 28.0)]);
+    let parts = Parts {
+        clock: clock.clone(),
+        sensors: Box::new(sensors),
+        transport: None,
+        history: None,
+        log: None,
+    };
+    (Gateway::with_parts(config(), parts), clock)
+}
+
+// Fields drop in order, so the daemon outlives both connections
+struct Setup {
+    service: Connection,
+    client: Connection,
+    gw: Gateway,
+    clock: Rc<ManualClock>,
+    bus: PrivateBus,
+}
+
+impl Setup {
+    fn proxy(&self) -> GatewayClientProxy<'_> {
+        GatewayClientProxy::builder(&self.client)
+            .cache_properties(CacheProperties::No)
+            .build()
+            .unwrap()
+    }
+
+    // `polls` ticks, publishing as the service binary does
+    fn run(&mut self, polls: usize) -> Vec<AlertEvent> {
+        let publisher = AlertPublisher::new(&self.service).unwrap();
+        let mut events = Vec::new();
+        for _ in 0..polls {
+            self.clock.advance(Duration::from_millis(100));
+            let tick = self.gw.tick().unwrap();
+            publisher.publish(&tick).unwrap();
+            events.extend(tick);
+        }
+        events
+    }
+}
+
+fn setup() -> Option<Setup> {
+    let bus = match PrivateBus::spawn() {
+        Ok(bus) => bus,
+        Err(e) => {
+            eprintln!("skipped, cannot start dbus-daemon: {}", e);
+            return None;
+        }
+    };
+    let (gw, clock) = gateway();
+    let service = Builder::address(bus.address())
+        .unwrap()
+        .name(SERVICE_NAME)
+        .unwrap()
+        .serve_at(OBJECT_PATH, GatewayService::new(gw.status()))
+        .unwrap()
+        .build()
+        .unwrap();
+    let client = Builder::address(bus.address()).unwrap().build().unwrap();
+    Some(Setup {
+        service,
+        client,
+        gw,
+        clock,
+        bus,
+    })
+}
+
+#[test]
+fn introspection_lists_the_interface() {
+    let Some(setup) = setup() else { return };
+    let xml = IntrospectableProxy::builder(&setup.client)
+        .destination(SERVICE_NAME)
+        .unwrap()
+        .path(OBJECT_PATH)
+        .unwrap()
+        .build()
+        .unwrap()
+        .introspect()
+        .unwrap();
+    assert!(xml.contains(&format!("<interface name=\"{}\">", SERVICE_NAME)));
+    for member in [
+        "<method name=\"Status\"",
+        "<method name=\"StatusJson\"",
+        "<method name=\"Acknowledge\"",
+        "<signal name=\"AlertRaised\"",
+        "<signal name=\"AlertCleared\"",
+        "<property name=\"GatewayId\" type=\"s\" access=\"read\"",
+        "<property name=\"ActiveAlerts\" type=\"as\" access=\"read\"",
+        "<property name=\"Unacknowledged\" type=\"as\" access=\"read\"",
+    ] {
+        assert!(xml.contains(member), "{}", member);
+    }
+}
+
+#[test]
+fn every_transition_is_one_signal() {
+    let Some(mut setup) = setup() else { return };
+    let client = setup.client.clone();
+    let proxy = GatewayClientProxy::new(&client).unwrap();
+    // Subscribed before anything happens, so the signals queue up
+    let raised = proxy.receive_alert_raised().unwrap();
+    let cleared = proxy.receive_alert_cleared().unwrap();
+
+    let events = setup.run(80);
+    let expected = |raise: bool| -> Vec<(String, u64)> {
+        events
+            .iter()
+            .filter_map(|e| match (e, raise) {
+                (AlertEvent::Raised { rule, at_ms, .. }, true)
+                | (AlertEvent::Cleared { rule, at_ms, .. }, false) => Some((rule.clone(), *at_ms)),
+                _ => None,
+            })
+            .collect()
+    };
+    let (want_raised, want_cleared) = (expected(true), expected(false));
+    assert_eq!(want_raised.len(), 2);
+    assert_eq!(want_cleared.len(), 1);
+
+    let got_raised: Vec<(String, u64)> = raised
+        .take(want_raised.len())
+        .map(|s| {
+            let args = s.args().unwrap();
+            (args.rule.to_string(), args.at_ms)
+        })
+        .collect();
+    let got_cleared: Vec<(String, u64)> = cleared
+        .take(want_cleared.len())
+        .map(|s| {
+            let args = s.args().unwrap();
+            (args.rule.to_string(), args.at_ms)
+        })
+        .collect();
+    assert_eq!(got_raised, want_raised);
+    assert_eq!(got_cleared, want_cleared);
+}
+
+#[test]
+fn status_is_served_from_the_shared_status() {
+    let Some(mut setup) = setup() else { return };
+    setup.run(30);
+    let proxy = setup.proxy();
+    let reply = proxy.status().unwrap();
+    assert_eq!(
+        reply,
+        StatusReply::from(&*setup.gw.status().lock().unwrap())
+    );
+    assert_eq!(reply.polls, 30);
+    assert_eq!(reply.active_alerts, [WARM]);
+
+    let json: serde_json::Value = serde_json::from_str(&proxy.status_json().unwrap()).unwrap();
+    assert_eq!(json["polls"], 30);
+    assert_eq!(json["gateway_id"], "gw-test");
+    assert_eq!(proxy.gateway_id().unwrap(), "gw-test");
+    assert_eq!(proxy.active_alerts().unwrap(), [WARM]);
+}
+
+#[test]
+fn acknowledge_once_then_false() {
+    let Some(mut setup) = setup() else { return };
+    setup.run(30);
+    let proxy = setup.proxy();
+    assert_eq!(proxy.unacknowledged().unwrap(), [WARM]);
+    assert!(proxy.acknowledge(WARM).unwrap());
+    assert!(!proxy.acknowledge(WARM).unwrap());
+    assert!(proxy.unacknowledged().unwrap().is_empty());
+    // Still active, only no longer waiting on anyone
+    assert_eq!(proxy.active_alerts().unwrap(), [WARM]);
+}
+
+#[test]
+fn errors_map_back_to_the_variant() {
+    let Some(setup) = setup() else { return };
+    let proxy = setup.proxy();
+    match proxy.acknowledge("flood/9") {
+        Err(GatewayError::NotActive(message)) => {
+            assert!(message.contains("flood/9"), "{}", message)
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn host_state_follows_the_network() {
+    let Some(setup) = setup() else { return };
+    // Nothing answers for logind or NetworkManager yet
+    assert_eq!(HostState::read(&setup.client), HostState::default());
+    assert!(!HostState::read(&setup.client).online());
+
+    let standins = StandIns::start(setup.bus.address()).unwrap();
+    let host = HostState::read(&setup.client);
+    assert!(host.online());
+    assert_eq!(host.connectivity, Some(4));
+    assert_eq!(host.preparing_for_shutdown, Some(false));
+    assert_eq!(
+        host.to_string(),
+        "network connected (global), connectivity full, running"
+    );
+
+    standins.set_network_state(20).unwrap();
+    let host = HostState::read(&setup.client);
+    assert!(!host.online());
+    assert_eq!(host.connectivity, Some(1));
+    // Site-only still counts: the collector may be on the premises
+    standins.set_network_state(60).unwrap();
+    assert!(HostState::read(&setup.client).online());
+}
+
+#[test]
+fn the_delay_lock_is_held_until_released() {
+    let Some(mut setup) = setup() else { return };
+    let standins = StandIns::start(setup.bus.address()).unwrap();
+    let logind = Login1ManagerProxy::new(&setup.client).unwrap();
+    assert_eq!(standins.locks_held(), 0);
+    let delay = ShutdownDelay::take(&logind, "gateway test").unwrap();
+    assert_eq!(standins.locks_held(), 1);
+
+    let mut announcements = logind.receive_prepare_for_shutdown().unwrap();
+    standins.begin_shutdown().unwrap();
+    assert!(announcements.next().unwrap().args().unwrap().start);
+    assert_eq!(
+        HostState::read(&setup.client).preparing_for_shutdown,
+        Some(true)
+    );
+    // Shutdown waits on us while the gateway flushes
+    setup.run(2);
+    assert_eq!(standins.locks_held(), 1);
+    delay.release();
+    assert_eq!(standins.locks_held(), 0);
+}
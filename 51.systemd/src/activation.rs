@@ -0,0 +1,211 @@
+// Socket activation (sd_listen_fds)
+//
+// systemd passes N descriptors starting at fd 3 and says so in three
+// variables:
+//
+//   LISTEN_PID=<pid>        the process they are meant for
+//   LISTEN_FDS=<N>          how many
+//   LISTEN_FDNAMES=a:b      optional names, from FileDescriptorName=
+//
+// The variables are checked, the descriptors taken over exactly once, and
+// the variables removed so that child processes do not try the same.
+
+use std::fmt;
+use std::io;
+use std::net::TcpListener;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::net::UnixListener;
+
+pub const LISTEN_FDS_START: RawFd = 3;
+
+#[derive(Debug)]
+pub enum ActivationError {
+    Invalid { var: &'static str, value: String },
+    // LISTEN_FDNAMES must name every descriptor or none
+    NameCount { fds: usize, names: usize },
+    Io(io::Error),
+}
+
+impl fmt::Display for ActivationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActivationError::Invalid { var, value } => write!(f, "invalid {}={:?}", var, value),
+            ActivationError::NameCount { fds, names } => {
+                write!(f, "LISTEN_FDNAMES has {} names for {} fds", names, fds)
+            }
+            ActivationError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ActivationError {}
+
+impl From<io::Error> for ActivationError {
+    fn from(e: io::Error) -> ActivationError {
+        ActivationError::Io(e)
+    }
+}
+
+// What the variables say, before any descriptor is touched
+#[derive(Debug, Clone, PartialEq)]
+pub struct Activation {
+    pub count: usize,
+    pub names: Vec<String>,
+}
+
+impl Activation {
+    // None when there is nothing for `my_pid`: no variables, or variables
+    // inherited from a parent that was activated
+    pub fn parse(
+        pid: Option<&str>,
+        fds: Option<&str>,
+        names: Option<&str>,
+        my_pid: u32,
+    ) -> Result<Option<Activation>, ActivationError> {
+        let (Some(pid), Some(fds)) = (pid, fds) else {
+            return Ok(None);
+        };
+        let pid: u32 = pid.parse().map_err(|_| ActivationError::Invalid {
+            var: "LISTEN_PID",
+            value: pid.to_string(),
+        })?;
+        if pid != my_pid {
+            return Ok(None);
+        }
+        let count: usize = fds.parse().map_err(|_| ActivationError::Invalid {
+            var: "LISTEN_FDS",
+            value: fds.to_string(),
+        })?;
+        let names: Vec<String> = match names {
+            Some(names) => names.split(':').map(str::to_string).collect(),
+            // systemd's own default
+            None => vec!["unknown".to_string(); count],
+        };
+        if names.len() != count {
+            return Err(ActivationError::NameCount {
+                fds: count,
+                names: names.len(),
+            });
+        }
+        Ok(Some(Activation { count, names }))
+    }
+}
+
+fn check(ret: libc::c_int) -> io::Result<libc::c_int> {
+    if ret < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(ret)
+    }
+}
+
+fn sockopt(fd: RawFd, option: libc::c_int) -> io::Result<libc::c_int> {
+    let mut value: libc::c_int = 0;
+    let mut len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+    // SAFETY: value and len point to a c_int and its size
+    check(unsafe {
+        libc::getsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            option,
+            (&mut value as *mut libc::c_int).cast(),
+            &mut len,
+        )
+    })?;
+    Ok(value)
+}
+
+// A listening stream socket of the given address family
+fn is_listener(fd: RawFd, family: libc::c_int) -> bool {
+    sockopt(fd, libc::SO_TYPE).is_ok_and(|t| t == libc::SOCK_STREAM)
+        && sockopt(fd, libc::SO_ACCEPTCONN).is_ok_and(|on| on == 1)
+        && sockopt(fd, libc::SO_DOMAIN).is_ok_and(|d| d == family)
+}
+
+// One passed descriptor and its FileDescriptorName=
+#[derive(Debug)]
+pub struct ListenFd {
+    fd: OwnedFd,
+    name: String,
+}
+
+impl ListenFd {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_tcp_listener(&self) -> bool {
+        let fd = self.fd.as_raw_fd();
+        is_listener(fd, libc::AF_INET) || is_listener(fd, libc::AF_INET6)
+    }
+
+    pub fn into_tcp_listener(self) -> io::Result<TcpListener> {
+        if !self.is_tcp_listener() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("fd '{}' is not a listening TCP socket", self.name),
+            ));
+        }
+        Ok(TcpListener::from(self.fd))
+    }
+
+    pub fn into_unix_listener(self) -> io::Result<UnixListener> {
+        if !is_listener(self.fd.as_raw_fd(), libc::AF_UNIX) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("fd '{}' is not a listening Unix socket", self.name),
+            ));
+        }
+        Ok(UnixListener::from(self.fd))
+    }
+}
+
+// Take the descriptors systemd passed to this process; empty when not
+// socket-activated. Call once, early, before spawning threads: it removes
+// the LISTEN_* variables.
+pub fn take_listen_fds() -> Result<Vec<ListenFd>, ActivationError> {
+    let var = |name| std::env::var(name).ok();
+    let parsed = Activation::parse(
+        var("LISTEN_PID").as_deref(),
+        var("LISTEN_FDS").as_deref(),
+        var("LISTEN_FDNAMES").as_deref(),
+        std::process::id(),
+    );
+    for name in ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"] {
+        std::env::remove_var(name);
+    }
+    let Some(activation) = parsed? else {
+        return Ok(Vec::new());
+    };
+
+    let mut fds = Vec::with_capacity(activation.count);
+    for (i, name) in activation.names.into_iter().enumerate() {
+        let raw = LISTEN_FDS_START + i as RawFd;
+        // Not inherited by anything this process starts
+        // SAFETY: fcntl on an fd number has no memory-safety requirements
+        check(unsafe { libc::fcntl(raw, libc::F_SETFD, libc::FD_CLOEXEC) })?;
+        // SAFETY: systemd opened these for this process (LISTEN_PID
+        // matched) and the variables are gone, so nothing else claims them
+        let fd = unsafe { OwnedFd::from_raw_fd(raw) };
+        fds.push(ListenFd { fd, name });
+    }
+    Ok(fds)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListenerSource {
+    // From a socket unit, with its FileDescriptorName=
+    Activated(String),
+    Bound,
+}
+
+// The first TCP listener systemd passed, or `addr` bound here when run by
+// hand. Passed descriptors that are not TCP listeners are closed.
+pub fn tcp_listener_or_bind(addr: &str) -> Result<(TcpListener, ListenerSource), ActivationError> {
+    let passed = take_listen_fds()?;
+    if let Some(fd) = passed.into_iter().find(ListenFd::is_tcp_listener) {
+        let name = fd.name().to_string();
+        return Ok((fd.into_tcp_listener()?, ListenerSource::Activated(name)));
+    }
+    Ok((TcpListener::bind(addr)?, ListenerSource::Bound))
+}
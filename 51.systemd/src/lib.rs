@@ -0,0 +1,20 @@
+// systemd integration without libsystemd
+//
+// Both halves of the protocol are small enough to speak directly:
+//
+// - `activation`: socket activation. systemd binds the listening socket
+//   from a `.socket` unit and starts the service with it already open as
+//   fd 3 (and up), described by LISTEN_PID, LISTEN_FDS and LISTEN_FDNAMES.
+// - `notify`: sd_notify. A `Type=notify` service reports READY=1, STATUS=,
+//   STOPPING=1 and watchdog pings as datagrams to $NOTIFY_SOCKET.
+//
+// Outside systemd the variables are absent and everything falls back:
+// listeners are bound by the program, notifications are dropped.
+
+pub mod activation;
+pub mod notify;
+
+pub use activation::{
+    take_listen_fds, tcp_listener_or_bind, ActivationError, ListenFd, ListenerSource,
+};
+pub use notify::{Notifier, State};
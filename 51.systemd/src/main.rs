@@ -0,0 +1,159 @@
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::os::fd::IntoRawFd;
+use std::os::unix::net::UnixDatagram;
+use std::thread;
+use std::time::{Duration, Instant};
+use systemd::activation::{Activation, LISTEN_FDS_START};
+use systemd::{tcp_listener_or_bind, Notifier, State};
+
+// Everything queued on the fake notify socket, one string per datagram
+fn received(socket: &UnixDatagram) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut buf = [0u8; 4096];
+    while let Ok(n) = socket.recv(&mut buf) {
+        messages.push(String::from_utf8_lossy(&buf[..n]).into_owned());
+    }
+    messages
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== systemd Integration Examples ===\n");
+    let pid = std::process::id();
+
+    // 1. Run by hand: nothing from systemd, everything falls back. The
+    //    tests cover the same ground with assertions.
+    println!("1. Outside systemd:");
+    let (listener, source) = tcp_listener_or_bind("127.0.0.1:0")?;
+    println!("   listener {} ({:?})", listener.local_addr()?, source);
+    drop(listener);
+    let notifier = Notifier::from_env()?;
+    println!(
+        "   notifications enabled: {}, READY=1 sent: {}",
+        notifier.is_enabled(),
+        notifier.notify(&[State::Ready])?
+    );
+
+    // 2. What the variables can say
+    println!("\n2. Reading LISTEN_*:");
+    let me = pid.to_string();
+    let cases = [
+        (
+            "for us, named",
+            Some(me.as_str()),
+            Some("2"),
+            Some("status:metrics"),
+        ),
+        ("for us, unnamed", Some(me.as_str()), Some("1"), None),
+        ("for our parent", Some("1"), Some("1"), None),
+        ("not activated", None, None, None),
+        ("garbage count", Some(me.as_str()), Some("two"), None),
+        (
+            "names mismatch",
+            Some(me.as_str()),
+            Some("2"),
+            Some("status"),
+        ),
+    ];
+    for (label, listen_pid, fds, names) in cases {
+        match Activation::parse(listen_pid, fds, names, pid) {
+            Ok(Some(a)) => println!("   {:<16} {} fd(s) {:?}", label, a.count, a.names),
+            Ok(None) => println!("   {:<16} not for this process", label),
+            Err(e) => println!("   {:<16} error: {}", label, e),
+        }
+    }
+
+    // 3. Socket activation, set up the way systemd does it: the listener
+    // at fd 3 and the variables pointing at this process
+    println!("\n3. Socket activation (simulated):");
+    // SAFETY: probing an fd number
+    if unsafe { libc::fcntl(LISTEN_FDS_START, libc::F_GETFD) } != -1 {
+        println!("   fd 3 already in use, skipping");
+    } else {
+        let original = TcpListener::bind("127.0.0.1:0")?;
+        let addr = original.local_addr()?;
+        // Move it to fd 3 (where it may already be, fd 3 being free)
+        let raw = original.into_raw_fd();
+        if raw != LISTEN_FDS_START {
+            // SAFETY: `raw` is ours and open; fd 3 is free
+            unsafe {
+                libc::dup2(raw, LISTEN_FDS_START);
+                libc::close(raw);
+            }
+        }
+        std::env::set_var("LISTEN_PID", &me);
+        std::env::set_var("LISTEN_FDS", "1");
+        std::env::set_var("LISTEN_FDNAMES", "status");
+        println!("   LISTEN_PID={} LISTEN_FDS=1 LISTEN_FDNAMES=status", pid);
+
+        let (listener, source) = tcp_listener_or_bind("127.0.0.1:9")?;
+        println!("   listener {} ({:?})", listener.local_addr()?, source);
+        println!(
+            "   the socket unit's listener: {}",
+            listener.local_addr()? == addr
+        );
+        println!(
+            "   LISTEN_FDS left for child processes: {:?}",
+            std::env::var_os("LISTEN_FDS")
+        );
+        // The connection was possible before the "service" existed; that
+        // is what lets systemd start services lazily and in parallel
+        let mut client = TcpStream::connect(addr)?;
+        client.write_all(b"GET /status")?;
+        let (mut conn, _) = listener.accept()?;
+        let mut buf = [0u8; 11];
+        conn.read_exact(&mut buf)?;
+        println!("   accepted {:?}", String::from_utf8_lossy(&buf));
+    }
+
+    // 4. sd_notify against a socket of our own
+    println!("\n4. Notifications (simulated):");
+    let path = std::env::temp_dir().join(format!("rust-sys-notify-{}.sock", pid));
+    let _ = std::fs::remove_file(&path);
+    let systemd_side = UnixDatagram::bind(&path)?;
+    systemd_side.set_nonblocking(true)?;
+    std::env::set_var("NOTIFY_SOCKET", &path);
+    std::env::set_var("WATCHDOG_USEC", "200000");
+    std::env::set_var("WATCHDOG_PID", &me);
+    let mut notifier = Notifier::from_env()?;
+    println!("   NOTIFY_SOCKET={} WATCHDOG_USEC=200000", path.display());
+    notifier.notify(&[State::Ready, State::Status("3 sensors, 2 rules")])?;
+    notifier.notify(&[State::ExtendTimeout(Duration::from_secs(10))])?;
+    let messages = received(&systemd_side);
+    for message in &messages {
+        println!("   <- {:?}", message);
+    }
+    println!(
+        "   other WATCHDOG_PID: {:?}",
+        Notifier::parse_watchdog(Some("200000"), Some("1"), pid)
+    );
+
+    // 5. Watchdog pacing: the loop calls keepalive() every tick, pings go
+    // out every WATCHDOG_USEC / 2
+    println!("\n5. Watchdog:");
+    println!(
+        "   timeout 200ms, pinging every {:?}",
+        notifier.watchdog_interval().unwrap_or_default()
+    );
+    let started = Instant::now();
+    let mut ticks = 0;
+    while started.elapsed() < Duration::from_secs(1) {
+        notifier.keepalive()?;
+        ticks += 1;
+        thread::sleep(Duration::from_millis(20));
+    }
+    let pings = received(&systemd_side)
+        .iter()
+        .filter(|m| *m == "WATCHDOG=1")
+        .count();
+    println!("   {} loop ticks in 1 s, {} pings", ticks, pings);
+
+    notifier.notify(&[State::Stopping, State::Status("flushing data log")])?;
+    for message in received(&systemd_side) {
+        println!("   <- {:?}", message);
+    }
+
+    let _ = std::fs::remove_file(&path);
+    println!("\n=== End of systemd Integration Examples ===");
+    Ok(())
+}
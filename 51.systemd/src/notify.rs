@@ -0,0 +1,144 @@
+// Readiness and watchdog notifications (sd_notify)
+//
+// A `Type=notify` service tells systemd how it is doing by sending
+// newline-separated KEY=VALUE datagrams to the Unix socket named in
+// $NOTIFY_SOCKET (a path, or an abstract name starting with '@'):
+//
+//   READY=1              start-up finished; dependent units may start
+//   STATUS=...           one line shown by `systemctl status`
+//   WATCHDOG=1           still alive; needed within WatchdogSec=
+//   STOPPING=1           shutting down on purpose
+//
+// Without $NOTIFY_SOCKET every call is a cheap no-op.
+
+use std::fmt;
+use std::io;
+use std::os::unix::net::{SocketAddr, UnixDatagram};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum State<'a> {
+    Ready,
+    Reloading,
+    Stopping,
+    Status(&'a str),
+    Watchdog,
+    // Ask for more time than TimeoutStartSec= / TimeoutStopSec=
+    ExtendTimeout(Duration),
+    Errno(i32),
+}
+
+impl fmt::Display for State<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            State::Ready => write!(f, "READY=1"),
+            State::Reloading => write!(f, "RELOADING=1"),
+            State::Stopping => write!(f, "STOPPING=1"),
+            // One line only; a newline would start a new assignment
+            State::Status(s) => write!(f, "STATUS={}", s.replace('\n', " ")),
+            State::Watchdog => write!(f, "WATCHDOG=1"),
+            State::ExtendTimeout(d) => write!(f, "EXTEND_TIMEOUT_USEC={}", d.as_micros()),
+            State::Errno(e) => write!(f, "ERRNO={}", e),
+        }
+    }
+}
+
+fn socket_addr(socket: &str) -> io::Result<SocketAddr> {
+    match socket.strip_prefix('@') {
+        #[cfg(target_os = "linux")]
+        Some(name) => {
+            use std::os::linux::net::SocketAddrExt;
+            SocketAddr::from_abstract_name(name)
+        }
+        #[cfg(not(target_os = "linux"))]
+        Some(_) => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "abstract sockets are Linux-only",
+        )),
+        None => SocketAddr::from_pathname(socket),
+    }
+}
+
+#[derive(Debug)]
+pub struct Notifier {
+    target: Option<(UnixDatagram, SocketAddr)>,
+    watchdog: Option<Duration>,
+    last_ping: Option<Instant>,
+}
+
+impl Notifier {
+    // From $NOTIFY_SOCKET and $WATCHDOG_USEC; disabled when not run by
+    // systemd
+    pub fn from_env() -> io::Result<Notifier> {
+        let var = |name| std::env::var(name).ok();
+        let watchdog = Notifier::parse_watchdog(
+            var("WATCHDOG_USEC").as_deref(),
+            var("WATCHDOG_PID").as_deref(),
+            std::process::id(),
+        );
+        match var("NOTIFY_SOCKET") {
+            Some(socket) if !socket.is_empty() => Notifier::connect(&socket, watchdog),
+            _ => Ok(Notifier::disabled()),
+        }
+    }
+
+    // The watchdog timeout, if it applies to `my_pid`
+    pub fn parse_watchdog(usec: Option<&str>, pid: Option<&str>, my_pid: u32) -> Option<Duration> {
+        if pid.is_some_and(|p| p.parse() != Ok(my_pid)) {
+            return None;
+        }
+        let usec: u64 = usec?.parse().ok()?;
+        (usec > 0).then(|| Duration::from_micros(usec))
+    }
+
+    pub fn connect(socket: &str, watchdog: Option<Duration>) -> io::Result<Notifier> {
+        let addr = socket_addr(socket)?;
+        Ok(Notifier {
+            target: Some((UnixDatagram::unbound()?, addr)),
+            watchdog,
+            last_ping: None,
+        })
+    }
+
+    pub fn disabled() -> Notifier {
+        Notifier {
+            target: None,
+            watchdog: None,
+            last_ping: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.target.is_some()
+    }
+
+    // One datagram carrying every state; false if there is no one to tell
+    pub fn notify(&self, states: &[State]) -> io::Result<bool> {
+        let Some((socket, addr)) = &self.target else {
+            return Ok(false);
+        };
+        let message: Vec<String> = states.iter().map(State::to_string).collect();
+        socket.send_to_addr(message.join("\n").as_bytes(), addr)?;
+        Ok(true)
+    }
+
+    // Ping at half the timeout, as sd_watchdog_enabled(3) recommends, so
+    // one late loop iteration does not get the service killed
+    pub fn watchdog_interval(&self) -> Option<Duration> {
+        self.watchdog.map(|timeout| timeout / 2)
+    }
+
+    // Call from the main loop as often as convenient; sends WATCHDOG=1
+    // only when the interval has passed. Ping from the loop that does the
+    // work, not a helper thread, or a hung loop keeps looking alive.
+    pub fn keepalive(&mut self) -> io::Result<bool> {
+        let Some(interval) = self.watchdog_interval() else {
+            return Ok(false);
+        };
+        if self.last_ping.is_some_and(|at| at.elapsed() < interval) {
+            return Ok(false);
+        }
+        self.last_ping = Some(Instant::now());
+        self.notify(&[State::Watchdog])
+    }
+}
@@ -0,0 +1,141 @@
+// Reads and changes the process environment and fds 3 and 4, so it is the
+// only test in this binary
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::os::fd::{IntoRawFd, RawFd};
+use std::os::unix::net::{UnixDatagram, UnixListener};
+use std::time::Duration;
+use systemd::activation::LISTEN_FDS_START;
+use systemd::{take_listen_fds, tcp_listener_or_bind, ListenerSource, Notifier, State};
+
+fn fd_is_open(fd: RawFd) -> bool {
+    // SAFETY: probing an fd number
+    unsafe { libc::fcntl(fd, libc::F_GETFD) != -1 }
+}
+
+// Move an owned descriptor to `target`, as systemd arranges before exec
+fn move_to(raw: RawFd, target: RawFd) {
+    if raw != target {
+        // SAFETY: `raw` is ours and open; `target` was checked to be free
+        unsafe {
+            assert_eq!(libc::dup2(raw, target), target);
+            libc::close(raw);
+        }
+    }
+}
+
+#[test]
+fn activation_and_notification_through_the_environment() {
+    let pid = std::process::id().to_string();
+
+    // Run by hand: nothing passed, listeners are bound here
+    for name in [
+        "LISTEN_PID",
+        "LISTEN_FDS",
+        "LISTEN_FDNAMES",
+        "NOTIFY_SOCKET",
+    ] {
+        std::env::remove_var(name);
+    }
+    assert!(take_listen_fds().unwrap().is_empty());
+    let (listener, source) = tcp_listener_or_bind("127.0.0.1:0").unwrap();
+    assert_eq!(source, ListenerSource::Bound);
+    drop(listener);
+    assert!(!Notifier::from_env().unwrap().is_enabled());
+
+    // Variables for another process are ignored, and removed all the same
+    std::env::set_var("LISTEN_PID", "1");
+    std::env::set_var("LISTEN_FDS", "1");
+    assert!(take_listen_fds().unwrap().is_empty());
+    assert!(std::env::var_os("LISTEN_PID").is_none());
+
+    // Socket activation: a TCP listener at fd 3 and a Unix one at fd 4
+    let start = LISTEN_FDS_START;
+    assert!(
+        !fd_is_open(start) && !fd_is_open(start + 1),
+        "fds 3 and 4 must be free"
+    );
+    let tcp = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = tcp.local_addr().unwrap();
+    move_to(tcp.into_raw_fd(), start);
+    let path = std::env::temp_dir().join(format!("rust-sys-activation-{}.sock", pid));
+    let _ = std::fs::remove_file(&path);
+    let unix = UnixListener::bind(&path).unwrap();
+    move_to(unix.into_raw_fd(), start + 1);
+
+    std::env::set_var("LISTEN_PID", &pid);
+    std::env::set_var("LISTEN_FDS", "2");
+    std::env::set_var("LISTEN_FDNAMES", "status:control");
+    let fds = take_listen_fds().unwrap();
+    for name in ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"] {
+        assert!(std::env::var_os(name).is_none(), "{} left behind", name);
+    }
+    let names: Vec<&str> = fds.iter().map(|fd| fd.name()).collect();
+    assert_eq!(names, ["status", "control"]);
+    assert!(fds[0].is_tcp_listener());
+    assert!(!fds[1].is_tcp_listener());
+    // Not inherited by child processes
+    // SAFETY: probing an fd number
+    let flags = unsafe { libc::fcntl(start, libc::F_GETFD) };
+    assert_eq!(flags & libc::FD_CLOEXEC, libc::FD_CLOEXEC);
+
+    let mut fds = fds.into_iter();
+    let tcp = fds.next().unwrap().into_tcp_listener().unwrap();
+    let control = fds.next().unwrap();
+    let control = control.into_unix_listener().unwrap();
+    assert_eq!(tcp.local_addr().unwrap(), addr);
+    // The connection was possible before the descriptors were taken over
+    let mut client = TcpStream::connect(addr).unwrap();
+    client.write_all(b"GET /status").unwrap();
+    let (mut conn, _) = tcp.accept().unwrap();
+    let mut buf = [0u8; 11];
+    conn.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"GET /status");
+    drop((tcp, control, conn, client));
+    std::fs::remove_file(&path).unwrap();
+
+    // tcp_listener_or_bind prefers a passed listener over the address
+    let tcp = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = tcp.local_addr().unwrap();
+    move_to(tcp.into_raw_fd(), start);
+    std::env::set_var("LISTEN_PID", &pid);
+    std::env::set_var("LISTEN_FDS", "1");
+    std::env::set_var("LISTEN_FDNAMES", "status");
+    let (listener, source) = tcp_listener_or_bind("127.0.0.1:9").unwrap();
+    assert_eq!(source, ListenerSource::Activated("status".to_string()));
+    assert_eq!(listener.local_addr().unwrap(), addr);
+    drop(listener);
+
+    // A passed descriptor of the wrong kind is refused, not misused
+    let (a, _b) = UnixDatagram::pair().unwrap();
+    move_to(a.into_raw_fd(), start);
+    std::env::set_var("LISTEN_PID", &pid);
+    std::env::set_var("LISTEN_FDS", "1");
+    std::env::remove_var("LISTEN_FDNAMES");
+    let fd = take_listen_fds().unwrap().pop().unwrap();
+    assert_eq!(fd.name(), "unknown");
+    assert!(fd.into_tcp_listener().is_err());
+    assert!(!fd_is_open(start));
+
+    // Notifications with the variables systemd sets
+    let path = std::env::temp_dir().join(format!("rust-sys-notify-env-{}.sock", pid));
+    let _ = std::fs::remove_file(&path);
+    let systemd_side = UnixDatagram::bind(&path).unwrap();
+    std::env::set_var("NOTIFY_SOCKET", &path);
+    std::env::set_var("WATCHDOG_USEC", "200000");
+    std::env::set_var("WATCHDOG_PID", &pid);
+    let notifier = Notifier::from_env().unwrap();
+    assert!(notifier.is_enabled());
+    assert_eq!(
+        notifier.watchdog_interval(),
+        Some(Duration::from_millis(100))
+    );
+    notifier.notify(&[State::Ready]).unwrap();
+    let mut buf = [0u8; 64];
+    let n = systemd_side.recv(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"READY=1");
+    // An empty NOTIFY_SOCKET counts as unset
+    std::env::set_var("NOTIFY_SOCKET", "");
+    assert!(!Notifier::from_env().unwrap().is_enabled());
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,106 @@
+use std::os::unix::net::UnixDatagram;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+use systemd::{Notifier, State};
+
+// A bound datagram socket playing systemd's side
+fn systemd_side(name: &str) -> (UnixDatagram, PathBuf) {
+    let path = std::env::temp_dir().join(format!(
+        "rust-sys-notify-{}-{}.sock",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let socket = UnixDatagram::bind(&path).unwrap();
+    socket.set_nonblocking(true).unwrap();
+    (socket, path)
+}
+
+fn received(socket: &UnixDatagram) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut buf = [0u8; 4096];
+    while let Ok(n) = socket.recv(&mut buf) {
+        messages.push(String::from_utf8_lossy(&buf[..n]).into_owned());
+    }
+    messages
+}
+
+#[test]
+fn disabled_notifier_is_a_no_op() {
+    let mut notifier = Notifier::disabled();
+    assert!(!notifier.is_enabled());
+    assert!(!notifier.notify(&[State::Ready]).unwrap());
+    assert_eq!(notifier.watchdog_interval(), None);
+    assert!(!notifier.keepalive().unwrap());
+}
+
+#[test]
+fn states_share_one_datagram() {
+    let (socket, path) = systemd_side("datagram");
+    let notifier = Notifier::connect(path.to_str().unwrap(), None).unwrap();
+    assert!(notifier.is_enabled());
+    assert!(notifier
+        .notify(&[State::Ready, State::Status("3 sensors, 2 rules")])
+        .unwrap());
+    assert!(notifier.notify(&[State::Stopping]).unwrap());
+    assert_eq!(
+        received(&socket),
+        ["READY=1\nSTATUS=3 sensors, 2 rules", "STOPPING=1"]
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn abstract_socket_names() {
+    use std::os::linux::net::SocketAddrExt;
+    use std::os::unix::net::SocketAddr;
+
+    let name = format!("rust-sys-notify-abstract-{}", std::process::id());
+    let addr = SocketAddr::from_abstract_name(&name).unwrap();
+    let socket = UnixDatagram::bind_addr(&addr).unwrap();
+    socket.set_nonblocking(true).unwrap();
+    let notifier = Notifier::connect(&format!("@{}", name), None).unwrap();
+    notifier.notify(&[State::Watchdog]).unwrap();
+    assert_eq!(received(&socket), ["WATCHDOG=1"]);
+}
+
+#[test]
+fn sending_to_a_missing_socket_fails() {
+    let path = std::env::temp_dir().join(format!(
+        "rust-sys-notify-missing-{}.sock",
+        std::process::id()
+    ));
+    let notifier = Notifier::connect(path.to_str().unwrap(), None).unwrap();
+    assert!(notifier.notify(&[State::Ready]).is_err());
+}
+
+#[test]
+fn keepalive_pings_at_half_the_timeout() {
+    let (socket, path) = systemd_side("watchdog");
+    let mut notifier =
+        Notifier::connect(path.to_str().unwrap(), Some(Duration::from_millis(200))).unwrap();
+    assert_eq!(
+        notifier.watchdog_interval(),
+        Some(Duration::from_millis(100))
+    );
+    // The first call pings, calls within the interval do not
+    assert!(notifier.keepalive().unwrap());
+    assert!(!notifier.keepalive().unwrap());
+    assert!(!notifier.keepalive().unwrap());
+    thread::sleep(Duration::from_millis(120));
+    assert!(notifier.keepalive().unwrap());
+    assert!(!notifier.keepalive().unwrap());
+    assert_eq!(received(&socket), ["WATCHDOG=1", "WATCHDOG=1"]);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn no_watchdog_means_no_pings() {
+    let (socket, path) = systemd_side("no-watchdog");
+    let mut notifier = Notifier::connect(path.to_str().unwrap(), None).unwrap();
+    assert!(!notifier.keepalive().unwrap());
+    assert!(received(&socket).is_empty());
+    std::fs::remove_file(&path).unwrap();
+}
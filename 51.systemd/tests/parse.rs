@@ -0,0 +1,111 @@
+use std::time::Duration;
+use systemd::activation::Activation;
+use systemd::{ActivationError, Notifier, State};
+
+const ME: u32 = 4242;
+
+#[test]
+fn activation_for_this_process() {
+    assert_eq!(
+        Activation::parse(Some("4242"), Some("2"), Some("status:metrics"), ME).unwrap(),
+        Some(Activation {
+            count: 2,
+            names: vec!["status".to_string(), "metrics".to_string()],
+        })
+    );
+    // Unnamed descriptors get systemd's default name
+    assert_eq!(
+        Activation::parse(Some("4242"), Some("1"), None, ME).unwrap(),
+        Some(Activation {
+            count: 1,
+            names: vec!["unknown".to_string()],
+        })
+    );
+}
+
+#[test]
+fn activation_for_someone_else() {
+    // Inherited from an activated parent
+    assert_eq!(
+        Activation::parse(Some("1"), Some("1"), None, ME).unwrap(),
+        None
+    );
+    // Not activated at all, or only half the variables
+    assert_eq!(Activation::parse(None, None, None, ME).unwrap(), None);
+    assert_eq!(
+        Activation::parse(Some("4242"), None, None, ME).unwrap(),
+        None
+    );
+    assert_eq!(Activation::parse(None, Some("1"), None, ME).unwrap(), None);
+    // A count for another process is not even parsed
+    assert_eq!(
+        Activation::parse(Some("1"), Some("two"), None, ME).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn invalid_activation_variables() {
+    assert!(matches!(
+        Activation::parse(Some("me"), Some("1"), None, ME),
+        Err(ActivationError::Invalid { var: "LISTEN_PID", value }) if value == "me"
+    ));
+    assert!(matches!(
+        Activation::parse(Some("4242"), Some("two"), None, ME),
+        Err(ActivationError::Invalid { var: "LISTEN_FDS", value }) if value == "two"
+    ));
+    assert!(matches!(
+        Activation::parse(Some("4242"), Some("-1"), None, ME),
+        Err(ActivationError::Invalid {
+            var: "LISTEN_FDS",
+            ..
+        })
+    ));
+    let err = Activation::parse(Some("4242"), Some("2"), Some("status"), ME).unwrap_err();
+    assert!(matches!(
+        err,
+        ActivationError::NameCount { fds: 2, names: 1 }
+    ));
+    assert_eq!(err.to_string(), "LISTEN_FDNAMES has 1 names for 2 fds");
+}
+
+#[test]
+fn states_format_as_assignments() {
+    let cases = [
+        (State::Ready, "READY=1"),
+        (State::Reloading, "RELOADING=1"),
+        (State::Stopping, "STOPPING=1"),
+        (State::Watchdog, "WATCHDOG=1"),
+        (State::Status("3 sensors"), "STATUS=3 sensors"),
+        // A newline would start a second assignment
+        (
+            State::Status("line one\nline two"),
+            "STATUS=line one line two",
+        ),
+        (
+            State::ExtendTimeout(Duration::from_millis(1500)),
+            "EXTEND_TIMEOUT_USEC=1500000",
+        ),
+        (State::Errno(5), "ERRNO=5"),
+    ];
+    for (state, text) in cases {
+        assert_eq!(state.to_string(), text);
+    }
+}
+
+#[test]
+fn watchdog_variables() {
+    let timeout = Some(Duration::from_millis(200));
+    assert_eq!(Notifier::parse_watchdog(Some("200000"), None, ME), timeout);
+    assert_eq!(
+        Notifier::parse_watchdog(Some("200000"), Some("4242"), ME),
+        timeout
+    );
+    assert_eq!(
+        Notifier::parse_watchdog(Some("200000"), Some("1"), ME),
+        None
+    );
+    assert_eq!(Notifier::parse_watchdog(Some("0"), None, ME), None);
+    assert_eq!(Notifier::parse_watchdog(Some("soon"), None, ME), None);
+    assert_eq!(Notifier::parse_watchdog(None, Some("4242"), ME), None);
+}
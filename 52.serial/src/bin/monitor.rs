@@ -0,0 +1,84 @@
+// Print readings from a sensor node on a serial port
+//
+//   cargo run --bin monitor -- --list
+//   cargo run --bin monitor -- /dev/ttyUSB0 --line 115200,8N1
+//   cargo run --bin monitor -- mock --count 20
+//
+// With no port given it takes the first one the system reports, or the
+// mock sensor node when there is none.
+
+use serial::{open, FrameReader, LineSettings, PortConfig, Reading};
+use std::process::ExitCode;
+
+fn usage() -> ExitCode {
+    eprintln!("usage: monitor [PORT|mock] [--line 115200,8N1] [--count N] [--list]");
+    ExitCode::from(2)
+}
+
+fn main() -> ExitCode {
+    let mut path: Option<String> = None;
+    let mut line = LineSettings::default();
+    let mut count: Option<usize> = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--list" => {
+                for port in serialport::available_ports().unwrap_or_default() {
+                    println!("{} ({:?})", port.port_name, port.port_type);
+                }
+                return ExitCode::SUCCESS;
+            }
+            "--line" => match args.next().map(|s| s.parse()) {
+                Some(Ok(settings)) => line = settings,
+                Some(Err(e)) => {
+                    eprintln!("monitor: {}", e);
+                    return usage();
+                }
+                None => return usage(),
+            },
+            "--count" => match args.next().and_then(|s| s.parse().ok()) {
+                Some(n) => count = Some(n),
+                None => return usage(),
+            },
+            _ if path.is_none() && !arg.starts_with('-') => path = Some(arg),
+            _ => return usage(),
+        }
+    }
+
+    let path = path.unwrap_or_else(|| {
+        serialport::available_ports()
+            .ok()
+            .and_then(|ports| ports.into_iter().next())
+            .map_or_else(|| "mock".to_string(), |p| p.port_name)
+    });
+    let config = PortConfig::new(&path, line);
+    let port = match open(&config) {
+        Ok(port) => port,
+        Err(e) => {
+            eprintln!("monitor: {}: {}", path, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("monitor: {} at {}", path, line);
+
+    let mut reader = FrameReader::new(port);
+    let mut seen = 0;
+    while count.is_none_or(|n| seen < n) {
+        match reader.next_frame() {
+            Ok(Some(frame)) => {
+                seen += 1;
+                match Reading::parse(&frame.payload) {
+                    Some(r) => println!("node {} {:<11} {:>8.2}", r.sensor_id, r.metric, r.value),
+                    None => println!("frame {:?}", String::from_utf8_lossy(&frame.payload)),
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                eprintln!("monitor: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    println!("monitor: {:?}", reader.stats());
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,192 @@
+// Port settings
+//
+// Serial settings are usually written the way device datasheets print
+// them: `115200,8N1` is 115200 baud, 8 data bits, no parity, 1 stop bit.
+// Both ends must agree on all four; nothing on the wire negotiates them.
+
+use crate::mock::MockSerial;
+use serialport::{DataBits, FlowControl, Parity, SerialPort, StopBits};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    Format(String),
+    BaudRate(String),
+    DataBits(char),
+    Parity(char),
+    StopBits(char),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Format(s) => {
+                write!(f, "expected <baud>,<bits><parity><stop>, got {:?}", s)
+            }
+            ConfigError::BaudRate(s) => write!(f, "invalid baud rate {:?}", s),
+            ConfigError::DataBits(c) => write!(f, "data bits must be 5-8, got '{}'", c),
+            ConfigError::Parity(c) => write!(f, "parity must be N, E or O, got '{}'", c),
+            ConfigError::StopBits(c) => write!(f, "stop bits must be 1 or 2, got '{}'", c),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+// What both ends of the wire must agree on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineSettings {
+    pub baud_rate: u32,
+    pub data_bits: DataBits,
+    pub parity: Parity,
+    pub stop_bits: StopBits,
+}
+
+impl Default for LineSettings {
+    fn default() -> Self {
+        LineSettings {
+            baud_rate: 115_200,
+            data_bits: DataBits::Eight,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+        }
+    }
+}
+
+impl LineSettings {
+    // Wire time per byte: start bit, data, parity, stop bits
+    pub fn bits_per_byte(&self) -> u32 {
+        let data = match self.data_bits {
+            DataBits::Five => 5,
+            DataBits::Six => 6,
+            DataBits::Seven => 7,
+            DataBits::Eight => 8,
+        };
+        let parity = u32::from(self.parity != Parity::None);
+        let stop = match self.stop_bits {
+            StopBits::One => 1,
+            StopBits::Two => 2,
+        };
+        1 + data + parity + stop
+    }
+
+    pub fn bytes_per_second(&self) -> u32 {
+        self.baud_rate / self.bits_per_byte()
+    }
+}
+
+impl fmt::Display for LineSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parity = match self.parity {
+            Parity::None => 'N',
+            Parity::Even => 'E',
+            Parity::Odd => 'O',
+        };
+        let stop = match self.stop_bits {
+            StopBits::One => 1,
+            StopBits::Two => 2,
+        };
+        write!(
+            f,
+            "{},{}{}{}",
+            self.baud_rate,
+            u8::from(self.data_bits),
+            parity,
+            stop
+        )
+    }
+}
+
+impl FromStr for LineSettings {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<LineSettings, ConfigError> {
+        let (baud, mode) = s
+            .split_once(',')
+            .ok_or_else(|| ConfigError::Format(s.to_string()))?;
+        let baud_rate = baud
+            .trim()
+            .parse()
+            .ok()
+            .filter(|&b| b > 0)
+            .ok_or_else(|| ConfigError::BaudRate(baud.to_string()))?;
+        let mode: Vec<char> = mode.trim().chars().collect();
+        let [bits, parity, stop] = mode[..] else {
+            return Err(ConfigError::Format(s.to_string()));
+        };
+        let data_bits = match bits {
+            '5' => DataBits::Five,
+            '6' => DataBits::Six,
+            '7' => DataBits::Seven,
+            '8' => DataBits::Eight,
+            other => return Err(ConfigError::DataBits(other)),
+        };
+        let parity = match parity.to_ascii_uppercase() {
+            'N' => Parity::None,
+            'E' => Parity::Even,
+            'O' => Parity::Odd,
+            other => return Err(ConfigError::Parity(other)),
+        };
+        let stop_bits = match stop {
+            '1' => StopBits::One,
+            '2' => StopBits::Two,
+            other => return Err(ConfigError::StopBits(other)),
+        };
+        Ok(LineSettings {
+            baud_rate,
+            data_bits,
+            parity,
+            stop_bits,
+        })
+    }
+}
+
+// Everything needed to open a port. `path` is a device such as
+// `/dev/ttyUSB0` or `/dev/ttyACM0` (COM3 on Windows), or `mock` for the
+// simulated sensor node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortConfig {
+    pub path: String,
+    pub line: LineSettings,
+    pub flow_control: FlowControl,
+    // How long a read waits for the first byte before TimedOut
+    pub timeout: Duration,
+}
+
+impl PortConfig {
+    pub fn new(path: &str, line: LineSettings) -> PortConfig {
+        PortConfig {
+            path: path.to_string(),
+            line,
+            flow_control: FlowControl::None,
+            timeout: Duration::from_millis(100),
+        }
+    }
+
+    pub fn is_mock(&self) -> bool {
+        self.path == "mock"
+    }
+}
+
+// Open the real device, or the mock for `path = "mock"`. The mock's sensor
+// node talks at 115200,8N1 whatever the port is set to, like real hardware.
+pub fn open(config: &PortConfig) -> serialport::Result<Box<dyn SerialPort>> {
+    if config.is_mock() {
+        let mut port = MockSerial::sensor("mock", LineSettings::default(), 1);
+        port.set_baud_rate(config.line.baud_rate)?;
+        port.set_data_bits(config.line.data_bits)?;
+        port.set_parity(config.line.parity)?;
+        port.set_stop_bits(config.line.stop_bits)?;
+        port.set_timeout(config.timeout)?;
+        return Ok(Box::new(port));
+    }
+    serialport::new(&config.path, config.line.baud_rate)
+        .data_bits(config.line.data_bits)
+        .parity(config.line.parity)
+        .stop_bits(config.line.stop_bits)
+        .flow_control(config.flow_control)
+        .timeout(config.timeout)
+        .open()
+}
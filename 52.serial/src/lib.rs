@@ -0,0 +1,41 @@
+// Serial ports from Linux, with or without hardware
+//
+// Everything here talks to `Box<dyn serialport::SerialPort>`, the trait the
+// `serialport` crate returns for a real `/dev/ttyUSB0`. `MockSerial`
+// implements the same trait over an in-memory line with a simulated sensor
+// node on the far end, so the same reader code runs in CI and on a laptop
+// with nothing plugged in. Bytes from either go through the UART frame
+// parser from 06.uart_framing.
+
+pub mod config;
+pub mod mock;
+pub mod reader;
+
+pub use config::{open, ConfigError, LineSettings, PortConfig};
+pub use mock::{MockDevice, MockSerial};
+pub use reader::{send_frame, FrameReader};
+
+// What the sensor nodes put in a frame: `<id>:<metric>=<value>` in ASCII
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reading {
+    pub sensor_id: u16,
+    pub metric: String,
+    pub value: f64,
+}
+
+impl Reading {
+    pub fn parse(payload: &[u8]) -> Option<Reading> {
+        let text = std::str::from_utf8(payload).ok()?;
+        let (id, rest) = text.split_once(':')?;
+        let (metric, value) = rest.split_once('=')?;
+        Some(Reading {
+            sensor_id: id.parse().ok()?,
+            metric: metric.to_string(),
+            value: value.parse().ok()?,
+        })
+    }
+
+    pub fn to_payload(&self) -> Vec<u8> {
+        format!("{}:{}={:.2}", self.sensor_id, self.metric, self.value).into_bytes()
+    }
+}
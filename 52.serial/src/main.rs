@@ -0,0 +1,178 @@
+use serial::{open, send_frame, FrameReader, LineSettings, MockSerial, PortConfig, Reading};
+use serialport::{SerialPort, TTYPort};
+use std::io::Write;
+use std::thread;
+use std::time::{Duration, Instant};
+use uart_framing::{encode, FrameParser};
+
+// Up to `count` frames, giving up after 20 reads that completed none
+fn collect<R: std::io::Read>(reader: &mut FrameReader<R>, count: usize) -> Vec<Vec<u8>> {
+    let mut frames = Vec::new();
+    let mut quiet = 0;
+    while frames.len() < count && quiet < 20 {
+        match reader.next_frame() {
+            Ok(Some(frame)) => frames.push(frame.payload),
+            Ok(None) => quiet += 1,
+            Err(e) => {
+                println!("   read error: {}", e);
+                break;
+            }
+        }
+    }
+    frames
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Serial Port Examples ===\n");
+
+    // 1. What is plugged in
+    println!("1. Serial ports on this machine:");
+    match serialport::available_ports() {
+        Ok(ports) if ports.is_empty() => println!("   (none; the rest uses the mock)"),
+        Ok(ports) => {
+            for port in ports {
+                println!("   {} ({:?})", port.port_name, port.port_type);
+            }
+        }
+        Err(e) => println!("   cannot enumerate: {}", e),
+    }
+
+    // 2. Settings as datasheets write them
+    println!("\n2. Line settings:");
+    for text in [
+        "115200,8N1",
+        "9600,7E1",
+        "19200,8N2",
+        "9600,8X1",
+        "fast,8N1",
+    ] {
+        match text.parse::<LineSettings>() {
+            Ok(line) => println!(
+                "   {:<11} -> {:?}/{:?}/{:?}, {} bits per byte, {} bytes/s",
+                text,
+                line.data_bits,
+                line.parity,
+                line.stop_bits,
+                line.bits_per_byte(),
+                line.bytes_per_second()
+            ),
+            Err(e) => println!("   {:<11} -> {}", text, e),
+        }
+    }
+    let line: LineSettings = "115200,8N1".parse()?;
+    println!("   displayed back: {}", line);
+
+    // 3. The simulated sensor node, behind the same trait as a real port
+    println!("\n3. Mock sensor node as Box<dyn SerialPort>:");
+    let config = PortConfig::new("mock", line);
+    let port: Box<dyn SerialPort> = open(&config)?;
+    println!(
+        "   {} at {} baud, timeout {:?}",
+        port.name().unwrap_or_default(),
+        port.baud_rate()?,
+        port.timeout()
+    );
+    let mut reader = FrameReader::new(port);
+    let frames = collect(&mut reader, 12);
+    let readings: Vec<Reading> = frames.iter().filter_map(|f| Reading::parse(f)).collect();
+    for r in readings.iter().take(6) {
+        println!("   node {} {:<11} {:>6.2}", r.sensor_id, r.metric, r.value);
+    }
+    println!(
+        "   ... {} of {} frames are readings, parser stats {:?}",
+        readings.len(),
+        frames.len(),
+        reader.stats()
+    );
+
+    // 4. Both ends must agree; nothing negotiates the baud rate
+    println!("\n4. Wrong baud rate:");
+    let wrong = PortConfig::new("mock", "9600,8N1".parse()?);
+    let mut reader = FrameReader::new(open(&wrong)?);
+    let frames = collect(&mut reader, 5);
+    println!(
+        "   9600 against a 115200 node: {} frames, stats {:?}",
+        frames.len(),
+        reader.stats()
+    );
+
+    // 5. Talking back
+    println!("\n5. Request and response:");
+    let mut reader = FrameReader::new(open(&config)?);
+    for request in ["PING", "ID?", "REBOOT"] {
+        send_frame(reader.get_mut(), request.as_bytes())?;
+        // Readings keep streaming; skip them until the reply
+        let reply = collect(&mut reader, 20)
+            .into_iter()
+            .find(|f| Reading::parse(f).is_none());
+        println!(
+            "   {:<7} -> {:?}",
+            request,
+            reply.map(|r| String::from_utf8_lossy(&r).into_owned())
+        );
+    }
+
+    // 6. A device end driven by hand: partial reads, corruption, timeouts
+    println!("\n6. Scripted device:");
+    let (port, device) = MockSerial::new("scripted", line);
+    let mut reader = FrameReader::new(port);
+    let frame = encode(b"0:temperature=21.50").unwrap();
+    let mut corrupt = encode(b"LOST").unwrap();
+    *corrupt.last_mut().unwrap() ^= 0xFF;
+    device.send(&frame[..5]);
+    device.send(&corrupt);
+    device.send(&frame[5..]);
+    device.send(&frame);
+    let frames = collect(&mut reader, 2);
+    println!("   {} frame(s), stats {:?}", frames.len(), reader.stats());
+    send_frame(reader.get_mut(), b"ACK")?;
+    let mut parser = FrameParser::new();
+    for frame in parser.feed(&device.received()) {
+        println!(
+            "   device received {:?}",
+            String::from_utf8_lossy(&frame.payload)
+        );
+    }
+    let started = Instant::now();
+    let quiet = reader.next_frame()?;
+    println!(
+        "   silent line: {:?} after {:?}",
+        quiet,
+        Duration::from_millis(started.elapsed().as_millis() as u64)
+    );
+
+    // 7. The real serialport code path, on a pseudo-terminal pair
+    println!("\n7. Real port on a pseudo-terminal:");
+    match TTYPort::pair() {
+        Ok((mut device_end, host_end)) => {
+            let path = host_end.name().unwrap_or_default();
+            // Open by path like any /dev/ttyUSB*; baud is accepted but a pty
+            // ignores it
+            let port = open(&PortConfig::new(&path, line))?;
+            drop(host_end);
+            println!("   opened {} via serialport::new()", path);
+            let writer = thread::spawn(move || {
+                for i in 0..5u16 {
+                    let reading = Reading {
+                        sensor_id: i,
+                        metric: "temperature".to_string(),
+                        value: 20.0 + i as f64,
+                    };
+                    let _ = device_end.write_all(&encode(&reading.to_payload()).unwrap());
+                    thread::sleep(Duration::from_millis(10));
+                }
+                device_end
+            });
+            let mut reader = FrameReader::new(port);
+            let frames = collect(&mut reader, 5);
+            let _device_end = writer.join();
+            for f in &frames {
+                println!("   {}", String::from_utf8_lossy(f));
+            }
+        }
+        Err(e) => println!("   no pseudo-terminals here: {}", e),
+    }
+
+    println!("\n=== End of Serial Port Examples ===");
+    Ok(())
+}
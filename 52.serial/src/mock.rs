@@ -0,0 +1,353 @@
+// In-memory serial port
+//
+// `MockSerial` implements `serialport::SerialPort`, so it goes wherever a
+// `Box<dyn SerialPort>` does. Behind it is a line shared with the far end,
+// which is either
+//
+// - a `MockDevice` that the caller drives by hand (`MockSerial::new`), or
+// - a simulated sensor node (`MockSerial::sensor`) that streams framed
+//   readings with a little line noise and answers `PING` and `ID?` frames.
+//
+// The far end has its own line settings. If the port is configured
+// differently, every byte is garbled, as a mismatched UART garbles it (not
+// bit-exact, but just as unreadable). Configuration mistakes therefore
+// show up in the mock as well.
+
+use crate::config::LineSettings;
+use crate::Reading;
+use serialport::{
+    ClearBuffer, DataBits, Error, ErrorKind, FlowControl, Parity, SerialPort, StopBits,
+};
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+use uart_framing::{encode, FrameParser};
+
+const METRICS: [&str; 2] = ["temperature", "humidity"];
+
+// A simulated node: three sensors, two metrics, now and then some noise
+struct SensorNode {
+    rng: u64,
+    seq: u64,
+    requests: FrameParser,
+}
+
+impl SensorNode {
+    fn new(seed: u64) -> SensorNode {
+        SensorNode {
+            rng: seed,
+            seq: 0,
+            requests: FrameParser::new(),
+        }
+    }
+
+    fn random(&mut self) -> u64 {
+        self.rng = self
+            .rng
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.rng >> 33
+    }
+
+    // What the node sends next: one framed reading, sometimes after a
+    // few bytes of noise
+    fn next_burst(&mut self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        if self.random().is_multiple_of(5) {
+            for _ in 0..1 + self.random() % 3 {
+                bytes.push(0x20 + (self.random() % 0x50) as u8);
+            }
+        }
+        let sensor_id = (self.seq % 3) as u16;
+        let metric = METRICS[(self.seq / 3 % 2) as usize];
+        let base = if metric == "temperature" { 21.0 } else { 48.0 };
+        let jitter = (self.random() % 200) as f64 / 100.0 - 1.0;
+        let reading = Reading {
+            sensor_id,
+            metric: metric.to_string(),
+            value: base + sensor_id as f64 + jitter,
+        };
+        bytes.extend(encode(&reading.to_payload()).expect("short payload"));
+        self.seq += 1;
+        bytes
+    }
+
+    // Replies to any complete request frames in `bytes`
+    fn answer(&mut self, bytes: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for frame in self.requests.feed(bytes) {
+            let reply: &[u8] = match frame.payload.as_slice() {
+                b"PING" => b"PONG",
+                b"ID?" => b"ID=th-node-7 fw=1.4.2",
+                _ => b"ERR=unknown command",
+            };
+            out.extend(encode(reply).expect("short reply"));
+        }
+        out
+    }
+}
+
+struct Line {
+    to_host: VecDeque<u8>,
+    from_host: Vec<u8>,
+    // The far end's settings
+    wire: LineSettings,
+    sensor: Option<SensorNode>,
+}
+
+type Shared = Arc<(Mutex<Line>, Condvar)>;
+
+fn garble(byte: u8) -> u8 {
+    byte.rotate_left(3) ^ 0xA5
+}
+
+pub struct MockSerial {
+    name: String,
+    line: Shared,
+    settings: LineSettings,
+    flow_control: FlowControl,
+    timeout: Duration,
+    rts: bool,
+    dtr: bool,
+}
+
+// The far end of a `MockSerial::new` line
+pub struct MockDevice {
+    line: Shared,
+}
+
+impl MockDevice {
+    // Bytes the device puts on the wire, for the port to read
+    pub fn send(&self, bytes: &[u8]) {
+        let (lock, ready) = &*self.line;
+        lock.lock().unwrap().to_host.extend(bytes);
+        ready.notify_all();
+    }
+
+    // Everything the port has written since the last call
+    pub fn received(&self) -> Vec<u8> {
+        std::mem::take(&mut self.line.0.lock().unwrap().from_host)
+    }
+}
+
+impl MockSerial {
+    fn with_line(name: &str, wire: LineSettings, sensor: Option<SensorNode>) -> MockSerial {
+        let line = Line {
+            to_host: VecDeque::new(),
+            from_host: Vec::new(),
+            wire,
+            sensor,
+        };
+        MockSerial {
+            name: name.to_string(),
+            line: Arc::new((Mutex::new(line), Condvar::new())),
+            settings: wire,
+            flow_control: FlowControl::None,
+            timeout: Duration::from_millis(100),
+            rts: false,
+            dtr: false,
+        }
+    }
+
+    // A port and the device end to drive by hand, both at `wire`
+    pub fn new(name: &str, wire: LineSettings) -> (MockSerial, MockDevice) {
+        let port = MockSerial::with_line(name, wire, None);
+        let device = MockDevice {
+            line: Arc::clone(&port.line),
+        };
+        (port, device)
+    }
+
+    // A port with a simulated sensor node on the far end, talking at `wire`
+    pub fn sensor(name: &str, wire: LineSettings, seed: u64) -> MockSerial {
+        MockSerial::with_line(name, wire, Some(SensorNode::new(seed)))
+    }
+
+    fn mismatched(&self, line: &Line) -> bool {
+        self.settings != line.wire
+    }
+}
+
+impl Read for MockSerial {
+    // Like a real port: whatever is buffered, or wait up to the timeout
+    // for the first byte
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let (lock, ready) = &*self.line;
+        let mut guard = lock.lock().unwrap();
+        let line = &mut *guard;
+        if line.to_host.is_empty() {
+            if let Some(sensor) = &mut line.sensor {
+                line.to_host.extend(sensor.next_burst());
+            }
+        }
+        let deadline = Instant::now() + self.timeout;
+        while guard.to_host.is_empty() {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "read timed out"));
+            }
+            guard = ready.wait_timeout(guard, left).unwrap().0;
+        }
+        let garbled = self.mismatched(&guard);
+        let n = buf.len().min(guard.to_host.len());
+        for (slot, byte) in buf.iter_mut().zip(guard.to_host.drain(..n)) {
+            *slot = if garbled { garble(byte) } else { byte };
+        }
+        Ok(n)
+    }
+}
+
+impl Write for MockSerial {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let (lock, ready) = &*self.line;
+        let mut guard = lock.lock().unwrap();
+        let bytes: Vec<u8> = if self.mismatched(&guard) {
+            buf.iter().map(|&b| garble(b)).collect()
+        } else {
+            buf.to_vec()
+        };
+        let line = &mut *guard;
+        match &mut line.sensor {
+            Some(sensor) => line.to_host.extend(sensor.answer(&bytes)),
+            None => line.from_host.extend(&bytes),
+        }
+        ready.notify_all();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SerialPort for MockSerial {
+    fn name(&self) -> Option<String> {
+        Some(self.name.clone())
+    }
+
+    fn baud_rate(&self) -> serialport::Result<u32> {
+        Ok(self.settings.baud_rate)
+    }
+
+    fn data_bits(&self) -> serialport::Result<DataBits> {
+        Ok(self.settings.data_bits)
+    }
+
+    fn flow_control(&self) -> serialport::Result<FlowControl> {
+        Ok(self.flow_control)
+    }
+
+    fn parity(&self) -> serialport::Result<Parity> {
+        Ok(self.settings.parity)
+    }
+
+    fn stop_bits(&self) -> serialport::Result<StopBits> {
+        Ok(self.settings.stop_bits)
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> serialport::Result<()> {
+        if baud_rate == 0 {
+            return Err(Error::new(ErrorKind::InvalidInput, "baud rate 0"));
+        }
+        self.settings.baud_rate = baud_rate;
+        Ok(())
+    }
+
+    fn set_data_bits(&mut self, data_bits: DataBits) -> serialport::Result<()> {
+        self.settings.data_bits = data_bits;
+        Ok(())
+    }
+
+    fn set_flow_control(&mut self, flow_control: FlowControl) -> serialport::Result<()> {
+        self.flow_control = flow_control;
+        Ok(())
+    }
+
+    fn set_parity(&mut self, parity: Parity) -> serialport::Result<()> {
+        self.settings.parity = parity;
+        Ok(())
+    }
+
+    fn set_stop_bits(&mut self, stop_bits: StopBits) -> serialport::Result<()> {
+        self.settings.stop_bits = stop_bits;
+        Ok(())
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> serialport::Result<()> {
+        self.timeout = timeout;
+        Ok(())
+    }
+
+    fn write_request_to_send(&mut self, level: bool) -> serialport::Result<()> {
+        self.rts = level;
+        Ok(())
+    }
+
+    fn write_data_terminal_ready(&mut self, level: bool) -> serialport::Result<()> {
+        self.dtr = level;
+        Ok(())
+    }
+
+    // The far end is always ready: CTS follows our RTS, DSR our DTR
+    fn read_clear_to_send(&mut self) -> serialport::Result<bool> {
+        Ok(self.rts)
+    }
+
+    fn read_data_set_ready(&mut self) -> serialport::Result<bool> {
+        Ok(self.dtr)
+    }
+
+    fn read_ring_indicator(&mut self) -> serialport::Result<bool> {
+        Ok(false)
+    }
+
+    fn read_carrier_detect(&mut self) -> serialport::Result<bool> {
+        Ok(true)
+    }
+
+    fn bytes_to_read(&self) -> serialport::Result<u32> {
+        Ok(self.line.0.lock().unwrap().to_host.len() as u32)
+    }
+
+    fn bytes_to_write(&self) -> serialport::Result<u32> {
+        Ok(0)
+    }
+
+    fn clear(&self, buffer_to_clear: ClearBuffer) -> serialport::Result<()> {
+        let mut line = self.line.0.lock().unwrap();
+        if matches!(buffer_to_clear, ClearBuffer::Input | ClearBuffer::All) {
+            line.to_host.clear();
+        }
+        if matches!(buffer_to_clear, ClearBuffer::Output | ClearBuffer::All) {
+            line.from_host.clear();
+        }
+        Ok(())
+    }
+
+    // Same line, same settings; like a real clone, reads and writes on
+    // either handle share the wire
+    fn try_clone(&self) -> serialport::Result<Box<dyn SerialPort>> {
+        Ok(Box::new(MockSerial {
+            name: self.name.clone(),
+            line: Arc::clone(&self.line),
+            settings: self.settings,
+            flow_control: self.flow_control,
+            timeout: self.timeout,
+            rts: self.rts,
+            dtr: self.dtr,
+        }))
+    }
+
+    fn set_break(&self) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn clear_break(&self) -> serialport::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+// Frames from a byte stream
+//
+// A `read()` on a serial port returns whatever bytes have arrived: half a
+// frame, three frames, or nothing before the timeout. `FrameReader` feeds
+// each read into the 06.uart_framing parser and queues what comes out; a
+// read without a whole frame is `Ok(None)`, so the caller's loop can do
+// other work between frames.
+
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use uart_framing::{encode, Frame, FrameParser, ParserStats};
+
+pub struct FrameReader<R> {
+    port: R,
+    parser: FrameParser,
+    ready: VecDeque<Frame>,
+    buf: [u8; 256],
+}
+
+impl<R: Read> FrameReader<R> {
+    pub fn new(port: R) -> FrameReader<R> {
+        FrameReader {
+            port,
+            parser: FrameParser::new(),
+            ready: VecDeque::new(),
+            buf: [0; 256],
+        }
+    }
+
+    // The next complete frame, or None if one read brought none: timeout,
+    // end of stream, or only part of a frame. At most one read() per call,
+    // so a line full of noise cannot keep the caller stuck in here.
+    pub fn next_frame(&mut self) -> io::Result<Option<Frame>> {
+        if let Some(frame) = self.ready.pop_front() {
+            return Ok(Some(frame));
+        }
+        let n = match self.port.read(&mut self.buf) {
+            Ok(n) => n,
+            Err(e)
+                if e.kind() == io::ErrorKind::TimedOut
+                    || e.kind() == io::ErrorKind::Interrupted =>
+            {
+                0
+            }
+            Err(e) => return Err(e),
+        };
+        self.ready.extend(self.parser.feed(&self.buf[..n]));
+        Ok(self.ready.pop_front())
+    }
+
+    pub fn stats(&self) -> ParserStats {
+        self.parser.stats()
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.port
+    }
+}
+
+// Frame a payload and write it out completely
+pub fn send_frame<W: Write + ?Sized>(port: &mut W, payload: &[u8]) -> io::Result<()> {
+    let bytes = encode(payload).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("payload of {} bytes cannot be framed", payload.len()),
+        )
+    })?;
+    port.write_all(&bytes)?;
+    port.flush()
+}
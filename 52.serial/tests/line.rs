@@ -0,0 +1,108 @@
+use serial::{ConfigError, LineSettings, PortConfig, Reading};
+use serialport::{DataBits, FlowControl, Parity, StopBits};
+use std::time::Duration;
+
+#[test]
+fn datasheet_notation_parses() {
+    let line: LineSettings = "9600,7E1".parse().unwrap();
+    assert_eq!(
+        line,
+        LineSettings {
+            baud_rate: 9600,
+            data_bits: DataBits::Seven,
+            parity: Parity::Even,
+            stop_bits: StopBits::One,
+        }
+    );
+    // Lower-case parity and spaces around the parts are fine
+    let line: LineSettings = " 19200 , 8o2 ".parse().unwrap();
+    assert_eq!(line.parity, Parity::Odd);
+    assert_eq!(line.stop_bits, StopBits::Two);
+    assert_eq!(
+        "115200,8N1".parse::<LineSettings>(),
+        Ok(LineSettings::default())
+    );
+}
+
+#[test]
+fn display_round_trips() {
+    for text in ["115200,8N1", "9600,7E1", "19200,8O2", "300,5N1"] {
+        let line: LineSettings = text.parse().unwrap();
+        assert_eq!(line.to_string(), text);
+    }
+}
+
+#[test]
+fn each_mistake_names_its_field() {
+    for (text, err) in [
+        ("115200", ConfigError::Format("115200".to_string())),
+        ("115200,8N", ConfigError::Format("115200,8N".to_string())),
+        (
+            "115200,8N11",
+            ConfigError::Format("115200,8N11".to_string()),
+        ),
+        ("fast,8N1", ConfigError::BaudRate("fast".to_string())),
+        ("0,8N1", ConfigError::BaudRate("0".to_string())),
+        ("9600,9N1", ConfigError::DataBits('9')),
+        ("9600,8X1", ConfigError::Parity('X')),
+        ("9600,8N3", ConfigError::StopBits('3')),
+    ] {
+        assert_eq!(text.parse::<LineSettings>(), Err(err), "{}", text);
+    }
+    assert_eq!(
+        ConfigError::Parity('X').to_string(),
+        "parity must be N, E or O, got 'X'"
+    );
+}
+
+#[test]
+fn wire_time_counts_start_parity_and_stop_bits() {
+    let bits = |text: &str| text.parse::<LineSettings>().unwrap().bits_per_byte();
+    assert_eq!(bits("9600,8N1"), 10);
+    assert_eq!(bits("9600,7E1"), 10);
+    assert_eq!(bits("9600,8E2"), 12);
+    assert_eq!(bits("9600,5N1"), 7);
+    assert_eq!(LineSettings::default().bytes_per_second(), 11_520);
+    assert_eq!(
+        "9600,8E2"
+            .parse::<LineSettings>()
+            .unwrap()
+            .bytes_per_second(),
+        800
+    );
+}
+
+#[test]
+fn port_config_defaults() {
+    let config = PortConfig::new("/dev/ttyUSB0", LineSettings::default());
+    assert_eq!(config.flow_control, FlowControl::None);
+    assert_eq!(config.timeout, Duration::from_millis(100));
+    assert!(!config.is_mock());
+    assert!(PortConfig::new("mock", LineSettings::default()).is_mock());
+}
+
+#[test]
+fn readings_round_trip_through_payloads() {
+    let reading = Reading {
+        sensor_id: 2,
+        metric: "humidity".to_string(),
+        value: 48.126,
+    };
+    assert_eq!(reading.to_payload(), b"2:humidity=48.13");
+    assert_eq!(
+        Reading::parse(b"2:humidity=48.13"),
+        Some(Reading {
+            value: 48.13,
+            ..reading
+        })
+    );
+    for bad in [
+        &b"PONG"[..],
+        b"x:temperature=1",
+        b"1:temperature",
+        b"1:t=warm",
+        b"\xff:t=1",
+    ] {
+        assert_eq!(Reading::parse(bad), None, "{:?}", bad);
+    }
+}
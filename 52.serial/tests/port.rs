@@ -0,0 +1,206 @@
+// The reader against the mock's sensor node, a mock device driven by
+// hand, and a real `serialport` TTY on a pseudo-terminal pair
+
+use serial::{open, send_frame, FrameReader, LineSettings, MockSerial, PortConfig, Reading};
+use serialport::{SerialPort, TTYPort};
+use std::io::{Read, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+use uart_framing::{encode, FrameParser, MAX_PAYLOAD};
+
+// Up to `count` frames, giving up after 20 reads that completed none
+fn collect<R: Read>(reader: &mut FrameReader<R>, count: usize) -> Vec<Vec<u8>> {
+    let mut frames = Vec::new();
+    let mut quiet = 0;
+    while frames.len() < count && quiet < 20 {
+        match reader.next_frame().unwrap() {
+            Some(frame) => frames.push(frame.payload),
+            None => quiet += 1,
+        }
+    }
+    frames
+}
+
+fn mock() -> PortConfig {
+    PortConfig::new("mock", LineSettings::default())
+}
+
+#[test]
+fn open_applies_the_config_to_the_mock() {
+    let mut config = PortConfig::new("mock", "9600,7E2".parse().unwrap());
+    config.timeout = Duration::from_millis(5);
+    let port = open(&config).unwrap();
+    assert_eq!(port.name().as_deref(), Some("mock"));
+    assert_eq!(port.baud_rate().unwrap(), 9600);
+    assert_eq!(port.data_bits().unwrap(), serialport::DataBits::Seven);
+    assert_eq!(port.parity().unwrap(), serialport::Parity::Even);
+    assert_eq!(port.stop_bits().unwrap(), serialport::StopBits::Two);
+    assert_eq!(port.timeout(), Duration::from_millis(5));
+}
+
+#[test]
+fn the_sensor_node_streams_readings_round_robin() {
+    let mut reader = FrameReader::new(open(&mock()).unwrap());
+    let frames = collect(&mut reader, 12);
+    assert_eq!(frames.len(), 12);
+    let readings: Vec<Reading> = frames.iter().filter_map(|f| Reading::parse(f)).collect();
+    assert_eq!(readings.len(), 12);
+    for (n, r) in readings.iter().enumerate() {
+        assert_eq!(r.sensor_id, (n % 3) as u16);
+        let (metric, base) = match n / 3 % 2 {
+            0 => ("temperature", 21.0),
+            _ => ("humidity", 48.0),
+        };
+        assert_eq!(r.metric, metric);
+        assert!(
+            (r.value - base - r.sensor_id as f64).abs() <= 1.0,
+            "{:?}",
+            r
+        );
+    }
+    // The node's noise only ever falls between frames
+    assert_eq!(reader.stats().bad_checksum, 0);
+}
+
+#[test]
+fn a_wrong_baud_rate_reads_only_garbage() {
+    let wrong = PortConfig::new("mock", "9600,8N1".parse().unwrap());
+    let mut reader = FrameReader::new(open(&wrong).unwrap());
+    assert!(collect(&mut reader, 5).is_empty());
+    assert!(reader.stats().noise_bytes > 0);
+}
+
+#[test]
+fn the_node_answers_requests_between_readings() {
+    let mut reader = FrameReader::new(open(&mock()).unwrap());
+    for (request, reply) in [
+        ("PING", &b"PONG"[..]),
+        ("ID?", b"ID=th-node-7 fw=1.4.2"),
+        ("REBOOT", b"ERR=unknown command"),
+    ] {
+        send_frame(reader.get_mut(), request.as_bytes()).unwrap();
+        let got = collect(&mut reader, 20)
+            .into_iter()
+            .find(|f| Reading::parse(f).is_none());
+        assert_eq!(got.as_deref(), Some(reply), "{}", request);
+    }
+}
+
+#[test]
+fn frames_survive_any_read_boundaries() {
+    let (mut port, device) = MockSerial::new("scripted", LineSettings::default());
+    port.set_timeout(Duration::from_millis(5)).unwrap();
+    let mut reader = FrameReader::new(port);
+    let frame = encode(b"0:temperature=21.50").unwrap();
+    for byte in &frame {
+        device.send(std::slice::from_ref(byte));
+    }
+    device.send(&[frame.clone(), frame.clone()].concat());
+    let frames = collect(&mut reader, 3);
+    assert_eq!(frames, vec![b"0:temperature=21.50".to_vec(); 3]);
+}
+
+#[test]
+fn a_split_frame_is_lost_to_interleaved_junk() {
+    let (mut port, device) = MockSerial::new("scripted", LineSettings::default());
+    port.set_timeout(Duration::from_millis(5)).unwrap();
+    let mut reader = FrameReader::new(port);
+    let frame = encode(b"0:temperature=21.50").unwrap();
+    let mut corrupt = encode(b"LOST").unwrap();
+    *corrupt.last_mut().unwrap() ^= 0xFF;
+    device.send(&frame[..5]);
+    device.send(&corrupt);
+    device.send(&frame[5..]);
+    device.send(&frame);
+    // Only the last, whole copy comes through
+    assert_eq!(collect(&mut reader, 2), [b"0:temperature=21.50"]);
+    let stats = reader.stats();
+    assert!(stats.bad_length + stats.bad_checksum > 0, "{:?}", stats);
+}
+
+#[test]
+fn sent_frames_reach_the_device() {
+    let (port, device) = MockSerial::new("scripted", LineSettings::default());
+    let mut reader = FrameReader::new(port);
+    send_frame(reader.get_mut(), b"ACK").unwrap();
+    send_frame(reader.get_mut(), &[b'x'; MAX_PAYLOAD]).unwrap();
+    let written = FrameParser::new().feed(&device.received());
+    let payloads: Vec<&[u8]> = written.iter().map(|f| f.payload.as_slice()).collect();
+    assert_eq!(payloads, [&b"ACK"[..], &[b'x'; MAX_PAYLOAD]]);
+    assert!(device.received().is_empty());
+
+    // Nothing goes on the wire for a payload the format cannot carry
+    for payload in [&[][..], &[b'x'; MAX_PAYLOAD + 1]] {
+        let err = send_frame(reader.get_mut(), payload).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+    assert!(device.received().is_empty());
+}
+
+#[test]
+fn a_silent_line_times_out_as_none() {
+    let (mut port, _device) = MockSerial::new("scripted", LineSettings::default());
+    port.set_timeout(Duration::from_millis(20)).unwrap();
+    let mut reader = FrameReader::new(port);
+    let started = Instant::now();
+    assert!(reader.next_frame().unwrap().is_none());
+    assert!(started.elapsed() >= Duration::from_millis(20));
+}
+
+#[test]
+fn control_lines_and_clones_share_the_wire() {
+    let (mut port, device) = MockSerial::new("scripted", LineSettings::default());
+    port.write_request_to_send(true).unwrap();
+    assert!(port.read_clear_to_send().unwrap());
+    assert!(!port.read_data_set_ready().unwrap());
+
+    let mut clone = port.try_clone().unwrap();
+    device.send(b"abc");
+    assert_eq!(clone.bytes_to_read().unwrap(), 3);
+    let mut buf = [0u8; 8];
+    assert_eq!(port.read(&mut buf).unwrap(), 3);
+    assert_eq!(clone.bytes_to_read().unwrap(), 0);
+    clone.write_all(b"xyz").unwrap();
+    assert_eq!(device.received(), b"xyz");
+
+    device.send(b"stale");
+    port.clear(serialport::ClearBuffer::Input).unwrap();
+    assert_eq!(port.bytes_to_read().unwrap(), 0);
+    assert!(port.set_baud_rate(0).is_err());
+}
+
+#[test]
+fn frames_through_a_pseudo_terminal() {
+    let Ok((mut device_end, host_end)) = TTYPort::pair() else {
+        eprintln!("skipped, no pseudo-terminals here");
+        return;
+    };
+    let path = host_end.name().unwrap();
+    // Opened by path like any /dev/ttyUSB*; a pty ignores the baud rate
+    let port = open(&PortConfig::new(&path, LineSettings::default())).unwrap();
+    drop(host_end);
+    let sent: Vec<Reading> = (0..5u16)
+        .map(|i| Reading {
+            sensor_id: i,
+            metric: "temperature".to_string(),
+            value: 20.0 + i as f64,
+        })
+        .collect();
+    let writer = {
+        let sent = sent.clone();
+        thread::spawn(move || {
+            for reading in &sent {
+                device_end
+                    .write_all(&encode(&reading.to_payload()).unwrap())
+                    .unwrap();
+                thread::sleep(Duration::from_millis(5));
+            }
+            device_end
+        })
+    };
+    let mut reader = FrameReader::new(port);
+    let frames = collect(&mut reader, 5);
+    let _device_end = writer.join().unwrap();
+    let got: Vec<Reading> = frames.iter().filter_map(|f| Reading::parse(f)).collect();
+    assert_eq!(got, sent);
+}
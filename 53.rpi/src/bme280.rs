@@ -0,0 +1,277 @@
+// BME280 temperature, pressure and humidity sensor over I2C
+//
+// The driver only knows `embedded_hal::i2c::I2c` and `DelayNs`, so the
+// same code runs on rppal's `/dev/i2c-1`, on a microcontroller HAL, or on
+// the mock bus in mock.rs.
+//
+// Every sensor leaves the factory with its own trimming constants in
+// registers 0x88..0xA1 and 0xE1..0xE7. Raw ADC counts mean nothing
+// without them; the compensation formulas below are Bosch's integer
+// versions from the datasheet, so results match the reference bit for bit.
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+use std::fmt;
+
+// SDO pin to GND / to VDD
+pub const ADDRESS_PRIMARY: u8 = 0x76;
+pub const ADDRESS_SECONDARY: u8 = 0x77;
+
+pub const CHIP_ID: u8 = 0x60;
+
+pub mod reg {
+    pub const CALIB_00: u8 = 0x88;
+    pub const ID: u8 = 0xD0;
+    pub const RESET: u8 = 0xE0;
+    pub const CALIB_26: u8 = 0xE1;
+    pub const CTRL_HUM: u8 = 0xF2;
+    pub const STATUS: u8 = 0xF3;
+    pub const CTRL_MEAS: u8 = 0xF4;
+    pub const CONFIG: u8 = 0xF5;
+    pub const PRESS_MSB: u8 = 0xF7;
+
+    pub const RESET_COMMAND: u8 = 0xB6;
+    // STATUS bit 3: a conversion is running
+    pub const STATUS_MEASURING: u8 = 0x08;
+    // CTRL_MEAS: osrs_t 001 (x1), osrs_p 001 (x1), mode 01 (forced)
+    pub const MEAS_FORCED_X1: u8 = 0b0010_0101;
+    // CTRL_HUM: humidity x1
+    pub const HUM_X1: u8 = 0b001;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error<E> {
+    I2c(E),
+    // Something answered, but it is not a BME280 (0x58 is a BMP280)
+    ChipId(u8),
+    // STATUS still said "measuring" after the longest conversion time
+    Timeout,
+}
+
+impl<E: fmt::Debug> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::I2c(e) => write!(f, "i2c: {:?}", e),
+            Error::ChipId(id) => write!(
+                f,
+                "chip id 0x{:02X}, expected 0x{:02X} (BME280)",
+                id, CHIP_ID
+            ),
+            Error::Timeout => write!(f, "measurement did not finish"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> std::error::Error for Error<E> {}
+
+// Factory trimming constants; names follow the datasheet's dig_T1 ..
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Calibration {
+    pub t1: u16,
+    pub t2: i16,
+    pub t3: i16,
+    pub p1: u16,
+    pub p2: i16,
+    pub p3: i16,
+    pub p4: i16,
+    pub p5: i16,
+    pub p6: i16,
+    pub p7: i16,
+    pub p8: i16,
+    pub p9: i16,
+    pub h1: u8,
+    pub h2: i16,
+    pub h3: u8,
+    pub h4: i16,
+    pub h5: i16,
+    pub h6: i8,
+}
+
+impl Calibration {
+    // `a` is 0x88..=0xA1, `b` is 0xE1..=0xE7. Little-endian, except H4
+    // and H5, which share a byte as 12-bit halves.
+    pub fn parse(a: &[u8; 26], b: &[u8; 7]) -> Calibration {
+        let u = |i: usize| u16::from_le_bytes([a[i], a[i + 1]]);
+        let s = |i: usize| i16::from_le_bytes([a[i], a[i + 1]]);
+        Calibration {
+            t1: u(0),
+            t2: s(2),
+            t3: s(4),
+            p1: u(6),
+            p2: s(8),
+            p3: s(10),
+            p4: s(12),
+            p5: s(14),
+            p6: s(16),
+            p7: s(18),
+            p8: s(20),
+            p9: s(22),
+            h1: a[25],
+            h2: i16::from_le_bytes([b[0], b[1]]),
+            h3: b[2],
+            h4: ((b[3] as i8 as i16) << 4) | (b[4] & 0x0F) as i16,
+            h5: ((b[5] as i8 as i16) << 4) | (b[4] >> 4) as i16,
+            h6: b[6] as i8,
+        }
+    }
+
+    // Returns (hundredths of °C, t_fine). t_fine carries the temperature
+    // into the pressure and humidity formulas.
+    pub fn temperature(&self, adc_t: i32) -> (i32, i32) {
+        let adc_t = adc_t as i64;
+        let (t1, t2, t3) = (self.t1 as i64, self.t2 as i64, self.t3 as i64);
+        let var1 = (((adc_t >> 3) - (t1 << 1)) * t2) >> 11;
+        let var2 = (((((adc_t >> 4) - t1) * ((adc_t >> 4) - t1)) >> 12) * t3) >> 14;
+        let t_fine = (var1 + var2) as i32;
+        ((t_fine * 5 + 128) >> 8, t_fine)
+    }
+
+    // Pascal in Q24.8 (divide by 256)
+    pub fn pressure(&self, adc_p: i32, t_fine: i32) -> u32 {
+        let mut var1 = t_fine as i64 - 128_000;
+        let mut var2 = var1 * var1 * self.p6 as i64;
+        var2 += (var1 * self.p5 as i64) << 17;
+        var2 += (self.p4 as i64) << 35;
+        var1 = ((var1 * var1 * self.p3 as i64) >> 8) + ((var1 * self.p2 as i64) << 12);
+        var1 = (((1i64 << 47) + var1) * self.p1 as i64) >> 33;
+        if var1 == 0 {
+            // Avoids a division by zero on an unprogrammed part
+            return 0;
+        }
+        let mut p = 1_048_576 - adc_p as i64;
+        p = (((p << 31) - var2) * 3125) / var1;
+        var1 = (self.p9 as i64 * (p >> 13) * (p >> 13)) >> 25;
+        var2 = (self.p8 as i64 * p) >> 19;
+        (((p + var1 + var2) >> 8) + ((self.p7 as i64) << 4)) as u32
+    }
+
+    // %RH in Q22.10 (divide by 1024)
+    pub fn humidity(&self, adc_h: i32, t_fine: i32) -> u32 {
+        let adc_h = adc_h as i64;
+        let (h1, h2, h3) = (self.h1 as i64, self.h2 as i64, self.h3 as i64);
+        let (h4, h5, h6) = (self.h4 as i64, self.h5 as i64, self.h6 as i64);
+        let mut v = t_fine as i64 - 76_800;
+        v = (((adc_h << 14) - (h4 << 20) - (h5 * v) + 16_384) >> 15)
+            * (((((((v * h6) >> 10) * (((v * h3) >> 11) + 32_768)) >> 10) + 2_097_152) * h2
+                + 8192)
+                >> 14);
+        v -= ((((v >> 15) * (v >> 15)) >> 7) * h1) >> 4;
+        (v.clamp(0, 419_430_400) >> 12) as u32
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub celsius: f32,
+    pub hpa: f32,
+    pub humidity: f32,
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.2} °C  {:.2} hPa  {:.1} %RH",
+            self.celsius, self.hpa, self.humidity
+        )
+    }
+}
+
+// Raw 20-bit pressure and temperature, 16-bit humidity, from the burst
+// read of 0xF7..=0xFE
+pub fn unpack(data: &[u8; 8]) -> (i32, i32, i32) {
+    let twenty = |i: usize| {
+        ((data[i] as i32) << 12) | ((data[i + 1] as i32) << 4) | (data[i + 2] as i32 >> 4)
+    };
+    let humidity = ((data[6] as i32) << 8) | data[7] as i32;
+    (twenty(0), twenty(3), humidity)
+}
+
+pub struct Bme280<I2C> {
+    i2c: I2C,
+    address: u8,
+    calibration: Calibration,
+}
+
+impl<I2C: I2c> Bme280<I2C> {
+    // Check the chip id, soft-reset, read the trimming constants and set
+    // humidity oversampling (CTRL_HUM only takes effect on the next
+    // CTRL_MEAS write, which every measurement does)
+    pub fn new<D: DelayNs>(
+        i2c: I2C,
+        address: u8,
+        delay: &mut D,
+    ) -> Result<Bme280<I2C>, Error<I2C::Error>> {
+        let mut sensor = Bme280 {
+            i2c,
+            address,
+            calibration: Calibration::default(),
+        };
+        let mut id = [0u8];
+        sensor.read(reg::ID, &mut id)?;
+        if id[0] != CHIP_ID {
+            return Err(Error::ChipId(id[0]));
+        }
+        sensor.write(reg::RESET, reg::RESET_COMMAND)?;
+        // Start-up time after reset is 2 ms
+        delay.delay_ms(2);
+
+        let mut a = [0u8; 26];
+        let mut b = [0u8; 7];
+        sensor.read(reg::CALIB_00, &mut a)?;
+        sensor.read(reg::CALIB_26, &mut b)?;
+        sensor.calibration = Calibration::parse(&a, &b);
+
+        sensor.write(reg::CONFIG, 0)?;
+        sensor.write(reg::CTRL_HUM, reg::HUM_X1)?;
+        Ok(sensor)
+    }
+
+    pub fn calibration(&self) -> &Calibration {
+        &self.calibration
+    }
+
+    // One forced-mode conversion: trigger, poll STATUS, burst-read, then
+    // compensate. The sensor sleeps between calls, drawing under 1 µA.
+    pub fn measure<D: DelayNs>(&mut self, delay: &mut D) -> Result<Measurement, Error<I2C::Error>> {
+        self.write(reg::CTRL_MEAS, reg::MEAS_FORCED_X1)?;
+        // x1 on all three channels takes at most 9.3 ms
+        let mut status = [reg::STATUS_MEASURING];
+        for _ in 0..10 {
+            delay.delay_ms(2);
+            self.read(reg::STATUS, &mut status)?;
+            if status[0] & reg::STATUS_MEASURING == 0 {
+                break;
+            }
+        }
+        if status[0] & reg::STATUS_MEASURING != 0 {
+            return Err(Error::Timeout);
+        }
+
+        let mut data = [0u8; 8];
+        self.read(reg::PRESS_MSB, &mut data)?;
+        let (adc_p, adc_t, adc_h) = unpack(&data);
+        let (centi, t_fine) = self.calibration.temperature(adc_t);
+        Ok(Measurement {
+            celsius: centi as f32 / 100.0,
+            hpa: self.calibration.pressure(adc_p, t_fine) as f32 / 256.0 / 100.0,
+            humidity: self.calibration.humidity(adc_h, t_fine) as f32 / 1024.0,
+        })
+    }
+
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    fn read(&mut self, register: u8, buf: &mut [u8]) -> Result<(), Error<I2C::Error>> {
+        self.i2c
+            .write_read(self.address, &[register], buf)
+            .map_err(Error::I2c)
+    }
+
+    fn write(&mut self, register: u8, value: u8) -> Result<(), Error<I2C::Error>> {
+        self.i2c
+            .write(self.address, &[register, value])
+            .map_err(Error::I2c)
+    }
+}
@@ -0,0 +1,70 @@
+// The devices the lesson drives, from whichever backend was compiled in
+//
+// With `--features rpi` on Linux, `Board::open` claims a GPIO line through
+// rppal and opens `/dev/i2c-1`. Anywhere else the same function returns the
+// mock devices, so callers compile and run unchanged; they are written
+// against the embedded-hal traits and never name a backend type.
+//
+// Wiring on the Pi header (BCM numbering):
+//
+//   LED + 330 Ω   GPIO17 (pin 11) -> GND (pin 9)
+//   BME280 SDA    GPIO2  (pin 3)
+//   BME280 SCL    GPIO3  (pin 5)
+//   BME280 VIN    3V3    (pin 1),  GND (pin 6),  SDO to GND -> 0x76
+
+#[cfg(all(feature = "rpi", target_os = "linux"))]
+mod backend {
+    use rppal::gpio::{Gpio, OutputPin};
+    use rppal::hal::Delay;
+    use rppal::i2c::I2c;
+
+    pub const NAME: &str = "rppal (Raspberry Pi GPIO and /dev/i2c-1)";
+
+    pub type Bus = I2c;
+    pub type Pin = OutputPin;
+    pub type Wait = Delay;
+
+    pub fn open(led_gpio: u8) -> Result<(Bus, Pin, Wait), Box<dyn std::error::Error>> {
+        let led = Gpio::new()?.get(led_gpio)?.into_output_low();
+        let i2c = I2c::with_bus(1)?;
+        Ok((i2c, led, Delay::new()))
+    }
+}
+
+#[cfg(not(all(feature = "rpi", target_os = "linux")))]
+mod backend {
+    use crate::mock::{Environment, MockBme280, MockDelay, MockPin};
+
+    pub const NAME: &str = "mock (no hardware)";
+
+    pub type Bus = MockBme280;
+    pub type Pin = MockPin;
+    pub type Wait = MockDelay;
+
+    pub fn open(_led_gpio: u8) -> Result<(Bus, Pin, Wait), Box<dyn std::error::Error>> {
+        let bus = MockBme280::new(Environment {
+            celsius: 22.4,
+            hpa: 1009.8,
+            humidity: 41.0,
+        });
+        Ok((bus, MockPin::default(), MockDelay::default()))
+    }
+}
+
+pub use backend::{Bus, Pin, Wait};
+
+pub const BACKEND: &str = backend::NAME;
+pub const LED_GPIO: u8 = 17;
+
+pub struct Board {
+    pub i2c: Bus,
+    pub led: Pin,
+    pub delay: Wait,
+}
+
+impl Board {
+    pub fn open() -> Result<Board, Box<dyn std::error::Error>> {
+        let (i2c, led, delay) = backend::open(LED_GPIO)?;
+        Ok(Board { i2c, led, delay })
+    }
+}
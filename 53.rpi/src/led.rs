@@ -0,0 +1,65 @@
+// A status LED on any `embedded_hal::digital::OutputPin`
+//
+// Lit while the temperature alert is active, with the same hysteresis as
+// 27.embassy's `AlertLimits`: a reading hovering at the limit does not
+// make the LED flicker.
+
+use embedded_hal::digital::OutputPin;
+
+pub struct AlertLed<P> {
+    pin: P,
+    raise_above: f32,
+    clear_below: f32,
+    active: bool,
+}
+
+impl<P: OutputPin> AlertLed<P> {
+    // Drives the pin low first, so the LED state is known from the start
+    pub fn new(mut pin: P, raise_above: f32, clear_below: f32) -> Result<AlertLed<P>, P::Error> {
+        pin.set_low()?;
+        Ok(AlertLed {
+            pin,
+            raise_above,
+            clear_below,
+            active: false,
+        })
+    }
+
+    // Feed a reading; returns whether the alert (and the LED) is on. The
+    // pin is only written when the state changes.
+    pub fn update(&mut self, celsius: f32) -> Result<bool, P::Error> {
+        let next = if self.active {
+            celsius >= self.clear_below
+        } else {
+            celsius > self.raise_above
+        };
+        if next != self.active {
+            if next {
+                self.pin.set_high()?;
+            } else {
+                self.pin.set_low()?;
+            }
+            self.active = next;
+        }
+        Ok(self.active)
+    }
+
+    // A short blink to show a reading was taken, unless the alert holds
+    // the LED on already
+    pub fn blink<D: embedded_hal::delay::DelayNs>(
+        &mut self,
+        delay: &mut D,
+        ms: u32,
+    ) -> Result<(), P::Error> {
+        if !self.active {
+            self.pin.set_high()?;
+            delay.delay_ms(ms);
+            self.pin.set_low()?;
+        }
+        Ok(())
+    }
+
+    pub fn release(self) -> P {
+        self.pin
+    }
+}
@@ -0,0 +1,16 @@
+// From simulated peripherals to a real Raspberry Pi
+//
+// A BME280 driver and an alert LED written against the embedded-hal 1.0
+// traits (`I2c`, `OutputPin`, `DelayNs`). Which implementation sits under
+// them is decided at compile time in board.rs: rppal on a Pi built with
+// `--features rpi`, the mocks in mock.rs everywhere else.
+
+pub mod bme280;
+pub mod board;
+pub mod led;
+pub mod mock;
+
+pub use bme280::{Bme280, Calibration, Error, Measurement};
+pub use board::{Board, BACKEND};
+pub use led::AlertLed;
+pub use mock::{Environment, MockBme280, MockDelay, MockPin};
@@ -0,0 +1,130 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::i2c::I2c;
+use rpi::bme280::{unpack, ADDRESS_PRIMARY, ADDRESS_SECONDARY};
+use rpi::{
+    AlertLed, Bme280, Board, Calibration, Environment, MockBme280, MockDelay, MockPin, BACKEND,
+};
+
+// Generic over the traits: the same function runs on the mocks and on a Pi
+fn station<I2C: I2c, P: OutputPin, D: DelayNs>(
+    sensor: &mut Bme280<I2C>,
+    led: &mut AlertLed<P>,
+    delay: &mut D,
+    samples: usize,
+    interval_ms: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for _ in 0..samples {
+        let m = sensor.measure(delay).map_err(|e| e.to_string())?;
+        let alert = led.update(m.celsius).map_err(|e| format!("led: {:?}", e))?;
+        led.blink(delay, 50).map_err(|e| format!("led: {:?}", e))?;
+        println!("   {}{}", m, if alert { "  ALERT" } else { "" });
+        delay.delay_ms(interval_ms);
+    }
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Raspberry Pi Hardware Examples ===\n");
+
+    // 1. Chosen when compiling
+    println!("1. Backend:");
+    println!("   {}", BACKEND);
+    println!(
+        "   rpi feature {}, target_os {}",
+        if cfg!(feature = "rpi") { "on" } else { "off" },
+        std::env::consts::OS
+    );
+
+    // 2. The datasheet's worked example (BMP280, same formulas)
+    println!("\n2. Compensation against the datasheet:");
+    let datasheet = Calibration {
+        t1: 27_504,
+        t2: 26_435,
+        t3: -1000,
+        p1: 36_477,
+        p2: -10_685,
+        p3: 3024,
+        p4: 2855,
+        p5: 140,
+        p6: -7,
+        p7: 15_500,
+        p8: -14_600,
+        p9: 6000,
+        ..Calibration::default()
+    };
+    let (centi, t_fine) = datasheet.temperature(519_888);
+    let pascal = datasheet.pressure(415_148, t_fine) as f64 / 256.0;
+    println!(
+        "   adc_T 519888 -> {:.2} °C (t_fine {})",
+        centi as f64 / 100.0,
+        t_fine
+    );
+    println!("   adc_P 415148 -> {:.2} Pa", pascal);
+    println!("   (the datasheet has 25.08 °C and 100653 Pa)");
+    let packed = [0x65, 0x5A, 0xC0, 0x7E, 0xED, 0x00, 0x6B, 0x3A];
+    let (adc_p, adc_t, adc_h) = unpack(&packed);
+    println!(
+        "   burst {:02X?} -> adc_P 0x{:05X}, adc_T 0x{:05X}, adc_H 0x{:04X}",
+        packed, adc_p, adc_t, adc_h
+    );
+
+    // 3. The driver over the mock bus
+    println!("\n3. Bringing up the sensor:");
+    let env = Environment {
+        celsius: 21.5,
+        hpa: 1013.25,
+        humidity: 45.0,
+    };
+    let mut delay = MockDelay::default();
+    let mut sensor = Bme280::new(MockBme280::new(env), ADDRESS_PRIMARY, &mut delay)?;
+    println!("   {:?}", sensor.calibration());
+    match Bme280::new(MockBme280::new(env), ADDRESS_SECONDARY, &mut delay) {
+        Err(e) => println!("   at 0x{:02X}: {}", ADDRESS_SECONDARY, e),
+        Ok(_) => println!("   at 0x{:02X}: answered?!", ADDRESS_SECONDARY),
+    }
+    let wrong = Bme280::new(MockBme280::bmp280(), ADDRESS_PRIMARY, &mut delay);
+    if let Err(e) = &wrong {
+        println!("   BMP280 on the bus: {}", e);
+    }
+
+    // 4. Forced mode: trigger, poll, read, compensate
+    println!("\n4. One forced-mode measurement:");
+    let mut delay = MockDelay::default();
+    let m = sensor.measure(&mut delay)?;
+    println!(
+        "   environment {:.2} °C  {:.2} hPa  {:.1} %RH",
+        env.celsius, env.hpa, env.humidity
+    );
+    println!("   measured    {}", m);
+    println!(
+        "   waited {} ms for the conversion, {} bus transactions so far",
+        delay.total_ns / 1_000_000,
+        sensor.release().transactions
+    );
+
+    // 5. The LED, driven from readings
+    println!("\n5. Alert LED with hysteresis (on above 26.0, off below 25.5):");
+    let mut led = AlertLed::new(MockPin::default(), 26.0, 25.5)?;
+    let mut lit = Vec::new();
+    for celsius in [24.0, 25.9, 26.2, 25.8, 26.1, 25.6, 25.4, 24.8] {
+        lit.push(led.update(celsius)?);
+    }
+    let pin = led.release();
+    println!("   alert {:?}", lit);
+    println!("   pin writes {:?}", pin.history);
+
+    // 6. Whatever `Board::open` gives: real hardware with --features rpi
+    println!("\n6. Station on the board ({}):", BACKEND);
+    match Board::open() {
+        Ok(mut board) => {
+            let mut sensor = Bme280::new(board.i2c, ADDRESS_PRIMARY, &mut board.delay)?;
+            let mut led = AlertLed::new(board.led, 26.0, 25.5).map_err(|e| format!("{:?}", e))?;
+            station(&mut sensor, &mut led, &mut board.delay, 5, 1000)?;
+        }
+        Err(e) => println!("   cannot open the board: {}", e),
+    }
+
+    println!("\n=== End of Raspberry Pi Hardware Examples ===");
+    Ok(())
+}
@@ -0,0 +1,265 @@
+// Hardware stand-ins for machines without a Raspberry Pi
+//
+// `MockBme280` is an I2C bus with one simulated sensor on it: a register
+// file with real-looking trimming constants, forced-mode conversions that
+// take a couple of STATUS polls, and raw ADC values worked backwards from
+// a chosen environment, so the driver's compensation has to be right to
+// read it back. `MockPin` and `MockDelay` record what was done to them.
+
+use crate::bme280::{reg, Calibration, ADDRESS_PRIMARY, CHIP_ID};
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{self, OutputPin};
+use embedded_hal::i2c::{self, ErrorKind, NoAcknowledgeSource, Operation};
+use std::convert::Infallible;
+
+// Typical trimming constants for a BME280
+pub const SAMPLE_CALIBRATION: Calibration = Calibration {
+    t1: 28_485,
+    t2: 26_735,
+    t3: 50,
+    p1: 37_183,
+    p2: -10_564,
+    p3: 3024,
+    p4: 7354,
+    p5: -157,
+    p6: -7,
+    p7: 9900,
+    p8: -10_230,
+    p9: 4285,
+    h1: 75,
+    h2: 362,
+    h3: 0,
+    h4: 313,
+    h5: 50,
+    h6: 30,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Environment {
+    pub celsius: f32,
+    pub hpa: f32,
+    pub humidity: f32,
+}
+
+// The raw reading the sensor would produce. Compensation is monotonic in
+// each ADC value, so a binary search over the ADC range inverts it.
+fn raw_for(cal: &Calibration, env: &Environment) -> (i32, i32, i32) {
+    fn search(bits: u32, rising: bool, value: impl Fn(i32) -> i64, target: i64) -> i32 {
+        let (mut lo, mut hi) = (0i32, (1i32 << bits) - 1);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let below = if rising {
+                value(mid) < target
+            } else {
+                value(mid) > target
+            };
+            if below {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+    let adc_t = search(
+        20,
+        true,
+        |adc| cal.temperature(adc).0 as i64,
+        (env.celsius * 100.0).round() as i64,
+    );
+    let t_fine = cal.temperature(adc_t).1;
+    let adc_p = search(
+        20,
+        false,
+        |adc| cal.pressure(adc, t_fine) as i64,
+        (env.hpa * 100.0 * 256.0).round() as i64,
+    );
+    let adc_h = search(
+        16,
+        true,
+        |adc| cal.humidity(adc, t_fine) as i64,
+        (env.humidity * 1024.0).round() as i64,
+    );
+    (adc_p, adc_t, adc_h)
+}
+
+pub struct MockBme280 {
+    pub address: u8,
+    pub environment: Environment,
+    regs: [u8; 256],
+    pointer: u8,
+    calibration: Calibration,
+    // STATUS reads left that still say "measuring"
+    busy: u8,
+    pub transactions: usize,
+}
+
+impl MockBme280 {
+    pub fn new(environment: Environment) -> MockBme280 {
+        let mut mock = MockBme280 {
+            address: ADDRESS_PRIMARY,
+            environment,
+            regs: [0; 256],
+            pointer: 0,
+            calibration: SAMPLE_CALIBRATION,
+            busy: 0,
+            transactions: 0,
+        };
+        mock.regs[reg::ID as usize] = CHIP_ID;
+        mock.program_calibration();
+        mock
+    }
+
+    // A BMP280 has the same address and registers but no humidity
+    pub fn bmp280() -> MockBme280 {
+        let mut mock = MockBme280::new(Environment {
+            celsius: 20.0,
+            hpa: 1013.25,
+            humidity: 0.0,
+        });
+        mock.regs[reg::ID as usize] = 0x58;
+        mock
+    }
+
+    fn program_calibration(&mut self) {
+        let c = &self.calibration;
+        let mut a = Vec::new();
+        for word in [c.t1 as i16, c.t2, c.t3, c.p1 as i16, c.p2, c.p3, c.p4] {
+            a.extend(word.to_le_bytes());
+        }
+        for word in [c.p5, c.p6, c.p7, c.p8, c.p9] {
+            a.extend(word.to_le_bytes());
+        }
+        a.extend([0, c.h1]);
+        let b = [
+            c.h2.to_le_bytes()[0],
+            c.h2.to_le_bytes()[1],
+            c.h3,
+            (c.h4 >> 4) as u8,
+            (c.h4 & 0x0F) as u8 | ((c.h5 & 0x0F) << 4) as u8,
+            (c.h5 >> 4) as u8,
+            c.h6 as u8,
+        ];
+        let start = reg::CALIB_00 as usize;
+        self.regs[start..start + 26].copy_from_slice(&a);
+        let start = reg::CALIB_26 as usize;
+        self.regs[start..start + 7].copy_from_slice(&b);
+    }
+
+    fn write_register(&mut self, register: u8, value: u8) {
+        match register {
+            reg::RESET if value == reg::RESET_COMMAND => {
+                for r in [reg::CTRL_HUM, reg::CTRL_MEAS, reg::CONFIG] {
+                    self.regs[r as usize] = 0;
+                }
+            }
+            reg::CTRL_MEAS if value & 0b11 != 0 => {
+                // Forced (or normal) mode: convert, then back to sleep
+                let (adc_p, adc_t, adc_h) = raw_for(&self.calibration, &self.environment);
+                let data = [
+                    (adc_p >> 12) as u8,
+                    (adc_p >> 4) as u8,
+                    (adc_p << 4) as u8,
+                    (adc_t >> 12) as u8,
+                    (adc_t >> 4) as u8,
+                    (adc_t << 4) as u8,
+                    (adc_h >> 8) as u8,
+                    adc_h as u8,
+                ];
+                let start = reg::PRESS_MSB as usize;
+                self.regs[start..start + 8].copy_from_slice(&data);
+                self.regs[reg::CTRL_MEAS as usize] = value & !0b11;
+                self.busy = 2;
+            }
+            reg::CTRL_HUM | reg::CTRL_MEAS | reg::CONFIG => self.regs[register as usize] = value,
+            // Everything else is read-only
+            _ => {}
+        }
+    }
+
+    fn read_register(&mut self, register: u8) -> u8 {
+        if register == reg::STATUS {
+            let measuring = self.busy > 0;
+            self.busy = self.busy.saturating_sub(1);
+            return if measuring { reg::STATUS_MEASURING } else { 0 };
+        }
+        self.regs[register as usize]
+    }
+}
+
+impl i2c::ErrorType for MockBme280 {
+    type Error = ErrorKind;
+}
+
+// Like the real chip: the first byte written sets the register pointer,
+// further bytes are written from there, and reads continue from the
+// pointer, incrementing as they go
+impl i2c::I2c for MockBme280 {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), ErrorKind> {
+        if address != self.address {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+        self.transactions += 1;
+        for op in operations {
+            match op {
+                Operation::Write(bytes) => {
+                    if let Some((&pointer, data)) = bytes.split_first() {
+                        self.pointer = pointer;
+                        for &value in data {
+                            self.write_register(self.pointer, value);
+                            self.pointer = self.pointer.wrapping_add(1);
+                        }
+                    }
+                }
+                Operation::Read(buf) => {
+                    for byte in buf.iter_mut() {
+                        *byte = self.read_register(self.pointer);
+                        self.pointer = self.pointer.wrapping_add(1);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// A GPIO output that remembers every level it was set to
+#[derive(Debug, Default)]
+pub struct MockPin {
+    pub high: bool,
+    pub history: Vec<bool>,
+}
+
+impl digital::ErrorType for MockPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for MockPin {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.high = false;
+        self.history.push(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.high = true;
+        self.history.push(true);
+        Ok(())
+    }
+}
+
+// Returns at once and adds up how long it was asked to wait
+#[derive(Debug, Default)]
+pub struct MockDelay {
+    pub total_ns: u64,
+}
+
+impl DelayNs for MockDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.total_ns += ns as u64;
+    }
+}
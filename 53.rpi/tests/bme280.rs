@@ -0,0 +1,171 @@
+use embedded_hal::i2c::{self, ErrorKind, I2c, NoAcknowledgeSource, Operation};
+use rpi::bme280::{reg, unpack, ADDRESS_PRIMARY, ADDRESS_SECONDARY};
+use rpi::mock::SAMPLE_CALIBRATION;
+use rpi::{Bme280, Calibration, Environment, Error, MockBme280, MockDelay};
+
+// The datasheet's worked example; it is for the BMP280, whose temperature
+// and pressure formulas the BME280 shares
+const DATASHEET: Calibration = Calibration {
+    t1: 27_504,
+    t2: 26_435,
+    t3: -1000,
+    p1: 36_477,
+    p2: -10_685,
+    p3: 3024,
+    p4: 2855,
+    p5: 140,
+    p6: -7,
+    p7: 15_500,
+    p8: -14_600,
+    p9: 6000,
+    h1: 0,
+    h2: 0,
+    h3: 0,
+    h4: 0,
+    h5: 0,
+    h6: 0,
+};
+
+const ROOM: Environment = Environment {
+    celsius: 21.5,
+    hpa: 1013.25,
+    humidity: 45.0,
+};
+
+#[test]
+fn compensation_matches_the_datasheet() {
+    let (centi, t_fine) = DATASHEET.temperature(519_888);
+    assert_eq!(centi, 2508);
+    assert_eq!(t_fine, 128_422);
+    let pascal = DATASHEET.pressure(415_148, t_fine) as f64 / 256.0;
+    assert_eq!(pascal.round(), 100_653.0);
+}
+
+#[test]
+fn an_unprogrammed_part_reads_zero_pressure() {
+    let blank = Calibration::default();
+    assert_eq!(blank.pressure(415_148, 128_422), 0);
+}
+
+#[test]
+fn humidity_is_clamped_to_0_to_100_percent() {
+    let t_fine = SAMPLE_CALIBRATION.temperature(519_888).1;
+    assert_eq!(SAMPLE_CALIBRATION.humidity(0, t_fine), 0);
+    assert_eq!(SAMPLE_CALIBRATION.humidity(0xFFFF, t_fine), 100 * 1024);
+}
+
+#[test]
+fn burst_read_unpacks_into_20_and_16_bit_fields() {
+    let packed = [0x65, 0x5A, 0xC0, 0x7E, 0xED, 0x00, 0x6B, 0x3A];
+    assert_eq!(unpack(&packed), (0x655AC, 0x7EED0, 0x6B3A));
+    // The low nibble of each xLSB byte is not part of the reading
+    let noisy = [0x65, 0x5A, 0xCF, 0x7E, 0xED, 0x0F, 0x6B, 0x3A];
+    assert_eq!(unpack(&noisy), unpack(&packed));
+}
+
+#[test]
+fn calibration_parses_the_shared_h4_h5_byte() {
+    let mut a = [0u8; 26];
+    a[0..2].copy_from_slice(&28_485u16.to_le_bytes());
+    a[2..4].copy_from_slice(&(-123i16).to_le_bytes());
+    a[25] = 75;
+    // H4 = 0x139 and H5 = -0x2D share 0xE5: H4's low nibble, H5's high
+    let b = [0x6A, 0x01, 0x00, 0x13, 0x39, 0xFD, 0x1E];
+    let c = Calibration::parse(&a, &b);
+    assert_eq!((c.t1, c.t2, c.h1), (28_485, -123, 75));
+    assert_eq!(c.h2, 362);
+    assert_eq!(c.h4, 0x139);
+    assert_eq!(c.h5, -0x2D);
+    assert_eq!(c.h6, 30);
+}
+
+#[test]
+fn bring_up_reads_the_trimming_constants() {
+    let mut delay = MockDelay::default();
+    let sensor = Bme280::new(MockBme280::new(ROOM), ADDRESS_PRIMARY, &mut delay).unwrap();
+    assert_eq!(*sensor.calibration(), SAMPLE_CALIBRATION);
+    // 2 ms start-up after the soft reset
+    assert_eq!(delay.total_ns, 2_000_000);
+}
+
+#[test]
+fn wrong_devices_are_refused() {
+    let mut delay = MockDelay::default();
+    match Bme280::new(MockBme280::new(ROOM), ADDRESS_SECONDARY, &mut delay) {
+        Err(Error::I2c(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))) => {}
+        other => panic!("{:?}", other.map(|_| ())),
+    }
+    let bmp = Bme280::new(MockBme280::bmp280(), ADDRESS_PRIMARY, &mut delay);
+    assert!(matches!(bmp, Err(Error::ChipId(0x58))));
+    assert_eq!(
+        Error::<ErrorKind>::ChipId(0x58).to_string(),
+        "chip id 0x58, expected 0x60 (BME280)"
+    );
+}
+
+#[test]
+fn forced_measurements_read_the_environment_back() {
+    let mut delay = MockDelay::default();
+    let mut sensor = Bme280::new(MockBme280::new(ROOM), ADDRESS_PRIMARY, &mut delay).unwrap();
+    for env in [
+        ROOM,
+        Environment {
+            celsius: -12.25,
+            hpa: 870.0,
+            humidity: 95.5,
+        },
+        Environment {
+            celsius: 38.0,
+            hpa: 1050.5,
+            humidity: 8.0,
+        },
+    ] {
+        let mut mock = sensor.release();
+        mock.environment = env;
+        sensor = Bme280::new(mock, ADDRESS_PRIMARY, &mut delay).unwrap();
+        let mut delay = MockDelay::default();
+        let m = sensor.measure(&mut delay).unwrap();
+        assert!((m.celsius - env.celsius).abs() < 0.01, "{} vs {:?}", m, env);
+        assert!((m.hpa - env.hpa).abs() < 0.01, "{} vs {:?}", m, env);
+        assert!(
+            (m.humidity - env.humidity).abs() < 0.01,
+            "{} vs {:?}",
+            m,
+            env
+        );
+        // The mock stays busy for two STATUS polls, 2 ms apart
+        assert_eq!(delay.total_ns, 6_000_000);
+    }
+}
+
+// The mock bus, except STATUS always says "measuring"
+struct Stuck(MockBme280);
+
+impl i2c::ErrorType for Stuck {
+    type Error = ErrorKind;
+}
+
+impl I2c for Stuck {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), ErrorKind> {
+        if let [Operation::Write([reg::STATUS]), Operation::Read(buf)] = operations {
+            buf.fill(reg::STATUS_MEASURING);
+            return Ok(());
+        }
+        self.0.transaction(address, operations)
+    }
+}
+
+#[test]
+fn a_conversion_that_never_ends_times_out() {
+    let mut delay = MockDelay::default();
+    let mut sensor =
+        Bme280::new(Stuck(MockBme280::new(ROOM)), ADDRESS_PRIMARY, &mut delay).unwrap();
+    let mut delay = MockDelay::default();
+    assert!(matches!(sensor.measure(&mut delay), Err(Error::Timeout)));
+    // Ten polls, well past the 9.3 ms a conversion can take
+    assert_eq!(delay.total_ns, 20_000_000);
+}
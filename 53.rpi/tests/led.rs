@@ -0,0 +1,45 @@
+use rpi::{AlertLed, MockDelay, MockPin};
+
+#[test]
+fn the_pin_starts_low() {
+    let led = AlertLed::new(MockPin::default(), 26.0, 25.5).unwrap();
+    let pin = led.release();
+    assert_eq!(pin.history, [false]);
+}
+
+#[test]
+fn hysteresis_ignores_the_wobble() {
+    let mut led = AlertLed::new(MockPin::default(), 26.0, 25.5).unwrap();
+    let lit: Vec<bool> = [24.0, 25.9, 26.2, 25.8, 26.1, 25.6, 25.4, 24.8]
+        .into_iter()
+        .map(|c| led.update(c).unwrap())
+        .collect();
+    assert_eq!(lit, [false, false, true, true, true, true, false, false]);
+    // Written only on the two changes
+    assert_eq!(led.release().history, [false, true, false]);
+}
+
+#[test]
+fn the_limits_themselves_do_not_change_the_state() {
+    let mut led = AlertLed::new(MockPin::default(), 26.0, 25.5).unwrap();
+    // Not above 26.0, so no alert
+    assert!(!led.update(26.0).unwrap());
+    assert!(led.update(26.01).unwrap());
+    // Not below 25.5, so it stays on
+    assert!(led.update(25.5).unwrap());
+    assert!(!led.update(25.49).unwrap());
+}
+
+#[test]
+fn blink_only_while_no_alert_holds_the_led() {
+    let mut led = AlertLed::new(MockPin::default(), 26.0, 25.5).unwrap();
+    let mut delay = MockDelay::default();
+    led.blink(&mut delay, 50).unwrap();
+    assert_eq!(delay.total_ns, 50_000_000);
+    led.update(30.0).unwrap();
+    led.blink(&mut delay, 50).unwrap();
+    assert_eq!(delay.total_ns, 50_000_000);
+    let pin = led.release();
+    assert!(pin.high);
+    assert_eq!(pin.history, [false, true, false, true]);
+}
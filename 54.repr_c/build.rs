@@ -0,0 +1,31 @@
+// Build steps that run before the crate compiles:
+// 1. cbindgen writes include/repr_c.h from the #[repr(C)] types in src/
+// 2. cc compiles c/firmware.c, the stand-in for the MCU side, against that
+//    header; its own _Static_asserts fail the build if C disagrees
+
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("cbindgen.toml is readable");
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("header generation failed")
+        .write_to_file(crate_dir.join("include").join("repr_c.h"));
+
+    cc::Build::new()
+        .file(crate_dir.join("c").join("firmware.c"))
+        .include(crate_dir.join("include"))
+        .warnings(true)
+        .extra_warnings(true)
+        .compile("c_firmware");
+
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=c/firmware.c");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+}
@@ -0,0 +1,102 @@
+/* The C side: what a sensor node's firmware does with the shared types.
+ *
+ * It includes only the header cbindgen generated from the Rust types, the
+ * way real firmware would, and pins the layout with its own static
+ * assertions so a header change that moves a field fails this build too.
+ */
+
+#include <stddef.h>
+#include <string.h>
+
+#include "repr_c.h"
+
+_Static_assert(sizeof(TelemetryPacket) == PACKET_SIZE, "packet size");
+_Static_assert(offsetof(TelemetryPacket, seq) == 6, "packet seq offset");
+_Static_assert(offsetof(TelemetryPacket, timestamp_ms) == 10, "packet timestamp offset");
+_Static_assert(sizeof(Record) == 16, "record size");
+_Static_assert(offsetof(Mailbox, records) == 16, "mailbox records offset");
+
+void fw_layout(LayoutReport *out) {
+    out->packet_size = sizeof(TelemetryPacket);
+    out->packet_align = _Alignof(TelemetryPacket);
+    out->packet_seq = offsetof(TelemetryPacket, seq);
+    out->packet_timestamp_ms = offsetof(TelemetryPacket, timestamp_ms);
+    out->packet_checksum = offsetof(TelemetryPacket, checksum);
+    out->record_size = sizeof(Record);
+    out->record_align = _Alignof(Record);
+    out->record_value = offsetof(Record, value);
+    out->mailbox_size = sizeof(Mailbox);
+    out->mailbox_head = offsetof(Mailbox, head);
+    out->mailbox_records = offsetof(Mailbox, records);
+}
+
+static uint8_t byte_sum(const uint8_t *bytes, size_t len) {
+    uint8_t sum = 0;
+    for (size_t i = 0; i < len; i++) {
+        sum = (uint8_t)(sum + bytes[i]);
+    }
+    return sum;
+}
+
+/* Fill in the struct and copy it out byte for byte, as firmware on a
+ * little-endian MCU does before handing a packet to the UART. Returns the
+ * number of bytes written, or 0 if `out` is too small. */
+size_t fw_encode_packet(uint16_t node_id, uint32_t seq, uint64_t timestamp_ms,
+                        int16_t temperature_centi, uint16_t battery_mv,
+                        uint8_t *out, size_t out_len) {
+    if (out == NULL || out_len < sizeof(TelemetryPacket)) {
+        return 0;
+    }
+    TelemetryPacket packet;
+    memset(&packet, 0, sizeof packet);
+    packet.magic = PACKET_MAGIC;
+    packet.version = PACKET_VERSION;
+    packet.node_id = node_id;
+    packet.seq = seq;
+    packet.timestamp_ms = timestamp_ms;
+    packet.temperature_centi = temperature_centi;
+    packet.humidity_permille = 473;
+    packet.battery_mv = battery_mv;
+    if (battery_mv < 3300) {
+        packet.flags |= FLAG_LOW_BATTERY;
+    }
+    memcpy(out, &packet, sizeof packet);
+    out[sizeof packet - 1] = (uint8_t)(0u - byte_sum(out, sizeof packet - 1));
+    return sizeof packet;
+}
+
+/* The reverse: check and copy bytes from the gateway into the struct.
+ * 0 on success, -1 on a bad length, magic or checksum. */
+int fw_decode_packet(const uint8_t *bytes, size_t len, TelemetryPacket *out) {
+    if (bytes == NULL || out == NULL || len != sizeof(TelemetryPacket)) {
+        return -1;
+    }
+    if (byte_sum(bytes, len) != 0) {
+        return -1;
+    }
+    memcpy(out, bytes, sizeof *out);
+    return out->magic == PACKET_MAGIC ? 0 : -1;
+}
+
+void fw_mailbox_init(Mailbox *mailbox) {
+    memset(mailbox, 0, sizeof *mailbox);
+    mailbox->magic = MAILBOX_MAGIC;
+    mailbox->version = MAILBOX_VERSION;
+    mailbox->slot_count = MAILBOX_SLOTS;
+}
+
+/* 0 on success, -1 when the gateway has not kept up */
+int fw_mailbox_push(Mailbox *mailbox, uint64_t timestamp_ms, uint16_t node_id,
+                    uint8_t kind, float value) {
+    if (mailbox->head - mailbox->tail >= MAILBOX_SLOTS) {
+        return -1;
+    }
+    Record *slot = &mailbox->records[mailbox->head % MAILBOX_SLOTS];
+    slot->timestamp_ms = timestamp_ms;
+    slot->node_id = node_id;
+    slot->kind = kind;
+    slot->_reserved = 0;
+    slot->value = value;
+    mailbox->head++;
+    return 0;
+}
@@ -0,0 +1,107 @@
+#ifndef REPR_C_H
+#define REPR_C_H
+
+/* Generated by cbindgen from src/ - do not edit. Shared with the C firmware. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define MAILBOX_MAGIC 1296191320
+
+#define MAILBOX_VERSION 1
+
+#define MAILBOX_SLOTS 8
+
+#define PACKET_MAGIC 21076
+
+#define PACKET_VERSION 1
+
+#define PACKET_SIZE 25
+
+#define FLAG_LOW_BATTERY 1
+
+#define FLAG_SENSOR_FAULT 2
+
+enum RecordKind
+#if __STDC_VERSION__ >= 202311L
+  : uint8_t
+#endif // __STDC_VERSION__ >= 202311L
+ {
+  RECORD_KIND_TEMPERATURE = 1,
+  RECORD_KIND_HUMIDITY = 2,
+  RECORD_KIND_BATTERY = 3,
+};
+#if __STDC_VERSION__ >= 202311L
+typedef enum RecordKind RecordKind;
+#else
+typedef uint8_t RecordKind;
+#endif // __STDC_VERSION__ >= 202311L
+
+typedef struct LayoutReport {
+  size_t packet_size;
+  size_t packet_align;
+  size_t packet_seq;
+  size_t packet_timestamp_ms;
+  size_t packet_checksum;
+  size_t record_size;
+  size_t record_align;
+  size_t record_value;
+  size_t mailbox_size;
+  size_t mailbox_head;
+  size_t mailbox_records;
+} LayoutReport;
+
+typedef struct __attribute__((packed)) TelemetryPacket {
+  uint16_t magic;
+  uint8_t version;
+  uint8_t flags;
+  uint16_t node_id;
+  uint32_t seq;
+  uint64_t timestamp_ms;
+  int16_t temperature_centi;
+  uint16_t humidity_permille;
+  uint16_t battery_mv;
+  uint8_t checksum;
+} TelemetryPacket;
+
+typedef struct Record {
+  uint64_t timestamp_ms;
+  uint16_t node_id;
+  uint8_t kind;
+  uint8_t _reserved;
+  float value;
+} Record;
+
+typedef struct Mailbox {
+  uint32_t magic;
+  uint16_t version;
+  uint16_t slot_count;
+  uint32_t head;
+  uint32_t tail;
+  struct Record records[MAILBOX_SLOTS];
+} Mailbox;
+
+extern void fw_layout(struct LayoutReport *out);
+
+extern size_t fw_encode_packet(uint16_t node_id,
+                               uint32_t seq,
+                               uint64_t timestamp_ms,
+                               int16_t temperature_centi,
+                               uint16_t battery_mv,
+                               uint8_t *out,
+                               size_t out_len);
+
+extern int fw_decode_packet(const uint8_t *bytes, size_t len, struct TelemetryPacket *out);
+
+extern void fw_mailbox_init(struct Mailbox *mailbox);
+
+extern int fw_mailbox_push(struct Mailbox *mailbox,
+                           uint64_t timestamp_ms,
+                           uint16_t node_id,
+                           uint8_t kind,
+                           float value);
+
+#endif  /* REPR_C_H */
@@ -0,0 +1,36 @@
+// The C firmware's entry points
+//
+// c/firmware.c is compiled and linked by build.rs. cbindgen copies these
+// declarations into include/repr_c.h as prototypes, and firmware.c
+// includes that header, so a signature that disagrees with the C
+// definition fails the C build.
+
+use crate::layout::LayoutReport;
+use crate::shared::Mailbox;
+use crate::wire::TelemetryPacket;
+use std::ffi::c_int;
+
+extern "C" {
+    pub fn fw_layout(out: *mut LayoutReport);
+    // Bytes written to `out`, or 0 if `out_len` is too small
+    pub fn fw_encode_packet(
+        node_id: u16,
+        seq: u32,
+        timestamp_ms: u64,
+        temperature_centi: i16,
+        battery_mv: u16,
+        out: *mut u8,
+        out_len: usize,
+    ) -> usize;
+    // 0 on success, -1 on a bad length, magic or checksum
+    pub fn fw_decode_packet(bytes: *const u8, len: usize, out: *mut TelemetryPacket) -> c_int;
+    pub fn fw_mailbox_init(mailbox: *mut Mailbox);
+    // 0 on success, -1 when the mailbox is full
+    pub fn fw_mailbox_push(
+        mailbox: *mut Mailbox,
+        timestamp_ms: u64,
+        node_id: u16,
+        kind: u8,
+        value: f32,
+    ) -> c_int;
+}
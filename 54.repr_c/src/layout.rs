@@ -0,0 +1,72 @@
+// Layout as each compiler sees it
+//
+// The static assertions pin the layout Rust uses; they cannot see the C
+// compiler. `LayoutReport` closes that gap at run time: the firmware fills
+// one with `sizeof`/`offsetof`/`_Alignof` (c/firmware.c), Rust fills one
+// with `size_of`/`offset_of!`, and any difference is a field both sides
+// would read differently.
+
+use crate::shared::{Mailbox, Record};
+use crate::wire::TelemetryPacket;
+use memoffset::offset_of;
+use std::mem::{align_of, size_of};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LayoutReport {
+    pub packet_size: usize,
+    pub packet_align: usize,
+    pub packet_seq: usize,
+    pub packet_timestamp_ms: usize,
+    pub packet_checksum: usize,
+    pub record_size: usize,
+    pub record_align: usize,
+    pub record_value: usize,
+    pub mailbox_size: usize,
+    pub mailbox_head: usize,
+    pub mailbox_records: usize,
+}
+
+impl LayoutReport {
+    pub fn rust() -> LayoutReport {
+        LayoutReport {
+            packet_size: size_of::<TelemetryPacket>(),
+            packet_align: align_of::<TelemetryPacket>(),
+            packet_seq: offset_of!(TelemetryPacket, seq),
+            packet_timestamp_ms: offset_of!(TelemetryPacket, timestamp_ms),
+            packet_checksum: offset_of!(TelemetryPacket, checksum),
+            record_size: size_of::<Record>(),
+            record_align: align_of::<Record>(),
+            record_value: offset_of!(Record, value),
+            mailbox_size: size_of::<Mailbox>(),
+            mailbox_head: offset_of!(Mailbox, head),
+            mailbox_records: offset_of!(Mailbox, records),
+        }
+    }
+
+    pub fn entries(&self) -> [(&'static str, usize); 11] {
+        [
+            ("sizeof(TelemetryPacket)", self.packet_size),
+            ("_Alignof(TelemetryPacket)", self.packet_align),
+            ("offsetof(TelemetryPacket, seq)", self.packet_seq),
+            ("offsetof(.., timestamp_ms)", self.packet_timestamp_ms),
+            ("offsetof(.., checksum)", self.packet_checksum),
+            ("sizeof(Record)", self.record_size),
+            ("_Alignof(Record)", self.record_align),
+            ("offsetof(Record, value)", self.record_value),
+            ("sizeof(Mailbox)", self.mailbox_size),
+            ("offsetof(Mailbox, head)", self.mailbox_head),
+            ("offsetof(Mailbox, records)", self.mailbox_records),
+        ]
+    }
+
+    // (field, ours, theirs) for every entry that differs
+    pub fn differences(&self, other: &LayoutReport) -> Vec<(&'static str, usize, usize)> {
+        self.entries()
+            .into_iter()
+            .zip(other.entries())
+            .filter(|((_, a), (_, b))| a != b)
+            .map(|((name, a), (_, b))| (name, a, b))
+            .collect()
+    }
+}
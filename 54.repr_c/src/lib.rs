@@ -0,0 +1,22 @@
+// Data structures shared with C firmware
+//
+// Two ways a Rust gateway and a C microcontroller share data:
+//
+// - wire.rs: a `#[repr(C, packed)]` packet sent as bytes over a UART,
+//   encoded and decoded field by field
+// - shared.rs: a `#[repr(C)]` mailbox both sides read in place, with
+//   explicit padding
+//
+// The layouts are pinned by compile-time assertions in Rust and
+// `_Static_assert`s in C, the C header is generated from these types by
+// cbindgen (include/repr_c.h), and layout.rs compares what the two
+// compilers actually produced. firmware.rs declares the C side's functions.
+
+pub mod firmware;
+pub mod layout;
+pub mod shared;
+pub mod wire;
+
+pub use layout::LayoutReport;
+pub use shared::{Mailbox, Record, RecordKind, MAILBOX_SLOTS};
+pub use wire::{DecodeError, TelemetryPacket, PACKET_SIZE};
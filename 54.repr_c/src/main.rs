@@ -0,0 +1,237 @@
+use memoffset::offset_of;
+use repr_c::firmware::{
+    fw_decode_packet, fw_encode_packet, fw_layout, fw_mailbox_init, fw_mailbox_push,
+};
+use repr_c::shared::records_span;
+use repr_c::wire::FLAG_LOW_BATTERY;
+use repr_c::{LayoutReport, Mailbox, RecordKind, TelemetryPacket, MAILBOX_SLOTS};
+use std::mem::{align_of, size_of};
+
+const HEADER: &str = include_str!("../include/repr_c.h");
+
+// The packet's fields without `packed`, to see what the compiler adds
+#[allow(dead_code)]
+#[repr(C)]
+struct Padded {
+    magic: u16,
+    version: u8,
+    flags: u8,
+    node_id: u16,
+    seq: u32,
+    timestamp_ms: u64,
+    temperature_centi: i16,
+    humidity_permille: u16,
+    battery_mv: u16,
+    checksum: u8,
+}
+
+// Same fields again; Rust may reorder them, but alignment still rounds
+// 25 bytes of fields up to 32
+#[allow(dead_code)]
+struct Reordered {
+    magic: u16,
+    version: u8,
+    flags: u8,
+    node_id: u16,
+    seq: u32,
+    timestamp_ms: u64,
+    temperature_centi: i16,
+    humidity_permille: u16,
+    battery_mv: u16,
+    checksum: u8,
+}
+
+fn c_decode(bytes: &[u8]) -> Option<TelemetryPacket> {
+    let mut out = TelemetryPacket::default();
+    // SAFETY: pointer and length describe `bytes`; `out` is a valid
+    // TelemetryPacket the C side may overwrite
+    let rc = unsafe { fw_decode_packet(bytes.as_ptr(), bytes.len(), &mut out) };
+    (rc == 0).then_some(out)
+}
+
+fn main() {
+    println!("=== Shared #[repr(C)] Structures Examples ===\n");
+
+    // 1. What packed removes
+    println!("1. One set of fields, three layouts:");
+    println!(
+        "   repr(C, packed)  size {:>2}, align {}, seq at {:>2}, timestamp_ms at {:>2}",
+        size_of::<TelemetryPacket>(),
+        align_of::<TelemetryPacket>(),
+        offset_of!(TelemetryPacket, seq),
+        offset_of!(TelemetryPacket, timestamp_ms)
+    );
+    println!(
+        "   repr(C)          size {:>2}, align {}, seq at {:>2}, timestamp_ms at {:>2}",
+        size_of::<Padded>(),
+        align_of::<Padded>(),
+        offset_of!(Padded, seq),
+        offset_of!(Padded, timestamp_ms)
+    );
+    println!(
+        "   repr(Rust)       size {:>2}, align {}, field order unspecified",
+        size_of::<Reordered>(),
+        align_of::<Reordered>()
+    );
+
+    // 2. The C compiler's view of the generated header
+    println!("\n2. Layout, Rust vs C:");
+    let rust = LayoutReport::rust();
+    let mut c = LayoutReport::default();
+    // SAFETY: fw_layout only writes the fields of `c`
+    unsafe { fw_layout(&mut c) };
+    for ((name, r), (_, cv)) in rust.entries().into_iter().zip(c.entries()) {
+        println!("   {:<32} rust {:>3}  c {:>3}", name, r, cv);
+    }
+    println!("   {} differences", rust.differences(&c).len());
+
+    // 3. Rust encodes, C decodes
+    println!("\n3. Gateway -> firmware:");
+    let mut command = TelemetryPacket::new(7, 41, 1_700_000_040_000);
+    command.temperature_centi = -1250;
+    command.humidity_permille = 815;
+    command.battery_mv = 3710;
+    let bytes = command.to_bytes();
+    println!("   {}", command);
+    println!("   bytes {:02X?}", &bytes[..12]);
+    match c_decode(&bytes) {
+        Some(p) => println!("   C decoded: {}", p),
+        None => println!("   C rejected the packet"),
+    }
+
+    // 4. C encodes, Rust decodes
+    println!("\n4. Firmware -> gateway:");
+    let mut buf = [0u8; 32];
+    // SAFETY: the C side writes at most `buf.len()` bytes into `buf`
+    let n = unsafe {
+        fw_encode_packet(
+            3,
+            1002,
+            1_700_000_041_500,
+            2187,
+            3210,
+            buf.as_mut_ptr(),
+            buf.len(),
+        )
+    };
+    let wire = &buf[..n];
+    match TelemetryPacket::from_bytes(wire) {
+        Ok(p) => {
+            println!("   {}", p);
+            println!(
+                "   low battery flag set by C: {}",
+                p.flags & FLAG_LOW_BATTERY != 0
+            );
+            if let Ok(raw) = wire.try_into() {
+                println!(
+                    "   read_unaligned: {}",
+                    TelemetryPacket::from_bytes_unchecked(raw)
+                );
+            }
+        }
+        Err(e) => println!("   decode failed: {}", e),
+    }
+
+    // 5. Bytes that must not become a packet
+    println!("\n5. Validation on the way in:");
+    let mut flipped = bytes;
+    flipped[12] ^= 0x10;
+    let mut foreign = TelemetryPacket::new(7, 41, 0);
+    foreign.magic = 0xBEEF;
+    let mut future = TelemetryPacket::new(7, 41, 0);
+    future.version = 9;
+    let cases = [
+        ("truncated", &bytes[..20]),
+        ("one bit flipped", &flipped[..]),
+        ("foreign magic", &foreign.to_bytes()[..]),
+        ("newer version", &future.to_bytes()[..]),
+    ];
+    for (name, input) in cases {
+        println!(
+            "   {:<16} -> {}",
+            name,
+            TelemetryPacket::from_bytes(input)
+                .map_or_else(|e| e.to_string(), |_| "accepted?!".to_string())
+        );
+    }
+    println!(
+        "   C on the flipped bit -> {}",
+        if c_decode(&flipped).is_none() {
+            "rejected"
+        } else {
+            "accepted?!"
+        }
+    );
+
+    // 6. Shared memory: no encoding at all
+    println!("\n6. Mailbox shared with the firmware:");
+    let mut mailbox = Box::new(Mailbox::empty());
+    let readings = [
+        (RecordKind::Temperature as u8, 21.5),
+        (RecordKind::Humidity as u8, 47.3),
+        (9, 0.0),
+        (RecordKind::Battery as u8, 3.21),
+    ];
+    let mut pushed = 0;
+    // SAFETY: `mailbox` is a valid, exclusively borrowed Mailbox for all
+    // of these calls
+    unsafe {
+        fw_mailbox_init(mailbox.as_mut());
+        for (i, (kind, value)) in readings.iter().enumerate() {
+            if fw_mailbox_push(mailbox.as_mut(), 1_000 * i as u64, 3, *kind, *value) == 0 {
+                pushed += 1;
+            }
+        }
+    }
+    println!(
+        "   magic 0x{:08X}, {} pending, records at bytes {:?}",
+        mailbox.magic,
+        mailbox.len(),
+        records_span()
+    );
+    println!("   firmware pushed {} of {}", pushed, readings.len());
+    while let Some((record, kind)) = mailbox.pop() {
+        match kind {
+            Ok(kind) => println!(
+                "   t={:>4} {:?} {}",
+                record.timestamp_ms, kind, record.value
+            ),
+            Err(raw) => println!(
+                "   t={:>4} unknown kind {}, skipped",
+                record.timestamp_ms, raw
+            ),
+        }
+    }
+    // SAFETY: as above
+    let overflow = unsafe {
+        (0..=MAILBOX_SLOTS)
+            .filter(|_| fw_mailbox_push(mailbox.as_mut(), 0, 3, 1, 0.0) == 0)
+            .count()
+    };
+    println!(
+        "   {} pushes into {} free slots: {} accepted",
+        MAILBOX_SLOTS + 1,
+        MAILBOX_SLOTS,
+        overflow
+    );
+
+    // 7. The generated header
+    println!("\n7. include/repr_c.h (generated by cbindgen):");
+    let start = HEADER
+        .find("typedef struct __attribute__((packed))")
+        .unwrap_or(0);
+    let end = HEADER[start..]
+        .find("} TelemetryPacket;")
+        .map_or(start, |i| start + i + "} TelemetryPacket;".len());
+    for line in HEADER[start..end].lines() {
+        println!("   | {}", line);
+    }
+    if let Some(define) = HEADER
+        .lines()
+        .find(|l| l.starts_with("#define PACKET_MAGIC"))
+    {
+        println!("   | {}", define);
+    }
+
+    println!("\n=== End of Shared #[repr(C)] Structures Examples ===");
+}
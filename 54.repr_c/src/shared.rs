@@ -0,0 +1,114 @@
+// A mailbox in memory shared with C firmware
+//
+// When Rust and C see the same memory (a coprocessor's shared RAM, a
+// mapped region, a buffer handed across FFI) there is no encoding step:
+// both sides read the struct where it lies. That needs `#[repr(C)]`
+// (C's field order and alignment) but not `packed`: unaligned fields are
+// slow or faulting on many MCUs. Padding is written out as `_reserved`
+// fields instead of left to the compiler, so both sides agree on every
+// byte and a copy of the struct never carries uninitialised padding.
+//
+// `kind` stays a `u8`: the firmware can write any value there, and an
+// out-of-range discriminant in a Rust enum field would be undefined
+// behaviour. `RecordKind::try_from` validates it on the way in.
+
+use memoffset::{offset_of, span_of};
+use std::mem::{align_of, size_of};
+
+pub const MAILBOX_MAGIC: u32 = 0x4D42_4F58;
+pub const MAILBOX_VERSION: u16 = 1;
+pub const MAILBOX_SLOTS: usize = 8;
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordKind {
+    Temperature = 1,
+    Humidity = 2,
+    Battery = 3,
+}
+
+impl TryFrom<u8> for RecordKind {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<RecordKind, u8> {
+        match value {
+            1 => Ok(RecordKind::Temperature),
+            2 => Ok(RecordKind::Humidity),
+            3 => Ok(RecordKind::Battery),
+            other => Err(other),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Record {
+    pub timestamp_ms: u64,
+    pub node_id: u16,
+    // A `RecordKind` value
+    pub kind: u8,
+    pub _reserved: u8,
+    pub value: f32,
+}
+
+// Single producer (the firmware) writes at `head`, single consumer (the
+// gateway) reads at `tail`. Across cores these would be atomics; here
+// both sides run in one thread.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mailbox {
+    pub magic: u32,
+    pub version: u16,
+    pub slot_count: u16,
+    pub head: u32,
+    pub tail: u32,
+    pub records: [Record; MAILBOX_SLOTS],
+}
+
+const _: () = assert!(size_of::<Record>() == 16);
+const _: () = assert!(align_of::<Record>() == 8);
+const _: () = assert!(offset_of!(Record, value) == 12);
+const _: () = assert!(offset_of!(Mailbox, records) == 16);
+const _: () = assert!(size_of::<Mailbox>() == 16 + 16 * MAILBOX_SLOTS);
+
+// The byte range of the records array, e.g. for a DMA or cache flush
+pub fn records_span() -> std::ops::Range<usize> {
+    span_of!(Mailbox, records)
+}
+
+impl Mailbox {
+    pub fn empty() -> Mailbox {
+        Mailbox {
+            magic: MAILBOX_MAGIC,
+            version: MAILBOX_VERSION,
+            slot_count: MAILBOX_SLOTS as u16,
+            head: 0,
+            tail: 0,
+            records: [Record::default(); MAILBOX_SLOTS],
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.magic == MAILBOX_MAGIC
+            && self.version == MAILBOX_VERSION
+            && self.slot_count as usize == MAILBOX_SLOTS
+    }
+
+    pub fn len(&self) -> usize {
+        self.head.wrapping_sub(self.tail) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // The oldest record, with its kind checked
+    pub fn pop(&mut self) -> Option<(Record, Result<RecordKind, u8>)> {
+        if self.is_empty() {
+            return None;
+        }
+        let record = self.records[self.tail as usize % MAILBOX_SLOTS];
+        self.tail = self.tail.wrapping_add(1);
+        Some((record, RecordKind::try_from(record.kind)))
+    }
+}
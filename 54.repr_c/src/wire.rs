@@ -0,0 +1,177 @@
+// The telemetry packet a C sensor node sends over the UART
+//
+// `#[repr(C, packed)]` gives the field order of the C struct and no
+// padding at all, matching `__attribute__((packed))` on the firmware side.
+// Multi-byte fields are little-endian on the wire, the byte order of
+// every Cortex-M and of the gateway, and `to_bytes`/`from_bytes` spell it
+// out so the code stays correct on a big-endian host too.
+//
+// A packed struct's fields may sit at odd addresses, so Rust refuses to
+// take references to them (`&packet.seq` is error E0793). Read them by
+// value instead: `{ packet.seq }` copies the field out.
+
+use memoffset::offset_of;
+use std::fmt;
+use std::mem::{align_of, size_of};
+
+pub const PACKET_MAGIC: u16 = 0x5254;
+pub const PACKET_VERSION: u8 = 1;
+pub const PACKET_SIZE: usize = 25;
+
+// flags
+pub const FLAG_LOW_BATTERY: u8 = 0x01;
+pub const FLAG_SENSOR_FAULT: u8 = 0x02;
+
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TelemetryPacket {
+    pub magic: u16,
+    pub version: u8,
+    pub flags: u8,
+    pub node_id: u16,
+    pub seq: u32,
+    pub timestamp_ms: u64,
+    // Hundredths of a degree
+    pub temperature_centi: i16,
+    // Tenths of a percent
+    pub humidity_permille: u16,
+    pub battery_mv: u16,
+    // Makes the byte sum of the whole packet zero
+    pub checksum: u8,
+}
+
+// Checked at compile time: change a field and the build stops here, before
+// anything goes out on the wire. c/firmware.c asserts the same numbers.
+const _: () = assert!(size_of::<TelemetryPacket>() == PACKET_SIZE);
+const _: () = assert!(align_of::<TelemetryPacket>() == 1);
+const _: () = assert!(offset_of!(TelemetryPacket, node_id) == 4);
+const _: () = assert!(offset_of!(TelemetryPacket, seq) == 6);
+const _: () = assert!(offset_of!(TelemetryPacket, timestamp_ms) == 10);
+const _: () = assert!(offset_of!(TelemetryPacket, checksum) == 24);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    Length { expected: usize, got: usize },
+    Magic(u16),
+    Version(u8),
+    // Byte sum of the packet; zero when intact
+    Checksum(u8),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Length { expected, got } => {
+                write!(f, "packet is {} bytes, expected {}", got, expected)
+            }
+            DecodeError::Magic(m) => write!(f, "bad magic 0x{:04X}", m),
+            DecodeError::Version(v) => write!(f, "unsupported version {}", v),
+            DecodeError::Checksum(sum) => write!(f, "checksum off by 0x{:02X}", sum),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+fn byte_sum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |sum, b| sum.wrapping_add(*b))
+}
+
+impl TelemetryPacket {
+    pub fn new(node_id: u16, seq: u32, timestamp_ms: u64) -> TelemetryPacket {
+        TelemetryPacket {
+            magic: PACKET_MAGIC,
+            version: PACKET_VERSION,
+            node_id,
+            seq,
+            timestamp_ms,
+            ..TelemetryPacket::default()
+        }
+    }
+
+    // Field by field, little-endian, in declaration order; the checksum is
+    // filled in here
+    pub fn to_bytes(&self) -> [u8; PACKET_SIZE] {
+        let mut out = [0u8; PACKET_SIZE];
+        let mut at = 0;
+        let mut put = |bytes: &[u8]| {
+            out[at..at + bytes.len()].copy_from_slice(bytes);
+            at += bytes.len();
+        };
+        put(&{ self.magic }.to_le_bytes());
+        put(&[self.version, self.flags]);
+        put(&{ self.node_id }.to_le_bytes());
+        put(&{ self.seq }.to_le_bytes());
+        put(&{ self.timestamp_ms }.to_le_bytes());
+        put(&{ self.temperature_centi }.to_le_bytes());
+        put(&{ self.humidity_permille }.to_le_bytes());
+        put(&{ self.battery_mv }.to_le_bytes());
+        out[PACKET_SIZE - 1] = 0u8.wrapping_sub(byte_sum(&out[..PACKET_SIZE - 1]));
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<TelemetryPacket, DecodeError> {
+        let bytes: &[u8; PACKET_SIZE] = bytes.try_into().map_err(|_| DecodeError::Length {
+            expected: PACKET_SIZE,
+            got: bytes.len(),
+        })?;
+        let sum = byte_sum(bytes);
+        if sum != 0 {
+            return Err(DecodeError::Checksum(sum));
+        }
+        let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]);
+        let packet = TelemetryPacket {
+            magic: u16_at(0),
+            version: bytes[2],
+            flags: bytes[3],
+            node_id: u16_at(4),
+            seq: u32::from_le_bytes(bytes[6..10].try_into().unwrap()),
+            timestamp_ms: u64::from_le_bytes(bytes[10..18].try_into().unwrap()),
+            temperature_centi: i16::from_le_bytes([bytes[18], bytes[19]]),
+            humidity_permille: u16_at(20),
+            battery_mv: u16_at(22),
+            checksum: bytes[24],
+        };
+        if packet.magic != PACKET_MAGIC {
+            return Err(DecodeError::Magic(packet.magic));
+        }
+        if packet.version != PACKET_VERSION {
+            return Err(DecodeError::Version(packet.version));
+        }
+        Ok(packet)
+    }
+
+    // The shortcut C code takes: reinterpret the bytes in place. Sound here
+    // only because the struct is packed (alignment 1, no padding), every bit
+    // pattern is a valid value for every field, and the host is
+    // little-endian like the wire. No checks; use `from_bytes` for input.
+    #[cfg(target_endian = "little")]
+    pub fn from_bytes_unchecked(bytes: &[u8; PACKET_SIZE]) -> TelemetryPacket {
+        // SAFETY: see above; read_unaligned copes with any address
+        unsafe { std::ptr::read_unaligned(bytes.as_ptr().cast()) }
+    }
+
+    pub fn celsius(&self) -> f32 {
+        self.temperature_centi as f32 / 100.0
+    }
+
+    pub fn humidity(&self) -> f32 {
+        self.humidity_permille as f32 / 10.0
+    }
+}
+
+impl fmt::Display for TelemetryPacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "node {} #{} t={} {:.2} °C {:.1} %RH {} mV flags 0x{:02X}",
+            { self.node_id },
+            { self.seq },
+            { self.timestamp_ms },
+            self.celsius(),
+            self.humidity(),
+            { self.battery_mv },
+            self.flags
+        )
+    }
+}
@@ -0,0 +1,186 @@
+// Rust against the C firmware compiled by build.rs
+
+use repr_c::firmware::{
+    fw_decode_packet, fw_encode_packet, fw_layout, fw_mailbox_init, fw_mailbox_push,
+};
+use repr_c::shared::{MAILBOX_MAGIC, MAILBOX_VERSION};
+use repr_c::wire::{FLAG_LOW_BATTERY, PACKET_MAGIC, PACKET_VERSION};
+use repr_c::{LayoutReport, Mailbox, RecordKind, TelemetryPacket, MAILBOX_SLOTS, PACKET_SIZE};
+
+const HEADER: &str = include_str!("../include/repr_c.h");
+
+fn c_decode(bytes: &[u8]) -> Option<TelemetryPacket> {
+    let mut out = TelemetryPacket::default();
+    // SAFETY: pointer and length describe `bytes`; `out` is a valid
+    // TelemetryPacket the C side may overwrite
+    let rc = unsafe { fw_decode_packet(bytes.as_ptr(), bytes.len(), &mut out) };
+    (rc == 0).then_some(out)
+}
+
+fn c_encode(node_id: u16, battery_mv: u16, out: &mut [u8]) -> usize {
+    // SAFETY: the C side writes at most `out.len()` bytes into `out`
+    unsafe {
+        fw_encode_packet(
+            node_id,
+            1002,
+            1_700_000_041_500,
+            2187,
+            battery_mv,
+            out.as_mut_ptr(),
+            out.len(),
+        )
+    }
+}
+
+#[test]
+fn both_compilers_agree_on_the_layout() {
+    let mut c = LayoutReport::default();
+    // SAFETY: fw_layout only writes the fields of `c`
+    unsafe { fw_layout(&mut c) };
+    assert_eq!(LayoutReport::rust().differences(&c), []);
+    assert_eq!(c.packet_size, PACKET_SIZE);
+    assert_eq!(c.packet_align, 1);
+}
+
+#[test]
+fn c_reads_what_rust_encodes() {
+    let mut packet = TelemetryPacket::new(7, 41, 1_700_000_040_000);
+    packet.temperature_centi = -1250;
+    packet.humidity_permille = 815;
+    packet.battery_mv = 3710;
+    let bytes = packet.to_bytes();
+    let mut expected = packet;
+    expected.checksum = bytes[PACKET_SIZE - 1];
+    assert_eq!(c_decode(&bytes), Some(expected));
+}
+
+#[test]
+fn c_rejects_bad_bytes() {
+    let bytes = TelemetryPacket::new(7, 41, 0).to_bytes();
+    let mut flipped = bytes;
+    flipped[12] ^= 0x10;
+    assert_eq!(c_decode(&flipped), None);
+    assert_eq!(c_decode(&bytes[..20]), None);
+    let mut foreign = TelemetryPacket::new(7, 41, 0);
+    foreign.magic = 0xBEEF;
+    assert_eq!(c_decode(&foreign.to_bytes()), None);
+}
+
+#[test]
+fn rust_reads_what_c_encodes() {
+    let mut buf = [0u8; 32];
+    let n = c_encode(3, 3210, &mut buf);
+    assert_eq!(n, PACKET_SIZE);
+    let packet = TelemetryPacket::from_bytes(&buf[..n]).unwrap();
+    assert_eq!(
+        (
+            { packet.magic },
+            packet.version,
+            { packet.node_id },
+            { packet.seq },
+            { packet.timestamp_ms },
+            { packet.temperature_centi },
+            { packet.humidity_permille },
+            { packet.battery_mv },
+        ),
+        (
+            PACKET_MAGIC,
+            PACKET_VERSION,
+            3,
+            1002,
+            1_700_000_041_500,
+            2187,
+            473,
+            3210
+        )
+    );
+    // Below 3.3 V the firmware flags a low battery
+    assert_eq!(packet.flags, FLAG_LOW_BATTERY);
+    assert_eq!(
+        TelemetryPacket::from_bytes_unchecked(buf[..n].try_into().unwrap()),
+        packet
+    );
+
+    let n = c_encode(3, 3300, &mut buf);
+    assert_eq!(TelemetryPacket::from_bytes(&buf[..n]).unwrap().flags, 0);
+}
+
+#[test]
+fn c_writes_nothing_into_a_short_buffer() {
+    let mut buf = [0xAAu8; PACKET_SIZE - 1];
+    assert_eq!(c_encode(3, 3210, &mut buf), 0);
+    assert!(buf.iter().all(|&b| b == 0xAA));
+}
+
+#[test]
+fn records_pushed_by_c_pop_in_rust() {
+    let mut mailbox = Box::new(Mailbox::empty());
+    let pushes = [
+        (RecordKind::Temperature as u8, 21.5),
+        (RecordKind::Humidity as u8, 47.3),
+        (9, 0.0),
+        (RecordKind::Battery as u8, 3.21),
+    ];
+    // SAFETY: `mailbox` is a valid, exclusively borrowed Mailbox for all
+    // of these calls
+    unsafe {
+        fw_mailbox_init(mailbox.as_mut());
+        for (i, (kind, value)) in pushes.iter().enumerate() {
+            assert_eq!(
+                fw_mailbox_push(mailbox.as_mut(), 1_000 * i as u64, 3, *kind, *value),
+                0
+            );
+        }
+    }
+    assert!(mailbox.is_valid());
+    assert_eq!(
+        (mailbox.magic, mailbox.version),
+        (MAILBOX_MAGIC, MAILBOX_VERSION)
+    );
+    let mut popped = Vec::new();
+    while let Some((record, kind)) = mailbox.pop() {
+        assert_eq!(record.node_id, 3);
+        popped.push((record.timestamp_ms, kind, record.value));
+    }
+    assert_eq!(
+        popped,
+        [
+            (0, Ok(RecordKind::Temperature), 21.5),
+            (1000, Ok(RecordKind::Humidity), 47.3),
+            (2000, Err(9), 0.0),
+            (3000, Ok(RecordKind::Battery), 3.21),
+        ]
+    );
+}
+
+#[test]
+fn c_stops_when_the_mailbox_is_full() {
+    let mut mailbox = Box::new(Mailbox::empty());
+    // SAFETY: as above
+    let accepted = unsafe {
+        fw_mailbox_init(mailbox.as_mut());
+        (0..MAILBOX_SLOTS + 3)
+            .filter(|_| fw_mailbox_push(mailbox.as_mut(), 0, 3, 1, 0.0) == 0)
+            .count()
+    };
+    assert_eq!(accepted, MAILBOX_SLOTS);
+    // One popped, one slot free again
+    mailbox.pop();
+    // SAFETY: as above
+    let rc = unsafe { fw_mailbox_push(mailbox.as_mut(), 0, 3, 1, 0.0) };
+    assert_eq!(rc, 0);
+    assert_eq!(mailbox.len(), MAILBOX_SLOTS);
+}
+
+#[test]
+fn the_header_carries_the_constants_and_packing() {
+    for define in [
+        format!("#define PACKET_MAGIC {}", PACKET_MAGIC),
+        format!("#define PACKET_SIZE {}", PACKET_SIZE),
+        format!("#define MAILBOX_SLOTS {}", MAILBOX_SLOTS),
+    ] {
+        assert!(HEADER.contains(&define), "{}", define);
+    }
+    assert!(HEADER.contains("typedef struct __attribute__((packed)) TelemetryPacket {"));
+    assert!(HEADER.contains("extern void fw_layout(struct LayoutReport *out);"));
+}
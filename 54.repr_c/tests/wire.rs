@@ -0,0 +1,135 @@
+use repr_c::shared::{records_span, MAILBOX_MAGIC};
+use repr_c::wire::{FLAG_SENSOR_FAULT, PACKET_MAGIC, PACKET_VERSION};
+use repr_c::{
+    DecodeError, Mailbox, Record, RecordKind, TelemetryPacket, MAILBOX_SLOTS, PACKET_SIZE,
+};
+
+fn sample() -> TelemetryPacket {
+    let mut packet = TelemetryPacket::new(7, 41, 1_700_000_040_000);
+    packet.temperature_centi = -1250;
+    packet.humidity_permille = 815;
+    packet.battery_mv = 3710;
+    packet.flags = FLAG_SENSOR_FAULT;
+    packet
+}
+
+#[test]
+fn bytes_are_little_endian_in_field_order() {
+    let bytes = sample().to_bytes();
+    assert_eq!(&bytes[..2], &PACKET_MAGIC.to_le_bytes());
+    assert_eq!(bytes[2], PACKET_VERSION);
+    assert_eq!(bytes[3], FLAG_SENSOR_FAULT);
+    assert_eq!(&bytes[4..6], &[7, 0]);
+    assert_eq!(&bytes[6..10], &[41, 0, 0, 0]);
+    assert_eq!(&bytes[10..18], &1_700_000_040_000u64.to_le_bytes());
+    assert_eq!(&bytes[18..20], &(-1250i16).to_le_bytes());
+    assert_eq!(&bytes[22..24], &3710u16.to_le_bytes());
+    // The checksum makes the byte sum zero
+    assert_eq!(bytes.iter().fold(0u8, |s, b| s.wrapping_add(*b)), 0);
+}
+
+#[test]
+fn round_trips_with_the_checksum_filled_in() {
+    let packet = sample();
+    let bytes = packet.to_bytes();
+    let mut expected = packet;
+    expected.checksum = bytes[PACKET_SIZE - 1];
+    assert_eq!(TelemetryPacket::from_bytes(&bytes), Ok(expected));
+    assert_eq!(TelemetryPacket::from_bytes_unchecked(&bytes), expected);
+    assert_eq!(expected.celsius(), -12.5);
+    assert_eq!(expected.humidity(), 81.5);
+}
+
+#[test]
+fn every_single_bit_flip_is_caught() {
+    let bytes = sample().to_bytes();
+    for at in 0..PACKET_SIZE {
+        for bit in 0..8 {
+            let mut flipped = bytes;
+            flipped[at] ^= 1 << bit;
+            // Checked before magic and version, so those fail the same way
+            assert!(
+                matches!(
+                    TelemetryPacket::from_bytes(&flipped),
+                    Err(DecodeError::Checksum(_))
+                ),
+                "byte {} bit {}",
+                at,
+                bit
+            );
+        }
+    }
+}
+
+#[test]
+fn bad_input_is_rejected_for_the_right_reason() {
+    let bytes = sample().to_bytes();
+    assert_eq!(
+        TelemetryPacket::from_bytes(&bytes[..20]),
+        Err(DecodeError::Length {
+            expected: PACKET_SIZE,
+            got: 20
+        })
+    );
+    let mut longer = bytes.to_vec();
+    longer.push(0);
+    assert!(matches!(
+        TelemetryPacket::from_bytes(&longer),
+        Err(DecodeError::Length { got: 26, .. })
+    ));
+
+    let mut foreign = sample();
+    foreign.magic = 0xBEEF;
+    assert_eq!(
+        TelemetryPacket::from_bytes(&foreign.to_bytes()),
+        Err(DecodeError::Magic(0xBEEF))
+    );
+    let mut future = sample();
+    future.version = 9;
+    assert_eq!(
+        TelemetryPacket::from_bytes(&future.to_bytes()),
+        Err(DecodeError::Version(9))
+    );
+}
+
+#[test]
+fn the_mailbox_is_a_ring_of_checked_records() {
+    let mut mailbox = Mailbox::empty();
+    assert!(mailbox.is_valid());
+    assert!(mailbox.is_empty());
+    assert_eq!(mailbox.pop(), None);
+
+    // Written directly, as the firmware would, including past the wrap
+    for i in 0..MAILBOX_SLOTS as u32 + 3 {
+        if mailbox.len() == MAILBOX_SLOTS {
+            mailbox.pop();
+        }
+        mailbox.records[mailbox.head as usize % MAILBOX_SLOTS] = Record {
+            timestamp_ms: i as u64,
+            node_id: 3,
+            kind: (i % 5) as u8,
+            _reserved: 0,
+            value: i as f32,
+        };
+        mailbox.head += 1;
+    }
+    assert_eq!(mailbox.len(), MAILBOX_SLOTS);
+    let mut popped = Vec::new();
+    while let Some((record, kind)) = mailbox.pop() {
+        popped.push((record.timestamp_ms, kind));
+    }
+    let expected: Vec<(u64, Result<RecordKind, u8>)> = (3..11)
+        .map(|i| (i as u64, RecordKind::try_from((i % 5) as u8)))
+        .collect();
+    assert_eq!(popped, expected);
+    assert_eq!(popped[1].1, Err(4));
+    assert_eq!(popped[2].1, Err(0));
+
+    mailbox.magic = !MAILBOX_MAGIC;
+    assert!(!mailbox.is_valid());
+}
+
+#[test]
+fn the_records_follow_the_16_byte_header() {
+    assert_eq!(records_span(), 16..16 + 16 * MAILBOX_SLOTS);
+}
@@ -0,0 +1,78 @@
+// Scalar vs SIMD for each kernel
+//
+//   cargo bench                                    # scalar and wide
+//   cargo bench -- count_above                     # one group
+//   cargo +nightly bench --features nightly        # adds std::simd
+//   RUSTFLAGS="-C target-cpu=native" cargo bench   # AVX2 where available
+//
+// 64K samples fit in L2, so the numbers measure the arithmetic rather than
+// memory bandwidth. Throughput is reported in samples per second.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use simd::implementations;
+use std::hint::black_box;
+
+const SAMPLES: usize = 65_536;
+
+fn floats() -> Vec<f32> {
+    (0..SAMPLES)
+        .map(|i| 21.0 + (i as f32 / 500.0).sin() * 5.0)
+        .collect()
+}
+
+fn ints() -> Vec<i32> {
+    (0..SAMPLES as i32)
+        .map(|i| i.wrapping_mul(2_654_435_761u32 as i32) >> 8)
+        .collect()
+}
+
+fn array_sum(c: &mut Criterion) {
+    let data = ints();
+    let mut group = c.benchmark_group("array_sum");
+    group.throughput(Throughput::Elements(SAMPLES as u64));
+    for k in implementations() {
+        group.bench_function(BenchmarkId::from_parameter(k.name), |b| {
+            b.iter(|| (k.array_sum)(black_box(&data)))
+        });
+    }
+    group.finish();
+}
+
+fn sum(c: &mut Criterion) {
+    let data = floats();
+    let mut group = c.benchmark_group("sum");
+    group.throughput(Throughput::Elements(SAMPLES as u64));
+    for k in implementations() {
+        group.bench_function(BenchmarkId::from_parameter(k.name), |b| {
+            b.iter(|| (k.sum)(black_box(&data)))
+        });
+    }
+    group.finish();
+}
+
+fn min_max(c: &mut Criterion) {
+    let data = floats();
+    let mut group = c.benchmark_group("min_max");
+    group.throughput(Throughput::Elements(SAMPLES as u64));
+    for k in implementations() {
+        group.bench_function(BenchmarkId::from_parameter(k.name), |b| {
+            b.iter(|| (k.min_max)(black_box(&data)))
+        });
+    }
+    group.finish();
+}
+
+fn count_above(c: &mut Criterion) {
+    let data = floats();
+    let mut group = c.benchmark_group("count_above");
+    group.throughput(Throughput::Elements(SAMPLES as u64));
+    for k in implementations() {
+        group.bench_function(BenchmarkId::from_parameter(k.name), |b| {
+            b.iter(|| (k.count_above)(black_box(&data), black_box(25.0)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, array_sum, sum, min_max, count_above);
+criterion_main!(benches);
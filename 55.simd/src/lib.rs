@@ -0,0 +1,63 @@
+// Vectorised aggregation over sample buffers
+//
+// A gateway summarising a second of 10 kHz ADC data, or a day of 1 Hz
+// readings, spends its time in tiny loops: sum, min/max, "how many above
+// the limit". SIMD runs those loops on 8 samples per instruction.
+//
+// Three implementations of the same four functions:
+//
+// - `scalar`: the reference, one sample at a time
+// - `stable`: stable Rust with the `wide` crate
+// - `portable`: `std::simd`, with `--features nightly` on a nightly toolchain
+
+#![cfg_attr(feature = "nightly", feature(portable_simd))]
+
+#[cfg(feature = "nightly")]
+pub mod portable;
+pub mod scalar;
+pub mod stable;
+
+// The fastest implementation this build has
+#[cfg(feature = "nightly")]
+pub use portable as best;
+#[cfg(not(feature = "nightly"))]
+pub use stable as best;
+
+// One implementation's four kernels, so callers can loop over all of them
+#[derive(Clone, Copy)]
+pub struct Kernels {
+    pub name: &'static str,
+    pub array_sum: fn(&[i32]) -> i32,
+    pub sum: fn(&[f32]) -> f32,
+    pub min_max: fn(&[f32]) -> Option<(f32, f32)>,
+    pub count_above: fn(&[f32], f32) -> usize,
+}
+
+pub fn implementations() -> Vec<Kernels> {
+    #[cfg_attr(not(feature = "nightly"), allow(unused_mut))]
+    let mut all = vec![
+        Kernels {
+            name: "scalar",
+            array_sum: scalar::array_sum,
+            sum: scalar::sum,
+            min_max: scalar::min_max,
+            count_above: scalar::count_above,
+        },
+        Kernels {
+            name: "wide",
+            array_sum: stable::array_sum,
+            sum: stable::sum,
+            min_max: stable::min_max,
+            count_above: stable::count_above,
+        },
+    ];
+    #[cfg(feature = "nightly")]
+    all.push(Kernels {
+        name: "std::simd",
+        array_sum: portable::array_sum,
+        sum: portable::sum,
+        min_max: portable::min_max,
+        count_above: portable::count_above,
+    });
+    all
+}
@@ -0,0 +1,140 @@
+use simd::{implementations, stable};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+struct Noise(u64);
+
+impl Noise {
+    // Uniform in [0, 1)
+    fn next(&mut self) -> f64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+// Temperature-like samples around 21 °C with noise and a few spikes
+fn temperatures(n: usize, seed: u64) -> Vec<f32> {
+    let mut noise = Noise(seed);
+    (0..n)
+        .map(|i| {
+            let base = 21.0 + (i as f64 / 5000.0).sin() * 3.0 + noise.next() - 0.5;
+            let spike = if noise.next() < 0.001 { 15.0 } else { 0.0 };
+            (base + spike) as f32
+        })
+        .collect()
+}
+
+// Raw 24-bit ADC counts, signed
+fn adc_counts(n: usize, seed: u64) -> Vec<i32> {
+    let mut noise = Noise(seed);
+    (0..n)
+        .map(|_| (noise.next() * 16_777_216.0) as i32 - 8_388_608)
+        .collect()
+}
+
+fn time<T>(reps: u32, mut f: impl FnMut() -> T) -> Duration {
+    let started = Instant::now();
+    for _ in 0..reps {
+        black_box(f());
+    }
+    started.elapsed() / reps
+}
+
+fn main() {
+    println!("=== SIMD Aggregation Examples ===\n");
+    let kernels = implementations();
+
+    // 1. What the vectors compile to
+    println!("1. This build:");
+    println!("   f32x8 from wide: {}", stable::backend());
+    println!(
+        "   implementations: {}",
+        kernels
+            .iter()
+            .map(|k| k.name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    if !cfg!(feature = "nightly") {
+        println!("   (std::simd: cargo +nightly run --features nightly)");
+    }
+
+    // 2. The same answers from every implementation
+    println!("\n2. One buffer, every implementation:");
+    let floats = temperatures(100_003, 7);
+    let ints = adc_counts(100_003, 11);
+    for k in &kernels {
+        let (lo, hi) = (k.min_max)(&floats).unwrap_or_default();
+        println!(
+            "   {:<10} sum {:>12.3}  min {:>6.2}  max {:>6.2}  >25 °C {:>5}  adc sum {:>11}",
+            k.name,
+            (k.sum)(&floats),
+            lo,
+            hi,
+            (k.count_above)(&floats, 25.0),
+            (k.array_sum)(&ints)
+        );
+    }
+
+    // 3. Tails and short inputs, where the scalar fallback takes over;
+    //    tests/kernels.rs checks every length and offset against scalar
+    println!("\n3. Short inputs:");
+    let wrapping = [i32::MAX, 1, 0, 0, 0, 0, 0, 0, 5];
+    for k in &kernels {
+        println!(
+            "   {:<10} 5 samples min/max {:?}  empty {:?}  wrapping sum {}",
+            k.name,
+            (k.min_max)(&floats[..5]),
+            (k.min_max)(&[]),
+            (k.array_sum)(&wrapping)
+        );
+    }
+
+    // 4. Float addition is not associative
+    println!("\n4. Summation order:");
+    let exact: f64 = floats.iter().map(|&x| x as f64).sum();
+    for k in &kernels {
+        let s = (k.sum)(&floats) as f64;
+        println!(
+            "   {:<10} {:>14.4}  error {:>8.4}",
+            k.name,
+            s,
+            (s - exact).abs()
+        );
+    }
+    println!("   f64 exact  {:>14.4}", exact);
+
+    // 5. Rough timings; `cargo bench` measures properly
+    println!("\n5. One million samples, mean of 20 runs (use --release):");
+    let floats = temperatures(1_000_000, 3);
+    let ints = adc_counts(1_000_000, 5);
+    println!(
+        "   {:<10} {:>10} {:>10} {:>10} {:>10}",
+        "", "sum", "min/max", "count", "adc sum"
+    );
+    let mut sums = Vec::new();
+    for k in &kernels {
+        let t = [
+            time(20, || (k.sum)(black_box(&floats))),
+            time(20, || (k.min_max)(black_box(&floats))),
+            time(20, || (k.count_above)(black_box(&floats), 25.0)),
+            time(20, || (k.array_sum)(black_box(&ints))),
+        ];
+        println!(
+            "   {:<10} {:>10.1?} {:>10.1?} {:>10.1?} {:>10.1?}",
+            k.name, t[0], t[1], t[2], t[3]
+        );
+        sums.push(t[0]);
+    }
+    if let [scalar_t, wide_t, ..] = sums.as_slice() {
+        println!(
+            "   float sum speedup with wide: {:.1}x",
+            scalar_t.as_secs_f64() / wide_t.as_secs_f64()
+        );
+    }
+
+    println!("\n=== End of SIMD Aggregation Examples ===");
+}
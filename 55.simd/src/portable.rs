@@ -0,0 +1,61 @@
+// The same kernels with `std::simd` (nightly, `--features nightly`)
+//
+// `Simd<f32, 8>` is the standard library's portable vector type. It has
+// the reductions `wide` lacks (`reduce_min`, `reduce_max`), masks with a
+// `to_bitmask`, and `as_simd`, which splits a slice into an unaligned
+// head, aligned vectors and a tail.
+
+use crate::scalar;
+use std::simd::prelude::*;
+
+pub const LANES: usize = 8;
+
+pub fn array_sum(samples: &[i32]) -> i32 {
+    let (head, middle, tail) = samples.as_simd::<LANES>();
+    let acc = middle.iter().fold(Simd::splat(0), |acc, v| acc + v);
+    // Integer reductions wrap
+    acc.reduce_sum()
+        .wrapping_add(scalar::array_sum(head))
+        .wrapping_add(scalar::array_sum(tail))
+}
+
+pub fn sum(samples: &[f32]) -> f32 {
+    let (head, middle, tail) = samples.as_simd::<LANES>();
+    let mut acc = [f32x8::splat(0.0); 4];
+    let quads = middle.chunks_exact(4);
+    let rest = quads.remainder();
+    for quad in quads {
+        for (a, v) in acc.iter_mut().zip(quad) {
+            *a += v;
+        }
+    }
+    for v in rest {
+        acc[0] += v;
+    }
+    ((acc[0] + acc[1]) + (acc[2] + acc[3])).reduce_sum() + scalar::sum(head) + scalar::sum(tail)
+}
+
+pub fn min_max(samples: &[f32]) -> Option<(f32, f32)> {
+    let (head, middle, tail) = samples.as_simd::<LANES>();
+    let Some((&first, rest)) = middle.split_first() else {
+        return scalar::min_max(samples);
+    };
+    let (lo, hi) = rest.iter().fold((first, first), |(lo, hi), &v| {
+        (lo.simd_min(v), hi.simd_max(v))
+    });
+    let edges = head.iter().chain(tail);
+    Some(
+        edges.fold((lo.reduce_min(), hi.reduce_max()), |(lo, hi), &x| {
+            (lo.min(x), hi.max(x))
+        }),
+    )
+}
+
+pub fn count_above(samples: &[f32], threshold: f32) -> usize {
+    let (head, middle, tail) = samples.as_simd::<LANES>();
+    let limit = f32x8::splat(threshold);
+    let edges = scalar::count_above(head, threshold) + scalar::count_above(tail, threshold);
+    middle.iter().fold(edges, |count, v| {
+        count + v.simd_gt(limit).to_bitmask().count_ones() as usize
+    })
+}
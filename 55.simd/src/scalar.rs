@@ -0,0 +1,27 @@
+// Reference implementations: one element at a time, in order
+//
+// These define what the vector versions must return. The integer sum and
+// the threshold count are simple enough that LLVM auto-vectorises them
+// anyway; the float sum and min/max are not, because reordering float
+// additions changes the result and `f32::min` has NaN rules the vector
+// instructions do not share.
+
+pub fn array_sum(samples: &[i32]) -> i32 {
+    samples.iter().fold(0i32, |sum, &x| sum.wrapping_add(x))
+}
+
+pub fn sum(samples: &[f32]) -> f32 {
+    samples.iter().sum()
+}
+
+pub fn min_max(samples: &[f32]) -> Option<(f32, f32)> {
+    let (&first, rest) = samples.split_first()?;
+    Some(
+        rest.iter()
+            .fold((first, first), |(lo, hi), &x| (lo.min(x), hi.max(x))),
+    )
+}
+
+pub fn count_above(samples: &[f32], threshold: f32) -> usize {
+    samples.iter().filter(|&&x| x > threshold).count()
+}
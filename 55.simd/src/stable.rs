@@ -0,0 +1,96 @@
+// Stable-Rust SIMD with the `wide` crate
+//
+// `f32x8` and `i32x8` are eight lanes processed by one instruction: an
+// AVX register when the build targets AVX, two SSE registers on plain
+// x86_64, NEON pairs on ARM. Each kernel has the same shape:
+//
+//   1. `chunks_exact(LANES)` over the slice, one vector per chunk
+//   2. a lane-wise operation into a vector accumulator
+//   3. one horizontal reduction at the end
+//   4. the remainder (fewer than LANES samples) with scalar code
+//
+// Inputs are assumed free of NaN, as validated sensor data is; see
+// scalar.rs for the NaN-aware reference.
+
+use crate::scalar;
+use wide::{f32x8, i32x8, CmpGt};
+
+pub const LANES: usize = 8;
+
+fn f32_lanes(chunk: &[f32]) -> f32x8 {
+    f32x8::new(chunk.try_into().expect("chunk of LANES"))
+}
+
+pub fn array_sum(samples: &[i32]) -> i32 {
+    let chunks = samples.chunks_exact(LANES);
+    let tail = scalar::array_sum(chunks.remainder());
+    // Lane-wise adds wrap, like `wrapping_add`
+    let acc = chunks.fold(i32x8::ZERO, |acc, chunk| {
+        acc + i32x8::new(chunk.try_into().expect("chunk of LANES"))
+    });
+    acc.to_array()
+        .iter()
+        .fold(tail, |sum, &lane| sum.wrapping_add(lane))
+}
+
+// Four independent accumulators: an add takes several cycles to finish,
+// and with one accumulator each add would wait for the previous one
+pub fn sum(samples: &[f32]) -> f32 {
+    let wide_chunks = samples.chunks_exact(4 * LANES);
+    let rest = wide_chunks.remainder();
+    let mut acc = [f32x8::ZERO; 4];
+    for chunk in wide_chunks {
+        for (a, part) in acc.iter_mut().zip(chunk.chunks_exact(LANES)) {
+            *a += f32_lanes(part);
+        }
+    }
+    let chunks = rest.chunks_exact(LANES);
+    let tail = scalar::sum(chunks.remainder());
+    for chunk in chunks {
+        acc[0] += f32_lanes(chunk);
+    }
+    ((acc[0] + acc[1]) + (acc[2] + acc[3])).reduce_add() + tail
+}
+
+pub fn min_max(samples: &[f32]) -> Option<(f32, f32)> {
+    if samples.len() < LANES {
+        return scalar::min_max(samples);
+    }
+    let chunks = samples.chunks_exact(LANES);
+    let tail = chunks.remainder();
+    let first = f32_lanes(&samples[..LANES]);
+    let (lo, hi) = chunks.fold((first, first), |(lo, hi), chunk| {
+        let v = f32_lanes(chunk);
+        (lo.fast_min(v), hi.fast_max(v))
+    });
+    let lo = lo.to_array().into_iter().fold(f32::INFINITY, f32::min);
+    let hi = hi.to_array().into_iter().fold(f32::NEG_INFINITY, f32::max);
+    Some(
+        tail.iter()
+            .fold((lo, hi), |(lo, hi), &x| (lo.min(x), hi.max(x))),
+    )
+}
+
+// A comparison gives a lane mask (all ones or all zeros); `move_mask`
+// packs the lanes' top bits into an integer whose set bits are counted
+pub fn count_above(samples: &[f32], threshold: f32) -> usize {
+    let limit = f32x8::splat(threshold);
+    let chunks = samples.chunks_exact(LANES);
+    let tail = scalar::count_above(chunks.remainder(), threshold);
+    chunks.fold(tail, |count, chunk| {
+        count + f32_lanes(chunk).cmp_gt(limit).move_mask().count_ones() as usize
+    })
+}
+
+// What `f32x8` compiles to in this build
+pub fn backend() -> &'static str {
+    if cfg!(target_feature = "avx") {
+        "AVX, one 256-bit register"
+    } else if cfg!(target_feature = "sse2") {
+        "SSE2, two 128-bit registers"
+    } else if cfg!(target_feature = "neon") {
+        "NEON, two 128-bit registers"
+    } else {
+        "no SIMD, plain arrays"
+    }
+}
@@ -0,0 +1,178 @@
+// Every implementation against the scalar reference. With
+// `--features nightly` the std::simd kernels are included too.
+
+use simd::{implementations, scalar, Kernels};
+
+struct Noise(u64);
+
+impl Noise {
+    // Uniform in [0, 1)
+    fn next(&mut self) -> f64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+fn floats(n: usize, seed: u64) -> Vec<f32> {
+    let mut noise = Noise(seed);
+    (0..n)
+        .map(|_| (noise.next() * 40.0 - 10.0) as f32)
+        .collect()
+}
+
+fn ints(n: usize, seed: u64) -> Vec<i32> {
+    let mut noise = Noise(seed);
+    (0..n)
+        .map(|_| (noise.next() * 16_777_216.0) as i32 - 8_388_608)
+        .collect()
+}
+
+fn vector_kernels() -> Vec<Kernels> {
+    let all = implementations();
+    assert_eq!(all[0].name, "scalar");
+    all[1..].to_vec()
+}
+
+fn close(x: f32, y: f32) -> bool {
+    (x - y).abs() <= 1e-4 * x.abs().max(y.abs()).max(1.0)
+}
+
+fn assert_agree(k: &Kernels, i: &[i32], f: &[f32]) {
+    let what = format!("{} on {} samples", k.name, f.len());
+    assert_eq!((k.array_sum)(i), scalar::array_sum(i), "{}", what);
+    assert_eq!((k.min_max)(f), scalar::min_max(f), "{}", what);
+    for threshold in [-20.0, 0.0, 25.0, 100.0] {
+        assert_eq!(
+            (k.count_above)(f, threshold),
+            scalar::count_above(f, threshold),
+            "{}",
+            what
+        );
+    }
+    assert!(close((k.sum)(f), scalar::sum(f)), "{}", what);
+}
+
+#[test]
+fn implementations_are_listed() {
+    let names: Vec<&str> = implementations().iter().map(|k| k.name).collect();
+    if cfg!(feature = "nightly") {
+        assert_eq!(names, ["scalar", "wide", "std::simd"]);
+    } else {
+        assert_eq!(names, ["scalar", "wide"]);
+    }
+}
+
+#[test]
+fn a_large_buffer_gives_the_same_answers() {
+    let f = floats(100_003, 7);
+    let i = ints(100_003, 11);
+    for k in vector_kernels() {
+        assert_agree(&k, &i, &f);
+    }
+}
+
+#[test]
+fn every_tail_length_and_offset() {
+    // Offsets move the start off vector alignment, which changes the
+    // head/tail split of std::simd's as_simd
+    let f = floats(200, 3);
+    let i = ints(200, 5);
+    for k in vector_kernels() {
+        for len in 0..=100 {
+            for offset in 0..4 {
+                assert_agree(&k, &i[offset..offset + len], &f[offset..offset + len]);
+            }
+        }
+    }
+}
+
+#[test]
+fn extremes_are_found_in_any_position() {
+    for k in implementations() {
+        for len in [2, 7, 8, 9, 31, 32, 33, 70] {
+            for at in 0..len {
+                let mut f = vec![1.0f32; len];
+                f[at] = -5.0;
+                assert_eq!(
+                    (k.min_max)(&f),
+                    Some((-5.0, 1.0)),
+                    "{} {} {}",
+                    k.name,
+                    len,
+                    at
+                );
+                f[at] = 9.0;
+                assert_eq!(
+                    (k.min_max)(&f),
+                    Some((1.0, 9.0)),
+                    "{} {} {}",
+                    k.name,
+                    len,
+                    at
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn known_values() {
+    let f: Vec<f32> = (1..=100).map(|x| x as f32).collect();
+    let i: Vec<i32> = (1..=100).collect();
+    for k in implementations() {
+        assert_eq!((k.sum)(&f), 5050.0, "{}", k.name);
+        assert_eq!((k.array_sum)(&i), 5050, "{}", k.name);
+        assert_eq!((k.min_max)(&f), Some((1.0, 100.0)), "{}", k.name);
+        // Strictly above: 50.0 itself does not count
+        assert_eq!((k.count_above)(&f, 50.0), 50, "{}", k.name);
+        assert_eq!((k.count_above)(&f, 0.0), 100, "{}", k.name);
+        assert_eq!((k.count_above)(&f, 100.0), 0, "{}", k.name);
+    }
+}
+
+#[test]
+fn empty_input() {
+    for k in implementations() {
+        assert_eq!((k.min_max)(&[]), None, "{}", k.name);
+        assert_eq!((k.sum)(&[]), 0.0, "{}", k.name);
+        assert_eq!((k.array_sum)(&[]), 0, "{}", k.name);
+        assert_eq!((k.count_above)(&[], 0.0), 0, "{}", k.name);
+    }
+}
+
+#[test]
+fn integer_sums_wrap() {
+    // The overflow happens in a vector lane in one case and in the tail in
+    // the other
+    let lane = [i32::MAX, 0, 0, 0, 0, 0, 0, 0, i32::MAX, 0, 0, 0, 0, 0, 0, 0];
+    let tail = [0, 0, 0, 0, 0, 0, 0, 0, i32::MAX, 1];
+    for k in implementations() {
+        assert_eq!((k.array_sum)(&lane), -2, "{}", k.name);
+        assert_eq!((k.array_sum)(&tail), i32::MIN, "{}", k.name);
+    }
+}
+
+#[test]
+fn lane_sums_are_at_least_as_accurate() {
+    // Values around 21 make a long sequential f32 sum drift
+    let f: Vec<f32> = floats(1_000_000, 9)
+        .iter()
+        .map(|x| 21.0 + x / 40.0)
+        .collect();
+    let exact: f64 = f.iter().map(|&x| x as f64).sum();
+    let error = |s: f32| (s as f64 - exact).abs();
+    let sequential = error(scalar::sum(&f));
+    assert!(sequential > 0.0);
+    for k in vector_kernels() {
+        assert!(
+            error((k.sum)(&f)) <= sequential,
+            "{}: {} vs {}",
+            k.name,
+            error((k.sum)(&f)),
+            sequential
+        );
+    }
+}
@@ -0,0 +1,70 @@
+// Sequential vs parallel pipelines across batch sizes
+//
+//   cargo bench                              # every group
+//   cargo bench -- summarise                 # one group
+//   RAYON_NUM_THREADS=2 cargo bench          # a smaller pool
+//
+// The sizes span the crossover: at 1,000 readings the parallel versions
+// lose to thread wake-up and merging, at a million they should approach
+// the core count. `summarise_auto` should track the faster of the two.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use parallel::{parallel as par, sequential as seq, simulate};
+use std::hint::black_box;
+
+const SIZES: [usize; 4] = [1_000, 10_000, 100_000, 1_000_000];
+
+fn summarise(c: &mut Criterion) {
+    let batch = simulate(SIZES[SIZES.len() - 1], 16, 42);
+    let mut group = c.benchmark_group("summarise");
+    for n in SIZES {
+        let slice = &batch[..n];
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("sequential", n), slice, |b, s| {
+            b.iter(|| seq::summarise(black_box(s)))
+        });
+        group.bench_with_input(BenchmarkId::new("par_iter", n), slice, |b, s| {
+            b.iter(|| par::summarise(black_box(s)))
+        });
+        group.bench_with_input(BenchmarkId::new("par_chunks", n), slice, |b, s| {
+            b.iter(|| par::summarise_chunks(black_box(s)))
+        });
+        group.bench_with_input(BenchmarkId::new("auto", n), slice, |b, s| {
+            b.iter(|| par::summarise_auto(black_box(s)))
+        });
+    }
+    group.finish();
+}
+
+fn alerts(c: &mut Criterion) {
+    let batch = simulate(SIZES[SIZES.len() - 1], 16, 42);
+    let mut group = c.benchmark_group("alerts");
+    for n in [10_000, 1_000_000] {
+        let slice = &batch[..n];
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("sequential", n), slice, |b, s| {
+            b.iter(|| seq::alerts(black_box(s), 31.0))
+        });
+        group.bench_with_input(BenchmarkId::new("par_iter", n), slice, |b, s| {
+            b.iter(|| par::alerts(black_box(s), 31.0))
+        });
+    }
+    group.finish();
+}
+
+fn buckets(c: &mut Criterion) {
+    let batch = simulate(16 * 86_400, 16, 42);
+    let mut group = c.benchmark_group("hourly_buckets");
+    group.throughput(Throughput::Elements(batch.len() as u64));
+    group.sample_size(20);
+    group.bench_function("sequential", |b| {
+        b.iter(|| seq::buckets(black_box(&batch), 3_600_000))
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| par::buckets(black_box(&batch), 3_600_000))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, summarise, alerts, buckets);
+criterion_main!(benches);
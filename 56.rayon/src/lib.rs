@@ -0,0 +1,16 @@
+// Data-parallel batch processing with rayon
+//
+// When a node reconnects after a day offline, or an operator re-processes
+// a week of history, the gateway gets millions of readings at once. The
+// pipeline is the usual one: reject bad readings, convert to °C, reduce to
+// per-sensor statistics and time buckets. Every step is independent per
+// reading, which is exactly what rayon's parallel iterators are for.
+//
+// - `sequential`: the pipeline with `iter()`, the reference
+// - `parallel`: the same steps with `par_iter()` / `par_chunks()`
+
+pub mod parallel;
+pub mod reading;
+pub mod sequential;
+
+pub use reading::{simulate, Reading, Stats, Summary};
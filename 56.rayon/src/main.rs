@@ -0,0 +1,122 @@
+use parallel::{parallel as par, sequential as seq, simulate, Reading, Summary};
+use rayon::ThreadPoolBuilder;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+// Best of `reps` runs, which filters out scheduler noise
+fn time(reps: u32, mut f: impl FnMut() -> Summary) -> Duration {
+    (0..reps)
+        .map(|_| {
+            let started = Instant::now();
+            black_box(f());
+            started.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn main() {
+    println!("=== Rayon Batch Processing Examples ===\n");
+
+    // 1. The global pool
+    println!("1. Thread pool:");
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+    println!("   available cores:      {}", cores);
+    println!("   rayon worker threads: {}", rayon::current_num_threads());
+    println!("   (RAYON_NUM_THREADS overrides the default)");
+
+    // 2. Same batch, three pipelines
+    println!("\n2. A day of 16 sensors at 1 Hz, three ways:");
+    let batch = simulate(16 * 86_400, 16, 42);
+    let reference = seq::summarise(&batch);
+    let by_item = par::summarise(&batch);
+    let by_chunk = par::summarise_chunks(&batch);
+    println!(
+        "   {} readings, {} accepted, {} rejected",
+        batch.len(),
+        reference.accepted(),
+        reference.rejected
+    );
+    for (id, s) in reference.sensors.iter().take(3) {
+        println!(
+            "   sensor {:>2}: n={} min {:.2} max {:.2} mean {:.3} °C",
+            id,
+            s.count,
+            s.min,
+            s.max,
+            s.mean()
+        );
+    }
+    let s0 = |summary: &Summary| summary.sensors[&0].mean();
+    println!(
+        "   sensor 0 mean: sequential {:.12}, par_iter {:.12}, par_chunks {:.12}",
+        s0(&reference),
+        s0(&by_item),
+        s0(&by_chunk)
+    );
+
+    // 3. Results that depend on order
+    println!("\n3. Order-preserving steps:");
+    let alerts = par::alerts(&batch, 31.0);
+    println!(
+        "   {} readings above 31 °C, first from sensor {:?}",
+        alerts.len(),
+        alerts.first().map(|r| r.sensor_id)
+    );
+    let buckets = par::buckets(&batch, 3_600_000);
+    let first = &buckets[&0][0];
+    println!(
+        "   sensor 0, first hour: n={} min {:.2} max {:.2} mean {:.3}",
+        first.count, first.min, first.max, first.mean
+    );
+
+    // 4. Dedicated pools
+    println!("\n4. Explicit pool sizes (use --release):");
+    for threads in [1, 2, 4] {
+        let pool = match ThreadPoolBuilder::new().num_threads(threads).build() {
+            Ok(pool) => pool,
+            Err(e) => {
+                println!("   {} threads: {}", threads, e);
+                continue;
+            }
+        };
+        let elapsed = time(5, || pool.install(|| par::summarise_chunks(&batch)));
+        println!("   {} threads: {:>9.2?}", threads, elapsed);
+    }
+    if cores == 1 {
+        println!("   (one core here: extra threads can only add overhead)");
+    }
+
+    // 5. Small batches: the overhead without the benefit
+    println!("\n5. Batch size vs overhead:");
+    println!(
+        "   {:>9} {:>11} {:>11} {:>11}",
+        "readings", "sequential", "par_iter", "auto"
+    );
+    for n in [100, 1_000, 10_000, 100_000, 1_000_000] {
+        let slice: &[Reading] = &batch[..n];
+        let reps = (2_000_000 / n).clamp(5, 200) as u32;
+        let s = time(reps, || seq::summarise(slice));
+        let p = time(reps, || par::summarise(slice));
+        let a = time(reps, || par::summarise_auto(slice));
+        println!("   {:>9} {:>11.1?} {:>11.1?} {:>11.1?}", n, s, p, a);
+    }
+    println!(
+        "   auto stays sequential below {} readings",
+        par::MIN_PARALLEL
+    );
+
+    // 6. Per-chunk latency
+    println!("\n6. Per-chunk latency (use --release):");
+    for chunk in [1_024, 8_192] {
+        let (_, latency) = par::summarise_timed(&batch, chunk);
+        println!(
+            "   {:>5} readings/chunk, {:>5} chunks: {}",
+            chunk,
+            latency.count(),
+            latency.percentiles()
+        );
+    }
+
+    println!("\n=== End of Rayon Batch Processing Examples ===");
+}
@@ -0,0 +1,103 @@
+// The same pipeline on rayon's thread pool
+//
+// Each function is the sequential one with `iter()` swapped for
+// `par_iter()` or `par_chunks()`, plus the one thing a parallel version
+// needs on top: a way to combine partial results (`Summary::merge`,
+// appending groups). rayon splits the slice, runs the pieces on its worker
+// threads by work stealing, and combines the pieces in order, so results
+// that depend on order (`collect`, grouped points) come out the same.
+
+use crate::reading::{Reading, Summary};
+use crate::sequential;
+use downsample::{aggregate, Bucket, Point};
+use histogram::Histogram;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+// Readings per task for the chunked versions: large enough that a task
+// does real work, small enough that every thread gets several
+pub const CHUNK: usize = 8192;
+
+// Below this, `summarise_auto` stays sequential (see the benchmarks)
+pub const MIN_PARALLEL: usize = 32_768;
+
+// fold: one Summary per rayon task, built like the sequential one;
+// reduce: merge the per-task summaries pairwise
+pub fn summarise(readings: &[Reading]) -> Summary {
+    readings
+        .par_iter()
+        .fold(Summary::default, Summary::record)
+        .reduce(Summary::default, Summary::merge)
+}
+
+// Explicit chunks: each task runs the sequential pipeline on its slice
+pub fn summarise_chunks(readings: &[Reading]) -> Summary {
+    readings
+        .par_chunks(CHUNK)
+        .map(sequential::summarise)
+        .reduce(Summary::default, Summary::merge)
+}
+
+pub fn summarise_auto(readings: &[Reading]) -> Summary {
+    if readings.len() < MIN_PARALLEL {
+        sequential::summarise(readings)
+    } else {
+        summarise_chunks(readings)
+    }
+}
+
+// The chunked pipeline, also reporting how long each chunk took. A
+// latency histogram has the same shape as `Summary`: an empty one, a way
+// to add a sample, and an associative merge, so it folds the same way
+pub fn summarise_timed(readings: &[Reading], chunk: usize) -> (Summary, Histogram) {
+    readings
+        .par_chunks(chunk.max(1))
+        .fold(
+            || (Summary::default(), Histogram::new()),
+            |(summary, mut latency), slice| {
+                let started = Instant::now();
+                let part = sequential::summarise(slice);
+                latency.record_duration(started.elapsed());
+                (summary.merge(part), latency)
+            },
+        )
+        .reduce(
+            || (Summary::default(), Histogram::new()),
+            |(left, mut latency), (right, other)| {
+                latency.merge(&other).expect("same precision");
+                (left.merge(right), latency)
+            },
+        )
+}
+
+// `collect` keeps input order, whatever order the threads finish in
+pub fn alerts(readings: &[Reading], limit: f64) -> Vec<Reading> {
+    readings
+        .par_iter()
+        .filter(|r| r.is_valid() && r.celsius() > limit)
+        .copied()
+        .collect()
+}
+
+// Each chunk groups its own readings; `reduce` sees neighbouring chunks
+// left then right, so appending keeps every sensor's points in order
+pub fn group_by_sensor(readings: &[Reading]) -> BTreeMap<u16, Vec<Point>> {
+    readings
+        .par_chunks(CHUNK)
+        .map(sequential::group_by_sensor)
+        .reduce(BTreeMap::new, |mut left, right| {
+            for (id, mut points) in right {
+                left.entry(id).or_default().append(&mut points);
+            }
+            left
+        })
+}
+
+// One task per sensor once the points are grouped
+pub fn buckets(readings: &[Reading], width_ms: u64) -> BTreeMap<u16, Vec<Bucket>> {
+    group_by_sensor(readings)
+        .into_par_iter()
+        .map(|(id, points)| (id, aggregate(&points, width_ms)))
+        .collect()
+}
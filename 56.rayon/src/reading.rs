@@ -0,0 +1,157 @@
+// Raw readings as they arrive in a batch, and the per-sensor summary the
+// pipeline reduces them to
+
+use std::collections::BTreeMap;
+
+pub const STATUS_OK: u8 = 0;
+// What a disconnected probe reads as
+pub const RAW_DISCONNECTED: i32 = 0x7FFF;
+// Plausible range for the probes, in centi-degrees
+pub const RAW_MIN: i32 = -4000;
+pub const RAW_MAX: i32 = 12500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reading {
+    pub sensor_id: u16,
+    pub timestamp_ms: u64,
+    // Centi-degrees Celsius, as the node sends them
+    pub raw: i32,
+    pub status: u8,
+}
+
+impl Reading {
+    pub fn is_valid(&self) -> bool {
+        self.status == STATUS_OK && (RAW_MIN..=RAW_MAX).contains(&self.raw)
+    }
+
+    pub fn celsius(&self) -> f64 {
+        self.raw as f64 / 100.0
+    }
+}
+
+// A batch from `sensors` nodes at 1 Hz, with ~1% bad status and ~0.5%
+// disconnected probes mixed in
+pub fn simulate(count: usize, sensors: u16, seed: u64) -> Vec<Reading> {
+    let mut state = seed;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 33) as u32
+    };
+    (0..count)
+        .map(|i| {
+            let sensor_id = (i % sensors as usize) as u16;
+            let second = (i / sensors as usize) as u64;
+            let wave = ((second as f64 / 600.0).sin() * 400.0) as i32;
+            let roll = next() % 1000;
+            Reading {
+                sensor_id,
+                timestamp_ms: 1_700_000_000_000 + second * 1000,
+                raw: if roll < 5 {
+                    RAW_DISCONNECTED
+                } else {
+                    2100 + 50 * sensor_id as i32 + wave + (next() % 60) as i32 - 30
+                },
+                status: if (5..15).contains(&roll) {
+                    3
+                } else {
+                    STATUS_OK
+                },
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub sum: f64,
+}
+
+impl Stats {
+    pub fn new(value: f64) -> Stats {
+        Stats {
+            count: 1,
+            min: value,
+            max: value,
+            sum: value,
+        }
+    }
+
+    pub fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+    }
+
+    pub fn merge(&mut self, other: &Stats) {
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.sum += other.sum;
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+}
+
+// Everything the pipeline keeps from a batch. `record` folds one reading in,
+// `merge` combines two partial summaries; rayon needs both
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Summary {
+    pub sensors: BTreeMap<u16, Stats>,
+    pub rejected: usize,
+}
+
+impl Summary {
+    pub fn record(mut self, reading: &Reading) -> Summary {
+        if !reading.is_valid() {
+            self.rejected += 1;
+            return self;
+        }
+        let value = reading.celsius();
+        self.sensors
+            .entry(reading.sensor_id)
+            .and_modify(|s| s.add(value))
+            .or_insert_with(|| Stats::new(value));
+        self
+    }
+
+    pub fn merge(mut self, other: Summary) -> Summary {
+        for (id, stats) in &other.sensors {
+            self.sensors
+                .entry(*id)
+                .and_modify(|s| s.merge(stats))
+                .or_insert(*stats);
+        }
+        self.rejected += other.rejected;
+        self
+    }
+
+    pub fn accepted(&self) -> usize {
+        self.sensors.values().map(|s| s.count).sum()
+    }
+
+    // Counts, minima and maxima must be identical; sums only close, since
+    // a parallel reduction adds them in a different order
+    pub fn agrees_with(&self, other: &Summary) -> bool {
+        self.rejected == other.rejected
+            && self.sensors.len() == other.sensors.len()
+            && self
+                .sensors
+                .iter()
+                .zip(&other.sensors)
+                .all(|((a, x), (b, y))| {
+                    a == b
+                        && x.count == y.count
+                        && x.min == y.min
+                        && x.max == y.max
+                        && (x.sum - y.sum).abs() <= 1e-9 * x.sum.abs().max(1.0)
+                })
+    }
+}
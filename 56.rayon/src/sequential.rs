@@ -0,0 +1,39 @@
+// The batch pipeline with ordinary iterators: the reference, and the
+// faster choice for small batches
+
+use crate::reading::{Reading, Summary};
+use downsample::{aggregate, Bucket, Point};
+use std::collections::BTreeMap;
+
+pub fn summarise(readings: &[Reading]) -> Summary {
+    readings.iter().fold(Summary::default(), Summary::record)
+}
+
+// Valid readings above `limit` °C, in input order
+pub fn alerts(readings: &[Reading], limit: f64) -> Vec<Reading> {
+    readings
+        .iter()
+        .filter(|r| r.is_valid() && r.celsius() > limit)
+        .copied()
+        .collect()
+}
+
+// Valid points per sensor, in timestamp order
+pub fn group_by_sensor(readings: &[Reading]) -> BTreeMap<u16, Vec<Point>> {
+    let mut groups: BTreeMap<u16, Vec<Point>> = BTreeMap::new();
+    for r in readings.iter().filter(|r| r.is_valid()) {
+        groups
+            .entry(r.sensor_id)
+            .or_default()
+            .push((r.timestamp_ms, r.celsius()));
+    }
+    groups
+}
+
+// Fixed-width buckets per sensor, from 12.downsample
+pub fn buckets(readings: &[Reading], width_ms: u64) -> BTreeMap<u16, Vec<Bucket>> {
+    group_by_sensor(readings)
+        .into_iter()
+        .map(|(id, points)| (id, aggregate(&points, width_ms)))
+        .collect()
+}
@@ -0,0 +1,129 @@
+use parallel::reading::{RAW_DISCONNECTED, RAW_MAX, RAW_MIN, STATUS_OK};
+use parallel::{parallel as par, sequential as seq, simulate, Reading, Stats, Summary};
+use rayon::ThreadPoolBuilder;
+
+fn reading(sensor_id: u16, raw: i32, status: u8) -> Reading {
+    Reading {
+        sensor_id,
+        timestamp_ms: 1_700_000_000_000,
+        raw,
+        status,
+    }
+}
+
+#[test]
+fn bad_readings_are_rejected_not_summarised() {
+    assert!(reading(0, RAW_MIN, STATUS_OK).is_valid());
+    assert!(reading(0, RAW_MAX, STATUS_OK).is_valid());
+    for bad in [
+        reading(0, RAW_MIN - 1, STATUS_OK),
+        reading(0, RAW_MAX + 1, STATUS_OK),
+        reading(0, RAW_DISCONNECTED, STATUS_OK),
+        reading(0, 2100, 3),
+    ] {
+        assert!(!bad.is_valid(), "{:?}", bad);
+    }
+    let summary = [
+        reading(1, 2150, STATUS_OK),
+        reading(1, RAW_DISCONNECTED, STATUS_OK),
+        reading(1, 1950, STATUS_OK),
+        reading(2, 2000, 3),
+    ]
+    .iter()
+    .fold(Summary::default(), Summary::record);
+    assert_eq!(summary.rejected, 2);
+    assert_eq!(summary.accepted(), 2);
+    let stats = summary.sensors[&1];
+    assert_eq!((stats.count, stats.min, stats.max), (2, 19.5, 21.5));
+    assert_eq!(stats.mean(), 20.5);
+    assert!(!summary.sensors.contains_key(&2));
+}
+
+#[test]
+fn merging_halves_equals_summarising_the_whole() {
+    let batch = simulate(10_000, 8, 3);
+    let (left, right) = batch.split_at(3_333);
+    let merged = seq::summarise(left).merge(seq::summarise(right));
+    assert!(merged.agrees_with(&seq::summarise(&batch)));
+    // Merging into an empty summary is the identity
+    assert_eq!(Summary::default().merge(merged.clone()), merged);
+
+    let mut a = Stats::new(2.0);
+    a.add(5.0);
+    let mut b = Stats::new(-1.0);
+    b.merge(&a);
+    assert_eq!((b.count, b.min, b.max, b.sum), (3, -1.0, 5.0, 6.0));
+}
+
+#[test]
+fn agrees_with_notices_any_difference() {
+    let reference = seq::summarise(&simulate(5_000, 4, 11));
+    assert!(reference.agrees_with(&reference.clone()));
+    let mut other = reference.clone();
+    other.rejected += 1;
+    assert!(!other.agrees_with(&reference));
+    let mut other = reference.clone();
+    other.sensors.get_mut(&2).unwrap().max += 0.01;
+    assert!(!other.agrees_with(&reference));
+    // A sum off by rounding is still the same summary
+    let mut other = reference.clone();
+    let stats = other.sensors.get_mut(&2).unwrap();
+    stats.sum += stats.sum * 1e-12;
+    assert!(other.agrees_with(&reference));
+}
+
+#[test]
+fn every_parallel_summary_matches_the_sequential_one() {
+    let batch = simulate(16 * 3_600, 16, 42);
+    for n in [0, 1, 100, 8_191, 8_193, 40_000, batch.len()] {
+        let slice = &batch[..n];
+        let reference = seq::summarise(slice);
+        assert_eq!(reference.accepted() + reference.rejected, n);
+        assert!(par::summarise(slice).agrees_with(&reference), "{}", n);
+        assert!(
+            par::summarise_chunks(slice).agrees_with(&reference),
+            "{}",
+            n
+        );
+        assert!(par::summarise_auto(slice).agrees_with(&reference), "{}", n);
+    }
+}
+
+#[test]
+fn below_the_threshold_auto_is_bit_for_bit_sequential() {
+    let batch = simulate(par::MIN_PARALLEL - 1, 16, 5);
+    assert_eq!(par::summarise_auto(&batch), seq::summarise(&batch));
+}
+
+#[test]
+fn order_preserving_steps_are_identical() {
+    let batch = simulate(16 * 3_600, 16, 42);
+    let alerts = par::alerts(&batch, 23.0);
+    assert!(!alerts.is_empty());
+    assert_eq!(alerts, seq::alerts(&batch, 23.0));
+    assert!(alerts
+        .windows(2)
+        .all(|w| w[0].timestamp_ms <= w[1].timestamp_ms));
+
+    assert_eq!(par::group_by_sensor(&batch), seq::group_by_sensor(&batch));
+    let buckets = par::buckets(&batch, 600_000);
+    assert_eq!(buckets, seq::buckets(&batch, 600_000));
+    assert_eq!(buckets.len(), 16);
+}
+
+#[test]
+fn every_pool_size_gives_the_same_summary() {
+    let batch = simulate(16 * 3_600, 16, 42);
+    let reference = seq::summarise(&batch);
+    for threads in [1, 2, 4, 7] {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        assert_eq!(pool.current_num_threads(), threads);
+        let summary = pool.install(|| par::summarise_chunks(&batch));
+        assert!(summary.agrees_with(&reference), "{} threads", threads);
+        let alerts = pool.install(|| par::alerts(&batch, 23.0));
+        assert_eq!(alerts, seq::alerts(&batch, 23.0), "{} threads", threads);
+    }
+}
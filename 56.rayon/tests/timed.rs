@@ -0,0 +1,29 @@
+use parallel::{parallel as par, sequential as seq, simulate};
+
+#[test]
+fn one_latency_sample_per_chunk() {
+    let batch = simulate(16 * 3_600, 16, 42);
+    let reference = seq::summarise(&batch);
+    for chunk in [1, 1_000, 1_024, 8_192, batch.len(), 10 * batch.len()] {
+        let (summary, latency) = par::summarise_timed(&batch, chunk);
+        assert_eq!(latency.count(), batch.len().div_ceil(chunk) as u64);
+        assert!(summary.agrees_with(&reference), "chunk {}", chunk);
+        assert!(latency.min() <= latency.value_at_percentile(50.0));
+        assert!(latency.value_at_percentile(99.9) <= latency.max());
+    }
+}
+
+#[test]
+fn a_zero_chunk_is_one_reading_per_chunk() {
+    let batch = simulate(500, 4, 7);
+    let (summary, latency) = par::summarise_timed(&batch, 0);
+    assert_eq!(latency.count(), 500);
+    assert!(summary.agrees_with(&seq::summarise(&batch)));
+}
+
+#[test]
+fn an_empty_batch_records_nothing() {
+    let (summary, latency) = par::summarise_timed(&[], 1_024);
+    assert!(latency.is_empty());
+    assert_eq!(summary.accepted(), 0);
+}
@@ -0,0 +1,81 @@
+// The three NMEA parsers side by side
+//
+//   cargo bench                  # allocation table, then every group
+//   cargo bench -- drive         # allocation table, then one group
+//
+// The counting allocator from src/counting.rs is installed here too, so
+// the table printed first shows what each parser allocates per sentence,
+// and the times include that cost.
+
+use criterion::{criterion_group, BenchmarkId, Criterion, Throughput};
+use nom_nmea::counting::{count, CountingAlloc};
+use nom_nmea::{drive, parsers};
+use std::hint::black_box;
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const GGA: &str = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+const RMC: &str = "$GPRMC,225446,A,4916.45,N,12311.12,W,000.5,054.7,191194,020.3,E*68";
+const BAD_CHECKSUM: &str = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*48";
+
+fn allocation_table() {
+    let lines = drive(1000);
+    println!("Allocations (allocs + reallocs) for 1000 sentences of the drive:");
+    for p in parsers() {
+        let (ok, a) = count(|| {
+            lines
+                .iter()
+                .filter(|l| black_box(p.parse(l.as_bytes())).is_ok())
+                .count()
+        });
+        println!(
+            "  {:<15} {:>5} allocations, {:>7} bytes, {} parsed",
+            p.name(),
+            a.allocs + a.reallocs,
+            a.bytes,
+            ok
+        );
+    }
+    println!();
+}
+
+fn single(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sentence");
+    for (label, line) in [("gga", GGA), ("rmc", RMC), ("bad_checksum", BAD_CHECKSUM)] {
+        for p in parsers() {
+            group.bench_with_input(BenchmarkId::new(p.name(), label), line, |b, l| {
+                b.iter(|| p.parse(black_box(l.as_bytes())))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn stream(c: &mut Criterion) {
+    let lines = drive(1000);
+    let mut group = c.benchmark_group("drive");
+    group.throughput(Throughput::Elements(lines.len() as u64));
+    for p in parsers() {
+        group.bench_function(p.name(), |b| {
+            b.iter(|| {
+                lines
+                    .iter()
+                    .filter(|l| p.parse(black_box(l.as_bytes())).is_ok())
+                    .count()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, single, stream);
+
+// criterion_main! plus the allocation table first
+fn main() {
+    allocation_table();
+    benches();
+    criterion::Criterion::default()
+        .configure_from_args()
+        .final_summary();
+}
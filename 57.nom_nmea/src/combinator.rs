@@ -0,0 +1,262 @@
+// The same grammar built from nom combinators
+//
+// Every parser takes `&[u8]` and returns the rest of the input with the
+// value it recognised, so nothing is copied and nothing is allocated. The
+// grammar reads top-down, one function per rule:
+//
+//   line        = ws "$" body "*" hex hex ws EOF
+//   body        = kind ( gga | rmc ) rest
+//   gga         = ",time" coordinate coordinate ",quality" ",sats" ",hdop" ",alt"
+//   coordinate  = ",ddmm.mmmm" ",N|S"
+//
+// nom's own errors only say which combinator failed. `Error` wraps the
+// `NmeaError` each rule reports instead, so callers see the same error type
+// as with the other parsers.
+
+use gps::{Date, FixQuality, Gga, NmeaError, Rmc, Sentence, Time};
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take, take_till, take_while_m_n};
+use nom::character::complete::{char, multispace0, u8 as decimal_u8};
+use nom::combinator::{all_consuming, eof, map, rest, value, verify};
+use nom::error::{ErrorKind, ParseError};
+use nom::number::complete::{double, float};
+use nom::sequence::{preceded, terminated};
+use nom::{IResult, Parser};
+
+#[derive(Debug)]
+pub struct Error(pub NmeaError);
+
+impl<I> ParseError<I> for Error {
+    // Only seen if a rule forgets to name its error
+    fn from_error_kind(_: I, _: ErrorKind) -> Error {
+        Error(NmeaError::InvalidField("syntax"))
+    }
+
+    fn append(_: I, _: ErrorKind, other: Error) -> Error {
+        other
+    }
+}
+
+type Res<'a, O> = IResult<&'a [u8], O, Error>;
+
+pub fn parse(line: &[u8]) -> Result<Sentence, NmeaError> {
+    let (_, (body, expected)) = finish(framing(line))?;
+    let computed = body.iter().fold(0, |acc, b| acc ^ b);
+    if expected != computed {
+        return Err(NmeaError::BadChecksum { expected, computed });
+    }
+    finish(sentence(body)).map(|(_, s)| s)
+}
+
+fn finish<'a, O>(result: Res<'a, O>) -> Result<(&'a [u8], O), NmeaError> {
+    result.map_err(|e| match e {
+        nom::Err::Error(Error(e)) | nom::Err::Failure(Error(e)) => e,
+        // Complete parsers never ask for more input
+        nom::Err::Incomplete(_) => NmeaError::MissingChecksum,
+    })
+}
+
+// Replace whatever error `parser` produces with `error`
+fn or_fail<'a, O>(
+    mut parser: impl Parser<&'a [u8], Output = O, Error = Error>,
+    error: NmeaError,
+) -> impl Parser<&'a [u8], Output = O, Error = Error> {
+    move |i| parser.parse(i).map_err(|e| e.map(|_| Error(error.clone())))
+}
+
+fn hex_byte(i: &[u8]) -> Res<'_, u8> {
+    map(
+        take_while_m_n(2, 2, |b: u8| b.is_ascii_hexdigit()),
+        |hh: &[u8]| {
+            hh.iter().fold(0, |acc, &b| {
+                acc << 4 | (b as char).to_digit(16).unwrap_or(0) as u8
+            })
+        },
+    )
+    .parse(i)
+}
+
+// -> (body, checksum from the sentence)
+fn framing(i: &[u8]) -> Res<'_, (&[u8], u8)> {
+    let (i, _) = or_fail(preceded(multispace0, char('$')), NmeaError::MissingDollar).parse(i)?;
+    let (i, body) = take_till(|b| b == b'*').parse(i)?;
+    let (i, sum) = or_fail(
+        terminated(preceded(char('*'), hex_byte), (multispace0, eof)),
+        NmeaError::MissingChecksum,
+    )
+    .parse(i)?;
+    Ok((i, (body, sum)))
+}
+
+fn sentence(i: &[u8]) -> Res<'_, Sentence> {
+    let (i, kind) = take_till(|b| b == b',').parse(i)?;
+    let (i, s) = match kind {
+        [_, _, b'G', b'G', b'A'] => map(gga, Sentence::Gga).parse(i)?,
+        [_, _, b'R', b'M', b'C'] => map(rmc, Sentence::Rmc).parse(i)?,
+        _ => {
+            let kind = String::from_utf8_lossy(kind).into_owned();
+            return Err(nom::Err::Failure(Error(NmeaError::Unsupported(kind))));
+        }
+    };
+    // Later fields (geoid height, magnetic variation, ...) are not used
+    let (i, _) = rest(i)?;
+    Ok((i, s))
+}
+
+// ",<value>" where `parser` must consume the whole value
+fn field<'a, O>(
+    name: &'static str,
+    mut parser: impl Parser<&'a [u8], Output = O, Error = Error>,
+) -> impl Parser<&'a [u8], Output = O, Error = Error> {
+    move |i: &'a [u8]| {
+        let (i, raw) = or_fail(
+            preceded(char(','), take_till(|b| b == b',')),
+            NmeaError::MissingField(name),
+        )
+        .parse(i)?;
+        let whole = terminated(|r| parser.parse(r), eof);
+        let (_, value) = or_fail(whole, NmeaError::InvalidField(name)).parse(raw)?;
+        Ok((i, value))
+    }
+}
+
+// An empty field means "no data"
+fn optional<'a, O>(
+    parser: impl Parser<&'a [u8], Output = O, Error = Error>,
+) -> impl Parser<&'a [u8], Output = Option<O>, Error = Error> {
+    alt((map(eof, |_| None), map(parser, Some)))
+}
+
+fn two_digits(i: &[u8]) -> Res<'_, u8> {
+    map(
+        take_while_m_n(2, 2, |b: u8| b.is_ascii_digit()),
+        |d: &[u8]| (d[0] - b'0') * 10 + (d[1] - b'0'),
+    )
+    .parse(i)
+}
+
+// hhmmss(.sss)
+fn time(i: &[u8]) -> Res<'_, Time> {
+    let seconds = verify(rest, |s: &[u8]| s.len() >= 2).and_then(all_consuming(float));
+    map(
+        verify((two_digits, two_digits, seconds), |&(h, m, s)| {
+            h <= 23 && m <= 59 && s < 61.0
+        }),
+        |(hour, minute, second)| Time {
+            hour,
+            minute,
+            second,
+        },
+    )
+    .parse(i)
+}
+
+// ddmmyy, with two-digit years pivoting at 80
+fn date(i: &[u8]) -> Res<'_, Date> {
+    map(
+        verify((two_digits, two_digits, two_digits), |&(d, m, _)| {
+            (1..=31).contains(&d) && (1..=12).contains(&m)
+        }),
+        |(day, month, yy)| Date {
+            day,
+            month,
+            year: if yy >= 80 {
+                1900 + yy as u16
+            } else {
+                2000 + yy as u16
+            },
+        },
+    )
+    .parse(i)
+}
+
+fn magnitude<'a>(degree_digits: usize) -> impl Parser<&'a [u8], Output = f64, Error = Error> {
+    let degrees = take(degree_digits).and_then(all_consuming(double));
+    let minutes = verify(rest, |m: &[u8]| m.len() >= 2).and_then(all_consuming(double));
+    map(verify((degrees, minutes), |&(_, m)| m < 60.0), |(d, m)| {
+        d + m / 60.0
+    })
+}
+
+fn hemisphere(i: &[u8]) -> Res<'_, f64> {
+    alt((
+        value(1.0, alt((tag("N"), tag("E")))),
+        value(-1.0, alt((tag("S"), tag("W")))),
+    ))
+    .parse(i)
+}
+
+// Two fields: the magnitude and its hemisphere, both empty without a fix
+fn coordinate<'a>(
+    value_name: &'static str,
+    hemisphere_name: &'static str,
+    degree_digits: usize,
+) -> impl Parser<&'a [u8], Output = Option<f64>, Error = Error> {
+    move |i: &'a [u8]| {
+        let (i, raw_value) = field(value_name, rest).parse(i)?;
+        let (i, raw_hemisphere) = field(hemisphere_name, rest).parse(i)?;
+        if raw_value.is_empty() && raw_hemisphere.is_empty() {
+            return Ok((i, None));
+        }
+        let (_, m) = or_fail(
+            terminated(magnitude(degree_digits), eof),
+            NmeaError::InvalidField("coordinate"),
+        )
+        .parse(raw_value)?;
+        let (_, sign) = or_fail(
+            terminated(hemisphere, eof),
+            NmeaError::InvalidField("hemisphere"),
+        )
+        .parse(raw_hemisphere)?;
+        Ok((i, Some(sign * m)))
+    }
+}
+
+fn gga(i: &[u8]) -> Res<'_, Gga> {
+    map(
+        (
+            field("time", optional(time)),
+            coordinate("latitude", "N/S", 2),
+            coordinate("longitude", "E/W", 3),
+            field("fix quality", decimal_u8),
+            field("satellites", optional(decimal_u8)),
+            field("hdop", optional(float)),
+            field("altitude", optional(float)),
+        ),
+        |(time, latitude, longitude, quality, satellites, hdop, altitude_m)| Gga {
+            time,
+            latitude,
+            longitude,
+            fix_quality: FixQuality::from_digit(quality),
+            satellites: satellites.unwrap_or(0),
+            hdop,
+            altitude_m,
+        },
+    )
+    .parse(i)
+}
+
+fn rmc(i: &[u8]) -> Res<'_, Rmc> {
+    let status = alt((value(true, char('A')), value(false, char('V'))));
+    map(
+        (
+            field("time", optional(time)),
+            field("status", status),
+            coordinate("latitude", "N/S", 2),
+            coordinate("longitude", "E/W", 3),
+            field("speed", optional(float)),
+            field("course", optional(float)),
+            field("date", optional(date)),
+        ),
+        |(time, active, latitude, longitude, speed_knots, course_deg, date)| Rmc {
+            time,
+            active,
+            latitude,
+            longitude,
+            speed_knots,
+            course_deg,
+            date,
+        },
+    )
+    .parse(i)
+}
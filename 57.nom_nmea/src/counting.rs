@@ -0,0 +1,61 @@
+// A global allocator that counts, for measuring what a parser allocates
+//
+// The library only defines the type; a binary opts in with
+//
+//   #[global_allocator]
+//   static GLOBAL: CountingAlloc = CountingAlloc;
+//
+// and then wraps the code under test in `count`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub struct CountingAlloc;
+
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+static REALLOCS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+
+// SAFETY: every call is forwarded unchanged to the system allocator
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        REALLOCS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(new_size.saturating_sub(layout.size()), Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Allocations {
+    pub allocs: usize,
+    pub reallocs: usize,
+    pub bytes: usize,
+}
+
+// Allocations made while `f` runs, including ones it frees again. Counts
+// are process-wide, so other threads must be idle. Always zero unless the
+// binary installed `CountingAlloc`
+pub fn count<T>(f: impl FnOnce() -> T) -> (T, Allocations) {
+    let before = (
+        ALLOCS.load(Ordering::Relaxed),
+        REALLOCS.load(Ordering::Relaxed),
+        BYTES.load(Ordering::Relaxed),
+    );
+    let result = f();
+    let after = Allocations {
+        allocs: ALLOCS.load(Ordering::Relaxed) - before.0,
+        reallocs: REALLOCS.load(Ordering::Relaxed) - before.1,
+        bytes: BYTES.load(Ordering::Relaxed) - before.2,
+    };
+    (result, after)
+}
@@ -0,0 +1,108 @@
+// One NMEA grammar, three parsers
+//
+// 09.gps parses NMEA 0183 with `&str` methods and a `Vec` of fields. This
+// crate parses the same sentences into the same `gps::Sentence` twice more:
+//
+// - `manual`: hand-written, straight from the byte slice, no allocation
+// - `combinator`: built from nom combinators over `&[u8]`
+//
+// `NmeaParser` puts all three behind one interface, so the demo and the
+// benchmarks can check they agree and compare their cost.
+
+pub mod combinator;
+pub mod counting;
+pub mod manual;
+
+use gps::{checksum, NmeaError, Sentence};
+use std::borrow::Cow;
+
+pub trait NmeaParser {
+    fn name(&self) -> &'static str;
+    fn parse(&self, line: &[u8]) -> Result<Sentence, NmeaError>;
+}
+
+// 09.gps as it is; non-UTF-8 bytes are replaced, as `NmeaReader` does
+pub struct Reference;
+
+pub struct Manual;
+
+pub struct Nom;
+
+impl NmeaParser for Reference {
+    fn name(&self) -> &'static str {
+        "gps (&str)"
+    }
+
+    fn parse(&self, line: &[u8]) -> Result<Sentence, NmeaError> {
+        // Borrowed, and free, when the line is valid UTF-8
+        let text: Cow<str> = String::from_utf8_lossy(line);
+        gps::parse_sentence(&text)
+    }
+}
+
+impl NmeaParser for Manual {
+    fn name(&self) -> &'static str {
+        "manual (&[u8])"
+    }
+
+    fn parse(&self, line: &[u8]) -> Result<Sentence, NmeaError> {
+        manual::parse(line)
+    }
+}
+
+impl NmeaParser for Nom {
+    fn name(&self) -> &'static str {
+        "nom (&[u8])"
+    }
+
+    fn parse(&self, line: &[u8]) -> Result<Sentence, NmeaError> {
+        combinator::parse(line)
+    }
+}
+
+pub fn parsers() -> [&'static dyn NmeaParser; 3] {
+    [&Reference, &Manual, &Nom]
+}
+
+// A drive around a city block: alternating GGA and RMC sentences with
+// valid checksums, varying positions, and every fifth fix missing
+pub fn drive(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|i| {
+            let second = i / 2;
+            let time = format!(
+                "{:02}{:02}{:02}.{:02}",
+                10 + second / 3600 % 10,
+                second / 60 % 60,
+                second % 60,
+                i % 100
+            );
+            let lat = format!("{:02}{:07.4}", 48, 7.038 + (i % 500) as f64 * 0.0113);
+            let lon = format!("{:03}{:07.4}", 11, 31.0 + (i % 700) as f64 * 0.0071);
+            let no_fix = i % 10 >= 8;
+            let body = match (i % 2, no_fix) {
+                (0, false) => format!(
+                    "GPGGA,{},{},N,{},E,1,{:02},0.{},{}.{},M,46.9,M,,",
+                    time,
+                    lat,
+                    lon,
+                    4 + i % 9,
+                    5 + i % 5,
+                    540 + i % 20,
+                    i % 10
+                ),
+                (0, true) => format!("GPGGA,{},,,,,0,00,,,M,,M,,", time),
+                (_, false) => format!(
+                    "GNRMC,{},A,{},N,{},E,{:05.1},{:05.1},191194,020.3,E",
+                    time,
+                    lat,
+                    lon,
+                    (i % 40) as f64 * 0.5,
+                    (i * 7 % 3600) as f64 / 10.0
+                ),
+                (_, true) => format!("GNRMC,{},V,,,,,,,191194,,", time),
+            };
+            format!("${}*{:02X}", body, checksum(&body))
+        })
+        .collect()
+}
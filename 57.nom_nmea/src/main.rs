@@ -0,0 +1,144 @@
+use gps::{checksum, Sentence};
+use nom_nmea::counting::{count, CountingAlloc};
+use nom_nmea::{drive, parsers};
+use std::hint::black_box;
+use std::time::Instant;
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const GGA: &str = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+const RMC: &str = "$GPRMC,225446,A,4916.45,N,12311.12,W,000.5,054.7,191194,020.3,E*68";
+
+fn with_checksum(body: &str) -> String {
+    format!("${}*{:02X}", body, checksum(body))
+}
+
+fn main() {
+    println!("=== nom vs Hand-Written NMEA Parsing Examples ===\n");
+    let parsers = parsers();
+
+    // 1. Three parsers, one sentence
+    println!("1. One GGA sentence:");
+    let results: Vec<_> = parsers.iter().map(|p| p.parse(GGA.as_bytes())).collect();
+    for (p, r) in parsers.iter().zip(&results) {
+        match r {
+            Ok(Sentence::Gga(fix)) => println!(
+                "   {:<15} {:.6}, {:.6}  {} sats, {:?} m",
+                p.name(),
+                fix.latitude.unwrap_or_default(),
+                fix.longitude.unwrap_or_default(),
+                fix.satellites,
+                fix.altitude_m
+            ),
+            other => println!("   {:<15} {:?}", p.name(), other),
+        }
+    }
+
+    // 2. A long, varied input
+    println!("\n2. 10,000 sentences from a simulated drive:");
+    let lines = drive(10_000);
+    let mut accepted = [0usize; 3];
+    let mut differing = 0;
+    for line in &lines {
+        let reference = parsers[0].parse(line.as_bytes());
+        for (n, p) in parsers.iter().enumerate() {
+            let result = p.parse(line.as_bytes());
+            accepted[n] += result.is_ok() as usize;
+            differing += (result != reference) as usize;
+        }
+    }
+    for (p, n) in parsers.iter().zip(accepted) {
+        println!("   {:<15} accepted {}", p.name(), n);
+    }
+    println!(
+        "   results differing from {}: {}",
+        parsers[0].name(),
+        differing
+    );
+
+    // 3. Input that must be rejected
+    println!("\n3. Malformed lines:");
+    let non_utf8 = {
+        let mut bytes = GGA.as_bytes().to_vec();
+        bytes[8] = 0xB5;
+        let fixed = bytes[1..bytes.len() - 3].iter().fold(0, |a, b| a ^ b);
+        let len = bytes.len();
+        bytes.splice(len - 2.., format!("{:02X}", fixed).into_bytes());
+        bytes
+    };
+    let cases: Vec<(&str, Vec<u8>)> = vec![
+        ("no '$'", GGA[1..].into()),
+        ("no checksum", GGA[..GGA.len() - 3].into()),
+        ("wrong checksum", GGA.replace("*47", "*48").into()),
+        (
+            "GSV, not supported",
+            with_checksum("GPGSV,3,1,11,03,03,111,00").into(),
+        ),
+        (
+            "RMC status X",
+            with_checksum("GPRMC,225446,X,4916.45,N,12311.12,W,,,191194,,").into(),
+        ),
+        (
+            "minutes >= 60",
+            with_checksum("GPGGA,123519,4867.038,N,01131.000,E,1,08,0.9,545.4,M,,M,,").into(),
+        ),
+        (
+            "hemisphere Q",
+            with_checksum("GPGGA,123519,4807.038,Q,01131.000,E,1,08,0.9,545.4,M,,M,,").into(),
+        ),
+        (
+            "hour 25",
+            with_checksum("GPRMC,255446,A,4916.45,N,12311.12,W,,,191194,,").into(),
+        ),
+        ("truncated GGA", "$GPGGA,123519*77".into()),
+        ("non-UTF-8 byte", non_utf8),
+    ];
+    for (label, line) in &cases {
+        println!("   {}:", label);
+        let results: Vec<_> = parsers.iter().map(|p| p.parse(line)).collect();
+        for (p, r) in parsers.iter().zip(&results) {
+            match r {
+                Ok(_) => println!("     {:<15} accepted", p.name()),
+                Err(e) => println!("     {:<15} {}", p.name(), e),
+            }
+        }
+    }
+
+    // 4. What each parse costs in heap allocations
+    println!("\n4. Heap allocations per call (allocs/reallocs, bytes):");
+    let inputs = [
+        ("GGA", GGA.as_bytes().to_vec()),
+        ("RMC", RMC.as_bytes().to_vec()),
+        (
+            "GSV",
+            with_checksum("GPGSV,3,1,11,03,03,111,00").into_bytes(),
+        ),
+    ];
+    for p in parsers {
+        let cells: Vec<String> = inputs
+            .iter()
+            .map(|(kind, line)| {
+                let (_, a) = count(|| black_box(p.parse(black_box(line))));
+                format!("{} {}/{}, {:>3} B", kind, a.allocs, a.reallocs, a.bytes)
+            })
+            .collect();
+        println!("   {:<15} {}", p.name(), cells.join("   "));
+    }
+
+    // 5. Rough timing; `cargo bench` measures properly
+    println!("\n5. Parsing the drive, mean per sentence (use --release):");
+    for p in parsers {
+        let started = Instant::now();
+        let mut ok = 0;
+        for _ in 0..5 {
+            for line in &lines {
+                ok += black_box(p.parse(black_box(line.as_bytes()))).is_ok() as usize;
+            }
+        }
+        let per_line = started.elapsed() / (5 * lines.len() as u32);
+        println!("   {:<15} {:>7.0?}  ({} ok)", p.name(), per_line, ok);
+    }
+
+    println!("\n=== End of nom vs Hand-Written NMEA Parsing Examples ===");
+}
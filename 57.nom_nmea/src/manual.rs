@@ -0,0 +1,179 @@
+// A hand-rolled parser that works on the raw bytes
+//
+// The same grammar as 09.gps, but without building a `Vec` of fields:
+// `Fields` walks the body one comma at a time, and every value is read
+// straight from the input slice. Integers are decoded digit by digit.
+// Floats go through `str::parse`, which is exact and does not allocate,
+// so hand-rolling them would only add bugs.
+
+use gps::{Date, FixQuality, Gga, NmeaError, Rmc, Sentence, Time};
+use std::str::FromStr;
+
+pub fn parse(line: &[u8]) -> Result<Sentence, NmeaError> {
+    let body = verify(line.trim_ascii())?;
+    let mut fields = Fields(body.split(|&b| b == b','));
+    // `split` always yields at least one item
+    let kind = fields.0.next().unwrap_or_default();
+    match kind {
+        [_, _, b'G', b'G', b'A'] => parse_gga(&mut fields).map(Sentence::Gga),
+        [_, _, b'R', b'M', b'C'] => parse_rmc(&mut fields).map(Sentence::Rmc),
+        _ => Err(NmeaError::Unsupported(
+            String::from_utf8_lossy(kind).into_owned(),
+        )),
+    }
+}
+
+// "$BODY*HH" -> BODY, checksum verified
+fn verify(line: &[u8]) -> Result<&[u8], NmeaError> {
+    let rest = line.strip_prefix(b"$").ok_or(NmeaError::MissingDollar)?;
+    let star = rest
+        .iter()
+        .rposition(|&b| b == b'*')
+        .ok_or(NmeaError::MissingChecksum)?;
+    let (body, sum) = (&rest[..star], &rest[star + 1..]);
+    let expected = match sum.trim_ascii_end() {
+        &[hi, lo] => hex(hi)
+            .zip(hex(lo))
+            .map(|(hi, lo)| hi << 4 | lo)
+            .ok_or(NmeaError::MissingChecksum)?,
+        _ => return Err(NmeaError::MissingChecksum),
+    };
+    let computed = body.iter().fold(0, |acc, b| acc ^ b);
+    if expected != computed {
+        return Err(NmeaError::BadChecksum { expected, computed });
+    }
+    Ok(body)
+}
+
+fn hex(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|d| d as u8)
+}
+
+struct Fields<'a, I: Iterator<Item = &'a [u8]>>(I);
+
+impl<'a, I: Iterator<Item = &'a [u8]>> Fields<'a, I> {
+    fn next(&mut self, name: &'static str) -> Result<&'a [u8], NmeaError> {
+        self.0.next().ok_or(NmeaError::MissingField(name))
+    }
+}
+
+fn digits(bytes: &[u8]) -> Option<u32> {
+    if bytes.is_empty() || bytes.len() > 9 {
+        return None;
+    }
+    bytes.iter().try_fold(0u32, |n, &b| {
+        b.is_ascii_digit().then(|| n * 10 + (b - b'0') as u32)
+    })
+}
+
+fn number<T: FromStr>(bytes: &[u8]) -> Option<T> {
+    std::str::from_utf8(bytes).ok()?.parse().ok()
+}
+
+fn optional<T: FromStr>(bytes: &[u8], name: &'static str) -> Result<Option<T>, NmeaError> {
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+    number(bytes).map(Some).ok_or(NmeaError::InvalidField(name))
+}
+
+fn time(bytes: &[u8]) -> Result<Option<Time>, NmeaError> {
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+    let invalid = NmeaError::InvalidField("time");
+    if bytes.len() < 6 {
+        return Err(invalid);
+    }
+    let hour = digits(&bytes[0..2]).ok_or(invalid.clone())?;
+    let minute = digits(&bytes[2..4]).ok_or(invalid.clone())?;
+    let second: f32 = number(&bytes[4..]).ok_or(invalid.clone())?;
+    if hour > 23 || minute > 59 || second >= 61.0 {
+        return Err(invalid);
+    }
+    Ok(Some(Time {
+        hour: hour as u8,
+        minute: minute as u8,
+        second,
+    }))
+}
+
+fn date(bytes: &[u8]) -> Result<Option<Date>, NmeaError> {
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+    let invalid = NmeaError::InvalidField("date");
+    if bytes.len() != 6 {
+        return Err(invalid);
+    }
+    let day = digits(&bytes[0..2]).ok_or(invalid.clone())?;
+    let month = digits(&bytes[2..4]).ok_or(invalid.clone())?;
+    let yy = digits(&bytes[4..6]).ok_or(invalid.clone())? as u16;
+    if !(1..=31).contains(&day) || !(1..=12).contains(&month) {
+        return Err(invalid);
+    }
+    Ok(Some(Date {
+        day: day as u8,
+        month: month as u8,
+        year: if yy >= 80 { 1900 + yy } else { 2000 + yy },
+    }))
+}
+
+fn coordinate(
+    value: &[u8],
+    hemisphere: &[u8],
+    degree_digits: usize,
+) -> Result<Option<f64>, NmeaError> {
+    if value.is_empty() && hemisphere.is_empty() {
+        return Ok(None);
+    }
+    let invalid = NmeaError::InvalidField("coordinate");
+    if value.len() < degree_digits + 2 {
+        return Err(invalid);
+    }
+    let degrees: f64 = number(&value[..degree_digits]).ok_or(invalid.clone())?;
+    let minutes: f64 = number(&value[degree_digits..]).ok_or(invalid.clone())?;
+    if minutes >= 60.0 {
+        return Err(invalid);
+    }
+    let magnitude = degrees + minutes / 60.0;
+    match hemisphere {
+        b"N" | b"E" => Ok(Some(magnitude)),
+        b"S" | b"W" => Ok(Some(-magnitude)),
+        _ => Err(NmeaError::InvalidField("hemisphere")),
+    }
+}
+
+fn parse_gga<'a>(f: &mut Fields<'a, impl Iterator<Item = &'a [u8]>>) -> Result<Gga, NmeaError> {
+    let time = time(f.next("time")?)?;
+    let latitude = coordinate(f.next("latitude")?, f.next("N/S")?, 2)?;
+    let longitude = coordinate(f.next("longitude")?, f.next("E/W")?, 3)?;
+    let quality = number(f.next("fix quality")?).ok_or(NmeaError::InvalidField("fix quality"))?;
+    Ok(Gga {
+        time,
+        latitude,
+        longitude,
+        fix_quality: FixQuality::from_digit(quality),
+        satellites: optional(f.next("satellites")?, "satellites")?.unwrap_or(0),
+        hdop: optional(f.next("hdop")?, "hdop")?,
+        altitude_m: optional(f.next("altitude")?, "altitude")?,
+    })
+}
+
+fn parse_rmc<'a>(f: &mut Fields<'a, impl Iterator<Item = &'a [u8]>>) -> Result<Rmc, NmeaError> {
+    let time = time(f.next("time")?)?;
+    let active = match f.next("status")? {
+        b"A" => true,
+        b"V" => false,
+        _ => return Err(NmeaError::InvalidField("status")),
+    };
+    Ok(Rmc {
+        time,
+        active,
+        latitude: coordinate(f.next("latitude")?, f.next("N/S")?, 2)?,
+        longitude: coordinate(f.next("longitude")?, f.next("E/W")?, 3)?,
+        speed_knots: optional(f.next("speed")?, "speed")?,
+        course_deg: optional(f.next("course")?, "course")?,
+        date: date(f.next("date")?)?,
+    })
+}
@@ -0,0 +1,127 @@
+use gps::{checksum, NmeaError, Sentence};
+use nom_nmea::{drive, parsers};
+
+const GGA: &str = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+const RMC: &str = "$GPRMC,225446,A,4916.45,N,12311.12,W,000.5,054.7,191194,020.3,E*68";
+
+fn with_checksum(body: &str) -> Vec<u8> {
+    format!("${}*{:02X}", body, checksum(body)).into_bytes()
+}
+
+// The three results for one line, labelled by parser
+fn parse_all(line: &[u8]) -> Vec<(&'static str, Result<Sentence, NmeaError>)> {
+    parsers()
+        .iter()
+        .map(|p| (p.name(), p.parse(line)))
+        .collect()
+}
+
+#[test]
+fn one_gga_three_identical_fixes() {
+    let results = parse_all(GGA.as_bytes());
+    let Ok(Sentence::Gga(fix)) = &results[0].1 else {
+        panic!("{:?}", results[0]);
+    };
+    assert!((fix.latitude.unwrap() - 48.1173).abs() < 1e-9);
+    assert!((fix.longitude.unwrap() - 11.516_666_666).abs() < 1e-6);
+    assert_eq!(fix.satellites, 8);
+    assert_eq!(fix.altitude_m, Some(545.4));
+    for (name, result) in &results[1..] {
+        assert_eq!(result, &results[0].1, "{}", name);
+    }
+}
+
+#[test]
+fn one_rmc_three_identical_fixes() {
+    let results = parse_all(RMC.as_bytes());
+    assert!(
+        matches!(results[0].1, Ok(Sentence::Rmc(_))),
+        "{:?}",
+        results[0]
+    );
+    for (name, result) in &results[1..] {
+        assert_eq!(result, &results[0].1, "{}", name);
+    }
+}
+
+#[test]
+fn a_whole_drive_parses_identically() {
+    let lines = drive(10_000);
+    let mut accepted = 0;
+    for line in &lines {
+        let results = parse_all(line.as_bytes());
+        accepted += results[0].1.is_ok() as usize;
+        for (name, result) in &results[1..] {
+            assert_eq!(result, &results[0].1, "{} on {}", name, line);
+        }
+    }
+    // Every generated line is well-formed, with or without a fix
+    assert_eq!(accepted, lines.len());
+}
+
+#[test]
+fn every_malformed_line_is_rejected_by_all_three() {
+    let non_utf8 = {
+        let mut bytes = GGA.as_bytes().to_vec();
+        bytes[8] = 0xB5;
+        let fixed = bytes[1..bytes.len() - 3].iter().fold(0, |a, b| a ^ b);
+        let len = bytes.len();
+        bytes.splice(len - 2.., format!("{:02X}", fixed).into_bytes());
+        bytes
+    };
+    let cases: Vec<(&str, Vec<u8>)> = vec![
+        ("no '$'", GGA[1..].into()),
+        ("no checksum", GGA[..GGA.len() - 3].into()),
+        ("wrong checksum", GGA.replace("*47", "*48").into()),
+        ("GSV", with_checksum("GPGSV,3,1,11,03,03,111,00")),
+        (
+            "RMC status X",
+            with_checksum("GPRMC,225446,X,4916.45,N,12311.12,W,,,191194,,"),
+        ),
+        (
+            "minutes >= 60",
+            with_checksum("GPGGA,123519,4867.038,N,01131.000,E,1,08,0.9,545.4,M,,M,,"),
+        ),
+        (
+            "hemisphere Q",
+            with_checksum("GPGGA,123519,4807.038,Q,01131.000,E,1,08,0.9,545.4,M,,M,,"),
+        ),
+        (
+            "hour 25",
+            with_checksum("GPRMC,255446,A,4916.45,N,12311.12,W,,,191194,,"),
+        ),
+        ("truncated GGA", "$GPGGA,123519*77".into()),
+        ("non-UTF-8 byte", non_utf8),
+        ("empty", Vec::new()),
+        ("only '$'", "$".into()),
+    ];
+    for (label, line) in &cases {
+        for (name, result) in parse_all(line) {
+            assert!(result.is_err(), "{} accepted {}", name, label);
+        }
+    }
+}
+
+#[test]
+fn the_envelope_errors_are_the_same_everywhere() {
+    for (line, expected) in [
+        (&GGA[1..], NmeaError::MissingDollar),
+        (&GGA[..GGA.len() - 3], NmeaError::MissingChecksum),
+    ] {
+        for (name, result) in parse_all(line.as_bytes()) {
+            assert_eq!(result, Err(expected.clone()), "{}", name);
+        }
+    }
+    let wrong = GGA.replace("*47", "*48");
+    for (name, result) in parse_all(wrong.as_bytes()) {
+        assert_eq!(
+            result,
+            Err(NmeaError::BadChecksum {
+                expected: 0x48,
+                computed: 0x47
+            }),
+            "{}",
+            name
+        );
+    }
+}
@@ -0,0 +1,34 @@
+// Installs the counting allocator for this test binary. Counts are
+// process-wide, so the file holds a single test: nothing else runs while
+// it measures.
+
+use nom_nmea::counting::{count, CountingAlloc};
+use nom_nmea::parsers;
+use std::hint::black_box;
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+#[test]
+fn byte_slice_parsers_never_allocate_on_success() {
+    let lines = [
+        "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47",
+        "$GPRMC,225446,A,4916.45,N,12311.12,W,000.5,054.7,191194,020.3,E*68",
+    ];
+    // The counter itself works: a Vec is one allocation
+    let (_, a) = count(|| black_box(vec![0u8; 100]));
+    assert_eq!((a.allocs, a.bytes), (1, 100));
+
+    let [reference, manual, nom] = parsers();
+    for line in lines {
+        let (result, a) = count(|| black_box(reference.parse(black_box(line.as_bytes()))));
+        assert!(result.is_ok());
+        // 09.gps splits into a Vec of fields
+        assert!(a.allocs > 0, "{}", line);
+        for p in [manual, nom] {
+            let (result, a) = count(|| black_box(p.parse(black_box(line.as_bytes()))));
+            assert!(result.is_ok());
+            assert_eq!(a.allocs + a.reallocs, 0, "{} on {}", p.name(), line);
+        }
+    }
+}
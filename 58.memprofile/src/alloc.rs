@@ -0,0 +1,109 @@
+// The allocator: the system allocator plus a few atomic counters
+//
+// Every counter is process-wide and updated with relaxed atomics, which
+// costs a few nanoseconds per allocation. Under concurrency the peak can
+// be off by one in-flight allocation; for profiling that is fine.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+pub struct Profiler;
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+static DEALLOCS: AtomicUsize = AtomicUsize::new(0);
+static REALLOCS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+// Peak of the innermost open scope; see scope.rs
+pub(crate) static SCOPE_PEAK: AtomicUsize = AtomicUsize::new(0);
+
+fn note_peak(bytes: usize) {
+    PEAK.fetch_max(bytes, Ordering::Relaxed);
+    SCOPE_PEAK.fetch_max(bytes, Ordering::Relaxed);
+}
+
+fn note_alloc(size: usize) {
+    ACTIVE.store(true, Ordering::Relaxed);
+    ALLOCS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED.fetch_add(size, Ordering::Relaxed);
+    note_peak(LIVE.fetch_add(size, Ordering::Relaxed) + size);
+}
+
+// SAFETY: every call is forwarded unchanged to the system allocator; the
+// counters never influence what is returned
+unsafe impl GlobalAlloc for Profiler {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            note_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            note_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        DEALLOCS.fetch_add(1, Ordering::Relaxed);
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    // A moving realloc holds the old and the new block at once, so the
+    // peak assumes both; growing in place is cheaper than that
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            REALLOCS.fetch_add(1, Ordering::Relaxed);
+            let old = layout.size();
+            if new_size > old {
+                ALLOCATED.fetch_add(new_size - old, Ordering::Relaxed);
+                note_peak(LIVE.load(Ordering::Relaxed) + new_size);
+                LIVE.fetch_add(new_size - old, Ordering::Relaxed);
+            } else {
+                LIVE.fetch_sub(old - new_size, Ordering::Relaxed);
+            }
+        }
+        new_ptr
+    }
+}
+
+// A snapshot of the counters since the process started
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub allocs: usize,
+    pub deallocs: usize,
+    pub reallocs: usize,
+    // Total bytes requested, including growth by realloc
+    pub allocated_bytes: usize,
+    pub live_bytes: usize,
+    pub peak_bytes: usize,
+}
+
+pub fn stats() -> Stats {
+    Stats {
+        allocs: ALLOCS.load(Ordering::Relaxed),
+        deallocs: DEALLOCS.load(Ordering::Relaxed),
+        reallocs: REALLOCS.load(Ordering::Relaxed),
+        allocated_bytes: ALLOCATED.load(Ordering::Relaxed),
+        live_bytes: LIVE.load(Ordering::Relaxed),
+        peak_bytes: PEAK.load(Ordering::Relaxed),
+    }
+}
+
+// True once `Profiler` has served an allocation, i.e. the binary installed
+// it with #[global_allocator]. Without it every counter stays at zero
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+pub(crate) fn live() -> usize {
+    LIVE.load(Ordering::Relaxed)
+}
@@ -0,0 +1,18 @@
+// Memory profiling with a counting global allocator
+//
+// `Profiler` wraps the system allocator and keeps process-wide counters:
+// allocations, frees, reallocations, bytes requested, live bytes and the
+// peak. `scope` opens a guard that reports what happened while it was
+// open. A binary opts in with
+//
+//   #[global_allocator]
+//   static GLOBAL: memprofile::Profiler = memprofile::Profiler;
+//
+// Libraries only call `stats`/`scope`; without the allocator installed
+// they see zeros and `is_active()` is false.
+
+mod alloc;
+mod scope;
+
+pub use alloc::{is_active, stats, Profiler, Stats};
+pub use scope::{human, profile, scope, Scope, ScopeReport};
@@ -0,0 +1,148 @@
+use memprofile::{human, is_active, profile, scope, stats, Profiler};
+use std::hint::black_box;
+
+#[global_allocator]
+static GLOBAL: Profiler = Profiler;
+
+const N: usize = 100_000;
+
+fn main() {
+    println!("=== Memory Profiling Examples ===\n");
+
+    // 1. The profiler is installed
+    println!("1. Process-wide counters:");
+    // Print only after both snapshots: the first println allocates stdout's buffer
+    let start = stats();
+    let boxed = black_box(Box::new([0u8; 1000]));
+    let after = stats();
+    println!(
+        "   before main's work: {} allocs, {} live, peak {}",
+        start.allocs,
+        human(start.live_bytes),
+        human(start.peak_bytes)
+    );
+    println!(
+        "   after one Box<[u8; 1000]>: {} allocs, {} live",
+        after.allocs,
+        human(after.live_bytes)
+    );
+    println!("   Profiler installed: {}", is_active());
+    drop(boxed);
+
+    // 2. Four ways to build the same Vec<u32>
+    println!("\n2. Building a Vec<u32> of {} values:", N);
+    let (pushed, push) = profile("push", || {
+        let mut v = Vec::new();
+        for i in 0..N as u32 {
+            v.push(i);
+        }
+        v
+    });
+    let (reserved, with_capacity) = profile("with_capacity", || {
+        let mut v = Vec::with_capacity(N);
+        for i in 0..N as u32 {
+            v.push(i);
+        }
+        v
+    });
+    let (collected, collect) = profile("collect", || (0..N as u32).collect::<Vec<_>>());
+    // reserve_exact before every push defeats the doubling; keep it small
+    let (exact, reserve_exact) = profile("reserve_exact", || {
+        let mut v = Vec::new();
+        for i in 0..(N / 10) as u32 {
+            v.reserve_exact(1);
+            v.push(i);
+        }
+        v
+    });
+    for r in [push, with_capacity, collect, reserve_exact] {
+        println!("   {}", r);
+    }
+    println!(
+        "   capacities: push {}, with_capacity {}, collect {}, reserve_exact {} (of {})",
+        pushed.capacity(),
+        reserved.capacity(),
+        collected.capacity(),
+        exact.capacity(),
+        N / 10
+    );
+    println!(
+        "   final size {}; push peaked at {}",
+        human(N * 4),
+        human(push.peak_bytes)
+    );
+    drop((pushed, reserved, collected, exact));
+
+    // 3. How a pushed Vec grows
+    println!("\n3. Capacity after each reallocation:");
+    let mut v: Vec<u32> = Vec::new();
+    let mut capacities = Vec::with_capacity(32);
+    for i in 0..1000 {
+        let before = v.capacity();
+        v.push(i);
+        if v.capacity() != before {
+            capacities.push(v.capacity());
+        }
+    }
+    println!("   {:?}", capacities);
+
+    // 4. Giving memory back
+    println!("\n4. Live bytes after shrinking:");
+    let guard = scope("shrink");
+    let mut readings: Vec<u64> = (0..N as u64).collect();
+    let full = guard.report().retained_bytes;
+    readings.retain(|r| r % 10 == 0);
+    let retained = guard.report().retained_bytes;
+    readings.shrink_to_fit();
+    let shrunk = guard.report().retained_bytes;
+    println!(
+        "   {} values: {}; after retain: {}; after shrink_to_fit: {}",
+        readings.len(),
+        human(full as usize),
+        human(retained as usize),
+        human(shrunk as usize)
+    );
+    drop(readings);
+    let after_drop = guard.finish();
+    println!("   {}", after_drop);
+
+    // 5. Nested scopes
+    println!("\n5. Nested scopes:");
+    let outer = scope("batch");
+    let first = profile("first half", || black_box(vec![0u8; 64 * 1024]).len()).1;
+    let second = profile("second half", || black_box(vec![0u8; 16 * 1024]).len()).1;
+    let outer = outer.finish();
+    for r in [first, second, outer] {
+        println!("   {}", r);
+    }
+
+    // 6. Nested Vecs vs one flat Vec
+    println!("\n6. 1000 windows of 100 samples:");
+    let (nested, nested_r) = profile("Vec<Vec<f32>>", || {
+        (0..1000)
+            .map(|w| (0..100).map(|s| (w * 100 + s) as f32).collect::<Vec<f32>>())
+            .collect::<Vec<_>>()
+    });
+    let (flat, flat_r) = profile("flat Vec<f32>", || {
+        (0..100_000).map(|s| s as f32).collect::<Vec<f32>>()
+    });
+    println!("   {}", nested_r);
+    println!("   {}", flat_r);
+    println!(
+        "   {} windows of {} samples, {} flat samples",
+        nested.len(),
+        nested[0].len(),
+        flat.len()
+    );
+
+    let end = stats();
+    println!(
+        "\n   whole run: {} allocs, {} reallocs, {} allocated, peak {}",
+        end.allocs,
+        end.reallocs,
+        human(end.allocated_bytes),
+        human(end.peak_bytes)
+    );
+
+    println!("\n=== End of Memory Profiling Examples ===");
+}
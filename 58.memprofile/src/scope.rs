@@ -0,0 +1,115 @@
+// Scoped measurements: what happened between creating a guard and now
+//
+//   let guard = memprofile::scope("parse");
+//   ... work ...
+//   println!("{}", guard.finish());
+//
+// Scopes nest. Each one resets the scope peak to the live bytes at its
+// start, and dropping it folds its peak back into the enclosing scope, so
+// an outer scope's peak always covers its inner ones. The counters are
+// process-wide: open scopes on one thread at a time, and expect other
+// threads' allocations to show up in them.
+
+use crate::alloc::{live, stats, Stats, SCOPE_PEAK};
+use std::fmt;
+use std::sync::atomic::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScopeReport {
+    pub name: &'static str,
+    pub allocs: usize,
+    pub deallocs: usize,
+    pub reallocs: usize,
+    pub allocated_bytes: usize,
+    // Highest live bytes above the level at the start of the scope
+    pub peak_bytes: usize,
+    // Live bytes now minus at the start; positive means still held
+    pub retained_bytes: isize,
+}
+
+#[must_use = "a scope measures until it is finished or dropped"]
+pub struct Scope {
+    name: &'static str,
+    start: Stats,
+    outer_peak: usize,
+}
+
+pub fn scope(name: &'static str) -> Scope {
+    let start = stats();
+    let outer_peak = SCOPE_PEAK.swap(start.live_bytes, Ordering::Relaxed);
+    Scope {
+        name,
+        start,
+        outer_peak,
+    }
+}
+
+// Run `f` in its own scope
+pub fn profile<T>(name: &'static str, f: impl FnOnce() -> T) -> (T, ScopeReport) {
+    let guard = scope(name);
+    let value = f();
+    (value, guard.finish())
+}
+
+impl Scope {
+    // The numbers so far; the scope stays open
+    pub fn report(&self) -> ScopeReport {
+        let now = stats();
+        ScopeReport {
+            name: self.name,
+            allocs: now.allocs - self.start.allocs,
+            deallocs: now.deallocs - self.start.deallocs,
+            reallocs: now.reallocs - self.start.reallocs,
+            allocated_bytes: now.allocated_bytes - self.start.allocated_bytes,
+            peak_bytes: SCOPE_PEAK
+                .load(Ordering::Relaxed)
+                .saturating_sub(self.start.live_bytes),
+            retained_bytes: now.live_bytes as isize - self.start.live_bytes as isize,
+        }
+    }
+
+    pub fn finish(self) -> ScopeReport {
+        self.report()
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let inner = SCOPE_PEAK.load(Ordering::Relaxed);
+        SCOPE_PEAK.store(self.outer_peak.max(inner).max(live()), Ordering::Relaxed);
+    }
+}
+
+// 1536 -> "1.5 KiB"
+pub fn human(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+impl fmt::Display for ScopeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.retained_bytes < 0 { "-" } else { "+" };
+        write!(
+            f,
+            "{}: {} allocs ({} reallocs), {} frees, {} allocated, peak +{}, retained {}{}",
+            self.name,
+            self.allocs,
+            self.reallocs,
+            self.deallocs,
+            human(self.allocated_bytes),
+            human(self.peak_bytes),
+            sign,
+            human(self.retained_bytes.unsigned_abs())
+        )
+    }
+}
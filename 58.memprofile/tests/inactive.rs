@@ -0,0 +1,28 @@
+// No #[global_allocator] here: a library calling the profiler from a
+// binary that never installed it.
+
+use memprofile::{human, is_active, profile, stats, Stats};
+
+#[test]
+fn without_the_allocator_everything_is_zero() {
+    let boxed = Box::new([0u8; 1000]);
+    assert!(!is_active());
+    assert_eq!(stats(), Stats::default());
+    let (len, report) = profile("idle", || vec![0u8; 4096].len());
+    assert_eq!(len, 4096);
+    assert_eq!(report.allocs, 0);
+    assert_eq!(report.peak_bytes, 0);
+    drop(boxed);
+}
+
+#[test]
+fn human_sizes() {
+    assert_eq!(human(0), "0 B");
+    assert_eq!(human(1023), "1023 B");
+    assert_eq!(human(1024), "1.0 KiB");
+    assert_eq!(human(1536), "1.5 KiB");
+    assert_eq!(human(5 * 1024 * 1024), "5.0 MiB");
+    assert_eq!(human(3 << 30), "3.0 GiB");
+    // GiB is the largest unit
+    assert_eq!(human(2048 << 30), "2048.0 GiB");
+}
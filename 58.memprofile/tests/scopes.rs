@@ -0,0 +1,69 @@
+// Installs the profiler for this test binary. Counts are process-wide, so
+// the file holds a single test, and it waits for the harness thread to go
+// quiet before measuring.
+
+use memprofile::{profile, scope, stats, Profiler};
+use std::hint::black_box;
+use std::thread;
+use std::time::Duration;
+
+#[global_allocator]
+static GLOBAL: Profiler = Profiler;
+
+// The harness thread allocates as it starts waiting on the test; once the
+// counters hold still for a while it is blocked until the test ends
+fn settle() {
+    let mut last = stats();
+    loop {
+        thread::sleep(Duration::from_millis(20));
+        let now = stats();
+        if now == last {
+            return;
+        }
+        last = now;
+    }
+}
+
+#[test]
+fn scopes_report_retained_bytes_and_nest() {
+    settle();
+    let guard = scope("shrink");
+    let mut readings: Vec<u64> = (0..100_000).collect();
+    let full = guard.report().retained_bytes;
+    assert_eq!(full, 800_000);
+    readings.retain(|r| r % 10 == 0);
+    // retain keeps the whole buffer
+    assert_eq!(guard.report().retained_bytes, full);
+    readings.shrink_to_fit();
+    assert_eq!(guard.report().retained_bytes, readings.len() as isize * 8);
+    drop(readings);
+    let shrink = guard.finish();
+    assert_eq!(shrink.retained_bytes, 0);
+    assert_eq!(shrink.allocs, 1);
+    assert_eq!(shrink.peak_bytes, 800_000);
+    assert!(shrink.to_string().ends_with("retained +0 B"), "{}", shrink);
+
+    // The outer peak covers the larger inner one, not their sum
+    let outer = scope("batch");
+    let first = profile("first half", || black_box(vec![0u8; 64 * 1024]).len()).1;
+    let second = profile("second half", || black_box(vec![0u8; 16 * 1024]).len()).1;
+    let outer = outer.finish();
+    assert_eq!(first.peak_bytes, 64 * 1024);
+    assert_eq!(second.peak_bytes, 16 * 1024);
+    assert!(outer.peak_bytes >= first.peak_bytes, "{}", outer);
+    assert!(outer.peak_bytes < first.peak_bytes + 1024, "{}", outer);
+    assert!(outer.allocs >= first.allocs + second.allocs, "{}", outer);
+
+    let (nested, nested_r) = profile("Vec<Vec<f32>>", || {
+        (0..1000)
+            .map(|w| (0..100).map(|s| (w * 100 + s) as f32).collect::<Vec<f32>>())
+            .collect::<Vec<_>>()
+    });
+    let (flat, flat_r) = profile("flat Vec<f32>", || {
+        (0..100_000).map(|s| s as f32).collect::<Vec<f32>>()
+    });
+    // One allocation per window plus the outer Vec, against one
+    assert_eq!(nested_r.allocs, 1001);
+    assert_eq!(flat_r.allocs, 1);
+    assert!(nested.iter().flatten().eq(flat.iter()));
+}
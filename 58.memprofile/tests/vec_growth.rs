@@ -0,0 +1,92 @@
+// Installs the profiler for this test binary. Counts are process-wide, so
+// the file holds a single test, and it waits for the harness thread to go
+// quiet before measuring.
+
+use memprofile::{is_active, profile, stats, Profiler};
+use std::hint::black_box;
+use std::thread;
+use std::time::Duration;
+
+#[global_allocator]
+static GLOBAL: Profiler = Profiler;
+
+// The harness thread allocates as it starts waiting on the test; once the
+// counters hold still for a while it is blocked until the test ends
+fn settle() {
+    let mut last = stats();
+    loop {
+        thread::sleep(Duration::from_millis(20));
+        let now = stats();
+        if now == last {
+            return;
+        }
+        last = now;
+    }
+}
+
+const N: usize = 100_000;
+
+#[test]
+fn vec_building_strategies_allocate_as_expected() {
+    settle();
+    // The harness has allocated by now, so the allocator is known to be live
+    assert!(is_active());
+    let start = stats();
+    let boxed = black_box(Box::new([0u8; 1000]));
+    let after = stats();
+    assert_eq!(after.allocs, start.allocs + 1);
+    assert_eq!(after.live_bytes, start.live_bytes + 1000);
+    drop(boxed);
+    assert_eq!(stats().live_bytes, start.live_bytes);
+
+    let (pushed, push) = profile("push", || {
+        let mut v = Vec::new();
+        for i in 0..N as u32 {
+            v.push(i);
+        }
+        v
+    });
+    let (reserved, with_capacity) = profile("with_capacity", || {
+        let mut v = Vec::with_capacity(N);
+        for i in 0..N as u32 {
+            v.push(i);
+        }
+        v
+    });
+    let (collected, collect) = profile("collect", || (0..N as u32).collect::<Vec<_>>());
+    let (exact, reserve_exact) = profile("reserve_exact", || {
+        let mut v = Vec::new();
+        for i in 0..(N / 10) as u32 {
+            v.reserve_exact(1);
+            v.push(i);
+        }
+        v
+    });
+
+    assert_eq!((with_capacity.allocs, with_capacity.reallocs), (1, 0));
+    assert_eq!(with_capacity.peak_bytes, N * 4);
+    // An exact-size range is one allocation as well
+    assert_eq!((collect.allocs, collect.reallocs), (1, 0));
+    // Doubling: about log2(n) reallocations, and a moving one holds both
+    // buffers at once
+    assert!(push.reallocs > 10 && push.reallocs < 25, "{}", push);
+    assert!(push.peak_bytes > N * 4, "{}", push);
+    assert_eq!(push.allocs, 1);
+    assert_eq!(reserve_exact.reallocs, N / 10 - 1);
+    assert_eq!(exact.capacity(), N / 10);
+    assert_eq!(pushed, reserved);
+    assert_eq!(reserved, collected);
+    assert_eq!(exact[..], collected[..N / 10]);
+    drop((pushed, reserved, collected, exact));
+
+    let mut v: Vec<u32> = Vec::new();
+    let mut capacities = Vec::with_capacity(32);
+    for i in 0..1000 {
+        let before = v.capacity();
+        v.push(i);
+        if v.capacity() != before {
+            capacities.push(v.capacity());
+        }
+    }
+    assert_eq!(capacities, [4, 8, 16, 32, 64, 128, 256, 512, 1024]);
+}
@@ -0,0 +1,72 @@
+// AoS vs SoA for each analytic, on a million samples
+//
+//   cargo bench                        # every group
+//   cargo bench -- mean_temperature    # one group
+//
+// A million 32-byte samples is 32 MiB, far beyond the caches, so the
+// scans measure memory traffic. Throughput is reported in samples per
+// second. `random_access` is the one group where AoS should win.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use soa::{simulate, Analytics, Sample, SoaSamples};
+use std::hint::black_box;
+
+const SAMPLES: usize = 1_000_000;
+
+// One group per analytic with the same expression over both layouts
+macro_rules! scan {
+    ($c:expr, $name:literal, $aos:expr, $soa:expr, |$d:ident| $body:expr) => {{
+        let mut group = $c.benchmark_group($name);
+        group.throughput(Throughput::Elements(SAMPLES as u64));
+        group.bench_function("aos", |b| {
+            b.iter(|| {
+                let $d: &[Sample] = black_box($aos);
+                $body
+            })
+        });
+        group.bench_function("soa", |b| {
+            b.iter(|| {
+                let $d: &SoaSamples = black_box($soa);
+                $body
+            })
+        });
+        group.finish();
+    }};
+}
+
+fn scans(c: &mut Criterion) {
+    let aos: Vec<Sample> = simulate(SAMPLES, 50, 7);
+    let soa = SoaSamples::from(&aos[..]);
+    scan!(c, "mean_temperature", &aos, &soa, |d| d.mean_temperature());
+    scan!(c, "count_hot", &aos, &soa, |d| d.count_hot(28.0));
+    scan!(c, "pressure_range", &aos, &soa, |d| d.pressure_range());
+    scan!(c, "mean_valid_temperature", &aos, &soa, |d| d
+        .mean_valid_temperature());
+    scan!(c, "low_battery", &aos, &soa, |d| d.low_battery(3000));
+}
+
+// Same indices for both layouts
+fn gather(data: &(impl Analytics + ?Sized), indices: &[usize]) -> f64 {
+    indices
+        .iter()
+        .filter_map(|&i| data.sample(black_box(i)))
+        .map(|s| s.temperature as f64 + s.battery_mv as f64)
+        .sum()
+}
+
+fn random_access(c: &mut Criterion) {
+    let aos: Vec<Sample> = simulate(SAMPLES, 50, 7);
+    let soa = SoaSamples::from(&aos[..]);
+    let indices: Vec<usize> = (0..10_000u64)
+        .map(|i| (i.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 20) as usize % SAMPLES)
+        .collect();
+
+    let mut group = c.benchmark_group("random_access");
+    group.throughput(Throughput::Elements(indices.len() as u64));
+    group.bench_function("aos", |b| b.iter(|| gather(&aos[..], &indices)));
+    group.bench_function("soa", |b| b.iter(|| gather(&soa, &indices)));
+    group.finish();
+}
+
+criterion_group!(benches, scans, random_access);
+criterion_main!(benches);
@@ -0,0 +1,98 @@
+// The same analytics over both layouts
+//
+// Each pair of implementations is written the same way and in the same
+// order, so the results agree bit for bit; only the memory they walk
+// differs. Over `[Sample]` a scan of one field still pulls every 32-byte
+// record through the cache. Over `SoaSamples` it reads one dense column.
+
+use crate::sample::{Sample, STATUS_OK};
+use crate::soa::SoaSamples;
+
+pub trait Analytics {
+    fn mean_temperature(&self) -> Option<f64>;
+    fn count_hot(&self, above: f32) -> usize;
+    fn pressure_range(&self) -> Option<(f32, f32)>;
+    // Two fields: the status filter and the value
+    fn mean_valid_temperature(&self) -> Option<f64>;
+    fn low_battery(&self, below_mv: u16) -> usize;
+    // Random access to one whole sample
+    fn sample(&self, index: usize) -> Option<Sample>;
+}
+
+fn mean(sum: f64, count: usize) -> Option<f64> {
+    (count > 0).then(|| sum / count as f64)
+}
+
+fn range(values: impl Iterator<Item = f32>) -> Option<(f32, f32)> {
+    values.fold(None, |acc, v| match acc {
+        None => Some((v, v)),
+        Some((lo, hi)) => Some((lo.min(v), hi.max(v))),
+    })
+}
+
+impl Analytics for [Sample] {
+    fn mean_temperature(&self) -> Option<f64> {
+        let sum: f64 = self.iter().map(|s| s.temperature as f64).sum();
+        mean(sum, self.len())
+    }
+
+    fn count_hot(&self, above: f32) -> usize {
+        self.iter().filter(|s| s.temperature > above).count()
+    }
+
+    fn pressure_range(&self) -> Option<(f32, f32)> {
+        range(self.iter().map(|s| s.pressure))
+    }
+
+    fn mean_valid_temperature(&self) -> Option<f64> {
+        let (sum, count) = self
+            .iter()
+            .filter(|s| s.status == STATUS_OK)
+            .fold((0.0, 0), |(sum, n), s| (sum + s.temperature as f64, n + 1));
+        mean(sum, count)
+    }
+
+    fn low_battery(&self, below_mv: u16) -> usize {
+        self.iter().filter(|s| s.battery_mv < below_mv).count()
+    }
+
+    fn sample(&self, index: usize) -> Option<Sample> {
+        self.get(index).copied()
+    }
+}
+
+impl Analytics for SoaSamples {
+    fn mean_temperature(&self) -> Option<f64> {
+        let sum: f64 = self.temperatures().iter().map(|&t| t as f64).sum();
+        mean(sum, self.len())
+    }
+
+    fn count_hot(&self, above: f32) -> usize {
+        self.temperatures().iter().filter(|&&t| t > above).count()
+    }
+
+    fn pressure_range(&self) -> Option<(f32, f32)> {
+        range(self.pressures().iter().copied())
+    }
+
+    fn mean_valid_temperature(&self) -> Option<f64> {
+        let (sum, count) = self
+            .statuses()
+            .iter()
+            .zip(self.temperatures())
+            .filter(|(&status, _)| status == STATUS_OK)
+            .fold((0.0, 0), |(sum, n), (_, &t)| (sum + t as f64, n + 1));
+        mean(sum, count)
+    }
+
+    fn low_battery(&self, below_mv: u16) -> usize {
+        self.battery_mv()
+            .iter()
+            .filter(|&&mv| mv < below_mv)
+            .count()
+    }
+
+    fn sample(&self, index: usize) -> Option<Sample> {
+        self.get(index)
+    }
+}
@@ -0,0 +1,20 @@
+// Array-of-structs vs struct-of-arrays
+//
+// A gateway that keeps a day of samples in memory can store them as
+// `Vec<Sample>` (AoS), one record after another, or as one `Vec` per field
+// (SoA). Most analytics read one or two fields of every sample, and there
+// SoA wins: the CPU fetches 64-byte cache lines, and with AoS most of each
+// line is fields the scan never looks at. Fetching whole samples by index
+// favours AoS instead.
+//
+// - `sample`: the `Sample` record and a deterministic simulator
+// - `soa`: `SoaSamples`, column storage behind a safe API
+// - `analytics`: the `Analytics` trait, implemented for both layouts
+
+pub mod analytics;
+pub mod sample;
+pub mod soa;
+
+pub use analytics::Analytics;
+pub use sample::{simulate, Sample};
+pub use soa::SoaSamples;
@@ -0,0 +1,162 @@
+use soa::{simulate, Analytics, Sample, SoaSamples};
+use std::hint::black_box;
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+
+const SAMPLES: usize = 1_000_000;
+
+// Best of `reps` runs, which filters out scheduler noise
+fn time<T>(reps: u32, mut f: impl FnMut() -> T) -> Duration {
+    (0..reps)
+        .map(|_| {
+            let started = Instant::now();
+            black_box(f());
+            started.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+// The same fixed pseudo-random indices for both layouts
+fn random_indices(count: usize, len: usize) -> Vec<usize> {
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    (0..count)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % len as u64) as usize
+        })
+        .collect()
+}
+
+fn gather(data: &(impl Analytics + ?Sized), indices: &[usize]) -> f64 {
+    indices
+        .iter()
+        .filter_map(|&i| data.sample(i))
+        .map(|s| s.temperature as f64 + s.humidity as f64 + s.battery_mv as f64)
+        .sum()
+}
+
+fn main() {
+    println!("=== AoS vs SoA Layout Examples ===\n");
+
+    // 1. What one sample costs
+    println!("1. Layout of one Sample:");
+    let field_bytes = 8 + 4 + 4 + 4 + 2 + 2 + 1 + 1;
+    println!(
+        "   size_of::<Sample>() = {} bytes, {} of them fields",
+        size_of::<Sample>(),
+        field_bytes
+    );
+    println!("   a temperature scan reads 4 bytes per sample:");
+    println!(
+        "     AoS: {} B per sample fetched, {:.0}% of it used",
+        size_of::<Sample>(),
+        400.0 / size_of::<Sample>() as f64
+    );
+    println!("     SoA: 4 B per sample fetched, 100% of it used");
+
+    // 2. The same million samples, two ways
+    println!("\n2. {} samples from 50 sensors:", SAMPLES);
+    let aos: Vec<Sample> = simulate(SAMPLES, 50, 7);
+    let soa = SoaSamples::from(&aos[..]);
+    println!(
+        "   AoS: {:.1} MiB, SoA: {:.1} MiB",
+        (aos.len() * size_of::<Sample>()) as f64 / 1048576.0,
+        soa.heap_bytes() as f64 / 1048576.0
+    );
+
+    // 3. Same questions, same answers
+    println!("\n3. Analytics on both layouts:");
+    let aos = &aos[..];
+    println!(
+        "   mean temperature:       {:.4} °C",
+        aos.mean_temperature().unwrap_or_default()
+    );
+    println!("   samples above 28 °C:    {}", aos.count_hot(28.0));
+    let (lo, hi) = aos.pressure_range().unwrap_or_default();
+    println!("   pressure range:         {:.2} .. {:.2} hPa", lo, hi);
+    println!(
+        "   mean of valid samples:  {:.4} °C",
+        aos.mean_valid_temperature().unwrap_or_default()
+    );
+    println!("   batteries below 3 V:    {}", aos.low_battery(3000));
+    println!(
+        "   SoA mean temperature:   {:.4} °C",
+        soa.mean_temperature().unwrap_or_default()
+    );
+
+    // 4. Scans: SoA reads less memory
+    println!("\n4. Scans over all samples (use --release):");
+    println!("   {:<24} {:>10} {:>10} {:>7}", "", "AoS", "SoA", "ratio");
+    let scans: [(&str, Duration, Duration); 5] = [
+        (
+            "mean_temperature",
+            time(5, || aos.mean_temperature()),
+            time(5, || soa.mean_temperature()),
+        ),
+        (
+            "count_hot",
+            time(5, || aos.count_hot(28.0)),
+            time(5, || soa.count_hot(28.0)),
+        ),
+        (
+            "pressure_range",
+            time(5, || aos.pressure_range()),
+            time(5, || soa.pressure_range()),
+        ),
+        (
+            "mean_valid_temperature",
+            time(5, || aos.mean_valid_temperature()),
+            time(5, || soa.mean_valid_temperature()),
+        ),
+        (
+            "low_battery",
+            time(5, || aos.low_battery(3000)),
+            time(5, || soa.low_battery(3000)),
+        ),
+    ];
+    for (name, a, s) in scans {
+        println!(
+            "   {:<24} {:>10.2?} {:>10.2?} {:>6.1}x",
+            name,
+            a,
+            s,
+            a.as_secs_f64() / s.as_secs_f64().max(1e-9)
+        );
+    }
+
+    // 5. Random access: AoS reads one line, SoA eight
+    println!("\n5. 100,000 whole samples at random indices:");
+    let indices = random_indices(100_000, aos.len());
+    let aos_time = time(5, || gather(aos, &indices));
+    let soa_time = time(5, || gather(&soa, &indices));
+    println!("   AoS: {:>9.2?}   SoA: {:>9.2?}", aos_time, soa_time);
+
+    // 6. The SoaSamples API keeps the columns together
+    println!("\n6. Working with SoaSamples:");
+    let mut recent: SoaSamples = aos[..5].iter().copied().collect();
+    recent.push(Sample {
+        temperature: 99.0,
+        ..aos[5]
+    });
+    let last = recent.temperatures()[recent.len() - 1];
+    println!("   {} samples, last temperature {:.1}", recent.len(), last);
+    for t in recent.temperatures_mut() {
+        *t -= 0.5;
+    }
+    println!(
+        "   after a -0.5 °C calibration: {:.2?}",
+        recent.temperatures()
+    );
+    recent.truncate(3);
+    println!(
+        "   truncated to {}: {:.2?}, get(3) = {:?}",
+        recent.len(),
+        recent.temperatures(),
+        recent.get(3)
+    );
+
+    println!("\n=== End of AoS vs SoA Layout Examples ===");
+}
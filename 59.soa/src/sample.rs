@@ -0,0 +1,53 @@
+// One record from an environmental node, as the firmware reports it
+
+pub const STATUS_OK: u8 = 0;
+
+// 26 bytes of fields, padded to 32 by the u64's alignment
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Sample {
+    pub timestamp_ms: u64,
+    pub temperature: f32,
+    pub humidity: f32,
+    pub pressure: f32,
+    pub sensor_id: u16,
+    pub battery_mv: u16,
+    pub status: u8,
+    pub rssi: i8,
+}
+
+impl Sample {
+    pub fn is_valid(&self) -> bool {
+        self.status == STATUS_OK
+    }
+}
+
+// `count` samples from `sensors` nodes at 1 Hz, deterministic for a seed.
+// About 2% carry a bad status, and batteries drain over time
+pub fn simulate(count: usize, sensors: u16, seed: u64) -> Vec<Sample> {
+    let mut state = seed;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 33) as u32
+    };
+    (0..count)
+        .map(|i| {
+            let sensor_id = (i % sensors as usize) as u16;
+            let second = (i / sensors as usize) as u64;
+            let wave = (second as f32 / 900.0).sin();
+            let noise = (next() % 1000) as f32 / 1000.0 - 0.5;
+            let roll = next() % 100;
+            Sample {
+                timestamp_ms: 1_700_000_000_000 + second * 1000,
+                temperature: 21.0 + sensor_id as f32 * 0.5 + wave * 4.0 + noise,
+                humidity: 45.0 - wave * 10.0 + noise * 2.0,
+                pressure: 1013.0 + wave * 6.0 + noise,
+                sensor_id,
+                battery_mv: 3300u16.saturating_sub((second / 60) as u16 + (next() % 40) as u16),
+                status: if roll < 2 { 3 } else { STATUS_OK },
+                rssi: -60 - (next() % 30) as i8,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,160 @@
+// Struct-of-arrays storage for `Sample`
+//
+// One `Vec` per field, all of the same length. The fields are private so
+// that no caller can push to one column and not the others; everything
+// that adds or removes samples goes through methods that touch every
+// column. Reading is by column (`temperatures()`) for scans, or by index
+// (`get`) when a whole sample is needed.
+
+use crate::sample::Sample;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SoaSamples {
+    timestamps_ms: Vec<u64>,
+    temperatures: Vec<f32>,
+    humidities: Vec<f32>,
+    pressures: Vec<f32>,
+    sensor_ids: Vec<u16>,
+    battery_mv: Vec<u16>,
+    statuses: Vec<u8>,
+    rssi: Vec<i8>,
+}
+
+impl SoaSamples {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        SoaSamples {
+            timestamps_ms: Vec::with_capacity(capacity),
+            temperatures: Vec::with_capacity(capacity),
+            humidities: Vec::with_capacity(capacity),
+            pressures: Vec::with_capacity(capacity),
+            sensor_ids: Vec::with_capacity(capacity),
+            battery_mv: Vec::with_capacity(capacity),
+            statuses: Vec::with_capacity(capacity),
+            rssi: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, s: Sample) {
+        self.timestamps_ms.push(s.timestamp_ms);
+        self.temperatures.push(s.temperature);
+        self.humidities.push(s.humidity);
+        self.pressures.push(s.pressure);
+        self.sensor_ids.push(s.sensor_id);
+        self.battery_mv.push(s.battery_mv);
+        self.statuses.push(s.status);
+        self.rssi.push(s.rssi);
+    }
+
+    pub fn len(&self) -> usize {
+        self.timestamps_ms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timestamps_ms.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        self.timestamps_ms.truncate(len);
+        self.temperatures.truncate(len);
+        self.humidities.truncate(len);
+        self.pressures.truncate(len);
+        self.sensor_ids.truncate(len);
+        self.battery_mv.truncate(len);
+        self.statuses.truncate(len);
+        self.rssi.truncate(len);
+    }
+
+    // Reassembles the sample from eight columns, so eight cache lines
+    pub fn get(&self, index: usize) -> Option<Sample> {
+        if index >= self.len() {
+            return None;
+        }
+        Some(Sample {
+            timestamp_ms: self.timestamps_ms[index],
+            temperature: self.temperatures[index],
+            humidity: self.humidities[index],
+            pressure: self.pressures[index],
+            sensor_id: self.sensor_ids[index],
+            battery_mv: self.battery_mv[index],
+            status: self.statuses[index],
+            rssi: self.rssi[index],
+        })
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Sample> + '_ {
+        (0..self.len()).map(|i| self.get(i).expect("index below len"))
+    }
+
+    pub fn timestamps_ms(&self) -> &[u64] {
+        &self.timestamps_ms
+    }
+
+    pub fn temperatures(&self) -> &[f32] {
+        &self.temperatures
+    }
+
+    pub fn humidities(&self) -> &[f32] {
+        &self.humidities
+    }
+
+    pub fn pressures(&self) -> &[f32] {
+        &self.pressures
+    }
+
+    pub fn sensor_ids(&self) -> &[u16] {
+        &self.sensor_ids
+    }
+
+    pub fn battery_mv(&self) -> &[u16] {
+        &self.battery_mv
+    }
+
+    pub fn statuses(&self) -> &[u8] {
+        &self.statuses
+    }
+
+    pub fn rssi(&self) -> &[i8] {
+        &self.rssi
+    }
+
+    // In-place edits of one column are safe: they cannot change its length
+    pub fn temperatures_mut(&mut self) -> &mut [f32] {
+        &mut self.temperatures
+    }
+
+    // Bytes held by the columns, not counting spare capacity
+    pub fn heap_bytes(&self) -> usize {
+        self.len() * (8 + 4 + 4 + 4 + 2 + 2 + 1 + 1)
+    }
+}
+
+impl FromIterator<Sample> for SoaSamples {
+    fn from_iter<I: IntoIterator<Item = Sample>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut soa = SoaSamples::with_capacity(iter.size_hint().0);
+        soa.extend(iter);
+        soa
+    }
+}
+
+impl Extend<Sample> for SoaSamples {
+    fn extend<I: IntoIterator<Item = Sample>>(&mut self, iter: I) {
+        for s in iter {
+            self.push(s);
+        }
+    }
+}
+
+impl From<&[Sample]> for SoaSamples {
+    fn from(samples: &[Sample]) -> Self {
+        samples.iter().copied().collect()
+    }
+}
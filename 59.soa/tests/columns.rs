@@ -0,0 +1,80 @@
+use soa::{simulate, Sample, SoaSamples};
+
+fn sample(n: u8) -> Sample {
+    Sample {
+        timestamp_ms: n as u64 * 1000,
+        temperature: 20.0 + n as f32,
+        humidity: 40.0,
+        pressure: 1013.0,
+        sensor_id: n as u16,
+        battery_mv: 3000,
+        status: 0,
+        rssi: -70,
+    }
+}
+
+#[test]
+fn push_and_get() {
+    let mut soa = SoaSamples::new();
+    assert!(soa.is_empty());
+    soa.push(sample(1));
+    soa.push(sample(2));
+    assert_eq!(soa.len(), 2);
+    assert_eq!(soa.get(1), Some(sample(2)));
+    assert_eq!(soa.get(2), None);
+    assert_eq!(soa.temperatures(), [21.0, 22.0]);
+    assert_eq!(soa.sensor_ids(), [1, 2]);
+    assert_eq!(soa.timestamps_ms(), [1000, 2000]);
+}
+
+#[test]
+fn truncate_and_clear_shorten_every_column() {
+    let mut soa: SoaSamples = simulate(10, 2, 1).into_iter().collect();
+    soa.truncate(3);
+    assert_eq!(soa.len(), 3);
+    for len in [
+        soa.timestamps_ms().len(),
+        soa.temperatures().len(),
+        soa.humidities().len(),
+        soa.pressures().len(),
+        soa.sensor_ids().len(),
+        soa.battery_mv().len(),
+        soa.statuses().len(),
+        soa.rssi().len(),
+    ] {
+        assert_eq!(len, 3);
+    }
+    assert_eq!(soa.get(3), None);
+    soa.clear();
+    assert!(soa.is_empty());
+    assert_eq!(soa.rssi().len(), 0);
+}
+
+#[test]
+fn editing_a_column_changes_only_that_field() {
+    let samples = simulate(6, 2, 7);
+    let mut soa: SoaSamples = samples.iter().copied().collect();
+    for t in soa.temperatures_mut() {
+        *t -= 0.5;
+    }
+    for (i, s) in samples.iter().enumerate() {
+        assert_eq!(
+            soa.get(i),
+            Some(Sample {
+                temperature: s.temperature - 0.5,
+                ..*s
+            })
+        );
+    }
+}
+
+#[test]
+fn extend_and_collect_build_the_same_columns() {
+    let samples = simulate(100, 4, 9);
+    let collected: SoaSamples = samples.iter().copied().collect();
+    let mut extended = SoaSamples::with_capacity(10);
+    extended.extend(samples[..40].iter().copied());
+    extended.extend(samples[40..].iter().copied());
+    assert_eq!(collected, extended);
+    assert_eq!(collected, SoaSamples::from(&samples[..]));
+}
@@ -0,0 +1,75 @@
+use soa::sample::STATUS_OK;
+use soa::{simulate, Analytics, Sample, SoaSamples};
+use std::mem::size_of;
+
+// The five analytics, in trait order
+type Answers = (Option<f64>, usize, Option<(f32, f32)>, Option<f64>, usize);
+
+fn answers(d: &(impl Analytics + ?Sized)) -> Answers {
+    (
+        d.mean_temperature(),
+        d.count_hot(28.0),
+        d.pressure_range(),
+        d.mean_valid_temperature(),
+        d.low_battery(3300),
+    )
+}
+
+#[test]
+fn padding_rounds_a_sample_up_to_32_bytes() {
+    assert_eq!(size_of::<Sample>(), 32);
+    // The columns hold only the 26 bytes of fields
+    let soa: SoaSamples = simulate(10, 2, 1).into_iter().collect();
+    assert_eq!(soa.heap_bytes(), 260);
+}
+
+#[test]
+fn simulate_is_deterministic_per_seed() {
+    let a = simulate(1000, 5, 7);
+    assert_eq!(a, simulate(1000, 5, 7));
+    assert_ne!(a, simulate(1000, 5, 8));
+    assert_eq!(a[0].sensor_id, 0);
+    assert_eq!(a[6].sensor_id, 1);
+    assert_eq!(a[5].timestamp_ms, a[0].timestamp_ms + 1000);
+    let bad = a.iter().filter(|s| s.status != STATUS_OK).count();
+    assert!(bad > 0 && bad < 60, "{} bad samples", bad);
+}
+
+// Each pair of implementations sums in the same order, so the results
+// agree bit for bit, not just approximately
+#[test]
+fn analytics_agree_exactly() {
+    let samples = simulate(100_000, 50, 7);
+    let aos = &samples[..];
+    let soa = SoaSamples::from(aos);
+    assert_eq!(answers(aos), answers(&soa));
+    let (mean, hot, range, valid, low) = answers(aos);
+    assert!(mean.unwrap() > 20.0 && mean.unwrap() < 40.0);
+    assert!(hot > 0 && hot < samples.len());
+    let (lo, hi) = range.unwrap();
+    assert!(lo < hi);
+    assert_ne!(valid, mean);
+    assert!(low > 0);
+}
+
+#[test]
+fn empty_layouts_have_no_answers() {
+    let aos: &[Sample] = &[];
+    let soa = SoaSamples::new();
+    assert_eq!(answers(aos), (None, 0, None, None, 0));
+    assert_eq!(answers(&soa), answers(aos));
+    assert_eq!(aos.sample(0), None);
+    assert_eq!(soa.sample(0), None);
+}
+
+#[test]
+fn every_sample_round_trips() {
+    let samples = simulate(10_000, 50, 3);
+    let aos = &samples[..];
+    let soa = SoaSamples::from(aos);
+    assert_eq!(soa.len(), aos.len());
+    assert!(soa.iter().eq(aos.iter().copied()));
+    for i in [0, 1, 4999, 9999, 10_000] {
+        assert_eq!(soa.sample(i), aos.sample(i), "{}", i);
+    }
+}
@@ -0,0 +1,51 @@
+// Throughput of each strategy under contention
+//
+//   cargo bench                    # both mixes
+//   cargo bench -- read_heavy      # one mix
+//
+// Each iteration is a whole workload run: the threads start together on a
+// barrier and the time is from release to the last join, so thread
+// startup is not measured. Tail latency needs every operation timed, which
+// criterion does not do; `cargo run --release` prints the percentiles.
+
+use contention::{run, strategies, Workload};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::time::Duration;
+
+const DEVICES: u32 = 1024;
+const OPS: usize = 40_000;
+
+fn mix(c: &mut Criterion, group_name: &str, read_percent: u32) {
+    let mut group = c.benchmark_group(group_name);
+    group.throughput(Throughput::Elements(OPS as u64));
+    group.sample_size(20);
+    for store in strategies(DEVICES) {
+        for threads in [1, 4] {
+            let w = Workload {
+                threads,
+                ops_per_thread: OPS / threads,
+                read_percent,
+                devices: DEVICES,
+            };
+            group.bench_function(BenchmarkId::new(store.name(), threads), |b| {
+                b.iter_custom(|iters| {
+                    (0..iters)
+                        .map(|_| run(store.as_ref(), &w).elapsed)
+                        .sum::<Duration>()
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn read_heavy(c: &mut Criterion) {
+    mix(c, "read_heavy", 95);
+}
+
+fn write_heavy(c: &mut Criterion) {
+    mix(c, "write_heavy", 10);
+}
+
+criterion_group!(benches, read_heavy, write_heavy);
+criterion_main!(benches);
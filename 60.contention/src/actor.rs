@@ -0,0 +1,95 @@
+// No shared map at all: one thread owns it and the others send messages
+//
+// Updates are fire-and-forget, so a writer only pays for the send. A read
+// is a round trip: the request carries a reply channel, and the caller
+// blocks until the owner thread gets to it. Every request goes through
+// one queue, so the owner is the bottleneck instead of a lock.
+
+use crate::locked::{counts, populated};
+use crate::store::{DeviceState, DeviceStore};
+use std::sync::mpsc::{self, Sender, SyncSender};
+use std::thread::{self, JoinHandle};
+
+enum Command {
+    Update {
+        id: u32,
+        value: f32,
+        at_ms: u64,
+    },
+    Get {
+        id: u32,
+        reply: SyncSender<Option<DeviceState>>,
+    },
+    Counts {
+        reply: SyncSender<Vec<u64>>,
+    },
+}
+
+pub struct ChannelStore {
+    requests: Sender<Command>,
+    owner: Option<JoinHandle<()>>,
+}
+
+impl ChannelStore {
+    pub fn new(devices: u32) -> Self {
+        let (requests, inbox) = mpsc::channel::<Command>();
+        let owner = thread::spawn(move || {
+            let mut map = populated(devices);
+            // Ends when the last Sender is dropped
+            for command in inbox {
+                match command {
+                    Command::Update { id, value, at_ms } => {
+                        map.entry(id).or_default().apply(value, at_ms)
+                    }
+                    Command::Get { id, reply } => {
+                        let _ = reply.send(map.get(&id).copied());
+                    }
+                    Command::Counts { reply } => {
+                        let _ = reply.send(counts(map.iter()));
+                    }
+                }
+            }
+        });
+        ChannelStore {
+            requests,
+            owner: Some(owner),
+        }
+    }
+}
+
+impl DeviceStore for ChannelStore {
+    fn name(&self) -> &'static str {
+        "Channel"
+    }
+
+    fn update(&self, id: u32, value: f32, at_ms: u64) {
+        let _ = self.requests.send(Command::Update { id, value, at_ms });
+    }
+
+    // One rendezvous channel per read: the honest price of request/reply
+    fn get(&self, id: u32) -> Option<DeviceState> {
+        let (reply, answer) = mpsc::sync_channel(1);
+        self.requests.send(Command::Get { id, reply }).ok()?;
+        answer.recv().ok().flatten()
+    }
+
+    // Queued behind every update sent before it, so it sees all of them
+    fn update_counts(&self) -> Vec<u64> {
+        let (reply, answer) = mpsc::sync_channel(1);
+        if self.requests.send(Command::Counts { reply }).is_err() {
+            return Vec::new();
+        }
+        answer.recv().unwrap_or_default()
+    }
+}
+
+impl Drop for ChannelStore {
+    fn drop(&mut self) {
+        // Replace our Sender so the owner's loop ends, then wait for it
+        let (closed, _) = mpsc::channel();
+        drop(std::mem::replace(&mut self.requests, closed));
+        if let Some(owner) = self.owner.take() {
+            let _ = owner.join();
+        }
+    }
+}
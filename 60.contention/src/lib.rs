@@ -0,0 +1,32 @@
+// Lock contention: four ways to share device state between threads
+//
+// The gateway keeps the latest state of every device in a map that the
+// poll threads write and the status and rule threads read. How that map
+// is shared decides how well it scales:
+//
+// - `locked`: one `Mutex<HashMap>`, or one `RwLock<HashMap>`
+// - `sharded`: 16 `Mutex<HashMap>`s, picked by device id
+// - `actor`: one owner thread, updates and reads sent over channels
+// - `workload`: N threads, a read/write mix, throughput and percentiles
+
+pub mod actor;
+pub mod locked;
+pub mod sharded;
+pub mod store;
+pub mod workload;
+
+pub use actor::ChannelStore;
+pub use locked::{MutexStore, RwLockStore};
+pub use sharded::{ShardedStore, DEFAULT_SHARDS};
+pub use store::{DeviceState, DeviceStore};
+pub use workload::{run, Latency, Outcome, Workload};
+
+// A fresh store of every kind, in the order the tables print them
+pub fn strategies(devices: u32) -> Vec<Box<dyn DeviceStore>> {
+    vec![
+        Box::new(MutexStore::new(devices)),
+        Box::new(RwLockStore::new(devices)),
+        Box::new(ShardedStore::new(devices, DEFAULT_SHARDS)),
+        Box::new(ChannelStore::new(devices)),
+    ]
+}
@@ -0,0 +1,88 @@
+// One lock around one HashMap: the first thing everyone writes
+
+use crate::store::{DeviceState, DeviceStore};
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+
+pub(crate) fn populated(devices: u32) -> HashMap<u32, DeviceState> {
+    (0..devices)
+        .map(|id| (id, DeviceState::default()))
+        .collect()
+}
+
+pub(crate) fn counts<'a>(states: impl Iterator<Item = (&'a u32, &'a DeviceState)>) -> Vec<u64> {
+    let mut counts = Vec::new();
+    for (&id, state) in states {
+        let id = id as usize;
+        if id >= counts.len() {
+            counts.resize(id + 1, 0);
+        }
+        counts[id] = state.updates;
+    }
+    counts
+}
+
+// Readers and writers all queue on the same lock
+pub struct MutexStore {
+    map: Mutex<HashMap<u32, DeviceState>>,
+}
+
+impl MutexStore {
+    pub fn new(devices: u32) -> Self {
+        MutexStore {
+            map: Mutex::new(populated(devices)),
+        }
+    }
+}
+
+impl DeviceStore for MutexStore {
+    fn name(&self) -> &'static str {
+        "Mutex"
+    }
+
+    fn update(&self, id: u32, value: f32, at_ms: u64) {
+        let mut map = self.map.lock().unwrap();
+        map.entry(id).or_default().apply(value, at_ms);
+    }
+
+    fn get(&self, id: u32) -> Option<DeviceState> {
+        self.map.lock().unwrap().get(&id).copied()
+    }
+
+    fn update_counts(&self) -> Vec<u64> {
+        counts(self.map.lock().unwrap().iter())
+    }
+}
+
+// Readers share the lock; a writer waits for all of them and blocks new
+// ones. Taking a read lock is still a write to the lock's shared counter
+pub struct RwLockStore {
+    map: RwLock<HashMap<u32, DeviceState>>,
+}
+
+impl RwLockStore {
+    pub fn new(devices: u32) -> Self {
+        RwLockStore {
+            map: RwLock::new(populated(devices)),
+        }
+    }
+}
+
+impl DeviceStore for RwLockStore {
+    fn name(&self) -> &'static str {
+        "RwLock"
+    }
+
+    fn update(&self, id: u32, value: f32, at_ms: u64) {
+        let mut map = self.map.write().unwrap();
+        map.entry(id).or_default().apply(value, at_ms);
+    }
+
+    fn get(&self, id: u32) -> Option<DeviceState> {
+        self.map.read().unwrap().get(&id).copied()
+    }
+
+    fn update_counts(&self) -> Vec<u64> {
+        counts(self.map.read().unwrap().iter())
+    }
+}
@@ -0,0 +1,27 @@
+// What every strategy stores, and the interface the workload drives
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DeviceState {
+    pub last_value: f32,
+    pub last_seen_ms: u64,
+    pub updates: u64,
+}
+
+impl DeviceState {
+    pub fn apply(&mut self, value: f32, at_ms: u64) {
+        self.last_value = value;
+        self.last_seen_ms = at_ms;
+        self.updates += 1;
+    }
+}
+
+// A map from device id to its latest state, shared by many threads.
+// Devices `0..devices` exist from the start; updates to other ids add them
+pub trait DeviceStore: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn update(&self, id: u32, value: f32, at_ms: u64);
+    fn get(&self, id: u32) -> Option<DeviceState>;
+    // Number of updates per device, indexed by id. Order-independent, so
+    // every strategy must agree on it after the same workload
+    fn update_counts(&self) -> Vec<u64>;
+}
@@ -0,0 +1,119 @@
+// N threads hammering one store, with a latency for every operation
+//
+// All threads wait on a barrier and start together. Each one draws device
+// ids and the read/write choice from its own seeded generator, so the same
+// `Workload` issues exactly the same updates against every strategy, and
+// the final update counts must match. Each operation is timed on its own
+// with `Instant`, which adds a few tens of nanoseconds to every sample
+// but keeps the tail visible.
+
+use crate::store::DeviceStore;
+use std::sync::Barrier;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Workload {
+    pub threads: usize,
+    pub ops_per_thread: usize,
+    // 0 = updates only, 100 = reads only
+    pub read_percent: u32,
+    pub devices: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Latency {
+    pub p50: Duration,
+    pub p99: Duration,
+    pub p999: Duration,
+    pub max: Duration,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Outcome {
+    pub ops: usize,
+    pub reads: usize,
+    pub elapsed: Duration,
+    pub latency: Latency,
+}
+
+impl Outcome {
+    pub fn ops_per_sec(&self) -> f64 {
+        self.ops as f64 / self.elapsed.as_secs_f64().max(1e-9)
+    }
+}
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 32) as u32
+    }
+}
+
+// Nearest-rank percentile of sorted nanoseconds
+fn percentile(sorted: &[u64], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Duration::from_nanos(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+pub fn run(store: &dyn DeviceStore, w: &Workload) -> Outcome {
+    let barrier = Barrier::new(w.threads + 1);
+    let (elapsed, per_thread) = thread::scope(|s| {
+        let workers: Vec<_> = (0..w.threads)
+            .map(|t| {
+                let barrier = &barrier;
+                s.spawn(move || {
+                    let seed = (t as u64 + 1).wrapping_mul(0xD1B5_4A32_D192_ED03);
+                    let mut rng = Rng(0x9E37_79B9_7F4A_7C15 ^ seed);
+                    let mut nanos = Vec::with_capacity(w.ops_per_thread);
+                    let mut reads = 0;
+                    barrier.wait();
+                    for n in 0..w.ops_per_thread {
+                        let id = rng.next() % w.devices.max(1);
+                        let read = rng.next() % 100 < w.read_percent;
+                        let started = Instant::now();
+                        if read {
+                            std::hint::black_box(store.get(id));
+                            reads += 1;
+                        } else {
+                            store.update(id, (n % 1000) as f32 / 10.0, n as u64);
+                        }
+                        nanos.push(started.elapsed().as_nanos() as u64);
+                    }
+                    (nanos, reads)
+                })
+            })
+            .collect();
+        barrier.wait();
+        let started = Instant::now();
+        let results: Vec<_> = workers
+            .into_iter()
+            .map(|h| h.join().expect("worker panicked"))
+            .collect();
+        (started.elapsed(), results)
+    });
+
+    let reads = per_thread.iter().map(|(_, r)| r).sum();
+    let mut nanos: Vec<u64> = per_thread.into_iter().flat_map(|(n, _)| n).collect();
+    nanos.sort_unstable();
+    Outcome {
+        ops: nanos.len(),
+        reads,
+        elapsed,
+        latency: Latency {
+            p50: percentile(&nanos, 50.0),
+            p99: percentile(&nanos, 99.0),
+            p999: percentile(&nanos, 99.9),
+            max: nanos
+                .last()
+                .map_or(Duration::ZERO, |&n| Duration::from_nanos(n)),
+        },
+    }
+}
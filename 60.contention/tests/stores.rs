@@ -0,0 +1,75 @@
+use contention::{strategies, DeviceState, DeviceStore, ShardedStore};
+use std::thread;
+
+#[test]
+fn strategies_in_table_order() {
+    let names: Vec<_> = strategies(4).iter().map(|s| s.name()).collect();
+    assert_eq!(names, ["Mutex", "RwLock", "Sharded", "Channel"]);
+}
+
+#[test]
+fn devices_exist_from_the_start() {
+    for store in strategies(8) {
+        assert_eq!(
+            store.get(7),
+            Some(DeviceState::default()),
+            "{}",
+            store.name()
+        );
+        assert_eq!(store.get(8), None, "{}", store.name());
+        assert_eq!(store.update_counts(), [0; 8], "{}", store.name());
+    }
+}
+
+#[test]
+fn updates_apply_and_add_unknown_devices() {
+    for store in strategies(4) {
+        store.update(2, 21.5, 1000);
+        store.update(2, 22.0, 2000);
+        // Reads after updates from the same thread see them, channel included
+        assert_eq!(
+            store.get(2),
+            Some(DeviceState {
+                last_value: 22.0,
+                last_seen_ms: 2000,
+                updates: 2,
+            }),
+            "{}",
+            store.name()
+        );
+        store.update(9, 1.0, 3000);
+        assert_eq!(store.get(9).map(|s| s.updates), Some(1), "{}", store.name());
+        assert_eq!(
+            store.update_counts(),
+            [0, 0, 2, 0, 0, 0, 0, 0, 0, 1],
+            "{}",
+            store.name()
+        );
+    }
+}
+
+#[test]
+fn concurrent_updates_are_all_counted() {
+    for store in strategies(16) {
+        let store = store.as_ref();
+        thread::scope(|s| {
+            for t in 0..4u32 {
+                s.spawn(move || {
+                    for n in 0..1000u32 {
+                        store.update((t * 7 + n) % 16, n as f32, n as u64);
+                    }
+                });
+            }
+        });
+        let counts = store.update_counts();
+        assert_eq!(counts.iter().sum::<u64>(), 4000, "{}", store.name());
+        assert_eq!(counts.len(), 16, "{}", store.name());
+    }
+}
+
+#[test]
+fn zero_shards_means_one() {
+    let store = ShardedStore::new(4, 0);
+    store.update(3, 1.0, 1);
+    assert_eq!(store.update_counts(), [0, 0, 0, 1]);
+}
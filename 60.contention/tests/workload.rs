@@ -0,0 +1,69 @@
+use contention::{run, strategies, Workload};
+
+fn workload(read_percent: u32) -> Workload {
+    Workload {
+        threads: 4,
+        ops_per_thread: 5_000,
+        read_percent,
+        devices: 256,
+    }
+}
+
+// Seeded per thread, so every strategy gets exactly the same updates
+#[test]
+fn every_strategy_ends_in_the_same_state() {
+    let w = workload(50);
+    let mut all_counts = Vec::new();
+    for store in strategies(w.devices) {
+        let o = run(store.as_ref(), &w);
+        assert_eq!(o.ops, 20_000, "{}", store.name());
+        let counts = store.update_counts();
+        let total: u64 = counts.iter().sum();
+        assert_eq!(total as usize, o.ops - o.reads, "{}", store.name());
+        all_counts.push((o.reads, counts));
+    }
+    for pair in all_counts.windows(2) {
+        assert_eq!(pair[0], pair[1]);
+    }
+    // Roughly the mix asked for
+    let reads = all_counts[0].0;
+    assert!(reads > 9_000 && reads < 11_000, "{}", reads);
+}
+
+#[test]
+fn the_mix_ends_are_all_reads_or_all_updates() {
+    for store in strategies(64) {
+        let o = run(store.as_ref(), &workload(100));
+        assert_eq!(o.reads, o.ops);
+        assert_eq!(store.update_counts().iter().sum::<u64>(), 0);
+        let o = run(store.as_ref(), &workload(0));
+        assert_eq!(o.reads, 0);
+        assert_eq!(store.update_counts().iter().sum::<u64>(), 20_000);
+    }
+}
+
+#[test]
+fn percentiles_are_ordered() {
+    let store = &strategies(64)[0];
+    let o = run(store.as_ref(), &workload(50));
+    let l = o.latency;
+    assert!(
+        l.p50 <= l.p99 && l.p99 <= l.p999 && l.p999 <= l.max,
+        "{:?}",
+        l
+    );
+    assert!(l.max > std::time::Duration::ZERO);
+    assert!(o.ops_per_sec() > 0.0);
+}
+
+#[test]
+fn no_operations_is_an_empty_outcome() {
+    let store = &strategies(4)[0];
+    let w = Workload {
+        ops_per_thread: 0,
+        ..workload(50)
+    };
+    let o = run(store.as_ref(), &w);
+    assert_eq!((o.ops, o.reads), (0, 0));
+    assert_eq!(o.latency.max, std::time::Duration::ZERO);
+}
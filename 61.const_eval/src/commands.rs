@@ -0,0 +1,122 @@
+// The console's command names, looked up without hashing at run time
+//
+// `build` runs at compile time: it tries seeds for an FNV-1a hash until
+// every name lands in its own slot of a 16-slot table. A lookup is then
+// one hash, one slot and one string comparison, with no probing. If a new
+// command makes every seed collide, the build fails with the message from
+// the panic, not the first user.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Command {
+    Ping,
+    Status,
+    Uptime,
+    Version,
+    Reboot,
+    SetInterval,
+    ReadSensor,
+    Calibrate,
+    LogLevel,
+    FactoryReset,
+}
+
+pub const NAMES: [(&str, Command); 10] = [
+    ("ping", Command::Ping),
+    ("status", Command::Status),
+    ("uptime", Command::Uptime),
+    ("version", Command::Version),
+    ("reboot", Command::Reboot),
+    ("set-interval", Command::SetInterval),
+    ("read-sensor", Command::ReadSensor),
+    ("calibrate", Command::Calibrate),
+    ("log-level", Command::LogLevel),
+    ("factory-reset", Command::FactoryReset),
+];
+
+pub const SLOTS: usize = 16;
+const MAX_SEED: u32 = 10_000;
+
+pub struct Table {
+    pub seed: u32,
+    pub slots: [Option<(&'static str, Command)>; SLOTS],
+}
+
+pub const fn hash(name: &[u8], seed: u32) -> u32 {
+    let mut h = 0x811C_9DC5 ^ seed;
+    let mut i = 0;
+    while i < name.len() {
+        h ^= name[i] as u32;
+        h = h.wrapping_mul(0x0100_0193);
+        i += 1;
+    }
+    h
+}
+
+// The high bits: FNV's low bits depend only on the low bits of the input
+const fn slot(name: &str, seed: u32) -> usize {
+    (hash(name.as_bytes(), seed) >> 16) as usize % SLOTS
+}
+
+const fn bytes_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn build(names: &[(&'static str, Command)]) -> Table {
+    let mut seed = 0;
+    while seed < MAX_SEED {
+        let mut slots: [Option<(&'static str, Command)>; SLOTS] = [None; SLOTS];
+        let mut ok = true;
+        let mut i = 0;
+        while i < names.len() && ok {
+            let s = slot(names[i].0, seed);
+            if slots[s].is_some() {
+                ok = false;
+            } else {
+                slots[s] = Some(names[i]);
+            }
+            i += 1;
+        }
+        if ok {
+            return Table { seed, slots };
+        }
+        seed += 1;
+    }
+    panic!("no collision-free seed: raise SLOTS or MAX_SEED");
+}
+
+pub static TABLE: Table = build(&NAMES);
+
+pub const fn lookup(name: &str) -> Option<Command> {
+    match TABLE.slots[slot(name, TABLE.seed)] {
+        Some((known, command)) if bytes_eq(known.as_bytes(), name.as_bytes()) => Some(command),
+        _ => None,
+    }
+}
+
+// The usual alternative: hashed with SipHash and filled at startup
+pub fn runtime_map() -> HashMap<&'static str, Command> {
+    NAMES.iter().copied().collect()
+}
+
+const _: () = assert!(NAMES.len() <= SLOTS);
+const _: () = assert!(matches!(lookup("reboot"), Some(Command::Reboot)));
+const _: () = assert!(lookup("rebooot").is_none());
+const _: () = {
+    let mut i = 0;
+    while i < NAMES.len() {
+        assert!(lookup(NAMES[i].0).is_some());
+        i += 1;
+    }
+};
@@ -0,0 +1,16 @@
+// Compile-time computation with const fn
+//
+// Lookup tables are usually filled by a loop at startup. With `const fn`
+// the compiler runs that loop instead, the result is stored in the
+// binary's read-only data, and `const _: () = assert!(...)` checks it
+// before the program exists. Each module also keeps the runtime version
+// for comparison.
+//
+// - `crc`: the CRC-8/MAXIM table used by 1-Wire sensors
+// - `sine`: a Q15 sine table, with sin computed by a const Taylor series
+// - `commands`: a collision-free command-name table whose seed is searched
+//   for at compile time
+
+pub mod commands;
+pub mod crc;
+pub mod sine;
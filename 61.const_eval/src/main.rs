@@ -0,0 +1,157 @@
+use const_eval::commands::{self, lookup, Command, NAMES, SLOTS};
+use const_eval::crc::{self, crc8_maxim};
+use const_eval::sine::{self, sin_q15, Q15_ONE, SINE, STEPS};
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::mem::size_of_val;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+// The runtime alternatives, filled on first access
+static RUNTIME_CRC: LazyLock<[u8; 256]> = LazyLock::new(crc::runtime_table);
+static RUNTIME_SINE: LazyLock<[i16; STEPS]> = LazyLock::new(sine::runtime_table);
+static RUNTIME_COMMANDS: LazyLock<HashMap<&str, Command>> = LazyLock::new(commands::runtime_map);
+
+// Results of const evaluation used where only constants are allowed
+const ROM_CODE: [u8; 7] = [0x28, 0xFF, 0x4C, 0x02, 0x93, 0x16, 0x04];
+const ROM_CRC: u8 = crc8_maxim(&ROM_CODE);
+const REBOOT: Option<Command> = lookup("reboot");
+const QUARTER_TURN: u8 = (STEPS / 4) as u8;
+
+fn first_access<T>(f: impl FnOnce() -> T) -> Duration {
+    let started = Instant::now();
+    black_box(f());
+    started.elapsed()
+}
+
+fn main() {
+    println!("=== Compile-Time Computation Examples ===\n");
+
+    // 1. CRC table
+    println!("1. CRC-8/MAXIM (1-Wire):");
+    println!("   TABLE[0..8] = {:02X?}", &crc::TABLE[..8]);
+    println!(
+        "   crc8_maxim(\"123456789\") = {:02X}",
+        crc8_maxim(b"123456789")
+    );
+    println!("   DS18B20 ROM {:02X?} -> CRC {:02X}", ROM_CODE, ROM_CRC);
+    println!("   runtime TABLE[0..8] = {:02X?}", &RUNTIME_CRC[..8]);
+    let mut scratchpad = ROM_CODE.to_vec();
+    scratchpad.push(ROM_CRC);
+    println!(
+        "   ROM code plus its CRC -> {:02X}",
+        crc8_maxim(&scratchpad)
+    );
+
+    // 2. Sine table
+    println!("\n2. Q15 sine table, {} steps:", STEPS);
+    for phase in [0u8, 21, 32, 64, 128, 192] {
+        let exact = (2.0 * std::f64::consts::PI * phase as f64 / STEPS as f64).sin();
+        println!(
+            "   phase {:>3}: {:>6}  ({:+.5}, f64::sin {:+.5})",
+            phase,
+            sin_q15(phase),
+            sin_q15(phase) as f64 / Q15_ONE as f64,
+            exact
+        );
+    }
+    let max_diff = SINE
+        .iter()
+        .zip(RUNTIME_SINE.iter())
+        .map(|(a, b)| (*a as i32 - *b as i32).abs())
+        .max()
+        .unwrap_or(0);
+    println!("   largest difference from f64::sin: {} LSB", max_diff);
+    println!(
+        "   a quarter turn, phase {}: {}",
+        QUARTER_TURN,
+        sin_q15(QUARTER_TURN)
+    );
+
+    // 3. Command table
+    println!("\n3. Command table, seed found while compiling:");
+    let occupancy: String = commands::TABLE
+        .slots
+        .iter()
+        .map(|s| if s.is_some() { '#' } else { '.' })
+        .collect();
+    println!(
+        "   seed {}, {} names in {} slots: [{}]",
+        commands::TABLE.seed,
+        NAMES.len(),
+        SLOTS,
+        occupancy
+    );
+    for name in ["status", "set-interval", "factory-reset", "reset", ""] {
+        println!("   {:<15} -> {:?}", format!("{:?}", name), lookup(name));
+    }
+    println!(
+        "   HashMap: \"reset\" -> {:?}, \"status\" -> {:?}",
+        RUNTIME_COMMANDS.get("reset"),
+        RUNTIME_COMMANDS.get("status")
+    );
+
+    // 4. Const results in const positions
+    println!("\n4. Using const results where constants are required:");
+    let describe = |crc: u8| match crc {
+        ROM_CRC => "matches the known sensor",
+        _ => "unknown sensor",
+    };
+    println!("   match arm on ROM_CRC: {}", describe(0x6C));
+    println!("   const REBOOT = {:?}", REBOOT);
+    let buffer = [0u8; STEPS / 8];
+    println!("   [u8; STEPS / 8] has {} bytes", buffer.len());
+
+    // 5. What each choice costs
+    println!("\n5. Startup and size:");
+    println!(
+        "   {:<10} {:>10} {:>14} {:>14}",
+        "table", "bytes", "const: build", "runtime: build"
+    );
+    let rows = [
+        (
+            "crc",
+            size_of_val(&crc::TABLE),
+            first_access(crc::runtime_table),
+        ),
+        (
+            "sine",
+            size_of_val(&SINE),
+            first_access(sine::runtime_table),
+        ),
+        (
+            "commands",
+            size_of_val(&commands::TABLE.slots),
+            first_access(commands::runtime_map),
+        ),
+    ];
+    for (name, bytes, runtime) in rows {
+        println!(
+            "   {:<10} {:>10} {:>14} {:>14.2?}",
+            name, bytes, "0 (compiler)", runtime
+        );
+    }
+    let lookups = 100_000;
+    let const_time = first_access(|| {
+        (0..lookups)
+            .filter(|i| lookup(black_box(NAMES[i % NAMES.len()].0)).is_some())
+            .count()
+    });
+    let map_time = first_access(|| {
+        (0..lookups)
+            .filter(|i| {
+                RUNTIME_COMMANDS
+                    .get(black_box(NAMES[i % NAMES.len()].0))
+                    .is_some()
+            })
+            .count()
+    });
+    println!(
+        "   {} lookups: const table {:.2?}, HashMap {:.2?} (use --release)",
+        lookups, const_time, map_time
+    );
+    println!("   const tables live in .rodata (flash on a microcontroller),");
+    println!("   runtime ones in RAM, plus the code that fills them");
+
+    println!("\n=== End of Compile-Time Computation Examples ===");
+}
@@ -0,0 +1,94 @@
+// A Q15 sine table for generating test waveforms and mixing phases
+//
+// `f64::sin` is not a const fn, but float arithmetic is (Rust 1.82), so
+// the table computes sin itself with a Taylor series. Only the first
+// quarter is computed; the rest is mirrored from it, so the symmetries
+// the const assertions check hold exactly, not just approximately.
+
+use std::f64::consts::PI;
+
+pub const Q15_ONE: i16 = i16::MAX;
+
+// sin(x) for 0 <= x <= π/2, to well below one Q15 step
+const fn taylor_sin(x: f64) -> f64 {
+    let x2 = x * x;
+    let mut term = x;
+    let mut sum = x;
+    let mut n = 1;
+    while n < 12 {
+        term = -term * x2 / ((2 * n) as f64 * (2 * n + 1) as f64);
+        sum += term;
+        n += 1;
+    }
+    sum
+}
+
+// Round half away from zero, as a const fn
+const fn to_q15(v: f64) -> i16 {
+    let scaled = v * Q15_ONE as f64;
+    if scaled >= 0.0 {
+        (scaled + 0.5) as i16
+    } else {
+        (scaled - 0.5) as i16
+    }
+}
+
+// One full period in N steps; N must be a multiple of 4
+pub const fn table<const N: usize>() -> [i16; N] {
+    const {
+        assert!(
+            N.is_multiple_of(4) && N > 0,
+            "N must be a positive multiple of 4"
+        )
+    };
+    let mut table = [0i16; N];
+    let quarter = N / 4;
+    let mut i = 0;
+    while i <= quarter {
+        let value = to_q15(taylor_sin(2.0 * PI * i as f64 / N as f64));
+        table[i] = value;
+        table[N / 2 - i] = value;
+        if i > 0 {
+            table[N - i] = -value;
+        }
+        table[(N / 2 + i) % N] = -value;
+        i += 1;
+    }
+    table
+}
+
+pub const STEPS: usize = 256;
+pub static SINE: [i16; STEPS] = table::<STEPS>();
+
+// Phase 0..=255 is one full turn
+pub fn sin_q15(phase: u8) -> i16 {
+    SINE[phase as usize]
+}
+
+// The same table from `f64::sin`, built when first needed
+pub fn runtime_table() -> [i16; STEPS] {
+    let mut table = [0i16; STEPS];
+    for (i, entry) in table.iter_mut().enumerate() {
+        let x = 2.0 * PI * i as f64 / STEPS as f64;
+        *entry = (x.sin() * Q15_ONE as f64).round() as i16;
+    }
+    table
+}
+
+const fn is_odd_symmetric(t: &[i16]) -> bool {
+    let n = t.len();
+    let mut i = 1;
+    while i < n {
+        if t[i] != -t[n - i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const _: () = assert!(SINE[0] == 0 && SINE[STEPS / 2] == 0);
+const _: () = assert!(SINE[STEPS / 4] == Q15_ONE && SINE[3 * STEPS / 4] == -Q15_ONE);
+const _: () = assert!(is_odd_symmetric(&SINE));
+// Other sizes are checked the same way: sin(45°) * 32767 = 23169.77
+const _: () = assert!(table::<8>()[1] == 23170);
@@ -0,0 +1,52 @@
+use const_eval::commands::{hash, lookup, runtime_map, Command, NAMES, SLOTS, TABLE};
+use std::collections::HashSet;
+
+const REBOOT: Option<Command> = lookup("reboot");
+
+#[test]
+fn every_name_finds_its_command() {
+    for (name, command) in NAMES {
+        assert_eq!(lookup(name), Some(command), "{}", name);
+    }
+    assert_eq!(REBOOT, Some(Command::Reboot));
+}
+
+#[test]
+fn agrees_with_a_hashmap_on_unknown_names() {
+    let map = runtime_map();
+    assert_eq!(map.len(), NAMES.len());
+    for name in [
+        "reset",
+        "Ping",
+        "ping ",
+        "set_interval",
+        "",
+        "factory-reset!",
+    ] {
+        assert_eq!(lookup(name), map.get(name).copied(), "{:?}", name);
+        assert_eq!(lookup(name), None, "{:?}", name);
+    }
+}
+
+#[test]
+fn the_seed_puts_each_name_in_its_own_slot() {
+    let filled: Vec<_> = TABLE.slots.iter().flatten().collect();
+    assert_eq!(filled.len(), NAMES.len());
+    let slots: HashSet<usize> = NAMES
+        .iter()
+        .map(|(name, _)| (hash(name.as_bytes(), TABLE.seed) >> 16) as usize % SLOTS)
+        .collect();
+    assert_eq!(slots.len(), NAMES.len());
+    for (i, slot) in TABLE.slots.iter().enumerate() {
+        if let Some((name, _)) = slot {
+            assert!(NAMES.iter().any(|(n, _)| n == name), "slot {}", i);
+        }
+    }
+}
+
+#[test]
+fn fnv1a_with_seed_zero_is_plain_fnv1a() {
+    assert_eq!(hash(b"", 0), 0x811C_9DC5);
+    assert_eq!(hash(b"a", 0), 0xE40C_292C);
+    assert_ne!(hash(b"a", 1), hash(b"a", 0));
+}
@@ -0,0 +1,32 @@
+use const_eval::crc::{crc8_maxim, runtime_table, table, POLY_REFLECTED, TABLE};
+
+// A DS18B20 ROM code: family 0x28, serial, then the CRC byte
+const ROM_CODE: [u8; 7] = [0x28, 0xFF, 0x4C, 0x02, 0x93, 0x16, 0x04];
+const ROM_CRC: u8 = crc8_maxim(&ROM_CODE);
+
+#[test]
+fn const_table_matches_the_runtime_loop() {
+    assert_eq!(TABLE, runtime_table());
+    assert_eq!(table(POLY_REFLECTED), TABLE);
+    assert_eq!(TABLE[..4], [0x00, 0x5E, 0xBC, 0xE2]);
+}
+
+#[test]
+fn check_value_and_rom_code() {
+    assert_eq!(crc8_maxim(b"123456789"), 0xA1);
+    assert_eq!(crc8_maxim(&[]), 0);
+    assert_eq!(ROM_CRC, 0x6C);
+    // A frame followed by its CRC checks to zero
+    let mut scratchpad = ROM_CODE.to_vec();
+    scratchpad.push(ROM_CRC);
+    assert_eq!(crc8_maxim(&scratchpad), 0);
+    scratchpad[3] ^= 0x10;
+    assert_ne!(crc8_maxim(&scratchpad), 0);
+}
+
+#[test]
+fn const_results_work_as_patterns() {
+    let known = |crc: u8| matches!(crc, ROM_CRC);
+    assert!(known(0x6C));
+    assert!(!known(0x6D));
+}
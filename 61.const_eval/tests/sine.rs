@@ -0,0 +1,32 @@
+use const_eval::sine::{runtime_table, sin_q15, table, Q15_ONE, SINE, STEPS};
+
+#[test]
+fn taylor_table_matches_f64_sin() {
+    assert_eq!(SINE, runtime_table());
+}
+
+#[test]
+fn quarter_turns() {
+    assert_eq!(sin_q15(0), 0);
+    assert_eq!(sin_q15((STEPS / 4) as u8), Q15_ONE);
+    assert_eq!(sin_q15((STEPS / 2) as u8), 0);
+    assert_eq!(sin_q15((3 * STEPS / 4) as u8), -Q15_ONE);
+    // Odd symmetry around the half turn
+    for phase in 1..=255u8 {
+        assert_eq!(sin_q15(phase), -sin_q15(phase.wrapping_neg()), "{}", phase);
+    }
+}
+
+#[test]
+fn other_sizes_from_the_same_const_fn() {
+    const EIGHT: [i16; 8] = table::<8>();
+    assert_eq!(
+        EIGHT,
+        [0, 23170, Q15_ONE, 23170, 0, -23170, -Q15_ONE, -23170]
+    );
+    // Every fourth entry of the big table is the 64-step table
+    let small = table::<64>();
+    for (i, &v) in small.iter().enumerate() {
+        assert_eq!(v, SINE[i * 4], "{}", i);
+    }
+}
@@ -0,0 +1,82 @@
+// Box-per-node vs arena parsing of the same rule expressions
+//
+//   cargo bench              # every group
+//   cargo bench -- parse     # parsing only
+//
+// `parse` includes dropping the tree, since that is where `Box` pays a
+// second time. `arena_reset` reuses one arena, the way a server parsing
+// one request after another would. `eval` checks that walking arena
+// references is no slower than walking boxes.
+
+use arena::{parse_boxed, parse_in, Arena};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use std::hint::black_box;
+
+const RULES: [&str; 6] = [
+    "temperature > 30.5 && humidity < 40",
+    "max(t1, t2, t3) - min(t1, t2, t3) > 4",
+    "!door_open || temperature < 5",
+    "abs(temperature - setpoint) > 2 * tolerance",
+    "(battery < 20 || rssi < -90) && uptime % 3600 < 60",
+    "-temperature + 2 * (humidity - 50) / 3 >= min(t1, 0)",
+];
+
+fn reading(name: &str) -> Option<f64> {
+    Some(name.len() as f64)
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    group.throughput(Throughput::Elements(RULES.len() as u64));
+    group.bench_function("box", |b| {
+        b.iter(|| {
+            for rule in RULES {
+                black_box(parse_boxed(black_box(rule)).unwrap());
+            }
+        })
+    });
+    group.bench_function("arena_fresh", |b| {
+        b.iter(|| {
+            let arena = Arena::new();
+            for rule in RULES {
+                black_box(parse_in(&arena, black_box(rule)).unwrap());
+            }
+        })
+    });
+    let mut arena = Arena::new();
+    group.bench_function("arena_reset", |b| {
+        b.iter(|| {
+            arena.reset();
+            for rule in RULES {
+                black_box(parse_in(&arena, black_box(rule)).unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
+fn eval(c: &mut Criterion) {
+    let boxed: Vec<_> = RULES.iter().map(|r| parse_boxed(r).unwrap()).collect();
+    let arena = Arena::new();
+    let in_arena: Vec<_> = RULES.iter().map(|r| parse_in(&arena, r).unwrap()).collect();
+    let mut group = c.benchmark_group("eval");
+    group.throughput(Throughput::Elements(RULES.len() as u64));
+    group.bench_function("box", |b| {
+        b.iter(|| {
+            for e in &boxed {
+                black_box(e.eval(&reading).unwrap());
+            }
+        })
+    });
+    group.bench_function("arena", |b| {
+        b.iter(|| {
+            for e in &in_arena {
+                black_box(e.eval(&reading).unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse, eval);
+criterion_main!(benches);
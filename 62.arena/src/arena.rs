@@ -0,0 +1,217 @@
+// A bump allocator: hand out the next free bytes of a chunk, free nothing
+// until the whole arena is reset or dropped
+//
+//   chunk 0 [node|node|str..|node|      free      ]
+//                                   ^ offset
+//
+// Allocation is a pointer bump and a bounds check. When a chunk is full,
+// the next one is twice as large. `reset` rewinds to the first chunk and
+// keeps every chunk, so a parser that resets between inputs stops calling
+// the system allocator once the arena has grown to its working size.
+//
+// Values are never dropped individually, so only types without drop glue
+// are accepted: `needs_drop::<T>()` is checked at compile time.
+
+use std::alloc::{self, Layout};
+use std::cell::{Cell, RefCell};
+use std::mem::{align_of, needs_drop};
+use std::ptr::{self, NonNull};
+use std::slice;
+
+// Chunk alignment, and so the largest alignment a value may need
+pub const MAX_ALIGN: usize = 16;
+pub const DEFAULT_CHUNK: usize = 4096;
+
+struct Chunk {
+    ptr: NonNull<u8>,
+    size: usize,
+}
+
+impl Chunk {
+    fn new(size: usize) -> Chunk {
+        let layout = Layout::from_size_align(size, MAX_ALIGN).expect("chunk size overflows");
+        // SAFETY: size is never zero (see `grow`)
+        let raw = unsafe { alloc::alloc(layout) };
+        let ptr = NonNull::new(raw).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+        Chunk { ptr, size }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ArenaStats {
+    pub chunks: usize,
+    // Bytes reserved from the system allocator
+    pub capacity_bytes: usize,
+    // Bytes handed out since the last reset, including alignment padding
+    pub used_bytes: usize,
+    pub allocations: usize,
+    pub resets: usize,
+}
+
+pub struct Arena {
+    chunks: RefCell<Vec<Chunk>>,
+    current: Cell<usize>,
+    offset: Cell<usize>,
+    used: Cell<usize>,
+    allocations: Cell<usize>,
+    first_chunk: usize,
+    resets: usize,
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Arena::with_chunk_size(DEFAULT_CHUNK)
+    }
+}
+
+impl Arena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // No memory is reserved until the first allocation
+    pub fn with_chunk_size(bytes: usize) -> Self {
+        Arena {
+            chunks: RefCell::new(Vec::new()),
+            current: Cell::new(0),
+            offset: Cell::new(0),
+            used: Cell::new(0),
+            allocations: Cell::new(0),
+            first_chunk: bytes.max(MAX_ALIGN),
+            resets: 0,
+        }
+    }
+
+    pub fn alloc<T>(&self, value: T) -> &T {
+        const { assert!(!needs_drop::<T>(), "arena values are never dropped") };
+        const { assert!(align_of::<T>() <= MAX_ALIGN) };
+        let ptr = self.alloc_layout(Layout::new::<T>()).cast::<T>();
+        // SAFETY: the memory is fresh, aligned for T, and never handed out again
+        unsafe {
+            ptr.as_ptr().write(value);
+            &*ptr.as_ptr()
+        }
+    }
+
+    pub fn alloc_str(&self, s: &str) -> &str {
+        let bytes = self.alloc_slice_copy(s.as_bytes());
+        // SAFETY: copied byte for byte from a `str`
+        unsafe { std::str::from_utf8_unchecked(bytes) }
+    }
+
+    pub fn alloc_slice_copy<T: Copy>(&self, values: &[T]) -> &[T] {
+        const { assert!(align_of::<T>() <= MAX_ALIGN) };
+        let layout = Layout::array::<T>(values.len()).expect("slice too large");
+        let ptr = self.alloc_layout(layout).cast::<T>();
+        // SAFETY: fresh memory for `values.len()` Ts; the ranges cannot overlap
+        unsafe {
+            ptr::copy_nonoverlapping(values.as_ptr(), ptr.as_ptr(), values.len());
+            slice::from_raw_parts(ptr.as_ptr(), values.len())
+        }
+    }
+
+    // Moves the items of an exact-size iterator, like a `Drain`, into the arena
+    pub fn alloc_slice_iter<T, I>(&self, items: I) -> &[T]
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        const { assert!(!needs_drop::<T>(), "arena values are never dropped") };
+        const { assert!(align_of::<T>() <= MAX_ALIGN) };
+        let mut items = items.into_iter();
+        let len = items.len();
+        let layout = Layout::array::<T>(len).expect("slice too large");
+        let ptr = self.alloc_layout(layout).cast::<T>();
+        for i in 0..len {
+            // `ExactSizeIterator` is a safe trait and may lie; stopping
+            // early only wastes the space, since T has no drop glue
+            let item = items.next().expect("iterator shorter than its len()");
+            // SAFETY: i < len, inside the block just allocated
+            unsafe { ptr.as_ptr().add(i).write(item) };
+        }
+        // SAFETY: all `len` elements were written above
+        unsafe { slice::from_raw_parts(ptr.as_ptr(), len) }
+    }
+
+    fn alloc_layout(&self, layout: Layout) -> NonNull<u8> {
+        self.allocations.set(self.allocations.get() + 1);
+        if layout.size() == 0 {
+            // Any aligned, non-null pointer is valid for a zero-sized value
+            return NonNull::new(ptr::without_provenance_mut(layout.align()))
+                .expect("alignment is never zero");
+        }
+        loop {
+            {
+                let chunks = self.chunks.borrow();
+                if let Some(chunk) = chunks.get(self.current.get()) {
+                    let start = self.offset.get().next_multiple_of(layout.align());
+                    if let Some(end) = start.checked_add(layout.size()) {
+                        if end <= chunk.size {
+                            self.used.set(self.used.get() + end - self.offset.get());
+                            self.offset.set(end);
+                            // SAFETY: start < end <= chunk.size
+                            return unsafe { chunk.ptr.add(start) };
+                        }
+                    }
+                }
+            }
+            self.grow(layout.size());
+        }
+    }
+
+    // Move to the next chunk that can hold `at_least` bytes, adding one
+    // if none is left from before a reset
+    fn grow(&self, at_least: usize) {
+        let mut chunks = self.chunks.borrow_mut();
+        let mut next = if chunks.is_empty() {
+            0
+        } else {
+            self.current.get() + 1
+        };
+        while next < chunks.len() && chunks[next].size < at_least {
+            next += 1;
+        }
+        if next == chunks.len() {
+            let last = chunks.last().map_or(self.first_chunk / 2, |c| c.size);
+            chunks.push(Chunk::new(
+                (last * 2).max(at_least.next_multiple_of(MAX_ALIGN)),
+            ));
+        }
+        self.current.set(next);
+        self.offset.set(0);
+    }
+
+    // `&mut self`: no reference into the arena can still be alive
+    pub fn reset(&mut self) {
+        self.current.set(0);
+        self.offset.set(0);
+        self.used.set(0);
+        self.allocations.set(0);
+        self.resets += 1;
+    }
+
+    pub fn stats(&self) -> ArenaStats {
+        let chunks = self.chunks.borrow();
+        ArenaStats {
+            chunks: chunks.len(),
+            capacity_bytes: chunks.iter().map(|c| c.size).sum(),
+            used_bytes: self.used.get(),
+            allocations: self.allocations.get(),
+            resets: self.resets,
+        }
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        for chunk in self.chunks.get_mut().drain(..) {
+            // SAFETY: allocated in `Chunk::new` with this exact layout
+            unsafe {
+                alloc::dealloc(
+                    chunk.ptr.as_ptr(),
+                    Layout::from_size_align_unchecked(chunk.size, MAX_ALIGN),
+                )
+            };
+        }
+    }
+}
@@ -0,0 +1,271 @@
+// Two trees for the same expressions
+//
+// `BoxExpr` owns its children: one heap allocation per `Box`, one per
+// variable name and one per argument list, each freed separately when the
+// tree is dropped. `Expr<'a>` borrows its children from an `Arena`: the
+// nodes are `Copy`, have no drop glue, and all go away with one reset.
+
+use crate::error::ExprError;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnOp {
+    Neg,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Or,
+    And,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Func {
+    Min,
+    Max,
+    Abs,
+}
+
+fn truth(v: f64) -> bool {
+    v != 0.0
+}
+
+fn flag(b: bool) -> f64 {
+    if b {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+impl UnOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            UnOp::Neg => "-",
+            UnOp::Not => "!",
+        }
+    }
+
+    fn apply(self, v: f64) -> f64 {
+        match self {
+            UnOp::Neg => -v,
+            UnOp::Not => flag(!truth(v)),
+        }
+    }
+}
+
+impl BinOp {
+    pub fn from_symbol(s: &str) -> Option<BinOp> {
+        Some(match s {
+            "||" => BinOp::Or,
+            "&&" => BinOp::And,
+            "<" => BinOp::Lt,
+            "<=" => BinOp::Le,
+            ">" => BinOp::Gt,
+            ">=" => BinOp::Ge,
+            "==" => BinOp::Eq,
+            "!=" => BinOp::Ne,
+            "+" => BinOp::Add,
+            "-" => BinOp::Sub,
+            "*" => BinOp::Mul,
+            "/" => BinOp::Div,
+            "%" => BinOp::Rem,
+            _ => return None,
+        })
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            BinOp::Or => "||",
+            BinOp::And => "&&",
+            BinOp::Lt => "<",
+            BinOp::Le => "<=",
+            BinOp::Gt => ">",
+            BinOp::Ge => ">=",
+            BinOp::Eq => "==",
+            BinOp::Ne => "!=",
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::Rem => "%",
+        }
+    }
+
+    // `&&` and `||` short-circuit in `eval` and never get here
+    fn apply(self, a: f64, b: f64) -> f64 {
+        match self {
+            BinOp::Or => flag(truth(a) || truth(b)),
+            BinOp::And => flag(truth(a) && truth(b)),
+            BinOp::Lt => flag(a < b),
+            BinOp::Le => flag(a <= b),
+            BinOp::Gt => flag(a > b),
+            BinOp::Ge => flag(a >= b),
+            BinOp::Eq => flag(a == b),
+            BinOp::Ne => flag(a != b),
+            BinOp::Add => a + b,
+            BinOp::Sub => a - b,
+            BinOp::Mul => a * b,
+            BinOp::Div => a / b,
+            BinOp::Rem => a % b,
+        }
+    }
+}
+
+impl Func {
+    pub fn from_name(name: &str) -> Option<Func> {
+        match name {
+            "min" => Some(Func::Min),
+            "max" => Some(Func::Max),
+            "abs" => Some(Func::Abs),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Func::Min => "min",
+            Func::Max => "max",
+            Func::Abs => "abs",
+        }
+    }
+
+    // (minimum, maximum) argument count
+    pub fn arity(self) -> (usize, usize) {
+        match self {
+            Func::Min | Func::Max => (1, usize::MAX),
+            Func::Abs => (1, 1),
+        }
+    }
+
+    // Takes the argument results as they are evaluated, so no Vec is needed
+    fn apply(
+        self,
+        mut args: impl Iterator<Item = Result<f64, ExprError>>,
+    ) -> Result<f64, ExprError> {
+        match self {
+            Func::Min => args.try_fold(f64::INFINITY, |acc, v| Ok(acc.min(v?))),
+            Func::Max => args.try_fold(f64::NEG_INFINITY, |acc, v| Ok(acc.max(v?))),
+            Func::Abs => Ok(args.next().transpose()?.map_or(f64::NAN, f64::abs)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoxExpr {
+    Num(f64),
+    Var(String),
+    Unary(UnOp, Box<BoxExpr>),
+    Binary(BinOp, Box<BoxExpr>, Box<BoxExpr>),
+    Call(Func, Vec<BoxExpr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Expr<'a> {
+    Num(f64),
+    Var(&'a str),
+    Unary(UnOp, &'a Expr<'a>),
+    Binary(BinOp, &'a Expr<'a>, &'a Expr<'a>),
+    Call(Func, &'a [Expr<'a>]),
+}
+
+impl BoxExpr {
+    pub fn eval<F: Fn(&str) -> Option<f64>>(&self, vars: &F) -> Result<f64, ExprError> {
+        Ok(match self {
+            BoxExpr::Num(v) => *v,
+            BoxExpr::Var(name) => {
+                vars(name).ok_or_else(|| ExprError::UnknownVariable(name.clone()))?
+            }
+            BoxExpr::Unary(op, e) => op.apply(e.eval(vars)?),
+            BoxExpr::Binary(BinOp::And, l, r) => flag(truth(l.eval(vars)?) && truth(r.eval(vars)?)),
+            BoxExpr::Binary(BinOp::Or, l, r) => flag(truth(l.eval(vars)?) || truth(r.eval(vars)?)),
+            BoxExpr::Binary(op, l, r) => op.apply(l.eval(vars)?, r.eval(vars)?),
+            BoxExpr::Call(f, args) => f.apply(args.iter().map(|a| a.eval(vars)))?,
+        })
+    }
+
+    pub fn node_count(&self) -> usize {
+        match self {
+            BoxExpr::Num(_) | BoxExpr::Var(_) => 1,
+            BoxExpr::Unary(_, e) => 1 + e.node_count(),
+            BoxExpr::Binary(_, l, r) => 1 + l.node_count() + r.node_count(),
+            BoxExpr::Call(_, args) => 1 + args.iter().map(BoxExpr::node_count).sum::<usize>(),
+        }
+    }
+}
+
+impl Expr<'_> {
+    pub fn eval<F: Fn(&str) -> Option<f64>>(&self, vars: &F) -> Result<f64, ExprError> {
+        Ok(match *self {
+            Expr::Num(v) => v,
+            Expr::Var(name) => {
+                vars(name).ok_or_else(|| ExprError::UnknownVariable(name.to_string()))?
+            }
+            Expr::Unary(op, e) => op.apply(e.eval(vars)?),
+            Expr::Binary(BinOp::And, l, r) => flag(truth(l.eval(vars)?) && truth(r.eval(vars)?)),
+            Expr::Binary(BinOp::Or, l, r) => flag(truth(l.eval(vars)?) || truth(r.eval(vars)?)),
+            Expr::Binary(op, l, r) => op.apply(l.eval(vars)?, r.eval(vars)?),
+            Expr::Call(f, args) => f.apply(args.iter().map(|a| a.eval(vars)))?,
+        })
+    }
+
+    pub fn node_count(&self) -> usize {
+        match self {
+            Expr::Num(_) | Expr::Var(_) => 1,
+            Expr::Unary(_, e) => 1 + e.node_count(),
+            Expr::Binary(_, l, r) => 1 + l.node_count() + r.node_count(),
+            Expr::Call(_, args) => 1 + args.iter().map(Expr::node_count).sum::<usize>(),
+        }
+    }
+}
+
+// Both print fully parenthesised, so equal strings mean equal trees
+impl fmt::Display for BoxExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoxExpr::Num(v) => write!(f, "{}", v),
+            BoxExpr::Var(name) => write!(f, "{}", name),
+            BoxExpr::Unary(op, e) => write!(f, "({}{})", op.symbol(), e),
+            BoxExpr::Binary(op, l, r) => write!(f, "({} {} {})", l, op.symbol(), r),
+            BoxExpr::Call(func, args) => {
+                write!(f, "{}(", func.name())?;
+                for (i, a) in args.iter().enumerate() {
+                    let sep = if i > 0 { ", " } else { "" };
+                    write!(f, "{}{}", sep, a)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Num(v) => write!(f, "{}", v),
+            Expr::Var(name) => write!(f, "{}", name),
+            Expr::Unary(op, e) => write!(f, "({}{})", op.symbol(), e),
+            Expr::Binary(op, l, r) => write!(f, "({} {} {})", l, op.symbol(), r),
+            Expr::Call(func, args) => {
+                write!(f, "{}(", func.name())?;
+                for (i, a) in args.iter().enumerate() {
+                    let sep = if i > 0 { ", " } else { "" };
+                    write!(f, "{}{}", sep, a)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExprError {
+    UnexpectedChar {
+        pos: usize,
+        ch: char,
+    },
+    BadNumber {
+        pos: usize,
+        text: String,
+    },
+    // `found` is None at the end of the input
+    Expected {
+        pos: usize,
+        expected: &'static str,
+        found: Option<String>,
+    },
+    UnknownFunction {
+        pos: usize,
+        name: String,
+    },
+    WrongArity {
+        pos: usize,
+        name: &'static str,
+        expected: &'static str,
+        found: usize,
+    },
+    UnknownVariable(String),
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprError::UnexpectedChar { pos, ch } => {
+                write!(f, "unexpected character {:?} at {}", ch, pos)
+            }
+            ExprError::BadNumber { pos, text } => write!(f, "bad number {:?} at {}", text, pos),
+            ExprError::Expected {
+                pos,
+                expected,
+                found: Some(found),
+            } => write!(f, "expected {} at {}, found {}", expected, pos, found),
+            ExprError::Expected {
+                pos,
+                expected,
+                found: None,
+            } => write!(f, "expected {} at {}, found end of input", expected, pos),
+            ExprError::UnknownFunction { pos, name } => {
+                write!(f, "unknown function {:?} at {}", name, pos)
+            }
+            ExprError::WrongArity {
+                pos,
+                name,
+                expected,
+                found,
+            } => write!(f, "{}() at {} takes {}, got {}", name, pos, expected, found),
+            ExprError::UnknownVariable(name) => write!(f, "unknown variable {:?}", name),
+        }
+    }
+}
+
+impl Error for ExprError {}
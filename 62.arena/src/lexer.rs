@@ -0,0 +1,103 @@
+// Tokens of the rule expression language, produced one at a time
+//
+//   temperature > 30.5 && (humidity < 40 || !door_open)
+//   max(t1, t2, t3) - min(t1, t2, t3) > 4
+
+use crate::error::ExprError;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Token<'src> {
+    Number(f64),
+    Ident(&'src str),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+}
+
+const OPERATORS: [&str; 14] = [
+    "&&", "||", "<=", ">=", "==", "!=", "<", ">", "+", "-", "*", "/", "!", "%",
+];
+
+pub struct Lexer<'src> {
+    src: &'src str,
+    pos: usize,
+    peeked: Option<(usize, Token<'src>)>,
+}
+
+impl<'src> Lexer<'src> {
+    pub fn new(src: &'src str) -> Self {
+        Lexer {
+            src,
+            pos: 0,
+            peeked: None,
+        }
+    }
+
+    // Offset of the peeked token, or of where scanning stopped
+    pub fn position(&self) -> usize {
+        self.peeked.map_or(self.pos, |(at, _)| at)
+    }
+
+    pub fn peek(&mut self) -> Result<Option<Token<'src>>, ExprError> {
+        if self.peeked.is_none() {
+            self.peeked = self.scan()?;
+        }
+        Ok(self.peeked.map(|(_, t)| t))
+    }
+
+    pub fn next_token(&mut self) -> Result<Option<Token<'src>>, ExprError> {
+        self.peek()?;
+        Ok(self.peeked.take().map(|(_, t)| t))
+    }
+
+    fn scan(&mut self) -> Result<Option<(usize, Token<'src>)>, ExprError> {
+        let bytes = self.src.as_bytes();
+        while self.pos < bytes.len() && bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+        let start = self.pos;
+        let Some(&c) = bytes.get(start) else {
+            return Ok(None);
+        };
+        let token = match c {
+            b'(' => Token::LParen,
+            b')' => Token::RParen,
+            b',' => Token::Comma,
+            b'0'..=b'9' | b'.' => {
+                let end = self.scan_while(|b| b.is_ascii_digit() || b == b'.');
+                let text = &self.src[start..end];
+                let value = text.parse().map_err(|_| ExprError::BadNumber {
+                    pos: start,
+                    text: text.to_string(),
+                })?;
+                return Ok(Some((start, Token::Number(value))));
+            }
+            b'a'..=b'z' | b'A'..=b'Z' | b'_' => {
+                let end = self.scan_while(|b| b.is_ascii_alphanumeric() || b == b'_');
+                return Ok(Some((start, Token::Ident(&self.src[start..end]))));
+            }
+            _ => {
+                let rest = &self.src[start..];
+                let op = OPERATORS.iter().find(|op| rest.starts_with(*op)).ok_or(
+                    ExprError::UnexpectedChar {
+                        pos: start,
+                        ch: rest.chars().next().unwrap_or('?'),
+                    },
+                )?;
+                self.pos += op.len();
+                return Ok(Some((start, Token::Op(op))));
+            }
+        };
+        self.pos += 1;
+        Ok(Some((start, token)))
+    }
+
+    fn scan_while(&mut self, keep: impl Fn(u8) -> bool) -> usize {
+        let bytes = self.src.as_bytes();
+        while self.pos < bytes.len() && keep(bytes[self.pos]) {
+            self.pos += 1;
+        }
+        self.pos
+    }
+}
@@ -0,0 +1,23 @@
+// Arena allocation for parser temporaries
+//
+// A parser builds many small nodes that all live exactly as long as the
+// tree. Allocating each one with `Box` costs a trip to the system
+// allocator per node and another per node when the tree is dropped. An
+// arena bump-allocates them from large chunks and frees them all at once.
+//
+// - `arena`: the bump allocator, with typed `alloc`, `reset` and stats
+// - `lexer`, `parser`: a small rule-expression language
+//   (`temperature > 30 && humidity < 40`); the parser builds nodes through
+//   a `Builder`, so the same code produces either tree
+// - `ast`: `BoxExpr` (Box per node) and `Expr<'a>` (arena references)
+
+pub mod arena;
+pub mod ast;
+pub mod error;
+pub mod lexer;
+pub mod parser;
+
+pub use arena::{Arena, ArenaStats};
+pub use ast::{BoxExpr, Expr};
+pub use error::ExprError;
+pub use parser::{parse, parse_boxed, parse_in, ArenaBuilder, BoxBuilder, Builder};
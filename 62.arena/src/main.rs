@@ -0,0 +1,193 @@
+use arena::{parse_boxed, parse_in, Arena};
+use memprofile::{stats, Profiler};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+#[global_allocator]
+static GLOBAL: Profiler = Profiler;
+
+const RULES: [&str; 6] = [
+    "temperature > 30.5 && humidity < 40",
+    "max(t1, t2, t3) - min(t1, t2, t3) > 4",
+    "!door_open || temperature < 5",
+    "abs(temperature - setpoint) > 2 * tolerance",
+    "(battery < 20 || rssi < -90) && uptime % 3600 < 60",
+    "-temperature + 2 * (humidity - 50) / 3 >= min(t1, 0)",
+];
+
+fn time<T>(reps: u32, mut f: impl FnMut() -> T) -> Duration {
+    (0..reps)
+        .map(|_| {
+            let started = Instant::now();
+            black_box(f());
+            started.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn reading(name: &str) -> Option<f64> {
+    Some(match name {
+        "temperature" => 31.0,
+        "humidity" => 35.0,
+        "door_open" => 0.0,
+        "t1" => 20.5,
+        "t2" => 25.0,
+        "t3" => 22.0,
+        "setpoint" => 28.0,
+        "tolerance" => 1.0,
+        "battery" => 80.0,
+        "rssi" => -95.0,
+        "uptime" => 7230.0,
+        _ => return None,
+    })
+}
+
+fn main() {
+    println!("=== Arena Allocation Examples ===\n");
+
+    // 1. The arena itself
+    println!("1. Bump allocation:");
+    let mut arena = Arena::with_chunk_size(64);
+    let fresh = arena.stats();
+    let a = arena.alloc(1u8);
+    let b = arena.alloc(2u64);
+    let name = arena.alloc_str("temperature");
+    let ids = arena.alloc_slice_copy(&[1u32, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+    let used = arena.stats();
+    println!(
+        "   {} allocations, {} bytes used of {} in {} chunk(s)",
+        used.allocations, used.used_bytes, used.capacity_bytes, used.chunks
+    );
+    println!(
+        "   before the first allocation: {} chunks; values read back: {} {} {:?} {:?}",
+        fresh.chunks,
+        a,
+        b,
+        name,
+        &ids[..3]
+    );
+    println!(
+        "   the u64 after the u8 starts {} bytes later",
+        b as *const u64 as usize - a as *const u8 as usize
+    );
+    arena.reset();
+    let after_reset = arena.stats();
+    println!(
+        "   after reset: {} bytes used of {} in {} chunk(s)",
+        after_reset.used_bytes, after_reset.capacity_bytes, after_reset.chunks
+    );
+
+    // 2. One parser, two trees
+    println!("\n2. Box tree and arena tree:");
+    let arena = Arena::new();
+    for rule in RULES {
+        parse_in(&arena, rule).expect("rule parses");
+    }
+    let boxed = parse_boxed(RULES[0]).expect("rule parses");
+    println!("   {}", RULES[0]);
+    println!("   -> {} ({} nodes)", boxed, boxed.node_count());
+    println!(
+        "   {} rules: {} bytes in the arena, {} allocations",
+        RULES.len(),
+        arena.stats().used_bytes,
+        arena.stats().allocations
+    );
+    println!(
+        "   -> {} in the arena",
+        parse_in(&arena, RULES[0]).expect("rule parses")
+    );
+
+    // 3. Evaluating against readings
+    println!("\n3. Evaluating the rules:");
+    for rule in RULES {
+        let in_arena = parse_in(&arena, rule).expect("rule parses").eval(&reading);
+        println!("   {:<54} = {:?}", rule, in_arena.as_ref().ok());
+    }
+    let missing = parse_in(&arena, "pressure > 1000")
+        .expect("rule parses")
+        .eval(&reading);
+    if let Err(e) = missing {
+        println!("   {:<54}   {}", "pressure > 1000", e);
+    }
+
+    // 4. Allocations per parse
+    println!("\n4. System allocator calls per parse:");
+    let rule = RULES[4];
+    let start = stats();
+    let tree = parse_boxed(rule).expect("rule parses");
+    let built = stats();
+    drop(tree);
+    let dropped = stats();
+    let mut arena = Arena::new();
+    // The first parse grows the arena; after a reset it is warm
+    parse_in(&arena, rule).expect("rule parses");
+    arena.reset();
+    let warm = stats();
+    let nodes = parse_in(&arena, rule).expect("rule parses").node_count();
+    arena.reset();
+    let reused = stats();
+    println!(
+        "   Box:   {} allocs to build, {} frees to drop ({} nodes)",
+        built.allocs - start.allocs,
+        dropped.deallocs - built.deallocs,
+        nodes
+    );
+    println!(
+        "   arena: {} allocs, {} frees after a reset",
+        reused.allocs - warm.allocs,
+        reused.deallocs - warm.deallocs
+    );
+
+    // 5. Timing
+    println!("\n5. Parsing all {} rules, best of 200:", RULES.len());
+    let boxed = time(200, || {
+        RULES
+            .iter()
+            .map(|r| parse_boxed(r).expect("rule parses").node_count())
+            .sum::<usize>()
+    });
+    let fresh = time(200, || {
+        let arena = Arena::new();
+        RULES
+            .iter()
+            .map(|r| parse_in(&arena, r).expect("rule parses").node_count())
+            .sum::<usize>()
+    });
+    let mut arena = Arena::new();
+    let reset = time(200, || {
+        arena.reset();
+        RULES
+            .iter()
+            .map(|r| parse_in(&arena, r).expect("rule parses").node_count())
+            .sum::<usize>()
+    });
+    println!("   Box per node (incl. drop): {:>9.2?}", boxed);
+    println!("   new arena each time:       {:>9.2?}", fresh);
+    println!("   one arena, reset:          {:>9.2?}", reset);
+    println!(
+        "   speedup of reset arena over Box: {:.1}x",
+        boxed.as_secs_f64() / reset.as_secs_f64().max(1e-9)
+    );
+
+    // 6. Errors
+    println!("\n6. Parse errors:");
+    let arena = Arena::new();
+    for bad in [
+        "temperature >",
+        "max(t1, t2",
+        "abs(t1, t2)",
+        "sqrt(t1)",
+        "t1 # 2",
+        "(t1 + 2",
+        "1.2.3 > t1",
+        "t1 t2",
+    ] {
+        match parse_in(&arena, bad) {
+            Err(e) => println!("   {:<14} {}", bad, e),
+            Ok(tree) => println!("   {:<14} parsed as {}", bad, tree),
+        }
+    }
+
+    println!("\n=== End of Arena Allocation Examples ===");
+}
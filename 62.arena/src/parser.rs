@@ -0,0 +1,264 @@
+// One recursive-descent parser, two kinds of tree
+//
+// The parser never builds nodes itself; it calls a `Builder`. That is the
+// whole refactoring needed to move from `Box` to an arena: `BoxBuilder`
+// and `ArenaBuilder` differ only in where a node's children are stored.
+//
+//   or      := and ("||" and)*
+//   and     := compare ("&&" compare)*
+//   compare := sum (("<" | "<=" | ">" | ">=" | "==" | "!=") sum)?
+//   sum     := product (("+" | "-") product)*
+//   product := unary (("*" | "/" | "%") unary)*
+//   unary   := ("-" | "!") unary | primary
+//   primary := number | name | name "(" or ("," or)* ")" | "(" or ")"
+
+use crate::arena::Arena;
+use crate::ast::{BinOp, BoxExpr, Expr, Func, UnOp};
+use crate::error::ExprError;
+use crate::lexer::{Lexer, Token};
+use std::vec::Drain;
+
+pub trait Builder {
+    type Node;
+    fn number(&mut self, value: f64) -> Self::Node;
+    fn var(&mut self, name: &str) -> Self::Node;
+    fn unary(&mut self, op: UnOp, operand: Self::Node) -> Self::Node;
+    fn binary(&mut self, op: BinOp, left: Self::Node, right: Self::Node) -> Self::Node;
+    fn call(&mut self, func: Func, args: Drain<'_, Self::Node>) -> Self::Node;
+}
+
+pub struct BoxBuilder;
+
+impl Builder for BoxBuilder {
+    type Node = BoxExpr;
+
+    fn number(&mut self, value: f64) -> BoxExpr {
+        BoxExpr::Num(value)
+    }
+
+    fn var(&mut self, name: &str) -> BoxExpr {
+        BoxExpr::Var(name.to_string())
+    }
+
+    fn unary(&mut self, op: UnOp, operand: BoxExpr) -> BoxExpr {
+        BoxExpr::Unary(op, Box::new(operand))
+    }
+
+    fn binary(&mut self, op: BinOp, left: BoxExpr, right: BoxExpr) -> BoxExpr {
+        BoxExpr::Binary(op, Box::new(left), Box::new(right))
+    }
+
+    fn call(&mut self, func: Func, args: Drain<'_, BoxExpr>) -> BoxExpr {
+        BoxExpr::Call(func, args.collect())
+    }
+}
+
+pub struct ArenaBuilder<'a> {
+    pub arena: &'a Arena,
+}
+
+impl<'a> Builder for ArenaBuilder<'a> {
+    type Node = Expr<'a>;
+
+    fn number(&mut self, value: f64) -> Expr<'a> {
+        Expr::Num(value)
+    }
+
+    // Copied, so the tree does not borrow the input line
+    fn var(&mut self, name: &str) -> Expr<'a> {
+        Expr::Var(self.arena.alloc_str(name))
+    }
+
+    fn unary(&mut self, op: UnOp, operand: Expr<'a>) -> Expr<'a> {
+        Expr::Unary(op, self.arena.alloc(operand))
+    }
+
+    fn binary(&mut self, op: BinOp, left: Expr<'a>, right: Expr<'a>) -> Expr<'a> {
+        Expr::Binary(op, self.arena.alloc(left), self.arena.alloc(right))
+    }
+
+    fn call(&mut self, func: Func, args: Drain<'_, Expr<'a>>) -> Expr<'a> {
+        Expr::Call(func, self.arena.alloc_slice_iter(args))
+    }
+}
+
+struct Parser<'src, 'b, B: Builder> {
+    lexer: Lexer<'src>,
+    builder: &'b mut B,
+    // Arguments of the calls being parsed, innermost last; only grows
+    // when the input has calls
+    args: Vec<B::Node>,
+}
+
+pub fn parse<B: Builder>(src: &str, builder: &mut B) -> Result<B::Node, ExprError> {
+    let mut p = Parser {
+        lexer: Lexer::new(src),
+        builder,
+        args: Vec::new(),
+    };
+    let root = p.or()?;
+    match p.lexer.peek()? {
+        None => Ok(root),
+        Some(t) => Err(p.expected("an operator or end of input", Some(t))),
+    }
+}
+
+pub fn parse_boxed(src: &str) -> Result<BoxExpr, ExprError> {
+    parse(src, &mut BoxBuilder)
+}
+
+pub fn parse_in<'a>(arena: &'a Arena, src: &str) -> Result<Expr<'a>, ExprError> {
+    parse(src, &mut ArenaBuilder { arena })
+}
+
+fn describe(t: Token<'_>) -> String {
+    match t {
+        Token::Number(v) => format!("number {}", v),
+        Token::Ident(name) => format!("name {:?}", name),
+        Token::Op(op) => format!("{:?}", op),
+        Token::LParen => "\"(\"".to_string(),
+        Token::RParen => "\")\"".to_string(),
+        Token::Comma => "\",\"".to_string(),
+    }
+}
+
+impl<B: Builder> Parser<'_, '_, B> {
+    fn expected(&self, expected: &'static str, found: Option<Token<'_>>) -> ExprError {
+        ExprError::Expected {
+            pos: self.lexer.position(),
+            expected,
+            found: found.map(describe),
+        }
+    }
+
+    // Consumes the next token if it is one of `ops`
+    fn operator(&mut self, ops: &[&str]) -> Result<Option<BinOp>, ExprError> {
+        match self.lexer.peek()? {
+            Some(Token::Op(op)) if ops.contains(&op) => {
+                self.lexer.next_token()?;
+                Ok(BinOp::from_symbol(op))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn left_assoc(
+        &mut self,
+        ops: &[&str],
+        operand: fn(&mut Self) -> Result<B::Node, ExprError>,
+    ) -> Result<B::Node, ExprError> {
+        let mut left = operand(self)?;
+        while let Some(op) = self.operator(ops)? {
+            let right = operand(self)?;
+            left = self.builder.binary(op, left, right);
+        }
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<B::Node, ExprError> {
+        self.left_assoc(&["||"], Self::and)
+    }
+
+    fn and(&mut self) -> Result<B::Node, ExprError> {
+        self.left_assoc(&["&&"], Self::compare)
+    }
+
+    fn compare(&mut self) -> Result<B::Node, ExprError> {
+        let left = self.sum()?;
+        match self.operator(&["<", "<=", ">", ">=", "==", "!="])? {
+            Some(op) => {
+                let right = self.sum()?;
+                Ok(self.builder.binary(op, left, right))
+            }
+            None => Ok(left),
+        }
+    }
+
+    fn sum(&mut self) -> Result<B::Node, ExprError> {
+        self.left_assoc(&["+", "-"], Self::product)
+    }
+
+    fn product(&mut self) -> Result<B::Node, ExprError> {
+        self.left_assoc(&["*", "/", "%"], Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<B::Node, ExprError> {
+        let op = match self.lexer.peek()? {
+            Some(Token::Op("-")) => UnOp::Neg,
+            Some(Token::Op("!")) => UnOp::Not,
+            _ => return self.primary(),
+        };
+        self.lexer.next_token()?;
+        let operand = self.unary()?;
+        Ok(self.builder.unary(op, operand))
+    }
+
+    fn primary(&mut self) -> Result<B::Node, ExprError> {
+        self.lexer.peek()?;
+        let pos = self.lexer.position();
+        match self.lexer.next_token()? {
+            Some(Token::Number(v)) => Ok(self.builder.number(v)),
+            Some(Token::LParen) => {
+                let inner = self.or()?;
+                self.expect(Token::RParen, "\")\"")?;
+                Ok(inner)
+            }
+            Some(Token::Ident(name)) if self.lexer.peek()? == Some(Token::LParen) => {
+                let func = Func::from_name(name).ok_or_else(|| ExprError::UnknownFunction {
+                    pos,
+                    name: name.to_string(),
+                })?;
+                self.lexer.next_token()?;
+                self.call(func, pos)
+            }
+            Some(Token::Ident(name)) => Ok(self.builder.var(name)),
+            other => Err(ExprError::Expected {
+                pos,
+                expected: "a number, name or \"(\"",
+                found: other.map(describe),
+            }),
+        }
+    }
+
+    // After `name(`: arguments up to the closing parenthesis
+    fn call(&mut self, func: Func, pos: usize) -> Result<B::Node, ExprError> {
+        let start = self.args.len();
+        loop {
+            let arg = self.or()?;
+            self.args.push(arg);
+            match self.lexer.peek()? {
+                Some(Token::Comma) => {}
+                Some(Token::RParen) => break,
+                other => return Err(self.expected("\",\" or \")\"", other)),
+            }
+            self.lexer.next_token()?;
+        }
+        self.lexer.next_token()?;
+        let (min, max) = func.arity();
+        let count = self.args.len() - start;
+        if count < min || count > max {
+            self.args.truncate(start);
+            return Err(ExprError::WrongArity {
+                pos,
+                name: func.name(),
+                expected: if max == 1 {
+                    "one argument"
+                } else {
+                    "at least one argument"
+                },
+                found: count,
+            });
+        }
+        Ok(self.builder.call(func, self.args.drain(start..)))
+    }
+
+    fn expect(&mut self, token: Token<'_>, name: &'static str) -> Result<(), ExprError> {
+        match self.lexer.peek()? {
+            Some(t) if t == token => {
+                self.lexer.next_token()?;
+                Ok(())
+            }
+            other => Err(self.expected(name, other)),
+        }
+    }
+}
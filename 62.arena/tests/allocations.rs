@@ -0,0 +1,85 @@
+// System allocator calls per parse, counted by the memprofile allocator.
+// The counters are process-wide, so this binary has its own `main`
+// instead of libtest, whose threads would allocate while it counts.
+
+use arena::{parse_boxed, parse_in, Arena};
+use memprofile::{stats, Profiler};
+
+#[global_allocator]
+static GLOBAL: Profiler = Profiler;
+
+const RULE: &str = "(battery < 20 || rssi < -90) && uptime % 3600 < 60";
+
+fn a_box_tree_allocates_and_frees_per_node() {
+    let start = stats();
+    let tree = parse_boxed(RULE).unwrap();
+    let built = stats();
+    let nodes = tree.node_count();
+    drop(tree);
+    let dropped = stats();
+    let allocs = built.allocs - start.allocs;
+    assert!(
+        allocs >= nodes,
+        "{} allocations for {} nodes",
+        allocs,
+        nodes
+    );
+    assert_eq!(dropped.deallocs - built.deallocs, allocs);
+    assert_eq!(dropped.live_bytes, start.live_bytes);
+}
+
+fn a_warm_arena_parses_without_the_allocator() {
+    let mut arena = Arena::new();
+    // The first parse grows the arena
+    parse_in(&arena, RULE).unwrap();
+    arena.reset();
+    let warm = stats();
+    for _ in 0..10 {
+        assert_eq!(parse_in(&arena, RULE).unwrap().node_count(), 14);
+        arena.reset();
+    }
+    let after = stats();
+    assert_eq!(after.allocs, warm.allocs);
+    assert_eq!(after.deallocs, warm.deallocs);
+    assert_eq!(after.reallocs, warm.reallocs);
+}
+
+fn dropping_an_arena_frees_each_chunk_once() {
+    let start = stats();
+    let mut arena = Arena::with_chunk_size(64);
+    for i in 0..500u64 {
+        arena.alloc(i);
+    }
+    arena.reset();
+    let chunks = arena.stats().chunks;
+    let reserved = arena.stats().capacity_bytes;
+    let grown = stats();
+    assert!(chunks > 1);
+    // The chunk list is a Vec that grew too; only chunk bytes stay live
+    assert!(grown.live_bytes - start.live_bytes >= reserved);
+    drop(arena);
+    let dropped = stats();
+    assert_eq!(dropped.deallocs - grown.deallocs, chunks + 1);
+    assert_eq!(dropped.live_bytes, start.live_bytes);
+}
+
+fn main() {
+    let checks: [(&str, fn()); 3] = [
+        (
+            "a_box_tree_allocates_and_frees_per_node",
+            a_box_tree_allocates_and_frees_per_node,
+        ),
+        (
+            "a_warm_arena_parses_without_the_allocator",
+            a_warm_arena_parses_without_the_allocator,
+        ),
+        (
+            "dropping_an_arena_frees_each_chunk_once",
+            dropping_an_arena_frees_each_chunk_once,
+        ),
+    ];
+    for (name, check) in checks {
+        check();
+        println!("test {} ... ok", name);
+    }
+}
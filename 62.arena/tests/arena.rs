@@ -0,0 +1,262 @@
+// `cargo +nightly miri test --test arena` runs these under Miri, which
+// also fails the run if dropping an arena leaks a chunk
+use arena::arena::{DEFAULT_CHUNK, MAX_ALIGN};
+use arena::Arena;
+use std::mem::align_of;
+
+// Miri interprets every instruction, so it gets fewer steps
+const STEPS: usize = if cfg!(miri) { 300 } else { 20_000 };
+
+// xorshift32: the same allocations on every run
+struct Rng(u32);
+
+impl Rng {
+    fn below(&mut self, n: u32) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 % n
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(align(16))]
+struct Aligned16([u8; 3]);
+
+// One reference of each shape the arena can hand out
+#[derive(Debug)]
+enum Held<'a> {
+    Byte(&'a u8),
+    Short(&'a u16),
+    Long(&'a u64),
+    Wide(&'a u128),
+    Padded(&'a Aligned16),
+    Bytes(&'a [u8]),
+    Floats(&'a [f64]),
+    Text(&'a str),
+}
+
+fn address<T: ?Sized>(value: &T) -> usize {
+    value as *const T as *const u8 as usize
+}
+
+#[test]
+fn a_new_arena_reserves_nothing() {
+    let arena = Arena::new();
+    let stats = arena.stats();
+    assert_eq!(stats.chunks, 0);
+    assert_eq!(stats.capacity_bytes, 0);
+    assert_eq!(stats.used_bytes, 0);
+    assert_eq!(stats.allocations, 0);
+
+    arena.alloc(1u8);
+    assert_eq!(arena.stats().chunks, 1);
+    assert_eq!(arena.stats().capacity_bytes, DEFAULT_CHUNK);
+}
+
+#[test]
+fn each_value_is_aligned_and_padding_counts_as_used() {
+    let arena = Arena::with_chunk_size(64);
+    let a = arena.alloc(1u8);
+    let b = arena.alloc(2u64);
+    let c = arena.alloc(Aligned16([3, 4, 5]));
+    assert_eq!(address(b) % 8, 0);
+    assert_eq!(address(c) % 16, 0);
+    // 1 byte, 7 of padding, 8, then up to 16 and 16 more
+    assert_eq!(arena.stats().used_bytes, 32);
+    assert_eq!((*a, *b, *c), (1, 2, Aligned16([3, 4, 5])));
+    assert_eq!(address(b) - address(a), 8);
+    assert_eq!(address(c) - address(a), 16);
+}
+
+#[test]
+fn random_mixes_stay_aligned_and_intact() {
+    let arena = Arena::with_chunk_size(128);
+    let mut rng = Rng(0x9E37_79B9);
+    let mut held = Vec::with_capacity(STEPS);
+    for step in 0..STEPS as u32 {
+        let value = match rng.below(8) {
+            0 => Held::Byte(arena.alloc(step as u8)),
+            1 => Held::Short(arena.alloc(step as u16)),
+            2 => Held::Long(arena.alloc(step as u64 * 3)),
+            3 => Held::Wide(arena.alloc(step as u128 * 5)),
+            4 => Held::Padded(arena.alloc(Aligned16([step as u8; 3]))),
+            5 => {
+                let len = rng.below(40) as usize;
+                Held::Bytes(arena.alloc_slice_copy(&vec![step as u8; len]))
+            }
+            6 => {
+                let len = rng.below(12) as usize;
+                Held::Floats(arena.alloc_slice_iter((0..len).map(|i| step as f64 + i as f64)))
+            }
+            _ => Held::Text(arena.alloc_str(&format!("sensor-{}", step))),
+        };
+        held.push(value);
+    }
+
+    // Checked only at the end, so a later allocation overwriting an
+    // earlier value would show
+    for (step, value) in held.iter().enumerate() {
+        let step = step as u32;
+        match *value {
+            Held::Byte(v) => assert_eq!(*v, step as u8),
+            Held::Short(v) => {
+                assert_eq!(address(v) % align_of::<u16>(), 0);
+                assert_eq!(*v, step as u16);
+            }
+            Held::Long(v) => {
+                assert_eq!(address(v) % align_of::<u64>(), 0);
+                assert_eq!(*v, step as u64 * 3);
+            }
+            Held::Wide(v) => {
+                assert_eq!(address(v) % align_of::<u128>(), 0);
+                assert_eq!(*v, step as u128 * 5);
+            }
+            Held::Padded(v) => {
+                assert_eq!(address(v) % 16, 0);
+                assert_eq!(*v, Aligned16([step as u8; 3]));
+            }
+            Held::Bytes(v) => assert!(v.iter().all(|&b| b == step as u8)),
+            Held::Floats(v) => {
+                assert_eq!(address(v) % align_of::<f64>(), 0);
+                for (i, &f) in v.iter().enumerate() {
+                    assert_eq!(f, step as f64 + i as f64);
+                }
+            }
+            Held::Text(v) => assert_eq!(v, format!("sensor-{}", step)),
+        }
+    }
+    assert_eq!(arena.stats().allocations, STEPS);
+}
+
+#[test]
+fn chunks_double_and_an_oversized_value_gets_its_own() {
+    let arena = Arena::with_chunk_size(64);
+    let first = arena.alloc_slice_copy(&[1u8; 60]);
+    assert_eq!(arena.stats().chunks, 1);
+    // 60 + 8 does not fit in 64: the next chunk is twice the size
+    let second = arena.alloc(7u64);
+    assert_eq!(arena.stats().chunks, 2);
+    assert_eq!(arena.stats().capacity_bytes, 64 + 128);
+    // Bigger than the next doubling: sized to fit, rounded to MAX_ALIGN
+    let big = arena.alloc_slice_copy(&[9u8; 1000]);
+    assert_eq!(arena.stats().chunks, 3);
+    assert_eq!(arena.stats().capacity_bytes, 64 + 128 + 1008);
+    assert_eq!(1008 % MAX_ALIGN, 0);
+    // And the doubling continues from the last chunk
+    arena.alloc_slice_copy(&[0u8; 1000]);
+    assert_eq!(arena.stats().capacity_bytes, 64 + 128 + 1008 + 2016);
+
+    // Moving on to a new chunk leaves earlier values where they were
+    assert!(first.iter().all(|&b| b == 1));
+    assert_eq!(*second, 7);
+    assert!(big.iter().all(|&b| b == 9));
+}
+
+#[test]
+fn reset_rewinds_into_the_same_chunks() {
+    let mut arena = Arena::with_chunk_size(64);
+    let before: Vec<usize> = (0..40u64).map(|i| address(arena.alloc(i))).collect();
+    let grown = arena.stats();
+    assert_eq!(grown.chunks, 3);
+
+    arena.reset();
+    let rewound = arena.stats();
+    assert_eq!(rewound.chunks, grown.chunks);
+    assert_eq!(rewound.capacity_bytes, grown.capacity_bytes);
+    assert_eq!(rewound.used_bytes, 0);
+    assert_eq!(rewound.allocations, 0);
+    assert_eq!(rewound.resets, 1);
+
+    // The same sequence lands on the same addresses, without a new chunk
+    let after: Vec<usize> = (0..40u64).map(|i| address(arena.alloc(i))).collect();
+    assert_eq!(after, before);
+    assert_eq!(arena.stats().capacity_bytes, grown.capacity_bytes);
+    assert_eq!(arena.stats().used_bytes, grown.used_bytes);
+}
+
+#[test]
+fn after_a_reset_a_large_value_skips_to_a_chunk_that_fits() {
+    let mut arena = Arena::with_chunk_size(64);
+    arena.alloc_slice_copy(&[0u8; 64]);
+    arena.alloc_slice_copy(&[0u8; 128]);
+    arena.alloc_slice_copy(&[0u8; 256]);
+    let grown = arena.stats();
+    assert_eq!(grown.capacity_bytes, 64 + 128 + 256);
+
+    arena.reset();
+    // Too big for the first two chunks, but the third one fits it
+    let values = arena.alloc_slice_copy(&[5u8; 200]);
+    assert_eq!(arena.stats().chunks, grown.chunks);
+    assert_eq!(arena.stats().capacity_bytes, grown.capacity_bytes);
+    assert!(values.iter().all(|&b| b == 5));
+}
+
+#[test]
+fn zero_sized_values_take_no_space() {
+    let arena = Arena::with_chunk_size(64);
+    let unit = arena.alloc(());
+    let empty: &[u64] = arena.alloc_slice_copy(&[]);
+    let none = arena.alloc_str("");
+    assert_eq!(*unit, ());
+    assert!(empty.is_empty());
+    assert_eq!(none, "");
+    assert_eq!(address(empty) % align_of::<u64>(), 0);
+    let stats = arena.stats();
+    assert_eq!(stats.chunks, 0);
+    assert_eq!(stats.used_bytes, 0);
+    assert_eq!(stats.allocations, 3);
+}
+
+// Reports a length it doesn't have
+struct Liar {
+    left: usize,
+    claims: usize,
+}
+
+impl Iterator for Liar {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        self.left = self.left.checked_sub(1)?;
+        Some(self.left as u32)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.claims, Some(self.claims))
+    }
+}
+
+impl ExactSizeIterator for Liar {}
+
+#[test]
+fn an_iterator_longer_than_its_len_is_cut_at_len() {
+    let arena = Arena::new();
+    let taken = arena.alloc_slice_iter(Liar {
+        left: 10,
+        claims: 3,
+    });
+    assert_eq!(taken, [9, 8, 7]);
+}
+
+#[test]
+#[should_panic(expected = "iterator shorter than its len()")]
+fn an_iterator_shorter_than_its_len_panics() {
+    let arena = Arena::new();
+    arena.alloc_slice_iter(Liar { left: 2, claims: 5 });
+}
+
+#[test]
+fn dropping_an_arena_with_many_chunks_frees_them() {
+    // Without Miri this only has to not crash; Miri reports a leaked or
+    // doubly freed chunk
+    for round in 0..if cfg!(miri) { 3 } else { 50 } {
+        let mut arena = Arena::with_chunk_size(16);
+        for i in 0..200u32 {
+            arena.alloc(i + round);
+        }
+        arena.reset();
+        arena.alloc_slice_copy(&[round as u8; 500]);
+        assert!(arena.stats().chunks > 1);
+    }
+}
@@ -0,0 +1,115 @@
+// The two builders against each other: the same rules must give the same
+// tree, the same value and the same error, whichever builder ran.
+// `cargo +nightly miri test --test parse` checks the arena side under Miri.
+use arena::{parse_boxed, parse_in, Arena, Expr, ExprError};
+use std::mem::needs_drop;
+
+const RULES: [&str; 6] = [
+    "temperature > 30.5 && humidity < 40",
+    "max(t1, t2, t3) - min(t1, t2, t3) > 4",
+    "!door_open || temperature < 5",
+    "abs(temperature - setpoint) > 2 * tolerance",
+    "(battery < 20 || rssi < -90) && uptime % 3600 < 60",
+    "-temperature + 2 * (humidity - 50) / 3 >= min(t1, 0)",
+];
+
+fn reading(name: &str) -> Option<f64> {
+    Some(match name {
+        "temperature" => 31.0,
+        "humidity" => 35.0,
+        "door_open" => 0.0,
+        "t1" => 20.5,
+        "t2" => 25.0,
+        "t3" => 22.0,
+        "setpoint" => 28.0,
+        "tolerance" => 1.0,
+        "battery" => 80.0,
+        "rssi" => -95.0,
+        "uptime" => 7230.0,
+        _ => return None,
+    })
+}
+
+#[test]
+fn both_builders_build_the_same_tree() {
+    let arena = Arena::new();
+    for rule in RULES {
+        let boxed = parse_boxed(rule).unwrap();
+        let in_arena = parse_in(&arena, rule).unwrap();
+        assert_eq!(boxed.to_string(), in_arena.to_string(), "{}", rule);
+        assert_eq!(boxed.node_count(), in_arena.node_count(), "{}", rule);
+    }
+    assert_eq!(parse_boxed(RULES[4]).unwrap().node_count(), 14);
+}
+
+#[test]
+fn both_trees_evaluate_alike() {
+    let arena = Arena::new();
+    let expected = [1.0, 1.0, 1.0, 1.0, 1.0, 0.0];
+    for (rule, want) in RULES.into_iter().zip(expected) {
+        let boxed = parse_boxed(rule).unwrap().eval(&reading);
+        let in_arena = parse_in(&arena, rule).unwrap().eval(&reading);
+        assert_eq!(boxed, in_arena, "{}", rule);
+        assert_eq!(in_arena, Ok(want), "{}", rule);
+    }
+}
+
+#[test]
+fn an_unknown_variable_is_reported() {
+    let arena = Arena::new();
+    let missing = parse_in(&arena, "pressure > 1000").unwrap().eval(&reading);
+    assert_eq!(
+        missing,
+        Err(ExprError::UnknownVariable("pressure".to_string()))
+    );
+    assert_eq!(
+        parse_boxed("pressure > 1000").unwrap().eval(&reading),
+        missing
+    );
+}
+
+#[test]
+fn both_builders_report_the_same_parse_errors() {
+    let arena = Arena::new();
+    for bad in [
+        "temperature >",
+        "max(t1, t2",
+        "abs(t1, t2)",
+        "sqrt(t1)",
+        "t1 # 2",
+        "(t1 + 2",
+        "1.2.3 > t1",
+        "t1 t2",
+        "",
+    ] {
+        let boxed = parse_boxed(bad).map(|_| ());
+        let in_arena = parse_in(&arena, bad).map(|_| ());
+        assert!(in_arena.is_err(), "{:?} parsed", bad);
+        assert_eq!(boxed, in_arena, "{:?}", bad);
+    }
+}
+
+#[test]
+fn arena_trees_are_plain_data() {
+    assert!(!needs_drop::<Expr>());
+    let arena = Arena::new();
+    let tree = parse_in(&arena, RULES[1]).unwrap();
+    // A copy is the same tree, pointing into the same arena
+    let copy = tree;
+    assert_eq!(copy.to_string(), tree.to_string());
+    assert_eq!(copy.eval(&reading), Ok(1.0));
+}
+
+#[test]
+fn a_reset_arena_parses_the_next_rule_set() {
+    let mut arena = Arena::new();
+    let mut used = Vec::new();
+    for _ in 0..3 {
+        for rule in RULES {
+            parse_in(&arena, rule).unwrap();
+        }
+        used.push((arena.stats().used_bytes, arena.stats().capacity_bytes));
+        arena.reset();
+    }
+    assert!(used.windows(2).all(|w| w[0] == w[1]), "{:?}", used);
+}
@@ -0,0 +1,77 @@
+// InlineVec<u8, 32> vs Vec<u8> for frames of typical packet sizes
+//
+//   cargo bench              # every group
+//   cargo bench -- build     # one group
+//
+// `build` creates a frame, fills it and drops it, which is where the
+// inline buffer saves an allocation and a free. 48 and 64 bytes do not fit
+// and show the cost of spilling. `read` sums frames built beforehand, so
+// only access through the inline/heap branch is measured.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use inline_vec::InlineVec;
+use std::hint::black_box;
+
+const SIZES: [usize; 6] = [4, 8, 16, 32, 48, 64];
+const BATCH: usize = 1000;
+
+fn build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build");
+    for size in SIZES {
+        group.throughput(Throughput::Elements(BATCH as u64));
+        group.bench_with_input(BenchmarkId::new("vec", size), &size, |b, &n| {
+            b.iter(|| {
+                for _ in 0..BATCH {
+                    let mut frame = Vec::new();
+                    for i in 0..black_box(n) {
+                        frame.push(i as u8);
+                    }
+                    black_box(&frame);
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("inline", size), &size, |b, &n| {
+            b.iter(|| {
+                for _ in 0..BATCH {
+                    let mut frame: InlineVec<u8, 32> = InlineVec::new();
+                    for i in 0..black_box(n) {
+                        frame.push(i as u8);
+                    }
+                    black_box(&frame);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn read(c: &mut Criterion) {
+    let mut group = c.benchmark_group("read");
+    for size in [8, 64] {
+        let vecs: Vec<Vec<u8>> = (0..BATCH).map(|_| vec![1; size]).collect();
+        let inlines: Vec<InlineVec<u8, 32>> = (0..BATCH)
+            .map(|_| std::iter::repeat_n(1, size).collect())
+            .collect();
+        group.throughput(Throughput::Elements(BATCH as u64));
+        group.bench_with_input(BenchmarkId::new("vec", size), &vecs, |b, frames| {
+            b.iter(|| {
+                frames
+                    .iter()
+                    .map(|f| f.iter().map(|&x| x as u32).sum::<u32>())
+                    .sum::<u32>()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("inline", size), &inlines, |b, frames| {
+            b.iter(|| {
+                frames
+                    .iter()
+                    .map(|f| f.iter().map(|&x| x as u32).sum::<u32>())
+                    .sum::<u32>()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, build, read);
+criterion_main!(benches);
@@ -0,0 +1,416 @@
+// A vector that keeps up to N elements inside itself
+//
+//   InlineVec<u8, 8>   len 3   [a b c . . . . .]          no allocation
+//   InlineVec<u8, 8>   len 12  Heap(Vec [a b c ... l])    spilled
+//
+// The inline buffer is `[MaybeUninit<T>; N]`: only the first `len` slots
+// are initialised, and every method that changes `len` must keep that
+// true, including when a `T`'s `Drop` or `Clone` panics. On the first
+// push past N the elements are moved into a `Vec` and the vector stays on
+// the heap from then on, like `smallvec::SmallVec`.
+
+use std::fmt;
+use std::iter::FusedIterator;
+use std::mem::{self, MaybeUninit};
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+use std::slice;
+
+struct Inline<T, const N: usize> {
+    len: usize,
+    buf: [MaybeUninit<T>; N],
+}
+
+impl<T, const N: usize> Inline<T, N> {
+    const fn new() -> Self {
+        Inline {
+            len: 0,
+            buf: [const { MaybeUninit::uninit() }; N],
+        }
+    }
+
+    fn as_slice(&self) -> &[T] {
+        // SAFETY: the first `len` slots are initialised
+        unsafe { slice::from_raw_parts(self.buf.as_ptr().cast(), self.len) }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: as above, and `&mut self` makes the borrow unique
+        unsafe { slice::from_raw_parts_mut(self.buf.as_mut_ptr().cast(), self.len) }
+    }
+
+    // Moves every element into a Vec with room for `extra` more
+    fn spill(&mut self, extra: usize) -> Vec<T> {
+        let mut vec = Vec::with_capacity((self.len + extra).max(N * 2));
+        // From here on the Vec owns the elements, so `Inline`'s Drop must
+        // not see them
+        let len = mem::replace(&mut self.len, 0);
+        // SAFETY: the slots were initialised, `len` is now 0 so they are
+        // never read again, and the Vec has capacity for them
+        unsafe {
+            ptr::copy_nonoverlapping(self.buf.as_ptr().cast::<T>(), vec.as_mut_ptr(), len);
+            vec.set_len(len);
+        }
+        vec
+    }
+}
+
+impl<T, const N: usize> Drop for Inline<T, N> {
+    fn drop(&mut self) {
+        // SAFETY: drops exactly the initialised prefix, once
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+    }
+}
+
+enum Data<T, const N: usize> {
+    Inline(Inline<T, N>),
+    Heap(Vec<T>),
+}
+
+pub struct InlineVec<T, const N: usize> {
+    data: Data<T, N>,
+}
+
+impl<T, const N: usize> InlineVec<T, N> {
+    pub const fn new() -> Self {
+        InlineVec {
+            data: Data::Inline(Inline::new()),
+        }
+    }
+
+    // Starts on the heap if `capacity` will not fit inline
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity <= N {
+            Self::new()
+        } else {
+            InlineVec {
+                data: Data::Heap(Vec::with_capacity(capacity)),
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.data {
+            Data::Inline(inline) => inline.len,
+            Data::Heap(vec) => vec.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        match &self.data {
+            Data::Inline(_) => N,
+            Data::Heap(vec) => vec.capacity(),
+        }
+    }
+
+    // True once the elements have moved to the heap
+    pub fn spilled(&self) -> bool {
+        matches!(self.data, Data::Heap(_))
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        match &self.data {
+            Data::Inline(inline) => inline.as_slice(),
+            Data::Heap(vec) => vec,
+        }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        match &mut self.data {
+            Data::Inline(inline) => inline.as_mut_slice(),
+            Data::Heap(vec) => vec,
+        }
+    }
+
+    // Spills if the inline buffer cannot take `additional` more
+    pub fn reserve(&mut self, additional: usize) {
+        match &mut self.data {
+            Data::Inline(inline) if inline.len + additional > N => {
+                let vec = inline.spill(additional);
+                self.data = Data::Heap(vec);
+            }
+            Data::Inline(_) => {}
+            Data::Heap(vec) => vec.reserve(additional),
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        match &mut self.data {
+            Data::Inline(inline) if inline.len < N => {
+                inline.buf[inline.len].write(value);
+                inline.len += 1;
+            }
+            Data::Inline(inline) => {
+                let mut vec = inline.spill(1);
+                vec.push(value);
+                self.data = Data::Heap(vec);
+            }
+            Data::Heap(vec) => vec.push(value),
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        match &mut self.data {
+            Data::Inline(inline) => {
+                if inline.len == 0 {
+                    return None;
+                }
+                inline.len -= 1;
+                // SAFETY: the slot was initialised and is now past `len`
+                Some(unsafe { inline.buf[inline.len].assume_init_read() })
+            }
+            Data::Heap(vec) => vec.pop(),
+        }
+    }
+
+    // Panics if `index > len`, like `Vec::insert`
+    pub fn insert(&mut self, index: usize, value: T) {
+        let len = self.len();
+        assert!(
+            index <= len,
+            "insert index {} out of range for length {}",
+            index,
+            len
+        );
+        self.reserve(1);
+        match &mut self.data {
+            Data::Inline(inline) => {
+                let base = inline.buf.as_mut_ptr().cast::<T>();
+                // SAFETY: `reserve` left room for one more; shift the tail
+                // up by one and write into the gap
+                unsafe {
+                    ptr::copy(base.add(index), base.add(index + 1), len - index);
+                    base.add(index).write(value);
+                }
+                inline.len += 1;
+            }
+            Data::Heap(vec) => vec.insert(index, value),
+        }
+    }
+
+    // Panics if `index >= len`, like `Vec::remove`
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.len();
+        assert!(
+            index < len,
+            "remove index {} out of range for length {}",
+            index,
+            len
+        );
+        match &mut self.data {
+            Data::Inline(inline) => {
+                let base = inline.buf.as_mut_ptr().cast::<T>();
+                // SAFETY: read the element out, then close the gap; the
+                // last slot is now a stale copy past `len`
+                unsafe {
+                    let value = base.add(index).read();
+                    ptr::copy(base.add(index + 1), base.add(index), len - index - 1);
+                    inline.len -= 1;
+                    value
+                }
+            }
+            Data::Heap(vec) => vec.remove(index),
+        }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        match &mut self.data {
+            Data::Inline(inline) => {
+                if len >= inline.len {
+                    return;
+                }
+                let tail = inline.len - len;
+                // Shorten first, so a panicking Drop can never lead to the
+                // tail being dropped a second time
+                inline.len = len;
+                // SAFETY: the `tail` slots after `len` were initialised
+                unsafe {
+                    let start = inline.buf.as_mut_ptr().cast::<T>().add(len);
+                    ptr::drop_in_place(ptr::slice_from_raw_parts_mut(start, tail));
+                }
+            }
+            Data::Heap(vec) => vec.truncate(len),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+}
+
+impl<T, const N: usize> Default for InlineVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Deref for InlineVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for InlineVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, const N: usize> Extend<T> for InlineVec<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for InlineVec<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = Self::new();
+        v.extend(iter);
+        v
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for InlineVec<T, N> {
+    // If a clone panics, the partly built copy drops what it already has
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for InlineVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for InlineVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for InlineVec<T, N> {}
+
+impl<'a, T, const N: usize> IntoIterator for &'a InlineVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut InlineVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for InlineVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> IntoIter<T, N> {
+        match self.data {
+            Data::Inline(mut inline) => {
+                let end = mem::replace(&mut inline.len, 0);
+                // `inline.len` is 0, so dropping `inline` would drop
+                // nothing; the iterator owns slots 0..end now
+                let buf = mem::replace(&mut inline.buf, [const { MaybeUninit::uninit() }; N]);
+                IntoIter {
+                    data: IterData::Inline { buf, start: 0, end },
+                }
+            }
+            Data::Heap(vec) => IntoIter {
+                data: IterData::Heap(vec.into_iter()),
+            },
+        }
+    }
+}
+
+enum IterData<T, const N: usize> {
+    // Slots start..end are initialised and not yet yielded
+    Inline {
+        buf: [MaybeUninit<T>; N],
+        start: usize,
+        end: usize,
+    },
+    Heap(std::vec::IntoIter<T>),
+}
+
+pub struct IntoIter<T, const N: usize> {
+    data: IterData<T, N>,
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match &mut self.data {
+            IterData::Inline { buf, start, end } => {
+                if *start == *end {
+                    return None;
+                }
+                *start += 1;
+                // SAFETY: the slot was in start..end, now excluded from it
+                Some(unsafe { buf[*start - 1].assume_init_read() })
+            }
+            IterData::Heap(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match &self.data {
+            IterData::Inline { start, end, .. } => end - start,
+            IterData::Heap(iter) => iter.len(),
+        };
+        (len, Some(len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        match &mut self.data {
+            IterData::Inline { buf, start, end } => {
+                if *start == *end {
+                    return None;
+                }
+                *end -= 1;
+                // SAFETY: the slot was in start..end, now excluded from it
+                Some(unsafe { buf[*end].assume_init_read() })
+            }
+            IterData::Heap(iter) => iter.next_back(),
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+// Elements not yet yielded are dropped with the iterator
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        if let IterData::Inline { buf, start, end } = &mut self.data {
+            let rest = *end - *start;
+            let first = buf.as_mut_ptr().cast::<T>();
+            // Empty the range first, as in `truncate`
+            let from = mem::replace(start, *end);
+            // SAFETY: slots from..from + rest were initialised and unyielded
+            unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(first.add(from), rest)) };
+        }
+    }
+}
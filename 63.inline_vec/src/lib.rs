@@ -0,0 +1,11 @@
+// Small-vector optimisation: `InlineVec<T, N>`
+//
+// Most frames on a sensor network are short: an 8-byte CAN payload, a
+// 20-byte BLE advertisement, a handful of Modbus registers. A `Vec` for
+// each one is a heap allocation and a free. `InlineVec<T, N>` stores up to
+// N elements inside the struct and moves them to a `Vec` only when a
+// frame turns out to be longer.
+
+pub mod inline_vec;
+
+pub use inline_vec::{InlineVec, IntoIter};
@@ -0,0 +1,252 @@
+use inline_vec::InlineVec;
+use memprofile::{stats, Profiler};
+use std::cell::Cell;
+use std::hint::black_box;
+use std::mem::size_of;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+#[global_allocator]
+static GLOBAL: Profiler = Profiler;
+
+// Under Miri (`cargo +nightly miri run`) only the first three sections run,
+// with fewer iterations; tests/inline_vec.rs checks the results
+const STEPS: usize = if cfg!(miri) { 5_000 } else { 50_000 };
+const FRAMES: usize = 100_000;
+
+fn time<T>(reps: u32, mut f: impl FnMut() -> T) -> Duration {
+    (0..reps)
+        .map(|_| {
+            let started = Instant::now();
+            black_box(f());
+            started.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+// xorshift64: the same sequence on every run
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+// Counts its own drops, to catch elements dropped twice or never
+struct Tracked {
+    id: u32,
+    drops: Rc<Cell<usize>>,
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+fn tracked(count: u32, drops: &Rc<Cell<usize>>) -> impl Iterator<Item = Tracked> + '_ {
+    (0..count).map(move |id| Tracked {
+        id,
+        drops: Rc::clone(drops),
+    })
+}
+
+// How each drop-accounting case disposes of its vector
+type Consume = fn(InlineVec<Tracked, 4>);
+
+// Frame lengths as a gateway sees them: mostly short, a few long
+fn frame_lengths(count: usize) -> Vec<usize> {
+    let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+    (0..count)
+        .map(|_| match rng.below(10) {
+            0 => 40 + rng.below(200),
+            _ => 4 + rng.below(20),
+        })
+        .collect()
+}
+
+fn main() {
+    println!("=== Inline Vector Examples ===\n");
+
+    // 1. Inline, then spilled
+    println!("1. Growing past N:");
+    let mut v: InlineVec<u8, 4> = InlineVec::new();
+    for byte in 1..=6u8 {
+        let start = stats();
+        v.push(byte);
+        let allocs = stats().allocs - start.allocs;
+        println!(
+            "   push {}: len {}, capacity {:>2}, spilled {:<5} allocations {}",
+            byte,
+            v.len(),
+            v.capacity(),
+            v.spilled(),
+            allocs
+        );
+    }
+    println!("   contents {:?}", v);
+    println!("   pop: {:?}, len {}", v.pop(), v.len());
+
+    // 2. Same results as Vec for random operations
+    println!("\n2. {} random operations against Vec:", STEPS);
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+    let mut ours: InlineVec<u32, 8> = InlineVec::new();
+    let mut model: Vec<u32> = Vec::new();
+    let mut spills = 0;
+    for step in 0..STEPS {
+        let value = step as u32;
+        match rng.below(8) {
+            0..=2 => {
+                ours.push(value);
+                model.push(value);
+            }
+            3 => {
+                ours.pop();
+                model.pop();
+            }
+            4 => {
+                let at = rng.below(model.len() + 1);
+                ours.insert(at, value);
+                model.insert(at, value);
+            }
+            5 if !model.is_empty() => {
+                let at = rng.below(model.len());
+                ours.remove(at);
+                model.remove(at);
+            }
+            6 => {
+                let len = rng.below(model.len() + 1);
+                ours.truncate(len);
+                model.truncate(len);
+            }
+            // Start over now and then, so the inline path keeps being used
+            _ if rng.below(4) == 0 => {
+                spills += ours.spilled() as usize;
+                ours = InlineVec::new();
+                model.clear();
+            }
+            _ => {}
+        }
+    }
+    println!("   {} vectors spilled to the heap along the way", spills);
+    println!("   InlineVec at the end: {:?}", ours);
+    println!("   Vec at the end:       {:?}", model);
+
+    // 3. Every element dropped exactly once
+    println!("\n3. Drop accounting:");
+    let drops = Rc::new(Cell::new(0));
+    let cases: [(&str, u32, Consume); 5] = [
+        ("dropped while inline", 3, drop),
+        ("dropped after spilling", 9, drop),
+        ("truncated, then dropped", 4, |mut v| v.truncate(1)),
+        ("into_iter half consumed", 4, |v| {
+            v.into_iter().take(2).for_each(drop)
+        }),
+        ("spilled into_iter half consumed", 8, |v| {
+            v.into_iter().take(3).for_each(drop)
+        }),
+    ];
+    for (label, count, consume) in cases {
+        drops.set(0);
+        consume(tracked(count, &drops).collect());
+        println!("   {:<34} {} of {} dropped", label, drops.get(), count);
+    }
+    drops.set(0);
+    let mut v: InlineVec<Tracked, 4> = tracked(4, &drops).collect();
+    let removed = v.remove(1);
+    v.insert(0, removed);
+    let ids: Vec<u32> = v.iter().map(|t| t.id).collect();
+    println!(
+        "   remove(1) + insert(0): ids {:?}, {} dropped while moving",
+        ids,
+        drops.get()
+    );
+    drop(v);
+    let mut units: InlineVec<(), 2> = InlineVec::new();
+    for _ in 0..5 {
+        units.push(());
+    }
+    println!(
+        "   five () pushed into InlineVec<(), 2>: len {}, spilled {}",
+        units.len(),
+        units.spilled()
+    );
+
+    if cfg!(miri) {
+        println!("\n=== End of Inline Vector Examples ===");
+        return;
+    }
+
+    // 4. Sizes and allocations
+    println!("\n4. Buffering {} frames:", FRAMES);
+    println!(
+        "   size_of: Vec<u8> {} B, InlineVec<u8, 24> {} B, InlineVec<u8, 64> {} B",
+        size_of::<Vec<u8>>(),
+        size_of::<InlineVec<u8, 24>>(),
+        size_of::<InlineVec<u8, 64>>()
+    );
+    let lengths = frame_lengths(FRAMES);
+    let start = stats();
+    let vecs: Vec<Vec<u8>> = lengths.iter().map(|&n| vec![0xAA; n]).collect();
+    let after_vec = stats();
+    let inlines: Vec<InlineVec<u8, 24>> = lengths
+        .iter()
+        .map(|&n| (0..n).map(|i| i as u8).collect())
+        .collect();
+    let after_inline = stats();
+    let vec_allocs = after_vec.allocs - start.allocs;
+    let inline_allocs = after_inline.allocs - after_vec.allocs;
+    let long = lengths.iter().filter(|&&n| n > 24).count();
+    // Both counts include one allocation for the outer Vec
+    println!("   Vec<u8>:            {:>7} allocations", vec_allocs);
+    println!(
+        "   InlineVec<u8, 24>:  {:>7} allocations ({} frames longer than 24)",
+        inline_allocs, long
+    );
+    drop((vecs, inlines));
+
+    // 5. Timing
+    // Byte by byte, the way a frame decoder fills its buffer
+    println!("\n5. Build and checksum {} frames, best of 20:", FRAMES);
+    let vec_time = time(20, || {
+        lengths
+            .iter()
+            .map(|&n| {
+                let mut frame = Vec::new();
+                for i in 0..n {
+                    frame.push(i as u8);
+                }
+                frame.iter().fold(0u8, |a, &b| a.wrapping_add(b))
+            })
+            .fold(0u8, u8::wrapping_add)
+    });
+    let inline_time = time(20, || {
+        lengths
+            .iter()
+            .map(|&n| {
+                let mut frame: InlineVec<u8, 24> = InlineVec::new();
+                for i in 0..n {
+                    frame.push(i as u8);
+                }
+                frame.iter().fold(0u8, |a, &b| a.wrapping_add(b))
+            })
+            .fold(0u8, u8::wrapping_add)
+    });
+    println!("   Vec<u8>:           {:>9.2?}", vec_time);
+    println!("   InlineVec<u8, 24>: {:>9.2?}", inline_time);
+    println!(
+        "   speedup: {:.1}x",
+        vec_time.as_secs_f64() / inline_time.as_secs_f64().max(1e-9)
+    );
+
+    println!("\n=== End of Inline Vector Examples ===");
+}
@@ -0,0 +1,306 @@
+use inline_vec::InlineVec;
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+// Under Miri the randomised comparison takes far fewer steps
+const STEPS: usize = if cfg!(miri) { 2_000 } else { 50_000 };
+
+// xorshift64: the same sequence on every run
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+// Counts its own drops, to catch elements dropped twice or never; drops
+// with `id == panic_on` panic after being counted
+struct Tracked {
+    id: u32,
+    drops: Rc<Cell<usize>>,
+    panic_on: Option<u32>,
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+        if self.panic_on == Some(self.id) {
+            panic!("drop of {} panicked", self.id);
+        }
+    }
+}
+
+fn tracked(count: u32, drops: &Rc<Cell<usize>>) -> impl Iterator<Item = Tracked> + '_ {
+    tracked_panicking(count, None, drops)
+}
+
+fn tracked_panicking(
+    count: u32,
+    panic_on: Option<u32>,
+    drops: &Rc<Cell<usize>>,
+) -> impl Iterator<Item = Tracked> + '_ {
+    (0..count).map(move |id| Tracked {
+        id,
+        drops: Rc::clone(drops),
+        panic_on,
+    })
+}
+
+#[test]
+fn new_is_empty_and_inline() {
+    let v: InlineVec<u8, 4> = InlineVec::new();
+    assert!(v.is_empty());
+    assert!(!v.spilled());
+    assert_eq!(v.capacity(), 4);
+    assert_eq!(InlineVec::<u8, 4>::default(), v);
+}
+
+#[test]
+fn pushing_past_n_spills_and_keeps_the_contents() {
+    let mut v: InlineVec<u8, 4> = InlineVec::new();
+    for byte in 1..=4 {
+        v.push(byte);
+        assert!(!v.spilled());
+    }
+    v.push(5);
+    assert!(v.spilled());
+    assert!(v.capacity() >= 8);
+    v.push(6);
+    assert_eq!(v[..], [1, 2, 3, 4, 5, 6]);
+    assert_eq!(v.pop(), Some(6));
+    assert_eq!(v.len(), 5);
+    // Once on the heap it stays there
+    v.clear();
+    assert!(v.spilled());
+}
+
+#[test]
+fn with_capacity_picks_inline_or_heap() {
+    let small: InlineVec<u8, 8> = InlineVec::with_capacity(8);
+    assert!(!small.spilled());
+    let large: InlineVec<u8, 8> = InlineVec::with_capacity(9);
+    assert!(large.spilled());
+    assert!(large.capacity() >= 9);
+}
+
+#[test]
+fn reserve_spills_only_when_needed() {
+    let mut v: InlineVec<u8, 4> = [1, 2].into_iter().collect();
+    v.reserve(2);
+    assert!(!v.spilled());
+    v.reserve(3);
+    assert!(v.spilled());
+    assert!(v.capacity() >= 5);
+    assert_eq!(v[..], [1, 2]);
+}
+
+#[test]
+fn insert_and_remove_shift_inline() {
+    let mut v: InlineVec<u32, 8> = [1, 2, 4].into_iter().collect();
+    v.insert(2, 3);
+    v.insert(0, 0);
+    v.insert(5, 5);
+    assert_eq!(v[..], [0, 1, 2, 3, 4, 5]);
+    assert_eq!(v.remove(0), 0);
+    assert_eq!(v.remove(4), 5);
+    assert_eq!(v.remove(1), 2);
+    assert_eq!(v[..], [1, 3, 4]);
+    assert!(!v.spilled());
+}
+
+#[test]
+fn insert_into_a_full_buffer_spills() {
+    let mut v: InlineVec<u32, 3> = [1, 2, 3].into_iter().collect();
+    v.insert(1, 9);
+    assert!(v.spilled());
+    assert_eq!(v[..], [1, 9, 2, 3]);
+}
+
+#[test]
+#[should_panic(expected = "insert index 3 out of range for length 2")]
+fn insert_past_the_end_panics() {
+    let mut v: InlineVec<u32, 4> = [1, 2].into_iter().collect();
+    v.insert(3, 0);
+}
+
+#[test]
+#[should_panic(expected = "remove index 2 out of range for length 2")]
+fn remove_past_the_end_panics() {
+    let mut v: InlineVec<u32, 4> = [1, 2].into_iter().collect();
+    v.remove(2);
+}
+
+#[test]
+fn truncate_longer_than_len_does_nothing() {
+    let mut v: InlineVec<u32, 4> = [1, 2].into_iter().collect();
+    v.truncate(3);
+    assert_eq!(v[..], [1, 2]);
+    v.truncate(1);
+    assert_eq!(v[..], [1]);
+}
+
+#[test]
+fn deref_mut_and_iter_mut_write_through() {
+    let mut v: InlineVec<u32, 4> = (1..=3).collect();
+    v[0] = 10;
+    for x in &mut v {
+        *x *= 2;
+    }
+    v.as_mut_slice().reverse();
+    assert_eq!(v[..], [6, 4, 20]);
+    assert_eq!(format!("{:?}", v), "[6, 4, 20]");
+}
+
+#[test]
+fn clone_equals_original_inline_and_spilled() {
+    for len in [3, 12] {
+        let v: InlineVec<String, 4> = (0..len).map(|i| i.to_string()).collect();
+        let clone = v.clone();
+        assert_eq!(clone, v);
+        assert_eq!(clone.spilled(), v.spilled());
+    }
+}
+
+#[test]
+fn into_iter_runs_from_both_ends() {
+    for len in [4u32, 9] {
+        let v: InlineVec<u32, 4> = (0..len).collect();
+        let mut iter = v.into_iter();
+        assert_eq!(iter.len(), len as usize);
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.next_back(), Some(len - 1));
+        assert_eq!(iter.len(), len as usize - 2);
+        let rest: Vec<u32> = iter.by_ref().collect();
+        assert_eq!(rest, (1..len - 1).collect::<Vec<_>>());
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+}
+
+#[test]
+fn random_operations_match_vec() {
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+    let mut ours: InlineVec<u32, 8> = InlineVec::new();
+    let mut model: Vec<u32> = Vec::new();
+    let mut spills = 0;
+    for step in 0..STEPS {
+        let value = step as u32;
+        match rng.below(8) {
+            0..=2 => {
+                ours.push(value);
+                model.push(value);
+            }
+            3 => assert_eq!(ours.pop(), model.pop(), "step {}", step),
+            4 => {
+                let at = rng.below(model.len() + 1);
+                ours.insert(at, value);
+                model.insert(at, value);
+            }
+            5 if !model.is_empty() => {
+                let at = rng.below(model.len());
+                assert_eq!(ours.remove(at), model.remove(at), "step {}", step);
+            }
+            6 => {
+                let len = rng.below(model.len() + 1);
+                ours.truncate(len);
+                model.truncate(len);
+            }
+            // Start over now and then, so the inline path keeps being used
+            _ if rng.below(4) == 0 => {
+                spills += ours.spilled() as usize;
+                ours = InlineVec::new();
+                model.clear();
+            }
+            _ => {}
+        }
+        assert_eq!(ours[..], model[..], "step {}", step);
+        assert_eq!(ours.spilled(), ours.capacity() > 8);
+    }
+    assert!(spills > 0);
+    let collected: Vec<u32> = ours.into_iter().rev().collect();
+    let reversed: Vec<u32> = model.into_iter().rev().collect();
+    assert_eq!(collected, reversed);
+}
+
+#[test]
+fn every_element_is_dropped_exactly_once() {
+    type Consume = fn(InlineVec<Tracked, 4>);
+    let cases: [(&str, u32, Consume); 6] = [
+        ("dropped while inline", 3, drop),
+        ("dropped after spilling", 9, drop),
+        ("truncated, then dropped", 4, |mut v| v.truncate(1)),
+        ("cleared after spilling", 7, |mut v| v.clear()),
+        ("into_iter half consumed", 4, |v| {
+            v.into_iter().take(2).for_each(drop)
+        }),
+        ("spilled into_iter half consumed", 8, |v| {
+            v.into_iter().take(3).for_each(drop)
+        }),
+    ];
+    let drops = Rc::new(Cell::new(0));
+    for (label, count, consume) in cases {
+        drops.set(0);
+        consume(tracked(count, &drops).collect());
+        assert_eq!(drops.get(), count as usize, "{}", label);
+    }
+}
+
+#[test]
+fn into_iter_from_both_ends_drops_the_middle() {
+    let drops = Rc::new(Cell::new(0));
+    let v: InlineVec<Tracked, 6> = tracked(6, &drops).collect();
+    let mut iter = v.into_iter();
+    let first = iter.next().unwrap();
+    let last = iter.next_back().unwrap();
+    assert_eq!((first.id, last.id), (0, 5));
+    drop(iter);
+    assert_eq!(drops.get(), 4);
+    drop((first, last));
+    assert_eq!(drops.get(), 6);
+}
+
+#[test]
+fn remove_and_insert_move_without_dropping() {
+    let drops = Rc::new(Cell::new(0));
+    let mut v: InlineVec<Tracked, 4> = tracked(4, &drops).collect();
+    let removed = v.remove(1);
+    v.insert(0, removed);
+    let ids: Vec<u32> = v.iter().map(|t| t.id).collect();
+    assert_eq!(ids, [1, 0, 2, 3]);
+    assert_eq!(drops.get(), 0);
+    drop(v);
+    assert_eq!(drops.get(), 4);
+}
+
+#[test]
+fn a_panicking_drop_in_truncate_never_drops_twice() {
+    let drops = Rc::new(Cell::new(0));
+    let mut v: InlineVec<Tracked, 8> = tracked_panicking(5, Some(2), &drops).collect();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| v.truncate(1)));
+    assert!(result.is_err());
+    // `drop_in_place` keeps dropping the rest of the tail after the panic
+    assert_eq!(drops.get(), 4);
+    assert_eq!(v.len(), 1);
+    drop(v);
+    assert_eq!(drops.get(), 5);
+}
+
+#[test]
+fn zero_sized_elements() {
+    let mut units: InlineVec<(), 2> = InlineVec::new();
+    for _ in 0..5 {
+        units.push(());
+    }
+    assert_eq!(units.len(), 5);
+    assert!(units.spilled());
+    assert_eq!(units.into_iter().count(), 5);
+}
@@ -0,0 +1,86 @@
+// Naive vs branchless vs lookup-table versions of three hot loops
+//
+//   cargo bench               # every group
+//   cargo bench -- classify   # one group
+//
+// `classify` runs on noisy and on sorted readings. Sorting the input makes
+// the if-chain's branches predictable, which shows how much of its cost is
+// misprediction.
+
+use branchless::clamp::{saturate_bits, saturate_minmax, saturate_naive};
+use branchless::classify::{bucket_chain, bucket_count, bucket_lut, histogram};
+use branchless::hex::{encode_branchless, encode_lut16, encode_lut256, encode_naive};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use std::hint::black_box;
+
+const N: usize = 64 * 1024;
+
+fn random(count: usize) -> impl Iterator<Item = u64> {
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    (0..count).map(move |_| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    })
+}
+
+fn hex(c: &mut Criterion) {
+    let bytes: Vec<u8> = random(N).map(|r| r as u8).collect();
+    let mut out = vec![0; 2 * N];
+    let mut group = c.benchmark_group("hex");
+    group.throughput(Throughput::Bytes(N as u64));
+    group.bench_function("naive", |b| {
+        b.iter(|| encode_naive(black_box(&bytes), &mut out))
+    });
+    group.bench_function("branchless", |b| {
+        b.iter(|| encode_branchless(black_box(&bytes), &mut out))
+    });
+    group.bench_function("lut16", |b| {
+        b.iter(|| encode_lut16(black_box(&bytes), &mut out))
+    });
+    group.bench_function("lut256", |b| {
+        b.iter(|| encode_lut256(black_box(&bytes), &mut out))
+    });
+    group.finish();
+}
+
+fn clamp(c: &mut Criterion) {
+    let samples: Vec<i32> = random(N)
+        .map(|r| ((r >> 48) as i16 as i32) * 3 / 2)
+        .collect();
+    let mut out = vec![0; N];
+    let mut group = c.benchmark_group("clamp");
+    group.throughput(Throughput::Elements(N as u64));
+    group.bench_function("naive", |b| {
+        b.iter(|| saturate_naive(black_box(&samples), &mut out))
+    });
+    group.bench_function("minmax", |b| {
+        b.iter(|| saturate_minmax(black_box(&samples), &mut out))
+    });
+    group.bench_function("bits", |b| {
+        b.iter(|| saturate_bits(black_box(&samples), &mut out))
+    });
+    group.finish();
+}
+
+fn classify(c: &mut Criterion) {
+    let noisy: Vec<u16> = random(N).map(|r| (r >> 52) as u16).collect();
+    let mut sorted = noisy.clone();
+    sorted.sort_unstable();
+    for (name, data) in [("classify_noisy", &noisy), ("classify_sorted", &sorted)] {
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Elements(N as u64));
+        group.bench_function("chain", |b| {
+            b.iter(|| histogram(black_box(data), bucket_chain))
+        });
+        group.bench_function("count", |b| {
+            b.iter(|| histogram(black_box(data), bucket_count))
+        });
+        group.bench_function("lut", |b| b.iter(|| histogram(black_box(data), bucket_lut)));
+        group.finish();
+    }
+}
+
+criterion_group!(benches, hex, clamp, classify);
+criterion_main!(benches);
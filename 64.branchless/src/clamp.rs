@@ -0,0 +1,56 @@
+// Saturating 24-bit ADC samples (held in i32) into i16 PCM
+//
+//   naive    if x < lo { lo } else if x > hi { hi } else { x }
+//   minmax   x.clamp(lo, hi), which compiles to compare + conditional move
+//   bits     x - max(x - hi, 0) + max(lo - x, 0), with max(d, 0) computed
+//            as d & !(d >> 63): no comparison at all
+//
+// `bits` works in i64 so that `x - hi` cannot overflow for any i32.
+
+pub fn clamp_naive(x: i32, lo: i32, hi: i32) -> i32 {
+    if x < lo {
+        lo
+    } else if x > hi {
+        hi
+    } else {
+        x
+    }
+}
+
+pub fn clamp_bits(x: i32, lo: i32, hi: i32) -> i32 {
+    debug_assert!(lo <= hi);
+    let x = x as i64;
+    let over = x - hi as i64;
+    let x = x - (over & !(over >> 63));
+    let under = lo as i64 - x;
+    let x = x + (under & !(under >> 63));
+    x as i32
+}
+
+fn check_len(samples: &[i32], out: &[i16]) {
+    assert_eq!(out.len(), samples.len(), "out must be as long as samples");
+}
+
+const LO: i32 = i16::MIN as i32;
+const HI: i32 = i16::MAX as i32;
+
+pub fn saturate_naive(samples: &[i32], out: &mut [i16]) {
+    check_len(samples, out);
+    for (&x, y) in samples.iter().zip(out.iter_mut()) {
+        *y = clamp_naive(x, LO, HI) as i16;
+    }
+}
+
+pub fn saturate_minmax(samples: &[i32], out: &mut [i16]) {
+    check_len(samples, out);
+    for (&x, y) in samples.iter().zip(out.iter_mut()) {
+        *y = x.clamp(LO, HI) as i16;
+    }
+}
+
+pub fn saturate_bits(samples: &[i32], out: &mut [i16]) {
+    check_len(samples, out);
+    for (&x, y) in samples.iter().zip(out.iter_mut()) {
+        *y = clamp_bits(x, LO, HI) as i16;
+    }
+}
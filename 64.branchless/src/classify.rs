@@ -0,0 +1,75 @@
+// Sorting 12-bit ADC readings into seven buckets for a histogram
+//
+//   bucket   0      1      2       3       4       5       6
+//   reading  <384   <800   <1504   <2496   <3200   <3808   >=3808
+//
+//   chain   an if / else-if ladder, taken branch depends on the value
+//   count   bucket = number of thresholds at or below the reading: six
+//           comparisons turned into 0/1 and added, always all six
+//   lut     every threshold is a multiple of 16, so the top 8 bits of the
+//           reading decide the bucket: one load from a 256-entry table
+
+pub const BUCKETS: usize = 7;
+pub const THRESHOLDS: [u16; BUCKETS - 1] = [384, 800, 1504, 2496, 3200, 3808];
+pub const MAX_READING: u16 = 4095;
+
+// The table depends on it; checked when the crate is compiled
+const _: () = {
+    let mut i = 0;
+    while i < THRESHOLDS.len() {
+        assert!(THRESHOLDS[i].is_multiple_of(16));
+        i += 1;
+    }
+};
+
+static BUCKET_OF: [u8; 256] = bucket_table();
+
+const fn bucket_table() -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let reading = (i as u16) << 4;
+        let mut bucket = 0;
+        while bucket < THRESHOLDS.len() && reading >= THRESHOLDS[bucket] {
+            bucket += 1;
+        }
+        table[i] = bucket as u8;
+        i += 1;
+    }
+    table
+}
+
+pub fn bucket_chain(reading: u16) -> usize {
+    if reading < THRESHOLDS[0] {
+        0
+    } else if reading < THRESHOLDS[1] {
+        1
+    } else if reading < THRESHOLDS[2] {
+        2
+    } else if reading < THRESHOLDS[3] {
+        3
+    } else if reading < THRESHOLDS[4] {
+        4
+    } else if reading < THRESHOLDS[5] {
+        5
+    } else {
+        6
+    }
+}
+
+pub fn bucket_count(reading: u16) -> usize {
+    THRESHOLDS.iter().map(|&t| (reading >= t) as usize).sum()
+}
+
+// Readings above 4095 are not 12-bit; they land in the last bucket
+pub fn bucket_lut(reading: u16) -> usize {
+    BUCKET_OF[(reading.min(MAX_READING) >> 4) as usize] as usize
+}
+
+pub fn histogram(readings: &[u16], bucket: impl Fn(u16) -> usize) -> [u32; BUCKETS] {
+    let mut counts = [0; BUCKETS];
+    for &r in readings {
+        counts[bucket(r)] += 1;
+    }
+    counts
+}
@@ -0,0 +1,85 @@
+// Hex encoding, one byte to two ASCII digits
+//
+// Every version writes lowercase digits into `out`, which must be exactly
+// twice as long as `bytes`:
+//
+//   naive       a branch per nibble: digit or letter?
+//   branchless  the same choice made with a sign mask
+//   lut16       "0123456789abcdef"[nibble]
+//   lut256      one load per byte from a 512-byte table of digit pairs
+
+const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+// Built by the compiler, as in 61.const_eval
+static PAIRS: [[u8; 2]; 256] = pairs();
+
+const fn pairs() -> [[u8; 2]; 256] {
+    let mut table = [[0; 2]; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = [DIGITS[i >> 4], DIGITS[i & 0xF]];
+        i += 1;
+    }
+    table
+}
+
+fn check_len(bytes: &[u8], out: &[u8]) {
+    assert_eq!(
+        out.len(),
+        bytes.len() * 2,
+        "out must be twice as long as bytes"
+    );
+}
+
+fn digit_naive(nibble: u8) -> u8 {
+    if nibble < 10 {
+        b'0' + nibble
+    } else {
+        b'a' + nibble - 10
+    }
+}
+
+// (9 - n) is negative exactly when n >= 10, so its arithmetic shift is an
+// all-ones mask that adds the distance from '9' + 1 to 'a' (39)
+fn digit_branchless(nibble: u8) -> u8 {
+    let letter = ((9 - nibble as i8) >> 7) as u8;
+    b'0' + nibble + (letter & (b'a' - b'0' - 10))
+}
+
+pub fn encode_naive(bytes: &[u8], out: &mut [u8]) {
+    check_len(bytes, out);
+    for (&b, pair) in bytes.iter().zip(out.chunks_exact_mut(2)) {
+        pair[0] = digit_naive(b >> 4);
+        pair[1] = digit_naive(b & 0xF);
+    }
+}
+
+pub fn encode_branchless(bytes: &[u8], out: &mut [u8]) {
+    check_len(bytes, out);
+    for (&b, pair) in bytes.iter().zip(out.chunks_exact_mut(2)) {
+        pair[0] = digit_branchless(b >> 4);
+        pair[1] = digit_branchless(b & 0xF);
+    }
+}
+
+pub fn encode_lut16(bytes: &[u8], out: &mut [u8]) {
+    check_len(bytes, out);
+    for (&b, pair) in bytes.iter().zip(out.chunks_exact_mut(2)) {
+        pair[0] = DIGITS[(b >> 4) as usize];
+        pair[1] = DIGITS[(b & 0xF) as usize];
+    }
+}
+
+pub fn encode_lut256(bytes: &[u8], out: &mut [u8]) {
+    check_len(bytes, out);
+    for (&b, pair) in bytes.iter().zip(out.chunks_exact_mut(2)) {
+        pair.copy_from_slice(&PAIRS[b as usize]);
+    }
+}
+
+// Convenience wrapper for display
+pub fn to_hex(bytes: &[u8]) -> String {
+    let mut out = vec![0; bytes.len() * 2];
+    encode_lut256(bytes, &mut out);
+    String::from_utf8(out).expect("hex digits are ASCII")
+}
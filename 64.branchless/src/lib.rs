@@ -0,0 +1,16 @@
+// Branchless code and lookup tables for hot inner loops
+//
+// A branch the CPU predicts correctly is almost free; one it mispredicts
+// costs 15-20 cycles. Loops over sensor data branch on the data itself,
+// and when that data is noisy the predictor guesses wrong half the time.
+// Each module has a naive version and faster ones that must give exactly
+// the same results:
+//
+// - `hex`: byte to hex digits, by branch, by arithmetic, by table
+// - `clamp`: saturating samples, by if/else, by min/max, by bit masks
+// - `classify`: readings into histogram buckets, by if-chain, by counting
+//   comparisons, by table
+
+pub mod clamp;
+pub mod classify;
+pub mod hex;
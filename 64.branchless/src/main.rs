@@ -0,0 +1,131 @@
+use branchless::clamp::{clamp_bits, saturate_bits, saturate_minmax, saturate_naive};
+use branchless::classify::{bucket_chain, bucket_count, bucket_lut, histogram};
+use branchless::hex::{encode_branchless, encode_lut16, encode_lut256, encode_naive, to_hex};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const N: usize = 1_000_000;
+
+fn time<T>(reps: u32, mut f: impl FnMut() -> T) -> Duration {
+    (0..reps)
+        .map(|_| {
+            let started = Instant::now();
+            black_box(f());
+            started.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn print_times(rows: &[(&str, Duration)]) {
+    let base = rows[0].1.as_secs_f64();
+    for (label, t) in rows {
+        println!(
+            "   {:<24} {:>9.2?}  {:>5.2}x",
+            label,
+            t,
+            base / t.as_secs_f64().max(1e-9)
+        );
+    }
+}
+
+// xorshift64: the same data on every run
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+type Encode = fn(&[u8], &mut [u8]);
+type Saturate = fn(&[i32], &mut [i16]);
+
+fn main() {
+    println!("=== Branchless and Lookup Table Examples ===\n");
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+
+    // 1. Hex encoding
+    println!("1. Hex encoding:");
+    let encoders: [(&str, Encode); 4] = [
+        ("naive (branch per nibble)", encode_naive),
+        ("branchless (sign mask)", encode_branchless),
+        ("lut16 (digit table)", encode_lut16),
+        ("lut256 (pair table)", encode_lut256),
+    ];
+    // tests/ compares every version against the naive one on all inputs
+    println!(
+        "   [0x00, 0x7f, 0xa5, 0xff] -> {}",
+        to_hex(&[0x00, 0x7f, 0xa5, 0xff])
+    );
+    let payload: Vec<u8> = (0..N).map(|_| rng.next() as u8).collect();
+    let mut out = vec![0; 2 * N];
+    let rows: Vec<(&str, Duration)> = encoders
+        .iter()
+        .map(|&(label, encode)| (label, time(10, || encode(&payload, &mut out))))
+        .collect();
+    println!("   {} random bytes, best of 10:", N);
+    print_times(&rows);
+
+    // 2. Clamping
+    println!("\n2. Saturating 24-bit samples to i16:");
+    for x in [i32::MIN, -40_000, 1_234, 40_000, i32::MAX] {
+        println!(
+            "   clamp_bits({:>11}, -32768, 32767) = {:>6}",
+            x,
+            clamp_bits(x, -32_768, 32_767)
+        );
+    }
+    let saturators: [(&str, Saturate); 3] = [
+        ("naive (if / else if)", saturate_naive),
+        ("minmax (i32::clamp)", saturate_minmax),
+        ("bits (sign masks)", saturate_bits),
+    ];
+    // A loud signal: a 24-bit value, a third of them outside i16
+    let samples: Vec<i32> = (0..N)
+        .map(|_| ((rng.next() >> 48) as i16 as i32) * 3 / 2)
+        .collect();
+    let mut out = vec![0i16; N];
+    let rows: Vec<(&str, Duration)> = saturators
+        .iter()
+        .map(|&(label, saturate)| (label, time(10, || saturate(&samples, &mut out))))
+        .collect();
+    println!("   {} samples, best of 10:", N);
+    print_times(&rows);
+
+    // 3. Classification
+    println!("\n3. Bucketing 12-bit readings:");
+    for r in [0, 383, 384, 2000, 4095, u16::MAX] {
+        println!("   reading {:>5} -> bucket {}", r, bucket_lut(r));
+    }
+    let noisy: Vec<u16> = (0..N).map(|_| (rng.next() >> 52) as u16).collect();
+    let mut sorted = noisy.clone();
+    sorted.sort_unstable();
+    println!("   histogram: {:?}", histogram(&noisy, bucket_lut));
+
+    // 4. Predictable vs unpredictable data
+    println!("\n4. Histogram of {} readings, best of 10:", N);
+    for (data, label) in [(&noisy, "noisy"), (&sorted, "sorted")] {
+        println!("   {} readings:", label);
+        let rows = [
+            (
+                "chain (if / else if)",
+                time(10, || histogram(data, bucket_chain)),
+            ),
+            (
+                "count (sum of compares)",
+                time(10, || histogram(data, bucket_count)),
+            ),
+            (
+                "lut (256-entry table)",
+                time(10, || histogram(data, bucket_lut)),
+            ),
+        ];
+        print_times(&rows);
+    }
+
+    println!("\n=== End of Branchless and Lookup Table Examples ===");
+}
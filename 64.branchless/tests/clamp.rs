@@ -0,0 +1,112 @@
+use branchless::clamp::{clamp_bits, clamp_naive, saturate_bits, saturate_minmax, saturate_naive};
+
+const EDGES: [i32; 10] = [
+    i32::MIN,
+    -8_388_608,
+    -32_769,
+    -32_768,
+    -1,
+    0,
+    32_767,
+    32_768,
+    8_388_607,
+    i32::MAX,
+];
+
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0
+    }
+}
+
+#[test]
+fn clamp_bits_matches_naive_on_edges_and_bounds() {
+    let bounds = [
+        (-32_768, 32_767),
+        (0, 0),
+        (-5, 5),
+        (i32::MIN, i32::MAX),
+        (i32::MIN, i32::MIN),
+        (i32::MAX, i32::MAX),
+    ];
+    for x in EDGES {
+        for (lo, hi) in bounds {
+            assert_eq!(
+                clamp_bits(x, lo, hi),
+                clamp_naive(x, lo, hi),
+                "clamp({}, {}, {})",
+                x,
+                lo,
+                hi
+            );
+        }
+    }
+}
+
+#[test]
+fn clamp_bits_matches_naive_on_random_values_and_bounds() {
+    let mut rng = Lcg(1);
+    for _ in 0..200_000 {
+        let x = (rng.next() >> 32) as i32;
+        let a = (rng.next() >> 40) as i32 - (1 << 23);
+        let b = (rng.next() >> 40) as i32 - (1 << 23);
+        let (lo, hi) = (a.min(b), a.max(b));
+        assert_eq!(clamp_bits(x, lo, hi), clamp_naive(x, lo, hi));
+    }
+}
+
+#[test]
+fn clamp_naive_saturates() {
+    assert_eq!(clamp_naive(-40_000, -32_768, 32_767), -32_768);
+    assert_eq!(clamp_naive(40_000, -32_768, 32_767), 32_767);
+    assert_eq!(clamp_naive(123, -32_768, 32_767), 123);
+}
+
+#[test]
+fn saturators_agree_on_edges() {
+    let expected: Vec<i16> = vec![
+        i16::MIN,
+        i16::MIN,
+        i16::MIN,
+        i16::MIN,
+        -1,
+        0,
+        i16::MAX,
+        i16::MAX,
+        i16::MAX,
+        i16::MAX,
+    ];
+    for saturate in [saturate_naive, saturate_minmax, saturate_bits] {
+        let mut out = [0i16; 10];
+        saturate(&EDGES, &mut out);
+        assert_eq!(out[..], expected[..]);
+    }
+}
+
+#[test]
+fn saturators_agree_on_a_loud_signal() {
+    let mut rng = Lcg(7);
+    let samples: Vec<i32> = (0..10_000)
+        .map(|_| ((rng.next() >> 48) as i16 as i32) * 3 / 2)
+        .collect();
+    let mut expected = vec![0i16; samples.len()];
+    saturate_naive(&samples, &mut expected);
+    assert!(expected.contains(&i16::MAX) && expected.contains(&i16::MIN));
+    for saturate in [saturate_minmax, saturate_bits] {
+        let mut out = vec![0i16; samples.len()];
+        saturate(&samples, &mut out);
+        assert_eq!(out, expected);
+    }
+}
+
+#[test]
+#[should_panic(expected = "out must be as long as samples")]
+fn mismatched_lengths_panic() {
+    saturate_bits(&[1, 2, 3], &mut [0; 2]);
+}
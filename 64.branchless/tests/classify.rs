@@ -0,0 +1,59 @@
+use branchless::classify::{
+    bucket_chain, bucket_count, bucket_lut, histogram, BUCKETS, MAX_READING, THRESHOLDS,
+};
+
+#[test]
+fn all_three_agree_on_every_reading() {
+    for r in 0..=MAX_READING {
+        let b = bucket_chain(r);
+        assert_eq!(bucket_count(r), b, "count({})", r);
+        assert_eq!(bucket_lut(r), b, "lut({})", r);
+    }
+}
+
+#[test]
+fn thresholds_start_their_bucket() {
+    assert_eq!(bucket_lut(0), 0);
+    for (i, &t) in THRESHOLDS.iter().enumerate() {
+        assert_eq!(bucket_chain(t - 1), i);
+        assert_eq!(bucket_chain(t), i + 1);
+        assert_eq!(bucket_lut(t - 1), i);
+        assert_eq!(bucket_lut(t), i + 1);
+    }
+    assert_eq!(bucket_lut(MAX_READING), BUCKETS - 1);
+}
+
+#[test]
+fn out_of_range_readings_go_in_the_last_bucket() {
+    for r in [MAX_READING + 1, 0x1000, 0x8000, u16::MAX] {
+        assert_eq!(bucket_lut(r), BUCKETS - 1);
+        assert_eq!(bucket_chain(r), BUCKETS - 1);
+        assert_eq!(bucket_count(r), BUCKETS - 1);
+    }
+}
+
+#[test]
+fn histograms_agree_and_count_every_reading() {
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    let readings: Vec<u16> = (0..50_000)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 52) as u16
+        })
+        .collect();
+    let reference = histogram(&readings, bucket_chain);
+    assert_eq!(reference.iter().sum::<u32>(), readings.len() as u32);
+    assert!(reference.iter().all(|&c| c > 0));
+    assert_eq!(histogram(&readings, bucket_count), reference);
+    assert_eq!(histogram(&readings, bucket_lut), reference);
+    let mut sorted = readings;
+    sorted.sort_unstable();
+    assert_eq!(histogram(&sorted, bucket_lut), reference);
+}
+
+#[test]
+fn empty_histogram() {
+    assert_eq!(histogram(&[], bucket_lut), [0; BUCKETS]);
+}
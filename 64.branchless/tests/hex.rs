@@ -0,0 +1,63 @@
+use branchless::hex::{encode_branchless, encode_lut16, encode_lut256, encode_naive, to_hex};
+
+type Encode = fn(&[u8], &mut [u8]);
+
+const ENCODERS: [(&str, Encode); 4] = [
+    ("naive", encode_naive),
+    ("branchless", encode_branchless),
+    ("lut16", encode_lut16),
+    ("lut256", encode_lut256),
+];
+
+#[test]
+fn every_byte_matches_format() {
+    let every_byte: Vec<u8> = (0..=255).collect();
+    let expected: String = every_byte.iter().map(|b| format!("{:02x}", b)).collect();
+    for (label, encode) in ENCODERS {
+        let mut out = vec![0; 512];
+        encode(&every_byte, &mut out);
+        assert_eq!(out, expected.as_bytes(), "{}", label);
+    }
+}
+
+#[test]
+fn known_bytes() {
+    assert_eq!(to_hex(&[0x00, 0x7f, 0xa5, 0xff]), "007fa5ff");
+    assert_eq!(to_hex(&[0x09, 0x0a, 0x90, 0xa0]), "090a90a0");
+}
+
+#[test]
+fn empty_input_is_accepted() {
+    for (_, encode) in ENCODERS {
+        encode(&[], &mut []);
+    }
+    assert_eq!(to_hex(&[]), "");
+}
+
+#[test]
+fn random_payloads_agree_with_naive() {
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    for len in [1, 7, 64, 1000] {
+        let bytes: Vec<u8> = (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (state >> 56) as u8
+            })
+            .collect();
+        let mut expected = vec![0; 2 * len];
+        encode_naive(&bytes, &mut expected);
+        for (label, encode) in &ENCODERS[1..] {
+            let mut out = vec![0; 2 * len];
+            encode(&bytes, &mut out);
+            assert_eq!(out, expected, "{} on {} bytes", label, len);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "out must be twice as long as bytes")]
+fn short_output_panics() {
+    encode_lut256(&[1, 2], &mut [0; 3]);
+}
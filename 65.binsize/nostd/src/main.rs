@@ -0,0 +1,108 @@
+// The frame line from src/frame.rs again, without std
+//
+// There is no `println!` and no runtime setup: the C library's startup
+// code calls `main` and `write` sends the bytes to stdout. What remains
+// in the binary is COBS, the digit loop and the C runtime's own startup,
+// which is the floor the other binaries are compared against in
+// `cargo xtask size-report`. Builds for Linux, where libc is linked anyway.
+
+#![no_std]
+#![no_main]
+
+use core::panic::PanicInfo;
+
+const READING: [u8; 7] = [0x07, 0x66, 0x08, 0xC4, 0x0B, 0x00, 0x00];
+const FRAME_CAPACITY: usize = cobs::max_encoded_len(READING.len());
+
+// no_std binaries don't link libc by default
+#[link(name = "c")]
+extern "C" {
+    fn write(fd: i32, buf: *const u8, count: usize) -> isize;
+    fn abort() -> !;
+}
+
+struct Line {
+    buf: [u8; 96],
+    len: usize,
+}
+
+impl Line {
+    fn push(&mut self, bytes: &[u8]) {
+        let n = bytes.len().min(self.buf.len() - self.len);
+        self.buf[self.len..self.len + n].copy_from_slice(&bytes[..n]);
+        self.len += n;
+    }
+
+    fn push_uint(&mut self, mut value: u32) {
+        let mut digits = [0u8; 10];
+        let mut i = digits.len();
+        loop {
+            i -= 1;
+            digits[i] = b'0' + (value % 10) as u8;
+            value /= 10;
+            if value == 0 {
+                break;
+            }
+        }
+        self.push(&digits[i..]);
+    }
+
+    fn push_hex(&mut self, byte: u8) {
+        const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+        self.push(&[DIGITS[(byte >> 4) as usize], DIGITS[(byte & 0xF) as usize]]);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn main(_argc: i32, _argv: *const *const u8) -> i32 {
+    let id = READING[0];
+    let centi = i16::from_le_bytes([READING[1], READING[2]]);
+    let mv = u16::from_le_bytes([READING[3], READING[4]]);
+
+    let mut line = Line {
+        buf: [0; 96],
+        len: 0,
+    };
+    line.push(b"reading ");
+    line.push_uint(id as u32);
+    line.push(b": ");
+    if centi < 0 {
+        line.push(b"-");
+    }
+    let abs = centi.unsigned_abs() as u32;
+    line.push_uint(abs / 100);
+    line.push(b".");
+    line.push(&[b'0' + (abs % 100 / 10) as u8, b'0' + (abs % 10) as u8]);
+    line.push(b" C, battery ");
+    line.push_uint(mv as u32);
+    line.push(b" mV -> frame");
+    let mut frame = [0; FRAME_CAPACITY];
+    let len = match cobs::encode_into(&READING, &mut frame) {
+        Ok(len) => len,
+        Err(_) => return 1,
+    };
+    for &b in &frame[..len] {
+        line.push(b" ");
+        line.push_hex(b);
+    }
+    line.push(b"\n");
+
+    // SAFETY: the pointer and length describe the initialised part of `buf`
+    let written = unsafe { write(1, line.buf.as_ptr(), line.len) };
+    if written == line.len as isize {
+        0
+    } else {
+        1
+    }
+}
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    // SAFETY: abort takes no arguments and never returns
+    unsafe { abort() }
+}
+
+// The precompiled `core` is built for unwinding and still names this
+// symbol in unoptimised builds. With panic = "abort" it is never called
+#[no_mangle]
+pub extern "C" fn rust_eh_personality() {}
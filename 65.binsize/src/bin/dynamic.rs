@@ -0,0 +1,11 @@
+// The same eight sensors through `summarize_dyn`: one copy of the loop
+fn main() {
+    let samples = std::env::args().count() as u32 * 1000;
+    // As if the sensor list came from a configuration file
+    let sensors = std::hint::black_box(binsize::sensors::all_dyn());
+    let total: i64 = binsize::sensors::summarize_all_dyn(&sensors, samples)
+        .iter()
+        .map(|s| s.median + s.jumps as i64)
+        .sum();
+    std::process::exit((total & 0x7F) as i32);
+}
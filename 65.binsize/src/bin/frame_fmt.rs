@@ -0,0 +1,4 @@
+// The frame line through `format!`: float formatting and `{:02X}` padding
+fn main() {
+    println!("{}", binsize::frame::line_fmt());
+}
@@ -0,0 +1,12 @@
+// The same line written by hand and sent with `write_all`, no `format!`
+use std::io::Write;
+
+use binsize::frame::{line_manual, Line};
+
+fn main() {
+    let mut line = Line::new();
+    line_manual(&mut line);
+    line.push(b"\n");
+    // Nothing useful to do if stdout is closed
+    let _ = std::io::stdout().write_all(line.as_bytes());
+}
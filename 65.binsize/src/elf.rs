@@ -0,0 +1,162 @@
+// Just enough ELF64 to list a binary's sections, like `size -A`
+//
+//   0x00  e_ident    7F 'E' 'L' 'F', class 2 = 64-bit, data 1 = little endian
+//   0x28  e_shoff    offset of the section header table
+//   0x3A  e_shentsize, e_shnum, e_shstrndx
+//
+// Each 64-byte section header gives a name (an offset into the section
+// name table), a type, flags and a size. The flags say whether a section
+// is loaded into memory (ALLOC), writable (WRITE) or code (EXECINSTR).
+
+use std::error::Error;
+use std::fmt;
+
+const SHF_WRITE: u64 = 0x1;
+const SHF_ALLOC: u64 = 0x2;
+const SHF_EXECINSTR: u64 = 0x4;
+const SHT_NOBITS: u32 = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElfError {
+    NotElf,
+    // Only 64-bit little-endian files, i.e. x86-64 and aarch64 Linux
+    Unsupported { class: u8, data: u8 },
+    Truncated,
+}
+
+impl fmt::Display for ElfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElfError::NotElf => write!(f, "not an ELF file"),
+            ElfError::Unsupported { class, data } => write!(
+                f,
+                "unsupported ELF class {} / data encoding {}: need 64-bit little endian",
+                class, data
+            ),
+            ElfError::Truncated => write!(f, "ELF file is truncated"),
+        }
+    }
+}
+
+impl Error for ElfError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub name: String,
+    pub size: u64,
+    flags: u64,
+    kind: u32,
+}
+
+impl Section {
+    // Part of the running program, as opposed to symbols or debug info
+    pub fn is_loaded(&self) -> bool {
+        self.flags & SHF_ALLOC != 0
+    }
+
+    pub fn is_code(&self) -> bool {
+        self.flags & SHF_EXECINSTR != 0
+    }
+
+    pub fn is_writable(&self) -> bool {
+        self.flags & SHF_WRITE != 0
+    }
+
+    // Like .bss: takes memory at run time but no bytes in the file
+    pub fn is_zero_filled(&self) -> bool {
+        self.kind == SHT_NOBITS
+    }
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> Result<u16, ElfError> {
+    let b = bytes.get(at..at + 2).ok_or(ElfError::Truncated)?;
+    Ok(u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> Result<u32, ElfError> {
+    let b = bytes.get(at..at + 4).ok_or(ElfError::Truncated)?;
+    Ok(u32::from_le_bytes(b.try_into().expect("4 bytes")))
+}
+
+fn u64_at(bytes: &[u8], at: usize) -> Result<u64, ElfError> {
+    let b = bytes.get(at..at + 8).ok_or(ElfError::Truncated)?;
+    Ok(u64::from_le_bytes(b.try_into().expect("8 bytes")))
+}
+
+pub fn sections(bytes: &[u8]) -> Result<Vec<Section>, ElfError> {
+    if bytes.get(..4) != Some(b"\x7FELF") {
+        return Err(ElfError::NotElf);
+    }
+    let (class, data) = (bytes.get(4), bytes.get(5));
+    if (class, data) != (Some(&2), Some(&1)) {
+        return Err(ElfError::Unsupported {
+            class: class.copied().unwrap_or(0),
+            data: data.copied().unwrap_or(0),
+        });
+    }
+    let table = u64_at(bytes, 0x28)? as usize;
+    let entry_size = u16_at(bytes, 0x3A)? as usize;
+    let count = u16_at(bytes, 0x3C)? as usize;
+    let names_index = u16_at(bytes, 0x3E)? as usize;
+    let header = |i: usize| table + i * entry_size;
+
+    let names_offset = u64_at(bytes, header(names_index) + 24)? as usize;
+    let mut sections = Vec::with_capacity(count);
+    // Entry 0 is always the empty null section
+    for i in 1..count {
+        let at = header(i);
+        let name_at = names_offset + u32_at(bytes, at)? as usize;
+        let name = bytes
+            .get(name_at..)
+            .and_then(|rest| rest.split(|&b| b == 0).next())
+            .ok_or(ElfError::Truncated)?;
+        sections.push(Section {
+            name: String::from_utf8_lossy(name).into_owned(),
+            kind: u32_at(bytes, at + 4)?,
+            flags: u64_at(bytes, at + 8)?,
+            size: u64_at(bytes, at + 32)?,
+        });
+    }
+    Ok(sections)
+}
+
+// Loaded sections grouped the way `size` does, with unwind tables apart
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub text: u64,
+    pub rodata: u64,
+    // .eh_frame and .eh_frame_hdr: tables for unwinding panics
+    pub unwind: u64,
+    pub data: u64,
+    pub bss: u64,
+    // Symbols and debug info: in the file, not in memory
+    pub not_loaded: u64,
+}
+
+impl Summary {
+    pub fn of(sections: &[Section]) -> Summary {
+        let mut s = Summary::default();
+        for section in sections {
+            let slot = if !section.is_loaded() {
+                &mut s.not_loaded
+            } else if section.name.starts_with(".eh_frame") {
+                &mut s.unwind
+            } else if section.is_code() {
+                &mut s.text
+            } else if section.is_zero_filled() {
+                &mut s.bss
+            } else if section.is_writable() {
+                &mut s.data
+            } else {
+                &mut s.rodata
+            };
+            *slot += section.size;
+        }
+        s
+    }
+
+    // What a device has to store: everything loaded except .bss
+    pub fn flash(&self) -> u64 {
+        self.text + self.rodata + self.unwind + self.data
+    }
+}
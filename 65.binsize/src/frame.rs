@@ -0,0 +1,116 @@
+// The same output line built two ways
+//
+//   reading 7: 21.50 C, battery 3012 mV -> frame 06 07 66 08 C4 0B 01 01
+//
+// `line_fmt` uses `format!` with a float and `{:02X}`; `line_manual`
+// writes digits into a byte buffer by hand. `format!` pulls in the
+// formatting machinery: `Formatter`, padding, and the float-to-decimal
+// code, which is several kilobytes on its own. The `frame_fmt` and
+// `frame_io` binaries each use one of them, and the no_std variant in
+// nostd/ repeats `line_manual` without std at all.
+
+// Sensor 7, 21.50 C (centi-degrees 2150), 3012 mV, status 0
+pub const READING: [u8; 7] = [0x07, 0x66, 0x08, 0xC4, 0x0B, 0x00, 0x00];
+
+pub const FRAME_CAPACITY: usize = cobs::max_encoded_len(READING.len());
+
+pub fn encode(frame: &mut [u8; FRAME_CAPACITY]) -> &[u8] {
+    let len = cobs::encode_into(&READING, frame).expect("sized by max_encoded_len");
+    &frame[..len]
+}
+
+fn fields() -> (u8, i16, u16) {
+    let id = READING[0];
+    let centi = i16::from_le_bytes([READING[1], READING[2]]);
+    let mv = u16::from_le_bytes([READING[3], READING[4]]);
+    (id, centi, mv)
+}
+
+pub fn line_fmt() -> String {
+    let (id, centi, mv) = fields();
+    let mut frame = [0; FRAME_CAPACITY];
+    let hex: Vec<String> = encode(&mut frame)
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect();
+    format!(
+        "reading {}: {:.2} C, battery {} mV -> frame {}",
+        id,
+        centi as f32 / 100.0,
+        mv,
+        hex.join(" ")
+    )
+}
+
+// A tiny writer over a fixed buffer; bytes past the end are dropped
+pub struct Line<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Line<N> {
+    pub fn new() -> Self {
+        Line {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        let n = bytes.len().min(N - self.len);
+        self.buf[self.len..self.len + n].copy_from_slice(&bytes[..n]);
+        self.len += n;
+    }
+
+    pub fn push_uint(&mut self, mut value: u32) {
+        let mut digits = [0u8; 10];
+        let mut i = digits.len();
+        loop {
+            i -= 1;
+            digits[i] = b'0' + (value % 10) as u8;
+            value /= 10;
+            if value == 0 {
+                break;
+            }
+        }
+        self.push(&digits[i..]);
+    }
+
+    pub fn push_hex(&mut self, byte: u8) {
+        const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+        self.push(&[DIGITS[(byte >> 4) as usize], DIGITS[(byte & 0xF) as usize]]);
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+impl<const N: usize> Default for Line<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Fixed-point: centi-degrees print as whole part, '.', two digits
+pub fn line_manual(line: &mut Line<96>) {
+    let (id, centi, mv) = fields();
+    line.push(b"reading ");
+    line.push_uint(id as u32);
+    line.push(b": ");
+    if centi < 0 {
+        line.push(b"-");
+    }
+    let abs = centi.unsigned_abs() as u32;
+    line.push_uint(abs / 100);
+    line.push(b".");
+    line.push(&[b'0' + (abs % 100 / 10) as u8, b'0' + (abs % 10) as u8]);
+    line.push(b" C, battery ");
+    line.push_uint(mv as u32);
+    line.push(b" mV -> frame");
+    let mut frame = [0; FRAME_CAPACITY];
+    for &b in encode(&mut frame) {
+        line.push(b" ");
+        line.push_hex(b);
+    }
+}
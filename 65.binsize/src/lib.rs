@@ -0,0 +1,13 @@
+// Binary size: what makes a Rust binary large, and how to measure it
+//
+// - `elf`: reads an ELF file's section table and groups it like `size`
+// - `frame`: one output line built with `format!` and by hand
+// - `sensors`: a generic function against its `dyn` twin
+//
+// The binaries in src/bin use one side of each comparison, and
+// `cargo xtask size-report` (xtask/ at the repository root) builds them,
+// and other lessons, under several profiles and prints their sections.
+
+pub mod elf;
+pub mod frame;
+pub mod sensors;
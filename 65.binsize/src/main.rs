@@ -0,0 +1,83 @@
+use binsize::elf::{sections, Summary};
+use binsize::frame::{encode, line_fmt, line_manual, Line, FRAME_CAPACITY};
+use binsize::sensors::{all_dyn, summarize_all_dyn, summarize_all_generic};
+use std::cmp::Reverse;
+use std::error::Error;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    println!("=== Binary Size Examples ===\n");
+
+    // 1. The sections of this binary
+    println!("1. Sections of this binary:");
+    let exe = std::env::current_exe()?;
+    let bytes = std::fs::read(&exe)?;
+    let all = sections(&bytes)?;
+    let mut loaded: Vec<_> = all.iter().filter(|s| s.is_loaded()).collect();
+    loaded.sort_by_key(|s| Reverse(s.size));
+    for section in loaded.iter().take(6) {
+        println!("   {:<20} {:>9} bytes", section.name, section.size);
+    }
+    let summary = Summary::of(&all);
+    println!(
+        "   text {} + rodata {} + unwind {} + data {} = {} bytes to store, bss {}",
+        summary.text,
+        summary.rodata,
+        summary.unwind,
+        summary.data,
+        summary.flash(),
+        summary.bss
+    );
+    println!(
+        "   not loaded (symbols, debug info): {} of {} bytes in the file",
+        summary.not_loaded,
+        bytes.len()
+    );
+
+    // 2. format! against a hand-written line
+    println!("\n2. format! and a hand-written line:");
+    let mut frame = [0; FRAME_CAPACITY];
+    println!("   frame: {:02X?}", encode(&mut frame));
+    let formatted = line_fmt();
+    let mut line = Line::new();
+    line_manual(&mut line);
+    println!("   fmt:    {}", formatted);
+    println!("   manual: {}", String::from_utf8_lossy(line.as_bytes()));
+
+    // 3. Generic against dyn
+    println!("\n3. Generic and dyn summaries:");
+    let generic = summarize_all_generic(10_000);
+    let sensors = all_dyn();
+    let dynamic = summarize_all_dyn(&sensors, 10_000);
+    for (sensor, s) in sensors.iter().zip(&generic) {
+        println!(
+            "   {:<12} min {:>7} median {:>7} max {:>7} jumps {:>5}",
+            sensor.name(),
+            s.min,
+            s.median,
+            s.max,
+            s.jumps
+        );
+    }
+    println!("   summarize_dyn, first sensor: {:?}", dynamic.first());
+
+    // 4. Files that are not 64-bit ELF
+    println!("\n4. Rejected input:");
+    let manifest = include_bytes!("../Cargo.toml");
+    if let Err(e) = sections(manifest) {
+        println!("   Cargo.toml: {}", e);
+    }
+    let mut elf32 = bytes[..64].to_vec();
+    elf32[4] = 1;
+    match sections(&elf32) {
+        Err(e) => println!("   32-bit header: {}", e),
+        Ok(_) => println!("   32-bit header: accepted"),
+    }
+    if let Err(e) = sections(&bytes[..64]) {
+        println!("   header alone: {}", e);
+    }
+
+    println!("\n   To compare binaries and profiles: cargo xtask size-report");
+
+    println!("\n=== End of Binary Size Examples ===");
+    Ok(())
+}
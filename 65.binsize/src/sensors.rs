@@ -0,0 +1,155 @@
+// Monomorphisation: one copy of a generic function per type it is used with
+//
+//   summarize::<Thermistor>  Vec<i16>, sort for i16
+//   summarize::<Light>       Vec<u8>,  sort for u8       8 sensors, 5 sample
+//   summarize::<Pressure>    Vec<u32>, sort for u32      types: 8 loops, 5 sorts
+//   ...
+//   summarize_dyn            Vec<i64>, sort for i64      1 loop, 1 sort
+//
+// Each sensor reports its natural sample type. `summarize::<S>` keeps the
+// samples as `S::Sample`, so the compiler emits its loop for every sensor
+// and `Vec` growth and `sort_unstable` for every sample type. The `dyn`
+// version widens each sample to `i64` behind a vtable call: slower per
+// sample, but compiled once. Results are the same either way.
+
+pub trait Sensor {
+    type Sample: Copy + Ord + Into<i64>;
+
+    fn name(&self) -> &'static str;
+    // Raw value at sample `t`
+    fn read(&self, t: u32) -> Self::Sample;
+}
+
+// The object-safe side: no associated type, samples as i64
+pub trait DynSensor {
+    fn name(&self) -> &'static str;
+    fn read_wide(&self, t: u32) -> i64;
+}
+
+impl<S: Sensor> DynSensor for S {
+    fn name(&self) -> &'static str {
+        Sensor::name(self)
+    }
+
+    fn read_wide(&self, t: u32) -> i64 {
+        self.read(t).into()
+    }
+}
+
+macro_rules! sensors {
+    ($($name:ident: $sample:ty => |$t:ident| $formula:expr;)*) => {
+        $(
+            pub struct $name;
+
+            impl Sensor for $name {
+                type Sample = $sample;
+
+                fn name(&self) -> &'static str {
+                    stringify!($name)
+                }
+
+                fn read(&self, $t: u32) -> $sample {
+                    $formula
+                }
+            }
+        )*
+    };
+}
+
+sensors! {
+    Thermistor: i16 => |t| 2000 + (t % 50) as i16 * 3;
+    Humidity: u16 => |t| 4000 + ((t * 7) % 300) as u16;
+    Pressure: u32 => |t| 101_325 + (t / 3) % 40;
+    Light: u8 => |t| ((t * 13) % 251) as u8;
+    Battery: u16 => |t| 3300 - (t / 100 % 300) as u16;
+    Rssi: i8 => |t| -60 - ((t * 5) % 30) as i8;
+    Co2: u16 => |t| 400 + ((t * t) % 900) as u16;
+    Vibration: i16 => |t| (((t * 31) % 200) as i16 - 100) * 3;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub min: i64,
+    pub median: i64,
+    pub max: i64,
+    // Samples that moved more than 50 from the previous one
+    pub jumps: u32,
+}
+
+pub fn summarize<S: Sensor>(sensor: &S, samples: u32) -> Summary {
+    let mut values: Vec<S::Sample> = Vec::new();
+    let mut jumps = 0;
+    let mut previous: i64 = sensor.read(0).into();
+    for t in 0..samples {
+        let v = sensor.read(t);
+        let wide: i64 = v.into();
+        if (wide - previous).abs() > 50 {
+            jumps += 1;
+        }
+        previous = wide;
+        values.push(v);
+    }
+    values.sort_unstable();
+    let at = |i: usize| values.get(i).copied().map_or(0, Into::into);
+    Summary {
+        min: at(0),
+        median: at(values.len() / 2),
+        max: at(values.len().wrapping_sub(1)),
+        jumps,
+    }
+}
+
+pub fn summarize_dyn(sensor: &dyn DynSensor, samples: u32) -> Summary {
+    let mut values: Vec<i64> = Vec::new();
+    let mut jumps = 0;
+    let mut previous = sensor.read_wide(0);
+    for t in 0..samples {
+        let v = sensor.read_wide(t);
+        if (v - previous).abs() > 50 {
+            jumps += 1;
+        }
+        previous = v;
+        values.push(v);
+    }
+    values.sort_unstable();
+    let at = |i: usize| values.get(i).copied().unwrap_or(0);
+    Summary {
+        min: at(0),
+        median: at(values.len() / 2),
+        max: at(values.len().wrapping_sub(1)),
+        jumps,
+    }
+}
+
+// Each sensor through the generic path: eight instantiations
+pub fn summarize_all_generic(samples: u32) -> [Summary; 8] {
+    [
+        summarize(&Thermistor, samples),
+        summarize(&Humidity, samples),
+        summarize(&Pressure, samples),
+        summarize(&Light, samples),
+        summarize(&Battery, samples),
+        summarize(&Rssi, samples),
+        summarize(&Co2, samples),
+        summarize(&Vibration, samples),
+    ]
+}
+
+// `sensors` comes from the caller, so the compiler can't see which types
+// are behind the references and turn the calls back into direct ones
+pub fn summarize_all_dyn(sensors: &[&dyn DynSensor], samples: u32) -> Vec<Summary> {
+    sensors.iter().map(|s| summarize_dyn(*s, samples)).collect()
+}
+
+pub fn all_dyn() -> [&'static dyn DynSensor; 8] {
+    [
+        &Thermistor,
+        &Humidity,
+        &Pressure,
+        &Light,
+        &Battery,
+        &Rssi,
+        &Co2,
+        &Vibration,
+    ]
+}
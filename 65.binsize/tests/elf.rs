@@ -0,0 +1,59 @@
+use binsize::elf::{sections, ElfError, Summary};
+
+// The test binary itself is a 64-bit little-endian ELF file
+fn this_binary() -> Vec<u8> {
+    std::fs::read(std::env::current_exe().unwrap()).unwrap()
+}
+
+#[test]
+fn lists_the_sections_of_a_real_binary() {
+    let bytes = this_binary();
+    let all = sections(&bytes).unwrap();
+    let text = all.iter().find(|s| s.name == ".text").unwrap();
+    assert!(text.is_loaded() && text.is_code() && !text.is_writable());
+    assert!(text.size > 0);
+    let bss = all.iter().find(|s| s.name == ".bss").unwrap();
+    assert!(bss.is_zero_filled() && bss.is_writable());
+    let symtab = all.iter().find(|s| s.name == ".symtab").unwrap();
+    assert!(!symtab.is_loaded());
+    assert!(all.iter().all(|s| !s.name.is_empty()));
+}
+
+#[test]
+fn summary_puts_every_section_in_one_group() {
+    let all = sections(&this_binary()).unwrap();
+    let s = Summary::of(&all);
+    let total: u64 = all.iter().map(|s| s.size).sum();
+    assert_eq!(
+        s.text + s.rodata + s.unwind + s.data + s.bss + s.not_loaded,
+        total
+    );
+    assert_eq!(s.flash(), s.text + s.rodata + s.unwind + s.data);
+    assert!(s.text > 0 && s.rodata > 0 && s.unwind > 0);
+    assert_eq!(Summary::of(&[]), Summary::default());
+}
+
+#[test]
+fn rejects_what_it_cannot_read() {
+    let bytes = this_binary();
+    assert_eq!(
+        sections(include_bytes!("../Cargo.toml")),
+        Err(ElfError::NotElf)
+    );
+    assert_eq!(sections(&[]), Err(ElfError::NotElf));
+    assert_eq!(
+        sections(b"\x7FELF"),
+        Err(ElfError::Unsupported { class: 0, data: 0 })
+    );
+    let mut elf32 = bytes[..64].to_vec();
+    elf32[4] = 1;
+    let err = sections(&elf32).unwrap_err();
+    assert_eq!(err, ElfError::Unsupported { class: 1, data: 1 });
+    assert_eq!(
+        err.to_string(),
+        "unsupported ELF class 1 / data encoding 1: need 64-bit little endian"
+    );
+    // The header points at a section table that is not there
+    assert_eq!(sections(&bytes[..64]), Err(ElfError::Truncated));
+    assert_eq!(sections(&bytes[..40]), Err(ElfError::Truncated));
+}
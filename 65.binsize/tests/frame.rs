@@ -0,0 +1,47 @@
+use binsize::frame::{encode, line_fmt, line_manual, Line, FRAME_CAPACITY};
+use std::process::Command;
+
+const EXPECTED: &str = "reading 7: 21.50 C, battery 3012 mV -> frame 06 07 66 08 C4 0B 01 01";
+
+#[test]
+fn both_ways_build_the_same_line() {
+    let mut frame = [0; FRAME_CAPACITY];
+    assert_eq!(
+        encode(&mut frame),
+        [0x06, 0x07, 0x66, 0x08, 0xC4, 0x0B, 0x01, 0x01]
+    );
+    assert_eq!(line_fmt(), EXPECTED);
+    let mut line = Line::new();
+    line_manual(&mut line);
+    assert_eq!(line.as_bytes(), EXPECTED.as_bytes());
+}
+
+#[test]
+fn the_two_frame_binaries_print_the_same_line() {
+    for exe in [
+        env!("CARGO_BIN_EXE_frame_fmt"),
+        env!("CARGO_BIN_EXE_frame_io"),
+    ] {
+        let out = Command::new(exe).output().unwrap();
+        assert!(out.status.success(), "{}", exe);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            format!("{}\n", EXPECTED)
+        );
+    }
+}
+
+#[test]
+fn line_writes_digits_and_drops_overflow() {
+    let mut line: Line<16> = Line::default();
+    line.push_uint(0);
+    line.push(b" ");
+    line.push_uint(u32::MAX);
+    line.push(b" ");
+    line.push_hex(0x0A);
+    assert_eq!(line.as_bytes(), b"0 4294967295 0A");
+    line.push(b"xyz");
+    assert_eq!(line.as_bytes(), b"0 4294967295 0Ax");
+    line.push_hex(0xFF);
+    assert_eq!(line.as_bytes().len(), 16);
+}
@@ -0,0 +1,59 @@
+use binsize::sensors::{
+    all_dyn, summarize, summarize_all_dyn, summarize_all_generic, summarize_dyn, Summary,
+    Thermistor,
+};
+
+#[test]
+fn generic_and_dyn_agree() {
+    for samples in [1, 10, 1000, 10_000] {
+        let generic = summarize_all_generic(samples);
+        let dynamic = summarize_all_dyn(&all_dyn(), samples);
+        assert_eq!(generic[..], dynamic[..], "{} samples", samples);
+    }
+}
+
+#[test]
+fn thermistor_summary() {
+    let s = summarize(&Thermistor, 1000);
+    assert_eq!(
+        s,
+        Summary {
+            min: 2000,
+            median: 2075,
+            max: 2147,
+            // Every wrap from 2147 back to 2000
+            jumps: 19,
+        }
+    );
+    assert_eq!(summarize_dyn(&Thermistor, 1000), s);
+}
+
+#[test]
+fn no_samples_is_all_zero() {
+    let zero = Summary {
+        min: 0,
+        median: 0,
+        max: 0,
+        jumps: 0,
+    };
+    assert_eq!(summarize(&Thermistor, 0), zero);
+    assert_eq!(summarize_dyn(&Thermistor, 0), zero);
+}
+
+#[test]
+fn sensors_in_table_order() {
+    let names: Vec<_> = all_dyn().iter().map(|s| s.name()).collect();
+    assert_eq!(
+        names,
+        [
+            "Thermistor",
+            "Humidity",
+            "Pressure",
+            "Light",
+            "Battery",
+            "Rssi",
+            "Co2",
+            "Vibration"
+        ]
+    );
+}
@@ -0,0 +1,83 @@
+// Recording, querying and merging latency histograms
+//
+//   cargo bench               # every group
+//   cargo bench -- record     # one group
+//
+// `record` compares a histogram with pushing every sample into a `Vec`
+// and sorting once for the percentile, the approach it replaces.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use histogram::Histogram;
+use std::hint::black_box;
+
+const N: usize = 64 * 1024;
+
+// Latencies from 100 ns to about 6.5 ms, spread over every octave
+fn latencies(count: usize) -> Vec<u64> {
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    (0..count)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            100 + ((state >> 40) >> (state % 16))
+        })
+        .collect()
+}
+
+fn record(c: &mut Criterion) {
+    let values = latencies(N);
+    let mut group = c.benchmark_group("record");
+    group.throughput(Throughput::Elements(N as u64));
+    group.bench_function("histogram", |b| {
+        b.iter(|| {
+            let mut h = Histogram::new();
+            values.iter().for_each(|&v| h.record(v));
+            h.value_at_percentile(99.0)
+        })
+    });
+    group.bench_function("vec_sort", |b| {
+        b.iter(|| {
+            let mut all = Vec::new();
+            values.iter().for_each(|&v| all.push(v));
+            all.sort_unstable();
+            all[all.len() * 99 / 100]
+        })
+    });
+    group.finish();
+}
+
+fn query(c: &mut Criterion) {
+    let mut h = Histogram::new();
+    latencies(N).iter().for_each(|&v| h.record(v));
+    let mut group = c.benchmark_group("query");
+    group.bench_function("p99", |b| {
+        b.iter(|| black_box(&h).value_at_percentile(99.0))
+    });
+    group.bench_function("percentiles", |b| b.iter(|| black_box(&h).percentiles()));
+    group.finish();
+}
+
+fn merge(c: &mut Criterion) {
+    let values = latencies(N);
+    let parts: Vec<Histogram> = values
+        .chunks(N / 8)
+        .map(|chunk| {
+            let mut h = Histogram::new();
+            chunk.iter().for_each(|&v| h.record(v));
+            h
+        })
+        .collect();
+    c.bench_function("merge/8_parts", |b| {
+        b.iter(|| {
+            let mut total = Histogram::new();
+            for part in &parts {
+                total.merge(part).expect("same precision");
+            }
+            total
+        })
+    });
+}
+
+criterion_group!(benches, record, query, merge);
+criterion_main!(benches);
@@ -0,0 +1,287 @@
+// A log-linear latency histogram in the style of HdrHistogram
+//
+//   precision_bits = 3: 8 exact buckets, then 4 buckets per power of two
+//
+//   0 1 2 3 4 5 6 7 | 8-9 10-11 12-13 14-15 | 16-19 20-23 24-27 28-31 | 32-39 ...
+//   width 1           width 2                  width 4                   width 8
+//
+// Values below 2^bits each get their own bucket. Above that, every power
+// of two is split into 2^(bits-1) equal buckets, so a bucket is never
+// wider than 2^(1-bits) of the values in it. Queries return the highest
+// value of the bucket, so a reported percentile is never below the true
+// one and at most 2^(1-bits) above it: 0.78% for the default 8 bits.
+//
+// Recording is a leading-zero count, a shift and an increment. The counts
+// grow with the largest value seen: with 8 bits, latencies up to one
+// second in nanoseconds need about 3,000 buckets, 24 KiB, however many
+// samples are recorded.
+
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+pub const DEFAULT_PRECISION_BITS: u32 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistogramError {
+    // Bucket boundaries only line up between equal precisions
+    PrecisionMismatch { ours: u32, theirs: u32 },
+}
+
+impl fmt::Display for HistogramError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistogramError::PrecisionMismatch { ours, theirs } => write!(
+                f,
+                "precision mismatch: {} bits merged into {} bits",
+                theirs, ours
+            ),
+        }
+    }
+}
+
+impl Error for HistogramError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    bits: u32,
+    // Only as long as the highest bucket recorded so far
+    counts: Vec<u64>,
+    total: u64,
+    // Exact, for the mean
+    sum: u128,
+    min: u64,
+    max: u64,
+}
+
+// One non-empty bucket: every value in `low..=high` was counted here
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bucket {
+    pub low: u64,
+    pub high: u64,
+    pub count: u64,
+}
+
+// The usual latency summary, in nanoseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Percentiles {
+    pub count: u64,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub p999: u64,
+    pub max: u64,
+}
+
+impl fmt::Display for Percentiles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let d = Duration::from_nanos;
+        write!(
+            f,
+            "p50 {:.2?}  p99 {:.2?}  p99.9 {:.2?}  max {:.2?}",
+            d(self.p50),
+            d(self.p99),
+            d(self.p999),
+            d(self.max)
+        )
+    }
+}
+
+// ceil(p% of total) in integers: 99.9 / 100.0 * 1000.0 is 999.0000000000001
+// in floating point, which would round the rank up to 1000. `p` is used
+// to four decimal places, enough for p99.9999
+pub fn rank(p: f64, total: u64) -> u64 {
+    let millionths = (p.clamp(0.0, 100.0) * 10_000.0).round() as u128;
+    let rank = (millionths * total as u128).div_ceil(1_000_000) as u64;
+    rank.clamp(1, total.max(1))
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Histogram::new()
+    }
+}
+
+impl Histogram {
+    pub fn new() -> Histogram {
+        Histogram::with_precision(DEFAULT_PRECISION_BITS)
+    }
+
+    // Panics unless 1 <= bits <= 16; 16 bits already means 0.003% error
+    pub fn with_precision(bits: u32) -> Histogram {
+        assert!((1..=16).contains(&bits), "precision_bits must be 1..=16");
+        Histogram {
+            bits,
+            counts: Vec::new(),
+            total: 0,
+            sum: 0,
+            min: u64::MAX,
+            max: 0,
+        }
+    }
+
+    pub fn precision_bits(&self) -> u32 {
+        self.bits
+    }
+
+    fn index(&self, value: u64) -> usize {
+        let bits = self.bits;
+        if value < 1 << bits {
+            return value as usize;
+        }
+        let half = 1usize << (bits - 1);
+        let exponent = 63 - value.leading_zeros();
+        let shift = exponent - bits + 1;
+        // Top `bits` bits of the value, the highest always set
+        let mantissa = (value >> shift) as usize;
+        (1 << bits) + (exponent - bits) as usize * half + (mantissa - half)
+    }
+
+    fn bounds(&self, index: usize) -> (u64, u64) {
+        let bits = self.bits;
+        if index < 1 << bits {
+            return (index as u64, index as u64);
+        }
+        let half = 1usize << (bits - 1);
+        let above = index - (1 << bits);
+        let shift = (above / half) as u32 + 1;
+        let low = ((half + above % half) as u64) << shift;
+        (low, low + ((1u64 << shift) - 1))
+    }
+
+    pub fn record(&mut self, value: u64) {
+        self.record_n(value, 1);
+    }
+
+    pub fn record_n(&mut self, value: u64, count: u64) {
+        if count == 0 {
+            return;
+        }
+        let index = self.index(value);
+        if index >= self.counts.len() {
+            self.counts.resize(index + 1, 0);
+        }
+        self.counts[index] += count;
+        self.total += count;
+        self.sum += value as u128 * count as u128;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    // Saturates at u64::MAX nanoseconds, about 584 years
+    pub fn record_duration(&mut self, elapsed: Duration) {
+        self.record(u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX));
+    }
+
+    pub fn count(&self) -> u64 {
+        self.total
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    // Exact, not bucketed; 0 when empty
+    pub fn min(&self) -> u64 {
+        if self.is_empty() {
+            0
+        } else {
+            self.min
+        }
+    }
+
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.is_empty() {
+            0.0
+        } else {
+            self.sum as f64 / self.total as f64
+        }
+    }
+
+    // Nearest rank: the smallest recorded value with at least `p` percent
+    // of the samples at or below it, rounded up to its bucket's highest
+    // value. 0 when empty
+    pub fn value_at_percentile(&self, p: f64) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+        let rank = rank(p, self.total);
+        let mut seen = 0;
+        for (index, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                let (_, high) = self.bounds(index);
+                return high.clamp(self.min, self.max);
+            }
+        }
+        self.max
+    }
+
+    pub fn percentiles(&self) -> Percentiles {
+        Percentiles {
+            count: self.total,
+            p50: self.value_at_percentile(50.0),
+            p90: self.value_at_percentile(90.0),
+            p99: self.value_at_percentile(99.0),
+            p999: self.value_at_percentile(99.9),
+            max: self.max,
+        }
+    }
+
+    // Adds every sample of `other`, as if they had been recorded here
+    pub fn merge(&mut self, other: &Histogram) -> Result<(), HistogramError> {
+        if other.bits != self.bits {
+            return Err(HistogramError::PrecisionMismatch {
+                ours: self.bits,
+                theirs: other.bits,
+            });
+        }
+        if other.is_empty() {
+            return Ok(());
+        }
+        if other.counts.len() > self.counts.len() {
+            self.counts.resize(other.counts.len(), 0);
+        }
+        for (ours, theirs) in self.counts.iter_mut().zip(&other.counts) {
+            *ours += theirs;
+        }
+        self.total += other.total;
+        self.sum += other.sum;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        Ok(())
+    }
+
+    // Forgets all samples but keeps the allocation
+    pub fn clear(&mut self) {
+        self.counts.clear();
+        self.total = 0;
+        self.sum = 0;
+        self.min = u64::MAX;
+        self.max = 0;
+    }
+
+    pub fn buckets(&self) -> impl Iterator<Item = Bucket> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(index, &count)| {
+                let (low, high) = self.bounds(index);
+                Bucket { low, high, count }
+            })
+    }
+
+    // Buckets allocated, which depends on the largest value only
+    pub fn bucket_count(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn memory_bytes(&self) -> usize {
+        self.counts.capacity() * std::mem::size_of::<u64>()
+    }
+}
@@ -0,0 +1,14 @@
+// Latency histograms without sorting every sample
+//
+// Percentiles over a `Vec` of latencies are exact, but memory grows with
+// every sample and each query sorts. An HDR-style histogram keeps a count
+// per logarithmic bucket instead: fixed memory, constant-time recording,
+// bounded relative error, and two histograms merge by adding counts, so
+// each thread can record into its own and the results are combined after.
+//
+// - `histogram`: `Histogram`, `Percentiles`, `Bucket`, `HistogramError`,
+//   and `rank`, the nearest-rank position shared with exact references
+
+pub mod histogram;
+
+pub use histogram::{rank, Bucket, Histogram, HistogramError, Percentiles, DEFAULT_PRECISION_BITS};
@@ -0,0 +1,223 @@
+use histogram::{rank, Histogram, DEFAULT_PRECISION_BITS};
+use std::hint::black_box;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const N: usize = 1_000_000;
+
+fn time<T>(reps: u32, mut f: impl FnMut() -> T) -> Duration {
+    (0..reps)
+        .map(|_| {
+            let started = Instant::now();
+            black_box(f());
+            started.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+// xorshift64: the same data on every run
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    // Uniform in [0, 1)
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+// Latencies in nanoseconds, shaped like real ones
+fn uniform(rng: &mut Rng, n: usize) -> Vec<u64> {
+    (0..n).map(|_| 1_000 + rng.next() % 999_000).collect()
+}
+
+// Spread evenly over five decades, 100 ns to 10 ms
+fn log_uniform(rng: &mut Rng, n: usize) -> Vec<u64> {
+    (0..n)
+        .map(|_| (100.0 * 10f64.powf(5.0 * rng.unit())) as u64)
+        .collect()
+}
+
+// 99% cache hits near 2 µs, 1% misses near 5 ms: all the interest is in the tail
+fn bimodal(rng: &mut Rng, n: usize) -> Vec<u64> {
+    (0..n)
+        .map(|_| {
+            if rng.next().is_multiple_of(100) {
+                4_000_000 + rng.next() % 2_000_000
+            } else {
+                1_500 + rng.next() % 1_000
+            }
+        })
+        .collect()
+}
+
+// Nearest-rank percentile of sorted values, the exact reference
+fn exact(sorted: &[u64], p: f64) -> u64 {
+    sorted[rank(p, sorted.len() as u64) as usize - 1]
+}
+
+const PERCENTILES: [f64; 6] = [50.0, 90.0, 99.0, 99.9, 99.99, 100.0];
+
+fn main() {
+    println!("=== Latency Histogram Examples ===\n");
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+
+    // 1. Buckets
+    println!("1. Log-linear buckets ({} bits):", DEFAULT_PRECISION_BITS);
+    for value in [7, 255, 256, 1_000, 1_000_000, 1_000_000_000] {
+        let mut h = Histogram::new();
+        h.record(value);
+        let b = h.buckets().next().expect("one bucket");
+        println!(
+            "   {:>13} ns -> bucket {:>5}: {:>13} ..= {:<13} width {}",
+            value,
+            h.bucket_count() - 1,
+            b.low,
+            b.high,
+            b.high - b.low + 1
+        );
+    }
+    let mut h = Histogram::new();
+    h.record(1_000_000_000);
+    println!(
+        "   up to 1 s: {} buckets, {} KiB, for any number of samples",
+        h.bucket_count(),
+        h.memory_bytes() / 1024
+    );
+
+    // 2. Accuracy against sorting every sample
+    println!("\n2. Accuracy against exact percentiles ({} samples):", N);
+    // tests/histogram.rs checks the error bound and every edge case below
+    let bound = 1.0 / (1u64 << (DEFAULT_PRECISION_BITS - 1)) as f64;
+    println!(
+        "   bound for {} bits: {:.2}%",
+        DEFAULT_PRECISION_BITS,
+        bound * 100.0
+    );
+    let sets = [
+        ("uniform 1 µs - 1 ms", uniform(&mut rng, N)),
+        ("log-uniform 100 ns - 10 ms", log_uniform(&mut rng, N)),
+        ("bimodal 2 µs / 5 ms", bimodal(&mut rng, N)),
+    ];
+    for (name, values) in &sets {
+        let mut h = Histogram::new();
+        for &v in values {
+            h.record(v);
+        }
+        let mut sorted = values.clone();
+        sorted.sort_unstable();
+        let mut worst: f64 = 0.0;
+        for p in PERCENTILES {
+            let (reported, truth) = (h.value_at_percentile(p), exact(&sorted, p));
+            worst = worst.max((reported as f64 - truth as f64) / truth as f64);
+        }
+        println!(
+            "   {:<28} p99 {:>9} vs {:>9}, worst error {:.3}%",
+            name,
+            h.value_at_percentile(99.0),
+            exact(&sorted, 99.0),
+            worst * 100.0
+        );
+    }
+
+    // 3. Merging per-thread histograms
+    println!("\n3. One histogram per thread, merged:");
+    let (_, values) = &sets[2];
+    let parts: Vec<Histogram> = thread::scope(|s| {
+        let workers: Vec<_> = values
+            .chunks(values.len() / 4)
+            .map(|chunk| {
+                s.spawn(move || {
+                    let mut h = Histogram::new();
+                    chunk.iter().for_each(|&v| h.record(v));
+                    h
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|w| w.join().expect("worker panicked"))
+            .collect()
+    });
+    let mut merged = Histogram::new();
+    for part in &parts {
+        merged.merge(part).expect("same precision");
+        println!(
+            "   thread: {:>7} samples, {}",
+            part.count(),
+            part.percentiles()
+        );
+    }
+    println!(
+        "   merged: {:>7} samples, {}",
+        merged.count(),
+        merged.percentiles()
+    );
+    if let Err(e) = merged.merge(&Histogram::with_precision(4)) {
+        println!("   4-bit into 8-bit: {}", e);
+    }
+
+    // 4. Edge cases
+    println!("\n4. Edge cases:");
+    let empty = Histogram::new();
+    println!(
+        "   empty: p99 {}, min {}, mean {}",
+        empty.value_at_percentile(99.0),
+        empty.min(),
+        empty.mean()
+    );
+    let mut h = Histogram::new();
+    h.record(0);
+    h.record(u64::MAX);
+    println!(
+        "   0 and u64::MAX: p50 {}, p100 {}",
+        h.value_at_percentile(50.0),
+        h.value_at_percentile(100.0)
+    );
+    let mut h = Histogram::new();
+    h.record_n(5_000, 999);
+    h.record_n(9_000_000, 1);
+    println!(
+        "   999 x 5 µs + one 9 ms: p99.9 {}, p99.95 {}",
+        h.value_at_percentile(99.9),
+        h.value_at_percentile(99.95)
+    );
+
+    // 5. Cost
+    println!("\n5. Cost against keeping every sample (use --release):");
+    let (_, values) = &sets[1];
+    let histogram = time(5, || {
+        let mut h = Histogram::new();
+        values.iter().for_each(|&v| h.record(v));
+        h.value_at_percentile(99.0)
+    });
+    let sorting = time(5, || {
+        let mut all = Vec::new();
+        values.iter().for_each(|&v| all.push(v));
+        all.sort_unstable();
+        exact(&all, 99.0)
+    });
+    let mut h = Histogram::new();
+    values.iter().for_each(|&v| h.record(v));
+    println!(
+        "   histogram:  {:>9.2?}  {:>5.1} ns/sample  {:>6} KiB",
+        histogram,
+        histogram.as_nanos() as f64 / N as f64,
+        h.memory_bytes() / 1024
+    );
+    println!(
+        "   Vec + sort: {:>9.2?}  {:>5.1} ns/sample  {:>6} KiB",
+        sorting,
+        sorting.as_nanos() as f64 / N as f64,
+        N * 8 / 1024
+    );
+
+    println!("\n=== End of Latency Histogram Examples ===");
+}
@@ -0,0 +1,283 @@
+use histogram::{rank, Bucket, Histogram, HistogramError, Percentiles, DEFAULT_PRECISION_BITS};
+use std::time::Duration;
+
+const PERCENTILES: [f64; 7] = [0.0, 50.0, 90.0, 99.0, 99.9, 99.99, 100.0];
+
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 11
+    }
+
+    fn unit(&mut self) -> f64 {
+        self.next() as f64 / (1u64 << 53) as f64
+    }
+}
+
+// Nearest-rank percentile of sorted values, the exact reference
+fn exact(sorted: &[u64], p: f64) -> u64 {
+    sorted[rank(p, sorted.len() as u64) as usize - 1]
+}
+
+fn recorded(values: &[u64]) -> Histogram {
+    let mut h = Histogram::new();
+    values.iter().for_each(|&v| h.record(v));
+    h
+}
+
+fn only_bucket(bits: u32, value: u64) -> Bucket {
+    let mut h = Histogram::with_precision(bits);
+    h.record(value);
+    let mut buckets = h.buckets();
+    let b = buckets.next().expect("one bucket");
+    assert!(buckets.next().is_none());
+    b
+}
+
+#[test]
+fn rank_is_nearest_rank_in_integers() {
+    assert_eq!(rank(99.9, 1000), 999);
+    assert_eq!(rank(50.0, 10), 5);
+    assert_eq!(rank(50.0, 11), 6);
+    assert_eq!(rank(0.0, 10), 1);
+    assert_eq!(rank(100.0, 10), 10);
+    assert_eq!(rank(150.0, 10), 10);
+    assert_eq!(rank(-1.0, 10), 1);
+    assert_eq!(rank(50.0, 0), 1);
+}
+
+#[test]
+fn small_values_get_their_own_bucket() {
+    for v in 0..256 {
+        let b = only_bucket(DEFAULT_PRECISION_BITS, v);
+        assert_eq!((b.low, b.high, b.count), (v, v, 1));
+    }
+}
+
+#[test]
+fn the_layout_in_the_header_comment() {
+    let layout: Vec<(u64, u64)> = [7, 8, 9, 14, 16, 31, 32]
+        .iter()
+        .map(|&v| {
+            let b = only_bucket(3, v);
+            (b.low, b.high)
+        })
+        .collect();
+    assert_eq!(
+        layout,
+        [
+            (7, 7),
+            (8, 9),
+            (8, 9),
+            (14, 15),
+            (16, 19),
+            (28, 31),
+            (32, 39)
+        ]
+    );
+}
+
+#[test]
+fn every_bucket_holds_its_value_at_every_precision() {
+    for bits in 1..=16 {
+        // One histogram per precision, cleared between values: at 16 bits
+        // the counts for the top powers of two take megabytes
+        let mut h = Histogram::with_precision(bits);
+        for shift in 0..64 {
+            for v in [1u64 << shift, (1u64 << shift) - 1, (1u64 << shift) + 1] {
+                h.clear();
+                h.record(v);
+                let b = h.buckets().next().expect("one bucket");
+                assert!(b.low <= v && v <= b.high, "{} in {:?}", v, b);
+                let width = (b.high - b.low) as f64;
+                assert!(width <= v as f64 / (1u64 << (bits - 1)) as f64);
+            }
+        }
+        assert_eq!(only_bucket(bits, u64::MAX).high, u64::MAX);
+    }
+}
+
+#[test]
+fn buckets_are_contiguous() {
+    let mut h = Histogram::with_precision(4);
+    (0..5_000).for_each(|v| h.record(v));
+    let buckets: Vec<Bucket> = h.buckets().collect();
+    assert_eq!(buckets[0].low, 0);
+    for pair in buckets.windows(2) {
+        assert_eq!(pair[0].high + 1, pair[1].low);
+    }
+    let total: u64 = buckets.iter().map(|b| b.count).sum();
+    assert_eq!(total, 5_000);
+}
+
+#[test]
+fn percentiles_are_never_below_and_within_the_bound() {
+    let bound = 1.0 / (1u64 << (DEFAULT_PRECISION_BITS - 1)) as f64;
+    let mut rng = Lcg(3);
+    let n = 100_000;
+    let uniform: Vec<u64> = (0..n).map(|_| 1_000 + rng.next() % 999_000).collect();
+    let log_uniform: Vec<u64> = (0..n)
+        .map(|_| (100.0 * 10f64.powf(5.0 * rng.unit())) as u64)
+        .collect();
+    let bimodal: Vec<u64> = (0..n)
+        .map(|_| {
+            if rng.next().is_multiple_of(100) {
+                4_000_000 + rng.next() % 2_000_000
+            } else {
+                1_500 + rng.next() % 1_000
+            }
+        })
+        .collect();
+    for values in [uniform, log_uniform, bimodal] {
+        let h = recorded(&values);
+        let mut sorted = values;
+        sorted.sort_unstable();
+        for p in PERCENTILES {
+            let (reported, truth) = (h.value_at_percentile(p), exact(&sorted, p));
+            assert!(reported >= truth, "p{}: {} < {}", p, reported, truth);
+            assert!(
+                reported as f64 <= truth as f64 * (1.0 + bound),
+                "p{}: {} vs {}",
+                p,
+                reported,
+                truth
+            );
+        }
+    }
+}
+
+#[test]
+fn count_min_max_and_mean_are_exact() {
+    let mut rng = Lcg(11);
+    let values: Vec<u64> = (0..10_000).map(|_| rng.next() % 10_000_000).collect();
+    let h = recorded(&values);
+    let mean = values.iter().map(|&v| v as f64).sum::<f64>() / values.len() as f64;
+    assert_eq!(h.count(), values.len() as u64);
+    assert_eq!(h.min(), *values.iter().min().unwrap());
+    assert_eq!(h.max(), *values.iter().max().unwrap());
+    assert!((h.mean() - mean).abs() < 1e-6 * mean);
+}
+
+#[test]
+fn percentiles_summary() {
+    // Below 256 every value has its own bucket, so these are exact
+    let h = recorded(&(1..=200).collect::<Vec<_>>());
+    let p = h.percentiles();
+    assert_eq!(p.count, 200);
+    assert_eq!(
+        (p.p50, p.p90, p.p99, p.p999, p.max),
+        (100, 180, 198, 200, 200)
+    );
+    let d = Percentiles {
+        count: 1,
+        p50: 1_500,
+        p90: 0,
+        p99: 2_000_000,
+        p999: 3_000_000,
+        max: 4_000_000,
+    };
+    assert_eq!(
+        d.to_string(),
+        "p50 1.50µs  p99 2.00ms  p99.9 3.00ms  max 4.00ms"
+    );
+}
+
+#[test]
+fn merged_histograms_equal_one_recorded_histogram() {
+    let mut rng = Lcg(5);
+    let values: Vec<u64> = (0..40_000).map(|_| rng.next() % 50_000_000).collect();
+    let mut merged = Histogram::new();
+    for chunk in values.chunks(7_000) {
+        merged.merge(&recorded(chunk)).unwrap();
+    }
+    merged.merge(&Histogram::new()).unwrap();
+    assert_eq!(merged, recorded(&values));
+    // Merging a wider histogram into a narrower one grows it
+    let mut small = recorded(&[1]);
+    small.merge(&recorded(&[1 << 40])).unwrap();
+    assert_eq!(small, recorded(&[1, 1 << 40]));
+}
+
+#[test]
+fn different_precisions_are_refused() {
+    let mut h = recorded(&[1, 2, 3]);
+    let before = h.clone();
+    let err = h.merge(&Histogram::with_precision(4)).unwrap_err();
+    assert_eq!(
+        err,
+        HistogramError::PrecisionMismatch { ours: 8, theirs: 4 }
+    );
+    assert_eq!(
+        err.to_string(),
+        "precision mismatch: 4 bits merged into 8 bits"
+    );
+    assert_eq!(h, before);
+}
+
+#[test]
+fn an_empty_histogram_answers_zero() {
+    let h = Histogram::default();
+    assert!(h.is_empty());
+    assert_eq!(h.value_at_percentile(99.0), 0);
+    assert_eq!((h.min(), h.max(), h.mean()), (0, 0, 0.0));
+    assert_eq!(h.percentiles(), Percentiles::default());
+    assert_eq!(h.buckets().count(), 0);
+}
+
+#[test]
+fn zero_and_u64_max_are_recorded_exactly() {
+    let h = recorded(&[0, u64::MAX]);
+    assert_eq!(h.value_at_percentile(50.0), 0);
+    assert_eq!(h.value_at_percentile(100.0), u64::MAX);
+    assert_eq!(h.mean(), u64::MAX as f64 / 2.0);
+}
+
+#[test]
+fn record_n_weights_a_value() {
+    let mut h = Histogram::new();
+    h.record_n(5_000, 999);
+    h.record_n(7, 0);
+    h.record_n(9_000_000, 1);
+    assert_eq!(h.count(), 1000);
+    assert_eq!(h.min(), 5_000);
+    assert!(h.value_at_percentile(99.9) < 5_050);
+    assert_eq!(h.value_at_percentile(99.95), 9_000_000);
+}
+
+#[test]
+fn record_duration_saturates() {
+    let mut h = Histogram::new();
+    h.record_duration(Duration::from_micros(3));
+    h.record_duration(Duration::MAX);
+    assert_eq!(h.min(), 3_000);
+    assert_eq!(h.max(), u64::MAX);
+}
+
+#[test]
+fn clear_forgets_every_sample_but_keeps_memory() {
+    let mut h = recorded(&[1, 1_000_000_000]);
+    let memory = h.memory_bytes();
+    assert!(memory > 0);
+    h.clear();
+    assert_eq!(h, Histogram::new());
+    assert_eq!(h.memory_bytes(), memory);
+}
+
+#[test]
+fn memory_depends_on_the_largest_value_only() {
+    let one = recorded(&[1_000_000_000]);
+    let many = recorded(&vec![1_000_000_000; 10_000]);
+    assert_eq!(one.bucket_count(), many.bucket_count());
+    assert!(one.bucket_count() < 4_000);
+}
+
+#[test]
+#[should_panic(expected = "precision_bits must be 1..=16")]
+fn zero_precision_panics() {
+    Histogram::with_precision(0);
+}
@@ -0,0 +1,64 @@
+// The `#[variant(...)]` helper attribute shared by all three derives
+//
+//   #[variant(rename_all = "snake_case")]      on the enum
+//   #[variant(name = "adv_ind")]               on a variant: replaces its name
+//   #[variant(alias = "ADV_IND")]              on a variant: also parsed, repeatable
+//
+// Unknown keys are errors, pointing at the key, rather than being ignored.
+
+use crate::case::Case;
+use syn::{Attribute, LitStr, Variant};
+
+pub fn rename_all(attrs: &[Attribute]) -> syn::Result<Option<Case>> {
+    let mut case = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("variant")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                let value: LitStr = meta.value()?.parse()?;
+                case = Some(Case::parse(&value.value()).ok_or_else(|| {
+                    syn::Error::new(
+                        value.span(),
+                        format!("unknown rename_all rule, expected one of {}", Case::NAMES),
+                    )
+                })?);
+                Ok(())
+            } else {
+                Err(meta.error("on the enum, #[variant] only takes `rename_all`"))
+            }
+        })?;
+    }
+    Ok(case)
+}
+
+pub struct Names {
+    // What Display prints and FromStr accepts
+    pub name: String,
+    // Also accepted by FromStr
+    pub aliases: Vec<String>,
+}
+
+pub fn names(variant: &Variant, case: Option<Case>) -> syn::Result<Names> {
+    let ident = variant.ident.to_string();
+    let mut names = Names {
+        name: case.map_or_else(|| ident.clone(), |c| c.apply(&ident)),
+        aliases: Vec::new(),
+    };
+    for attr in variant
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("variant"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                names.name = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else if meta.path.is_ident("alias") {
+                names.aliases.push(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("on a variant, #[variant] takes `name` or `alias`"))
+            }
+        })?;
+    }
+    Ok(names)
+}
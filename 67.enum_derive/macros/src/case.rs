@@ -0,0 +1,70 @@
+// `rename_all` rules: variant identifiers are PascalCase, split into words
+// at each capital letter
+//
+//   AdvNonconnInd -> adv_nonconn_ind, ADV_NONCONN_IND, adv-nonconn-ind, ...
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    Lower,
+    Upper,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+}
+
+impl Case {
+    pub const NAMES: &'static str =
+        "\"lowercase\", \"UPPERCASE\", \"snake_case\", \"SCREAMING_SNAKE_CASE\", \"kebab-case\"";
+
+    pub fn parse(name: &str) -> Option<Case> {
+        match name {
+            "lowercase" => Some(Case::Lower),
+            "UPPERCASE" => Some(Case::Upper),
+            "snake_case" => Some(Case::Snake),
+            "SCREAMING_SNAKE_CASE" => Some(Case::ScreamingSnake),
+            "kebab-case" => Some(Case::Kebab),
+            _ => None,
+        }
+    }
+
+    pub fn apply(self, ident: &str) -> String {
+        let words = words(ident);
+        let lower: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+        let upper: Vec<String> = words.iter().map(|w| w.to_uppercase()).collect();
+        match self {
+            Case::Lower => lower.concat(),
+            Case::Upper => upper.concat(),
+            Case::Snake => lower.join("_"),
+            Case::ScreamingSnake => upper.join("_"),
+            Case::Kebab => lower.join("-"),
+        }
+    }
+}
+
+// A capital letter starts a word after a lowercase letter or a digit. In
+// a run of capitals, the last one starts the next word if a lowercase
+// letter follows it: `PDUType` -> `PDU`, `Type`
+fn words(ident: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = ident.char_indices().collect();
+    let mut words = Vec::new();
+    let mut start = 0;
+    for i in 1..chars.len() {
+        let (at, c) = chars[i];
+        let prev = chars[i - 1].1;
+        let next_lower = chars.get(i + 1).is_some_and(|&(_, n)| n.is_lowercase());
+        let boundary = c == '_'
+            || prev == '_'
+            || (c.is_uppercase() && (prev.is_lowercase() || prev.is_ascii_digit()))
+            || (c.is_uppercase() && prev.is_uppercase() && next_lower);
+        if boundary {
+            words.push(&ident[start..at]);
+            start = at;
+        }
+    }
+    words.push(&ident[start..]);
+    words
+        .into_iter()
+        .map(|w| w.trim_matches('_'))
+        .filter(|w| !w.is_empty())
+        .collect()
+}
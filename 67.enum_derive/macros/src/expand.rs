@@ -0,0 +1,197 @@
+// The code each derive generates
+//
+// Generated code names everything by absolute path (`::core::fmt`,
+// `::enum_derive::EnumIter`) so it compiles whatever the user has
+// imported or shadowed.
+
+use crate::attrs::{self, Names};
+use crate::machine::{self, Origin};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DataEnum, DeriveInput, Fields, Variant};
+
+fn variants<'a>(input: &'a DeriveInput, derive: &str) -> syn::Result<&'a DataEnum> {
+    match &input.data {
+        Data::Enum(data) => Ok(data),
+        Data::Struct(s) => Err(syn::Error::new_spanned(
+            s.struct_token,
+            format!("{} can only be derived for enums", derive),
+        )),
+        Data::Union(u) => Err(syn::Error::new_spanned(
+            u.union_token,
+            format!("{} can only be derived for enums", derive),
+        )),
+    }
+}
+
+// Iteration and parsing have to construct a variant, which they can't do
+// without values for its fields
+fn require_unit(variant: &Variant, derive: &str) -> syn::Result<()> {
+    if matches!(variant.fields, Fields::Unit) {
+        Ok(())
+    } else {
+        Err(syn::Error::new_spanned(
+            &variant.fields,
+            format!(
+                "{} only supports unit variants; `{}` has fields",
+                derive, variant.ident
+            ),
+        ))
+    }
+}
+
+pub fn enum_iter(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let data = variants(input, "EnumIter")?;
+    for variant in &data.variants {
+        require_unit(variant, "EnumIter")?;
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let count = data.variants.len();
+    let arms = data.variants.iter().enumerate().map(|(i, v)| {
+        let ident = &v.ident;
+        quote! { #i => ::core::option::Option::Some(#name::#ident), }
+    });
+    Ok(quote! {
+        impl #impl_generics ::enum_derive::EnumIter for #name #ty_generics #where_clause {
+            const COUNT: usize = #count;
+
+            fn from_index(index: usize) -> ::core::option::Option<Self> {
+                match index {
+                    #(#arms)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    })
+}
+
+pub fn enum_display(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let data = variants(input, "EnumDisplay")?;
+    let case = attrs::rename_all(&input.attrs)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    // Variants with fields print their name only
+    let arms = data
+        .variants
+        .iter()
+        .map(|v| {
+            let ident = &v.ident;
+            let Names { name: text, .. } = attrs::names(v, case)?;
+            let pattern = match v.fields {
+                Fields::Unit => quote! { #name::#ident },
+                Fields::Unnamed(_) => quote! { #name::#ident(..) },
+                Fields::Named(_) => quote! { #name::#ident { .. } },
+            };
+            Ok(quote! { #pattern => #text, })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                // `pad` rather than `write_str`, so `{:<10}` and `{:>10}` work
+                f.pad(match *self {
+                    #(#arms)*
+                })
+            }
+        }
+    })
+}
+
+pub fn enum_from_str(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let data = variants(input, "EnumFromStr")?;
+    let case = attrs::rename_all(&input.attrs)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut seen: Vec<(String, &syn::Ident)> = Vec::new();
+    let mut arms = Vec::new();
+    let mut expected = Vec::new();
+    for variant in &data.variants {
+        require_unit(variant, "EnumFromStr")?;
+        let ident = &variant.ident;
+        let names = attrs::names(variant, case)?;
+        let all: Vec<String> = std::iter::once(names.name.clone())
+            .chain(names.aliases)
+            .collect();
+        // A duplicate would make the second arm unreachable
+        for text in &all {
+            if let Some((_, first)) = seen.iter().find(|(t, _)| t == text) {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    format!("`{}` and `{}` both parse from \"{}\"", first, ident, text),
+                ));
+            }
+            seen.push((text.clone(), ident));
+        }
+        arms.push(quote! { #(#all)|* => ::core::result::Result::Ok(#name::#ident), });
+        expected.push(names.name);
+    }
+    let type_name = name.to_string();
+    Ok(quote! {
+        impl #impl_generics ::core::str::FromStr for #name #ty_generics #where_clause {
+            type Err = ::enum_derive::ParseEnumError;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                match s {
+                    #(#arms)*
+                    _ => ::core::result::Result::Err(::enum_derive::ParseEnumError {
+                        type_name: #type_name,
+                        input: s.into(),
+                        expected: &[#(#expected),*],
+                    }),
+                }
+            }
+        }
+    })
+}
+
+pub fn state_machine(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let data = variants(input, "StateMachine")?;
+    for variant in &data.variants {
+        require_unit(variant, "StateMachine")?;
+    }
+    let event = machine::event_type(input)?;
+    let rules = machine::rules(input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // `{ .. }` matches unit, tuple and struct variants alike, so events
+    // may carry data the rules don't look at
+    let arms = rules.iter().map(|rule| {
+        let (on, to) = (&rule.on, &rule.to);
+        let from = match &rule.from {
+            Origin::Any => quote! { _ },
+            Origin::States(states) => quote! { #(#name::#states)|* },
+        };
+        quote! { (#from, #event::#on { .. }) => ::core::result::Result::Ok(#name::#to), }
+    });
+    let table = rules.iter().map(|rule| {
+        let from = rule.source_text();
+        let on = rule.on.to_string();
+        let to = rule.to.to_string();
+        quote! { ::enum_derive::Rule { from: #from, on: #on, to: #to }, }
+    });
+    Ok(quote! {
+        impl #impl_generics ::enum_derive::StateMachine for #name #ty_generics #where_clause {
+            type Event = #event;
+
+            const TRANSITIONS: &'static [::enum_derive::Rule] = &[#(#table)*];
+
+            fn next(
+                self,
+                event: &#event,
+            ) -> ::core::result::Result<Self, ::enum_derive::InvalidTransition<Self>> {
+                match (self, event) {
+                    #(#arms)*
+                    // Unreachable when the rules cover every pair
+                    #[allow(unreachable_patterns)]
+                    _ => ::core::result::Result::Err(::enum_derive::InvalidTransition {
+                        state: self,
+                        event: ::core::clone::Clone::clone(event),
+                    }),
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,55 @@
+// Derive macros for field-less enums
+//
+//   #[derive(EnumIter, EnumDisplay, EnumFromStr)]
+//   #[variant(rename_all = "lowercase")]
+//   enum Direction { North, South, East, West }
+//
+// expands to an `EnumIter` impl (`Direction::iter()`, `COUNT`), a
+// `Display` impl printing "north" and a `FromStr` impl parsing it back.
+// Each derive reads the enum's syntax tree with syn, checks that it can
+// handle every variant, and writes the impl with quote. Problems become
+// compile errors pointing at the offending variant or attribute.
+//
+// `#[derive(StateMachine)]` turns the `#[transition(...)]` and
+// `#[transitions(...)]` rules on a state enum into its `next(event)`
+// method and transition table.
+
+mod attrs;
+mod case;
+mod expand;
+mod machine;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+#[proc_macro_derive(EnumIter, attributes(variant))]
+pub fn derive_enum_iter(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::enum_iter(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(EnumDisplay, attributes(variant))]
+pub fn derive_enum_display(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::enum_display(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(EnumFromStr, attributes(variant))]
+pub fn derive_enum_from_str(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::enum_from_str(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(StateMachine, attributes(state_machine, transition, transitions))]
+pub fn derive_state_machine(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::state_machine(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
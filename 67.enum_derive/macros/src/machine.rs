@@ -0,0 +1,210 @@
+// The attributes of `#[derive(StateMachine)]`
+//
+//   #[state_machine(event = PowerEvent)]                            once, on the enum
+//   #[transition(from = "_", on = "LowBattery", to = "DeepSleep")]   one rule
+//   #[transitions(                                                   several rules
+//       (Active, TimerExpired) => Idle,
+//       (Idle | LightSleep, Activity) => Active,
+//   )]
+//
+// `#[transition]` names one rule's parts as strings; `#[transitions]`
+// writes rules as the arms of a match on (state, event), `_` for any
+// state. The two may be mixed, and all rules are tried in the order they
+// are written, like match arms; a pair no rule covers is an invalid
+// transition. State names are checked against the enum here; event names
+// become paths into the event type, so the compiler checks those.
+
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parenthesized, Attribute, Data, DeriveInput, Ident, LitStr, Path, Token};
+
+pub enum Origin {
+    Any,
+    States(Vec<Ident>),
+}
+
+pub struct Rule {
+    pub from: Origin,
+    pub on: Ident,
+    pub to: Ident,
+}
+
+impl Rule {
+    // As written in the attribute, for the TRANSITIONS table
+    pub fn source_text(&self) -> String {
+        match &self.from {
+            Origin::Any => "_".to_string(),
+            Origin::States(states) => states
+                .iter()
+                .map(Ident::to_string)
+                .collect::<Vec<_>>()
+                .join(" | "),
+        }
+    }
+}
+
+// `(from, on) => to`, one arm of `#[transitions]`
+impl Parse for Rule {
+    fn parse(input: ParseStream) -> syn::Result<Rule> {
+        let pair;
+        parenthesized!(pair in input);
+        let from = if pair.peek(Token![_]) {
+            pair.parse::<Token![_]>()?;
+            Origin::Any
+        } else {
+            let states = Punctuated::<Ident, Token![|]>::parse_separated_nonempty(&pair)?;
+            Origin::States(states.into_iter().collect())
+        };
+        pair.parse::<Token![,]>()?;
+        let on = pair.parse()?;
+        input.parse::<Token![=>]>()?;
+        let to = input.parse()?;
+        Ok(Rule { from, on, to })
+    }
+}
+
+pub fn event_type(input: &DeriveInput) -> syn::Result<Path> {
+    let mut event = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("state_machine"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("event") {
+                event = Some(meta.value()?.parse::<Path>()?);
+                Ok(())
+            } else {
+                Err(meta.error("#[state_machine] only takes `event`"))
+            }
+        })?;
+    }
+    event.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "StateMachine needs the event type: #[state_machine(event = EventType)]",
+        )
+    })
+}
+
+// A name inside a string, spanned at the string so errors point there
+fn ident_in(lit: &LitStr, text: &str) -> syn::Result<Ident> {
+    syn::parse_str::<Ident>(text.trim())
+        .map(|ident| Ident::new(&ident.to_string(), lit.span()))
+        .map_err(|_| syn::Error::new(lit.span(), format!("`{}` is not a variant name", text)))
+}
+
+// `#[transition(from = "A | B", on = "Event", to = "C")]`
+fn transition(attr: &Attribute) -> syn::Result<Rule> {
+    let (mut from, mut on, mut to) = (None, None, None);
+    attr.parse_nested_meta(|meta| {
+        let key = meta.path.clone();
+        let lit: LitStr = meta.value()?.parse()?;
+        if key.is_ident("from") {
+            from = Some(if lit.value().trim() == "_" {
+                Origin::Any
+            } else {
+                Origin::States(
+                    lit.value()
+                        .split('|')
+                        .map(|name| ident_in(&lit, name))
+                        .collect::<syn::Result<_>>()?,
+                )
+            });
+        } else if key.is_ident("on") {
+            on = Some(ident_in(&lit, &lit.value())?);
+        } else if key.is_ident("to") {
+            to = Some(ident_in(&lit, &lit.value())?);
+        } else {
+            return Err(syn::Error::new_spanned(
+                key,
+                "#[transition] takes `from`, `on` and `to`",
+            ));
+        }
+        Ok(())
+    })?;
+    match (from, on, to) {
+        (Some(from), Some(on), Some(to)) => Ok(Rule { from, on, to }),
+        _ => Err(syn::Error::new_spanned(
+            attr,
+            "#[transition] needs all of `from`, `on` and `to`",
+        )),
+    }
+}
+
+// The rules in declaration order, each checked against the states and
+// against the rules before it
+pub fn rules(input: &DeriveInput) -> syn::Result<Vec<Rule>> {
+    let states: Vec<&Ident> = match &input.data {
+        Data::Enum(data) => data.variants.iter().map(|v| &v.ident).collect(),
+        _ => Vec::new(),
+    };
+    let known = |ident: &Ident| -> syn::Result<()> {
+        if states.contains(&ident) {
+            Ok(())
+        } else {
+            Err(syn::Error::new(
+                ident.span(),
+                format!("`{}` has no state `{}`", input.ident, ident),
+            ))
+        }
+    };
+
+    let mut rules = Vec::new();
+    for attr in &input.attrs {
+        if attr.path().is_ident("transition") {
+            rules.push(transition(attr)?);
+        } else if attr.path().is_ident("transitions") {
+            rules.extend(attr.parse_args_with(Punctuated::<Rule, Token![,]>::parse_terminated)?);
+        }
+    }
+    if rules.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "StateMachine needs its rules: #[transition(..)] or #[transitions(..)]",
+        ));
+    }
+
+    // (state, event) pairs handled so far; None is the wildcard
+    let mut covered: Vec<(Option<String>, String)> = Vec::new();
+    let handled = |covered: &[(Option<String>, String)], state: &str, on: &str| {
+        covered
+            .iter()
+            .any(|(s, e)| e == on && s.as_deref().is_none_or(|s| s == state))
+    };
+    for rule in &rules {
+        known(&rule.to)?;
+        let on = rule.on.to_string();
+        let shadowed = |state: &str| {
+            syn::Error::new(
+                rule.on.span(),
+                format!(
+                    "unreachable: an earlier rule already handles {} on `{}`",
+                    state, on
+                ),
+            )
+        };
+        match &rule.from {
+            Origin::Any => {
+                if states
+                    .iter()
+                    .all(|s| handled(&covered, &s.to_string(), &on))
+                {
+                    return Err(shadowed("every state"));
+                }
+                covered.push((None, on.clone()));
+            }
+            Origin::States(from) => {
+                for state in from {
+                    known(state)?;
+                    let state = state.to_string();
+                    if handled(&covered, &state, &on) {
+                        return Err(shadowed(&format!("`{}`", state)));
+                    }
+                    covered.push((Some(state), on.clone()));
+                }
+            }
+        }
+    }
+    Ok(rules)
+}
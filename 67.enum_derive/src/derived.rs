@@ -0,0 +1,94 @@
+// The same enums with the boilerplate derived. The names come from the
+// variants themselves, so a new variant is listed, printed and parsed as
+// soon as it is added.
+//
+// Serde gets its own `rename_all` and `alias`, written to match the
+// `#[variant]` ones, so an enum reads the same in JSON as in `Display`.
+// Nothing ties the two attributes together; tests/json.rs checks that
+// they agree and pins the exact JSON of every variant.
+
+use crate::{EnumDisplay, EnumFromStr, EnumIter, StateMachine};
+use serde::{Deserialize, Serialize};
+
+pub use crate::manual::LinkEvent;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumDisplay, EnumFromStr, Serialize, Deserialize,
+)]
+#[variant(rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    #[variant(alias = "n")]
+    #[serde(alias = "n")]
+    North,
+    #[variant(alias = "s")]
+    #[serde(alias = "s")]
+    South,
+    #[variant(alias = "e")]
+    #[serde(alias = "e")]
+    East,
+    #[variant(alias = "w")]
+    #[serde(alias = "w")]
+    West,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumDisplay, EnumFromStr, Serialize, Deserialize,
+)]
+#[variant(rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Active,
+    Inactive,
+    Pending,
+}
+
+// BLE legacy advertising PDU types, the 4-bit field in the PDU header
+// (07.ble). CONNECT_IND was called CONNECT_REQ before Bluetooth 5.
+// In JSON it is the name, not the 4-bit value: a log line reading
+// "SCAN_RSP" needs no table to decode
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumDisplay, EnumFromStr, Serialize, Deserialize,
+)]
+#[variant(rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[repr(u8)]
+pub enum PacketType {
+    AdvInd = 0x0,
+    AdvDirectInd = 0x1,
+    AdvNonconnInd = 0x2,
+    ScanReq = 0x3,
+    ScanRsp = 0x4,
+    #[variant(alias = "CONNECT_REQ")]
+    #[serde(alias = "CONNECT_REQ")]
+    ConnectInd = 0x5,
+    AdvScanInd = 0x6,
+}
+
+impl PacketType {
+    // The header field decoded by searching the variants, so there is no
+    // second list of discriminants to keep in step
+    pub fn from_bits(bits: u8) -> Option<PacketType> {
+        PacketType::iter().find(|t| *t as u8 == bits)
+    }
+}
+
+// The rules are written once and the match and TRANSITIONS are generated
+// from them, in this order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumDisplay, StateMachine)]
+#[state_machine(event = LinkEvent)]
+#[transitions(
+    (Standby, Advertise) => Advertising,
+    (Standby, Scan) => Scanning,
+    (Standby, Initiate) => Initiating,
+    (Advertising | Initiating, ConnectInd) => Connection,
+    (Advertising | Scanning | Initiating, Stop) => Standby,
+    (Connection, Disconnect) => Standby,
+)]
+pub enum LinkState {
+    Standby,
+    Advertising,
+    Scanning,
+    Initiating,
+    Connection,
+}
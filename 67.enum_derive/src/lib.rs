@@ -0,0 +1,108 @@
+// Derive macros that replace hand-written match arms on enums
+//
+// An enum like `Direction` usually grows three pieces of boilerplate: a
+// list of all variants, a `Display` impl and a `FromStr` impl, each a
+// match with one arm per variant that must be kept in step with the
+// others. The macros in macros/ generate all three from the enum itself:
+//
+// - `#[derive(EnumIter)]`: `COUNT`, `from_index` and `iter()`
+// - `#[derive(EnumDisplay)]`: `Display`, by variant name or `#[variant]`
+// - `#[derive(EnumFromStr)]`: `FromStr`, returning `ParseEnumError`
+// - `#[derive(StateMachine)]`: `next(event)` from `#[transitions]` rules
+//
+// - `manual`: the enums with hand-written impls, the "before"
+// - `derived`: the same enums with derives, the "after"
+// - `machine`: the `StateMachine` trait and `InvalidTransition`
+
+// The generated code refers to `::enum_derive::...`, which inside this
+// crate only resolves with this alias
+extern crate self as enum_derive;
+
+pub mod derived;
+pub mod machine;
+pub mod manual;
+
+pub use enum_derive_macros::{EnumDisplay, EnumFromStr, EnumIter, StateMachine};
+pub use machine::{InvalidTransition, Rule, StateMachine};
+
+use std::error::Error;
+use std::fmt;
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+
+// Implemented by #[derive(EnumIter)]; only for enums whose variants all
+// have no fields. Variants are numbered in declaration order
+pub trait EnumIter: Sized {
+    const COUNT: usize;
+
+    fn from_index(index: usize) -> Option<Self>;
+
+    fn iter() -> Variants<Self> {
+        Variants {
+            front: 0,
+            back: Self::COUNT,
+            _enum: PhantomData,
+        }
+    }
+}
+
+// Builds each variant when it is reached, so the enum needn't be `Copy`
+pub struct Variants<T> {
+    front: usize,
+    back: usize,
+    _enum: PhantomData<fn() -> T>,
+}
+
+impl<T: EnumIter> Iterator for Variants<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        T::from_index(self.front - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.back - self.front;
+        (left, Some(left))
+    }
+}
+
+impl<T: EnumIter> DoubleEndedIterator for Variants<T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        T::from_index(self.back)
+    }
+}
+
+impl<T: EnumIter> ExactSizeIterator for Variants<T> {}
+
+impl<T: EnumIter> FusedIterator for Variants<T> {}
+
+// Returned by every derived `FromStr`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseEnumError {
+    pub type_name: &'static str,
+    pub input: String,
+    // The primary name of each variant, in declaration order
+    pub expected: &'static [&'static str],
+}
+
+impl fmt::Display for ParseEnumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown {} {:?}, expected one of: {}",
+            self.type_name,
+            self.input,
+            self.expected.join(", ")
+        )
+    }
+}
+
+impl Error for ParseEnumError {}
@@ -0,0 +1,48 @@
+// The trait #[derive(StateMachine)] implements
+//
+// A state machine written by hand is a match on (state, event) whose arms
+// are the transition table. The derive generates that match from the
+// `#[transition(from, on, to)]` and `#[transitions((from, on) => to)]`
+// rules, in the order they are written, and keeps the rules as data in
+// `TRANSITIONS` for printing and checking. Pairs no rule covers return
+// `InvalidTransition`.
+
+use std::error::Error;
+use std::fmt::{self, Debug};
+
+pub trait StateMachine: Copy + PartialEq + Debug {
+    type Event: Clone + Debug;
+
+    // The rules in declaration order
+    const TRANSITIONS: &'static [Rule];
+
+    fn next(self, event: &Self::Event) -> Result<Self, InvalidTransition<Self>>;
+}
+
+// One rule as declared; `from` is "_" for any state, or "A | B"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    pub from: &'static str,
+    pub on: &'static str,
+    pub to: &'static str,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} --{}--> {}", self.from, self.on, self.to)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidTransition<S: StateMachine> {
+    pub state: S,
+    pub event: S::Event,
+}
+
+impl<S: StateMachine> fmt::Display for InvalidTransition<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no transition from {:?} on {:?}", self.state, self.event)
+    }
+}
+
+impl<S: StateMachine> Error for InvalidTransition<S> {}
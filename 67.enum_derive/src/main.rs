@@ -0,0 +1,113 @@
+use enum_derive::{derived, manual, EnumDisplay, EnumIter, StateMachine};
+use std::fmt::Display;
+
+fn names<T: Display>(values: impl IntoIterator<Item = T>) -> Vec<String> {
+    values.into_iter().map(|v| v.to_string()).collect()
+}
+
+// Only Display: variants with fields print their name
+#[derive(Debug, EnumDisplay)]
+#[variant(rename_all = "kebab-case")]
+enum Event {
+    LinkUp,
+    Reading(f32),
+    #[variant(name = "fault!")]
+    SensorFault {
+        code: u16,
+    },
+}
+
+fn main() {
+    println!("=== Enum Derive Examples ===\n");
+
+    // 1. EnumIter
+    println!("1. Iterating variants:");
+    println!("   Direction::COUNT = {}", derived::Direction::COUNT);
+    println!(
+        "   forwards:  {}",
+        names(derived::Direction::iter()).join(", ")
+    );
+    println!(
+        "   backwards: {}",
+        names(derived::Direction::iter().rev()).join(", ")
+    );
+    println!(
+        "   Status:    {}",
+        names(derived::Status::iter()).join(", ")
+    );
+    println!("   manual:    {}", names(manual::Direction::ALL).join(", "));
+
+    // 2. EnumDisplay
+    println!("\n2. Display, with rename_all and padding:");
+    for t in derived::PacketType::iter() {
+        println!("   0x{:X} {:<16}|", t as u8, t);
+    }
+
+    // 3. EnumFromStr
+    println!("\n3. Parsing:");
+    for input in ["west", "w", "CONNECT_REQ", "up"] {
+        let direction = input.parse::<derived::Direction>();
+        let packet = input.parse::<derived::PacketType>();
+        match (direction, packet) {
+            (Ok(d), _) => println!("   {:<12} -> Direction::{:?}", input, d),
+            (_, Ok(p)) => println!("   {:<12} -> PacketType::{:?}", input, p),
+            (Err(e), _) => println!("   {:<12} -> {}", input, e),
+        }
+    }
+    if let Err(e) = "up".parse::<manual::Direction>() {
+        println!("   {:<12} -> {} (manual)", "up", e);
+    }
+
+    // 4. Decoding by iteration
+    println!("\n4. PacketType::from_bits without a second match:");
+    let decoded: Vec<String> = (0..16u8)
+        .map(|bits| derived::PacketType::from_bits(bits).map_or("-".into(), |t| t.to_string()))
+        .collect();
+    println!("   {}", decoded[..8].join(" "));
+
+    // 5. Variants with fields
+    println!("\n5. Display on variants with fields:");
+    let events = [
+        Event::LinkUp,
+        Event::Reading(21.5),
+        Event::SensorFault { code: 0x31 },
+    ];
+    // The derive names the variant; reading the fields is still up to you
+    for event in &events {
+        let detail = match event {
+            Event::LinkUp => String::new(),
+            Event::Reading(celsius) => format!("{:.1} °C", celsius),
+            Event::SensorFault { code } => format!("code 0x{:02X}", code),
+        };
+        println!("   {}", format!("{:<10} {}", event, detail).trim_end());
+    }
+
+    // 6. StateMachine
+    println!("\n6. A state machine from #[transitions] rules:");
+    for rule in derived::LinkState::TRANSITIONS {
+        println!("   {}", rule);
+    }
+    let walk = [
+        manual::LinkEvent::Advertise,
+        manual::LinkEvent::ConnectInd,
+        manual::LinkEvent::Stop,
+        // 0x13: remote user terminated connection
+        manual::LinkEvent::Disconnect { reason: 0x13 },
+    ];
+    let mut link = derived::LinkState::Standby;
+    for event in &walk {
+        match link.next(event) {
+            Ok(to) => {
+                println!("   {:<11} --{:?}--> {}", link, event, to);
+                link = to;
+            }
+            Err(e) => println!("   rejected: {}", e),
+        }
+    }
+    match derived::LinkState::Connection.next(&manual::LinkEvent::Scan) {
+        Ok(to) => println!("   Connection --Scan--> {}", to),
+        Err(e) => println!("   rejected: {}", e),
+    }
+
+    println!("\n=== End of Enum Derive Examples ===");
+}
@@ -0,0 +1,263 @@
+// The enums as they are usually written by hand: a list of variants, a
+// `Display` match and a `FromStr` match, each with one arm per variant.
+// Adding a variant means editing all three, and nothing checks that the
+// names in `Display` and `FromStr` agree.
+
+use crate::{InvalidTransition, ParseEnumError, Rule, StateMachine};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::South,
+        Direction::East,
+        Direction::West,
+    ];
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Direction::North => "north",
+            Direction::South => "south",
+            Direction::East => "east",
+            Direction::West => "west",
+        })
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Direction, ParseEnumError> {
+        match s {
+            "north" | "n" => Ok(Direction::North),
+            "south" | "s" => Ok(Direction::South),
+            "east" | "e" => Ok(Direction::East),
+            "west" | "w" => Ok(Direction::West),
+            _ => Err(ParseEnumError {
+                type_name: "Direction",
+                input: s.to_string(),
+                expected: &["north", "south", "east", "west"],
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Active,
+    Inactive,
+    Pending,
+}
+
+impl Status {
+    pub const ALL: [Status; 3] = [Status::Active, Status::Inactive, Status::Pending];
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Status::Active => "active",
+            Status::Inactive => "inactive",
+            Status::Pending => "pending",
+        })
+    }
+}
+
+impl FromStr for Status {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Status, ParseEnumError> {
+        match s {
+            "active" => Ok(Status::Active),
+            "inactive" => Ok(Status::Inactive),
+            "pending" => Ok(Status::Pending),
+            _ => Err(ParseEnumError {
+                type_name: "Status",
+                input: s.to_string(),
+                expected: &["active", "inactive", "pending"],
+            }),
+        }
+    }
+}
+
+// BLE legacy advertising PDU types, the 4-bit field in the PDU header
+// (07.ble). CONNECT_IND was called CONNECT_REQ before Bluetooth 5
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PacketType {
+    AdvInd = 0x0,
+    AdvDirectInd = 0x1,
+    AdvNonconnInd = 0x2,
+    ScanReq = 0x3,
+    ScanRsp = 0x4,
+    ConnectInd = 0x5,
+    AdvScanInd = 0x6,
+}
+
+impl PacketType {
+    pub const ALL: [PacketType; 7] = [
+        PacketType::AdvInd,
+        PacketType::AdvDirectInd,
+        PacketType::AdvNonconnInd,
+        PacketType::ScanReq,
+        PacketType::ScanRsp,
+        PacketType::ConnectInd,
+        PacketType::AdvScanInd,
+    ];
+
+    pub fn from_bits(bits: u8) -> Option<PacketType> {
+        match bits {
+            0x0 => Some(PacketType::AdvInd),
+            0x1 => Some(PacketType::AdvDirectInd),
+            0x2 => Some(PacketType::AdvNonconnInd),
+            0x3 => Some(PacketType::ScanReq),
+            0x4 => Some(PacketType::ScanRsp),
+            0x5 => Some(PacketType::ConnectInd),
+            0x6 => Some(PacketType::AdvScanInd),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PacketType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            PacketType::AdvInd => "ADV_IND",
+            PacketType::AdvDirectInd => "ADV_DIRECT_IND",
+            PacketType::AdvNonconnInd => "ADV_NONCONN_IND",
+            PacketType::ScanReq => "SCAN_REQ",
+            PacketType::ScanRsp => "SCAN_RSP",
+            PacketType::ConnectInd => "CONNECT_IND",
+            PacketType::AdvScanInd => "ADV_SCAN_IND",
+        })
+    }
+}
+
+impl FromStr for PacketType {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<PacketType, ParseEnumError> {
+        match s {
+            "ADV_IND" => Ok(PacketType::AdvInd),
+            "ADV_DIRECT_IND" => Ok(PacketType::AdvDirectInd),
+            "ADV_NONCONN_IND" => Ok(PacketType::AdvNonconnInd),
+            "SCAN_REQ" => Ok(PacketType::ScanReq),
+            "SCAN_RSP" => Ok(PacketType::ScanRsp),
+            "CONNECT_IND" | "CONNECT_REQ" => Ok(PacketType::ConnectInd),
+            "ADV_SCAN_IND" => Ok(PacketType::AdvScanInd),
+            _ => Err(ParseEnumError {
+                type_name: "PacketType",
+                input: s.to_string(),
+                expected: &[
+                    "ADV_IND",
+                    "ADV_DIRECT_IND",
+                    "ADV_NONCONN_IND",
+                    "SCAN_REQ",
+                    "SCAN_RSP",
+                    "CONNECT_IND",
+                    "ADV_SCAN_IND",
+                ],
+            }),
+        }
+    }
+}
+
+// BLE link layer states, simplified from the Core specification (Vol 6,
+// Part B, 1.1). The table of rules and the match are two copies of the
+// same thing, and nothing checks that they agree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkState {
+    Standby,
+    Advertising,
+    Scanning,
+    Initiating,
+    Connection,
+}
+
+impl LinkState {
+    pub const ALL: [LinkState; 5] = [
+        LinkState::Standby,
+        LinkState::Advertising,
+        LinkState::Scanning,
+        LinkState::Initiating,
+        LinkState::Connection,
+    ];
+}
+
+// Plain data with nothing to derive; `derived` uses it too
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkEvent {
+    Advertise,
+    Scan,
+    Initiate,
+    // Received by the advertiser, sent by the initiator
+    ConnectInd,
+    Stop,
+    Disconnect { reason: u8 },
+}
+
+impl StateMachine for LinkState {
+    type Event = LinkEvent;
+
+    const TRANSITIONS: &'static [Rule] = &[
+        Rule {
+            from: "Standby",
+            on: "Advertise",
+            to: "Advertising",
+        },
+        Rule {
+            from: "Standby",
+            on: "Scan",
+            to: "Scanning",
+        },
+        Rule {
+            from: "Standby",
+            on: "Initiate",
+            to: "Initiating",
+        },
+        Rule {
+            from: "Advertising | Initiating",
+            on: "ConnectInd",
+            to: "Connection",
+        },
+        Rule {
+            from: "Advertising | Scanning | Initiating",
+            on: "Stop",
+            to: "Standby",
+        },
+        Rule {
+            from: "Connection",
+            on: "Disconnect",
+            to: "Standby",
+        },
+    ];
+
+    fn next(self, event: &LinkEvent) -> Result<LinkState, InvalidTransition<LinkState>> {
+        use LinkEvent::*;
+        use LinkState::*;
+
+        match (self, event) {
+            (Standby, Advertise) => Ok(Advertising),
+            (Standby, Scan) => Ok(Scanning),
+            (Standby, Initiate) => Ok(Initiating),
+            (Advertising | Initiating, ConnectInd) => Ok(Connection),
+            (Advertising | Scanning | Initiating, Stop) => Ok(Standby),
+            (Connection, Disconnect { .. }) => Ok(Standby),
+            _ => Err(InvalidTransition {
+                state: self,
+                event: *event,
+            }),
+        }
+    }
+}
@@ -0,0 +1,126 @@
+// The JSON of every derived enum, pinned. Renaming a variant or changing
+// a `rename_all` changes what other programs read and write, so it
+// should fail here first rather than in a log parser later.
+//
+// Each table lists every variant with its exact JSON. `assert_wire`
+// checks the table against `iter()`, so a new variant fails until its
+// JSON is written down too.
+
+use enum_derive::derived::{Direction, PacketType, Status};
+use enum_derive::EnumIter;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::{Debug, Display};
+
+fn assert_wire<T>(table: &[(T, &str)])
+where
+    T: EnumIter + Serialize + DeserializeOwned + Display + PartialEq + Debug,
+{
+    let listed: Vec<&T> = table.iter().map(|(v, _)| v).collect();
+    let all: Vec<T> = T::iter().collect();
+    assert_eq!(
+        listed,
+        all.iter().collect::<Vec<_>>(),
+        "table misses a variant"
+    );
+    for (variant, json) in table {
+        assert_eq!(serde_json::to_string(variant).unwrap(), *json);
+        assert_eq!(&serde_json::from_str::<T>(json).unwrap(), variant);
+        // The serde attributes are separate from `#[variant]`; they must
+        // still name each variant the same way
+        assert_eq!(format!("\"{}\"", variant), *json, "JSON and Display differ");
+    }
+}
+
+#[test]
+fn direction_json() {
+    assert_wire(&[
+        (Direction::North, r#""north""#),
+        (Direction::South, r#""south""#),
+        (Direction::East, r#""east""#),
+        (Direction::West, r#""west""#),
+    ]);
+}
+
+#[test]
+fn status_json() {
+    assert_wire(&[
+        (Status::Active, r#""active""#),
+        (Status::Inactive, r#""inactive""#),
+        (Status::Pending, r#""pending""#),
+    ]);
+}
+
+#[test]
+fn packet_type_json() {
+    assert_wire(&[
+        (PacketType::AdvInd, r#""ADV_IND""#),
+        (PacketType::AdvDirectInd, r#""ADV_DIRECT_IND""#),
+        (PacketType::AdvNonconnInd, r#""ADV_NONCONN_IND""#),
+        (PacketType::ScanReq, r#""SCAN_REQ""#),
+        (PacketType::ScanRsp, r#""SCAN_RSP""#),
+        (PacketType::ConnectInd, r#""CONNECT_IND""#),
+        (PacketType::AdvScanInd, r#""ADV_SCAN_IND""#),
+    ]);
+}
+
+#[test]
+fn aliases_are_read_but_never_written() {
+    let old: PacketType = serde_json::from_str(r#""CONNECT_REQ""#).unwrap();
+    assert_eq!(old, PacketType::ConnectInd);
+    assert_eq!(serde_json::to_string(&old).unwrap(), r#""CONNECT_IND""#);
+
+    let short: Vec<Direction> = serde_json::from_str(r#"["n", "s", "e", "w"]"#).unwrap();
+    assert_eq!(short, Direction::iter().collect::<Vec<_>>());
+    assert_eq!(
+        serde_json::to_string(&short).unwrap(),
+        r#"["north","south","east","west"]"#
+    );
+}
+
+#[test]
+fn unknown_names_are_rejected() {
+    for json in [
+        r#""up""#,
+        r#""North""#,
+        r#""adv_ind""#,
+        r#""""#,
+        "0",
+        "null",
+    ] {
+        assert!(serde_json::from_str::<Direction>(json).is_err(), "{}", json);
+        assert!(
+            serde_json::from_str::<PacketType>(json).is_err(),
+            "{}",
+            json
+        );
+    }
+    let e = serde_json::from_str::<Status>(r#""paused""#).unwrap_err();
+    assert!(
+        e.to_string().starts_with("unknown variant `paused`"),
+        "{}",
+        e
+    );
+}
+
+#[test]
+fn fields_of_a_record_use_the_same_names() {
+    #[derive(Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Observation {
+        heading: Direction,
+        status: Status,
+        pdu: PacketType,
+    }
+
+    let seen = Observation {
+        heading: Direction::West,
+        status: Status::Pending,
+        pdu: PacketType::ScanRsp,
+    };
+    let json = serde_json::to_string(&seen).unwrap();
+    assert_eq!(
+        json,
+        r#"{"heading":"west","status":"pending","pdu":"SCAN_RSP"}"#
+    );
+    assert_eq!(serde_json::from_str::<Observation>(&json).unwrap(), seen);
+}
@@ -0,0 +1,45 @@
+use enum_derive::StateMachine;
+
+#[derive(Debug, Clone, PartialEq)]
+enum PowerEvent {
+    LowBattery,
+    Activity,
+    TimerExpired,
+}
+
+// One rule per #[transition], mixed with a #[transitions] table; the
+// rules keep the order they are written in
+#[derive(Debug, Clone, Copy, PartialEq, StateMachine)]
+#[state_machine(event = PowerEvent)]
+#[transition(from = "_", on = "LowBattery", to = "DeepSleep")]
+#[transitions(
+    (Active, TimerExpired) => Idle,
+    (Idle, TimerExpired) => DeepSleep,
+)]
+#[transition(from = "Idle | DeepSleep", on = "Activity", to = "Active")]
+enum PowerState {
+    Active,
+    Idle,
+    DeepSleep,
+}
+
+fn main() {
+    use PowerState::*;
+    assert_eq!(Active.next(&PowerEvent::LowBattery), Ok(DeepSleep));
+    assert_eq!(Active.next(&PowerEvent::TimerExpired), Ok(Idle));
+    assert_eq!(DeepSleep.next(&PowerEvent::Activity), Ok(Active));
+    assert!(Active.next(&PowerEvent::Activity).is_err());
+    let table: Vec<String> = PowerState::TRANSITIONS
+        .iter()
+        .map(|rule| rule.to_string())
+        .collect();
+    assert_eq!(
+        table,
+        [
+            "_ --LowBattery--> DeepSleep",
+            "Active --TimerExpired--> Idle",
+            "Idle --TimerExpired--> DeepSleep",
+            "Idle | DeepSleep --Activity--> Active",
+        ]
+    );
+}
@@ -0,0 +1,16 @@
+// Misuse of the derives must fail to compile with a message that points
+// at the problem. Each file in tests/ui is compiled on its own and its
+// errors compared with the .stderr file next to it; after changing a
+// message, regenerate them with TRYBUILD=overwrite cargo test
+#[test]
+fn misuse_is_a_compile_error() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
+
+// The files in tests/pass must compile, and their `main` must not panic
+#[test]
+fn attribute_forms_compile_and_run() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/pass/*.rs");
+}
@@ -0,0 +1,10 @@
+use enum_derive::EnumDisplay;
+
+#[derive(EnumDisplay)]
+#[variant(rename_all = "camelCase")]
+enum Status {
+    Active,
+    Inactive,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use enum_derive::EnumDisplay;
+
+#[derive(EnumDisplay)]
+struct Reading {
+    celsius: f32,
+}
+
+fn main() {}
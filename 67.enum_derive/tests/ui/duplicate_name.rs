@@ -0,0 +1,11 @@
+use enum_derive::EnumFromStr;
+
+#[derive(Debug, EnumFromStr)]
+#[variant(rename_all = "lowercase")]
+enum Direction {
+    North,
+    #[variant(alias = "north")]
+    Up,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use enum_derive::EnumFromStr;
+
+#[derive(Debug, EnumFromStr)]
+enum Event {
+    LinkUp,
+    SensorFault { code: u16 },
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+// 07.ble's PduType keeps unknown header values; EnumIter can't build them
+use enum_derive::EnumIter;
+
+#[derive(EnumIter)]
+enum PduType {
+    AdvInd,
+    ScanRsp,
+    Unknown(u8),
+}
+
+fn main() {}
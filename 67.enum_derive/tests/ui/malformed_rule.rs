@@ -0,0 +1,17 @@
+use enum_derive::StateMachine;
+
+#[derive(Debug, Clone)]
+enum LightEvent {
+    TimerExpired,
+}
+
+// A rule is `(state, event) => next`; the event is missing here
+#[derive(Debug, Clone, Copy, PartialEq, StateMachine)]
+#[state_machine(event = LightEvent)]
+#[transitions((Red) => Green)]
+enum TrafficLight {
+    Red,
+    Green,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use enum_derive::StateMachine;
+
+#[derive(Debug, Clone, Copy, PartialEq, StateMachine)]
+#[transitions((Red, TimerExpired) => Green)]
+enum TrafficLight {
+    Red,
+    Green,
+}
+
+fn main() {}
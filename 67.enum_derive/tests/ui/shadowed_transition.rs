@@ -0,0 +1,22 @@
+use enum_derive::StateMachine;
+
+#[derive(Debug, Clone)]
+enum PowerEvent {
+    Activity,
+    LowBattery,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, StateMachine)]
+#[state_machine(event = PowerEvent)]
+#[transitions(
+    (_, LowBattery) => DeepSleep,
+    (_, Activity) => Active,
+    // Never reached: the wildcard above already handles it
+    (Active, LowBattery) => Active,
+)]
+enum PowerState {
+    Active,
+    DeepSleep,
+}
+
+fn main() {}
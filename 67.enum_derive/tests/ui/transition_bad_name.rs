@@ -0,0 +1,17 @@
+use enum_derive::StateMachine;
+
+#[derive(Debug, Clone)]
+enum LightEvent {
+    TimerExpired,
+}
+
+// Names inside the strings must be identifiers
+#[derive(Debug, Clone, Copy, PartialEq, StateMachine)]
+#[state_machine(event = LightEvent)]
+#[transition(from = "Red", on = "Timer Expired", to = "Green")]
+enum TrafficLight {
+    Red,
+    Green,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use enum_derive::StateMachine;
+
+#[derive(Debug, Clone)]
+enum LightEvent {
+    TimerExpired,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, StateMachine)]
+#[state_machine(event = LightEvent)]
+#[transition(from = "Red", on = "TimerExpired")]
+enum TrafficLight {
+    Red,
+    Green,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use enum_derive::EnumDisplay;
+
+#[derive(EnumDisplay)]
+enum Status {
+    #[variant(rename = "on")]
+    Active,
+    Inactive,
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use enum_derive::StateMachine;
+
+#[derive(Debug, Clone)]
+enum LightEvent {
+    TimerExpired,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, StateMachine)]
+#[state_machine(event = LightEvent)]
+#[transitions(
+    (Red, TimerExpired) => Green,
+    (Green, TimerExpired) => Blue,
+)]
+enum TrafficLight {
+    Red,
+    Green,
+}
+
+fn main() {}
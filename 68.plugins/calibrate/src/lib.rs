@@ -0,0 +1,35 @@
+// Linear calibration, `value * gain + offset`, for a sensor whose
+// datasheet curve drifted. Readings that are not finite before or after
+// are dropped rather than passed on to the rules and the uplink.
+//
+//   config: "gain=1.02 offset=-0.3"
+
+use plugin_abi::{declare_plugin, parse_config, Processor, Reading};
+
+struct Calibrate {
+    gain: f64,
+    offset: f64,
+}
+
+impl Processor for Calibrate {
+    fn new(config: &str) -> Result<Calibrate, String> {
+        let mut calibrate = Calibrate {
+            gain: 1.0,
+            offset: 0.0,
+        };
+        for (key, value) in parse_config(config, &["gain", "offset"])? {
+            match key {
+                "gain" => calibrate.gain = value,
+                _ => calibrate.offset = value,
+            }
+        }
+        Ok(calibrate)
+    }
+
+    fn process(&mut self, reading: &mut Reading) -> bool {
+        reading.value = reading.value * self.gain + self.offset;
+        reading.value.is_finite()
+    }
+}
+
+declare_plugin!(Calibrate, "calibrate");
@@ -0,0 +1,54 @@
+// Report-by-exception: forward a reading only when it has moved by at
+// least `band` since the last one forwarded for that sensor, or when
+// `heartbeat` readings in a row have been dropped. A slowly drifting
+// temperature then costs a fraction of the uplink, and the heartbeat
+// shows the sensor is still alive.
+//
+//   config: "band=0.5 heartbeat=20"
+
+use plugin_abi::{declare_plugin, parse_config, Processor, Reading};
+use std::collections::HashMap;
+
+struct Deadband {
+    band: f64,
+    heartbeat: u32,
+    // Per sensor: the last value forwarded and the readings dropped since
+    last: HashMap<u16, (f64, u32)>,
+}
+
+impl Processor for Deadband {
+    fn new(config: &str) -> Result<Deadband, String> {
+        let mut deadband = Deadband {
+            band: 0.5,
+            heartbeat: 20,
+            last: HashMap::new(),
+        };
+        for (key, value) in parse_config(config, &["band", "heartbeat"])? {
+            match key {
+                "band" if value > 0.0 => deadband.band = value,
+                "heartbeat" if value >= 1.0 && value.fract() == 0.0 => {
+                    deadband.heartbeat = value as u32
+                }
+                _ => return Err(format!("{} out of range: {}", key, value)),
+            }
+        }
+        Ok(deadband)
+    }
+
+    fn process(&mut self, reading: &mut Reading) -> bool {
+        match self.last.get_mut(&reading.sensor_id) {
+            Some((last, dropped))
+                if (reading.value - *last).abs() < self.band && *dropped < self.heartbeat =>
+            {
+                *dropped += 1;
+                false
+            }
+            _ => {
+                self.last.insert(reading.sensor_id, (reading.value, 0));
+                true
+            }
+        }
+    }
+}
+
+declare_plugin!(Deadband, "deadband");
@@ -0,0 +1,30 @@
+// A plugin written for version 0 of the interface, before readings had a
+// timestamp and before instances had state: a name and a function from
+// value to value. The host must read `abi_version`, see 0 and stop; read
+// as today's table, `transform` would be taken for the `version` string.
+
+use std::ffi::c_char;
+
+#[repr(C)]
+pub struct ProcessorPluginV0 {
+    abi_version: u32,
+    name: *const c_char,
+    transform: extern "C" fn(value: f64) -> f64,
+}
+
+unsafe impl Sync for ProcessorPluginV0 {}
+
+extern "C" fn fahrenheit(celsius: f64) -> f64 {
+    celsius * 9.0 / 5.0 + 32.0
+}
+
+static PLUGIN: ProcessorPluginV0 = ProcessorPluginV0 {
+    abi_version: 0,
+    name: c"fahrenheit".as_ptr(),
+    transform: fahrenheit,
+};
+
+#[no_mangle]
+pub extern "C" fn processor_plugin() -> *const ProcessorPluginV0 {
+    &PLUGIN
+}
@@ -0,0 +1,181 @@
+// The interface between the gateway and its processor plugins
+//
+// A plugin is a shared library (a cdylib) exporting one C function:
+//
+//   const ProcessorPlugin *processor_plugin(void);
+//
+// The table it returns starts with `abi_version`. The host reads that
+// field first and only touches the rest if it equals ABI_VERSION, so a
+// plugin built against another version of this crate is refused instead
+// of being called through a table with a different layout.
+//
+// Everything that crosses the boundary is #[repr(C)] and every function is
+// `extern "C"`. Rust's own ABI is unstable: a host and a plugin built by
+// different compilers may disagree about the layout of a Rust struct, a
+// trait object or a `String`, and nothing would detect it.
+
+use std::ffi::{c_char, c_void, CStr};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+// Bump on any change to `ProcessorPlugin` or `Reading`
+pub const ABI_VERSION: u32 = 1;
+
+// NUL-terminated, as the dynamic loader wants it
+pub const ENTRY_SYMBOL: &[u8] = b"processor_plugin\0";
+
+pub type EntryFn = unsafe extern "C" fn() -> *const ProcessorPlugin;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reading {
+    pub sensor_id: u16,
+    pub timestamp_ms: u64,
+    pub value: f64,
+}
+
+// What `process` returns; anything else is a broken plugin
+pub const FORWARD: u32 = 0;
+pub const DROP: u32 = 1;
+
+#[repr(C)]
+pub struct ProcessorPlugin {
+    // Must stay the first field in every version
+    pub abi_version: u32,
+    // NUL-terminated, valid while the library is loaded
+    pub name: *const c_char,
+    pub version: *const c_char,
+    // A new instance configured by `config`, or null with a message in
+    // `error` (at most `error_len` bytes including the NUL)
+    pub create: unsafe extern "C" fn(
+        config: *const c_char,
+        error: *mut c_char,
+        error_len: usize,
+    ) -> *mut c_void,
+    // May change the reading in place; returns FORWARD or DROP
+    pub process: unsafe extern "C" fn(state: *mut c_void, reading: *mut Reading) -> u32,
+    pub destroy: unsafe extern "C" fn(state: *mut c_void),
+}
+
+// The table is a read-only static and its strings are literals
+unsafe impl Sync for ProcessorPlugin {}
+
+// What a plugin writes in Rust; `declare_plugin!` wraps it in the C table
+pub trait Processor: Sized {
+    fn new(config: &str) -> Result<Self, String>;
+
+    // true to forward the reading, false to drop it
+    fn process(&mut self, reading: &mut Reading) -> bool;
+}
+
+// Exports `processor_plugin` returning a table for `$processor`
+#[macro_export]
+macro_rules! declare_plugin {
+    ($processor:ty, $name:literal) => {
+        static PLUGIN: $crate::ProcessorPlugin = $crate::ProcessorPlugin {
+            abi_version: $crate::ABI_VERSION,
+            name: concat!($name, "\0").as_ptr().cast(),
+            version: concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast(),
+            create: $crate::create::<$processor>,
+            process: $crate::process::<$processor>,
+            destroy: $crate::destroy::<$processor>,
+        };
+
+        #[no_mangle]
+        pub extern "C" fn processor_plugin() -> *const $crate::ProcessorPlugin {
+            &PLUGIN
+        }
+    };
+}
+
+// The shims below are generic, so each plugin gets its own copies. A
+// panic must not unwind into the host's frames (for `extern "C"` it would
+// abort the whole gateway), so each one is caught and reported instead.
+
+fn write_error(message: &str, error: *mut c_char, error_len: usize) {
+    if error.is_null() || error_len == 0 {
+        return;
+    }
+    let bytes = message.as_bytes();
+    let n = bytes.len().min(error_len - 1);
+    // SAFETY: the caller gave us `error_len` writable bytes
+    unsafe {
+        ptr::copy_nonoverlapping(bytes.as_ptr(), error.cast::<u8>(), n);
+        *error.add(n) = 0;
+    }
+}
+
+/// # Safety
+///
+/// `config` must point to a NUL-terminated string, and `error` must be null
+/// or valid for `error_len` bytes of writes.
+pub unsafe extern "C" fn create<P: Processor>(
+    config: *const c_char,
+    error: *mut c_char,
+    error_len: usize,
+) -> *mut c_void {
+    if config.is_null() {
+        write_error("config is null", error, error_len);
+        return ptr::null_mut();
+    }
+    let config = CStr::from_ptr(config).to_string_lossy();
+    match panic::catch_unwind(|| P::new(&config)) {
+        Ok(Ok(processor)) => Box::into_raw(Box::new(processor)).cast(),
+        Ok(Err(message)) => {
+            write_error(&message, error, error_len);
+            ptr::null_mut()
+        }
+        Err(_) => {
+            write_error("panicked while creating", error, error_len);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// # Safety
+///
+/// `state` must come from `create::<P>` and not be destroyed yet; `reading`
+/// must be null or valid for reads and writes.
+pub unsafe extern "C" fn process<P: Processor>(state: *mut c_void, reading: *mut Reading) -> u32 {
+    let (Some(processor), Some(reading)) = (state.cast::<P>().as_mut(), reading.as_mut()) else {
+        return DROP;
+    };
+    match panic::catch_unwind(AssertUnwindSafe(|| processor.process(reading))) {
+        Ok(true) => FORWARD,
+        Ok(false) | Err(_) => DROP,
+    }
+}
+
+/// # Safety
+///
+/// `state` must be null or come from `create::<P>`, and is invalid after.
+pub unsafe extern "C" fn destroy<P: Processor>(state: *mut c_void) {
+    if !state.is_null() {
+        drop(Box::from_raw(state.cast::<P>()));
+    }
+}
+
+// Configs are `key=value` pairs separated by spaces or commas, with
+// numeric values; unknown keys are errors so typos don't go unnoticed
+pub fn parse_config<'a>(config: &'a str, keys: &[&str]) -> Result<Vec<(&'a str, f64)>, String> {
+    config
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected key=value, got {:?}", pair))?;
+            if !keys.contains(&key) {
+                return Err(format!(
+                    "unknown key {:?}, expected {}",
+                    key,
+                    keys.join(", ")
+                ));
+            }
+            let value = value
+                .parse::<f64>()
+                .map_err(|_| format!("{} is not a number: {:?}", key, value))?;
+            Ok((key, value))
+        })
+        .collect()
+}
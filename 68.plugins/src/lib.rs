@@ -0,0 +1,16 @@
+// Processor plugins: telemetry filters the gateway loads at run time
+//
+// - `loader`: finding, loading and checking plugin libraries
+// - `pipeline`: running readings through a chain of plugin instances
+// - `samples`: building the sample plugins for the demo and the tests
+//
+// The interface itself is in plugin_abi/, shared with the plugins;
+// deadband/, calibrate/ and outdated/ are sample plugins built as cdylibs.
+
+pub mod loader;
+pub mod pipeline;
+pub mod samples;
+
+pub use loader::{discover, Instance, Plugin, PluginError};
+pub use pipeline::Pipeline;
+pub use plugin_abi::{Reading, ABI_VERSION};
@@ -0,0 +1,231 @@
+// Loading processor plugins from shared libraries
+//
+//   discover(dir)            every lib*.so (.dylib, .dll) in the directory
+//     └─ Plugin::load        dlopen, look up `processor_plugin`, check the ABI
+//          └─ instantiate    `create(config)` -> Instance, one per pipeline
+//
+// Nothing a plugin hands back is trusted before it is checked: the table
+// pointer for null, `abi_version` before any other field, the strings for
+// null and UTF-8, and every `process` result for a known value. Each
+// `Instance` holds the library open, so a plugin can't be unloaded while
+// its code might still run.
+
+use libloading::Library;
+use plugin_abi::{EntryFn, ProcessorPlugin, Reading, ABI_VERSION, DROP, ENTRY_SYMBOL, FORWARD};
+use std::error::Error;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::ptr::NonNull;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub enum PluginError {
+    Open(libloading::Error),
+    // A shared library, but not a plugin
+    NoEntry,
+    NullTable,
+    AbiMismatch { found: u32, expected: u32 },
+    BadString(&'static str),
+    Create { plugin: String, message: String },
+    BadConfig,
+    BadResult { plugin: String, result: u32 },
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PluginError::Open(e) => write!(f, "cannot load: {}", e),
+            PluginError::NoEntry => write!(
+                f,
+                "no `{}` symbol, not a plugin",
+                String::from_utf8_lossy(&ENTRY_SYMBOL[..ENTRY_SYMBOL.len() - 1])
+            ),
+            PluginError::NullTable => write!(f, "plugin returned a null table"),
+            PluginError::AbiMismatch { found, expected } => write!(
+                f,
+                "built for plugin ABI {}, this host speaks {}",
+                found, expected
+            ),
+            PluginError::BadString(field) => {
+                write!(f, "plugin {} is null or not UTF-8", field)
+            }
+            PluginError::Create { plugin, message } => {
+                write!(f, "{} rejected its config: {}", plugin, message)
+            }
+            PluginError::BadConfig => write!(f, "config contains a NUL byte"),
+            PluginError::BadResult { plugin, result } => {
+                write!(f, "{} returned unknown result {}", plugin, result)
+            }
+        }
+    }
+}
+
+impl Error for PluginError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PluginError::Open(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+// SAFETY: the caller guarantees `ptr` is null or a NUL-terminated string
+// that outlives the returned reference
+unsafe fn text(ptr: *const c_char, field: &'static str) -> Result<String, PluginError> {
+    if ptr.is_null() {
+        return Err(PluginError::BadString(field));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map(str::to_string)
+        .map_err(|_| PluginError::BadString(field))
+}
+
+pub struct Plugin {
+    path: PathBuf,
+    name: String,
+    version: String,
+    table: NonNull<ProcessorPlugin>,
+    library: Arc<Library>,
+}
+
+impl Plugin {
+    /// # Safety
+    ///
+    /// Loading a library runs its initialisers, and a plugin that passes
+    /// the checks is still trusted to follow the interface. Only load
+    /// libraries from a directory as trusted as the gateway binary itself.
+    pub unsafe fn load(path: &Path) -> Result<Plugin, PluginError> {
+        let library = Library::new(path).map_err(PluginError::Open)?;
+        let entry = *library
+            .get::<EntryFn>(ENTRY_SYMBOL)
+            .map_err(|_| PluginError::NoEntry)?;
+        let table = NonNull::new(entry().cast_mut()).ok_or(PluginError::NullTable)?;
+        // Only the first field has the same meaning in every version
+        let found = table.cast::<u32>().read();
+        if found != ABI_VERSION {
+            return Err(PluginError::AbiMismatch {
+                found,
+                expected: ABI_VERSION,
+            });
+        }
+        let plugin = table.as_ref();
+        Ok(Plugin {
+            path: path.to_path_buf(),
+            name: text(plugin.name, "name")?,
+            version: text(plugin.version, "version")?,
+            table,
+            library: Arc::new(library),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn instantiate(&self, config: &str) -> Result<Instance, PluginError> {
+        let config = CString::new(config).map_err(|_| PluginError::BadConfig)?;
+        let mut error = [0 as c_char; 256];
+        // SAFETY: the table was checked in `load` and the library is open
+        let plugin = unsafe { self.table.as_ref() };
+        let state = unsafe { (plugin.create)(config.as_ptr(), error.as_mut_ptr(), error.len()) };
+        let Some(state) = NonNull::new(state) else {
+            // SAFETY: the plugin NUL-terminates within `error.len()` bytes,
+            // and the buffer starts zeroed in case it wrote nothing
+            let message = unsafe { CStr::from_ptr(error.as_ptr()) };
+            return Err(PluginError::Create {
+                plugin: self.name.clone(),
+                message: message.to_string_lossy().into_owned(),
+            });
+        };
+        Ok(Instance {
+            name: self.name.clone(),
+            state,
+            process: plugin.process,
+            destroy: plugin.destroy,
+            _library: Arc::clone(&self.library),
+        })
+    }
+
+    // Libraries open: one for the plugin plus one per live instance
+    pub fn handles(&self) -> usize {
+        Arc::strong_count(&self.library)
+    }
+}
+
+// One configured processor, owned by the host and destroyed by the plugin
+pub struct Instance {
+    name: String,
+    state: NonNull<c_void>,
+    process: unsafe extern "C" fn(*mut c_void, *mut Reading) -> u32,
+    destroy: unsafe extern "C" fn(*mut c_void),
+    // Dropped after `destroy` has run, since fields drop after `Drop::drop`
+    _library: Arc<Library>,
+}
+
+impl Instance {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // Ok(true) to forward the (possibly changed) reading, Ok(false) to drop it
+    pub fn process(&mut self, reading: &mut Reading) -> Result<bool, PluginError> {
+        // SAFETY: `state` came from this plugin's `create` and is live
+        match unsafe { (self.process)(self.state.as_ptr(), reading) } {
+            FORWARD => Ok(true),
+            DROP => Ok(false),
+            result => Err(PluginError::BadResult {
+                plugin: self.name.clone(),
+                result,
+            }),
+        }
+    }
+}
+
+impl Drop for Instance {
+    fn drop(&mut self) {
+        // SAFETY: created by this plugin, destroyed exactly once
+        unsafe { (self.destroy)(self.state.as_ptr()) }
+    }
+}
+
+// Shared library file names on this platform: libx.so, libx.dylib, x.dll
+pub fn is_library(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    name.starts_with(std::env::consts::DLL_PREFIX)
+        && name.ends_with(std::env::consts::DLL_SUFFIX)
+        && path.is_file()
+}
+
+// Every shared library in `dir`, in name order, each loaded or with the
+// reason it was refused
+/// # Safety
+///
+/// As for [`Plugin::load`], for every library in the directory.
+pub unsafe fn discover(dir: &Path) -> io::Result<Vec<(PathBuf, Result<Plugin, PluginError>)>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<_>>()?;
+    paths.retain(|p| is_library(p));
+    paths.sort();
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let plugin = Plugin::load(&path);
+            (path, plugin)
+        })
+        .collect())
+}
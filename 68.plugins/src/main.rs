@@ -0,0 +1,161 @@
+use plugins::samples::{self, SAMPLES};
+use plugins::{discover, Pipeline, Plugin, Reading, ABI_VERSION};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const CALIBRATE: &str = "gain=1.02 offset=-0.3";
+const DEADBAND: &str = "band=0.5, heartbeat=20";
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    // Uniform in [-1, 1)
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+    }
+}
+
+// Three slowly drifting temperatures with noise, and a sensor 2 that
+// reports NaN now and then
+fn readings(rounds: u64) -> Vec<Reading> {
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+    let mut out = Vec::new();
+    for round in 0..rounds {
+        for sensor_id in 1..=3u16 {
+            let drift = (round as f64 / 40.0 + sensor_id as f64).sin() * 3.0;
+            let value = if sensor_id == 2 && round % 97 == 50 {
+                f64::NAN
+            } else {
+                18.0 + sensor_id as f64 + drift + rng.unit() * 0.2
+            };
+            out.push(Reading {
+                sensor_id,
+                timestamp_ms: round * 1000,
+                value,
+            });
+        }
+    }
+    out
+}
+
+fn main() {
+    println!("=== Processor Plugin Examples ===\n");
+
+    // 1. Where the plugins come from
+    println!("1. Plugin directory:");
+    let dir = match std::env::args_os().nth(1) {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            println!("   building {} into target/plugins", SAMPLES.join(", "));
+            match samples::build() {
+                Ok(dir) => dir,
+                Err(e) => {
+                    println!("   {}", e);
+                    return;
+                }
+            }
+        }
+    };
+    println!("   {}", dir.display());
+
+    // 2. Discovery with ABI checks
+    println!("\n2. Discovery (host ABI {}):", ABI_VERSION);
+    // SAFETY: the directory holds only the samples built above
+    let found = match unsafe { discover(&dir) } {
+        Ok(found) => found,
+        Err(e) => {
+            println!("   cannot read {}: {}", dir.display(), e);
+            return;
+        }
+    };
+    let mut loaded: HashMap<String, Plugin> = HashMap::new();
+    for (path, result) in found {
+        let file = path.file_name().unwrap_or_default().to_string_lossy();
+        match result {
+            Ok(plugin) => {
+                println!(
+                    "   {:<20} loaded {} {}",
+                    file,
+                    plugin.name(),
+                    plugin.version()
+                );
+                loaded.insert(plugin.name().to_string(), plugin);
+            }
+            Err(e) => println!("   {:<20} refused: {}", file, e),
+        }
+    }
+    let (Some(calibrate), Some(deadband)) = (loaded.get("calibrate"), loaded.get("deadband"))
+    else {
+        println!("   calibrate and deadband are needed for the rest");
+        return;
+    };
+
+    // 3. A chain of plugin instances
+    println!("\n3. Pipeline:");
+    let mut pipeline = Pipeline::new();
+    for (plugin, config) in [(calibrate, CALIBRATE), (deadband, DEADBAND)] {
+        match plugin.instantiate(config) {
+            Ok(instance) => pipeline.push(instance),
+            Err(e) => {
+                println!("   {}", e);
+                return;
+            }
+        }
+    }
+    println!("   {}", pipeline.stages().collect::<Vec<_>>().join(" -> "));
+    let input = readings(400);
+    let mut forwarded = Vec::new();
+    for &reading in &input {
+        match pipeline.run(reading) {
+            Ok(Some(out)) => forwarded.push(out),
+            Ok(None) => {}
+            Err(e) => println!("   {}", e),
+        }
+    }
+    for r in forwarded.iter().take(4) {
+        println!(
+            "   t={:>5} ms  sensor {}  {:.2}",
+            r.timestamp_ms, r.sensor_id, r.value
+        );
+    }
+    println!(
+        "   {} readings in, {} forwarded ({:.0}% saved)",
+        input.len(),
+        forwarded.len(),
+        100.0 * (1.0 - forwarded.len() as f64 / input.len() as f64)
+    );
+
+    // 4. Bad configuration is reported by the plugin, through a C buffer
+    println!("\n4. Configuration errors:");
+    for (plugin, config) in [
+        (deadband, "band=-1"),
+        (deadband, "bnad=1"),
+        (calibrate, "gain=fast"),
+    ] {
+        match plugin.instantiate(config) {
+            Ok(_) => println!("   {:<10} accepted {:?}", plugin.name(), config),
+            Err(e) => println!("   {}", e),
+        }
+    }
+
+    // 5. Instances keep their library loaded
+    println!("\n5. Library lifetime:");
+    println!(
+        "   deadband handles with the pipeline: {}",
+        deadband.handles()
+    );
+    drop(pipeline);
+    println!(
+        "   after dropping the pipeline:         {}",
+        deadband.handles()
+    );
+
+    println!("\n=== End of Processor Plugin Examples ===");
+}
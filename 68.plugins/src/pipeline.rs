@@ -0,0 +1,34 @@
+// Plugin instances chained in order, as a gateway would run them on each
+// reading between the sensor poll and the broker
+
+use crate::loader::{Instance, PluginError};
+use plugin_abi::Reading;
+
+#[derive(Default)]
+pub struct Pipeline {
+    stages: Vec<Instance>,
+}
+
+impl Pipeline {
+    pub fn new() -> Pipeline {
+        Pipeline::default()
+    }
+
+    pub fn push(&mut self, stage: Instance) {
+        self.stages.push(stage);
+    }
+
+    pub fn stages(&self) -> impl Iterator<Item = &str> {
+        self.stages.iter().map(Instance::name)
+    }
+
+    // The reading as the last stage left it, or None if a stage dropped it
+    pub fn run(&mut self, mut reading: Reading) -> Result<Option<Reading>, PluginError> {
+        for stage in &mut self.stages {
+            if !stage.process(&mut reading)? {
+                return Ok(None);
+            }
+        }
+        Ok(Some(reading))
+    }
+}
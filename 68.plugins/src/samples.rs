@@ -0,0 +1,30 @@
+// The sample plugins, built for the demo and the tests
+//
+// A deployment builds plugins on their own and copies the libraries into
+// the gateway's plugin directory; this builds deadband/, calibrate/ and
+// outdated/ into target/plugins the same way, each as its own package.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub const SAMPLES: [&str; 3] = ["calibrate", "deadband", "outdated"];
+
+// The directory holding the three libraries
+pub fn build() -> Result<PathBuf, String> {
+    let lesson = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let target = lesson.join("target").join("plugins");
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    for name in SAMPLES {
+        let status = Command::new(&cargo)
+            .args(["build", "-q", "--manifest-path"])
+            .arg(lesson.join(name).join("Cargo.toml"))
+            .arg("--target-dir")
+            .arg(&target)
+            .status()
+            .map_err(|e| format!("cannot run cargo: {}", e))?;
+        if !status.success() {
+            return Err(format!("building {} failed: {}", name, status));
+        }
+    }
+    Ok(target.join("debug"))
+}
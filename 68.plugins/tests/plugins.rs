@@ -0,0 +1,220 @@
+// The sample plugins, built once per run with `samples::build` and loaded
+// from target/plugins as the gateway would load them.
+
+use plugins::{discover, samples, Pipeline, Plugin, PluginError, Reading, ABI_VERSION};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const CALIBRATE: &str = "gain=1.02 offset=-0.3";
+const DEADBAND: &str = "band=0.5, heartbeat=20";
+
+fn plugin_dir() -> &'static Path {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| samples::build().unwrap())
+}
+
+fn load(name: &str) -> Plugin {
+    let file = format!(
+        "{}{}{}",
+        std::env::consts::DLL_PREFIX,
+        name,
+        std::env::consts::DLL_SUFFIX
+    );
+    // SAFETY: the directory holds only the sample plugins
+    unsafe { Plugin::load(&plugin_dir().join(file)) }.unwrap()
+}
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+// Three drifting temperatures with noise; sensor 2 reports NaN now and then
+fn readings(rounds: u64) -> Vec<Reading> {
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+    let mut out = Vec::new();
+    for round in 0..rounds {
+        for sensor_id in 1..=3u16 {
+            let drift = (round as f64 / 40.0 + sensor_id as f64).sin() * 3.0;
+            let noise = (rng.next() >> 11) as f64 / (1u64 << 52) as f64 - 1.0;
+            let value = if sensor_id == 2 && round % 97 == 50 {
+                f64::NAN
+            } else {
+                18.0 + sensor_id as f64 + drift + noise * 0.2
+            };
+            out.push(Reading {
+                sensor_id,
+                timestamp_ms: round * 1000,
+                value,
+            });
+        }
+    }
+    out
+}
+
+// calibrate then deadband, written directly in Rust
+fn reference(readings: &[Reading]) -> Vec<Reading> {
+    let mut last: HashMap<u16, (f64, u32)> = HashMap::new();
+    let mut out = Vec::new();
+    for &reading in readings {
+        let value = reading.value * 1.02 - 0.3;
+        if !value.is_finite() {
+            continue;
+        }
+        if let Some((sent, dropped)) = last.get_mut(&reading.sensor_id) {
+            if (value - *sent).abs() < 0.5 && *dropped < 20 {
+                *dropped += 1;
+                continue;
+            }
+        }
+        last.insert(reading.sensor_id, (value, 0));
+        out.push(Reading { value, ..reading });
+    }
+    out
+}
+
+#[test]
+fn discovery_refuses_the_outdated_abi() {
+    // SAFETY: only the sample plugins, as in `load`
+    let found = unsafe { discover(plugin_dir()) }.unwrap();
+    let outcome: Vec<(String, Result<String, String>)> = found
+        .iter()
+        .map(|(path, result)| {
+            let file = path.file_stem().unwrap().to_string_lossy().into_owned();
+            let result = match result {
+                Ok(p) => Ok(format!("{} {}", p.name(), p.version())),
+                Err(e) => Err(e.to_string()),
+            };
+            (file, result)
+        })
+        .collect();
+    let prefix = std::env::consts::DLL_PREFIX;
+    assert_eq!(
+        outcome,
+        [
+            (format!("{}calibrate", prefix), Ok("calibrate 0.1.0".into())),
+            (format!("{}deadband", prefix), Ok("deadband 0.1.0".into())),
+            (
+                format!("{}outdated", prefix),
+                Err(format!(
+                    "built for plugin ABI 0, this host speaks {}",
+                    ABI_VERSION
+                ))
+            ),
+        ]
+    );
+    assert!(matches!(
+        &found[2].1,
+        Err(PluginError::AbiMismatch { found: 0, expected }) if *expected == ABI_VERSION
+    ));
+}
+
+#[test]
+fn a_file_that_is_not_a_library_cannot_be_opened() {
+    // SAFETY: dlopen fails on a text file before running anything
+    let result = unsafe {
+        Plugin::load(
+            Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("Cargo.toml")
+                .as_path(),
+        )
+    };
+    let e = result.err().unwrap();
+    assert!(matches!(e, PluginError::Open(_)), "{}", e);
+    assert!(std::error::Error::source(&e).is_some());
+}
+
+#[test]
+fn the_chain_matches_the_rust_reference() {
+    let (calibrate, deadband) = (load("calibrate"), load("deadband"));
+    let mut pipeline = Pipeline::new();
+    pipeline.push(calibrate.instantiate(CALIBRATE).unwrap());
+    pipeline.push(deadband.instantiate(DEADBAND).unwrap());
+    assert_eq!(
+        pipeline.stages().collect::<Vec<_>>(),
+        ["calibrate", "deadband"]
+    );
+
+    let input = readings(400);
+    let forwarded: Vec<Reading> = input
+        .iter()
+        .filter_map(|&r| pipeline.run(r).unwrap())
+        .collect();
+    assert_eq!(forwarded, reference(&input));
+    assert!(forwarded.len() < input.len() / 4);
+    assert!(forwarded.iter().all(|r| r.value.is_finite()));
+
+    // The heartbeat: sensors 1 and 3 never stay silent past 21 readings.
+    // Sensor 2's NaNs never reach the deadband, so it may go one longer
+    for sensor in [1, 3] {
+        let times: Vec<u64> = forwarded
+            .iter()
+            .filter(|r| r.sensor_id == sensor)
+            .map(|r| r.timestamp_ms)
+            .collect();
+        let gap = times.windows(2).map(|w| w[1] - w[0]).max().unwrap();
+        assert!(gap <= 21_000, "sensor {} silent for {} ms", sensor, gap);
+    }
+}
+
+#[test]
+fn an_empty_pipeline_forwards_unchanged() {
+    let mut pipeline = Pipeline::new();
+    let reading = Reading {
+        sensor_id: 4,
+        timestamp_ms: 10,
+        value: f64::INFINITY,
+    };
+    assert_eq!(pipeline.run(reading).unwrap(), Some(reading));
+}
+
+#[test]
+fn bad_config_comes_back_through_the_error_buffer() {
+    let (calibrate, deadband) = (load("calibrate"), load("deadband"));
+    let message =
+        |plugin: &Plugin, config: &str| plugin.instantiate(config).err().unwrap().to_string();
+    assert_eq!(
+        message(&deadband, "band=-1"),
+        "deadband rejected its config: band out of range: -1"
+    );
+    assert_eq!(
+        message(&deadband, "bnad=1"),
+        "deadband rejected its config: unknown key \"bnad\", expected band, heartbeat"
+    );
+    assert_eq!(
+        message(&calibrate, "gain=fast"),
+        "calibrate rejected its config: gain is not a number: \"fast\""
+    );
+    assert!(matches!(
+        calibrate.instantiate("gain=1\0"),
+        Err(PluginError::BadConfig)
+    ));
+}
+
+#[test]
+fn instances_keep_the_library_open() {
+    let deadband = load("deadband");
+    assert_eq!(deadband.handles(), 1);
+    let first = deadband.instantiate(DEADBAND).unwrap();
+    let second = deadband.instantiate("").unwrap();
+    assert_eq!(deadband.handles(), 3);
+    drop(first);
+    assert_eq!(deadband.handles(), 2);
+    drop(deadband);
+    // The library stays loaded for the instance still running
+    let mut second = second;
+    let mut reading = Reading {
+        sensor_id: 1,
+        timestamp_ms: 0,
+        value: 20.0,
+    };
+    assert!(second.process(&mut reading).unwrap());
+    assert_eq!(second.name(), "deadband");
+}
@@ -0,0 +1,51 @@
+// Checks sensor logs and prints a summary of each
+//
+//   cargo run --bin logcheck -- a.bin b.bin
+//
+// An application binary: every error becomes an `anyhow::Error` with
+// context, and returning it from `main` prints the whole chain.
+
+use anyhow::{bail, Context};
+use error_layers::{calibrations, Link};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufReader;
+
+fn check(path: &str) -> anyhow::Result<()> {
+    let file = File::open(path).with_context(|| format!("opening sensor log {}", path))?;
+    let readings = Link::new(BufReader::new(file), calibrations())
+        .read_all()
+        .with_context(|| format!("decoding sensor log {}", path))?;
+    let mut per_sensor: BTreeMap<u8, (usize, i32, i32)> = BTreeMap::new();
+    for r in &readings {
+        let (count, min, max) =
+            per_sensor
+                .entry(r.sensor)
+                .or_insert((0, r.centi_celsius, r.centi_celsius));
+        *count += 1;
+        *min = (*min).min(r.centi_celsius);
+        *max = (*max).max(r.centi_celsius);
+    }
+    println!("{}: {} readings", path, readings.len());
+    for (sensor, (count, min, max)) in per_sensor {
+        println!(
+            "  sensor {}: {} readings, {:.2}..{:.2} °C",
+            sensor,
+            count,
+            min as f64 / 100.0,
+            max as f64 / 100.0
+        );
+    }
+    Ok(())
+}
+
+fn main() -> anyhow::Result<()> {
+    let paths: Vec<String> = std::env::args().skip(1).collect();
+    if paths.is_empty() {
+        bail!("usage: logcheck <sensor log>...");
+    }
+    for path in &paths {
+        check(path)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,37 @@
+// Walking and printing `source()` chains
+//
+// `Display` on an error says what failed at its own layer; the layers
+// below are reached through `source()`. Printing only the outermost error
+// loses the cause, and an error that also prints its source in `Display`
+// makes every printed chain say it twice.
+
+use std::error::Error;
+use std::iter;
+
+// From the error itself down to the root cause
+pub fn chain<'a>(
+    error: &'a (dyn Error + 'static),
+) -> impl Iterator<Item = &'a (dyn Error + 'static)> {
+    iter::successors(Some(error), |&e| e.source())
+}
+
+// "frame 2: bad COBS framing: encoded data ends mid-block", the same
+// format as anyhow's `{:#}`
+pub fn one_line(error: &(dyn Error + 'static)) -> String {
+    chain(error)
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join(": ")
+}
+
+// One cause per line, indented, for logs read by people
+pub fn tree(error: &(dyn Error + 'static)) -> String {
+    chain(error)
+        .enumerate()
+        .map(|(depth, e)| match depth {
+            0 => format!("error: {}", e),
+            _ => format!("{}└─ {}", "   ".repeat(depth - 1), e),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
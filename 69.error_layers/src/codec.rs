@@ -0,0 +1,62 @@
+// The codec layer: one reading per COBS frame
+//
+//   sensor (u8) | raw (i16 LE) | CRC-8 over the first three bytes
+//
+// COBS-encoded and followed by a 0x00 delimiter, as in 19.cobs.
+
+use cobs::CobsError;
+use thiserror::Error;
+
+const PAYLOAD_LEN: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum CodecError {
+    // Says what failed at this layer; the COBS detail is the source, so it
+    // isn't repeated here
+    #[error("bad COBS framing")]
+    Cobs(#[from] CobsError),
+    #[error("payload is {found} bytes, expected {expected}")]
+    Length { expected: usize, found: usize },
+    #[error("checksum {found:#04x}, expected {expected:#04x}")]
+    Checksum { expected: u8, found: u8 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+    pub sensor: u8,
+    pub raw: i16,
+}
+
+impl Frame {
+    // Encoded and delimited, ready for the link
+    pub fn encode(&self) -> Vec<u8> {
+        let [lo, hi] = self.raw.to_le_bytes();
+        let body = [self.sensor, lo, hi];
+        let payload = [self.sensor, lo, hi, crc::crc8(&body)];
+        let mut out = cobs::encode(&payload);
+        out.push(cobs::DELIMITER);
+        out
+    }
+
+    // One frame without its delimiter
+    pub fn decode(encoded: &[u8]) -> Result<Frame, CodecError> {
+        let payload = cobs::decode(encoded)?;
+        if payload.len() != PAYLOAD_LEN {
+            return Err(CodecError::Length {
+                expected: PAYLOAD_LEN,
+                found: payload.len(),
+            });
+        }
+        let expected = crc::crc8(&payload[..3]);
+        if payload[3] != expected {
+            return Err(CodecError::Checksum {
+                expected,
+                found: payload[3],
+            });
+        }
+        Ok(Frame {
+            sensor: payload[0],
+            raw: i16::from_le_bytes([payload[1], payload[2]]),
+        })
+    }
+}
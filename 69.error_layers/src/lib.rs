@@ -0,0 +1,59 @@
+// Error layering with thiserror and anyhow
+//
+// Errors grow with the layers they pass through. A failing read on a
+// sensor log is an IO error, inside a protocol error that names the
+// frame, inside an application error that names the file:
+//
+//   app (anyhow)    decoding sensor log readings.bin
+//   protocol.rs     frame 2
+//   codec.rs        bad COBS framing
+//   19.cobs         encoded data ends mid-block
+//
+// - `math`: `MathError`, fixed-point calibration
+// - `codec`: `CodecError`, COBS frames with a CRC-8
+// - `protocol`: `ProtocolError`, a byte stream to calibrated readings
+// - `chain`: walking and printing `source()` chains
+// - `manual`: `MathError` and `CodecError` written without thiserror
+
+pub mod chain;
+pub mod codec;
+pub mod manual;
+pub mod math;
+pub mod protocol;
+
+pub use codec::{CodecError, Frame};
+pub use math::{Calibration, MathError};
+pub use protocol::{Link, ProtocolError, Reading};
+
+use std::collections::HashMap;
+
+// The calibration table both binaries use. Sensor 9 was configured with a
+// zero denominator, which only shows when it reports
+pub fn calibrations() -> HashMap<u8, Calibration> {
+    HashMap::from([
+        (
+            1,
+            Calibration {
+                gain_num: 1,
+                gain_den: 1,
+                offset: 0,
+            },
+        ),
+        (
+            2,
+            Calibration {
+                gain_num: 102,
+                gain_den: 100,
+                offset: -30,
+            },
+        ),
+        (
+            9,
+            Calibration {
+                gain_num: 1,
+                gain_den: 0,
+                offset: 0,
+            },
+        ),
+    ])
+}
@@ -0,0 +1,282 @@
+use anyhow::{ensure, Context};
+use error_layers::chain::{chain, one_line, tree};
+use error_layers::{calibrations, manual, CodecError, Frame, Link, MathError, ProtocolError};
+use std::fs;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+fn stream(frames: &[Frame]) -> Vec<u8> {
+    frames.iter().flat_map(Frame::encode).collect()
+}
+
+fn good_frames() -> Vec<Frame> {
+    vec![
+        Frame {
+            sensor: 1,
+            raw: 2150,
+        },
+        Frame {
+            sensor: 2,
+            raw: 2210,
+        },
+        Frame {
+            sensor: 1,
+            raw: 2160,
+        },
+    ]
+}
+
+// A link that delivers `bytes` and then times out, like a serial port
+// whose device stopped answering
+struct Flaky {
+    bytes: io::Cursor<Vec<u8>>,
+}
+
+impl Read for Flaky {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.bytes.read(buf)? {
+            0 => Err(io::Error::new(io::ErrorKind::TimedOut, "no data for 2 s")),
+            n => Ok(n),
+        }
+    }
+}
+
+fn decode(bytes: Vec<u8>) -> Result<Vec<error_layers::Reading>, ProtocolError> {
+    Link::new(io::Cursor::new(bytes), calibrations()).read_all()
+}
+
+// The application layer: anyhow, with context saying what it was doing
+fn load(path: &Path) -> anyhow::Result<Vec<error_layers::Reading>> {
+    let file =
+        fs::File::open(path).with_context(|| format!("opening sensor log {}", path.display()))?;
+    let readings = Link::new(BufReader::new(file), calibrations())
+        .read_all()
+        .with_context(|| format!("decoding sensor log {}", path.display()))?;
+    ensure!(
+        !readings.is_empty(),
+        "sensor log {} has no readings",
+        path.display()
+    );
+    Ok(readings)
+}
+
+// What to do about a failure is decided from the chain, not the message
+fn action(error: &anyhow::Error) -> &'static str {
+    for cause in error.chain() {
+        if let Some(io) = cause.downcast_ref::<io::Error>() {
+            return match io.kind() {
+                io::ErrorKind::TimedOut | io::ErrorKind::Interrupted => "retry",
+                io::ErrorKind::NotFound => "skip the file",
+                _ => "give up",
+            };
+        }
+        if cause.downcast_ref::<CodecError>().is_some() {
+            return "drop the frame, resync";
+        }
+        if cause.downcast_ref::<MathError>().is_some() {
+            return "fix the calibration table";
+        }
+    }
+    "report"
+}
+
+// Two ways to wrap another error
+#[derive(Debug, Error)]
+enum Wrapped {
+    // Prints the inner message and also returns it as the source
+    #[error("{0}")]
+    Repeats(#[source] CodecError),
+    // Display and source() both delegate, so the wrapper adds no layer
+    #[error(transparent)]
+    Transparent(CodecError),
+}
+
+fn main() {
+    println!("=== Error Layering Examples ===\n");
+
+    // 1. thiserror writes what was written by hand
+    println!("1. thiserror against the hand-written impls:");
+    let math = [
+        (MathError::DivideByZero, manual::MathError::DivideByZero),
+        (
+            MathError::Overflow { op: "multiply" },
+            manual::MathError::Overflow { op: "multiply" },
+        ),
+        (
+            MathError::OutOfRange {
+                value: 20370,
+                min: -4000,
+                max: 12500,
+            },
+            manual::MathError::OutOfRange {
+                value: 20370,
+                min: -4000,
+                max: 12500,
+            },
+        ),
+    ];
+    let codec = [
+        (
+            CodecError::from(cobs::CobsError::Truncated),
+            manual::CodecError::from(cobs::CobsError::Truncated),
+        ),
+        (
+            CodecError::Length {
+                expected: 4,
+                found: 2,
+            },
+            manual::CodecError::Length {
+                expected: 4,
+                found: 2,
+            },
+        ),
+        (
+            CodecError::Checksum {
+                expected: 0x5E,
+                found: 0x5F,
+            },
+            manual::CodecError::Checksum {
+                expected: 0x5E,
+                found: 0x5F,
+            },
+        ),
+    ];
+    for (derived, _) in &codec {
+        println!("   {}", one_line(derived));
+    }
+    for (derived, by_hand) in &math {
+        println!("   {} / {}", derived, by_hand);
+    }
+
+    // 2. Each layer adds what it knows
+    println!("\n2. Errors from each layer:");
+    let good = good_frames();
+    let readings = decode(stream(&good));
+    if let Ok(readings) = &readings {
+        for r in readings {
+            println!(
+                "   sensor {}: {}.{:02} °C",
+                r.sensor,
+                r.centi_celsius / 100,
+                r.centi_celsius % 100
+            );
+        }
+    }
+    let mut truncated = stream(&good);
+    // The second frame's first code byte now points past its end
+    truncated[6] = 0x07;
+    let mut bad_crc = stream(&good);
+    bad_crc[2] ^= 0x01;
+    let unknown = stream(&[Frame { sensor: 7, raw: 0 }]);
+    let zero_den = stream(&[Frame {
+        sensor: 9,
+        raw: 100,
+    }]);
+    let too_hot = stream(&[Frame {
+        sensor: 2,
+        raw: 20000,
+    }]);
+    let cases = [truncated, bad_crc, unknown, zero_den, too_hot];
+    let errors: Vec<ProtocolError> = cases
+        .into_iter()
+        .filter_map(|bytes| decode(bytes).err())
+        .collect();
+    for e in &errors {
+        println!("   {}", one_line(e));
+    }
+    let timeout = Link::new(
+        BufReader::new(Flaky {
+            bytes: io::Cursor::new(stream(&good[..1])),
+        }),
+        calibrations(),
+    )
+    .read_all();
+    if let Err(e) = &timeout {
+        println!("   {}", one_line(e));
+    }
+
+    // 3. Printing a chain
+    println!("\n3. One error, three ways:");
+    let error = &errors[0];
+    println!("   Display only:  {}", error);
+    println!("   whole chain:   {}", one_line(error));
+    if let Some(root) = chain(error).last() {
+        println!("   root cause:    {}", root);
+    }
+    for line in tree(error).lines() {
+        println!("   {}", line);
+    }
+
+    // 4. anyhow and context in the application
+    println!("\n4. anyhow with context:");
+    let dir = std::env::temp_dir().join(format!("error_layers_{}", std::process::id()));
+    let files: [(&str, Vec<u8>); 3] = [
+        ("good.bin", stream(&good)),
+        ("corrupt.bin", stream(&[Frame { sensor: 9, raw: 1 }])),
+        ("empty.bin", Vec::new()),
+    ];
+    let written = fs::create_dir_all(&dir).and_then(|_| {
+        files
+            .iter()
+            .try_for_each(|(name, bytes)| fs::write(dir.join(name), bytes))
+    });
+    if let Err(e) = written {
+        println!("   cannot write to {}: {}", dir.display(), e);
+        return;
+    }
+    let paths: Vec<PathBuf> = ["good.bin", "corrupt.bin", "empty.bin", "missing.bin"]
+        .iter()
+        .map(|name| dir.join(name))
+        .collect();
+    let mut outcomes = Vec::new();
+    for path in &paths {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        match load(path) {
+            Ok(readings) => println!("   {:<12} {} readings", name, readings.len()),
+            Err(e) => {
+                // {:#} prints the context and every cause on one line
+                let line = format!("{:#}", e).replace(&*dir.to_string_lossy(), "$TMP");
+                println!("   {:<12} {}", name, line);
+                outcomes.push(e);
+            }
+        }
+    }
+    let _ = fs::remove_dir_all(&dir);
+    if let Some(e) = outcomes.first() {
+        println!("   {{:?}} of the first, as `main` returning Err prints it:");
+        let debug = format!("{:?}", e).replace(&*dir.to_string_lossy(), "$TMP");
+        // With RUST_BACKTRACE set, a backtrace follows; only the causes here
+        for line in debug
+            .lines()
+            .take_while(|l| !l.starts_with("Stack backtrace"))
+            .filter(|l| !l.is_empty())
+        {
+            println!("     {}", line);
+        }
+    }
+
+    // 5. Deciding from the chain
+    println!("\n5. What to do about it:");
+    let from_timeout = anyhow::Error::new(timeout.expect_err("timed out above"))
+        .context("polling sensor link /dev/ttyUSB0");
+    let decisions: Vec<(String, &str)> = outcomes
+        .iter()
+        .chain([&from_timeout])
+        .map(|e| (e.to_string(), action(e)))
+        .collect();
+    for (what, action) in &decisions {
+        let what = what.replace(&*dir.to_string_lossy(), "$TMP");
+        println!("   {:<44} -> {}", what, action);
+    }
+
+    // 6. Wrapping without repeating
+    println!("\n6. #[error(\"{{0}}\")] + #[source] against transparent:");
+    let inner = CodecError::from(cobs::CobsError::ZeroInData);
+    let repeats = Wrapped::Repeats(inner);
+    let transparent = Wrapped::Transparent(inner);
+    println!("   {}", one_line(&repeats));
+    println!("   {}", one_line(&transparent));
+
+    println!("\n=== End of Error Layering Examples ===");
+}
@@ -0,0 +1,66 @@
+// `MathError` and `CodecError` the way the earlier lessons write errors:
+// an enum, a Display match, an empty or hand-written `Error` impl and a
+// `From` for `?`. thiserror generates exactly this from the attributes in
+// math.rs and codec.rs; the demo checks that the two agree.
+
+use cobs::CobsError;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathError {
+    DivideByZero,
+    Overflow { op: &'static str },
+    OutOfRange { value: i32, min: i32, max: i32 },
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MathError::DivideByZero => write!(f, "division by zero"),
+            MathError::Overflow { op } => write!(f, "{} overflowed i32", op),
+            MathError::OutOfRange { value, min, max } => {
+                write!(f, "{} is outside {}..={}", value, min, max)
+            }
+        }
+    }
+}
+
+impl Error for MathError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodecError {
+    Cobs(CobsError),
+    Length { expected: usize, found: usize },
+    Checksum { expected: u8, found: u8 },
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::Cobs(_) => write!(f, "bad COBS framing"),
+            CodecError::Length { expected, found } => {
+                write!(f, "payload is {} bytes, expected {}", found, expected)
+            }
+            CodecError::Checksum { expected, found } => {
+                write!(f, "checksum {:#04x}, expected {:#04x}", found, expected)
+            }
+        }
+    }
+}
+
+// The part most hand-written errors leave out, which breaks the chain
+impl Error for CodecError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CodecError::Cobs(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<CobsError> for CodecError {
+    fn from(e: CobsError) -> CodecError {
+        CodecError::Cobs(e)
+    }
+}
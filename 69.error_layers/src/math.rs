@@ -0,0 +1,50 @@
+// The arithmetic layer: fixed-point calibration of raw ADC counts
+//
+//   centi-degrees = raw * gain_num / gain_den + offset
+//
+// with every step checked, and the result held to the sensor's range.
+
+use thiserror::Error;
+
+// The sensor's rated range, -40.00 °C to 125.00 °C
+pub const MIN_CENTI: i32 = -4000;
+pub const MAX_CENTI: i32 = 12500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum MathError {
+    #[error("division by zero")]
+    DivideByZero,
+    #[error("{op} overflowed i32")]
+    Overflow { op: &'static str },
+    #[error("{value} is outside {min}..={max}")]
+    OutOfRange { value: i32, min: i32, max: i32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Calibration {
+    pub gain_num: i32,
+    pub gain_den: i32,
+    pub offset: i32,
+}
+
+impl Calibration {
+    pub fn apply(&self, raw: i16) -> Result<i32, MathError> {
+        if self.gain_den == 0 {
+            return Err(MathError::DivideByZero);
+        }
+        let scaled = i32::from(raw)
+            .checked_mul(self.gain_num)
+            .ok_or(MathError::Overflow { op: "multiply" })?;
+        let value = (scaled / self.gain_den)
+            .checked_add(self.offset)
+            .ok_or(MathError::Overflow { op: "add" })?;
+        if !(MIN_CENTI..=MAX_CENTI).contains(&value) {
+            return Err(MathError::OutOfRange {
+                value,
+                min: MIN_CENTI,
+                max: MAX_CENTI,
+            });
+        }
+        Ok(value)
+    }
+}
@@ -0,0 +1,101 @@
+// The protocol layer: a byte stream in, calibrated readings out
+//
+// Each variant says which frame failed and at which step, and keeps the
+// lower layer's error as its `source()`:
+//
+//   ProtocolError::Frame { index: 2 }   "frame 2"
+//     └─ CodecError::Cobs               "bad COBS framing"
+//          └─ CobsError::Truncated      "encoded data ends mid-block"
+
+use crate::codec::{CodecError, Frame};
+use crate::math::{Calibration, MathError};
+use std::collections::HashMap;
+use std::io::{self, BufRead};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ProtocolError {
+    #[error("reading the link")]
+    Io(#[from] io::Error),
+    #[error("frame {index}")]
+    Frame {
+        index: usize,
+        #[source]
+        source: CodecError,
+    },
+    #[error("frame {index}: no calibration for sensor {sensor}")]
+    UnknownSensor { index: usize, sensor: u8 },
+    #[error("frame {index}: calibrating sensor {sensor}")]
+    Calibration {
+        index: usize,
+        sensor: u8,
+        #[source]
+        source: MathError,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reading {
+    pub sensor: u8,
+    pub centi_celsius: i32,
+}
+
+pub struct Link<R> {
+    reader: R,
+    calibrations: HashMap<u8, Calibration>,
+    frames: usize,
+    buf: Vec<u8>,
+}
+
+impl<R: BufRead> Link<R> {
+    pub fn new(reader: R, calibrations: HashMap<u8, Calibration>) -> Link<R> {
+        Link {
+            reader,
+            calibrations,
+            frames: 0,
+            buf: Vec::new(),
+        }
+    }
+
+    // The next reading, or None at the end of the stream
+    pub fn next_reading(&mut self) -> Result<Option<Reading>, ProtocolError> {
+        self.buf.clear();
+        if self.reader.read_until(cobs::DELIMITER, &mut self.buf)? == 0 {
+            return Ok(None);
+        }
+        let index = self.frames;
+        self.frames += 1;
+        if self.buf.last() == Some(&cobs::DELIMITER) {
+            self.buf.pop();
+        }
+        let frame =
+            Frame::decode(&self.buf).map_err(|source| ProtocolError::Frame { index, source })?;
+        let calibration =
+            self.calibrations
+                .get(&frame.sensor)
+                .ok_or(ProtocolError::UnknownSensor {
+                    index,
+                    sensor: frame.sensor,
+                })?;
+        let centi_celsius =
+            calibration
+                .apply(frame.raw)
+                .map_err(|source| ProtocolError::Calibration {
+                    index,
+                    sensor: frame.sensor,
+                    source,
+                })?;
+        Ok(Some(Reading {
+            sensor: frame.sensor,
+            centi_celsius,
+        }))
+    }
+
+    pub fn read_all(&mut self) -> Result<Vec<Reading>, ProtocolError> {
+        let mut readings = Vec::new();
+        while let Some(reading) = self.next_reading()? {
+            readings.push(reading);
+        }
+        Ok(readings)
+    }
+}
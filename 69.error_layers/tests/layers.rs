@@ -0,0 +1,256 @@
+use error_layers::chain::{chain, one_line, tree};
+use error_layers::{
+    calibrations, manual, Calibration, CodecError, Frame, Link, MathError, ProtocolError, Reading,
+};
+use std::error::Error;
+use std::io::{self, BufReader, Read};
+use thiserror::Error;
+
+fn stream(frames: &[Frame]) -> Vec<u8> {
+    frames.iter().flat_map(Frame::encode).collect()
+}
+
+fn good_frames() -> [Frame; 3] {
+    [
+        Frame {
+            sensor: 1,
+            raw: 2150,
+        },
+        Frame {
+            sensor: 2,
+            raw: 2210,
+        },
+        Frame {
+            sensor: 1,
+            raw: 2160,
+        },
+    ]
+}
+
+fn decode(bytes: Vec<u8>) -> Result<Vec<Reading>, ProtocolError> {
+    Link::new(io::Cursor::new(bytes), calibrations()).read_all()
+}
+
+fn decode_error(bytes: Vec<u8>) -> String {
+    one_line(&decode(bytes).unwrap_err())
+}
+
+// Delivers `bytes` and then times out, like a serial port whose device
+// stopped answering
+struct Flaky(io::Cursor<Vec<u8>>);
+
+impl Read for Flaky {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.0.read(buf)? {
+            0 => Err(io::Error::new(io::ErrorKind::TimedOut, "no data for 2 s")),
+            n => Ok(n),
+        }
+    }
+}
+
+#[test]
+fn thiserror_matches_the_hand_written_impls() {
+    let math = [
+        (MathError::DivideByZero, manual::MathError::DivideByZero),
+        (
+            MathError::Overflow { op: "multiply" },
+            manual::MathError::Overflow { op: "multiply" },
+        ),
+        (
+            MathError::OutOfRange {
+                value: 20370,
+                min: -4000,
+                max: 12500,
+            },
+            manual::MathError::OutOfRange {
+                value: 20370,
+                min: -4000,
+                max: 12500,
+            },
+        ),
+    ];
+    for (derived, by_hand) in &math {
+        assert_eq!(derived.to_string(), by_hand.to_string());
+        assert!(derived.source().is_none() && by_hand.source().is_none());
+    }
+    let codec = [
+        (
+            CodecError::from(cobs::CobsError::Truncated),
+            manual::CodecError::from(cobs::CobsError::Truncated),
+        ),
+        (
+            CodecError::Length {
+                expected: 4,
+                found: 2,
+            },
+            manual::CodecError::Length {
+                expected: 4,
+                found: 2,
+            },
+        ),
+        (
+            CodecError::Checksum {
+                expected: 0x5E,
+                found: 0x5F,
+            },
+            manual::CodecError::Checksum {
+                expected: 0x5E,
+                found: 0x5F,
+            },
+        ),
+    ];
+    for (derived, by_hand) in &codec {
+        assert_eq!(one_line(derived), one_line(by_hand));
+    }
+    assert_eq!(
+        one_line(&codec[0].0),
+        "bad COBS framing: encoded data ends mid-block"
+    );
+}
+
+#[test]
+fn calibration_checks_every_step() {
+    let unity = Calibration {
+        gain_num: 1,
+        gain_den: 1,
+        offset: 0,
+    };
+    assert_eq!(unity.apply(2150), Ok(2150));
+    let scaled = Calibration {
+        gain_num: 102,
+        gain_den: 100,
+        offset: -30,
+    };
+    assert_eq!(scaled.apply(2210), Ok(2224));
+    let steep = Calibration {
+        gain_num: i32::MAX,
+        ..unity
+    };
+    assert_eq!(steep.apply(2), Err(MathError::Overflow { op: "multiply" }));
+    let shifted = Calibration {
+        offset: i32::MAX,
+        ..unity
+    };
+    assert_eq!(shifted.apply(1), Err(MathError::Overflow { op: "add" }));
+    assert_eq!(
+        unity.apply(-4001),
+        Err(MathError::OutOfRange {
+            value: -4001,
+            min: -4000,
+            max: 12500,
+        })
+    );
+}
+
+#[test]
+fn frames_round_trip_and_reject_bad_payloads() {
+    for frame in good_frames() {
+        let encoded = frame.encode();
+        assert_eq!(encoded.last(), Some(&0));
+        assert_eq!(Frame::decode(&encoded[..encoded.len() - 1]), Ok(frame));
+    }
+    let short = cobs::encode(&[1, 2]);
+    assert_eq!(
+        Frame::decode(&short),
+        Err(CodecError::Length {
+            expected: 4,
+            found: 2,
+        })
+    );
+}
+
+#[test]
+fn valid_frames_decode() {
+    let readings = decode(stream(&good_frames())).unwrap();
+    let values: Vec<(u8, i32)> = readings
+        .iter()
+        .map(|r| (r.sensor, r.centi_celsius))
+        .collect();
+    assert_eq!(values, [(1, 2150), (2, 2224), (1, 2160)]);
+    assert!(decode(Vec::new()).unwrap().is_empty());
+}
+
+#[test]
+fn each_layer_adds_what_it_knows() {
+    let good = good_frames();
+    let mut truncated = stream(&good);
+    // The second frame's first code byte now points past its end
+    truncated[6] = 0x07;
+    assert_eq!(
+        decode_error(truncated),
+        "frame 1: bad COBS framing: encoded data ends mid-block"
+    );
+    let mut bad_crc = stream(&good);
+    bad_crc[2] ^= 0x01;
+    assert_eq!(
+        decode_error(bad_crc),
+        "frame 0: checksum 0xd8, expected 0xcd"
+    );
+    assert_eq!(
+        decode_error(stream(&[Frame { sensor: 7, raw: 0 }])),
+        "frame 0: no calibration for sensor 7"
+    );
+    assert_eq!(
+        decode_error(stream(&[Frame {
+            sensor: 9,
+            raw: 100,
+        }])),
+        "frame 0: calibrating sensor 9: division by zero"
+    );
+    assert_eq!(
+        decode_error(stream(&[Frame {
+            sensor: 2,
+            raw: 20000,
+        }])),
+        "frame 0: calibrating sensor 2: 20370 is outside -4000..=12500"
+    );
+}
+
+#[test]
+fn a_timeout_keeps_its_io_error() {
+    let flaky = Flaky(io::Cursor::new(stream(&good_frames()[..1])));
+    let mut link = Link::new(BufReader::new(flaky), calibrations());
+    assert!(link.next_reading().unwrap().is_some());
+    let e = link.next_reading().unwrap_err();
+    assert_eq!(one_line(&e), "reading the link: no data for 2 s");
+    let io = chain(&e)
+        .find_map(|cause| cause.downcast_ref::<io::Error>())
+        .unwrap();
+    assert_eq!(io.kind(), io::ErrorKind::TimedOut);
+}
+
+#[test]
+fn chains_end_at_the_lowest_layer() {
+    let mut truncated = stream(&good_frames());
+    truncated[6] = 0x07;
+    let e = decode(truncated).unwrap_err();
+    assert_eq!(chain(&e).count(), 3);
+    assert!(chain(&e).last().unwrap().is::<cobs::CobsError>());
+    assert_eq!(e.to_string(), "frame 1");
+    assert_eq!(
+        tree(&e),
+        "error: frame 1\n└─ bad COBS framing\n   └─ encoded data ends mid-block"
+    );
+}
+
+#[test]
+fn transparent_adds_no_layer() {
+    #[derive(Debug, Error)]
+    enum Wrapped {
+        #[error("{0}")]
+        Repeats(#[source] CodecError),
+        #[error(transparent)]
+        Transparent(CodecError),
+    }
+    let inner = CodecError::from(cobs::CobsError::ZeroInData);
+    assert_eq!(
+        one_line(&Wrapped::Repeats(inner)),
+        "bad COBS framing: bad COBS framing: zero byte inside encoded data"
+    );
+    let transparent = Wrapped::Transparent(inner);
+    assert_eq!(one_line(&transparent), one_line(&inner));
+    assert_eq!(
+        transparent.source().map(|s| s.to_string()),
+        inner.source().map(|s| s.to_string())
+    );
+}
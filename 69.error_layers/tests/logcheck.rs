@@ -0,0 +1,100 @@
+// The logcheck binary end to end: returning an anyhow::Error from `main`
+// prints the context and every cause, and fails the process.
+
+use error_layers::Frame;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("error_layers_{}_{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_log(dir: &Path, name: &str, frames: &[Frame]) -> String {
+    let path = dir.join(name);
+    let bytes: Vec<u8> = frames.iter().flat_map(Frame::encode).collect();
+    fs::write(&path, bytes).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+fn logcheck(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_logcheck"))
+        .args(args)
+        .env_remove("RUST_BACKTRACE")
+        .env_remove("RUST_LIB_BACKTRACE")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn a_good_log_is_summarised_per_sensor() {
+    let dir = temp_dir("good");
+    let path = write_log(
+        &dir,
+        "good.bin",
+        &[
+            Frame {
+                sensor: 1,
+                raw: 2150,
+            },
+            Frame {
+                sensor: 2,
+                raw: 2210,
+            },
+            Frame {
+                sensor: 1,
+                raw: 2160,
+            },
+        ],
+    );
+    let out = logcheck(&[&path]);
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&out.stdout),
+        format!(
+            "{}: 3 readings\n  sensor 1: 2 readings, 21.50..21.60 °C\n  sensor 2: 1 readings, 22.24..22.24 °C\n",
+            path
+        )
+    );
+}
+
+#[test]
+fn errors_print_the_context_and_every_cause() {
+    let dir = temp_dir("corrupt");
+    let path = write_log(&dir, "corrupt.bin", &[Frame { sensor: 9, raw: 1 }]);
+    let out = logcheck(&[&path]);
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(!out.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&out.stderr),
+        format!(
+            "Error: decoding sensor log {}\n\nCaused by:\n    0: frame 0: calibrating sensor 9\n    1: division by zero\n",
+            path
+        )
+    );
+}
+
+#[test]
+fn a_missing_file_names_the_file() {
+    let out = logcheck(&["/nonexistent/sensor.bin"]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.starts_with("Error: opening sensor log /nonexistent/sensor.bin\n\nCaused by:\n"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn no_arguments_prints_usage() {
+    let out = logcheck(&[]);
+    assert!(!out.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&out.stderr),
+        "Error: usage: logcheck <sensor log>...\n"
+    );
+}
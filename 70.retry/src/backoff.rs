@@ -0,0 +1,125 @@
+// Retry policies and the delays they produce
+//
+//   delay(n) = min(max_delay, base * multiplier^n)     n = 0, 1, 2, ...
+//
+// then jitter spreads each delay so that clients which failed together
+// don't all retry together:
+//
+//   None    d                        100 200 400 800 ...
+//   Full    uniform [0, d]           any point up to the limit
+//   Equal   d/2 + uniform [0, d/2]   never less than half
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Jitter {
+    None,
+    Full,
+    Equal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Policy {
+    // Including the first attempt; None retries until the deadline
+    pub max_attempts: Option<u32>,
+    // Measured from the start of the first attempt; no retry is started
+    // if its delay would end past it
+    pub deadline: Option<Duration>,
+    pub base: Duration,
+    pub multiplier: f64,
+    pub max_delay: Duration,
+    pub jitter: Jitter,
+    // Fixed seed for repeatable jitter; None seeds from the system time
+    pub seed: Option<u64>,
+}
+
+impl Default for Policy {
+    fn default() -> Policy {
+        Policy {
+            max_attempts: Some(5),
+            deadline: None,
+            base: Duration::from_millis(100),
+            multiplier: 2.0,
+            max_delay: Duration::from_secs(10),
+            jitter: Jitter::Full,
+            seed: None,
+        }
+    }
+}
+
+// The delays of one policy, one per failed attempt
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    base: Duration,
+    multiplier: f64,
+    max_delay: Duration,
+    jitter: Jitter,
+    retries: u32,
+    rng: u64,
+}
+
+impl Backoff {
+    pub fn new(policy: &Policy) -> Backoff {
+        let seed = policy.seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |t| t.as_nanos() as u64)
+        });
+        Backoff {
+            base: policy.base,
+            multiplier: policy.multiplier.max(1.0),
+            max_delay: policy.max_delay,
+            jitter: policy.jitter,
+            retries: 0,
+            rng: mix(seed),
+        }
+    }
+
+    // How long to wait before the next retry
+    pub fn next_delay(&mut self) -> Duration {
+        let limit = self.ceiling(self.retries);
+        self.retries = self.retries.saturating_add(1);
+        match self.jitter {
+            Jitter::None => limit,
+            Jitter::Full => limit.mul_f64(self.unit()),
+            Jitter::Equal => limit / 2 + (limit / 2).mul_f64(self.unit()),
+        }
+    }
+
+    // After a success, start again from `base`
+    pub fn reset(&mut self) {
+        self.retries = 0;
+    }
+
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    // The un-jittered delay for retry `n`. Computed in f64 and clamped,
+    // since base * 2^n overflows a Duration long before n gets large
+    fn ceiling(&self, n: u32) -> Duration {
+        let secs = self.base.as_secs_f64() * self.multiplier.powi(n.min(1000) as i32);
+        if secs.is_finite() && secs < self.max_delay.as_secs_f64() {
+            Duration::from_secs_f64(secs)
+        } else {
+            self.max_delay
+        }
+    }
+
+    // Uniform in [0, 1]
+    fn unit(&mut self) -> f64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 11) as f64 / ((1u64 << 53) - 1) as f64
+    }
+}
+
+// Spreads nearby seeds (device ids, timestamps) over the whole state, as
+// xorshift's first outputs from similar seeds are similar; never zero
+fn mix(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (z ^ (z >> 31)) | 1
+}
@@ -0,0 +1,148 @@
+// Where retries get the time and how they wait
+//
+// Retrying code that reads `Instant::now()` and calls `thread::sleep`
+// directly can only be checked by waiting: a policy with a 30 s deadline
+// takes 30 s to exercise. Going through `Clock` lets the same code run
+// against `FakeClock`, where sleeping just moves the time forward and
+// is recorded.
+
+use std::future::Future;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub trait Clock {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration);
+}
+
+// Sleeping for `retry_async`, without blocking the executor
+pub trait AsyncClock {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()>;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration)
+    }
+}
+
+// A shared clock, so one `FakeClock` can drive several components (a
+// breaker and a rate limiter, say) and the test advancing it
+impl<C: Clock + ?Sized> Clock for Rc<C> {
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        (**self).sleep(duration)
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        (**self).sleep(duration)
+    }
+}
+
+// Sleeps on the tokio timer; follows `tokio::time::pause` in tests
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioClock;
+
+#[cfg(feature = "tokio")]
+impl AsyncClock for TokioClock {
+    fn now(&self) -> Instant {
+        tokio::time::Instant::now().into_std()
+    }
+
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> {
+        tokio::time::sleep(duration)
+    }
+}
+
+// Time that only moves when something sleeps on it (or `advance` is
+// called). Both traits are implemented, so it can stand in for either.
+#[derive(Debug)]
+pub struct FakeClock {
+    start: Instant,
+    state: Mutex<FakeState>,
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    elapsed: Duration,
+    sleeps: Vec<Duration>,
+}
+
+impl FakeClock {
+    pub fn new() -> FakeClock {
+        FakeClock {
+            start: Instant::now(),
+            state: Mutex::new(FakeState::default()),
+        }
+    }
+
+    // Time passing without a sleep, e.g. an attempt that took a while
+    pub fn advance(&self, duration: Duration) {
+        self.state.lock().unwrap().elapsed += duration;
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.state.lock().unwrap().elapsed
+    }
+
+    // Every sleep so far, in order
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.state.lock().unwrap().sleeps.clone()
+    }
+
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.elapsed += duration;
+        state.sleeps.push(duration);
+    }
+}
+
+impl Default for FakeClock {
+    fn default() -> FakeClock {
+        FakeClock::new()
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Instant {
+        FakeClock::now(self)
+    }
+
+    fn sleep(&self, duration: Duration) {
+        FakeClock::sleep(self, duration)
+    }
+}
+
+impl AsyncClock for FakeClock {
+    fn now(&self) -> Instant {
+        FakeClock::now(self)
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        FakeClock::sleep(self, duration)
+    }
+}
@@ -0,0 +1,193 @@
+// Retrying fallible operations with exponential backoff and jitter
+//
+//   attempt 1 ── Err ──▶ retry_on(&err)? ── no ──▶ GaveUp::Permanent
+//                              │ yes
+//                        attempts left? ── no ──▶ GaveUp::Attempts
+//                              │ yes
+//                        delay = backoff.next_delay()
+//                        past the deadline? ── yes ──▶ GaveUp::Deadline
+//                              │ no
+//                        sleep(delay), attempt 2 ...
+//
+// `retry` is the short form: every error is retried and the wait is a real
+// `thread::sleep`. `retry_with` takes the clock and the predicate, and
+// `retry_async` does the same for futures. The operation gets the attempt
+// number (from 1), e.g. for logging.
+
+pub mod backoff;
+pub mod clock;
+
+pub use backoff::{Backoff, Jitter, Policy};
+#[cfg(feature = "tokio")]
+pub use clock::TokioClock;
+pub use clock::{AsyncClock, Clock, FakeClock, SystemClock};
+
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GaveUp {
+    // `retry_on` said the error won't go away by itself
+    Permanent,
+    Attempts,
+    Deadline,
+}
+
+// The last error, and why there was no further attempt
+#[derive(Debug)]
+pub struct RetryError<E> {
+    pub reason: GaveUp,
+    pub attempts: u32,
+    pub elapsed: Duration,
+    pub last: E,
+}
+
+impl<E> RetryError<E> {
+    pub fn into_inner(self) -> E {
+        self.last
+    }
+}
+
+impl<E> fmt::Display for RetryError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let why = match self.reason {
+            GaveUp::Permanent => "error is not retryable",
+            GaveUp::Attempts => "no attempts left",
+            GaveUp::Deadline => "deadline reached",
+        };
+        write!(
+            f,
+            "gave up after {} attempt{} in {:?} ({})",
+            self.attempts,
+            if self.attempts == 1 { "" } else { "s" },
+            self.elapsed,
+            why
+        )
+    }
+}
+
+impl<E: Error + 'static> Error for RetryError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.last)
+    }
+}
+
+// What the sync and async loops share: counting attempts and deciding
+// whether, and how long, to wait after a failure
+struct Attempts<'a> {
+    policy: &'a Policy,
+    backoff: Backoff,
+    start: Instant,
+    count: u32,
+}
+
+impl<'a> Attempts<'a> {
+    fn new(policy: &'a Policy, start: Instant) -> Attempts<'a> {
+        Attempts {
+            policy,
+            backoff: Backoff::new(policy),
+            start,
+            count: 0,
+        }
+    }
+
+    fn next(&mut self) -> u32 {
+        self.count += 1;
+        self.count
+    }
+
+    fn after_failure<E>(
+        &mut self,
+        error: E,
+        retryable: bool,
+        now: Instant,
+    ) -> Result<Duration, RetryError<E>> {
+        let elapsed = now.saturating_duration_since(self.start);
+        let attempts = self.count;
+        let gave_up = |reason| RetryError {
+            reason,
+            attempts,
+            elapsed,
+            last: error,
+        };
+        if !retryable {
+            return Err(gave_up(GaveUp::Permanent));
+        }
+        if self
+            .policy
+            .max_attempts
+            .is_some_and(|max| self.count >= max)
+        {
+            return Err(gave_up(GaveUp::Attempts));
+        }
+        let delay = self.backoff.next_delay();
+        if self
+            .policy
+            .deadline
+            .is_some_and(|deadline| elapsed + delay > deadline)
+        {
+            return Err(gave_up(GaveUp::Deadline));
+        }
+        Ok(delay)
+    }
+}
+
+// Every error is retried, sleeping on the real clock
+pub fn retry<T, E, F>(policy: &Policy, op: F) -> Result<T, RetryError<E>>
+where
+    F: FnMut(u32) -> Result<T, E>,
+{
+    retry_with(policy, &SystemClock, |_| true, op)
+}
+
+pub fn retry_with<T, E, C, P, F>(
+    policy: &Policy,
+    clock: &C,
+    retry_on: P,
+    mut op: F,
+) -> Result<T, RetryError<E>>
+where
+    C: Clock,
+    P: Fn(&E) -> bool,
+    F: FnMut(u32) -> Result<T, E>,
+{
+    let mut attempts = Attempts::new(policy, clock.now());
+    loop {
+        match op(attempts.next()) {
+            Ok(value) => return Ok(value),
+            Err(error) => {
+                let retryable = retry_on(&error);
+                let delay = attempts.after_failure(error, retryable, clock.now())?;
+                clock.sleep(delay);
+            }
+        }
+    }
+}
+
+// As `retry_with`, for an operation that returns a future per attempt
+pub async fn retry_async<T, E, C, P, F, Fut>(
+    policy: &Policy,
+    clock: &C,
+    retry_on: P,
+    mut op: F,
+) -> Result<T, RetryError<E>>
+where
+    C: AsyncClock,
+    P: Fn(&E) -> bool,
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut attempts = Attempts::new(policy, clock.now());
+    loop {
+        match op(attempts.next()).await {
+            Ok(value) => return Ok(value),
+            Err(error) => {
+                let retryable = retry_on(&error);
+                let delay = attempts.after_failure(error, retryable, clock.now())?;
+                clock.sleep(delay).await;
+            }
+        }
+    }
+}
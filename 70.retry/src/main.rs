@@ -0,0 +1,226 @@
+use retry::{retry, retry_async, retry_with, Backoff, FakeClock, Jitter, Policy, TokioClock};
+use std::collections::HashMap;
+use std::io;
+use std::time::{Duration, Instant};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn policy(jitter: Jitter) -> Policy {
+    Policy {
+        max_attempts: Some(8),
+        deadline: None,
+        base: ms(100),
+        multiplier: 2.0,
+        max_delay: ms(1000),
+        jitter,
+        seed: Some(0x2545_F491_4F6C_DD1D),
+    }
+}
+
+// A collector that refuses connections until it has been up for a while
+struct Collector<'a> {
+    clock: &'a FakeClock,
+    up_after: Duration,
+}
+
+impl Collector<'_> {
+    fn connect(&self, attempt: u32) -> io::Result<String> {
+        if self.clock.elapsed() < self.up_after {
+            return Err(io::ErrorKind::ConnectionRefused.into());
+        }
+        Ok(format!("session {}", attempt))
+    }
+}
+
+// Worth another try: the network, not the request
+fn transient(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::TimedOut
+            | io::ErrorKind::Interrupted
+    )
+}
+
+fn main() {
+    println!("=== Retry Examples ===\n");
+
+    // 1. The delays a policy produces
+    println!("1. Backoff delays (base 100 ms, x2, max 1 s):");
+    for jitter in [Jitter::None, Jitter::Full, Jitter::Equal] {
+        let mut backoff = Backoff::new(&policy(jitter));
+        let series: Vec<Duration> = (0..7).map(|_| backoff.next_delay()).collect();
+        println!(
+            "   {:<6} {}",
+            format!("{:?}", jitter),
+            series
+                .iter()
+                .map(|d| format!("{:>4}", d.as_millis()))
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+    }
+    let mut again = Backoff::new(&policy(Jitter::Full));
+    println!(
+        "   the same seed again: {:?}",
+        (0..7)
+            .map(|_| again.next_delay().as_millis())
+            .collect::<Vec<_>>()
+    );
+
+    // 2. Retrying on a fake clock
+    println!("\n2. A collector that is down for 1.2 s, on a fake clock:");
+    let clock = FakeClock::new();
+    let collector = Collector {
+        clock: &clock,
+        up_after: ms(1200),
+    };
+    let started = Instant::now();
+    let result = retry_with(&policy(Jitter::None), &clock, transient, |attempt| {
+        let result = collector.connect(attempt);
+        println!(
+            "   t={:>5} ms  attempt {}: {}",
+            clock.elapsed().as_millis(),
+            attempt,
+            match &result {
+                Ok(session) => session.clone(),
+                Err(e) => e.to_string(),
+            }
+        );
+        result
+    });
+    println!(
+        "   {:?}, slept {:?}",
+        result.map_err(|e| e.to_string()),
+        clock.sleeps()
+    );
+    println!(
+        "   {:?} passed on the clock, {:?} for real",
+        clock.elapsed(),
+        started.elapsed()
+    );
+
+    // 3. Giving up
+    println!("\n3. Limits and permanent errors:");
+    let down = |_: u32| -> io::Result<()> { Err(io::ErrorKind::ConnectionRefused.into()) };
+    let clock = FakeClock::new();
+    let attempts = retry_with(&policy(Jitter::None), &clock, transient, down);
+    let clock = FakeClock::new();
+    let deadline = Policy {
+        max_attempts: None,
+        deadline: Some(ms(2000)),
+        ..policy(Jitter::None)
+    };
+    let timed_out = retry_with(&deadline, &clock, transient, down);
+    let clock = FakeClock::new();
+    let denied = retry_with(
+        &policy(Jitter::None),
+        &clock,
+        transient,
+        |_| -> io::Result<()> { Err(io::ErrorKind::PermissionDenied.into()) },
+    );
+    for (label, result) in [
+        ("max_attempts = 8", &attempts),
+        ("deadline = 2 s", &timed_out),
+        ("permission denied", &denied),
+    ] {
+        if let Err(e) = result {
+            println!("   {:<18} {}: {}", label, e, e.last);
+        }
+    }
+
+    // 4. Async operations
+    println!("\n4. retry_async on tokio:");
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            println!("   cannot start tokio: {}", e);
+            return;
+        }
+    };
+    let fast = Policy {
+        base: ms(5),
+        max_delay: ms(20),
+        ..policy(Jitter::Equal)
+    };
+    let started = Instant::now();
+    let result = runtime.block_on(retry_async(
+        &fast,
+        &TokioClock,
+        transient,
+        |attempt| async move {
+            // Stands in for an async connect that fails twice
+            tokio::time::sleep(ms(1)).await;
+            if attempt < 3 {
+                Err(io::Error::from(io::ErrorKind::TimedOut))
+            } else {
+                Ok(attempt)
+            }
+        },
+    ));
+    println!(
+        "   {:?} after {} ms of real time",
+        result.as_ref().map_err(|e| e.to_string()),
+        started.elapsed().as_millis()
+    );
+    let clock = FakeClock::new();
+    let collector = Collector {
+        clock: &clock,
+        up_after: ms(250),
+    };
+    let result = runtime.block_on(retry_async(
+        &policy(Jitter::None),
+        &clock,
+        transient,
+        |attempt| {
+            let result = collector.connect(attempt);
+            async move { result }
+        },
+    ));
+    println!(
+        "   on a fake clock: {:?} after sleeping {:?}",
+        result.map_err(|e| e.to_string()),
+        clock.sleeps()
+    );
+    let real = retry(
+        &fast,
+        |attempt| if attempt < 2 { Err("busy") } else { Ok(()) },
+    );
+    println!(
+        "   retry() with real sleeps: {:?}",
+        real.map_err(|e| e.to_string())
+    );
+
+    // 5. Why jitter
+    println!("\n5. 200 devices losing the uplink at the same moment:");
+    for jitter in [Jitter::None, Jitter::Full, Jitter::Equal] {
+        // When each device makes its first three retries, in 10 ms slots
+        let mut slots: HashMap<u128, usize> = HashMap::new();
+        for device in 0..200u64 {
+            let mut backoff = Backoff::new(&Policy {
+                seed: Some(device),
+                ..policy(jitter)
+            });
+            let mut at = Duration::ZERO;
+            for _ in 0..3 {
+                at += backoff.next_delay();
+                *slots.entry(at.as_millis() / 10).or_default() += 1;
+            }
+        }
+        let most = slots.values().max().copied().unwrap_or(0);
+        println!(
+            "   {:<6} busiest 10 ms slot: {:>3} connects, {} slots used",
+            format!("{:?}", jitter),
+            most,
+            slots.len()
+        );
+    }
+
+    println!("\n=== End of Retry Examples ===");
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "tokio")]
+
+use retry::{retry_async, FakeClock, GaveUp, Jitter, Policy, TokioClock};
+use std::io;
+use std::time::{Duration, Instant};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn policy() -> Policy {
+    Policy {
+        max_attempts: Some(8),
+        deadline: None,
+        base: ms(100),
+        multiplier: 2.0,
+        max_delay: ms(1000),
+        jitter: Jitter::None,
+        seed: Some(1),
+    }
+}
+
+fn transient(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::TimedOut
+}
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn retries_on_the_tokio_timer() {
+    let p = Policy {
+        base: ms(5),
+        max_delay: ms(20),
+        jitter: Jitter::Equal,
+        ..policy()
+    };
+    let started = Instant::now();
+    let result = runtime().block_on(retry_async(
+        &p,
+        &TokioClock,
+        transient,
+        |attempt| async move {
+            tokio::time::sleep(ms(1)).await;
+            if attempt < 3 {
+                Err(io::Error::from(io::ErrorKind::TimedOut))
+            } else {
+                Ok(attempt)
+            }
+        },
+    ));
+    assert_eq!(result.unwrap(), 3);
+    // Equal jitter waits at least half of 5 ms, then of 10 ms
+    assert!(started.elapsed() >= ms(2 + 5));
+}
+
+#[test]
+fn the_fake_clock_works_for_async_too() {
+    let clock = FakeClock::new();
+    let result = runtime().block_on(retry_async(&policy(), &clock, transient, |attempt| {
+        let result = if clock.elapsed() < ms(250) {
+            Err(io::Error::from(io::ErrorKind::TimedOut))
+        } else {
+            Ok(attempt)
+        };
+        async move { result }
+    }));
+    assert_eq!(result.unwrap(), 3);
+    assert_eq!(clock.sleeps(), [ms(100), ms(200)]);
+}
+
+#[test]
+fn async_gives_up_like_sync() {
+    let clock = FakeClock::new();
+    let e = runtime()
+        .block_on(retry_async(&policy(), &clock, transient, |_| async {
+            Err::<(), _>(io::Error::from(io::ErrorKind::NotFound))
+        }))
+        .unwrap_err();
+    assert_eq!((e.reason, e.attempts), (GaveUp::Permanent, 1));
+}
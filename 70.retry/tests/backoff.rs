@@ -0,0 +1,134 @@
+use retry::{Backoff, Jitter, Policy};
+use std::time::Duration;
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn policy(jitter: Jitter, seed: u64) -> Policy {
+    Policy {
+        max_attempts: Some(8),
+        deadline: None,
+        base: ms(100),
+        multiplier: 2.0,
+        max_delay: ms(1000),
+        jitter,
+        seed: Some(seed),
+    }
+}
+
+fn series(policy: &Policy, n: usize) -> Vec<Duration> {
+    let mut backoff = Backoff::new(policy);
+    (0..n).map(|_| backoff.next_delay()).collect()
+}
+
+const CEILING: [u64; 7] = [100, 200, 400, 800, 1000, 1000, 1000];
+
+#[test]
+fn no_jitter_doubles_up_to_max_delay() {
+    let delays = series(&policy(Jitter::None, 1), 7);
+    assert_eq!(delays, CEILING.map(ms));
+}
+
+#[test]
+fn full_jitter_stays_under_the_ceiling() {
+    for seed in 0..200 {
+        let delays = series(&policy(Jitter::Full, seed), 7);
+        for (d, c) in delays.iter().zip(CEILING.map(ms)) {
+            assert!(*d <= c, "seed {}: {:?} > {:?}", seed, d, c);
+        }
+    }
+}
+
+#[test]
+fn equal_jitter_keeps_at_least_half() {
+    for seed in 0..200 {
+        let delays = series(&policy(Jitter::Equal, seed), 7);
+        for (d, c) in delays.iter().zip(CEILING.map(ms)) {
+            assert!(*d >= c / 2 && *d <= c, "seed {}: {:?} of {:?}", seed, d, c);
+        }
+    }
+}
+
+#[test]
+fn full_jitter_covers_the_range() {
+    // The first delay of 2,000 devices, in tenths of the 100 ms ceiling
+    let mut tenths = [0u32; 10];
+    for seed in 0..2_000 {
+        let d = series(&policy(Jitter::Full, seed), 1)[0];
+        tenths[((d.as_secs_f64() / 0.1 * 10.0) as usize).min(9)] += 1;
+    }
+    // Uniform would be 200 each
+    assert!(
+        tenths.iter().all(|&n| (120..=280).contains(&n)),
+        "{:?}",
+        tenths
+    );
+}
+
+#[test]
+fn a_fixed_seed_repeats_the_same_delays() {
+    let p = policy(Jitter::Full, 42);
+    assert_eq!(series(&p, 10), series(&p, 10));
+    assert_ne!(series(&p, 10), series(&policy(Jitter::Full, 43), 10));
+}
+
+#[test]
+fn reset_starts_again_from_base() {
+    let mut backoff = Backoff::new(&policy(Jitter::None, 1));
+    for _ in 0..4 {
+        backoff.next_delay();
+    }
+    assert_eq!(backoff.retries(), 4);
+    backoff.reset();
+    assert_eq!(backoff.retries(), 0);
+    assert_eq!(backoff.next_delay(), ms(100));
+}
+
+#[test]
+fn huge_retry_counts_stay_at_max_delay() {
+    let mut backoff = Backoff::new(&policy(Jitter::None, 1));
+    for _ in 0..5_000 {
+        assert!(backoff.next_delay() <= ms(1000));
+    }
+    assert_eq!(backoff.next_delay(), ms(1000));
+}
+
+#[test]
+fn a_multiplier_below_one_is_treated_as_one() {
+    let p = Policy {
+        multiplier: 0.5,
+        ..policy(Jitter::None, 1)
+    };
+    assert_eq!(series(&p, 4), [ms(100); 4]);
+}
+
+#[test]
+fn default_policy() {
+    let p = Policy::default();
+    assert_eq!(p.max_attempts, Some(5));
+    assert_eq!(p.base, ms(100));
+    assert_eq!(p.jitter, Jitter::Full);
+    assert_eq!(p.seed, None);
+}
+
+// The busiest 10 ms slot when 200 devices make their first three retries
+fn busiest_slot(jitter: Jitter) -> usize {
+    let mut slots = std::collections::HashMap::<u128, usize>::new();
+    for device in 0..200 {
+        let mut backoff = Backoff::new(&policy(jitter, device));
+        let mut at = Duration::ZERO;
+        for _ in 0..3 {
+            at += backoff.next_delay();
+            *slots.entry(at.as_millis() / 10).or_default() += 1;
+        }
+    }
+    slots.into_values().max().unwrap_or(0)
+}
+
+#[test]
+fn full_jitter_spreads_a_thundering_herd() {
+    assert_eq!(busiest_slot(Jitter::None), 200);
+    assert!(busiest_slot(Jitter::Full) * 5 < 200);
+    assert!(busiest_slot(Jitter::Equal) < 200);
+}
@@ -0,0 +1,170 @@
+use retry::{retry, retry_with, FakeClock, GaveUp, Jitter, Policy, RetryError};
+use std::cell::Cell;
+use std::error::Error;
+use std::io;
+use std::time::{Duration, Instant};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn policy() -> Policy {
+    Policy {
+        max_attempts: Some(8),
+        deadline: None,
+        base: ms(100),
+        multiplier: 2.0,
+        max_delay: ms(1000),
+        jitter: Jitter::None,
+        seed: Some(1),
+    }
+}
+
+fn transient(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::ConnectionRefused | io::ErrorKind::TimedOut
+    )
+}
+
+fn refused<T>(_: u32) -> io::Result<T> {
+    Err(io::ErrorKind::ConnectionRefused.into())
+}
+
+#[test]
+fn succeeds_once_the_collector_is_up() {
+    let clock = FakeClock::new();
+    let started = Instant::now();
+    let result = retry_with(&policy(), &clock, transient, |attempt| {
+        if clock.elapsed() < ms(1200) {
+            refused(attempt)
+        } else {
+            Ok(attempt)
+        }
+    });
+    assert_eq!(result.unwrap(), 5);
+    assert_eq!(clock.sleeps(), [ms(100), ms(200), ms(400), ms(800)]);
+    assert_eq!(clock.elapsed(), ms(1500));
+    assert!(started.elapsed() < ms(100));
+}
+
+#[test]
+fn success_on_the_first_attempt_never_sleeps() {
+    let clock = FakeClock::new();
+    let result: Result<u32, RetryError<io::Error>> = retry_with(&policy(), &clock, transient, Ok);
+    assert_eq!(result.unwrap(), 1);
+    assert!(clock.sleeps().is_empty());
+}
+
+#[test]
+fn attempts_are_numbered_from_one() {
+    let clock = FakeClock::new();
+    let seen = Cell::new(Vec::new());
+    let _ = retry_with(&policy(), &clock, transient, |attempt| {
+        let mut v = seen.take();
+        v.push(attempt);
+        seen.set(v);
+        refused::<()>(attempt)
+    });
+    assert_eq!(seen.take(), (1..=8).collect::<Vec<_>>());
+}
+
+#[test]
+fn stops_after_max_attempts() {
+    let clock = FakeClock::new();
+    let e = retry_with(&policy(), &clock, transient, refused::<()>).unwrap_err();
+    assert_eq!(e.reason, GaveUp::Attempts);
+    assert_eq!(e.attempts, 8);
+    // Seven sleeps between eight attempts
+    assert_eq!(clock.sleeps().len(), 7);
+    assert_eq!(e.elapsed, ms(100 + 200 + 400 + 800 + 1000 + 1000 + 1000));
+    assert_eq!(e.last.kind(), io::ErrorKind::ConnectionRefused);
+}
+
+#[test]
+fn never_sleeps_past_the_deadline() {
+    let clock = FakeClock::new();
+    let p = Policy {
+        max_attempts: None,
+        deadline: Some(ms(2000)),
+        ..policy()
+    };
+    let e = retry_with(&p, &clock, transient, refused::<()>).unwrap_err();
+    // 100 + 200 + 400 + 800 = 1.5 s; another 1 s would end past 2 s
+    assert_eq!(e.reason, GaveUp::Deadline);
+    assert_eq!(e.attempts, 5);
+    assert_eq!(clock.elapsed(), ms(1500));
+}
+
+#[test]
+fn slow_attempts_count_towards_the_deadline() {
+    let clock = FakeClock::new();
+    let p = Policy {
+        max_attempts: None,
+        deadline: Some(ms(2000)),
+        ..policy()
+    };
+    let e = retry_with(&p, &clock, transient, |attempt| {
+        clock.advance(ms(500));
+        refused::<()>(attempt)
+    })
+    .unwrap_err();
+    // 500, +100, 500, +200, 500: a 400 ms wait would end at 2.2 s
+    assert_eq!(e.reason, GaveUp::Deadline);
+    assert_eq!(e.attempts, 3);
+    assert_eq!(e.elapsed, ms(1800));
+}
+
+#[test]
+fn permanent_errors_are_not_retried() {
+    let clock = FakeClock::new();
+    let e = retry_with(&policy(), &clock, transient, |_| -> io::Result<()> {
+        Err(io::ErrorKind::PermissionDenied.into())
+    })
+    .unwrap_err();
+    assert_eq!(e.reason, GaveUp::Permanent);
+    assert_eq!(e.attempts, 1);
+    assert!(clock.sleeps().is_empty());
+    assert_eq!(e.into_inner().kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+fn the_error_explains_itself_and_keeps_its_source() {
+    let clock = FakeClock::new();
+    let p = Policy {
+        max_attempts: Some(1),
+        ..policy()
+    };
+    let e = retry_with(&p, &clock, transient, refused::<()>).unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        "gave up after 1 attempt in 0ns (no attempts left)"
+    );
+    let source = e.source().expect("a source");
+    assert_eq!(
+        source.to_string(),
+        io::Error::from(io::ErrorKind::ConnectionRefused).to_string()
+    );
+
+    let e = retry_with(&policy(), &clock, transient, refused::<()>).unwrap_err();
+    assert!(e.to_string().starts_with("gave up after 8 attempts in "));
+}
+
+#[test]
+fn retry_sleeps_for_real_and_retries_every_error() {
+    let p = Policy {
+        base: ms(2),
+        max_delay: ms(5),
+        ..policy()
+    };
+    let started = Instant::now();
+    let result = retry(&p, |attempt| {
+        if attempt < 3 {
+            Err("busy")
+        } else {
+            Ok(attempt)
+        }
+    });
+    assert_eq!(result.unwrap(), 3);
+    assert!(started.elapsed() >= ms(2 + 4));
+}
@@ -0,0 +1,173 @@
+// A token-bucket rate limiter
+//
+//         refill: per_second tokens/s
+//                   │
+//              ┌────▼────┐
+//              │ ● ● ● ○ │  capacity 4, 3 tokens in it
+//              └────┬────┘
+//   acquire(n) ─────┘  takes n tokens, or waits/refuses until there are n
+//
+// The capacity is the largest burst that passes at once; the refill rate
+// is the long-run average. A bucket that has been idle is full, so a quiet
+// sender may burst, and a busy one is held to `per_second`.
+//
+// Tokens are refilled lazily from the time since the last call instead of
+// by a timer, so a bucket costs nothing while nobody asks it. Time comes
+// from a `Clock` (see 70.retry), so tests run on a `FakeClock`.
+
+#[cfg(feature = "tokio")]
+pub use retry::TokioClock;
+pub use retry::{AsyncClock, Clock, FakeClock, SystemClock};
+
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+// Float error after a refill must not leave a caller a hair short forever
+const EPSILON: f64 = 1e-9;
+
+// More tokens asked for than the bucket can ever hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooLarge {
+    pub requested: u32,
+    pub capacity: u32,
+}
+
+impl fmt::Display for TooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "asked for {} tokens, the bucket holds {}",
+            self.requested, self.capacity
+        )
+    }
+}
+
+impl Error for TooLarge {}
+
+#[derive(Debug)]
+pub struct TokenBucket<C = SystemClock> {
+    capacity: u32,
+    per_second: f64,
+    tokens: f64,
+    // None until the first call, which finds the bucket full
+    last: Option<Instant>,
+    clock: C,
+}
+
+impl TokenBucket<SystemClock> {
+    pub fn new(capacity: u32, per_second: f64) -> TokenBucket<SystemClock> {
+        TokenBucket::with_clock(capacity, per_second, SystemClock)
+    }
+}
+
+impl<C> TokenBucket<C> {
+    pub fn with_clock(capacity: u32, per_second: f64, clock: C) -> TokenBucket<C> {
+        assert!(
+            per_second.is_finite() && per_second > 0.0,
+            "refill rate must be positive"
+        );
+        let capacity = capacity.max(1);
+        TokenBucket {
+            capacity,
+            per_second,
+            tokens: capacity as f64,
+            last: None,
+            clock,
+        }
+    }
+
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    pub fn per_second(&self) -> f64 {
+        self.per_second
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    fn refill(&mut self, now: Instant) {
+        if let Some(last) = self.last {
+            let elapsed = now.saturating_duration_since(last).as_secs_f64();
+            self.tokens = (self.tokens + elapsed * self.per_second).min(self.capacity as f64);
+        }
+        self.last = Some(now);
+    }
+
+    // Whole tokens available at `now`
+    pub fn available_at(&mut self, now: Instant) -> u32 {
+        self.refill(now);
+        (self.tokens + EPSILON) as u32
+    }
+
+    // Takes `n` tokens if they are all there; never takes some of them
+    pub fn try_acquire_at(&mut self, n: u32, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens + EPSILON >= n as f64 {
+            self.tokens = (self.tokens - n as f64).max(0.0);
+            true
+        } else {
+            false
+        }
+    }
+
+    // How long from `now` until `n` tokens are there, without taking them
+    pub fn wait_at(&mut self, n: u32, now: Instant) -> Result<Duration, TooLarge> {
+        if n > self.capacity {
+            return Err(TooLarge {
+                requested: n,
+                capacity: self.capacity,
+            });
+        }
+        self.refill(now);
+        let missing = n as f64 - self.tokens;
+        if missing <= EPSILON {
+            return Ok(Duration::ZERO);
+        }
+        // Rounded up, so sleeping this long is always enough
+        Ok(Duration::from_nanos(
+            (missing / self.per_second * 1e9).ceil() as u64,
+        ))
+    }
+}
+
+impl<C: Clock> TokenBucket<C> {
+    pub fn available(&mut self) -> u32 {
+        let now = self.clock.now();
+        self.available_at(now)
+    }
+
+    pub fn try_acquire(&mut self, n: u32) -> bool {
+        let now = self.clock.now();
+        self.try_acquire_at(n, now)
+    }
+
+    // Blocks until `n` tokens are there, then takes them
+    pub fn acquire(&mut self, n: u32) -> Result<(), TooLarge> {
+        loop {
+            let now = self.clock.now();
+            let wait = self.wait_at(n, now)?;
+            if wait.is_zero() && self.try_acquire_at(n, now) {
+                return Ok(());
+            }
+            self.clock.sleep(wait);
+        }
+    }
+}
+
+impl<C: AsyncClock> TokenBucket<C> {
+    // As `acquire`, sleeping on the async clock
+    pub async fn acquire_async(&mut self, n: u32) -> Result<(), TooLarge> {
+        loop {
+            let now = self.clock.now();
+            let wait = self.wait_at(n, now)?;
+            if wait.is_zero() && self.try_acquire_at(n, now) {
+                return Ok(());
+            }
+            self.clock.sleep(wait).await;
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use ratelimit::{FakeClock, TokenBucket, TokioClock};
+use std::time::{Duration, Instant};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+// Counts per whole second of `times` (offsets from the start)
+fn per_second(times: &[Duration], seconds: usize) -> Vec<usize> {
+    let mut counts = vec![0; seconds];
+    for t in times {
+        if let Some(count) = counts.get_mut(t.as_secs() as usize) {
+            *count += 1;
+        }
+    }
+    counts
+}
+
+fn main() {
+    println!("=== Rate Limiting Examples ===\n");
+
+    // 1. A burst, then the refill rate
+    println!("1. Capacity 5, refill 10/s:");
+    let mut bucket = TokenBucket::with_clock(5, 10.0, FakeClock::new());
+    let burst: Vec<bool> = (0..8).map(|_| bucket.try_acquire(1)).collect();
+    println!(
+        "   t=0 ms    8 requests: {}",
+        burst
+            .iter()
+            .map(|&ok| if ok { "pass" } else { "wait" })
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+    bucket.clock().advance(ms(250));
+    let available = bucket.available();
+    println!("   t=250 ms  {} tokens refilled", available);
+    bucket.clock().advance(ms(2000));
+    println!("   t=2250 ms {} tokens, the capacity", bucket.available());
+
+    // 2. Blocking acquire on the fake clock
+    println!("\n2. acquire() for 20 messages:");
+    let mut bucket = TokenBucket::with_clock(5, 10.0, FakeClock::new());
+    let started = Instant::now();
+    let mut sent = Vec::new();
+    for _ in 0..20 {
+        if bucket.acquire(1).is_ok() {
+            sent.push(bucket.clock().elapsed());
+        }
+    }
+    println!(
+        "   sent at {} ms",
+        sent.iter()
+            .map(|t| t.as_millis().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    println!(
+        "   {:?} on the fake clock, {:?} for real",
+        bucket.clock().elapsed(),
+        started.elapsed()
+    );
+
+    // 3. Smoothing bursty traffic, polled like the gateway's uplink
+    println!("\n3. 30 batches every 3 s, limited to 5 + 10/s:");
+    let mut bucket = TokenBucket::with_clock(5, 10.0, FakeClock::new());
+    let mut queued = 0;
+    let (mut offered, mut forwarded) = (Vec::new(), Vec::new());
+    for tick in 0..90u64 {
+        let now = ms(tick * 100);
+        if tick % 30 == 0 {
+            queued += 30;
+            offered.extend([now; 30]);
+        }
+        while queued > 0 && bucket.try_acquire(1) {
+            queued -= 1;
+            forwarded.push(now);
+        }
+        bucket.clock().advance(ms(100));
+    }
+    let offered = per_second(&offered, 9);
+    let forwarded = per_second(&forwarded, 9);
+    println!("   offered   per second: {:?}", offered);
+    println!("   forwarded per second: {:?}", forwarded);
+    println!("   still queued: {}", queued);
+
+    // 4. Weighted requests: limiting bytes, not messages
+    println!("\n4. 2 KiB/s with 4 KiB bursts, by message size:");
+    let mut bytes = TokenBucket::with_clock(4096, 2048.0, FakeClock::new());
+    for size in [1500, 1500, 1500, 600, 5000] {
+        let before = bytes.clock().elapsed();
+        match bytes.acquire(size) {
+            Ok(()) => println!(
+                "   {:>5} bytes  waited {:>4} ms",
+                size,
+                (bytes.clock().elapsed() - before).as_millis()
+            ),
+            Err(e) => println!("   {:>5} bytes  {}", size, e),
+        }
+    }
+    // 5100 bytes: 4096 from the burst, the other 1004 at 2048/s = 490 ms
+    println!(
+        "   waited {:?} in all",
+        bytes.clock().sleeps().iter().sum::<Duration>()
+    );
+
+    // 5. Async acquire on tokio
+    println!("\n5. acquire_async on tokio (capacity 2, 50/s):");
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            println!("   cannot start tokio: {}", e);
+            return;
+        }
+    };
+    let mut bucket = TokenBucket::with_clock(2, 50.0, TokioClock);
+    let started = Instant::now();
+    let acquired = runtime.block_on(async {
+        let mut acquired = 0;
+        for _ in 0..6 {
+            if bucket.acquire_async(1).await.is_ok() {
+                acquired += 1;
+            }
+        }
+        acquired
+    });
+    let elapsed = started.elapsed();
+    println!(
+        "   {} tokens in {} ms, 4 of them 20 ms apart",
+        acquired,
+        elapsed.as_millis()
+    );
+
+    println!("\n=== End of Rate Limiting Examples ===");
+}
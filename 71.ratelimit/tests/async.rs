@@ -0,0 +1,42 @@
+#![cfg(feature = "tokio")]
+
+use ratelimit::{FakeClock, TokenBucket, TokioClock, TooLarge};
+use std::time::{Duration, Instant};
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn acquire_async_waits_on_the_tokio_timer() {
+    let mut bucket = TokenBucket::with_clock(2, 50.0, TokioClock);
+    let started = Instant::now();
+    runtime().block_on(async {
+        for _ in 0..6 {
+            bucket.acquire_async(1).await.unwrap();
+        }
+    });
+    // 2 from the burst, then 4 at 20 ms each
+    assert!(started.elapsed() >= Duration::from_millis(78));
+}
+
+#[test]
+fn acquire_async_on_the_fake_clock() {
+    let mut bucket = TokenBucket::with_clock(3, 10.0, FakeClock::new());
+    runtime().block_on(async {
+        for _ in 0..5 {
+            bucket.acquire_async(1).await.unwrap();
+        }
+        assert_eq!(
+            bucket.acquire_async(4).await,
+            Err(TooLarge {
+                requested: 4,
+                capacity: 3
+            })
+        );
+    });
+    assert_eq!(bucket.clock().elapsed(), Duration::from_millis(200));
+}
@@ -0,0 +1,195 @@
+use ratelimit::{Clock, FakeClock, TokenBucket, TooLarge};
+use std::time::{Duration, Instant};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn bucket(capacity: u32, per_second: f64) -> TokenBucket<FakeClock> {
+    TokenBucket::with_clock(capacity, per_second, FakeClock::new())
+}
+
+// A 64-bit LCG: the same sequence on every run
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % n
+    }
+}
+
+#[test]
+fn a_full_bucket_lets_the_burst_through() {
+    let mut b = bucket(5, 10.0);
+    let burst: Vec<bool> = (0..8).map(|_| b.try_acquire(1)).collect();
+    assert_eq!(burst, [true, true, true, true, true, false, false, false]);
+    assert_eq!(b.available(), 0);
+}
+
+#[test]
+fn partial_tokens_round_down() {
+    let mut b = bucket(5, 10.0);
+    assert!(b.try_acquire(5));
+    b.clock().advance(ms(250));
+    assert_eq!(b.available(), 2);
+    assert!(!b.try_acquire(3));
+    b.clock().advance(ms(50));
+    assert!(b.try_acquire(3));
+}
+
+#[test]
+fn never_more_than_the_capacity() {
+    let mut b = bucket(5, 10.0);
+    b.try_acquire(5);
+    b.clock().advance(Duration::from_secs(3600));
+    assert_eq!(b.available(), 5);
+    assert!(!b.try_acquire(6));
+}
+
+#[test]
+fn a_refused_request_takes_nothing() {
+    let mut b = bucket(5, 10.0);
+    assert!(b.try_acquire(3));
+    assert!(!b.try_acquire(3));
+    assert_eq!(b.available(), 2);
+}
+
+#[test]
+fn zero_capacity_becomes_one() {
+    let b = bucket(0, 1.0);
+    assert_eq!(b.capacity(), 1);
+}
+
+#[test]
+#[should_panic(expected = "refill rate must be positive")]
+fn zero_rate_is_rejected() {
+    bucket(5, 0.0);
+}
+
+#[test]
+fn acquire_sends_the_burst_then_one_per_refill() {
+    let mut b = bucket(5, 10.0);
+    let started = Instant::now();
+    let sent: Vec<Duration> = (0..20)
+        .map(|_| {
+            b.acquire(1).unwrap();
+            b.clock().elapsed()
+        })
+        .collect();
+    let expected: Vec<Duration> = [0; 5]
+        .into_iter()
+        .chain((1..=15).map(|i| 100 * i))
+        .map(ms)
+        .collect();
+    assert_eq!(sent, expected);
+    assert_eq!(b.clock().elapsed(), ms(1500));
+    assert!(started.elapsed() < ms(100));
+}
+
+#[test]
+fn wait_is_just_long_enough() {
+    let mut b = bucket(4, 3.0);
+    let now = b.clock().now();
+    assert!(b.try_acquire_at(4, now));
+    let wait = b.wait_at(1, now).unwrap();
+    // 1/3 s, rounded up to the nanosecond
+    assert_eq!(wait, Duration::from_nanos(333_333_334));
+    assert!(!b.try_acquire_at(1, now + wait - Duration::from_nanos(2)));
+    assert!(b.try_acquire_at(1, now + wait));
+    assert_eq!(b.wait_at(0, now + wait).unwrap(), Duration::ZERO);
+}
+
+#[test]
+fn larger_than_the_bucket_is_refused() {
+    let mut b = bucket(4096, 2048.0);
+    let too_large = TooLarge {
+        requested: 5000,
+        capacity: 4096,
+    };
+    assert_eq!(b.acquire(5000), Err(too_large));
+    assert_eq!(b.wait_at(5000, b.clock().now()), Err(too_large));
+    assert!(b.clock().sleeps().is_empty());
+    assert_eq!(
+        too_large.to_string(),
+        "asked for 5000 tokens, the bucket holds 4096"
+    );
+}
+
+#[test]
+fn weighted_acquire_waits_only_for_the_missing_bytes() {
+    let mut b = bucket(4096, 2048.0);
+    for size in [1500, 1500, 1500, 600] {
+        b.acquire(size).unwrap();
+    }
+    // 5100 bytes: 4096 from the burst, the other 1004 at 2048/s = 490 ms
+    let waited: Duration = b.clock().sleeps().iter().sum();
+    assert!(waited.abs_diff(Duration::from_nanos(490_234_375)) < Duration::from_micros(1));
+}
+
+#[test]
+fn bursty_traffic_is_smoothed_not_dropped() {
+    let mut b = bucket(5, 10.0);
+    let mut queued = 0;
+    let mut forwarded = [0; 9];
+    for tick in 0..90u64 {
+        if tick % 30 == 0 {
+            queued += 30;
+        }
+        while queued > 0 && b.try_acquire(1) {
+            queued -= 1;
+            forwarded[tick as usize / 10] += 1;
+        }
+        b.clock().advance(ms(100));
+    }
+    assert_eq!(queued, 0);
+    assert_eq!(forwarded.iter().sum::<usize>(), 90);
+    assert!(forwarded.iter().all(|&n| n <= 15), "{:?}", forwarded);
+}
+
+#[test]
+fn no_window_passes_more_than_capacity_plus_rate() {
+    let (capacity, rate) = (8, 25.0);
+    let mut b = bucket(capacity, rate);
+    let mut rng = Rng(7);
+    let mut passed: Vec<(Duration, u32)> = Vec::new();
+    for _ in 0..5_000 {
+        b.clock().advance(Duration::from_micros(rng.below(60_000)));
+        let n = 1 + rng.below(3) as u32;
+        if b.try_acquire(n) {
+            passed.push((b.clock().elapsed(), n));
+        }
+    }
+    assert!(passed.len() > 1_000);
+    // Every window that starts and ends on a passed request
+    for start in 0..passed.len() {
+        let mut tokens = 0;
+        for &(at, n) in &passed[start..] {
+            tokens += n;
+            let window = (at - passed[start].0).as_secs_f64();
+            assert!(
+                tokens as f64 <= capacity as f64 + rate * window + 1e-6,
+                "{} tokens in {:.3} s",
+                tokens,
+                window
+            );
+            if window > 2.0 {
+                break;
+            }
+        }
+    }
+}
+
+#[test]
+fn explicit_times_work_without_the_clock() {
+    let mut b = TokenBucket::new(2, 1.0);
+    let t0 = Instant::now();
+    assert!(b.try_acquire_at(2, t0));
+    assert_eq!(b.available_at(t0 + ms(999)), 0);
+    assert_eq!(b.available_at(t0 + ms(1000)), 1);
+    // Time going backwards refills nothing
+    assert_eq!(b.available_at(t0), 1);
+}
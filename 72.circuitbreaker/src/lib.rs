@@ -0,0 +1,295 @@
+// A circuit breaker for calls to a flaky upstream
+//
+//            failure rate >= threshold
+//   Closed ──────────────────────────────▶ Open
+//     ▲                                    │ cool-down elapsed
+//     │ all probes succeed                 ▼
+//     └────────────────────────────── HalfOpen ──▶ Open
+//                                        any probe fails
+//
+// Closed passes every call and records its outcome in a window of the last
+// `window` calls. Once the window holds at least `min_calls` and the share
+// of failures reaches `failure_rate`, the breaker opens: calls fail at once
+// without touching the upstream, which gets time to recover and the caller
+// gets a fast answer. After `cool_down` a few probe calls are let through;
+// if they all succeed the breaker closes with an empty window, otherwise
+// it opens for another cool-down.
+//
+// Every state change is passed to the listeners registered with
+// `on_transition`, e.g. to count trips or log them.
+
+pub use retry::{Clock, FakeClock, SystemClock};
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    // Share of failed calls in the window that opens the breaker, 0..=1
+    pub failure_rate: f64,
+    // How many recent calls the rate is computed over
+    pub window: usize,
+    // Fewer calls than this never open the breaker
+    pub min_calls: usize,
+    pub cool_down: Duration,
+    // Successful probes needed in HalfOpen to close again
+    pub probes: u32,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            failure_rate: 0.5,
+            window: 20,
+            min_calls: 5,
+            cool_down: Duration::from_secs(10),
+            probes: 2,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum State {
+    #[default]
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl State {
+    pub fn name(self) -> &'static str {
+        match self {
+            State::Closed => "closed",
+            State::Open => "open",
+            State::HalfOpen => "half-open",
+        }
+    }
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transition {
+    pub from: State,
+    pub to: State,
+    pub at: Instant,
+    // The failure rate that opened the breaker (1.0 for a failed probe);
+    // 0 for the other transitions
+    pub failure_rate: f64,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub calls: u64,
+    pub failures: u64,
+    // Refused while open, or beyond the probes while half-open
+    pub rejected: u64,
+    // Times the breaker opened
+    pub trips: u64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BreakerError<E> {
+    // The call was not made; the upstream may be tried again after `retry_in`
+    Open { retry_in: Duration },
+    // The call was made and failed
+    Failed(E),
+}
+
+impl<E: fmt::Display> fmt::Display for BreakerError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BreakerError::Open { retry_in } => {
+                write!(f, "circuit open, next try in {:?}", retry_in)
+            }
+            BreakerError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for BreakerError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BreakerError::Open { .. } => None,
+            BreakerError::Failed(e) => e.source(),
+        }
+    }
+}
+
+type Listener = Box<dyn FnMut(&Transition) + Send>;
+
+pub struct CircuitBreaker<C = SystemClock> {
+    config: Config,
+    clock: C,
+    state: State,
+    // Outcomes of recent calls in Closed, true for a failure
+    outcomes: VecDeque<bool>,
+    opened_at: Option<Instant>,
+    // Probes let through and succeeded in HalfOpen
+    probing: u32,
+    succeeded: u32,
+    stats: Stats,
+    listeners: Vec<Listener>,
+}
+
+impl CircuitBreaker<SystemClock> {
+    pub fn new(config: Config) -> CircuitBreaker<SystemClock> {
+        CircuitBreaker::with_clock(config, SystemClock)
+    }
+}
+
+impl<C: Clock> CircuitBreaker<C> {
+    pub fn with_clock(config: Config, clock: C) -> CircuitBreaker<C> {
+        CircuitBreaker {
+            outcomes: VecDeque::with_capacity(config.window.max(1)),
+            config,
+            clock,
+            state: State::Closed,
+            opened_at: None,
+            probing: 0,
+            succeeded: 0,
+            stats: Stats::default(),
+            listeners: Vec::new(),
+        }
+    }
+
+    pub fn on_transition(&mut self, listener: impl FnMut(&Transition) + Send + 'static) {
+        self.listeners.push(Box::new(listener));
+    }
+
+    // The state as of now: an open breaker whose cool-down is over reports
+    // HalfOpen, since the next call will be a probe
+    pub fn state(&mut self) -> State {
+        self.update();
+        self.state
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    // Share of failures among the recorded calls in the window
+    pub fn failure_rate(&self) -> f64 {
+        if self.outcomes.is_empty() {
+            return 0.0;
+        }
+        self.outcomes.iter().filter(|&&failed| failed).count() as f64 / self.outcomes.len() as f64
+    }
+
+    // Whether a call may go ahead now. Every `Ok(())` must be followed by
+    // `record`; `call` does both.
+    pub fn allow(&mut self) -> Result<(), Duration> {
+        self.update();
+        match self.state {
+            State::Closed => Ok(()),
+            State::HalfOpen if self.probing < self.config.probes => {
+                self.probing += 1;
+                Ok(())
+            }
+            State::HalfOpen => {
+                self.stats.rejected += 1;
+                Err(Duration::ZERO)
+            }
+            State::Open => {
+                self.stats.rejected += 1;
+                Err(self.retry_in())
+            }
+        }
+    }
+
+    // The outcome of a call that `allow` let through
+    pub fn record(&mut self, success: bool) {
+        self.stats.calls += 1;
+        if !success {
+            self.stats.failures += 1;
+        }
+        match self.state {
+            State::Closed => {
+                if self.outcomes.len() == self.config.window.max(1) {
+                    self.outcomes.pop_front();
+                }
+                self.outcomes.push_back(!success);
+                let rate = self.failure_rate();
+                if self.outcomes.len() >= self.config.min_calls && rate >= self.config.failure_rate
+                {
+                    self.trip(rate);
+                }
+            }
+            State::HalfOpen if success => {
+                self.succeeded += 1;
+                if self.succeeded >= self.config.probes {
+                    self.outcomes.clear();
+                    self.transition(State::Closed, 0.0);
+                }
+            }
+            State::HalfOpen => self.trip(1.0),
+            // A call allowed before the breaker opened; it has done its damage
+            State::Open => {}
+        }
+    }
+
+    // Runs `op` if the breaker allows it and records the outcome
+    pub fn call<T, E>(&mut self, op: impl FnOnce() -> Result<T, E>) -> Result<T, BreakerError<E>> {
+        if let Err(retry_in) = self.allow() {
+            return Err(BreakerError::Open { retry_in });
+        }
+        let result = op();
+        self.record(result.is_ok());
+        result.map_err(BreakerError::Failed)
+    }
+
+    fn retry_in(&self) -> Duration {
+        let now = self.clock.now();
+        self.opened_at
+            .map(|at| (at + self.config.cool_down).saturating_duration_since(now))
+            .unwrap_or_default()
+    }
+
+    fn update(&mut self) {
+        if self.state == State::Open && self.retry_in().is_zero() {
+            self.probing = 0;
+            self.succeeded = 0;
+            self.transition(State::HalfOpen, 0.0);
+        }
+    }
+
+    fn trip(&mut self, rate: f64) {
+        self.stats.trips += 1;
+        self.opened_at = Some(self.clock.now());
+        self.transition(State::Open, rate);
+    }
+
+    fn transition(&mut self, to: State, failure_rate: f64) {
+        let event = Transition {
+            from: self.state,
+            to,
+            at: self.clock.now(),
+            failure_rate,
+        };
+        self.state = to;
+        for listener in &mut self.listeners {
+            listener(&event);
+        }
+    }
+}
+
+impl<C> fmt::Debug for CircuitBreaker<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("config", &self.config)
+            .field("state", &self.state)
+            .field("stats", &self.stats)
+            .field("listeners", &self.listeners.len())
+            .finish_non_exhaustive()
+    }
+}
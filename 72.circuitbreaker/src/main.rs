@@ -0,0 +1,202 @@
+use circuitbreaker::{BreakerError, CircuitBreaker, Clock, Config, FakeClock, State, Transition};
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn config() -> Config {
+    Config {
+        failure_rate: 0.5,
+        window: 10,
+        min_calls: 5,
+        cool_down: Duration::from_secs(3),
+        probes: 2,
+    }
+}
+
+// An upstream that is down between two times on the fake clock
+struct Upstream {
+    down: (Duration, Duration),
+    calls: u64,
+}
+
+impl Upstream {
+    fn is_down(&self, now: Duration) -> bool {
+        now >= self.down.0 && now < self.down.1
+    }
+
+    fn send(&mut self, now: Duration) -> io::Result<()> {
+        self.calls += 1;
+        if self.is_down(now) {
+            Err(io::ErrorKind::ConnectionRefused.into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// Where the listener sends transitions: a log line each, and the edges
+// for counting, as a metrics module would
+#[derive(Debug, Default)]
+struct Sink {
+    log: Vec<String>,
+    edges: Vec<(State, State)>,
+}
+
+impl Sink {
+    fn record(&mut self, at: Duration, t: &Transition) {
+        let mut line = format!("t={:>5} ms  {} -> {}", at.as_millis(), t.from, t.to);
+        if t.to == State::Open {
+            line += &format!(" (failure rate {:.0}%)", t.failure_rate * 100.0);
+        }
+        self.log.push(line);
+        self.edges.push((t.from, t.to));
+    }
+}
+
+fn main() {
+    println!("=== Circuit Breaker Examples ===\n");
+
+    // 1. A 6 s outage, one call every 100 ms
+    println!("1. Outage from t=2 s to t=8 s (50% of 10 calls, 3 s cool-down):");
+    let mut breaker = CircuitBreaker::with_clock(config(), FakeClock::new());
+    let sink = Arc::new(Mutex::new(Sink::default()));
+    let events = Arc::clone(&sink);
+    let t0 = Clock::now(breaker.clock());
+    breaker.on_transition(move |t: &Transition| {
+        events.lock().unwrap().record(t.at - t0, t);
+    });
+    let mut upstream = Upstream {
+        down: (ms(2000), ms(8000)),
+        calls: 0,
+    };
+    let mut timeline = String::new();
+    let (mut ok, mut failed, mut rejected) = (0, 0, 0);
+    let mut reached_during_outage = 0;
+    for tick in 0..120u64 {
+        let now = ms(tick * 100);
+        let before = upstream.calls;
+        match breaker.call(|| upstream.send(now)) {
+            Ok(()) => ok += 1,
+            Err(BreakerError::Failed(_)) => failed += 1,
+            Err(BreakerError::Open { .. }) => rejected += 1,
+        }
+        if upstream.is_down(now) {
+            reached_during_outage += upstream.calls - before;
+        }
+        if tick % 5 == 0 {
+            timeline.push(match breaker.state() {
+                State::Closed => '-',
+                State::Open => 'X',
+                State::HalfOpen => '?',
+            });
+        }
+        breaker.clock().advance(ms(100));
+    }
+    println!("   state every 0.5 s: {}", timeline);
+    println!(
+        "   {} ok, {} failed, {} rejected without a call",
+        ok, failed, rejected
+    );
+    for line in &sink.lock().unwrap().log {
+        println!("   {}", line);
+    }
+    println!(
+        "   {} of the calls during the outage reached the upstream",
+        reached_during_outage
+    );
+
+    // 2. What doesn't trip it
+    println!("\n2. Sporadic failures and a quiet start:");
+    let mut breaker = CircuitBreaker::with_clock(config(), FakeClock::new());
+    for i in 0..200 {
+        // Every 4th call fails: 25%, under the 50% threshold
+        let _ = breaker.call(|| if i % 4 == 3 { Err(()) } else { Ok(()) });
+    }
+    println!(
+        "   200 calls, 1 in 4 failing: {} (window rate {:.0}%)",
+        breaker.state(),
+        breaker.failure_rate() * 100.0
+    );
+    let mut breaker = CircuitBreaker::with_clock(config(), FakeClock::new());
+    let mut states = Vec::new();
+    for _ in 0..5 {
+        let _ = breaker.call(|| Err::<(), _>(()));
+        states.push(breaker.state());
+    }
+    println!("   first 5 calls all failing: {:?}", states);
+
+    // 3. Probes in half-open
+    println!("\n3. Half-open lets through {} probes:", config().probes);
+    breaker.clock().advance(config().cool_down);
+    let allowed: Vec<bool> = (0..3).map(|_| breaker.allow().is_ok()).collect();
+    println!("   3 callers at once: {:?}", allowed);
+    breaker.record(true);
+    let after_one = breaker.state();
+    breaker.record(true);
+    println!(
+        "   after 1 good probe: {}, after 2: {}",
+        after_one,
+        breaker.state()
+    );
+
+    // 4. In front of a real connection
+    println!("\n4. Connecting to a closed port:");
+    let closed: SocketAddr = match TcpListener::bind("127.0.0.1:0").and_then(|l| l.local_addr()) {
+        Ok(addr) => addr,
+        Err(e) => {
+            println!("   cannot bind: {}", e);
+            return;
+        }
+    };
+    let mut breaker = CircuitBreaker::new(Config {
+        cool_down: Duration::from_secs(30),
+        ..config()
+    });
+    let mut reached = 0;
+    let mut fast = Duration::ZERO;
+    for attempt in 1..=10 {
+        let started = Instant::now();
+        let result = breaker.call(|| {
+            reached += 1;
+            TcpStream::connect_timeout(&closed, ms(500))
+        });
+        if let Err(e) = result {
+            if matches!(e, BreakerError::Open { .. }) {
+                fast = fast.max(started.elapsed());
+            }
+            if attempt == 1 || attempt == 5 || attempt == 6 {
+                println!("   attempt {:>2}: {}", attempt, e);
+            }
+        }
+    }
+    println!("   slowest rejected call: {:?}", fast);
+    println!(
+        "   {} connects made, {} answered by the breaker",
+        reached,
+        breaker.stats().rejected
+    );
+
+    // 5. Transitions as metrics
+    println!("\n5. Transition counts from section 1:");
+    let sink = sink.lock().unwrap();
+    let mut counted: Vec<((State, State), u64)> = Vec::new();
+    for &edge in &sink.edges {
+        match counted.iter_mut().find(|(e, _)| *e == edge) {
+            Some((_, n)) => *n += 1,
+            None => counted.push((edge, 1)),
+        }
+    }
+    for ((from, to), n) in &counted {
+        println!(
+            "   breaker_transitions{{from=\"{}\",to=\"{}\"}} {}",
+            from, to, n
+        );
+    }
+
+    println!("\n=== End of Circuit Breaker Examples ===");
+}
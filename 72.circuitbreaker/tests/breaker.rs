@@ -0,0 +1,253 @@
+use circuitbreaker::{
+    BreakerError, CircuitBreaker, Clock, Config, FakeClock, State, Stats, Transition,
+};
+use std::error::Error;
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn config() -> Config {
+    Config {
+        failure_rate: 0.5,
+        window: 10,
+        min_calls: 5,
+        cool_down: Duration::from_secs(3),
+        probes: 2,
+    }
+}
+
+fn breaker() -> CircuitBreaker<FakeClock> {
+    CircuitBreaker::with_clock(config(), FakeClock::new())
+}
+
+fn fail(breaker: &mut CircuitBreaker<FakeClock>) {
+    let _ = breaker.call(|| Err::<(), _>(()));
+}
+
+fn succeed(breaker: &mut CircuitBreaker<FakeClock>) {
+    let _ = breaker.call(|| Ok::<_, ()>(()));
+}
+
+// Every transition as (from, to, time since start, failure rate)
+type Log = Arc<Mutex<Vec<(State, State, Duration, f64)>>>;
+
+fn listen(breaker: &mut CircuitBreaker<FakeClock>) -> Log {
+    let log = Log::default();
+    let events = Arc::clone(&log);
+    let t0 = Clock::now(breaker.clock());
+    breaker.on_transition(move |t: &Transition| {
+        events
+            .lock()
+            .unwrap()
+            .push((t.from, t.to, t.at - t0, t.failure_rate));
+    });
+    log
+}
+
+#[test]
+fn an_outage_opens_probes_and_closes() {
+    let mut breaker = breaker();
+    let log = listen(&mut breaker);
+    let (mut reached, mut reached_in_outage, mut rejected) = (0, 0, 0);
+    for tick in 0..120u64 {
+        let now = ms(tick * 100);
+        let down = now >= ms(2000) && now < ms(8000);
+        let result = breaker.call(|| {
+            reached += 1;
+            reached_in_outage += down as u32;
+            if down {
+                Err(())
+            } else {
+                Ok(())
+            }
+        });
+        rejected += matches!(result, Err(BreakerError::Open { .. })) as u32;
+        breaker.clock().advance(ms(100));
+    }
+    // Opens on the 5th failure in a window of 10 calls, at 2.4 s; the probe
+    // at 5.4 s fails, the two at 8.4 s succeed
+    let log = log.lock().unwrap();
+    let edges: Vec<_> = log
+        .iter()
+        .map(|&(from, to, at, _)| (from, to, at))
+        .collect();
+    assert_eq!(
+        edges,
+        [
+            (State::Closed, State::Open, ms(2400)),
+            (State::Open, State::HalfOpen, ms(5400)),
+            (State::HalfOpen, State::Open, ms(5400)),
+            (State::Open, State::HalfOpen, ms(8400)),
+            (State::HalfOpen, State::Closed, ms(8500)),
+        ]
+    );
+    assert_eq!(log[0].3, 0.5);
+    assert_eq!(log[2].3, 1.0);
+    // 60 calls in the outage, 6 reached it
+    assert_eq!(reached_in_outage, 6);
+    assert_eq!(reached + rejected, 120);
+    assert_eq!(
+        breaker.stats(),
+        Stats {
+            calls: reached as u64,
+            failures: 6,
+            rejected: rejected as u64,
+            trips: 2,
+        }
+    );
+}
+
+#[test]
+fn sporadic_failures_stay_closed() {
+    let mut breaker = breaker();
+    for i in 0..200 {
+        // Every 4th call fails: 25%, under the 50% threshold
+        let _ = breaker.call(|| if i % 4 == 3 { Err(()) } else { Ok(()) });
+        assert_eq!(breaker.state(), State::Closed);
+    }
+    assert!((0.2..=0.3).contains(&breaker.failure_rate()));
+    assert_eq!(breaker.stats().trips, 0);
+}
+
+#[test]
+fn fewer_than_min_calls_never_open() {
+    let mut breaker = breaker();
+    for _ in 0..4 {
+        fail(&mut breaker);
+        assert_eq!(breaker.state(), State::Closed);
+    }
+    fail(&mut breaker);
+    assert_eq!(breaker.state(), State::Open);
+}
+
+#[test]
+fn old_failures_slide_out_of_the_window() {
+    let mut breaker = breaker();
+    for _ in 0..6 {
+        succeed(&mut breaker);
+    }
+    for _ in 0..3 {
+        fail(&mut breaker);
+    }
+    for _ in 0..10 {
+        succeed(&mut breaker);
+    }
+    assert_eq!(breaker.failure_rate(), 0.0);
+    for _ in 0..4 {
+        fail(&mut breaker);
+    }
+    assert_eq!(breaker.failure_rate(), 0.4);
+    assert_eq!(breaker.state(), State::Closed);
+    fail(&mut breaker);
+    assert_eq!(breaker.state(), State::Open);
+}
+
+#[test]
+fn open_rejects_without_calling_and_says_when() {
+    let mut breaker = breaker();
+    for _ in 0..5 {
+        fail(&mut breaker);
+    }
+    breaker.clock().advance(ms(1000));
+    let mut called = false;
+    let result = breaker.call(|| {
+        called = true;
+        Ok::<_, ()>(())
+    });
+    assert!(!called);
+    assert_eq!(result, Err(BreakerError::Open { retry_in: ms(2000) }));
+    assert_eq!(breaker.allow(), Err(ms(2000)));
+    assert_eq!(breaker.stats().rejected, 2);
+    breaker.clock().advance(ms(2000));
+    assert_eq!(breaker.state(), State::HalfOpen);
+}
+
+#[test]
+fn half_open_lets_only_the_probes_through() {
+    let mut breaker = breaker();
+    for _ in 0..5 {
+        fail(&mut breaker);
+    }
+    breaker.clock().advance(config().cool_down);
+    let allowed: Vec<bool> = (0..3).map(|_| breaker.allow().is_ok()).collect();
+    assert_eq!(allowed, [true, true, false]);
+    breaker.record(true);
+    assert_eq!(breaker.state(), State::HalfOpen);
+    breaker.record(true);
+    assert_eq!(breaker.state(), State::Closed);
+    // Closing starts from an empty window
+    assert_eq!(breaker.failure_rate(), 0.0);
+}
+
+#[test]
+fn a_failed_probe_reopens_for_a_full_cool_down() {
+    let mut breaker = breaker();
+    for _ in 0..5 {
+        fail(&mut breaker);
+    }
+    breaker.clock().advance(config().cool_down);
+    succeed(&mut breaker);
+    fail(&mut breaker);
+    assert_eq!(breaker.state(), State::Open);
+    assert_eq!(breaker.allow(), Err(config().cool_down));
+    assert_eq!(breaker.stats().trips, 2);
+}
+
+#[test]
+fn a_late_outcome_while_open_is_ignored() {
+    let mut breaker = breaker();
+    breaker.allow().unwrap();
+    for _ in 0..5 {
+        fail(&mut breaker);
+    }
+    // The call allowed before the breaker opened reports back
+    breaker.record(true);
+    assert_eq!(breaker.state(), State::Open);
+    assert_eq!(breaker.stats().calls, 6);
+}
+
+#[test]
+fn failed_is_transparent() {
+    let inner = io::Error::other("upstream said no");
+    let failed = BreakerError::Failed(inner);
+    assert_eq!(failed.to_string(), "upstream said no");
+    assert!(failed.source().is_none());
+    let open = BreakerError::<io::Error>::Open { retry_in: ms(1500) };
+    assert_eq!(open.to_string(), "circuit open, next try in 1.5s");
+}
+
+#[test]
+fn state_names() {
+    assert_eq!(State::default(), State::Closed);
+    let names: Vec<String> = [State::Closed, State::Open, State::HalfOpen]
+        .iter()
+        .map(State::to_string)
+        .collect();
+    assert_eq!(names, ["closed", "open", "half-open"]);
+}
+
+#[test]
+fn a_closed_port_is_answered_by_the_breaker() {
+    let closed = TcpListener::bind("127.0.0.1:0")
+        .and_then(|l| l.local_addr())
+        .unwrap();
+    let mut breaker = CircuitBreaker::new(Config {
+        cool_down: Duration::from_secs(30),
+        ..config()
+    });
+    let mut reached = 0;
+    for _ in 0..10 {
+        let _ = breaker.call(|| {
+            reached += 1;
+            TcpStream::connect_timeout(&closed, ms(500))
+        });
+    }
+    assert_eq!(reached, 5);
+    assert_eq!(breaker.stats().rejected, 5);
+    assert_eq!(breaker.state(), State::Open);
+}
@@ -0,0 +1,281 @@
+// Running jobs on cron schedules
+//
+//   add("rotate", "0 0 * * *", Missed::RunOnce, task)
+//        │
+//        ▼  next = schedule.next_after(now)
+//   ┌──────────────────────────────┐   run_due(now): every job whose
+//   │ job   next fire    policy    │   next fire is <= now runs, by its
+//   │ rotate 00:00:00    RunOnce   │   policy for fire times it missed
+//   │ calib  06:00:00    Skip      │──▶ run(name, task, Fire { .. })
+//   └──────────────────────────────┘
+//
+// `Scheduler` itself never looks at the clock: `run_due(now)` is given the
+// time, so a loop that already has one (like the gateway's) calls it each
+// cycle, and a demo can jump a day ahead. `spawn` runs it on a background
+// thread against the system clock, sleeping until the next fire time.
+//
+// A fire time counts as missed when the scheduler wasn't asked until more
+// than `grace` seconds after it, e.g. because the device was suspended or
+// the loop stalled. `Missed` decides what happens to those.
+//
+// Jobs wait in an `IndexedHeap` (79.heap) ordered by next fire time, so
+// `next_due` is a peek and `run_due` only touches the jobs that are due,
+// however many are scheduled. Ties go to the job added first.
+
+pub mod civil;
+pub mod schedule;
+
+pub use civil::DateTime;
+pub use schedule::{ParseError, Schedule};
+
+use heap::IndexedHeap;
+use std::fmt;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Fire times looked at per job and call. A job that missed more (an
+// every-second job after a day suspended) gets at most this many runs.
+const MAX_CATCH_UP: usize = 1000;
+
+// The background thread wakes at least this often, so a wall clock that
+// was set forward is noticed
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Missed {
+    // Run only if the latest fire time is within the grace period; drop
+    // the rest
+    Skip,
+    // Run once for any number of missed fire times
+    RunOnce,
+    // Run once per missed fire time, oldest first
+    RunAll,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fire {
+    // The fire time this run is for and the time it actually ran, in
+    // Unix seconds
+    pub scheduled: i64,
+    pub now: i64,
+    // Earlier fire times folded into this run (RunOnce) or dropped (Skip)
+    pub missed: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobInfo {
+    pub name: String,
+    pub schedule: String,
+    pub next: Option<i64>,
+    pub runs: u64,
+    // Fire times that did not get a run of their own
+    pub missed: u64,
+}
+
+struct Job<T> {
+    name: String,
+    schedule: Schedule,
+    policy: Missed,
+    next: Option<i64>,
+    task: T,
+    runs: u64,
+    missed: u64,
+}
+
+pub struct Scheduler<T> {
+    jobs: Vec<Job<T>>,
+    // Job index by (next fire time, job index); jobs with no next fire
+    // time are not queued
+    queue: IndexedHeap<usize, (i64, usize)>,
+    grace: i64,
+}
+
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+impl<T> Default for Scheduler<T> {
+    fn default() -> Self {
+        Scheduler::new()
+    }
+}
+
+impl<T> Scheduler<T> {
+    pub fn new() -> Scheduler<T> {
+        Scheduler {
+            jobs: Vec::new(),
+            queue: IndexedHeap::new(),
+            grace: 1,
+        }
+    }
+
+    // How late (in seconds) a run may start and still count as on time
+    pub fn with_grace(mut self, secs: i64) -> Scheduler<T> {
+        self.grace = secs.max(0);
+        self
+    }
+
+    // The first fire time is the first one after `now`
+    pub fn add(&mut self, name: &str, schedule: Schedule, policy: Missed, task: T, now: i64) {
+        let index = self.jobs.len();
+        let next = schedule.next_after(now);
+        self.jobs.push(Job {
+            name: name.to_string(),
+            next,
+            schedule,
+            policy,
+            task,
+            runs: 0,
+            missed: 0,
+        });
+        if let Some(next) = next {
+            self.queue.push(index, (next, index));
+        }
+    }
+
+    // Gives the job called `name` a new schedule, counted from `now`; the
+    // next fire time may move either way. False if there is no such job.
+    pub fn reschedule(&mut self, name: &str, schedule: Schedule, now: i64) -> bool {
+        let Some(index) = self.jobs.iter().position(|job| job.name == name) else {
+            return false;
+        };
+        let job = &mut self.jobs[index];
+        job.next = schedule.next_after(now);
+        job.schedule = schedule;
+        match job.next {
+            Some(next) => {
+                self.queue.push(index, (next, index));
+            }
+            None => {
+                self.queue.remove(&index);
+            }
+        }
+        true
+    }
+
+    pub fn next_due(&self) -> Option<i64> {
+        self.queue.peek().map(|(_, &(next, _))| next)
+    }
+
+    pub fn jobs(&self) -> Vec<JobInfo> {
+        self.jobs
+            .iter()
+            .map(|job| JobInfo {
+                name: job.name.clone(),
+                schedule: job.schedule.to_string(),
+                next: job.next,
+                runs: job.runs,
+                missed: job.missed,
+            })
+            .collect()
+    }
+
+    // Runs every job that is due at `now`, earliest fire time first;
+    // returns how many runs there were
+    pub fn run_due(&mut self, now: i64, mut run: impl FnMut(&str, &mut T, Fire)) -> usize {
+        let mut ran = 0;
+        while let Some((&index, &(first, _))) = self.queue.peek() {
+            if first > now {
+                break;
+            }
+            self.queue.pop();
+            let job = &mut self.jobs[index];
+            let mut due = vec![first];
+            while due.len() < MAX_CATCH_UP {
+                match job.schedule.next_after(due[due.len() - 1]) {
+                    Some(t) if t <= now => due.push(t),
+                    _ => break,
+                }
+            }
+            let latest = due[due.len() - 1];
+            let missed = due.len() as u32 - 1;
+            let fires = match job.policy {
+                Missed::RunAll => due
+                    .iter()
+                    .map(|&scheduled| Fire {
+                        scheduled,
+                        now,
+                        missed: 0,
+                    })
+                    .collect(),
+                Missed::Skip if now - latest > self.grace => {
+                    job.missed += due.len() as u64;
+                    Vec::new()
+                }
+                Missed::Skip | Missed::RunOnce => {
+                    job.missed += missed as u64;
+                    vec![Fire {
+                        scheduled: latest,
+                        now,
+                        missed,
+                    }]
+                }
+            };
+            for fire in fires {
+                run(&job.name, &mut job.task, fire);
+                job.runs += 1;
+                ran += 1;
+            }
+            // Always after `now`, so this loop can't pick the job again
+            job.next = job.schedule.next_after(now);
+            if let Some(next) = job.next {
+                self.queue.push(index, (next, index));
+            }
+        }
+        ran
+    }
+}
+
+impl<T> fmt::Debug for Scheduler<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scheduler")
+            .field("jobs", &self.jobs())
+            .field("grace", &self.grace)
+            .finish()
+    }
+}
+
+// A job for the background thread
+pub type Task = Box<dyn FnMut(Fire) + Send>;
+
+#[derive(Debug)]
+pub struct Runner {
+    stop: mpsc::Sender<()>,
+    handle: JoinHandle<Scheduler<Task>>,
+}
+
+impl Scheduler<Task> {
+    // Runs the jobs on a background thread until `Runner::stop`
+    pub fn spawn(mut self) -> Runner {
+        let (stop, stopped) = mpsc::channel();
+        let handle = thread::spawn(move || loop {
+            self.run_due(unix_now(), |_, task, fire| task(fire));
+            let wait = match self.next_due() {
+                Some(next) => {
+                    let now = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default();
+                    Duration::from_secs(next.max(0) as u64).saturating_sub(now)
+                }
+                None => MAX_SLEEP,
+            };
+            match stopped.recv_timeout(wait.min(MAX_SLEEP)) {
+                Err(RecvTimeoutError::Timeout) => {}
+                Ok(()) | Err(RecvTimeoutError::Disconnected) => return self,
+            }
+        });
+        Runner { stop, handle }
+    }
+}
+
+impl Runner {
+    // Stops the thread and hands back the scheduler, e.g. for its stats
+    pub fn stop(self) -> Scheduler<Task> {
+        let _ = self.stop.send(());
+        self.handle.join().expect("scheduler thread panicked")
+    }
+}
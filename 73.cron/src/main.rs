@@ -0,0 +1,201 @@
+use cron::{unix_now, DateTime, Fire, Missed, Schedule, Scheduler, Task};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn at(year: i64, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> i64 {
+    DateTime::new(year, month, day, hour, minute, second).to_unix()
+}
+
+fn show(t: i64) -> String {
+    DateTime::from_unix(t).to_string()
+}
+
+// The next `n` fire times after `start`
+fn upcoming(schedule: &Schedule, start: i64, n: usize) -> Vec<i64> {
+    let mut times = Vec::new();
+    let mut t = start;
+    while times.len() < n {
+        match schedule.next_after(t) {
+            Some(next) => {
+                times.push(next);
+                t = next;
+            }
+            None => break,
+        }
+    }
+    times
+}
+
+fn main() {
+    println!("=== Cron Scheduling Examples ===\n");
+
+    // 1. Next fire times
+    // Saturday 2026-03-14 09:26:53 UTC
+    let start = at(2026, 3, 14, 9, 26, 53);
+    println!("1. Next fire times after {} (a Saturday):", show(start));
+    for text in [
+        "*/15 * * * *",
+        "0 9 * * MON-FRI",
+        "30 2 1 * *",
+        // Both day fields set: the 1st or any Monday
+        "0 0 1 * MON",
+        "0 0 29 2 *",
+        "*/20 * * * * *",
+        "@hourly",
+    ] {
+        let schedule: Schedule = text.parse().expect("valid expression");
+        let times: Vec<String> = upcoming(&schedule, start, 3)
+            .into_iter()
+            .map(show)
+            .collect();
+        println!("   {:<16} {}", text, times.join(", "));
+    }
+    let never: Schedule = "0 0 30 2 *".parse().expect("valid expression");
+    println!("   {:<16} {:?}", "0 0 30 2 *", never.next_after(start));
+
+    // 2. Errors
+    println!("\n2. Rejected expressions:");
+    let bad = [
+        "* * * *",
+        "61 * * * *",
+        "*/0 * * * *",
+        "0 9 * * FRY",
+        "0 17-9 * * *",
+    ];
+    for text in bad {
+        match text.parse::<Schedule>() {
+            Ok(_) => println!("   {:<14} accepted", text),
+            Err(e) => println!("   {:<14} {}", text, e),
+        }
+    }
+
+    // 3. Calendar arithmetic
+    println!("\n3. Unix time and the calendar (UTC):");
+    let leap = DateTime::new(2000, 2, 29, 12, 0, 0);
+    println!(
+        "   {} = {}, weekday {}",
+        leap,
+        leap.to_unix(),
+        leap.weekday()
+    );
+    println!(
+        "   0 = {}, weekday {}",
+        DateTime::from_unix(0),
+        DateTime::from_unix(0).weekday()
+    );
+
+    // 4. Missed runs
+    println!("\n4. An hourly job, the device suspended 10:30 to 14:10:");
+    let hourly: Schedule = "0 * * * *".parse().expect("valid expression");
+    let mut scheduler: Scheduler<()> = Scheduler::new();
+    let suspended = at(2026, 3, 14, 10, 30, 0);
+    for (name, policy) in [
+        ("skip", Missed::Skip),
+        ("run-once", Missed::RunOnce),
+        ("run-all", Missed::RunAll),
+    ] {
+        scheduler.add(name, hourly.clone(), policy, (), suspended);
+    }
+    let mut fired: Vec<(String, Fire)> = Vec::new();
+    scheduler.run_due(at(2026, 3, 14, 14, 10, 0), |name, _, fire| {
+        fired.push((name.to_string(), fire))
+    });
+    for (name, fire) in &fired {
+        println!(
+            "   14:10:00 {:<9} runs for {}, {} earlier folded in",
+            name,
+            show(fire.scheduled),
+            fire.missed
+        );
+    }
+    fired.clear();
+    scheduler.run_due(at(2026, 3, 14, 15, 0, 0), |name, _, fire| {
+        fired.push((name.to_string(), fire))
+    });
+    println!("   15:00:00 on time: {} job(s) ran", fired.len());
+    for job in scheduler.jobs() {
+        println!(
+            "   {:<9} {} run(s), {} missed",
+            job.name, job.runs, job.missed
+        );
+    }
+
+    // 5. On a background thread
+    println!("\n5. Background thread, every second and every 2 s for 2.5 s:");
+    let log: Arc<Mutex<Vec<(&str, Fire)>>> = Arc::new(Mutex::new(Vec::new()));
+    let mut scheduler: Scheduler<Task> = Scheduler::new();
+    let now = unix_now();
+    for (name, text) in [("tick", "* * * * * *"), ("tock", "*/2 * * * * *")] {
+        let log = Arc::clone(&log);
+        let task: Task = Box::new(move |fire| log.lock().unwrap().push((name, fire)));
+        scheduler.add(
+            name,
+            text.parse().expect("valid expression"),
+            Missed::Skip,
+            task,
+            now,
+        );
+    }
+    let started = Instant::now();
+    let runner = scheduler.spawn();
+    thread::sleep(Duration::from_millis(2500));
+    let scheduler = runner.stop();
+    let stopped_in = started
+        .elapsed()
+        .saturating_sub(Duration::from_millis(2500));
+    let log = log.lock().unwrap();
+    for (name, fire) in log.iter() {
+        println!(
+            "   {} {} (late by {} s)",
+            &show(fire.scheduled)[11..],
+            name,
+            fire.now - fire.scheduled
+        );
+    }
+    println!(
+        "   stopped {:?} after asking, {} tick(s) counted",
+        stopped_in,
+        scheduler.jobs()[0].runs
+    );
+
+    // 6. Many jobs and rescheduling
+    println!("\n6. 2,000 daily jobs from 01:00, then rescheduling one:");
+    let midnight = at(2026, 3, 15, 0, 0, 0);
+    let mut scheduler: Scheduler<u32> = Scheduler::new();
+    for i in 0..2000u32 {
+        // Spread over the minutes of 01:00 to 23:59; jobs 0 and 1380 share 01:00
+        let text = format!("{} {} * * *", i % 60, 1 + (i / 60) % 23);
+        let schedule: Schedule = text.parse().expect("valid expression");
+        scheduler.add(&format!("job-{}", i), schedule, Missed::Skip, i, midnight);
+    }
+    let first = scheduler.next_due();
+    println!("   next due: {}", show(first.unwrap_or(0)));
+    scheduler.reschedule(
+        "job-1999",
+        "30 0 * * *".parse().expect("valid expression"),
+        midnight,
+    );
+    let earlier = scheduler.next_due();
+    let mut ran = Vec::new();
+    scheduler.run_due(at(2026, 3, 15, 0, 30, 0), |name, _, _| {
+        ran.push(name.to_string())
+    });
+    println!(
+        "   job-1999 moved to 00:30: next due {}, ran {:?}",
+        show(earlier.unwrap_or(0)),
+        ran
+    );
+    scheduler.reschedule(
+        "job-1999",
+        "0 0 1 1 *".parse().expect("valid expression"),
+        at(2026, 3, 15, 0, 30, 0),
+    );
+    ran.clear();
+    scheduler.run_due(at(2026, 3, 15, 1, 0, 0), |name, _, _| {
+        ran.push(name.to_string())
+    });
+    println!("   job-1999 moved to 1 January; at 01:00 ran {:?}", ran);
+
+    println!("\n=== End of Cron Scheduling Examples ===");
+}
@@ -0,0 +1,274 @@
+// Cron expressions
+//
+//   ┌───────────── second (0-59, optional: only with six fields)
+//   │ ┌─────────── minute (0-59)
+//   │ │ ┌───────── hour (0-23)
+//   │ │ │ ┌─────── day of month (1-31)
+//   │ │ │ │ ┌───── month (1-12 or JAN-DEC)
+//   │ │ │ │ │ ┌─── day of week (0-7 or SUN-SAT, 0 and 7 are Sunday)
+//   * * * * * *
+//
+// Each field is a comma-separated list of `*`, `N`, `A-B`, each optionally
+// followed by `/STEP` (`N/STEP` runs from N to the end of the range). The
+// `@yearly`, `@monthly`, `@weekly`, `@daily` (`@midnight`) and `@hourly`
+// shorthands are accepted. Not supported: `L`, `W`, `#` and `?`.
+//
+// As in Vixie cron, when both day fields are restricted a day matches if
+// either does: `0 0 1 * MON` is the 1st of the month and every Monday.
+// All times are UTC.
+
+use crate::civil::{days_from_civil, DateTime};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+// How far `next_after` looks before deciding an expression never fires.
+// 29 Feb on a given weekday recurs within 28 years.
+const SEARCH_YEARS: i64 = 30;
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+struct Field {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    // Names for the values from `min`, matched case-insensitively
+    names: &'static [&'static str],
+}
+
+const SECOND: Field = Field {
+    name: "second",
+    min: 0,
+    max: 59,
+    names: &[],
+};
+const MINUTE: Field = Field {
+    name: "minute",
+    min: 0,
+    max: 59,
+    names: &[],
+};
+const HOUR: Field = Field {
+    name: "hour",
+    min: 0,
+    max: 23,
+    names: &[],
+};
+const DAY: Field = Field {
+    name: "day of month",
+    min: 1,
+    max: 31,
+    names: &[],
+};
+const MONTH: Field = Field {
+    name: "month",
+    min: 1,
+    max: 12,
+    names: &MONTHS,
+};
+// 7 is accepted and folded into 0
+const WEEKDAY: Field = Field {
+    name: "day of week",
+    min: 0,
+    max: 7,
+    names: &WEEKDAYS,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    // The field that failed, or "expression" for the whole string
+    pub field: &'static str,
+    pub text: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} '{}': {}", self.field, self.text, self.reason)
+    }
+}
+
+impl Error for ParseError {}
+
+impl Field {
+    fn error(&self, text: &str, reason: impl Into<String>) -> ParseError {
+        ParseError {
+            field: self.name,
+            text: text.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    fn value(&self, text: &str) -> Result<u32, ParseError> {
+        let value = match self.names.iter().position(|n| n.eq_ignore_ascii_case(text)) {
+            Some(i) => self.min + i as u32,
+            None => text
+                .parse()
+                .map_err(|_| self.error(text, "not a number or name"))?,
+        };
+        if value < self.min || value > self.max {
+            return Err(self.error(text, format!("out of range {}-{}", self.min, self.max)));
+        }
+        Ok(value)
+    }
+
+    // A bit per allowed value; also whether the field starts with `*`,
+    // which is what Vixie cron checks for the either-day rule
+    fn parse(&self, text: &str) -> Result<(u64, bool), ParseError> {
+        let mut bits = 0u64;
+        for part in text.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => {
+                    let step: u32 = step
+                        .parse()
+                        .map_err(|_| self.error(part, "step is not a number"))?;
+                    if step == 0 {
+                        return Err(self.error(part, "step must be positive"));
+                    }
+                    (range, Some(step))
+                }
+                None => (part, None),
+            };
+            let (lo, hi) = match range.split_once('-') {
+                _ if range == "*" => (self.min, self.max),
+                Some((lo, hi)) => (self.value(lo)?, self.value(hi)?),
+                // `N/STEP` runs to the end of the range
+                None if step.is_some() => (self.value(range)?, self.max),
+                None => {
+                    let v = self.value(range)?;
+                    (v, v)
+                }
+            };
+            if lo > hi {
+                return Err(self.error(part, "range runs backwards"));
+            }
+            for v in (lo..=hi).step_by(step.unwrap_or(1) as usize) {
+                bits |= 1 << v;
+            }
+        }
+        Ok((bits, text.starts_with('*')))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    // Which day fields started with `*`, for the either-day rule
+    any_day: bool,
+    any_weekday: bool,
+    text: String,
+}
+
+fn has(bits: u64, v: u32) -> bool {
+    bits & (1 << v) != 0
+}
+
+impl Schedule {
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn matches(&self, t: DateTime) -> bool {
+        has(self.months, t.month)
+            && self.day_matches(t)
+            && has(self.hours, t.hour)
+            && has(self.minutes, t.minute)
+            && has(self.seconds, t.second)
+    }
+
+    fn day_matches(&self, t: DateTime) -> bool {
+        let day = has(self.days, t.day);
+        let weekday = has(self.weekdays, t.weekday());
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    // The first time strictly after `after` (Unix seconds) that matches, or
+    // None if there is none in the next 30 years (e.g. `0 0 30 2 *`)
+    pub fn next_after(&self, after: i64) -> Option<i64> {
+        let mut t = DateTime::from_unix(after + 1);
+        let limit = t.year + SEARCH_YEARS;
+        // Skip whole months, days, hours and minutes that can't match
+        // instead of testing every second
+        while t.year <= limit {
+            if !has(self.months, t.month) {
+                let (year, month) = if t.month == 12 {
+                    (t.year + 1, 1)
+                } else {
+                    (t.year, t.month + 1)
+                };
+                t = DateTime::new(year, month, 1, 0, 0, 0);
+            } else if !self.day_matches(t) {
+                let next = days_from_civil(t.year, t.month, t.day) + 1;
+                t = DateTime::from_unix(next * 86_400);
+            } else if !has(self.hours, t.hour) {
+                t = DateTime::from_unix(t.to_unix().div_euclid(3600) * 3600 + 3600);
+            } else if !has(self.minutes, t.minute) {
+                t = DateTime::from_unix(t.to_unix().div_euclid(60) * 60 + 60);
+            } else if !has(self.seconds, t.second) {
+                t = DateTime::from_unix(t.to_unix() + 1);
+            } else {
+                return Some(t.to_unix());
+            }
+        }
+        None
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Schedule, ParseError> {
+        let expanded = match text.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let (second, rest) = match fields.len() {
+            5 => ("0", &fields[..]),
+            6 => (fields[0], &fields[1..]),
+            n => {
+                return Err(ParseError {
+                    field: "expression",
+                    text: text.to_string(),
+                    reason: format!("expected 5 or 6 fields, found {}", n),
+                })
+            }
+        };
+        let (weekdays, any_weekday) = WEEKDAY.parse(rest[4])?;
+        let (days, any_day) = DAY.parse(rest[2])?;
+        let schedule = Schedule {
+            seconds: SECOND.parse(second)?.0,
+            minutes: MINUTE.parse(rest[0])?.0,
+            hours: HOUR.parse(rest[1])?.0,
+            days,
+            months: MONTH.parse(rest[3])?.0,
+            // Sunday is both 0 and 7
+            weekdays: (weekdays | weekdays >> 7) & 0x7F,
+            any_day,
+            any_weekday,
+            text: text.trim().to_string(),
+        };
+        Ok(schedule)
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
@@ -0,0 +1,25 @@
+use cron::civil::{civil_from_days, days_from_civil};
+use cron::DateTime;
+
+#[test]
+fn known_dates_and_weekdays() {
+    let leap = DateTime::new(2000, 2, 29, 12, 0, 0);
+    assert_eq!(leap.to_unix(), 951_825_600);
+    // 0 is Sunday
+    assert_eq!(leap.weekday(), 2);
+    assert_eq!(DateTime::from_unix(0).to_string(), "1970-01-01 00:00:00");
+    assert_eq!(DateTime::from_unix(0).weekday(), 4);
+    assert_eq!(DateTime::from_unix(-1).to_string(), "1969-12-31 23:59:59");
+    assert_eq!(days_from_civil(1970, 1, 1), 0);
+    assert_eq!(civil_from_days(-25_567), (1900, 1, 1));
+}
+
+#[test]
+fn every_day_from_1900_to_2100_round_trips() {
+    for day in -25_567..47_482i64 {
+        let t = day * 86_400 + 3_723;
+        assert_eq!(DateTime::from_unix(t).to_unix(), t, "day {}", day);
+        let (y, m, d) = civil_from_days(day);
+        assert_eq!(days_from_civil(y, m, d), day);
+    }
+}
@@ -0,0 +1,81 @@
+use cron::{DateTime, Fire, Missed, Schedule, Scheduler};
+
+fn at(hour: u32, minute: u32) -> i64 {
+    DateTime::new(2026, 3, 14, hour, minute, 0).to_unix()
+}
+
+fn schedule(text: &str) -> Schedule {
+    text.parse().expect("valid expression")
+}
+
+// An hourly job per policy, added at 10:30; the device then sleeps
+// through 11:00 to 14:00 and wakes at 14:10
+fn suspended() -> (Scheduler<()>, Vec<(String, Fire)>) {
+    let mut scheduler = Scheduler::new();
+    for (name, policy) in [
+        ("skip", Missed::Skip),
+        ("run-once", Missed::RunOnce),
+        ("run-all", Missed::RunAll),
+    ] {
+        scheduler.add(name, schedule("0 * * * *"), policy, (), at(10, 30));
+    }
+    let mut fired = Vec::new();
+    scheduler.run_due(at(14, 10), |name, _, fire| {
+        fired.push((name.to_string(), fire))
+    });
+    (scheduler, fired)
+}
+
+fn fires<'a>(fired: &'a [(String, Fire)], name: &str) -> Vec<&'a Fire> {
+    fired
+        .iter()
+        .filter(|(n, _)| n == name)
+        .map(|(_, f)| f)
+        .collect()
+}
+
+#[test]
+fn skip_drops_the_missed_hours() {
+    let (scheduler, fired) = suspended();
+    assert!(fires(&fired, "skip").is_empty());
+    assert_eq!(scheduler.jobs()[0].missed, 4);
+    assert_eq!(scheduler.jobs()[0].next, Some(at(15, 0)));
+}
+
+#[test]
+fn run_once_runs_for_the_latest_hour() {
+    let (scheduler, fired) = suspended();
+    assert_eq!(
+        fires(&fired, "run-once"),
+        [&Fire {
+            scheduled: at(14, 0),
+            now: at(14, 10),
+            missed: 3,
+        }]
+    );
+    assert_eq!(scheduler.jobs()[1].runs, 1);
+    assert_eq!(scheduler.jobs()[1].missed, 3);
+}
+
+#[test]
+fn run_all_catches_up_in_order() {
+    let (_, fired) = suspended();
+    let times: Vec<i64> = fires(&fired, "run-all")
+        .iter()
+        .map(|f| f.scheduled)
+        .collect();
+    assert_eq!(times, [at(11, 0), at(12, 0), at(13, 0), at(14, 0)]);
+}
+
+#[test]
+fn on_time_runs_are_the_same_for_every_policy() {
+    let (mut scheduler, _) = suspended();
+    let mut fired = Vec::new();
+    scheduler.run_due(at(15, 0), |name, _, fire| {
+        fired.push((name.to_string(), fire))
+    });
+    assert_eq!(fired.len(), 3);
+    assert!(fired
+        .iter()
+        .all(|(_, f)| f.scheduled == at(15, 0) && f.missed == 0));
+}
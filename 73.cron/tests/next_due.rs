@@ -0,0 +1,166 @@
+use cron::{DateTime, Missed, Schedule, Scheduler};
+
+fn at(year: i64, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> i64 {
+    DateTime::new(year, month, day, hour, minute, second).to_unix()
+}
+
+fn schedule(text: &str) -> Schedule {
+    text.parse().expect("valid expression")
+}
+
+// 2,000 daily jobs spread over the minutes of 01:00 to 23:59; jobs 0 and
+// 1380 share 01:00
+fn daily_jobs(now: i64) -> Scheduler<u32> {
+    let mut scheduler = Scheduler::new();
+    for i in 0..2000u32 {
+        let text = format!("{} {} * * *", i % 60, 1 + (i / 60) % 23);
+        scheduler.add(&format!("job-{}", i), schedule(&text), Missed::Skip, i, now);
+    }
+    scheduler
+}
+
+fn run_names(scheduler: &mut Scheduler<u32>, now: i64) -> Vec<String> {
+    let mut ran = Vec::new();
+    scheduler.run_due(now, |name, _, _| ran.push(name.to_string()));
+    ran
+}
+
+// The earliest next fire time by looking at every job
+fn scanned_next_due<T>(scheduler: &Scheduler<T>) -> Option<i64> {
+    scheduler.jobs().iter().filter_map(|job| job.next).min()
+}
+
+#[test]
+fn the_earliest_of_many_jobs_is_next() {
+    let midnight = at(2026, 3, 15, 0, 0, 0);
+    let scheduler = daily_jobs(midnight);
+    assert_eq!(scheduler.next_due(), Some(at(2026, 3, 15, 1, 0, 0)));
+    assert_eq!(scheduler.next_due(), scanned_next_due(&scheduler));
+}
+
+#[test]
+fn ties_run_in_the_order_jobs_were_added() {
+    let midnight = at(2026, 3, 15, 0, 0, 0);
+    let mut scheduler = daily_jobs(midnight);
+    assert_eq!(
+        run_names(&mut scheduler, at(2026, 3, 15, 1, 0, 0)),
+        ["job-0", "job-1380"]
+    );
+}
+
+#[test]
+fn due_jobs_run_earliest_first() {
+    let midnight = at(2026, 3, 15, 0, 0, 0);
+    // A grace of an hour, so Skip still runs the jobs it is late for
+    let mut scheduler = daily_jobs(midnight).with_grace(3600);
+    // Everything from 01:00 to 01:59 at once
+    let ran = run_names(&mut scheduler, at(2026, 3, 15, 1, 59, 0));
+    let expected: Vec<String> = (0..60)
+        .flat_map(|minute| [minute, minute + 1380])
+        .filter(|&i| i < 2000)
+        .map(|i| format!("job-{}", i))
+        .collect();
+    assert_eq!(ran, expected);
+    assert_eq!(scheduler.next_due(), Some(at(2026, 3, 15, 2, 0, 0)));
+}
+
+#[test]
+fn rescheduling_moves_a_job_either_way() {
+    let midnight = at(2026, 3, 15, 0, 0, 0);
+    let mut scheduler = daily_jobs(midnight);
+    // Earlier than everything else
+    assert!(scheduler.reschedule("job-1999", schedule("30 0 * * *"), midnight));
+    assert_eq!(scheduler.next_due(), Some(at(2026, 3, 15, 0, 30, 0)));
+    assert_eq!(
+        run_names(&mut scheduler, at(2026, 3, 15, 0, 30, 0)),
+        ["job-1999"]
+    );
+    // Later than everything else
+    assert!(scheduler.reschedule("job-0", schedule("0 0 1 1 *"), midnight));
+    assert_eq!(
+        run_names(&mut scheduler, at(2026, 3, 15, 1, 0, 0)),
+        ["job-1380"]
+    );
+    let job_0 = scheduler.jobs().into_iter().find(|j| j.name == "job-0");
+    assert_eq!(job_0.unwrap().next, Some(at(2027, 1, 1, 0, 0, 0)));
+    assert_eq!(scheduler.next_due(), scanned_next_due(&scheduler));
+}
+
+#[test]
+fn a_schedule_that_never_fires_leaves_the_queue() {
+    let midnight = at(2026, 3, 15, 0, 0, 0);
+    let mut scheduler: Scheduler<()> = Scheduler::new();
+    scheduler.add("a", schedule("0 1 * * *"), Missed::Skip, (), midnight);
+    scheduler.add("b", schedule("0 2 * * *"), Missed::Skip, (), midnight);
+    assert!(scheduler.reschedule("a", schedule("0 0 30 2 *"), midnight));
+    assert_eq!(scheduler.next_due(), Some(at(2026, 3, 15, 2, 0, 0)));
+    assert!(scheduler.reschedule("b", schedule("0 0 30 2 *"), midnight));
+    assert_eq!(scheduler.next_due(), None);
+    assert_eq!(scheduler.run_due(at(2030, 1, 1, 0, 0, 0), |_, _, _| {}), 0);
+    // And comes back with a schedule that does
+    assert!(scheduler.reschedule("a", schedule("0 3 * * *"), midnight));
+    assert_eq!(scheduler.next_due(), Some(at(2026, 3, 15, 3, 0, 0)));
+}
+
+#[test]
+fn unknown_names_are_not_rescheduled() {
+    let midnight = at(2026, 3, 15, 0, 0, 0);
+    let mut scheduler = daily_jobs(midnight);
+    assert!(!scheduler.reschedule("job-2000", schedule("* * * * *"), midnight));
+    assert_eq!(scheduler.next_due(), Some(at(2026, 3, 15, 1, 0, 0)));
+}
+
+#[test]
+fn random_reschedules_and_runs_agree_with_a_scan() {
+    let mut x: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move |n: u64| {
+        x = x
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (x >> 33) % n
+    };
+    let mut now = at(2026, 3, 15, 0, 0, 0);
+    let mut scheduler: Scheduler<u32> = Scheduler::new();
+    for i in 0..200 {
+        let text = format!("{} {} * * *", next(60), next(24));
+        scheduler.add(
+            &format!("job-{}", i),
+            schedule(&text),
+            Missed::RunOnce,
+            i,
+            now,
+        );
+    }
+    for step in 0..2_000 {
+        if next(3) == 0 {
+            let text = format!("{} {} * * *", next(60), next(24));
+            let name = format!("job-{}", next(200));
+            assert!(scheduler.reschedule(&name, schedule(&text), now));
+        } else {
+            now += next(3 * 3600) as i64;
+            // Everything that was due by `now`, in fire time order
+            let mut expected: Vec<(i64, usize)> = scheduler
+                .jobs()
+                .iter()
+                .enumerate()
+                .filter_map(|(i, job)| job.next.filter(|&t| t <= now).map(|t| (t, i)))
+                .collect();
+            expected.sort();
+            let mut ran = Vec::new();
+            scheduler.run_due(now, |_, &mut i, fire| {
+                ran.push((fire.scheduled, i as usize))
+            });
+            // RunOnce reports the latest missed fire time, so compare jobs
+            let order: Vec<usize> = ran.iter().map(|&(_, i)| i).collect();
+            let expected: Vec<usize> = expected.iter().map(|&(_, i)| i).collect();
+            assert_eq!(order, expected, "step {}", step);
+        }
+        assert_eq!(
+            scheduler.next_due(),
+            scanned_next_due(&scheduler),
+            "step {}",
+            step
+        );
+        assert!(scheduler.next_due().is_some_and(|t| t > now));
+    }
+}
@@ -0,0 +1,39 @@
+use cron::{unix_now, Fire, Missed, Scheduler, Task};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Real time: ticks every second and tocks on even seconds for 2.5 s
+#[test]
+fn the_background_thread_runs_jobs_and_stops_promptly() {
+    let log: Arc<Mutex<Vec<(&str, Fire)>>> = Arc::new(Mutex::new(Vec::new()));
+    let mut scheduler: Scheduler<Task> = Scheduler::new();
+    let now = unix_now();
+    for (name, text) in [("tick", "* * * * * *"), ("tock", "*/2 * * * * *")] {
+        let log = Arc::clone(&log);
+        let task: Task = Box::new(move |fire| log.lock().unwrap().push((name, fire)));
+        scheduler.add(
+            name,
+            text.parse().expect("valid expression"),
+            Missed::Skip,
+            task,
+            now,
+        );
+    }
+    let runner = scheduler.spawn();
+    thread::sleep(Duration::from_millis(2500));
+    let asked = Instant::now();
+    let scheduler = runner.stop();
+    assert!(asked.elapsed() < Duration::from_millis(500));
+
+    let log = log.lock().unwrap();
+    let ticks = log.iter().filter(|(n, _)| *n == "tick").count();
+    assert!((2..=3).contains(&ticks), "{} ticks", ticks);
+    assert_eq!(scheduler.jobs()[0].runs, ticks as u64);
+    for (name, fire) in log.iter() {
+        assert!(fire.now >= fire.scheduled, "{} {:?}", name, fire);
+        if *name == "tock" {
+            assert_eq!(fire.scheduled % 2, 0);
+        }
+    }
+}
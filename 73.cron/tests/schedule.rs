@@ -0,0 +1,137 @@
+use cron::{DateTime, Schedule};
+
+fn at(year: i64, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> i64 {
+    DateTime::new(year, month, day, hour, minute, second).to_unix()
+}
+
+fn schedule(text: &str) -> Schedule {
+    text.parse().expect("valid expression")
+}
+
+// The next `n` fire times after `start`, as text
+fn upcoming(schedule: &Schedule, start: i64, n: usize) -> Vec<String> {
+    std::iter::successors(schedule.next_after(start), |&t| schedule.next_after(t))
+        .take(n)
+        .map(|t| DateTime::from_unix(t).to_string())
+        .collect()
+}
+
+// Saturday 2026-03-14 09:26:53 UTC
+const START: i64 = 1_773_480_413;
+
+#[test]
+fn next_fire_times() {
+    assert_eq!(at(2026, 3, 14, 9, 26, 53), START);
+    let cases: [(&str, [&str; 3]); 7] = [
+        (
+            "*/15 * * * *",
+            [
+                "2026-03-14 09:30:00",
+                "2026-03-14 09:45:00",
+                "2026-03-14 10:00:00",
+            ],
+        ),
+        (
+            "0 9 * * MON-FRI",
+            [
+                "2026-03-16 09:00:00",
+                "2026-03-17 09:00:00",
+                "2026-03-18 09:00:00",
+            ],
+        ),
+        (
+            "30 2 1 * *",
+            [
+                "2026-04-01 02:30:00",
+                "2026-05-01 02:30:00",
+                "2026-06-01 02:30:00",
+            ],
+        ),
+        // Both day fields set: the 1st or any Monday
+        (
+            "0 0 1 * MON",
+            [
+                "2026-03-16 00:00:00",
+                "2026-03-23 00:00:00",
+                "2026-03-30 00:00:00",
+            ],
+        ),
+        (
+            "0 0 29 2 *",
+            [
+                "2028-02-29 00:00:00",
+                "2032-02-29 00:00:00",
+                "2036-02-29 00:00:00",
+            ],
+        ),
+        (
+            "*/20 * * * * *",
+            [
+                "2026-03-14 09:27:00",
+                "2026-03-14 09:27:20",
+                "2026-03-14 09:27:40",
+            ],
+        ),
+        (
+            "@hourly",
+            [
+                "2026-03-14 10:00:00",
+                "2026-03-14 11:00:00",
+                "2026-03-14 12:00:00",
+            ],
+        ),
+    ];
+    for (text, expected) in cases {
+        assert_eq!(upcoming(&schedule(text), START, 3), expected, "{}", text);
+    }
+}
+
+#[test]
+fn thirty_february_never_fires() {
+    assert_eq!(schedule("0 0 30 2 *").next_after(START), None);
+}
+
+#[test]
+fn next_after_matches_a_minute_by_minute_scan() {
+    let from = START - 53;
+    let end = from + 60 * 24 * 60 * 60;
+    for text in [
+        "*/7 3-5 * * *",
+        "0 12 10-15 * TUE",
+        "15,45 */6 * JAN-APR SAT,SUN",
+    ] {
+        let schedule = schedule(text);
+        let scanned: Vec<i64> = (1..=60 * 24 * 60)
+            .map(|m| from + m * 60)
+            .filter(|&t| schedule.matches(DateTime::from_unix(t)))
+            .collect();
+        let jumped: Vec<i64> =
+            std::iter::successors(schedule.next_after(START), |&t| schedule.next_after(t))
+                .take_while(|&t| t <= end)
+                .collect();
+        assert!(!scanned.is_empty(), "{}", text);
+        assert_eq!(scanned, jumped, "{}", text);
+    }
+}
+
+#[test]
+fn bad_expressions_say_which_field() {
+    for (text, message) in [
+        (
+            "* * * *",
+            "expression '* * * *': expected 5 or 6 fields, found 4",
+        ),
+        ("61 * * * *", "minute '61': out of range 0-59"),
+        ("*/0 * * * *", "minute '*/0': step must be positive"),
+        ("0 9 * * FRY", "day of week 'FRY': not a number or name"),
+        ("0 17-9 * * *", "hour '17-9': range runs backwards"),
+    ] {
+        let e = text.parse::<Schedule>().unwrap_err();
+        assert_eq!(e.to_string(), message);
+    }
+}
+
+#[test]
+fn the_text_is_kept() {
+    assert_eq!(schedule("0 9 * * MON-FRI").as_str(), "0 9 * * MON-FRI");
+}
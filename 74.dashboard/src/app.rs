@@ -0,0 +1,205 @@
+// What the dashboard shows, built up from the feed
+//
+// The UI thread owns this model outright; nothing else touches it, so it
+// needs no locks. Each frame `pull` empties the channels into it and takes
+// a fresh `Status`, then `ui::draw` renders it. Drawing never waits on the
+// gateway, and the gateway never waits on drawing.
+
+use crate::feed::{AlertChange, Feed, Sample};
+use gateway::status::Status;
+use gateway::Config;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+// Samples kept per channel, enough for a wide terminal's sparkline
+pub const HISTORY: usize = 240;
+
+#[derive(Debug, Clone, Default)]
+pub struct Series {
+    pub values: VecDeque<f64>,
+    pub raw: f64,
+    pub count: u64,
+}
+
+impl Series {
+    fn push(&mut self, sample: &Sample) {
+        if self.values.len() == HISTORY {
+            self.values.pop_front();
+        }
+        self.values.push_back(sample.value);
+        self.raw = sample.raw;
+        self.count += 1;
+    }
+
+    pub fn latest(&self) -> Option<f64> {
+        self.values.back().copied()
+    }
+
+    // The last `n` values scaled to 0..=100 between their own min and max,
+    // which is what a sparkline draws
+    pub fn scaled(&self, n: usize) -> Vec<u64> {
+        let skip = self.values.len().saturating_sub(n);
+        let tail = self.values.iter().skip(skip);
+        let (min, max) = tail
+            .clone()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+                (lo.min(v), hi.max(v))
+            });
+        let span = max - min;
+        tail.map(|&v| {
+            if span > 0.0 {
+                (13.0 + 87.0 * (v - min) / span) as u64
+            } else {
+                50
+            }
+        })
+        .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlertState {
+    pub raised: bool,
+    pub since_ms: u64,
+    pub transitions: u32,
+}
+
+// One stage of the pipeline; `capacity` is `None` when it is switched off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Queue {
+    pub name: &'static str,
+    pub depth: usize,
+    pub capacity: Option<usize>,
+}
+
+#[derive(Debug)]
+pub struct App {
+    pub series: BTreeMap<(u16, String), Series>,
+    pub alerts: BTreeMap<String, AlertState>,
+    pub status: Status,
+    // Samples taken off the channel, samples that were waiting in it when
+    // the last frame began, and samples the feed has dropped
+    pub received: u64,
+    pub backlog: usize,
+    pub dropped: u64,
+    nodes: u16,
+    // Rule name -> the metric it watches, to colour the alerting channel
+    rule_metrics: HashMap<String, String>,
+    batch_size: usize,
+    max_pending: Option<usize>,
+    history_batch: Option<usize>,
+    channel_capacity: usize,
+}
+
+impl App {
+    pub fn new(config: &Config, channel_capacity: usize) -> App {
+        App {
+            series: BTreeMap::new(),
+            alerts: BTreeMap::new(),
+            status: Status::default(),
+            received: 0,
+            backlog: 0,
+            dropped: 0,
+            nodes: config.sensors.count,
+            rule_metrics: config
+                .rules
+                .iter()
+                .map(|rule| (rule.name.clone(), rule.metric.clone()))
+                .collect(),
+            batch_size: config.uplink.batch_size,
+            max_pending: (!config.uplink.addr.is_empty())
+                .then_some(config.uplink.max_pending_batches),
+            history_batch: (!config.storage.backend.is_empty())
+                .then_some(config.storage.batch_size),
+            channel_capacity,
+        }
+    }
+
+    pub fn apply_sample(&mut self, sample: Sample) {
+        self.received += 1;
+        self.series
+            .entry((sample.node, sample.metric.clone()))
+            .or_default()
+            .push(&sample);
+    }
+
+    pub fn apply_alert(&mut self, change: AlertChange) {
+        let state = self.alerts.entry(change.rule).or_insert(AlertState {
+            raised: false,
+            since_ms: 0,
+            transitions: 0,
+        });
+        state.raised = change.raised;
+        state.since_ms = change.at_ms;
+        state.transitions += 1;
+    }
+
+    // Everything waiting in the channels, then the current status; returns
+    // how many samples and alert changes were applied
+    pub fn pull(&mut self, feed: &Feed) -> usize {
+        self.backlog = feed.sent().saturating_sub(self.received) as usize;
+        let mut applied = 0;
+        while let Ok(change) = feed.alerts.try_recv() {
+            self.apply_alert(change);
+            applied += 1;
+        }
+        while let Ok(sample) = feed.samples.try_recv() {
+            self.apply_sample(sample);
+            applied += 1;
+        }
+        self.status = feed.status();
+        self.dropped = feed.dropped();
+        applied
+    }
+
+    pub fn nodes(&self) -> u16 {
+        self.nodes
+    }
+
+    pub fn series(&self, node: u16, metric: &str) -> Option<&Series> {
+        self.series.get(&(node, metric.to_string()))
+    }
+
+    // Whether a raised alert on `node` watches `metric`
+    pub fn alerting(&self, node: u16, metric: &str) -> bool {
+        self.alerts.iter().any(|(name, state)| {
+            state.raised
+                && name.rsplit_once('/').is_some_and(|(rule, n)| {
+                    n.parse() == Ok(node)
+                        && self.rule_metrics.get(rule).map(String::as_str) == Some(metric)
+                })
+        })
+    }
+
+    pub fn raised(&self) -> Vec<&str> {
+        self.alerts
+            .iter()
+            .filter(|(_, state)| state.raised)
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    pub fn queues(&self) -> [Queue; 4] {
+        [
+            Queue {
+                name: "ui channel",
+                depth: self.backlog,
+                capacity: Some(self.channel_capacity),
+            },
+            Queue {
+                name: "batch",
+                depth: self.status.batch_queued,
+                capacity: Some(self.batch_size),
+            },
+            Queue {
+                name: "uplink",
+                depth: self.status.batches_pending,
+                capacity: self.max_pending,
+            },
+            Queue {
+                name: "history",
+                depth: self.status.history_queued,
+                capacity: self.history_batch,
+            },
+        ]
+    }
+}
@@ -0,0 +1,211 @@
+// The gateway on its own thread, feeding the UI over channels
+//
+//   gateway thread                                  UI thread
+//   ┌──────────────────┐ sensors/#  try_send  ┌──────────────────┐
+//   │ Gateway::tick()  │─────────────────────▶│ samples (bounded)│ lossy
+//   │   route(..)      │ alerts/#   send      │                  │
+//   │   route(..)      │─────────────────────▶│ alerts           │ lossless
+//   │   status() ──────┼── Arc<Mutex<Status>> │ latest snapshot  │
+//   └──────────────────┘                      └──────────────────┘
+//
+// The handlers are the same `TopicRouter` routes any in-process consumer
+// of the gateway would attach, so the UI sees exactly what is published.
+// Samples go through a bounded channel with `try_send`: a UI that falls
+// behind loses samples (counted) instead of slowing the gateway down.
+// Alert transitions are rare and each one matters, so they get an
+// unbounded channel. Counters and queue depths are state, not events, and
+// are read from the shared `Status` whenever a frame is drawn.
+
+use gateway::status::{SharedStatus, Status};
+use gateway::{Config, Delivery, Gateway};
+use serde_json::Value;
+use std::io;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub node: u16,
+    pub metric: String,
+    pub raw: f64,
+    // After the gateway's smoothing filter
+    pub value: f64,
+    pub t: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertChange {
+    // `<rule>/<node>`, e.g. `hot/0`
+    pub rule: String,
+    pub raised: bool,
+    // Gateway uptime when the rule changed state
+    pub at_ms: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    sent: AtomicU64,
+    dropped: AtomicU64,
+}
+
+#[derive(Debug)]
+pub struct Feed {
+    pub samples: Receiver<Sample>,
+    pub alerts: Receiver<AlertChange>,
+    capacity: usize,
+    status: SharedStatus,
+    counters: Arc<Counters>,
+    stop: Arc<AtomicBool>,
+    // Taken by `stop`
+    handle: Option<JoinHandle<io::Result<Status>>>,
+}
+
+// `sensors/<node>/<metric>` with `{"t", "raw", "value"}`
+fn parse_sample(delivery: &Delivery) -> Option<Sample> {
+    let mut parts = delivery.topic.strip_prefix("sensors/")?.split('/');
+    let node = parts.next()?.parse().ok()?;
+    let metric = parts.next()?.to_string();
+    let payload: Value = serde_json::from_slice(delivery.payload).ok()?;
+    Some(Sample {
+        node,
+        metric,
+        raw: payload["raw"].as_f64()?,
+        value: payload["value"].as_f64()?,
+        t: payload["t"].as_u64()?,
+    })
+}
+
+// `alerts/<rule>/<node>` with `{"t", "at_ms", "state"}`
+fn parse_alert(delivery: &Delivery) -> Option<AlertChange> {
+    let rule = delivery.topic.strip_prefix("alerts/")?.to_string();
+    let payload: Value = serde_json::from_slice(delivery.payload).ok()?;
+    Some(AlertChange {
+        rule,
+        raised: payload["state"].as_str()? == "raised",
+        at_ms: payload["at_ms"].as_u64()?,
+    })
+}
+
+fn attach(
+    gateway: &mut Gateway,
+    samples: SyncSender<Sample>,
+    alerts: mpsc::Sender<AlertChange>,
+    counters: &Arc<Counters>,
+) {
+    let counters = Arc::clone(counters);
+    gateway
+        .route("sensors/#", move |delivery| {
+            if let Some(sample) = parse_sample(delivery) {
+                match samples.try_send(sample) {
+                    Ok(()) => counters.sent.fetch_add(1, Ordering::Relaxed),
+                    // Full, or the UI is gone: the gateway carries on
+                    Err(TrySendError::Full(_) | TrySendError::Disconnected(_)) => {
+                        counters.dropped.fetch_add(1, Ordering::Relaxed)
+                    }
+                };
+            }
+        })
+        .expect("'sensors/#' is a valid filter");
+    gateway
+        .route("alerts/#", move |delivery| {
+            if let Some(change) = parse_alert(delivery) {
+                let _ = alerts.send(change);
+            }
+        })
+        .expect("'alerts/#' is a valid filter");
+}
+
+impl Feed {
+    // Starts the gateway loop on a thread. `capacity` bounds the samples
+    // the UI may fall behind by before they are dropped.
+    pub fn start(config: Config, capacity: usize) -> io::Result<Feed> {
+        let (sample_tx, samples) = mpsc::sync_channel(capacity);
+        let (alert_tx, alerts) = mpsc::channel();
+        let (ready_tx, ready) = mpsc::channel();
+        let counters = Arc::new(Counters::default());
+        let stop = Arc::new(AtomicBool::new(false));
+
+        // The gateway is built on its thread: its route handlers are not
+        // `Send`, so it has to stay where it was created
+        let handle = {
+            let counters = Arc::clone(&counters);
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || {
+                let interval = Duration::from_millis(config.gateway.poll_interval_ms);
+                let mut gateway = match Gateway::new(config) {
+                    Ok((gateway, _)) => gateway,
+                    Err(e) => {
+                        let kind = e.kind();
+                        let _ = ready_tx.send(Err(e));
+                        return Err(io::Error::new(kind, "gateway failed to start"));
+                    }
+                };
+                attach(&mut gateway, sample_tx, alert_tx, &counters);
+                let _ = ready_tx.send(Ok(gateway.status()));
+                while !stop.load(Ordering::Relaxed) {
+                    let started = Instant::now();
+                    gateway.tick()?;
+                    thread::sleep(interval.saturating_sub(started.elapsed()));
+                }
+                gateway.shutdown()
+            })
+        };
+
+        let status = match ready.recv() {
+            Ok(result) => result?,
+            Err(_) => return Err(io::Error::other("gateway thread exited")),
+        };
+        Ok(Feed {
+            samples,
+            alerts,
+            capacity,
+            status,
+            counters,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn status(&self) -> Status {
+        self.status.lock().unwrap().clone()
+    }
+
+    // Samples put in the channel, and samples dropped because it was full
+    pub fn sent(&self) -> u64 {
+        self.counters.sent.load(Ordering::Relaxed)
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.counters.dropped.load(Ordering::Relaxed)
+    }
+
+    // False once the loop has ended, e.g. because a tick failed
+    pub fn is_running(&self) -> bool {
+        self.handle.as_ref().is_some_and(|h| !h.is_finished())
+    }
+
+    // Stops the loop and returns the gateway's final status. The channels
+    // stay open, so what was sent before the stop can still be received.
+    pub fn stop(&mut self) -> io::Result<Status> {
+        self.stop.store(true, Ordering::Relaxed);
+        match self.handle.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(_)) => Err(io::Error::other("gateway thread panicked")),
+            None => Err(io::Error::other("gateway already stopped")),
+        }
+    }
+}
+
+impl Drop for Feed {
+    // Dropped without `stop`: let the thread finish on its own
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
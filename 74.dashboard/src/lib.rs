@@ -0,0 +1,19 @@
+// Live terminal dashboard for the gateway
+//
+// The gateway from 16.gateway runs on a background thread exactly as it
+// does in its own binary. The dashboard attaches to it the way any
+// in-process consumer would, through `TopicRouter` routes and the shared
+// `Status`, and forwards what it sees to the UI thread over channels:
+//
+//   Gateway ──routes──▶ channels ──pull()──▶ App ──draw()──▶ terminal
+//   (feed.rs)                                (app.rs)        (ui.rs)
+//
+// The UI thread owns its model and redraws at a fixed rate, however fast
+// or slow the data arrives.
+
+pub mod app;
+pub mod feed;
+pub mod ui;
+
+pub use app::{AlertState, App, Queue, Series};
+pub use feed::{AlertChange, Feed, Sample};
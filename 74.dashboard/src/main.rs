@@ -0,0 +1,208 @@
+use dashboard::{ui, App, Feed};
+use gateway::status::Status;
+use gateway::Config;
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{DefaultTerminal, Terminal};
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Redraw rate, and how often the channels are emptied
+const FRAME: Duration = Duration::from_millis(100);
+// About three seconds of samples from three nodes at 10 Hz
+const CHANNEL_CAPACITY: usize = 256;
+
+const USAGE: &str = "usage: dashboard [--config FILE] [--headless]
+
+  --config FILE   gateway configuration, as for the gateway binary
+  --headless      run the scripted examples instead of the live view
+                  (also what happens when stdout is not a terminal)";
+
+fn main() -> ExitCode {
+    let mut config_path = None;
+    let mut headless = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--headless" => headless = true,
+            "-c" | "--config" => match args.next() {
+                Some(path) => config_path = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("{}", USAGE);
+                    return ExitCode::from(2);
+                }
+            },
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    if headless || !io::stdout().is_terminal() {
+        examples();
+        return ExitCode::SUCCESS;
+    }
+    let config = match Config::load(config_path.as_deref()) {
+        Ok((config, _)) => config,
+        Err(e) => {
+            eprintln!("dashboard: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    match live(config) {
+        Ok(status) => {
+            println!(
+                "{}: {} polls, {} readings, {} alert(s) raised",
+                status.gateway_id, status.polls, status.readings, status.alerts_raised
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("dashboard: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn live(config: Config) -> io::Result<Status> {
+    let mut app = App::new(&config, CHANNEL_CAPACITY);
+    let mut feed = Feed::start(config, CHANNEL_CAPACITY)?;
+    // Raw mode and the alternate screen; `restore` undoes both, and the
+    // panic hook `init` installs does it too
+    let mut terminal = ratatui::init();
+    let result = run_ui(&mut terminal, &mut app, &feed);
+    ratatui::restore();
+    let status = feed.stop();
+    result.and(status)
+}
+
+fn run_ui(terminal: &mut DefaultTerminal, app: &mut App, feed: &Feed) -> io::Result<()> {
+    while feed.is_running() {
+        app.pull(feed);
+        terminal.draw(|frame| ui::draw(frame, app))?;
+        // Waiting for a key is also the frame timer
+        if event::poll(FRAME)? {
+            if let Event::Key(key) = event::read()? {
+                let ctrl_c =
+                    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                let quit = matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) || ctrl_c;
+                if key.kind == KeyEventKind::Press && quit {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn demo_config() -> Config {
+    let mut config = Config::default();
+    config.datalog.dir = std::env::temp_dir().join("rust-sys-dashboard-log");
+    config
+}
+
+// Pulls every frame for `duration`
+fn watch(app: &mut App, feed: &Feed, duration: Duration) {
+    let started = Instant::now();
+    while started.elapsed() < duration {
+        app.pull(feed);
+        thread::sleep(FRAME);
+    }
+}
+
+fn examples() {
+    println!("=== Dashboard Examples ===\n");
+
+    // 1. The feed
+    println!(
+        "1. The gateway on a thread, pulled every {:?} for 4.5 s:",
+        FRAME
+    );
+    let config = demo_config();
+    let mut app = App::new(&config, CHANNEL_CAPACITY);
+    let mut feed = Feed::start(config, CHANNEL_CAPACITY).expect("gateway starts");
+    watch(&mut app, &feed, Duration::from_millis(4500));
+    let status = feed.stop().expect("gateway stops cleanly");
+    app.pull(&feed);
+    for ((node, metric), series) in &app.series {
+        println!(
+            "   node {} {:<12} {:>3} samples, latest {:>6.2} (raw {:>6.2})",
+            node,
+            metric,
+            series.count,
+            series.latest().unwrap_or_default(),
+            series.raw
+        );
+    }
+    println!(
+        "   gateway: {} polls, {} readings",
+        status.polls, status.readings
+    );
+    println!("   UI: {} received, {} dropped", app.received, app.dropped);
+
+    // 2. Alert states
+    println!("\n2. Alert states from the rule engine:");
+    for (name, state) in &app.alerts {
+        println!(
+            "   {:<14} {:<7} since {:.1} s, {} transition(s)",
+            name,
+            if state.raised { "raised" } else { "clear" },
+            state.since_ms as f64 / 1000.0,
+            state.transitions
+        );
+    }
+    println!("   gateway active alerts: {:?}", status.active_alerts);
+    println!("   alerts panel raised: {:?}", app.raised());
+    let alerting: Vec<u16> = (0..app.nodes())
+        .filter(|&node| app.alerting(node, "temperature"))
+        .collect();
+    println!("   nodes with temperature alerting: {:?}", alerting);
+
+    // 3. A frame
+    println!("\n3. The same model drawn on a 100x24 test backend:");
+    let mut terminal = Terminal::new(TestBackend::new(100, 24)).expect("test backend");
+    terminal
+        .draw(|frame| ui::draw(frame, &app))
+        .expect("draw to a buffer");
+    let buffer = terminal.backend().buffer();
+    let lines: Vec<String> = (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+    for line in &lines {
+        println!("   {}", line);
+    }
+
+    // 4. A stalled UI
+    println!("\n4. The UI stalls for 4 s with a 32-sample channel:");
+    let config = demo_config();
+    let mut app = App::new(&config, 32);
+    let mut feed = Feed::start(config, 32).expect("gateway starts");
+    thread::sleep(Duration::from_secs(4));
+    app.pull(&feed);
+    println!("   waiting when the UI woke: {}", app.backlog);
+    for queue in app.queues() {
+        match queue.capacity {
+            Some(capacity) => println!("   {:<10} {:>3}/{}", queue.name, queue.depth, capacity),
+            None => println!("   {:<10} off", queue.name),
+        }
+    }
+    let status = feed.stop().expect("gateway stops cleanly");
+    app.pull(&feed);
+    println!(
+        "   gateway: {} polls, {} readings; UI: {} received, {} dropped",
+        status.polls, status.readings, app.received, app.dropped
+    );
+    println!("   still raised: {:?}", app.raised());
+
+    println!("\n=== End of Dashboard Examples ===");
+}
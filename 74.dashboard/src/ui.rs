@@ -0,0 +1,186 @@
+// Rendering the model
+//
+//   ┌ gw-01  up 4.5 s  polls 45 ... ─────────────────────────────────────┐
+//   └────────────────────────────────────────────────────────────────────┘
+//   ┌ node 0 ─────────────────────────────────┐┌ alerts ─────────────────┐
+//   │ temperature   31.20 °C ▁▂▂▃▄▅▆▇█████    ││ ● hot/0     raised 3.3 s│
+//   │ humidity      46.11 %  ▆▇██▇▆▅▄▃▂▁▁     │└─────────────────────────┘
+//   │ battery        3.58 V  █▇▇▆▆▅▅▄▄▃▃▂     │┌ queues ─────────────────┐
+//   └─────────────────────────────────────────┘│ batch      12/20  ━━━━━ │
+//    ...                                       └─────────────────────────┘
+//    q quit   405 samples received, 0 dropped
+//
+// `draw` is a pure function of `App`: it reads the model and writes cells,
+// so the same code renders to the terminal and to a `TestBackend`.
+
+use crate::app::App;
+use gateway::sensors::METRICS;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, LineGauge, List, ListItem, Paragraph, Sparkline};
+use ratatui::Frame;
+
+fn unit(metric: &str) -> &'static str {
+    match metric {
+        "temperature" => "°C",
+        "humidity" => "%",
+        "battery" => "V",
+        _ => "",
+    }
+}
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [header, body, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [sensors, side] =
+        Layout::horizontal([Constraint::Min(40), Constraint::Length(34)]).areas(body);
+    let [alerts, queues] =
+        Layout::vertical([Constraint::Min(4), Constraint::Length(6)]).areas(side);
+
+    draw_header(frame, app, header);
+    draw_sensors(frame, app, sensors);
+    draw_alerts(frame, app, alerts);
+    draw_queues(frame, app, queues);
+
+    let dropped = if app.dropped > 0 {
+        Span::styled(format!("{} dropped", app.dropped), Color::Yellow)
+    } else {
+        Span::raw("0 dropped")
+    };
+    let line = Line::from(vec![
+        Span::styled(" q", Modifier::BOLD),
+        Span::raw(format!(" quit   {} samples received, ", app.received)),
+        dropped,
+    ]);
+    frame.render_widget(Paragraph::new(line).dim(), footer);
+}
+
+fn draw_header(frame: &mut Frame, app: &App, area: Rect) {
+    let status = &app.status;
+    let circuit = if status.uplink_circuit.is_empty() {
+        "off"
+    } else {
+        &status.uplink_circuit
+    };
+    let line = Line::from(vec![
+        Span::styled(format!(" {} ", status.gateway_id), Modifier::BOLD),
+        Span::raw(format!(
+            " up {:.1} s   polls {}   readings {}   alerts raised {}   uplink {}",
+            status.uptime_ms as f64 / 1000.0,
+            status.polls,
+            status.readings,
+            status.alerts_raised,
+            circuit
+        )),
+    ]);
+    frame.render_widget(
+        Paragraph::new(line).block(Block::bordered().title(" gateway ")),
+        area,
+    );
+}
+
+fn draw_sensors(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = METRICS.len() as u16 + 2;
+    let mut constraints: Vec<Constraint> =
+        (0..app.nodes()).map(|_| Constraint::Length(rows)).collect();
+    constraints.push(Constraint::Min(0));
+    let areas = Layout::vertical(constraints).split(area);
+
+    for node in 0..app.nodes() {
+        let block = Block::bordered().title(format!(" node {} ", node));
+        let inner = block.inner(areas[node as usize]);
+        frame.render_widget(block, areas[node as usize]);
+
+        let lines = Layout::vertical(METRICS.map(|_| Constraint::Length(1))).split(inner);
+        for (metric, &line) in METRICS.iter().zip(lines.iter()) {
+            let [label, spark] =
+                Layout::horizontal([Constraint::Length(24), Constraint::Min(0)]).areas(line);
+            let colour = if app.alerting(node, metric) {
+                Color::Red
+            } else {
+                Color::Cyan
+            };
+            let Some(series) = app.series(node, metric) else {
+                frame.render_widget(Paragraph::new(format!(" {:<12}     -", metric)), label);
+                continue;
+            };
+            let value = series.latest().unwrap_or_default();
+            let text = format!(" {:<12}{:>7.2} {:<2}", metric, value, unit(metric));
+            frame.render_widget(Paragraph::new(text).fg(colour), label);
+            let data = series.scaled(spark.width as usize);
+            frame.render_widget(
+                Sparkline::default()
+                    .data(&data)
+                    .max(100)
+                    .style(Style::default().fg(colour)),
+                spark,
+            );
+        }
+    }
+}
+
+fn draw_alerts(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = if app.alerts.is_empty() {
+        vec![ListItem::new(" no alerts yet").dim()]
+    } else {
+        app.alerts
+            .iter()
+            .map(|(name, state)| {
+                let since = state.since_ms as f64 / 1000.0;
+                if state.raised {
+                    ListItem::new(format!(" ● {:<14} raised {:.1} s", name, since))
+                        .fg(Color::Red)
+                        .bold()
+                } else {
+                    ListItem::new(format!(" ○ {:<14} clear  {:.1} s", name, since)).fg(Color::Green)
+                }
+            })
+            .collect()
+    };
+    frame.render_widget(
+        List::new(items).block(Block::bordered().title(" alerts ")),
+        area,
+    );
+}
+
+fn draw_queues(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::bordered().title(" queues ");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let queues = app.queues();
+    let lines = Layout::vertical(queues.map(|_| Constraint::Length(1))).split(inner);
+    for (queue, &line) in queues.iter().zip(lines.iter()) {
+        let Some(capacity) = queue.capacity else {
+            frame.render_widget(
+                Paragraph::new(format!(" {:<10}      off", queue.name)).dim(),
+                line,
+            );
+            continue;
+        };
+        let ratio = (queue.depth as f64 / capacity.max(1) as f64).min(1.0);
+        let colour = if ratio < 0.5 {
+            Color::Green
+        } else if ratio < 0.9 {
+            Color::Yellow
+        } else {
+            Color::Red
+        };
+        frame.render_widget(
+            LineGauge::default()
+                .ratio(ratio)
+                .label(format!(
+                    " {:<10} {:>4}/{:<4}",
+                    queue.name, queue.depth, capacity
+                ))
+                .filled_style(Style::default().fg(colour))
+                .unfilled_style(Style::default().fg(Color::DarkGray)),
+            line,
+        );
+    }
+}
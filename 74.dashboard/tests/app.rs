@@ -0,0 +1,144 @@
+use dashboard::app::HISTORY;
+use dashboard::{AlertChange, AlertState, App, Queue, Sample};
+use gateway::sensors::METRICS;
+use gateway::Config;
+
+fn sample(node: u16, metric: &str, value: f64) -> Sample {
+    Sample {
+        node,
+        metric: metric.to_string(),
+        raw: value + 0.5,
+        value,
+        t: 0,
+    }
+}
+
+fn change(rule: &str, raised: bool, at_ms: u64) -> AlertChange {
+    AlertChange {
+        rule: rule.to_string(),
+        raised,
+        at_ms,
+    }
+}
+
+#[test]
+fn samples_land_in_their_channel() {
+    let mut app = App::new(&Config::default(), 256);
+    assert_eq!(app.nodes(), 3);
+    for node in 0..3 {
+        for (i, metric) in METRICS.iter().enumerate() {
+            app.apply_sample(sample(node, metric, f64::from(node) * 10.0 + i as f64));
+        }
+    }
+    app.apply_sample(sample(1, "humidity", 40.0));
+    assert_eq!(app.received, 10);
+    assert_eq!(app.series.len(), 9);
+    let humidity = app.series(1, "humidity").unwrap();
+    assert_eq!(humidity.count, 2);
+    assert_eq!(humidity.latest(), Some(40.0));
+    assert_eq!(humidity.raw, 40.5);
+    assert!(app.series(3, "humidity").is_none());
+    assert!(app.series(0, "pressure").is_none());
+}
+
+#[test]
+fn a_series_keeps_the_last_history_values() {
+    let mut app = App::new(&Config::default(), 256);
+    for i in 0..HISTORY + 10 {
+        app.apply_sample(sample(0, "battery", i as f64));
+    }
+    let series = app.series(0, "battery").unwrap();
+    assert_eq!(series.values.len(), HISTORY);
+    assert_eq!(series.values.front(), Some(&10.0));
+    assert_eq!(series.count, (HISTORY + 10) as u64);
+}
+
+#[test]
+fn scaled_spans_the_sparkline_between_its_own_min_and_max() {
+    let mut app = App::new(&Config::default(), 256);
+    for value in [5.0, 1.0, 2.0, 3.0] {
+        app.apply_sample(sample(0, "temperature", value));
+    }
+    let series = app.series(0, "temperature").unwrap();
+    // The 5.0 falls out of the window, so 1..=3 is the whole range
+    assert_eq!(series.scaled(3), [13, 56, 100]);
+    assert_eq!(series.scaled(10), [100, 13, 34, 56]);
+    app.apply_sample(sample(0, "humidity", 40.0));
+    app.apply_sample(sample(0, "humidity", 40.0));
+    // A flat line sits in the middle
+    assert_eq!(app.series(0, "humidity").unwrap().scaled(10), [50, 50]);
+}
+
+#[test]
+fn alert_changes_count_transitions() {
+    let mut app = App::new(&Config::default(), 256);
+    app.apply_alert(change("hot/0", true, 3300));
+    app.apply_alert(change("battery-low/2", true, 4000));
+    app.apply_alert(change("battery-low/2", false, 4500));
+    assert_eq!(
+        app.alerts["hot/0"],
+        AlertState {
+            raised: true,
+            since_ms: 3300,
+            transitions: 1
+        }
+    );
+    assert_eq!(
+        app.alerts["battery-low/2"],
+        AlertState {
+            raised: false,
+            since_ms: 4500,
+            transitions: 2
+        }
+    );
+    assert_eq!(app.raised(), ["hot/0"]);
+}
+
+#[test]
+fn alerting_marks_the_metric_the_rule_watches_on_its_node() {
+    let mut app = App::new(&Config::default(), 256);
+    app.apply_alert(change("hot/0", true, 3300));
+    for node in 0..3 {
+        assert_eq!(app.alerting(node, "temperature"), node == 0, "{}", node);
+        assert!(!app.alerting(node, "humidity"));
+    }
+    app.apply_alert(change("battery-low/2", true, 4000));
+    assert!(app.alerting(2, "battery"));
+    app.apply_alert(change("hot/0", false, 5000));
+    assert!(!app.alerting(0, "temperature"));
+    // Unknown rules and malformed names mark nothing
+    app.apply_alert(change("flood/1", true, 5000));
+    app.apply_alert(change("hot", true, 5000));
+    assert!(METRICS.iter().all(|m| !app.alerting(1, m)));
+}
+
+#[test]
+fn queues_switched_off_in_the_config_have_no_capacity() {
+    let mut config = Config::default();
+    let app = App::new(&config, 32);
+    let names = app.queues().map(|q| q.name);
+    assert_eq!(names, ["ui channel", "batch", "uplink", "history"]);
+    assert_eq!(
+        app.queues()[..2],
+        [
+            Queue {
+                name: "ui channel",
+                depth: 0,
+                capacity: Some(32)
+            },
+            Queue {
+                name: "batch",
+                depth: 0,
+                capacity: Some(20)
+            },
+        ]
+    );
+    assert_eq!(app.queues()[2].capacity, None);
+    assert_eq!(app.queues()[3].capacity, None);
+
+    config.uplink.addr = "127.0.0.1:1883".to_string();
+    config.storage.backend = "sqlite".to_string();
+    let app = App::new(&config, 32);
+    assert_eq!(app.queues()[2].capacity, Some(50));
+    assert_eq!(app.queues()[3].capacity, Some(60));
+}
@@ -0,0 +1,73 @@
+// The gateway on its thread at its real 100 ms poll interval, so these
+// take a second or two each
+use dashboard::{App, Feed};
+use gateway::Config;
+use std::thread;
+use std::time::Duration;
+
+fn config(name: &str) -> Config {
+    let mut config = Config::default();
+    config.datalog.dir =
+        std::env::temp_dir().join(format!("dashboard-{}-{}", name, std::process::id()));
+    config
+}
+
+fn sorted(names: Vec<&str>) -> Vec<String> {
+    let mut names: Vec<String> = names.into_iter().map(String::from).collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn a_ui_that_keeps_up_sees_every_sample() {
+    let config = config("keeps-up");
+    let dir = config.datalog.dir.clone();
+    let mut app = App::new(&config, 256);
+    let mut feed = Feed::start(config, 256).unwrap();
+    assert_eq!(feed.capacity(), 256);
+    for _ in 0..15 {
+        app.pull(&feed);
+        thread::sleep(Duration::from_millis(100));
+    }
+    let status = feed.stop().unwrap();
+    assert!(!feed.is_running());
+    app.pull(&feed);
+
+    assert!(status.polls > 0);
+    assert_eq!(app.series.len(), 9);
+    assert!(app.series.values().all(|s| s.count == status.polls));
+    assert_eq!(app.dropped, 0);
+    assert_eq!(app.received, status.readings);
+    assert_eq!(app.status.polls, status.polls);
+    assert_eq!(
+        sorted(app.raised()),
+        sorted(status.active_alerts.iter().map(String::as_str).collect())
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn a_stalled_ui_drops_samples_but_no_alert_changes() {
+    let config = config("stalled");
+    let dir = config.datalog.dir.clone();
+    let mut app = App::new(&config, 32);
+    let mut feed = Feed::start(config, 32).unwrap();
+    // Nine readings a poll fill 32 slots in four polls
+    thread::sleep(Duration::from_millis(1500));
+    app.pull(&feed);
+    assert_eq!(app.backlog, 32);
+    assert_eq!(app.queues()[0].depth, 32);
+
+    let status = feed.stop().unwrap();
+    app.pull(&feed);
+    assert!(app.dropped > 0);
+    assert_eq!(app.received + app.dropped, status.readings);
+    // The gateway kept polling at its own rate meanwhile
+    assert!(status.polls >= 10, "{}", status.polls);
+    assert_eq!(
+        sorted(app.raised()),
+        sorted(status.active_alerts.iter().map(String::as_str).collect())
+    );
+    assert!(feed.stop().is_err());
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,88 @@
+use dashboard::{ui, AlertChange, App, Sample};
+use gateway::sensors::METRICS;
+use gateway::Config;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+use ratatui::Terminal;
+
+// Three nodes with a rising line on every channel, and node 0 hot
+fn app() -> App {
+    let mut app = App::new(&Config::default(), 256);
+    for step in 0..40 {
+        for node in 0..3 {
+            for metric in METRICS {
+                app.apply_sample(Sample {
+                    node,
+                    metric: metric.to_string(),
+                    raw: f64::from(step),
+                    value: f64::from(step),
+                    t: 0,
+                });
+            }
+        }
+    }
+    app.apply_alert(AlertChange {
+        rule: "hot/0".to_string(),
+        raised: true,
+        at_ms: 3300,
+    });
+    app
+}
+
+fn render(app: &App) -> (Buffer, Vec<String>) {
+    let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+    terminal.draw(|frame| ui::draw(frame, app)).unwrap();
+    let buffer = terminal.backend().buffer().clone();
+    let lines = (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .collect();
+    (buffer, lines)
+}
+
+#[test]
+fn every_node_and_metric_is_on_screen() {
+    let (_, lines) = render(&app());
+    let text = lines.join("\n");
+    for node in 0..3 {
+        assert!(text.contains(&format!("node {}", node)), "{}", text);
+    }
+    for metric in METRICS {
+        assert!(text.contains(metric), "{}", metric);
+    }
+    // One sparkline per channel, each reaching the top at its newest value
+    assert!(lines.iter().filter(|l| l.contains('█')).count() >= 9);
+    assert!(text.contains("360 samples received, 0 dropped"), "{}", text);
+}
+
+#[test]
+fn a_raised_alert_is_drawn_in_red() {
+    let (buffer, lines) = render(&app());
+    let y = lines.iter().position(|l| l.contains("● hot/0")).unwrap();
+    let x = lines[y].chars().position(|c| c == '●').unwrap();
+    assert_eq!(buffer[(x as u16, y as u16)].fg, Color::Red);
+}
+
+#[test]
+fn disabled_queues_show_as_off() {
+    let (_, lines) = render(&app());
+    assert!(lines.iter().any(|l| l.contains("batch")));
+    assert!(lines
+        .iter()
+        .any(|l| l.contains("uplink") && l.contains("off")));
+    assert!(lines
+        .iter()
+        .any(|l| l.contains("history") && l.contains("off")));
+}
+
+#[test]
+fn drops_are_shown_in_the_footer() {
+    let mut app = app();
+    app.dropped = 7;
+    let (_, lines) = render(&app);
+    assert!(lines.last().unwrap().contains("7 dropped"));
+}
@@ -0,0 +1,15 @@
+// Trees in an arena, indexed instead of pointed to
+//
+// - `tree`: `Tree<T>`, nodes in one Vec linked by generational `NodeId`s,
+//   with insert, subtree removal, moves, ancestors and descendants
+// - `topology`: a gateway's device tree (gateway -> hubs -> sensors)
+//   built on `Tree`
+// - `rc_tree`: the same structure as `Rc<RefCell<_>>` nodes with `Weak`
+//   parent links, for the comparison in the demo
+
+pub mod rc_tree;
+pub mod topology;
+pub mod tree;
+
+pub use topology::{Device, Kind, Topology};
+pub use tree::{Ancestors, Descendants, NodeId, Tree, TreeError};
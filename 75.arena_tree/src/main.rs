@@ -0,0 +1,223 @@
+use arena_tree::rc_tree;
+use arena_tree::{Device, Kind, NodeId, Topology, Tree};
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use std::time::Instant;
+
+fn names(topology: &Topology, ids: &[NodeId]) -> Vec<String> {
+    ids.iter()
+        .map(|&id| topology.name(id).to_string())
+        .collect()
+}
+
+// A node whose parent link is strong, the mistake `rc_tree` avoids
+struct Leaky {
+    parent: RefCell<Option<Rc<Leaky>>>,
+    children: RefCell<Vec<Rc<Leaky>>>,
+}
+
+fn main() {
+    println!("=== Arena Tree Examples ===\n");
+
+    // 1. Building
+    println!("1. A gateway's device topology:");
+    let (mut topo, gw) = Topology::new("gw-01");
+    let ble = topo.add(gw, Kind::Hub, "ble-1").unwrap();
+    for name in ["temp-1", "temp-2", "door-1"] {
+        topo.add(ble, Kind::Sensor, name).unwrap();
+    }
+    let lora = topo.add(gw, Kind::Hub, "lora-1").unwrap();
+    for name in ["soil-1", "soil-2", "rain-1"] {
+        topo.add(lora, Kind::Sensor, name).unwrap();
+    }
+    let can = topo.add(gw, Kind::Hub, "can-1").unwrap();
+    let flow = topo.add(can, Kind::Sensor, "flow-1").unwrap();
+    // Wired straight into the gateway
+    topo.add(gw, Kind::Sensor, "power-1").unwrap();
+    for line in topo.render() {
+        println!("   {}", line);
+    }
+    println!(
+        "   {} devices in {} slots",
+        topo.tree.len(),
+        topo.tree.slots()
+    );
+
+    // 2. Queries
+    println!("\n2. Queries:");
+    let soil2 = topo.find("soil-2").unwrap();
+    let temp1 = topo.find("temp-1").unwrap();
+    let door1 = topo.find("door-1").unwrap();
+    let soil1 = topo.find("soil-1").unwrap();
+    println!("   route to soil-2: {}", topo.route(soil2).join(" -> "));
+    let near = topo.tree.common_ancestor(temp1, door1).unwrap();
+    let far = topo.tree.common_ancestor(temp1, soil1).unwrap();
+    println!(
+        "   common ancestor of temp-1 and door-1: {}, of temp-1 and soil-1: {}",
+        topo.name(near),
+        topo.name(far)
+    );
+    println!(
+        "   sensors under ble-1: {:?}",
+        names(&topo, &topo.sensors_under(ble))
+    );
+    topo.tree.get_mut(lora).unwrap().online = false;
+    let dark: Vec<NodeId> = topo
+        .sensors_under(gw)
+        .into_iter()
+        .filter(|&id| !topo.reachable(id))
+        .collect();
+    println!(
+        "   lora-1 goes offline, unreachable: {:?}",
+        names(&topo, &dark)
+    );
+    topo.tree.get_mut(lora).unwrap().online = true;
+
+    // 3. Removal and slot reuse
+    println!("\n3. Removing can-1 and adding a new hub:");
+    let removed = topo.tree.remove(can).unwrap();
+    let removed: Vec<&str> = removed.iter().map(|d| d.name.as_str()).collect();
+    let left = topo.tree.len();
+    println!("   removed {:?}, {} devices left", removed, left);
+    let can2 = topo.add(gw, Kind::Hub, "can-2").unwrap();
+    println!(
+        "   can-1 was {}, can-2 is {}, flow-1 was {}",
+        can, can2, flow
+    );
+    println!("   get(can-1) = {:?}", topo.tree.get(can).map(|d| &d.name));
+    match topo.tree.insert(can, orphan()) {
+        Ok(id) => println!("   insert under can-1: {}", id),
+        Err(e) => println!("   insert under can-1: {}", e),
+    }
+
+    // 4. Moving
+    println!("\n4. Moving subtrees:");
+    topo.tree.move_to(soil2, ble).unwrap();
+    println!("   soil-2 re-homed: {}", topo.route(soil2).join(" -> "));
+    let cycle = topo.tree.move_to(ble, temp1);
+    let root = topo.tree.move_to(gw, ble);
+    for (what, result) in [("ble-1 under temp-1", cycle), ("gw-01 under ble-1", root)] {
+        match result {
+            Ok(()) => println!("   {}: moved", what),
+            Err(e) => println!("   {}: {}", what, e),
+        }
+    }
+
+    // 5. Against Rc<RefCell<_>>
+    const HUBS: u64 = 200;
+    const SENSORS: u64 = 1000;
+    println!(
+        "\n5. Against Rc<RefCell<_>>, 1 + {} hubs x {} sensors:",
+        HUBS, SENSORS
+    );
+    let started = Instant::now();
+    let mut tree: Tree<u64> = Tree::new();
+    let root = tree.insert_root(0).unwrap();
+    let mut last = root;
+    for h in 0..HUBS {
+        let hub = tree.insert(root, h * 10_000).unwrap();
+        for s in 0..SENSORS {
+            last = tree.insert(hub, h * 10_000 + s + 1).unwrap();
+        }
+    }
+    let arena_build = started.elapsed();
+    let started = Instant::now();
+    let arena_order: Vec<u64> = tree
+        .descendants(root)
+        .map(|id| *tree.get(id).unwrap())
+        .collect();
+    let arena_walk = started.elapsed();
+
+    let started = Instant::now();
+    let rc_root = rc_tree::root(0u64);
+    let mut rc_last = Rc::clone(&rc_root);
+    for h in 0..HUBS {
+        let hub = rc_tree::insert(&rc_root, h * 10_000);
+        for s in 0..SENSORS {
+            rc_last = rc_tree::insert(&hub, h * 10_000 + s + 1);
+        }
+    }
+    let rc_build = started.elapsed();
+    let started = Instant::now();
+    let mut rc_order = Vec::with_capacity(arena_order.len());
+    rc_tree::visit(&rc_root, |&v| rc_order.push(v));
+    let rc_walk = started.elapsed();
+
+    println!(
+        "   {:<14} build {:>9.2?}   walk {:>9.2?}",
+        "arena", arena_build, arena_walk
+    );
+    println!(
+        "   {:<14} build {:>9.2?}   walk {:>9.2?}",
+        "Rc<RefCell<_>>", rc_build, rc_walk
+    );
+    println!(
+        "   node allocations: the arena's one Vec (grown) vs {}",
+        arena_order.len()
+    );
+    let arena_up: Vec<u64> = tree
+        .ancestors(last)
+        .map(|id| *tree.get(id).unwrap())
+        .collect();
+    let rc_up: Vec<u64> = rc_tree::ancestors(&rc_last)
+        .iter()
+        .map(|node| node.borrow().value)
+        .collect();
+    println!(
+        "   above the last sensor: arena {:?}, Rc {:?}",
+        arena_up, rc_up
+    );
+
+    // Weak parent links free the tree; strong ones leak it
+    let weak_leaf = Rc::downgrade(&rc_last);
+    drop(rc_last);
+    drop(rc_root);
+    let parent = Rc::new(Leaky {
+        parent: RefCell::new(None),
+        children: RefCell::new(Vec::new()),
+    });
+    let child = Rc::new(Leaky {
+        parent: RefCell::new(Some(Rc::clone(&parent))),
+        children: RefCell::new(Vec::new()),
+    });
+    parent.children.borrow_mut().push(Rc::clone(&child));
+    let weak_parent: Weak<Leaky> = Rc::downgrade(&parent);
+    drop(child);
+    drop(parent);
+    let leaked = weak_parent.upgrade();
+    println!(
+        "   after dropping the roots: Weak-parent leaf {}, strong-parent pair {}",
+        if weak_leaf.upgrade().is_none() {
+            "freed"
+        } else {
+            "alive"
+        },
+        if leaked.is_some() { "leaked" } else { "freed" }
+    );
+    // Break the cycle so the demo itself doesn't leak
+    if let Some(parent) = leaked {
+        for child in parent.children.borrow_mut().drain(..) {
+            child.parent.borrow_mut().take();
+        }
+    }
+
+    // Borrow conflicts: a compile error in the arena, a run-time one here
+    let node = rc_tree::root(1u64);
+    let reading = node.borrow();
+    let conflict = node.try_borrow_mut().is_err();
+    drop(reading);
+    println!(
+        "   borrow_mut while borrowed: {}",
+        if conflict {
+            "BorrowMutError"
+        } else {
+            "allowed"
+        }
+    );
+
+    println!("\n=== End of Arena Tree Examples ===");
+}
+
+fn orphan() -> Device {
+    Device::new(Kind::Sensor, "orphan")
+}
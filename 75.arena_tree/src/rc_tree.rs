@@ -0,0 +1,74 @@
+// The same tree with reference counting, for comparison
+//
+//   Rc<RefCell<RcNode>> ──children──▶ Rc<RefCell<RcNode>>
+//           ▲                                 │
+//           └────────── parent: Weak ─────────┘
+//
+// Each node is its own heap allocation. Children are owned through `Rc`,
+// and the parent link has to be a `Weak`, since two strong links would
+// form a cycle that is never freed. Mutating anything needs
+// `borrow_mut()`, which is checked at run time and panics if the node is
+// already borrowed.
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+pub type Link<T> = Rc<RefCell<RcNode<T>>>;
+
+#[derive(Debug)]
+pub struct RcNode<T> {
+    pub value: T,
+    pub parent: Weak<RefCell<RcNode<T>>>,
+    pub children: Vec<Link<T>>,
+}
+
+pub fn root<T>(value: T) -> Link<T> {
+    Rc::new(RefCell::new(RcNode {
+        value,
+        parent: Weak::new(),
+        children: Vec::new(),
+    }))
+}
+
+pub fn insert<T>(parent: &Link<T>, value: T) -> Link<T> {
+    let child = Rc::new(RefCell::new(RcNode {
+        value,
+        parent: Rc::downgrade(parent),
+        children: Vec::new(),
+    }));
+    parent.borrow_mut().children.push(Rc::clone(&child));
+    child
+}
+
+// Detaches `node` from its parent; it is freed when the last `Rc` to it
+// is dropped
+pub fn detach<T>(node: &Link<T>) {
+    let parent = node.borrow().parent.upgrade();
+    if let Some(parent) = parent {
+        parent
+            .borrow_mut()
+            .children
+            .retain(|child| !Rc::ptr_eq(child, node));
+    }
+    node.borrow_mut().parent = Weak::new();
+}
+
+pub fn ancestors<T>(node: &Link<T>) -> Vec<Link<T>> {
+    let mut out = Vec::new();
+    let mut next = node.borrow().parent.upgrade();
+    while let Some(parent) = next {
+        next = parent.borrow().parent.upgrade();
+        out.push(parent);
+    }
+    out
+}
+
+// Depth first, children in order, like `Tree::descendants`
+pub fn visit<T>(node: &Link<T>, mut f: impl FnMut(&T)) {
+    let mut stack = vec![Rc::clone(node)];
+    while let Some(node) = stack.pop() {
+        let node = node.borrow();
+        f(&node.value);
+        stack.extend(node.children.iter().rev().cloned());
+    }
+}
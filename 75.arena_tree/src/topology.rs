@@ -0,0 +1,121 @@
+// Device topology: gateway -> hubs -> sensors
+//
+// What a gateway knows about the devices behind it. Hubs are radio
+// concentrators (BLE, LoRa) or wired buses; sensors hang off a hub or,
+// for wired ones, off the gateway directly. The tree answers the questions
+// a gateway asks about that topology: which path does a message to this
+// sensor take, what goes dark when this hub does, and which sensors sit on
+// the same hub.
+
+use crate::tree::{NodeId, Tree, TreeError};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Gateway,
+    Hub,
+    Sensor,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Device {
+    pub kind: Kind,
+    pub name: String,
+    pub online: bool,
+}
+
+impl Device {
+    pub fn new(kind: Kind, name: &str) -> Device {
+        Device {
+            kind,
+            name: name.to_string(),
+            online: true,
+        }
+    }
+}
+
+impl fmt::Display for Device {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            Kind::Gateway => "gateway",
+            Kind::Hub => "hub",
+            Kind::Sensor => "sensor",
+        };
+        write!(f, "{} {}", kind, self.name)?;
+        if !self.online {
+            write!(f, " (offline)")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Topology {
+    pub tree: Tree<Device>,
+}
+
+impl Topology {
+    pub fn new(gateway: &str) -> (Topology, NodeId) {
+        let mut tree = Tree::new();
+        let root = tree
+            .insert_root(Device::new(Kind::Gateway, gateway))
+            .expect("a new tree has no root");
+        (Topology { tree }, root)
+    }
+
+    pub fn add(&mut self, parent: NodeId, kind: Kind, name: &str) -> Result<NodeId, TreeError> {
+        self.tree.insert(parent, Device::new(kind, name))
+    }
+
+    pub fn find(&self, name: &str) -> Option<NodeId> {
+        let root = self.tree.root()?;
+        self.tree
+            .descendants(root)
+            .find(|&id| self.tree.get(id).is_some_and(|d| d.name == name))
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        self.tree.get(id).map_or("?", |d| d.name.as_str())
+    }
+
+    // Names from the gateway down to `id`, the hops a downlink takes
+    pub fn route(&self, id: NodeId) -> Vec<&str> {
+        let mut hops: Vec<&str> = self.tree.ancestors(id).map(|a| self.name(a)).collect();
+        hops.reverse();
+        if self.tree.contains(id) {
+            hops.push(self.name(id));
+        }
+        hops
+    }
+
+    // A device is reachable when it and every device above it are online
+    pub fn reachable(&self, id: NodeId) -> bool {
+        self.tree.get(id).is_some_and(|d| d.online)
+            && self
+                .tree
+                .ancestors(id)
+                .all(|a| self.tree.get(a).is_some_and(|d| d.online))
+    }
+
+    // Sensors at or below `id`
+    pub fn sensors_under(&self, id: NodeId) -> Vec<NodeId> {
+        self.tree
+            .descendants(id)
+            .filter(|&d| self.tree.get(d).is_some_and(|d| d.kind == Kind::Sensor))
+            .collect()
+    }
+
+    // One line per device, indented by depth
+    pub fn render(&self) -> Vec<String> {
+        let Some(root) = self.tree.root() else {
+            return Vec::new();
+        };
+        self.tree
+            .descendants(root)
+            .map(|id| {
+                let device = self.tree.get(id).expect("descendants are live");
+                format!("{}{}", "  ".repeat(self.tree.depth(id)), device)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,322 @@
+// A tree stored in one Vec, linked by indices
+//
+//   nodes: [ 0: gw-01   | 1: hub-a   | 2: temp-1  | 3: (free) | 4: hub-b  ]
+//              parent -     parent 0     parent 1               parent 0
+//              [1, 4]       [2]          []                     []
+//   free:  [3]
+//
+// A node refers to its parent and children by `NodeId`, not by pointer, so
+// there is one owner (the Vec) and no `Rc`, `Weak` or `RefCell`. Parent
+// links cost nothing extra and cannot form a leak.
+//
+// A removed node's slot goes on the free list and is reused by a later
+// insert. Each slot has a generation that is bumped on removal, and a
+// `NodeId` carries the generation it was created with, so an id kept from
+// before the removal no longer finds anything instead of finding the new
+// occupant.
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId {
+    index: u32,
+    generation: u32,
+}
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}v{}", self.index, self.generation)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeError {
+    // The node was removed (or never belonged to this tree)
+    Stale(NodeId),
+    RootExists,
+    // Moving `node` under `parent` would make it its own ancestor
+    WouldCycle { node: NodeId, parent: NodeId },
+    // The root has no parent to move away from
+    IsRoot(NodeId),
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TreeError::Stale(id) => write!(f, "node {} no longer exists", id),
+            TreeError::RootExists => write!(f, "the tree already has a root"),
+            TreeError::WouldCycle { node, parent } => {
+                write!(f, "{} is inside the subtree of {}", parent, node)
+            }
+            TreeError::IsRoot(id) => write!(f, "{} is the root", id),
+        }
+    }
+}
+
+impl Error for TreeError {}
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    value: T,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+#[derive(Debug, Clone)]
+struct Slot<T> {
+    generation: u32,
+    node: Option<Node<T>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Tree<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    root: Option<NodeId>,
+    len: usize,
+}
+
+impl<T> Default for Tree<T> {
+    fn default() -> Self {
+        Tree::new()
+    }
+}
+
+impl<T> Tree<T> {
+    pub fn new() -> Tree<T> {
+        Tree {
+            slots: Vec::new(),
+            free: Vec::new(),
+            root: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Slots allocated, live or free
+    pub fn slots(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn root(&self) -> Option<NodeId> {
+        self.root
+    }
+
+    fn node(&self, id: NodeId) -> Result<&Node<T>, TreeError> {
+        self.slots
+            .get(id.index())
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.node.as_ref())
+            .ok_or(TreeError::Stale(id))
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> Result<&mut Node<T>, TreeError> {
+        self.slots
+            .get_mut(id.index())
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.node.as_mut())
+            .ok_or(TreeError::Stale(id))
+    }
+
+    fn alloc(&mut self, node: Node<T>) -> NodeId {
+        self.len += 1;
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.node = Some(node);
+                NodeId {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    node: Some(node),
+                });
+                NodeId {
+                    index: self.slots.len() as u32 - 1,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    pub fn insert_root(&mut self, value: T) -> Result<NodeId, TreeError> {
+        if self.root.is_some() {
+            return Err(TreeError::RootExists);
+        }
+        let id = self.alloc(Node {
+            value,
+            parent: None,
+            children: Vec::new(),
+        });
+        self.root = Some(id);
+        Ok(id)
+    }
+
+    // Adds `value` as the last child of `parent`
+    pub fn insert(&mut self, parent: NodeId, value: T) -> Result<NodeId, TreeError> {
+        self.node(parent)?;
+        let id = self.alloc(Node {
+            value,
+            parent: Some(parent),
+            children: Vec::new(),
+        });
+        self.node_mut(parent)?.children.push(id);
+        Ok(id)
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.node(id).is_ok()
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        self.node(id).ok().map(|node| &node.value)
+    }
+
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut T> {
+        self.node_mut(id).ok().map(|node| &mut node.value)
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok().and_then(|node| node.parent)
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        self.node(id).map_or(&[], |node| &node.children)
+    }
+
+    // Removes `id` and everything below it; returns their values in
+    // pre-order, `id`'s first
+    pub fn remove(&mut self, id: NodeId) -> Result<Vec<T>, TreeError> {
+        let parent = self.node(id)?.parent;
+        match parent {
+            Some(parent) => self.node_mut(parent)?.children.retain(|&c| c != id),
+            None => self.root = None,
+        }
+        let order: Vec<NodeId> = self.descendants(id).collect();
+        let mut values = Vec::with_capacity(order.len());
+        for node in order {
+            let slot = &mut self.slots[node.index()];
+            if let Some(node) = slot.node.take() {
+                values.push(node.value);
+            }
+            slot.generation = slot.generation.wrapping_add(1);
+            self.free.push(node.index);
+            self.len -= 1;
+        }
+        Ok(values)
+    }
+
+    // Makes `id` the last child of `new_parent`, taking its subtree along
+    pub fn move_to(&mut self, id: NodeId, new_parent: NodeId) -> Result<(), TreeError> {
+        let old_parent = self.node(id)?.parent.ok_or(TreeError::IsRoot(id))?;
+        self.node(new_parent)?;
+        if new_parent == id || self.ancestors(new_parent).any(|a| a == id) {
+            return Err(TreeError::WouldCycle {
+                node: id,
+                parent: new_parent,
+            });
+        }
+        self.node_mut(old_parent)?.children.retain(|&c| c != id);
+        self.node_mut(new_parent)?.children.push(id);
+        self.node_mut(id)?.parent = Some(new_parent);
+        Ok(())
+    }
+
+    // Parent, grandparent, ... up to the root; not `id` itself
+    pub fn ancestors(&self, id: NodeId) -> Ancestors<'_, T> {
+        Ancestors {
+            tree: self,
+            next: self.parent(id),
+        }
+    }
+
+    // `id` and everything below it, depth first, children in order
+    pub fn descendants(&self, id: NodeId) -> Descendants<'_, T> {
+        Descendants {
+            tree: self,
+            stack: if self.contains(id) {
+                vec![id]
+            } else {
+                Vec::new()
+            },
+        }
+    }
+
+    // Edges between `id` and the root
+    pub fn depth(&self, id: NodeId) -> usize {
+        self.ancestors(id).count()
+    }
+
+    // The deepest node that has both `a` and `b` below it (or is one of them)
+    pub fn common_ancestor(&self, a: NodeId, b: NodeId) -> Option<NodeId> {
+        if !self.contains(a) || !self.contains(b) {
+            return None;
+        }
+        let (mut a, mut b) = (a, b);
+        let (mut da, mut db) = (self.depth(a), self.depth(b));
+        // Walk the deeper one up to the same depth, then both together
+        while da > db {
+            a = self.parent(a)?;
+            da -= 1;
+        }
+        while db > da {
+            b = self.parent(b)?;
+            db -= 1;
+        }
+        while a != b {
+            a = self.parent(a)?;
+            b = self.parent(b)?;
+        }
+        Some(a)
+    }
+}
+
+pub struct Ancestors<'a, T> {
+    tree: &'a Tree<T>,
+    next: Option<NodeId>,
+}
+
+impl<T> Iterator for Ancestors<'_, T> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let id = self.next?;
+        self.next = self.tree.parent(id);
+        Some(id)
+    }
+}
+
+// An explicit stack rather than recursion, so a deep chain can't overflow
+// the thread's stack
+pub struct Descendants<'a, T> {
+    tree: &'a Tree<T>,
+    stack: Vec<NodeId>,
+}
+
+impl<T> Iterator for Descendants<'_, T> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let id = self.stack.pop()?;
+        self.stack
+            .extend(self.tree.children(id).iter().rev().copied());
+        Some(id)
+    }
+}
@@ -0,0 +1,103 @@
+use arena_tree::rc_tree;
+use arena_tree::Tree;
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+#[test]
+fn both_trees_walk_in_the_same_order() {
+    let mut tree = Tree::new();
+    let root = tree.insert_root(0u64).unwrap();
+    let rc_root = rc_tree::root(0u64);
+    let (mut last, mut rc_last) = (root, Rc::clone(&rc_root));
+    for h in 1..=20 {
+        let hub = tree.insert(root, h * 100).unwrap();
+        let rc_hub = rc_tree::insert(&rc_root, h * 100);
+        for s in 1..=50 {
+            last = tree.insert(hub, h * 100 + s).unwrap();
+            rc_last = rc_tree::insert(&rc_hub, h * 100 + s);
+        }
+    }
+    let arena: Vec<u64> = tree
+        .descendants(root)
+        .map(|id| *tree.get(id).unwrap())
+        .collect();
+    let mut rc = Vec::new();
+    rc_tree::visit(&rc_root, |&v| rc.push(v));
+    assert_eq!(arena.len(), 1021);
+    assert_eq!(arena, rc);
+
+    let ancestors: Vec<u64> = rc_tree::ancestors(&rc_last)
+        .iter()
+        .map(|n| n.borrow().value)
+        .collect();
+    assert_eq!(ancestors, [2000, 0]);
+    assert_eq!(
+        tree.ancestors(last)
+            .map(|id| *tree.get(id).unwrap())
+            .collect::<Vec<_>>(),
+        ancestors
+    );
+}
+
+#[test]
+fn detach_frees_the_subtree_once_unreferenced() {
+    let root = rc_tree::root(0);
+    let hub = rc_tree::insert(&root, 1);
+    let leaf = Rc::downgrade(&rc_tree::insert(&hub, 2));
+    rc_tree::detach(&hub);
+    assert!(root.borrow().children.is_empty());
+    assert!(rc_tree::ancestors(&hub).is_empty());
+    assert!(leaf.upgrade().is_some());
+    drop(hub);
+    assert!(leaf.upgrade().is_none());
+}
+
+#[test]
+fn weak_parents_let_the_tree_go() {
+    let root = rc_tree::root(0);
+    let hub = rc_tree::insert(&root, 1);
+    let leaf = Rc::downgrade(&rc_tree::insert(&hub, 2));
+    drop(hub);
+    assert!(leaf.upgrade().is_some());
+    drop(root);
+    assert!(leaf.upgrade().is_none());
+}
+
+// A node whose parent link is strong, the mistake `rc_tree` avoids
+struct Leaky {
+    parent: RefCell<Option<Rc<Leaky>>>,
+    children: RefCell<Vec<Rc<Leaky>>>,
+}
+
+#[test]
+fn strong_parents_leak_the_cycle() {
+    let parent = Rc::new(Leaky {
+        parent: RefCell::new(None),
+        children: RefCell::new(Vec::new()),
+    });
+    let child = Rc::new(Leaky {
+        parent: RefCell::new(Some(Rc::clone(&parent))),
+        children: RefCell::new(Vec::new()),
+    });
+    parent.children.borrow_mut().push(Rc::clone(&child));
+    let weak: Weak<Leaky> = Rc::downgrade(&parent);
+    drop(child);
+    drop(parent);
+    let leaked = weak.upgrade().expect("the cycle keeps the parent alive");
+    // Break it by hand so the test itself doesn't leak
+    for child in leaked.children.borrow_mut().drain(..) {
+        child.parent.borrow_mut().take();
+    }
+    drop(leaked);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn refcell_catches_the_conflict_at_run_time() {
+    let node = rc_tree::root(1u64);
+    let reading = node.borrow();
+    assert!(node.try_borrow_mut().is_err());
+    drop(reading);
+    node.borrow_mut().value = 2;
+    assert_eq!(node.borrow().value, 2);
+}
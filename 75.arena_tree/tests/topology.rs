@@ -0,0 +1,162 @@
+use arena_tree::{Device, Kind, NodeId, Topology, TreeError};
+
+struct Fleet {
+    topo: Topology,
+    gw: NodeId,
+    ble: NodeId,
+    lora: NodeId,
+    can: NodeId,
+}
+
+// The demo's gateway: three radios, seven sensors and one wired sensor
+fn fleet() -> Fleet {
+    let (mut topo, gw) = Topology::new("gw-01");
+    let ble = topo.add(gw, Kind::Hub, "ble-1").unwrap();
+    for name in ["temp-1", "temp-2", "door-1"] {
+        topo.add(ble, Kind::Sensor, name).unwrap();
+    }
+    let lora = topo.add(gw, Kind::Hub, "lora-1").unwrap();
+    for name in ["soil-1", "soil-2", "rain-1"] {
+        topo.add(lora, Kind::Sensor, name).unwrap();
+    }
+    let can = topo.add(gw, Kind::Hub, "can-1").unwrap();
+    topo.add(can, Kind::Sensor, "flow-1").unwrap();
+    topo.add(gw, Kind::Sensor, "power-1").unwrap();
+    Fleet {
+        topo,
+        gw,
+        ble,
+        lora,
+        can,
+    }
+}
+
+fn names(topo: &Topology, ids: &[NodeId]) -> Vec<String> {
+    ids.iter().map(|&id| topo.name(id).to_string()).collect()
+}
+
+#[test]
+fn render_indents_by_depth_in_pre_order() {
+    let Fleet { topo, gw, .. } = fleet();
+    assert_eq!(topo.tree.len(), 12);
+    assert_eq!(topo.tree.slots(), 12);
+    assert_eq!(topo.tree.descendants(gw).count(), 12);
+    assert_eq!(
+        topo.render(),
+        [
+            "gateway gw-01",
+            "  hub ble-1",
+            "    sensor temp-1",
+            "    sensor temp-2",
+            "    sensor door-1",
+            "  hub lora-1",
+            "    sensor soil-1",
+            "    sensor soil-2",
+            "    sensor rain-1",
+            "  hub can-1",
+            "    sensor flow-1",
+            "  sensor power-1",
+        ]
+    );
+}
+
+#[test]
+fn routes_and_common_ancestors_follow_parent_links() {
+    let Fleet { topo, gw, ble, .. } = fleet();
+    let soil2 = topo.find("soil-2").unwrap();
+    assert_eq!(topo.route(soil2), ["gw-01", "lora-1", "soil-2"]);
+    assert_eq!(topo.tree.depth(soil2), 2);
+    assert_eq!(topo.route(gw), ["gw-01"]);
+
+    let temp1 = topo.find("temp-1").unwrap();
+    let door1 = topo.find("door-1").unwrap();
+    let soil1 = topo.find("soil-1").unwrap();
+    assert_eq!(topo.tree.common_ancestor(temp1, door1), Some(ble));
+    assert_eq!(topo.tree.common_ancestor(temp1, soil1), Some(gw));
+    assert_eq!(topo.tree.common_ancestor(temp1, ble), Some(ble));
+    assert!(topo.find("temp-9").is_none());
+}
+
+#[test]
+fn sensors_under_skips_hubs() {
+    let Fleet { topo, gw, ble, .. } = fleet();
+    assert_eq!(
+        names(&topo, &topo.sensors_under(ble)),
+        ["temp-1", "temp-2", "door-1"]
+    );
+    assert_eq!(topo.sensors_under(gw).len(), 8);
+    let power = topo.find("power-1").unwrap();
+    assert_eq!(topo.sensors_under(power), [power]);
+}
+
+#[test]
+fn a_dark_hub_takes_exactly_its_sensors() {
+    let Fleet {
+        mut topo, gw, lora, ..
+    } = fleet();
+    topo.tree.get_mut(lora).unwrap().online = false;
+    let dark: Vec<NodeId> = topo
+        .sensors_under(gw)
+        .into_iter()
+        .filter(|&id| !topo.reachable(id))
+        .collect();
+    assert_eq!(names(&topo, &dark), ["soil-1", "soil-2", "rain-1"]);
+    assert!(!topo.reachable(lora));
+    assert!(topo.reachable(topo.find("temp-1").unwrap()));
+
+    // The sensor itself going dark counts too
+    topo.tree.get_mut(lora).unwrap().online = true;
+    let door = topo.find("door-1").unwrap();
+    topo.tree.get_mut(door).unwrap().online = false;
+    assert!(!topo.reachable(door));
+}
+
+#[test]
+fn removing_a_hub_frees_its_slots_for_reuse() {
+    let Fleet {
+        mut topo, gw, can, ..
+    } = fleet();
+    let flow = topo.find("flow-1").unwrap();
+    let removed = topo.tree.remove(can).unwrap();
+    let removed: Vec<&str> = removed.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(removed, ["can-1", "flow-1"]);
+    assert_eq!(topo.tree.len(), 10);
+    assert!(topo.find("flow-1").is_none());
+
+    let can2 = topo.add(gw, Kind::Hub, "can-2").unwrap();
+    assert_eq!(can2.index(), flow.index());
+    assert_eq!(topo.tree.slots(), 12);
+    assert!(topo.tree.get(can).is_none());
+    assert!(topo.tree.get(flow).is_none());
+    assert_eq!(
+        topo.tree.insert(can, Device::new(Kind::Sensor, "orphan")),
+        Err(TreeError::Stale(can))
+    );
+}
+
+#[test]
+fn moving_a_sensor_to_another_hub() {
+    let Fleet {
+        mut topo,
+        gw,
+        ble,
+        lora,
+        ..
+    } = fleet();
+    let soil2 = topo.find("soil-2").unwrap();
+    topo.tree.move_to(soil2, ble).unwrap();
+    assert_eq!(topo.route(soil2), ["gw-01", "ble-1", "soil-2"]);
+    assert_eq!(topo.tree.parent(soil2), Some(ble));
+    assert!(!topo.tree.children(lora).contains(&soil2));
+    assert_eq!(topo.tree.children(ble).last(), Some(&soil2));
+
+    let temp1 = topo.find("temp-1").unwrap();
+    assert_eq!(
+        topo.tree.move_to(ble, temp1),
+        Err(TreeError::WouldCycle {
+            node: ble,
+            parent: temp1
+        })
+    );
+    assert_eq!(topo.tree.move_to(gw, ble), Err(TreeError::IsRoot(gw)));
+}
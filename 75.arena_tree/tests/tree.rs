@@ -0,0 +1,191 @@
+use arena_tree::{NodeId, Tree, TreeError};
+use std::collections::HashMap;
+
+// xorshift32: the same operations on every run
+struct Rng(u32);
+
+impl Rng {
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+}
+
+fn values(tree: &Tree<u32>, ids: impl Iterator<Item = NodeId>) -> Vec<u32> {
+    ids.map(|id| *tree.get(id).unwrap()).collect()
+}
+
+//   0
+//   ├─ 1
+//   │  ├─ 3
+//   │  └─ 4
+//   └─ 2
+fn small() -> (Tree<u32>, Vec<NodeId>) {
+    let mut tree = Tree::new();
+    let root = tree.insert_root(0).unwrap();
+    let a = tree.insert(root, 1).unwrap();
+    let b = tree.insert(root, 2).unwrap();
+    let c = tree.insert(a, 3).unwrap();
+    let d = tree.insert(a, 4).unwrap();
+    (tree, vec![root, a, b, c, d])
+}
+
+#[test]
+fn an_empty_tree() {
+    let mut tree: Tree<u32> = Tree::new();
+    assert!(tree.is_empty());
+    assert_eq!(tree.root(), None);
+    let root = tree.insert_root(7).unwrap();
+    assert_eq!(tree.insert_root(8), Err(TreeError::RootExists));
+    assert_eq!(tree.root(), Some(root));
+    assert_eq!(tree.remove(root), Ok(vec![7]));
+    assert!(tree.is_empty());
+    assert_eq!(tree.root(), None);
+    // With the root gone a new one may go in, in the same slot
+    let again = tree.insert_root(9).unwrap();
+    assert_eq!(again.index(), root.index());
+    assert_ne!(again, root);
+    assert_eq!(tree.slots(), 1);
+}
+
+#[test]
+fn descendants_are_pre_order_and_ancestors_go_up() {
+    let (tree, ids) = small();
+    assert_eq!(values(&tree, tree.descendants(ids[0])), [0, 1, 3, 4, 2]);
+    assert_eq!(values(&tree, tree.descendants(ids[1])), [1, 3, 4]);
+    assert_eq!(values(&tree, tree.ancestors(ids[4])), [1, 0]);
+    assert_eq!(tree.ancestors(ids[0]).count(), 0);
+    assert_eq!(tree.depth(ids[3]), 2);
+    assert_eq!(tree.children(ids[1]), [ids[3], ids[4]]);
+    assert_eq!(tree.common_ancestor(ids[3], ids[2]), Some(ids[0]));
+    assert_eq!(tree.common_ancestor(ids[3], ids[4]), Some(ids[1]));
+}
+
+#[test]
+fn stale_ids_find_nothing() {
+    let (mut tree, ids) = small();
+    assert_eq!(tree.remove(ids[1]), Ok(vec![1, 3, 4]));
+    for id in [ids[1], ids[3], ids[4]] {
+        assert!(!tree.contains(id));
+        assert_eq!(tree.get(id), None);
+        assert_eq!(tree.parent(id), None);
+        assert!(tree.children(id).is_empty());
+        assert_eq!(tree.descendants(id).count(), 0);
+        assert_eq!(tree.remove(id), Err(TreeError::Stale(id)));
+        assert_eq!(tree.insert(id, 9), Err(TreeError::Stale(id)));
+        assert_eq!(tree.common_ancestor(id, ids[0]), None);
+    }
+    assert_eq!(tree.children(ids[0]), [ids[2]]);
+    // The freed slots are reused, under new generations
+    let reused = tree.insert(ids[2], 5).unwrap();
+    assert!(ids[3..].iter().any(|id| id.index() == reused.index()));
+    assert!(!ids.contains(&reused));
+    assert_eq!(tree.slots(), 5);
+}
+
+#[test]
+fn moves_refuse_cycles_and_the_root() {
+    let (mut tree, ids) = small();
+    assert_eq!(
+        tree.move_to(ids[1], ids[1]),
+        Err(TreeError::WouldCycle {
+            node: ids[1],
+            parent: ids[1]
+        })
+    );
+    assert_eq!(
+        tree.move_to(ids[1], ids[4]),
+        Err(TreeError::WouldCycle {
+            node: ids[1],
+            parent: ids[4]
+        })
+    );
+    assert_eq!(tree.move_to(ids[0], ids[2]), Err(TreeError::IsRoot(ids[0])));
+    // Failed moves change nothing
+    assert_eq!(values(&tree, tree.descendants(ids[0])), [0, 1, 3, 4, 2]);
+
+    tree.move_to(ids[1], ids[2]).unwrap();
+    assert_eq!(values(&tree, tree.descendants(ids[0])), [0, 2, 1, 3, 4]);
+    assert_eq!(tree.depth(ids[4]), 3);
+}
+
+#[test]
+fn errors_name_the_nodes() {
+    let (_, ids) = small();
+    assert_eq!(
+        TreeError::Stale(ids[3]).to_string(),
+        "node #3v0 no longer exists"
+    );
+    assert_eq!(
+        TreeError::WouldCycle {
+            node: ids[1],
+            parent: ids[4]
+        }
+        .to_string(),
+        "#4v0 is inside the subtree of #1v0"
+    );
+    assert_eq!(TreeError::IsRoot(ids[0]).to_string(), "#0v0 is the root");
+    assert_eq!(
+        TreeError::RootExists.to_string(),
+        "the tree already has a root"
+    );
+}
+
+// Random inserts, removes and moves against a parent map; the tree must
+// agree on every parent and every subtree's size
+#[test]
+fn random_edits_match_a_parent_map() {
+    let mut rng = Rng(0x9E37_79B9);
+    let mut tree = Tree::new();
+    let root = tree.insert_root(0).unwrap();
+    let mut parents: HashMap<NodeId, Option<NodeId>> = HashMap::from([(root, None)]);
+    let mut gone = Vec::new();
+    for step in 0..3000u32 {
+        let live: Vec<NodeId> = {
+            let mut live: Vec<NodeId> = parents.keys().copied().collect();
+            live.sort();
+            live
+        };
+        let pick = live[rng.next() as usize % live.len()];
+        match rng.next() % 4 {
+            0 | 1 => {
+                let id = tree.insert(pick, step).unwrap();
+                parents.insert(id, Some(pick));
+            }
+            2 if pick != root => {
+                let subtree: Vec<NodeId> = tree.descendants(pick).collect();
+                assert_eq!(tree.remove(pick).unwrap().len(), subtree.len());
+                for id in subtree {
+                    parents.remove(&id);
+                    gone.push(id);
+                }
+            }
+            _ => {
+                let to = live[rng.next() as usize % live.len()];
+                let mut up = Some(to);
+                let mut cycle = false;
+                while let Some(a) = up {
+                    cycle |= a == pick;
+                    up = parents[&a];
+                }
+                match tree.move_to(pick, to) {
+                    Ok(()) => {
+                        assert!(!cycle && pick != root, "step {}", step);
+                        parents.insert(pick, Some(to));
+                    }
+                    Err(TreeError::IsRoot(_)) => assert_eq!(pick, root),
+                    Err(TreeError::WouldCycle { .. }) => assert!(cycle),
+                    Err(e) => panic!("step {}: {}", step, e),
+                }
+            }
+        }
+        assert_eq!(tree.len(), parents.len(), "step {}", step);
+    }
+    for (&id, &parent) in &parents {
+        assert_eq!(tree.parent(id), parent);
+    }
+    assert_eq!(tree.descendants(root).count(), parents.len());
+    assert!(gone.iter().all(|&id| !tree.contains(id)));
+}
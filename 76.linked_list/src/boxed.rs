@@ -0,0 +1,152 @@
+// Singly linked stack with Box
+//
+//   head ─▶ [3 | next] ─▶ [2 | next] ─▶ [1 | None]
+//
+// Each node owns the next one, so the whole list has a single chain of
+// ownership and the borrow checker can follow every step: no `unsafe`, no
+// run-time checks. The catch is that only one direction works. A node
+// can't also be owned by the node after it, so this is a stack (push and
+// pop at the head), not a queue.
+
+type Link<T> = Option<Box<Node<T>>>;
+
+#[derive(Debug)]
+struct Node<T> {
+    value: T,
+    next: Link<T>,
+}
+
+#[derive(Debug)]
+pub struct Stack<T> {
+    head: Link<T>,
+    len: usize,
+}
+
+impl<T> Default for Stack<T> {
+    fn default() -> Self {
+        Stack::new()
+    }
+}
+
+impl<T> Stack<T> {
+    pub fn new() -> Stack<T> {
+        Stack { head: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn push(&mut self, value: T) {
+        // `take` leaves `None` behind, so `head` is never moved out of
+        // while `self` is borrowed
+        let next = self.head.take();
+        self.head = Some(Box::new(Node { value, next }));
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.head.take().map(|node| {
+            self.head = node.next;
+            self.len -= 1;
+            node.value
+        })
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.value)
+    }
+
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.head.as_mut().map(|node| &mut node.value)
+    }
+
+    // Reverses the links in place, without allocating
+    pub fn reverse(&mut self) {
+        let mut done: Link<T> = None;
+        let mut rest = self.head.take();
+        while let Some(mut node) = rest {
+            rest = node.next.take();
+            node.next = done;
+            done = Some(node);
+        }
+        self.head = done;
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            next: self.head.as_deref_mut(),
+        }
+    }
+}
+
+// The derived drop would recurse once per node and overflow the stack on
+// a long list; unlinking one node at a time keeps it flat
+impl<T> Drop for Stack<T> {
+    fn drop(&mut self) {
+        let mut link = self.head.take();
+        while let Some(mut node) = link {
+            link = node.next.take();
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.next.map(|node| {
+            self.next = node.next.as_deref();
+            &node.value
+        })
+    }
+}
+
+pub struct IterMut<'a, T> {
+    next: Option<&'a mut Node<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        // `take` moves the `&mut` out, so no two items alias
+        self.next.take().map(|node| {
+            self.next = node.next.as_deref_mut();
+            &mut node.value
+        })
+    }
+}
+
+pub struct IntoIter<T>(Stack<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop()
+    }
+}
+
+impl<T> IntoIterator for Stack<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
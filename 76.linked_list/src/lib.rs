@@ -0,0 +1,20 @@
+// Linked lists three ways
+//
+// - `boxed`: a singly linked stack, each node owning the next through
+//   `Box`; entirely safe and checked at compile time
+// - `shared`: a doubly linked deque of `Rc<RefCell<_>>` nodes with `Weak`
+//   back links; safe, but ownership and borrows are checked at run time
+// - `raw`: a doubly linked queue with a cursor, built on raw pointers and
+//   `unsafe`, checked by Miri
+//
+// Lists are rarely the right data structure (a `Vec` or `VecDeque` is
+// nearly always faster); they are here because each one meets a different
+// corner of the ownership rules.
+
+pub mod boxed;
+pub mod raw;
+pub mod shared;
+
+pub use boxed::Stack;
+pub use raw::Queue;
+pub use shared::Deque;
@@ -0,0 +1,255 @@
+use linked_list::{Deque, Queue, Stack};
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::thread;
+use std::time::Instant;
+
+// Miri interprets every instruction, so it gets smaller workloads
+const LONG: usize = if cfg!(miri) { 1_000 } else { 1_000_000 };
+const OPS: usize = if cfg!(miri) { 2_000 } else { 200_000 };
+
+// Counts its drops, to catch leaks and double frees
+struct Tracked {
+    id: u32,
+    drops: Rc<Cell<usize>>,
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+// xorshift32, so every run (and every list) sees the same operations
+fn ops(seed: u32, n: usize) -> Vec<u32> {
+    let mut x = seed;
+    (0..n)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        })
+        .collect()
+}
+
+fn main() {
+    println!("=== Linked List Examples ===\n");
+
+    // 1. Box
+    println!("1. Stack with Box, an undo history of config edits:");
+    let mut undo: Stack<(&str, u32)> = Stack::new();
+    undo.push(("poll_interval_ms", 100));
+    undo.push(("batch_size", 20));
+    undo.push(("poll_interval_ms", 250));
+    let listed: Vec<_> = undo.iter().copied().collect();
+    println!("   newest first: {:?}", listed);
+    if let Some(top) = undo.peek_mut() {
+        top.1 = 500;
+    }
+    let undone = undo.pop();
+    println!("   undo: {:?}, next: {:?}", undone, undo.peek());
+    for edit in undo.iter_mut() {
+        edit.1 *= 2;
+    }
+    undo.reverse();
+    let reversed: Vec<_> = undo.into_iter().collect();
+    println!("   doubled, reversed, drained: {:?}", reversed);
+    let mut long = Stack::new();
+    for i in 0..LONG {
+        long.push(i);
+    }
+    let len = long.len();
+    drop(long);
+    println!("   dropped a {}-node stack", len);
+
+    // 2. Rc<RefCell<_>>
+    println!("\n2. Deque with Rc<RefCell<_>>, the last 5 readings:");
+    let drops = Rc::new(Cell::new(0));
+    let mut window: Deque<Tracked> = Deque::new();
+    for id in 1..=8 {
+        window.push_back(Tracked {
+            id,
+            drops: Rc::clone(&drops),
+        });
+        if window.len() > 5 {
+            window.pop_front();
+        }
+    }
+    let front = window.peek_front().map(|t| t.id);
+    let back = window.peek_back().map(|t| t.id);
+    let evicted = drops.get();
+    println!(
+        "   front {:?}, back {:?}, len {}, dropped so far {}",
+        front,
+        back,
+        window.len(),
+        evicted
+    );
+    if let Some(mut newest) = window.peek_back_mut() {
+        newest.id = 80;
+    }
+    let popped = window.pop_back().map(|t| t.id);
+    window.push_front(Tracked {
+        id: 3,
+        drops: Rc::clone(&drops),
+    });
+    println!(
+        "   pop_back {:?}, push_front 3, front now {:?}",
+        popped,
+        window.peek_front().map(|t| t.id)
+    );
+    drop(window);
+    println!("   after drop: {} of 9 values dropped", drops.get());
+    let mut numbers = Deque::new();
+    for i in 0..5 {
+        numbers.push_front(i);
+    }
+    println!("   push_front 0 to 4, to_vec: {:?}", numbers.to_vec());
+
+    // 3. Raw pointers
+    println!("\n3. Queue with raw pointers, outbound messages:");
+    let drops = Rc::new(Cell::new(0));
+    let mut outbox: Queue<Tracked> = Queue::new();
+    let mut sent = Vec::new();
+    for id in 1..=6 {
+        outbox.push(Tracked {
+            id,
+            drops: Rc::clone(&drops),
+        });
+        // Send one for every two queued
+        if id % 2 == 0 {
+            sent.extend(outbox.pop().map(|t| t.id));
+        }
+    }
+    if let Some(head) = outbox.peek_mut() {
+        head.id += 100;
+    }
+    let queued: Vec<u32> = outbox.iter().map(|t| t.id).collect();
+    println!("   sent {:?}, still queued {:?}", sent, queued);
+    // A cursor walks the queue: expired messages (odd ids) are dropped,
+    // and 104 gets an urgent follow-up (7) right behind it
+    let mut cursor = outbox.cursor_mut();
+    cursor.move_next();
+    while let Some(message) = cursor.current() {
+        if message.id == 104 {
+            cursor.insert_after(Tracked {
+                id: 7,
+                drops: Rc::clone(&drops),
+            });
+            // Past the new message, which isn't expired
+            cursor.move_next();
+            cursor.move_next();
+        } else if message.id % 2 == 1 {
+            cursor.remove_current();
+        } else {
+            cursor.move_next();
+        }
+    }
+    outbox.push_front(Tracked {
+        id: 8,
+        drops: Rc::clone(&drops),
+    });
+    let queued: Vec<u32> = outbox.iter().map(|t| t.id).collect();
+    let newest = outbox.pop_back().map(|t| t.id);
+    println!(
+        "   after the cursor and push_front 8: {:?}, pop_back {:?}",
+        queued, newest
+    );
+    drop(outbox);
+    println!("   after drop: {} of 8 values dropped", drops.get());
+    let mut numbers: Queue<u32> = Queue::new();
+    for i in 0..4 {
+        numbers.push(i);
+    }
+    let handle = thread::spawn(move || {
+        numbers.push(4);
+        format!("{:?}", numbers)
+    });
+    let from_thread = handle.join().unwrap();
+    println!("   filled here, finished on a thread: {}", from_thread);
+
+    // 4. The same work three ways
+    println!("\n4. {} random operations, next to std:", OPS);
+    let script = ops(0x2545_f491, OPS);
+    let started = Instant::now();
+    let mut stack = Stack::new();
+    let mut model = Vec::new();
+    for &op in &script {
+        if op.is_multiple_of(3) {
+            stack.pop();
+            model.pop();
+        } else {
+            stack.push(op);
+            model.push(op);
+        }
+    }
+    let stack_time = started.elapsed();
+
+    let started = Instant::now();
+    let mut deque = Deque::new();
+    let mut model = VecDeque::new();
+    for &op in &script {
+        match op % 4 {
+            0 => {
+                deque.pop_front();
+                model.pop_front();
+            }
+            1 => {
+                deque.pop_back();
+                model.pop_back();
+            }
+            2 => {
+                deque.push_front(op);
+                model.push_front(op);
+            }
+            _ => {
+                deque.push_back(op);
+                model.push_back(op);
+            }
+        }
+    }
+    let deque_time = started.elapsed();
+
+    let started = Instant::now();
+    let mut queue = Queue::new();
+    let mut model = VecDeque::new();
+    for &op in &script {
+        match op % 4 {
+            0 => {
+                queue.pop();
+                model.pop_front();
+            }
+            1 => {
+                queue.pop_back();
+                model.pop_back();
+            }
+            2 => {
+                queue.push_front(op);
+                model.push_front(op);
+            }
+            _ => {
+                queue.push(op);
+                model.push_back(op);
+            }
+        }
+    }
+    let queue_time = started.elapsed();
+    // Includes the std model's share; it is the same for all three
+    println!(
+        "   Box {:.2?}, Rc<RefCell<_>> {:.2?}, raw {:.2?}",
+        stack_time, deque_time, queue_time
+    );
+
+    println!(
+        "\n   under Miri: {}",
+        if cfg!(miri) {
+            "yes, and no undefined behaviour was found"
+        } else {
+            "no (cargo +nightly miri run, or miri test)"
+        }
+    );
+
+    println!("\n=== End of Linked List Examples ===");
+}
@@ -0,0 +1,347 @@
+// Doubly linked queue with raw pointers
+//
+//   head ─▶ [1] ⇄ [2] ⇄ [3] ◀─ tail
+//            prev and next: *mut Node<T>
+//
+// A queue pushes at one end and pops at the other, so it needs a second
+// way into the list: `tail` points at the last node, which the node before
+// it also points to. With `prev` links as well, every node in the middle
+// is pointed at by both neighbours, and a cursor can stop at any node to
+// insert or remove around it. Safe Rust can't express that without `Rc`,
+// so the links here are raw pointers and the list takes responsibility for
+// what the compiler would otherwise check.
+//
+// The rules this file keeps:
+// - Every node is created by `Box::into_raw` in `link` and freed by
+//   exactly one `Box::from_raw` in `unlink` (`Drop` pops everything)
+// - After a node is allocated, it is only ever accessed through raw
+//   pointers derived from the one `into_raw` returned. Mixing in `&mut`
+//   references or a live `Box` to the same node would invalidate the
+//   others under Rust's aliasing rules, which Miri checks
+// - `head` and `tail` are both null or both non-null; a node's `prev` is
+//   null exactly when it is the head, its `next` exactly when it is the
+//   tail, and `len` counts the nodes between them
+//
+// `cargo +nightly miri test` runs the tests under Miri, which reports any
+// use after free, double free, leak or aliasing violation.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ptr;
+
+struct Node<T> {
+    value: T,
+    prev: *mut Node<T>,
+    next: *mut Node<T>,
+}
+
+pub struct Queue<T> {
+    head: *mut Node<T>,
+    tail: *mut Node<T>,
+    len: usize,
+    // The queue owns values of type T, for drop checking and auto traits
+    _owns: PhantomData<T>,
+}
+
+impl<T> Default for Queue<T> {
+    fn default() -> Self {
+        Queue::new()
+    }
+}
+
+impl<T> Queue<T> {
+    pub fn new() -> Queue<T> {
+        Queue {
+            head: ptr::null_mut(),
+            tail: ptr::null_mut(),
+            len: 0,
+            _owns: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_null()
+    }
+
+    // Allocates a node for `value` between two neighbours, either of which
+    // may be null for the ends of the list
+    //
+    // SAFETY: the caller passes adjacent nodes of this queue: `prev` is
+    // null or a live node whose `next` is `next`, and `next` is null or a
+    // live node whose `prev` is `prev`; both null only when the queue is
+    // empty
+    unsafe fn link(&mut self, prev: *mut Node<T>, next: *mut Node<T>, value: T) -> *mut Node<T> {
+        let node = Box::into_raw(Box::new(Node { value, prev, next }));
+        if prev.is_null() {
+            self.head = node;
+        } else {
+            (*prev).next = node;
+        }
+        if next.is_null() {
+            self.tail = node;
+        } else {
+            (*next).prev = node;
+        }
+        self.len += 1;
+        node
+    }
+
+    // Takes `node` out of the list, frees it and returns its value
+    //
+    // SAFETY: `node` is a live node of this queue; nothing may use the
+    // pointer afterwards
+    unsafe fn unlink(&mut self, node: *mut Node<T>) -> T {
+        let node = Box::from_raw(node);
+        if node.prev.is_null() {
+            self.head = node.next;
+        } else {
+            (*node.prev).next = node.next;
+        }
+        if node.next.is_null() {
+            self.tail = node.prev;
+        } else {
+            (*node.next).prev = node.prev;
+        }
+        self.len -= 1;
+        node.value
+    }
+
+    pub fn push(&mut self, value: T) {
+        // SAFETY: the tail and null are the two sides of the back end
+        unsafe { self.link(self.tail, ptr::null_mut(), value) };
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        // SAFETY: null and the head are the two sides of the front end
+        unsafe { self.link(ptr::null_mut(), self.head, value) };
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.head.is_null() {
+            return None;
+        }
+        // SAFETY: a non-null head is a live node, and `head` is replaced
+        // by `unlink` before the node is freed
+        Some(unsafe { self.unlink(self.head) })
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.tail.is_null() {
+            return None;
+        }
+        // SAFETY: as in `pop`, for the tail
+        Some(unsafe { self.unlink(self.tail) })
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        // SAFETY: the node lives until it is popped, which needs `&mut
+        // self`, so not while this borrow of `self` exists
+        unsafe { self.head.as_ref().map(|node| &node.value) }
+    }
+
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: as in `peek`, and `&mut self` makes this the only access
+        unsafe { self.head.as_mut().map(|node| &mut node.value) }
+    }
+
+    pub fn peek_back(&self) -> Option<&T> {
+        // SAFETY: as in `peek`
+        unsafe { self.tail.as_ref().map(|node| &node.value) }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            _queue: PhantomData,
+        }
+    }
+
+    // A cursor starting on the "ghost" position before the head, from
+    // which `move_next` goes to the head and `move_prev` to the tail
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: ptr::null_mut(),
+            queue: self,
+        }
+    }
+}
+
+impl<T> Drop for Queue<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Queue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// SAFETY: the queue owns its nodes outright, as a `Vec<T>` owns its
+// elements; no pointer to a node escapes except through borrows of the
+// queue
+unsafe impl<T: Send> Send for Queue<T> {}
+unsafe impl<T: Sync> Sync for Queue<T> {}
+
+pub struct Iter<'a, T> {
+    head: *const Node<T>,
+    tail: *const Node<T>,
+    // Nodes not yet returned from either end; the ends meet at 0
+    len: usize,
+    _queue: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: the iterator borrows the queue, so no node is popped or
+        // freed while it exists, and `len` keeps it inside the list
+        unsafe {
+            let node = &*self.head;
+            self.head = node.next;
+            Some(&node.value)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: as in `next`
+        unsafe {
+            let node = &*self.tail;
+            self.tail = node.prev;
+            Some(&node.value)
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IntoIter<T>(Queue<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> IntoIterator for Queue<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+// A position in the queue that can edit around itself in O(1)
+//
+//   ghost ─▶ [1] ⇄ [2] ⇄ [3] ─▶ ghost      (one ghost, null `current`)
+//                   ▲ current
+//
+// The cursor holds the queue's `&mut`, so nothing else can free the node
+// it is on; it is the only way to insert or remove in the middle.
+pub struct CursorMut<'a, T> {
+    current: *mut Node<T>,
+    queue: &'a mut Queue<T>,
+}
+
+impl<T> CursorMut<'_, T> {
+    // The value under the cursor, None on the ghost
+    pub fn current(&mut self) -> Option<&mut T> {
+        // SAFETY: a non-null `current` is a live node, and the `&mut self`
+        // borrow keeps the cursor from moving or removing it meanwhile
+        unsafe { self.current.as_mut().map(|node| &mut node.value) }
+    }
+
+    pub fn move_next(&mut self) {
+        self.current = if self.current.is_null() {
+            self.queue.head
+        } else {
+            // SAFETY: a non-null `current` is a live node
+            unsafe { (*self.current).next }
+        };
+    }
+
+    pub fn move_prev(&mut self) {
+        self.current = if self.current.is_null() {
+            self.queue.tail
+        } else {
+            // SAFETY: as in `move_next`
+            unsafe { (*self.current).prev }
+        };
+    }
+
+    // Inserts after the cursor, or at the front when on the ghost; the
+    // cursor stays where it is
+    pub fn insert_after(&mut self, value: T) {
+        // SAFETY: `current` and its `next` are adjacent, as are the ghost
+        // and the head
+        unsafe {
+            if self.current.is_null() {
+                self.queue.link(ptr::null_mut(), self.queue.head, value);
+            } else {
+                self.queue.link(self.current, (*self.current).next, value);
+            }
+        }
+    }
+
+    // Inserts before the cursor, or at the back when on the ghost
+    pub fn insert_before(&mut self, value: T) {
+        // SAFETY: as in `insert_after`, on the other side
+        unsafe {
+            if self.current.is_null() {
+                self.queue.link(self.queue.tail, ptr::null_mut(), value);
+            } else {
+                self.queue.link((*self.current).prev, self.current, value);
+            }
+        }
+    }
+
+    // Removes the value under the cursor and moves on to the next node
+    // (the ghost after the tail); None on the ghost
+    pub fn remove_current(&mut self) -> Option<T> {
+        if self.current.is_null() {
+            return None;
+        }
+        let node = self.current;
+        // SAFETY: `node` is live; the cursor leaves it before `unlink`
+        // frees it, and no other pointer to it survives the unlinking
+        unsafe {
+            self.current = (*node).next;
+            Some(self.queue.unlink(node))
+        }
+    }
+}
@@ -0,0 +1,170 @@
+// Doubly linked deque with Rc<RefCell<_>>
+//
+//   head ─▶ [a] ⇄ [b] ⇄ [c] ◀─ tail
+//            next: Rc (strong), prev: Weak
+//
+// A node in the middle is reachable from both neighbours, so it needs
+// shared ownership (`Rc`), and changing its links through a shared handle
+// needs `RefCell`. The backward links are `Weak`: with `Rc` both ways each
+// pair of neighbours would keep the other alive and the list would never
+// be freed.
+//
+// Everything is safe, but ownership is now checked at run time: each
+// access takes a borrow flag, and values can't be lent out as plain `&T`,
+// only as a `Ref<T>` guard tied to the node's `RefCell`.
+
+use std::cell::{Ref, RefCell, RefMut};
+use std::rc::{Rc, Weak};
+
+type Link<T> = Option<Rc<RefCell<Node<T>>>>;
+
+#[derive(Debug)]
+struct Node<T> {
+    value: T,
+    next: Link<T>,
+    prev: Option<Weak<RefCell<Node<T>>>>,
+}
+
+#[derive(Debug)]
+pub struct Deque<T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+}
+
+impl<T> Default for Deque<T> {
+    fn default() -> Self {
+        Deque::new()
+    }
+}
+
+impl<T> Deque<T> {
+    pub fn new() -> Deque<T> {
+        Deque {
+            head: None,
+            tail: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        let node = Rc::new(RefCell::new(Node {
+            value,
+            next: None,
+            prev: None,
+        }));
+        match self.head.take() {
+            Some(old) => {
+                old.borrow_mut().prev = Some(Rc::downgrade(&node));
+                node.borrow_mut().next = Some(old);
+            }
+            None => self.tail = Some(Rc::clone(&node)),
+        }
+        self.head = Some(node);
+        self.len += 1;
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        let node = Rc::new(RefCell::new(Node {
+            value,
+            next: None,
+            prev: None,
+        }));
+        match self.tail.take() {
+            Some(old) => {
+                node.borrow_mut().prev = Some(Rc::downgrade(&old));
+                old.borrow_mut().next = Some(Rc::clone(&node));
+            }
+            None => self.head = Some(Rc::clone(&node)),
+        }
+        self.tail = Some(node);
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let old = self.head.take()?;
+        match old.borrow_mut().next.take() {
+            Some(next) => {
+                next.borrow_mut().prev = None;
+                self.head = Some(next);
+            }
+            None => self.tail = None,
+        }
+        self.len -= 1;
+        Some(Self::unwrap(old))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let old = self.tail.take()?;
+        let prev = old.borrow_mut().prev.take().and_then(|w| w.upgrade());
+        match prev {
+            Some(prev) => {
+                // Dropping the previous node's `next` releases the second
+                // strong reference to `old`
+                prev.borrow_mut().next = None;
+                self.tail = Some(prev);
+            }
+            None => self.head = None,
+        }
+        self.len -= 1;
+        Some(Self::unwrap(old))
+    }
+
+    // The popped node must be the last strong reference by now; if it
+    // isn't, a link was left behind
+    fn unwrap(node: Rc<RefCell<Node<T>>>) -> T {
+        match Rc::try_unwrap(node) {
+            Ok(cell) => cell.into_inner().value,
+            Err(_) => panic!("popped node still linked"),
+        }
+    }
+
+    pub fn peek_front(&self) -> Option<Ref<'_, T>> {
+        self.head
+            .as_ref()
+            .map(|node| Ref::map(node.borrow(), |node| &node.value))
+    }
+
+    pub fn peek_back(&self) -> Option<Ref<'_, T>> {
+        self.tail
+            .as_ref()
+            .map(|node| Ref::map(node.borrow(), |node| &node.value))
+    }
+
+    pub fn peek_back_mut(&mut self) -> Option<RefMut<'_, T>> {
+        self.tail
+            .as_ref()
+            .map(|node| RefMut::map(node.borrow_mut(), |node| &mut node.value))
+    }
+
+    // Copies out the values front to back. A borrowing iterator would have
+    // to hold a `Ref` to each node while handing out the next one, which
+    // `RefCell` makes impractical; this is the price of the design.
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let mut out = Vec::with_capacity(self.len);
+        let mut next = self.head.clone();
+        while let Some(node) = next {
+            let node = node.borrow();
+            out.push(node.value.clone());
+            next = node.next.clone();
+        }
+        out
+    }
+}
+
+impl<T> Drop for Deque<T> {
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
@@ -0,0 +1,78 @@
+use linked_list::Stack;
+
+// Miri interprets every instruction, so it gets smaller workloads
+const LONG: usize = if cfg!(miri) { 1_000 } else { 1_000_000 };
+const STEPS: usize = if cfg!(miri) { 2_000 } else { 100_000 };
+
+#[test]
+fn pops_in_reverse_push_order() {
+    let mut stack = Stack::new();
+    assert!(stack.is_empty());
+    for i in 1..=3 {
+        stack.push(i);
+    }
+    assert_eq!(stack.len(), 3);
+    assert_eq!(stack.peek(), Some(&3));
+    assert_eq!(stack.pop(), Some(3));
+    assert_eq!(stack.pop(), Some(2));
+    assert_eq!(stack.pop(), Some(1));
+    assert_eq!(stack.pop(), None);
+    assert_eq!(stack.peek(), None);
+}
+
+#[test]
+fn peek_mut_iter_mut_and_reverse() {
+    let mut stack = Stack::new();
+    for i in 1..=4 {
+        stack.push(i);
+    }
+    *stack.peek_mut().unwrap() = 40;
+    for x in stack.iter_mut() {
+        *x *= 2;
+    }
+    assert_eq!(stack.iter().copied().collect::<Vec<_>>(), [80, 6, 4, 2]);
+    stack.reverse();
+    assert_eq!(stack.peek(), Some(&2));
+    assert_eq!(stack.into_iter().collect::<Vec<_>>(), [2, 4, 6, 80]);
+}
+
+#[test]
+fn reverse_of_empty_and_single() {
+    let mut empty: Stack<u8> = Stack::new();
+    empty.reverse();
+    assert!(empty.is_empty());
+    let mut single = Stack::new();
+    single.push(1);
+    single.reverse();
+    assert_eq!(single.pop(), Some(1));
+}
+
+#[test]
+fn dropping_a_long_stack_does_not_overflow() {
+    let mut long = Stack::new();
+    for i in 0..LONG {
+        long.push(i);
+    }
+    assert_eq!(long.len(), LONG);
+    drop(long);
+}
+
+#[test]
+fn random_operations_match_vec() {
+    let mut x = 0x2545_f491u32;
+    let mut stack = Stack::new();
+    let mut model = Vec::new();
+    for _ in 0..STEPS {
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        if x.is_multiple_of(3) {
+            assert_eq!(stack.pop(), model.pop());
+        } else {
+            stack.push(x);
+            model.push(x);
+        }
+        assert_eq!(stack.len(), model.len());
+    }
+    assert!(stack.iter().eq(model.iter().rev()));
+}
@@ -0,0 +1,341 @@
+// `cargo +nightly miri test --test raw` runs these under Miri
+use linked_list::Queue;
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::thread;
+
+// Miri interprets every instruction, so it gets fewer steps
+const STEPS: usize = if cfg!(miri) { 2_000 } else { 100_000 };
+
+// xorshift32: the same operations on every run
+struct Rng(u32);
+
+impl Rng {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 as usize % n
+    }
+}
+
+// Counts its drops, to catch leaks and double frees
+struct Tracked {
+    id: u32,
+    drops: Rc<Cell<usize>>,
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+fn tracked(id: u32, drops: &Rc<Cell<usize>>) -> Tracked {
+    Tracked {
+        id,
+        drops: Rc::clone(drops),
+    }
+}
+
+fn contents(queue: &Queue<u32>) -> Vec<u32> {
+    queue.iter().copied().collect()
+}
+
+#[test]
+fn first_in_first_out() {
+    let mut queue = Queue::new();
+    assert!(queue.is_empty());
+    for i in 1..=4 {
+        queue.push(i);
+    }
+    assert_eq!(queue.len(), 4);
+    assert_eq!(queue.peek(), Some(&1));
+    assert_eq!(queue.peek_back(), Some(&4));
+    assert_eq!(queue.pop(), Some(1));
+    assert_eq!(queue.pop(), Some(2));
+    if let Some(head) = queue.peek_mut() {
+        *head += 100;
+    }
+    assert_eq!(contents(&queue), [103, 4]);
+    assert_eq!(format!("{:?}", queue), "[103, 4]");
+}
+
+#[test]
+fn both_ends() {
+    let mut queue = Queue::new();
+    queue.push(2);
+    queue.push_front(1);
+    queue.push(3);
+    queue.push_front(0);
+    assert_eq!(contents(&queue), [0, 1, 2, 3]);
+    assert_eq!(queue.pop_back(), Some(3));
+    assert_eq!(queue.pop(), Some(0));
+    assert_eq!(queue.pop_back(), Some(2));
+    assert_eq!(queue.pop_back(), Some(1));
+    assert_eq!(queue.pop_back(), None);
+    assert_eq!(queue.pop(), None);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn emptying_and_refilling_resets_both_ends() {
+    let mut queue = Queue::new();
+    queue.push(1);
+    queue.pop();
+    queue.push(2);
+    assert_eq!((queue.peek(), queue.peek_back()), (Some(&2), Some(&2)));
+    queue.pop_back();
+    queue.push_front(3);
+    queue.push(4);
+    assert_eq!(contents(&queue), [3, 4]);
+}
+
+#[test]
+fn iter_runs_from_both_ends_and_meets_in_the_middle() {
+    let queue: Queue<u32> = {
+        let mut q = Queue::new();
+        (0..6).for_each(|i| q.push(i));
+        q
+    };
+    let mut iter = queue.iter();
+    assert_eq!(iter.len(), 6);
+    assert_eq!(iter.next(), Some(&0));
+    assert_eq!(iter.next_back(), Some(&5));
+    assert_eq!(iter.next_back(), Some(&4));
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.by_ref().collect::<Vec<_>>(), [&1, &2, &3]);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+    assert!(queue.iter().rev().eq([5, 4, 3, 2, 1, 0].iter()));
+}
+
+#[test]
+fn into_iter_from_both_ends() {
+    let mut queue = Queue::new();
+    (0..5).for_each(|i| queue.push(i));
+    let mut iter = queue.into_iter();
+    assert_eq!(iter.next_back(), Some(4));
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.collect::<Vec<_>>(), [1, 2, 3]);
+}
+
+#[test]
+fn every_node_is_freed_exactly_once() {
+    let drops = Rc::new(Cell::new(0));
+    let mut queue = Queue::new();
+    for id in 0..6 {
+        queue.push(tracked(id, &drops));
+    }
+    drop(queue.pop());
+    drop(queue.pop_back());
+    assert_eq!(drops.get(), 2);
+    drop(queue);
+    assert_eq!(drops.get(), 6);
+
+    // A partly consumed IntoIter drops the rest
+    drops.set(0);
+    let mut queue = Queue::new();
+    for id in 0..6 {
+        queue.push(tracked(id, &drops));
+    }
+    let mut iter = queue.into_iter();
+    let first = iter.next().unwrap();
+    drop(iter);
+    assert_eq!((first.id, drops.get()), (0, 5));
+}
+
+#[test]
+fn cursor_walks_both_ways_through_the_ghost() {
+    let mut queue = Queue::new();
+    (1..=3).for_each(|i| queue.push(i));
+    let mut cursor = queue.cursor_mut();
+    assert_eq!(cursor.current(), None);
+    let mut forward = Vec::new();
+    for _ in 0..4 {
+        cursor.move_next();
+        forward.push(cursor.current().copied());
+    }
+    assert_eq!(forward, [Some(1), Some(2), Some(3), None]);
+    let mut backward = Vec::new();
+    for _ in 0..4 {
+        cursor.move_prev();
+        backward.push(cursor.current().copied());
+    }
+    assert_eq!(backward, [Some(3), Some(2), Some(1), None]);
+}
+
+#[test]
+fn cursor_edits_in_the_middle() {
+    let mut queue = Queue::new();
+    (1..=5).for_each(|i| queue.push(i));
+    let mut cursor = queue.cursor_mut();
+    cursor.move_next();
+    cursor.move_next();
+    // On 2
+    cursor.insert_before(10);
+    cursor.insert_after(20);
+    *cursor.current().unwrap() *= 100;
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&mut 20));
+    cursor.move_next();
+    // Removing 3 moves on to 4
+    assert_eq!(cursor.remove_current(), Some(3));
+    assert_eq!(cursor.current(), Some(&mut 4));
+    assert_eq!(contents(&queue), [1, 10, 200, 20, 4, 5]);
+    assert_eq!(queue.len(), 6);
+}
+
+#[test]
+fn cursor_edits_at_the_ends() {
+    let mut queue = Queue::new();
+    let mut cursor = queue.cursor_mut();
+    // On the ghost: after is the front, before is the back
+    cursor.insert_after(2);
+    cursor.insert_after(1);
+    cursor.insert_before(3);
+    assert_eq!(cursor.remove_current(), None);
+    cursor.move_prev();
+    assert_eq!(cursor.remove_current(), Some(3));
+    // Removing the tail lands on the ghost
+    assert_eq!(cursor.current(), None);
+    cursor.move_next();
+    assert_eq!(cursor.remove_current(), Some(1));
+    assert_eq!(cursor.remove_current(), Some(2));
+    assert_eq!(cursor.current(), None);
+    assert!(queue.is_empty());
+    assert_eq!((queue.peek(), queue.peek_back()), (None, None));
+    queue.push(7);
+    assert_eq!(contents(&queue), [7]);
+}
+
+#[test]
+fn cursor_removal_frees_each_node_once() {
+    let drops = Rc::new(Cell::new(0));
+    let mut queue = Queue::new();
+    for id in 0..10 {
+        queue.push(tracked(id, &drops));
+    }
+    // Drop the odd ids on the way through
+    let mut cursor = queue.cursor_mut();
+    cursor.move_next();
+    while let Some(t) = cursor.current() {
+        if t.id % 2 == 1 {
+            drop(cursor.remove_current());
+        } else {
+            cursor.move_next();
+        }
+    }
+    assert_eq!(drops.get(), 5);
+    let ids: Vec<u32> = queue.iter().map(|t| t.id).collect();
+    assert_eq!(ids, [0, 2, 4, 6, 8]);
+    drop(queue);
+    assert_eq!(drops.get(), 10);
+}
+
+#[test]
+fn random_operations_match_vec_deque() {
+    let mut rng = Rng(0x2545_f491);
+    let mut queue = Queue::new();
+    let mut model = VecDeque::new();
+    for step in 0..STEPS as u32 {
+        match rng.below(6) {
+            0 => assert_eq!(queue.pop(), model.pop_front(), "step {}", step),
+            1 => assert_eq!(queue.pop_back(), model.pop_back(), "step {}", step),
+            2 => {
+                queue.push_front(step);
+                model.push_front(step);
+            }
+            _ => {
+                queue.push(step);
+                model.push_back(step);
+            }
+        }
+        assert_eq!(queue.len(), model.len());
+        assert_eq!(queue.peek(), model.front());
+        assert_eq!(queue.peek_back(), model.back());
+    }
+    assert!(queue.iter().eq(model.iter()));
+    assert!(queue.iter().rev().eq(model.iter().rev()));
+}
+
+#[test]
+fn random_cursor_edits_match_vec() {
+    let mut rng = Rng(0x9e37_79b9);
+    let mut queue = Queue::new();
+    let mut model: Vec<u32> = Vec::new();
+    // The cursor's index in the model; None on the ghost
+    let mut at: Option<usize> = None;
+    let mut cursor = queue.cursor_mut();
+    for step in 0..STEPS as u32 {
+        match rng.below(7) {
+            0 => {
+                cursor.move_next();
+                at = match at {
+                    None if model.is_empty() => None,
+                    None => Some(0),
+                    Some(i) if i + 1 == model.len() => None,
+                    Some(i) => Some(i + 1),
+                };
+            }
+            1 => {
+                cursor.move_prev();
+                at = match at {
+                    None => model.len().checked_sub(1),
+                    Some(0) => None,
+                    Some(i) => Some(i - 1),
+                };
+            }
+            2 => {
+                cursor.insert_after(step);
+                match at {
+                    None => model.insert(0, step),
+                    Some(i) => model.insert(i + 1, step),
+                }
+            }
+            3 => {
+                cursor.insert_before(step);
+                match at {
+                    None => model.push(step),
+                    Some(i) => {
+                        model.insert(i, step);
+                        at = Some(i + 1);
+                    }
+                }
+            }
+            4 => {
+                let expected = at.map(|i| model.remove(i));
+                assert_eq!(cursor.remove_current(), expected, "step {}", step);
+                at = at.filter(|&i| i < model.len());
+            }
+            5 => {
+                if let Some(value) = cursor.current() {
+                    *value += 1;
+                }
+                if let Some(i) = at {
+                    model[i] += 1;
+                }
+            }
+            _ => {}
+        }
+        let expected = at.map(|i| model[i]);
+        assert_eq!(cursor.current().copied(), expected, "step {}", step);
+    }
+    assert_eq!(queue.len(), model.len());
+    assert_eq!(contents(&queue), model);
+    assert!(queue.iter().rev().eq(model.iter().rev()));
+}
+
+#[test]
+fn send_when_t_is_send() {
+    let mut numbers = Queue::new();
+    (0..4).for_each(|i| numbers.push(i));
+    let from_thread = thread::spawn(move || {
+        numbers.push(4);
+        format!("{:?}", numbers)
+    })
+    .join()
+    .unwrap();
+    assert_eq!(from_thread, "[0, 1, 2, 3, 4]");
+}
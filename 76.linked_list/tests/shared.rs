@@ -0,0 +1,103 @@
+use linked_list::Deque;
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+// Miri interprets every instruction, so it gets fewer steps
+const STEPS: usize = if cfg!(miri) { 2_000 } else { 100_000 };
+
+// Counts its drops, to catch leaks
+struct Tracked {
+    id: u32,
+    drops: Rc<Cell<usize>>,
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+#[test]
+fn both_ends() {
+    let mut deque = Deque::new();
+    assert!(deque.is_empty());
+    deque.push_back(2);
+    deque.push_front(1);
+    deque.push_back(3);
+    assert_eq!(deque.len(), 3);
+    assert_eq!(*deque.peek_front().unwrap(), 1);
+    assert_eq!(*deque.peek_back().unwrap(), 3);
+    *deque.peek_back_mut().unwrap() = 30;
+    assert_eq!(deque.to_vec(), [1, 2, 30]);
+    assert_eq!(deque.pop_back(), Some(30));
+    assert_eq!(deque.pop_front(), Some(1));
+    assert_eq!(deque.pop_back(), Some(2));
+    assert_eq!(deque.pop_front(), None);
+    assert!(deque.peek_front().is_none() && deque.peek_back().is_none());
+}
+
+#[test]
+fn a_sliding_window_keeps_the_newest() {
+    let drops = Rc::new(Cell::new(0));
+    let mut window = Deque::new();
+    for id in 1..=8 {
+        window.push_back(Tracked {
+            id,
+            drops: Rc::clone(&drops),
+        });
+        if window.len() > 5 {
+            window.pop_front();
+        }
+    }
+    assert_eq!(window.peek_front().map(|t| t.id), Some(4));
+    assert_eq!(window.peek_back().map(|t| t.id), Some(8));
+    assert_eq!(drops.get(), 3);
+}
+
+#[test]
+fn weak_back_links_free_every_node() {
+    let drops = Rc::new(Cell::new(0));
+    let mut deque = Deque::new();
+    for id in 0..10 {
+        let t = Tracked {
+            id,
+            drops: Rc::clone(&drops),
+        };
+        if id % 2 == 0 {
+            deque.push_back(t);
+        } else {
+            deque.push_front(t);
+        }
+    }
+    drop(deque.pop_back());
+    assert_eq!(drops.get(), 1);
+    drop(deque);
+    assert_eq!(drops.get(), 10);
+}
+
+#[test]
+fn random_operations_match_vec_deque() {
+    let mut x = 0x2545_f491u32;
+    let mut deque = Deque::new();
+    let mut model = VecDeque::new();
+    for _ in 0..STEPS {
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        match x % 4 {
+            0 => assert_eq!(deque.pop_front(), model.pop_front()),
+            1 => assert_eq!(deque.pop_back(), model.pop_back()),
+            2 => {
+                deque.push_front(x);
+                model.push_front(x);
+            }
+            _ => {
+                deque.push_back(x);
+                model.push_back(x);
+            }
+        }
+        assert_eq!(deque.len(), model.len());
+    }
+    assert!(deque.to_vec().iter().eq(model.iter()));
+}
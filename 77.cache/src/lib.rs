@@ -0,0 +1,14 @@
+// LRU caching
+//
+// - `lru`: `LruCache`, a fixed-capacity map that evicts the least recently
+//   used entry, with hit/miss statistics
+// - `sync`: `SyncLruCache` (one lock) and `ShardedLruCache` (a lock per
+//   shard), for sharing a cache between threads through `&self`
+//
+// 46.grpc_device caches device metadata with `SyncLruCache`.
+
+pub mod lru;
+pub mod sync;
+
+pub use lru::{LruCache, Stats};
+pub use sync::{ShardedLruCache, SyncLruCache};
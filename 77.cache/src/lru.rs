@@ -0,0 +1,290 @@
+// Least-recently-used cache
+//
+//   map: key -> slot            entries (a Vec), linked by index:
+//   ┌─────────┐                 head (most recent)          tail (least)
+//   │ "th-2"  │──▶ 2            [2] ⇄ [0] ⇄ [1]
+//   │ "th-7"  │──▶ 0             │                           │
+//   │ "th-9"  │──▶ 1             get moves a slot to head    insert into a
+//   └─────────┘                                              full cache evicts tail
+//
+// The recency order is a doubly linked list, but its links are indices
+// into `entries` (as in 75.arena_tree) rather than `Rc` or raw pointers
+// (76.linked_list). Every operation is O(1): a hash lookup plus relinking
+// a couple of slots. An evicted entry's slot is reused by the insert that
+// evicted it, so a full cache stops allocating.
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+const NIL: usize = usize::MAX;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+    pub inserts: u64,
+    pub evictions: u64,
+}
+
+impl Stats {
+    pub fn lookups(&self) -> u64 {
+        self.hits + self.misses
+    }
+
+    // 0.0 before the first lookup
+    pub fn hit_rate(&self) -> f64 {
+        if self.lookups() == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.lookups() as f64
+        }
+    }
+
+    pub fn merge(&self, other: &Stats) -> Stats {
+        Stats {
+            hits: self.hits + other.hits,
+            misses: self.misses + other.misses,
+            inserts: self.inserts + other.inserts,
+            evictions: self.evictions + other.evictions,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry<K, V> {
+    key: K,
+    value: V,
+    prev: usize,
+    next: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct LruCache<K, V> {
+    map: HashMap<K, usize>,
+    entries: Vec<Entry<K, V>>,
+    head: usize,
+    tail: usize,
+    capacity: usize,
+    stats: Stats,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    // A capacity of 0 is treated as 1
+    pub fn new(capacity: usize) -> LruCache<K, V> {
+        let capacity = capacity.max(1);
+        LruCache {
+            map: HashMap::with_capacity(capacity),
+            entries: Vec::with_capacity(capacity),
+            head: NIL,
+            tail: NIL,
+            capacity,
+            stats: Stats::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = Stats::default();
+    }
+
+    fn unlink(&mut self, slot: usize) {
+        let (prev, next) = (self.entries[slot].prev, self.entries[slot].next);
+        match prev {
+            NIL => self.head = next,
+            p => self.entries[p].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            n => self.entries[n].prev = prev,
+        }
+    }
+
+    fn push_front(&mut self, slot: usize) {
+        self.entries[slot].prev = NIL;
+        self.entries[slot].next = self.head;
+        match self.head {
+            NIL => self.tail = slot,
+            h => self.entries[h].prev = slot,
+        }
+        self.head = slot;
+    }
+
+    fn touch(&mut self, slot: usize) {
+        if self.head != slot {
+            self.unlink(slot);
+            self.push_front(slot);
+        }
+    }
+
+    // Counts a hit or a miss and makes a hit the most recent entry
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_mut(key).map(|v| &*v)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.map.get(key) {
+            Some(&slot) => {
+                self.stats.hits += 1;
+                self.touch(slot);
+                Some(&mut self.entries[slot].value)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    // Looks without counting or changing the order
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(key).map(|&slot| &self.entries[slot].value)
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    // Inserts or replaces `key` as the most recent entry. Returns the entry
+    // evicted to make room, if the cache was full.
+    pub fn insert(&mut self, key: K, value: V) -> Option<(K, V)> {
+        self.stats.inserts += 1;
+        if let Some(&slot) = self.map.get(&key) {
+            self.entries[slot].value = value;
+            self.touch(slot);
+            return None;
+        }
+        if self.map.len() < self.capacity {
+            self.entries.push(Entry {
+                key: key.clone(),
+                value,
+                prev: NIL,
+                next: NIL,
+            });
+            let slot = self.entries.len() - 1;
+            self.map.insert(key, slot);
+            self.push_front(slot);
+            return None;
+        }
+        // Full: the least recent slot takes the new entry
+        let slot = self.tail;
+        self.unlink(slot);
+        let old = std::mem::replace(
+            &mut self.entries[slot],
+            Entry {
+                key: key.clone(),
+                value,
+                prev: NIL,
+                next: NIL,
+            },
+        );
+        self.map.remove(&old.key);
+        self.map.insert(key, slot);
+        self.push_front(slot);
+        self.stats.evictions += 1;
+        Some((old.key, old.value))
+    }
+
+    // The cached value, or the one `make` produces (cached from now on)
+    pub fn get_or_insert_with(&mut self, key: K, make: impl FnOnce() -> V) -> &V {
+        self.try_get_or_insert_with(key, || Ok::<V, std::convert::Infallible>(make()))
+            .unwrap_or_else(|never| match never {})
+    }
+
+    // Like `get_or_insert_with`; an error is returned and nothing is cached
+    pub fn try_get_or_insert_with<E>(
+        &mut self,
+        key: K,
+        make: impl FnOnce() -> Result<V, E>,
+    ) -> Result<&V, E> {
+        let slot = match self.map.get(&key) {
+            Some(&slot) => {
+                self.stats.hits += 1;
+                self.touch(slot);
+                slot
+            }
+            None => {
+                self.stats.misses += 1;
+                let value = make()?;
+                self.insert(key.clone(), value);
+                self.map[&key]
+            }
+        };
+        Ok(&self.entries[slot].value)
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let slot = self.map.remove(key)?;
+        self.unlink(slot);
+        // Keep `entries` dense: move the last entry into the hole
+        let last = self.entries.len() - 1;
+        if slot != last {
+            let (prev, next) = (self.entries[last].prev, self.entries[last].next);
+            match prev {
+                NIL => self.head = slot,
+                p => self.entries[p].next = slot,
+            }
+            match next {
+                NIL => self.tail = slot,
+                n => self.entries[n].prev = slot,
+            }
+            *self
+                .map
+                .get_mut::<K>(&self.entries[last].key)
+                .expect("every entry is mapped") = slot;
+        }
+        Some(self.entries.swap_remove(slot).value)
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.entries.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+
+    // Most recent first
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        let mut slot = self.head;
+        std::iter::from_fn(move || {
+            let entry = self.entries.get(slot)?;
+            slot = entry.next;
+            Some((&entry.key, &entry.value))
+        })
+    }
+}
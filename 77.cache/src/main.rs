@@ -0,0 +1,212 @@
+use cache::{LruCache, ShardedLruCache, SyncLruCache};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+const LOOKUPS: usize = 20_000;
+const THREADS: usize = 8;
+
+// xorshift32, so every run sees the same keys
+fn random(seed: u32, n: usize) -> Vec<u32> {
+    let mut x = seed;
+    (0..n)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        })
+        .collect()
+}
+
+// Device lookups are skewed: a few busy devices and a long tail. Cubing a
+// uniform fraction puts most picks near device 0.
+fn skewed(seed: u32, n: usize, devices: u32) -> Vec<u32> {
+    random(seed, n)
+        .into_iter()
+        .map(|r| {
+            let f = r as f64 / u32::MAX as f64;
+            (f * f * f * devices as f64) as u32
+        })
+        .collect()
+}
+
+fn main() {
+    println!("=== LRU Cache Examples ===\n");
+
+    // 1. Recency order and eviction
+    println!("1. Capacity 3, firmware manifests by version:");
+    let mut manifests: LruCache<&str, u32> = LruCache::new(3);
+    manifests.insert("1.2.0", 120);
+    manifests.insert("1.3.1", 131);
+    manifests.insert("1.4.2", 142);
+    let hit = manifests.get("1.2.0").copied();
+    let evicted = manifests.insert("2.0.0", 200);
+    let order: Vec<&str> = manifests.iter().map(|(k, _)| *k).collect();
+    println!("   get 1.2.0 -> {:?}, then insert 2.0.0", hit);
+    println!("   evicted {:?}, most recent first: {:?}", evicted, order);
+    let peeked = manifests.peek("1.4.2").copied();
+    let still_last = manifests.iter().last().map(|(k, _)| *k);
+    println!(
+        "   peek 1.4.2 -> {:?}, least recent still {:?}",
+        peeked, still_last
+    );
+    let removed = manifests.remove("1.2.0");
+    manifests.insert("2.0.1", 201);
+    let order: Vec<&str> = manifests.iter().map(|(k, _)| *k).collect();
+    println!(
+        "   removed 1.2.0 ({:?}), inserted 2.0.1: {:?}",
+        removed, order
+    );
+    println!("   evictions so far: {}", manifests.stats().evictions);
+
+    // 2. get_or_insert_with
+    println!("\n2. get_or_insert_with in front of a slow lookup:");
+    let fetches = Cell::new(0);
+    let fetch = |device: u32| {
+        fetches.set(fetches.get() + 1);
+        format!("th-sensor #{} fw 1.4.2", device)
+    };
+    let mut metadata: LruCache<u32, String> = LruCache::new(16);
+    for &device in &[7, 9, 7, 7, 12, 9] {
+        let value = metadata.get_or_insert_with(device, || fetch(device));
+        println!("   device {:>2}: {}", device, value);
+    }
+    let stats = metadata.stats();
+    println!(
+        "   {} hits, {} misses, {} fetches",
+        stats.hits,
+        stats.misses,
+        fetches.get()
+    );
+    let failed: Result<&String, String> =
+        metadata.try_get_or_insert_with(404, || Err("device 404 not in catalog".to_string()));
+    println!("   try_get_or_insert_with(404) -> {:?}", failed);
+    println!(
+        "   cached 404: {}, {} entries",
+        metadata.contains(&404),
+        metadata.len()
+    );
+
+    // 3. Hit rate against capacity
+    println!(
+        "\n3. Hit rate for {} skewed lookups over 1000 devices:",
+        LOOKUPS
+    );
+    let keys = skewed(0x2545_f491, LOOKUPS, 1000);
+    for capacity in [10, 50, 100, 250, 1000] {
+        let mut cache: LruCache<u32, u32> = LruCache::new(capacity);
+        for &key in &keys {
+            cache.get_or_insert_with(key, || key * 2);
+        }
+        let stats = cache.stats();
+        println!(
+            "   capacity {:>4}: hit rate {:>5.1}%, {:>5} evictions",
+            capacity,
+            stats.hit_rate() * 100.0,
+            stats.evictions
+        );
+    }
+    // With room for every device, only the first lookup of each misses
+    let mut distinct = keys.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+    println!(
+        "   {} distinct devices, so at most {:.1}%",
+        distinct.len(),
+        (1.0 - distinct.len() as f64 / LOOKUPS as f64) * 100.0
+    );
+
+    // 4. Sharing between threads
+    println!("\n4. {} threads, {} lookups each:", THREADS, LOOKUPS);
+    let fetches = Arc::new(AtomicU64::new(0));
+    // Cheap on purpose: with most lookups hitting, the time measured is
+    // the time spent waiting for locks
+    let fetch_metadata = {
+        let fetches = Arc::clone(&fetches);
+        move |device: u32| {
+            fetches.fetch_add(1, Ordering::Relaxed);
+            Arc::new(format!("metadata for device {}", device))
+        }
+    };
+
+    // Keys are drawn up front so only the lookups are timed
+    let per_thread: Vec<Arc<Vec<u32>>> = (0..THREADS as u32)
+        .map(|t| Arc::new(skewed(0x9e37_79b9 ^ t, LOOKUPS, 1000)))
+        .collect();
+    let single: Arc<SyncLruCache<u32, Arc<String>>> = Arc::new(SyncLruCache::new(250));
+    let started = Instant::now();
+    let workers: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let cache = Arc::clone(&single);
+            let fetch = fetch_metadata.clone();
+            let keys = Arc::clone(&per_thread[t]);
+            thread::spawn(move || {
+                for &key in keys.iter() {
+                    cache.get_or_insert_with(key, || fetch(key));
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    let single_time = started.elapsed();
+    let single_stats = single.stats();
+    let single_fetches = fetches.swap(0, Ordering::Relaxed);
+
+    let sharded: Arc<ShardedLruCache<u32, Arc<String>>> = Arc::new(ShardedLruCache::new(250, 8));
+    let started = Instant::now();
+    let workers: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let cache = Arc::clone(&sharded);
+            let fetch = fetch_metadata.clone();
+            let keys = Arc::clone(&per_thread[t]);
+            thread::spawn(move || {
+                for &key in keys.iter() {
+                    cache.get_or_insert_with(key, || fetch(key));
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    let sharded_time = started.elapsed();
+    let sharded_stats = sharded.stats();
+    let sharded_fetches = fetches.load(Ordering::Relaxed);
+
+    println!(
+        "   one lock:  hit rate {:>5.1}%, {:>5} fetches, {:.2?}",
+        single_stats.hit_rate() * 100.0,
+        single_fetches,
+        single_time
+    );
+    println!(
+        "   {} shards:  hit rate {:>5.1}%, {:>5} fetches, {:.2?}",
+        sharded.shards(),
+        sharded_stats.hit_rate() * 100.0,
+        sharded_fetches,
+        sharded_time
+    );
+    // Sharding pays off only when threads really run at the same time
+    let cores = thread::available_parallelism().map_or(1, |n| n.get());
+    println!("   on {} core(s)", cores);
+    println!(
+        "   lookups counted: {} and {} of {}",
+        single_stats.lookups(),
+        sharded_stats.lookups(),
+        THREADS * LOOKUPS
+    );
+    // 250 over 8 shards rounds up to 32 each
+    println!(
+        "   entries held: {} of 250, {} of {}",
+        single.len(),
+        sharded.len(),
+        sharded.shards() * 32
+    );
+
+    println!("\n=== End of LRU Cache Examples ===");
+}
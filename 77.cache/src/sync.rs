@@ -0,0 +1,166 @@
+// Sharing a cache between threads
+//
+// Even a lookup changes an LRU cache (the entry moves to the front and the
+// stats count it), so a shared cache needs `&mut` access on every call.
+// Two wrappers give that through `&self`:
+//
+//   SyncLruCache     one Mutex around one LruCache; simple, but every
+//                    thread waits on the same lock
+//   ShardedLruCache  N caches, each behind its own Mutex; a key's hash
+//                    picks its shard, so threads looking up different
+//                    keys rarely wait on each other
+//
+// Values are cloned out, since a reference can't outlive the lock guard.
+// Cache `Arc<V>` when V is large.
+//
+// `get_or_insert_with` runs `make` without holding the lock. Otherwise one
+// slow fetch would block every other thread, hits included. The cost is
+// that two threads missing the same key at once may both run `make`; the
+// second insert just replaces the first.
+
+use crate::lru::{LruCache, Stats};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(Debug)]
+pub struct SyncLruCache<K, V> {
+    inner: Mutex<LruCache<K, V>>,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> SyncLruCache<K, V> {
+    pub fn new(capacity: usize) -> SyncLruCache<K, V> {
+        SyncLruCache {
+            inner: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    // A panic while the lock was held can't leave the cache half updated
+    // (each method finishes its relinking before it returns), so a
+    // poisoned lock is still safe to use
+    fn lock(&self) -> MutexGuard<'_, LruCache<K, V>> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.lock().get(key).cloned()
+    }
+
+    pub fn insert(&self, key: K, value: V) -> Option<(K, V)> {
+        self.lock().insert(key, value)
+    }
+
+    pub fn remove(&self, key: &K) -> Option<V> {
+        self.lock().remove(key)
+    }
+
+    pub fn get_or_insert_with(&self, key: K, make: impl FnOnce() -> V) -> V {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+        let value = make();
+        self.insert(key, value.clone());
+        value
+    }
+
+    pub fn try_get_or_insert_with<E>(
+        &self,
+        key: K,
+        make: impl FnOnce() -> Result<V, E>,
+    ) -> Result<V, E> {
+        if let Some(value) = self.get(&key) {
+            return Ok(value);
+        }
+        let value = make()?;
+        self.insert(key, value.clone());
+        Ok(value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.lock().capacity()
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.lock().stats()
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear()
+    }
+}
+
+#[derive(Debug)]
+pub struct ShardedLruCache<K, V> {
+    shards: Vec<SyncLruCache<K, V>>,
+    hasher: RandomState,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardedLruCache<K, V> {
+    // `capacity` is split evenly, so each shard evicts on its own: the
+    // entry evicted is the least recent in its shard, not overall
+    pub fn new(capacity: usize, shards: usize) -> ShardedLruCache<K, V> {
+        let shards = shards.max(1);
+        let per_shard = capacity.div_ceil(shards);
+        ShardedLruCache {
+            shards: (0..shards).map(|_| SyncLruCache::new(per_shard)).collect(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    fn shard(&self, key: &K) -> &SyncLruCache<K, V> {
+        let index = self.hasher.hash_one(key) as usize % self.shards.len();
+        &self.shards[index]
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.shard(key).get(key)
+    }
+
+    pub fn insert(&self, key: K, value: V) -> Option<(K, V)> {
+        self.shard(&key).insert(key, value)
+    }
+
+    pub fn remove(&self, key: &K) -> Option<V> {
+        self.shard(key).remove(key)
+    }
+
+    pub fn get_or_insert_with(&self, key: K, make: impl FnOnce() -> V) -> V {
+        self.shard(&key).get_or_insert_with(key, make)
+    }
+
+    pub fn try_get_or_insert_with<E>(
+        &self,
+        key: K,
+        make: impl FnOnce() -> Result<V, E>,
+    ) -> Result<V, E> {
+        self.shard(&key).try_get_or_insert_with(key, make)
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(SyncLruCache::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(SyncLruCache::is_empty)
+    }
+
+    pub fn shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.shards
+            .iter()
+            .fold(Stats::default(), |total, shard| total.merge(&shard.stats()))
+    }
+}
@@ -0,0 +1,343 @@
+use cache::{LruCache, Stats};
+
+// xorshift32, so every run sees the same operations
+struct Rng(u32);
+
+impl Rng {
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+}
+
+// The obvious LRU: a Vec in recency order, O(n) per operation
+struct SlowLru {
+    entries: Vec<(u32, u32)>,
+    capacity: usize,
+}
+
+impl SlowLru {
+    fn get(&mut self, key: u32) -> Option<u32> {
+        let i = self.entries.iter().position(|&(k, _)| k == key)?;
+        let entry = self.entries.remove(i);
+        self.entries.insert(0, entry);
+        Some(entry.1)
+    }
+
+    fn insert(&mut self, key: u32, value: u32) -> Option<(u32, u32)> {
+        if let Some(i) = self.entries.iter().position(|&(k, _)| k == key) {
+            self.entries.remove(i);
+        }
+        self.entries.insert(0, (key, value));
+        if self.entries.len() > self.capacity {
+            self.entries.pop()
+        } else {
+            None
+        }
+    }
+
+    fn remove(&mut self, key: u32) -> Option<u32> {
+        let i = self.entries.iter().position(|&(k, _)| k == key)?;
+        Some(self.entries.remove(i).1)
+    }
+}
+
+fn keys<V>(cache: &LruCache<&'static str, V>) -> Vec<&'static str> {
+    cache.iter().map(|(k, _)| *k).collect()
+}
+
+// Filling the cache with new keys evicts from the tail: the evictions
+// come out in reverse recency order, which checks the `prev` links that
+// `iter` never follows
+fn drain_by_eviction(cache: &mut LruCache<&'static str, u32>) -> Vec<&'static str> {
+    const FRESH: [&str; 8] = ["n0", "n1", "n2", "n3", "n4", "n5", "n6", "n7"];
+    let mut evicted = Vec::new();
+    for (i, key) in FRESH.into_iter().enumerate().take(cache.capacity()) {
+        if let Some((old, _)) = cache.insert(key, i as u32) {
+            evicted.push(old);
+        }
+    }
+    evicted
+}
+
+// a, b, c, d inserted in order (slots 0..=3), then `a` touched:
+// recency a, d, c, b and `d` in the last slot
+fn four() -> LruCache<&'static str, u32> {
+    let mut cache = LruCache::new(4);
+    for (i, key) in ["a", "b", "c", "d"].into_iter().enumerate() {
+        cache.insert(key, i as u32);
+    }
+    cache.get("a");
+    assert_eq!(keys(&cache), ["a", "d", "c", "b"]);
+    cache
+}
+
+#[test]
+fn removing_the_head_relinks_the_moved_last_slot() {
+    // `a` is the head in slot 0; `d` moves from the last slot into it
+    let mut cache = four();
+    assert_eq!(cache.remove("a"), Some(0));
+    assert_eq!(keys(&cache), ["d", "c", "b"]);
+    assert_eq!(cache.peek("d"), Some(&3));
+    cache.insert("e", 4);
+    assert_eq!(keys(&cache), ["e", "d", "c", "b"]);
+    assert_eq!(drain_by_eviction(&mut cache), ["b", "c", "d", "e"]);
+}
+
+#[test]
+fn removing_the_tail_moves_the_tail() {
+    let mut cache = four();
+    assert_eq!(cache.remove("b"), Some(1));
+    assert_eq!(keys(&cache), ["a", "d", "c"]);
+    cache.get("c");
+    assert_eq!(keys(&cache), ["c", "a", "d"]);
+    assert_eq!(drain_by_eviction(&mut cache), ["d", "a", "c"]);
+}
+
+#[test]
+fn removing_a_middle_entry_joins_its_neighbours() {
+    let mut cache = four();
+    assert_eq!(cache.remove("c"), Some(2));
+    assert_eq!(keys(&cache), ["a", "d", "b"]);
+    assert_eq!(drain_by_eviction(&mut cache), ["b", "d", "a"]);
+}
+
+#[test]
+fn removing_the_entry_in_the_last_slot_moves_nothing() {
+    let mut cache = four();
+    assert_eq!(cache.remove("d"), Some(3));
+    assert_eq!(keys(&cache), ["a", "c", "b"]);
+    assert_eq!(drain_by_eviction(&mut cache), ["b", "c", "a"]);
+}
+
+#[test]
+fn the_moved_slot_can_be_the_tail() {
+    // a, b and c touched after the inserts leave `d`, in the last slot,
+    // as the tail
+    let mut cache = LruCache::new(4);
+    for (i, key) in ["a", "b", "c", "d"].into_iter().enumerate() {
+        cache.insert(key, i as u32);
+    }
+    cache.get("a");
+    cache.get("b");
+    cache.get("c");
+    assert_eq!(keys(&cache), ["c", "b", "a", "d"]);
+    // `d` is the tail and in the last slot; `b` in slot 1 is removed
+    assert_eq!(cache.remove("b"), Some(1));
+    assert_eq!(keys(&cache), ["c", "a", "d"]);
+    assert_eq!(drain_by_eviction(&mut cache), ["d", "a", "c"]);
+}
+
+#[test]
+fn removing_every_entry_leaves_an_empty_cache_that_works() {
+    let mut cache = four();
+    for key in ["d", "a", "b", "c"] {
+        assert!(cache.remove(key).is_some(), "{}", key);
+    }
+    assert!(cache.is_empty());
+    assert_eq!(cache.iter().count(), 0);
+    assert_eq!(cache.remove("a"), None);
+    cache.insert("z", 26);
+    assert_eq!(keys(&cache), ["z"]);
+}
+
+#[test]
+fn get_protects_an_entry_from_eviction() {
+    let mut cache = LruCache::new(3);
+    cache.insert("1.2.0", 120);
+    cache.insert("1.3.1", 131);
+    cache.insert("1.4.2", 142);
+    assert_eq!(cache.get("1.2.0"), Some(&120));
+    assert_eq!(cache.insert("2.0.0", 200), Some(("1.3.1", 131)));
+    assert_eq!(keys(&cache), ["2.0.0", "1.2.0", "1.4.2"]);
+    // Then the next least recent, one per insert
+    assert_eq!(cache.insert("2.0.1", 201), Some(("1.4.2", 142)));
+    assert_eq!(cache.insert("2.0.2", 202), Some(("1.2.0", 120)));
+    assert_eq!(cache.stats().evictions, 3);
+}
+
+#[test]
+fn peek_and_contains_change_nothing() {
+    let mut cache = four();
+    let before = cache.stats();
+    assert_eq!(cache.peek("b"), Some(&1));
+    assert!(cache.contains("b"));
+    assert!(!cache.contains("x"));
+    assert_eq!(cache.stats(), before);
+    // `b` is still the one to go
+    assert_eq!(cache.insert("e", 4), Some(("b", 1)));
+}
+
+#[test]
+fn replacing_a_value_makes_it_most_recent_without_evicting() {
+    let mut cache = four();
+    assert_eq!(cache.insert("b", 10), None);
+    assert_eq!(keys(&cache), ["b", "a", "d", "c"]);
+    assert_eq!(cache.peek("b"), Some(&10));
+    assert_eq!(cache.len(), 4);
+    assert_eq!(cache.stats().evictions, 0);
+}
+
+#[test]
+fn evicted_slots_are_reused_in_place() {
+    let mut cache: LruCache<u32, u32> = LruCache::new(8);
+    for key in 0..1000 {
+        cache.insert(key, key * 2);
+        assert!(cache.len() <= 8);
+    }
+    let left: Vec<(u32, u32)> = cache.iter().map(|(&k, &v)| (k, v)).collect();
+    let expected: Vec<(u32, u32)> = (992..1000).rev().map(|k| (k, k * 2)).collect();
+    assert_eq!(left, expected);
+    assert_eq!(cache.stats().evictions, 992);
+}
+
+#[test]
+fn get_or_insert_with_makes_a_value_only_on_a_miss() {
+    let mut cache: LruCache<u32, String> = LruCache::new(16);
+    let mut made = 0;
+    for device in [7, 9, 7, 7, 12, 9] {
+        let value = cache.get_or_insert_with(device, || {
+            made += 1;
+            format!("th-sensor #{}", device)
+        });
+        assert_eq!(*value, format!("th-sensor #{}", device));
+    }
+    assert_eq!(made, 3);
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses, stats.inserts), (3, 3, 3));
+}
+
+#[test]
+fn a_failed_make_caches_nothing_and_evicts_nothing() {
+    let mut cache: LruCache<u32, String> = LruCache::new(2);
+    cache.insert(1, "one".to_string());
+    cache.insert(2, "two".to_string());
+    let before = cache.stats();
+
+    let failed: Result<&String, String> =
+        cache.try_get_or_insert_with(404, || Err("device 404 not in catalog".to_string()));
+    assert_eq!(failed, Err("device 404 not in catalog".to_string()));
+    assert!(!cache.contains(&404));
+    assert_eq!(cache.len(), 2);
+    let after = cache.stats();
+    assert_eq!(after.misses, before.misses + 1);
+    assert_eq!(after.inserts, before.inserts);
+    assert_eq!(after.evictions, 0);
+    // Nor was the order touched: 1 is still the one to go
+    let order: Vec<u32> = cache.iter().map(|(&k, _)| k).collect();
+    assert_eq!(order, [2, 1]);
+
+    // A hit never calls `make`
+    let hit: Result<&String, String> =
+        cache.try_get_or_insert_with(1, || panic!("called on a hit"));
+    assert_eq!(hit.map(String::as_str), Ok("one"));
+}
+
+#[test]
+fn a_capacity_of_zero_holds_one() {
+    let mut cache = LruCache::new(0);
+    assert_eq!(cache.capacity(), 1);
+    cache.insert("a", 1);
+    assert_eq!(cache.insert("b", 2), Some(("a", 1)));
+    assert_eq!(keys(&cache), ["b"]);
+}
+
+#[test]
+fn clear_empties_but_keeps_the_stats() {
+    let mut cache = four();
+    cache.clear();
+    assert!(cache.is_empty());
+    assert_eq!(cache.iter().count(), 0);
+    assert_eq!(cache.stats().inserts, 4);
+    cache.reset_stats();
+    assert_eq!(cache.stats(), Stats::default());
+    cache.insert("x", 1);
+    assert_eq!(keys(&cache), ["x"]);
+}
+
+#[test]
+fn stats_merge_and_hit_rate() {
+    let a = Stats {
+        hits: 3,
+        misses: 1,
+        inserts: 1,
+        evictions: 0,
+    };
+    let b = Stats {
+        hits: 1,
+        misses: 3,
+        inserts: 3,
+        evictions: 2,
+    };
+    let total = a.merge(&b);
+    assert_eq!(
+        total,
+        Stats {
+            hits: 4,
+            misses: 4,
+            inserts: 4,
+            evictions: 2,
+        }
+    );
+    assert_eq!(total.lookups(), 8);
+    assert_eq!(total.hit_rate(), 0.5);
+    assert_eq!(a.hit_rate(), 0.75);
+    assert_eq!(Stats::default().hit_rate(), 0.0);
+}
+
+// A few busy devices and a long tail, as in main.rs
+#[test]
+fn a_bigger_cache_never_hits_less() {
+    let mut rng = Rng(0x2545_f491);
+    let keys: Vec<u32> = (0..20_000)
+        .map(|_| {
+            let f = rng.next() as f64 / u32::MAX as f64;
+            (f * f * f * 1000.0) as u32
+        })
+        .collect();
+    let rates: Vec<f64> = [10, 50, 100, 250, 1000]
+        .into_iter()
+        .map(|capacity| {
+            let mut cache: LruCache<u32, u32> = LruCache::new(capacity);
+            for &key in &keys {
+                cache.get_or_insert_with(key, || key * 2);
+            }
+            cache.stats().hit_rate()
+        })
+        .collect();
+    assert!(rates.windows(2).all(|w| w[0] <= w[1]), "{:?}", rates);
+    // With room for every device, only the first lookup of each misses
+    let mut distinct = keys.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+    assert_eq!(rates[4], 1.0 - distinct.len() as f64 / keys.len() as f64);
+}
+
+// Random gets, inserts and removes against the O(n) model, with few keys
+// and a small capacity so every removal and eviction path comes up often
+#[test]
+fn random_scripts_match_a_vec_model() {
+    let mut rng = Rng(0x1234_5678);
+    for capacity in [1, 2, 3, 5, 8, 32] {
+        let mut fast: LruCache<u32, u32> = LruCache::new(capacity);
+        let mut slow = SlowLru {
+            entries: Vec::new(),
+            capacity,
+        };
+        for step in 0..20_000 {
+            let op = rng.next();
+            let key = (op >> 8) % (2 * capacity as u32 + 2);
+            let at = format!("capacity {} step {} key {}", capacity, step, key);
+            match op % 8 {
+                0..=2 => assert_eq!(fast.get(&key).copied(), slow.get(key), "{}", at),
+                3..=5 => assert_eq!(fast.insert(key, op), slow.insert(key, op), "{}", at),
+                _ => assert_eq!(fast.remove(&key), slow.remove(key), "{}", at),
+            }
+            assert_eq!(fast.len(), slow.entries.len(), "{}", at);
+        }
+        let entries: Vec<(u32, u32)> = fast.iter().map(|(&k, &v)| (k, v)).collect();
+        assert_eq!(entries, slow.entries, "capacity {}", capacity);
+    }
+}
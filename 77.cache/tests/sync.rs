@@ -0,0 +1,151 @@
+use cache::{ShardedLruCache, Stats, SyncLruCache};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+const THREADS: usize = 8;
+
+// xorshift32 per thread, so every run sees the same keys
+fn keys(seed: u32, n: usize, devices: u32) -> Vec<u32> {
+    let mut x = seed;
+    (0..n)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x % devices
+        })
+        .collect()
+}
+
+#[test]
+fn shard_stats_add_up_to_the_operations() {
+    // 8 shards of 100: 100 keys fit wherever they hash, so nothing is evicted
+    let cache: ShardedLruCache<u32, u32> = ShardedLruCache::new(800, 8);
+    assert_eq!(cache.shards(), 8);
+    for key in 0..100 {
+        cache.insert(key, key * 2);
+    }
+    for key in 0..150 {
+        assert_eq!(cache.get(&key), (key < 100).then_some(key * 2));
+    }
+    for key in 90..110 {
+        cache.get_or_insert_with(key, || key * 2);
+    }
+    assert_eq!(cache.len(), 110);
+    assert_eq!(
+        cache.stats(),
+        Stats {
+            hits: 100 + 10,
+            misses: 50 + 10,
+            inserts: 100 + 10,
+            evictions: 0,
+        }
+    );
+}
+
+#[test]
+fn shard_evictions_are_counted_where_they_happen() {
+    // One entry per shard: whichever shards the keys land in, every insert
+    // past the first in a shard evicts
+    let cache: ShardedLruCache<u32, u32> = ShardedLruCache::new(8, 8);
+    for key in 0..100 {
+        cache.insert(key, key);
+    }
+    let stats = cache.stats();
+    assert!(cache.len() <= 8);
+    assert_eq!(stats.inserts, 100);
+    assert_eq!(stats.evictions, 100 - cache.len() as u64);
+}
+
+#[test]
+fn a_failed_make_caches_nothing_behind_a_lock() {
+    let single: SyncLruCache<u32, String> = SyncLruCache::new(4);
+    let sharded: ShardedLruCache<u32, String> = ShardedLruCache::new(4, 2);
+    let failed = single.try_get_or_insert_with(404, || Err("not in catalog"));
+    assert_eq!(failed, Err("not in catalog"));
+    let failed = sharded.try_get_or_insert_with(404, || Err("not in catalog"));
+    assert_eq!(failed, Err("not in catalog"));
+    assert!(single.is_empty() && sharded.is_empty());
+    assert_eq!(single.get(&404), None);
+    assert_eq!(sharded.get(&404), None);
+    // The failed lookup and the get both missed, and nothing went in
+    for stats in [single.stats(), sharded.stats()] {
+        assert_eq!((stats.misses, stats.inserts), (2, 0));
+    }
+
+    let made: Result<String, &str> = sharded.try_get_or_insert_with(7, || Ok("seven".into()));
+    assert_eq!(made.as_deref(), Ok("seven"));
+    let hit: Result<String, &str> = sharded.try_get_or_insert_with(7, || Err("called on a hit"));
+    assert_eq!(hit.as_deref(), Ok("seven"));
+}
+
+#[test]
+fn the_sync_cache_clones_values_out() {
+    let cache: SyncLruCache<&str, Vec<u8>> = SyncLruCache::new(2);
+    cache.insert("a", vec![1]);
+    let mut copy = cache.get(&"a").unwrap();
+    copy.push(2);
+    assert_eq!(cache.get(&"a"), Some(vec![1]));
+    assert_eq!(cache.insert("b", vec![2]), None);
+    assert_eq!(cache.insert("c", vec![3]), Some(("a", vec![1])));
+    assert_eq!(cache.remove(&"b"), Some(vec![2]));
+    assert_eq!(cache.len(), 1);
+    cache.clear();
+    assert!(cache.is_empty());
+    assert_eq!(cache.capacity(), 2);
+}
+
+// Eight threads on each wrapper: every lookup lands in exactly one
+// counter, and the caches never hold more than their capacity
+#[test]
+fn threads_count_every_lookup_once() {
+    let lookups = 20_000;
+    let per_thread: Vec<Arc<Vec<u32>>> = (0..THREADS as u32)
+        .map(|t| Arc::new(keys(0x9e37_79b9 ^ t, lookups, 1000)))
+        .collect();
+    let distinct: HashSet<u32> = per_thread.iter().flat_map(|k| k.iter().copied()).collect();
+
+    let single: Arc<SyncLruCache<u32, u32>> = Arc::new(SyncLruCache::new(250));
+    let sharded: Arc<ShardedLruCache<u32, u32>> = Arc::new(ShardedLruCache::new(250, 8));
+    let fetches = [Arc::new(AtomicU64::new(0)), Arc::new(AtomicU64::new(0))];
+    let workers: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let (single, sharded) = (Arc::clone(&single), Arc::clone(&sharded));
+            let fetches = fetches.clone();
+            let keys = Arc::clone(&per_thread[t]);
+            thread::spawn(move || {
+                for &key in keys.iter() {
+                    let a = single.get_or_insert_with(key, || {
+                        fetches[0].fetch_add(1, Ordering::Relaxed);
+                        key * 2
+                    });
+                    let b = sharded.get_or_insert_with(key, || {
+                        fetches[1].fetch_add(1, Ordering::Relaxed);
+                        key * 2
+                    });
+                    assert_eq!((a, b), (key * 2, key * 2));
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+
+    let total = (THREADS * lookups) as u64;
+    for (stats, fetches) in [
+        (single.stats(), fetches[0].load(Ordering::Relaxed)),
+        (sharded.stats(), fetches[1].load(Ordering::Relaxed)),
+    ] {
+        assert_eq!(stats.lookups(), total);
+        // Every miss runs `make` once, even when two threads miss the same
+        // key together, and every device misses at least once
+        assert_eq!(fetches, stats.misses);
+        assert!(stats.misses >= distinct.len() as u64);
+    }
+    assert!(single.len() <= 250);
+    // 250 over 8 shards rounds up to 32 each
+    assert!(sharded.len() <= 256);
+}
@@ -0,0 +1,78 @@
+// Duplicate suppression over a stream of message ids
+//
+// A single filter fills up: after `capacity` ids its false-positive rate
+// climbs past the target and it starts dropping new messages. A stream
+// never ends, but duplicates arrive close to the original (a resend after
+// a reconnect, the same frame heard by two receivers), so only recent ids
+// need remembering. `Dedup` keeps two filters:
+//
+//   current   receives every id; once it holds `window` ids it becomes...
+//   previous  ...this one, and the old previous is cleared to be current
+//
+// An id is remembered for between `window` and `2 * window` further ids.
+// A duplicate older than that passes as new.
+//
+// Every check consults both filters, so a new id can be a false positive
+// in either. Each filter is therefore built for half the requested rate,
+// which keeps the combined rate at or below it.
+
+use crate::filter::{BloomError, BloomFilter};
+use std::hash::Hash;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DedupStats {
+    pub checked: u64,
+    pub duplicates: u64,
+    pub rotations: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Dedup {
+    current: BloomFilter,
+    previous: BloomFilter,
+    window: usize,
+    stats: DedupStats,
+}
+
+impl Dedup {
+    pub fn new(window: usize, fp_rate: f64) -> Result<Dedup, BloomError> {
+        let current = BloomFilter::new(window, fp_rate / 2.0)?;
+        Ok(Dedup {
+            previous: current.clone(),
+            current,
+            window,
+            stats: DedupStats::default(),
+        })
+    }
+
+    // True the first time an id is seen (forward the message), false for a
+    // duplicate, or for a new id that hit a false positive
+    pub fn check<T: Hash + ?Sized>(&mut self, id: &T) -> bool {
+        self.stats.checked += 1;
+        // Insert even when `previous` has it, so a busy id stays remembered
+        // after the next rotation
+        let new_here = self.current.insert(id);
+        let first = new_here && !self.previous.contains(id);
+        if !first {
+            self.stats.duplicates += 1;
+        }
+        if self.current.len() >= self.window {
+            std::mem::swap(&mut self.current, &mut self.previous);
+            self.current.clear();
+            self.stats.rotations += 1;
+        }
+        first
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    pub fn stats(&self) -> DedupStats {
+        self.stats
+    }
+
+    pub fn memory_bytes(&self) -> usize {
+        self.current.memory_bytes() + self.previous.memory_bytes()
+    }
+}
@@ -0,0 +1,167 @@
+// Bloom filter
+//
+//   insert("gw-1/42"):  h1, h2 = two hashes of the item
+//                       bit (h1 + i*h2) mod m is set for i in 0..k
+//
+//   bits: 0 1 0 0 1 0 1 1 0 0 1 0 ...   (m bits, about half set when full)
+//
+// `contains` checks the same k bits. If any is 0 the item was never
+// inserted: there are no false negatives. If all are 1 it probably was,
+// but those bits may have been set by other items, so a Bloom filter
+// answers "definitely new" or "probably seen", never "definitely seen".
+//
+// Sizing for `capacity` items n and false-positive rate p:
+//   m = -n ln p / (ln 2)^2     bits
+//   k = (m / n) ln 2           hashes
+// which works out to about 9.6 bits per item for 1%, and 4.8 more bits
+// per item for every further factor of 10.
+//
+// The k indices come from two hashes by double hashing (Kirsch and
+// Mitzenmacher): g_i = h1 + i*h2. That is as good as k independent
+// hashes for a Bloom filter and costs two hash computations per item.
+
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BloomError {
+    ZeroCapacity,
+    // Must be strictly between 0 and 1
+    BadRate(f64),
+}
+
+impl fmt::Display for BloomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BloomError::ZeroCapacity => write!(f, "capacity must be at least 1"),
+            BloomError::BadRate(p) => {
+                write!(f, "false-positive rate {} is not between 0 and 1", p)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BloomError {}
+
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    words: Vec<u64>,
+    bits: u64,
+    hashes: u32,
+    capacity: usize,
+    // Inserts that set at least one bit
+    items: usize,
+}
+
+// `DefaultHasher::new()` always starts from the same keys, so a filter
+// gives the same answers on every run (but not necessarily across Rust
+// releases; don't persist the bits)
+fn hash_with<T: Hash + ?Sized>(seed: u64, item: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    item.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl BloomFilter {
+    pub fn new(capacity: usize, fp_rate: f64) -> Result<BloomFilter, BloomError> {
+        if capacity == 0 {
+            return Err(BloomError::ZeroCapacity);
+        }
+        if !(fp_rate > 0.0 && fp_rate < 1.0) {
+            return Err(BloomError::BadRate(fp_rate));
+        }
+        let ln2 = std::f64::consts::LN_2;
+        let bits = (-(capacity as f64) * fp_rate.ln() / (ln2 * ln2)).ceil() as u64;
+        let hashes = ((bits as f64 / capacity as f64) * ln2).round().max(1.0) as u32;
+        Ok(BloomFilter::with_size(bits, hashes, capacity))
+    }
+
+    // Explicit size, for experiments; `capacity` is only reported back
+    pub fn with_size(bits: u64, hashes: u32, capacity: usize) -> BloomFilter {
+        let bits = bits.max(64);
+        BloomFilter {
+            words: vec![0; bits.div_ceil(64) as usize],
+            bits,
+            hashes: hashes.max(1),
+            capacity,
+            items: 0,
+        }
+    }
+
+    // h2 is forced odd so the k indices can't collapse onto one when h2
+    // shares a factor with m
+    fn indices<T: Hash + ?Sized>(&self, item: &T) -> impl Iterator<Item = u64> {
+        let h1 = hash_with(0, item);
+        let h2 = hash_with(1, item) | 1;
+        let bits = self.bits;
+        (0..self.hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % bits)
+    }
+
+    // Returns true if the item is definitely new (some bit was still 0),
+    // false if it was probably inserted before
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) -> bool {
+        let mut new = false;
+        for index in self.indices(item) {
+            let (word, mask) = ((index / 64) as usize, 1u64 << (index % 64));
+            new |= self.words[word] & mask == 0;
+            self.words[word] |= mask;
+        }
+        if new {
+            self.items += 1;
+        }
+        new
+    }
+
+    pub fn contains<T: Hash + ?Sized>(&self, item: &T) -> bool {
+        self.indices(item)
+            .all(|index| self.words[(index / 64) as usize] & (1 << (index % 64)) != 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+        self.items = 0;
+    }
+
+    // Distinct items inserted, give or take the false positives among them
+    pub fn len(&self) -> usize {
+        self.items
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn bits(&self) -> u64 {
+        self.bits
+    }
+
+    pub fn hashes(&self) -> u32 {
+        self.hashes
+    }
+
+    pub fn memory_bytes(&self) -> usize {
+        self.words.len() * 8
+    }
+
+    pub fn fill_ratio(&self) -> f64 {
+        let set: u64 = self.words.iter().map(|w| w.count_ones() as u64).sum();
+        set as f64 / self.bits as f64
+    }
+
+    // The chance that a new item is reported as seen, right now: every one
+    // of its k bits must already be set
+    pub fn estimated_fp_rate(&self) -> f64 {
+        self.fill_ratio().powi(self.hashes as i32)
+    }
+
+    // The textbook prediction after `items` inserts: (1 - e^(-kn/m))^k
+    pub fn expected_fp_rate(&self, items: usize) -> f64 {
+        let k = self.hashes as f64;
+        (1.0 - (-k * items as f64 / self.bits as f64).exp()).powf(k)
+    }
+}
@@ -0,0 +1,15 @@
+// Bloom filters
+//
+// - `filter`: `BloomFilter`, sized from an item count and a target
+//   false-positive rate, with k indices from double hashing
+// - `dedup`: `Dedup`, two rotating filters that drop repeated message ids
+//   from an endless stream
+//
+// 16.gateway's `ingest` module uses `Dedup` to drop duplicate telemetry
+// arriving at the collector.
+
+pub mod dedup;
+pub mod filter;
+
+pub use dedup::{Dedup, DedupStats};
+pub use filter::{BloomError, BloomFilter};
@@ -0,0 +1,177 @@
+use bloom::{BloomFilter, Dedup};
+use std::collections::HashSet;
+use std::time::Instant;
+
+const ITEMS: usize = 20_000;
+const PROBES: usize = 200_000;
+const STREAM: usize = 200_000;
+
+// xorshift32, so every run sees the same stream
+fn random(seed: u32, n: usize) -> Vec<u32> {
+    let mut x = seed;
+    (0..n)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        })
+        .collect()
+}
+
+// Message ids as the gateway stamps them: gateway, boot, counter
+fn id(gateway: u32, n: usize) -> String {
+    format!("gw-{}/1760000000/{}", gateway, n)
+}
+
+fn main() {
+    println!("=== Bloom Filter Examples ===\n");
+
+    // 1. Sizing
+    println!("1. Size for 100,000 message ids:");
+    println!("   target FPR   bits/id  hashes   memory");
+    for p in [0.01, 0.001, 0.0001, 0.000001] {
+        let filter = BloomFilter::new(100_000, p).expect("valid parameters");
+        println!(
+            "   {:>10}  {:>8.1}  {:>6}  {:>6.1} KiB",
+            p,
+            filter.bits() as f64 / 100_000.0,
+            filter.hashes(),
+            filter.memory_bytes() as f64 / 1024.0
+        );
+    }
+    let mut set: HashSet<String> = HashSet::new();
+    for n in 0..100_000 {
+        set.insert(id(1, n));
+    }
+    // Each slot holds a String (24 bytes) plus a control byte; the ids
+    // themselves are another ~25 bytes each on the heap
+    let set_bytes = set.capacity() * 25 + set.iter().map(|s| s.capacity()).sum::<usize>();
+    println!(
+        "   HashSet<String> of the same ids: about {:.0} KiB",
+        set_bytes as f64 / 1024.0
+    );
+    let errors = [
+        BloomFilter::new(0, 0.01).unwrap_err(),
+        BloomFilter::new(10, 1.5).unwrap_err(),
+    ];
+    for e in &errors {
+        println!("   rejected: {}", e);
+    }
+
+    // 2. No false negatives
+    println!("\n2. Insert and look up {} ids:", ITEMS);
+    let mut filter = BloomFilter::new(ITEMS, 0.01).expect("valid parameters");
+    let first = (0..ITEMS).filter(|&n| filter.insert(&id(1, n))).count();
+    let found = (0..ITEMS).filter(|&n| filter.contains(&id(1, n))).count();
+    let again = (0..ITEMS).filter(|&n| filter.insert(&id(1, n))).count();
+    println!(
+        "   {} reported new on insert, {} found, {} new on re-insert",
+        first, found, again
+    );
+    // k is rounded to a whole number, so the fill is near but not at 0.5
+    let k = filter.hashes() as f64;
+    let predicted = 1.0 - (-k * ITEMS as f64 / filter.bits() as f64).exp();
+    println!(
+        "   fill ratio {:.3}, predicted 1 - e^(-kn/m) = {:.3}",
+        filter.fill_ratio(),
+        predicted
+    );
+
+    // 3. The false-positive rate, measured
+    println!(
+        "\n3. False positives: {} ids in, {} other ids probed:",
+        ITEMS, PROBES
+    );
+    println!("   target      measured    predicted   estimated");
+    for p in [0.05, 0.01, 0.001] {
+        let mut filter = BloomFilter::new(ITEMS, p).expect("valid parameters");
+        for n in 0..ITEMS {
+            filter.insert(&id(1, n));
+        }
+        // Gateway 2's ids were never inserted; every hit is a false positive
+        let hits = (0..PROBES).filter(|&n| filter.contains(&id(2, n))).count();
+        let measured = hits as f64 / PROBES as f64;
+        // The rounding of m and k moves the real rate slightly off the
+        // target; the prediction is for the actual m and k
+        println!(
+            "   {:<10}  {:<10.5}  {:<10.5}  {:.5}",
+            p,
+            measured,
+            filter.expected_fp_rate(ITEMS),
+            filter.estimated_fp_rate()
+        );
+    }
+
+    let mut overfull = BloomFilter::new(ITEMS, 0.01).expect("valid parameters");
+    for n in 0..4 * ITEMS {
+        overfull.insert(&id(1, n));
+    }
+    let hits = (0..PROBES)
+        .filter(|&n| overfull.contains(&id(2, n)))
+        .count();
+    let measured = hits as f64 / PROBES as f64;
+    println!(
+        "   at 4x capacity: fill {:.2}, measured rate {:.3}",
+        overfull.fill_ratio(),
+        measured
+    );
+
+    // 4. Dropping duplicates from a stream
+    println!(
+        "\n4. {} messages, about 5% resent within 50 messages:",
+        STREAM
+    );
+    let rolls = random(0x2545_f491, STREAM);
+    let mut stream: Vec<(usize, bool)> = Vec::with_capacity(STREAM + STREAM / 10);
+    let mut replays: Vec<(usize, usize)> = Vec::new();
+    for (n, roll) in rolls.iter().enumerate() {
+        stream.push((n, false));
+        if roll % 20 == 0 {
+            replays.push((n + 1 + (roll / 20 % 50) as usize, n));
+        }
+        while let Some(at) = replays.iter().position(|&(due, _)| due <= n) {
+            let (_, original) = replays.swap_remove(at);
+            stream.push((original, true));
+        }
+    }
+    let resent = stream.iter().filter(|&&(_, dup)| dup).count();
+
+    let started = Instant::now();
+    let mut dedup = Dedup::new(10_000, 0.001).expect("valid parameters");
+    let mut caught = 0;
+    let mut lost = 0;
+    for &(n, dup) in &stream {
+        let first = dedup.check(&id(1, n));
+        match (first, dup) {
+            (false, true) => caught += 1,
+            (false, false) => lost += 1,
+            _ => {}
+        }
+    }
+    let elapsed = started.elapsed();
+    let stats = dedup.stats();
+    println!(
+        "   {} resent, {} dropped as duplicates, {} new ones lost",
+        resent, caught, lost
+    );
+    println!(
+        "   {} rotations, {} KiB for the filters, {:.2?}",
+        stats.rotations,
+        dedup.memory_bytes() / 1024,
+        elapsed
+    );
+
+    let mut dedup = Dedup::new(1_000, 0.001).expect("valid parameters");
+    dedup.check(&id(1, 0));
+    for n in 1..=3_000 {
+        dedup.check(&id(1, n));
+    }
+    let stale = dedup.check(&id(1, 0));
+    println!(
+        "   id 0 resent 3000 ids later, window 1000: new = {}",
+        stale
+    );
+
+    println!("\n=== End of Bloom Filter Examples ===");
+}
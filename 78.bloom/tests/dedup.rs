@@ -0,0 +1,96 @@
+use bloom::{Dedup, DedupStats};
+
+const STREAM: usize = 200_000;
+
+fn id(gateway: u32, n: usize) -> String {
+    format!("gw-{}/1760000000/{}", gateway, n)
+}
+
+// A stream of message numbers where about 5% are resent within 50
+// messages; true marks a resend
+fn stream_with_resends() -> Vec<(usize, bool)> {
+    let mut x = 0x2545_f491u32;
+    let mut stream = Vec::with_capacity(STREAM + STREAM / 10);
+    let mut replays: Vec<(usize, usize)> = Vec::new();
+    for n in 0..STREAM {
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        stream.push((n, false));
+        if x.is_multiple_of(20) {
+            replays.push((n + 1 + (x / 20 % 50) as usize, n));
+        }
+        while let Some(at) = replays.iter().position(|&(due, _)| due <= n) {
+            let (_, original) = replays.swap_remove(at);
+            stream.push((original, true));
+        }
+    }
+    stream
+}
+
+#[test]
+fn resends_are_dropped_and_new_ids_rarely_lost() {
+    let stream = stream_with_resends();
+    let resent = stream.iter().filter(|&&(_, dup)| dup).count();
+    assert!(resent > STREAM / 25);
+    let mut dedup = Dedup::new(10_000, 0.001).unwrap();
+    let (mut caught, mut lost) = (0, 0);
+    for &(n, dup) in &stream {
+        match (dedup.check(&id(1, n)), dup) {
+            (false, true) => caught += 1,
+            (false, false) => lost += 1,
+            (true, true) => panic!("resend of {} passed", n),
+            (true, false) => {}
+        }
+    }
+    assert_eq!(caught, resent);
+    // Up to 0.1% of new messages may be lost to false positives
+    assert!((lost as f64) < STREAM as f64 * 0.001, "{} lost", lost);
+    assert_eq!(
+        dedup.stats(),
+        DedupStats {
+            checked: stream.len() as u64,
+            duplicates: (caught + lost) as u64,
+            rotations: dedup.stats().rotations,
+        }
+    );
+    // About one rotation per 10,000 distinct ids
+    assert!((19..=20).contains(&dedup.stats().rotations));
+}
+
+#[test]
+fn ids_are_remembered_for_at_least_a_window() {
+    // Wherever the id falls between rotations
+    for phase in [0, 1, 500, 999, 1_000, 1_750] {
+        let mut dedup = Dedup::new(1_000, 0.001).unwrap();
+        assert_eq!(dedup.window(), 1_000);
+        for n in 0..phase {
+            dedup.check(&id(1, n));
+        }
+        let target = id(2, phase);
+        assert!(dedup.check(&target));
+        // The lookup itself is the window-th id after the target
+        for n in phase..phase + 999 {
+            dedup.check(&id(1, n));
+        }
+        assert!(!dedup.check(&target), "forgot at phase {}", phase);
+    }
+}
+
+#[test]
+fn ids_older_than_two_windows_are_forgotten() {
+    let mut dedup = Dedup::new(1_000, 0.001).unwrap();
+    assert!(dedup.check(&id(1, 0)));
+    for n in 1..=3_000 {
+        dedup.check(&id(1, n));
+    }
+    assert!(dedup.check(&id(1, 0)));
+}
+
+#[test]
+fn each_filter_gets_half_the_rate() {
+    let dedup = Dedup::new(10_000, 0.001).unwrap();
+    let single = bloom::BloomFilter::new(10_000, 0.0005).unwrap();
+    assert_eq!(dedup.memory_bytes(), 2 * single.memory_bytes());
+    assert!(Dedup::new(0, 0.01).is_err());
+}
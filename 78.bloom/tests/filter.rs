@@ -0,0 +1,175 @@
+use bloom::{BloomError, BloomFilter};
+
+const ITEMS: usize = 20_000;
+const PROBES: usize = 200_000;
+
+// Message ids as the gateway stamps them: gateway, boot, counter
+fn id(gateway: u32, n: usize) -> String {
+    format!("gw-{}/1760000000/{}", gateway, n)
+}
+
+fn filled(capacity: usize, p: f64, items: usize) -> BloomFilter {
+    let mut filter = BloomFilter::new(capacity, p).unwrap();
+    for n in 0..items {
+        filter.insert(&id(1, n));
+    }
+    filter
+}
+
+// Gateway 2's ids were never inserted; every hit is a false positive
+fn false_positives(filter: &BloomFilter) -> usize {
+    (0..PROBES).filter(|&n| filter.contains(&id(2, n))).count()
+}
+
+// Probing ids that were never inserted is a binomial experiment. With a
+// true rate p the count of false positives has mean trials*p and standard
+// deviation sqrt(trials*p*(1-p)); a count more than four deviations from
+// the mean happens by chance well under once in 10,000 runs.
+fn assert_within_four_sigma(observed: usize, trials: usize, p: f64) {
+    let mean = trials as f64 * p;
+    let sigma = (trials as f64 * p * (1.0 - p)).sqrt();
+    assert!(
+        (observed as f64 - mean).abs() <= 4.0 * sigma,
+        "{} false positives in {}, expected {:.0} ± {:.0}",
+        observed,
+        trials,
+        mean,
+        4.0 * sigma
+    );
+}
+
+#[test]
+fn one_percent_costs_about_ten_bits_per_item() {
+    let filter = BloomFilter::new(100_000, 0.01).unwrap();
+    let per_item = filter.bits() as f64 / 100_000.0;
+    assert!((per_item - 9.59).abs() < 0.05, "{}", per_item);
+    assert_eq!(filter.hashes(), 7);
+    assert_eq!(filter.capacity(), 100_000);
+    assert_eq!(filter.memory_bytes() as u64, filter.bits().div_ceil(64) * 8);
+}
+
+#[test]
+fn each_tenfold_lower_rate_adds_about_five_bits() {
+    let per_item: Vec<f64> = [0.01, 0.001, 0.0001, 0.000001]
+        .iter()
+        .map(|&p| BloomFilter::new(100_000, p).unwrap().bits() as f64 / 100_000.0)
+        .collect();
+    for w in per_item.windows(2).take(2) {
+        assert!((w[1] - w[0] - 4.79).abs() < 0.05, "{:?}", per_item);
+    }
+    // 10^-6 is two steps on from 10^-4
+    assert!((per_item[3] - per_item[2] - 2.0 * 4.79).abs() < 0.05);
+}
+
+#[test]
+fn bad_parameters_are_errors() {
+    assert_eq!(
+        BloomFilter::new(0, 0.01).unwrap_err(),
+        BloomError::ZeroCapacity
+    );
+    for p in [0.0, 1.0, 1.5, -0.1, f64::NAN] {
+        assert!(matches!(
+            BloomFilter::new(10, p),
+            Err(BloomError::BadRate(_))
+        ));
+    }
+    assert_eq!(
+        BloomError::BadRate(1.5).to_string(),
+        "false-positive rate 1.5 is not between 0 and 1"
+    );
+}
+
+#[test]
+fn with_size_has_a_floor() {
+    let filter = BloomFilter::with_size(1, 0, 10);
+    assert_eq!((filter.bits(), filter.hashes()), (64, 1));
+}
+
+#[test]
+fn no_false_negatives() {
+    let mut filter = BloomFilter::new(ITEMS, 0.01).unwrap();
+    let first = (0..ITEMS).filter(|&n| filter.insert(&id(1, n))).count();
+    assert!((0..ITEMS).all(|n| filter.contains(&id(1, n))));
+    // Re-inserting finds every bit already set
+    assert_eq!((0..ITEMS).filter(|&n| filter.insert(&id(1, n))).count(), 0);
+    // "Definitely new" is only reported when a bit flips, so a few distinct
+    // ids are counted as seen
+    assert!(ITEMS - first < ITEMS / 100);
+    assert_eq!(filter.len(), first);
+}
+
+#[test]
+fn about_half_the_bits_are_set_at_capacity() {
+    let filter = filled(ITEMS, 0.01, ITEMS);
+    // k is rounded to a whole number, so the fill is near but not at 0.5
+    let k = filter.hashes() as f64;
+    let predicted = 1.0 - (-k * ITEMS as f64 / filter.bits() as f64).exp();
+    assert!((filter.fill_ratio() - predicted).abs() < 0.005);
+    assert!((filter.fill_ratio() - 0.5).abs() < 0.03);
+}
+
+#[test]
+fn false_positive_rate_meets_the_target() {
+    for p in [0.05, 0.01, 0.001] {
+        let filter = filled(ITEMS, p, ITEMS);
+        let hits = false_positives(&filter);
+        // The rounding of m and k moves the real rate slightly off the
+        // target, so the binomial test uses the prediction for the actual
+        // m and k, and the prediction must itself be close to the target
+        let predicted = filter.expected_fp_rate(ITEMS);
+        assert!(
+            (predicted - p).abs() < p * 0.1,
+            "target {}, predicted {}",
+            p,
+            predicted
+        );
+        assert_within_four_sigma(hits, PROBES, predicted);
+        let measured = hits as f64 / PROBES as f64;
+        assert!(
+            (measured - p).abs() < p * 0.25,
+            "target {}, measured {}",
+            p,
+            measured
+        );
+        // fill^k from the bits alone agrees with the probes too
+        assert_within_four_sigma(hits, PROBES, filter.estimated_fp_rate());
+    }
+}
+
+#[test]
+fn a_half_full_filter_does_better_than_the_target() {
+    let filter = filled(ITEMS, 0.01, ITEMS / 2);
+    let hits = false_positives(&filter);
+    assert_within_four_sigma(hits, PROBES, filter.expected_fp_rate(ITEMS / 2));
+    assert!((hits as f64 / PROBES as f64) < 0.002);
+}
+
+#[test]
+fn an_overfull_filter_misses_its_target() {
+    let filter = filled(ITEMS, 0.01, 4 * ITEMS);
+    let hits = false_positives(&filter);
+    assert!(filter.fill_ratio() > 0.9);
+    assert!(hits as f64 / PROBES as f64 > 0.1);
+    assert_within_four_sigma(hits, PROBES, filter.estimated_fp_rate());
+}
+
+#[test]
+fn clear_forgets_everything() {
+    let mut filter = filled(1_000, 0.01, 1_000);
+    assert!(!filter.is_empty());
+    filter.clear();
+    assert!(filter.is_empty());
+    assert_eq!(filter.fill_ratio(), 0.0);
+    assert!((0..1_000).all(|n| !filter.contains(&id(1, n))));
+}
+
+#[test]
+fn any_hashable_item() {
+    let mut filter = BloomFilter::new(100, 0.01).unwrap();
+    filter.insert(&42u64);
+    filter.insert("a str");
+    filter.insert(&(7u16, 'x'));
+    assert!(filter.contains(&42u64));
+    assert!(filter.contains("a str"));
+    assert!(filter.contains(&(7u16, 'x')));
+}
@@ -0,0 +1,154 @@
+// Binary max-heap, the same contract as std::collections::BinaryHeap
+//
+//   as a tree:        9              as the Vec:  [9, 7, 8, 3, 5, 1]
+//                   /   \                          0  1  2  3  4  5
+//                  7     8
+//                 / \   /           children of i: 2i + 1, 2i + 2
+//                3   5 1            parent of i:   (i - 1) / 2
+//
+// The one rule (the heap property): every parent is >= its children, so
+// the largest value is at index 0. Nothing is said about siblings, which
+// is what makes the heap cheaper to maintain than a sorted Vec.
+//
+// - push: append at the end, then sift up (swap with the parent while
+//   larger); O(log n)
+// - pop: swap the root with the last element, remove it, then sift the new
+//   root down (swap with the larger child while smaller); O(log n)
+// - from a Vec: sift down every parent, last to first; O(n) in total,
+//   because most nodes are near the bottom and move only a level or two
+//
+// For a min-heap, wrap values in `std::cmp::Reverse`, as with std.
+
+#[derive(Debug, Clone)]
+pub struct BinaryHeap<T> {
+    data: Vec<T>,
+}
+
+impl<T: Ord> Default for BinaryHeap<T> {
+    fn default() -> Self {
+        BinaryHeap::new()
+    }
+}
+
+impl<T: Ord> BinaryHeap<T> {
+    pub fn new() -> BinaryHeap<T> {
+        BinaryHeap { data: Vec::new() }
+    }
+
+    pub fn with_capacity(capacity: usize) -> BinaryHeap<T> {
+        BinaryHeap {
+            data: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.data.push(value);
+        self.sift_up(self.data.len() - 1);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let last = self.data.len() - 1;
+        self.data.swap(0, last);
+        let top = self.data.pop();
+        self.sift_down(0, self.data.len());
+        top
+    }
+
+    // Moves `pos` up until its parent is at least as large
+    fn sift_up(&mut self, mut pos: usize) {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if self.data[pos] <= self.data[parent] {
+                break;
+            }
+            self.data.swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    // Moves `pos` down until both children within `end` are no larger
+    fn sift_down(&mut self, mut pos: usize, end: usize) {
+        loop {
+            let left = 2 * pos + 1;
+            if left >= end {
+                break;
+            }
+            let right = left + 1;
+            let larger = if right < end && self.data[right] > self.data[left] {
+                right
+            } else {
+                left
+            };
+            if self.data[pos] >= self.data[larger] {
+                break;
+            }
+            self.data.swap(pos, larger);
+            pos = larger;
+        }
+    }
+
+    // Heap order, which is not sorted order
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    // Heapsort: move the root behind a shrinking heap, ascending result
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut end = self.data.len();
+        while end > 1 {
+            end -= 1;
+            self.data.swap(0, end);
+            self.sift_down(0, end);
+        }
+        self.data
+    }
+}
+
+impl<T: Ord> From<Vec<T>> for BinaryHeap<T> {
+    fn from(data: Vec<T>) -> BinaryHeap<T> {
+        let mut heap = BinaryHeap { data };
+        let end = heap.data.len();
+        // Leaves are heaps already; fix every parent, bottom up
+        for pos in (0..end / 2).rev() {
+            heap.sift_down(pos, end);
+        }
+        heap
+    }
+}
+
+impl<T: Ord> FromIterator<T> for BinaryHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> BinaryHeap<T> {
+        BinaryHeap::from(iter.into_iter().collect::<Vec<T>>())
+    }
+}
+
+impl<T: Ord> Extend<T> for BinaryHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
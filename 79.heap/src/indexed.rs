@@ -0,0 +1,170 @@
+// Indexed min-heap with decrease-key
+//
+//   heap:      [(t3, 2), (t1, 5), (t7, 9)]     the binary heap, by priority
+//   positions: {t3: 0, t1: 1, t7: 2}           where each key sits in it
+//
+// A plain heap can't find an element without scanning, so changing one
+// element's priority (a timer moved earlier, a job rescheduled) would cost
+// O(n). Keeping each key's index in a map makes it O(log n): look up the
+// position, overwrite the priority, then sift up if it decreased or down if
+// it increased. Every swap updates both keys' positions.
+//
+// This is a min-heap, the way schedulers want it: `peek` is the earliest
+// deadline. Priorities should be unique (add a tie-breaker such as the key)
+// when equal priorities must come out in a fixed order.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Debug, Clone)]
+pub struct IndexedHeap<K, P> {
+    heap: Vec<(K, P)>,
+    positions: HashMap<K, usize>,
+}
+
+impl<K: Hash + Eq + Clone, P: Ord> Default for IndexedHeap<K, P> {
+    fn default() -> Self {
+        IndexedHeap::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone, P: Ord> IndexedHeap<K, P> {
+    pub fn new() -> IndexedHeap<K, P> {
+        IndexedHeap {
+            heap: Vec::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    pub fn priority(&self, key: &K) -> Option<&P> {
+        self.positions.get(key).map(|&pos| &self.heap[pos].1)
+    }
+
+    // The key with the smallest priority
+    pub fn peek(&self) -> Option<(&K, &P)> {
+        self.heap.first().map(|(k, p)| (k, p))
+    }
+
+    // Adds `key`, or changes its priority if it is already queued; returns
+    // the old priority in that case
+    pub fn push(&mut self, key: K, priority: P) -> Option<P> {
+        if self.positions.contains_key(&key) {
+            return self.change_priority(&key, priority);
+        }
+        self.heap.push((key.clone(), priority));
+        let pos = self.heap.len() - 1;
+        self.positions.insert(key, pos);
+        self.sift_up(pos);
+        None
+    }
+
+    pub fn pop(&mut self) -> Option<(K, P)> {
+        self.remove_at(0)
+    }
+
+    // Sets a new priority in either direction; None if `key` isn't queued
+    pub fn change_priority(&mut self, key: &K, priority: P) -> Option<P> {
+        let pos = *self.positions.get(key)?;
+        let old = std::mem::replace(&mut self.heap[pos].1, priority);
+        if self.heap[pos].1 < old {
+            self.sift_up(pos);
+        } else {
+            self.sift_down(pos);
+        }
+        Some(old)
+    }
+
+    // The classic decrease-key: only ever moves a key earlier. Returns
+    // whether the priority changed.
+    pub fn decrease_priority(&mut self, key: &K, priority: P) -> bool {
+        match self.priority(key) {
+            Some(current) if priority < *current => {
+                self.change_priority(key, priority);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<P> {
+        let pos = *self.positions.get(key)?;
+        self.remove_at(pos).map(|(_, p)| p)
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.positions.clear();
+    }
+
+    // In heap order
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &P)> {
+        self.heap.iter().map(|(k, p)| (k, p))
+    }
+
+    // Swap with the last entry, drop it, then repair the entry that moved
+    // into `pos`: it may belong higher or lower than where it landed
+    fn remove_at(&mut self, pos: usize) -> Option<(K, P)> {
+        if pos >= self.heap.len() {
+            return None;
+        }
+        let last = self.heap.len() - 1;
+        self.swap(pos, last);
+        let (key, priority) = self.heap.pop().expect("not empty");
+        self.positions.remove(&key);
+        if pos < self.heap.len() {
+            self.sift_down(pos);
+            self.sift_up(pos);
+        }
+        Some((key, priority))
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        *self.positions.get_mut(&self.heap[a].0).expect("queued") = a;
+        *self.positions.get_mut(&self.heap[b].0).expect("queued") = b;
+    }
+
+    fn sift_up(&mut self, mut pos: usize) {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if self.heap[pos].1 >= self.heap[parent].1 {
+                break;
+            }
+            self.swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut pos: usize) {
+        let end = self.heap.len();
+        loop {
+            let left = 2 * pos + 1;
+            if left >= end {
+                break;
+            }
+            let right = left + 1;
+            let smaller = if right < end && self.heap[right].1 < self.heap[left].1 {
+                right
+            } else {
+                left
+            };
+            if self.heap[pos].1 <= self.heap[smaller].1 {
+                break;
+            }
+            self.swap(pos, smaller);
+            pos = smaller;
+        }
+    }
+}
@@ -0,0 +1,15 @@
+// Binary heaps from scratch
+//
+// - `binary`: `BinaryHeap`, a max-heap in a Vec with the same behaviour as
+//   std's, including O(n) construction from a Vec and heapsort
+// - `indexed`: `IndexedHeap`, a min-heap of keys with a position map, so a
+//   key's priority can be changed or the key removed in O(log n)
+//
+// 73.cron's `Scheduler` keeps its jobs in an `IndexedHeap` keyed by next
+// fire time, so finding the next deadline is a peek.
+
+pub mod binary;
+pub mod indexed;
+
+pub use binary::BinaryHeap;
+pub use indexed::IndexedHeap;
@@ -0,0 +1,192 @@
+use heap::{BinaryHeap, IndexedHeap};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap as StdHeap;
+use std::sync::atomic::{AtomicUsize, Ordering as Atomic};
+use std::time::Instant;
+
+const TIMERS: usize = 10_000;
+const FIRINGS: usize = 50_000;
+
+// xorshift32, so every run sees the same operations
+fn random(seed: u32, n: usize) -> Vec<u32> {
+    let mut x = seed.max(1);
+    (0..n)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        })
+        .collect()
+}
+
+// Counts its comparisons, to show what heap construction costs
+static COMPARISONS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, PartialEq, Eq)]
+struct Counted(u32);
+
+impl PartialOrd for Counted {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Counted {
+    fn cmp(&self, other: &Self) -> Ordering {
+        COMPARISONS.fetch_add(1, Atomic::Relaxed);
+        self.0.cmp(&other.0)
+    }
+}
+
+fn main() {
+    println!("=== Binary Heap Examples ===\n");
+
+    // 1. Sift up, sift down
+    println!("1. Push then pop, the Vec after each push:");
+    let mut heap = BinaryHeap::new();
+    for value in [5, 3, 8, 1, 9, 7] {
+        heap.push(value);
+        println!("   push {} -> {:?}", value, heap.as_slice());
+    }
+    let mut popped = Vec::new();
+    while let Some(top) = heap.pop() {
+        popped.push(top);
+    }
+    println!("   popped: {:?}", popped);
+    let mut min_heap = BinaryHeap::new();
+    min_heap.extend([5, 3, 8].map(Reverse));
+    println!("   with Reverse, 5 3 8 pop as {:?} first", min_heap.pop());
+
+    // 2. Building from a Vec
+    println!("\n2. Building a heap of 100,000 ascending values:");
+    let n = 100_000u32;
+    COMPARISONS.store(0, Atomic::Relaxed);
+    let mut pushed = BinaryHeap::new();
+    for v in 0..n {
+        pushed.push(Counted(v));
+    }
+    let by_push = COMPARISONS.swap(0, Atomic::Relaxed);
+    let built = BinaryHeap::from((0..n).map(Counted).collect::<Vec<_>>());
+    let by_heapify = COMPARISONS.swap(0, Atomic::Relaxed);
+    println!(
+        "   one push at a time: {} comparisons ({:.1} per value)",
+        by_push,
+        by_push as f64 / n as f64
+    );
+    println!(
+        "   From<Vec> (bottom-up): {} comparisons ({:.1} per value)",
+        by_heapify,
+        by_heapify as f64 / n as f64
+    );
+    println!("   both peek {:?}", (pushed.peek(), built.peek()));
+    let values: Vec<u32> = random(7, 12).into_iter().map(|v| v % 100).collect();
+    println!("   heapsort {:?}", values);
+    println!(
+        "         -> {:?}",
+        BinaryHeap::from(values).into_sorted_vec()
+    );
+
+    // 3. Against std
+    println!("\n3. The same pushes and pops on std::collections::BinaryHeap:");
+    let mut ours = BinaryHeap::new();
+    let mut theirs = StdHeap::new();
+    let (mut from_ours, mut from_theirs) = (Vec::new(), Vec::new());
+    for op in random(2024, 40) {
+        if op % 3 == 0 {
+            from_ours.extend(ours.pop());
+            from_theirs.extend(theirs.pop());
+        } else {
+            // Values 0..32 make duplicates common
+            ours.push(op % 32);
+            theirs.push(op % 32);
+        }
+    }
+    println!("   ours: {:?}", from_ours);
+    println!("   std:  {:?}", from_theirs);
+    println!(
+        "   left: {:?} and {:?}",
+        ours.into_sorted_vec(),
+        theirs.into_sorted_vec()
+    );
+
+    // 4. Decrease-key
+    println!("\n4. Gateway timers in an IndexedHeap (deadline in ms):");
+    let mut timers: IndexedHeap<&str, u64> = IndexedHeap::new();
+    for (name, at) in [
+        ("heartbeat", 1000),
+        ("batch-flush", 500),
+        ("watchdog", 3000),
+        ("retry", 1500),
+    ] {
+        timers.push(name, at);
+    }
+    println!("   next: {:?}", timers.peek());
+    let moved_up = timers.decrease_priority(&"watchdog", 200);
+    let refused = timers.decrease_priority(&"heartbeat", 5000);
+    let pushed_back = timers.change_priority(&"retry", 4000);
+    let cancelled = timers.remove(&"batch-flush");
+    println!(
+        "   decrease watchdog to 200: {}, heartbeat to 5000: {}",
+        moved_up, refused
+    );
+    println!(
+        "   retry moved from {:?} to 4000, batch-flush cancelled ({:?})",
+        pushed_back, cancelled
+    );
+    let mut order = Vec::new();
+    while let Some((name, at)) = timers.pop() {
+        order.push((name, at));
+    }
+    println!("   fires: {:?}", order);
+
+    // 5. Next-deadline selection
+    println!(
+        "\n5. {} periodic timers, the next {} firings:",
+        TIMERS, FIRINGS
+    );
+    let periods: Vec<u64> = random(99, TIMERS)
+        .into_iter()
+        .map(|r| 100 + (r % 9_900) as u64)
+        .collect();
+
+    // A Vec of deadlines, scanned for the earliest each time
+    let started = Instant::now();
+    let mut deadlines: Vec<u64> = periods.clone();
+    let mut scanned = Vec::with_capacity(FIRINGS);
+    for _ in 0..FIRINGS {
+        let (id, &at) = deadlines
+            .iter()
+            .enumerate()
+            .min_by_key(|&(id, &at)| (at, id))
+            .expect("timers");
+        scanned.push((at, id));
+        deadlines[id] = at + periods[id];
+    }
+    let scan_time = started.elapsed();
+
+    // The same timers in the heap: peek for the next, re-arm by changing
+    // the fired timer's priority
+    let started = Instant::now();
+    let mut queue: IndexedHeap<usize, (u64, usize)> = IndexedHeap::new();
+    for (id, &period) in periods.iter().enumerate() {
+        queue.push(id, (period, id));
+    }
+    let mut popped = Vec::with_capacity(FIRINGS);
+    for _ in 0..FIRINGS {
+        let (&id, &(at, _)) = queue.peek().expect("timers");
+        popped.push((at, id));
+        queue.change_priority(&id, (at + periods[id], id));
+    }
+    let heap_time = started.elapsed();
+    println!(
+        "   scan: {:.2?}, heap: {:.2?} ({:.0}x)",
+        scan_time,
+        heap_time,
+        scan_time.as_secs_f64() / heap_time.as_secs_f64().max(1e-9)
+    );
+    println!("   first firings (ms, timer): {:?}", &popped[..4]);
+    println!("   scan found the same:       {:?}", &scanned[..4]);
+
+    println!("\n=== End of Binary Heap Examples ===");
+}
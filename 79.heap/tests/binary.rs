@@ -0,0 +1,164 @@
+use heap::BinaryHeap;
+use std::cell::Cell;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap as StdHeap;
+use std::fmt::Debug;
+
+// xorshift32: the same operations on every run
+fn random(seed: u32, n: usize) -> Vec<u32> {
+    let mut x = seed.max(1);
+    (0..n)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        })
+        .collect()
+}
+
+fn is_heap<T: Ord>(data: &[T]) -> bool {
+    (1..data.len()).all(|i| data[(i - 1) / 2] >= data[i])
+}
+
+// Applies one random script of pushes, pops and peeks to both heaps and
+// fails on the first difference in what they return
+fn assert_same_as_std<T: Ord + Debug>(script: &[u32], value: impl Fn(u32) -> T) {
+    let mut ours = BinaryHeap::new();
+    let mut theirs = StdHeap::new();
+    for (step, &op) in script.iter().enumerate() {
+        match op % 10 {
+            0..=5 => {
+                ours.push(value(op >> 4));
+                theirs.push(value(op >> 4));
+            }
+            6..=8 => assert_eq!(ours.pop(), theirs.pop(), "step {}", step),
+            _ => assert_eq!(ours.peek(), theirs.peek(), "step {}", step),
+        }
+        assert_eq!(ours.len(), theirs.len(), "step {}", step);
+        assert!(is_heap(ours.as_slice()), "step {}", step);
+    }
+    assert_eq!(ours.into_sorted_vec(), theirs.into_sorted_vec());
+}
+
+#[test]
+fn random_scripts_match_std() {
+    for seed in 1..=500 {
+        let len = (random(seed, 1)[0] % 400) as usize;
+        let script = random(seed.wrapping_mul(2_654_435_761), len);
+        // Values 0..32 make duplicates common
+        assert_same_as_std(&script, |v| v % 32);
+        assert_same_as_std(&script, |v| v);
+    }
+}
+
+#[test]
+fn random_scripts_match_std_as_a_min_heap() {
+    for seed in 1..=500 {
+        let len = (random(seed, 1)[0] % 400) as usize;
+        let script = random(seed.wrapping_mul(2_654_435_761), len);
+        assert_same_as_std(&script, |v| Reverse((v % 32, v % 7)));
+    }
+}
+
+#[test]
+fn from_vec_drains_like_std() {
+    for seed in 1..=50 {
+        let values = random(seed, seed as usize * 20);
+        let mut ours = BinaryHeap::from(values.clone());
+        let mut theirs = StdHeap::from(values);
+        assert!(is_heap(ours.as_slice()));
+        while let Some(v) = theirs.pop() {
+            assert_eq!(ours.pop(), Some(v));
+        }
+        assert!(ours.is_empty());
+        assert_eq!(ours.pop(), None);
+    }
+}
+
+#[test]
+fn push_keeps_the_heap_property() {
+    let mut heap = BinaryHeap::new();
+    for value in [5, 3, 8, 1, 9, 7] {
+        heap.push(value);
+        assert!(is_heap(heap.as_slice()), "{:?}", heap.as_slice());
+    }
+    assert_eq!(heap.as_slice(), [9, 8, 7, 1, 3, 5]);
+    let popped: Vec<i32> = std::iter::from_fn(|| heap.pop()).collect();
+    assert_eq!(popped, [9, 8, 7, 5, 3, 1]);
+}
+
+#[test]
+fn reverse_makes_a_min_heap() {
+    let mut heap = BinaryHeap::new();
+    heap.extend([5, 3, 8].map(Reverse));
+    assert_eq!(heap.peek(), Some(&Reverse(3)));
+    assert_eq!(heap.pop(), Some(Reverse(3)));
+    assert_eq!(heap.pop(), Some(Reverse(5)));
+}
+
+#[test]
+fn into_sorted_vec_is_a_heapsort() {
+    for (seed, len) in [(7, 10_000), (8, 0), (9, 1), (10, 2), (11, 3)] {
+        let values: Vec<u32> = random(seed, len).into_iter().map(|v| v % 1000).collect();
+        let mut expected = values.clone();
+        expected.sort();
+        assert_eq!(BinaryHeap::from(values).into_sorted_vec(), expected);
+    }
+}
+
+thread_local! {
+    static COMPARISONS: Cell<usize> = const { Cell::new(0) };
+}
+
+// Counts its comparisons, to check what heap construction costs
+#[derive(Debug, PartialEq, Eq)]
+struct Counted(u32);
+
+impl PartialOrd for Counted {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Counted {
+    fn cmp(&self, other: &Self) -> Ordering {
+        COMPARISONS.with(|c| c.set(c.get() + 1));
+        self.0.cmp(&other.0)
+    }
+}
+
+#[test]
+fn bottom_up_build_is_linear() {
+    let n = 100_000u32;
+    let counted = || COMPARISONS.with(|c| c.replace(0));
+    counted();
+    let mut pushed = BinaryHeap::new();
+    for v in 0..n {
+        pushed.push(Counted(v));
+    }
+    let by_push = counted();
+    let built = BinaryHeap::from((0..n).map(Counted).collect::<Vec<_>>());
+    let by_heapify = counted();
+    // Ascending input is the worst case for pushing: every value climbs
+    // to the root, about log2(n) = 16 comparisons each
+    assert!(by_push > 10 * n as usize, "{}", by_push);
+    assert!(by_heapify < 2 * n as usize, "{}", by_heapify);
+    assert_eq!(pushed.peek(), Some(&Counted(n - 1)));
+    assert_eq!(built.peek(), Some(&Counted(n - 1)));
+}
+
+#[test]
+fn collect_clear_and_into_vec() {
+    let mut heap: BinaryHeap<u8> = [3, 1, 4, 1, 5].into_iter().collect();
+    assert_eq!(heap.len(), 5);
+    assert_eq!(heap.peek(), Some(&5));
+    let mut raw = heap.clone().into_vec();
+    raw.sort();
+    assert_eq!(raw, [1, 1, 3, 4, 5]);
+    heap.clear();
+    assert!(heap.is_empty());
+    assert_eq!(heap.peek(), None);
+    let empty: BinaryHeap<u8> = BinaryHeap::with_capacity(8);
+    assert!(empty.is_empty());
+}
@@ -0,0 +1,174 @@
+use heap::IndexedHeap;
+use std::collections::HashMap;
+
+// xorshift32: the same operations on every run
+fn random(seed: u32, n: usize) -> Vec<u32> {
+    let mut x = seed.max(1);
+    (0..n)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        })
+        .collect()
+}
+
+fn gateway_timers() -> IndexedHeap<&'static str, u64> {
+    let mut timers = IndexedHeap::new();
+    for (name, at) in [
+        ("heartbeat", 1000),
+        ("batch-flush", 500),
+        ("watchdog", 3000),
+        ("retry", 1500),
+    ] {
+        assert_eq!(timers.push(name, at), None);
+    }
+    timers
+}
+
+fn drain<K: std::hash::Hash + Eq + Clone, P: Ord>(heap: &mut IndexedHeap<K, P>) -> Vec<(K, P)> {
+    std::iter::from_fn(|| heap.pop()).collect()
+}
+
+#[test]
+fn peek_is_the_earliest_deadline() {
+    let timers = gateway_timers();
+    assert_eq!(timers.peek(), Some((&"batch-flush", &500)));
+    assert_eq!(timers.len(), 4);
+    assert!(timers.contains(&"retry"));
+    assert_eq!(timers.priority(&"watchdog"), Some(&3000));
+    assert_eq!(timers.iter().count(), 4);
+}
+
+#[test]
+fn decrease_key_moves_a_timer_to_the_front() {
+    let mut timers = gateway_timers();
+    assert!(timers.decrease_priority(&"watchdog", 200));
+    assert_eq!(timers.peek(), Some((&"watchdog", &200)));
+    // Never later, and not for a missing key
+    assert!(!timers.decrease_priority(&"heartbeat", 5000));
+    assert!(!timers.decrease_priority(&"heartbeat", 1000));
+    assert!(!timers.decrease_priority(&"missing", 0));
+    assert_eq!(timers.priority(&"heartbeat"), Some(&1000));
+}
+
+#[test]
+fn change_push_and_remove_keep_the_order() {
+    let mut timers = gateway_timers();
+    timers.decrease_priority(&"watchdog", 200);
+    assert_eq!(timers.change_priority(&"retry", 4000), Some(1500));
+    assert_eq!(timers.remove(&"batch-flush"), Some(500));
+    assert_eq!(timers.remove(&"batch-flush"), None);
+    assert_eq!(timers.change_priority(&"batch-flush", 1), None);
+    // Pushing a queued key changes its priority
+    assert_eq!(timers.push("heartbeat", 900), Some(1000));
+    assert_eq!(
+        drain(&mut timers),
+        [("watchdog", 200), ("heartbeat", 900), ("retry", 4000)]
+    );
+    assert!(timers.is_empty());
+}
+
+#[test]
+fn clear_forgets_the_positions() {
+    let mut timers = gateway_timers();
+    timers.clear();
+    assert!(timers.is_empty() && !timers.contains(&"retry"));
+    timers.push("retry", 7);
+    assert_eq!(timers.pop(), Some(("retry", 7)));
+}
+
+// The same operations on a HashMap, finding the minimum by scanning.
+// Priorities are (deadline, key) so ties can't differ between the two.
+#[test]
+fn random_operations_match_a_scan() {
+    let mut heap: IndexedHeap<u32, (u32, u32)> = IndexedHeap::new();
+    let mut model: HashMap<u32, (u32, u32)> = HashMap::new();
+    for (step, op) in random(0xdead_beef, 100_000).into_iter().enumerate() {
+        let key = (op >> 4) % 200;
+        let at = (op >> 12) % 10_000;
+        match op % 7 {
+            0 | 1 => assert_eq!(
+                heap.push(key, (at, key)),
+                model.insert(key, (at, key)),
+                "step {}",
+                step
+            ),
+            2 => {
+                let min = model.iter().map(|(&k, &p)| (p, k)).min();
+                if let Some((_, k)) = min {
+                    model.remove(&k);
+                }
+                assert_eq!(heap.pop().map(|(k, p)| (p, k)), min, "step {}", step);
+            }
+            3 => {
+                let lower = model.get(&key).is_some_and(|&p| (at, key) < p);
+                if lower {
+                    model.insert(key, (at, key));
+                }
+                assert_eq!(
+                    heap.decrease_priority(&key, (at, key)),
+                    lower,
+                    "step {}",
+                    step
+                );
+            }
+            4 => assert_eq!(heap.remove(&key), model.remove(&key), "step {}", step),
+            5 => {
+                let expected = model.get_mut(&key).map(|p| std::mem::replace(p, (at, key)));
+                assert_eq!(
+                    heap.change_priority(&key, (at, key)),
+                    expected,
+                    "step {}",
+                    step
+                );
+            }
+            _ => {
+                let min = model.iter().map(|(&k, p)| (p, k)).min();
+                assert_eq!(heap.peek().map(|(k, p)| (p, *k)), min, "step {}", step);
+            }
+        }
+        assert_eq!(heap.len(), model.len(), "step {}", step);
+        assert_eq!(heap.priority(&key), model.get(&key), "step {}", step);
+    }
+    // Every position is still right: each key reports its own priority
+    for (key, priority) in heap.iter() {
+        assert_eq!(model.get(key), Some(priority));
+    }
+}
+
+#[test]
+fn periodic_timers_fire_in_the_same_order_as_a_scan() {
+    const TIMERS: usize = 1_000;
+    const FIRINGS: usize = 20_000;
+    let periods: Vec<u64> = random(99, TIMERS)
+        .into_iter()
+        .map(|r| 100 + (r % 9_900) as u64)
+        .collect();
+
+    let mut deadlines = periods.clone();
+    let mut scanned = Vec::with_capacity(FIRINGS);
+    for _ in 0..FIRINGS {
+        let (id, &at) = deadlines
+            .iter()
+            .enumerate()
+            .min_by_key(|&(id, &at)| (at, id))
+            .unwrap();
+        scanned.push((at, id));
+        deadlines[id] = at + periods[id];
+    }
+
+    let mut queue: IndexedHeap<usize, (u64, usize)> = IndexedHeap::new();
+    for (id, &period) in periods.iter().enumerate() {
+        queue.push(id, (period, id));
+    }
+    let mut fired = Vec::with_capacity(FIRINGS);
+    for _ in 0..FIRINGS {
+        let (&id, &(at, _)) = queue.peek().unwrap();
+        fired.push((at, id));
+        queue.change_priority(&id, (at + periods[id], id));
+    }
+    assert_eq!(fired, scanned);
+    assert!(fired.windows(2).all(|w| w[0].0 <= w[1].0));
+}
@@ -0,0 +1,12 @@
+// Radix tries
+//
+// - `radix`: `RadixTrie`, a map from string keys to values that shares
+//   common prefixes, with lookups by key and iteration by prefix in key
+//   order
+//
+// 46.grpc_device's `Registry` keeps its devices in a `RadixTrie`, so
+// "every device under plant-3/floor-2/" walks one subtree.
+
+pub mod radix;
+
+pub use radix::{Iter, RadixTrie};
@@ -0,0 +1,188 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+use trie::RadixTrie;
+
+const SCRIPT: usize = 16;
+const QUERIES: usize = 1_000;
+
+// xorshift32, so every run sees the same operations
+fn random(seed: u32, n: usize) -> Vec<u32> {
+    let mut x = seed.max(1);
+    (0..n)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        })
+        .collect()
+}
+
+// Short keys over a tiny alphabet, so keys share prefixes, split edges
+// and end inside each other all the time
+fn random_key(r: u32) -> String {
+    let len = (r % 7) as usize;
+    (0..len)
+        .map(|i| ['a', 'b', '/', 'é'][(r >> (3 + 2 * i)) as usize % 4])
+        .collect()
+}
+
+fn collect(iter: trie::Iter<'_, u32>) -> Vec<(String, u32)> {
+    iter.map(|(k, &v)| (k, v)).collect()
+}
+
+fn main() {
+    println!("=== Radix Trie Examples ===\n");
+
+    // 1. Shared prefixes
+    println!("1. Inserting device ids, the tree after each:");
+    let mut devices = RadixTrie::new();
+    for (id, model) in [
+        ("plant-3/floor-1/th-1", "th-sensor"),
+        ("plant-3/floor-2/th-1", "th-sensor"),
+        ("plant-3/floor-2/th-2", "th-sensor"),
+        ("plant-3/floor-2", "gateway"),
+    ] {
+        devices.insert(id, model);
+        println!("   insert {}:", id);
+        for line in devices.tree().lines() {
+            println!("     {}", line);
+        }
+    }
+    println!("   {} keys, {} edges", devices.len(), devices.nodes());
+    for id in ["plant-3/floor-2", "plant-3/floor", "plant-3/floor-2/th"] {
+        println!("   get {:<20} -> {:?}", id, devices.get(id));
+    }
+    if let Some(model) = devices.get_mut("plant-3/floor-1/th-1") {
+        *model = "th-sensor-v2";
+    }
+    println!(
+        "   after get_mut: plant-3/floor-1/th-1 -> {:?}",
+        devices.get("plant-3/floor-1/th-1")
+    );
+
+    // 2. Prefix queries
+    println!("\n2. Prefix queries:");
+    for prefix in ["plant-3/floor-2/", "plant-3/fl", "plant-4/", ""] {
+        let ids: Vec<String> = devices.prefix(prefix).map(|(id, _)| id).collect();
+        println!("   {:<18} -> {:?}", format!("\"{}\"", prefix), ids);
+    }
+    let before = devices.tree();
+    devices.insert("plant-3/floor-2/th-10", "th-sensor");
+    let added = devices.nodes();
+    devices.remove("plant-3/floor-2/th-10");
+    println!(
+        "   insert then remove th-10: {} edges, back to {}",
+        added,
+        devices.nodes()
+    );
+    println!("   tree unchanged: {}", devices.tree() == before);
+    println!(
+        "   remove plant-3/floor-9 -> {:?}, still {} keys",
+        devices.remove("plant-3/floor-9"),
+        devices.len()
+    );
+
+    // 3. A random script
+    println!("\n3. The start of a random script over keys of a, b, / and é:");
+    let mut trie = RadixTrie::new();
+    for op in random(7, SCRIPT) {
+        let key = random_key(op >> 4);
+        let shown = format!("{:?}", key);
+        match op % 8 {
+            0..=2 => println!(
+                "   insert {:<10} -> {:?}",
+                shown,
+                trie.insert(&key, op % 100)
+            ),
+            3 | 4 => println!("   remove {:<10} -> {:?}", shown, trie.remove(&key)),
+            5 => println!("   get    {:<10} -> {:?}", shown, trie.get(&key)),
+            _ => println!(
+                "   prefix {:<10} -> {:?}",
+                shown,
+                collect(trie.prefix(&key))
+            ),
+        }
+    }
+    println!("   the tree now:");
+    for line in trie.tree().lines() {
+        println!("     {}", line);
+    }
+
+    // 4. A fleet
+    println!("\n4. 10,000 devices in plant/floor/line/device ids:");
+    let models = ["th-sensor", "flow-meter", "t-probe", "pump"];
+    let ids: Vec<String> = random(42, 10_000)
+        .into_iter()
+        .enumerate()
+        .map(|(n, r)| {
+            format!(
+                "plant-{}/floor-{}/line-{}/{}-{}",
+                r % 10,
+                (r >> 4) % 10,
+                (r >> 8) % 10,
+                models[(r >> 12) as usize % 4],
+                n
+            )
+        })
+        .collect();
+    let fleet: RadixTrie<usize> = ids
+        .iter()
+        .enumerate()
+        .map(|(n, id)| (id.as_str(), n))
+        .collect();
+    let prefixes: HashSet<&str> = ids
+        .iter()
+        .flat_map(|id| (1..=id.len()).map(move |end| &id[..end]))
+        .collect();
+    let key_bytes: usize = ids.iter().map(|id| id.len()).sum();
+    println!(
+        "   {} key bytes; plain trie {} nodes, radix trie {}",
+        key_bytes,
+        prefixes.len(),
+        fleet.nodes()
+    );
+
+    // What the registry did before: scan every device, then sort
+    let map: HashMap<&str, usize> = ids
+        .iter()
+        .enumerate()
+        .map(|(n, id)| (id.as_str(), n))
+        .collect();
+    let prefix = "plant-3/floor-2/";
+    let started = Instant::now();
+    let mut scanned = Vec::new();
+    for _ in 0..QUERIES {
+        scanned = map
+            .iter()
+            .filter(|(id, _)| id.starts_with(prefix))
+            .map(|(id, &n)| (id.to_string(), n))
+            .collect::<Vec<_>>();
+        scanned.sort();
+    }
+    let scan_time = started.elapsed();
+    let started = Instant::now();
+    let mut walked = Vec::new();
+    for _ in 0..QUERIES {
+        walked = fleet
+            .prefix(prefix)
+            .map(|(id, &n)| (id, n))
+            .collect::<Vec<_>>();
+    }
+    let trie_time = started.elapsed();
+    println!(
+        "   {} x \"{}\" ({} devices): scan {:.2?}, trie {:.2?} ({:.0}x)",
+        QUERIES,
+        prefix,
+        walked.len(),
+        scan_time,
+        trie_time,
+        scan_time.as_secs_f64() / trie_time.as_secs_f64().max(1e-9)
+    );
+    println!(
+        "   the walk and the scan find the same: {}",
+        walked == scanned
+    );
+
+    println!("\n=== End of Radix Trie Examples ===");
+}
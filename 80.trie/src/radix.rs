@@ -0,0 +1,330 @@
+// Radix trie (a compressed prefix tree), byte-wise
+//
+//   keys: plant-3/floor-1/th-1  plant-3/floor-2/th-1  plant-3/floor-2/th-2
+//
+//   (root)
+//     └─ "plant-3/floor-"
+//          ├─ "1/th-1"  *
+//          └─ "2/th-"
+//               ├─ "1"  *          * = a key ends here
+//               └─ "2"  *
+//
+// A plain trie has a node per byte, so a fleet of 32-byte ids costs tens
+// of nodes per device. A radix trie puts a run of bytes on each edge, so a
+// node exists only where keys branch or end. No two edges out of a node
+// start with the same byte. The edges are kept sorted by that byte, so
+// finding the next one is a binary search, and a depth-first walk visits
+// the keys in sorted order.
+//
+// - insert: follow the edges that match; where the key leaves an edge
+//   part-way, split the edge in two
+// - remove: take the value, then merge a node left with a single edge into
+//   the edge above it, and drop a node left with none. The trie ends up the
+//   same shape as if the key had never been inserted.
+// - prefix: follow the prefix, which may end in the middle of an edge, and
+//   walk everything below that point
+//
+// Every lookup is O(key length), however many keys are stored.
+
+use std::fmt::Write;
+
+#[derive(Debug, Clone)]
+pub struct RadixTrie<V> {
+    root: Node<V>,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node<V> {
+    value: Option<V>,
+    edges: Vec<Edge<V>>,
+}
+
+#[derive(Debug, Clone)]
+struct Edge<V> {
+    label: Vec<u8>,
+    node: Node<V>,
+}
+
+fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+impl<V> Node<V> {
+    fn new() -> Node<V> {
+        Node {
+            value: None,
+            edges: Vec::new(),
+        }
+    }
+
+    // The edge starting with `byte`, or where it would be inserted
+    fn find(&self, byte: u8) -> Result<usize, usize> {
+        self.edges.binary_search_by_key(&byte, |e| e.label[0])
+    }
+
+    fn remove(&mut self, key: &[u8]) -> Option<V> {
+        if key.is_empty() {
+            return self.value.take();
+        }
+        let at = self.find(key[0]).ok()?;
+        let edge = &mut self.edges[at];
+        let rest = key.strip_prefix(edge.label.as_slice())?;
+        let removed = edge.node.remove(rest)?;
+        if edge.node.value.is_none() {
+            match edge.node.edges.len() {
+                0 => {
+                    self.edges.remove(at);
+                }
+                1 => {
+                    let child = edge.node.edges.pop().expect("one edge");
+                    edge.label.extend(child.label);
+                    edge.node = child.node;
+                }
+                _ => {}
+            }
+        }
+        Some(removed)
+    }
+
+    fn count(&self) -> usize {
+        self.edges.iter().map(|e| 1 + e.node.count()).sum()
+    }
+}
+
+impl<V> Edge<V> {
+    // "floor-2/" split at 6 becomes "floor-" with one edge "2/" below it
+    fn split(&mut self, at: usize) {
+        let tail = self.label.split_off(at);
+        let below = std::mem::replace(&mut self.node, Node::new());
+        self.node.edges.push(Edge {
+            label: tail,
+            node: below,
+        });
+    }
+}
+
+impl<V> Default for RadixTrie<V> {
+    fn default() -> Self {
+        RadixTrie::new()
+    }
+}
+
+impl<V> RadixTrie<V> {
+    pub fn new() -> RadixTrie<V> {
+        RadixTrie {
+            root: Node::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.root = Node::new();
+        self.len = 0;
+    }
+
+    // Returns the old value if `key` was already present
+    pub fn insert(&mut self, key: &str, value: V) -> Option<V> {
+        let mut node = &mut self.root;
+        let mut rest = key.as_bytes();
+        while !rest.is_empty() {
+            match node.find(rest[0]) {
+                Err(at) => {
+                    node.edges.insert(
+                        at,
+                        Edge {
+                            label: rest.to_vec(),
+                            node: Node::new(),
+                        },
+                    );
+                    node = &mut node.edges[at].node;
+                    rest = &[];
+                }
+                Ok(at) => {
+                    let edge = &mut node.edges[at];
+                    let common = common_prefix(&edge.label, rest);
+                    if common < edge.label.len() {
+                        edge.split(common);
+                    }
+                    rest = &rest[common..];
+                    node = &mut edge.node;
+                }
+            }
+        }
+        let old = node.value.replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    fn node(&self, key: &str) -> Option<&Node<V>> {
+        let mut node = &self.root;
+        let mut rest = key.as_bytes();
+        while !rest.is_empty() {
+            let edge = &node.edges[node.find(rest[0]).ok()?];
+            rest = rest.strip_prefix(edge.label.as_slice())?;
+            node = &edge.node;
+        }
+        Some(node)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&V> {
+        self.node(key)?.value.as_ref()
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut V> {
+        let mut node = &mut self.root;
+        let mut rest = key.as_bytes();
+        while !rest.is_empty() {
+            let at = node.find(rest[0]).ok()?;
+            let edge = &mut node.edges[at];
+            rest = rest.strip_prefix(edge.label.as_slice())?;
+            node = &mut edge.node;
+        }
+        node.value.as_mut()
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<V> {
+        let removed = self.root.remove(key.as_bytes());
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    // Every key starting with `prefix`, in sorted order
+    pub fn prefix(&self, prefix: &str) -> Iter<'_, V> {
+        let mut node = &self.root;
+        let mut rest = prefix.as_bytes();
+        let mut key = Vec::new();
+        while !rest.is_empty() {
+            let Ok(at) = node.find(rest[0]) else {
+                return Iter::empty();
+            };
+            let edge = &node.edges[at];
+            let common = common_prefix(&edge.label, rest);
+            if common == rest.len() {
+                // The prefix ends on this edge, so all of it matches
+                return Iter {
+                    stack: vec![(key.len(), edge)],
+                    key,
+                    first: None,
+                };
+            }
+            if common < edge.label.len() {
+                return Iter::empty();
+            }
+            key.extend_from_slice(&edge.label);
+            rest = &rest[common..];
+            node = &edge.node;
+        }
+        Iter {
+            stack: node.edges.iter().rev().map(|e| (0, e)).collect(),
+            key,
+            first: node.value.as_ref(),
+        }
+    }
+
+    // All keys, in sorted order
+    pub fn iter(&self) -> Iter<'_, V> {
+        self.prefix("")
+    }
+
+    // Nodes below the root; a plain trie would have one per distinct
+    // prefix of the keys
+    pub fn nodes(&self) -> usize {
+        self.root.count()
+    }
+
+    // One line per edge, indented by depth, with `*` where a key ends
+    pub fn tree(&self) -> String {
+        let mut out = String::new();
+        let mut stack: Vec<(usize, &Edge<V>)> =
+            self.root.edges.iter().rev().map(|e| (0, e)).collect();
+        while let Some((depth, edge)) = stack.pop() {
+            let mark = if edge.node.value.is_some() { " *" } else { "" };
+            let label = String::from_utf8_lossy(&edge.label);
+            let _ = writeln!(out, "{:width$}\"{}\"{}", "", label, mark, width = depth * 2);
+            stack.extend(edge.node.edges.iter().rev().map(|e| (depth + 1, e)));
+        }
+        out
+    }
+}
+
+// Depth-first, edges in byte order, so keys come out sorted
+pub struct Iter<'a, V> {
+    // The key of the node most recently visited
+    key: Vec<u8>,
+    // Edges still to visit, each with the key length above it
+    stack: Vec<(usize, &'a Edge<V>)>,
+    // A value on the starting node itself, which comes first
+    first: Option<&'a V>,
+}
+
+impl<V> Iter<'_, V> {
+    fn empty() -> Self {
+        Iter {
+            key: Vec::new(),
+            stack: Vec::new(),
+            first: None,
+        }
+    }
+
+    fn current_key(&self) -> String {
+        // Labels may split a UTF-8 character, but a whole key never does
+        String::from_utf8(self.key.clone()).expect("keys are inserted as str")
+    }
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (String, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(value) = self.first.take() {
+            return Some((self.current_key(), value));
+        }
+        while let Some((depth, edge)) = self.stack.pop() {
+            self.key.truncate(depth);
+            self.key.extend_from_slice(&edge.label);
+            let depth = self.key.len();
+            self.stack
+                .extend(edge.node.edges.iter().rev().map(|e| (depth, e)));
+            if let Some(value) = &edge.node.value {
+                return Some((self.current_key(), value));
+            }
+        }
+        None
+    }
+}
+
+impl<'a, V> IntoIterator for &'a RadixTrie<V> {
+    type Item = (String, &'a V);
+    type IntoIter = Iter<'a, V>;
+
+    fn into_iter(self) -> Iter<'a, V> {
+        self.iter()
+    }
+}
+
+impl<'a, V> FromIterator<(&'a str, V)> for RadixTrie<V> {
+    fn from_iter<I: IntoIterator<Item = (&'a str, V)>>(iter: I) -> RadixTrie<V> {
+        let mut trie = RadixTrie::new();
+        for (key, value) in iter {
+            trie.insert(key, value);
+        }
+        trie
+    }
+}
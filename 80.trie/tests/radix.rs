@@ -0,0 +1,305 @@
+use std::collections::{BTreeMap, HashMap};
+use trie::RadixTrie;
+
+// xorshift32: the same operations on every run
+struct Rng(u32);
+
+impl Rng {
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+}
+
+// Short keys over a tiny alphabet, so keys share prefixes, split edges
+// and end inside each other all the time
+fn random_key(r: u32) -> String {
+    let len = (r % 7) as usize;
+    (0..len)
+        .map(|i| ['a', 'b', '/', 'é'][(r >> (3 + 2 * i)) as usize % 4])
+        .collect()
+}
+
+fn collect(iter: trie::Iter<'_, u32>) -> Vec<(String, u32)> {
+    iter.map(|(k, &v)| (k, v)).collect()
+}
+
+// What a prefix query on an ordered map is: the run of keys from the
+// prefix onwards that still start with it
+fn oracle_prefix(oracle: &BTreeMap<String, u32>, prefix: &str) -> Vec<(String, u32)> {
+    oracle
+        .range(prefix.to_string()..)
+        .take_while(|(k, _)| k.starts_with(prefix))
+        .map(|(k, &v)| (k.clone(), v))
+        .collect()
+}
+
+fn devices() -> RadixTrie<&'static str> {
+    [
+        ("plant-3/floor-1/th-1", "th-sensor"),
+        ("plant-3/floor-2/th-1", "th-sensor"),
+        ("plant-3/floor-2/th-2", "th-sensor"),
+        ("plant-3/floor-2", "gateway"),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn new_is_empty() {
+    let trie: RadixTrie<u32> = RadixTrie::new();
+    assert!(trie.is_empty());
+    assert_eq!(trie.len(), 0);
+    assert_eq!(trie.nodes(), 0);
+    assert_eq!(trie.iter().count(), 0);
+    assert_eq!(trie.get(""), None);
+}
+
+#[test]
+fn inserts_split_edges_where_keys_branch() {
+    let trie = devices();
+    assert_eq!(trie.len(), 4);
+    assert_eq!(trie.nodes(), 6);
+    assert_eq!(
+        trie.tree(),
+        "\"plant-3/floor-\"\n\
+         \x20 \"1/th-1\" *\n\
+         \x20 \"2\" *\n\
+         \x20   \"/th-\"\n\
+         \x20     \"1\" *\n\
+         \x20     \"2\" *\n"
+    );
+}
+
+#[test]
+fn lookups_match_whole_keys_only() {
+    let trie = devices();
+    // A key may end on a branch point
+    assert_eq!(trie.get("plant-3/floor-2"), Some(&"gateway"));
+    assert_eq!(trie.get("plant-3/floor-2/th-2"), Some(&"th-sensor"));
+    // Part-way along an edge, or on a node with no value, is no match
+    assert_eq!(trie.get("plant-3/floor"), None);
+    assert_eq!(trie.get("plant-3/floor-"), None);
+    assert!(!trie.contains_key("plant-3/floor-2/th"));
+    assert!(!trie.contains_key("plant-3/floor-2/th-22"));
+    assert!(!trie.contains_key("plant-4"));
+}
+
+#[test]
+fn insert_replaces_and_get_mut_changes_in_place() {
+    let mut trie = devices();
+    assert_eq!(trie.insert("plant-3/floor-2", "router"), Some("gateway"));
+    assert_eq!(trie.len(), 4);
+    if let Some(model) = trie.get_mut("plant-3/floor-1/th-1") {
+        *model = "th-sensor-v2";
+    }
+    assert_eq!(trie.get("plant-3/floor-1/th-1"), Some(&"th-sensor-v2"));
+    assert_eq!(trie.get("plant-3/floor-2"), Some(&"router"));
+    assert!(trie.get_mut("plant-3/floor-2/th").is_none());
+}
+
+#[test]
+fn prefix_queries_in_key_order() {
+    let trie = devices();
+    let ids = |prefix| -> Vec<String> { trie.prefix(prefix).map(|(id, _)| id).collect() };
+    // The trailing / leaves out the gateway itself
+    assert_eq!(
+        ids("plant-3/floor-2/"),
+        ["plant-3/floor-2/th-1", "plant-3/floor-2/th-2"]
+    );
+    // A prefix may end inside an edge
+    assert_eq!(
+        ids("plant-3/fl"),
+        [
+            "plant-3/floor-1/th-1",
+            "plant-3/floor-2",
+            "plant-3/floor-2/th-1",
+            "plant-3/floor-2/th-2",
+        ]
+    );
+    assert_eq!(ids(""), ids("plant-3/fl"));
+    assert_eq!(ids("plant-3/floor-2/th-2"), ["plant-3/floor-2/th-2"]);
+    assert!(ids("plant-4/").is_empty());
+    // Leaving an edge before the prefix ends matches nothing
+    assert!(ids("plant-3/flx").is_empty());
+    assert!(ids("plant-3/floor-2/th-22").is_empty());
+}
+
+#[test]
+fn remove_merges_edges_back() {
+    let mut trie = devices();
+    let before = trie.tree();
+    trie.insert("plant-3/floor-2/th-10", "th-sensor");
+    assert_eq!(trie.nodes(), 7);
+    assert_eq!(trie.remove("plant-3/floor-2/th-10"), Some("th-sensor"));
+    assert_eq!(trie.nodes(), 6);
+    assert_eq!(trie.tree(), before);
+
+    // Removing the gateway leaves "2" with one edge, merged into "2/th-"
+    assert_eq!(trie.remove("plant-3/floor-2"), Some("gateway"));
+    assert_eq!(
+        trie.tree(),
+        "\"plant-3/floor-\"\n\
+         \x20 \"1/th-1\" *\n\
+         \x20 \"2/th-\"\n\
+         \x20   \"1\" *\n\
+         \x20   \"2\" *\n"
+    );
+}
+
+#[test]
+fn removing_a_missing_key_changes_nothing() {
+    let mut trie = devices();
+    let before = trie.tree();
+    for key in ["plant-3/floor-9", "plant-3/floor", "plant-3/floor-", ""] {
+        assert_eq!(trie.remove(key), None, "{:?}", key);
+    }
+    assert_eq!(trie.len(), 4);
+    assert_eq!(trie.tree(), before);
+}
+
+#[test]
+fn the_empty_key_is_stored_on_the_root() {
+    let mut trie = RadixTrie::new();
+    assert_eq!(trie.insert("", 0), None);
+    trie.insert("a", 1);
+    assert_eq!(trie.len(), 2);
+    assert_eq!(trie.nodes(), 1);
+    assert_eq!(trie.get(""), Some(&0));
+    assert_eq!(
+        collect(trie.iter()),
+        [("".to_string(), 0), ("a".to_string(), 1)]
+    );
+    assert_eq!(trie.remove(""), Some(0));
+    assert_eq!(collect(trie.iter()), [("a".to_string(), 1)]);
+}
+
+#[test]
+fn splits_inside_a_utf8_character_give_whole_keys_back() {
+    // "é" is 0xC3 0xA9 and "ê" is 0xC3 0xAA, so they split between bytes
+    let trie: RadixTrie<u32> = [("é", 1), ("ê", 2), ("éa", 3)].into_iter().collect();
+    assert_eq!(
+        collect(trie.iter()),
+        [
+            ("é".to_string(), 1),
+            ("éa".to_string(), 3),
+            ("ê".to_string(), 2),
+        ]
+    );
+    assert_eq!(
+        collect(trie.prefix("é")),
+        [("é".to_string(), 1), ("éa".to_string(), 3)]
+    );
+    assert_eq!(trie.nodes(), 4);
+}
+
+#[test]
+fn clear_and_from_iter_with_repeated_keys() {
+    let mut trie: RadixTrie<u32> = [("a", 1), ("ab", 2), ("a", 3)].into_iter().collect();
+    assert_eq!(trie.len(), 2);
+    assert_eq!(trie.get("a"), Some(&3));
+    let pairs: Vec<(String, u32)> = (&trie).into_iter().map(|(k, &v)| (k, v)).collect();
+    assert_eq!(pairs, [("a".to_string(), 3), ("ab".to_string(), 2)]);
+    trie.clear();
+    assert!(trie.is_empty());
+    assert_eq!(trie.nodes(), 0);
+    assert_eq!(trie.get("a"), None);
+}
+
+// Random scripts of inserts, removes, lookups and prefix queries, applied
+// to the trie and a BTreeMap; any difference is a bug in the trie
+#[test]
+fn random_scripts_match_a_btreemap() {
+    let mut rng = Rng(0x2545_F491);
+    for script in 0..300 {
+        let mut trie = RadixTrie::new();
+        let mut oracle = BTreeMap::new();
+        let len = rng.next() % 600;
+        for step in 0..len {
+            let op = rng.next();
+            let key = random_key(op >> 4);
+            let at = format!("script {} step {} key {:?}", script, step, key);
+            match op % 8 {
+                0..=2 => assert_eq!(trie.insert(&key, op), oracle.insert(key, op), "{}", at),
+                3 | 4 => assert_eq!(trie.remove(&key), oracle.remove(&key), "{}", at),
+                5 => {
+                    assert_eq!(trie.get(&key), oracle.get(&key), "{}", at);
+                    assert_eq!(trie.contains_key(&key), oracle.contains_key(&key), "{}", at);
+                }
+                6 => {
+                    if let Some(value) = trie.get_mut(&key) {
+                        *value ^= 1;
+                    }
+                    if let Some(value) = oracle.get_mut(&key) {
+                        *value ^= 1;
+                    }
+                }
+                _ => assert_eq!(
+                    collect(trie.prefix(&key)),
+                    oracle_prefix(&oracle, &key),
+                    "{}",
+                    at
+                ),
+            }
+            assert_eq!(trie.len(), oracle.len(), "{}", at);
+        }
+        let entries: Vec<(String, u32)> = oracle.iter().map(|(k, &v)| (k.clone(), v)).collect();
+        assert_eq!(collect(trie.iter()), entries, "script {}", script);
+        // Removes must leave the shape a fresh build of the same keys has
+        let rebuilt: RadixTrie<u32> = oracle.iter().map(|(k, &v)| (k.as_str(), v)).collect();
+        assert_eq!(trie.tree(), rebuilt.tree(), "script {}", script);
+        assert_eq!(trie.nodes(), rebuilt.nodes(), "script {}", script);
+    }
+}
+
+#[test]
+fn a_fleet_answers_prefixes_like_a_scan() {
+    let models = ["th-sensor", "flow-meter", "t-probe", "pump"];
+    let mut rng = Rng(42);
+    let ids: Vec<String> = (0..10_000)
+        .map(|n| {
+            let r = rng.next();
+            format!(
+                "plant-{}/floor-{}/line-{}/{}-{}",
+                r % 10,
+                (r >> 4) % 10,
+                (r >> 8) % 10,
+                models[(r >> 12) as usize % 4],
+                n
+            )
+        })
+        .collect();
+    let fleet: RadixTrie<usize> = ids
+        .iter()
+        .enumerate()
+        .map(|(n, id)| (id.as_str(), n))
+        .collect();
+    assert_eq!(fleet.len(), ids.len());
+    // A node only where keys branch or end
+    assert!(fleet.nodes() < 2 * fleet.len());
+
+    // What the registry did before: scan every device, then sort
+    let map: HashMap<&str, usize> = ids
+        .iter()
+        .enumerate()
+        .map(|(n, id)| (id.as_str(), n))
+        .collect();
+    for prefix in [
+        "plant-3/floor-2/",
+        "plant-7/",
+        "plant-1/floor-0/line-5/pu",
+        "plant-",
+        "x",
+    ] {
+        let mut scanned: Vec<(String, usize)> = map
+            .iter()
+            .filter(|(id, _)| id.starts_with(prefix))
+            .map(|(id, &n)| (id.to_string(), n))
+            .collect();
+        scanned.sort();
+        let walked: Vec<(String, usize)> = fleet.prefix(prefix).map(|(id, &n)| (id, n)).collect();
+        assert_eq!(walked, scanned, "{:?}", prefix);
+    }
+}
@@ -0,0 +1,197 @@
+// Event bus keyed by type
+//
+//   bus.subscribe::<AlertRaised>(|e| ...)      bus.publish(AlertRaised { .. })
+//
+//   subscribers: TypeId ──▶ [handler, handler, ...]
+//                              │
+//   Box<dyn FnMut(&dyn Any)> ──┘ downcasts to &AlertRaised, then calls
+//                                the handler that was registered
+//
+// Publishers and subscribers share only the event types. A rule engine
+// publishes `AlertRaised` without knowing whether a logger, an uplink or
+// nobody at all is listening.
+//
+// Each handler is stored type-erased, with its event type's `TypeId` as
+// the key. `subscribe::<E>` wraps the handler in a closure that downcasts
+// `&dyn Any` back to `&E`. That downcast can't fail, because the closure
+// only ever sees events filed under `TypeId::of::<E>()`.
+//
+// Handlers run while the bus is borrowed mutably, so they can't publish on
+// it directly. Instead they keep a `Poster`, a handle to the bus's queue.
+// `publish` delivers its event and then whatever the handlers posted,
+// first in, first out, until the queue is empty. A reading therefore
+// reaches every subscriber before the alert it caused reaches any.
+
+use std::any::{type_name, Any, TypeId};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SubscriptionId(pub(crate) u64);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BusStats {
+    // Events taken off the queue, including follow-ups
+    pub published: u64,
+    // Handler calls
+    pub delivered: u64,
+    // Events no handler was subscribed to
+    pub unhandled: u64,
+}
+
+type Handler = Box<dyn FnMut(&dyn Any)>;
+
+struct Subscriber {
+    id: SubscriptionId,
+    handler: Handler,
+}
+
+struct Envelope {
+    type_id: TypeId,
+    event: Box<dyn Any>,
+}
+
+type Queue = Rc<RefCell<VecDeque<Envelope>>>;
+
+// Posts events from inside a handler; they are delivered once the current
+// event has reached all of its subscribers
+#[derive(Clone)]
+pub struct Poster {
+    queue: Queue,
+}
+
+impl Poster {
+    pub fn post<E: Any>(&self, event: E) {
+        self.queue.borrow_mut().push_back(Envelope {
+            type_id: TypeId::of::<E>(),
+            event: Box::new(event),
+        });
+    }
+}
+
+impl fmt::Debug for Poster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Poster({} queued)", self.queue.borrow().len())
+    }
+}
+
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: HashMap<TypeId, Vec<Subscriber>>,
+    // For `event_types`; `TypeId` itself prints as an opaque number
+    names: HashMap<TypeId, &'static str>,
+    queue: Queue,
+    next_id: u64,
+    stats: BusStats,
+}
+
+impl EventBus {
+    pub fn new() -> EventBus {
+        EventBus::default()
+    }
+
+    // Handlers for the same type run in the order they subscribed
+    pub fn subscribe<E, F>(&mut self, mut handler: F) -> SubscriptionId
+    where
+        E: Any,
+        F: FnMut(&E) + 'static,
+    {
+        let handler: Handler = Box::new(move |event: &dyn Any| {
+            let event = event.downcast_ref::<E>().expect("filed under E's TypeId");
+            handler(event)
+        });
+        let id = SubscriptionId(self.next_id);
+        self.next_id += 1;
+        let type_id = TypeId::of::<E>();
+        self.names.insert(type_id, type_name::<E>());
+        self.subscribers
+            .entry(type_id)
+            .or_default()
+            .push(Subscriber { id, handler });
+        id
+    }
+
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        for subscribers in self.subscribers.values_mut() {
+            if let Some(at) = subscribers.iter().position(|s| s.id == id) {
+                subscribers.remove(at);
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn subscribers<E: Any>(&self) -> usize {
+        self.subscribers
+            .get(&TypeId::of::<E>())
+            .map_or(0, |s| s.len())
+    }
+
+    // Names of the event types with at least one subscriber, sorted
+    pub fn event_types(&self) -> Vec<&'static str> {
+        let mut names: Vec<&'static str> = self
+            .subscribers
+            .iter()
+            .filter(|(_, s)| !s.is_empty())
+            .map(|(type_id, _)| self.names[type_id])
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub fn poster(&self) -> Poster {
+        Poster {
+            queue: Rc::clone(&self.queue),
+        }
+    }
+
+    // Delivers `event`, then the events its handlers post; returns the
+    // number of handler calls
+    pub fn publish<E: Any>(&mut self, event: E) -> usize {
+        self.poster().post(event);
+        self.dispatch()
+    }
+
+    // Delivers everything queued so far
+    pub fn dispatch(&mut self) -> usize {
+        let mut calls = 0;
+        loop {
+            // The borrow must end before any handler runs, since handlers
+            // post to the same queue
+            let next = self.queue.borrow_mut().pop_front();
+            let Some(envelope) = next else {
+                break;
+            };
+            self.stats.published += 1;
+            match self.subscribers.get_mut(&envelope.type_id) {
+                Some(subscribers) if !subscribers.is_empty() => {
+                    for subscriber in subscribers.iter_mut() {
+                        // `&*` reaches the event inside the Box; a
+                        // `&Box<dyn Any>` would itself coerce to `&dyn Any`
+                        // and never downcast to `E`
+                        (subscriber.handler)(&*envelope.event);
+                        calls += 1;
+                    }
+                }
+                _ => self.stats.unhandled += 1,
+            }
+        }
+        self.stats.delivered += calls as u64;
+        calls
+    }
+
+    pub fn stats(&self) -> BusStats {
+        self.stats
+    }
+}
+
+impl fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventBus")
+            .field("event_types", &self.event_types())
+            .field("stats", &self.stats)
+            .finish()
+    }
+}
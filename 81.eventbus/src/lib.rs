@@ -0,0 +1,16 @@
+// Typed event buses
+//
+// - `bus`: `EventBus`, where components subscribe by event type and
+//   publishers post any `'static` value; handlers can post follow-up
+//   events through a `Poster`
+// - `sync`: `SyncEventBus`, the same idea behind `&self` for handlers that
+//   are `Send + Sync`, shared between threads in an `Arc`
+//
+// 16.gateway's rule engine, data logger and uplink batcher talk to each
+// other only through an `EventBus`.
+
+pub mod bus;
+pub mod sync;
+
+pub use bus::{BusStats, EventBus, Poster, SubscriptionId};
+pub use sync::SyncEventBus;
@@ -0,0 +1,237 @@
+use eventbus::{EventBus, SyncEventBus};
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+const THREADS: u64 = 4;
+const PER_THREAD: u64 = 25_000;
+const EVENTS: u64 = 1_000_000;
+
+// Events are plain structs; nothing to implement or register
+#[derive(Debug, Clone, PartialEq)]
+struct ReadingTaken {
+    sensor: u16,
+    celsius: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct AlertRaised {
+    rule: String,
+    celsius: f64,
+}
+
+#[derive(Debug)]
+struct BatchSent {
+    seq: u64,
+}
+
+fn main() {
+    println!("=== Typed Event Bus Examples ===\n");
+
+    // 1. Subscribing by type
+    println!("1. Subscribing by type:");
+    let mut bus = EventBus::new();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    {
+        let log = Rc::clone(&log);
+        bus.subscribe::<ReadingTaken, _>(move |r| {
+            log.borrow_mut()
+                .push(format!("logger: sensor {} {:.1} C", r.sensor, r.celsius))
+        });
+    }
+    {
+        let log = Rc::clone(&log);
+        bus.subscribe(move |a: &AlertRaised| log.borrow_mut().push(format!("uplink: {}", a.rule)));
+    }
+    let readings = bus.publish(ReadingTaken {
+        sensor: 3,
+        celsius: 21.5,
+    });
+    let alerts = bus.publish(AlertRaised {
+        rule: "overheat/3".into(),
+        celsius: 81.0,
+    });
+    let nobody = bus.publish(BatchSent { seq: 1 });
+    for line in log.borrow().iter() {
+        println!("   {}", line);
+    }
+    println!("   subscribed types: {:?}", bus.event_types());
+    println!(
+        "   handler calls: reading {}, alert {}, batch {} ({} unhandled)",
+        readings,
+        alerts,
+        nobody,
+        bus.stats().unhandled
+    );
+
+    // 2. Follow-up events
+    println!("\n2. A handler that publishes (rule engine -> alerts):");
+    let mut bus = EventBus::new();
+    let order = Rc::new(RefCell::new(Vec::new()));
+    let poster = bus.poster();
+    bus.subscribe(move |r: &ReadingTaken| {
+        if r.celsius > 80.0 {
+            poster.post(AlertRaised {
+                rule: format!("overheat/{}", r.sensor),
+                celsius: r.celsius,
+            });
+        }
+    });
+    for name in ["logger", "uplink"] {
+        let readings = Rc::clone(&order);
+        bus.subscribe(move |r: &ReadingTaken| {
+            readings
+                .borrow_mut()
+                .push(format!("{} <- reading {}", name, r.sensor))
+        });
+        let alerts = Rc::clone(&order);
+        bus.subscribe(move |a: &AlertRaised| {
+            alerts
+                .borrow_mut()
+                .push(format!("{} <- alert {}", name, a.rule))
+        });
+    }
+    let calls = bus.publish(ReadingTaken {
+        sensor: 7,
+        celsius: 85.0,
+    });
+    for line in order.borrow().iter() {
+        println!("   {}", line);
+    }
+    println!("   one publish, {} handler calls", calls);
+
+    // 3. Unsubscribing, and what Any checks
+    println!("\n3. Unsubscribing and downcasting:");
+    let mut bus = EventBus::new();
+    let seen = Rc::new(Cell::new(0));
+    let first = {
+        let seen = Rc::clone(&seen);
+        bus.subscribe(move |_: &BatchSent| seen.set(seen.get() + 1))
+    };
+    {
+        let seen = Rc::clone(&seen);
+        bus.subscribe(move |b: &BatchSent| seen.set(seen.get() + b.seq as i32 * 10));
+    }
+    bus.publish(BatchSent { seq: 1 });
+    let removed = bus.unsubscribe(first);
+    bus.publish(BatchSent { seq: 2 });
+    println!(
+        "   handlers called: 11 before, then 20 after unsubscribing ({})",
+        seen.get()
+    );
+    println!(
+        "   unsubscribe: {} the first time, {} the second",
+        removed,
+        bus.unsubscribe(first)
+    );
+    // The same event, boxed; `&boxed` is a `&Box<dyn Any>`, which is
+    // itself an Any of type Box<dyn Any>
+    let boxed: Box<dyn Any> = Box::new(BatchSent { seq: 3 });
+    let through_box = (&boxed as &dyn Any).downcast_ref::<BatchSent>().is_some();
+    let inside = boxed.downcast_ref::<BatchSent>().map(|b| b.seq);
+    println!(
+        "   downcast of &boxed: {}, of &*boxed: {:?}",
+        through_box, inside
+    );
+
+    // 4. Across threads
+    println!(
+        "\n4. SyncEventBus, {} threads publishing {} readings each:",
+        THREADS, PER_THREAD
+    );
+    let bus = Arc::new(SyncEventBus::new());
+    let total = Arc::new(AtomicU64::new(0));
+    let alerts = Arc::new(Mutex::new(Vec::new()));
+    {
+        let total = Arc::clone(&total);
+        bus.subscribe(move |r: &ReadingTaken| {
+            total.fetch_add(r.sensor as u64, Ordering::Relaxed);
+        });
+    }
+    {
+        // Handlers may publish on the bus they are called from
+        let inner = Arc::downgrade(&bus);
+        bus.subscribe(move |r: &ReadingTaken| {
+            if r.celsius > 80.0 {
+                if let Some(bus) = inner.upgrade() {
+                    bus.publish(AlertRaised {
+                        rule: format!("overheat/{}", r.sensor),
+                        celsius: r.celsius,
+                    });
+                }
+            }
+        });
+    }
+    {
+        let alerts = Arc::clone(&alerts);
+        bus.subscribe(move |a: &AlertRaised| alerts.lock().unwrap().push(a.rule.clone()));
+    }
+    let workers: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let bus = Arc::clone(&bus);
+            thread::spawn(move || {
+                for i in 0..PER_THREAD {
+                    // Every 1,000th reading is too hot
+                    let celsius = if i % 1000 == 999 { 90.0 } else { 20.0 };
+                    bus.publish(ReadingTaken {
+                        sensor: t as u16 + 1,
+                        celsius,
+                    });
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().expect("publisher thread");
+    }
+    let stats = bus.stats();
+    let raised = alerts.lock().unwrap().len() as u64;
+    println!(
+        "   {} published, {} handler calls, {} alerts from nested publishes",
+        stats.published, stats.delivered, raised
+    );
+    println!(
+        "   sensor ids summed by the handler: {}",
+        total.load(Ordering::Relaxed)
+    );
+
+    // 5. The price of decoupling
+    println!("\n5. {} events, direct call vs bus:", EVENTS);
+    let mut direct_sum = 0.0;
+    let mut record = |r: &ReadingTaken| direct_sum += r.celsius;
+    let started = Instant::now();
+    for i in 0..EVENTS {
+        record(&ReadingTaken {
+            sensor: 1,
+            celsius: i as f64,
+        });
+    }
+    let direct = started.elapsed();
+    let mut bus = EventBus::new();
+    let bus_sum = Rc::new(Cell::new(0.0));
+    {
+        let bus_sum = Rc::clone(&bus_sum);
+        bus.subscribe(move |r: &ReadingTaken| bus_sum.set(bus_sum.get() + r.celsius));
+    }
+    let started = Instant::now();
+    for i in 0..EVENTS {
+        bus.publish(ReadingTaken {
+            sensor: 1,
+            celsius: i as f64,
+        });
+    }
+    let published = started.elapsed();
+    println!(
+        "   direct: {:.2?}, bus: {:.2?} ({:.0} ns per event: a Box, a hash lookup, a downcast)",
+        direct,
+        published,
+        (published.as_nanos() as f64 - direct.as_nanos() as f64) / EVENTS as f64
+    );
+    println!("   both summed {} and {}", direct_sum, bus_sum.get());
+
+    println!("\n=== End of Typed Event Bus Examples ===");
+}
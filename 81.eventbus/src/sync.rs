@@ -0,0 +1,114 @@
+// Thread-safe event bus
+//
+// The same type-keyed map behind an `RwLock`, with every method on `&self`
+// so one bus can be shared in an `Arc`. Handlers are `Fn + Send + Sync`
+// and are stored as `Arc`s. `publish` clones the matching handlers under
+// the read lock, releases it, and then calls them on the publishing
+// thread. Because no lock is held while a handler runs, a handler may
+// publish or subscribe on the same bus without deadlocking. A nested
+// publish is delivered at once, depth first, rather than queued as in
+// `EventBus`.
+//
+// The event itself never leaves the publishing thread, so it needs no
+// `Send` bound; the handlers do, since any thread may call them.
+
+use crate::bus::{BusStats, SubscriptionId};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+type SyncHandler = Arc<dyn Fn(&dyn Any) + Send + Sync>;
+type Subscribers = HashMap<TypeId, Vec<(SubscriptionId, SyncHandler)>>;
+
+#[derive(Default)]
+pub struct SyncEventBus {
+    subscribers: RwLock<Subscribers>,
+    next_id: AtomicU64,
+    published: AtomicU64,
+    delivered: AtomicU64,
+    unhandled: AtomicU64,
+}
+
+impl SyncEventBus {
+    pub fn new() -> SyncEventBus {
+        SyncEventBus::default()
+    }
+
+    // Handlers run with the lock released, so a panicking handler can't
+    // poison it, and the map is never left half-updated anyway
+    fn read(&self) -> RwLockReadGuard<'_, Subscribers> {
+        self.subscribers.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Subscribers> {
+        self.subscribers.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn subscribe<E, F>(&self, handler: F) -> SubscriptionId
+    where
+        E: Any,
+        F: Fn(&E) + Send + Sync + 'static,
+    {
+        let handler: SyncHandler = Arc::new(move |event: &dyn Any| {
+            handler(event.downcast_ref::<E>().expect("filed under E's TypeId"))
+        });
+        let id = SubscriptionId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.write()
+            .entry(TypeId::of::<E>())
+            .or_default()
+            .push((id, handler));
+        id
+    }
+
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        for subscribers in self.write().values_mut() {
+            if let Some(at) = subscribers.iter().position(|(s, _)| *s == id) {
+                subscribers.remove(at);
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn subscribers<E: Any>(&self) -> usize {
+        self.read().get(&TypeId::of::<E>()).map_or(0, |s| s.len())
+    }
+
+    // Calls every handler for `E` on this thread; returns how many ran
+    pub fn publish<E: Any>(&self, event: E) -> usize {
+        let handlers: Vec<SyncHandler> = self
+            .read()
+            .get(&TypeId::of::<E>())
+            .map(|s| s.iter().map(|(_, h)| Arc::clone(h)).collect())
+            .unwrap_or_default();
+        self.published.fetch_add(1, Ordering::Relaxed);
+        if handlers.is_empty() {
+            self.unhandled.fetch_add(1, Ordering::Relaxed);
+        }
+        for handler in &handlers {
+            handler(&event);
+        }
+        self.delivered
+            .fetch_add(handlers.len() as u64, Ordering::Relaxed);
+        handlers.len()
+    }
+
+    pub fn stats(&self) -> BusStats {
+        BusStats {
+            published: self.published.load(Ordering::Relaxed),
+            delivered: self.delivered.load(Ordering::Relaxed),
+            unhandled: self.unhandled.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl fmt::Debug for SyncEventBus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyncEventBus")
+            .field("event_types", &self.read().len())
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
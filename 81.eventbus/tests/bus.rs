@@ -0,0 +1,184 @@
+use eventbus::{BusStats, EventBus};
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq)]
+struct ReadingTaken {
+    sensor: u16,
+    celsius: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct AlertRaised {
+    rule: String,
+}
+
+#[derive(Debug)]
+struct BatchSent {
+    seq: u64,
+}
+
+type Log = Rc<RefCell<Vec<String>>>;
+
+#[test]
+fn each_event_reaches_its_own_types_handlers() {
+    let mut bus = EventBus::new();
+    let log: Log = Rc::default();
+    let readings = Rc::clone(&log);
+    bus.subscribe(move |r: &ReadingTaken| {
+        readings
+            .borrow_mut()
+            .push(format!("reading {} {:.1}", r.sensor, r.celsius))
+    });
+    let alerts = Rc::clone(&log);
+    bus.subscribe(move |a: &AlertRaised| alerts.borrow_mut().push(format!("alert {}", a.rule)));
+
+    let calls = bus.publish(ReadingTaken {
+        sensor: 3,
+        celsius: 21.5,
+    });
+    assert_eq!(calls, 1);
+    assert_eq!(
+        bus.publish(AlertRaised {
+            rule: "overheat/3".into()
+        }),
+        1
+    );
+    assert_eq!(*log.borrow(), ["reading 3 21.5", "alert overheat/3"]);
+    assert_eq!(bus.subscribers::<ReadingTaken>(), 1);
+    assert_eq!(bus.subscribers::<BatchSent>(), 0);
+    let types = bus.event_types();
+    assert_eq!(types.len(), 2);
+    assert!(types[0].ends_with("AlertRaised"), "{:?}", types);
+    assert!(types[1].ends_with("ReadingTaken"), "{:?}", types);
+}
+
+#[test]
+fn an_unsubscribed_type_is_counted_not_lost() {
+    let mut bus = EventBus::new();
+    bus.subscribe(|_: &ReadingTaken| {});
+    assert_eq!(bus.publish(BatchSent { seq: 1 }), 0);
+    assert_eq!(
+        bus.stats(),
+        BusStats {
+            published: 1,
+            delivered: 0,
+            unhandled: 1
+        }
+    );
+}
+
+#[test]
+fn handlers_for_a_type_run_in_subscription_order() {
+    let mut bus = EventBus::new();
+    let log: Log = Rc::default();
+    for name in ["logger", "uplink", "display"] {
+        let log = Rc::clone(&log);
+        bus.subscribe(move |_: &BatchSent| log.borrow_mut().push(name.to_string()));
+    }
+    bus.publish(BatchSent { seq: 1 });
+    assert_eq!(*log.borrow(), ["logger", "uplink", "display"]);
+}
+
+// The rule engine turns a hot reading into an alert through a `Poster`
+#[test]
+fn follow_ups_wait_until_the_event_that_caused_them_is_delivered() {
+    let mut bus = EventBus::new();
+    let log: Log = Rc::default();
+    let poster = bus.poster();
+    bus.subscribe(move |r: &ReadingTaken| {
+        if r.celsius > 80.0 {
+            poster.post(AlertRaised {
+                rule: format!("overheat/{}", r.sensor),
+            });
+        }
+    });
+    for name in ["logger", "uplink"] {
+        let readings = Rc::clone(&log);
+        bus.subscribe(move |r: &ReadingTaken| {
+            readings
+                .borrow_mut()
+                .push(format!("{} <- reading {}", name, r.sensor))
+        });
+        let alerts = Rc::clone(&log);
+        bus.subscribe(move |a: &AlertRaised| {
+            alerts
+                .borrow_mut()
+                .push(format!("{} <- alert {}", name, a.rule))
+        });
+    }
+    let calls = bus.publish(ReadingTaken {
+        sensor: 7,
+        celsius: 85.0,
+    });
+    assert_eq!(
+        *log.borrow(),
+        [
+            "logger <- reading 7",
+            "uplink <- reading 7",
+            "logger <- alert overheat/7",
+            "uplink <- alert overheat/7",
+        ]
+    );
+    assert_eq!(calls, 5);
+    assert_eq!(bus.stats().published, 2);
+    assert_eq!(bus.stats().delivered, 5);
+}
+
+#[test]
+fn posted_events_wait_for_dispatch() {
+    let mut bus = EventBus::new();
+    let seen = Rc::new(Cell::new(0));
+    let counter = Rc::clone(&seen);
+    bus.subscribe(move |b: &BatchSent| counter.set(counter.get() + b.seq));
+    let poster = bus.poster();
+    poster.post(BatchSent { seq: 1 });
+    poster.post(BatchSent { seq: 2 });
+    assert_eq!(seen.get(), 0);
+    assert_eq!(bus.dispatch(), 2);
+    assert_eq!(seen.get(), 3);
+    assert_eq!(bus.dispatch(), 0);
+}
+
+#[test]
+fn unsubscribe_removes_exactly_one_handler() {
+    let mut bus = EventBus::new();
+    let seen = Rc::new(Cell::new(0));
+    let first = {
+        let seen = Rc::clone(&seen);
+        bus.subscribe(move |_: &BatchSent| seen.set(seen.get() + 1))
+    };
+    {
+        let seen = Rc::clone(&seen);
+        bus.subscribe(move |b: &BatchSent| seen.set(seen.get() + b.seq * 10));
+    }
+    bus.publish(BatchSent { seq: 1 });
+    assert_eq!(seen.get(), 11);
+    assert!(bus.unsubscribe(first));
+    assert!(!bus.unsubscribe(first));
+    bus.publish(BatchSent { seq: 2 });
+    assert_eq!(seen.get(), 31);
+    assert_eq!(bus.subscribers::<BatchSent>(), 1);
+}
+
+#[test]
+fn a_type_with_no_handlers_left_is_not_listed() {
+    let mut bus = EventBus::new();
+    let id = bus.subscribe(|_: &BatchSent| {});
+    assert_eq!(bus.event_types().len(), 1);
+    bus.unsubscribe(id);
+    assert!(bus.event_types().is_empty());
+    assert_eq!(bus.publish(BatchSent { seq: 1 }), 0);
+    assert_eq!(bus.stats().unhandled, 1);
+}
+
+// What `dispatch`'s `&*envelope.event` is about: a `&Box<dyn Any>` is an
+// Any of its own and never downcasts to what is inside
+#[test]
+fn downcasting_needs_the_value_not_the_box() {
+    let boxed: Box<dyn Any> = Box::new(BatchSent { seq: 3 });
+    assert!((&boxed as &dyn Any).downcast_ref::<BatchSent>().is_none());
+    assert_eq!(boxed.downcast_ref::<BatchSent>().map(|b| b.seq), Some(3));
+    assert!(boxed.downcast_ref::<AlertRaised>().is_none());
+}
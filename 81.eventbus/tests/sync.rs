@@ -0,0 +1,163 @@
+use eventbus::SyncEventBus;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+const THREADS: u64 = 4;
+const PER_THREAD: u64 = 25_000;
+
+#[derive(Debug)]
+struct ReadingTaken {
+    sensor: u16,
+    celsius: f64,
+}
+
+#[derive(Debug)]
+struct AlertRaised {
+    rule: String,
+}
+
+#[test]
+fn no_event_is_lost_between_threads() {
+    let bus = Arc::new(SyncEventBus::new());
+    let total = Arc::new(AtomicU64::new(0));
+    {
+        let total = Arc::clone(&total);
+        bus.subscribe(move |r: &ReadingTaken| {
+            total.fetch_add(r.sensor as u64, Ordering::Relaxed);
+        });
+    }
+    let workers: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let bus = Arc::clone(&bus);
+            thread::spawn(move || {
+                for _ in 0..PER_THREAD {
+                    bus.publish(ReadingTaken {
+                        sensor: t as u16 + 1,
+                        celsius: 20.0,
+                    });
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    assert_eq!(
+        total.load(Ordering::Relaxed),
+        (1..=THREADS).sum::<u64>() * PER_THREAD
+    );
+    let stats = bus.stats();
+    assert_eq!(stats.published, THREADS * PER_THREAD);
+    assert_eq!(stats.delivered, THREADS * PER_THREAD);
+    assert_eq!(stats.unhandled, 0);
+}
+
+// A nested publish is delivered at once, before the outer one's next handler
+#[test]
+fn a_handler_can_publish_on_its_own_bus() {
+    let bus = Arc::new(SyncEventBus::new());
+    let log = Arc::new(Mutex::new(Vec::new()));
+    {
+        let inner = Arc::downgrade(&bus);
+        bus.subscribe(move |r: &ReadingTaken| {
+            if r.celsius > 80.0 {
+                if let Some(bus) = inner.upgrade() {
+                    bus.publish(AlertRaised {
+                        rule: format!("overheat/{}", r.sensor),
+                    });
+                }
+            }
+        });
+    }
+    {
+        let log = Arc::clone(&log);
+        bus.subscribe(move |r: &ReadingTaken| {
+            log.lock().unwrap().push(format!("reading {}", r.sensor))
+        });
+    }
+    {
+        let log = Arc::clone(&log);
+        bus.subscribe(move |a: &AlertRaised| log.lock().unwrap().push(format!("alert {}", a.rule)));
+    }
+    bus.publish(ReadingTaken {
+        sensor: 7,
+        celsius: 85.0,
+    });
+    assert_eq!(*log.lock().unwrap(), ["alert overheat/7", "reading 7"]);
+    assert_eq!(bus.stats().published, 2);
+}
+
+#[test]
+fn nested_publishes_from_many_threads_do_not_deadlock() {
+    let bus = Arc::new(SyncEventBus::new());
+    let alerts = Arc::new(AtomicU64::new(0));
+    {
+        let inner = Arc::downgrade(&bus);
+        bus.subscribe(move |r: &ReadingTaken| {
+            if r.celsius > 80.0 {
+                if let Some(bus) = inner.upgrade() {
+                    bus.publish(AlertRaised {
+                        rule: format!("overheat/{}", r.sensor),
+                    });
+                }
+            }
+        });
+    }
+    {
+        let alerts = Arc::clone(&alerts);
+        bus.subscribe(move |_: &AlertRaised| {
+            alerts.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+    let workers: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let bus = Arc::clone(&bus);
+            thread::spawn(move || {
+                for i in 0..PER_THREAD {
+                    // Every 1,000th reading is too hot
+                    let celsius = if i % 1000 == 999 { 90.0 } else { 20.0 };
+                    bus.publish(ReadingTaken {
+                        sensor: t as u16 + 1,
+                        celsius,
+                    });
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    assert_eq!(alerts.load(Ordering::Relaxed), THREADS * PER_THREAD / 1000);
+}
+
+#[test]
+fn subscribe_and_unsubscribe_through_a_shared_reference() {
+    let bus = SyncEventBus::new();
+    let seen = Arc::new(AtomicU64::new(0));
+    let id = {
+        let seen = Arc::clone(&seen);
+        bus.subscribe(move |r: &ReadingTaken| {
+            seen.fetch_add(r.sensor as u64, Ordering::Relaxed);
+        })
+    };
+    assert_eq!(bus.subscribers::<ReadingTaken>(), 1);
+    assert_eq!(
+        bus.publish(ReadingTaken {
+            sensor: 2,
+            celsius: 0.0
+        }),
+        1
+    );
+    assert!(bus.unsubscribe(id));
+    assert!(!bus.unsubscribe(id));
+    assert_eq!(
+        bus.publish(ReadingTaken {
+            sensor: 2,
+            celsius: 0.0
+        }),
+        0
+    );
+    assert_eq!(seen.load(Ordering::Relaxed), 2);
+    assert_eq!(bus.stats().unhandled, 1);
+}
@@ -0,0 +1,201 @@
+// `Stats` with `record(&self)`, three ways
+//
+//   CellStats     every field in a `Cell`. Values are copied in and out
+//                 and no reference to the inside ever exists, so nothing
+//                 can go wrong at run time. The window is not `Copy`, so
+//                 it has to be taken out, changed and put back.
+//   RefCellStats  the plain `Stats` inside one `RefCell`. The least code,
+//                 and references into the window are possible, but
+//                 every access is checked at run time, and a `record`
+//                 while a `Ref` is still alive panics.
+//   LocalStats    the cheapest cell for each field: counters in `Cell`,
+//                 the window in `RefCell`, the baseline in `OnceCell`,
+//                 which is written once and then readable by reference.
+//
+// None of them is `Sync`: a `Cell` changed from two threads at once would
+// be a data race, so the compiler refuses to share them. `SyncStats` is
+// the thread-safe version.
+
+use crate::stats::{Stats, Summary};
+use std::cell::{BorrowMutError, Cell, OnceCell, Ref, RefCell};
+use std::collections::VecDeque;
+use std::fmt;
+
+pub struct CellStats {
+    count: Cell<u64>,
+    sum: Cell<f64>,
+    min: Cell<f64>,
+    max: Cell<f64>,
+    recent: Cell<VecDeque<f64>>,
+    window: usize,
+    baseline: Cell<Option<f64>>,
+}
+
+impl CellStats {
+    pub fn new(window: usize) -> CellStats {
+        CellStats {
+            count: Cell::new(0),
+            sum: Cell::new(0.0),
+            min: Cell::new(f64::INFINITY),
+            max: Cell::new(f64::NEG_INFINITY),
+            recent: Cell::new(VecDeque::with_capacity(window.max(1))),
+            window: window.max(1),
+            baseline: Cell::new(None),
+        }
+    }
+
+    pub fn record(&self, value: f64) {
+        self.count.set(self.count.get() + 1);
+        self.sum.set(self.sum.get() + value);
+        self.min.set(self.min.get().min(value));
+        self.max.set(self.max.get().max(value));
+        // `take` leaves an empty VecDeque behind (no allocation) until
+        // `set` puts the real one back
+        let mut recent = self.recent.take();
+        if recent.len() == self.window {
+            recent.pop_front();
+        }
+        recent.push_back(value);
+        self.recent.set(recent);
+        if self.baseline.get().is_none() {
+            self.baseline.set(Some(value));
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.get()
+    }
+
+    pub fn summary(&self) -> Summary {
+        let recent = self.recent.take();
+        let summary = Summary::from_parts(
+            self.count.get(),
+            self.sum.get(),
+            self.min.get(),
+            self.max.get(),
+            &recent,
+            self.baseline.get(),
+        );
+        self.recent.set(recent);
+        summary
+    }
+}
+
+// `Cell<T>` is only `Debug` for `T: Copy` (it can't lend out a reference
+// to format), so the window is left out
+impl fmt::Debug for CellStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CellStats")
+            .field("count", &self.count)
+            .field("sum", &self.sum)
+            .field("min", &self.min)
+            .field("max", &self.max)
+            .field("window", &self.window)
+            .field("baseline", &self.baseline)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug)]
+pub struct RefCellStats {
+    inner: RefCell<Stats>,
+}
+
+impl RefCellStats {
+    pub fn new(window: usize) -> RefCellStats {
+        RefCellStats {
+            inner: RefCell::new(Stats::new(window)),
+        }
+    }
+
+    // Panics if a `Ref` from `recent` is still alive
+    pub fn record(&self, value: f64) {
+        self.inner.borrow_mut().record(value);
+    }
+
+    // The same, reporting the conflict instead of panicking
+    pub fn try_record(&self, value: f64) -> Result<(), BorrowMutError> {
+        self.inner.try_borrow_mut()?.record(value);
+        Ok(())
+    }
+
+    // A view into the window without copying it. The `Ref` keeps the
+    // RefCell borrowed until it is dropped.
+    pub fn recent(&self) -> Ref<'_, VecDeque<f64>> {
+        Ref::map(self.inner.borrow(), |s| s.recent())
+    }
+
+    pub fn count(&self) -> u64 {
+        self.inner.borrow().count()
+    }
+
+    pub fn summary(&self) -> Summary {
+        self.inner.borrow().summary()
+    }
+}
+
+#[derive(Debug)]
+pub struct LocalStats {
+    count: Cell<u64>,
+    sum: Cell<f64>,
+    min: Cell<f64>,
+    max: Cell<f64>,
+    recent: RefCell<VecDeque<f64>>,
+    window: usize,
+    baseline: OnceCell<f64>,
+}
+
+impl LocalStats {
+    pub fn new(window: usize) -> LocalStats {
+        LocalStats {
+            count: Cell::new(0),
+            sum: Cell::new(0.0),
+            min: Cell::new(f64::INFINITY),
+            max: Cell::new(f64::NEG_INFINITY),
+            recent: RefCell::new(VecDeque::with_capacity(window.max(1))),
+            window: window.max(1),
+            baseline: OnceCell::new(),
+        }
+    }
+
+    pub fn record(&self, value: f64) {
+        self.count.set(self.count.get() + 1);
+        self.sum.set(self.sum.get() + value);
+        self.min.set(self.min.get().min(value));
+        self.max.set(self.max.get().max(value));
+        // Borrowed for two statements only; nothing here calls out while
+        // it is held, so it can't conflict
+        {
+            let mut recent = self.recent.borrow_mut();
+            if recent.len() == self.window {
+                recent.pop_front();
+            }
+            recent.push_back(value);
+        }
+        self.baseline.get_or_init(|| value);
+    }
+
+    // Written by the first `record`, never again
+    pub fn baseline(&self) -> Option<&f64> {
+        self.baseline.get()
+    }
+
+    pub fn recent(&self) -> Ref<'_, VecDeque<f64>> {
+        self.recent.borrow()
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.get()
+    }
+
+    pub fn summary(&self) -> Summary {
+        Summary::from_parts(
+            self.count.get(),
+            self.sum.get(),
+            self.min.get(),
+            self.max.get(),
+            &self.recent.borrow(),
+            self.baseline.get().copied(),
+        )
+    }
+}
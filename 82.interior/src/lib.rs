@@ -0,0 +1,20 @@
+// Interior mutability
+//
+// One stats collector, refactored through each way of changing data behind
+// a shared reference:
+//
+// - `stats`: `Stats`, the plain version (`record(&mut self)`) and its
+//   `Summary`
+// - `cells`: `CellStats` (every field in a `Cell`), `RefCellStats` (the
+//   whole struct in a `RefCell`) and `LocalStats` (the cheapest cell per
+//   field: `Cell`, `RefCell`, `OnceCell`)
+// - `sync`: `SyncStats`, a `Mutex` and a `OnceLock`, for sharing between
+//   threads
+
+pub mod cells;
+pub mod stats;
+pub mod sync;
+
+pub use cells::{CellStats, LocalStats, RefCellStats};
+pub use stats::{Stats, Summary};
+pub use sync::SyncStats;
@@ -0,0 +1,255 @@
+use interior::{CellStats, LocalStats, RefCellStats, Stats, SyncStats};
+use std::any::Any;
+use std::cell::{Cell, OnceCell, RefCell};
+use std::hint::black_box;
+use std::mem::size_of;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier, Mutex, OnceLock};
+use std::thread;
+use std::time::Instant;
+
+const WINDOW: usize = 16;
+const THREADS: usize = 4;
+const PER_THREAD: usize = 25_000;
+const READINGS: usize = 1_000_000;
+
+// xorshift32 mapped to temperatures in [18, 26), so every run is the same
+fn random(seed: u32, n: usize) -> Vec<f64> {
+    let mut x = seed.max(1);
+    (0..n)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            18.0 + (x % 8000) as f64 / 1000.0
+        })
+        .collect()
+}
+
+// A panic's message, whichever way it was built
+fn message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<String>()
+        .cloned()
+        .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+        .unwrap_or_default()
+}
+
+// Runs `f` with the default panic message silenced
+fn quietly<T>(f: impl FnOnce() -> T) -> thread::Result<T> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    panic::set_hook(hook);
+    result
+}
+
+// Nanoseconds per `record` over `readings`
+fn time(readings: &[f64], mut record: impl FnMut(f64)) -> f64 {
+    let started = Instant::now();
+    for &v in readings {
+        record(black_box(v));
+    }
+    started.elapsed().as_nanos() as f64 / readings.len() as f64
+}
+
+fn main() {
+    println!("=== Interior Mutability Examples ===\n");
+    let readings = random(7, 1000);
+
+    // 1. Cell
+    println!("1. Cell: two handlers, one collector:");
+    // Both handlers are `Fn` and share the collector through an Rc, so
+    // neither can have `&mut`; with plain `Stats` this does not compile
+    let stats = Rc::new(CellStats::new(WINDOW));
+    let indoor = {
+        let stats = Rc::clone(&stats);
+        move |v: f64| stats.record(v)
+    };
+    let outdoor = {
+        let stats = Rc::clone(&stats);
+        move |v: f64| stats.record(v)
+    };
+    for (i, &v) in readings.iter().enumerate() {
+        if i % 2 == 0 {
+            indoor(v)
+        } else {
+            outdoor(v)
+        }
+    }
+    let summary = stats.summary();
+    println!(
+        "   {} readings, mean {:.3}, min {:.3}, max {:.3}",
+        summary.count, summary.mean, summary.min, summary.max
+    );
+    let last = Cell::new(21.5);
+    let previous = last.replace(22.0);
+    println!(
+        "   Cell::replace: {} -> {} (values move in and out, never a reference)",
+        previous,
+        last.get()
+    );
+
+    // 2. RefCell
+    println!("\n2. RefCell: borrow rules checked at run time:");
+    let stats = RefCellStats::new(4);
+    for &v in &readings[..6] {
+        stats.record(v);
+    }
+    let view = stats.recent();
+    println!("   window while a Ref is held: {:.2?}", *view);
+    let refused = stats.try_record(20.0);
+    println!("   try_record: {:?}", refused);
+    let panicked = quietly(|| stats.record(20.0));
+    if let Err(payload) = &panicked {
+        println!("   record:     panicked with {:?}", message(&**payload));
+    }
+    drop(view);
+    stats.record(20.0);
+    println!(
+        "   size: Stats {} bytes, RefCell<Stats> {} (the borrow counter)",
+        size_of::<Stats>(),
+        size_of::<RefCell<Stats>>()
+    );
+
+    // 3. OnceCell and OnceLock
+    println!("\n3. OnceCell and OnceLock: written once:");
+    let stats = LocalStats::new(WINDOW);
+    for v in [21.0, 22.5, 30.0] {
+        stats.record(v);
+    }
+    println!(
+        "   baseline {:?}, window {:?}, drift {:+.2}",
+        stats.baseline(),
+        *stats.recent(),
+        stats.summary().drift
+    );
+    let unit = OnceCell::new();
+    let first = unit.set("celsius");
+    let second = unit.set("kelvin");
+    println!("   set(celsius): {:?}, set(kelvin): {:?}", first, second);
+    // Eight threads race to initialise one value
+    let calibration = Arc::new(OnceLock::new());
+    let inits = Arc::new(AtomicUsize::new(0));
+    let start = Arc::new(Barrier::new(8));
+    let seen: Vec<String> = (0..8)
+        .map(|t| {
+            let (calibration, inits, start) = (
+                Arc::clone(&calibration),
+                Arc::clone(&inits),
+                Arc::clone(&start),
+            );
+            thread::spawn(move || {
+                start.wait();
+                calibration
+                    .get_or_init(|| {
+                        inits.fetch_add(1, Ordering::Relaxed);
+                        format!("offset -0.{} C, from thread {}", t + 1, t)
+                    })
+                    .clone()
+            })
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|h| h.join().expect("racer"))
+        .collect();
+    println!("   all 8 threads read: {:?}", seen[0]);
+
+    // 4. Mutex
+    println!(
+        "\n4. Mutex: {} threads recording {} readings each:",
+        THREADS, PER_THREAD
+    );
+    let stats = Arc::new(SyncStats::new(WINDOW));
+    let workers: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let stats = Arc::clone(&stats);
+            thread::spawn(move || {
+                for i in 0..PER_THREAD {
+                    stats.record(20.0 + t as f64 + (i % 10) as f64 / 10.0);
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().expect("recorder");
+    }
+    let summary = stats.summary();
+    println!(
+        "   {} readings, min {:.1}, max {:.1}, baseline {:?}",
+        summary.count,
+        summary.min,
+        summary.max,
+        stats.baseline()
+    );
+    // A panic while the guard is held marks the Mutex poisoned
+    let shared = Arc::new(Mutex::new(Stats::new(WINDOW)));
+    {
+        let shared = Arc::clone(&shared);
+        let _ = quietly(move || {
+            thread::spawn(move || {
+                let mut stats = shared.lock().unwrap();
+                stats.record(21.0);
+                panic!("sensor driver crashed");
+            })
+            .join()
+        });
+    }
+    let poisoned = shared.lock().is_err();
+    let recovered = shared.lock().unwrap_or_else(|e| e.into_inner()).count();
+    println!(
+        "   after a panic under the lock: poisoned {}, into_inner sees {} reading",
+        poisoned, recovered
+    );
+
+    // 5. The same collector five ways
+    println!("\n5. {} readings through each version:", READINGS);
+    let readings = random(11, READINGS);
+    println!("   type          size  ns/record  on a conflicting access");
+    let mut stats = Stats::new(WINDOW);
+    let ns_plain = time(&readings, |v| stats.record(v));
+    let cell = CellStats::new(WINDOW);
+    let ns_cell = time(&readings, |v| cell.record(v));
+    let refcell = RefCellStats::new(WINDOW);
+    let ns_refcell = time(&readings, |v| refcell.record(v));
+    let local = LocalStats::new(WINDOW);
+    let ns_local = time(&readings, |v| local.record(v));
+    let sync = SyncStats::new(WINDOW);
+    let ns_sync = time(&readings, |v| sync.record(v));
+    // What a second writer at the wrong moment gets
+    let rows = [
+        ("Stats", size_of::<Stats>(), ns_plain, "compile error"),
+        (
+            "CellStats",
+            size_of::<CellStats>(),
+            ns_cell,
+            "can't conflict",
+        ),
+        (
+            "RefCellStats",
+            size_of::<RefCellStats>(),
+            ns_refcell,
+            "panic",
+        ),
+        ("LocalStats", size_of::<LocalStats>(), ns_local, "panic"),
+        ("SyncStats", size_of::<SyncStats>(), ns_sync, "waits"),
+    ];
+    for (name, size, ns, conflict) in rows {
+        println!("   {:<12} {:>5} {:>10.1}  {}", name, size, ns, conflict);
+    }
+    let means: Vec<String> = [
+        stats.summary(),
+        cell.summary(),
+        refcell.summary(),
+        local.summary(),
+        sync.summary(),
+    ]
+    .iter()
+    .map(|s| format!("{:.6}", s.mean))
+    .collect();
+    println!("   means: {}", means.join(", "));
+
+    println!("\n=== End of Interior Mutability Examples ===");
+}
@@ -0,0 +1,106 @@
+// The stats collector before any cells
+//
+// Counts readings, tracks min, max and mean, keeps the last `window`
+// values for a moving average, and remembers the first reading as a
+// baseline to measure drift from. `record` takes `&mut self`, so the
+// borrow checker proves at compile time that nobody else is looking while
+// it changes. That is also its limit: a collector shared by two handlers,
+// or stored in an `Rc`, only ever gets `&self`.
+
+use std::collections::VecDeque;
+
+// What every version reports; equal input gives equal summaries
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Summary {
+    pub count: u64,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    // Mean of the last `window` readings
+    pub window_mean: f64,
+    // `window_mean` minus the first reading
+    pub drift: f64,
+}
+
+impl Summary {
+    // Shared by every version, so they can only differ in how they store
+    // the numbers, not in how they combine them
+    pub fn from_parts(
+        count: u64,
+        sum: f64,
+        min: f64,
+        max: f64,
+        window: &VecDeque<f64>,
+        baseline: Option<f64>,
+    ) -> Summary {
+        if count == 0 {
+            return Summary::default();
+        }
+        let window_mean = window.iter().sum::<f64>() / window.len() as f64;
+        Summary {
+            count,
+            mean: sum / count as f64,
+            min,
+            max,
+            window_mean,
+            drift: window_mean - baseline.unwrap_or(window_mean),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Stats {
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+    recent: VecDeque<f64>,
+    window: usize,
+    baseline: Option<f64>,
+}
+
+impl Stats {
+    pub fn new(window: usize) -> Stats {
+        let window = window.max(1);
+        Stats {
+            count: 0,
+            sum: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            recent: VecDeque::with_capacity(window),
+            window,
+            baseline: None,
+        }
+    }
+
+    pub fn record(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        if self.recent.len() == self.window {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(value);
+        self.baseline.get_or_insert(value);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn recent(&self) -> &VecDeque<f64> {
+        &self.recent
+    }
+
+    pub fn summary(&self) -> Summary {
+        Summary::from_parts(
+            self.count,
+            self.sum,
+            self.min,
+            self.max,
+            &self.recent,
+            self.baseline,
+        )
+    }
+}
@@ -0,0 +1,50 @@
+// `Stats` shared between threads
+//
+// `Mutex` is `RefCell` for threads: it hands out one `&mut` at a time, but
+// a second caller waits instead of panicking. The baseline sits in a
+// `OnceLock` next to it, so once it is set any thread can read it without
+// taking the lock. It is set under the lock, by the same `record` that
+// gives `Stats` its first reading, so the two always agree.
+
+use crate::stats::{Stats, Summary};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+#[derive(Debug)]
+pub struct SyncStats {
+    inner: Mutex<Stats>,
+    baseline: OnceLock<f64>,
+}
+
+impl SyncStats {
+    pub fn new(window: usize) -> SyncStats {
+        SyncStats {
+            inner: Mutex::new(Stats::new(window)),
+            baseline: OnceLock::new(),
+        }
+    }
+
+    // Nothing in `Stats::record` can panic halfway through an update, so
+    // a lock poisoned by a panicking caller still guards consistent stats
+    fn lock(&self) -> MutexGuard<'_, Stats> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn record(&self, value: f64) {
+        let mut stats = self.lock();
+        stats.record(value);
+        self.baseline.get_or_init(|| value);
+    }
+
+    // Lock-free once set
+    pub fn baseline(&self) -> Option<f64> {
+        self.baseline.get().copied()
+    }
+
+    pub fn count(&self) -> u64 {
+        self.lock().count()
+    }
+
+    pub fn summary(&self) -> Summary {
+        self.lock().summary()
+    }
+}
@@ -0,0 +1,99 @@
+use interior::{CellStats, LocalStats, RefCellStats, Stats};
+use std::cell::{Cell, OnceCell, RefCell};
+use std::mem::size_of;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+#[test]
+fn two_fn_handlers_fill_one_cell_stats() {
+    let stats = Rc::new(CellStats::new(8));
+    let indoor = {
+        let stats = Rc::clone(&stats);
+        move |v: f64| stats.record(v)
+    };
+    let outdoor = {
+        let stats = Rc::clone(&stats);
+        move |v: f64| stats.record(v)
+    };
+    let mut plain = Stats::new(8);
+    for i in 0..100 {
+        let v = 18.0 + f64::from(i % 7);
+        if i % 2 == 0 {
+            indoor(v)
+        } else {
+            outdoor(v)
+        }
+        plain.record(v);
+    }
+    assert_eq!(stats.summary(), plain.summary());
+}
+
+#[test]
+fn cells_cost_no_space_except_refcells_counter() {
+    assert_eq!(size_of::<Cell<u64>>(), size_of::<u64>());
+    assert_eq!(size_of::<Cell<f64>>(), size_of::<f64>());
+    assert_eq!(
+        size_of::<RefCell<Stats>>(),
+        size_of::<Stats>() + size_of::<isize>()
+    );
+}
+
+#[test]
+fn cell_replace_moves_values_in_and_out() {
+    let last = Cell::new(21.5);
+    assert_eq!(last.replace(22.0), 21.5);
+    assert_eq!(last.get(), 22.0);
+    assert_eq!(last.take(), 22.0);
+    assert_eq!(last.get(), 0.0);
+}
+
+#[test]
+fn a_live_ref_blocks_recording() {
+    let stats = RefCellStats::new(4);
+    for v in [20.0, 21.0, 22.0, 23.0, 24.0, 25.0] {
+        stats.record(v);
+    }
+    let view = stats.recent();
+    assert_eq!(
+        view.iter().copied().collect::<Vec<_>>(),
+        [22.0, 23.0, 24.0, 25.0]
+    );
+    assert!(stats.try_record(20.0).is_err());
+    let panicked = panic::catch_unwind(AssertUnwindSafe(|| stats.record(20.0)));
+    let payload = panicked.unwrap_err();
+    let message = payload
+        .downcast_ref::<String>()
+        .cloned()
+        .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+        .unwrap_or_default();
+    assert!(message.contains("already borrowed"), "{}", message);
+    assert_eq!(stats.count(), 6);
+
+    // No poisoning: once the Ref is gone the cell works again
+    drop(view);
+    stats.try_record(19.0).unwrap();
+    stats.record(20.0);
+    assert_eq!(stats.count(), 8);
+    assert_eq!(stats.recent().back(), Some(&20.0));
+}
+
+#[test]
+fn the_baseline_is_the_first_reading_only() {
+    let stats = LocalStats::new(16);
+    assert_eq!(stats.baseline(), None);
+    for v in [21.0, 22.5, 30.0] {
+        stats.record(v);
+    }
+    assert_eq!(stats.baseline(), Some(&21.0));
+    assert_eq!(*stats.recent(), [21.0, 22.5, 30.0]);
+    assert!((stats.summary().drift - 3.5).abs() < 1e-12);
+}
+
+#[test]
+fn once_cell_refuses_a_second_value() {
+    let unit = OnceCell::new();
+    assert_eq!(unit.set("celsius"), Ok(()));
+    assert_eq!(unit.set("kelvin"), Err("kelvin"));
+    assert_eq!(unit.get(), Some(&"celsius"));
+    assert_eq!(*unit.get_or_init(|| "fahrenheit"), "celsius");
+}
@@ -0,0 +1,82 @@
+use interior::{CellStats, LocalStats, RefCellStats, Stats, Summary, SyncStats};
+
+const WINDOW: usize = 16;
+
+// xorshift32 mapped to temperatures in [18, 26), so every run is the same
+fn random(seed: u32, n: usize) -> Vec<f64> {
+    let mut x = seed.max(1);
+    (0..n)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            18.0 + (x % 8000) as f64 / 1000.0
+        })
+        .collect()
+}
+
+#[test]
+fn nothing_recorded_is_all_zero() {
+    assert_eq!(Stats::new(WINDOW).summary(), Summary::default());
+    assert_eq!(LocalStats::new(WINDOW).summary(), Summary::default());
+    assert_eq!(SyncStats::new(WINDOW).baseline(), None);
+}
+
+#[test]
+fn the_window_keeps_the_last_readings() {
+    let mut stats = Stats::new(3);
+    for v in [20.0, 21.0, 22.0, 23.0, 24.0] {
+        stats.record(v);
+    }
+    assert_eq!(stats.count(), 5);
+    assert_eq!(
+        stats.recent().iter().copied().collect::<Vec<_>>(),
+        [22.0, 23.0, 24.0]
+    );
+    assert_eq!(
+        stats.summary(),
+        Summary {
+            count: 5,
+            mean: 22.0,
+            min: 20.0,
+            max: 24.0,
+            window_mean: 23.0,
+            // Measured from the first reading, long gone from the window
+            drift: 3.0,
+        }
+    );
+    // A zero window still holds the latest reading
+    let mut stats = Stats::new(0);
+    stats.record(1.0);
+    stats.record(2.0);
+    assert_eq!(stats.summary().window_mean, 2.0);
+}
+
+// Same readings in, bit-identical summaries out, whatever the cells
+#[test]
+fn all_five_versions_agree() {
+    let readings = random(11, 10_000);
+    let mut stats = Stats::new(WINDOW);
+    let cell = CellStats::new(WINDOW);
+    let refcell = RefCellStats::new(WINDOW);
+    let local = LocalStats::new(WINDOW);
+    let sync = SyncStats::new(WINDOW);
+    for &v in &readings {
+        stats.record(v);
+        cell.record(v);
+        refcell.record(v);
+        local.record(v);
+        sync.record(v);
+    }
+    let expected = stats.summary();
+    assert_eq!(expected.count, 10_000);
+    assert_eq!(cell.summary(), expected);
+    assert_eq!(refcell.summary(), expected);
+    assert_eq!(local.summary(), expected);
+    assert_eq!(sync.summary(), expected);
+    for count in [cell.count(), refcell.count(), local.count(), sync.count()] {
+        assert_eq!(count, 10_000);
+    }
+    assert_eq!(local.baseline(), Some(&readings[0]));
+    assert_eq!(sync.baseline(), Some(readings[0]));
+}
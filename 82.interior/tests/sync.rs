@@ -0,0 +1,87 @@
+use interior::{Stats, SyncStats};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier, Mutex, OnceLock};
+use std::thread;
+
+const THREADS: usize = 4;
+const PER_THREAD: usize = 25_000;
+
+#[test]
+fn no_reading_is_lost_between_threads() {
+    let stats = Arc::new(SyncStats::new(16));
+    let workers: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let stats = Arc::clone(&stats);
+            thread::spawn(move || {
+                for i in 0..PER_THREAD {
+                    stats.record(20.0 + t as f64 + (i % 10) as f64 / 10.0);
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    let summary = stats.summary();
+    assert_eq!(summary.count, (THREADS * PER_THREAD) as u64);
+    assert_eq!(summary.min, 20.0);
+    assert_eq!(summary.max, 20.0 + THREADS as f64 - 1.0 + 0.9);
+    // Whichever thread got there first; each starts on a whole degree
+    let baseline = stats.baseline().unwrap();
+    assert!(
+        (0..THREADS).any(|t| baseline == 20.0 + t as f64),
+        "{}",
+        baseline
+    );
+}
+
+#[test]
+fn racing_threads_initialise_a_once_lock_once() {
+    let calibration = Arc::new(OnceLock::new());
+    let inits = Arc::new(AtomicUsize::new(0));
+    let start = Arc::new(Barrier::new(8));
+    let seen: Vec<String> = (0..8)
+        .map(|t| {
+            let (calibration, inits, start) = (
+                Arc::clone(&calibration),
+                Arc::clone(&inits),
+                Arc::clone(&start),
+            );
+            thread::spawn(move || {
+                start.wait();
+                calibration
+                    .get_or_init(|| {
+                        inits.fetch_add(1, Ordering::Relaxed);
+                        format!("from thread {}", t)
+                    })
+                    .clone()
+            })
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|h| h.join().unwrap())
+        .collect();
+    assert_eq!(inits.load(Ordering::Relaxed), 1);
+    assert!(seen.iter().all(|s| s == &seen[0]));
+    assert_eq!(calibration.get(), Some(&seen[0]));
+}
+
+#[test]
+fn a_panic_holding_the_lock_poisons_it() {
+    let shared = Arc::new(Mutex::new(Stats::new(16)));
+    let crashed = {
+        let shared = Arc::clone(&shared);
+        thread::spawn(move || {
+            let mut stats = shared.lock().unwrap();
+            stats.record(21.0);
+            panic!("sensor driver crashed");
+        })
+        .join()
+    };
+    assert!(crashed.is_err());
+    assert!(shared.is_poisoned());
+    assert!(shared.lock().is_err());
+    // The data is still there for whoever decides it is consistent
+    let recovered = shared.lock().unwrap_or_else(|e| e.into_inner());
+    assert_eq!(recovered.count(), 1);
+}
@@ -0,0 +1,98 @@
+// A process-wide configuration
+//
+// `main` calls `init` once with whatever it parsed. Code anywhere else
+// calls `get` and receives a `&'static Config`, without threading a
+// parameter through every function in between. The `OnceLock` makes
+// that safe. It can be written exactly once, and only through `&self`,
+// so every later reference is to a value that will never change. If
+// `main` never called `init` (a test, a tool that links the library),
+// the first `get` fills it from the environment instead, and `init`
+// after that is refused rather than silently changing what earlier
+// readers saw.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub gateway_id: String,
+    pub poll_interval_ms: u64,
+    pub verbose: bool,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            gateway_id: "gateway-0".to_string(),
+            poll_interval_ms: 1000,
+            verbose: false,
+        }
+    }
+}
+
+impl Config {
+    // GATEWAY_ID, POLL_INTERVAL_MS and VERBOSE; anything missing or
+    // unparsable keeps its default
+    pub fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> Config {
+        let vars: HashMap<String, String> = vars.into_iter().collect();
+        let mut config = Config::default();
+        if let Some(id) = vars.get("GATEWAY_ID") {
+            config.gateway_id = id.clone();
+        }
+        if let Some(ms) = vars.get("POLL_INTERVAL_MS").and_then(|v| v.parse().ok()) {
+            config.poll_interval_ms = ms;
+        }
+        if let Some(v) = vars.get("VERBOSE") {
+            config.verbose = matches!(v.as_str(), "1" | "true" | "yes");
+        }
+        config
+    }
+
+    pub fn from_env() -> Config {
+        Config::from_vars(std::env::vars())
+    }
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+// `init` lost the race, or came after the first `get`. The rejected
+// config is handed back so the caller can log or compare it.
+#[derive(Debug)]
+pub struct AlreadySet {
+    pub rejected: Config,
+    pub current: &'static Config,
+}
+
+impl fmt::Display for AlreadySet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "configuration already set (gateway '{}'), '{}' was not applied",
+            self.current.gateway_id, self.rejected.gateway_id
+        )
+    }
+}
+
+impl Error for AlreadySet {}
+
+// Exactly one caller wins, even if several threads call it at once
+pub fn init(config: Config) -> Result<&'static Config, AlreadySet> {
+    match CONFIG.set(config) {
+        Ok(()) => Ok(get()),
+        Err(rejected) => Err(AlreadySet {
+            rejected,
+            current: get(),
+        }),
+    }
+}
+
+// `None` until `init` or the first `get`
+pub fn try_get() -> Option<&'static Config> {
+    CONFIG.get()
+}
+
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::from_env)
+}
@@ -0,0 +1,44 @@
+// A global counter with no initialisation at all
+//
+// `AtomicU64::new` is a `const fn`, so `Counter::new` can be one too, and
+// `static READINGS: Counter = Counter::new();` is built by the compiler
+// and sits in the binary ready to use. No `OnceLock`, no `LazyLock`, no
+// first-use check. The atomic is the interior mutability: `add` takes
+// `&self`, which is all a `static` ever gives out, and `fetch_add` makes
+// the read, add and write one step that no other thread can split.
+//
+// `Relaxed` is enough for a counter. It only promises that no increment
+// is lost, not that other memory writes become visible in any order,
+// and nothing here reads the count to decide what other data to trust.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Debug, Default)]
+pub struct Counter {
+    value: AtomicU64,
+}
+
+impl Counter {
+    pub const fn new() -> Counter {
+        Counter {
+            value: AtomicU64::new(0),
+        }
+    }
+
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    pub fn add(&self, n: u64) {
+        self.value.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    // The count so far, starting again from zero, as one atomic step
+    pub fn take(&self) -> u64 {
+        self.value.swap(0, Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,17 @@
+// Global state without `static mut`
+//
+// - `config`: a process-wide configuration in a `OnceLock`, set once by
+//   `init` or filled from the environment on first use
+// - `counter`: `Counter`, an atomic that can be a plain `static` because
+//   its constructor is `const`
+// - `metrics`: a registry of named counters behind a `LazyLock`, since a
+//   `HashMap` can't be built at compile time, plus the `counter!` macro
+//   that caches each call site's lookup
+
+pub mod config;
+pub mod counter;
+pub mod metrics;
+
+pub use config::{AlreadySet, Config};
+pub use counter::Counter;
+pub use metrics::Registry;
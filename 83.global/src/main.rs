@@ -0,0 +1,203 @@
+use global::{config, counter, metrics, Config, Counter};
+use std::hint::black_box;
+use std::ptr;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Barrier, LazyLock};
+use std::thread;
+use std::time::Instant;
+
+const THREADS: usize = 4;
+const PER_THREAD: u64 = 250_000;
+const SPLIT_PER_THREAD: u64 = 10_000;
+const CALLS: u64 = 10_000_000;
+
+// Built by the compiler; nothing runs at startup or on first use
+static READINGS: Counter = Counter::new();
+
+// What most C ports start with. Every access needs `unsafe`, and the
+// compiler can't check any of them.
+static mut LEGACY_COUNT: u64 = 0;
+
+// The same count done "by hand": a load, an add and a store, each atomic
+// on its own but not together, which is what `LEGACY_COUNT += 1` does
+// without the atomics (and without the guarantee that it's defined)
+static SPLIT_COUNT: AtomicU64 = AtomicU64::new(0);
+
+static PROBE_INITS: AtomicUsize = AtomicUsize::new(0);
+static PROBE: LazyLock<String> = LazyLock::new(|| {
+    PROBE_INITS.fetch_add(1, Ordering::Relaxed);
+    format!("calibration table loaded at {:?}", Instant::now())
+});
+
+// Stand-ins for code far from `main` that reads the global config and
+// counts what it does, without either being passed in
+fn poll_sensors() -> usize {
+    let sensors = if config::get().verbose { 3 } else { 2 };
+    counter!("sensor_polls_total").inc();
+    counter!("sensor_readings_total").add(sensors as u64);
+    sensors
+}
+
+fn send_batch(lines: usize) {
+    counter!("uplink_batches_total").inc();
+    counter!("uplink_lines_total").add(lines as u64);
+}
+
+fn legacy_bump() {
+    // SAFETY: only called from the main thread, and no reference to the
+    // static is ever created, only this one read-modify-write
+    unsafe {
+        LEGACY_COUNT += 1;
+    }
+}
+
+fn legacy_count() -> u64 {
+    // SAFETY: as above. This copies the value out; `&LEGACY_COUNT` would be
+    // a reference to data that can change under it, which the
+    // `static_mut_refs` lint warns about (and denies in edition 2024)
+    unsafe { LEGACY_COUNT }
+}
+
+// Nanoseconds per call of `f`
+fn time(mut f: impl FnMut()) -> f64 {
+    let started = Instant::now();
+    for _ in 0..CALLS {
+        f();
+    }
+    started.elapsed().as_nanos() as f64 / CALLS as f64
+}
+
+// Runs `work` on `n` threads released together from a barrier
+fn race<T: Send>(n: usize, work: impl Fn(usize) -> T + Sync) -> Vec<T> {
+    let start = Barrier::new(n);
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..n)
+            .map(|t| {
+                let (start, work) = (&start, &work);
+                s.spawn(move || {
+                    start.wait();
+                    work(t)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("racer"))
+            .collect()
+    })
+}
+
+fn main() {
+    println!("=== Global State Examples ===\n");
+
+    // 1. A global configuration in a OnceLock
+    println!("1. OnceLock: the configuration, set once by main:");
+    println!("   before init: {:?}", config::try_get());
+    let parsed = Config::from_vars([
+        ("GATEWAY_ID".to_string(), "edge-7".to_string()),
+        ("POLL_INTERVAL_MS".to_string(), "250".to_string()),
+    ]);
+    let installed = config::init(parsed.clone()).expect("first init");
+    println!("   init: {:?}", installed);
+    let again = config::init(Config::default());
+    if let Err(e) = &again {
+        println!("   init again: {}", e);
+    }
+    let seen = race(THREADS, |_| config::get().poll_interval_ms);
+    println!(
+        "   poll_interval_ms read by {} threads: {:?}",
+        THREADS, seen
+    );
+
+    // 2. A const-initialised counter
+    println!("\n2. A static Counter: no initialisation needed:");
+    race(THREADS, |_| {
+        for _ in 0..PER_THREAD {
+            READINGS.inc();
+        }
+    });
+    println!(
+        "   {} threads x {} increments: {}",
+        THREADS,
+        PER_THREAD,
+        READINGS.get()
+    );
+    let taken = READINGS.take();
+    println!("   take: {}, then get: {}", taken, READINGS.get());
+
+    // 3. A LazyLock registry of named counters
+    println!("\n3. LazyLock: a registry built on first use:");
+    race(THREADS, |t| {
+        for _ in 0..100 {
+            let found = poll_sensors();
+            if t % 2 == 0 {
+                send_batch(found);
+            }
+        }
+    });
+    for line in metrics::REGISTRY.render().lines() {
+        println!("   {}", line);
+    }
+    let ns_static = time(|| black_box(&READINGS).inc());
+    let ns_macro = time(|| counter!("bench_total").inc());
+    let ns_lookup = time(|| metrics::counter(black_box("bench_total")).inc());
+    println!(
+        "   per increment: static {:.1} ns, counter!() {:.1} ns, registry lookup {:.1} ns",
+        ns_static, ns_macro, ns_lookup
+    );
+
+    // 4. Why not static mut
+    println!("\n4. static mut: unsafe, and racy once threads arrive:");
+    for _ in 0..1000 {
+        legacy_bump();
+    }
+    println!("   one thread, 1000 bumps: {}", legacy_count());
+    // From several threads, `LEGACY_COUNT += 1` would be a data race, which
+    // is undefined behaviour, so it isn't run here. Its split load and store
+    // are. `yield_now` between them stands in for the thread switch that,
+    // on a busy machine, lands there by chance.
+    race(THREADS, |_| {
+        for _ in 0..SPLIT_PER_THREAD {
+            let v = SPLIT_COUNT.load(Ordering::Relaxed);
+            thread::yield_now();
+            SPLIT_COUNT.store(v + 1, Ordering::Relaxed);
+        }
+    });
+    let expected = THREADS as u64 * SPLIT_PER_THREAD;
+    let counted = SPLIT_COUNT.load(Ordering::Relaxed);
+    println!(
+        "   load, add, store: {} of {} counted, {} lost",
+        counted,
+        expected,
+        expected - counted
+    );
+
+    // 5. Initialisation races
+    println!("\n5. Racing the first access:");
+    let tables = race(8, |_| PROBE.as_str());
+    println!("   {}", tables[0]);
+    println!(
+        "   LazyLock initialisers run: {}, threads reading the same String: {}",
+        PROBE_INITS.load(Ordering::Relaxed),
+        tables.iter().filter(|t| ptr::eq(**t, tables[0])).count()
+    );
+    let handles = race(8, |_| metrics::counter("late_registration_total"));
+    println!(
+        "   threads registering one name that got the same counter: {}",
+        handles.iter().filter(|c| ptr::eq(**c, handles[0])).count()
+    );
+    let winners = race(8, |t| {
+        global::config::init(Config {
+            gateway_id: format!("racer-{}", t),
+            ..Config::default()
+        })
+        .is_ok()
+    });
+    println!(
+        "   inits after main's that won: {}, gateway still {}",
+        winners.iter().filter(|won| **won).count(),
+        config::get().gateway_id
+    );
+
+    println!("\n=== End of Global State Examples ===");
+}
@@ -0,0 +1,106 @@
+// A global registry of named counters
+//
+// Unlike `Counter`, the registry can't be built at compile time: a
+// `HashMap`'s hasher is seeded at run time and `Instant::now` reads the
+// clock. `LazyLock` runs `Registry::new` on the first access from any
+// thread. Threads that arrive meanwhile wait for it, and every later
+// access is one atomic load.
+//
+// Each counter is leaked into a `&'static Counter` when first
+// registered. It lives as long as the registry, which is the whole
+// process, so callers can keep the reference and increment it with no
+// lock and no map lookup. The `counter!` macro does exactly that, once
+// per call site.
+
+use crate::counter::Counter;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::{LazyLock, RwLock};
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct Registry {
+    started: Instant,
+    counters: RwLock<HashMap<&'static str, &'static Counter>>,
+}
+
+impl Registry {
+    // Only the `static` below needs one, but a private registry is useful
+    // in tests that must not share counts
+    pub fn new() -> Registry {
+        Registry {
+            started: Instant::now(),
+            counters: RwLock::new(HashMap::new()),
+        }
+    }
+
+    // The counter called `name`, registered on first use. Racing callers
+    // for a new name all get the same counter: the second look, under
+    // the write lock, finds the one the winner inserted.
+    pub fn counter(&self, name: &'static str) -> &'static Counter {
+        if let Some(counter) = self
+            .counters
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+        {
+            return counter;
+        }
+        self.counters
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(name)
+            .or_insert_with(|| Box::leak(Box::new(Counter::new())))
+    }
+
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    // Every counter and its value, sorted by name
+    pub fn snapshot(&self) -> Vec<(&'static str, u64)> {
+        let mut all: Vec<_> = self
+            .counters
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(name, counter)| (*name, counter.get()))
+            .collect();
+        all.sort_unstable();
+        all
+    }
+
+    // One `name value` line per counter, in the Prometheus text format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (name, value) in self.snapshot() {
+            let _ = writeln!(out, "{} {}", name, value);
+        }
+        let _ = writeln!(out, "uptime_seconds {:.3}", self.uptime().as_secs_f64());
+        out
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Registry {
+        Registry::new()
+    }
+}
+
+pub static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::new);
+
+pub fn counter(name: &'static str) -> &'static Counter {
+    REGISTRY.counter(name)
+}
+
+// `counter!("uplink_sent_total").inc()`. The first call at each call site
+// looks the name up in `REGISTRY`; the handle is then cached in a
+// `OnceLock` belonging to that call site, so later calls skip the lock.
+#[macro_export]
+macro_rules! counter {
+    ($name:expr) => {{
+        static HANDLE: ::std::sync::OnceLock<&'static $crate::Counter> =
+            ::std::sync::OnceLock::new();
+        *HANDLE.get_or_init(|| $crate::metrics::counter($name))
+    }};
+}
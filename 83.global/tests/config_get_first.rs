@@ -0,0 +1,37 @@
+// Code that reads the configuration before `main` set it. The first
+// `get` fills it from the environment, and it stays that way.
+
+use global::{config, Config};
+use std::ptr;
+use std::sync::Barrier;
+use std::thread;
+
+const READERS: usize = 8;
+
+#[test]
+fn racing_readers_fill_it_once_and_init_is_refused_afterwards() {
+    let start = Barrier::new(READERS);
+    let seen: Vec<&'static Config> = thread::scope(|s| {
+        let handles: Vec<_> = (0..READERS)
+            .map(|_| {
+                let start = &start;
+                s.spawn(move || {
+                    start.wait();
+                    config::get()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert!(seen.iter().all(|c| ptr::eq(*c, seen[0])));
+    assert_eq!(*seen[0], Config::from_env());
+
+    let late = Config {
+        gateway_id: "set-too-late".to_string(),
+        ..Config::default()
+    };
+    let refused = config::init(late).unwrap_err();
+    assert_eq!(refused.rejected.gateway_id, "set-too-late");
+    assert!(ptr::eq(refused.current, seen[0]));
+    assert!(ptr::eq(config::get(), seen[0]));
+}
@@ -0,0 +1,50 @@
+// `config::init` called from many threads at once. Every test binary is
+// its own process with its own `CONFIG`, which is why this file holds a
+// single test: a second test here would share the global and could run
+// first, on another thread.
+
+use global::{config, Config};
+use std::ptr;
+use std::sync::Barrier;
+use std::thread;
+
+const RACERS: usize = 16;
+
+#[test]
+fn exactly_one_init_wins_and_everyone_sees_it() {
+    assert!(config::try_get().is_none());
+    let start = Barrier::new(RACERS);
+    let results: Vec<_> = thread::scope(|s| {
+        let handles: Vec<_> = (0..RACERS)
+            .map(|t| {
+                let start = &start;
+                s.spawn(move || {
+                    start.wait();
+                    config::init(Config {
+                        gateway_id: format!("racer-{}", t),
+                        ..Config::default()
+                    })
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let winners: Vec<&'static Config> = results
+        .iter()
+        .filter_map(|r| r.as_ref().ok().copied())
+        .collect();
+    assert_eq!(winners.len(), 1, "one init must succeed");
+    let winner = winners[0];
+    for result in &results {
+        match result {
+            Ok(config) => assert!(ptr::eq(*config, winner)),
+            Err(e) => {
+                assert!(ptr::eq(e.current, winner));
+                assert_ne!(e.rejected.gateway_id, winner.gateway_id);
+            }
+        }
+    }
+    assert!(ptr::eq(config::get(), winner));
+    assert!(ptr::eq(config::try_get().unwrap(), winner));
+}
@@ -0,0 +1,61 @@
+// `Config` as an ordinary value: parsing and the refusal message. Nothing
+// here touches the global `CONFIG`.
+
+use global::config::AlreadySet;
+use global::Config;
+
+fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn missing_variables_keep_their_defaults() {
+    assert_eq!(Config::from_vars(vars(&[])), Config::default());
+    assert_eq!(
+        Config::from_vars(vars(&[
+            ("GATEWAY_ID", "edge-7"),
+            ("POLL_INTERVAL_MS", "250")
+        ])),
+        Config {
+            gateway_id: "edge-7".to_string(),
+            poll_interval_ms: 250,
+            verbose: false,
+        }
+    );
+}
+
+#[test]
+fn unparsable_values_are_ignored() {
+    let config = Config::from_vars(vars(&[("POLL_INTERVAL_MS", "fast"), ("VERBOSE", "on")]));
+    assert_eq!(config.poll_interval_ms, 1000);
+    assert!(!config.verbose);
+    for yes in ["1", "true", "yes"] {
+        assert!(
+            Config::from_vars(vars(&[("VERBOSE", yes)])).verbose,
+            "{}",
+            yes
+        );
+    }
+}
+
+#[test]
+fn the_refusal_names_both_gateways() {
+    let current: &'static Config = Box::leak(Box::new(Config {
+        gateway_id: "edge-7".to_string(),
+        ..Config::default()
+    }));
+    let error = AlreadySet {
+        rejected: Config {
+            gateway_id: "racer-3".to_string(),
+            ..Config::default()
+        },
+        current,
+    };
+    assert_eq!(
+        error.to_string(),
+        "configuration already set (gateway 'edge-7'), 'racer-3' was not applied"
+    );
+}
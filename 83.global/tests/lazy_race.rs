@@ -0,0 +1,37 @@
+// Eight threads make the first access to a `LazyLock` together. The
+// static lives in this file's own process, so it is fresh for the one
+// test here.
+
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Barrier, LazyLock};
+use std::thread;
+use std::time::Instant;
+
+const THREADS: usize = 8;
+
+static INITS: AtomicUsize = AtomicUsize::new(0);
+static TABLE: LazyLock<String> = LazyLock::new(|| {
+    INITS.fetch_add(1, Ordering::Relaxed);
+    format!("calibration table loaded at {:?}", Instant::now())
+});
+
+#[test]
+fn one_initialiser_runs_and_everyone_reads_its_value() {
+    assert_eq!(INITS.load(Ordering::Relaxed), 0);
+    let start = Barrier::new(THREADS);
+    let tables: Vec<&'static str> = thread::scope(|s| {
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let start = &start;
+                s.spawn(move || {
+                    start.wait();
+                    TABLE.as_str()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert_eq!(INITS.load(Ordering::Relaxed), 1);
+    assert!(tables.iter().all(|t| ptr::eq(*t, tables[0])));
+}
@@ -0,0 +1,131 @@
+// Counters and the registry under concurrent first use. Each test uses
+// its own counter names, so they can share `REGISTRY` while the test
+// harness runs them in parallel.
+
+use global::{counter, metrics, Counter, Registry};
+use std::ptr;
+use std::sync::Barrier;
+use std::thread;
+
+const THREADS: usize = 8;
+const PER_THREAD: u64 = 10_000;
+
+// Runs `work` on `THREADS` threads released together
+fn race<T: Send>(work: impl Fn(usize) -> T + Sync) -> Vec<T> {
+    let start = Barrier::new(THREADS);
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let (start, work) = (&start, &work);
+                s.spawn(move || {
+                    start.wait();
+                    work(t)
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    })
+}
+
+#[test]
+fn a_static_counter_loses_no_increment() {
+    static HITS: Counter = Counter::new();
+    race(|_| {
+        for _ in 0..PER_THREAD {
+            HITS.inc();
+        }
+    });
+    assert_eq!(HITS.get(), THREADS as u64 * PER_THREAD);
+}
+
+#[test]
+fn racing_registrations_of_one_name_share_a_counter() {
+    let handles = race(|_| {
+        let counter = metrics::counter("race_registered_total");
+        for _ in 0..PER_THREAD {
+            counter.inc();
+        }
+        counter
+    });
+    assert!(handles.iter().all(|c| ptr::eq(*c, handles[0])));
+    assert_eq!(
+        metrics::counter("race_registered_total").get(),
+        THREADS as u64 * PER_THREAD
+    );
+}
+
+#[test]
+fn counter_macro_caches_the_registered_handle() {
+    let handles = race(|_| {
+        for _ in 0..PER_THREAD {
+            counter!("race_macro_total").inc();
+        }
+        counter!("race_macro_total")
+    });
+    let registered = metrics::counter("race_macro_total");
+    assert!(handles.iter().all(|c| ptr::eq(*c, registered)));
+    assert_eq!(registered.get(), THREADS as u64 * PER_THREAD);
+}
+
+#[test]
+fn a_private_registry_shares_nothing_with_the_global() {
+    let local = Registry::new();
+    race(|t| {
+        local
+            .counter(if t % 2 == 0 {
+                "even_total"
+            } else {
+                "odd_total"
+            })
+            .inc()
+    });
+    assert_eq!(
+        local.snapshot(),
+        vec![
+            ("even_total", THREADS as u64 / 2),
+            ("odd_total", THREADS as u64 / 2)
+        ]
+    );
+    assert!(!ptr::eq(
+        local.counter("even_total"),
+        metrics::counter("even_total")
+    ));
+    assert!(metrics::REGISTRY
+        .snapshot()
+        .iter()
+        .all(|(name, _)| *name != "odd_total"));
+}
+
+#[test]
+fn take_returns_the_count_and_resets_it() {
+    let counter = Counter::new();
+    race(|_| {
+        for _ in 0..PER_THREAD {
+            counter.inc();
+        }
+    });
+    assert_eq!(counter.take(), THREADS as u64 * PER_THREAD);
+    assert_eq!(counter.get(), 0);
+    counter.add(5);
+    assert_eq!(counter.take(), 5);
+}
+
+#[test]
+fn render_lists_counters_by_name_then_uptime() {
+    let local = Registry::new();
+    local.counter("uplink_lines_total").add(400);
+    local.counter("sensor_polls_total").add(400);
+    local.counter("uplink_batches_total").inc();
+    let text = local.render();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(
+        lines[..3],
+        [
+            "sensor_polls_total 400",
+            "uplink_batches_total 1",
+            "uplink_lines_total 400"
+        ]
+    );
+    assert!(lines[3].starts_with("uptime_seconds "), "{}", lines[3]);
+    assert_eq!(lines.len(), 4);
+}
@@ -0,0 +1,104 @@
+// Exercise 1: config lookup
+//
+//   get(key) -> value      the key is usually a literal and the value
+//                          already lives in the config, so both are
+//                          borrowed: `&str` in, `Option<&str>` out, tied
+//                          to `&self`. A `HashMap<String, _>` can be
+//                          searched with a `&str` because `String:
+//                          Borrow<str>`, so not even the lookup copies.
+//   set(key, value)        the config keeps both, so it takes ownership.
+//                          `Into<String>` accepts a literal (copied once,
+//                          unavoidably) or a `String` the caller built
+//                          and no longer needs (moved, no copy). The key
+//                          is also `AsRef<str>`, so an existing key is
+//                          found without converting it at all.
+//   resolve(key)           a value may refer to another as `${name}`.
+//                          Most don't, so the answer is borrowed when
+//                          there is nothing to expand and owned when
+//                          there is: `Option<Cow<str>>`.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    values: HashMap<String, String>,
+}
+
+impl Config {
+    // `key = value` lines; blank lines and `#` comments are skipped, as
+    // are lines without an `=`
+    pub fn parse(text: &str) -> Config {
+        let values = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+            .collect();
+        Config { values }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    // The default must live as long as the config for the result to
+    // borrow from either; a literal always does
+    pub fn get_or<'a>(&'a self, key: &str, default: &'a str) -> &'a str {
+        self.get(key).unwrap_or(default)
+    }
+
+    // An existing key keeps its stored `String`, so the new one is only
+    // converted (and a literal only copied) when the key is new.
+    // `insert` wouldn't do: it makes room for the key before it looks
+    // for it, so a full map grows even when replacing a value.
+    pub fn set<K>(&mut self, key: K, value: impl Into<String>)
+    where
+        K: AsRef<str> + Into<String>,
+    {
+        match self.values.get_mut(key.as_ref()) {
+            Some(slot) => *slot = value.into(),
+            None => {
+                self.values.insert(key.into(), value.into());
+            }
+        }
+    }
+
+    // `${name}` is replaced by the value of `name`, one level deep (the
+    // replacement is not expanded again, so a loop can't hang). Unknown
+    // names are left as written.
+    pub fn resolve(&self, key: &str) -> Option<Cow<'_, str>> {
+        let value = self.get(key)?;
+        if !value.contains("${") {
+            return Some(Cow::Borrowed(value));
+        }
+        let mut out = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            match after.find('}') {
+                Some(end) => {
+                    let name = &after[..end];
+                    match self.get(name) {
+                        Some(v) => out.push_str(v),
+                        None => out.push_str(&rest[start..start + 3 + end]),
+                    }
+                    rest = &after[end + 1..];
+                }
+                None => break,
+            }
+        }
+        out.push_str(rest);
+        Some(Cow::Owned(out))
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
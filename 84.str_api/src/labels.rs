@@ -0,0 +1,81 @@
+// Exercise 3: label rendering
+//
+// A metrics scrape is a few hundred lines like
+//
+//   sensor_celsius{device="t1",room="lab"} 21.5
+//
+// written into one response body. Returning a `String` per line (or per
+// label) allocates for text that is copied into the body a moment
+// later. So rendering appends to a `&mut String` the caller owns, and
+// `Labels` is a `Display` adapter that formats straight into any
+// writer. With a buffer reused between scrapes, nothing is allocated.
+//
+// Label keys and values come both as literals and as strings built at
+// run time, so the slices are generic over `AsRef<str>`: `&[(&str,
+// &str)]` and `&[(String, String)]` both work without converting.
+//
+// `escape_value` is for callers that need the escaped value itself, to
+// store it or use it as a key. Nearly no value contains `\`, `"` or a
+// newline, so it returns `Cow` and borrows in that case.
+
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+
+const SPECIAL: [char; 3] = ['\\', '"', '\n'];
+
+// Writes `value` with `\`, `"` and newlines escaped, copying the plain
+// runs between them in one piece
+fn write_escaped(out: &mut impl Write, value: &str) -> fmt::Result {
+    let mut rest = value;
+    while let Some(i) = rest.find(SPECIAL) {
+        out.write_str(&rest[..i])?;
+        out.write_str(match rest.as_bytes()[i] {
+            b'\\' => "\\\\",
+            b'"' => "\\\"",
+            _ => "\\n",
+        })?;
+        rest = &rest[i + 1..];
+    }
+    out.write_str(rest)
+}
+
+pub fn escape_value(value: &str) -> Cow<'_, str> {
+    if !value.contains(SPECIAL) {
+        return Cow::Borrowed(value);
+    }
+    let mut out = String::with_capacity(value.len() + 2);
+    let _ = write_escaped(&mut out, value);
+    Cow::Owned(out)
+}
+
+// `{k="v",...}`, or nothing at all for no labels
+pub struct Labels<'a, K, V>(pub &'a [(K, V)]);
+
+impl<K: AsRef<str>, V: AsRef<str>> fmt::Display for Labels<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return Ok(());
+        }
+        f.write_char('{')?;
+        for (i, (key, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_char(',')?;
+            }
+            f.write_str(key.as_ref())?;
+            f.write_str("=\"")?;
+            write_escaped(f, value.as_ref())?;
+            f.write_char('"')?;
+        }
+        f.write_char('}')
+    }
+}
+
+// Appends one sample line to `out`
+pub fn render_sample<K: AsRef<str>, V: AsRef<str>>(
+    out: &mut String,
+    name: &str,
+    labels: &[(K, V)],
+    value: f64,
+) {
+    let _ = writeln!(out, "{}{} {}", name, Labels(labels), value);
+}
@@ -0,0 +1,24 @@
+// Choosing between &str, String and Cow in real signatures
+//
+// Each module is one exercise: a function (or type) from the gateway
+// whose parameter and return types have to be chosen, solved.
+//
+// - `config`: lookups borrow both the key and the answer; storing takes
+//   ownership; expanding `${refs}` returns `Cow`
+// - `topic`: normalising a topic borrows when it is already tidy, which
+//   is nearly always
+// - `labels`: rendering writes into the caller's buffer, and escaping
+//   returns `Cow` for callers that need a value
+// - `metric`: a name that is usually a literal is a `Cow<'static, str>`
+// - `naive`: the first drafts, all `String`, for comparison
+
+pub mod config;
+pub mod labels;
+pub mod metric;
+pub mod naive;
+pub mod topic;
+
+pub use config::Config;
+pub use labels::{escape_value, render_sample, Labels};
+pub use metric::Metric;
+pub use topic::{is_normal, matches, normalize_topic};
@@ -0,0 +1,230 @@
+use memprofile::{profile, Profiler};
+use std::borrow::Cow;
+use std::hint::black_box;
+use std::time::Instant;
+use str_api::{escape_value, naive, normalize_topic, render_sample, Config, Labels, Metric};
+
+#[global_allocator]
+static GLOBAL: Profiler = Profiler;
+
+const MESSAGES: usize = 100_000;
+
+const CONFIG: &str = "
+# gateway settings
+gateway_id = edge-7
+data_dir = /var/lib/gateway
+history = ${data_dir}/history.db
+poll_interval_ms = 250
+";
+
+// Allocations made while running `f`
+fn allocs<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let (value, report) = profile("call", f);
+    (value, report.allocs)
+}
+
+// Asks which variant, which is the one thing `&str` can't answer
+#[allow(clippy::ptr_arg)]
+fn kind(value: &Cow<'_, str>) -> &'static str {
+    match value {
+        Cow::Borrowed(_) => "Borrowed",
+        Cow::Owned(_) => "Owned",
+    }
+}
+
+// xorshift32, so every run sees the same topics
+fn random(seed: u32, n: usize) -> Vec<u32> {
+    let mut x = seed.max(1);
+    (0..n)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        })
+        .collect()
+}
+
+// What devices send: one in twenty from firmware that gets it wrong
+fn topics(n: usize) -> Vec<String> {
+    const ROOMS: [&str; 4] = ["lab", "hall", "roof", "cellar"];
+    const METRICS: [&str; 3] = ["temp", "humidity", "co2"];
+    random(5, n)
+        .into_iter()
+        .map(|x| {
+            let room = ROOMS[x as usize % 4];
+            let metric = METRICS[(x >> 4) as usize % 3];
+            if x % 20 == 0 {
+                format!("/Sensors//{}/{}/", room.to_uppercase(), metric)
+            } else {
+                format!("sensors/{}/{}", room, metric)
+            }
+        })
+        .collect()
+}
+
+fn main() {
+    println!("=== &str, String and Cow Examples ===\n");
+    let mut config = Config::parse(CONFIG);
+
+    // 1. Config lookup
+    println!("1. Config lookup: borrow in, borrow out:");
+    println!("   get(&str) -> Option<&str>         vs  get(String) -> Option<String>");
+    let (id, n) = allocs(|| config.get(black_box("gateway_id")));
+    let (_, naive_n) = allocs(|| naive::config_get(&config, "gateway_id".to_string()));
+    println!(
+        "   get(\"gateway_id\"): {:?}, {} allocs; naive: {} allocs",
+        id, n, naive_n
+    );
+    let (region, n) = allocs(|| config.get_or("region", "eu-west"));
+    println!("   get_or(\"region\", ..): {:?}, {} allocs", region, n);
+    for key in ["data_dir", "history"] {
+        let (value, n) = allocs(|| config.resolve(key));
+        let value = value.unwrap_or_default();
+        println!(
+            "   resolve({:?}): {} {:?}, {} allocs",
+            key,
+            kind(&value),
+            value,
+            n
+        );
+    }
+    let built = format!("edge-{}", 8);
+    let (_, n) = allocs(|| config.set("gateway_id", built));
+    println!(
+        "   set(\"gateway_id\", String): now {:?}, {} allocs",
+        config.get_or("gateway_id", ""),
+        n
+    );
+
+    // 2. Topic normalisation
+    println!("\n2. Topics: Cow borrows when nothing changes:");
+    for topic in [
+        "sensors/lab/temp",
+        " /sensors/lab/temp/",
+        "/Sensors//LAB/temp/",
+    ] {
+        let (normal, n) = allocs(|| normalize_topic(topic));
+        println!(
+            "   {:<22} -> {:<8} {:<18} {} allocs",
+            format!("{:?}", topic),
+            kind(&normal),
+            format!("{:?}", normal),
+            n
+        );
+    }
+    let (normal, n) = allocs(|| naive::normalize_topic("sensors/lab/temp".to_string()));
+    println!("   naive, already normal: {:?}, {} allocs", normal, n);
+    let (hit, n) = allocs(|| str_api::matches("sensors/+/temp", "sensors/lab/temp"));
+    println!(
+        "   sensors/+/temp matches sensors/lab/temp: {}, {} allocs",
+        hit, n
+    );
+
+    // 3. Label rendering
+    println!("\n3. Labels: write into the caller's buffer:");
+    let literal = [("device", "t1"), ("room", "lab \"A\"")];
+    let built: Vec<(String, String)> = literal
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    let mut body = String::with_capacity(1024);
+    let (_, n) = allocs(|| {
+        render_sample(&mut body, "sensor_celsius", &literal, 21.5);
+        render_sample(&mut body, "sensor_celsius", &built, 21.5);
+    });
+    print!("   {}", body.replace('\n', "\n   "));
+    println!("two samples, &str and String labels: {} allocs", n);
+    let (_, naive_n) = allocs(|| naive::render_sample("sensor_celsius", &built, 21.5));
+    println!("   naive render_sample: {} allocs", naive_n);
+    let (plain, n) = allocs(|| escape_value("lab 2"));
+    let (quoted, m) = allocs(|| escape_value("lab \"A\""));
+    println!(
+        "   escape_value: {} {:?} ({} allocs), {} {:?} ({} allocs)",
+        kind(&plain),
+        plain,
+        n,
+        kind(&quoted),
+        quoted,
+        m
+    );
+    println!("   Labels as Display: {}", Labels(&literal));
+
+    // 4. Metric names
+    println!("\n4. Metric names: Cow<'static, str> fields:");
+    let (sent, n) = allocs(|| Metric::new("uplink_sent_total", "Lines sent upstream"));
+    println!(
+        "   literal name: borrowed {}, {} allocs",
+        sent.borrows_name(),
+        n
+    );
+    let name = format!("sensor_{}_celsius", "lab");
+    let (temp, n) = allocs(|| Metric::new(name, "Temperature"));
+    println!(
+        "   built name:   borrowed {}, {} allocs",
+        temp.borrows_name(),
+        n
+    );
+
+    // 5. A scrape's worth of messages
+    println!("\n5. {} messages: look up, normalise, render:", MESSAGES);
+    let topics = topics(MESSAGES);
+    let labels: Vec<(String, String)> = vec![(
+        "gateway".to_string(),
+        config.get_or("gateway_id", "").to_string(),
+    )];
+    let started = Instant::now();
+    let (naive_body, naive_report) = profile("naive", || {
+        let mut body = String::new();
+        for topic in &topics {
+            let id = naive::config_get(&config, "gateway_id".to_string());
+            let topic = naive::normalize_topic(topic.clone());
+            black_box((id, &topic));
+            body.push_str(&naive::render_sample(
+                &topic.replace('/', "_"),
+                &labels,
+                1.0,
+            ));
+        }
+        body
+    });
+    let naive_time = started.elapsed();
+    let started = Instant::now();
+    let (body, designed_report) = profile("designed", || {
+        let mut body = String::with_capacity(naive_body.len());
+        let labels = [("gateway", config.get_or("gateway_id", ""))];
+        let mut name = String::new();
+        for topic in &topics {
+            let id = config.get("gateway_id");
+            let topic = normalize_topic(topic);
+            black_box((id, &topic));
+            // One scratch buffer for the metric name, reused every line
+            name.clear();
+            name.extend(topic.chars().map(|c| if c == '/' { '_' } else { c }));
+            render_sample(&mut body, &name, &labels, 1.0);
+        }
+        body
+    });
+    let time = started.elapsed();
+    println!("   version    allocs/msg  bytes/msg      time");
+    for (label, r, t) in [
+        ("naive", naive_report, naive_time),
+        ("designed", designed_report, time),
+    ] {
+        println!(
+            "   {:<9} {:>10.2} {:>10.1} {:>9.1?}",
+            label,
+            r.allocs as f64 / MESSAGES as f64,
+            r.allocated_bytes as f64 / MESSAGES as f64,
+            t
+        );
+    }
+    println!(
+        "   bodies: {} and {} bytes, identical: {}",
+        naive_body.len(),
+        body.len(),
+        body == naive_body
+    );
+
+    println!("\n=== End of &str, String and Cow Examples ===");
+}
@@ -0,0 +1,54 @@
+// Exercise 4: a name stored in a struct
+//
+// A field has to own its data or borrow it for a lifetime the struct
+// then carries everywhere. Metric names are nearly always literals
+// (`"uplink_sent_total"`), sometimes built at run time
+// (`format!("sensor_{}_celsius", kind)`). `String` would copy every
+// literal; `&'static str` would rule out the built ones, or leak them.
+// `Cow<'static, str>` holds either: a literal costs nothing, a built
+// `String` is moved in. `impl Into<Cow<'static, str>>` lets callers pass
+// both without writing `Cow::` themselves.
+
+use crate::labels::Labels;
+use std::borrow::Cow;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metric {
+    name: Cow<'static, str>,
+    help: Cow<'static, str>,
+    value: f64,
+}
+
+impl Metric {
+    pub fn new(name: impl Into<Cow<'static, str>>, help: impl Into<Cow<'static, str>>) -> Metric {
+        Metric {
+            name: name.into(),
+            help: help.into(),
+            value: 0.0,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // True when the name points at a literal instead of an owned copy
+    pub fn borrows_name(&self) -> bool {
+        matches!(self.name, Cow::Borrowed(_))
+    }
+
+    pub fn set(&mut self, value: f64) {
+        self.value = value;
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    // `# HELP` and the sample line, appended to `out`
+    pub fn render<K: AsRef<str>, V: AsRef<str>>(&self, out: &mut String, labels: &[(K, V)]) {
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "{}{} {}", self.name, Labels(labels), self.value);
+    }
+}
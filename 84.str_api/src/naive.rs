@@ -0,0 +1,47 @@
+// The first drafts: every string parameter and result a `String`
+//
+// They compile, they work and they read simply, which is why code ends
+// up like this. Each one copies on the way in, on the way out, or both,
+// whether or not anything changed. main.rs counts the difference.
+
+use crate::config::Config;
+
+// The caller has to build a `String` key to ask, and gets a copy back
+pub fn config_get(config: &Config, key: String) -> Option<String> {
+    config.get(&key).map(|v| v.to_string())
+}
+
+// Takes ownership it doesn't need and always builds a new `String`
+pub fn normalize_topic(topic: String) -> String {
+    topic
+        .split('/')
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| l.to_ascii_lowercase())
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+// Only accepts `String` pairs, and returns a new `String` per label set
+pub fn render_labels(labels: &[(String, String)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let parts: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| {
+            format!(
+                "{}=\"{}\"",
+                k,
+                v.replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('\n', "\\n")
+            )
+        })
+        .collect();
+    format!("{{{}}}", parts.join(","))
+}
+
+pub fn render_sample(name: &str, labels: &[(String, String)], value: f64) -> String {
+    format!("{}{} {}\n", name, render_labels(labels), value)
+}
@@ -0,0 +1,57 @@
+// Exercise 2: topic normalisation
+//
+// Devices publish to topics like `sensors/lab/temp`, but some firmware
+// sends `/Sensors//Lab/TEMP/`. The canonical form is lowercase, with no
+// empty levels and no leading or trailing `/`.
+//
+// The input is only read, so it is `&str`. The output is the question.
+// `String` would copy every topic, though nearly all of them arrive
+// canonical already. `&str` can't hold a lowercased copy. `Cow<'_, str>`
+// returns the input (or a slice of it, when only the outer slashes or
+// spaces were wrong) and allocates only for the rest. Callers that need
+// to keep it call `into_owned()`, which moves an owned result without a
+// second copy.
+
+use std::borrow::Cow;
+
+pub fn is_normal(topic: &str) -> bool {
+    !topic.is_empty()
+        && !topic.starts_with('/')
+        && !topic.ends_with('/')
+        && !topic.contains("//")
+        && !topic
+            .bytes()
+            .any(|b| b.is_ascii_uppercase() || b.is_ascii_whitespace())
+}
+
+pub fn normalize_topic(topic: &str) -> Cow<'_, str> {
+    let trimmed = topic.trim().trim_matches('/');
+    if is_normal(trimmed) || trimmed.is_empty() {
+        return Cow::Borrowed(trimmed);
+    }
+    let mut out = String::with_capacity(trimmed.len());
+    for level in trimmed.split('/').map(str::trim).filter(|l| !l.is_empty()) {
+        if !out.is_empty() {
+            out.push('/');
+        }
+        out.extend(level.chars().map(|c| c.to_ascii_lowercase()));
+    }
+    Cow::Owned(out)
+}
+
+// MQTT-style filter: `+` matches one level, a final `#` any number
+// (including none). Both sides are only read, so both are `&str`, and
+// the levels are compared as slices, so nothing is allocated.
+pub fn matches(filter: &str, topic: &str) -> bool {
+    let mut topic_levels = topic.split('/');
+    for level in filter.split('/') {
+        if level == "#" {
+            return true;
+        }
+        match topic_levels.next() {
+            Some(t) if level == "+" || level == t => {}
+            _ => return false,
+        }
+    }
+    topic_levels.next().is_none()
+}
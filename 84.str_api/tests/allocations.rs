@@ -0,0 +1,213 @@
+// Every borrowed path allocates nothing; every owned path allocates what
+// it must and no more
+//
+// The counts come from `memprofile::Profiler`, installed below. Its
+// counters are process-wide, so a check running on one thread would
+// also count another thread's allocations. The test harness runs tests
+// on several threads and allocates as it starts each one, which is why
+// this file has `harness = false` in Cargo.toml and runs its checks one
+// after another from `main`.
+
+use memprofile::{profile, Profiler};
+use std::borrow::Cow;
+use std::hint::black_box;
+use std::process;
+use str_api::{escape_value, naive, normalize_topic, render_sample, Config, Labels, Metric};
+
+#[global_allocator]
+static GLOBAL: Profiler = Profiler;
+
+const CONFIG: &str = "
+# gateway settings
+gateway_id = edge-7
+data_dir = /var/lib/gateway
+history = ${data_dir}/history.db
+";
+
+// Allocations made while running `f`; its result is dropped afterwards,
+// outside the measurement
+fn allocs<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let (value, report) = profile("check", f);
+    (value, report.allocs)
+}
+
+struct Checks {
+    failed: usize,
+}
+
+impl Checks {
+    fn check(&mut self, name: &str, ok: bool) {
+        println!("{} ... {}", name, if ok { "ok" } else { "FAILED" });
+        if !ok {
+            self.failed += 1;
+        }
+    }
+}
+
+fn main() {
+    let mut c = Checks { failed: 0 };
+    // A zero count only means something if the allocator is counting
+    drop(black_box(vec![0u8; 16]));
+    c.check(
+        "the counting allocator is installed",
+        memprofile::is_active(),
+    );
+
+    // Config lookup
+    let config = Config::parse(CONFIG);
+    let (value, n) = allocs(|| config.get(black_box("gateway_id")));
+    c.check(
+        "config get borrows key and value",
+        value == Some("edge-7") && n == 0,
+    );
+    let (value, n) = allocs(|| config.get_or(black_box("region"), "eu-west"));
+    c.check(
+        "config get_or borrows the default",
+        value == "eu-west" && n == 0,
+    );
+    let (value, n) = allocs(|| config.resolve(black_box("data_dir")));
+    c.check(
+        "config resolve without refs borrows",
+        matches!(value, Some(Cow::Borrowed("/var/lib/gateway"))) && n == 0,
+    );
+    let (value, n) = allocs(|| config.resolve(black_box("history")));
+    c.check(
+        "config resolve with a ref allocates once",
+        value.as_deref() == Some("/var/lib/gateway/history.db") && n == 1,
+    );
+    let mut config = config;
+    let built = format!("edge-{}", black_box(8));
+    let key = String::from("gateway_id");
+    let (_, n) = allocs(move || config.set(key, built));
+    c.check("config set moves owned strings in", n == 0);
+    let mut config = Config::parse(CONFIG);
+    let (_, n) = allocs(|| config.set("gateway_id", "edge-9"));
+    c.check("config set copies only the new value", n == 1);
+    let (_, n) = allocs(|| config.set("region", "eu-west"));
+    c.check("a new key copies both literals", n >= 2);
+    let (value, n) = allocs(|| naive::config_get(&config, "gateway_id".to_string()));
+    c.check(
+        "naive config get allocates key and value",
+        value.as_deref() == Some("edge-9") && n == 2,
+    );
+
+    // Topic normalisation
+    let (value, n) = allocs(|| normalize_topic(black_box("sensors/lab/temp")));
+    c.check(
+        "a normal topic is borrowed",
+        matches!(value, Cow::Borrowed("sensors/lab/temp")) && n == 0,
+    );
+    let (value, n) = allocs(|| normalize_topic(black_box(" /sensors/lab/temp/ ")));
+    c.check(
+        "outer slashes only: borrowed slice",
+        matches!(value, Cow::Borrowed("sensors/lab/temp")) && n == 0,
+    );
+    let (value, n) = allocs(|| normalize_topic(black_box("/Sensors//Lab/TEMP/")));
+    c.check(
+        "an untidy topic allocates once",
+        value == "sensors/lab/temp" && matches!(value, Cow::Owned(_)) && n == 1,
+    );
+    let owned = normalize_topic("Sensors/Lab");
+    let (value, n) = allocs(move || owned.into_owned());
+    c.check(
+        "into_owned moves an owned result",
+        value == "sensors/lab" && n == 0,
+    );
+    let (ok, n) = allocs(|| {
+        str_api::matches(black_box("sensors/+/temp"), black_box("sensors/lab/temp"))
+            && str_api::matches("sensors/#", "sensors/lab/temp")
+            && !str_api::matches("sensors/+", "sensors/lab/temp")
+    });
+    c.check("topic matching allocates nothing", ok && n == 0);
+    let (value, n) = allocs(|| naive::normalize_topic(black_box("sensors/lab/temp").to_string()));
+    c.check(
+        "naive normalize allocates for a tidy topic",
+        value == "sensors/lab/temp" && n > 1,
+    );
+
+    // Label rendering
+    let (value, n) = allocs(|| escape_value(black_box("lab 2")));
+    c.check(
+        "a plain label value is borrowed",
+        matches!(value, Cow::Borrowed("lab 2")) && n == 0,
+    );
+    let (value, n) = allocs(|| escape_value(black_box("say \"hi\"\n")));
+    c.check(
+        "a value with quotes is escaped once",
+        value == "say \\\"hi\\\"\\n" && n == 1,
+    );
+    let literal = [("device", "t1"), ("room", "lab \"A\"")];
+    let built = vec![
+        ("device".to_string(), "t1".to_string()),
+        ("room".to_string(), "lab \"A\"".to_string()),
+    ];
+    let mut out = String::with_capacity(256);
+    let (_, n) = allocs(|| {
+        render_sample(&mut out, "sensor_celsius", &literal, 21.5);
+        render_sample(&mut out, "sensor_celsius", &built, 21.5);
+    });
+    c.check(
+        "rendering into a sized buffer allocates 0",
+        n == 0 && out == "sensor_celsius{device=\"t1\",room=\"lab \\\"A\\\"\"} 21.5\n".repeat(2),
+    );
+    out.clear();
+    let (_, n) = allocs(|| {
+        use std::fmt::Write;
+        write!(out, "{}", Labels::<&str, &str>(&[])).unwrap();
+    });
+    c.check("no labels render as nothing", out.is_empty() && n == 0);
+    let (value, n) = allocs(|| naive::render_sample("sensor_celsius", &built, 21.5));
+    c.check(
+        "naive render allocates per label and line",
+        value == "sensor_celsius{device=\"t1\",room=\"lab \\\"A\\\"\"} 21.5\n" && n >= 4,
+    );
+
+    // Metric names
+    let (metric, n) = allocs(|| Metric::new("uplink_sent_total", "Lines sent upstream"));
+    c.check(
+        "a literal metric name borrows",
+        metric.borrows_name() && n == 0,
+    );
+    let name = format!("sensor_{}_celsius", black_box("lab"));
+    let (metric, n) = allocs(move || Metric::new(name, "Temperature"));
+    c.check(
+        "a built metric name is moved in",
+        !metric.borrows_name() && metric.name() == "sensor_lab_celsius" && n == 0,
+    );
+    let mut out = String::with_capacity(256);
+    let (_, n) = allocs(|| metric.render(&mut out, &[("room", "lab")]));
+    c.check(
+        "rendering a metric allocates 0",
+        n == 0
+            && out == "# HELP sensor_lab_celsius Temperature\nsensor_lab_celsius{room=\"lab\"} 0\n",
+    );
+
+    // A scrape's worth of messages: only untidy topics allocate
+    let topics: Vec<String> = (0..1000)
+        .map(|i| {
+            if i % 20 == 0 {
+                "/Sensors//LAB/temp/".to_string()
+            } else {
+                "sensors/lab/temp".to_string()
+            }
+        })
+        .collect();
+    let mut body = String::with_capacity(64 * 1024);
+    let mut name = String::with_capacity(64);
+    let labels = [("gateway", config.get_or("gateway_id", ""))];
+    let (_, n) = allocs(|| {
+        for topic in &topics {
+            let topic = normalize_topic(topic);
+            name.clear();
+            name.extend(topic.chars().map(|c| if c == '/' { '_' } else { c }));
+            render_sample(&mut body, &name, &labels, 1.0);
+        }
+    });
+    c.check("a message allocates only for an untidy topic", n == 50);
+
+    if c.failed > 0 {
+        eprintln!("{} allocation check(s) failed", c.failed);
+        process::exit(1);
+    }
+    println!("all allocation checks passed");
+}
@@ -0,0 +1,161 @@
+// What each function returns, separate from what it allocates; the
+// counting allocator lives only in allocations.rs
+
+use std::borrow::Cow;
+use str_api::{
+    escape_value, is_normal, matches, naive, normalize_topic, render_sample, Config, Labels, Metric,
+};
+
+// xorshift32, so every run sees the same topics
+struct Rng(u32);
+
+impl Rng {
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+}
+
+// Topics built from a few awkward pieces, tidy or not
+fn random_topic(rng: &mut Rng) -> String {
+    const PIECES: [&str; 8] = ["sensors", "Lab", "/", "//", " ", "temp", "CO2", "x"];
+    let len = rng.next() % 8;
+    (0..len)
+        .map(|_| PIECES[rng.next() as usize % PIECES.len()])
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[test]
+fn config_parse_skips_comments_and_lines_without_equals() {
+    let config = Config::parse("# comment\n\n gateway_id = edge-7 \nno equals here\nempty =\n");
+    assert_eq!(config.len(), 2);
+    assert_eq!(config.get("gateway_id"), Some("edge-7"));
+    assert_eq!(config.get("empty"), Some(""));
+    assert_eq!(config.get("# comment"), None);
+    assert_eq!(config.get_or("region", "eu-west"), "eu-west");
+    assert!(Config::parse("").is_empty());
+}
+
+#[test]
+fn resolve_expands_one_level() {
+    let config = Config::parse(
+        "data_dir = /var/lib/gateway
+         history = ${data_dir}/history.db
+         nested = ${history}
+         unknown = ${nope}/x
+         open = ${data_dir",
+    );
+    assert_eq!(
+        config.resolve("data_dir"),
+        Some(Cow::Borrowed("/var/lib/gateway"))
+    );
+    assert_eq!(
+        config.resolve("history").as_deref(),
+        Some("/var/lib/gateway/history.db")
+    );
+    // The replacement is not expanded again
+    assert_eq!(
+        config.resolve("nested").as_deref(),
+        Some("${data_dir}/history.db")
+    );
+    assert_eq!(config.resolve("unknown").as_deref(), Some("${nope}/x"));
+    assert_eq!(config.resolve("open").as_deref(), Some("${data_dir"));
+    assert_eq!(config.resolve("missing"), None);
+}
+
+#[test]
+fn set_replaces_or_adds() {
+    let mut config = Config::parse("gateway_id = edge-7");
+    config.set("gateway_id", format!("edge-{}", 8));
+    config.set(String::from("region"), "eu-west");
+    assert_eq!(config.get("gateway_id"), Some("edge-8"));
+    assert_eq!(config.get("region"), Some("eu-west"));
+    assert_eq!(config.len(), 2);
+}
+
+#[test]
+fn normalize_borrows_tidy_topics_and_slices_outer_slashes() {
+    assert!(matches!(
+        normalize_topic("sensors/lab/temp"),
+        Cow::Borrowed("sensors/lab/temp")
+    ));
+    assert!(matches!(
+        normalize_topic(" /sensors/lab/temp/"),
+        Cow::Borrowed("sensors/lab/temp")
+    ));
+    let untidy = normalize_topic("/Sensors//LAB/temp/");
+    assert!(matches!(untidy, Cow::Owned(_)));
+    assert_eq!(untidy, "sensors/lab/temp");
+    assert_eq!(normalize_topic("///"), "");
+    assert!(is_normal("sensors/lab"));
+    for bad in ["", "/sensors", "sensors/", "a//b", "Sensors", "a b"] {
+        assert!(!is_normal(bad), "{:?}", bad);
+    }
+}
+
+#[test]
+fn normalize_agrees_with_the_naive_version() {
+    let mut rng = Rng(5);
+    for _ in 0..5000 {
+        let topic = random_topic(&mut rng);
+        let normal = normalize_topic(&topic);
+        assert_eq!(normal, naive::normalize_topic(topic.clone()), "{:?}", topic);
+        assert!(normal.is_empty() || is_normal(&normal), "{:?}", topic);
+        // Normalising is idempotent, and the second time always borrows
+        assert!(matches!(normalize_topic(&normal), Cow::Borrowed(_)));
+    }
+}
+
+#[test]
+fn wildcards_match_levels() {
+    assert!(matches("sensors/+/temp", "sensors/lab/temp"));
+    assert!(matches("sensors/#", "sensors/lab/temp"));
+    assert!(matches("sensors/#", "sensors"));
+    assert!(matches("#", "anything/at/all"));
+    assert!(!matches("sensors/+", "sensors/lab/temp"));
+    assert!(!matches("sensors/+/temp", "sensors/lab/co2"));
+    assert!(!matches("sensors/lab/temp", "sensors/lab"));
+}
+
+#[test]
+fn labels_escape_and_render_like_the_naive_version() {
+    assert!(matches!(escape_value("lab 2"), Cow::Borrowed("lab 2")));
+    assert_eq!(escape_value("say \"hi\"\n"), "say \\\"hi\\\"\\n");
+    assert_eq!(escape_value("a\\b"), "a\\\\b");
+
+    let literal = [("device", "t1"), ("room", "lab \"A\"")];
+    let built: Vec<(String, String)> = literal
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    assert_eq!(
+        Labels(&literal).to_string(),
+        "{device=\"t1\",room=\"lab \\\"A\\\"\"}"
+    );
+    assert_eq!(Labels::<&str, &str>(&[]).to_string(), "");
+    let mut out = String::new();
+    render_sample(&mut out, "sensor_celsius", &literal, 21.5);
+    render_sample(&mut out, "sensor_celsius", &built, 21.5);
+    let line = naive::render_sample("sensor_celsius", &built, 21.5);
+    assert_eq!(out, line.repeat(2));
+    assert_eq!(naive::render_labels(&[]), "");
+}
+
+#[test]
+fn metrics_take_literal_or_built_names() {
+    let sent = Metric::new("uplink_sent_total", "Lines sent upstream");
+    assert!(sent.borrows_name());
+    let mut temp = Metric::new(format!("sensor_{}_celsius", "lab"), "Temperature");
+    assert!(!temp.borrows_name());
+    temp.set(21.5);
+    assert_eq!(temp.value(), 21.5);
+    let mut out = String::new();
+    temp.render(&mut out, &[("room", "lab")]);
+    assert_eq!(
+        out,
+        "# HELP sensor_lab_celsius Temperature\nsensor_lab_celsius{room=\"lab\"} 21.5\n"
+    );
+}
@@ -0,0 +1,116 @@
+// Two clocks, for two different questions
+//
+//   monotonic  `retry::Clock::now`, an `Instant`. It only ever moves
+//              forward, at a steady rate, from an arbitrary start. It
+//              answers "how long since?" and nothing else.
+//   wall       `Clock::utc`, a `DateTime<Utc>`. It answers "when?", and
+//              can be wrong: an RTC gains or loses seconds a day, NTP
+//              steps it, a user sets it, a reboot without a battery
+//              resets it to 1970.
+//
+// Telemetry needs both. A `Stamp` takes them together, so intervals come
+// from the clock that can't jump and timestamps from the one that can be
+// corrected.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+pub use retry::SystemClock;
+
+pub trait Clock: retry::Clock {
+    fn utc(&self) -> DateTime<Utc>;
+}
+
+impl Clock for SystemClock {
+    fn utc(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+// Time for tests. The monotonic side moves only in `advance` (or
+// `sleep`); the wall side follows it at `1 + drift_ppm / 1e6` times the
+// rate, like a crystal that runs fast or slow, plus any `step_wall`.
+#[derive(Debug)]
+pub struct ManualClock {
+    start: Instant,
+    elapsed: Cell<Duration>,
+    wall_start: DateTime<Utc>,
+    drift_ppm: f64,
+    stepped: Cell<TimeDelta>,
+}
+
+impl ManualClock {
+    pub fn new(utc: DateTime<Utc>) -> ManualClock {
+        ManualClock {
+            start: Instant::now(),
+            elapsed: Cell::new(Duration::ZERO),
+            wall_start: utc,
+            drift_ppm: 0.0,
+            stepped: Cell::new(TimeDelta::zero()),
+        }
+    }
+
+    // Positive runs fast; a cheap 32 kHz crystal is within about ±20
+    // ppm at room temperature and much worse at the ends of its range
+    pub fn with_drift_ppm(mut self, ppm: f64) -> ManualClock {
+        self.drift_ppm = ppm;
+        self
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed.set(self.elapsed.get() + duration);
+    }
+
+    // The wall clock jumps; the monotonic clock doesn't notice
+    pub fn step_wall(&self, delta: TimeDelta) {
+        self.stepped.set(self.stepped.get() + delta);
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed.get()
+    }
+}
+
+impl retry::Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed.get()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration)
+    }
+}
+
+impl Clock for ManualClock {
+    fn utc(&self) -> DateTime<Utc> {
+        let ns = self.elapsed.get().as_nanos() as f64 * (1.0 + self.drift_ppm / 1e6);
+        self.wall_start + TimeDelta::nanoseconds(ns as i64) + self.stepped.get()
+    }
+}
+
+// Both clocks read at one moment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stamp {
+    pub mono: Instant,
+    pub utc: DateTime<Utc>,
+}
+
+impl Stamp {
+    pub fn now(clock: &impl Clock) -> Stamp {
+        Stamp {
+            mono: clock.now(),
+            utc: clock.utc(),
+        }
+    }
+
+    // The real time between two stamps, whatever the wall clock did
+    pub fn since(&self, earlier: &Stamp) -> Duration {
+        self.mono.saturating_duration_since(earlier.mono)
+    }
+
+    // What the wall clock claims passed; negative if it was set back
+    pub fn wall_since(&self, earlier: &Stamp) -> TimeDelta {
+        self.utc - earlier.utc
+    }
+}
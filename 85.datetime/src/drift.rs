@@ -0,0 +1,250 @@
+// Steering a drifting RTC toward server time
+//
+// A device's clock is off by some offset and gains or loses a steady
+// amount, parts per million of elapsed time (86 ms a day per ppm). Now
+// and then it asks the server for the time, NTP-style:
+//
+//   device sends at `sent` (its clock) ──> server stamps `server` (true)
+//   device receives at `received` (its clock) <──
+//
+// Assuming the two legs took equally long, the server stamped its time
+// at the device's midpoint `(sent + received) / 2`, so the correction
+// needed then is `server - midpoint`. The asymmetry between the legs is
+// the error, which is at most half the round trip, so long round trips
+// are refused.
+//
+// The corrector never touches the device clock. `corrected(device_time)`
+// adds a correction that changes smoothly with device time:
+//
+//   correction(t) = base + freq_ppm * (t - anchor) + slew(t - anchor)
+//
+// - A small error is **slewed**. The correction moves toward it at most
+//   `max_slew_ppm`, so corrected time never jumps and never runs
+//   backwards. This matters for log order and for rates computed from
+//   timestamps.
+// - A large error (the first sync, an RTC reset by a flat battery) is
+//   **stepped**. Slewing 10 minutes at 500 ppm would take two weeks.
+// - The slope of successive measurements is the crystal's rate error.
+//   Once `freq_ppm` has learned it, the correction keeps up between
+//   syncs instead of the error building up again each time.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DriftConfig {
+    // Fastest the correction may change; 500 ppm is Linux adjtime's limit
+    pub max_slew_ppm: f64,
+    // Errors larger than this are stepped, smaller ones slewed
+    pub step_threshold: TimeDelta,
+    // Exchanges with a longer round trip are refused
+    pub max_round_trip: TimeDelta,
+    // Syncs closer together than this don't update the rate: a few ms of
+    // jitter over one second is thousands of ppm
+    pub min_rate_interval: TimeDelta,
+    // How far each new rate measurement moves the estimate, 0 to 1
+    pub rate_gain: f64,
+    // No real crystal is off by more
+    pub max_rate_ppm: f64,
+}
+
+impl Default for DriftConfig {
+    fn default() -> DriftConfig {
+        DriftConfig {
+            max_slew_ppm: 500.0,
+            step_threshold: TimeDelta::milliseconds(500),
+            max_round_trip: TimeDelta::seconds(2),
+            min_rate_interval: TimeDelta::minutes(1),
+            rate_gain: 0.25,
+            max_rate_ppm: 500.0,
+        }
+    }
+}
+
+// One request/response to the time server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exchange {
+    // Device clock when the request left
+    pub sent: DateTime<Utc>,
+    // Server clock when it answered
+    pub server: DateTime<Utc>,
+    // Device clock when the answer arrived
+    pub received: DateTime<Utc>,
+}
+
+impl Exchange {
+    pub fn round_trip(&self) -> TimeDelta {
+        self.received - self.sent
+    }
+
+    pub fn midpoint(&self) -> DateTime<Utc> {
+        self.sent + self.round_trip() / 2
+    }
+
+    // Server time minus device time, at the midpoint
+    pub fn offset(&self) -> TimeDelta {
+        self.server - self.midpoint()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Correction {
+    // Corrected time jumped by this much; it may have gone backwards
+    Stepped(TimeDelta),
+    // This much error is being worked off, finishing within `done_in`
+    Slewing { error: TimeDelta, done_in: Duration },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncError {
+    // The answer arrived before the request left: the device clock was
+    // set during the exchange
+    ReceivedBeforeSent,
+    RoundTripTooLong(TimeDelta),
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncError::ReceivedBeforeSent => {
+                write!(f, "time reply received before the request was sent")
+            }
+            SyncError::RoundTripTooLong(rtt) => write!(
+                f,
+                "round trip of {} ms is too long to trust",
+                rtt.num_milliseconds()
+            ),
+        }
+    }
+}
+
+impl Error for SyncError {}
+
+#[derive(Debug, Clone)]
+pub struct DriftCorrector {
+    config: DriftConfig,
+    // Device time the current parameters start from; `None` before the
+    // first sync, when no correction is applied
+    anchor: Option<DateTime<Utc>>,
+    base_ns: i64,
+    rate_ppm: f64,
+    // Error left to slew off, as of `anchor`
+    pending_ns: i64,
+    // The last measurement used for the rate: midpoint and offset
+    last: Option<(DateTime<Utc>, i64)>,
+}
+
+fn ns(delta: TimeDelta) -> i64 {
+    delta
+        .num_nanoseconds()
+        .unwrap_or(if delta < TimeDelta::zero() {
+            i64::MIN
+        } else {
+            i64::MAX
+        })
+}
+
+impl DriftCorrector {
+    pub fn new(config: DriftConfig) -> DriftCorrector {
+        DriftCorrector {
+            config,
+            anchor: None,
+            base_ns: 0,
+            rate_ppm: 0.0,
+            pending_ns: 0,
+            last: None,
+        }
+    }
+
+    // The estimated rate error the correction follows, in ppm; negative
+    // means the device runs fast
+    pub fn rate_ppm(&self) -> f64 {
+        self.rate_ppm
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.anchor.is_some()
+    }
+
+    fn correction_ns(&self, device: DateTime<Utc>) -> i64 {
+        let Some(anchor) = self.anchor else {
+            return 0;
+        };
+        let since = ns(device - anchor).max(0) as f64;
+        let rate = self.rate_ppm * since / 1e6;
+        let slew_max = self.config.max_slew_ppm * since / 1e6;
+        let slew =
+            (self.pending_ns.unsigned_abs() as f64).min(slew_max) * self.pending_ns.signum() as f64;
+        self.base_ns + (rate + slew) as i64
+    }
+
+    pub fn correction(&self, device: DateTime<Utc>) -> TimeDelta {
+        TimeDelta::nanoseconds(self.correction_ns(device))
+    }
+
+    // Device time to server time. Between syncs it is non-decreasing in
+    // `device`, because the correction changes by at most
+    // `max_rate_ppm + max_slew_ppm`, a thousandth of the elapsed time.
+    pub fn corrected(&self, device: DateTime<Utc>) -> DateTime<Utc> {
+        device + self.correction(device)
+    }
+
+    pub fn sync(&mut self, exchange: &Exchange) -> Result<Correction, SyncError> {
+        let rtt = exchange.round_trip();
+        if rtt < TimeDelta::zero() {
+            return Err(SyncError::ReceivedBeforeSent);
+        }
+        if rtt > self.config.max_round_trip {
+            return Err(SyncError::RoundTripTooLong(rtt));
+        }
+        let mid = exchange.midpoint();
+        let measured = ns(exchange.offset());
+        // The correction needed when the reply arrived, projected from the
+        // midpoint with the current rate
+        let since_mid = ns(exchange.received - mid) as f64;
+        let needed = measured + (self.rate_ppm * since_mid / 1e6) as i64;
+        // Continue from where the old parameters left off, so nothing
+        // already handed out by `corrected` is contradicted
+        let current = self.correction_ns(exchange.received);
+        let error = needed - current;
+
+        if self.anchor.is_none()
+            || error.unsigned_abs() > ns(self.config.step_threshold).unsigned_abs()
+        {
+            // A rate measured across a step would be meaningless
+            self.anchor = Some(exchange.received);
+            self.base_ns = needed;
+            self.pending_ns = 0;
+            self.last = Some((mid, measured));
+            return Ok(Correction::Stepped(TimeDelta::nanoseconds(error)));
+        }
+
+        if let Some((last_mid, last_measured)) = self.last {
+            let interval = mid - last_mid;
+            if interval >= self.config.min_rate_interval {
+                let rate = (measured - last_measured) as f64 / ns(interval) as f64 * 1e6;
+                let limit = self.config.max_rate_ppm;
+                self.rate_ppm = (self.rate_ppm + self.config.rate_gain * (rate - self.rate_ppm))
+                    .clamp(-limit, limit);
+                self.last = Some((mid, measured));
+            }
+        }
+
+        self.anchor = Some(exchange.received);
+        self.base_ns = current;
+        self.pending_ns = error;
+        let done_in = error.unsigned_abs() as f64 / (self.config.max_slew_ppm / 1e6);
+        Ok(Correction::Slewing {
+            error: TimeDelta::nanoseconds(error),
+            done_in: Duration::from_nanos(done_in as u64),
+        })
+    }
+}
+
+impl Default for DriftCorrector {
+    fn default() -> DriftCorrector {
+        DriftCorrector::new(DriftConfig::default())
+    }
+}
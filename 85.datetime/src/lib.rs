@@ -0,0 +1,21 @@
+// Time on a device that can't trust its own clock
+//
+// - `clock`: the monotonic clock (for intervals) and the wall clock (for
+//   timestamps) behind one `Clock` trait, with `ManualClock` for tests,
+//   which can drift and be stepped
+// - `wire`: ISO-8601 / RFC 3339 timestamps and Unix milliseconds, parsed
+//   from and written to the wire as `DateTime<Utc>`
+// - `zone`: the EU daylight-saving rule as a chrono `TimeZone`, for
+//   showing times in a site's local time
+// - `drift`: `DriftCorrector`, which steers a drifting RTC toward server
+//   time without ever running it backwards
+
+pub mod clock;
+pub mod drift;
+pub mod wire;
+pub mod zone;
+
+pub use clock::{Clock, ManualClock, Stamp, SystemClock};
+pub use drift::{Correction, DriftConfig, DriftCorrector, Exchange, SyncError};
+pub use wire::{format_wire, from_unix_ms, parse_wire, TimestampError};
+pub use zone::{EuOffset, EuZone};
@@ -0,0 +1,193 @@
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use datetime::{
+    format_wire, from_unix_ms, parse_wire, Clock, Correction, DriftCorrector, EuZone, Exchange,
+    ManualClock, Stamp,
+};
+use std::time::Duration;
+
+const HOURS: u64 = 6;
+const SYNC_EVERY: Duration = Duration::from_secs(600);
+const DRIFT_PPM: f64 = 120.0;
+
+// xorshift32 mapped to network legs of 5-60 ms, so every run is the same
+fn random(seed: u32, n: usize) -> Vec<Duration> {
+    let mut x = seed.max(1);
+    (0..n)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            Duration::from_millis(5 + (x % 56) as u64)
+        })
+        .collect()
+}
+
+fn ms(delta: TimeDelta) -> f64 {
+    delta.num_microseconds().unwrap_or(i64::MAX) as f64 / 1000.0
+}
+
+fn main() {
+    println!("=== Date and Time Examples ===\n");
+
+    // 1. Timestamps
+    println!("1. Timestamps: one type inside, Unix ms at the edges:");
+    let ms_from_device = 1_711_846_799_250;
+    let t = from_unix_ms(ms_from_device).expect("in range");
+    println!("   {} ms -> {} -> {}", ms_from_device, t, format_wire(t));
+    let later = t + TimeDelta::milliseconds(750);
+    println!(
+        "   + 750 ms = {}, difference {} ms",
+        format_wire(later),
+        (later - t).num_milliseconds()
+    );
+
+    // 2. Parsing from the wire
+    println!("\n2. Parsing ISO-8601 from the wire:");
+    let inputs = [
+        "2024-03-31T00:59:59.250Z",
+        "2024-03-31T01:59:59.25+01:00",
+        "20240331T005959Z",
+        "1711846799",
+        "1711846799250",
+        "2024-03-31T02:30:00",
+        "31/03/2024 00:59",
+        "",
+    ];
+    for input in inputs {
+        match parse_wire(input) {
+            Ok(t) => println!("   {:<32} {}", format!("{:?}", input), format_wire(t)),
+            Err(e) => println!("   {:<32} error: {}", format!("{:?}", input), e),
+        }
+    }
+
+    // 3. Time zones
+    println!("\n3. A site's local time across the summer-time switch:");
+    let zone = EuZone::CENTRAL;
+    for utc in [t, t + TimeDelta::seconds(1)] {
+        println!(
+            "   {}  =  {}",
+            format_wire(utc),
+            zone.at(utc).format("%Y-%m-%d %H:%M:%S %Z (%:z)")
+        );
+    }
+    let gap = zone.with_ymd_and_hms(2024, 3, 31, 2, 30, 0);
+    let fold = zone.with_ymd_and_hms(2024, 10, 27, 2, 30, 0);
+    println!(
+        "   local 2024-03-31 02:30: {:?}",
+        gap.map(|d| d.to_rfc3339())
+    );
+    println!(
+        "   local 2024-10-27 02:30: {:?}",
+        fold.map(|d| d.to_rfc3339())
+    );
+    let fixed = chrono::FixedOffset::east_opt(5 * 3600 + 1800).expect("+05:30");
+    println!(
+        "   the same instant elsewhere: {}, {}",
+        t.with_timezone(&fixed).format("%H:%M:%S %:z"),
+        EuZone::WESTERN.at(t).format("%H:%M:%S %Z")
+    );
+
+    // 4. Monotonic vs wall
+    println!("\n4. Monotonic vs wall clock in telemetry:");
+    let clock = ManualClock::new(t);
+    let first = Stamp::now(&clock);
+    clock.advance(Duration::from_secs(10));
+    // Somewhere in those 10 s, NTP set the wall clock back 12 s
+    clock.step_wall(TimeDelta::seconds(-12));
+    let second = Stamp::now(&clock);
+    let readings = 500.0;
+    println!(
+        "   10 s apart, wall clock set back 12 s between: monotonic {:?}, wall {} s",
+        second.since(&first),
+        second.wall_since(&first).num_seconds()
+    );
+    println!(
+        "   {} readings: {:.1}/s from the monotonic clock, {:.1}/s from the wall clock",
+        readings,
+        readings / second.since(&first).as_secs_f64(),
+        readings / second.wall_since(&first).as_seconds_f64()
+    );
+
+    // 5. Drift correction
+    println!(
+        "\n5. Drift correction: an RTC {} ppm fast, 1.5 s ahead, synced every {} min:",
+        DRIFT_PPM,
+        SYNC_EVERY.as_secs() / 60
+    );
+    let start: DateTime<Utc> = Utc.with_ymd_and_hms(2024, 3, 30, 22, 0, 0).unwrap();
+    let server = ManualClock::new(start);
+    let device = ManualClock::new(start + TimeDelta::milliseconds(1500)).with_drift_ppm(DRIFT_PPM);
+    let advance = |d: Duration| {
+        server.advance(d);
+        device.advance(d);
+    };
+    let mut corrector = DriftCorrector::default();
+    let per_hour = (3600 / SYNC_EVERY.as_secs()) as usize;
+    let syncs = HOURS as usize * per_hour;
+    let legs = random(17, syncs * 2);
+    let mut last_corrected = corrector.corrected(device.utc());
+    let mut backwards = 0;
+    let mut steps = 0;
+    let mut worst_late = TimeDelta::zero();
+    println!("   hour   raw error   corrected   rate estimate");
+    for sync in 0..syncs {
+        let sent = device.utc();
+        advance(legs[sync * 2]);
+        let server_time = server.utc();
+        advance(legs[sync * 2 + 1]);
+        let exchange = Exchange {
+            sent,
+            server: server_time,
+            received: device.utc(),
+        };
+        // A step may go backwards on purpose; a slew never does
+        if let Ok(Correction::Stepped(_)) = corrector.sync(&exchange) {
+            steps += 1;
+            last_corrected = corrector.corrected(device.utc());
+        }
+        // Check every ten seconds until the next sync
+        for tick in 0..SYNC_EVERY.as_secs() / 10 {
+            advance(Duration::from_secs(10));
+            let corrected = corrector.corrected(device.utc());
+            if corrected < last_corrected {
+                backwards += 1;
+            }
+            last_corrected = corrected;
+            let error = corrected - server.utc();
+            if sync >= syncs / 2 {
+                worst_late = worst_late.max(error.abs());
+            }
+            if tick + 1 == SYNC_EVERY.as_secs() / 10 && (sync + 1) % per_hour == 0 {
+                println!(
+                    "   {:>4} {:>10.1} ms {:>8.2} ms {:>10.1} ppm",
+                    (sync + 1) / per_hour,
+                    ms(device.utc() - server.utc()),
+                    ms(error),
+                    corrector.rate_ppm()
+                );
+            }
+        }
+    }
+    let raw = device.utc() - server.utc();
+    // Each measurement can be off by half its round trip, and nothing in
+    // one exchange shows by how much
+    let worst_rtt = legs
+        .chunks(2)
+        .map(|l| l[0] + l[1])
+        .max()
+        .unwrap_or_default();
+    println!(
+        "   from hour 3: worst corrected error {:.1} ms, half the worst round trip {:.1} ms",
+        ms(worst_late),
+        worst_rtt.as_secs_f64() * 500.0
+    );
+    println!(
+        "   {} step(s), {} backward reading(s), {:.1} ms uncorrected after {} h",
+        steps,
+        backwards,
+        ms(raw),
+        HOURS
+    );
+
+    println!("\n=== End of Date and Time Examples ===");
+}
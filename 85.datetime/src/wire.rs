@@ -0,0 +1,82 @@
+// Timestamps on the wire
+//
+// Everything inside the gateway is a `DateTime<Utc>`: one type, no
+// offset to forget, ordered and subtractable. Conversion happens at the
+// edges. Outgoing timestamps are RFC 3339 in UTC with milliseconds
+// (`2024-03-31T00:59:59.250Z`), which sorts as text and parses anywhere.
+//
+// Incoming ones come from firmware of every age, so `parse_wire` accepts
+// - RFC 3339 with any offset: `2024-03-31T02:59:59+02:00`, fractions too
+// - the ISO-8601 basic form: `20240331T005959Z`
+// - Unix time as digits, in seconds or (from 13 digits on) milliseconds
+// and refuses a date and time with no offset. `2024-03-31T02:30:00`
+// could be UTC, the device's zone or the installer's, and on that night
+// in Central Europe it doesn't exist at all. Guessing turns into data
+// an hour off that nobody notices.
+
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimestampError {
+    Empty,
+    // A date and time without `Z` or `+hh:mm`
+    NoOffset(String),
+    // Digits that overflow a `DateTime`
+    OutOfRange(String),
+    Invalid(String),
+}
+
+impl fmt::Display for TimestampError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimestampError::Empty => write!(f, "empty timestamp"),
+            TimestampError::NoOffset(s) => {
+                write!(f, "timestamp '{}' has no offset; add Z or +hh:mm", s)
+            }
+            TimestampError::OutOfRange(s) => write!(f, "timestamp '{}' is out of range", s),
+            TimestampError::Invalid(s) => write!(f, "'{}' is not an ISO-8601 timestamp", s),
+        }
+    }
+}
+
+impl Error for TimestampError {}
+
+pub fn parse_wire(input: &str) -> Result<DateTime<Utc>, TimestampError> {
+    let s = input.trim();
+    if s.is_empty() {
+        return Err(TimestampError::Empty);
+    }
+    if s.bytes().all(|b| b.is_ascii_digit()) {
+        let out_of_range = || TimestampError::OutOfRange(s.to_string());
+        let n: i64 = s.parse().map_err(|_| out_of_range())?;
+        // 13 digits is 2001 in milliseconds and the year 33658 in seconds
+        let parsed = if s.len() >= 13 {
+            DateTime::from_timestamp_millis(n)
+        } else {
+            DateTime::from_timestamp(n, 0)
+        };
+        return parsed.ok_or_else(out_of_range);
+    }
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    if let Ok(t) = NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%SZ") {
+        return Ok(t.and_utc());
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"] {
+        if NaiveDateTime::parse_from_str(s, format).is_ok() {
+            return Err(TimestampError::NoOffset(s.to_string()));
+        }
+    }
+    Err(TimestampError::Invalid(s.to_string()))
+}
+
+pub fn format_wire(t: DateTime<Utc>) -> String {
+    t.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+pub fn from_unix_ms(ms: i64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(ms)
+}
@@ -0,0 +1,153 @@
+// A site's local time: the EU daylight-saving rule as a chrono `TimeZone`
+//
+// `FixedOffset` is enough for timestamps that carry their offset. Showing
+// "what time is it at the site" needs the rule: since 1996 the EU
+// switches to summer time at 01:00 UTC on the last Sunday of March, and
+// back at 01:00 UTC on the last Sunday of October, in every zone at
+// once. (A full tz database, via the chrono-tz crate, covers every
+// country and every historical change; this is the same interface with
+// one rule.)
+//
+// Going from UTC to local time always has one answer. Going from a local
+// time to UTC may not:
+//
+//   last Sunday of March, Central Europe:   02:00 -> 03:00   a gap,
+//     02:30 never happens                                     `None`
+//   last Sunday of October:                 03:00 -> 02:00   a fold,
+//     02:30 happens twice, at 00:30 and 01:30 UTC             `Ambiguous`
+//
+// chrono returns a `MappedLocalTime` so the caller has to choose what a
+// cron job at 02:30 should do on those two nights.
+
+use chrono::{
+    DateTime, Datelike, Days, FixedOffset, MappedLocalTime, NaiveDate, NaiveDateTime, NaiveTime,
+    Offset, TimeZone, Utc,
+};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EuZone {
+    standard: &'static str,
+    summer: &'static str,
+    // Standard time east of UTC, in hours
+    offset_hours: i32,
+}
+
+impl EuZone {
+    pub const WESTERN: EuZone = EuZone {
+        standard: "WET",
+        summer: "WEST",
+        offset_hours: 0,
+    };
+    pub const CENTRAL: EuZone = EuZone {
+        standard: "CET",
+        summer: "CEST",
+        offset_hours: 1,
+    };
+    pub const EASTERN: EuZone = EuZone {
+        standard: "EET",
+        summer: "EEST",
+        offset_hours: 2,
+    };
+
+    // The local time at the site
+    pub fn at(&self, utc: DateTime<Utc>) -> DateTime<EuZone> {
+        utc.with_timezone(self)
+    }
+
+    fn offset(&self, summer: bool) -> EuOffset {
+        EuOffset {
+            zone: *self,
+            summer,
+        }
+    }
+
+    // Summer time runs from the first switch (inclusive) to the second,
+    // both at 01:00 UTC
+    pub fn is_summer(&self, utc: &NaiveDateTime) -> bool {
+        let year = utc.year();
+        let switch = |month| {
+            last_sunday(year, month)
+                .and_time(NaiveTime::from_hms_opt(1, 0, 0).expect("01:00 is valid"))
+        };
+        *utc >= switch(3) && *utc < switch(10)
+    }
+}
+
+fn last_sunday(year: i32, month: u32) -> NaiveDate {
+    let first_of_next = NaiveDate::from_ymd_opt(year, month + 1, 1).expect("month is 3 or 10");
+    let last = first_of_next - Days::new(1);
+    last - Days::new(last.weekday().num_days_from_sunday() as u64)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EuOffset {
+    zone: EuZone,
+    summer: bool,
+}
+
+impl EuOffset {
+    pub fn is_summer(&self) -> bool {
+        self.summer
+    }
+}
+
+impl Offset for EuOffset {
+    fn fix(&self) -> FixedOffset {
+        let hours = self.zone.offset_hours + self.summer as i32;
+        FixedOffset::east_opt(hours * 3600).expect("offset within a day")
+    }
+}
+
+// `%Z` in a format string prints this
+impl fmt::Display for EuOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = if self.summer {
+            self.zone.summer
+        } else {
+            self.zone.standard
+        };
+        f.write_str(name)
+    }
+}
+
+impl TimeZone for EuZone {
+    type Offset = EuOffset;
+
+    fn from_offset(offset: &EuOffset) -> EuZone {
+        offset.zone
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> EuOffset {
+        self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> EuOffset {
+        self.offset(self.is_summer(utc))
+    }
+
+    // A whole date has a single offset except on switch days; noon is
+    // past either switch in every EU zone
+    fn offset_from_local_date(&self, local: &NaiveDate) -> MappedLocalTime<EuOffset> {
+        self.offset_from_local_datetime(
+            &local.and_time(NaiveTime::from_hms_opt(12, 0, 0).expect("noon")),
+        )
+    }
+
+    // Try both offsets; each is right if converting back to UTC lands in
+    // a period where it applies
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> MappedLocalTime<EuOffset> {
+        let fits = |offset: EuOffset| {
+            let utc = *local - offset.fix();
+            self.offset_from_utc_datetime(&utc) == offset
+        };
+        let (summer, standard) = (self.offset(true), self.offset(false));
+        match (fits(summer), fits(standard)) {
+            // Summer time is the earlier of the two instants
+            (true, true) => MappedLocalTime::Ambiguous(summer, standard),
+            (true, false) => MappedLocalTime::Single(summer),
+            (false, true) => MappedLocalTime::Single(standard),
+            (false, false) => MappedLocalTime::None,
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use chrono::{TimeDelta, TimeZone, Utc};
+use datetime::{Clock, ManualClock, Stamp};
+use retry::Clock as _;
+use std::time::Duration;
+
+#[test]
+fn a_wall_step_does_not_touch_the_monotonic_interval() {
+    let clock = ManualClock::new(Utc.with_ymd_and_hms(2024, 3, 31, 0, 0, 0).unwrap());
+    let first = Stamp::now(&clock);
+    clock.advance(Duration::from_secs(10));
+    // NTP set the wall clock back 12 s in between
+    clock.step_wall(TimeDelta::seconds(-12));
+    let second = Stamp::now(&clock);
+    assert_eq!(second.since(&first), Duration::from_secs(10));
+    assert_eq!(second.wall_since(&first), TimeDelta::seconds(-2));
+    assert_eq!(second.mono, clock.now());
+    // Going the other way saturates instead of panicking
+    assert_eq!(first.since(&second), Duration::ZERO);
+}
+
+#[test]
+fn a_drifting_clock_gains_at_its_rate() {
+    let start = Utc.with_ymd_and_hms(2024, 3, 31, 0, 0, 0).unwrap();
+    let clock = ManualClock::new(start).with_drift_ppm(120.0);
+    clock.advance(Duration::from_secs(86_400));
+    // 120 ppm of a day is 10.368 s, give or take float rounding
+    let gained = clock.utc() - start - TimeDelta::seconds(86_400);
+    assert!(
+        (gained - TimeDelta::milliseconds(10_368)).abs() < TimeDelta::microseconds(1),
+        "{:?}",
+        gained
+    );
+    assert_eq!(clock.elapsed(), Duration::from_secs(86_400));
+}
+
+#[test]
+fn sleep_advances_a_manual_clock() {
+    let clock = ManualClock::new(Utc.with_ymd_and_hms(2024, 3, 31, 0, 0, 0).unwrap());
+    let before = clock.now();
+    clock.sleep(Duration::from_millis(1500));
+    assert_eq!(clock.now() - before, Duration::from_millis(1500));
+}
@@ -0,0 +1,208 @@
+// `DriftCorrector` against simulated clocks. The server is a
+// `ManualClock` that keeps true time; the device is one that starts off
+// by some offset and drifts. Both move only when `World::advance` says,
+// so hours of drift run in microseconds and every run is the same.
+
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use datetime::{Clock, Correction, DriftConfig, DriftCorrector, Exchange, ManualClock, SyncError};
+use std::time::Duration;
+
+struct World {
+    server: ManualClock,
+    device: ManualClock,
+}
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 30, 22, 0, 0).unwrap()
+}
+
+impl World {
+    fn new(offset: TimeDelta, drift_ppm: f64) -> World {
+        World {
+            server: ManualClock::new(start()),
+            device: ManualClock::new(start() + offset).with_drift_ppm(drift_ppm),
+        }
+    }
+
+    fn advance(&self, duration: Duration) {
+        self.server.advance(duration);
+        self.device.advance(duration);
+    }
+
+    // A request taking `up` to reach the server and `down` to come back
+    fn exchange(&self, up: Duration, down: Duration) -> Exchange {
+        let sent = self.device.utc();
+        self.advance(up);
+        let server = self.server.utc();
+        self.advance(down);
+        Exchange {
+            sent,
+            server,
+            received: self.device.utc(),
+        }
+    }
+
+    // Corrected device time minus true time
+    fn error(&self, corrector: &DriftCorrector) -> TimeDelta {
+        corrector.corrected(self.device.utc()) - self.server.utc()
+    }
+}
+
+const LEG: Duration = Duration::from_millis(20);
+
+#[test]
+fn the_first_sync_steps_to_server_time() {
+    let world = World::new(TimeDelta::seconds(-3), 0.0);
+    let mut corrector = DriftCorrector::default();
+    assert!(!corrector.is_synced());
+    let outcome = corrector.sync(&world.exchange(LEG, LEG)).unwrap();
+    assert!(matches!(outcome, Correction::Stepped(d) if d == TimeDelta::seconds(3)));
+    assert_eq!(world.error(&corrector), TimeDelta::zero());
+}
+
+#[test]
+fn a_small_error_is_slewed_without_going_backwards() {
+    let world = World::new(TimeDelta::zero(), 0.0);
+    let mut corrector = DriftCorrector::default();
+    corrector.sync(&world.exchange(LEG, LEG)).unwrap();
+    // The device clock is set 200 ms ahead behind the corrector's back
+    world.device.step_wall(TimeDelta::milliseconds(200));
+    let outcome = corrector.sync(&world.exchange(LEG, LEG)).unwrap();
+    let Correction::Slewing { error, done_in } = outcome else {
+        panic!("expected a slew, got {:?}", outcome);
+    };
+    assert_eq!(error, TimeDelta::milliseconds(-200));
+    // 200 ms at 500 ppm
+    assert_eq!(done_in, Duration::from_secs(400));
+
+    let mut previous = corrector.corrected(world.device.utc());
+    for _ in 0..500 {
+        world.advance(Duration::from_secs(1));
+        let now = corrector.corrected(world.device.utc());
+        assert!(now > previous, "corrected time went backwards");
+        assert!(now - previous <= TimeDelta::milliseconds(1000));
+        previous = now;
+    }
+    assert!(world.error(&corrector).abs() < TimeDelta::milliseconds(1));
+}
+
+#[test]
+fn a_large_error_is_stepped() {
+    let world = World::new(TimeDelta::zero(), 0.0);
+    let mut corrector = DriftCorrector::default();
+    corrector.sync(&world.exchange(LEG, LEG)).unwrap();
+    // The RTC lost power and restarted an hour behind
+    world.device.step_wall(TimeDelta::hours(-1));
+    world.advance(Duration::from_secs(60));
+    let outcome = corrector.sync(&world.exchange(LEG, LEG)).unwrap();
+    assert!(matches!(outcome, Correction::Stepped(d) if d == TimeDelta::hours(1)));
+    assert_eq!(world.error(&corrector), TimeDelta::zero());
+}
+
+#[test]
+fn the_rate_is_learned_and_followed_between_syncs() {
+    // 120 ppm fast is 10 seconds a day
+    let world = World::new(TimeDelta::milliseconds(-2500), 120.0);
+    let mut corrector = DriftCorrector::default();
+    let mut worst_after_learning = TimeDelta::zero();
+    for sync in 0..24 {
+        corrector.sync(&world.exchange(LEG, LEG)).unwrap();
+        for _ in 0..10 {
+            world.advance(Duration::from_secs(60));
+            if sync >= 12 {
+                worst_after_learning = worst_after_learning.max(world.error(&corrector).abs());
+            }
+        }
+    }
+    assert!(
+        (corrector.rate_ppm() + 120.0).abs() < 1.0,
+        "rate {} ppm",
+        corrector.rate_ppm()
+    );
+    // Uncorrected, 10 minutes at 120 ppm is 72 ms
+    assert!(
+        worst_after_learning < TimeDelta::milliseconds(5),
+        "worst {:?}",
+        worst_after_learning
+    );
+}
+
+#[test]
+fn asymmetric_legs_bound_the_error_by_half_the_round_trip() {
+    let world = World::new(TimeDelta::seconds(5), 0.0);
+    let mut corrector = DriftCorrector::default();
+    corrector
+        .sync(&world.exchange(Duration::from_millis(10), Duration::from_millis(190)))
+        .unwrap();
+    let error = world.error(&corrector).abs();
+    assert!(error > TimeDelta::zero() && error <= TimeDelta::milliseconds(100));
+}
+
+#[test]
+fn untrustworthy_exchanges_are_refused() {
+    let world = World::new(TimeDelta::zero(), 0.0);
+    let mut corrector = DriftCorrector::new(DriftConfig {
+        max_round_trip: TimeDelta::seconds(1),
+        ..DriftConfig::default()
+    });
+    let slow = world.exchange(Duration::from_millis(800), Duration::from_millis(800));
+    assert_eq!(
+        corrector.sync(&slow),
+        Err(SyncError::RoundTripTooLong(TimeDelta::milliseconds(1600)))
+    );
+    let mut backwards = world.exchange(LEG, LEG);
+    backwards.received = backwards.sent - TimeDelta::seconds(1);
+    assert_eq!(
+        corrector.sync(&backwards),
+        Err(SyncError::ReceivedBeforeSent)
+    );
+    assert!(!corrector.is_synced());
+}
+
+// The demo's six hours: 1.5 s ahead, 120 ppm fast, a sync every ten
+// minutes over legs of 5-60 ms, the corrected time read every ten seconds
+#[test]
+fn six_hours_of_jittery_syncs() {
+    let world = World::new(TimeDelta::milliseconds(1500), 120.0);
+    let mut corrector = DriftCorrector::default();
+    let mut x: u32 = 17;
+    let mut leg = || {
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        Duration::from_millis(5 + (x % 56) as u64)
+    };
+    let mut last = corrector.corrected(world.device.utc());
+    let (mut steps, mut worst_late, mut worst_rtt) = (0, TimeDelta::zero(), Duration::ZERO);
+    for sync in 0..36 {
+        let (up, down) = (leg(), leg());
+        worst_rtt = worst_rtt.max(up + down);
+        if let Ok(Correction::Stepped(_)) = corrector.sync(&world.exchange(up, down)) {
+            steps += 1;
+            last = corrector.corrected(world.device.utc());
+        }
+        for _ in 0..60 {
+            world.advance(Duration::from_secs(10));
+            let corrected = corrector.corrected(world.device.utc());
+            assert!(corrected >= last, "sync {} went backwards", sync);
+            last = corrected;
+            if sync >= 18 {
+                worst_late = worst_late.max(world.error(&corrector).abs());
+            }
+        }
+    }
+    assert_eq!(steps, 1);
+    assert!(
+        (corrector.rate_ppm() + 120.0).abs() < 10.0,
+        "rate {} ppm",
+        corrector.rate_ppm()
+    );
+    assert!(
+        worst_late.to_std().unwrap() < worst_rtt / 2,
+        "{:?}",
+        worst_late
+    );
+    // Left alone the device would be more than 2 s further out
+    let raw = world.device.utc() - world.server.utc();
+    assert!(raw - TimeDelta::milliseconds(1500) > TimeDelta::seconds(2));
+}
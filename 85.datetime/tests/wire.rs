@@ -0,0 +1,93 @@
+use chrono::{TimeDelta, TimeZone, Utc};
+use datetime::{format_wire, from_unix_ms, parse_wire, TimestampError};
+
+const MS: i64 = 1_711_846_799_250;
+
+#[test]
+fn unix_ms_round_trips_and_formats_in_utc() {
+    let t = from_unix_ms(MS).unwrap();
+    assert_eq!(t.timestamp_millis(), MS);
+    assert_eq!(format_wire(t), "2024-03-31T00:59:59.250Z");
+    assert_eq!(parse_wire(&format_wire(t)), Ok(t));
+    assert_eq!(from_unix_ms(i64::MAX), None);
+}
+
+#[test]
+fn the_wire_format_sorts_as_text() {
+    let t = from_unix_ms(MS).unwrap();
+    let mut times: Vec<_> = [0, 750, 1, 3_600_000, 86_400_000 * 400]
+        .iter()
+        .map(|&ms| t + TimeDelta::milliseconds(ms))
+        .collect();
+    let mut texts: Vec<String> = times.iter().map(|&t| format_wire(t)).collect();
+    times.sort();
+    texts.sort();
+    assert_eq!(
+        texts,
+        times.iter().map(|&t| format_wire(t)).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn every_offset_form_gives_the_same_instant() {
+    let t = from_unix_ms(MS).unwrap();
+    let whole = t - TimeDelta::milliseconds(250);
+    for input in [
+        "2024-03-31T00:59:59.250Z",
+        "2024-03-31T01:59:59.25+01:00",
+        "2024-03-30T19:59:59.250-05:00",
+        "1711846799250",
+        "  1711846799250\n",
+    ] {
+        assert_eq!(parse_wire(input), Ok(t), "{:?}", input);
+    }
+    for input in [
+        "20240331T005959Z",
+        "1711846799",
+        "2024-03-31T02:59:59+02:00",
+    ] {
+        assert_eq!(parse_wire(input), Ok(whole), "{:?}", input);
+    }
+    assert_eq!(
+        parse_wire("0"),
+        Ok(Utc.with_ymd_and_hms(1970, 1, 1, 0, 0, 0).unwrap())
+    );
+}
+
+#[test]
+fn no_offset_is_an_error_not_a_guess() {
+    assert_eq!(
+        parse_wire("2024-03-31T02:30:00"),
+        Err(TimestampError::NoOffset("2024-03-31T02:30:00".to_string()))
+    );
+    assert_eq!(
+        parse_wire("2024-03-31 02:30:00.5"),
+        Err(TimestampError::NoOffset(
+            "2024-03-31 02:30:00.5".to_string()
+        ))
+    );
+    assert_eq!(parse_wire(" "), Err(TimestampError::Empty));
+    assert_eq!(
+        parse_wire("31/03/2024 00:59"),
+        Err(TimestampError::Invalid("31/03/2024 00:59".to_string()))
+    );
+    assert_eq!(
+        parse_wire("99999999999999999999"),
+        Err(TimestampError::OutOfRange(
+            "99999999999999999999".to_string()
+        ))
+    );
+}
+
+#[test]
+fn errors_say_what_to_fix() {
+    assert_eq!(
+        TimestampError::NoOffset("2024-03-31T02:30:00".to_string()).to_string(),
+        "timestamp '2024-03-31T02:30:00' has no offset; add Z or +hh:mm"
+    );
+    assert_eq!(TimestampError::Empty.to_string(), "empty timestamp");
+    assert_eq!(
+        TimestampError::Invalid("x".to_string()).to_string(),
+        "'x' is not an ISO-8601 timestamp"
+    );
+}
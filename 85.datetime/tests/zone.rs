@@ -0,0 +1,79 @@
+use chrono::{MappedLocalTime, NaiveDate, TimeDelta, TimeZone, Utc};
+use datetime::EuZone;
+
+#[test]
+fn summer_time_starts_at_one_utc_on_the_last_sunday_of_march() {
+    let zone = EuZone::CENTRAL;
+    let switch = Utc.with_ymd_and_hms(2024, 3, 31, 1, 0, 0).unwrap();
+    let before = zone.at(switch - TimeDelta::seconds(1));
+    let after = zone.at(switch);
+    assert!(!before.offset().is_summer());
+    assert!(after.offset().is_summer());
+    assert_eq!(
+        before.format("%H:%M:%S %Z %:z").to_string(),
+        "01:59:59 CET +01:00"
+    );
+    assert_eq!(
+        after.format("%H:%M:%S %Z %:z").to_string(),
+        "03:00:00 CEST +02:00"
+    );
+}
+
+#[test]
+fn every_zone_switches_at_the_same_instant() {
+    let switch = Utc.with_ymd_and_hms(2024, 10, 27, 1, 0, 0).unwrap();
+    for (zone, summer, winter) in [
+        (EuZone::WESTERN, "01:59:59 WEST", "01:00:00 WET"),
+        (EuZone::CENTRAL, "02:59:59 CEST", "02:00:00 CET"),
+        (EuZone::EASTERN, "03:59:59 EEST", "03:00:00 EET"),
+    ] {
+        let last = zone.at(switch - TimeDelta::seconds(1));
+        assert_eq!(last.format("%H:%M:%S %Z").to_string(), summer);
+        assert_eq!(zone.at(switch).format("%H:%M:%S %Z").to_string(), winter);
+    }
+}
+
+#[test]
+fn the_last_sunday_moves_each_year() {
+    let zone = EuZone::CENTRAL;
+    for (year, march, october) in [(2024, 31, 27), (2025, 30, 26), (2026, 29, 25)] {
+        let at = |month, day, hour| {
+            NaiveDate::from_ymd_opt(year, month, day)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+        };
+        assert!(!zone.is_summer(&at(3, march, 0)), "{}", year);
+        assert!(zone.is_summer(&at(3, march, 1)), "{}", year);
+        assert!(zone.is_summer(&at(10, october, 0)), "{}", year);
+        assert!(!zone.is_summer(&at(10, october, 1)), "{}", year);
+    }
+}
+
+#[test]
+fn local_times_in_the_gap_and_the_fold() {
+    let zone = EuZone::CENTRAL;
+    assert_eq!(
+        zone.with_ymd_and_hms(2024, 3, 31, 2, 30, 0),
+        MappedLocalTime::None
+    );
+    match zone.with_ymd_and_hms(2024, 10, 27, 2, 30, 0) {
+        MappedLocalTime::Ambiguous(a, b) => {
+            assert_eq!(b - a, TimeDelta::hours(1));
+            assert_eq!(
+                a.with_timezone(&Utc),
+                Utc.with_ymd_and_hms(2024, 10, 27, 0, 30, 0).unwrap()
+            );
+            assert!(a.offset().is_summer() && !b.offset().is_summer());
+        }
+        other => panic!("{:?}", other),
+    }
+    let noon = zone
+        .with_ymd_and_hms(2024, 7, 1, 12, 0, 0)
+        .single()
+        .unwrap();
+    assert_eq!(
+        noon.with_timezone(&Utc),
+        Utc.with_ymd_and_hms(2024, 7, 1, 10, 0, 0).unwrap()
+    );
+}
@@ -0,0 +1,84 @@
+// Dimensions as marker types
+//
+// Each dimension is an enum with no variants: it can never be
+// constructed, only named in a type, so it takes no space and does no
+// work at run time. Which dimensions combine, and into what, is written
+// out as trait impls:
+//
+//   Linear         can be added and subtracted (everything but an
+//                  absolute temperature)
+//   Per<Rhs>       `Self / Rhs` is `Output`
+//   Times<Rhs>     `Self * Rhs` is `Output`
+//
+// Rust can't yet compute with const generic exponents on stable (that
+// needs `generic_const_exprs`), which is what a general `Quantity<L, M,
+// T, ...>` would use. Listing the combinations a program needs is more
+// typing, but every one is visible and each error names the missing
+// impl.
+
+use std::fmt::Debug;
+
+pub trait Dimension: Copy + Debug + PartialEq + PartialOrd {
+    // SI symbol for `Display`, in base units
+    const SYMBOL: &'static str;
+}
+
+pub trait Linear: Dimension {}
+
+pub trait Per<Rhs: Dimension>: Dimension {
+    type Output: Dimension;
+}
+
+pub trait Times<Rhs: Dimension>: Dimension {
+    type Output: Dimension;
+}
+
+macro_rules! dimension {
+    ($name:ident, $symbol:expr) => {
+        #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+        pub enum $name {}
+
+        impl Dimension for $name {
+            const SYMBOL: &'static str = $symbol;
+        }
+    };
+}
+
+dimension!(Ratio, "");
+dimension!(Length, "m");
+dimension!(Time, "s");
+dimension!(Velocity, "m/s");
+dimension!(Temperature, "K");
+dimension!(TemperatureDelta, "K");
+
+impl Linear for Ratio {}
+impl Linear for Length {}
+impl Linear for Time {}
+impl Linear for Velocity {}
+impl Linear for TemperatureDelta {}
+
+// Anything over itself is a plain number, and a plain number scales
+// anything without changing it
+impl<D: Dimension> Per<D> for D {
+    type Output = Ratio;
+}
+
+impl<D: Linear> Times<Ratio> for D {
+    type Output = D;
+}
+
+impl Per<Time> for Length {
+    type Output = Velocity;
+}
+
+impl Per<Velocity> for Length {
+    type Output = Time;
+}
+
+impl Times<Time> for Velocity {
+    type Output = Length;
+}
+
+impl Times<Velocity> for Time {
+    type Output = Length;
+}
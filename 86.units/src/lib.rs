@@ -0,0 +1,25 @@
+// Units of measure checked by the compiler
+//
+// A `Quantity<D>` is an `f64` in SI base units tagged with a dimension
+// `D`, a marker type that exists only at compile time. The operators are
+// implemented only where the physics allows them, so
+//
+//   Length / Time          -> Velocity          compiles
+//   Velocity * Time        -> Length            compiles
+//   Length + Time                               does not compile
+//   Temperature + Temperature                   does not compile
+//   Temperature - Temperature -> TemperatureDelta
+//
+// - `dim`: the marker types and the traits saying which combine how
+// - `quantity`: `Quantity<D>` and its operators
+// - `units`: the named types (`Length`, `Time`, ...) with constructors
+//   and conversions (`Length::kilometers`, `Temperature::fahrenheit`)
+//
+// A `Quantity` is exactly an `f64` at run time; the checks cost nothing.
+
+pub mod dim;
+pub mod quantity;
+pub mod units;
+
+pub use quantity::Quantity;
+pub use units::{Length, Ratio, Temperature, TemperatureDelta, Time, Velocity};
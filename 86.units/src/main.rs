@@ -0,0 +1,138 @@
+use std::hint::black_box;
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+use units::{Length, Ratio, Temperature, TemperatureDelta, Time, Velocity};
+
+const SAMPLES: usize = 1_000_000;
+
+// xorshift32 mapped to [0, 1), so every run is the same
+fn random(seed: u32, n: usize) -> Vec<f64> {
+    let mut x = seed.max(1);
+    (0..n)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x as f64 / u32::MAX as f64
+        })
+        .collect()
+}
+
+// Wind speed from an anemometer: each turn moves the cups one
+// circumference, so speed is distance over time. `factor` corrects for
+// the cups moving slower than the wind.
+fn wind_speed(cup_radius: Length, turns: f64, interval: Time, factor: f64) -> Velocity {
+    let circumference = cup_radius * (2.0 * std::f64::consts::PI);
+    circumference * turns * factor / interval
+}
+
+// The same with bare numbers: which unit is each argument in?
+fn wind_speed_f64(cup_radius: f64, turns: f64, interval: f64, factor: f64) -> f64 {
+    2.0 * std::f64::consts::PI * cup_radius * turns * factor / interval
+}
+
+fn main() {
+    println!("=== Units of Measure Examples ===\n");
+
+    // 1. Constructing and converting
+    println!("1. Every value says its unit once, when it is made:");
+    let run = Length::kilometers(10.0);
+    let time = Time::minutes(42.0) + Time::seconds(30.0);
+    let pace = run / time;
+    println!(
+        "   {} in {} = {:.3} = {:.2} km/h",
+        run,
+        time,
+        pace,
+        pace.as_kilometers_per_hour()
+    );
+    let laps: Ratio = run / Length::meters(400.0);
+    println!("   {} is {} laps of 400 m", run, laps.value());
+    let poll: Time = Duration::from_millis(250).into();
+    println!("   Duration 250 ms -> {}", poll);
+
+    // 2. A calculation with units in its signature
+    println!("\n2. Wind speed from anemometer turns:");
+    let radius = Length::millimeters(70.0);
+    let speed = wind_speed(radius, 45.0, Time::seconds(10.0), 2.5);
+    println!(
+        "   70 mm cups, 45 turns in 10 s: {:.2} m/s, {:.1} km/h",
+        speed.as_meters_per_second(),
+        speed.as_kilometers_per_hour()
+    );
+    // The bare version accepts millimetres where it meant metres
+    let wrong = wind_speed_f64(70.0, 45.0, 10.0, 2.5);
+    println!(
+        "   wind_speed_f64(70.0, ...) with the radius in mm: {:.0} \"m/s\"",
+        wrong
+    );
+
+    // 3. Temperatures
+    println!("\n3. Temperatures: points and differences:");
+    let morning = Temperature::celsius(12.0);
+    let noon = Temperature::fahrenheit(77.0);
+    let rise = noon - morning;
+    println!(
+        "   {:.2} °C to {:.2} °F: a rise of {:.2} K = {:.2} °F",
+        morning.as_celsius(),
+        noon.as_fahrenheit(),
+        rise.as_kelvin(),
+        rise.as_fahrenheit()
+    );
+    // The classic bug: converting a difference as if it were a reading
+    let as_reading = Temperature::celsius(rise.as_kelvin()).as_fahrenheit();
+    println!(
+        "   converting the rise as a reading instead: {:.1} °F, off by 32",
+        as_reading
+    );
+    let day = [
+        Temperature::celsius(12.0),
+        Temperature::celsius(18.5),
+        Temperature::celsius(25.0),
+        Temperature::celsius(21.5),
+    ];
+    let mean = Temperature::mean(&day).expect("four readings");
+    println!(
+        "   mean of {} readings: {:.2} °C",
+        day.len(),
+        mean.as_celsius()
+    );
+    let heated = morning + TemperatureDelta::kelvin(5.0);
+    println!(
+        "   {:.2} °C + 5 K = {:.2} °C",
+        morning.as_celsius(),
+        heated.as_celsius()
+    );
+    println!("   what doesn't compile (tests/ui):");
+    for line in [
+        "Length::meters(100.0) + Time::seconds(9.58)",
+        "Temperature::celsius(20.0) + Temperature::celsius(20.0)",
+        "Temperature::celsius(10.0) * 2.0",
+        "let _: Length = Length::meters(100.0) / Time::seconds(9.58)",
+        "Length::meters(2.0) * Length::meters(3.0)",
+        "Length::meters(2.0) + 1.5",
+    ] {
+        println!("     {}", line);
+    }
+
+    // 4. The cost
+    println!("\n4. Zero cost: {} samples summed both ways:", SAMPLES);
+    let raw = random(9, SAMPLES);
+    let typed: Vec<Length> = raw.iter().map(|&m| Length::meters(m)).collect();
+    let started = Instant::now();
+    let sum_f64: f64 = black_box(&raw).iter().sum();
+    let t_f64 = started.elapsed();
+    let started = Instant::now();
+    let sum_typed: Length = black_box(&typed).iter().copied().sum();
+    let t_typed = started.elapsed();
+    println!("   f64:    {:.3} in {:?}", sum_f64, t_f64);
+    println!("   Length: {:.3} in {:?}", sum_typed, t_typed);
+    println!(
+        "   size_of: Length {} bytes, f64 {} bytes; sums equal: {}",
+        size_of::<Length>(),
+        size_of::<f64>(),
+        sum_typed.as_meters() == sum_f64
+    );
+
+    println!("\n=== End of Units of Measure Examples ===");
+}
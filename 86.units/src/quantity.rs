@@ -0,0 +1,184 @@
+// `Quantity<D>`: an `f64` in SI base units that remembers its dimension
+//
+// `D` appears only in a `PhantomData`, so a `Quantity<Length>` has the
+// size and speed of an `f64`, and the compiler will still not let it be
+// added to a `Quantity<Time>`. Each operator below has a bound on `D`
+// (or on the pair), and that bound is the physics: the code for
+// `meters + seconds` isn't wrong, it doesn't exist.
+
+use crate::dim::{Dimension, Linear, Per, Ratio, Temperature, TemperatureDelta, Times};
+use std::fmt;
+use std::iter::Sum;
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Quantity<D: Dimension> {
+    si: f64,
+    dim: PhantomData<D>,
+}
+
+impl<D: Dimension> Quantity<D> {
+    // In SI base units: metres, seconds, kelvin
+    pub const fn from_si(si: f64) -> Quantity<D> {
+        Quantity {
+            si,
+            dim: PhantomData,
+        }
+    }
+
+    pub const fn si(self) -> f64 {
+        self.si
+    }
+
+    pub fn abs(self) -> Quantity<D> {
+        Quantity::from_si(self.si.abs())
+    }
+
+    pub fn min(self, other: Quantity<D>) -> Quantity<D> {
+        Quantity::from_si(self.si.min(other.si))
+    }
+
+    pub fn max(self, other: Quantity<D>) -> Quantity<D> {
+        Quantity::from_si(self.si.max(other.si))
+    }
+
+    pub fn clamp(self, low: Quantity<D>, high: Quantity<D>) -> Quantity<D> {
+        Quantity::from_si(self.si.clamp(low.si, high.si))
+    }
+}
+
+impl<D: Dimension> fmt::Display for Quantity<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.si, f)?;
+        if !D::SYMBOL.is_empty() {
+            write!(f, " {}", D::SYMBOL)?;
+        }
+        Ok(())
+    }
+}
+
+// Same dimension only
+
+impl<D: Linear> Add for Quantity<D> {
+    type Output = Quantity<D>;
+
+    fn add(self, rhs: Quantity<D>) -> Quantity<D> {
+        Quantity::from_si(self.si + rhs.si)
+    }
+}
+
+impl<D: Linear> Sub for Quantity<D> {
+    type Output = Quantity<D>;
+
+    fn sub(self, rhs: Quantity<D>) -> Quantity<D> {
+        Quantity::from_si(self.si - rhs.si)
+    }
+}
+
+impl<D: Linear> AddAssign for Quantity<D> {
+    fn add_assign(&mut self, rhs: Quantity<D>) {
+        self.si += rhs.si;
+    }
+}
+
+impl<D: Linear> SubAssign for Quantity<D> {
+    fn sub_assign(&mut self, rhs: Quantity<D>) {
+        self.si -= rhs.si;
+    }
+}
+
+impl<D: Linear> Neg for Quantity<D> {
+    type Output = Quantity<D>;
+
+    fn neg(self) -> Quantity<D> {
+        Quantity::from_si(-self.si)
+    }
+}
+
+impl<D: Linear> Sum for Quantity<D> {
+    fn sum<I: Iterator<Item = Quantity<D>>>(iter: I) -> Quantity<D> {
+        Quantity::from_si(iter.map(|q| q.si).sum())
+    }
+}
+
+// Scaling by a plain number
+
+impl<D: Linear> Mul<f64> for Quantity<D> {
+    type Output = Quantity<D>;
+
+    fn mul(self, rhs: f64) -> Quantity<D> {
+        Quantity::from_si(self.si * rhs)
+    }
+}
+
+impl<D: Linear> Mul<Quantity<D>> for f64 {
+    type Output = Quantity<D>;
+
+    fn mul(self, rhs: Quantity<D>) -> Quantity<D> {
+        Quantity::from_si(self * rhs.si)
+    }
+}
+
+impl<D: Linear> Div<f64> for Quantity<D> {
+    type Output = Quantity<D>;
+
+    fn div(self, rhs: f64) -> Quantity<D> {
+        Quantity::from_si(self.si / rhs)
+    }
+}
+
+// Combining dimensions, where `dim` says what the result is
+
+impl<A: Times<B>, B: Dimension> Mul<Quantity<B>> for Quantity<A> {
+    type Output = Quantity<A::Output>;
+
+    fn mul(self, rhs: Quantity<B>) -> Quantity<A::Output> {
+        Quantity::from_si(self.si * rhs.si)
+    }
+}
+
+impl<A: Per<B>, B: Dimension> Div<Quantity<B>> for Quantity<A> {
+    type Output = Quantity<A::Output>;
+
+    fn div(self, rhs: Quantity<B>) -> Quantity<A::Output> {
+        Quantity::from_si(self.si / rhs.si)
+    }
+}
+
+// A ratio is just a number once the units have cancelled
+impl From<Quantity<Ratio>> for f64 {
+    fn from(q: Quantity<Ratio>) -> f64 {
+        q.si
+    }
+}
+
+// Absolute temperatures are points on a scale, not amounts. 20 °C plus
+// 20 °C is not 40 °C (in kelvin it would be 586 K), so only these exist:
+//
+//   Temperature - Temperature       -> TemperatureDelta
+//   Temperature ± TemperatureDelta  -> Temperature
+
+impl Sub for Quantity<Temperature> {
+    type Output = Quantity<TemperatureDelta>;
+
+    fn sub(self, rhs: Quantity<Temperature>) -> Quantity<TemperatureDelta> {
+        Quantity::from_si(self.si - rhs.si)
+    }
+}
+
+impl Add<Quantity<TemperatureDelta>> for Quantity<Temperature> {
+    type Output = Quantity<Temperature>;
+
+    fn add(self, rhs: Quantity<TemperatureDelta>) -> Quantity<Temperature> {
+        Quantity::from_si(self.si + rhs.si)
+    }
+}
+
+impl Sub<Quantity<TemperatureDelta>> for Quantity<Temperature> {
+    type Output = Quantity<Temperature>;
+
+    fn sub(self, rhs: Quantity<TemperatureDelta>) -> Quantity<Temperature> {
+        Quantity::from_si(self.si - rhs.si)
+    }
+}
@@ -0,0 +1,165 @@
+// Named quantities, with constructors and conversions
+//
+// Values are stored in SI base units, so converting happens exactly
+// twice: once in the constructor (`Length::kilometers(1.2)`) and once in
+// an accessor (`as_kilometers`). Everything in between is plain `f64`
+// arithmetic with no unit in sight. A constructor's name is the only
+// place the unit is written, which is the point: `Length::meters(x)`
+// can't be misread, and a bare `1.2` can't be passed where a `Length`
+// is expected.
+
+use crate::dim;
+use crate::quantity::Quantity;
+use std::time::Duration;
+
+pub type Ratio = Quantity<dim::Ratio>;
+pub type Length = Quantity<dim::Length>;
+pub type Time = Quantity<dim::Time>;
+pub type Velocity = Quantity<dim::Velocity>;
+pub type Temperature = Quantity<dim::Temperature>;
+pub type TemperatureDelta = Quantity<dim::TemperatureDelta>;
+
+const ZERO_CELSIUS_K: f64 = 273.15;
+const FAHRENHEIT_PER_KELVIN: f64 = 1.8;
+const KMH_PER_MS: f64 = 3.6;
+
+impl Ratio {
+    pub const fn new(value: f64) -> Ratio {
+        Quantity::from_si(value)
+    }
+
+    pub const fn value(self) -> f64 {
+        self.si()
+    }
+}
+
+impl Length {
+    pub const fn meters(m: f64) -> Length {
+        Quantity::from_si(m)
+    }
+
+    pub const fn millimeters(mm: f64) -> Length {
+        Quantity::from_si(mm / 1000.0)
+    }
+
+    pub const fn kilometers(km: f64) -> Length {
+        Quantity::from_si(km * 1000.0)
+    }
+
+    pub const fn as_meters(self) -> f64 {
+        self.si()
+    }
+
+    pub const fn as_millimeters(self) -> f64 {
+        self.si() * 1000.0
+    }
+
+    pub const fn as_kilometers(self) -> f64 {
+        self.si() / 1000.0
+    }
+}
+
+impl Time {
+    pub const fn seconds(s: f64) -> Time {
+        Quantity::from_si(s)
+    }
+
+    pub const fn millis(ms: f64) -> Time {
+        Quantity::from_si(ms / 1000.0)
+    }
+
+    pub const fn minutes(min: f64) -> Time {
+        Quantity::from_si(min * 60.0)
+    }
+
+    pub const fn hours(h: f64) -> Time {
+        Quantity::from_si(h * 3600.0)
+    }
+
+    pub const fn as_seconds(self) -> f64 {
+        self.si()
+    }
+
+    pub const fn as_hours(self) -> f64 {
+        self.si() / 3600.0
+    }
+}
+
+impl From<Duration> for Time {
+    fn from(d: Duration) -> Time {
+        Time::seconds(d.as_secs_f64())
+    }
+}
+
+impl Velocity {
+    pub const fn meters_per_second(ms: f64) -> Velocity {
+        Quantity::from_si(ms)
+    }
+
+    pub const fn kilometers_per_hour(kmh: f64) -> Velocity {
+        Quantity::from_si(kmh / KMH_PER_MS)
+    }
+
+    pub const fn as_meters_per_second(self) -> f64 {
+        self.si()
+    }
+
+    pub const fn as_kilometers_per_hour(self) -> f64 {
+        self.si() * KMH_PER_MS
+    }
+}
+
+// An absolute temperature; its zero is absolute zero
+impl Temperature {
+    pub const fn kelvin(k: f64) -> Temperature {
+        Quantity::from_si(k)
+    }
+
+    pub const fn celsius(c: f64) -> Temperature {
+        Quantity::from_si(c + ZERO_CELSIUS_K)
+    }
+
+    pub const fn fahrenheit(f: f64) -> Temperature {
+        Quantity::from_si((f - 32.0) / FAHRENHEIT_PER_KELVIN + ZERO_CELSIUS_K)
+    }
+
+    pub const fn as_kelvin(self) -> f64 {
+        self.si()
+    }
+
+    pub const fn as_celsius(self) -> f64 {
+        self.si() - ZERO_CELSIUS_K
+    }
+
+    pub const fn as_fahrenheit(self) -> f64 {
+        (self.si() - ZERO_CELSIUS_K) * FAHRENHEIT_PER_KELVIN + 32.0
+    }
+
+    // Temperatures can't be summed, but their differences from any one
+    // of them can: the mean is that point plus the mean difference
+    pub fn mean(temperatures: &[Temperature]) -> Option<Temperature> {
+        let (&first, _) = temperatures.split_first()?;
+        let spread: TemperatureDelta = temperatures.iter().map(|&t| t - first).sum();
+        Some(first + spread / temperatures.len() as f64)
+    }
+}
+
+// A change in temperature; a kelvin and a degree Celsius are the same
+// size, a degree Fahrenheit is 5/9 of one, and no offset applies
+impl TemperatureDelta {
+    pub const fn kelvin(k: f64) -> TemperatureDelta {
+        Quantity::from_si(k)
+    }
+
+    pub const fn fahrenheit(f: f64) -> TemperatureDelta {
+        Quantity::from_si(f / FAHRENHEIT_PER_KELVIN)
+    }
+
+    pub const fn as_kelvin(self) -> f64 {
+        self.si()
+    }
+
+    pub const fn as_fahrenheit(self) -> f64 {
+        self.si() * FAHRENHEIT_PER_KELVIN
+    }
+}
@@ -0,0 +1,167 @@
+use std::mem::size_of;
+use std::time::Duration;
+use units::{Length, Ratio, Temperature, TemperatureDelta, Time, Velocity};
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9 * a.abs().max(1.0)
+}
+
+// xorshift32: the same values on every run
+struct Rng(u32);
+
+impl Rng {
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+}
+
+// The anemometer from the demo: each turn moves the cups one circumference
+fn wind_speed(cup_radius: Length, turns: f64, interval: Time, factor: f64) -> Velocity {
+    let circumference = cup_radius * (2.0 * std::f64::consts::PI);
+    circumference * turns * factor / interval
+}
+
+#[test]
+fn length_over_time_is_a_velocity() {
+    let run = Length::kilometers(10.0);
+    let time = Time::minutes(42.0) + Time::seconds(30.0);
+    assert_eq!(time.as_seconds(), 2550.0);
+    let pace = run / time;
+    assert!(close(pace.as_meters_per_second(), 10_000.0 / 2550.0));
+    assert!(close(pace.as_kilometers_per_hour(), 36_000.0 / 2550.0));
+    // And back again, either way round
+    assert!(close((pace * time).as_kilometers(), 10.0));
+    assert!(close((time * pace).as_meters(), 10_000.0));
+    assert!(close((run / pace).as_seconds(), 2550.0));
+}
+
+#[test]
+fn the_same_dimension_divides_to_a_plain_number() {
+    let laps: Ratio = Length::kilometers(10.0) / Length::meters(400.0);
+    assert_eq!(laps.value(), 25.0);
+    assert_eq!(f64::from(laps), 25.0);
+    let doubled = Length::meters(3.0) * Ratio::new(2.0);
+    assert_eq!(doubled, Length::meters(6.0));
+}
+
+#[test]
+fn every_constructor_lands_in_si() {
+    assert_eq!(Length::millimeters(70.0).as_meters(), 0.07);
+    assert_eq!(Length::meters(0.07).as_millimeters(), 70.0);
+    assert_eq!(Time::millis(250.0), Time::seconds(0.25));
+    assert_eq!(Time::hours(1.5).as_seconds(), 5400.0);
+    assert_eq!(Time::minutes(90.0).as_hours(), 1.5);
+    assert_eq!(Time::from(Duration::from_millis(250)), Time::seconds(0.25));
+    assert!(close(
+        Velocity::kilometers_per_hour(36.0).as_meters_per_second(),
+        10.0
+    ));
+}
+
+#[test]
+fn typed_and_f64_agree_when_the_units_do() {
+    let speed = wind_speed(Length::millimeters(70.0), 45.0, Time::seconds(10.0), 2.5);
+    let bare = 2.0 * std::f64::consts::PI * 0.07 * 45.0 * 2.5 / 10.0;
+    assert!(close(speed.as_meters_per_second(), bare));
+}
+
+#[test]
+fn display_shows_the_si_unit() {
+    assert_eq!(Length::kilometers(1.5).to_string(), "1500 m");
+    assert_eq!(
+        format!("{:.1}", Velocity::meters_per_second(2.25)),
+        "2.2 m/s"
+    );
+    assert_eq!(Time::millis(250.0).to_string(), "0.25 s");
+    assert_eq!(Ratio::new(25.0).to_string(), "25");
+}
+
+#[test]
+fn sums_and_comparisons() {
+    let legs = [
+        Length::meters(1.0),
+        Length::meters(2.5),
+        Length::meters(-0.5),
+    ];
+    let total: Length = legs.iter().copied().sum();
+    assert_eq!(total, Length::meters(3.0));
+    let mut running = Length::meters(0.0);
+    running += Length::meters(2.0);
+    running -= Length::meters(0.5);
+    assert_eq!(running, Length::meters(1.5));
+    assert_eq!(-running, Length::meters(-1.5));
+    assert!(Length::millimeters(999.0) < Length::meters(1.0));
+    assert_eq!((-running).abs(), running);
+    assert_eq!(
+        Length::meters(5.0).clamp(Length::meters(0.0), Length::meters(2.0)),
+        Length::meters(2.0)
+    );
+    assert_eq!(
+        Length::meters(1.0).max(Length::meters(2.0)),
+        Length::meters(2.0)
+    );
+}
+
+#[test]
+fn temperatures_convert_with_their_offset() {
+    assert!(close(Temperature::fahrenheit(77.0).as_celsius(), 25.0));
+    assert!(close(Temperature::celsius(-40.0).as_fahrenheit(), -40.0));
+    assert!(close(Temperature::kelvin(0.0).as_celsius(), -273.15));
+    assert!(close(Temperature::celsius(0.0).as_kelvin(), 273.15));
+}
+
+#[test]
+fn differences_convert_without_it() {
+    let rise = Temperature::fahrenheit(77.0) - Temperature::celsius(12.0);
+    assert!(close(rise.as_kelvin(), 13.0));
+    assert!(close(rise.as_fahrenheit(), 23.4));
+    // The classic bug: converting the rise as if it were a reading
+    let as_reading = Temperature::celsius(rise.as_kelvin()).as_fahrenheit();
+    assert!(close(as_reading - rise.as_fahrenheit(), 32.0));
+    assert!(close(TemperatureDelta::fahrenheit(9.0).as_kelvin(), 5.0));
+}
+
+#[test]
+fn a_point_plus_a_difference_is_a_point() {
+    let morning = Temperature::celsius(12.0);
+    let heated = morning + TemperatureDelta::kelvin(5.0);
+    assert!(close(heated.as_celsius(), 17.0));
+    assert!(close(
+        (heated - TemperatureDelta::kelvin(5.0)).as_celsius(),
+        12.0
+    ));
+}
+
+#[test]
+fn the_mean_of_temperatures() {
+    let day = [
+        Temperature::celsius(12.0),
+        Temperature::celsius(18.5),
+        Temperature::celsius(25.0),
+        Temperature::celsius(21.5),
+    ];
+    assert!(close(Temperature::mean(&day).unwrap().as_celsius(), 19.25));
+    assert_eq!(
+        Temperature::mean(&[Temperature::fahrenheit(50.0)]),
+        Some(Temperature::fahrenheit(50.0))
+    );
+    assert_eq!(Temperature::mean(&[]), None);
+}
+
+#[test]
+fn a_length_is_exactly_an_f64() {
+    assert_eq!(size_of::<Length>(), size_of::<f64>());
+    assert_eq!(size_of::<Vec<Length>>(), size_of::<Vec<f64>>());
+    assert_eq!(size_of::<Option<Temperature>>(), size_of::<Option<f64>>());
+
+    let mut rng = Rng(9);
+    let raw: Vec<f64> = (0..10_000)
+        .map(|_| rng.next() as f64 / u32::MAX as f64)
+        .collect();
+    let typed: Length = raw.iter().map(|&m| Length::meters(m)).sum();
+    // Bit for bit: the same additions in the same order
+    assert_eq!(typed.as_meters(), raw.iter().sum::<f64>());
+}
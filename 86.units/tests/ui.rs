@@ -0,0 +1,9 @@
+// Dimension errors must fail to compile. Each file in tests/ui is
+// compiled on its own and its errors compared with the .stderr file next
+// to it; after changing the types, regenerate them with
+// TRYBUILD=overwrite cargo test
+#[test]
+fn dimension_errors_do_not_compile() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
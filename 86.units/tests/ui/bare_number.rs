@@ -0,0 +1,5 @@
+use units::Length;
+
+fn main() {
+    let _ = Length::meters(2.0) + 1.5;
+}
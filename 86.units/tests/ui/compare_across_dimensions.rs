@@ -0,0 +1,5 @@
+use units::{Length, Time};
+
+fn main() {
+    let _ = Length::meters(1.0) < Time::seconds(1.0);
+}
@@ -0,0 +1,5 @@
+use units::{Length, Time};
+
+fn main() {
+    let _ = Length::meters(100.0) + Time::seconds(9.58);
+}
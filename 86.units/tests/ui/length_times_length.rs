@@ -0,0 +1,6 @@
+use units::Length;
+
+// Area isn't one of the dimensions, so there is nothing to multiply into
+fn main() {
+    let _ = Length::meters(2.0) * Length::meters(3.0);
+}
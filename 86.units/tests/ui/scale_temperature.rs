@@ -0,0 +1,6 @@
+use units::Temperature;
+
+// "Twice as warm" has no meaning on a scale with an arbitrary zero
+fn main() {
+    let _ = Temperature::celsius(10.0) * 2.0;
+}
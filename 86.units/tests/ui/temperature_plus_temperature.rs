@@ -0,0 +1,5 @@
+use units::Temperature;
+
+fn main() {
+    let _ = Temperature::celsius(20.0) + Temperature::celsius(20.0);
+}
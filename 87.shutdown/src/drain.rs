@@ -0,0 +1,145 @@
+// Stopping a pipeline of threads, or tasks, joined by channels
+//
+//   source ──tx──▶ stage ──tx──▶ sink
+//     │
+//   token
+//
+// Only the source watches the token. When it stops, it drops its sender.
+// The stage takes everything still queued, sees the channel close, and
+// finishes, dropping its own sender, and so on down the line. Every
+// message the source accepted is handled, and no thread is left waiting
+// on a channel that will never produce. Stopping every stage at the
+// token instead throws away whatever was queued between them.
+//
+// - `recv`: for the source, or any stage reading from outside, which must
+//   stop on the token
+// - `drain`: for the end of the line, with a deadline in case something
+//   upstream is stuck
+// - `join_within`: waits for the threads, up to a deadline
+//
+// `recv_async` and `drain_async` do the same for tokio's channels.
+
+use crate::token::Token;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// How often a blocked `recv` or `join_within` looks at the token or the
+// threads
+pub const POLL: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Drained {
+    pub handled: usize,
+    // Every sender was dropped, so nothing more can arrive; false if the
+    // deadline came first
+    pub complete: bool,
+}
+
+// The next message, or None once the token is cancelled or every sender
+// is gone. Messages still queued when the token fires are left for
+// `drain`.
+pub fn recv<T>(rx: &Receiver<T>, token: &Token) -> Option<T> {
+    while !token.is_cancelled() {
+        match rx.recv_timeout(POLL) {
+            Ok(message) => return Some(message),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return None,
+        }
+    }
+    None
+}
+
+// Handles messages until every sender is dropped, or until `within` has
+// passed
+pub fn drain<T>(rx: &Receiver<T>, within: Duration, mut handle: impl FnMut(T)) -> Drained {
+    let deadline = Instant::now() + within;
+    let mut handled = 0;
+    loop {
+        match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(message) => {
+                handle(message);
+                handled += 1;
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                return Drained {
+                    handled,
+                    complete: true,
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                return Drained {
+                    handled,
+                    complete: false,
+                }
+            }
+        }
+    }
+}
+
+// Joins the threads that finish within `within` and returns the names of
+// those that didn't. Those are left running, and end with the process.
+// A thread that panicked counts as finished; the panic hook has already
+// reported it.
+pub fn join_within<T>(threads: Vec<JoinHandle<T>>, within: Duration) -> Vec<String> {
+    let deadline = Instant::now() + within;
+    let mut running = threads;
+    loop {
+        let (finished, still): (Vec<_>, Vec<_>) =
+            running.into_iter().partition(|t| t.is_finished());
+        for thread in finished {
+            let _ = thread.join();
+        }
+        running = still;
+        if running.is_empty() || Instant::now() >= deadline {
+            break;
+        }
+        thread::sleep(POLL.min(deadline.saturating_duration_since(Instant::now())));
+    }
+    running
+        .iter()
+        .map(|t| t.thread().name().unwrap_or("unnamed").to_string())
+        .collect()
+}
+
+// `recv` for a task: no polling, the token and the channel are awaited
+// together. `biased` checks the token first, so a cancelled source stops
+// even with messages waiting.
+#[cfg(feature = "tokio")]
+pub async fn recv_async<T>(rx: &mut tokio::sync::mpsc::Receiver<T>, token: &Token) -> Option<T> {
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => None,
+        message = rx.recv() => message,
+    }
+}
+
+#[cfg(feature = "tokio")]
+pub async fn drain_async<T>(
+    rx: &mut tokio::sync::mpsc::Receiver<T>,
+    within: Duration,
+    mut handle: impl FnMut(T),
+) -> Drained {
+    let deadline = tokio::time::Instant::now() + within;
+    let mut handled = 0;
+    loop {
+        match tokio::time::timeout_at(deadline, rx.recv()).await {
+            Ok(Some(message)) => {
+                handle(message);
+                handled += 1;
+            }
+            Ok(None) => {
+                return Drained {
+                    handled,
+                    complete: true,
+                }
+            }
+            Err(_) => {
+                return Drained {
+                    handled,
+                    complete: false,
+                }
+            }
+        }
+    }
+}
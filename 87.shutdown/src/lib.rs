@@ -0,0 +1,28 @@
+// Graceful shutdown shared by the network lessons
+//
+//   Ctrl-C / SIGTERM ──▶ handle_signals ──▶ Shutdown::trigger
+//                                                │
+//               ┌──────────────┬─────────────────┼──────────────────┐
+//          Token::wait   is_cancelled()   wait_timeout(poll)   cancelled().await
+//          (a thread)     (a hot loop)    (a periodic loop)      (a tokio task)
+//
+// Each part of the program gets a `Token`, stops what it is doing when it
+// fires, and finishes what it already started: a listener stops
+// accepting, a pipeline drains its channels, a log is flushed and a
+// socket closed. Then `main` returns, instead of the process dying
+// wherever the signal found it.
+//
+// - `token`: `Shutdown` (the trigger), `Token` (the view) and `Reason`
+// - `signal`: `handle_signals`, and `Shutdown::signal` to simulate one
+// - `drain`: `recv`, `drain` and `join_within` for channel pipelines,
+//   and with the `tokio` feature `recv_async` and `drain_async`
+
+pub mod drain;
+pub mod signal;
+pub mod token;
+
+pub use drain::{drain, join_within, recv, Drained};
+#[cfg(feature = "tokio")]
+pub use drain::{drain_async, recv_async};
+pub use signal::{handle_signals, Escalation, FORCED_EXIT_CODE};
+pub use token::{Reason, Shutdown, Token};
@@ -0,0 +1,261 @@
+use shutdown::{drain, Reason, Shutdown};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const RUN: Duration = Duration::from_millis(100);
+
+fn micros(d: Duration) -> String {
+    format!("{} µs", d.as_micros())
+}
+
+// source -> stage -> sink, run for `RUN` and then shut down. The source
+// sends faster than the stage works, so messages queue between them.
+// With `stop_every_stage` the stage also watches the token and leaves at
+// once; otherwise it drains its input first. Returns (sent, handled).
+fn pipeline(stop_every_stage: bool) -> (usize, usize) {
+    let shutdown = Shutdown::new();
+    let (raw_tx, raw_rx) = mpsc::channel::<u32>();
+    let (out_tx, out_rx) = mpsc::channel::<u32>();
+
+    let token = shutdown.token();
+    let source = thread::spawn(move || {
+        let mut sent = 0;
+        while !token.is_cancelled() {
+            raw_tx.send(sent as u32).expect("stage is running");
+            sent += 1;
+            thread::sleep(Duration::from_micros(100));
+        }
+        sent
+    });
+    let token = shutdown.token();
+    let stage = thread::spawn(move || {
+        let work = |reading: u32| {
+            thread::sleep(Duration::from_micros(300));
+            let _ = out_tx.send(reading * 2);
+        };
+        if stop_every_stage {
+            while let Some(reading) = shutdown::recv(&raw_rx, &token) {
+                work(reading);
+            }
+        } else {
+            // Ends when the source drops its sender
+            for reading in raw_rx {
+                work(reading);
+            }
+        }
+    });
+
+    thread::sleep(RUN);
+    shutdown.trigger(Reason::Requested("demo"));
+    let drained = drain(&out_rx, Duration::from_secs(10), |_| {});
+    let sent = source.join().expect("source");
+    stage.join().expect("stage");
+    (sent, drained.handled)
+}
+
+fn main() {
+    println!("=== Graceful Shutdown Examples ===\n");
+
+    // 1. One trigger, every kind of watcher
+    println!("1. One trigger reaches every watcher:");
+    let shutdown = Shutdown::new();
+    let watchers: Vec<(&str, thread::JoinHandle<Instant>)> = vec![
+        ("blocked in wait()", {
+            let token = shutdown.token();
+            thread::spawn(move || {
+                token.wait();
+                Instant::now()
+            })
+        }),
+        ("busy loop polling is_cancelled()", {
+            let token = shutdown.token();
+            thread::spawn(move || {
+                while !token.is_cancelled() {
+                    thread::sleep(Duration::from_micros(200));
+                }
+                Instant::now()
+            })
+        }),
+        ("loop sleeping 10 s per round", {
+            let token = shutdown.token();
+            thread::spawn(move || {
+                while !token.wait_timeout(Duration::from_secs(10)) {}
+                Instant::now()
+            })
+        }),
+    ];
+    thread::sleep(Duration::from_millis(50));
+    let triggered = Instant::now();
+    let first = shutdown.trigger(Reason::Requested("run time limit"));
+    for (name, watcher) in watchers {
+        let noticed = watcher.join().expect("watcher");
+        let latency = noticed.saturating_duration_since(triggered);
+        println!("   {:<34} stopped after {}", name, micros(latency));
+    }
+    let again = shutdown.trigger(Reason::Signal);
+    println!(
+        "   trigger returned {}, then {}; reason {:?}",
+        first,
+        again,
+        shutdown.token().reason()
+    );
+
+    // 2. Simulated signals
+    println!("\n2. Signals, simulated with Shutdown::signal:");
+    let shutdown = Shutdown::new();
+    let token = shutdown.token();
+    let first = shutdown.signal();
+    println!("   first:  {:?}, token reason {:?}", first, token.reason());
+    let second = shutdown.signal();
+    println!(
+        "   second: {:?} (the handler would exit with {})",
+        second,
+        shutdown::FORCED_EXIT_CODE
+    );
+    let shutdown = Shutdown::new();
+    shutdown.trigger(Reason::Requested("config reload failed"));
+    println!(
+        "   a first signal mid-shutdown: {:?}, reason still {:?}",
+        shutdown.signal(),
+        shutdown.token().reason()
+    );
+
+    // 3. Draining a pipeline
+    println!("\n3. A pipeline stopped after {:?}, two ways:", RUN);
+    let (sent, handled) = pipeline(true);
+    println!(
+        "   every stage stops at the token: {} sent, {} handled, {} lost",
+        sent,
+        handled,
+        sent - handled
+    );
+    let (sent, handled) = pipeline(false);
+    println!(
+        "   only the source stops, the rest drain: {} sent, {} handled",
+        sent, handled
+    );
+
+    // 4. The same token in tokio tasks
+    println!("\n4. Tokio tasks, triggered from a thread:");
+    tasks();
+
+    // 5. A real signal
+    println!("\n5. A real SIGINT through handle_signals:");
+    real_signal();
+
+    println!("\n=== End of Graceful Shutdown Examples ===");
+}
+
+#[cfg(feature = "tokio")]
+fn tasks() {
+    use shutdown::{drain_async, recv_async};
+    use tokio::sync::mpsc;
+
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            println!("   cannot start tokio: {}", e);
+            return;
+        }
+    };
+    let shutdown = Shutdown::new();
+    let (sent, handled, commands) = runtime.block_on(async {
+        // The source: a reading every millisecond until the token fires
+        let (tx, mut rx) = mpsc::channel::<u32>(16);
+        let token = shutdown.token();
+        let source = tokio::spawn(async move {
+            let mut tick = tokio::time::interval(Duration::from_millis(1));
+            let mut sent = 0u32;
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = tick.tick() => {
+                        if tx.send(sent).await.is_err() {
+                            break;
+                        }
+                        sent += 1;
+                    }
+                }
+            }
+            sent
+        });
+        // The sink drains until the source's sender is gone
+        let sink = tokio::spawn(async move {
+            drain_async(&mut rx, Duration::from_secs(10), |_| {})
+                .await
+                .handled
+        });
+        // Commands arrive from outside, whose sender never closes; this
+        // one has to stop on the token
+        let (command_tx, mut command_rx) = mpsc::channel::<&str>(4);
+        let token = shutdown.token();
+        let listener = tokio::spawn(async move {
+            let mut handled = 0;
+            while recv_async(&mut command_rx, &token).await.is_some() {
+                handled += 1;
+            }
+            handled
+        });
+        command_tx.send("ping").await.expect("listener is running");
+
+        // Triggered from a plain thread, as a signal handler would
+        let trigger = shutdown.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            trigger.trigger(Reason::Signal);
+        });
+        let sent = source.await.expect("source");
+        let handled = sink.await.expect("sink");
+        let commands = listener.await.expect("listener");
+        drop(command_tx);
+        (sent, handled, commands)
+    });
+    println!(
+        "   source sent {}, sink handled {}, listener took {} command(s)",
+        sent, handled, commands
+    );
+}
+
+#[cfg(not(feature = "tokio"))]
+fn tasks() {
+    println!("   (built without the tokio feature)");
+}
+
+#[cfg(unix)]
+fn real_signal() {
+    use shutdown::{handle_signals, Token};
+
+    let shutdown = Shutdown::new();
+    if let Err(e) = handle_signals(&shutdown) {
+        println!("   cannot install the handler: {}", e);
+        return;
+    }
+    let token: Token = shutdown.token();
+    let pid = std::process::id();
+    let sent = std::process::Command::new("kill")
+        .args(["-INT", &pid.to_string()])
+        .status();
+    match sent {
+        Ok(status) if status.success() => println!("   kill -INT {}", pid),
+        other => {
+            println!("   cannot run kill: {:?}", other);
+            return;
+        }
+    }
+    let stopped = token.wait_timeout(Duration::from_secs(5));
+    println!(
+        "   stopped: {}, token: {:?}, {} signal(s) received",
+        stopped,
+        token.reason(),
+        shutdown.signals()
+    );
+}
+
+#[cfg(not(unix))]
+fn real_signal() {
+    println!("   (needs kill; run the gateway and press Ctrl-C instead)");
+}
@@ -0,0 +1,56 @@
+// Ctrl-C and termination signals
+//
+// The handler does as little as possible: it triggers the shutdown and
+// returns, and the program's own threads flush and close. A second
+// signal means whoever sent it has run out of patience (a flush that
+// hangs, a user pressing Ctrl-C again), so the process exits at once with
+// 130, the shell's code for "killed by SIGINT".
+//
+// `Shutdown::signal` is what the handler runs for each signal. Tests call
+// it directly to simulate one, without sending anything to the process.
+
+use crate::token::{Reason, Shutdown};
+use std::process;
+use std::sync::atomic::Ordering;
+
+pub const FORCED_EXIT_CODE: i32 = 130;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escalation {
+    // The first signal: shut down gracefully
+    Graceful,
+    // Another one while that is still going on
+    Forced,
+}
+
+impl Shutdown {
+    pub fn signal(&self) -> Escalation {
+        let earlier = self.inner.signals.fetch_add(1, Ordering::AcqRel);
+        self.trigger(Reason::Signal);
+        if earlier == 0 {
+            Escalation::Graceful
+        } else {
+            Escalation::Forced
+        }
+    }
+
+    // Signals received so far
+    pub fn signals(&self) -> u32 {
+        self.inner.signals.load(Ordering::Acquire)
+    }
+}
+
+// Routes SIGINT, SIGTERM and SIGHUP to `shutdown`. A process has one
+// handler, so this fails if one is already installed.
+//
+// ctrlc runs the closure on a thread of its own, not inside the signal
+// handler, so it may lock, print and exit like any other code.
+pub fn handle_signals(shutdown: &Shutdown) -> Result<(), ctrlc::Error> {
+    let shutdown = shutdown.clone();
+    ctrlc::set_handler(move || {
+        if shutdown.signal() == Escalation::Forced {
+            eprintln!("second signal: exiting without cleanup");
+            process::exit(FORCED_EXIT_CODE);
+        }
+    })
+}
@@ -0,0 +1,148 @@
+// The cancellation token
+//
+// `Shutdown` is the trigger and `Token` is a view of it. All of them share
+// one state, so a single `trigger` reaches every thread blocked in
+// `wait`, every loop polling `is_cancelled` and, with the `tokio` feature,
+// every task awaiting `cancelled()`. A `Token` can't trigger, so a
+// component that only has to stop can't stop the whole program by
+// mistake.
+//
+// The flag is an atomic, so polling it in a hot loop costs one load.
+// Blocked threads sleep on a `Condvar` and tasks on a tokio `Notify`, and
+// `trigger` wakes both.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    // SIGINT (Ctrl-C), SIGTERM or SIGHUP
+    Signal,
+    // The program decided: a run time limit, a fatal error
+    Requested(&'static str),
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reason::Signal => write!(f, "signal"),
+            Reason::Requested(why) => write!(f, "{}", why),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Inner {
+    cancelled: AtomicBool,
+    // Set once, under the lock the waiters sleep on
+    reason: Mutex<Option<Reason>>,
+    woken: Condvar,
+    pub(crate) signals: AtomicU32,
+    #[cfg(feature = "tokio")]
+    notify: tokio::sync::Notify,
+}
+
+impl Inner {
+    // Nothing panics while holding it, but a waiter's thread may be
+    // killed by a panic elsewhere; the reason is still valid
+    fn reason(&self) -> MutexGuard<'_, Option<Reason>> {
+        self.reason.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Shutdown {
+    pub(crate) inner: Arc<Inner>,
+}
+
+impl Shutdown {
+    pub fn new() -> Shutdown {
+        Shutdown::default()
+    }
+
+    pub fn token(&self) -> Token {
+        Token {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+
+    // Returns false if it had already been triggered; the first reason
+    // is the one kept
+    pub fn trigger(&self, reason: Reason) -> bool {
+        let mut current = self.inner.reason();
+        if current.is_some() {
+            return false;
+        }
+        *current = Some(reason);
+        self.inner.cancelled.store(true, Ordering::Release);
+        self.inner.woken.notify_all();
+        #[cfg(feature = "tokio")]
+        self.inner.notify.notify_waiters();
+        true
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Token {
+    inner: Arc<Inner>,
+}
+
+impl Token {
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+    }
+
+    pub fn reason(&self) -> Option<Reason> {
+        *self.inner.reason()
+    }
+
+    // Blocks the thread until the shutdown is triggered
+    pub fn wait(&self) -> Reason {
+        let mut reason = self.inner.reason();
+        loop {
+            if let Some(reason) = *reason {
+                return reason;
+            }
+            reason = self
+                .inner
+                .woken
+                .wait(reason)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    // A sleep that ends early on shutdown: true if it was triggered. Use
+    // it instead of `thread::sleep` in a loop, so the loop stops at once
+    // rather than after its interval.
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let reason = self.inner.reason();
+        let (reason, _) = self
+            .inner
+            .woken
+            .wait_timeout_while(reason, timeout, |r| r.is_none())
+            .unwrap_or_else(|e| e.into_inner());
+        reason.is_some()
+    }
+
+    // Completes when the shutdown is triggered, without blocking the
+    // executor; the async `wait`
+    #[cfg(feature = "tokio")]
+    pub async fn cancelled(&self) -> Reason {
+        loop {
+            // A `Notified` is woken by every later `notify_waiters`, even
+            // before it is first polled. Creating it before checking the
+            // flag means a trigger in between can't be missed.
+            let notified = self.inner.notify.notified();
+            if let Some(reason) = self.reason() {
+                return reason;
+            }
+            notified.await;
+        }
+    }
+}
@@ -0,0 +1,28 @@
+// A real SIGINT, sent to this test process with kill(1). A process has
+// one handler, so this file holds a single test and runs as a binary of
+// its own. Only the first signal is sent: a second would exit the
+// process, which is the point of it.
+
+#![cfg(unix)]
+
+use shutdown::{handle_signals, Reason, Shutdown};
+use std::process::Command;
+use std::time::Duration;
+
+#[test]
+fn sigint_triggers_a_graceful_shutdown() {
+    let shutdown = Shutdown::new();
+    handle_signals(&shutdown).expect("first handler in this process");
+    assert!(handle_signals(&shutdown).is_err(), "only one per process");
+
+    let token = shutdown.token();
+    let status = Command::new("kill")
+        .args(["-INT", &std::process::id().to_string()])
+        .status()
+        .expect("kill is available");
+    assert!(status.success());
+
+    assert!(token.wait_timeout(Duration::from_secs(5)));
+    assert_eq!(token.reason(), Some(Reason::Signal));
+    assert_eq!(shutdown.signals(), 1);
+}
@@ -0,0 +1,170 @@
+// Pipelines stopped mid-stream. The source always sends faster than the
+// stage works, so the channels are never empty when the signal arrives.
+
+use shutdown::{drain, join_within, recv, Drained, Shutdown};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// source -> stage -> returned receiver; the source stops on the token
+fn pipeline(shutdown: &Shutdown) -> (thread::JoinHandle<u32>, mpsc::Receiver<u32>) {
+    let (raw_tx, raw_rx) = mpsc::channel();
+    let (out_tx, out_rx) = mpsc::channel();
+    let token = shutdown.token();
+    let source = thread::spawn(move || {
+        let mut sent = 0;
+        while !token.is_cancelled() {
+            raw_tx.send(sent).unwrap();
+            sent += 1;
+            thread::sleep(Duration::from_micros(50));
+        }
+        sent
+    });
+    thread::spawn(move || {
+        for reading in raw_rx {
+            thread::sleep(Duration::from_micros(200));
+            out_tx.send(reading).unwrap();
+        }
+    });
+    (source, out_rx)
+}
+
+#[test]
+fn a_signalled_pipeline_drains_every_message_in_order() {
+    let shutdown = Shutdown::new();
+    let (source, out) = pipeline(&shutdown);
+    thread::sleep(Duration::from_millis(50));
+    shutdown.signal();
+
+    let mut seen = Vec::new();
+    let drained = drain(&out, Duration::from_secs(30), |r| seen.push(r));
+    let sent = source.join().unwrap();
+    assert!(drained.complete);
+    assert_eq!(drained.handled, sent as usize);
+    assert_eq!(seen, (0..sent).collect::<Vec<_>>());
+}
+
+#[test]
+fn drain_gives_up_at_its_deadline_while_a_sender_lives() {
+    let (tx, rx) = mpsc::channel();
+    for i in 0..3 {
+        tx.send(i).unwrap();
+    }
+    let started = Instant::now();
+    let drained = drain(&rx, Duration::from_millis(30), |_| {});
+    assert_eq!(
+        drained,
+        Drained {
+            handled: 3,
+            complete: false
+        }
+    );
+    assert!(started.elapsed() >= Duration::from_millis(30));
+    drop(tx);
+}
+
+#[test]
+fn recv_stops_on_the_token_with_the_sender_still_open() {
+    let shutdown = Shutdown::new();
+    let (tx, rx) = mpsc::channel();
+    tx.send("first").unwrap();
+    let token = shutdown.token();
+    let reader = thread::spawn(move || {
+        let mut got = Vec::new();
+        while let Some(message) = recv(&rx, &token) {
+            got.push(message);
+        }
+        (got, rx)
+    });
+    thread::sleep(Duration::from_millis(30));
+    shutdown.signal();
+    let (got, rx) = reader.join().unwrap();
+    assert_eq!(got, ["first"]);
+    // Sent after the token fired: still there for whoever drains
+    tx.send("late").unwrap();
+    assert_eq!(rx.try_recv(), Ok("late"));
+}
+
+// Why only the source should watch the token: a stage that stops on it
+// too leaves whatever was queued for it unhandled
+#[test]
+fn a_stage_stopped_by_the_token_leaves_its_queue_behind() {
+    let shutdown = Shutdown::new();
+    let (tx, rx) = mpsc::channel();
+    for i in 0..5 {
+        tx.send(i).unwrap();
+    }
+    shutdown.signal();
+    assert_eq!(recv(&rx, &shutdown.token()), None);
+    assert_eq!(rx.try_iter().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn recv_ends_when_every_sender_is_gone() {
+    let shutdown = Shutdown::new();
+    let (tx, rx) = mpsc::channel();
+    tx.send(1).unwrap();
+    drop(tx);
+    let token = shutdown.token();
+    assert_eq!(recv(&rx, &token), Some(1));
+    assert_eq!(recv(&rx, &token), None);
+    assert!(!token.is_cancelled());
+}
+
+#[test]
+fn join_within_names_the_threads_still_running() {
+    let shutdown = Shutdown::new();
+    let token = shutdown.token();
+    let quick = thread::Builder::new()
+        .name("quick".into())
+        .spawn(move || {
+            token.wait();
+        })
+        .unwrap();
+    let (_keep, stuck_rx) = mpsc::channel::<()>();
+    let stuck = thread::Builder::new()
+        .name("stuck".into())
+        .spawn(move || {
+            // Ignores the token and waits on a channel nobody sends on
+            let _ = stuck_rx.recv_timeout(Duration::from_secs(30));
+        })
+        .unwrap();
+    shutdown.signal();
+    let late = join_within(vec![quick, stuck], Duration::from_millis(100));
+    assert_eq!(late, ["stuck"]);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn async_pipelines_drain_the_same_way() {
+    use shutdown::{drain_async, recv_async, Reason};
+    use tokio::sync::mpsc;
+
+    let shutdown = Shutdown::new();
+    let (tx, mut rx) = mpsc::channel(32);
+    let (command_tx, mut command_rx) = mpsc::channel::<u32>(32);
+    let token = shutdown.token();
+    let source = tokio::spawn(async move {
+        let mut sent = 0u32;
+        // Forwards commands until the token fires, with the sender open
+        while let Some(command) = recv_async(&mut command_rx, &token).await {
+            tx.send(command).await.unwrap();
+            sent += 1;
+        }
+        sent
+    });
+    for i in 0..20 {
+        command_tx.send(i).await.unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let trigger = shutdown.clone();
+    thread::spawn(move || trigger.trigger(Reason::Signal));
+
+    let mut seen = Vec::new();
+    let drained = drain_async(&mut rx, Duration::from_secs(10), |r| seen.push(r)).await;
+    let sent = source.await.unwrap();
+    assert!(drained.complete);
+    assert_eq!(sent, 20);
+    assert_eq!(seen, (0..20).collect::<Vec<_>>());
+    drop(command_tx);
+}
@@ -0,0 +1,144 @@
+// The token and simulated signals. `Shutdown::signal` is what the real
+// handler calls, so calling it here exercises the same path a Ctrl-C
+// takes, for any number of shutdowns in one test process.
+
+use shutdown::{Escalation, Reason, Shutdown};
+use std::sync::Barrier;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const WATCHERS: usize = 8;
+
+#[test]
+fn one_trigger_wakes_every_waiting_thread() {
+    let shutdown = Shutdown::new();
+    let ready = Barrier::new(WATCHERS + 1);
+    let reasons = thread::scope(|s| {
+        let handles: Vec<_> = (0..WATCHERS)
+            .map(|_| {
+                let (token, ready) = (shutdown.token(), &ready);
+                s.spawn(move || {
+                    ready.wait();
+                    token.wait()
+                })
+            })
+            .collect();
+        ready.wait();
+        assert!(shutdown.trigger(Reason::Requested("test")));
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(reasons, [Reason::Requested("test"); WATCHERS]);
+}
+
+#[test]
+fn wait_timeout_returns_early_on_trigger() {
+    let shutdown = Shutdown::new();
+    let token = shutdown.token();
+    let started = Instant::now();
+    let sleeper = thread::spawn(move || token.wait_timeout(Duration::from_secs(30)));
+    thread::sleep(Duration::from_millis(20));
+    shutdown.signal();
+    assert!(sleeper.join().unwrap());
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn wait_timeout_without_a_trigger_times_out() {
+    let shutdown = Shutdown::new();
+    assert!(!shutdown.token().wait_timeout(Duration::from_millis(10)));
+    assert!(!shutdown.is_triggered());
+}
+
+#[test]
+fn a_token_made_after_the_trigger_is_already_cancelled() {
+    let shutdown = Shutdown::new();
+    shutdown.trigger(Reason::Requested("early"));
+    let late = shutdown.token();
+    assert!(late.is_cancelled());
+    assert_eq!(late.wait(), Reason::Requested("early"));
+    assert!(late.wait_timeout(Duration::ZERO));
+}
+
+#[test]
+fn the_first_reason_is_kept() {
+    let shutdown = Shutdown::new();
+    assert!(shutdown.trigger(Reason::Requested("run time limit")));
+    assert!(!shutdown.trigger(Reason::Requested("fatal error")));
+    assert_eq!(shutdown.signal(), Escalation::Graceful);
+    assert_eq!(
+        shutdown.token().reason(),
+        Some(Reason::Requested("run time limit"))
+    );
+}
+
+#[test]
+fn a_second_signal_escalates() {
+    let shutdown = Shutdown::new();
+    let token = shutdown.token();
+    assert_eq!(token.reason(), None);
+    assert_eq!(shutdown.signal(), Escalation::Graceful);
+    assert_eq!(token.reason(), Some(Reason::Signal));
+    assert_eq!(shutdown.signal(), Escalation::Forced);
+    assert_eq!(shutdown.signal(), Escalation::Forced);
+    assert_eq!(shutdown.signals(), 3);
+}
+
+#[test]
+fn racing_signals_escalate_exactly_once() {
+    let shutdown = Shutdown::new();
+    let start = Barrier::new(WATCHERS);
+    let escalations: Vec<Escalation> = thread::scope(|s| {
+        let handles: Vec<_> = (0..WATCHERS)
+            .map(|_| {
+                let (shutdown, start) = (&shutdown, &start);
+                s.spawn(move || {
+                    start.wait();
+                    shutdown.signal()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    let graceful = escalations
+        .iter()
+        .filter(|e| **e == Escalation::Graceful)
+        .count();
+    assert_eq!(graceful, 1);
+    assert_eq!(shutdown.signals(), WATCHERS as u32);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn a_signal_from_a_thread_wakes_every_task() {
+    let shutdown = Shutdown::new();
+    let tasks: Vec<_> = (0..WATCHERS)
+        .map(|_| {
+            let token = shutdown.token();
+            tokio::spawn(async move { token.cancelled().await })
+        })
+        .collect();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let trigger = shutdown.clone();
+    thread::spawn(move || trigger.signal()).join().unwrap();
+    for task in tasks {
+        let reason = tokio::time::timeout(Duration::from_secs(5), task)
+            .await
+            .expect("woken")
+            .unwrap();
+        assert_eq!(reason, Reason::Signal);
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn cancelled_after_the_trigger_completes_at_once() {
+    let shutdown = Shutdown::new();
+    shutdown.trigger(Reason::Requested("done"));
+    let reason = tokio::time::timeout(Duration::from_secs(1), shutdown.token().cancelled())
+        .await
+        .expect("already cancelled");
+    assert_eq!(reason, Reason::Requested("done"));
+}
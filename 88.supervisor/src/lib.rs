@@ -0,0 +1,24 @@
+// Supervising worker threads and tasks
+//
+//   worker ──▶ Ok / Err / panic ──▶ Exit ──▶ policy ──▶ Restart(after backoff)
+//                                                  ├──▶ Stop
+//                                                  └──▶ Escalate: Shutdown::trigger
+//
+// A worker that crashes is started again, after a delay that grows with
+// each crash. If restarts come too often (more than `max_restarts` in
+// `window`), restarting isn't fixing anything, and the supervisor shuts
+// the whole process down through the same `Shutdown` as Ctrl-C (87).
+//
+// - `policy`: `Policy`, `Restart`, `Exit`, `Action`
+// - `supervisor`: `Supervisor` for threads, its `Report`
+// - `task` (feature `tokio`): `supervise_task` for one tokio task
+
+pub mod policy;
+pub mod supervisor;
+#[cfg(feature = "tokio")]
+pub mod task;
+
+pub use policy::{Action, Exit, Policy, Restart};
+pub use supervisor::{Event, Outcome, Report, Supervisor, WorkerError, WorkerStats, RESTART_LIMIT};
+#[cfg(feature = "tokio")]
+pub use task::supervise_task;
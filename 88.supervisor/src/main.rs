@@ -0,0 +1,189 @@
+use shutdown::{Reason, Shutdown, Token};
+use std::panic;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use supervisor::{Action, Event, Policy, Restart, Supervisor, WorkerError};
+
+// Short, unjittered delays so the demo runs in well under a second
+fn policy(max_restarts: u32) -> Policy {
+    Policy {
+        max_restarts,
+        window: Duration::from_secs(1),
+        backoff: retry::Policy {
+            base: Duration::from_millis(10),
+            multiplier: 2.0,
+            max_delay: Duration::from_millis(80),
+            jitter: retry::Jitter::None,
+            ..retry::Policy::default()
+        },
+    }
+}
+
+fn print_event(e: &Event) {
+    println!(
+        "   [{:>4} ms] {:<8} {:<42} -> {}",
+        e.at.as_millis(),
+        e.worker,
+        e.exit.to_string(),
+        e.action
+    );
+}
+
+// A sensor reader that crashes on some of its runs, counted across
+// restarts. Healthy runs read until the shutdown.
+fn flaky_reader(runs: Arc<AtomicU32>) -> impl Fn(&Token) -> Result<(), WorkerError> {
+    move |token| {
+        let run = runs.fetch_add(1, Ordering::SeqCst) + 1;
+        match run {
+            1 => panic!("index out of bounds in frame {}", run),
+            2 => Err("i2c bus: no ACK from 0x40".into()),
+            3 => panic!("calibration table corrupt"),
+            _ => {
+                token.wait();
+                Ok(())
+            }
+        }
+    }
+}
+
+fn main() {
+    println!("=== Supervisor Examples ===\n");
+    // The supervisor reports each panic itself; the default hook would
+    // print a second copy with a backtrace hint
+    panic::set_hook(Box::new(|_| {}));
+
+    // 1. Restarting a crashing worker
+    println!("1. A reader that crashes three times, then recovers:");
+    let shutdown = Shutdown::new();
+    let mut sup = Supervisor::new(policy(5), &shutdown);
+    sup.on_event(print_event);
+    sup.add(
+        "reader",
+        Restart::Permanent,
+        flaky_reader(Arc::new(AtomicU32::new(0))),
+    );
+    let stopper = shutdown.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(200));
+        stopper.trigger(Reason::Requested("demo over"));
+    });
+    let result = sup.run();
+    let delays: Vec<Duration> = result
+        .events
+        .iter()
+        .filter_map(|e| match e.action {
+            Action::Restart(after) => Some(after),
+            _ => None,
+        })
+        .collect();
+    println!("   backoff before each restart: {:?}", delays);
+    println!(
+        "   {} restarts, outcome: {:?}",
+        result.restarts(),
+        result.outcome
+    );
+
+    // 2. Restart kinds
+    println!("\n2. Which exits are restarted:");
+    let shutdown = Shutdown::new();
+    let mut sup = Supervisor::new(policy(5), &shutdown);
+    sup.on_event(print_event);
+    let tries = Arc::new(AtomicU32::new(0));
+    let job_tries = Arc::clone(&tries);
+    sup.add("upload", Restart::Transient, move |_| {
+        match job_tries.fetch_add(1, Ordering::SeqCst) {
+            0 => Err("collector refused the connection".into()),
+            _ => Ok(()),
+        }
+    });
+    sup.add("selftest", Restart::Temporary, |_| {
+        Err("humidity sensor missing".into())
+    });
+    let result = sup.run();
+    for worker in &result.workers {
+        println!("   {:<8} started {} time(s)", worker.name, worker.starts);
+    }
+    println!(
+        "   outcome: {:?}, shutdown triggered: {}",
+        result.outcome,
+        shutdown.is_triggered()
+    );
+
+    // 3. Escalation
+    println!("\n3. A worker that never recovers (limit: 3 restarts per second):");
+    let shutdown = Shutdown::new();
+    let mut sup = Supervisor::new(policy(3), &shutdown);
+    sup.on_event(print_event);
+    sup.add("decoder", Restart::Permanent, |_| {
+        panic!("unsupported frame version 7")
+    });
+    sup.add("uplink", Restart::Permanent, |token| {
+        token.wait();
+        Ok(())
+    });
+    let result = sup.run();
+    println!(
+        "   outcome: {:?}, shutdown reason: {:?}",
+        result.outcome,
+        shutdown.token().reason()
+    );
+    println!("   (main would now exit with a failure code for the service manager)");
+
+    // 4. A tokio task
+    println!("\n4. The same policy for a tokio task:");
+    tasks();
+
+    println!("\n=== End of Supervisor Examples ===");
+}
+
+#[cfg(feature = "tokio")]
+fn tasks() {
+    use supervisor::supervise_task;
+
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            println!("   cannot start tokio: {}", e);
+            return;
+        }
+    };
+    let shutdown = Shutdown::new();
+    let runs = Arc::new(AtomicU32::new(0));
+    let result = runtime.block_on(async {
+        let stopper = shutdown.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            stopper.trigger(Reason::Requested("demo over"));
+        });
+        supervise_task(
+            "poller",
+            Restart::Permanent,
+            &policy(5),
+            &shutdown,
+            |token| {
+                let run = runs.fetch_add(1, Ordering::SeqCst) + 1;
+                async move {
+                    if run <= 2 {
+                        panic!("poll {} hit a malformed reply", run);
+                    }
+                    token.cancelled().await;
+                    Ok(())
+                }
+            },
+        )
+        .await
+    });
+    for e in &result.events {
+        print_event(e);
+    }
+}
+
+#[cfg(not(feature = "tokio"))]
+fn tasks() {
+    println!("   (built without the tokio feature)");
+}
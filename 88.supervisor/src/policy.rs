@@ -0,0 +1,179 @@
+// When a worker is restarted, and when the supervisor gives up
+//
+// Each worker has a `Restart` kind saying which exits are worth a
+// restart. Each restart waits for a backoff delay, so a worker that
+// fails at once doesn't spin. All restarts count against one limit:
+// more than `max_restarts` within `window` means restarting isn't
+// helping, and the supervisor escalates.
+//
+//   window = 10 s, max_restarts = 3
+//
+//   ──x────x──────x──────────────────x───────x──▶ time
+//     1    2      3                  1       2      (restarts in the window)
+//                                                   fine
+//   ──x──x──x──x──▶
+//     1  2  3  4                                    escalate
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Restart {
+    // Always restarted, even after returning Ok: a loop that should run
+    // until shutdown
+    Permanent,
+    // Restarted only after an error or a panic: a job that may finish
+    Transient,
+    // Never restarted
+    Temporary,
+}
+
+impl Restart {
+    pub fn restarts(self, exit: &Exit) -> bool {
+        match self {
+            Restart::Permanent => true,
+            Restart::Transient => exit.is_failure(),
+            Restart::Temporary => false,
+        }
+    }
+}
+
+// How one run of a worker ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Exit {
+    Normal,
+    Error(String),
+    // With the panic message, if it was a string
+    Panic(String),
+}
+
+impl Exit {
+    pub fn is_failure(&self) -> bool {
+        !matches!(self, Exit::Normal)
+    }
+}
+
+impl fmt::Display for Exit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Exit::Normal => write!(f, "exited normally"),
+            Exit::Error(e) => write!(f, "failed: {}", e),
+            Exit::Panic(message) => write!(f, "panicked: {}", message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Policy {
+    pub max_restarts: u32,
+    pub window: Duration,
+    // Delay before each restart of a worker; `max_attempts` and
+    // `deadline` are not used. A worker that stays up for a whole
+    // `window` starts again from `base`.
+    pub backoff: retry::Policy,
+}
+
+impl Default for Policy {
+    fn default() -> Policy {
+        Policy {
+            max_restarts: 5,
+            window: Duration::from_secs(60),
+            backoff: retry::Policy {
+                base: Duration::from_millis(100),
+                max_delay: Duration::from_secs(5),
+                jitter: retry::Jitter::Equal,
+                ..retry::Policy::default()
+            },
+        }
+    }
+}
+
+// The restarts within the last `window`
+#[derive(Debug)]
+pub(crate) struct Intensity {
+    max_restarts: u32,
+    window: Duration,
+    recent: VecDeque<Instant>,
+}
+
+impl Intensity {
+    pub(crate) fn new(policy: &Policy) -> Intensity {
+        Intensity {
+            max_restarts: policy.max_restarts,
+            window: policy.window,
+            recent: VecDeque::new(),
+        }
+    }
+
+    // Records a restart at `now`; false if that is one too many
+    pub(crate) fn allow(&mut self, now: Instant) -> bool {
+        while self
+            .recent
+            .front()
+            .is_some_and(|t| now.saturating_duration_since(*t) >= self.window)
+        {
+            self.recent.pop_front();
+        }
+        if self.recent.len() >= self.max_restarts as usize {
+            return false;
+        }
+        self.recent.push_back(now);
+        true
+    }
+}
+
+// What a supervisor does after a worker exits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Restart(Duration),
+    // Not restarted: its `Restart` kind says so, or a shutdown is under way
+    Stop,
+    // One restart too many; the whole process shuts down
+    Escalate,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Restart(after) => write!(f, "restart in {:?}", after),
+            Action::Stop => write!(f, "stop"),
+            Action::Escalate => write!(f, "escalate"),
+        }
+    }
+}
+
+// The decision shared by the thread and the task supervisors. `uptime`
+// is how long the run that just ended lasted.
+pub(crate) fn decide(
+    restart: Restart,
+    exit: &Exit,
+    stopping: bool,
+    uptime: Duration,
+    intensity: &mut Intensity,
+    backoff: &mut retry::Backoff,
+    now: Instant,
+) -> Action {
+    if stopping || !restart.restarts(exit) {
+        return Action::Stop;
+    }
+    if !intensity.allow(now) {
+        return Action::Escalate;
+    }
+    if uptime >= intensity.window {
+        backoff.reset();
+    }
+    Action::Restart(backoff.next_delay())
+}
+
+// `panic!` payloads are a `&str` or a `String`, unless someone used
+// `panic_any`
+pub(crate) fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "(not a string)".to_string()
+    }
+}
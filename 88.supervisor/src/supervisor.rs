@@ -0,0 +1,295 @@
+// A supervisor for worker threads
+//
+//   Supervisor::run ◀──(index, Exit)── worker threads, each wrapped in
+//        │                             catch_unwind
+//        ├─ Restart(delay) ──▶ spawn it again once the delay is up
+//        ├─ Stop           ──▶ leave it
+//        └─ Escalate       ──▶ Shutdown::trigger, stop the others
+//
+// A worker is a closure run on its own thread with a `Token`. It returns
+// `Ok(())`, returns an error, or panics; the wrapper turns each into an
+// `Exit` and sends it to the supervisor, which applies the policy. The
+// closure is kept, so a restart runs it again from the start with fresh
+// local state.
+//
+// `run` blocks until every worker has stopped for good, the shutdown is
+// triggered (by a signal, or by the supervisor escalating), and then
+// gives the running workers `grace` to notice their token.
+
+use crate::policy::{decide, panic_message, Action, Exit, Intensity, Policy, Restart};
+use retry::Backoff;
+use shutdown::{join_within, Reason, Shutdown, Token};
+use std::error::Error;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+pub type WorkerError = Box<dyn Error + Send + Sync>;
+
+type Work = Arc<dyn Fn(&Token) -> Result<(), WorkerError> + Send + Sync>;
+type Hook = Box<dyn FnMut(&Event)>;
+
+// The reason given to the shutdown when the supervisor escalates
+pub const RESTART_LIMIT: &str = "restart limit exceeded";
+
+// How often `run` looks at the token while no worker exits
+const POLL: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    // Since `run` started
+    pub at: Duration,
+    pub worker: String,
+    pub exit: Exit,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    // Every worker stopped by itself, none is due a restart
+    Finished,
+    // Someone else triggered the shutdown
+    ShutDown(Reason),
+    // This worker used up the restart limit
+    Escalated(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerStats {
+    pub name: String,
+    pub starts: u32,
+    pub failures: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub outcome: Outcome,
+    pub events: Vec<Event>,
+    pub workers: Vec<WorkerStats>,
+    // Workers still running when `grace` ran out
+    pub stuck: Vec<String>,
+}
+
+impl Report {
+    pub fn restarts(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|e| matches!(e.action, Action::Restart(_)))
+            .count()
+    }
+}
+
+struct Worker {
+    name: String,
+    restart: Restart,
+    work: Work,
+    backoff: Backoff,
+    handle: Option<JoinHandle<()>>,
+    started: Instant,
+    starts: u32,
+    failures: u32,
+}
+
+pub struct Supervisor {
+    policy: Policy,
+    shutdown: Shutdown,
+    grace: Duration,
+    workers: Vec<Worker>,
+    on_event: Option<Hook>,
+    tx: Sender<(usize, Exit)>,
+    rx: Receiver<(usize, Exit)>,
+}
+
+impl Supervisor {
+    // Escalating triggers `shutdown`; pass the one the signal handler
+    // triggers, so both end the same way
+    pub fn new(policy: Policy, shutdown: &Shutdown) -> Supervisor {
+        let (tx, rx) = mpsc::channel();
+        Supervisor {
+            policy,
+            shutdown: shutdown.clone(),
+            grace: Duration::from_secs(5),
+            workers: Vec::new(),
+            on_event: None,
+            tx,
+            rx,
+        }
+    }
+
+    // How long `run` waits for workers to stop once the shutdown is
+    // triggered
+    pub fn with_grace(mut self, grace: Duration) -> Supervisor {
+        self.grace = grace;
+        self
+    }
+
+    // Called for each event as it happens, e.g. to log it
+    pub fn on_event(&mut self, f: impl FnMut(&Event) + 'static) {
+        self.on_event = Some(Box::new(f));
+    }
+
+    pub fn add(
+        &mut self,
+        name: &str,
+        restart: Restart,
+        work: impl Fn(&Token) -> Result<(), WorkerError> + Send + Sync + 'static,
+    ) {
+        self.workers.push(Worker {
+            name: name.to_string(),
+            restart,
+            work: Arc::new(work),
+            backoff: Backoff::new(&self.policy.backoff),
+            handle: None,
+            started: Instant::now(),
+            starts: 0,
+            failures: 0,
+        });
+    }
+
+    fn start(&mut self, index: usize) {
+        let worker = &mut self.workers[index];
+        let (work, token, tx) = (
+            Arc::clone(&worker.work),
+            self.shutdown.token(),
+            self.tx.clone(),
+        );
+        worker.started = Instant::now();
+        worker.starts += 1;
+        let spawned = thread::Builder::new()
+            .name(worker.name.clone())
+            .spawn(move || {
+                // Whatever the closure had on its stack is dropped with the
+                // unwind, and the next run starts afresh. Only state it
+                // shares with others can be left half-updated, and a
+                // `Mutex` around it is poisoned to say so.
+                let exit = match panic::catch_unwind(AssertUnwindSafe(|| work(&token))) {
+                    Ok(Ok(())) => Exit::Normal,
+                    Ok(Err(e)) => Exit::Error(e.to_string()),
+                    Err(payload) => Exit::Panic(panic_message(payload.as_ref())),
+                };
+                let _ = tx.send((index, exit));
+            });
+        match spawned {
+            Ok(handle) => worker.handle = Some(handle),
+            // Counts as a failed run, so the policy decides what follows
+            Err(e) => {
+                let _ = self
+                    .tx
+                    .send((index, Exit::Error(format!("cannot spawn: {}", e))));
+            }
+        }
+    }
+
+    pub fn run(mut self) -> Report {
+        let began = Instant::now();
+        let token = self.shutdown.token();
+        let mut intensity = Intensity::new(&self.policy);
+        let mut events = Vec::new();
+        let mut due: Vec<(Instant, usize)> = Vec::new();
+        let mut running = self.workers.len();
+        let mut escalated = None;
+        for index in 0..self.workers.len() {
+            self.start(index);
+        }
+
+        while !token.is_cancelled() && (running > 0 || !due.is_empty()) {
+            let now = Instant::now();
+            let (ready, waiting): (Vec<_>, Vec<_>) = due.into_iter().partition(|(t, _)| *t <= now);
+            due = waiting;
+            for (_, index) in ready {
+                self.start(index);
+                running += 1;
+            }
+            let next = due.iter().map(|(t, _)| *t).min();
+            let wait = next.map_or(POLL, |t| t.saturating_duration_since(now).min(POLL));
+            let (index, exit) = match self.rx.recv_timeout(wait) {
+                Ok(exited) => exited,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => {
+                    unreachable!("the supervisor holds a sender")
+                }
+            };
+            running -= 1;
+            let now = Instant::now();
+            let worker = &mut self.workers[index];
+            if let Some(handle) = worker.handle.take() {
+                let _ = handle.join();
+            }
+            if exit.is_failure() {
+                worker.failures += 1;
+            }
+            let action = decide(
+                worker.restart,
+                &exit,
+                token.is_cancelled(),
+                now.saturating_duration_since(worker.started),
+                &mut intensity,
+                &mut worker.backoff,
+                now,
+            );
+            match action {
+                Action::Restart(delay) => due.push((now + delay, index)),
+                Action::Stop => {}
+                Action::Escalate => {
+                    escalated = Some(worker.name.clone());
+                    self.shutdown.trigger(Reason::Requested(RESTART_LIMIT));
+                }
+            }
+            let event = Event {
+                at: now - began,
+                worker: worker.name.clone(),
+                exit,
+                action,
+            };
+            if let Some(f) = &mut self.on_event {
+                f(&event);
+            }
+            events.push(event);
+        }
+
+        // Stopping: whoever is still running has its token cancelled
+        let outcome = match (escalated, token.reason()) {
+            (Some(worker), _) => Outcome::Escalated(worker),
+            (None, Some(reason)) => Outcome::ShutDown(reason),
+            (None, None) => Outcome::Finished,
+        };
+        let handles = self
+            .workers
+            .iter_mut()
+            .filter_map(|w| w.handle.take())
+            .collect();
+        let stuck = join_within(handles, self.grace);
+        while let Ok((index, exit)) = self.rx.try_recv() {
+            let worker = &mut self.workers[index];
+            if exit.is_failure() {
+                worker.failures += 1;
+            }
+            let event = Event {
+                at: began.elapsed(),
+                worker: worker.name.clone(),
+                exit,
+                action: Action::Stop,
+            };
+            if let Some(f) = &mut self.on_event {
+                f(&event);
+            }
+            events.push(event);
+        }
+        Report {
+            outcome,
+            events,
+            workers: self
+                .workers
+                .iter()
+                .map(|w| WorkerStats {
+                    name: w.name.clone(),
+                    starts: w.starts,
+                    failures: w.failures,
+                })
+                .collect(),
+            stuck,
+        }
+    }
+}
@@ -0,0 +1,95 @@
+// The same policy for one tokio task
+//
+// `make` builds the task's future for each run. tokio already catches a
+// panicking task: awaiting its `JoinHandle` gives a `JoinError` whose
+// `is_panic()` is true, so no `catch_unwind` is needed. The backoff
+// sleep is cut short by the shutdown, as is the run itself.
+
+use crate::policy::{decide, panic_message, Action, Exit, Intensity, Policy, Restart};
+use crate::supervisor::{Event, Outcome, Report, WorkerError, WorkerStats, RESTART_LIMIT};
+use retry::Backoff;
+use shutdown::{Reason, Shutdown, Token};
+use std::future::Future;
+use std::time::Instant;
+
+pub async fn supervise_task<F, Fut>(
+    name: &str,
+    restart: Restart,
+    policy: &Policy,
+    shutdown: &Shutdown,
+    mut make: F,
+) -> Report
+where
+    F: FnMut(Token) -> Fut,
+    Fut: Future<Output = Result<(), WorkerError>> + Send + 'static,
+{
+    let began = Instant::now();
+    let token = shutdown.token();
+    let mut intensity = Intensity::new(policy);
+    let mut backoff = Backoff::new(&policy.backoff);
+    let mut stats = WorkerStats {
+        name: name.to_string(),
+        starts: 0,
+        failures: 0,
+    };
+    let mut events = Vec::new();
+    let mut escalated = false;
+
+    loop {
+        let started = Instant::now();
+        stats.starts += 1;
+        // The task sees the token too; aborting it instead would skip
+        // its own cleanup
+        let exit = match tokio::spawn(make(token.clone())).await {
+            Ok(Ok(())) => Exit::Normal,
+            Ok(Err(e)) => Exit::Error(e.to_string()),
+            Err(e) if e.is_panic() => Exit::Panic(panic_message(e.into_panic().as_ref())),
+            Err(e) => Exit::Error(e.to_string()),
+        };
+        let now = Instant::now();
+        if exit.is_failure() {
+            stats.failures += 1;
+        }
+        let action = decide(
+            restart,
+            &exit,
+            token.is_cancelled(),
+            now.saturating_duration_since(started),
+            &mut intensity,
+            &mut backoff,
+            now,
+        );
+        events.push(Event {
+            at: now - began,
+            worker: name.to_string(),
+            exit,
+            action,
+        });
+        match action {
+            Action::Restart(delay) => {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = tokio::time::sleep(delay) => {}
+                }
+            }
+            Action::Stop => break,
+            Action::Escalate => {
+                escalated = true;
+                shutdown.trigger(Reason::Requested(RESTART_LIMIT));
+                break;
+            }
+        }
+    }
+
+    let outcome = match (escalated, token.reason()) {
+        (true, _) => Outcome::Escalated(name.to_string()),
+        (false, Some(reason)) => Outcome::ShutDown(reason),
+        (false, None) => Outcome::Finished,
+    };
+    Report {
+        outcome,
+        events,
+        workers: vec![stats],
+        stuck: Vec::new(),
+    }
+}
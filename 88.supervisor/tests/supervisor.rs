@@ -0,0 +1,229 @@
+// Thread workers that crash on purpose. Their panic messages appear on
+// stderr; the panic hook is left alone so a failing test still shows its
+// own.
+
+use shutdown::{Reason, Shutdown};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use supervisor::{Action, Exit, Outcome, Policy, Restart, Supervisor, RESTART_LIMIT};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn policy(max_restarts: u32, window: Duration, base: Duration, multiplier: f64) -> Policy {
+    Policy {
+        max_restarts,
+        window,
+        backoff: retry::Policy {
+            base,
+            multiplier,
+            max_delay: ms(1000),
+            jitter: retry::Jitter::None,
+            ..retry::Policy::default()
+        },
+    }
+}
+
+// Counts runs across restarts; returns the run number, from 1
+fn counter() -> (Arc<AtomicU32>, impl Fn() -> u32 + Send + Sync + 'static) {
+    let runs = Arc::new(AtomicU32::new(0));
+    let next = Arc::clone(&runs);
+    (runs, move || next.fetch_add(1, Ordering::SeqCst) + 1)
+}
+
+fn delays(events: &[supervisor::Event]) -> Vec<Duration> {
+    events
+        .iter()
+        .filter_map(|e| match e.action {
+            Action::Restart(after) => Some(after),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn panics_and_errors_are_restarted_with_growing_delays() {
+    let shutdown = Shutdown::new();
+    let mut sup = Supervisor::new(policy(10, ms(5000), ms(5), 2.0), &shutdown);
+    let (_, run) = counter();
+    sup.add("flaky", Restart::Transient, move |_| match run() {
+        1 => panic!("crash {}", 1),
+        2 => Err("bus error".into()),
+        3 => panic!("static message"),
+        _ => Ok(()),
+    });
+    let report = sup.run();
+
+    let exits: Vec<&Exit> = report.events.iter().map(|e| &e.exit).collect();
+    assert_eq!(
+        exits,
+        [
+            &Exit::Panic("crash 1".into()),
+            &Exit::Error("bus error".into()),
+            &Exit::Panic("static message".into()),
+            &Exit::Normal,
+        ]
+    );
+    assert_eq!(delays(&report.events), [ms(5), ms(10), ms(20)]);
+    assert_eq!(report.outcome, Outcome::Finished);
+    assert_eq!(report.workers[0].starts, 4);
+    assert_eq!(report.workers[0].failures, 3);
+    assert!(!shutdown.is_triggered());
+}
+
+#[test]
+fn the_restart_kind_decides_which_exits_restart() {
+    let shutdown = Shutdown::new();
+    let mut sup = Supervisor::new(policy(10, ms(5000), ms(1), 1.0), &shutdown);
+    let (_, permanent) = counter();
+    // Returns Ok every time, and is restarted anyway until the shutdown
+    let stopper = shutdown.clone();
+    sup.add("permanent", Restart::Permanent, move |_| {
+        if permanent() == 3 {
+            stopper.trigger(Reason::Requested("test"));
+        }
+        Ok(())
+    });
+    sup.add("transient", Restart::Transient, |_| Ok(()));
+    sup.add("temporary", Restart::Temporary, |_| panic!("never again"));
+    let report = sup.run();
+
+    let starts: Vec<(&str, u32)> = report
+        .workers
+        .iter()
+        .map(|w| (w.name.as_str(), w.starts))
+        .collect();
+    assert_eq!(
+        starts,
+        [("permanent", 3), ("transient", 1), ("temporary", 1)]
+    );
+    assert_eq!(report.outcome, Outcome::ShutDown(Reason::Requested("test")));
+}
+
+#[test]
+fn jobs_that_finish_end_the_run_without_a_shutdown() {
+    let shutdown = Shutdown::new();
+    let mut sup = Supervisor::new(policy(5, ms(5000), ms(1), 1.0), &shutdown);
+    let (_, upload) = counter();
+    sup.add("upload", Restart::Transient, move |_| match upload() {
+        1 => Err("collector refused the connection".into()),
+        _ => Ok(()),
+    });
+    sup.add("selftest", Restart::Temporary, |_| {
+        Err("humidity sensor missing".into())
+    });
+    let report = sup.run();
+
+    let starts: Vec<(&str, u32)> = report
+        .workers
+        .iter()
+        .map(|w| (w.name.as_str(), w.starts))
+        .collect();
+    assert_eq!(starts, [("upload", 2), ("selftest", 1)]);
+    assert_eq!(report.outcome, Outcome::Finished);
+    assert!(!shutdown.is_triggered());
+}
+
+#[test]
+fn too_many_restarts_in_the_window_escalate_and_stop_the_rest() {
+    let shutdown = Shutdown::new();
+    let mut sup = Supervisor::new(policy(3, ms(5000), ms(1), 1.0), &shutdown);
+    sup.add("broken", Restart::Permanent, |_| panic!("always"));
+    let (healthy_runs, run) = counter();
+    sup.add("healthy", Restart::Permanent, move |token| {
+        run();
+        token.wait();
+        Ok(())
+    });
+    let report = sup.run();
+
+    assert_eq!(report.outcome, Outcome::Escalated("broken".into()));
+    assert_eq!(
+        shutdown.token().reason(),
+        Some(Reason::Requested(RESTART_LIMIT))
+    );
+    // Three restarts allowed, the fourth crash escalates
+    let broken: Vec<Action> = report
+        .events
+        .iter()
+        .filter(|e| e.worker == "broken")
+        .map(|e| e.action)
+        .collect();
+    assert_eq!(broken.len(), 4);
+    assert_eq!(broken[3], Action::Escalate);
+    // The healthy worker saw its token and was not restarted
+    assert_eq!(healthy_runs.load(Ordering::SeqCst), 1);
+    let last = report.events.last().unwrap();
+    assert_eq!(
+        (last.worker.as_str(), &last.exit, last.action),
+        ("healthy", &Exit::Normal, Action::Stop)
+    );
+    assert!(report.stuck.is_empty());
+}
+
+#[test]
+fn restarts_spread_out_over_time_never_escalate() {
+    let shutdown = Shutdown::new();
+    // At most 2 restarts per 30 ms, with 40 ms between them
+    let mut sup = Supervisor::new(policy(2, ms(30), ms(40), 1.0), &shutdown);
+    let (_, run) = counter();
+    sup.add("slow-crasher", Restart::Transient, move |_| {
+        if run() < 6 {
+            panic!("again");
+        }
+        Ok(())
+    });
+    let report = sup.run();
+    assert_eq!(report.outcome, Outcome::Finished);
+    assert_eq!(report.restarts(), 5);
+}
+
+#[test]
+fn a_run_lasting_a_whole_window_resets_the_backoff() {
+    let shutdown = Shutdown::new();
+    let mut sup = Supervisor::new(policy(10, ms(50), ms(5), 2.0), &shutdown);
+    let (_, run) = counter();
+    sup.add("recovering", Restart::Transient, move |_| match run() {
+        1 | 2 => panic!("early crash"),
+        3 => {
+            thread::sleep(ms(60));
+            panic!("crash after a long healthy run")
+        }
+        _ => Ok(()),
+    });
+    let report = sup.run();
+    assert_eq!(delays(&report.events), [ms(5), ms(10), ms(5)]);
+}
+
+#[test]
+fn a_shutdown_during_the_backoff_cancels_the_restart() {
+    let shutdown = Shutdown::new();
+    let mut sup = Supervisor::new(policy(10, ms(5000), ms(2000), 1.0), &shutdown);
+    sup.add("crasher", Restart::Permanent, |_| panic!("once"));
+    let stopper = shutdown.clone();
+    thread::spawn(move || {
+        thread::sleep(ms(50));
+        stopper.signal();
+    });
+    let started = Instant::now();
+    let report = sup.run();
+    assert!(started.elapsed() < ms(1000), "did not wait out the backoff");
+    assert_eq!(report.outcome, Outcome::ShutDown(Reason::Signal));
+    assert_eq!(report.workers[0].starts, 1);
+}
+
+#[test]
+fn a_worker_ignoring_its_token_is_reported_stuck() {
+    let shutdown = Shutdown::new();
+    let mut sup = Supervisor::new(policy(10, ms(5000), ms(1), 1.0), &shutdown).with_grace(ms(50));
+    sup.add("deaf", Restart::Permanent, |_| {
+        thread::sleep(ms(500));
+        Ok(())
+    });
+    shutdown.trigger(Reason::Requested("test"));
+    let report = sup.run();
+    assert_eq!(report.stuck, ["deaf"]);
+}
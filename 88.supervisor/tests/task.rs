@@ -0,0 +1,99 @@
+// Tokio tasks that crash on purpose
+
+#![cfg(feature = "tokio")]
+
+use shutdown::{Reason, Shutdown};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use supervisor::{supervise_task, Action, Exit, Outcome, Policy, Restart, RESTART_LIMIT};
+
+fn policy(max_restarts: u32, base: Duration) -> Policy {
+    Policy {
+        max_restarts,
+        window: Duration::from_secs(5),
+        backoff: retry::Policy {
+            base,
+            multiplier: 2.0,
+            jitter: retry::Jitter::None,
+            ..retry::Policy::default()
+        },
+    }
+}
+
+#[tokio::test]
+async fn a_panicking_task_is_restarted_until_it_succeeds() {
+    let shutdown = Shutdown::new();
+    let runs = Arc::new(AtomicU32::new(0));
+    let report = supervise_task(
+        "poller",
+        Restart::Transient,
+        &policy(5, Duration::from_millis(2)),
+        &shutdown,
+        |_| {
+            let run = runs.fetch_add(1, Ordering::SeqCst) + 1;
+            async move {
+                match run {
+                    1 => panic!("malformed reply"),
+                    2 => Err("timed out".into()),
+                    _ => Ok(()),
+                }
+            }
+        },
+    )
+    .await;
+
+    let exits: Vec<&Exit> = report.events.iter().map(|e| &e.exit).collect();
+    assert_eq!(
+        exits,
+        [
+            &Exit::Panic("malformed reply".into()),
+            &Exit::Error("timed out".into()),
+            &Exit::Normal
+        ]
+    );
+    assert_eq!(report.outcome, Outcome::Finished);
+    assert_eq!(report.restarts(), 2);
+}
+
+#[tokio::test]
+async fn a_task_that_keeps_crashing_escalates() {
+    let shutdown = Shutdown::new();
+    let report = supervise_task(
+        "decoder",
+        Restart::Permanent,
+        &policy(2, Duration::from_millis(1)),
+        &shutdown,
+        |_| async { panic!("always") },
+    )
+    .await;
+    assert_eq!(report.outcome, Outcome::Escalated("decoder".into()));
+    assert_eq!(report.events.last().unwrap().action, Action::Escalate);
+    assert_eq!(report.workers[0].starts, 3);
+    assert_eq!(
+        shutdown.token().reason(),
+        Some(Reason::Requested(RESTART_LIMIT))
+    );
+}
+
+#[tokio::test]
+async fn the_shutdown_cuts_the_backoff_short() {
+    let shutdown = Shutdown::new();
+    let stopper = shutdown.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        stopper.signal();
+    });
+    let started = Instant::now();
+    let report = supervise_task(
+        "crasher",
+        Restart::Permanent,
+        &policy(5, Duration::from_secs(10)),
+        &shutdown,
+        |_| async { Err("down".into()) },
+    )
+    .await;
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(report.outcome, Outcome::ShutDown(Reason::Signal));
+    assert_eq!(report.workers[0].starts, 1);
+}
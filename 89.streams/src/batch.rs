@@ -0,0 +1,109 @@
+// Batching a stream: a full chunk, or whatever arrived within a deadline
+//
+//   items  ─a─b─c─d─e─f─g─h─────────i──────────────────────j──▶
+//   chunks              [a..h]                 [i]        [j]   (end)
+//                       full: 8     timeout after the first item
+//
+// A batch goes out when it holds `max` items, or `timeout` after its
+// first item arrived, whichever comes first. A fast source fills batches
+// and never waits; a slow one still gets its readings out within
+// `timeout`. At the end of the input the partial batch is emitted.
+// tokio-stream has the same combinator as `StreamExt::chunks_timeout`;
+// this one is written out to show what implementing `Stream` involves.
+//
+// `poll_next` is called by whoever awaits the next chunk. It must never
+// block: it returns `Poll::Pending` after making sure something (the
+// inner stream or the timer) will wake the task when it is worth calling
+// again.
+
+use futures::stream::Stream;
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::{sleep_until, Instant, Sleep};
+
+pin_project! {
+    pub struct ChunksTimeout<S: Stream> {
+        #[pin]
+        inner: S,
+        // Armed when the first item of a chunk arrives
+        #[pin]
+        deadline: Sleep,
+        armed: bool,
+        items: Vec<S::Item>,
+        max: usize,
+        timeout: Duration,
+        done: bool,
+    }
+}
+
+impl<S: Stream> ChunksTimeout<S> {
+    pub fn new(inner: S, max: usize, timeout: Duration) -> ChunksTimeout<S> {
+        let max = max.max(1);
+        ChunksTimeout {
+            inner,
+            deadline: sleep_until(Instant::now()),
+            armed: false,
+            items: Vec::with_capacity(max),
+            max,
+            timeout,
+            done: false,
+        }
+    }
+}
+
+impl<S: Stream> Stream for ChunksTimeout<S> {
+    type Item = Vec<S::Item>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        if *this.done {
+            return Poll::Ready(None);
+        }
+        // Take everything the inner stream has ready, up to a full chunk
+        loop {
+            match this.inner.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    if this.items.is_empty() {
+                        this.deadline.as_mut().reset(Instant::now() + *this.timeout);
+                        *this.armed = true;
+                    }
+                    this.items.push(item);
+                    if this.items.len() >= *this.max {
+                        *this.armed = false;
+                        let full = Vec::with_capacity(*this.max);
+                        return Poll::Ready(Some(std::mem::replace(this.items, full)));
+                    }
+                }
+                Poll::Ready(None) => {
+                    *this.done = true;
+                    if this.items.is_empty() {
+                        return Poll::Ready(None);
+                    }
+                    return Poll::Ready(Some(std::mem::take(this.items)));
+                }
+                // The inner stream will wake us for the next item
+                Poll::Pending => break,
+            }
+        }
+        // Nothing more yet: a partial chunk goes out at its deadline. Polling
+        // the timer registers the wakeup for it.
+        if *this.armed && this.deadline.poll(cx).is_ready() {
+            *this.armed = false;
+            let partial = Vec::with_capacity(*this.max);
+            return Poll::Ready(Some(std::mem::replace(this.items, partial)));
+        }
+        Poll::Pending
+    }
+}
+
+// `stream.chunks_timeout(max, timeout)` in the style of `StreamExt`
+pub trait ChunksTimeoutExt: Stream + Sized {
+    fn chunks_timeout(self, max: usize, timeout: Duration) -> ChunksTimeout<Self> {
+        ChunksTimeout::new(self, max, timeout)
+    }
+}
+
+impl<S: Stream> ChunksTimeoutExt for S {}
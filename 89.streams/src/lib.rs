@@ -0,0 +1,10 @@
+// Async streams: the simulated sensors as `Stream<Item = Reading>`, a
+// batching combinator, and the gateway path written as one pipeline
+
+pub mod batch;
+pub mod path;
+pub mod source;
+
+pub use batch::{ChunksTimeout, ChunksTimeoutExt};
+pub use path::{forward, plausible, Forwarded};
+pub use source::{glitchy, hub, node, EPOCH_MS};
@@ -0,0 +1,159 @@
+use futures::stream::{self, StreamExt};
+use gateway::sensors::Reading;
+use gateway::uplink::Batch;
+use gateway::Config;
+use shutdown::{Reason, Shutdown};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use streams::{forward, glitchy, node, plausible, ChunksTimeoutExt};
+use tokio::sync::mpsc;
+use tokio::time::{self, Instant};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn per_sensor(readings: &[Reading]) -> BTreeMap<u16, usize> {
+    let mut counts = BTreeMap::new();
+    for r in readings {
+        *counts.entry(r.sensor_id).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn main() {
+    println!("=== Async Streams Examples ===\n");
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("tokio runtime");
+    runtime.block_on(run());
+    println!("\n=== End of Async Streams Examples ===");
+}
+
+async fn run() {
+    // 1. A sensor node as a stream
+    println!("1. A sensor node as a stream:");
+    let started = Instant::now();
+    let first: Vec<Reading> = node(0, ms(20)).take(6).collect().await;
+    for r in &first {
+        println!(
+            "   t+{:>3} ms  sensor {} {:<12} {:>6.2}",
+            r.timestamp_ms - streams::EPOCH_MS,
+            r.sensor_id,
+            r.metric,
+            r.value
+        );
+    }
+    println!(
+        "   two polls took {} ms of waiting on the timer",
+        started.elapsed().as_millis()
+    );
+
+    // 2. map and filter
+    println!("\n2. map and filter:");
+    // Every 5th reading of node 3 is a bus error
+    let raw: Vec<Reading> = glitchy(3, ms(5), 5).take(30).collect().await;
+    let temperatures_f: Vec<f64> = stream::iter(raw.clone())
+        .filter(|r| futures::future::ready(plausible(r)))
+        .filter(|r| futures::future::ready(r.metric == "temperature"))
+        .map(|r| r.value * 9.0 / 5.0 + 32.0)
+        .collect()
+        .await;
+    let glitches = raw.iter().filter(|r| !plausible(r)).count();
+    println!("   30 readings, {} implausible (6553.5)", glitches);
+    println!(
+        "   temperatures in °F: {:.1?}",
+        &temperatures_f[..temperatures_f.len().min(5)]
+    );
+
+    // 3. Batching with chunks_timeout
+    println!("\n3. Batching with chunks_timeout (20 or 50 ms):");
+    let started = Instant::now();
+    let fast: Vec<(usize, Duration)> = node(0, ms(5))
+        .chunks_timeout(20, ms(50))
+        .take(3)
+        .map(|chunk| (chunk.len(), started.elapsed()))
+        .collect()
+        .await;
+    let started = Instant::now();
+    let slow: Vec<(usize, Duration)> = node(1, ms(200))
+        .chunks_timeout(20, ms(50))
+        .take(3)
+        .map(|chunk| (chunk.len(), started.elapsed()))
+        .collect()
+        .await;
+    for (name, chunks) in [("fast node (5 ms)", &fast), ("slow node (200 ms)", &slow)] {
+        let shown: Vec<String> = chunks
+            .iter()
+            .map(|(n, at)| format!("{} at {} ms", n, at.as_millis()))
+            .collect();
+        println!("   {:<20} {}", name, shown.join(", "));
+    }
+    // The first poll is immediate, then one every 200 ms plus the 50 ms wait
+
+    // 4. Merging nodes with select_all
+    println!("\n4. Merging nodes with select_all:");
+    let merged = stream::select_all([
+        node(0, ms(20)).boxed(),
+        node(1, ms(50)).boxed(),
+        glitchy(2, ms(40), 7).boxed(),
+    ]);
+    let readings: Vec<Reading> = merged.take_until(time::sleep(ms(400))).collect().await;
+    let counts = per_sensor(&readings);
+    for (id, n) in &counts {
+        println!("   sensor {}: {:>3} readings", id, n);
+    }
+    // Interleaved as the timers fire, not one node's readings after another's
+    let switches = readings
+        .windows(2)
+        .filter(|w| w[0].sensor_id != w[1].sensor_id)
+        .count();
+    println!("   the stream switched node {} times", switches);
+
+    // 5. The async gateway path
+    println!("\n5. The async gateway path:");
+    let config = Config::default();
+    let shutdown = Shutdown::new();
+    let token = shutdown.token();
+    let (tx, mut rx) = mpsc::channel::<Batch>(4);
+    // Stands in for the uplink: takes batches until the path closes the
+    // channel
+    let uplink = tokio::spawn(async move {
+        let mut batches = Vec::new();
+        while let Some(batch) = rx.recv().await {
+            batches.push(batch);
+        }
+        batches
+    });
+    let stopper = shutdown.clone();
+    tokio::spawn(async move {
+        time::sleep(ms(500)).await;
+        stopper.trigger(Reason::Requested("demo over"));
+    });
+    let sensors = stream::select_all([
+        node(0, ms(20)).boxed(),
+        node(1, ms(50)).boxed(),
+        glitchy(2, ms(40), 7).boxed(),
+    ]);
+    let stats = forward(sensors, &config, ms(100), &token, tx).await;
+    let batches = uplink.await.expect("uplink task");
+
+    println!(
+        "   {} readings, {} dropped, {} messages in {} batches of up to {}",
+        stats.readings, stats.dropped, stats.messages, stats.batches, config.uplink.batch_size
+    );
+    println!(
+        "   stopped: {}",
+        token.reason().map(|r| r.to_string()).unwrap_or_default()
+    );
+    let sizes: Vec<usize> = batches.iter().map(|b| b.messages.len()).collect();
+    println!(
+        "   batch sizes: {:?}, ids 1..={}",
+        sizes,
+        batches
+            .last()
+            .and_then(|b| b.messages.last())
+            .map_or(0, |m| m.id)
+    );
+}
@@ -0,0 +1,126 @@
+// The async gateway path: readings in, uplink batches out
+//
+//   Stream<Reading> ─take_until(shutdown)─filter(plausible)─map(EMA → message)
+//        ─chunks_timeout(batch_size, max_delay)─▶ Batch { seq } ─▶ mpsc ─▶ uplink
+//
+// The same steps as `Gateway::tick` and `Uplink::forward` in 16.gateway,
+// written as one pipeline of stream combinators. Nothing runs until the
+// final loop awaits the next batch: each `.await` pulls one chunk through
+// every stage, and a slow uplink (a full channel) holds the whole pipeline
+// back instead of piling up readings in between.
+//
+// Shutdown ends the input with `take_until`. The partial chunk is then
+// emitted by `chunks_timeout` at the end of the stream, so every reading
+// taken in before the shutdown is sent.
+
+use crate::batch::ChunksTimeoutExt;
+use crate::source::EPOCH_MS;
+use futures::future;
+use futures::stream::{Stream, StreamExt};
+use gateway::events::ReadingFiltered;
+use gateway::filter::{Ema, Filter};
+use gateway::sensors::Reading;
+use gateway::uplink::{Batch, UplinkMessage};
+use gateway::Config;
+use shutdown::Token;
+use std::collections::HashMap;
+use std::pin::pin;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Forwarded {
+    pub readings: u64,
+    pub dropped: u64,
+    pub messages: u64,
+    pub batches: u64,
+}
+
+// A reading outside what the sensor can measure is a bus error, not
+// weather: 0xFFFF from a node that did not answer, or a torn read
+pub fn plausible(r: &Reading) -> bool {
+    let range = match r.metric {
+        "temperature" => -40.0..=85.0,
+        "humidity" => 0.0..=100.0,
+        "battery" => 0.0..=5.0,
+        _ => return false,
+    };
+    range.contains(&r.value)
+}
+
+// Forwards `readings` as batches until the input ends, the token fires or
+// the receiver is dropped
+pub async fn forward<S>(
+    readings: S,
+    config: &Config,
+    max_delay: Duration,
+    token: &Token,
+    out: mpsc::Sender<Batch>,
+) -> Forwarded
+where
+    S: Stream<Item = Reading>,
+{
+    let mut stats = Forwarded::default();
+    let (mut seen, mut dropped) = (0, 0);
+    let alpha = config.filter.alpha;
+    let mut filters: HashMap<(u16, &'static str), Ema> = HashMap::new();
+    let mut next_id = 0;
+
+    // The combinators borrow the counters, so the pipeline lives in a
+    // block that ends before they are read
+    {
+        let chunks = readings
+            .take_until(token.cancelled())
+            .filter(|r| {
+                seen += 1;
+                let keep = plausible(r);
+                if !keep {
+                    dropped += 1;
+                }
+                future::ready(keep)
+            })
+            .map(|r| {
+                let value = filters
+                    .entry((r.sensor_id, r.metric))
+                    .or_insert_with(|| Ema::new(alpha))
+                    .update(r.value);
+                let event = ReadingFiltered {
+                    sensor_id: r.sensor_id,
+                    metric: r.metric,
+                    raw: r.value,
+                    value,
+                    t: r.timestamp_ms,
+                    elapsed_ms: r.timestamp_ms.saturating_sub(EPOCH_MS),
+                };
+                next_id += 1;
+                UplinkMessage {
+                    id: next_id,
+                    topic: event.topic(),
+                    payload: event.payload(),
+                }
+            })
+            .chunks_timeout(config.uplink.batch_size, max_delay);
+        // `Sleep` inside the batcher is !Unpin, so the stream is pinned to
+        // this stack frame before `next` can borrow it
+        let mut chunks = pin!(chunks);
+
+        while let Some(messages) = chunks.next().await {
+            let count = messages.len() as u64;
+            let batch = Batch {
+                gateway: config.gateway.id.clone(),
+                boot: EPOCH_MS,
+                seq: stats.batches + 1,
+                messages,
+            };
+            if out.send(batch).await.is_err() {
+                // The uplink is gone; what is left in the stream stays unsent
+                break;
+            }
+            stats.batches += 1;
+            stats.messages += count;
+        }
+    }
+    stats.readings = seen;
+    stats.dropped = dropped;
+    stats
+}
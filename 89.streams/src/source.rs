@@ -0,0 +1,65 @@
+// Sensors as streams
+//
+// The gateway polls its `SensorHub` from a loop. Here the same simulator
+// is wrapped as a `Stream<Item = Reading>`: an async iterator whose
+// consumer awaits the next reading, and which waits on a tokio timer in
+// between without blocking a thread.
+//
+//   stream::unfold(state, step)      step: state -> Future<Option<(item, state)>>
+//
+// `unfold` is the short way to write a stream: the step function ticks
+// the interval, polls the hub and hands back the readings plus the state
+// for the next step. `flatten` turns each poll's Vec into single
+// readings.
+
+use futures::stream::{self, Stream, StreamExt};
+use gateway::sensors::{Reading, SensorHub};
+use std::time::Duration;
+use tokio::time::{self, Instant, MissedTickBehavior};
+
+// Unix ms at which every demo run starts, so timestamps repeat
+pub const EPOCH_MS: u64 = 1_700_000_000_000;
+
+struct Poller {
+    hub: SensorHub,
+    tick: time::Interval,
+    started: Instant,
+}
+
+// Every node of a simulated hub, polled every `every`
+pub fn hub(nodes: u16, seed: u32, every: Duration) -> impl Stream<Item = Reading> {
+    let mut tick = time::interval(every);
+    // A slow consumer gets the next reading late rather than a burst of
+    // catch-up readings
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let poller = Poller {
+        hub: SensorHub::new(nodes, seed),
+        tick,
+        started: Instant::now(),
+    };
+    stream::unfold(poller, |mut p| async move {
+        p.tick.tick().await;
+        let elapsed = p.started.elapsed().as_millis() as u64;
+        let readings = p.hub.poll(elapsed, EPOCH_MS + elapsed);
+        Some((stream::iter(readings), p))
+    })
+    .flatten()
+}
+
+// One node on its own bus, with its own poll rate. The simulator models
+// a whole bus, so this runs one with `id + 1` nodes and keeps node `id`.
+pub fn node(id: u16, every: Duration) -> impl Stream<Item = Reading> {
+    hub(id + 1, 0x5EED + id as u32, every)
+        .filter(move |r| futures::future::ready(r.sensor_id == id))
+}
+
+// A node with a loose connector: every `glitch_every`th reading is the
+// 0xFFFF an I2C read returns when nobody answers, scaled as a value
+pub fn glitchy(id: u16, every: Duration, glitch_every: usize) -> impl Stream<Item = Reading> {
+    node(id, every).enumerate().map(move |(i, mut r)| {
+        if (i + 1) % glitch_every == 0 {
+            r.value = 6553.5;
+        }
+        r
+    })
+}
@@ -0,0 +1,66 @@
+// `chunks_timeout` on a paused clock, fed items at chosen times
+
+use futures::stream::{self, Stream, StreamExt};
+use std::time::Duration;
+use streams::ChunksTimeoutExt;
+use tokio::time::{self, Instant};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+// Each item is the ms after the start at which it arrives
+fn arriving_at(at: &'static [u64]) -> impl Stream<Item = u64> {
+    let start = Instant::now();
+    stream::iter(at.iter().copied()).then(move |t| async move {
+        time::sleep_until(start + ms(t)).await;
+        t
+    })
+}
+
+// Each chunk with the ms after the start at which it came out
+async fn chunks<S: Stream<Item = Vec<u64>>>(s: S) -> Vec<(Vec<u64>, u64)> {
+    let start = Instant::now();
+    s.map(|chunk| (chunk, start.elapsed().as_millis() as u64))
+        .collect()
+        .await
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_full_chunk_goes_out_at_once() {
+    let got = chunks(arriving_at(&[0, 1, 2, 3, 4, 5]).chunks_timeout(3, ms(50))).await;
+    assert_eq!(got, [(vec![0, 1, 2], 2), (vec![3, 4, 5], 5)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_partial_chunk_goes_out_a_timeout_after_its_first_item() {
+    let got = chunks(arriving_at(&[0, 30, 200, 400, 410]).chunks_timeout(20, ms(50))).await;
+    assert_eq!(
+        got,
+        [
+            (vec![0, 30], 50),
+            (vec![200], 250),
+            // The input ends before the deadline: out at once
+            (vec![400, 410], 410),
+        ]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_full_chunk_disarms_the_deadline() {
+    let got = chunks(arriving_at(&[0, 10, 20, 100]).chunks_timeout(2, ms(50))).await;
+    // [20] starts a deadline of its own, 50 ms after it arrived
+    assert_eq!(got, [(vec![0, 10], 10), (vec![20], 70), (vec![100], 100)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn an_empty_input_gives_no_chunks() {
+    let got = chunks(arriving_at(&[]).chunks_timeout(4, ms(50))).await;
+    assert!(got.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_max_of_zero_is_one() {
+    let got = chunks(arriving_at(&[0, 5]).chunks_timeout(0, ms(50))).await;
+    assert_eq!(got, [(vec![0], 0), (vec![5], 5)]);
+}
@@ -0,0 +1,88 @@
+// `forward` end to end on a paused clock: three nodes in, uplink batches
+// out, stopped by a shutdown part-way through a batch
+
+use futures::stream::{self, StreamExt};
+use gateway::uplink::Batch;
+use gateway::Config;
+use shutdown::{Reason, Shutdown};
+use std::time::Duration;
+use streams::{forward, glitchy, node, Forwarded};
+use tokio::sync::mpsc;
+use tokio::time;
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+// Runs the demo's path for `run` and returns what the uplink received
+async fn run_path(run: Duration, capacity: usize) -> (Forwarded, Vec<Batch>, Config) {
+    let config = Config::default();
+    let shutdown = Shutdown::new();
+    let token = shutdown.token();
+    let (tx, mut rx) = mpsc::channel::<Batch>(capacity);
+    let uplink = tokio::spawn(async move {
+        let mut batches = Vec::new();
+        while let Some(batch) = rx.recv().await {
+            batches.push(batch);
+        }
+        batches
+    });
+    let stopper = shutdown.clone();
+    tokio::spawn(async move {
+        time::sleep(run).await;
+        stopper.trigger(Reason::Requested("test over"));
+    });
+    let sensors = stream::select_all([
+        node(0, ms(20)).boxed(),
+        node(1, ms(50)).boxed(),
+        glitchy(2, ms(40), 7).boxed(),
+    ]);
+    let stats = forward(sensors, &config, ms(100), &token, tx).await;
+    (stats, uplink.await.unwrap(), config)
+}
+
+#[tokio::test(start_paused = true)]
+async fn every_plausible_reading_reaches_the_uplink() {
+    let (stats, batches, config) = run_path(ms(500), 4).await;
+    assert!(stats.dropped > 0);
+    assert_eq!(stats.messages, stats.readings - stats.dropped);
+    assert_eq!(stats.batches, batches.len() as u64);
+
+    let ids: Vec<u64> = batches
+        .iter()
+        .flat_map(|b| b.messages.iter().map(|m| m.id))
+        .collect();
+    assert!(ids.iter().copied().eq(1..=stats.messages));
+    let seqs: Vec<u64> = batches.iter().map(|b| b.seq).collect();
+    assert!(seqs.iter().copied().eq(1..=stats.batches));
+    // The 6553.5 glitches never reach a payload
+    assert!(batches
+        .iter()
+        .flat_map(|b| &b.messages)
+        .all(|m| m.payload["raw"].as_f64().is_some_and(|v| v < 1000.0)));
+    assert!(batches.iter().all(|b| b.gateway == config.gateway.id));
+}
+
+#[tokio::test(start_paused = true)]
+async fn only_the_batch_cut_by_the_shutdown_is_partial() {
+    let (_, batches, config) = run_path(ms(500), 4).await;
+    let (last, full) = batches.split_last().unwrap();
+    assert!(full
+        .iter()
+        .all(|b| b.messages.len() == config.uplink.batch_size));
+    assert!(!last.messages.is_empty());
+    assert!(last.messages.len() <= config.uplink.batch_size);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_dropped_receiver_stops_the_path() {
+    let config = Config::default();
+    let shutdown = Shutdown::new();
+    let (tx, rx) = mpsc::channel::<Batch>(1);
+    drop(rx);
+    let stats = forward(node(0, ms(1)), &config, ms(100), &shutdown.token(), tx).await;
+    assert_eq!(stats.batches, 0);
+    assert_eq!(stats.messages, 0);
+    assert!(stats.readings > 0);
+    assert!(!shutdown.is_triggered());
+}
@@ -0,0 +1,84 @@
+// The sensor streams on a paused clock: tokio jumps straight to the next
+// timer, so every poll lands on an exact millisecond.
+
+use futures::stream::{self, StreamExt};
+use gateway::sensors::{Reading, METRICS};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use streams::{glitchy, hub, node, plausible, EPOCH_MS};
+use tokio::time::{self, Instant};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_node_yields_every_metric_once_per_interval() {
+    let started = Instant::now();
+    let first: Vec<Reading> = node(0, ms(20)).take(9).collect().await;
+    let metrics: Vec<&str> = first.iter().map(|r| r.metric).collect();
+    assert_eq!(metrics, [METRICS, METRICS, METRICS].concat());
+    let times: Vec<u64> = first.iter().map(|r| r.timestamp_ms - EPOCH_MS).collect();
+    assert_eq!(times, [0, 0, 0, 20, 20, 20, 40, 40, 40]);
+    assert!(first.iter().all(|r| r.sensor_id == 0));
+    assert_eq!(started.elapsed(), ms(40));
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_hub_polls_every_node_together() {
+    let readings: Vec<Reading> = hub(4, 7, ms(10)).take(24).collect().await;
+    let ids: Vec<u16> = readings.iter().step_by(3).map(|r| r.sensor_id).collect();
+    assert_eq!(ids, [0, 1, 2, 3, 0, 1, 2, 3]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn every_nth_reading_of_a_glitchy_node_is_implausible() {
+    let raw: Vec<Reading> = glitchy(3, ms(5), 5).take(30).collect().await;
+    for (i, r) in raw.iter().enumerate() {
+        assert_eq!(plausible(r), (i + 1) % 5 != 0, "reading {}", i);
+    }
+    // Once the glitches are gone, a map sees only real temperatures
+    let fahrenheit: Vec<f64> = stream::iter(raw)
+        .filter(|r| futures::future::ready(plausible(r) && r.metric == "temperature"))
+        .map(|r| r.value * 9.0 / 5.0 + 32.0)
+        .collect()
+        .await;
+    assert!(!fahrenheit.is_empty());
+    assert!(fahrenheit.iter().all(|f| (60.0..=85.0).contains(f)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn select_all_interleaves_nodes_at_their_own_rates() {
+    let merged = stream::select_all([
+        node(0, ms(20)).boxed(),
+        node(1, ms(50)).boxed(),
+        node(2, ms(40)).boxed(),
+    ]);
+    let readings: Vec<Reading> = merged.take_until(time::sleep(ms(399))).collect().await;
+    let mut counts = BTreeMap::new();
+    for r in &readings {
+        *counts.entry(r.sensor_id).or_insert(0) += 1;
+    }
+    // A poll at 0 and then every interval up to 399 ms: 20, 8 and 10 polls
+    assert_eq!(counts, BTreeMap::from([(0, 60), (1, 24), (2, 30)]));
+    // In arrival order: timestamps never go back
+    assert!(readings
+        .windows(2)
+        .all(|w| w[0].timestamp_ms <= w[1].timestamp_ms));
+}
+
+#[test]
+fn plausible_ranges_per_metric() {
+    let reading = |metric, value| Reading {
+        sensor_id: 0,
+        metric,
+        value,
+        timestamp_ms: EPOCH_MS,
+    };
+    assert!(plausible(&reading("temperature", -40.0)));
+    assert!(!plausible(&reading("temperature", 6553.5)));
+    assert!(plausible(&reading("humidity", 100.0)));
+    assert!(!plausible(&reading("humidity", -0.1)));
+    assert!(plausible(&reading("battery", 3.3)));
+    assert!(!plausible(&reading("pressure", 1013.0)));
+}
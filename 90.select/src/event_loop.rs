@@ -0,0 +1,263 @@
+// One task, one loop, four things to wait for
+//
+//   loop {
+//       select! {
+//           biased;
+//           token.cancelled()          => flush, send what is left, stop
+//           heartbeat.tick()           => heartbeat, retry the backlog
+//           flush, if buffered         => send the partial batch
+//           readings.next()            => buffer; send when full
+//       }
+//   }
+//
+// `select!` polls every branch and runs the handler of the first that is
+// ready; the others are dropped. `biased` polls them in the order
+// written instead of at random, so a shutdown is seen even while
+// readings are always ready, and heartbeats are never starved by data.
+//
+// Every branch future here is cancellation safe: dropping an unfinished
+// `tick()`, `next()` or `cancelled()` loses nothing, and the next turn of
+// the loop makes a new one. The uplink sends are not raced in the select
+// (dropping one halfway would lose track of the batch). They run in the
+// handlers, each bounded by `time::timeout`, so a hung broker costs at
+// most `uplink_timeout` before the loop is back to listening.
+
+use crate::uplink::{Heartbeat, SimUplink};
+use futures::stream::{Stream, StreamExt};
+use gateway::events::ReadingFiltered;
+use gateway::sensors::Reading;
+use gateway::uplink::{Batch, UplinkMessage};
+use shutdown::{Reason, Token};
+use std::collections::VecDeque;
+use std::fmt;
+use std::mem;
+use std::pin::pin;
+use std::time::Duration;
+use tokio::time::{self, Instant, MissedTickBehavior};
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub gateway: String,
+    pub heartbeat: Duration,
+    // Longest wait for one uplink send
+    pub uplink_timeout: Duration,
+    pub batch_size: usize,
+    // Longest a reading waits in a partial batch
+    pub max_delay: Duration,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            gateway: "gw-01".to_string(),
+            heartbeat: Duration::from_secs(10),
+            uplink_timeout: Duration::from_secs(2),
+            batch_size: 20,
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Sent { seq: u64, messages: usize },
+    // The batch stays in the backlog and is sent again later
+    TimedOut { seq: u64 },
+    Heartbeat { n: u64 },
+    // Not retried; the next heartbeat replaces it
+    HeartbeatTimedOut { n: u64 },
+    Stopped(Reason),
+    InputEnded,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Sent { seq, messages } => {
+                write!(f, "batch {} sent ({} messages)", seq, messages)
+            }
+            Event::TimedOut { seq } => write!(f, "batch {} timed out, kept", seq),
+            Event::Heartbeat { n } => write!(f, "heartbeat {}", n),
+            Event::HeartbeatTimedOut { n } => write!(f, "heartbeat {} timed out", n),
+            Event::Stopped(reason) => write!(f, "stopped: {}", reason),
+            Event::InputEnded => write!(f, "input ended"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Summary {
+    pub readings: u64,
+    // Time since the loop started, and what happened
+    pub events: Vec<(Duration, Event)>,
+    // Batches the uplink never took
+    pub unsent: Vec<Batch>,
+}
+
+// Batches on their way to the uplink
+struct Outbox<'a> {
+    uplink: &'a mut SimUplink,
+    settings: &'a Settings,
+    started: Instant,
+    backlog: VecDeque<Batch>,
+    events: Vec<(Duration, Event)>,
+    readings: u64,
+    seq: u64,
+    next_id: u64,
+    heartbeats: u64,
+}
+
+impl Outbox<'_> {
+    fn record(&mut self, event: Event) {
+        self.events.push((self.started.elapsed(), event));
+    }
+
+    fn queue(&mut self, readings: Vec<Reading>) {
+        if readings.is_empty() {
+            return;
+        }
+        let started_ms = streams::EPOCH_MS;
+        let messages = readings
+            .into_iter()
+            .map(|r| {
+                // Unfiltered here; 89.streams shows the EMA stage
+                let event = ReadingFiltered {
+                    sensor_id: r.sensor_id,
+                    metric: r.metric,
+                    raw: r.value,
+                    value: r.value,
+                    t: r.timestamp_ms,
+                    elapsed_ms: r.timestamp_ms.saturating_sub(started_ms),
+                };
+                self.next_id += 1;
+                UplinkMessage {
+                    id: self.next_id,
+                    topic: event.topic(),
+                    payload: event.payload(),
+                }
+            })
+            .collect();
+        self.seq += 1;
+        self.backlog.push_back(Batch {
+            gateway: self.settings.gateway.clone(),
+            boot: started_ms,
+            seq: self.seq,
+            messages,
+        });
+    }
+
+    // Sends the backlog oldest first, and stops at the first timeout so
+    // batches stay in order
+    async fn drain(&mut self) {
+        while let Some(batch) = self.backlog.front() {
+            let (seq, messages) = (batch.seq, batch.messages.len());
+            let sent = time::timeout(self.settings.uplink_timeout, self.uplink.send(batch)).await;
+            if sent.is_err() {
+                self.record(Event::TimedOut { seq });
+                return;
+            }
+            self.backlog.pop_front();
+            self.record(Event::Sent { seq, messages });
+        }
+    }
+
+    async fn heartbeat(&mut self, due: Instant) {
+        self.heartbeats += 1;
+        let n = self.heartbeats;
+        let heartbeat = Heartbeat {
+            n,
+            uptime_ms: (due - self.started).as_millis() as u64,
+            readings: self.readings,
+            backlog: self.backlog.len(),
+        };
+        let sent = time::timeout(
+            self.settings.uplink_timeout,
+            self.uplink.heartbeat(&heartbeat),
+        )
+        .await;
+        self.record(match sent {
+            Ok(()) => Event::Heartbeat { n },
+            Err(_) => Event::HeartbeatTimedOut { n },
+        });
+    }
+}
+
+// Runs until the token fires or `readings` ends
+pub async fn run<S>(
+    readings: S,
+    uplink: &mut SimUplink,
+    settings: &Settings,
+    token: &Token,
+) -> Summary
+where
+    S: Stream<Item = Reading>,
+{
+    let started = Instant::now();
+    let mut readings = pin!(readings);
+    // The first heartbeat one period in, not at once as `interval` would
+    let mut heartbeat = time::interval_at(started + settings.heartbeat, settings.heartbeat);
+    // After a long uplink stall, one heartbeat rather than a burst
+    heartbeat.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    // Reset when the first reading of a batch arrives; only polled while
+    // the buffer holds something
+    let mut flush = pin!(time::sleep(settings.max_delay));
+    let mut buffer: Vec<Reading> = Vec::with_capacity(settings.batch_size);
+    let mut out = Outbox {
+        uplink,
+        settings,
+        started,
+        backlog: VecDeque::new(),
+        events: Vec::new(),
+        readings: 0,
+        seq: 0,
+        next_id: 0,
+        heartbeats: 0,
+    };
+
+    loop {
+        tokio::select! {
+            biased;
+            reason = token.cancelled() => {
+                out.queue(mem::take(&mut buffer));
+                out.drain().await;
+                out.record(Event::Stopped(reason));
+                break;
+            }
+            // `tick` returns when the tick was due, which the uptime uses
+            // so a late turn of the loop does not skew it
+            due = heartbeat.tick() => {
+                out.heartbeat(due).await;
+                out.drain().await;
+            }
+            () = &mut flush, if !buffer.is_empty() => {
+                out.queue(mem::take(&mut buffer));
+                out.drain().await;
+            }
+            next = readings.next() => match next {
+                Some(reading) => {
+                    if buffer.is_empty() {
+                        flush.as_mut().reset(Instant::now() + settings.max_delay);
+                    }
+                    out.readings += 1;
+                    buffer.push(reading);
+                    if buffer.len() >= settings.batch_size {
+                        out.queue(mem::take(&mut buffer));
+                        out.drain().await;
+                    }
+                }
+                None => {
+                    out.queue(mem::take(&mut buffer));
+                    out.drain().await;
+                    out.record(Event::InputEnded);
+                    break;
+                }
+            },
+        }
+    }
+
+    Summary {
+        readings: out.readings,
+        events: out.events,
+        unsent: out.backlog.into(),
+    }
+}
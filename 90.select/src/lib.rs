@@ -0,0 +1,11 @@
+// Multiplexing with tokio::select!: sensor data, heartbeats and the
+// shutdown in one loop, with a timeout around every uplink send
+//
+// - `event_loop`: `run`, its `Settings` and the `Summary` of events
+// - `uplink`: `SimUplink` with scripted latency, and `Heartbeat`
+
+pub mod event_loop;
+pub mod uplink;
+
+pub use event_loop::{run, Event, Settings, Summary};
+pub use uplink::{Heartbeat, SimUplink};
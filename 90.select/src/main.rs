@@ -0,0 +1,151 @@
+use futures::stream;
+use gateway::sensors::Reading;
+use select::{run, Event, Settings, SimUplink, Summary};
+use shutdown::{Reason, Shutdown};
+use std::time::{Duration, Instant};
+use tokio::time;
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+// The defaults are seconds; the demo runs them in milliseconds
+fn settings() -> Settings {
+    Settings {
+        heartbeat: ms(250),
+        uplink_timeout: ms(100),
+        batch_size: 20,
+        max_delay: ms(200),
+        ..Settings::default()
+    }
+}
+
+fn stop_after(shutdown: &Shutdown, after: Duration) {
+    let stopper = shutdown.clone();
+    tokio::spawn(async move {
+        time::sleep(after).await;
+        stopper.trigger(Reason::Requested("demo over"));
+    });
+}
+
+fn print_events(summary: &Summary) {
+    for (at, event) in &summary.events {
+        println!("   [{:>4} ms] {}", at.as_millis(), event);
+    }
+}
+
+fn sent(summary: &Summary) -> usize {
+    summary
+        .events
+        .iter()
+        .map(|(_, e)| match e {
+            Event::Sent { messages, .. } => *messages,
+            _ => 0,
+        })
+        .sum()
+}
+
+fn main() {
+    println!("=== select! Examples ===\n");
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("tokio runtime");
+    runtime.block_on(examples());
+    println!("\n=== End of select! Examples ===");
+}
+
+async fn examples() {
+    // 1. Sensor data, heartbeats and shutdown in one loop
+    println!("1. Sensor data, heartbeats and shutdown in one loop:");
+    let shutdown = Shutdown::new();
+    stop_after(&shutdown, ms(600));
+    let mut uplink = SimUplink::new(ms(5));
+    let summary = run(
+        streams::node(0, ms(50)),
+        &mut uplink,
+        &settings(),
+        &shutdown.token(),
+    )
+    .await;
+    print_events(&summary);
+    let uptimes: Vec<u64> = uplink.heartbeats.iter().map(|h| h.uptime_ms).collect();
+    println!(
+        "   heartbeats at {:?} ms; {} of {} readings sent",
+        uptimes,
+        sent(&summary),
+        summary.readings
+    );
+
+    // 2. A timeout around the uplink
+    println!("\n2. A timeout around the uplink (100 ms):");
+    let shutdown = Shutdown::new();
+    stop_after(&shutdown, ms(600));
+    // The first send hangs for a second; later ones take 5 ms
+    let mut uplink = SimUplink::new(ms(5)).with_script([ms(1000)]);
+    let summary = run(
+        streams::node(0, ms(20)),
+        &mut uplink,
+        &settings(),
+        &shutdown.token(),
+    )
+    .await;
+    print_events(&summary);
+    let first_timeout = summary
+        .events
+        .iter()
+        .find(|(_, e)| *e == Event::TimedOut { seq: 1 })
+        .map(|(at, _)| *at);
+    let seqs: Vec<u64> = uplink.batches.iter().map(|b| b.seq).collect();
+    println!(
+        "   batch 1 cut off at {:?} ms; uplink got seqs {:?}; {} of {} readings sent",
+        first_timeout.map(|at| at.as_millis()),
+        seqs,
+        sent(&summary),
+        summary.readings
+    );
+
+    // 3. biased: the shutdown before endless data
+    println!("\n3. biased: the shutdown before endless data:");
+    let shutdown = Shutdown::new();
+    shutdown.trigger(Reason::Signal);
+    let endless = stream::repeat(Reading {
+        sensor_id: 0,
+        metric: "temperature",
+        value: 21.0,
+        timestamp_ms: streams::EPOCH_MS,
+    });
+    let mut uplink = SimUplink::new(ms(5));
+    let summary = run(endless, &mut uplink, &settings(), &shutdown.token()).await;
+    print_events(&summary);
+
+    // 4. Shutdown with the uplink down
+    println!("\n4. Shutdown with the uplink down:");
+    let shutdown = Shutdown::new();
+    stop_after(&shutdown, ms(300));
+    let mut uplink = SimUplink::new(ms(60_000));
+    // No heartbeat in the way, so each handler makes one send attempt
+    let quiet = Settings {
+        heartbeat: ms(60_000),
+        ..settings()
+    };
+    let started = Instant::now();
+    let summary = run(
+        streams::node(0, ms(20)),
+        &mut uplink,
+        &quiet,
+        &shutdown.token(),
+    )
+    .await;
+    let took = started.elapsed();
+    print_events(&summary);
+    let unsent: usize = summary.unsent.iter().map(|b| b.messages.len()).sum();
+    println!(
+        "   {} batches ({} readings) unsent after {} ms",
+        summary.unsent.len(),
+        unsent,
+        took.as_millis()
+    );
+    // The token is seen once the send in progress gives up, then the
+    // final flush gets one more timeout
+}
@@ -0,0 +1,80 @@
+// A simulated uplink with scripted latency
+//
+// Each send takes the next latency from the script, or the default once
+// the script is used up. A latency longer than the caller's timeout is a
+// broker that stopped answering: the caller gives up and the send future
+// is dropped halfway through its sleep, before it records the delivery.
+// A real client could be cut off after the broker had the message, which
+// is why uplink messages carry ids for the receiver to deduplicate.
+
+use gateway::uplink::Batch;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::time;
+
+// The gateway's "still alive" message, sent whether or not there is data
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heartbeat {
+    pub n: u64,
+    pub uptime_ms: u64,
+    pub readings: u64,
+    // Batches waiting for the uplink
+    pub backlog: usize,
+}
+
+impl Heartbeat {
+    pub fn topic(&self, gateway: &str) -> String {
+        format!("gateway/{}/heartbeat", gateway)
+    }
+
+    pub fn payload(&self) -> Value {
+        json!({
+            "n": self.n,
+            "uptime_ms": self.uptime_ms,
+            "readings": self.readings,
+            "backlog": self.backlog,
+        })
+    }
+}
+
+pub struct SimUplink {
+    latency: Duration,
+    script: VecDeque<Duration>,
+    pub batches: Vec<Batch>,
+    pub heartbeats: Vec<Heartbeat>,
+}
+
+impl SimUplink {
+    pub fn new(latency: Duration) -> SimUplink {
+        SimUplink {
+            latency,
+            script: VecDeque::new(),
+            batches: Vec::new(),
+            heartbeats: Vec::new(),
+        }
+    }
+
+    // Latencies for the next sends, in order
+    pub fn with_script(mut self, latencies: impl IntoIterator<Item = Duration>) -> SimUplink {
+        self.script.extend(latencies);
+        self
+    }
+
+    async fn round_trip(&mut self) {
+        let latency = self.script.pop_front().unwrap_or(self.latency);
+        if !latency.is_zero() {
+            time::sleep(latency).await;
+        }
+    }
+
+    pub async fn send(&mut self, batch: &Batch) {
+        self.round_trip().await;
+        self.batches.push(batch.clone());
+    }
+
+    pub async fn heartbeat(&mut self, heartbeat: &Heartbeat) {
+        self.round_trip().await;
+        self.heartbeats.push(heartbeat.clone());
+    }
+}
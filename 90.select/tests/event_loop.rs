@@ -0,0 +1,296 @@
+// The loop on a paused clock. With `start_paused`, tokio's clock only
+// moves when every task is waiting on a timer, and then jumps straight to
+// the next deadline; a minute of heartbeats runs in microseconds, and
+// every event lands on an exact time.
+
+use futures::stream::{self, Stream, StreamExt};
+use gateway::sensors::Reading;
+use select::{run, Event, Settings, SimUplink};
+use shutdown::{Reason, Shutdown};
+use std::time::Duration;
+use tokio::time::{self, Instant};
+
+fn secs(n: u64) -> Duration {
+    Duration::from_secs(n)
+}
+
+fn settings() -> Settings {
+    Settings {
+        heartbeat: secs(10),
+        uplink_timeout: secs(2),
+        batch_size: 6,
+        max_delay: secs(5),
+        ..Settings::default()
+    }
+}
+
+fn reading(n: u64) -> Reading {
+    Reading {
+        sensor_id: 0,
+        metric: "temperature",
+        value: 20.0 + n as f64 / 10.0,
+        timestamp_ms: streams::EPOCH_MS + n,
+    }
+}
+
+// `count` readings at each of the given seconds, then silence
+fn readings_at(schedule: &[(u64, usize)]) -> impl Stream<Item = Reading> {
+    let start = Instant::now();
+    let times: Vec<u64> = schedule
+        .iter()
+        .flat_map(|&(at, count)| std::iter::repeat_n(at, count))
+        .collect();
+    stream::iter(times.into_iter().enumerate())
+        .then(move |(i, at)| async move {
+            time::sleep_until(start + secs(at)).await;
+            reading(i as u64)
+        })
+        .chain(stream::pending())
+}
+
+fn stop_after(shutdown: &Shutdown, after: Duration) {
+    let stopper = shutdown.clone();
+    tokio::spawn(async move {
+        time::sleep(after).await;
+        stopper.trigger(Reason::Requested("test"));
+    });
+}
+
+fn events_without_heartbeats(events: &[(Duration, Event)]) -> Vec<(Duration, Event)> {
+    events
+        .iter()
+        .filter(|(_, e)| !matches!(e, Event::Heartbeat { .. }))
+        .cloned()
+        .collect()
+}
+
+#[tokio::test(start_paused = true)]
+async fn heartbeats_keep_their_schedule_without_any_data() {
+    let shutdown = Shutdown::new();
+    stop_after(&shutdown, secs(35));
+    let mut uplink = SimUplink::new(Duration::ZERO);
+    let summary = run(
+        stream::pending(),
+        &mut uplink,
+        &settings(),
+        &shutdown.token(),
+    )
+    .await;
+    assert_eq!(
+        summary.events,
+        [
+            (secs(10), Event::Heartbeat { n: 1 }),
+            (secs(20), Event::Heartbeat { n: 2 }),
+            (secs(30), Event::Heartbeat { n: 3 }),
+            (secs(35), Event::Stopped(Reason::Requested("test"))),
+        ]
+    );
+    assert_eq!(uplink.heartbeats[2].uptime_ms, 30_000);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_full_batch_goes_at_once_and_a_partial_one_after_max_delay() {
+    let shutdown = Shutdown::new();
+    stop_after(&shutdown, secs(9));
+    let mut uplink = SimUplink::new(Duration::ZERO);
+    let summary = run(
+        readings_at(&[(0, 6), (1, 2), (2, 1)]),
+        &mut uplink,
+        &settings(),
+        &shutdown.token(),
+    )
+    .await;
+    assert_eq!(
+        summary.events,
+        [
+            (
+                secs(0),
+                Event::Sent {
+                    seq: 1,
+                    messages: 6
+                }
+            ),
+            // The deadline runs from the first reading of the batch, at 1 s
+            (
+                secs(6),
+                Event::Sent {
+                    seq: 2,
+                    messages: 3
+                }
+            ),
+            (secs(9), Event::Stopped(Reason::Requested("test"))),
+        ]
+    );
+    assert_eq!(summary.readings, 9);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_slow_uplink_is_cut_off_and_the_batch_resent_on_the_heartbeat() {
+    let shutdown = Shutdown::new();
+    stop_after(&shutdown, secs(15));
+    // The first send hangs for 60 s; everything after is instant
+    let mut uplink = SimUplink::new(Duration::ZERO).with_script([secs(60)]);
+    let summary = run(
+        readings_at(&[(0, 6)]),
+        &mut uplink,
+        &settings(),
+        &shutdown.token(),
+    )
+    .await;
+    assert_eq!(
+        summary.events,
+        [
+            (secs(2), Event::TimedOut { seq: 1 }),
+            (secs(10), Event::Heartbeat { n: 1 }),
+            (
+                secs(10),
+                Event::Sent {
+                    seq: 1,
+                    messages: 6
+                }
+            ),
+            (secs(15), Event::Stopped(Reason::Requested("test"))),
+        ]
+    );
+    // The heartbeat reported the batch that was waiting
+    assert_eq!(uplink.heartbeats[0].backlog, 1);
+    assert_eq!(uplink.batches.len(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_hung_heartbeat_does_not_stall_the_loop() {
+    let shutdown = Shutdown::new();
+    stop_after(&shutdown, secs(25));
+    let mut uplink = SimUplink::new(Duration::ZERO).with_script([secs(3600)]);
+    let summary = run(
+        stream::pending(),
+        &mut uplink,
+        &settings(),
+        &shutdown.token(),
+    )
+    .await;
+    assert_eq!(
+        summary.events,
+        [
+            (secs(12), Event::HeartbeatTimedOut { n: 1 }),
+            (secs(20), Event::Heartbeat { n: 2 }),
+            (secs(25), Event::Stopped(Reason::Requested("test"))),
+        ]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn shutdown_flushes_the_partial_batch() {
+    let shutdown = Shutdown::new();
+    stop_after(&shutdown, secs(3));
+    let mut uplink = SimUplink::new(Duration::ZERO);
+    let summary = run(
+        readings_at(&[(0, 4)]),
+        &mut uplink,
+        &settings(),
+        &shutdown.token(),
+    )
+    .await;
+    assert_eq!(
+        summary.events,
+        [
+            (
+                secs(3),
+                Event::Sent {
+                    seq: 1,
+                    messages: 4
+                }
+            ),
+            (secs(3), Event::Stopped(Reason::Requested("test"))),
+        ]
+    );
+    assert!(summary.unsent.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn shutdown_with_a_dead_uplink_waits_one_timeout_and_reports_the_rest() {
+    let shutdown = Shutdown::new();
+    stop_after(&shutdown, secs(3));
+    let mut uplink = SimUplink::new(secs(3600));
+    let summary = run(
+        readings_at(&[(0, 8)]),
+        &mut uplink,
+        &settings(),
+        &shutdown.token(),
+    )
+    .await;
+    assert_eq!(
+        events_without_heartbeats(&summary.events),
+        [
+            (secs(2), Event::TimedOut { seq: 1 }),
+            (secs(5), Event::TimedOut { seq: 1 }),
+            (secs(5), Event::Stopped(Reason::Requested("test"))),
+        ]
+    );
+    let unsent: Vec<(u64, usize)> = summary
+        .unsent
+        .iter()
+        .map(|b| (b.seq, b.messages.len()))
+        .collect();
+    assert_eq!(unsent, [(1, 6), (2, 2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn biased_select_sees_the_shutdown_before_endless_data() {
+    let shutdown = Shutdown::new();
+    shutdown.trigger(Reason::Signal);
+    let mut uplink = SimUplink::new(Duration::ZERO);
+    // Always ready: an unbiased select would pick it about half the time
+    let endless = stream::iter(0..).map(reading);
+    let summary = run(endless, &mut uplink, &settings(), &shutdown.token()).await;
+    assert_eq!(summary.readings, 0);
+    assert_eq!(summary.events, [(secs(0), Event::Stopped(Reason::Signal))]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn the_end_of_the_input_sends_the_rest_and_stops() {
+    let shutdown = Shutdown::new();
+    let mut uplink = SimUplink::new(Duration::ZERO);
+    let three = stream::iter(0..3).map(reading);
+    let summary = run(three, &mut uplink, &settings(), &shutdown.token()).await;
+    assert_eq!(
+        summary.events,
+        [
+            (
+                secs(0),
+                Event::Sent {
+                    seq: 1,
+                    messages: 3
+                }
+            ),
+            (secs(0), Event::InputEnded),
+        ]
+    );
+}
+
+// Driving the clock by hand: `pause` stops it and `advance` moves it.
+// Timers that came due fire when the runtime next parks, so the test
+// yields after each step to let the loop run.
+#[tokio::test]
+async fn advancing_a_paused_clock_by_hand() {
+    time::pause();
+    let shutdown = Shutdown::new();
+    let token = shutdown.token();
+    let looping = tokio::spawn(async move {
+        let mut uplink = SimUplink::new(Duration::ZERO);
+        run(stream::pending(), &mut uplink, &settings(), &token).await;
+        uplink
+    });
+
+    // Let the loop start and set its timers, then step the clock past two
+    // heartbeats
+    tokio::task::yield_now().await;
+    for _ in 0..5 {
+        time::advance(secs(5)).await;
+        tokio::task::yield_now().await;
+    }
+    shutdown.trigger(Reason::Requested("test"));
+    let uplink = looping.await.unwrap();
+    let uptimes: Vec<u64> = uplink.heartbeats.iter().map(|h| h.uptime_ms).collect();
+    assert_eq!(uptimes, [10_000, 20_000]);
+}
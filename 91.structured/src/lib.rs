@@ -0,0 +1,13 @@
+// Structured concurrency: tasks that never outlive their scope, and work
+// that is rolled back when it is cancelled halfway
+//
+// - `scope`: `Scope`, a `JoinSet` with a token, a grace period and one
+//   `End` per task
+// - `ota`: a firmware download into the inactive boot slot that leaves
+//   nothing behind when cancelled at any await
+
+pub mod ota;
+pub mod scope;
+
+pub use ota::{download, part_path, FlashTiming, ImageServer, Manifest, OtaError, Staging};
+pub use scope::{End, Scope, TaskError, SIBLING_FAILED};
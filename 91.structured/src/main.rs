@@ -0,0 +1,193 @@
+use boot_handoff::flash::{self, Slot};
+use boot_handoff::BootState;
+use shutdown::{Shutdown, Token};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use structured::{
+    download, part_path, End, FlashTiming, ImageServer, OtaError, Scope, Staging, TaskError,
+};
+use tokio::time;
+
+const CHUNK: usize = 1024;
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn image() -> Vec<u8> {
+    (0..8192u32).map(|i| (i * 7 % 251) as u8).collect()
+}
+
+// 8 chunks, each 20 ms to fetch and 4 x 5 ms to program
+fn server() -> ImageServer {
+    ImageServer::new(image(), CHUNK, ms(20))
+}
+
+// A device running from slot A, nothing staged
+fn device(dir: &Path) -> io::Result<()> {
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir)?;
+    flash::write_slot(dir, Slot::A, b"firmware 1.4.2")?;
+    BootState::default().save(dir)
+}
+
+fn untouched(dir: &Path) -> bool {
+    !part_path(dir).exists()
+        && BootState::load(dir) == BootState::default()
+        && flash::read_slot(dir, Slot::B).is_err()
+}
+
+fn print_ends(ends: &[(String, End)], started: Instant) {
+    for (name, end) in ends {
+        println!("   {:<8} {}", name, end);
+    }
+    println!("   scope joined after {} ms", started.elapsed().as_millis());
+}
+
+// The OTA task as the gateway would run it: a cancellation is a normal
+// way to stop, not a failure of the scope
+async fn ota_task(dir: PathBuf, token: Token) -> Result<(), TaskError> {
+    match download(&server(), &dir, FlashTiming::default(), &token).await {
+        Ok(slot) => {
+            println!("   ota      staged in slot {}", slot);
+            Ok(())
+        }
+        Err(OtaError::Cancelled { reason, written }) => {
+            println!(
+                "   ota      cancelled ({}) at {} bytes, {} into a chunk",
+                reason,
+                written,
+                written % CHUNK
+            );
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn poller(token: Token) -> Result<(), TaskError> {
+    let mut polls = 0;
+    while !token.is_cancelled() {
+        tokio::select! {
+            _ = token.cancelled() => {}
+            _ = time::sleep(ms(10)) => polls += 1,
+        }
+    }
+    println!("   poller   stopped after {} polls", polls);
+    Ok(())
+}
+
+fn main() -> io::Result<()> {
+    println!("=== Structured Concurrency Examples ===\n");
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()?;
+    let dir = std::env::temp_dir().join("rust-sys-structured-demo");
+    runtime.block_on(examples(&dir))?;
+    fs::remove_dir_all(&dir)?;
+    println!("\n=== End of Structured Concurrency Examples ===");
+    Ok(())
+}
+
+async fn examples(dir: &Path) -> io::Result<()> {
+    // 1. A scope: the first failure cancels the siblings
+    println!("1. A scope: the first failure cancels the siblings:");
+    let started = Instant::now();
+    let mut scope = Scope::new(ms(100));
+    scope.spawn("poller", poller);
+    scope.spawn("uplink", |_| async {
+        time::sleep(ms(50)).await;
+        Err("broker refused the connection".into())
+    });
+    scope.spawn("logger", |_| async {
+        // Never looks at its token
+        time::sleep(ms(60_000)).await;
+        Ok(())
+    });
+    let ends = scope.join(&Shutdown::new().token()).await;
+    print_ends(&ends, started);
+
+    // 2. An await that is not cancellation safe
+    println!("\n2. An await that is not cancellation safe:");
+    device(dir)?;
+    let mut staging = Staging::create(dir)?;
+    let chunk = vec![0xA5; CHUNK];
+    // Programming a chunk takes 20 ms; give up after 12
+    let cut = time::timeout(ms(12), staging.program(&chunk, FlashTiming::default())).await;
+    let written = staging.written();
+    println!(
+        "   timed out: {}, {} of {} bytes in the .part file",
+        cut.is_err(),
+        written,
+        CHUNK
+    );
+    drop(staging);
+
+    // 3. The download, start to finish
+    println!("\n3. The download, start to finish:");
+    device(dir)?;
+    let started = Instant::now();
+    let token = Shutdown::new().token();
+    let slot = download(&server(), dir, FlashTiming::default(), &token)
+        .await
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    let state = BootState::load(dir);
+    println!(
+        "   8 KiB in {} ms, slot {}, pending {:?}",
+        started.elapsed().as_millis(),
+        slot,
+        state.pending
+    );
+
+    // 4. Cancelled by the shutdown partway through
+    println!("\n4. Cancelled by the shutdown partway through:");
+    device(dir)?;
+    let shutdown = Shutdown::new();
+    let stopper = shutdown.clone();
+    tokio::spawn(async move {
+        time::sleep(ms(150)).await;
+        stopper.signal();
+    });
+    let result = download(&server(), dir, FlashTiming::default(), &shutdown.token()).await;
+    match &result {
+        Err(e) => println!("   {}", e),
+        Ok(slot) => println!("   unexpectedly staged in slot {}", slot),
+    }
+    println!("   boot state and slots as before: {}", untouched(dir));
+
+    // 5. A corrupted chunk
+    println!("\n5. A corrupted chunk:");
+    device(dir)?;
+    let result = download(
+        &server().corrupting(3),
+        dir,
+        FlashTiming::default(),
+        &Shutdown::new().token(),
+    )
+    .await;
+    if let Err(e) = &result {
+        println!("   {}", e);
+    }
+    println!("   boot state and slots as before: {}", untouched(dir));
+
+    // 6. Ctrl-C during an update: the whole scope winds down
+    println!("\n6. Ctrl-C during an update:");
+    device(dir)?;
+    let process = Shutdown::new();
+    let stopper = process.clone();
+    tokio::spawn(async move {
+        time::sleep(ms(100)).await;
+        stopper.signal();
+    });
+    let started = Instant::now();
+    let mut scope = Scope::new(ms(100));
+    let ota_dir = dir.to_path_buf();
+    scope.spawn("ota", move |token| ota_task(ota_dir, token));
+    scope.spawn("poller", poller);
+    let ends = scope.join(&process.token()).await;
+    print_ends(&ends, started);
+    println!("   boot state and slots as before: {}", untouched(dir));
+    Ok(())
+}
@@ -0,0 +1,257 @@
+// An OTA download that can be cancelled at any await and leaves nothing
+// behind
+//
+//   fetch chunk 0 ─ program pages ─ fetch chunk 1 ─ program pages ─ ... ─ verify ─ stage
+//   └──────────────── reversible: a .part file next to the slots ────────┘   └ one sync step ┘
+//
+// A download takes minutes on a cellular link, so it will be cancelled:
+// by a shutdown, by a timeout, or by its task being aborted. In async
+// Rust any of those can happen at any `.await`, including halfway
+// through programming a chunk. Two rules keep that safe:
+//
+// - Everything before the commit goes into a separate `.part` file owned
+//   by a `Staging` guard. If the guard is dropped before `commit`, its
+//   `Drop` deletes the file. Drop runs on every exit path: an error, the
+//   token, `timeout`, `JoinSet::abort_all`.
+// - The commit (`stage_update`: write the slot, mark it pending) has no
+//   `.await` in it, so it cannot be cancelled halfway. Until it runs, the
+//   boot state still points at the running image.
+
+use boot_handoff::{stage_update, Slot};
+use crc::crc32;
+use shutdown::{Reason, Token};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::time;
+
+// What the update server announces before the download
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Manifest {
+    pub len: usize,
+    pub crc: u32,
+}
+
+// A simulated update server: one chunk per request, after `latency`
+pub struct ImageServer {
+    image: Vec<u8>,
+    chunk_size: usize,
+    latency: Duration,
+    // Flip a byte in this chunk, as a bad proxy would
+    corrupt: Option<usize>,
+}
+
+impl ImageServer {
+    pub fn new(image: Vec<u8>, chunk_size: usize, latency: Duration) -> ImageServer {
+        ImageServer {
+            image,
+            chunk_size,
+            latency,
+            corrupt: None,
+        }
+    }
+
+    pub fn corrupting(mut self, chunk: usize) -> ImageServer {
+        self.corrupt = Some(chunk);
+        self
+    }
+
+    pub fn manifest(&self) -> Manifest {
+        Manifest {
+            len: self.image.len(),
+            crc: crc32(&self.image),
+        }
+    }
+
+    pub fn chunks(&self) -> usize {
+        self.image.len().div_ceil(self.chunk_size)
+    }
+
+    // Cancellation safe: nothing happens until the chunk is returned
+    pub async fn fetch(&self, index: usize) -> Vec<u8> {
+        time::sleep(self.latency).await;
+        let start = index * self.chunk_size;
+        let end = (start + self.chunk_size).min(self.image.len());
+        let mut chunk = self.image[start..end].to_vec();
+        if self.corrupt == Some(index) {
+            chunk[0] ^= 0xFF;
+        }
+        chunk
+    }
+}
+
+// Flash is programmed one page at a time, and each page takes a while
+#[derive(Debug, Clone, Copy)]
+pub struct FlashTiming {
+    pub page: usize,
+    pub program: Duration,
+}
+
+impl Default for FlashTiming {
+    fn default() -> Self {
+        FlashTiming {
+            page: 256,
+            program: Duration::from_millis(5),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum OtaError {
+    // `written` bytes were in the .part file when it was rolled back
+    Cancelled { reason: Reason, written: usize },
+    Checksum { expected: u32, computed: u32 },
+    Length { expected: usize, received: usize },
+    Io(io::Error),
+}
+
+impl fmt::Display for OtaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OtaError::Cancelled { reason, written } => write!(
+                f,
+                "download cancelled ({}) with {} bytes written, rolled back",
+                reason, written
+            ),
+            OtaError::Checksum { expected, computed } => write!(
+                f,
+                "image CRC {:08X} does not match the manifest's {:08X}",
+                computed, expected
+            ),
+            OtaError::Length { expected, received } => write!(
+                f,
+                "received {} bytes, the manifest says {}",
+                received, expected
+            ),
+            OtaError::Io(e) => write!(f, "flash: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for OtaError {}
+
+impl From<io::Error> for OtaError {
+    fn from(e: io::Error) -> Self {
+        OtaError::Io(e)
+    }
+}
+
+// Where the download goes before it is committed
+pub fn part_path(dir: &Path) -> PathBuf {
+    dir.join("update.part")
+}
+
+// The partially written image, deleted unless committed
+pub struct Staging {
+    path: PathBuf,
+    file: File,
+    written: usize,
+    committed: bool,
+}
+
+impl Staging {
+    pub fn create(dir: &Path) -> io::Result<Staging> {
+        let path = part_path(dir);
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)?;
+        Ok(Staging {
+            path,
+            file,
+            written: 0,
+            committed: false,
+        })
+    }
+
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    // Not cancellation safe on purpose: dropped between two pages, the
+    // .part file holds half a chunk. The guard makes that harmless.
+    pub async fn program(&mut self, chunk: &[u8], flash: FlashTiming) -> io::Result<()> {
+        for page in chunk.chunks(flash.page) {
+            self.file.write_all(page)?;
+            self.written += page.len();
+            time::sleep(flash.program).await;
+        }
+        Ok(())
+    }
+
+    // Checks the image against the manifest, then stages it for a trial
+    // boot. Synchronous: once it starts, it finishes.
+    pub fn commit(mut self, dir: &Path, manifest: Manifest) -> Result<Slot, OtaError> {
+        self.file.flush()?;
+        let image = fs::read(&self.path)?;
+        if image.len() != manifest.len {
+            return Err(OtaError::Length {
+                expected: manifest.len,
+                received: image.len(),
+            });
+        }
+        let computed = crc32(&image);
+        if computed != manifest.crc {
+            return Err(OtaError::Checksum {
+                expected: manifest.crc,
+                computed,
+            });
+        }
+        let slot = stage_update(dir, &image)?;
+        self.committed = true;
+        let _ = fs::remove_file(&self.path);
+        Ok(slot)
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+// Downloads the server's image into the inactive slot of `dir` and marks
+// it pending. On cancellation, by the token or by dropping this future,
+// the boot state and the slots are as they were.
+pub async fn download(
+    server: &ImageServer,
+    dir: &Path,
+    flash: FlashTiming,
+    token: &Token,
+) -> Result<Slot, OtaError> {
+    let manifest = server.manifest();
+    let mut staging = Staging::create(dir)?;
+
+    // The chunk loop borrows `staging`; the block ends the borrow so the
+    // cancelled branch can read how far it got
+    let finished = {
+        let chunks = async {
+            for index in 0..server.chunks() {
+                let chunk = server.fetch(index).await;
+                staging.program(&chunk, flash).await?;
+            }
+            Ok::<(), io::Error>(())
+        };
+        tokio::select! {
+            biased;
+            reason = token.cancelled() => Err(reason),
+            done = chunks => Ok(done),
+        }
+    };
+
+    match finished {
+        Ok(done) => {
+            done?;
+            staging.commit(dir, manifest)
+        }
+        Err(reason) => Err(OtaError::Cancelled {
+            reason,
+            written: staging.written(),
+        }),
+    }
+}
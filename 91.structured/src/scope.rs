@@ -0,0 +1,137 @@
+// A task scope: spawned tasks never outlive the code that spawned them
+//
+//   Scope::new ── spawn(ota) ── spawn(poller) ── spawn(uplink) ── join
+//                                                                 │
+//        first failure, or the parent token ──▶ scope token fires ┤
+//        tasks that ignore it for `grace` ──▶ abort_all ──────────┤
+//                                                                 ▼
+//                                       every task ended, one `End` each
+//
+// `tokio::spawn` returns a handle that can be forgotten, and the task
+// then runs on unsupervised. A `JoinSet` owns its tasks instead: `join`
+// waits for all of them, and dropping the set aborts whatever is left,
+// so even a scope that is itself cancelled cannot leak a task. On top of
+// that the scope gives its tasks a token. The first failure cancels the
+// siblings, as they usually can't do anything useful alone, and a token
+// from outside (the process shutdown) cancels them too.
+
+use shutdown::{Reason, Shutdown, Token};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+use tokio::task::{Id, JoinError, JoinSet};
+use tokio::time::{self, Instant};
+
+pub type TaskError = Box<dyn Error + Send + Sync>;
+
+// Triggered on the scope's token when one of its tasks fails
+pub const SIBLING_FAILED: &str = "sibling failed";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum End {
+    Ok,
+    Failed(String),
+    Panicked(String),
+    // Did not stop within the grace period after its token fired
+    Aborted,
+}
+
+impl fmt::Display for End {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            End::Ok => write!(f, "ok"),
+            End::Failed(e) => write!(f, "failed: {}", e),
+            End::Panicked(msg) => write!(f, "panicked: {}", msg),
+            End::Aborted => write!(f, "aborted"),
+        }
+    }
+}
+
+pub struct Scope {
+    tasks: JoinSet<Result<(), TaskError>>,
+    names: HashMap<Id, String>,
+    shutdown: Shutdown,
+    grace: Duration,
+}
+
+impl Scope {
+    pub fn new(grace: Duration) -> Scope {
+        Scope {
+            tasks: JoinSet::new(),
+            names: HashMap::new(),
+            shutdown: Shutdown::new(),
+            grace,
+        }
+    }
+
+    pub fn token(&self) -> Token {
+        self.shutdown.token()
+    }
+
+    pub fn spawn<F, Fut>(&mut self, name: &str, task: F)
+    where
+        F: FnOnce(Token) -> Fut,
+        Fut: Future<Output = Result<(), TaskError>> + Send + 'static,
+    {
+        let handle = self.tasks.spawn(task(self.shutdown.token()));
+        self.names.insert(handle.id(), name.to_string());
+    }
+
+    // Waits until every task has ended, in the order they ended. `parent`
+    // cancels the whole scope.
+    pub async fn join(mut self, parent: &Token) -> Vec<(String, End)> {
+        let mut ends = Vec::new();
+        // When the scope's token fires, tasks get `grace` to stop before
+        // they are aborted
+        let mut abort_at: Option<Instant> = None;
+        let mut cancelled = false;
+
+        while !self.tasks.is_empty() {
+            tokio::select! {
+                biased;
+                reason = parent.cancelled(), if !cancelled => {
+                    self.shutdown.trigger(reason);
+                    cancelled = true;
+                    abort_at = Some(Instant::now() + self.grace);
+                }
+                () = time::sleep_until(abort_at.unwrap_or_else(Instant::now)), if abort_at.is_some() => {
+                    self.tasks.abort_all();
+                    abort_at = None;
+                }
+                Some(joined) = self.tasks.join_next_with_id() => {
+                    let (id, end) = match joined {
+                        Ok((id, Ok(()))) => (id, End::Ok),
+                        Ok((id, Err(e))) => (id, End::Failed(e.to_string())),
+                        Err(e) => (e.id(), ended_by(e)),
+                    };
+                    let failed = matches!(end, End::Failed(_) | End::Panicked(_));
+                    if failed && !cancelled {
+                        self.shutdown.trigger(Reason::Requested(SIBLING_FAILED));
+                        cancelled = true;
+                        abort_at = Some(Instant::now() + self.grace);
+                    }
+                    let name = self.names.remove(&id).unwrap_or_default();
+                    ends.push((name, end));
+                }
+            }
+        }
+        ends
+    }
+}
+
+fn ended_by(e: JoinError) -> End {
+    if e.is_cancelled() {
+        return End::Aborted;
+    }
+    let payload = e.into_panic();
+    let msg = match payload.downcast_ref::<&str>() {
+        Some(s) => s.to_string(),
+        None => match payload.downcast_ref::<String>() {
+            Some(s) => s.clone(),
+            None => "non-string panic payload".to_string(),
+        },
+    };
+    End::Panicked(msg)
+}
@@ -0,0 +1,163 @@
+// Cancelling the download at every kind of await. On a paused clock each
+// chunk takes exactly 40 ms: 20 ms to fetch, then four 256-byte pages at
+// 5 ms each. Cancelling at 67 ms lands in the middle of chunk 1, with its
+// first two pages programmed.
+
+use boot_handoff::flash::{self, Slot, SlotError};
+use boot_handoff::BootState;
+use shutdown::{Reason, Shutdown};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use structured::{download, part_path, FlashTiming, ImageServer, OtaError, Staging};
+use tokio::task::JoinSet;
+use tokio::time;
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn image() -> Vec<u8> {
+    (0..8192u32).map(|i| (i * 7 % 251) as u8).collect()
+}
+
+fn server() -> ImageServer {
+    ImageServer::new(image(), 1024, ms(20))
+}
+
+// A device running from slot A, with nothing in slot B
+fn device(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rust-sys-ota-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    flash::write_slot(&dir, Slot::A, b"running image").unwrap();
+    BootState::default().save(&dir).unwrap();
+    dir
+}
+
+fn assert_untouched(dir: &Path) {
+    assert!(!part_path(dir).exists(), "partial download left behind");
+    assert_eq!(BootState::load(dir), BootState::default());
+    assert_eq!(flash::read_slot(dir, Slot::B), Err(SlotError::Empty));
+    assert_eq!(flash::read_slot(dir, Slot::A).unwrap(), b"running image");
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_complete_download_is_staged_for_a_trial_boot() {
+    let dir = device("complete");
+    let started = time::Instant::now();
+    let slot = download(
+        &server(),
+        &dir,
+        FlashTiming::default(),
+        &Shutdown::new().token(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(started.elapsed(), ms(8 * 40));
+    assert_eq!(slot, Slot::B);
+    assert_eq!(flash::read_slot(&dir, Slot::B).unwrap(), image());
+    assert_eq!(BootState::load(&dir).pending, Some(Slot::B));
+    assert!(!part_path(&dir).exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn the_token_mid_chunk_rolls_back() {
+    let dir = device("token");
+    let shutdown = Shutdown::new();
+    let stopper = shutdown.clone();
+    tokio::spawn(async move {
+        time::sleep(ms(67)).await;
+        stopper.trigger(Reason::Signal);
+    });
+    let result = download(&server(), &dir, FlashTiming::default(), &shutdown.token()).await;
+    match result {
+        Err(OtaError::Cancelled { reason, written }) => {
+            assert_eq!(reason, Reason::Signal);
+            // Chunk 0 and half of chunk 1
+            assert_eq!(written, 1024 + 2 * 256);
+        }
+        other => panic!("expected a cancellation, got {:?}", other),
+    }
+    assert_untouched(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn dropping_the_future_mid_chunk_rolls_back() {
+    let dir = device("timeout");
+    let token = Shutdown::new().token();
+    let result = time::timeout(
+        ms(67),
+        download(&server(), &dir, FlashTiming::default(), &token),
+    )
+    .await;
+    assert!(result.is_err(), "should have timed out");
+    assert_untouched(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn aborting_the_task_mid_chunk_rolls_back() {
+    let dir = device("abort");
+    let mut tasks = JoinSet::new();
+    let task_dir = dir.clone();
+    tasks.spawn(async move {
+        let token = Shutdown::new().token();
+        download(&server(), &task_dir, FlashTiming::default(), &token).await
+    });
+    time::sleep(ms(67)).await;
+    assert!(part_path(&dir).exists(), "download should be under way");
+    tasks.abort_all();
+    let joined = tasks.join_next().await.unwrap();
+    assert!(joined.unwrap_err().is_cancelled());
+    assert_untouched(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_corrupt_chunk_fails_the_checksum_and_leaves_nothing() {
+    let dir = device("corrupt");
+    let token = Shutdown::new().token();
+    let result = download(
+        &server().corrupting(5),
+        &dir,
+        FlashTiming::default(),
+        &token,
+    )
+    .await;
+    assert!(matches!(result, Err(OtaError::Checksum { .. })));
+    assert_untouched(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_token_fired_before_the_start_writes_nothing() {
+    let dir = device("early");
+    let shutdown = Shutdown::new();
+    shutdown.trigger(Reason::Signal);
+    let result = download(&server(), &dir, FlashTiming::default(), &shutdown.token()).await;
+    assert!(matches!(
+        result,
+        Err(OtaError::Cancelled { written: 0, .. })
+    ));
+    assert_untouched(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// The await that isn't cancellation safe, on its own: each page is
+// written and then waits 5 ms, so a 12 ms timeout stops after the third
+#[tokio::test(start_paused = true)]
+async fn a_program_cut_short_leaves_part_of_a_chunk_until_the_guard_drops() {
+    let dir = device("staging");
+    let mut staging = Staging::create(&dir).unwrap();
+    let chunk = vec![0xA5; 1024];
+    let cut = time::timeout(ms(12), staging.program(&chunk, FlashTiming::default())).await;
+    assert!(cut.is_err());
+    assert_eq!(staging.written(), 768);
+    assert_eq!(fs::metadata(part_path(&dir)).unwrap().len(), 768);
+    drop(staging);
+    assert_untouched(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,126 @@
+// Scopes on a paused clock: failures, panics, a parent shutdown, tasks
+// that ignore their token, and a scope that is itself cancelled
+
+use boot_handoff::flash::{self, Slot};
+use boot_handoff::BootState;
+use shutdown::{Reason, Shutdown};
+use std::fs;
+use std::time::Duration;
+use structured::{download, part_path, End, FlashTiming, ImageServer, Scope, SIBLING_FAILED};
+use tokio::time::{self, Instant};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+#[tokio::test(start_paused = true)]
+async fn every_task_is_joined_in_the_order_it_ends() {
+    let mut scope = Scope::new(ms(100));
+    for (name, after) in [("slow", 30), ("fast", 10), ("medium", 20)] {
+        scope.spawn(name, move |_| async move {
+            time::sleep(ms(after)).await;
+            Ok(())
+        });
+    }
+    let ends = scope.join(&Shutdown::new().token()).await;
+    let names: Vec<&str> = ends.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, ["fast", "medium", "slow"]);
+    assert!(ends.iter().all(|(_, e)| *e == End::Ok));
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_failure_cancels_the_siblings_and_stragglers_are_aborted() {
+    let started = Instant::now();
+    let mut scope = Scope::new(ms(100));
+    let token = scope.token();
+    scope.spawn("uplink", |_| async {
+        time::sleep(ms(10)).await;
+        Err("broker refused the connection".into())
+    });
+    scope.spawn("poller", |token| async move {
+        let reason = token.cancelled().await;
+        assert_eq!(reason, Reason::Requested(SIBLING_FAILED));
+        Ok(())
+    });
+    scope.spawn("deaf", |_| async {
+        time::sleep(ms(60_000)).await;
+        Ok(())
+    });
+    let ends = scope.join(&Shutdown::new().token()).await;
+    assert_eq!(
+        ends,
+        [
+            (
+                "uplink".to_string(),
+                End::Failed("broker refused the connection".into())
+            ),
+            ("poller".to_string(), End::Ok),
+            ("deaf".to_string(), End::Aborted),
+        ]
+    );
+    assert_eq!(started.elapsed(), ms(110));
+    assert!(token.is_cancelled());
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_panic_is_reported_and_cancels_the_siblings() {
+    let mut scope = Scope::new(ms(100));
+    scope.spawn("decoder", |_| async { panic!("frame too short") });
+    scope.spawn("poller", |token| async move {
+        token.cancelled().await;
+        Ok(())
+    });
+    let ends = scope.join(&Shutdown::new().token()).await;
+    assert_eq!(
+        ends[0],
+        ("decoder".into(), End::Panicked("frame too short".into()))
+    );
+    assert_eq!(ends[1], ("poller".into(), End::Ok));
+}
+
+#[tokio::test(start_paused = true)]
+async fn the_parent_token_cancels_the_scope() {
+    let parent = Shutdown::new();
+    let stopper = parent.clone();
+    tokio::spawn(async move {
+        time::sleep(ms(50)).await;
+        stopper.signal();
+    });
+    let mut scope = Scope::new(ms(100));
+    let token = scope.token();
+    for name in ["a", "b"] {
+        scope.spawn(name, |token| async move {
+            token.cancelled().await;
+            Ok(())
+        });
+    }
+    let ends = scope.join(&parent.token()).await;
+    assert!(ends.iter().all(|(_, e)| *e == End::Ok));
+    assert_eq!(token.reason(), Some(Reason::Signal));
+}
+
+#[tokio::test(start_paused = true)]
+async fn dropping_the_scope_aborts_its_tasks_and_their_work_rolls_back() {
+    let dir = std::env::temp_dir().join(format!("rust-sys-scope-ota-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    flash::write_slot(&dir, Slot::A, b"running image").unwrap();
+
+    let mut scope = Scope::new(ms(100));
+    let task_dir = dir.clone();
+    scope.spawn("ota", move |_| async move {
+        // Ignores the scope token, so only an abort stops it
+        let server = ImageServer::new(vec![0x5A; 8192], 1024, ms(20));
+        let own = Shutdown::new().token();
+        download(&server, &task_dir, FlashTiming::default(), &own).await?;
+        Ok(())
+    });
+    // The caller gives up on the whole scope halfway through the download
+    let joined = time::timeout(ms(67), scope.join(&Shutdown::new().token())).await;
+    assert!(joined.is_err());
+    // Aborted tasks are dropped the next time the runtime runs them
+    time::sleep(ms(1)).await;
+    assert!(!part_path(&dir).exists());
+    assert_eq!(BootState::load(&dir).pending, None);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,146 @@
+// Commands to the gateway: request/response over mpsc + oneshot
+//
+//   Client::set_interval ──mpsc──▶ server task ──▶ watch::Sender<Duration>
+//          ▲                          │                    │
+//          └──── oneshot reply ───────┘                    ▼
+//                                               every task that polls
+//
+// Each command carries its own `oneshot::Sender` for the reply. The
+// server handles commands one at a time from a bounded mpsc queue. The
+// resulting setting is published on a `watch`, because the tasks using
+// it only care about its current value, not about every change.
+//
+// Each way a request can go wrong maps to a different channel error:
+// the queue is closed (the server is gone), the reply sender is dropped
+// unanswered (the server stopped with the command still queued), or
+// nothing arrives in time (the server is busy). The caller can tell
+// them apart.
+
+use std::fmt;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, watch};
+use tokio::time;
+
+pub const MIN_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub commands: u64,
+    pub interval: Duration,
+}
+
+#[derive(Debug)]
+pub enum Command {
+    Stats(oneshot::Sender<Stats>),
+    // Replies with the previous interval
+    SetInterval(Duration, oneshot::Sender<Result<Duration, String>>),
+    // Stops the server; commands still queued are dropped unanswered
+    Stop,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    // The server task is gone: `mpsc::Sender::send` failed
+    Closed,
+    // The server dropped the reply sender without answering
+    NoReply,
+    // No answer within the caller's timeout
+    Timeout,
+    Rejected(String),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Closed => write!(f, "command server is not running"),
+            CommandError::NoReply => write!(f, "command dropped without a reply"),
+            CommandError::Timeout => write!(f, "no reply in time"),
+            CommandError::Rejected(why) => write!(f, "rejected: {}", why),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+#[derive(Clone)]
+pub struct Client {
+    commands: mpsc::Sender<Command>,
+    timeout: Duration,
+}
+
+impl Client {
+    async fn request<T>(
+        &self,
+        make: impl FnOnce(oneshot::Sender<T>) -> Command,
+    ) -> Result<T, CommandError> {
+        let (reply, response) = oneshot::channel();
+        let ask = async {
+            self.commands
+                .send(make(reply))
+                .await
+                .map_err(|_| CommandError::Closed)?;
+            response.await.map_err(|_| CommandError::NoReply)
+        };
+        // The timeout covers the wait for queue space too
+        time::timeout(self.timeout, ask)
+            .await
+            .unwrap_or(Err(CommandError::Timeout))
+    }
+
+    pub async fn stats(&self) -> Result<Stats, CommandError> {
+        self.request(Command::Stats).await
+    }
+
+    pub async fn set_interval(&self, every: Duration) -> Result<Duration, CommandError> {
+        self.request(|reply| Command::SetInterval(every, reply))
+            .await?
+            .map_err(CommandError::Rejected)
+    }
+
+    pub async fn stop(&self) -> Result<(), CommandError> {
+        self.commands
+            .send(Command::Stop)
+            .await
+            .map_err(|_| CommandError::Closed)
+    }
+}
+
+// Starts the server task. Each command takes `handling` to process.
+// Returns the client and a receiver for the poll interval.
+pub fn spawn_server(
+    interval: Duration,
+    handling: Duration,
+    queue: usize,
+    timeout: Duration,
+) -> (Client, watch::Receiver<Duration>) {
+    let (commands, mut rx) = mpsc::channel::<Command>(queue);
+    let (settings, interval_rx) = watch::channel(interval);
+    tokio::spawn(async move {
+        let mut handled = 0;
+        while let Some(command) = rx.recv().await {
+            time::sleep(handling).await;
+            handled += 1;
+            match command {
+                Command::Stats(reply) => {
+                    // The caller may have given up; that's not our error
+                    let _ = reply.send(Stats {
+                        commands: handled,
+                        interval: *settings.borrow(),
+                    });
+                }
+                Command::SetInterval(every, reply) => {
+                    let result = if every < MIN_INTERVAL {
+                        Err(format!("interval below {:?}", MIN_INTERVAL))
+                    } else {
+                        Ok(settings.send_replace(every))
+                    };
+                    let _ = reply.send(result);
+                }
+                Command::Stop => break,
+            }
+        }
+        // Dropping `rx` here drops every queued command, and with it
+        // each one's reply sender
+    });
+    (Client { commands, timeout }, interval_rx)
+}
@@ -0,0 +1,252 @@
+// One producer, several consumers, four ways to connect them
+//
+//   producer ──▶ mpsc ──▶ uplink     one queue per consumer; a full queue
+//            ──▶ mpsc ──▶ display    makes the producer wait (send) or
+//                                    loses the sample for that consumer (try_send)
+//
+//   producer ──▶ broadcast ──┬──▶ uplink    one ring buffer shared by all;
+//                            └──▶ display   a consumer that falls `capacity`
+//                                           behind skips ahead (Lagged)
+//
+//   producer ──▶ watch ──┬──▶ uplink        one slot holding the latest
+//                        └──▶ display       value; a slow consumer sees
+//                                           fewer values, never an old one
+//
+// The producer emits a sample every `every`; each consumer takes
+// `per_sample` to handle one. What differs is who pays when a consumer
+// is slower than the producer: the producer (mpsc + send), or the slow
+// consumer, by losing samples (the other three).
+
+use gateway::sensors::SensorHub;
+use std::time::Duration;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio::time::{self, Instant, MissedTickBehavior};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub seq: u64,
+    pub sensor_id: u16,
+    pub value: f64,
+}
+
+// `count` temperature samples from a simulated hub of two nodes
+pub fn samples(count: usize) -> Vec<Sample> {
+    let mut hub = SensorHub::new(2, 42);
+    let mut out = Vec::with_capacity(count);
+    let mut elapsed = 0;
+    while out.len() < count {
+        for r in hub.poll(elapsed, elapsed) {
+            if r.metric == "temperature" && out.len() < count {
+                out.push(Sample {
+                    seq: out.len() as u64 + 1,
+                    sensor_id: r.sensor_id,
+                    value: r.value,
+                });
+            }
+        }
+        elapsed += 100;
+    }
+    out
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Consumer {
+    pub name: &'static str,
+    pub per_sample: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Delivery {
+    pub name: &'static str,
+    // Sequence numbers, in the order they were handled
+    pub received: Vec<u64>,
+    // Samples this consumer never saw
+    pub missed: u64,
+}
+
+impl Delivery {
+    fn new(name: &'static str) -> Delivery {
+        Delivery {
+            name,
+            received: Vec::new(),
+            missed: 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Report {
+    // How long the producer took to hand out every sample
+    pub producer_time: Duration,
+    pub deliveries: Vec<Delivery>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    // `send().await`: the producer waits for room
+    Wait,
+    // `try_send`: the sample is dropped for the consumer that is full
+    DropNewest,
+}
+
+// Ticks every `every`; a producer held up by `send` carries on from
+// where it is rather than bursting to catch up
+fn ticker(every: Duration) -> time::Interval {
+    let mut tick = time::interval(every);
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    tick
+}
+
+async fn join(handles: Vec<JoinHandle<Delivery>>) -> Vec<Delivery> {
+    let mut deliveries = Vec::with_capacity(handles.len());
+    for handle in handles {
+        deliveries.push(handle.await.expect("consumer task"));
+    }
+    deliveries
+}
+
+pub async fn via_mpsc(
+    samples: &[Sample],
+    every: Duration,
+    consumers: &[Consumer],
+    capacity: usize,
+    overflow: Overflow,
+) -> Report {
+    let mut senders = Vec::new();
+    let mut handles = Vec::new();
+    for &c in consumers {
+        let (tx, mut rx) = mpsc::channel::<Sample>(capacity);
+        senders.push(tx);
+        handles.push(tokio::spawn(async move {
+            let mut d = Delivery::new(c.name);
+            // `None` once every sender is dropped and the queue is empty
+            while let Some(s) = rx.recv().await {
+                time::sleep(c.per_sample).await;
+                d.received.push(s.seq);
+            }
+            d
+        }));
+    }
+
+    let started = Instant::now();
+    let mut missed = vec![0; consumers.len()];
+    let mut tick = ticker(every);
+    for &s in samples {
+        tick.tick().await;
+        for (i, tx) in senders.iter().enumerate() {
+            match overflow {
+                Overflow::Wait => {
+                    let _ = tx.send(s).await;
+                }
+                Overflow::DropNewest => {
+                    if let Err(TrySendError::Full(_)) = tx.try_send(s) {
+                        missed[i] += 1;
+                    }
+                }
+            }
+        }
+    }
+    let producer_time = started.elapsed();
+    // Closing the queues is what ends the consumers' loops
+    drop(senders);
+
+    let mut deliveries = join(handles).await;
+    for (d, m) in deliveries.iter_mut().zip(missed) {
+        d.missed = m;
+    }
+    Report {
+        producer_time,
+        deliveries,
+    }
+}
+
+pub async fn via_broadcast(
+    samples: &[Sample],
+    every: Duration,
+    consumers: &[Consumer],
+    capacity: usize,
+) -> Report {
+    let (tx, _) = broadcast::channel::<Sample>(capacity);
+    let mut handles = Vec::new();
+    for &c in consumers {
+        // Only samples sent after `subscribe` are received
+        let mut rx = tx.subscribe();
+        handles.push(tokio::spawn(async move {
+            let mut d = Delivery::new(c.name);
+            loop {
+                match rx.recv().await {
+                    Ok(s) => {
+                        time::sleep(c.per_sample).await;
+                        d.received.push(s.seq);
+                    }
+                    // The oldest `n` were overwritten before this
+                    // consumer got to them; the next recv returns the
+                    // oldest one still in the buffer
+                    Err(broadcast::error::RecvError::Lagged(n)) => d.missed += n,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+            d
+        }));
+    }
+
+    let started = Instant::now();
+    let mut tick = ticker(every);
+    for &s in samples {
+        tick.tick().await;
+        // Never waits; fails only if nobody is subscribed
+        let _ = tx.send(s);
+    }
+    let producer_time = started.elapsed();
+    drop(tx);
+
+    Report {
+        producer_time,
+        deliveries: join(handles).await,
+    }
+}
+
+pub async fn via_watch(samples: &[Sample], every: Duration, consumers: &[Consumer]) -> Report {
+    let (tx, rx) = watch::channel::<Option<Sample>>(None);
+    let mut handles = Vec::new();
+    for &c in consumers {
+        let mut rx = rx.clone();
+        handles.push(tokio::spawn(async move {
+            let mut d = Delivery::new(c.name);
+            // Waits for a value this receiver has not seen; after the
+            // sender is dropped, only an unseen last value is returned
+            while rx.changed().await.is_ok() {
+                // Copy out and release the lock before the slow part;
+                // the sender can't update while a borrow is held
+                let latest = *rx.borrow_and_update();
+                if let Some(s) = latest {
+                    time::sleep(c.per_sample).await;
+                    d.received.push(s.seq);
+                }
+            }
+            d
+        }));
+    }
+    drop(rx);
+
+    let started = Instant::now();
+    let mut tick = ticker(every);
+    for &s in samples {
+        tick.tick().await;
+        // `send_replace` stores the value even with no receiver left
+        tx.send_replace(Some(s));
+    }
+    let producer_time = started.elapsed();
+    drop(tx);
+
+    let mut deliveries = join(handles).await;
+    for d in &mut deliveries {
+        d.missed = samples.len() as u64 - d.received.len() as u64;
+    }
+    Report {
+        producer_time,
+        deliveries,
+    }
+}
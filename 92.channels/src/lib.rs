@@ -0,0 +1,14 @@
+// tokio channels compared on one scenario: telemetry fanned out to
+// several consumers, and commands sent back with a reply
+//
+// - `fanout`: the same producer and consumers over mpsc, broadcast and
+//   watch, with what each consumer received and missed
+// - `command`: request/response with mpsc + oneshot, settings on a watch
+
+pub mod command;
+pub mod fanout;
+
+pub use command::{spawn_server, Client, Command, CommandError, Stats, MIN_INTERVAL};
+pub use fanout::{
+    samples, via_broadcast, via_mpsc, via_watch, Consumer, Delivery, Overflow, Report, Sample,
+};
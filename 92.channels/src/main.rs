@@ -0,0 +1,114 @@
+use channels::{
+    samples, spawn_server, via_broadcast, via_mpsc, via_watch, Consumer, Overflow, Report, Sample,
+};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time;
+
+const COUNT: usize = 40;
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+// A fast uplink and a display that needs three sample periods per sample
+fn consumers() -> [Consumer; 2] {
+    [
+        Consumer {
+            name: "uplink",
+            per_sample: ms(1),
+        },
+        Consumer {
+            name: "display",
+            per_sample: ms(15),
+        },
+    ]
+}
+
+fn print_report(r: &Report) {
+    println!(
+        "   producer took {} ms for {} samples",
+        r.producer_time.as_millis(),
+        COUNT
+    );
+    for d in &r.deliveries {
+        println!(
+            "   {:<8} received {:>2}, missed {:>2}, last seq {}",
+            d.name,
+            d.received.len(),
+            d.missed,
+            d.received.last().copied().unwrap_or(0)
+        );
+    }
+}
+
+fn main() {
+    println!("=== Channels Examples ===\n");
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("runtime");
+    runtime.block_on(examples());
+    println!("\n=== End of Channels Examples ===");
+}
+
+async fn examples() {
+    let all: Vec<Sample> = samples(COUNT);
+    let every = ms(5);
+    let on_schedule = every * COUNT as u32 * 2;
+    println!(
+        "{} samples every {} ms; twice that schedule is {} ms\n",
+        COUNT,
+        every.as_millis(),
+        on_schedule.as_millis()
+    );
+
+    // 1. mpsc with send().await: backpressure
+    println!("1. mpsc with send().await:");
+    let r = via_mpsc(&all, every, &consumers(), 4, Overflow::Wait).await;
+    print_report(&r);
+
+    // 2. mpsc with try_send: the slow consumer loses samples
+    println!("\n2. mpsc with try_send:");
+    let r = via_mpsc(&all, every, &consumers(), 4, Overflow::DropNewest).await;
+    print_report(&r);
+
+    // 3. broadcast: one buffer, a lagging receiver skips ahead
+    println!("\n3. broadcast with capacity 4:");
+    let r = via_broadcast(&all, every, &consumers(), 4).await;
+    print_report(&r);
+    // With no receivers a broadcast send fails and the value is lost
+    let (tx, rx) = broadcast::channel::<u64>(4);
+    drop(rx);
+    println!("   send with no subscribers -> {:?}", tx.send(1));
+
+    // 4. watch: only the latest value
+    println!("\n4. watch:");
+    let r = via_watch(&all, every, &consumers()).await;
+    print_report(&r);
+
+    // 5. Commands with a oneshot reply
+    println!("\n5. Commands with a oneshot reply:");
+    let (client, mut interval) = spawn_server(ms(1000), ms(2), 8, ms(100));
+    let previous = client.set_interval(ms(250)).await;
+    println!("   set_interval(250 ms) -> {:?}", previous);
+    let changed = interval.has_changed().unwrap_or(false);
+    println!(
+        "   watch receivers see {:?} (changed: {})",
+        *interval.borrow_and_update(),
+        changed
+    );
+    let rejected = client.set_interval(ms(1)).await;
+    if let Err(e) = &rejected {
+        println!("   set_interval(1 ms) -> {}", e);
+    }
+    let stats = client.stats().await;
+    println!("   stats -> {:?}", stats);
+    // The server stops; the request queued behind Stop gets no reply
+    let _ = client.stop().await;
+    let queued = client.stats().await;
+    println!("   stats after stop -> {:?}", queued);
+    time::sleep(ms(10)).await;
+    let closed = client.stats().await;
+    println!("   stats once it is gone -> {:?}", closed);
+}
@@ -0,0 +1,65 @@
+// Request/response over mpsc + oneshot: replies, rejections, and each
+// way a request can fail, told apart by the caller
+
+use channels::{spawn_server, CommandError, Stats};
+use std::time::Duration;
+use tokio::time;
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+#[tokio::test(start_paused = true)]
+async fn set_interval_replies_with_the_previous_value_and_publishes_the_new() {
+    let (client, mut interval) = spawn_server(ms(1000), ms(5), 4, ms(100));
+    assert!(!interval.has_changed().unwrap());
+    assert_eq!(client.set_interval(ms(250)).await, Ok(ms(1000)));
+    assert!(interval.has_changed().unwrap());
+    assert_eq!(*interval.borrow_and_update(), ms(250));
+    assert_eq!(client.set_interval(ms(500)).await, Ok(ms(250)));
+    assert_eq!(
+        client.stats().await,
+        Ok(Stats {
+            commands: 3,
+            interval: ms(500)
+        })
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_rejected_interval_changes_nothing() {
+    let (client, interval) = spawn_server(ms(1000), ms(5), 4, ms(100));
+    let result = client.set_interval(ms(1)).await;
+    assert!(
+        matches!(result, Err(CommandError::Rejected(_))),
+        "{:?}",
+        result
+    );
+    assert!(!interval.has_changed().unwrap());
+    assert_eq!(*interval.borrow(), ms(1000));
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_request_queued_behind_a_slow_one_times_out() {
+    // 80 ms per command, 100 ms to wait for a reply
+    let (client, _interval) = spawn_server(ms(1000), ms(80), 4, ms(100));
+    let (first, second) = tokio::join!(client.stats(), client.stats());
+    assert_eq!(first.map(|s| s.commands), Ok(1));
+    assert_eq!(second, Err(CommandError::Timeout));
+    // The abandoned request was still handled; its reply went nowhere
+    time::sleep(ms(100)).await;
+    assert_eq!(client.stats().await.map(|s| s.commands), Ok(3));
+}
+
+#[tokio::test(start_paused = true)]
+async fn stop_drops_queued_requests_and_then_closes_the_queue() {
+    let (client, interval) = spawn_server(ms(1000), ms(5), 4, ms(100));
+    client.stop().await.unwrap();
+    // Queued behind Stop: its reply sender is dropped with the queue
+    assert_eq!(client.stats().await, Err(CommandError::NoReply));
+    assert_eq!(client.stats().await, Err(CommandError::Closed));
+    assert_eq!(client.stop().await, Err(CommandError::Closed));
+    // The watch sender went with the server task
+    assert!(interval.has_changed().is_err());
+    assert_eq!(*interval.borrow(), ms(1000));
+}
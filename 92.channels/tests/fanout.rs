@@ -0,0 +1,134 @@
+// The same fan-out over each channel on a paused clock: who received
+// what, who missed what, and how long the producer took
+
+use channels::{samples, via_broadcast, via_mpsc, via_watch, Consumer, Overflow};
+use std::time::Duration;
+
+const COUNT: usize = 20;
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+// Samples every 10 ms; the display needs 30 ms for each
+fn consumers() -> [Consumer; 2] {
+    [
+        Consumer {
+            name: "uplink",
+            per_sample: ms(1),
+        },
+        Consumer {
+            name: "display",
+            per_sample: ms(30),
+        },
+    ]
+}
+
+fn all_seqs() -> Vec<u64> {
+    (1..=COUNT as u64).collect()
+}
+
+fn increasing(seqs: &[u64]) -> bool {
+    seqs.windows(2).all(|w| w[0] < w[1])
+}
+
+#[test]
+fn samples_are_numbered_from_one() {
+    let s = samples(COUNT);
+    assert_eq!(s.iter().map(|s| s.seq).collect::<Vec<_>>(), all_seqs());
+    assert!(s.iter().any(|x| x.sensor_id != s[0].sensor_id));
+}
+
+#[tokio::test(start_paused = true)]
+async fn mpsc_send_delivers_everything_and_slows_the_producer() {
+    let r = via_mpsc(&samples(COUNT), ms(10), &consumers(), 2, Overflow::Wait).await;
+    for d in &r.deliveries {
+        assert_eq!(d.received, all_seqs(), "{}", d.name);
+        assert_eq!(d.missed, 0);
+    }
+    // On schedule it would take 190 ms; the display sets the pace at 30
+    assert!(r.producer_time > ms(400), "{:?}", r.producer_time);
+}
+
+#[tokio::test(start_paused = true)]
+async fn mpsc_try_send_drops_for_the_full_queue_only() {
+    let r = via_mpsc(
+        &samples(COUNT),
+        ms(10),
+        &consumers(),
+        2,
+        Overflow::DropNewest,
+    )
+    .await;
+    assert_eq!(r.producer_time, ms(190));
+    let (uplink, display) = (&r.deliveries[0], &r.deliveries[1]);
+    assert_eq!(uplink.received, all_seqs());
+    assert!(display.missed > 0);
+    assert_eq!(display.received.len() as u64 + display.missed, COUNT as u64);
+    assert!(increasing(&display.received));
+    // The queue keeps the oldest: the first sample always gets through
+    assert_eq!(display.received[0], 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn mpsc_try_send_with_room_for_everything_loses_nothing() {
+    let r = via_mpsc(
+        &samples(COUNT),
+        ms(10),
+        &consumers(),
+        COUNT,
+        Overflow::DropNewest,
+    )
+    .await;
+    assert_eq!(r.producer_time, ms(190));
+    for d in &r.deliveries {
+        assert_eq!(d.received, all_seqs());
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn broadcast_reports_the_lag_of_a_slow_receiver() {
+    let r = via_broadcast(&samples(COUNT), ms(10), &consumers(), 4).await;
+    assert_eq!(r.producer_time, ms(190));
+    let (uplink, display) = (&r.deliveries[0], &r.deliveries[1]);
+    assert_eq!(uplink.received, all_seqs());
+    assert_eq!(uplink.missed, 0);
+    // Every sample is either received or counted by a Lagged error
+    assert!(display.missed > 0);
+    assert_eq!(display.received.len() as u64 + display.missed, COUNT as u64);
+    assert!(increasing(&display.received));
+    // What is still buffered when the sender drops is delivered before Closed
+    assert_eq!(display.received.last(), Some(&(COUNT as u64)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn broadcast_with_a_buffer_for_everything_does_not_lag() {
+    let r = via_broadcast(&samples(COUNT), ms(10), &consumers(), COUNT).await;
+    for d in &r.deliveries {
+        assert_eq!(d.received, all_seqs());
+        assert_eq!(d.missed, 0);
+    }
+}
+
+// Why the fan-out subscribes every consumer before the first send: with
+// no receivers a broadcast send fails and the value is gone
+#[test]
+fn a_broadcast_send_with_no_subscribers_is_lost() {
+    let (tx, rx) = tokio::sync::broadcast::channel::<u64>(4);
+    drop(rx);
+    assert!(tx.send(1).is_err());
+    let mut late = tx.subscribe();
+    assert!(late.try_recv().is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn watch_gives_a_slow_receiver_only_the_latest_value() {
+    let r = via_watch(&samples(COUNT), ms(10), &consumers()).await;
+    assert_eq!(r.producer_time, ms(190));
+    let (uplink, display) = (&r.deliveries[0], &r.deliveries[1]);
+    assert_eq!(uplink.received, all_seqs());
+    // One value per 30 ms, whichever is current when it looks, and
+    // the last one once the producer is done
+    assert_eq!(display.received, [1, 4, 7, 10, 13, 16, 19, 20]);
+    assert_eq!(display.missed, (COUNT - display.received.len()) as u64);
+}
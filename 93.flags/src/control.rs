@@ -0,0 +1,148 @@
+// Flag commands over a Unix socket, for an operator on the device
+//
+//   $ echo "set new-batcher 25%" | nc -U /run/gateway/control.sock
+//   ok new-batcher = 25% (control), disabled
+//
+// One command per connection, answered and closed, like the status
+// endpoint. The control layer is the highest, so a command overrides
+// the config file and the environment until `clear` hands the flag back
+// to them, or the process restarts. That makes it the kill switch: a
+// rollout going wrong on one device is turned off there at once, without
+// editing files.
+//
+//   list                 every flag: setting, layer, answer, description
+//   get NAME             one flag
+//   set NAME VALUE       on, off or N%, in the control layer
+//   clear NAME           drop the control layer's setting
+//
+// Only the owner of the socket file can connect: it is created with the
+// process's umask, and the directory it lives in should not be world
+// writable.
+
+use crate::registry::{FlagError, FlagState, Registry};
+use crate::setting::Source;
+use shutdown::Token;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+fn row(flag: &FlagState) -> String {
+    format!(
+        "{} = {} ({}), {}",
+        flag.name,
+        flag.setting,
+        flag.source,
+        if flag.enabled { "enabled" } else { "disabled" }
+    )
+}
+
+// The reply to one command line; starts with "ok" or "error"
+pub fn execute(registry: &Registry, line: &str) -> String {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let state = |name: &str| {
+        registry
+            .list()
+            .into_iter()
+            .find(|f| f.name == name)
+            .ok_or_else(|| FlagError::Unknown(name.to_string()))
+    };
+    let result = match words.as_slice() {
+        ["list"] => Ok(registry
+            .list()
+            .iter()
+            .map(|f| format!("{}  # {}", row(f), f.description))
+            .collect::<Vec<_>>()
+            .join("\n")),
+        ["get", name] => state(name).map(|f| row(&f)),
+        ["set", name, value] => match value.parse() {
+            Ok(setting) => registry
+                .set(name, Source::Control, Some(setting))
+                .and_then(|_| state(name))
+                .map(|f| row(&f)),
+            Err(_) => Err(FlagError::Value {
+                name: name.to_string(),
+                value: value.to_string(),
+            }),
+        },
+        ["clear", name] => registry
+            .set(name, Source::Control, None)
+            .and_then(|_| state(name))
+            .map(|f| row(&f)),
+        _ => return "error: expected list, get NAME, set NAME VALUE or clear NAME".to_string(),
+    };
+    match result {
+        Ok(text) if text.is_empty() => "ok".to_string(),
+        Ok(text) if text.contains('\n') => format!("ok\n{}", text),
+        Ok(text) => format!("ok {}", text),
+        Err(e) => format!("error: {}", e),
+    }
+}
+
+fn respond(stream: UnixStream, registry: &Registry) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_millis(500)))?;
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut stream = reader.into_inner();
+    writeln!(stream, "{}", execute(registry, &line))
+}
+
+#[derive(Debug)]
+pub struct ControlSocket {
+    path: PathBuf,
+    handle: JoinHandle<()>,
+}
+
+impl ControlSocket {
+    // Listens at `path`, replacing a socket file left by an earlier run
+    pub fn serve(path: &Path, registry: &'static Registry, token: Token) -> io::Result<Self> {
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)?;
+        // Non-blocking, so the thread notices the shutdown token
+        listener.set_nonblocking(true)?;
+        let handle = thread::spawn(move || {
+            while !token.is_cancelled() {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let _ = stream.set_nonblocking(false);
+                        if let Err(e) = respond(stream, registry) {
+                            eprintln!("control: {}", e);
+                        }
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                        token.wait_timeout(Duration::from_millis(20));
+                    }
+                    Err(e) => eprintln!("control: accept failed: {}", e),
+                }
+            }
+        });
+        Ok(ControlSocket {
+            path: path.to_path_buf(),
+            handle,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Waits for the thread and removes the socket file; the caller
+    // triggers the shutdown first
+    pub fn join(self) {
+        let _ = self.handle.join();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+// Sends one command and returns the reply, without the final newline
+pub fn send(path: &Path, command: &str) -> io::Result<String> {
+    let mut stream = UnixStream::connect(path)?;
+    writeln!(stream, "{}", command)?;
+    let mut reply = String::new();
+    io::Read::read_to_string(&mut stream, &mut reply)?;
+    Ok(reply.trim_end().to_string())
+}
@@ -0,0 +1,41 @@
+// Runtime feature flags for incremental rollout on a fleet of devices
+//
+// - `setting`: on, off or a percentage of devices, the stable bucket
+//   that decides which devices, and the layers a setting comes from
+// - `registry`: flags defined in code, overridden per layer, read with
+//   one atomic load, with change notifications
+// - `control`: `list`/`get`/`set`/`clear` over a Unix socket
+//
+// The process-wide registry is `FLAGS`; the free functions below use it:
+//
+//   flags::define("new-batcher", Setting::Off, "batch by size and age");
+//   if flags::enabled("new-batcher") { ... }
+
+pub mod control;
+pub mod registry;
+pub mod setting;
+
+pub use control::ControlSocket;
+pub use registry::{Change, Flag, FlagError, FlagState, Registry};
+pub use setting::{bucket, BadSetting, Setting, Source};
+
+use std::sync::mpsc::Receiver;
+use std::sync::LazyLock;
+
+pub static FLAGS: LazyLock<Registry> = LazyLock::new(Registry::new);
+
+pub fn define(name: &'static str, default: Setting, description: &'static str) -> &'static Flag {
+    FLAGS.define(name, default, description)
+}
+
+pub fn enabled(name: &str) -> bool {
+    FLAGS.enabled(name)
+}
+
+pub fn set_unit(unit: &str) -> Vec<Change> {
+    FLAGS.set_unit(unit)
+}
+
+pub fn subscribe() -> Receiver<Change> {
+    FLAGS.subscribe()
+}
@@ -0,0 +1,166 @@
+use flags::{control, ControlSocket, Setting, Source, FLAGS};
+use shutdown::Shutdown;
+use std::collections::HashSet;
+
+fn fleet() -> Vec<String> {
+    (0..500).map(|i| format!("gw-{:03}", i)).collect()
+}
+
+// The devices of `fleet` that a percentage turns the flag on for
+fn rolled_out<'a>(fleet: &'a [String], flag: &str, percent: u8) -> HashSet<&'a str> {
+    fleet
+        .iter()
+        .filter(|unit| Setting::Percent(percent).enabled_for(flag, unit))
+        .map(String::as_str)
+        .collect()
+}
+
+fn main() {
+    println!("=== Feature Flags Examples ===\n");
+
+    // 1. Flags defined in code
+    println!("1. Flags defined in code:");
+    let new_batcher = flags::define(
+        "new-batcher",
+        Setting::Off,
+        "batch uplink messages by size and age",
+    );
+    flags::define(
+        "compact-json",
+        Setting::On,
+        "shorter field names on the wire",
+    );
+    flags::set_unit("gw-042");
+    for flag in FLAGS.list() {
+        println!(
+            "   {:<13} {:<4} {}",
+            flag.name,
+            flag.setting.to_string(),
+            flag.description
+        );
+    }
+
+    // 2. Percentage rollout across a fleet
+    println!("\n2. Rolling out to a fleet of 500 devices:");
+    let fleet = fleet();
+    let stages = [1, 5, 25, 50, 100];
+    let mut previous: HashSet<&str> = HashSet::new();
+    for percent in stages {
+        let on = rolled_out(&fleet, "new-batcher", percent);
+        println!(
+            "   {:>3}%: {:>3} devices, {} of the last stage's kept",
+            percent,
+            on.len(),
+            previous.intersection(&on).count()
+        );
+        previous = on;
+    }
+    let a = rolled_out(&fleet, "new-batcher", 10);
+    let b = rolled_out(&fleet, "compact-json", 10);
+    println!(
+        "   10% of two flags: {} and {} devices, {} in both",
+        a.len(),
+        b.len(),
+        a.intersection(&b).count()
+    );
+
+    // 3. Layers: default < config < env < control
+    println!("\n3. Layers:");
+    let show = || {
+        let (setting, source) = new_batcher.setting();
+        println!(
+            "   new-batcher = {} from {}, {}",
+            setting,
+            source,
+            if new_batcher.enabled() {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        );
+    };
+    FLAGS
+        .replace_layer(Source::Config, [("new-batcher", "25%")])
+        .expect("valid");
+    show();
+    FLAGS
+        .apply_env(
+            "GATEWAY_FLAGS_",
+            [("GATEWAY_FLAGS_NEW_BATCHER".to_string(), "on".to_string())],
+        )
+        .expect("valid");
+    show();
+    FLAGS
+        .set("new-batcher", Source::Control, Some(Setting::Off))
+        .expect("defined");
+    show();
+    FLAGS
+        .set("new-batcher", Source::Control, None)
+        .expect("defined");
+    show();
+    let typo = FLAGS.replace_layer(Source::Config, [("new-bacther", "on")]);
+    println!("   config with a typo: {}", typo.clone().unwrap_err());
+    FLAGS
+        .apply_env("GATEWAY_FLAGS_", Vec::new())
+        .expect("valid");
+
+    // 4. Change notifications
+    println!("\n4. Change notifications:");
+    let changes = flags::subscribe();
+    FLAGS
+        .replace_layer(Source::Config, [("new-batcher", "50%")])
+        .expect("valid");
+    let first: Vec<_> = changes.try_iter().collect();
+    for change in &first {
+        println!("   {}", change);
+    }
+    FLAGS
+        .replace_layer(Source::Config, [("new-batcher", "50%")])
+        .expect("valid");
+    println!(
+        "   the same setting again: {} notification(s)",
+        changes.try_iter().count()
+    );
+    // Under another device id a percentage flag may flip either way
+    for unit in ["gw-001", "gw-002", "gw-003", "gw-004"] {
+        flags::set_unit(unit);
+        let sent = changes.try_iter().count();
+        println!(
+            "   as {}: new-batcher {}, {} notification(s)",
+            unit,
+            if new_batcher.enabled() { "on" } else { "off" },
+            sent
+        );
+    }
+
+    // 5. The control socket
+    println!("\n5. The control socket:");
+    let path = std::env::temp_dir().join(format!("rust-sys-flags-{}.sock", std::process::id()));
+    let shutdown = Shutdown::new();
+    let socket = match ControlSocket::serve(&path, &FLAGS, shutdown.token()) {
+        Ok(socket) => socket,
+        Err(e) => {
+            println!("   cannot listen at {}: {}", path.display(), e);
+            return;
+        }
+    };
+    let mut replies = Vec::new();
+    for command in [
+        "set new-batcher off",
+        "get new-batcher",
+        "set new-batcher maybe",
+        "clear new-batcher",
+        "list",
+    ] {
+        let reply = control::send(socket.path(), command).unwrap_or_else(|e| e.to_string());
+        println!("   > {}", command);
+        for line in reply.lines() {
+            println!("     {}", line);
+        }
+        replies.push(reply);
+    }
+    shutdown.trigger(shutdown::Reason::Requested("demo done"));
+    socket.join();
+
+    println!("\n=== End of Feature Flags Examples ===");
+}
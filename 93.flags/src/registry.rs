@@ -0,0 +1,327 @@
+// Flags defined in code, overridden in layers, read with one atomic load
+//
+//   define("new-batcher", Off) ──▶ &'static Flag ──▶ flag.enabled()   one atomic load
+//                                        ▲
+//   config / env / control ── set ───────┘ recompute ──▶ Change ──▶ subscribers
+//
+// Every flag the program uses is defined in code with a default, so a
+// config file can't invent one and a typo in an override is an error
+// instead of a silently ignored key. Each flag keeps one optional
+// setting per layer; the highest layer that has one wins. Whenever a
+// layer or the device id changes, the flag works out its answer for this
+// device and stores it in an `AtomicBool`. Reading a flag never takes a
+// lock or hashes a percentage.
+//
+// Like the counters in 83.global, each flag is leaked into a
+// `&'static Flag` on definition, so code on a hot path can keep the
+// handle and skip the name lookup.
+
+use crate::setting::{Setting, Source};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Mutex, RwLock};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlagError {
+    Unknown(String),
+    Value { name: String, value: String },
+}
+
+impl fmt::Display for FlagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlagError::Unknown(name) => write!(f, "no flag named {:?}", name),
+            FlagError::Value { name, value } => {
+                write!(f, "{}: {:?} is not on, off or a percentage", name, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FlagError {}
+
+// Sent to subscribers when a flag's answer or effective setting changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub name: &'static str,
+    pub setting: Setting,
+    pub source: Source,
+    pub enabled: bool,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} = {} ({}), {}",
+            self.name,
+            self.setting,
+            self.source,
+            if self.enabled { "enabled" } else { "disabled" }
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct Flag {
+    name: &'static str,
+    description: &'static str,
+    // Indexed by `Source as usize`; the default is always set
+    layers: Mutex<[Option<Setting>; 4]>,
+    enabled: AtomicBool,
+}
+
+impl Flag {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+
+    // Relaxed is enough: the flag guards which code path runs, it doesn't
+    // publish data that the other path reads
+    pub fn enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    // The effective setting and the layer it comes from
+    pub fn setting(&self) -> (Setting, Source) {
+        let layers = self.layers.lock().unwrap_or_else(|e| e.into_inner());
+        effective(&layers)
+    }
+}
+
+fn effective(layers: &[Option<Setting>; 4]) -> (Setting, Source) {
+    Source::ALL
+        .iter()
+        .rev()
+        .find_map(|&source| layers[source as usize].map(|s| (s, source)))
+        .expect("the default layer is always set")
+}
+
+// One row of `Registry::list`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlagState {
+    pub name: &'static str,
+    pub setting: Setting,
+    pub source: Source,
+    pub enabled: bool,
+    pub description: &'static str,
+}
+
+#[derive(Debug)]
+pub struct Registry {
+    // The device id that percentages are decided for
+    unit: RwLock<String>,
+    flags: RwLock<HashMap<&'static str, &'static Flag>>,
+    subscribers: Mutex<Vec<Sender<Change>>>,
+}
+
+impl Registry {
+    // Only the global `FLAGS` needs one, but a private registry keeps
+    // tests from sharing flags
+    pub fn new() -> Registry {
+        Registry {
+            unit: RwLock::new(String::new()),
+            flags: RwLock::new(HashMap::new()),
+            subscribers: Mutex::new(Vec::new()),
+        }
+    }
+
+    // Defines a flag and returns its handle. Defining a name again
+    // returns the existing flag; the first default stays.
+    pub fn define(
+        &self,
+        name: &'static str,
+        default: Setting,
+        description: &'static str,
+    ) -> &'static Flag {
+        let mut flags = self.flags.write().unwrap_or_else(|e| e.into_inner());
+        flags.entry(name).or_insert_with(|| {
+            let unit = self.unit.read().unwrap_or_else(|e| e.into_inner());
+            let flag = Flag {
+                name,
+                description,
+                layers: Mutex::new([Some(default), None, None, None]),
+                enabled: AtomicBool::new(default.enabled_for(name, &unit)),
+            };
+            Box::leak(Box::new(flag))
+        })
+    }
+
+    pub fn flag(&self, name: &str) -> Option<&'static Flag> {
+        self.flags
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .copied()
+    }
+
+    // False for a name that was never defined, like a flag that is off
+    pub fn enabled(&self, name: &str) -> bool {
+        self.flag(name).is_some_and(Flag::enabled)
+    }
+
+    pub fn unit(&self) -> String {
+        self.unit.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    // Sets the device id and re-decides every percentage flag for it
+    pub fn set_unit(&self, unit: &str) -> Vec<Change> {
+        *self.unit.write().unwrap_or_else(|e| e.into_inner()) = unit.to_string();
+        let all: Vec<&'static Flag> = self.all();
+        all.into_iter()
+            .filter_map(|flag| self.update(flag, |_| {}))
+            .collect()
+    }
+
+    // Sets (`Some`) or clears (`None`) one layer of one flag. Returns the
+    // change if the flag's effective setting or answer changed.
+    pub fn set(
+        &self,
+        name: &str,
+        source: Source,
+        setting: Option<Setting>,
+    ) -> Result<Option<Change>, FlagError> {
+        assert!(source != Source::Default, "defaults are set by `define`");
+        let flag = self
+            .flag(name)
+            .ok_or_else(|| FlagError::Unknown(name.to_string()))?;
+        Ok(self.update(flag, |layers| layers[source as usize] = setting))
+    }
+
+    // Replaces a whole layer with `settings`, e.g. the `[flags]` table of a
+    // reloaded config file; flags missing from it lose that layer. Nothing
+    // changes if any name or value is invalid.
+    pub fn replace_layer<I, K, V>(
+        &self,
+        source: Source,
+        settings: I,
+    ) -> Result<Vec<Change>, FlagError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        assert!(source != Source::Default, "defaults are set by `define`");
+        let mut parsed: HashMap<&'static str, Setting> = HashMap::new();
+        for (name, value) in settings {
+            let (name, value) = (name.as_ref(), value.as_ref());
+            let flag = self
+                .flag(name)
+                .ok_or_else(|| FlagError::Unknown(name.to_string()))?;
+            let setting = value.parse().map_err(|_| FlagError::Value {
+                name: name.to_string(),
+                value: value.to_string(),
+            })?;
+            parsed.insert(flag.name, setting);
+        }
+        let all: Vec<&'static Flag> = self.all();
+        Ok(all
+            .into_iter()
+            .filter_map(|flag| {
+                let setting = parsed.get(flag.name).copied();
+                self.update(flag, |layers| layers[source as usize] = setting)
+            })
+            .collect())
+    }
+
+    // The env layer from variables named `prefix` + the flag name in
+    // upper case with `-` as `_`: `GATEWAY_FLAGS_NEW_BATCHER=25%`. Other
+    // variables are ignored.
+    pub fn apply_env<I>(&self, prefix: &str, vars: I) -> Result<Vec<Change>, FlagError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut settings = Vec::new();
+        for (key, value) in vars {
+            let Some(rest) = key.strip_prefix(prefix) else {
+                continue;
+            };
+            let name = rest.to_ascii_lowercase().replace('_', "-");
+            settings.push((name, value));
+        }
+        self.replace_layer(Source::Env, settings)
+    }
+
+    // A receiver for every later change. Subscribers that have gone away
+    // are dropped on the next change.
+    pub fn subscribe(&self) -> Receiver<Change> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(tx);
+        rx
+    }
+
+    // Every flag, sorted by name
+    pub fn list(&self) -> Vec<FlagState> {
+        self.all()
+            .into_iter()
+            .map(|flag| {
+                let (setting, source) = flag.setting();
+                FlagState {
+                    name: flag.name,
+                    setting,
+                    source,
+                    enabled: flag.enabled(),
+                    description: flag.description,
+                }
+            })
+            .collect()
+    }
+
+    fn all(&self) -> Vec<&'static Flag> {
+        let mut all: Vec<&'static Flag> = self
+            .flags
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .copied()
+            .collect();
+        all.sort_unstable_by_key(|flag| flag.name);
+        all
+    }
+
+    // Edits the flag's layers, stores the new answer and tells the
+    // subscribers if anything they can see changed
+    fn update(
+        &self,
+        flag: &'static Flag,
+        edit: impl FnOnce(&mut [Option<Setting>; 4]),
+    ) -> Option<Change> {
+        let unit = self.unit();
+        let mut layers = flag.layers.lock().unwrap_or_else(|e| e.into_inner());
+        let before = (effective(&layers), flag.enabled());
+        edit(&mut layers);
+        let (setting, source) = effective(&layers);
+        let enabled = setting.enabled_for(flag.name, &unit);
+        flag.enabled.store(enabled, Ordering::Relaxed);
+        drop(layers);
+        if before == ((setting, source), enabled) {
+            return None;
+        }
+        let change = Change {
+            name: flag.name,
+            setting,
+            source,
+            enabled,
+        };
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|tx| tx.send(change.clone()).is_ok());
+        Some(change)
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Registry {
+        Registry::new()
+    }
+}
@@ -0,0 +1,113 @@
+// What a flag is set to, and which layer set it
+//
+//   default (code) < config file < environment < control socket
+//
+// A flag is on, off, or on for a percentage of devices. Which devices
+// is decided by hashing the flag name with the device's id into a
+// bucket 0..100: a device is in if its bucket is below the percentage.
+// The bucket never changes, so raising 5% to 25% keeps the first 5% and
+// adds more, and every device keeps its answer across restarts. The
+// flag name is part of the hash, so the first 5% for one flag are not
+// the same devices as for another.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    Off,
+    On,
+    // On for this share of devices, 0..=100
+    Percent(u8),
+}
+
+impl Setting {
+    // Whether the flag is on for the device `unit`
+    pub fn enabled_for(self, flag: &str, unit: &str) -> bool {
+        match self {
+            Setting::Off => false,
+            Setting::On => true,
+            Setting::Percent(p) => bucket(flag, unit) < p as u32,
+        }
+    }
+}
+
+impl fmt::Display for Setting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Setting::Off => write!(f, "off"),
+            Setting::On => write!(f, "on"),
+            Setting::Percent(p) => write!(f, "{}%", p),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BadSetting(pub String);
+
+impl fmt::Display for BadSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} is not on, off or a percentage 0%..100%", self.0)
+    }
+}
+
+impl std::error::Error for BadSetting {}
+
+impl FromStr for Setting {
+    type Err = BadSetting;
+
+    // on/off, true/false, 1/0, or a percentage such as "25%"
+    fn from_str(s: &str) -> Result<Setting, BadSetting> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "on" | "true" | "1" => Ok(Setting::On),
+            "off" | "false" | "0" => Ok(Setting::Off),
+            other => other
+                .strip_suffix('%')
+                .and_then(|p| p.trim().parse::<u8>().ok())
+                .filter(|p| *p <= 100)
+                .map(Setting::Percent)
+                .ok_or_else(|| BadSetting(s.to_string())),
+        }
+    }
+}
+
+// Later layers win
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Source {
+    Default,
+    Config,
+    Env,
+    Control,
+}
+
+impl Source {
+    pub const ALL: [Source; 4] = [
+        Source::Default,
+        Source::Config,
+        Source::Env,
+        Source::Control,
+    ];
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Source::Default => "default",
+            Source::Config => "config",
+            Source::Env => "env",
+            Source::Control => "control",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// The device's bucket for `flag`, 0..100: FNV-1a of "flag/unit". Stable
+// across runs and platforms, unlike `DefaultHasher`.
+pub fn bucket(flag: &str, unit: &str) -> u32 {
+    let mut hash: u32 = 0x811c_9dc5;
+    for byte in flag.bytes().chain([b'/']).chain(unit.bytes()) {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash % 100
+}
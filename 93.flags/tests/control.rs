@@ -0,0 +1,82 @@
+// Control commands, directly and over a real Unix socket
+
+use flags::control::{self, execute};
+use flags::{ControlSocket, Registry, Setting, Source};
+use shutdown::{Reason, Shutdown};
+
+fn registry() -> &'static Registry {
+    let registry: &'static Registry = Box::leak(Box::new(Registry::new()));
+    registry.define("new-batcher", Setting::Off, "batch by size and age");
+    registry.define("compact-json", Setting::On, "shorter field names");
+    registry
+}
+
+#[test]
+fn commands_set_get_clear_and_list() {
+    let flags = registry();
+    flags
+        .set("new-batcher", Source::Config, Some(Setting::Percent(0)))
+        .unwrap();
+    assert_eq!(
+        execute(flags, "set new-batcher on"),
+        "ok new-batcher = on (control), enabled"
+    );
+    assert_eq!(
+        execute(flags, "get new-batcher\n"),
+        "ok new-batcher = on (control), enabled"
+    );
+    assert_eq!(
+        execute(flags, "clear new-batcher"),
+        "ok new-batcher = 0% (config), disabled"
+    );
+    assert_eq!(
+        execute(flags, "list"),
+        "ok\n\
+         compact-json = on (default), enabled  # shorter field names\n\
+         new-batcher = 0% (config), disabled  # batch by size and age"
+    );
+}
+
+#[test]
+fn bad_commands_are_errors_and_change_nothing() {
+    let flags = registry();
+    for command in ["", "set new-batcher", "frobnicate", "get"] {
+        assert!(
+            execute(flags, command).starts_with("error: expected"),
+            "{:?}",
+            command
+        );
+    }
+    assert_eq!(
+        execute(flags, "set new-batcher 150%"),
+        "error: new-batcher: \"150%\" is not on, off or a percentage"
+    );
+    assert_eq!(execute(flags, "get nope"), "error: no flag named \"nope\"");
+    assert_eq!(
+        flags.flag("new-batcher").unwrap().setting(),
+        (Setting::Off, Source::Default)
+    );
+}
+
+#[test]
+fn the_socket_answers_one_command_per_connection() {
+    let flags = registry();
+    let path =
+        std::env::temp_dir().join(format!("rust-sys-flags-test-{}.sock", std::process::id()));
+    // A stale socket file from an earlier run is replaced
+    std::fs::write(&path, "stale").unwrap();
+    let shutdown = Shutdown::new();
+    let socket = ControlSocket::serve(&path, flags, shutdown.token()).unwrap();
+
+    assert_eq!(
+        control::send(&path, "set new-batcher 100%").unwrap(),
+        "ok new-batcher = 100% (control), enabled"
+    );
+    assert!(flags.enabled("new-batcher"));
+    assert_eq!(control::send(&path, "list").unwrap().lines().count(), 3);
+
+    shutdown.trigger(Reason::Requested("test done"));
+    socket.join();
+    assert!(!path.exists());
+    assert!(control::send(&path, "list").is_err());
+}
@@ -0,0 +1,289 @@
+// Settings, buckets, layers and notifications on private registries, so
+// tests running in parallel don't share flags
+
+use flags::{bucket, Change, FlagError, Registry, Setting, Source};
+
+fn registry() -> &'static Registry {
+    let registry: &'static Registry = Box::leak(Box::new(Registry::new()));
+    registry.define("new-batcher", Setting::Off, "batch by size and age");
+    registry.define("compact-json", Setting::On, "shorter field names");
+    registry
+}
+
+fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn settings_parse_and_print() {
+    for (text, setting) in [
+        ("on", Setting::On),
+        ("TRUE", Setting::On),
+        ("1", Setting::On),
+        ("off", Setting::Off),
+        ("false", Setting::Off),
+        ("25%", Setting::Percent(25)),
+        (" 100% ", Setting::Percent(100)),
+        ("0%", Setting::Percent(0)),
+    ] {
+        assert_eq!(text.parse::<Setting>(), Ok(setting), "{:?}", text);
+    }
+    for bad in ["", "yes please", "101%", "-5%", "25"] {
+        assert!(bad.parse::<Setting>().is_err(), "{:?}", bad);
+    }
+    assert_eq!(Setting::Percent(25).to_string(), "25%");
+    assert_eq!(Setting::Off.to_string(), "off");
+}
+
+#[test]
+fn buckets_are_stable_and_rollouts_only_grow() {
+    // Fixed values: a device must get the same answer after an update
+    assert_eq!(
+        bucket("new-batcher", "gw-042"),
+        bucket("new-batcher", "gw-042")
+    );
+    let units: Vec<String> = (0..1000).map(|i| format!("gw-{}", i)).collect();
+    let on = |percent: u8| -> Vec<&String> {
+        units
+            .iter()
+            .filter(|u| Setting::Percent(percent).enabled_for("new-batcher", u))
+            .collect()
+    };
+    let mut last = Vec::new();
+    for percent in [0, 1, 10, 25, 50, 90, 100] {
+        let now = on(percent);
+        assert!(
+            last.iter().all(|u| now.contains(u)),
+            "{}% lost devices",
+            percent
+        );
+        // Near the percentage on a large fleet
+        let share = now.len() as f64 / units.len() as f64 * 100.0;
+        assert!(
+            (share - percent as f64).abs() <= 4.0,
+            "{}%: {}",
+            percent,
+            share
+        );
+        last = now;
+    }
+    assert_eq!(on(0).len(), 0);
+    assert_eq!(on(100).len(), units.len());
+}
+
+// The flag name is hashed with the device, so the first 10% of one
+// rollout are not always the same devices as the first 10% of the next
+#[test]
+fn each_flag_picks_its_own_devices() {
+    let units: Vec<String> = (0..500).map(|i| format!("gw-{:03}", i)).collect();
+    let on = |flag: &str| -> Vec<&String> {
+        units
+            .iter()
+            .filter(|u| Setting::Percent(10).enabled_for(flag, u))
+            .collect()
+    };
+    let (a, b) = (on("new-batcher"), on("compact-json"));
+    assert!(!a.is_empty() && !b.is_empty());
+    let both = a.iter().filter(|u| b.contains(u)).count();
+    assert!(both < a.len() / 2, "{} of {} shared", both, a.len());
+}
+
+#[test]
+fn higher_layers_win_and_clearing_falls_back() {
+    let flags = registry();
+    let flag = flags.flag("new-batcher").unwrap();
+    assert_eq!(flag.setting(), (Setting::Off, Source::Default));
+
+    flags
+        .set("new-batcher", Source::Env, Some(Setting::On))
+        .unwrap();
+    flags
+        .set("new-batcher", Source::Config, Some(Setting::Off))
+        .unwrap();
+    // Config is below env, so env still wins
+    assert_eq!(flag.setting(), (Setting::On, Source::Env));
+    assert!(flags.enabled("new-batcher"));
+
+    flags
+        .set("new-batcher", Source::Control, Some(Setting::Off))
+        .unwrap();
+    assert_eq!(flag.setting(), (Setting::Off, Source::Control));
+    assert!(!flag.enabled());
+
+    flags.set("new-batcher", Source::Control, None).unwrap();
+    flags.set("new-batcher", Source::Env, None).unwrap();
+    assert_eq!(flag.setting(), (Setting::Off, Source::Config));
+}
+
+#[test]
+fn define_twice_keeps_the_first_flag() {
+    let flags = registry();
+    let again = flags.define("new-batcher", Setting::On, "other");
+    assert!(std::ptr::eq(again, flags.flag("new-batcher").unwrap()));
+    assert_eq!(again.setting(), (Setting::Off, Source::Default));
+    assert_eq!(again.description(), "batch by size and age");
+}
+
+#[test]
+fn unknown_names_and_bad_values_change_nothing() {
+    let flags = registry();
+    assert!(!flags.enabled("no-such-flag"));
+    assert_eq!(
+        flags.set("no-such-flag", Source::Control, Some(Setting::On)),
+        Err(FlagError::Unknown("no-such-flag".to_string()))
+    );
+    flags
+        .replace_layer(Source::Config, [("new-batcher", "on")])
+        .unwrap();
+    // One bad entry rejects the whole layer
+    let result = flags.replace_layer(
+        Source::Config,
+        [("compact-json", "off"), ("new-batcher", "sometimes")],
+    );
+    assert!(matches!(result, Err(FlagError::Value { .. })));
+    assert!(flags.enabled("new-batcher"));
+    assert!(flags.enabled("compact-json"));
+}
+
+#[test]
+fn replacing_a_layer_clears_flags_missing_from_it() {
+    let flags = registry();
+    flags
+        .replace_layer(
+            Source::Config,
+            [("new-batcher", "on"), ("compact-json", "off")],
+        )
+        .unwrap();
+    let changes = flags
+        .replace_layer(Source::Config, [("new-batcher", "on")])
+        .unwrap();
+    assert_eq!(
+        changes,
+        [Change {
+            name: "compact-json",
+            setting: Setting::On,
+            source: Source::Default,
+            enabled: true,
+        }]
+    );
+    assert!(flags.enabled("new-batcher"));
+}
+
+#[test]
+fn env_names_map_to_flag_names() {
+    let flags = registry();
+    let changes = flags
+        .apply_env(
+            "GATEWAY_FLAGS_",
+            vars(&[
+                ("GATEWAY_FLAGS_NEW_BATCHER", "on"),
+                ("GATEWAY_ID", "gw-9"),
+                ("PATH", "/usr/bin"),
+            ]),
+        )
+        .unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(
+        flags.flag("new-batcher").unwrap().setting(),
+        (Setting::On, Source::Env)
+    );
+    let typo = flags.apply_env("GATEWAY_FLAGS_", vars(&[("GATEWAY_FLAGS_NEW_BATCH", "on")]));
+    assert_eq!(typo, Err(FlagError::Unknown("new-batch".to_string())));
+}
+
+#[test]
+fn subscribers_hear_about_effective_changes_only() {
+    let flags = registry();
+    let rx = flags.subscribe();
+    flags
+        .set("new-batcher", Source::Config, Some(Setting::On))
+        .unwrap();
+    // Same setting from the same layer: nothing changed
+    flags
+        .set("new-batcher", Source::Config, Some(Setting::On))
+        .unwrap();
+    // A lower layer under a higher one: nothing visible changed
+    flags
+        .set("compact-json", Source::Config, Some(Setting::On))
+        .unwrap();
+    let got: Vec<Change> = rx.try_iter().collect();
+    assert_eq!(got.len(), 2);
+    assert_eq!(got[0].name, "new-batcher");
+    assert!(got[0].enabled);
+    // The source moved from default to config, though the answer didn't
+    assert_eq!(
+        (got[1].name, got[1].source),
+        ("compact-json", Source::Config)
+    );
+
+    // A dropped receiver is forgotten, the others still get changes
+    let second = flags.subscribe();
+    drop(rx);
+    flags
+        .set("new-batcher", Source::Control, Some(Setting::Off))
+        .unwrap();
+    assert_eq!(second.try_iter().count(), 1);
+}
+
+#[test]
+fn a_percentage_is_decided_per_device() {
+    let flags = registry();
+    flags
+        .set("new-batcher", Source::Config, Some(Setting::Percent(50)))
+        .unwrap();
+    let flag = flags.flag("new-batcher").unwrap();
+    let units: Vec<String> = (0..40).map(|i| format!("gw-{}", i)).collect();
+    let mut answers = Vec::new();
+    for unit in &units {
+        flags.set_unit(unit);
+        assert_eq!(
+            flag.enabled(),
+            Setting::Percent(50).enabled_for("new-batcher", unit)
+        );
+        answers.push(flag.enabled());
+    }
+    assert!(answers.contains(&true) && answers.contains(&false));
+    // Defined after the unit was set: decided for it straight away
+    let late = flags.define("late", Setting::Percent(50), "");
+    assert_eq!(
+        late.enabled(),
+        Setting::Percent(50).enabled_for("late", units.last().unwrap())
+    );
+}
+
+#[test]
+fn a_new_device_id_notifies_only_on_a_flip() {
+    let flags = registry();
+    flags
+        .set("new-batcher", Source::Config, Some(Setting::Percent(50)))
+        .unwrap();
+    let flag = flags.flag("new-batcher").unwrap();
+    let rx = flags.subscribe();
+    let mut flips = 0;
+    for i in 0..40 {
+        let before = flag.enabled();
+        flags.set_unit(&format!("gw-{}", i));
+        let sent: Vec<Change> = rx.try_iter().collect();
+        if before != flag.enabled() {
+            flips += 1;
+            assert_eq!(sent.len(), 1, "gw-{}", i);
+            assert_eq!(sent[0].enabled, flag.enabled());
+        } else {
+            assert!(sent.is_empty(), "gw-{}", i);
+        }
+    }
+    assert!(flips > 0);
+}
+
+#[test]
+fn the_global_registry_backs_the_free_functions() {
+    let flag = flags::define("global-test-flag", Setting::On, "");
+    assert!(flags::enabled("global-test-flag"));
+    assert!(std::ptr::eq(
+        flag,
+        flags::FLAGS.flag("global-test-flag").unwrap()
+    ));
+}
@@ -0,0 +1,80 @@
+// Counters, gauges and histograms in a global registry, exposed in the
+// Prometheus text format
+//
+// - `metric`: `Counter`, `Gauge`, `Histogram`, each a few atomics
+// - `registry`: metrics by name and labels, leaked into `&'static` handles
+// - `text`: the exposition format a Prometheus server scrapes
+//
+// The process-wide registry is `REGISTRY`. The macros look a metric up
+// once per call site and keep the handle, so on a hot path an increment
+// is one atomic add:
+//
+//   metrics::counter!("uplink_batches_total", "Batches sent", "result" => "sent").inc();
+//   metrics::histogram!("poll_seconds", "One poll cycle", metrics::DEFAULT_BUCKETS)
+//       .observe_duration(started.elapsed());
+//   let body = metrics::render();
+
+pub mod metric;
+pub mod registry;
+pub mod text;
+
+pub use metric::{Counter, Gauge, Histogram, HistogramSnapshot, DEFAULT_BUCKETS};
+pub use registry::{Family, Kind, Labels, Metric, Registry};
+pub use text::CONTENT_TYPE;
+
+use std::sync::LazyLock;
+
+pub static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::new);
+
+pub fn counter(name: &'static str, help: &'static str) -> &'static Counter {
+    REGISTRY.counter(name, help)
+}
+
+pub fn gauge(name: &'static str, help: &'static str) -> &'static Gauge {
+    REGISTRY.gauge(name, help)
+}
+
+pub fn histogram(name: &'static str, help: &'static str, bounds: &[f64]) -> &'static Histogram {
+    REGISTRY.histogram(name, help, bounds)
+}
+
+// Everything in `REGISTRY`, in the text format
+pub fn render() -> String {
+    text::render(&REGISTRY)
+}
+
+// The handle for a metric in `REGISTRY`, registered by the first call at
+// this call site and cached in a `OnceLock` of its own; later calls skip
+// the registry's lock. Name, help and labels must be literals, since the
+// cache would otherwise keep whatever the first call passed. For label
+// values only known at run time, call `REGISTRY.counter_with` once and
+// keep the handle.
+#[macro_export]
+macro_rules! counter {
+    ($name:literal, $help:literal $(, $key:literal => $value:literal)* $(,)?) => {{
+        static HANDLE: ::std::sync::OnceLock<&'static $crate::Counter> =
+            ::std::sync::OnceLock::new();
+        *HANDLE.get_or_init(|| $crate::REGISTRY.counter_with($name, $help, &[$(($key, $value)),*]))
+    }};
+}
+
+#[macro_export]
+macro_rules! gauge {
+    ($name:literal, $help:literal $(, $key:literal => $value:literal)* $(,)?) => {{
+        static HANDLE: ::std::sync::OnceLock<&'static $crate::Gauge> =
+            ::std::sync::OnceLock::new();
+        *HANDLE.get_or_init(|| $crate::REGISTRY.gauge_with($name, $help, &[$(($key, $value)),*]))
+    }};
+}
+
+// The bounds are only used by the call that registers the series
+#[macro_export]
+macro_rules! histogram {
+    ($name:literal, $help:literal, $bounds:expr $(, $key:literal => $value:literal)* $(,)?) => {{
+        static HANDLE: ::std::sync::OnceLock<&'static $crate::Histogram> =
+            ::std::sync::OnceLock::new();
+        *HANDLE.get_or_init(|| {
+            $crate::REGISTRY.histogram_with($name, $help, $bounds, &[$(($key, $value)),*])
+        })
+    }};
+}
@@ -0,0 +1,156 @@
+use metrics::{counter, gauge, histogram, text, Registry, DEFAULT_BUCKETS, REGISTRY};
+use std::panic;
+use std::thread;
+use std::time::Instant;
+
+// Uplink round trips in seconds, mostly fast with a slow tail
+fn latencies() -> Vec<f64> {
+    (0..1000u64)
+        .map(|i| {
+            let x = (i * 7919 % 1000) as f64 / 1000.0;
+            0.002 + 0.03 * x * x * x + if i % 97 == 0 { 1.5 } else { 0.0 }
+        })
+        .collect()
+}
+
+fn main() {
+    println!("=== Metrics Examples ===\n");
+
+    // 1. Counters and gauges
+    println!("1. Counters and gauges:");
+    let sent = metrics::counter("demo_messages_sent_total", "Messages sent upstream");
+    let queued = metrics::gauge("demo_queue_depth", "Messages waiting to be sent");
+    for i in 0..50 {
+        queued.inc();
+        if i % 5 == 4 {
+            // Every fifth message the queue drains four
+            queued.sub(4.0);
+            sent.add(4);
+        }
+    }
+    println!("   sent {}, queued {}", sent.get(), queued.get());
+    let temperature = metrics::gauge("demo_temperature_celsius", "Board temperature");
+    temperature.set(-3.25);
+
+    // 2. Histograms
+    println!("\n2. A histogram of 1000 round trips:");
+    let rtt = metrics::histogram(
+        "demo_uplink_rtt_seconds",
+        "Uplink round trip time",
+        DEFAULT_BUCKETS,
+    );
+    let samples = latencies();
+    for &s in &samples {
+        rtt.observe(s);
+    }
+    rtt.observe(f64::NAN);
+    let snapshot = rtt.snapshot();
+    for (bound, count) in &snapshot.buckets {
+        println!("   le {:>5}: {:>4}", text::format_value(*bound), count);
+    }
+    println!(
+        "   sum {:.3} s over {} observations",
+        snapshot.sum, snapshot.count
+    );
+    println!(
+        "   {} of {} samples under 10 ms, NaN not counted",
+        samples.iter().filter(|&&s| s <= 0.01).count(),
+        samples.len()
+    );
+
+    // 3. Labels
+    println!("\n3. Labels:");
+    let help = "Batches handed to the uplink, by outcome";
+    let ok = REGISTRY.counter_with("demo_batches_total", help, &[("result", "sent")]);
+    let dropped = REGISTRY.counter_with("demo_batches_total", help, &[("result", "dropped")]);
+    ok.add(40);
+    dropped.add(2);
+    // Label values known only at run time: look up once, keep the handle
+    let per_node: Vec<_> = (0..3)
+        .map(|node| {
+            let node = node.to_string();
+            REGISTRY.gauge_with(
+                "demo_node_battery_volts",
+                "Battery voltage per node",
+                &[("node", &node), ("site", "lab")],
+            )
+        })
+        .collect();
+    for (i, gauge) in per_node.iter().enumerate() {
+        gauge.set(3.3 + i as f64 / 10.0);
+    }
+    let again = REGISTRY.gauge_with(
+        "demo_node_battery_volts",
+        "Battery voltage per node",
+        &[("site", "lab"), ("node", "1")],
+    );
+    println!(
+        "   node 1 looked up with its labels reversed: {} V",
+        again.get()
+    );
+    // A bug in the caller, so it panics; the hook keeps the backtrace quiet
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let wrong_kind = panic::catch_unwind(|| {
+        REGISTRY.gauge("demo_messages_sent_total", "");
+    });
+    panic::set_hook(hook);
+    if let Err(payload) = &wrong_kind {
+        let message = payload
+            .downcast_ref::<String>()
+            .cloned()
+            .unwrap_or_default();
+        println!("   gauge(\"demo_messages_sent_total\") panics: {}", message);
+    }
+
+    // 4. Macros on a hot path
+    println!("\n4. Macros across 8 threads:");
+    const PER_THREAD: u64 = 100_000;
+    let started = Instant::now();
+    thread::scope(|s| {
+        for _ in 0..8 {
+            s.spawn(|| {
+                for _ in 0..PER_THREAD {
+                    counter!("demo_readings_total", "Sensor readings processed").inc();
+                }
+            });
+        }
+    });
+    let macro_ns = started.elapsed().as_nanos() as f64 / (8 * PER_THREAD) as f64;
+    let started = Instant::now();
+    for _ in 0..PER_THREAD {
+        REGISTRY
+            .counter("demo_lookups_total", "Counted by name every time")
+            .inc();
+    }
+    let lookup_ns = started.elapsed().as_nanos() as f64 / PER_THREAD as f64;
+    let total = counter!("demo_readings_total", "Sensor readings processed").get();
+    println!(
+        "   {} increments; ~{:.1} ns each through the macro, ~{:.1} ns by name",
+        total, macro_ns, lookup_ns
+    );
+    gauge!("demo_up", "1 while the demo runs").set(1.0);
+    histogram!("demo_step_seconds", "One demo step", &[0.001, 0.01, 0.1]).observe(0.004);
+
+    // 5. The exposition
+    println!("\n5. What a scrape returns:");
+    let body = metrics::render();
+    for line in body.lines().filter(|l| {
+        l.contains("demo_batches") || l.contains("demo_node") || l.contains("demo_step")
+    }) {
+        println!("   {}", line);
+    }
+    println!(
+        "   ... {} lines in all, served as {}",
+        body.lines().count(),
+        metrics::CONTENT_TYPE
+    );
+    let private = Registry::new();
+    private
+        .counter("escaped_total", "Line one\nback\\slash")
+        .inc();
+    let escaped = text::render(&private);
+    println!("   {}", escaped.lines().next().unwrap_or_default());
+
+    println!("\n=== End of Metrics Examples ===");
+}
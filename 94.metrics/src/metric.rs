@@ -0,0 +1,163 @@
+// The three metric types, each a handful of atomics
+//
+//   Counter     only goes up: requests, bytes, errors      rate() over time
+//   Gauge       goes up and down: queue depth, temperature  the value now
+//   Histogram   counts observations per bucket, plus a sum  quantiles, averages
+//
+// A counter never resets while the process runs. The server that scrapes
+// it sees a drop to zero as a restart and carries on from there, so a
+// `take` like 83.global's `Counter` has would read as a restart on every
+// scrape.
+//
+// A gauge holds an `f64`, kept as its bits in an `AtomicU64`; `add` is a
+// compare-and-swap loop, because there is no atomic float add.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+pub struct Counter {
+    value: AtomicU64,
+}
+
+impl Counter {
+    pub const fn new() -> Counter {
+        Counter {
+            value: AtomicU64::new(0),
+        }
+    }
+
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    pub fn add(&self, n: u64) {
+        self.value.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Gauge {
+    bits: AtomicU64,
+}
+
+impl Gauge {
+    pub const fn new() -> Gauge {
+        // 0.0 is all zero bits
+        Gauge {
+            bits: AtomicU64::new(0),
+        }
+    }
+
+    pub fn set(&self, value: f64) {
+        self.bits.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> f64 {
+        f64::from_bits(self.bits.load(Ordering::Relaxed))
+    }
+
+    pub fn add(&self, delta: f64) {
+        let _ = self
+            .bits
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                Some((f64::from_bits(bits) + delta).to_bits())
+            });
+    }
+
+    pub fn sub(&self, delta: f64) {
+        self.add(-delta);
+    }
+
+    pub fn inc(&self) {
+        self.add(1.0);
+    }
+
+    pub fn dec(&self) {
+        self.add(-1.0);
+    }
+}
+
+// Upper bounds for latencies in seconds, 5 ms to 10 s, as most
+// Prometheus clients use
+pub const DEFAULT_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+// Counts per bucket, with the bucket bounds fixed when the histogram is
+// created. An observation lands in the first bucket whose bound is at
+// least the value; larger ones land in the implicit `+Inf` bucket.
+#[derive(Debug)]
+pub struct Histogram {
+    bounds: Box<[f64]>,
+    // One more than `bounds`: the last is `+Inf`. Not cumulative; the
+    // exposition adds them up.
+    counts: Box<[AtomicU64]>,
+    sum: Gauge,
+}
+
+// What `Histogram::snapshot` read: cumulative counts per upper bound,
+// ending with `+Inf`, whose count is the total
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramSnapshot {
+    pub buckets: Vec<(f64, u64)>,
+    pub sum: f64,
+    pub count: u64,
+}
+
+impl Histogram {
+    // Panics unless `bounds` are finite and strictly increasing: the
+    // buckets are part of the metric's definition, fixed in code
+    pub fn new(bounds: &[f64]) -> Histogram {
+        assert!(
+            bounds.iter().all(|b| b.is_finite()) && bounds.windows(2).all(|w| w[0] < w[1]),
+            "histogram bounds must be finite and increasing: {:?}",
+            bounds
+        );
+        Histogram {
+            bounds: bounds.into(),
+            counts: (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect(),
+            sum: Gauge::new(),
+        }
+    }
+
+    // NaN is ignored: it belongs in no bucket and would make the sum NaN
+    pub fn observe(&self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        let i = self.bounds.partition_point(|&bound| bound < value);
+        self.counts[i].fetch_add(1, Ordering::Relaxed);
+        self.sum.add(value);
+    }
+
+    pub fn observe_duration(&self, elapsed: Duration) {
+        self.observe(elapsed.as_secs_f64());
+    }
+
+    pub fn bounds(&self) -> &[f64] {
+        &self.bounds
+    }
+
+    // The count is the sum of the buckets as read, so `+Inf` and `_count`
+    // always agree. An observation in progress may already be in a
+    // bucket but not yet in the sum.
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let mut total = 0;
+        let mut buckets = Vec::with_capacity(self.counts.len());
+        for (i, count) in self.counts.iter().enumerate() {
+            total += count.load(Ordering::Relaxed);
+            let bound = self.bounds.get(i).copied().unwrap_or(f64::INFINITY);
+            buckets.push((bound, total));
+        }
+        HistogramSnapshot {
+            buckets,
+            sum: self.sum.get(),
+            count: total,
+        }
+    }
+}
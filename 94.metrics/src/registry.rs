@@ -0,0 +1,225 @@
+// Metrics registered by name and labels, leaked into `&'static` handles
+//
+//   counter_with("uplink_batches_total", help, &[("result", "sent")])
+//        │
+//        ▼
+//   families: "uplink_batches_total" ─▶ Family { help, kind: counter }
+//                                          ├─ {result="dropped"} ─▶ &'static Counter
+//                                          └─ {result="sent"}    ─▶ &'static Counter
+//
+// The pattern of 83.global's registry, for three metric types: a lookup
+// under a read lock, and on a miss a second look under the write lock, so
+// racing callers for a new series all get the same handle. Handles live
+// for the whole process; callers keep them and update them with no lock.
+//
+// A family is one metric name with one type and help text; each distinct
+// set of label values is a series of it. Names and label names follow the
+// Prometheus rules. They are fixed in code, so a bad one, or a name used
+// as two types, is a bug and panics on registration.
+
+use crate::metric::{Counter, Gauge, Histogram};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Kind::Counter => "counter",
+            Kind::Gauge => "gauge",
+            Kind::Histogram => "histogram",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Metric {
+    Counter(&'static Counter),
+    Gauge(&'static Gauge),
+    Histogram(&'static Histogram),
+}
+
+impl Metric {
+    pub fn kind(&self) -> Kind {
+        match self {
+            Metric::Counter(_) => Kind::Counter,
+            Metric::Gauge(_) => Kind::Gauge,
+            Metric::Histogram(_) => Kind::Histogram,
+        }
+    }
+}
+
+// Label pairs sorted by label name, so the order they are given in
+// doesn't make a different series
+pub type Labels = Vec<(&'static str, String)>;
+
+#[derive(Debug)]
+pub struct Family {
+    pub help: &'static str,
+    pub kind: Kind,
+    pub series: BTreeMap<Labels, Metric>,
+}
+
+// `[a-zA-Z_:][a-zA-Z0-9_:]*` for metric names; label names have no `:`
+fn valid_name(name: &str, colon: bool) -> bool {
+    let ok = |c: char, first: bool| {
+        c.is_ascii_alphabetic() || c == '_' || (colon && c == ':') || (!first && c.is_ascii_digit())
+    };
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| ok(c, true)) && chars.all(|c| ok(c, false))
+}
+
+fn labels(kind: Kind, given: &[(&'static str, &str)]) -> Labels {
+    let mut labels: Labels = given.iter().map(|&(k, v)| (k, v.to_string())).collect();
+    labels.sort();
+    for (name, _) in &labels {
+        // `__` names are reserved for Prometheus itself, and `le` is the
+        // bucket label every histogram line already has
+        assert!(
+            valid_name(name, false) && !name.starts_with("__"),
+            "invalid label name {:?}",
+            name
+        );
+        assert!(
+            !(kind == Kind::Histogram && *name == "le"),
+            "\"le\" is the bucket label of a histogram"
+        );
+    }
+    assert!(
+        labels.windows(2).all(|w| w[0].0 != w[1].0),
+        "label given twice: {:?}",
+        labels
+    );
+    labels
+}
+
+#[derive(Debug)]
+pub struct Registry {
+    families: RwLock<BTreeMap<&'static str, Family>>,
+}
+
+impl Registry {
+    // Only the global `REGISTRY` needs one, but a private registry keeps
+    // tests from sharing counts
+    pub fn new() -> Registry {
+        Registry {
+            families: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn counter(&self, name: &'static str, help: &'static str) -> &'static Counter {
+        self.counter_with(name, help, &[])
+    }
+
+    pub fn counter_with(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&'static str, &str)],
+    ) -> &'static Counter {
+        let make = || Metric::Counter(Box::leak(Box::new(Counter::new())));
+        match self.register(name, help, Kind::Counter, labels, make) {
+            Metric::Counter(counter) => counter,
+            _ => unreachable!("kind checked in register"),
+        }
+    }
+
+    pub fn gauge(&self, name: &'static str, help: &'static str) -> &'static Gauge {
+        self.gauge_with(name, help, &[])
+    }
+
+    pub fn gauge_with(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&'static str, &str)],
+    ) -> &'static Gauge {
+        let make = || Metric::Gauge(Box::leak(Box::new(Gauge::new())));
+        match self.register(name, help, Kind::Gauge, labels, make) {
+            Metric::Gauge(gauge) => gauge,
+            _ => unreachable!("kind checked in register"),
+        }
+    }
+
+    // `bounds` only matter for a new series; an existing one keeps its own
+    pub fn histogram(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        bounds: &[f64],
+    ) -> &'static Histogram {
+        self.histogram_with(name, help, bounds, &[])
+    }
+
+    pub fn histogram_with(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        bounds: &[f64],
+        labels: &[(&'static str, &str)],
+    ) -> &'static Histogram {
+        let make = || Metric::Histogram(Box::leak(Box::new(Histogram::new(bounds))));
+        match self.register(name, help, Kind::Histogram, labels, make) {
+            Metric::Histogram(histogram) => histogram,
+            _ => unreachable!("kind checked in register"),
+        }
+    }
+
+    fn register(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        kind: Kind,
+        given: &[(&'static str, &str)],
+        make: impl FnOnce() -> Metric,
+    ) -> Metric {
+        let labels = labels(kind, given);
+        let found = |families: &BTreeMap<&'static str, Family>| {
+            let family = families.get(name)?;
+            assert!(
+                family.kind == kind,
+                "metric {} is a {}, not a {}",
+                name,
+                family.kind,
+                kind
+            );
+            family.series.get(&labels).copied()
+        };
+        if let Some(metric) = found(&self.families.read().unwrap_or_else(|e| e.into_inner())) {
+            return metric;
+        }
+        let mut families = self.families.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(metric) = found(&families) {
+            return metric;
+        }
+        assert!(valid_name(name, true), "invalid metric name {:?}", name);
+        let family = families.entry(name).or_insert_with(|| Family {
+            help,
+            kind,
+            series: BTreeMap::new(),
+        });
+        *family.series.entry(labels).or_insert_with(make)
+    }
+
+    // Calls `f` with every family, sorted by name, under the read lock
+    pub fn visit(&self, mut f: impl FnMut(&str, &Family)) {
+        let families = self.families.read().unwrap_or_else(|e| e.into_inner());
+        for (name, family) in families.iter() {
+            f(name, family);
+        }
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Registry {
+        Registry::new()
+    }
+}
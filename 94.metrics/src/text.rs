@@ -0,0 +1,102 @@
+// The Prometheus text exposition format, version 0.0.4
+//
+//   # HELP uplink_batches_total Batches handed to the uplink, by outcome
+//   # TYPE uplink_batches_total counter
+//   uplink_batches_total{result="dropped"} 2
+//   uplink_batches_total{result="sent"} 40
+//   # HELP poll_seconds Time spent in one poll cycle
+//   # TYPE poll_seconds histogram
+//   poll_seconds_bucket{le="0.005"} 37
+//   poll_seconds_bucket{le="+Inf"} 42
+//   poll_seconds_sum 0.171
+//   poll_seconds_count 42
+//
+// One line per sample, `name{labels} value`, after a HELP and a TYPE line
+// for its family. A histogram's buckets are cumulative: each counts the
+// observations up to and including its `le` bound, so the `+Inf` bucket
+// equals `_count`. Families come sorted by name and series by labels, so
+// the same state always renders to the same text.
+
+use crate::registry::{Family, Labels, Metric, Registry};
+use std::fmt::Write;
+
+// For the `Content-Type` header of a scrape response
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+// Go's float formatting, which Prometheus parses: `1`, `0.25`, `+Inf`
+pub fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape(text: &str, quote: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '"' if quote => out.push_str("\\\""),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// `{a="1",b="2"}` with `extra` (a histogram's `le`) last, or nothing
+fn label_set(labels: &Labels, extra: Option<(&str, &str)>) -> String {
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| (*name, value.as_str()))
+        .chain(extra)
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape(value, true)))
+        .collect();
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn family(out: &mut String, name: &str, family: &Family) {
+    if !family.help.is_empty() {
+        let _ = writeln!(out, "# HELP {} {}", name, escape(family.help, false));
+    }
+    let _ = writeln!(out, "# TYPE {} {}", name, family.kind);
+    for (labels, metric) in &family.series {
+        match metric {
+            Metric::Counter(counter) => {
+                let _ = writeln!(out, "{}{} {}", name, label_set(labels, None), counter.get());
+            }
+            Metric::Gauge(gauge) => {
+                let value = format_value(gauge.get());
+                let _ = writeln!(out, "{}{} {}", name, label_set(labels, None), value);
+            }
+            Metric::Histogram(histogram) => {
+                let snapshot = histogram.snapshot();
+                for (bound, count) in &snapshot.buckets {
+                    let le = format_value(*bound);
+                    let set = label_set(labels, Some(("le", &le)));
+                    let _ = writeln!(out, "{}_bucket{} {}", name, set, count);
+                }
+                let set = label_set(labels, None);
+                let sum = format_value(snapshot.sum);
+                let _ = writeln!(out, "{}_sum{} {}", name, set, sum);
+                let _ = writeln!(out, "{}_count{} {}", name, set, snapshot.count);
+            }
+        }
+    }
+}
+
+// Every metric in `registry`, ready to serve to a scraper
+pub fn render(registry: &Registry) -> String {
+    let mut out = String::new();
+    registry.visit(|name, f| family(&mut out, name, f));
+    out
+}
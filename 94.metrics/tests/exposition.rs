@@ -0,0 +1,190 @@
+// The rendered text, line for line, on private registries
+
+use metrics::text::{format_value, render};
+use metrics::Registry;
+use std::collections::HashMap;
+
+// What a scraper insists on: a TYPE line before a family's samples, and
+// `name{labels} value` with a number for each sample. Returns the number
+// of samples.
+fn check_format(text: &str) -> Result<usize, String> {
+    let mut types: HashMap<&str, &str> = HashMap::new();
+    let mut samples = 0;
+    for line in text.lines() {
+        if let Some(rest) = line.strip_prefix("# TYPE ") {
+            let (name, kind) = rest.split_once(' ').ok_or(line)?;
+            types.insert(name, kind);
+            continue;
+        }
+        if line.starts_with("# HELP ") {
+            continue;
+        }
+        let (series, value) = line.rsplit_once(' ').ok_or(line)?;
+        let name = series.split('{').next().unwrap_or_default();
+        let family = ["_bucket", "_sum", "_count"]
+            .iter()
+            .find_map(|suffix| {
+                let base = name.strip_suffix(suffix)?;
+                (types.get(base) == Some(&"histogram")).then_some(base)
+            })
+            .unwrap_or(name);
+        if !types.contains_key(family) {
+            return Err(format!("no TYPE before {}", line));
+        }
+        if !matches!(value, "+Inf" | "-Inf" | "NaN") && value.parse::<f64>().is_err() {
+            return Err(format!("bad value in {}", line));
+        }
+        samples += 1;
+    }
+    Ok(samples)
+}
+
+#[test]
+fn counters_and_gauges_render_with_help_and_type() {
+    let registry = Registry::new();
+    registry
+        .counter("uplink_batches_total", "Batches sent")
+        .add(40);
+    registry.gauge("queue_depth", "Messages waiting").set(3.5);
+    // No help: only the TYPE line
+    registry.gauge("temperature_celsius", "").set(-2.0);
+    assert_eq!(
+        render(&registry),
+        "# HELP queue_depth Messages waiting\n\
+         # TYPE queue_depth gauge\n\
+         queue_depth 3.5\n\
+         # TYPE temperature_celsius gauge\n\
+         temperature_celsius -2\n\
+         # HELP uplink_batches_total Batches sent\n\
+         # TYPE uplink_batches_total counter\n\
+         uplink_batches_total 40\n"
+    );
+}
+
+#[test]
+fn series_are_sorted_by_labels_under_one_header() {
+    let registry = Registry::new();
+    let help = "Batches by outcome";
+    registry
+        .counter_with(
+            "batches_total",
+            help,
+            &[("result", "sent"), ("link", "lte")],
+        )
+        .add(7);
+    registry
+        .counter_with(
+            "batches_total",
+            help,
+            &[("link", "lte"), ("result", "dropped")],
+        )
+        .inc();
+    assert_eq!(
+        render(&registry),
+        "# HELP batches_total Batches by outcome\n\
+         # TYPE batches_total counter\n\
+         batches_total{link=\"lte\",result=\"dropped\"} 1\n\
+         batches_total{link=\"lte\",result=\"sent\"} 7\n"
+    );
+}
+
+#[test]
+fn histogram_buckets_are_cumulative_and_end_at_inf() {
+    let registry = Registry::new();
+    let rtt = registry.histogram_with(
+        "rtt_seconds",
+        "Round trip",
+        &[0.25, 0.5, 1.0],
+        &[("peer", "collector")],
+    );
+    // On a bound counts in that bucket: `le` is "less than or equal"
+    for value in [0.125, 0.25, 0.375, 0.75, 1.0, 4.0] {
+        rtt.observe(value);
+    }
+    assert_eq!(
+        render(&registry),
+        "# HELP rtt_seconds Round trip\n\
+         # TYPE rtt_seconds histogram\n\
+         rtt_seconds_bucket{peer=\"collector\",le=\"0.25\"} 2\n\
+         rtt_seconds_bucket{peer=\"collector\",le=\"0.5\"} 3\n\
+         rtt_seconds_bucket{peer=\"collector\",le=\"1\"} 5\n\
+         rtt_seconds_bucket{peer=\"collector\",le=\"+Inf\"} 6\n\
+         rtt_seconds_sum{peer=\"collector\"} 6.5\n\
+         rtt_seconds_count{peer=\"collector\"} 6\n"
+    );
+}
+
+#[test]
+fn an_empty_histogram_still_has_every_line() {
+    let registry = Registry::new();
+    registry.histogram("idle_seconds", "", &[1.0]);
+    assert_eq!(
+        render(&registry),
+        "# TYPE idle_seconds histogram\n\
+         idle_seconds_bucket{le=\"1\"} 0\n\
+         idle_seconds_bucket{le=\"+Inf\"} 0\n\
+         idle_seconds_sum 0\n\
+         idle_seconds_count 0\n"
+    );
+}
+
+#[test]
+fn help_and_label_values_are_escaped() {
+    let registry = Registry::new();
+    registry
+        .gauge_with(
+            "note",
+            "Two\nlines, a \\ and \"quotes\"",
+            &[("text", "say \"hi\"\\\n")],
+        )
+        .set(1.0);
+    assert_eq!(
+        render(&registry),
+        "# HELP note Two\\nlines, a \\\\ and \"quotes\"\n\
+         # TYPE note gauge\n\
+         note{text=\"say \\\"hi\\\"\\\\\\n\"} 1\n"
+    );
+}
+
+#[test]
+fn values_use_the_go_float_format() {
+    assert_eq!(format_value(1.0), "1");
+    assert_eq!(format_value(0.25), "0.25");
+    assert_eq!(format_value(-3.0), "-3");
+    assert_eq!(format_value(f64::INFINITY), "+Inf");
+    assert_eq!(format_value(f64::NEG_INFINITY), "-Inf");
+    assert_eq!(format_value(f64::NAN), "NaN");
+    let registry = Registry::new();
+    registry.gauge("ratio", "").set(f64::NAN);
+    assert!(render(&registry).ends_with("ratio NaN\n"));
+}
+
+#[test]
+fn an_empty_registry_renders_nothing() {
+    assert_eq!(render(&Registry::new()), "");
+}
+
+#[test]
+fn every_sample_has_a_type_and_a_number() {
+    let registry = Registry::new();
+    let help = "Batches by outcome";
+    registry
+        .counter_with("batches_total", help, &[("result", "sent")])
+        .add(40);
+    registry
+        .counter_with("batches_total", help, &[("result", "dropped")])
+        .add(2);
+    for node in ["0", "1", "2"] {
+        registry
+            .gauge_with("battery_volts", "", &[("node", node), ("site", "lab")])
+            .set(3.3);
+    }
+    registry.gauge("ratio", "").set(f64::INFINITY);
+    registry
+        .histogram("step_seconds", "One step", &[0.001, 0.01, 0.1])
+        .observe(0.004);
+    let body = render(&registry);
+    // 2 counters, 3 gauges, 1 more gauge, 4 buckets + sum + count
+    assert_eq!(check_format(&body), Ok(12));
+    assert_eq!(body, render(&registry));
+}
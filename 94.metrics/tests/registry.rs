@@ -0,0 +1,160 @@
+// Registration, the metric types, and the global registry with its macros
+
+use metrics::{counter, gauge, histogram, Histogram, Registry, DEFAULT_BUCKETS};
+use std::thread;
+
+// What the case is about, and a registration that must panic for it
+type Case = (&'static str, fn(&Registry));
+
+fn leak() -> &'static Registry {
+    Box::leak(Box::new(Registry::new()))
+}
+
+#[test]
+fn the_same_name_and_labels_give_the_same_handle() {
+    let registry = Registry::new();
+    let a = registry.counter_with("x_total", "", &[("a", "1"), ("b", "2")]);
+    let b = registry.counter_with("x_total", "other help", &[("b", "2"), ("a", "1")]);
+    let c = registry.counter_with("x_total", "", &[("a", "1"), ("b", "3")]);
+    assert!(std::ptr::eq(a, b));
+    assert!(!std::ptr::eq(a, c));
+}
+
+#[test]
+#[should_panic(expected = "is a counter, not a gauge")]
+fn a_name_keeps_its_type() {
+    let registry = Registry::new();
+    registry.counter("requests_total", "");
+    registry.gauge("requests_total", "");
+}
+
+#[test]
+fn bad_names_panic() {
+    let cases: [Case; 5] = [
+        ("metric name", |r| {
+            r.counter("2xx_total", "");
+        }),
+        ("metric name", |r| {
+            r.counter("with-dash", "");
+        }),
+        ("label name", |r| {
+            r.counter_with("ok_total", "", &[("__reserved", "x")]);
+        }),
+        ("given twice", |r| {
+            r.counter_with("ok_total", "", &[("a", "1"), ("a", "2")]);
+        }),
+        ("bucket label", |r| {
+            r.histogram_with("h", "", &[1.0], &[("le", "1")]);
+        }),
+    ];
+    std::panic::set_hook(Box::new(|_| {}));
+    for (what, case) in cases {
+        let registry = Registry::new();
+        let err = std::panic::catch_unwind(|| case(&registry)).expect_err(what);
+        let message = match err.downcast_ref::<&str>() {
+            Some(s) => s.to_string(),
+            None => err.downcast_ref::<String>().cloned().unwrap_or_default(),
+        };
+        assert!(message.contains(what), "{}: {}", what, message);
+    }
+    let _ = std::panic::take_hook();
+    // Colons are allowed in metric names, by convention for recording rules
+    Registry::new().gauge("job:up:sum", "");
+}
+
+#[test]
+#[should_panic(expected = "finite and increasing")]
+fn histogram_bounds_must_increase() {
+    Histogram::new(&[0.1, 0.1, 1.0]);
+}
+
+#[test]
+fn gauges_add_and_subtract() {
+    let registry = Registry::new();
+    let g = registry.gauge("g", "");
+    g.inc();
+    g.add(2.5);
+    g.dec();
+    g.sub(0.5);
+    assert_eq!(g.get(), 2.0);
+    g.set(-7.0);
+    assert_eq!(g.get(), -7.0);
+}
+
+// Round trips in seconds, mostly fast with a slow tail past every bound
+fn latencies() -> Vec<f64> {
+    (0..1000u64)
+        .map(|i| {
+            let x = (i * 7919 % 1000) as f64 / 1000.0;
+            0.002 + 0.03 * x * x * x + if i % 97 == 0 { 1.5 } else { 0.0 }
+        })
+        .collect()
+}
+
+#[test]
+fn a_histogram_agrees_with_counting_by_hand() {
+    let registry = Registry::new();
+    let rtt = registry.histogram("rtt_seconds", "", DEFAULT_BUCKETS);
+    let samples = latencies();
+    for &s in &samples {
+        rtt.observe(s);
+    }
+    rtt.observe(f64::NAN);
+    let snapshot = rtt.snapshot();
+    assert_eq!(snapshot.buckets.len(), DEFAULT_BUCKETS.len() + 1);
+    for &(bound, count) in &snapshot.buckets {
+        let by_hand = samples.iter().filter(|&&s| s <= bound).count() as u64;
+        assert_eq!(count, by_hand, "le {}", bound);
+    }
+    // NaN is skipped, so +Inf is the count of real observations
+    assert_eq!(snapshot.buckets.last(), Some(&(f64::INFINITY, 1000)));
+    assert_eq!(snapshot.count, 1000);
+    let sum: f64 = samples.iter().sum();
+    assert!((snapshot.sum - sum).abs() < 1e-9);
+}
+
+#[test]
+fn concurrent_updates_are_not_lost() {
+    let registry = leak();
+    thread::scope(|s| {
+        for t in 0..8 {
+            s.spawn(move || {
+                let c = registry.counter("hits_total", "");
+                let g = registry.gauge("level", "");
+                let h = registry.histogram("sizes", "", &[10.0, 100.0]);
+                for i in 0..10_000 {
+                    c.inc();
+                    g.add(1.0);
+                    h.observe(((t * 10_000 + i) % 200) as f64);
+                }
+            });
+        }
+    });
+    assert_eq!(registry.counter("hits_total", "").get(), 80_000);
+    assert_eq!(registry.gauge("level", "").get(), 80_000.0);
+    let snapshot = registry.histogram("sizes", "", &[]).snapshot();
+    assert_eq!(snapshot.count, 80_000);
+    // 0..=10 of every 200 values, then 0..=100
+    assert_eq!(snapshot.buckets[0], (10.0, 80_000 / 200 * 11));
+    assert_eq!(snapshot.buckets[1], (100.0, 80_000 / 200 * 101));
+    assert_eq!(snapshot.sum, (0..200).sum::<u64>() as f64 * 400.0);
+}
+
+#[test]
+fn macros_register_once_in_the_global_registry() {
+    let handles: Vec<_> = (0..3)
+        .map(|_| counter!("test_macro_total", "From the macro", "site" => "a"))
+        .collect();
+    assert!(handles.iter().all(|h| std::ptr::eq(*h, handles[0])));
+    handles[0].add(5);
+    gauge!("test_macro_level", "").set(2.0);
+    histogram!("test_macro_seconds", "", &[1.0], "site" => "a").observe(0.5);
+    let text = metrics::render();
+    assert!(text.contains("test_macro_total{site=\"a\"} 5\n"));
+    assert!(text.contains("test_macro_level 2\n"));
+    assert!(text.contains("test_macro_seconds_bucket{site=\"a\",le=\"1\"} 1\n"));
+    assert!(std::ptr::eq(
+        handles[0],
+        metrics::REGISTRY.counter_with("test_macro_total", "", &[("site", "a")])
+    ));
+}
@@ -0,0 +1,289 @@
+// A stand-in for an OpenTelemetry collector, for the demo and the tests
+//
+//   exporter ── POST /v1/traces  (OTLP/JSON) ──▶ MockCollector ──▶ spans()
+//            ── POST /v1/metrics (OTLP/JSON) ──▶               ──▶ points()
+//
+// A real collector listens on 4318 for OTLP/HTTP and forwards to a
+// backend. This one keeps every request body, answers 200 and flattens
+// what it got: one `ExportedSpan` per span and one `ExportedPoint` per
+// data point, each with the resource attributes of its batch. The
+// exporter keeps its connection open between exports, so each
+// connection gets a thread that reads requests until the client hangs
+// up.
+//
+// JSON, not protobuf, so the bodies can be read without generated code.
+// The exporter's `http-proto` protocol sends the same messages in less
+// space; a real deployment would use that.
+
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+pub type Attributes = BTreeMap<String, String>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedSpan {
+    pub name: String,
+    pub trace_id: String,
+    pub span_id: String,
+    // Empty for the root of a trace
+    pub parent_span_id: String,
+    pub attributes: Attributes,
+    pub events: Vec<String>,
+    pub error: bool,
+    pub resource: Attributes,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedPoint {
+    pub metric: String,
+    pub attributes: Attributes,
+    pub value: f64,
+    pub resource: Attributes,
+}
+
+// One request: the path it was posted to and the decoded body
+#[derive(Debug, Clone)]
+pub struct Export {
+    pub path: String,
+    pub body: Value,
+}
+
+// OTLP/JSON `AnyValue` as text: `{"intValue": "3"}` is "3"
+fn any_value(value: &Value) -> String {
+    match value.as_object().and_then(|o| o.values().next()) {
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+        None => String::new(),
+    }
+}
+
+fn attributes(value: &Value) -> Attributes {
+    list(&value["attributes"])
+        .iter()
+        .map(|kv| (text(&kv["key"]), any_value(&kv["value"])))
+        .collect()
+}
+
+fn list(value: &Value) -> &[Value] {
+    value.as_array().map(Vec::as_slice).unwrap_or(&[])
+}
+
+fn text(value: &Value) -> String {
+    value.as_str().unwrap_or_default().to_string()
+}
+
+// A span's status is `{"code": 2}` for an error; the enum may also be
+// written by name
+fn is_error(status: &Value) -> bool {
+    status["code"] == 2 || status["code"] == "STATUS_CODE_ERROR"
+}
+
+// `asInt` is a string in OTLP/JSON, since int64 doesn't fit a JSON number
+fn point_value(point: &Value) -> f64 {
+    match (&point["asDouble"], &point["asInt"]) {
+        (Value::Number(n), _) => n.as_f64().unwrap_or(f64::NAN),
+        (_, Value::String(s)) => s.parse().unwrap_or(f64::NAN),
+        (_, Value::Number(n)) => n.as_f64().unwrap_or(f64::NAN),
+        _ => f64::NAN,
+    }
+}
+
+pub fn spans_of(body: &Value) -> Vec<ExportedSpan> {
+    let mut spans = Vec::new();
+    for batch in list(&body["resourceSpans"]) {
+        let resource = attributes(&batch["resource"]);
+        for scope in list(&batch["scopeSpans"]) {
+            for span in list(&scope["spans"]) {
+                spans.push(ExportedSpan {
+                    name: text(&span["name"]),
+                    trace_id: text(&span["traceId"]),
+                    span_id: text(&span["spanId"]),
+                    parent_span_id: text(&span["parentSpanId"]),
+                    attributes: attributes(span),
+                    events: list(&span["events"])
+                        .iter()
+                        .map(|e| text(&e["name"]))
+                        .collect(),
+                    error: is_error(&span["status"]),
+                    resource: resource.clone(),
+                });
+            }
+        }
+    }
+    spans
+}
+
+pub fn points_of(body: &Value) -> Vec<ExportedPoint> {
+    let mut points = Vec::new();
+    for batch in list(&body["resourceMetrics"]) {
+        let resource = attributes(&batch["resource"]);
+        for scope in list(&batch["scopeMetrics"]) {
+            for metric in list(&scope["metrics"]) {
+                for kind in ["sum", "gauge"] {
+                    for point in list(&metric[kind]["dataPoints"]) {
+                        points.push(ExportedPoint {
+                            metric: text(&metric["name"]),
+                            attributes: attributes(point),
+                            value: point_value(point),
+                            resource: resource.clone(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    points
+}
+
+// Reads one request; None once the client has closed the connection
+fn read_request(reader: &mut BufReader<TcpStream>) -> io::Result<Option<(String, Vec<u8>)>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    let path = line.split_whitespace().nth(1).unwrap_or("").to_string();
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Some((path, body)))
+}
+
+fn serve(stream: TcpStream, exports: &Mutex<Vec<Export>>) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut stream = stream;
+    while let Some((path, body)) = read_request(&mut reader)? {
+        let (code, reply) = match serde_json::from_slice(&body) {
+            Ok(body) => {
+                exports
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push(Export { path, body });
+                ("200 OK", "{}")
+            }
+            Err(_) => ("400 Bad Request", "{\"message\":\"not OTLP/JSON\"}"),
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            code,
+            reply.len(),
+            reply
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub struct MockCollector {
+    addr: SocketAddr,
+    exports: Arc<Mutex<Vec<Export>>>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockCollector {
+    // Listens on a free port of 127.0.0.1
+    pub fn start() -> io::Result<MockCollector> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        // Non-blocking, so the thread notices `stop`
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let exports = Arc::new(Mutex::new(Vec::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let (exports, stop) = (Arc::clone(&exports), Arc::clone(&stop));
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok((stream, _)) => {
+                            let _ = stream.set_nonblocking(false);
+                            let exports = Arc::clone(&exports);
+                            thread::spawn(move || {
+                                if let Err(e) = serve(stream, &exports) {
+                                    if e.kind() != io::ErrorKind::WouldBlock {
+                                        eprintln!("collector: {}", e);
+                                    }
+                                }
+                            });
+                        }
+                        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                            thread::sleep(Duration::from_millis(10));
+                        }
+                        Err(e) => eprintln!("collector: accept failed: {}", e),
+                    }
+                }
+            })
+        };
+        Ok(MockCollector {
+            addr,
+            exports,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    // The base URL to give the exporter
+    pub fn endpoint(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn exports(&self) -> Vec<Export> {
+        self.exports
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn spans(&self) -> Vec<ExportedSpan> {
+        self.exports()
+            .iter()
+            .filter(|e| e.path == "/v1/traces")
+            .flat_map(|e| spans_of(&e.body))
+            .collect()
+    }
+
+    pub fn points(&self) -> Vec<ExportedPoint> {
+        self.exports()
+            .iter()
+            .filter(|e| e.path == "/v1/metrics")
+            .flat_map(|e| points_of(&e.body))
+            .collect()
+    }
+
+    // Forgets what was received so far
+    pub fn clear(&self) {
+        self.exports
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+impl Drop for MockCollector {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,215 @@
+// What the exporter needs to know: where to send, who is sending, and
+// how many traces to keep
+//
+//   TelemetryConfig::new("gw-042", "1.4.2")
+//       .apply_env(std::env::vars())?    OTEL_EXPORTER_OTLP_ENDPOINT, OTEL_TRACES_SAMPLER, ...
+//       .resource()  ──▶ service.name, device.id, device.firmware.version, ...
+//
+// The resource is attached once per export, not per span: it says which
+// device the spans came from, so a backend can filter a fleet of
+// gateways by firmware version without every span carrying it.
+//
+// Sampling is decided when a trace starts, from its trace id. With
+// `traceidratio` a device keeps that fraction of traces. The
+// `parentbased_` variants follow the decision of a remote parent, e.g. a
+// command whose trace began in the cloud, and only decide for traces that
+// start here. The names and the environment variables are the ones every
+// OpenTelemetry SDK reads, so an operator sets them the same way for any
+// service.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    Sampler(String),
+    SamplerArg(String),
+    Interval(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Sampler(name) => write!(f, "unknown sampler {:?}", name),
+            ConfigError::SamplerArg(arg) => {
+                write!(f, "sampler argument {:?} is not a ratio in 0..=1", arg)
+            }
+            ConfigError::Interval(value) => {
+                write!(
+                    f,
+                    "export interval {:?} is not a number of milliseconds",
+                    value
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+// The fraction of traces started here that are kept; `always_on` is 1
+// and `always_off` 0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sampling {
+    pub ratio: f64,
+    // Follow a remote parent's decision instead of deciding again
+    pub parent_based: bool,
+}
+
+impl Sampling {
+    pub const ALWAYS_ON: Sampling = Sampling {
+        ratio: 1.0,
+        parent_based: false,
+    };
+
+    pub fn ratio(ratio: f64) -> Sampling {
+        Sampling {
+            ratio: ratio.clamp(0.0, 1.0),
+            parent_based: false,
+        }
+    }
+
+    pub fn parent_based(self) -> Sampling {
+        Sampling {
+            parent_based: true,
+            ..self
+        }
+    }
+
+    // From `OTEL_TRACES_SAMPLER` and `OTEL_TRACES_SAMPLER_ARG`; the
+    // argument is only read by the ratio samplers, and defaults to 1
+    pub fn parse(name: &str, arg: Option<&str>) -> Result<Sampling, ConfigError> {
+        let (parent_based, root) = match name.strip_prefix("parentbased_") {
+            Some(root) => (true, root),
+            None => (false, name),
+        };
+        let ratio = match root {
+            "always_on" => 1.0,
+            "always_off" => 0.0,
+            "traceidratio" => match arg {
+                None => 1.0,
+                Some(arg) => arg
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|r| (0.0..=1.0).contains(r))
+                    .ok_or_else(|| ConfigError::SamplerArg(arg.to_string()))?,
+            },
+            _ => return Err(ConfigError::Sampler(name.to_string())),
+        };
+        Ok(Sampling {
+            ratio,
+            parent_based,
+        })
+    }
+}
+
+impl Default for Sampling {
+    fn default() -> Sampling {
+        Sampling::ALWAYS_ON.parent_based()
+    }
+}
+
+impl fmt::Display for Sampling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.parent_based {
+            write!(f, "parentbased_")?;
+        }
+        match self.ratio {
+            r if r >= 1.0 => write!(f, "always_on"),
+            r if r <= 0.0 => write!(f, "always_off"),
+            r => write!(f, "traceidratio {}", r),
+        }
+    }
+}
+
+impl FromStr for Sampling {
+    type Err = ConfigError;
+
+    // "traceidratio 0.25" or "always_on": the name and optional argument
+    fn from_str(s: &str) -> Result<Sampling, ConfigError> {
+        let mut words = s.split_whitespace();
+        let name = words.next().unwrap_or("");
+        Sampling::parse(name, words.next())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetryConfig {
+    // The collector's base URL; `/v1/traces` and `/v1/metrics` are added
+    pub endpoint: String,
+    pub service_name: String,
+    pub device_id: String,
+    pub firmware_version: String,
+    pub sampling: Sampling,
+    // How often the metrics are read and sent
+    pub export_interval: Duration,
+}
+
+impl TelemetryConfig {
+    // OTLP/HTTP's default port on this host
+    pub const DEFAULT_ENDPOINT: &'static str = "http://localhost:4318";
+
+    pub fn new(device_id: &str, firmware_version: &str) -> TelemetryConfig {
+        TelemetryConfig {
+            endpoint: TelemetryConfig::DEFAULT_ENDPOINT.to_string(),
+            service_name: "edge-gateway".to_string(),
+            device_id: device_id.to_string(),
+            firmware_version: firmware_version.to_string(),
+            sampling: Sampling::default(),
+            export_interval: Duration::from_secs(60),
+        }
+    }
+
+    // The standard variables that apply; others are ignored. Taking them
+    // as pairs keeps tests away from the process environment.
+    pub fn apply_env<I>(mut self, vars: I) -> Result<TelemetryConfig, ConfigError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let (mut sampler, mut arg) = (None, None);
+        for (key, value) in vars {
+            match key.as_str() {
+                "OTEL_EXPORTER_OTLP_ENDPOINT" => self.endpoint = value,
+                "OTEL_SERVICE_NAME" => self.service_name = value,
+                "OTEL_TRACES_SAMPLER" => sampler = Some(value),
+                "OTEL_TRACES_SAMPLER_ARG" => arg = Some(value),
+                "OTEL_METRIC_EXPORT_INTERVAL" => {
+                    let ms = value
+                        .trim()
+                        .parse()
+                        .map_err(|_| ConfigError::Interval(value.clone()))?;
+                    self.export_interval = Duration::from_millis(ms);
+                }
+                _ => {}
+            }
+        }
+        if let Some(name) = sampler {
+            self.sampling = Sampling::parse(&name, arg.as_deref())?;
+        }
+        Ok(self)
+    }
+
+    // Resource attributes, in the semantic conventions' names where there
+    // is one; firmware has none, so it sits under `device.`
+    pub fn resource(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("service.name", self.service_name.clone()),
+            ("service.version", env!("CARGO_PKG_VERSION").to_string()),
+            ("service.instance.id", self.device_id.clone()),
+            ("device.id", self.device_id.clone()),
+            ("device.firmware.version", self.firmware_version.clone()),
+            ("host.arch", std::env::consts::ARCH.to_string()),
+            ("os.type", std::env::consts::OS.to_string()),
+        ]
+    }
+
+    pub fn traces_url(&self) -> String {
+        format!("{}/v1/traces", self.endpoint.trim_end_matches('/'))
+    }
+
+    pub fn metrics_url(&self) -> String {
+        format!("{}/v1/metrics", self.endpoint.trim_end_matches('/'))
+    }
+}
@@ -0,0 +1,248 @@
+// OTLP export of the `tracing` spans and the 94.metrics registry (feature `otlp`)
+//
+//   info_span!(..) ─▶ tracing-opentelemetry layer ─▶ SdkTracerProvider ─▶ batch ─▶ POST /v1/traces
+//                       (a tracing subscriber)        sampler, resource
+//
+//   metrics::REGISTRY ◀─ observable instruments ◀─ SdkMeterProvider ─▶ every interval ─▶ POST /v1/metrics
+//
+// Spans are buffered and sent by the batch processor's own thread, so a
+// slow collector never holds up the code that opened them. A full buffer
+// drops spans rather than grow. Metrics are read on the reader's thread
+// at each interval: every family of the registry becomes an observable
+// instrument whose callback reads the current values. Nothing in the
+// pipeline changes when the exporter is added.
+//
+// The registry only has counters, gauges and histograms of its own kind.
+// Counters map to OTLP sums and gauges to gauges. OpenTelemetry has no
+// observable histogram, so a histogram is sent as its `_sum` and `_count`;
+// the buckets stay on the Prometheus endpoint. Families defined after
+// `init` aren't bridged, so a program defines its metrics first.
+//
+// `shutdown` exports what is still buffered and stops both threads; a
+// program that exits without it loses its last spans.
+
+use crate::config::{Sampling, TelemetryConfig};
+use metrics::{Kind, Labels, Metric, Registry};
+use opentelemetry::metrics::{Meter, MeterProvider};
+use opentelemetry::trace::TracerProvider;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::{MetricExporter, Protocol, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
+use opentelemetry_sdk::trace::{
+    BatchConfigBuilder, BatchSpanProcessor, Sampler, SdkTracerProvider,
+};
+use opentelemetry_sdk::Resource;
+use std::fmt;
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+
+// The instrumentation scope spans and metrics are reported under
+const SCOPE: &str = "rust-sys";
+
+#[derive(Debug)]
+pub enum ExportError {
+    Build(String),
+    Shutdown(String),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Build(e) => write!(f, "cannot build the OTLP exporter: {}", e),
+            ExportError::Shutdown(e) => write!(f, "shutting the exporter down: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+fn sampler(sampling: Sampling) -> Sampler {
+    let root = match sampling.ratio {
+        r if r >= 1.0 => Sampler::AlwaysOn,
+        r if r <= 0.0 => Sampler::AlwaysOff,
+        r => Sampler::TraceIdRatioBased(r),
+    };
+    if sampling.parent_based {
+        Sampler::ParentBased(Box::new(root))
+    } else {
+        root
+    }
+}
+
+fn key_values(labels: &Labels) -> Vec<KeyValue> {
+    labels
+        .iter()
+        .map(|(k, v)| KeyValue::new(*k, v.clone()))
+        .collect()
+}
+
+// Calls `f` with every series of the family `name`
+fn each_series(registry: &Registry, name: &str, mut f: impl FnMut(&Labels, Metric)) {
+    registry.visit(|family_name, family| {
+        if family_name == name {
+            for (labels, metric) in &family.series {
+                f(labels, *metric);
+            }
+        }
+    });
+}
+
+// One observable instrument per family, or two for a histogram. The
+// instruments live as long as the meter provider; their handles aren't
+// needed to keep them registered.
+fn bridge(meter: &Meter, registry: &'static Registry) {
+    let mut families = Vec::new();
+    registry.visit(|name, family| families.push((name.to_string(), family.help, family.kind)));
+    for (name, help, kind) in families {
+        match kind {
+            Kind::Counter => {
+                let family = name.clone();
+                meter
+                    .u64_observable_counter(name)
+                    .with_description(help)
+                    .with_callback(move |observer| {
+                        each_series(registry, &family, |labels, metric| {
+                            if let Metric::Counter(c) = metric {
+                                observer.observe(c.get(), &key_values(labels));
+                            }
+                        })
+                    })
+                    .build();
+            }
+            Kind::Gauge => {
+                let family = name.clone();
+                meter
+                    .f64_observable_gauge(name)
+                    .with_description(help)
+                    .with_callback(move |observer| {
+                        each_series(registry, &family, |labels, metric| {
+                            if let Metric::Gauge(g) = metric {
+                                observer.observe(g.get(), &key_values(labels));
+                            }
+                        })
+                    })
+                    .build();
+            }
+            Kind::Histogram => {
+                let family = name.clone();
+                meter
+                    .f64_observable_counter(format!("{}_sum", name))
+                    .with_description(help)
+                    .with_callback(move |observer| {
+                        each_series(registry, &family, |labels, metric| {
+                            if let Metric::Histogram(h) = metric {
+                                observer.observe(h.snapshot().sum, &key_values(labels));
+                            }
+                        })
+                    })
+                    .build();
+                let family = name.clone();
+                meter
+                    .u64_observable_counter(format!("{}_count", name))
+                    .with_description(help)
+                    .with_callback(move |observer| {
+                        each_series(registry, &family, |labels, metric| {
+                            if let Metric::Histogram(h) = metric {
+                                observer.observe(h.snapshot().count, &key_values(labels));
+                            }
+                        })
+                    })
+                    .build();
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Telemetry {
+    tracer: SdkTracerProvider,
+    meter: SdkMeterProvider,
+}
+
+impl Telemetry {
+    // Builds both exporters and bridges `registry`; nothing is sent until
+    // spans close or the first interval passes. Install `subscriber()` (or
+    // add `layer()` to one) to route the spans here.
+    pub fn init(
+        config: &TelemetryConfig,
+        registry: &'static Registry,
+    ) -> Result<Telemetry, ExportError> {
+        let build = |e: opentelemetry_otlp::ExporterBuildError| ExportError::Build(e.to_string());
+        let resource = Resource::builder_empty()
+            .with_attributes(
+                config
+                    .resource()
+                    .into_iter()
+                    .map(|(k, v)| KeyValue::new(k, v)),
+            )
+            .build();
+
+        let spans = SpanExporter::builder()
+            .with_http()
+            .with_protocol(Protocol::HttpJson)
+            .with_endpoint(config.traces_url())
+            .build()
+            .map_err(build)?;
+        let batches = BatchSpanProcessor::builder(spans)
+            .with_batch_config(
+                BatchConfigBuilder::default()
+                    .with_max_queue_size(2048)
+                    .build(),
+            )
+            .build();
+        let tracer = SdkTracerProvider::builder()
+            .with_span_processor(batches)
+            .with_sampler(sampler(config.sampling))
+            .with_resource(resource.clone())
+            .build();
+
+        let points = MetricExporter::builder()
+            .with_http()
+            .with_protocol(Protocol::HttpJson)
+            .with_endpoint(config.metrics_url())
+            .build()
+            .map_err(build)?;
+        let reader = PeriodicReader::builder(points)
+            .with_interval(config.export_interval)
+            .build();
+        let meter = SdkMeterProvider::builder()
+            .with_reader(reader)
+            .with_resource(resource)
+            .build();
+        bridge(&meter.meter(SCOPE), registry);
+
+        Ok(Telemetry { tracer, meter })
+    }
+
+    // The layer that turns closed `tracing` spans into OTLP spans
+    pub fn layer<S>(&self) -> OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        tracing_opentelemetry::layer().with_tracer(self.tracer.tracer(SCOPE))
+    }
+
+    // A subscriber with only that layer, for `tracing::subscriber::set_global_default`
+    pub fn subscriber(&self) -> impl Subscriber + Send + Sync {
+        tracing_subscriber::registry().with(self.layer())
+    }
+
+    // Sends buffered spans and the current metric values now
+    pub fn flush(&self) -> Result<(), ExportError> {
+        let shutdown =
+            |e: opentelemetry_sdk::error::OTelSdkError| ExportError::Shutdown(e.to_string());
+        self.tracer.force_flush().map_err(shutdown)?;
+        self.meter.force_flush().map_err(shutdown)
+    }
+
+    // Flushes, then stops both exporters' threads
+    pub fn shutdown(self) -> Result<(), ExportError> {
+        let shutdown =
+            |e: opentelemetry_sdk::error::OTelSdkError| ExportError::Shutdown(e.to_string());
+        let traces = self.tracer.shutdown().map_err(shutdown);
+        let metrics = self.meter.shutdown().map_err(shutdown);
+        traces.and(metrics)
+    }
+}
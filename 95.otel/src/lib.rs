@@ -0,0 +1,26 @@
+// OpenTelemetry export of the spans and metrics a device already has
+//
+// - `config`: endpoint, resource attributes (device id, firmware version)
+//   and sampling, from code and the standard `OTEL_*` variables
+// - `pipeline`: a gateway-shaped workload with `tracing` spans and
+//   94.metrics counters
+// - `export` (feature `otlp`): the OTLP/HTTP exporter for both, as a
+//   tracing layer and a bridge from the metrics registry
+// - `collector`: a mock OTLP/JSON collector that keeps what it receives
+//
+//   let telemetry = otel::Telemetry::init(&config, &metrics::REGISTRY)?;
+//   tracing::subscriber::set_global_default(telemetry.subscriber())?;
+//   ...
+//   telemetry.shutdown()?;
+
+pub mod collector;
+pub mod config;
+#[cfg(feature = "otlp")]
+pub mod export;
+pub mod pipeline;
+
+pub use collector::{ExportedPoint, ExportedSpan, MockCollector};
+pub use config::{ConfigError, Sampling, TelemetryConfig};
+#[cfg(feature = "otlp")]
+pub use export::{ExportError, Telemetry};
+pub use pipeline::Pipeline;
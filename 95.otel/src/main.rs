@@ -0,0 +1,154 @@
+use otel::{Sampling, TelemetryConfig};
+
+#[cfg(feature = "otlp")]
+mod export_demo {
+    use otel::{ExportedSpan, MockCollector, Pipeline, Sampling, Telemetry, TelemetryConfig};
+    use std::collections::BTreeMap;
+
+    fn run(config: &TelemetryConfig, pipeline: &mut Pipeline, cycles: usize) -> Telemetry {
+        let telemetry = Telemetry::init(config, &metrics::REGISTRY).expect("exporter");
+        tracing::subscriber::with_default(telemetry.subscriber(), || {
+            for _ in 0..cycles {
+                pipeline.cycle();
+            }
+        });
+        telemetry
+    }
+
+    fn traces(spans: &[ExportedSpan]) -> BTreeMap<&str, Vec<&ExportedSpan>> {
+        let mut traces: BTreeMap<&str, Vec<&ExportedSpan>> = BTreeMap::new();
+        for span in spans {
+            traces.entry(&span.trace_id).or_default().push(span);
+        }
+        traces
+    }
+
+    fn print_tree(spans: &[&ExportedSpan], parent: &str, depth: usize) {
+        for span in spans.iter().filter(|s| s.parent_span_id == parent) {
+            let fields: Vec<String> = ["cycle", "node", "messages"]
+                .iter()
+                .filter_map(|k| span.attributes.get(*k).map(|v| format!("{}={}", k, v)))
+                .collect();
+            println!(
+                "   {}{}{{{}}}{}{}",
+                "  ".repeat(depth),
+                span.name,
+                fields.join(" "),
+                if span.error { " ERROR" } else { "" },
+                span.events
+                    .iter()
+                    .map(|e| format!(" event: {}", e))
+                    .collect::<String>()
+            );
+            print_tree(spans, &span.span_id, depth + 1);
+        }
+    }
+
+    pub fn main(config: &TelemetryConfig) {
+        let collector = match MockCollector::start() {
+            Ok(collector) => collector,
+            Err(e) => {
+                println!("   cannot start the mock collector: {}", e);
+                return;
+            }
+        };
+        let mut config = config.clone();
+        config.endpoint = collector.endpoint();
+
+        // 3. Spans to a collector
+        println!("\n3. Spans to a collector:");
+        println!("   mock collector at {}", config.endpoint);
+        config.sampling = Sampling::ALWAYS_ON;
+        let mut pipeline = Pipeline::new(3, 6).with_failing_node(1);
+        run(&config, &mut pipeline, 3).shutdown().expect("shutdown");
+        let spans = collector.spans();
+        let by_trace = traces(&spans);
+        println!(
+            "   {} spans in {} traces; the last one:",
+            spans.len(),
+            by_trace.len()
+        );
+        let last = by_trace
+            .values()
+            .find(|t| t.iter().any(|s| s.name == "forward_batch"))
+            .expect("the third cycle forwards");
+        print_tree(last, "", 0);
+
+        // 4. Sampling in action
+        println!("\n4. Sampling 500 cycles:");
+        for sampling in ["always_on", "parentbased_traceidratio 0.1", "always_off"] {
+            collector.clear();
+            config.sampling = sampling.parse().expect("valid");
+            run(&config, &mut Pipeline::new(2, usize::MAX), 500)
+                .shutdown()
+                .expect("shutdown");
+            let spans = collector.spans();
+            let by_trace = traces(&spans);
+            println!(
+                "   {:<30} {:>3} traces, {:>4} spans",
+                sampling,
+                by_trace.len(),
+                spans.len()
+            );
+        }
+
+        // 5. The metrics registry over OTLP
+        println!("\n5. The metrics registry over OTLP:");
+        collector.clear();
+        let telemetry = run(&config, &mut pipeline, 2);
+        telemetry.flush().expect("flush");
+        let points = collector.points();
+        for point in &points {
+            println!("   {:<32} {}", point.metric, point.value);
+        }
+        telemetry.shutdown().expect("shutdown");
+    }
+}
+
+fn main() {
+    println!("=== OpenTelemetry Export Examples ===\n");
+
+    // 1. Resource attributes
+    println!("1. Resource attributes:");
+    let config = match TelemetryConfig::new("gw-042", "1.4.2").apply_env(std::env::vars()) {
+        Ok(config) => config,
+        Err(e) => {
+            println!("   OTEL_* variables: {}", e);
+            return;
+        }
+    };
+    for (key, value) in config.resource() {
+        println!("   {:<24} {}", key, value);
+    }
+    println!("   traces to  {}", config.traces_url());
+    println!("   metrics to {}", config.metrics_url());
+
+    // 2. Sampling configuration
+    println!("\n2. Sampling configuration:");
+    for text in [
+        "always_on",
+        "traceidratio 0.25",
+        "parentbased_traceidratio 0.01",
+        "parentbased_always_off",
+        "traceidratio 2",
+        "sometimes",
+    ] {
+        match text.parse::<Sampling>() {
+            Ok(sampling) => {
+                println!(
+                    "   {:<30} ratio {:<5} parent-based: {:<5} prints as {}",
+                    text, sampling.ratio, sampling.parent_based, sampling
+                );
+            }
+            Err(e) => println!("   {:<30} {}", text, e),
+        }
+    }
+    println!("   in effect: {}", config.sampling);
+
+    #[cfg(feature = "otlp")]
+    export_demo::main(&config);
+    #[cfg(not(feature = "otlp"))]
+    println!("\n3-5. Export needs the exporter: cargo run --features otlp");
+
+    println!("\n=== End of OpenTelemetry Export Examples ===");
+}
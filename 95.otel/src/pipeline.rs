@@ -0,0 +1,110 @@
+// A gateway-shaped workload to export: poll the nodes, queue the
+// readings, forward them in batches
+//
+//   poll_cycle{cycle=7}
+//   ├── read_sensor{node=0}
+//   ├── read_sensor{node=1}   ── warn!("no response"), otel.status_code = ERROR
+//   ├── read_sensor{node=2}
+//   └── forward_batch{messages=6}
+//
+// The spans are plain `tracing` spans and know nothing about
+// OpenTelemetry. With no subscriber installed they cost a check of one
+// atomic; with the `otlp` layer (see `export`) each closed span becomes
+// an OTLP span with its fields as attributes, its parent link, and any
+// event logged inside it. Two field names mean something to that layer:
+// `otel.status_code` sets the span's status, and `otel.name` renames it.
+//
+// The counters go to the 94.metrics registry as in the gateway. They are
+// looked up in `new`, so they exist before the exporter bridges the
+// registry.
+
+use metrics::{Counter, Gauge, Histogram, REGISTRY};
+use tracing::{info_span, warn};
+
+const BATCH_BUCKETS: &[f64] = &[1.0, 5.0, 10.0, 20.0];
+
+#[derive(Debug)]
+pub struct Pipeline {
+    nodes: u16,
+    failing: Option<u16>,
+    batch_size: usize,
+    queue: Vec<f64>,
+    cycle: u64,
+    cycles: &'static Counter,
+    readings: &'static Counter,
+    read_errors: &'static Counter,
+    queued: &'static Gauge,
+    batch_messages: &'static Histogram,
+}
+
+impl Pipeline {
+    pub fn new(nodes: u16, batch_size: usize) -> Pipeline {
+        Pipeline {
+            nodes,
+            failing: None,
+            batch_size,
+            queue: Vec::new(),
+            cycle: 0,
+            cycles: REGISTRY.counter("pipeline_cycles_total", "Poll cycles run"),
+            readings: REGISTRY.counter("pipeline_readings_total", "Readings polled"),
+            read_errors: REGISTRY.counter("pipeline_read_errors_total", "Nodes that didn't answer"),
+            queued: REGISTRY.gauge("pipeline_queued_readings", "Readings waiting for a batch"),
+            batch_messages: REGISTRY.histogram(
+                "pipeline_batch_messages",
+                "Readings per forwarded batch",
+                BATCH_BUCKETS,
+            ),
+        }
+    }
+
+    // A node that never answers, for error spans
+    pub fn with_failing_node(mut self, node: u16) -> Pipeline {
+        self.failing = Some(node);
+        self
+    }
+
+    // One poll cycle under a `poll_cycle` span; returns the readings it got
+    pub fn cycle(&mut self) -> usize {
+        self.cycle += 1;
+        let span = info_span!("poll_cycle", cycle = self.cycle);
+        let _entered = span.enter();
+        let mut got = 0;
+        for node in 0..self.nodes {
+            if let Some(value) = self.read(node) {
+                self.queue.push(value);
+                got += 1;
+            }
+        }
+        if self.queue.len() >= self.batch_size {
+            self.forward();
+        }
+        self.cycles.inc();
+        self.readings.add(got as u64);
+        self.queued.set(self.queue.len() as f64);
+        got
+    }
+
+    fn read(&self, node: u16) -> Option<f64> {
+        let span = info_span!(
+            "read_sensor",
+            node,
+            otel.status_code = tracing::field::Empty
+        );
+        let _entered = span.enter();
+        if self.failing == Some(node) {
+            // An event inside a span is exported as a span event
+            warn!(node, "no response");
+            span.record("otel.status_code", "ERROR");
+            self.read_errors.inc();
+            return None;
+        }
+        Some(20.0 + f64::from(node) + self.cycle as f64 * 0.1)
+    }
+
+    fn forward(&mut self) {
+        let messages = self.queue.len();
+        let _entered = info_span!("forward_batch", messages).entered();
+        self.batch_messages.observe(messages as f64);
+        self.queue.clear();
+    }
+}
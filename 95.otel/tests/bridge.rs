@@ -0,0 +1,61 @@
+// The 94.metrics registry as OTLP metrics. The registry is global to the
+// process, so this test binary has a single test and the values are exact.
+
+use otel::{MockCollector, Pipeline, Telemetry, TelemetryConfig};
+
+#[test]
+fn registry_families_are_exported_as_otlp_metrics() {
+    let collector = MockCollector::start().unwrap();
+    let mut config = TelemetryConfig::new("gw-042", "1.4.2");
+    config.endpoint = collector.endpoint();
+
+    let mut pipeline = Pipeline::new(3, 4).with_failing_node(2);
+    let sent = metrics::REGISTRY.counter_with(
+        "uplink_batches_total",
+        "Batches by outcome",
+        &[("result", "sent")],
+    );
+    let telemetry = Telemetry::init(&config, &metrics::REGISTRY).unwrap();
+    // Defined after `init`: not bridged
+    metrics::counter("late_total", "Defined too late").inc();
+    for _ in 0..5 {
+        pipeline.cycle();
+    }
+    sent.add(7);
+    telemetry.flush().unwrap();
+
+    let points = collector.points();
+    let value = |metric: &str| {
+        let found: Vec<_> = points.iter().filter(|p| p.metric == metric).collect();
+        assert_eq!(found.len(), 1, "{}: {:?}", metric, found);
+        found[0].value
+    };
+    // Two readings a cycle; a batch of four at cycles 2 and 4
+    assert_eq!(value("pipeline_cycles_total"), 5.0);
+    assert_eq!(value("pipeline_readings_total"), 10.0);
+    assert_eq!(value("pipeline_read_errors_total"), 5.0);
+    assert_eq!(value("pipeline_queued_readings"), 2.0);
+    assert_eq!(value("pipeline_batch_messages_count"), 2.0);
+    assert_eq!(value("pipeline_batch_messages_sum"), 8.0);
+
+    // Labels become attributes, and every point carries the resource
+    let uplink = points
+        .iter()
+        .find(|p| p.metric == "uplink_batches_total")
+        .unwrap();
+    assert_eq!(uplink.value, 7.0);
+    assert_eq!(uplink.attributes["result"], "sent");
+    assert!(points.iter().all(|p| p.resource["device.id"] == "gw-042"));
+    assert!(points.iter().all(|p| p.metric != "late_total"));
+
+    // Counters are cumulative: the next export repeats the totals
+    collector.clear();
+    pipeline.cycle();
+    telemetry.shutdown().unwrap();
+    let again = collector.points();
+    let cycles = again
+        .iter()
+        .find(|p| p.metric == "pipeline_cycles_total")
+        .unwrap();
+    assert_eq!(cycles.value, 6.0);
+}
@@ -0,0 +1,110 @@
+// Sampler names, the `OTEL_*` variables and the resource attributes
+
+use otel::{ConfigError, Sampling, TelemetryConfig};
+use std::time::Duration;
+
+fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn the_standard_sampler_names_parse() {
+    let cases = [
+        ("always_on", None, 1.0, false),
+        ("always_off", None, 0.0, false),
+        ("traceidratio", Some("0.1"), 0.1, false),
+        ("traceidratio", None, 1.0, false),
+        ("parentbased_always_on", None, 1.0, true),
+        ("parentbased_traceidratio", Some(" 0.25 "), 0.25, true),
+    ];
+    for (name, arg, ratio, parent_based) in cases {
+        assert_eq!(
+            Sampling::parse(name, arg),
+            Ok(Sampling {
+                ratio,
+                parent_based
+            }),
+            "{} {:?}",
+            name,
+            arg
+        );
+    }
+}
+
+#[test]
+fn bad_sampler_names_and_ratios_are_errors() {
+    assert_eq!(
+        Sampling::parse("sometimes", None),
+        Err(ConfigError::Sampler("sometimes".into()))
+    );
+    assert_eq!(
+        Sampling::parse("parentbased_", None),
+        Err(ConfigError::Sampler("parentbased_".into()))
+    );
+    for arg in ["1.5", "-0.1", "half", "NaN"] {
+        assert_eq!(
+            Sampling::parse("traceidratio", Some(arg)),
+            Err(ConfigError::SamplerArg(arg.into()))
+        );
+    }
+}
+
+#[test]
+fn sampling_prints_as_it_parses() {
+    for text in [
+        "always_on",
+        "always_off",
+        "traceidratio 0.5",
+        "parentbased_traceidratio 0.01",
+        "parentbased_always_off",
+    ] {
+        let sampling: Sampling = text.parse().unwrap();
+        assert_eq!(sampling.to_string(), text);
+    }
+    assert_eq!(Sampling::ratio(7.0), Sampling::ALWAYS_ON);
+}
+
+#[test]
+fn otel_variables_override_the_code() {
+    let config = TelemetryConfig::new("gw-042", "1.4.2")
+        .apply_env(vars(&[
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", "http://collector:4318/"),
+            ("OTEL_SERVICE_NAME", "cold-room"),
+            ("OTEL_TRACES_SAMPLER_ARG", "0.05"),
+            ("OTEL_TRACES_SAMPLER", "parentbased_traceidratio"),
+            ("OTEL_METRIC_EXPORT_INTERVAL", "15000"),
+            ("PATH", "/usr/bin"),
+        ]))
+        .unwrap();
+    assert_eq!(config.traces_url(), "http://collector:4318/v1/traces");
+    assert_eq!(config.metrics_url(), "http://collector:4318/v1/metrics");
+    assert_eq!(config.service_name, "cold-room");
+    assert_eq!(config.sampling, Sampling::ratio(0.05).parent_based());
+    assert_eq!(config.export_interval, Duration::from_secs(15));
+
+    let unchanged = TelemetryConfig::new("gw-042", "1.4.2");
+    assert_eq!(unchanged.clone().apply_env(Vec::new()), Ok(unchanged));
+    assert_eq!(
+        TelemetryConfig::new("gw-042", "1.4.2")
+            .apply_env(vars(&[("OTEL_METRIC_EXPORT_INTERVAL", "soon")])),
+        Err(ConfigError::Interval("soon".into()))
+    );
+}
+
+#[test]
+fn the_resource_names_the_device() {
+    let resource = TelemetryConfig::new("gw-042", "1.4.2").resource();
+    let get = |key: &str| {
+        resource
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.as_str())
+    };
+    assert_eq!(get("device.id"), Some("gw-042"));
+    assert_eq!(get("service.instance.id"), Some("gw-042"));
+    assert_eq!(get("device.firmware.version"), Some("1.4.2"));
+    assert_eq!(get("os.type"), Some(std::env::consts::OS));
+}
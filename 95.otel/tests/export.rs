@@ -0,0 +1,138 @@
+// Spans from the pipeline through the OTLP exporter to a mock collector.
+// Each test installs its own subscriber for its own thread, so the tests
+// don't see each other's spans.
+
+use otel::{ExportedSpan, MockCollector, Pipeline, Sampling, Telemetry, TelemetryConfig};
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+fn config(endpoint: &str, sampling: Sampling) -> TelemetryConfig {
+    let mut config = TelemetryConfig::new("gw-042", "1.4.2");
+    config.endpoint = endpoint.to_string();
+    config.sampling = sampling;
+    config
+}
+
+// Runs `cycles` poll cycles with the exporter installed and shuts it down,
+// which sends everything still buffered
+fn run(config: &TelemetryConfig, mut pipeline: Pipeline, cycles: usize) {
+    let telemetry = Telemetry::init(config, &metrics::REGISTRY).unwrap();
+    tracing::subscriber::with_default(telemetry.subscriber(), || {
+        for _ in 0..cycles {
+            pipeline.cycle();
+        }
+    });
+    telemetry.shutdown().unwrap();
+}
+
+fn by_trace(spans: &[ExportedSpan]) -> BTreeMap<&str, Vec<&ExportedSpan>> {
+    let mut traces: BTreeMap<&str, Vec<&ExportedSpan>> = BTreeMap::new();
+    for span in spans {
+        traces.entry(&span.trace_id).or_default().push(span);
+    }
+    traces
+}
+
+#[test]
+fn spans_are_exported_with_their_parents_and_the_resource() {
+    let collector = MockCollector::start().unwrap();
+    let config = config(&collector.endpoint(), Sampling::ALWAYS_ON);
+    // Two readings a cycle, so the third cycle fills a batch of six
+    run(&config, Pipeline::new(3, 6).with_failing_node(1), 3);
+
+    let spans = collector.spans();
+    let count = |name: &str| spans.iter().filter(|s| s.name == name).count();
+    assert_eq!(spans.len(), 13, "{:#?}", spans);
+    assert_eq!(count("poll_cycle"), 3);
+    assert_eq!(count("read_sensor"), 9);
+    assert_eq!(count("forward_batch"), 1);
+
+    // One trace per cycle: the cycle is the root, everything else its child
+    let traces = by_trace(&spans);
+    assert_eq!(traces.len(), 3);
+    let mut cycles = Vec::new();
+    for trace in traces.values() {
+        let roots: Vec<_> = trace
+            .iter()
+            .filter(|s| s.parent_span_id.is_empty())
+            .collect();
+        assert_eq!(roots.len(), 1);
+        assert_eq!(roots[0].name, "poll_cycle");
+        cycles.push(roots[0].attributes["cycle"].clone());
+        for child in trace.iter().filter(|s| s.name != "poll_cycle") {
+            assert_eq!(child.parent_span_id, roots[0].span_id, "{:?}", child);
+        }
+    }
+    cycles.sort();
+    assert_eq!(cycles, ["1", "2", "3"]);
+
+    // The node that doesn't answer: error status and the warning as an event
+    for span in spans.iter().filter(|s| s.name == "read_sensor") {
+        let failing = span.attributes["node"] == "1";
+        assert_eq!(span.error, failing, "{:?}", span);
+        let events: &[&str] = if failing { &["no response"] } else { &[] };
+        assert_eq!(span.events, events);
+    }
+    let batch = spans.iter().find(|s| s.name == "forward_batch").unwrap();
+    assert_eq!(batch.attributes["messages"], "6");
+
+    for span in &spans {
+        assert_eq!(span.resource["device.id"], "gw-042");
+        assert_eq!(span.resource["device.firmware.version"], "1.4.2");
+        assert_eq!(span.resource["service.name"], "edge-gateway");
+    }
+}
+
+#[test]
+fn a_ratio_sampler_keeps_some_traces_whole() {
+    let collector = MockCollector::start().unwrap();
+    let config = config(&collector.endpoint(), Sampling::ratio(0.25).parent_based());
+    run(&config, Pipeline::new(2, usize::MAX), 400);
+
+    let spans = collector.spans();
+    let traces = by_trace(&spans);
+    // 100 expected; the decision hashes random trace ids
+    assert!(
+        (60..=140).contains(&traces.len()),
+        "{} traces",
+        traces.len()
+    );
+    // Decided once per trace: a kept trace has its root and both reads
+    for trace in traces.values() {
+        let mut names: Vec<&str> = trace.iter().map(|s| s.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, ["poll_cycle", "read_sensor", "read_sensor"]);
+    }
+}
+
+#[test]
+fn always_off_exports_no_spans() {
+    let collector = MockCollector::start().unwrap();
+    let config = config(&collector.endpoint(), "always_off".parse().unwrap());
+    run(&config, Pipeline::new(3, 6), 20);
+    assert!(collector.spans().is_empty());
+    assert!(collector.exports().iter().all(|e| e.path != "/v1/traces"));
+}
+
+#[test]
+fn a_missing_collector_does_not_hold_up_the_pipeline() {
+    // Nothing listens on port 9 of this host
+    let config = config("http://127.0.0.1:9", Sampling::ALWAYS_ON);
+    let telemetry = Telemetry::init(&config, &metrics::REGISTRY).unwrap();
+    let mut pipeline = Pipeline::new(3, 6);
+    let started = Instant::now();
+    tracing::subscriber::with_default(telemetry.subscriber(), || {
+        for _ in 0..200 {
+            pipeline.cycle();
+        }
+    });
+    // The spans wait in the batch processor's buffer, not in `cycle`
+    assert!(
+        started.elapsed().as_millis() < 1000,
+        "{:?}",
+        started.elapsed()
+    );
+    // The last export fails, and shutdown says so or logs it; either way
+    // it returns
+    let _ = telemetry.shutdown();
+}
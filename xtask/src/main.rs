@@ -0,0 +1,281 @@
+// Repository tasks, run with `cargo xtask <task>` (alias in .cargo/config.toml)
+//
+//   cargo xtask size-report                      default lessons, both profiles
+//   cargo xtask size-report --lesson 18.crc      one lesson, its default binary
+//   cargo xtask size-report --lesson 65.binsize:generic --profile size
+//
+// size-report builds each binary with `cargo build` and reads the
+// sections of the result with binsize::elf. The `size` profile is defined
+// on the command line with `--config`, so no lesson has to carry it.
+
+use binsize::elf::{sections, Summary};
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+const USAGE: &str =
+    "usage: cargo xtask size-report [--lesson DIR[:BIN]]... [--profile release|size]...";
+
+// Chosen to cover std with and without formatting, the generic and dyn
+// binaries of 65.binsize, and the no_std floor
+const DEFAULT_TARGETS: &[&str] = &[
+    "18.crc",
+    "19.cobs",
+    "61.const_eval",
+    "65.binsize:frame_fmt",
+    "65.binsize:frame_io",
+    "65.binsize:generic",
+    "65.binsize:dynamic",
+    "65.binsize/nostd:binsize_nostd",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Profile {
+    // cargo's release profile as the lesson defines it
+    Release,
+    // opt-level "z", fat LTO, one codegen unit, abort on panic, stripped
+    Size,
+}
+
+impl Profile {
+    fn parse(name: &str) -> Option<Profile> {
+        match name {
+            "release" => Some(Profile::Release),
+            "size" => Some(Profile::Size),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Profile::Release => "release",
+            Profile::Size => "size",
+        }
+    }
+
+    fn cargo_args(self) -> Vec<&'static str> {
+        match self {
+            Profile::Release => vec!["--release"],
+            Profile::Size => vec![
+                "--profile",
+                "size",
+                "--config",
+                "profile.size.inherits=\"release\"",
+                "--config",
+                "profile.size.opt-level=\"z\"",
+                "--config",
+                "profile.size.lto=true",
+                "--config",
+                "profile.size.codegen-units=1",
+                "--config",
+                "profile.size.panic=\"abort\"",
+                "--config",
+                "profile.size.strip=true",
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Target {
+    // As given, for the report
+    spec: String,
+    // Relative to the repository root
+    dir: String,
+    bin: String,
+}
+
+impl Target {
+    // "18.crc" builds the package's own binary, "65.binsize:generic" a named one
+    fn parse(root: &Path, spec: &str) -> Result<Target, TaskError> {
+        let (dir, bin) = match spec.split_once(':') {
+            Some((dir, bin)) => (dir, Some(bin)),
+            None => (spec, None),
+        };
+        let manifest = root.join(dir).join("Cargo.toml");
+        let bin = match bin {
+            Some(bin) => bin.to_string(),
+            None => package_name(&manifest),
+        };
+        if !manifest.is_file() || bin.is_empty() {
+            return Err(TaskError::NoManifest(dir.to_string()));
+        }
+        Ok(Target {
+            spec: spec.to_string(),
+            dir: dir.to_string(),
+            bin,
+        })
+    }
+}
+
+#[derive(Debug)]
+enum TaskError {
+    Usage(String),
+    NoManifest(String),
+    Cargo(std::io::Error),
+    Build {
+        target: String,
+        profile: &'static str,
+    },
+    Read {
+        path: PathBuf,
+        source: Box<dyn Error>,
+    },
+}
+
+impl fmt::Display for TaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskError::Usage(problem) => write!(f, "{}\n{}", problem, USAGE),
+            TaskError::NoManifest(dir) => write!(f, "{} has no Cargo.toml", dir),
+            TaskError::Cargo(e) => write!(f, "could not run cargo: {}", e),
+            TaskError::Build { target, profile } => {
+                write!(f, "cargo build failed for {} ({})", target, profile)
+            }
+            TaskError::Read { path, source } => write!(f, "{}: {}", path.display(), source),
+        }
+    }
+}
+
+impl Error for TaskError {}
+
+// The `name` under [package], which the default binary shares; empty
+// when the manifest can't be read
+fn package_name(manifest: &Path) -> String {
+    let text = std::fs::read_to_string(manifest).unwrap_or_default();
+    text.lines()
+        .filter_map(|line| line.trim().strip_prefix("name"))
+        .filter_map(|rest| rest.trim_start().strip_prefix('='))
+        .map(|value| value.trim().trim_matches('"').to_string())
+        .next()
+        .unwrap_or_default()
+}
+
+struct Row {
+    target: String,
+    profile: Profile,
+    summary: Summary,
+    file: u64,
+}
+
+fn build(root: &Path, target: &Target, profile: Profile) -> Result<Row, TaskError> {
+    let dir = root.join(&target.dir);
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let status = Command::new(cargo)
+        .arg("build")
+        .arg("--quiet")
+        .arg("--manifest-path")
+        .arg(dir.join("Cargo.toml"))
+        .args(["--bin", &target.bin])
+        .args(profile.cargo_args())
+        .status()
+        .map_err(TaskError::Cargo)?;
+    if !status.success() {
+        return Err(TaskError::Build {
+            target: target.spec.clone(),
+            profile: profile.name(),
+        });
+    }
+
+    let target_dir = env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| dir.join("target"));
+    let path = target_dir.join(profile.name()).join(&target.bin);
+    let read_error = |source: Box<dyn Error>| TaskError::Read {
+        path: path.clone(),
+        source,
+    };
+    let bytes = std::fs::read(&path).map_err(|e| read_error(Box::new(e)))?;
+    let all = sections(&bytes).map_err(|e| read_error(Box::new(e)))?;
+    Ok(Row {
+        target: target.spec.clone(),
+        profile,
+        summary: Summary::of(&all),
+        file: bytes.len() as u64,
+    })
+}
+
+fn print_table(rows: &[Row]) {
+    println!(
+        "{:<32} {:<8} {:>9} {:>8} {:>8} {:>7} {:>6} {:>9} {:>9}",
+        "binary", "profile", ".text", ".rodata", "unwind", ".data", ".bss", "flash", "file"
+    );
+    for row in rows {
+        let s = &row.summary;
+        println!(
+            "{:<32} {:<8} {:>9} {:>8} {:>8} {:>7} {:>6} {:>9} {:>9}",
+            row.target,
+            row.profile.name(),
+            s.text,
+            s.rodata,
+            s.unwind,
+            s.data,
+            s.bss,
+            s.flash(),
+            row.file
+        );
+    }
+    println!("\nflash = .text + .rodata + unwind + .data, what a device would store");
+    println!("file includes symbols and debug info, which are never loaded");
+}
+
+fn size_report(root: &Path, args: &[String]) -> Result<(), TaskError> {
+    let mut specs = Vec::new();
+    let mut profiles = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| TaskError::Usage(format!("{} needs a value", arg)))?;
+        match arg.as_str() {
+            "--lesson" => specs.push(value.clone()),
+            "--profile" => profiles.push(
+                Profile::parse(value)
+                    .ok_or_else(|| TaskError::Usage(format!("unknown profile {}", value)))?,
+            ),
+            other => return Err(TaskError::Usage(format!("unknown option {}", other))),
+        }
+    }
+    if specs.is_empty() {
+        specs = DEFAULT_TARGETS.iter().map(|s| s.to_string()).collect();
+    }
+    if profiles.is_empty() {
+        profiles = vec![Profile::Release, Profile::Size];
+    }
+
+    let targets = specs
+        .iter()
+        .map(|spec| Target::parse(root, spec))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut rows = Vec::new();
+    for target in &targets {
+        for &profile in &profiles {
+            eprintln!("building {} ({})", target.spec, profile.name());
+            rows.push(build(root, target, profile)?);
+        }
+    }
+    print_table(&rows);
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask lives in the repository root")
+        .to_path_buf();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("size-report") => size_report(&root, &args[1..]),
+        Some(other) => Err(TaskError::Usage(format!("unknown task {}", other))),
+        None => Err(TaskError::Usage("no task given".to_string())),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}